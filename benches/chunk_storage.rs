@@ -0,0 +1,149 @@
+//! Chunk storage memory on typical scenes, and what compact storage
+//! costs per `get` / `set`.
+//!
+//! Run with `cargo bench --bench chunk_storage`. Prints, per scene, the
+//! heap held by voxel storage next to what the same chunks took as flat
+//! 256 KB arrays, then access timings against a flat `Vec<Voxel>`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use voxelith::core::{Chunk, Voxel, World, CHUNK_SIZE, CHUNK_VOLUME};
+use voxelith::procgen::{PerlinTerrain, VoxelGenerator};
+
+const FLAT_CHUNK_BYTES: usize = CHUNK_VOLUME * std::mem::size_of::<Voxel>();
+
+fn terrain() -> World {
+    let mut world = World::new();
+    let terrain = PerlinTerrain {
+        width: 256,
+        depth: 256,
+        max_height: 48,
+        ..PerlinTerrain::default()
+    };
+    terrain.generate().expect("terrain").apply(&mut world);
+    world
+}
+
+fn ground() -> World {
+    let mut world = World::new();
+    world.create_test_ground(128, 8);
+    world
+}
+
+fn colored_cube() -> World {
+    let mut world = World::new();
+    world.create_test_cube((0, 24, 0), 24);
+    world
+}
+
+/// A sphere shaded with a smooth color gradient: hundreds of distinct
+/// voxels per chunk, the worst case (imported, textured models).
+fn gradient_sphere() -> World {
+    let mut world = World::new();
+    let r = 40;
+    for z in -r..=r {
+        for y in -r..=r {
+            for x in -r..=r {
+                if x * x + y * y + z * z <= r * r {
+                    let c = |v: i32| ((v + r) * 255 / (2 * r)) as u8;
+                    world.set_voxel(x, y, z, Voxel::from_rgb(c(x), c(y), c(z)));
+                }
+            }
+        }
+    }
+    world
+}
+
+fn report(name: &str, mut world: World) {
+    let chunks = world.chunk_count();
+    let flat = chunks * FLAT_CHUNK_BYTES;
+    let live = world.heap_bytes();
+    world.prune_empty_chunks();
+    let compacted = world.heap_bytes();
+    println!(
+        "{:<16} {:>6} chunks  flat {:>9.1} KB  compact {:>9.1} KB ({:>5.1}%)  after compact() {:>9.1} KB",
+        name,
+        chunks,
+        flat as f64 / 1024.0,
+        live as f64 / 1024.0,
+        100.0 * live as f64 / flat.max(1) as f64,
+        compacted as f64 / 1024.0,
+    );
+}
+
+fn time(label: &str, rounds: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..rounds {
+        f();
+    }
+    let per_voxel = start.elapsed().as_nanos() as f64 / (rounds as f64 * CHUNK_VOLUME as f64);
+    println!("{:<32} {:>6.2} ns/voxel", label, per_voxel);
+}
+
+fn filled_chunk(colors: u32) -> Chunk {
+    let mut chunk = Chunk::new();
+    for z in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let c = (x * 7 + y * 13 + z * 29) as u32 % colors;
+                chunk.set(x, y, z, Voxel::from_rgb(c as u8, (c >> 8) as u8, 1));
+            }
+        }
+    }
+    chunk
+}
+
+fn main() {
+    println!("Memory");
+    report("empty chunks", {
+        let mut world = World::new();
+        for x in 0..8 {
+            for z in 0..8 {
+                world.get_or_create_chunk(voxelith::core::ChunkPos::new(x, 0, z));
+            }
+        }
+        world
+    });
+    report("test ground", ground());
+    report("perlin terrain", terrain());
+    report("colored cube", colored_cube());
+    report("gradient sphere", gradient_sphere());
+
+    println!();
+    println!("Access");
+    let rounds = 50;
+    let flat = vec![Voxel::from_rgb(1, 2, 3); CHUNK_VOLUME];
+    time("flat Vec get", rounds, || {
+        for v in &flat {
+            black_box(*v);
+        }
+    });
+    for (label, colors) in [
+        ("palette (4 voxels)", 4),
+        ("palette (200 voxels)", 200),
+        ("flat fallback", 1000),
+    ] {
+        let mut chunk = filled_chunk(colors);
+        time(&format!("{} get", label), rounds, || {
+            for z in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        black_box(chunk.get(x, y, z));
+                    }
+                }
+            }
+        });
+        let mut n = 0u32;
+        time(&format!("{} set", label), rounds, || {
+            for z in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        n = (n + 1) % colors;
+                        chunk.set(x, y, z, Voxel::from_rgb(n as u8, (n >> 8) as u8, 1));
+                    }
+                }
+            }
+        });
+    }
+}
@@ -44,8 +44,8 @@ use super::provider::{AiProvider, AiRequest};
 use super::voxelize::voxelize_glb;
 
 /// fal.ai queue API base. The provider formats this with the model id
-/// + endpoint at submit time so future providers (different models on
-/// fal.ai) can share most of the polling code.
+/// and endpoint at submit time so future providers (different models
+/// on fal.ai) can share most of the polling code.
 const TEXT_TO_3D_ENDPOINT: &str =
     "https://queue.fal.run/fal-ai/hunyuan3d-v3/text-to-3d";
 
@@ -9,9 +9,10 @@ use crate::procgen::VoxelPatch;
 
 /// What the AI generation pipeline is currently doing. Single value
 /// owned by `App`; UI reads it to render the panel state.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum AiJobState {
     /// No active job. Generate button enabled.
+    #[default]
     Idle,
     /// HTTP submission to the provider; waiting for the provider to
     /// accept and assign a job id. Cancel allowed.
@@ -73,12 +74,6 @@ impl AiJobState {
     }
 }
 
-impl Default for AiJobState {
-    fn default() -> Self {
-        AiJobState::Idle
-    }
-}
-
 /// Events emitted by the worker task. The `App::tick_ai_job` drains
 /// these via `mpsc::Receiver::try_iter`.
 #[derive(Debug, Clone)]
@@ -19,7 +19,7 @@
 //!
 //! - [`job`]: `AiJobState` enum + `JobEvent` events
 //! - [`provider`]: `AiProvider` trait + `AiRequest` / `JobHandle` types
-//! - [`runtime`]: `AiRuntime` (tokio thread + handle)
+//! - [`runtime`]: `AiRuntime` — tokio thread + handle
 //! - [`keyring_store`]: load/save/clear API key
 //! - [`mock`]: a `MockProvider` for tests / offline wiring
 //! - [`client`]: real fal.ai HTTP client (Hunyuan3D V3 text-to-3D, queue API)
@@ -494,14 +494,9 @@ fn fill_interior(
             .fold((0u64, 0u64, 0u64, 0u64), |(r, g, b, c), v| {
                 (r + v.r as u64, g + v.g as u64, b + v.b as u64, c + 1)
             });
-    let fill_voxel = if count > 0 {
-        Voxel::from_rgb(
-            (r_sum / count) as u8,
-            (g_sum / count) as u8,
-            (b_sum / count) as u8,
-        )
-    } else {
-        Voxel::from_rgb(180, 180, 180)
+    let fill_voxel = match count {
+        0 => Voxel::from_rgb(180, 180, 180),
+        n => Voxel::from_rgb((r_sum / n) as u8, (g_sum / n) as u8, (b_sum / n) as u8),
     };
 
     let mut result = surface.clone();
@@ -633,10 +628,11 @@ mod tests {
 
     #[test]
     fn finalize_surface_averages_colors_at_overlapping_samples() {
-        // Two samples land on the same cell with different colors;
-        // the cell should end up at their per-channel mean.
+        // Two samples land on the same cell with different colors,
+        // (200, 0, 100) and (100, 100, 200), both opaque; the cell
+        // should end up at their per-channel mean.
         let mut grid: HashMap<(i32, i32, i32), ColorAccum> = HashMap::new();
-        grid.insert((1, 2, 3), [200 + 100, 0 + 100, 100 + 200, 255 + 255, 2]);
+        grid.insert((1, 2, 3), [300, 100, 300, 510, 2]);
         let surface = finalize_surface(grid);
         let v = surface.get(&(1, 2, 3)).expect("cell exists");
         assert_eq!(v.r, 150);
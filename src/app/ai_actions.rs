@@ -128,7 +128,7 @@ impl App {
             .voxels
             .iter()
             .filter_map(|&(pos, new_voxel)| {
-                let old_voxel = self.world.get_voxel(pos.0, pos.1, pos.2);
+                let old_voxel = self.scene.active_world().get_voxel(pos.0, pos.1, pos.2);
                 if old_voxel == new_voxel {
                     None
                 } else {
@@ -146,15 +146,15 @@ impl App {
         // Remember the generated footprint for the "Frame Generated"
         // camera action.
         self.last_generated_bounds = super::bounds_of(patch.voxels.iter().map(|&(p, _)| p));
-        let cmd = Command::set_voxels(changes);
-        self.editor.history.execute(cmd, &mut self.world);
+        let cmd = Command::set_voxels(changes).named("AI Generation");
+        self.editor.history.execute(cmd, self.scene.active_world_mut());
 
         // Placement polish: auto-select the result's AABB so it can be
         // moved / copied immediately (mirrors Paste's auto-select), and
         // frame it — the model lands at the world origin and is often
         // off-screen from where the user was working.
         if let Some((min, max)) = self.last_generated_bounds {
-            self.editor.selection = Some(Selection::from_corners(min, max));
+            self.editor.set_selection(Some(Selection::from_corners(min, max)));
         }
         self.frame_generated();
     }
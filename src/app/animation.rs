@@ -0,0 +1,212 @@
+//! Animation frames: the Timeline panel's actions, playback and the
+//! onion-skin overlay.
+//!
+//! The scene's layers always hold the shown frame (`core::Scene` swaps
+//! the others in and out), so every tool, export and the renderer work
+//! on it unchanged. Undo follows the same split: each frame keeps its
+//! own per-layer histories in `App::frame_histories`, parked and
+//! restored around every frame switch — an undo never rewrites a frame
+//! that isn't on screen.
+
+use std::time::{Duration, Instant};
+
+use voxelith::core::{Scene, Voxel};
+use voxelith::editor::{CommandHistory, UNDO_DEPTH};
+use voxelith::mesh::patch_to_mesh;
+use voxelith::ui::UiAction;
+
+use super::App;
+
+/// Alpha of the onion-skin ghosts. Fainter than the move ghost (0.55):
+/// they're reference, not something about to land.
+const ONION_SKIN_ALPHA: f32 = 0.3;
+
+/// Tints mixed half-and-half into the previous / next frame's colors,
+/// the usual red-behind / green-ahead convention.
+const ONION_SKIN_PREVIOUS_TINT: [u8; 3] = [255, 64, 64];
+const ONION_SKIN_NEXT_TINT: [u8; 3] = [64, 255, 96];
+
+/// Ghost voxels beyond this are left out, so onion skinning a large
+/// terrain can't stall a frame building a huge overlay mesh.
+const ONION_SKIN_MAX_VOXELS: usize = 200_000;
+
+impl App {
+    /// Run `change` on the scene with every layer's history parked
+    /// under the shown frame, then take out the histories of the frame
+    /// shown afterwards. Everything that can switch frames goes
+    /// through here.
+    fn change_frame<R>(&mut self, change: impl FnOnce(&mut Scene) -> R) -> R {
+        self.editor.history.end_stroke();
+        let active = self.scene.active().id;
+        let outgoing = self.scene.frames()[self.scene.current_frame()].id;
+        let parked = std::mem::replace(&mut self.editor.history, CommandHistory::new(UNDO_DEPTH));
+        self.layer_histories.insert(active, parked);
+        let histories = std::mem::take(&mut self.layer_histories);
+        self.frame_histories.insert(outgoing, histories);
+
+        let result = change(&mut self.scene);
+
+        let incoming = self.scene.frames()[self.scene.current_frame()].id;
+        self.layer_histories = self.frame_histories.remove(&incoming).unwrap_or_default();
+        self.editor.history = self
+            .layer_histories
+            .remove(&active)
+            .unwrap_or_else(|| CommandHistory::new(UNDO_DEPTH));
+        self.sync_history_lock();
+        self.onion_skin_stale = true;
+        result
+    }
+
+    /// Show frame `index` in the viewport.
+    fn show_frame(&mut self, index: usize) {
+        if index < self.scene.frames().len() && index != self.scene.current_frame() {
+            self.change_frame(|scene| scene.set_current_frame(index));
+        }
+    }
+
+    /// Apply one of the Timeline panel's actions; other actions are
+    /// ignored (`handle_ui_action` only routes frame actions here).
+    pub(super) fn handle_frame_action(&mut self, action: UiAction) {
+        let count = self.scene.frames().len();
+        let current = self.scene.current_frame();
+        match action {
+            UiAction::AddFrame => {
+                self.change_frame(|scene| scene.add_frame());
+                self.unsaved_changes = true;
+            }
+            UiAction::DuplicateFrame => {
+                self.change_frame(|scene| scene.duplicate_frame());
+                self.unsaved_changes = true;
+            }
+            UiAction::SelectFrame(index) => self.show_frame(index),
+            UiAction::RenameFrame(index, name) => {
+                self.scene.rename_frame(index, name);
+                self.unsaved_changes = true;
+            }
+            UiAction::SetFrameDuration(index, duration_ms) => {
+                self.scene.set_frame_duration(index, duration_ms);
+                self.unsaved_changes = true;
+            }
+            UiAction::MoveFrame { from, to } => {
+                self.scene.move_frame(from, to);
+                self.onion_skin_stale = true;
+                self.unsaved_changes = true;
+            }
+            UiAction::RemoveFrame(index) => {
+                let Some(name) = self.scene.frames().get(index).map(|f| f.name.clone()) else {
+                    return;
+                };
+                if let Some(id) = self.change_frame(|scene| scene.remove_frame(index)) {
+                    self.frame_histories.remove(&id);
+                    self.ui.set_status(format!("Deleted frame \"{name}\""));
+                    self.onion_skin_stale = true;
+                    self.unsaved_changes = true;
+                }
+            }
+            UiAction::TogglePlayback => {
+                self.playback = match self.playback {
+                    Some(_) => None,
+                    None if count > 1 => Some(Instant::now()),
+                    None => None,
+                };
+                self.onion_skin_stale = true;
+            }
+            UiAction::PreviousFrame if count > 1 => {
+                self.show_frame((current + count - 1) % count);
+            }
+            UiAction::NextFrame if count > 1 => self.show_frame((current + 1) % count),
+            _ => {}
+        }
+    }
+
+    /// Advance playback once the shown frame's hold time is up,
+    /// looping after the last frame. Called every frame from
+    /// `RedrawRequested`.
+    pub(super) fn tick_playback(&mut self) {
+        let Some(shown) = self.playback else {
+            return;
+        };
+        let count = self.scene.frames().len();
+        if count <= 1 {
+            self.playback = None;
+            return;
+        }
+        let current = self.scene.current_frame();
+        let hold = Duration::from_millis(self.scene.frames()[current].duration_ms as u64);
+        if shown.elapsed() >= hold {
+            self.show_frame((current + 1) % count);
+            self.playback = Some(Instant::now());
+        }
+    }
+
+    /// Rebuild or clear the onion-skin overlay: the frames either side
+    /// of the shown one, tinted and translucent, wherever the shown
+    /// frame is empty. Hidden during playback, where the ghosts would
+    /// only flicker.
+    pub(super) fn update_onion_skin(&mut self) {
+        let enabled =
+            self.ui.state.onion_skin && self.playback.is_none() && self.scene.frames().len() > 1;
+        let Some(renderer) = &mut self.renderer else {
+            return;
+        };
+        if !enabled {
+            if renderer.onion_skin_mesh.is_some() {
+                renderer.clear_onion_skin();
+            }
+            // Rebuild from scratch the next time it's turned on.
+            self.onion_skin_stale = true;
+            return;
+        }
+        if !self.onion_skin_stale {
+            return;
+        }
+        self.onion_skin_stale = false;
+
+        let current = self.scene.current_frame();
+        let count = self.scene.frames().len();
+        let neighbors = [
+            (current.checked_sub(1), ONION_SKIN_PREVIOUS_TINT),
+            (
+                (current + 1 < count).then_some(current + 1),
+                ONION_SKIN_NEXT_TINT,
+            ),
+        ];
+        let view = self.scene.view();
+        let mut voxels = Vec::new();
+        'frames: for (index, tint) in neighbors {
+            let Some(index) = index else {
+                continue;
+            };
+            let world = self.scene.frame_world(index);
+            for (pos, chunk) in world.chunks() {
+                let (ox, oy, oz) = pos.world_origin();
+                for (local, voxel) in chunk.read().iter_solid() {
+                    let (x, y, z) = (
+                        ox + local.x as i32,
+                        oy + local.y as i32,
+                        oz + local.z as i32,
+                    );
+                    if view.get_voxel(x, y, z).is_solid() {
+                        continue;
+                    }
+                    if voxels.len() >= ONION_SKIN_MAX_VOXELS {
+                        break 'frames;
+                    }
+                    voxels.push(((x, y, z), tinted(*voxel, tint)));
+                }
+            }
+        }
+        if voxels.is_empty() {
+            renderer.clear_onion_skin();
+        } else {
+            renderer.set_onion_skin_mesh(&patch_to_mesh(&voxels, ONION_SKIN_ALPHA));
+        }
+    }
+}
+
+/// `voxel`'s color mixed half-and-half with `tint`.
+fn tinted(voxel: Voxel, tint: [u8; 3]) -> Voxel {
+    let [r, g, b, _] = voxel.color();
+    let mix = |c: u8, t: u8| ((c as u16 + t as u16) / 2) as u8;
+    Voxel::from_rgb(mix(r, tint[0]), mix(g, tint[1]), mix(b, tint[2]))
+}
@@ -0,0 +1,297 @@
+//! Render ▸ Camera Path: editing the keyframed fly-through, previewing
+//! it in the viewport and rendering it to a PNG sequence.
+//!
+//! The path is document data (saved in `.vxlt` next to the camera
+//! bookmarks) and, like them, stays out of the undo history. The
+//! render mirrors the turntable: one offscreen frame per app frame
+//! (`tick_flythrough`), so the window stays responsive and Cancel is
+//! immediate.
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use voxelith::io::CameraPathKey;
+use voxelith::prefs::FileDirKind;
+use voxelith::render::{Camera, CameraKey, CameraPath, OffscreenStyle};
+use voxelith::ui::{FlythroughSettings, GenJobStatus, UiAction};
+
+use super::App;
+
+/// Default time from a new key to the next.
+const DEFAULT_KEY_MS: u32 = 2000;
+
+/// A fly-through render in progress.
+pub(super) struct FlythroughJob {
+    settings: FlythroughSettings,
+    /// The path as it was when Render was clicked.
+    path: CameraPath,
+    /// Editor camera at start, for the lens (field of view, clip
+    /// planes).
+    main: Camera,
+    total: u32,
+    /// Frames written so far.
+    done: u32,
+    dir: PathBuf,
+    started: Instant,
+}
+
+/// File name of sequence frame `index`, zero-padded like the
+/// turntable's so the frames sort in order.
+fn frame_file_name(index: u32) -> String {
+    format!("flythrough_{:04}.png", index)
+}
+
+/// The path as stored in a project.
+pub(super) fn camera_path_to_state(path: &CameraPath) -> Vec<CameraPathKey> {
+    path.keys
+        .iter()
+        .map(|k| CameraPathKey {
+            position: k.position.to_array(),
+            target: k.target.to_array(),
+            duration_ms: k.duration_ms,
+        })
+        .collect()
+}
+
+/// Inverse of [`camera_path_to_state`].
+pub(super) fn camera_path_from_state(keys: &[CameraPathKey], closed: bool) -> CameraPath {
+    CameraPath {
+        keys: keys
+            .iter()
+            .map(|k| CameraKey {
+                position: glam::Vec3::from_array(k.position),
+                target: glam::Vec3::from_array(k.target),
+                duration_ms: k.duration_ms,
+            })
+            .collect(),
+        closed,
+    }
+}
+
+impl App {
+    /// A key at the viewport camera's current view.
+    fn camera_key_from_view(&self, duration_ms: u32) -> Option<CameraKey> {
+        let camera = &self.renderer.as_ref()?.camera;
+        Some(CameraKey {
+            position: camera.position,
+            target: camera.target,
+            duration_ms,
+        })
+    }
+
+    /// Apply one of the Camera Path window's actions; other actions are
+    /// ignored (`handle_ui_action` only routes camera path actions here).
+    pub(super) fn handle_camera_path_action(&mut self, action: UiAction) {
+        let keys = &mut self.camera_path.keys;
+        match action {
+            UiAction::AddCameraKey => {
+                let after = self.ui.state.selected_camera_key;
+                // A new key inherits its predecessor's timing.
+                let duration_ms = after
+                    .and_then(|i| keys.get(i))
+                    .or(keys.last())
+                    .map_or(DEFAULT_KEY_MS, |k| k.duration_ms);
+                let Some(key) = self.camera_key_from_view(duration_ms) else {
+                    return;
+                };
+                let keys = &mut self.camera_path.keys;
+                let index = after.map_or(keys.len(), |i| (i + 1).min(keys.len()));
+                keys.insert(index, key);
+                self.ui.state.selected_camera_key = Some(index);
+                self.ui
+                    .set_status(format!("Added camera key {}", index + 1));
+            }
+            UiAction::UpdateCameraKey(index) => {
+                let Some(duration_ms) = keys.get(index).map(|k| k.duration_ms) else {
+                    return;
+                };
+                let Some(key) = self.camera_key_from_view(duration_ms) else {
+                    return;
+                };
+                self.camera_path.keys[index] = key;
+                self.ui
+                    .set_status(format!("Updated camera key {}", index + 1));
+            }
+            UiAction::GoToCameraKey(index) => {
+                let (Some(key), Some(renderer)) = (keys.get(index), &mut self.renderer) else {
+                    return;
+                };
+                let pose = voxelith::render::CameraPose::looking_at(key.position, key.target);
+                self.camera_path_preview = None;
+                renderer
+                    .camera_controller
+                    .animate_to(&mut renderer.camera, pose);
+                // Only the view moved.
+                return;
+            }
+            UiAction::SetCameraKeyDuration(index, duration_ms) => {
+                let Some(key) = keys.get_mut(index) else {
+                    return;
+                };
+                key.duration_ms = duration_ms;
+            }
+            UiAction::MoveCameraKey { from, to } => {
+                if from >= keys.len() || to >= keys.len() || from == to {
+                    return;
+                }
+                let key = keys.remove(from);
+                keys.insert(to, key);
+                self.ui.state.selected_camera_key = Some(to);
+            }
+            UiAction::RemoveCameraKey(index) => {
+                if index >= keys.len() {
+                    return;
+                }
+                keys.remove(index);
+                self.ui.state.selected_camera_key = None;
+                if keys.len() < 2 {
+                    self.camera_path_preview = None;
+                }
+            }
+            UiAction::SetCameraPathClosed(closed) => self.camera_path.closed = closed,
+            UiAction::ToggleCameraPathPreview => {
+                self.camera_path_preview = match self.camera_path_preview {
+                    Some(_) => None,
+                    None if keys.len() > 1 => Some(Instant::now()),
+                    None => {
+                        self.ui.set_status("Camera path needs at least two keys");
+                        None
+                    }
+                };
+                // Playback only moves the view.
+                return;
+            }
+            _ => return,
+        }
+        self.unsaved_changes = true;
+    }
+
+    /// Fly the viewport camera along the path while previewing. An
+    /// open path stops on its last key; a closed one loops until
+    /// stopped. Called every frame from `RedrawRequested`.
+    pub(super) fn tick_camera_path_preview(&mut self) {
+        let (Some(started), Some(renderer)) = (self.camera_path_preview, &mut self.renderer) else {
+            return;
+        };
+        let elapsed = started.elapsed().as_secs_f32();
+        let Some((position, target)) = self.camera_path.sample(elapsed) else {
+            self.camera_path_preview = None;
+            return;
+        };
+        renderer.camera.position = position;
+        renderer.camera.target = target;
+        // The camera was placed from outside: re-derive the orbit state
+        // so the next drag continues from here.
+        renderer
+            .camera_controller
+            .sync_orbit_state_from_camera(&renderer.camera);
+        if !self.camera_path.closed && elapsed >= self.camera_path.duration() {
+            self.camera_path_preview = None;
+        }
+    }
+
+    /// Ask for an output folder and start rendering the fly-through
+    /// with the Camera Path window's settings. No-op while one is
+    /// running.
+    pub(super) fn start_flythrough(&mut self) {
+        if self.flythrough.is_some() {
+            return;
+        }
+        if self.camera_path.keys.len() < 2 {
+            self.ui.set_status("Camera path needs at least two keys");
+            return;
+        }
+        let Some(renderer) = &self.renderer else {
+            return;
+        };
+        let main = renderer.camera.clone();
+        let Some(dir) = self
+            .file_dialog(FileDirKind::Render)
+            .set_title("Fly-through Output Folder")
+            .pick_folder()
+        else {
+            return;
+        };
+        self.prefs
+            .last_dirs
+            .remember_folder(FileDirKind::Render, &dir);
+        let settings = self.ui.flythrough.clone();
+        self.camera_path_preview = None;
+        self.flythrough = Some(FlythroughJob {
+            total: self.camera_path.frame_count(settings.fps),
+            path: self.camera_path.clone(),
+            settings,
+            main,
+            done: 0,
+            dir,
+            started: Instant::now(),
+        });
+    }
+
+    /// Stop the running fly-through render. Frames already written are
+    /// kept.
+    pub(super) fn cancel_flythrough(&mut self) {
+        if let Some(job) = self.flythrough.take() {
+            self.ui
+                .set_status(format!("Fly-through cancelled after {} frames", job.done));
+        }
+    }
+
+    /// Render and write the next fly-through frame; finish the job
+    /// after the last. Called every frame from `RedrawRequested`.
+    pub(super) fn tick_flythrough(&mut self) {
+        let (Some(job), Some(renderer)) = (&mut self.flythrough, &mut self.renderer) else {
+            return;
+        };
+        let FlythroughSettings { width, height, fps } = job.settings;
+        let t = job.done as f32 / fps.max(1) as f32;
+        let Some((position, target)) = job.path.sample(t) else {
+            self.flythrough = None;
+            return;
+        };
+        let mut camera = job.main.clone();
+        camera.position = position;
+        camera.target = target;
+        camera.aspect = width as f32 / height as f32;
+        let path = job.dir.join(frame_file_name(job.done));
+        let written =
+            match renderer.render_offscreen(&camera, width, height, OffscreenStyle::default()) {
+                Some(image) => image
+                    .save_with_format(&path, image::ImageFormat::Png)
+                    .map_err(|e| format!("couldn't write {}: {}", path.display(), e)),
+                None => Err(format!(
+                    "the GPU couldn't render a {}×{} frame",
+                    width, height
+                )),
+            };
+        if let Err(e) = written {
+            log::error!("Fly-through failed: {}", e);
+            self.flythrough = None;
+            self.show_error_dialog("Fly-through Failed", &format!("Rendering stopped: {}.", e));
+            return;
+        }
+        job.done += 1;
+        if job.done >= job.total {
+            if let Some(job) = self.flythrough.take() {
+                self.ui.set_status(format!(
+                    "Fly-through saved: {} frames → {}",
+                    job.total,
+                    job.dir.display()
+                ));
+            }
+        }
+    }
+
+    /// Snapshot of the running render for the Camera Path window.
+    pub(super) fn flythrough_status(&self) -> Option<GenJobStatus> {
+        let job = self.flythrough.as_ref()?;
+        let progress = job.done as f32 / job.total.max(1) as f32;
+        let elapsed = job.started.elapsed();
+        Some(GenJobStatus {
+            label: format!("Fly-through frame {} / {}", job.done, job.total),
+            progress,
+            cancelling: false,
+            remaining: (job.done > 0).then(|| elapsed.div_f32(progress).saturating_sub(elapsed)),
+        })
+    }
+}
@@ -1,11 +1,81 @@
 //! File operations: project new/save/open and VOX import/export.
+//!
+//! Project saves (and autosaves) are encoded on the main thread and
+//! then compressed and written by `io::save_project_in_background`, so
+//! a big world doesn't freeze the window; `tick_background_save`
+//! reports the outcome once the write is done.
 
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use voxelith::{core::Voxel, editor::Socket, io, ui::ExportReport};
+use voxelith::{
+    core::{Layer, ReferenceImage, ReferenceModel, ReferenceView, Scene, Voxel, World},
+    editor::{BonePose, BrushShape, BrushStamp, Rig, Socket, MAX_STAMP_SIDE},
+    io,
+    mesh::{patch_to_mesh, ChunkMesh},
+    prefs::FileDirKind,
+    render::{
+        downsample, fit_reference_image, outline, sprite_sheet, thumbnail_camera, EnvironmentMap,
+        Msaa, OffscreenStyle, SpriteFrame, THUMBNAIL_SIZE,
+    },
+    ui::{ExportReport, MeshExportFormat, MeshExportSettings},
+};
 
+use super::camera_path::{camera_path_from_state, camera_path_to_state};
 use super::App;
 
+/// What a running background save is for.
+pub(super) enum SaveKind {
+    /// File ▸ Save / Save As. `previous_path` is restored if it fails.
+    Project { previous_path: Option<PathBuf> },
+    /// The crash-recovery autosave.
+    Autosave,
+}
+
+/// A project write running on a background thread.
+pub(super) struct PendingSave {
+    pub(super) save: io::BackgroundSave,
+    pub(super) kind: SaveKind,
+}
+
+/// A file named on the command line (`voxelith <file>`), opened once
+/// the window is up.
+pub enum StartupFile {
+    Project(PathBuf),
+    Vox(PathBuf),
+}
+
+impl StartupFile {
+    /// Classify `path` by extension (any case). The error is a
+    /// one-line message for the terminal.
+    pub fn from_path(path: PathBuf) -> Result<Self, String> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        let file = match ext.as_deref() {
+            Some("vxlt") => Self::Project(path),
+            Some("vox") => Self::Vox(path),
+            _ => {
+                return Err(format!(
+                    "{}: unsupported file type (expected a .vxlt project or a .vox model)",
+                    path.display()
+                ))
+            }
+        };
+        if !file.path().is_file() {
+            return Err(format!("{}: no such file", file.path().display()));
+        }
+        Ok(file)
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            Self::Project(path) | Self::Vox(path) => path,
+        }
+    }
+}
+
 /// Rebuild the live `editor::Socket` list from a loaded `EditorState`.
 /// Inverse of `current_editor_state`'s socket mapping; shared by the
 /// open-project and crash-recovery restore paths.
@@ -17,16 +87,57 @@ fn sockets_from_state(state: &io::EditorState) -> Vec<Socket> {
         .collect()
 }
 
+/// Rebuild the rig from a loaded `EditorState`; the inverse of
+/// `current_editor_state`'s bone mapping. Parents are linked once every
+/// bone exists, so a parent listed after its child still resolves.
+fn rig_from_state(state: &io::EditorState) -> Rig {
+    let mut rig = Rig::new();
+    for bone in &state.bones {
+        let index = rig.add_bone(
+            bone.name.clone(),
+            bone.pivot,
+            bone.cells.iter().map(|&[x, y, z]| (x, y, z)),
+        );
+        rig.set_pose(
+            index,
+            BonePose {
+                translation: bone.translation,
+                rotation: bone.rotation,
+            },
+        );
+    }
+    for (index, bone) in state.bones.iter().enumerate() {
+        rig.set_parent(index, bone.parent);
+    }
+    rig
+}
+
 impl App {
+    /// A file dialog that opens in the folder the last `kind` dialog
+    /// picked from, when there was one this install.
+    pub(super) fn file_dialog(&self, kind: FileDirKind) -> rfd::FileDialog {
+        let dialog = rfd::FileDialog::new();
+        match self.prefs.last_dirs.get(kind) {
+            Some(dir) => dialog.set_directory(dir),
+            None => dialog,
+        }
+    }
+
+    /// Remember `file`'s folder for the next `kind` dialog.
+    pub(super) fn remember_dir(&mut self, kind: FileDirKind, file: &Path) {
+        self.prefs.last_dirs.remember_file(kind, file);
+    }
+
     /// Create a new empty project.
     pub(super) fn new_project(&mut self) {
-        self.world.clear();
-        self.editor.history.clear();
+        self.set_scene(Scene::new());
         self.editor.sockets.clear();
+        self.editor.rig = Rig::new();
         self.project_path = None;
         self.unsaved_changes = false;
         if let Some(renderer) = &mut self.renderer {
-            renderer.chunk_meshes.clear();
+            renderer.clear_meshes();
+            self.mesh_worker.cancel_all();
         }
         self.ui.set_status("New project created");
     }
@@ -73,6 +184,34 @@ impl App {
                     normal: s.normal,
                 })
                 .collect(),
+            // Filled in from the scene by `io::Project::from_scene_with_state`.
+            layers: Vec::new(),
+            active_layer: 0,
+            frames: Vec::new(),
+            current_frame: 0,
+            procgen_seeds: self.procgen_seeds.clone(),
+            camera_bookmarks: self.camera_bookmarks,
+            camera_path: camera_path_to_state(&self.camera_path),
+            camera_path_closed: self.camera_path.closed,
+            materials: self.editor.materials.clone(),
+            background: self.ui.background.clone(),
+            lights: self.ui.lights.clone(),
+            references: self.ui.references.clone(),
+            reference_model: self.ui.reference_model.clone(),
+            bones: self
+                .editor
+                .rig
+                .bones()
+                .iter()
+                .map(|b| io::BoneData {
+                    name: b.name.clone(),
+                    parent: b.parent,
+                    pivot: b.pivot,
+                    translation: b.pose.translation,
+                    rotation: b.pose.rotation,
+                    cells: b.cells().iter().map(|&(x, y, z)| [x, y, z]).collect(),
+                })
+                .collect(),
         }
     }
 
@@ -98,15 +237,14 @@ impl App {
     /// false — caller falls back to the default scene — if the file is
     /// unreadable.
     pub(super) fn recover_from_autosave(&mut self, path: &Path) -> bool {
-        let (world, editor_state) = match io::load_world_with_state(path) {
+        let (scene, editor_state) = match io::load_scene_with_state(path) {
             Ok(v) => v,
             Err(e) => {
                 log::warn!("Failed to load autosave {}: {}", path.display(), e);
                 return false;
             }
         };
-        self.world = world;
-        self.editor.history.clear();
+        self.set_scene(scene);
         self.project_path = None;
         self.editor.brush_color = Voxel::from_rgba(
             editor_state.brush_color[0],
@@ -114,15 +252,30 @@ impl App {
             editor_state.brush_color[2],
             editor_state.brush_color[3],
         );
-        self.editor.palette = editor_state
-            .palette
-            .iter()
-            .map(|c| Voxel::from_rgba(c[0], c[1], c[2], c[3]))
-            .collect();
+        self.editor.replace_palette(
+            editor_state
+                .palette
+                .iter()
+                .map(|c| Voxel::from_rgba(c[0], c[1], c[2], c[3]))
+                .collect(),
+        );
         self.editor.current_tool = super::tool_from_index(editor_state.selected_tool as u8);
         self.editor.sockets = sockets_from_state(&editor_state);
+        self.editor.rig = rig_from_state(&editor_state);
+        self.restore_procgen_seeds(editor_state.procgen_seeds.clone());
+        self.camera_bookmarks = editor_state.camera_bookmarks;
+        self.camera_path = camera_path_from_state(
+            &editor_state.camera_path,
+            editor_state.camera_path_closed,
+        );
+        self.restore_materials(editor_state.materials.clone());
+        self.restore_background(editor_state.background.clone());
+        self.restore_lights(editor_state.lights.clone());
+        self.restore_references(editor_state.references.clone());
+        self.restore_reference_model(editor_state.reference_model.clone());
         if let Some(renderer) = &mut self.renderer {
-            renderer.chunk_meshes.clear();
+            renderer.clear_meshes();
+            self.mesh_worker.cancel_all();
             renderer.camera.position = glam::Vec3::new(
                 editor_state.camera_position[0],
                 editor_state.camera_position[1],
@@ -154,31 +307,283 @@ impl App {
 
     /// Prompt for a path and save.
     pub(super) fn save_project_as(&mut self) {
-        let dialog = rfd::FileDialog::new()
+        let dialog = self.file_dialog(FileDirKind::Project)
             .add_filter("Voxelith Project", &["vxlt"])
             .set_title("Save Project As");
 
         if let Some(path) = dialog.save_file() {
+            self.remember_dir(FileDirKind::Project, &path);
             self.do_save_project(path);
         }
     }
 
+    /// Render the visible scene offscreen and PNG-encode it for the
+    /// project's embedded thumbnail. `None` for an empty scene, without
+    /// a renderer, or if the GPU readback fails — the project then just
+    /// saves without one. Draws the chunk meshes currently uploaded, so
+    /// chunks still queued on the mesh worker are missing from it.
+    fn capture_thumbnail(&mut self) -> Option<Vec<u8>> {
+        let (min, max) = self.scene.view().scene_aabb()?;
+        let renderer = self.renderer.as_mut()?;
+        let to_vec = |(x, y, z): (i32, i32, i32)| glam::Vec3::new(x as f32, y as f32, z as f32);
+        let camera = thumbnail_camera(&renderer.camera, to_vec(min), to_vec(max));
+        let image = renderer.render_thumbnail(&camera, THUMBNAIL_SIZE)?;
+        let mut png = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .map_err(|e| log::warn!("Thumbnail encode failed: {}", e))
+            .ok()?;
+        Some(png)
+    }
+
+    /// Render ▸ Screenshot: render the current view offscreen at the
+    /// Screenshot window's size — `supersample`× larger, then
+    /// box-filtered down — and save it as a PNG. Same camera as the
+    /// viewport (only the aspect follows the requested size); editing
+    /// overlays never appear, the grid / axes only on an opaque
+    /// background and only if the viewport shows them.
+    pub(super) fn save_screenshot(&mut self) {
+        let settings = self.ui.screenshot.clone();
+        let factor = settings.supersample.max(1);
+        let style = OffscreenStyle {
+            transparent_background: settings.transparent,
+            show_grid: !settings.transparent && self.ui.viewport.show_grid,
+            show_axes: !settings.transparent && self.ui.viewport.show_axes,
+            downsample: factor,
+        };
+        let Some(path) = self
+            .file_dialog(FileDirKind::Render)
+            .add_filter("PNG Image", &["png"])
+            .set_file_name("screenshot.png")
+            .set_title("Save Screenshot")
+            .save_file()
+        else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Render, &path);
+        let Some(renderer) = self.renderer.as_mut() else {
+            return;
+        };
+        let (width, height) = (settings.width * factor, settings.height * factor);
+        let mut camera = renderer.camera.clone();
+        camera.aspect = settings.width as f32 / settings.height as f32;
+        let Some(image) = renderer.render_offscreen(&camera, width, height, style) else {
+            self.show_error_dialog(
+                "Screenshot Failed",
+                &format!(
+                    "The GPU couldn't render a {}×{} image. Lower the size or the \
+                     supersampling factor and try again.",
+                    width, height
+                ),
+            );
+            return;
+        };
+        let image = downsample(&image, factor);
+        match image.save_with_format(&path, image::ImageFormat::Png) {
+            Ok(()) => self.ui.set_status(format!(
+                "Saved {}×{} screenshot to {}",
+                image.width(),
+                image.height(),
+                file_label(&path)
+            )),
+            Err(e) => self.show_write_error("Screenshot Failed", &path, "save", &e),
+        }
+    }
+
+    /// Render ▸ Sprite Sheet: render the model from every direction of
+    /// the Sprite Sheet window at its exact pixel scale, with MSAA off
+    /// for hard pixel edges, and save one sheet or one PNG per frame.
+    pub(super) fn export_sprites(&mut self) {
+        let Some((min, max)) = self.scene.view().scene_aabb() else {
+            self.ui.set_status("World is empty — nothing to render");
+            return;
+        };
+        let settings = self.ui.sprites.clone();
+        let angles = settings.directions.angles();
+        let to_vec = |(x, y, z): (i32, i32, i32)| glam::Vec3::new(x as f32, y as f32, z as f32);
+        let frame = SpriteFrame::fit(
+            to_vec(min),
+            to_vec(max),
+            settings.view,
+            &angles,
+            settings.pixels_per_voxel,
+        );
+        let target = if settings.sheet {
+            let Some(path) = self
+                .file_dialog(FileDirKind::Render)
+                .add_filter("PNG Image", &["png"])
+                .set_file_name("sprites.png")
+                .set_title("Save Sprite Sheet")
+                .save_file()
+            else {
+                return;
+            };
+            self.remember_dir(FileDirKind::Render, &path);
+            path
+        } else {
+            let Some(dir) = self
+                .file_dialog(FileDirKind::Render)
+                .set_title("Sprite Frames Folder")
+                .pick_folder()
+            else {
+                return;
+            };
+            self.prefs.last_dirs.remember_folder(FileDirKind::Render, &dir);
+            dir
+        };
+        let Some(renderer) = self.renderer.as_mut() else {
+            return;
+        };
+        let previous_msaa = renderer.msaa;
+        renderer.set_msaa(Msaa::Off);
+        let style = OffscreenStyle {
+            transparent_background: true,
+            ..OffscreenStyle::default()
+        };
+        let frames: Option<Vec<_>> = angles
+            .iter()
+            .map(|&angle| {
+                let camera = frame.camera(settings.view, angle);
+                let image = renderer.render_offscreen(&camera, frame.width, frame.height, style)?;
+                Some(if settings.outline {
+                    outline(&image, settings.outline_color)
+                } else {
+                    image
+                })
+            })
+            .collect();
+        renderer.set_msaa(previous_msaa);
+        let Some(frames) = frames else {
+            self.show_error_dialog(
+                "Sprite Export Failed",
+                &format!(
+                    "The GPU couldn't render a {}×{} sprite. Lower the scale and try again.",
+                    frame.width, frame.height
+                ),
+            );
+            return;
+        };
+
+        if settings.sheet {
+            let sheet = sprite_sheet(&frames);
+            match sheet.save_with_format(&target, image::ImageFormat::Png) {
+                Ok(()) => self.ui.set_status(format!(
+                    "Saved {} sprites of {}×{} to {}",
+                    frames.len(),
+                    frame.width,
+                    frame.height,
+                    file_label(&target)
+                )),
+                Err(e) => self.show_write_error("Sprite Export Failed", &target, "save", &e),
+            }
+            return;
+        }
+        for (image, angle) in frames.iter().zip(&angles) {
+            let path = target.join(format!("sprite_{:03}.png", angle));
+            if let Err(e) = image.save_with_format(&path, image::ImageFormat::Png) {
+                self.show_write_error("Sprite Export Failed", &path, "save", &e);
+                return;
+            }
+        }
+        self.ui.set_status(format!(
+            "Saved {} sprites of {}×{} to {}",
+            frames.len(),
+            frame.width,
+            frame.height,
+            file_label(&target)
+        ));
+    }
+
     fn do_save_project(&mut self, path: PathBuf) {
+        // One write at a time: a save still running finishes first.
+        self.finish_background_save();
         let editor_state = self.current_editor_state();
+        let mut project = io::Project::from_scene_with_state(&self.scene, editor_state);
+        // Building the project read every chunk, so a cold one that
+        // can't be decoded has shown itself by now. Its voxels read as
+        // air; writing them would overwrite the real ones on disk.
+        let unreadable = self.scene.unreadable_chunks();
+        if unreadable > 0 {
+            self.show_error_dialog(
+                "Save blocked",
+                &format!(
+                    "{} streamed-out chunk(s) couldn't be read back, so saving now would \
+                     replace them with empty space. Nothing was written to \"{}\".\n\n\
+                     Check the disk holding the temporary spill file and try again.",
+                    unreadable,
+                    file_label(&path)
+                ),
+            );
+            self.ui.set_status("Save blocked — some chunks couldn't be read back");
+            return;
+        }
+        project.thumbnail = self.capture_thumbnail();
 
-        match io::save_world_with_state(&self.world, editor_state, &path) {
-            Ok(_) => {
-                self.project_path = Some(path.clone());
-                self.unsaved_changes = false;
+        // The project now holds everything to write, so edits made
+        // while it's written count as unsaved again.
+        let previous_path = self.project_path.replace(path.clone());
+        self.unsaved_changes = false;
+        self.ui.set_status(format!("Saving {}…", file_label(&path)));
+        self.background_save = Some(PendingSave {
+            save: io::save_project_in_background(project, path),
+            kind: SaveKind::Project { previous_path },
+        });
+    }
+
+    /// Start the crash-recovery autosave of the current scene to
+    /// `path` on the background save thread.
+    pub(super) fn start_autosave(&mut self, path: PathBuf) {
+        let state = self.current_editor_state();
+        let project = io::Project::from_scene_with_state(&self.scene, state);
+        // As in `do_save_project`: don't write air over unreadable chunks.
+        if self.scene.unreadable_chunks() > 0 {
+            log::warn!("Autosave skipped: some chunks couldn't be read back");
+            return;
+        }
+        self.unsaved_changes = false;
+        self.background_save = Some(PendingSave {
+            save: io::save_project_in_background(project, path),
+            kind: SaveKind::Autosave,
+        });
+    }
+
+    /// Per-frame check on the running background save: report it once
+    /// the write is done.
+    pub(super) fn tick_background_save(&mut self) {
+        if self
+            .background_save
+            .as_ref()
+            .is_some_and(|pending| pending.save.is_finished())
+        {
+            self.finish_background_save();
+        }
+    }
+
+    /// Wait for the running background save, if any, and report how it
+    /// went. Called before another save starts and before exiting.
+    pub(super) fn finish_background_save(&mut self) {
+        let Some(PendingSave { save, kind }) = self.background_save.take() else {
+            return;
+        };
+        let path = save.path().to_path_buf();
+        match (save.wait(), kind) {
+            (Ok(()), SaveKind::Autosave) => log::info!("Autosaved to {}", path.display()),
+            (Err(e), SaveKind::Autosave) => {
+                // Retried next interval.
+                log::warn!("Autosave failed: {}", e);
+                self.unsaved_changes = true;
+            }
+            (Ok(()), SaveKind::Project { .. }) => {
                 self.touch_recent(&path);
-                let filename = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("project");
-                self.ui.set_status(format!("Saved: {}", filename));
+                self.ui.set_status(format!("Saved: {}", file_label(&path)));
             }
-            Err(e) => {
+            (Err(e), SaveKind::Project { previous_path }) => {
                 log::error!("Failed to save project {:?}: {}", path, e);
+                // Unless another project was opened in the meantime.
+                if self.project_path.as_deref() == Some(path.as_path()) {
+                    self.project_path = previous_path;
+                    self.unsaved_changes = true;
+                }
                 self.show_write_error("Save failed", &path, "save", &e);
                 self.ui.set_status(format!(
                     "Save failed: {} — your work is NOT saved",
@@ -190,7 +595,7 @@ impl App {
 
     /// Prompt for a path and open a project.
     pub(super) fn open_project(&mut self) {
-        let dialog = rfd::FileDialog::new()
+        let dialog = self.file_dialog(FileDirKind::Project)
             .add_filter("Voxelith Project", &["vxlt"])
             .add_filter("All Files", &["*"])
             .set_title("Open Project");
@@ -198,16 +603,23 @@ impl App {
         let Some(path) = dialog.pick_file() else {
             return;
         };
-        self.do_open_project(path);
+        self.remember_dir(FileDirKind::Project, &path);
+        self.open_project_in_background(path);
+    }
+
+    /// Open `path` on the next frame, so the status bar can show
+    /// "Opening …" while the load blocks.
+    pub(super) fn open_project_in_background(&mut self, path: PathBuf) {
+        let label = format!("Opening {}", file_label(&path));
+        self.defer_file_task(label, move |app| app.do_open_project(path));
     }
 
     /// Open a project from a known path (used by `open_project` and
     /// the Open Recent menu). Touches the recent-files MRU on success.
     pub(super) fn do_open_project(&mut self, path: PathBuf) {
-        match io::load_world_with_state(&path) {
-            Ok((world, editor_state)) => {
-                self.world = world;
-                self.editor.history.clear();
+        match io::load_scene_with_state(&path) {
+            Ok((scene, editor_state)) => {
+                self.set_scene(scene);
                 self.project_path = Some(path.clone());
 
                 self.editor.brush_color = Voxel::from_rgba(
@@ -216,17 +628,32 @@ impl App {
                     editor_state.brush_color[2],
                     editor_state.brush_color[3],
                 );
-                self.editor.palette = editor_state
-                    .palette
-                    .iter()
-                    .map(|c| Voxel::from_rgba(c[0], c[1], c[2], c[3]))
-                    .collect();
+                self.editor.replace_palette(
+                    editor_state
+                        .palette
+                        .iter()
+                        .map(|c| Voxel::from_rgba(c[0], c[1], c[2], c[3]))
+                        .collect(),
+                );
                 self.editor.current_tool =
                     super::tool_from_index(editor_state.selected_tool as u8);
                 self.editor.sockets = sockets_from_state(&editor_state);
+                self.editor.rig = rig_from_state(&editor_state);
+                self.restore_procgen_seeds(editor_state.procgen_seeds.clone());
+                self.camera_bookmarks = editor_state.camera_bookmarks;
+                self.camera_path = camera_path_from_state(
+                    &editor_state.camera_path,
+                    editor_state.camera_path_closed,
+                );
+                self.restore_materials(editor_state.materials.clone());
+                self.restore_background(editor_state.background.clone());
+                self.restore_lights(editor_state.lights.clone());
+                self.restore_references(editor_state.references.clone());
+                self.restore_reference_model(editor_state.reference_model.clone());
 
                 if let Some(renderer) = &mut self.renderer {
-                    renderer.chunk_meshes.clear();
+                    renderer.clear_meshes();
+                    self.mesh_worker.cancel_all();
                     renderer.camera.position = glam::Vec3::new(
                         editor_state.camera_position[0],
                         editor_state.camera_position[1],
@@ -265,353 +692,1076 @@ impl App {
         }
     }
 
+    /// Open the file given on the command line, through the same
+    /// deferred path as File ▸ Open / Import.
+    pub(super) fn open_startup_file(&mut self, file: StartupFile) {
+        match file {
+            StartupFile::Project(path) => self.open_project_in_background(path),
+            StartupFile::Vox(path) => self.import_vox_from(path),
+        }
+    }
+
+    /// Viewport Settings ▸ Background ▸ Browse...: pick the image to
+    /// show behind the scene. The next frame loads it.
+    pub(super) fn pick_background_image(&mut self) {
+        let dialog = self
+            .file_dialog(FileDirKind::Import)
+            .add_filter("Images", &["hdr", "png", "jpg", "jpeg"])
+            .add_filter("Radiance HDR", &["hdr"])
+            .set_title("Background Image");
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Import, &path);
+        self.ui.background.hdri_path = Some(path);
+    }
+
+    /// Decode the background image at `path` on the next frame and hand
+    /// it to the renderer. An unreadable file leaves the fallback color
+    /// showing.
+    pub(super) fn load_background_image(&mut self, path: PathBuf) {
+        let label = format!("Loading {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let map = match image::open(&path) {
+                Ok(image) => Some(EnvironmentMap::from_image(image)),
+                Err(e) => {
+                    app.ui.set_status(format!(
+                        "Couldn't load background image {}: {}",
+                        file_label(&path),
+                        e
+                    ));
+                    None
+                }
+            };
+            if let Some(renderer) = &mut app.renderer {
+                renderer.set_environment_map(map.as_ref());
+            }
+        });
+    }
+
+    /// Ask for an image to add as a reference plane facing Front, and
+    /// select it in the panel.
+    pub(super) fn pick_reference_image(&mut self) {
+        let dialog = self
+            .file_dialog(FileDirKind::Import)
+            .add_filter("Images", &["png", "jpg", "jpeg", "bmp", "webp"])
+            .set_title("Reference Image");
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Import, &path);
+        self.ui.references.push(ReferenceImage::new(path, ReferenceView::Front));
+        self.ui.selected_reference = Some(self.ui.references.len() - 1);
+    }
+
+    /// Decode the reference images at `paths` on the next frame and
+    /// hand them to the renderer. A file that won't load leaves its
+    /// planes undrawn.
+    pub(super) fn load_reference_images(&mut self, paths: Vec<PathBuf>) {
+        let label = match paths.as_slice() {
+            [path] => format!("Loading {}", file_label(path)),
+            _ => format!("Loading {} reference images", paths.len()),
+        };
+        self.defer_file_task(label, move |app| {
+            for path in paths {
+                match image::open(&path) {
+                    Ok(image) => {
+                        let image = fit_reference_image(image);
+                        if let Some(renderer) = &mut app.renderer {
+                            renderer.set_reference_image(&path, &image);
+                        }
+                    }
+                    Err(e) => app.ui.set_status(format!(
+                        "Couldn't load reference image {}: {}",
+                        file_label(&path),
+                        e
+                    )),
+                }
+            }
+            // Rebuild the planes with the new images.
+            app.applied_references = None;
+        });
+    }
+
+    /// Ask for a model to show as the ghost, in place of any before.
+    pub(super) fn pick_reference_model(&mut self) {
+        let dialog = self
+            .file_dialog(FileDirKind::Import)
+            .add_filter("Models", &["vox", "obj"])
+            .add_filter("MagicaVoxel", &["vox"])
+            .add_filter("Wavefront OBJ", &["obj"])
+            .set_title("Ghost Model");
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Import, &path);
+        self.ui.reference_model = Some(ReferenceModel::new(path));
+    }
+
+    /// Read the ghost model at `path` on the next frame and keep its
+    /// mesh for the renderer. A file that won't load leaves no ghost.
+    pub(super) fn load_reference_model(&mut self, path: PathBuf) {
+        let label = format!("Loading {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let is_obj = ReferenceModel::new(path.clone()).is_obj();
+            let mesh = std::fs::File::open(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|mut file| {
+                    if is_obj {
+                        io::import_obj_mesh(std::io::BufReader::new(file), GHOST_OBJ_COLOR)
+                            .map_err(|e| e.to_string())
+                    } else {
+                        io::import_vox_full(&mut file, app.ui.general.vox_axes)
+                            .map(|import| world_mesh(&import.world))
+                            .map_err(|e| e.to_string())
+                    }
+                });
+            match mesh {
+                Ok(mesh) => app.reference_model_source = Some((path, mesh)),
+                Err(e) => app.ui.set_status(format!(
+                    "Couldn't load ghost model {}: {}",
+                    file_label(&path),
+                    e
+                )),
+            }
+            // Place the new mesh.
+            app.applied_reference_model = None;
+        });
+    }
+
+    /// Ask for a `.vox` to use as the brush stamp.
+    pub(super) fn pick_brush_stamp(&mut self) {
+        let dialog = self
+            .file_dialog(FileDirKind::Import)
+            .add_filter("MagicaVoxel", &["vox"])
+            .set_title("Brush Stamp");
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Import, &path);
+        match read_brush_stamp(&path, self.ui.general.vox_axes) {
+            Ok(stamp) => {
+                self.ui.set_status(format!("Stamp brush: {}", stamp.name));
+                self.editor.brush.stamp = Some(Arc::new(stamp));
+                self.editor.brush.stamp_path = Some(path);
+                self.editor.brush.shape = BrushShape::Stamp;
+            }
+            Err(e) => self.ui.set_status(e),
+        }
+    }
+
     /// Prompt for a VOX file and import it.
     pub(super) fn import_vox(&mut self) {
-        let dialog = rfd::FileDialog::new()
+        let dialog = self.file_dialog(FileDirKind::Import)
             .add_filter("MagicaVoxel", &["vox"])
             .set_title("Import MagicaVoxel File");
 
         let Some(path) = dialog.pick_file() else {
             return;
         };
+        self.remember_dir(FileDirKind::Import, &path);
+        self.import_vox_from(path);
+    }
 
-        match std::fs::File::open(&path) {
-            Ok(mut file) => match io::import_vox(&mut file) {
-                Ok(world) => {
-                    self.world = world;
-                    self.editor.history.clear();
-                    // A .vox carries no sockets; the imported model
-                    // replaces the scene, so drop any from the old one.
-                    self.editor.sockets.clear();
-                    if let Some(renderer) = &mut self.renderer {
-                        renderer.chunk_meshes.clear();
+    /// Import the VOX file at `path` on the next frame.
+    fn import_vox_from(&mut self, path: PathBuf) {
+        let label = format!("Importing {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            match std::fs::File::open(&path) {
+                Ok(mut file) => match io::import_vox_full(&mut file, app.ui.general.vox_axes) {
+                    Ok(import) => {
+                        app.set_scene(Scene::from_world(import.world));
+                        // Voxels arrive stamped with the file's MATL
+                        // materials; unknown chunks wait for export.
+                        app.restore_materials(import.materials);
+                        app.vox_chunks = import.chunks;
+                        // A .vox carries no sockets; the imported model
+                        // replaces the scene, so drop any from the old one.
+                        app.editor.sockets.clear();
+                        app.editor.rig = Rig::new();
+                        if let Some(renderer) = &mut app.renderer {
+                            renderer.clear_meshes();
+                            app.mesh_worker.cancel_all();
+                        }
+                        app.rebuild_all_meshes();
+                        // Imported world replaces everything; the previous
+                        // camera target is now meaningless. Anchor orbit
+                        // pivot on the imported scene so middle-orbit
+                        // immediately circles the new model. (`do_open_project`
+                        // doesn't do this because it restores the saved
+                        // camera pose verbatim — but .vox files don't carry
+                        // camera state.)
+                        app.recenter_camera_on_scene();
+                        app.unsaved_changes = false;
+                        let filename = path
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or("file");
+                        app.ui.set_status(format!("Imported: {}", filename));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to import VOX from {:?}: {}", path, e);
+                        let (short, detail) = describe_vox_import_error(&e, &path);
+                        app.show_error_dialog("Import failed", &detail);
+                        app.ui.set_status(short);
                     }
-                    self.rebuild_all_meshes();
-                    // Imported world replaces everything; the previous
-                    // camera target is now meaningless. Anchor orbit
-                    // pivot on the imported scene so middle-orbit
-                    // immediately circles the new model. (`do_open_project`
-                    // doesn't do this because it restores the saved
-                    // camera pose verbatim — but .vox files don't carry
-                    // camera state.)
-                    self.recenter_camera_on_scene();
-                    self.unsaved_changes = false;
-                    self.touch_recent(&path);
+                },
+                Err(e) => {
+                    log::error!("Failed to open file {:?}: {}", path, e);
+                    let detail = format!(
+                        "Couldn't open \"{}\" — {}.\n\nCheck the file still exists \
+                         and isn't locked by another app.",
+                        file_label(&path),
+                        e
+                    );
+                    app.show_error_dialog("Import failed", &detail);
+                    app.ui.set_status(format!("Import failed: {}", e));
+                }
+            }
+        });
+    }
+
+    /// File ▸ Export ▸ OBJ / glTF: show the Export dialog with `format`
+    /// selected. The dialog's Export… button comes back as
+    /// `UiAction::ExportMesh`.
+    pub(super) fn open_export_dialog(&mut self, format: MeshExportFormat) {
+        self.ui.mesh_export.format = format;
+        self.ui.state.show_export = true;
+    }
+
+    /// Prompt for a path and write the visible scene as OBJ or glTF
+    /// Binary per the Export dialog's format and [`io::ExportOptions`]
+    /// (surface, colors, pivot / up-axis / scale, merged or per-chunk
+    /// meshes). Status bar reports triangle / chunk counts so the user
+    /// knows the export wasn't silently empty, and a non-empty export
+    /// opens the export report.
+    pub(super) fn export_mesh(&mut self) {
+        let MeshExportSettings { format, options } = self.ui.mesh_export;
+        let (filter, ext, title) = match format {
+            MeshExportFormat::Obj => ("Wavefront OBJ", "obj", "Export as Wavefront OBJ"),
+            MeshExportFormat::Glb => ("glTF Binary", "glb", "Export as glTF Binary"),
+        };
+        let dialog = self.file_dialog(FileDirKind::Export)
+            .add_filter(filter, &[ext])
+            .set_title(title);
+
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Export, &path);
+
+        let label = format!("Exporting {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let sockets = match format {
+                MeshExportFormat::Obj => Vec::new(),
+                MeshExportFormat::Glb => app.socket_export_nodes(),
+            };
+            // (vertices, triangles, chunks, GLB byte size, atlas size)
+            let result = match format {
+                MeshExportFormat::Obj => io::export_obj_with_options(app.scene.view(), &path, &options)
+                    .map(|s| (s.vertex_count, s.triangle_count, s.chunk_count, None, s.atlas_size))
+                    .map_err(|e| e.to_string()),
+                MeshExportFormat::Glb => {
+                    io::export_glb_with_options(app.scene.view(), &sockets, &path, &options)
+                        .map(|s| {
+                            let size = Some(s.byte_size);
+                            (s.vertex_count, s.triangle_count, s.chunk_count, size, s.atlas_size)
+                        })
+                        .map_err(|e| e.to_string())
+                }
+            };
+            match result {
+                Ok((vertices, triangles, chunks, byte_size, atlas_size)) => {
                     let filename = path
                         .file_name()
                         .and_then(|n| n.to_str())
                         .unwrap_or("file");
-                    self.ui.set_status(format!("Imported: {}", filename));
+                    let msg = if triangles == 0 {
+                        format!("Exported: {} (empty — no geometry)", filename)
+                    } else if let Some(bytes) = byte_size {
+                        let kib = (bytes as f32) / 1024.0;
+                        format!(
+                            "Exported: {} ({} tris, {} chunks, {:.1} KiB)",
+                            filename, triangles, chunks, kib
+                        )
+                    } else {
+                        format!("Exported: {} ({} tris, {} chunks)", filename, triangles, chunks)
+                    };
+                    app.ui.set_status(msg);
+                    let mut notes = socket_note(sockets.len());
+                    if format == MeshExportFormat::Obj && atlas_size.is_some() {
+                        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("Voxelith");
+                        notes.push(format!("Texture written beside it as {stem}.png + {stem}.mtl"));
+                    }
+                    if triangles > 0 {
+                        app.set_export_report(
+                            &path,
+                            ExportReport {
+                                format: format.label().into(),
+                                mesh_source: mesh_source(options.surface).into(),
+                                triangles: Some(triangles),
+                                vertices: Some(vertices),
+                                chunks: Some(chunks),
+                                color_model: match (atlas_size, options.colors) {
+                                    (Some([w, h]), io::GlbColors::Shaded) => {
+                                        format!("{w}×{h} texture atlas (face shading baked)")
+                                    }
+                                    (Some([w, h]), io::GlbColors::Flat) => {
+                                        format!("{w}×{h} texture atlas (flat, unshaded)")
+                                    }
+                                    (None, io::GlbColors::Shaded) => {
+                                        "Per-vertex RGBA (shading + AO baked)".into()
+                                    }
+                                    (None, io::GlbColors::Flat) => {
+                                        "Per-vertex RGBA (flat, unshaded)".into()
+                                    }
+                                },
+                                notes,
+                                ..Default::default()
+                            },
+                        );
+                    }
                 }
                 Err(e) => {
-                    log::error!("Failed to import VOX from {:?}: {}", path, e);
-                    let (short, detail) = describe_vox_import_error(&e, &path);
-                    self.show_error_dialog("Import failed", &detail);
-                    self.ui.set_status(short);
+                    log::error!("Failed to export {}: {}", format.label(), e);
+                    app.show_write_error("Export failed", &path, "export", &e);
+                    app.ui
+                        .set_status(format!("Export failed: {}", file_label(&path)));
                 }
-            },
-            Err(e) => {
-                log::error!("Failed to open file {:?}: {}", path, e);
-                let detail = format!(
-                    "Couldn't open \"{}\" — {}.\n\nCheck the file still exists \
-                     and isn't locked by another app.",
-                    file_label(&path),
-                    e
-                );
-                self.show_error_dialog("Import failed", &detail);
-                self.ui.set_status(format!("Import failed: {}", e));
             }
-        }
+        });
     }
 
-    /// OBJ export with Marching Cubes smoothing. `blur` selects the
-    /// strength: `false` keeps thin features by running MC on the
-    /// raw 0/1 density (rounded-cube look); `true` runs a 3×3×3 blur
-    /// first for clay-like terrain output but dissolves sparse
-    /// 1-cell features.
-    pub(super) fn export_obj_smoothed(&mut self, blur: bool) {
-        let title = if blur {
-            "Export Smoothed OBJ (heavy / clay)"
-        } else {
-            "Export Smoothed OBJ (light / preserve detail)"
+    /// Prompt for a path and export to VOX.
+    pub(super) fn export_vox(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Export)
+            .add_filter("MagicaVoxel", &["vox"])
+            .set_title("Export as MagicaVoxel");
+
+        let Some(path) = dialog.save_file() else {
+            return;
         };
-        let dialog = rfd::FileDialog::new()
-            .add_filter("Wavefront OBJ", &["obj"])
-            .set_title(title);
+        self.remember_dir(FileDirKind::Export, &path);
+
+        let label = format!("Exporting {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            match std::fs::File::create(&path) {
+                Ok(mut file) => match io::export_vox_with_chunks(
+                    app.scene.view(),
+                    &app.editor.materials,
+                    &app.vox_chunks,
+                    app.ui.general.vox_axes,
+                    &mut file,
+                ) {
+                    Ok(stats) => {
+                        let overflow = stats.palette_overflow;
+                        let filename = path
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or("file");
+                        let msg = if overflow > 0 {
+                            format!(
+                                "Exported: {} ({} colors quantized — VOX is 255-color)",
+                                filename, overflow
+                            )
+                        } else {
+                            format!("Exported: {}", filename)
+                        };
+                        app.ui.set_status(msg);
+                        let mut notes = Vec::new();
+                        if stats.models > 1 {
+                            notes.push(format!(
+                                "World exceeds 256 on an axis — split into {} models \
+                                 (MagicaVoxel scene graph, v200)",
+                                stats.models
+                            ));
+                        }
+                        if overflow > 0 {
+                            notes.push(format!(
+                                "{} colors quantized to the nearest of 254 \
+                                 palette slots",
+                                overflow
+                            ));
+                        }
+                        app.set_export_report(
+                            &path,
+                            ExportReport {
+                                format: "MagicaVoxel (.vox)".into(),
+                                color_model: "254-color palette".into(),
+                                notes,
+                                ..Default::default()
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        log::error!("Failed to export VOX: {}", e);
+                        app.show_write_error("Export failed", &path, "export", &e);
+                        app.ui
+                            .set_status(format!("Export failed: {}", file_label(&path)));
+                    }
+                },
+                Err(e) => {
+                    log::error!("Failed to create file {:?}: {}", path, e);
+                    app.show_write_error("Export failed", &path, "create", &e);
+                    app.ui
+                        .set_status(format!("Export failed: {}", file_label(&path)));
+                }
+            }
+        });
+    }
+
+    /// Prompt for a path and export through a registered exporter plugin.
+    pub(super) fn export_plugin(&mut self, id: &str) {
+        let Some(plugin) = voxelith::plugin::registry().exporter(id) else {
+            self.ui.set_status(format!("Exporter plugin \"{id}\" is not registered"));
+            return;
+        };
+        let meta = plugin.metadata();
+        let dialog = self.file_dialog(FileDirKind::Export)
+            .add_filter(meta.name, meta.extensions)
+            .set_title(format!("Export as {}", meta.name));
 
         let Some(path) = dialog.save_file() else {
             return;
         };
+        self.remember_dir(FileDirKind::Export, &path);
 
-        match io::export_obj_smoothed(&self.world, &path, blur) {
-            Ok(stats) => {
-                self.touch_recent(&path);
-                let filename = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("file");
-                let mode = if blur { "heavy" } else { "light" };
-                let msg = if stats.triangle_count == 0 {
-                    format!("Exported: {} (empty — no geometry)", filename)
-                } else {
-                    format!(
-                        "Exported (smoothed, {}): {} ({} tris)",
-                        mode, filename, stats.triangle_count
-                    )
-                };
-                self.ui.set_status(msg);
-                if stats.triangle_count > 0 {
-                    self.set_export_report(
+        let label = format!("Exporting {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            match plugin.export(app.scene.view(), &path) {
+                Ok(()) => {
+                    app.ui.set_status(format!("Exported: {}", file_label(&path)));
+                    app.set_export_report(
                         &path,
                         ExportReport {
-                            format: "Wavefront OBJ (.obj)".into(),
-                            mesh_source: smoothed_mesh_source(blur).into(),
-                            triangles: Some(stats.triangle_count),
-                            vertices: Some(stats.vertex_count),
-                            chunks: Some(stats.chunk_count),
-                            color_model: "Per-vertex RGBA".into(),
+                            format: meta.name.into(),
+                            mesh_source: "—".into(),
+                            notes: vec![format!("Written by the \"{}\" plugin", meta.id)],
                             ..Default::default()
                         },
                     );
                 }
+                Err(e) => {
+                    log::error!("Plugin export {} failed: {}", meta.id, e);
+                    app.show_write_error("Export failed", &path, "export", &e);
+                    app.ui
+                        .set_status(format!("Export failed: {}", file_label(&path)));
+                }
             }
-            Err(e) => {
-                log::error!("Failed to export smoothed OBJ: {}", e);
-                self.show_write_error("Export failed", &path, "export", &e);
-                self.ui
-                    .set_status(format!("Export failed: {}", file_label(&path)));
-            }
-        }
+        });
     }
 
-    /// GLB export with Marching Cubes smoothing. `blur` matches
-    /// `export_obj_smoothed`: light (no blur) preserves detail,
-    /// heavy (3×3×3 blur) is clay-like and best for terrain.
-    pub(super) fn export_glb_smoothed(&mut self, blur: bool) {
-        let title = if blur {
-            "Export Smoothed glTF Binary (heavy / clay)"
-        } else {
-            "Export Smoothed glTF Binary (light / preserve detail)"
+    /// Export every animation frame to its own file, named
+    /// `<stem>_000.<ext>`, `<stem>_001.<ext>`, … after the chosen one
+    /// in timeline order. `format` is [`io::FileFormat::Vox`] or
+    /// [`io::FileFormat::Obj`]; each frame is written like the single
+    /// export (visible layers composited, greedy mesh for OBJ).
+    pub(super) fn export_frames(&mut self, format: io::FileFormat) {
+        let (filter, extensions) = format.filter();
+        let dialog = self.file_dialog(FileDirKind::Export)
+            .add_filter(filter, extensions)
+            .set_title(format!("Export Frames as {}", format.name()));
+
+        let Some(path) = dialog.save_file() else {
+            return;
         };
-        let dialog = rfd::FileDialog::new()
+        self.remember_dir(FileDirKind::Export, &path);
+
+        let label = format!("Exporting frames to {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let count = app.scene.frames().len();
+            let paths = io::numbered_paths(&path, count, "frame", format.extension());
+            for (i, frame_path) in paths.iter().enumerate() {
+                let world = app.scene.frame_world(i);
+                let written: Result<(), Box<dyn std::error::Error>> =
+                    if format == io::FileFormat::Obj {
+                        io::export_obj(&world, frame_path).map(drop).map_err(Into::into)
+                    } else {
+                        std::fs::File::create(frame_path)
+                            .map_err(Into::into)
+                            .and_then(|mut file| {
+                                io::export_vox_with_chunks(
+                                    &world,
+                                    &app.editor.materials,
+                                    &app.vox_chunks,
+                                    app.ui.general.vox_axes,
+                                    &mut file,
+                                )
+                                .map(drop)
+                                .map_err(Into::into)
+                            })
+                    };
+                if let Err(e) = written {
+                    log::error!("Failed to export frame {}: {}", i, e);
+                    app.show_write_error("Export failed", frame_path, "export", &e);
+                    app.ui
+                        .set_status(format!("Export failed: {}", file_label(frame_path)));
+                    return;
+                }
+            }
+            let first = &paths[0];
+            app.ui
+                .set_status(format!("Exported {} frames ({}…)", count, file_label(first)));
+            let holds = app
+                .scene
+                .frames()
+                .iter()
+                .map(|f| f.duration_ms.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            app.set_export_report(
+                first,
+                ExportReport {
+                    format: format!("{} frame sequence", format.name()),
+                    mesh_source: if format == io::FileFormat::Obj {
+                        "Greedy mesh".into()
+                    } else {
+                        "—".into()
+                    },
+                    notes: vec![
+                        format!("{} files, one per frame, in timeline order", count),
+                        format!("Frame hold times (ms), not stored in the files: {}", holds),
+                    ],
+                    ..Default::default()
+                },
+            );
+        });
+    }
+
+    /// Export every animation frame into one `.glb`: a mesh and node
+    /// per frame plus a looping `Flipbook` animation that shows them in
+    /// turn for their hold times (see `io::export_glb_animation`).
+    pub(super) fn export_frames_glb(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Export)
             .add_filter("glTF Binary", &["glb"])
-            .set_title(title);
+            .set_title("Export Frames as Animated glTF");
 
         let Some(path) = dialog.save_file() else {
             return;
         };
+        self.remember_dir(FileDirKind::Export, &path);
 
-        let sockets = self.socket_export_nodes();
-        match io::export_glb_smoothed(&self.world, &sockets, &path, blur) {
-            Ok(stats) => {
-                self.touch_recent(&path);
-                let filename = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("file");
-                let mode = if blur { "heavy" } else { "light" };
-                let msg = if stats.triangle_count == 0 {
-                    format!("Exported: {} (empty — no geometry)", filename)
-                } else {
-                    let kib = (stats.byte_size as f32) / 1024.0;
-                    format!(
-                        "Exported (smoothed, {}): {} ({} tris, {:.1} KiB)",
-                        mode, filename, stats.triangle_count, kib
-                    )
-                };
-                self.ui.set_status(msg);
-                if stats.triangle_count > 0 {
-                    self.set_export_report(
+        let label = format!("Exporting {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let frames = app.scene.frames();
+            let worlds: Vec<_> = (0..frames.len()).map(|i| app.scene.frame_world(i)).collect();
+            let sequence: Vec<io::GlbFrame> = frames
+                .iter()
+                .zip(&worlds)
+                .map(|(frame, world)| io::GlbFrame {
+                    name: &frame.name,
+                    world,
+                    duration_ms: frame.duration_ms,
+                })
+                .collect();
+            let total_ms: u64 = frames.iter().map(|f| f.duration_ms as u64).sum();
+            match io::export_glb_animation(&sequence, &path, io::GlbColors::Shaded) {
+                Ok(stats) => {
+                    app.ui.set_status(format!(
+                        "Exported: {} ({} frames, {} tris)",
+                        file_label(&path),
+                        sequence.len(),
+                        stats.triangle_count
+                    ));
+                    let mut notes = vec![format!(
+                        "{} frames, one node each; \"Flipbook\" animation loops every {:.2} s",
+                        sequence.len(),
+                        total_ms as f32 / 1000.0
+                    )];
+                    if sequence.len() > 1 {
+                        notes.push(
+                            "Frames switch by node scale (1 shown, 0 hidden) with STEP keys"
+                                .into(),
+                        );
+                    }
+                    app.set_export_report(
                         &path,
                         ExportReport {
-                            format: "glTF Binary (.glb)".into(),
-                            mesh_source: smoothed_mesh_source(blur).into(),
+                            format: "glTF Binary (.glb), animated".into(),
+                            mesh_source: "Greedy mesh".into(),
                             triangles: Some(stats.triangle_count),
                             vertices: Some(stats.vertex_count),
                             chunks: Some(stats.chunk_count),
                             color_model: "Per-vertex RGBA".into(),
-                            notes: socket_note(sockets.len()),
+                            notes,
                             ..Default::default()
                         },
                     );
                 }
+                Err(e) => {
+                    log::error!("Failed to export animated GLB: {}", e);
+                    app.show_write_error("Export failed", &path, "export", &e);
+                    app.ui
+                        .set_status(format!("Export failed: {}", file_label(&path)));
+                }
             }
-            Err(e) => {
-                log::error!("Failed to export smoothed GLB: {}", e);
-                self.show_write_error("Export failed", &path, "export", &e);
-                self.ui
-                    .set_status(format!("Export failed: {}", file_label(&path)));
-            }
-        }
+        });
     }
 
-    /// Prompt for a path and export to glTF Binary (.glb). Same
-    /// mesh-collection path as OBJ (greedy meshing across all
-    /// chunks), but writes a single self-contained .glb that imports
-    /// directly into Unity / Unreal / Godot / Blender. Status bar
-    /// reports vertex / triangle / chunk counts and the resulting
-    /// file size so the user can sanity-check large exports.
-    pub(super) fn export_glb(&mut self) {
-        let dialog = rfd::FileDialog::new()
+    /// Export the view as a rigged glTF: the unrigged voxels as a
+    /// `Body` node and one node per bone, parents before children, each
+    /// carrying its pose as the node transform (`Rig::node_transform`).
+    /// Sockets ride along as in the plain export.
+    pub(super) fn export_rigged_glb(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Export)
             .add_filter("glTF Binary", &["glb"])
-            .set_title("Export as glTF Binary");
+            .set_title("Export Rigged glTF");
 
         let Some(path) = dialog.save_file() else {
             return;
         };
+        self.remember_dir(FileDirKind::Export, &path);
 
-        let sockets = self.socket_export_nodes();
-        match io::export_glb(&self.world, &sockets, &path) {
-            Ok(stats) => {
-                self.touch_recent(&path);
-                let filename = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("file");
-                let msg = if stats.triangle_count == 0 {
-                    format!("Exported: {} (empty — no geometry)", filename)
-                } else {
-                    let kib = (stats.byte_size as f32) / 1024.0;
-                    format!(
-                        "Exported: {} ({} tris, {} chunks, {:.1} KiB)",
-                        filename, stats.triangle_count, stats.chunk_count, kib
-                    )
-                };
-                self.ui.set_status(msg);
-                if stats.triangle_count > 0 {
-                    self.set_export_report(
+        let label = format!("Exporting {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let rig = &app.editor.rig;
+            let bones = rig.bones();
+            let view = app.scene.view();
+
+            // Split the view: bone cells into their bone's world, the
+            // rest into the body.
+            let mut body = World::new();
+            let mut bone_worlds: Vec<World> = bones.iter().map(|_| World::new()).collect();
+            for (pos, chunk) in view.chunks() {
+                let (ox, oy, oz) = pos.world_origin();
+                for (local, voxel) in chunk.read().iter_solid() {
+                    let (x, y, z) = (
+                        ox + local.x as i32,
+                        oy + local.y as i32,
+                        oz + local.z as i32,
+                    );
+                    match rig.bone_of((x, y, z)) {
+                        Some(i) => bone_worlds[i].set_voxel(x, y, z, *voxel),
+                        None => body.set_voxel(x, y, z, *voxel),
+                    }
+                }
+            }
+
+            // Parents before children, so every part's parent is an
+            // earlier part (`export_glb_parts` requires it).
+            let mut order: Vec<usize> =
+                (0..bones.len()).filter(|&i| bones[i].parent.is_none()).collect();
+            let mut next = 0;
+            while next < order.len() {
+                let parent = order[next];
+                order.extend((0..bones.len()).filter(|&i| bones[i].parent == Some(parent)));
+                next += 1;
+            }
+            let mut part_of = vec![0; bones.len()];
+            for (part, &bone) in order.iter().enumerate() {
+                part_of[bone] = part + 1;
+            }
+            let mut parts = vec![io::GlbPart {
+                name: "Body",
+                world: &body,
+                parent: None,
+                translation: [0.0; 3],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+            }];
+            for &i in &order {
+                let (translation, rotation) = rig.node_transform(i);
+                parts.push(io::GlbPart {
+                    name: &bones[i].name,
+                    world: &bone_worlds[i],
+                    parent: Some(bones[i].parent.map_or(0, |p| part_of[p])),
+                    translation,
+                    rotation,
+                });
+            }
+
+            let sockets = app.socket_export_nodes();
+            match io::export_glb_parts(&parts, &sockets, &path, io::GlbColors::Shaded) {
+                Ok(stats) => {
+                    app.ui.set_status(format!(
+                        "Exported: {} ({} bones, {} tris)",
+                        file_label(&path),
+                        bones.len(),
+                        stats.triangle_count
+                    ));
+                    let mut notes = vec![format!(
+                        "\"Body\" node plus {} bone nodes, nested by parent",
+                        bones.len()
+                    )];
+                    if app.editor.rig.is_posed() {
+                        notes.push("Current pose stored in the bone node transforms".into());
+                    }
+                    notes.extend(socket_note(sockets.len()));
+                    app.set_export_report(
                         &path,
                         ExportReport {
-                            format: "glTF Binary (.glb)".into(),
+                            format: "glTF Binary (.glb), rigged".into(),
                             mesh_source: "Greedy mesh".into(),
                             triangles: Some(stats.triangle_count),
                             vertices: Some(stats.vertex_count),
                             chunks: Some(stats.chunk_count),
                             color_model: "Per-vertex RGBA".into(),
-                            notes: socket_note(sockets.len()),
+                            notes,
                             ..Default::default()
                         },
                     );
                 }
+                Err(e) => {
+                    log::error!("Failed to export rigged GLB: {}", e);
+                    app.show_write_error("Export failed", &path, "export", &e);
+                    app.ui
+                        .set_status(format!("Export failed: {}", file_label(&path)));
+                }
             }
-            Err(e) => {
-                log::error!("Failed to export GLB: {}", e);
-                self.show_write_error("Export failed", &path, "export", &e);
-                self.ui
-                    .set_status(format!("Export failed: {}", file_label(&path)));
+        });
+    }
+
+    /// Import a Qubicle `.qb` / `.qbt` file, replacing the scene. Each
+    /// matrix becomes its own layer (named after the matrix), so a
+    /// rigged character arrives with its parts still separable.
+    pub(super) fn import_qubicle(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Import)
+            .add_filter("Qubicle", &["qb", "qbt"])
+            .set_title("Import Qubicle File");
+
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Import, &path);
+
+        let label = format!("Importing {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let is_qbt = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("qbt"));
+            let result = std::fs::File::open(&path)
+                .map_err(io::QubicleError::from)
+                .and_then(|file| {
+                    let mut reader = std::io::BufReader::new(file);
+                    if is_qbt {
+                        io::import_qbt(&mut reader)
+                    } else {
+                        io::import_qb(&mut reader)
+                    }
+                });
+
+            match result {
+                Ok(matrices) => {
+                    let layers = matrices
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, m)| {
+                            let name = if m.name.is_empty() {
+                                format!("Matrix {}", i + 1)
+                            } else {
+                                m.name
+                            };
+                            Layer::new(name, m.world)
+                        })
+                        .collect();
+                    app.replace_with_import(Scene::from_layers(layers));
+                    app.ui.set_status(format!(
+                        "Imported: {} ({} layers)",
+                        file_label(&path),
+                        app.scene.len()
+                    ));
+                }
+                Err(e) => {
+                    log::error!("Failed to import Qubicle from {:?}: {}", path, e);
+                    let reason = match &e {
+                        io::QubicleError::Io(inner)
+                            if inner.kind() == std::io::ErrorKind::UnexpectedEof =>
+                        {
+                            "a truncated or corrupt file (ran out of data)".to_string()
+                        }
+                        other => other.to_string(),
+                    };
+                    let detail = format!(
+                        "Couldn't import \"{}\" — {}.\n\nMake sure the file is a \
+                         Qubicle .qb / .qbt export and re-export it if needed.",
+                        file_label(&path),
+                        reason
+                    );
+                    app.show_error_dialog("Import failed", &detail);
+                    app.ui.set_status(format!("Import failed: {}", reason));
+                }
             }
-        }
+        });
     }
 
-    /// Prompt for a path and export to OBJ. Walks every chunk, runs
-    /// the greedy mesher to capture currently-visible geometry, and
-    /// writes a single .obj with vertex colors. Touches the recent-
-    /// files MRU on success and surfaces triangle counts in the status
-    /// bar so the user knows the export wasn't silently empty.
-    pub(super) fn export_obj(&mut self) {
-        let dialog = rfd::FileDialog::new()
-            .add_filter("Wavefront OBJ", &["obj"])
-            .set_title("Export as Wavefront OBJ");
+    /// Export the visible layers as a Qubicle `.qb` (`qbt == false`) or
+    /// `.qbt` file, one matrix per non-empty layer.
+    pub(super) fn export_qubicle(&mut self, qbt: bool) {
+        let (filter, ext, format) = if qbt {
+            ("Qubicle Binary Tree", "qbt", "Qubicle Binary Tree (.qbt)")
+        } else {
+            ("Qubicle Binary", "qb", "Qubicle Binary (.qb)")
+        };
+        let dialog = self.file_dialog(FileDirKind::Export)
+            .add_filter(filter, &[ext])
+            .set_title("Export as Qubicle");
 
         let Some(path) = dialog.save_file() else {
             return;
         };
+        self.remember_dir(FileDirKind::Export, &path);
 
-        match io::export_obj(&self.world, &path) {
-            Ok(stats) => {
-                self.touch_recent(&path);
-                let filename = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("file");
-                let msg = if stats.triangle_count == 0 {
-                    format!("Exported: {} (empty — no geometry)", filename)
-                } else {
-                    format!(
-                        "Exported: {} ({} tris, {} chunks)",
-                        filename, stats.triangle_count, stats.chunk_count
-                    )
-                };
-                self.ui.set_status(msg);
-                if stats.triangle_count > 0 {
-                    self.set_export_report(
+        let label = format!("Exporting {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let matrices: Vec<(&str, &voxelith::World)> = app
+                .scene
+                .layers()
+                .iter()
+                .filter(|l| l.visible)
+                .map(|l| (l.name.as_str(), &l.world))
+                .collect();
+            let result = std::fs::File::create(&path).map_err(io::QubicleError::from).and_then(
+                |file| {
+                    let mut writer = std::io::BufWriter::new(file);
+                    let written = if qbt {
+                        io::export_qbt(&matrices, &mut writer)?
+                    } else {
+                        io::export_qb(&matrices, &mut writer)?
+                    };
+                    std::io::Write::flush(&mut writer)?;
+                    Ok(written)
+                },
+            );
+
+            match result {
+                Ok(written) => {
+                    app.ui.set_status(format!("Exported: {}", file_label(&path)));
+                    let mut notes = vec![format!("{} matrices (one per visible layer)", written)];
+                    if app.scene.layers().iter().any(|l| !l.visible) {
+                        notes.push("Hidden layers were left out".into());
+                    }
+                    app.set_export_report(
                         &path,
                         ExportReport {
-                            format: "Wavefront OBJ (.obj)".into(),
-                            mesh_source: "Greedy mesh".into(),
-                            triangles: Some(stats.triangle_count),
-                            vertices: Some(stats.vertex_count),
-                            chunks: Some(stats.chunk_count),
-                            color_model: "Per-vertex RGBA".into(),
+                            format: format.into(),
+                            color_model: "RGB per voxel".into(),
+                            notes,
                             ..Default::default()
                         },
                     );
                 }
+                Err(e) => {
+                    log::error!("Failed to export Qubicle: {}", e);
+                    app.show_write_error("Export failed", &path, "export", &e);
+                    app.ui
+                        .set_status(format!("Export failed: {}", file_label(&path)));
+                }
             }
-            Err(e) => {
-                log::error!("Failed to export OBJ: {}", e);
-                self.show_write_error("Export failed", &path, "export", &e);
-                self.ui
-                    .set_status(format!("Export failed: {}", file_label(&path)));
-            }
+        });
+    }
+
+    /// Swap in a scene produced by a geometry-only import (Qubicle,
+    /// PNG slices / heightmap): none of these carry sockets or camera
+    /// state, so drop the old sockets and frame the new model, as
+    /// `import_vox` does.
+    fn replace_with_import(&mut self, scene: Scene) {
+        self.set_scene(scene);
+        self.editor.sockets.clear();
+        self.editor.rig = Rig::new();
+        if let Some(renderer) = &mut self.renderer {
+            renderer.clear_meshes();
+            self.mesh_worker.cancel_all();
         }
+        self.rebuild_all_meshes();
+        self.recenter_camera_on_scene();
+        self.unsaved_changes = false;
     }
 
-    /// Prompt for a path and export to VOX.
-    pub(super) fn export_vox(&mut self) {
-        let dialog = rfd::FileDialog::new()
-            .add_filter("MagicaVoxel", &["vox"])
-            .set_title("Export as MagicaVoxel");
+    /// Import a stack of per-Y-layer PNG slices (multi-select, bottom
+    /// layer first by file name), replacing the scene.
+    pub(super) fn import_png_slices(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Import)
+            .add_filter("PNG slices", &["png"])
+            .set_title("Import PNG Slices (select all layers)");
+
+        let Some(paths) = dialog.pick_files() else {
+            return;
+        };
+        if let Some(first) = paths.first() {
+            self.remember_dir(FileDirKind::Import, first);
+        }
+
+        let label = format!("Importing {} PNG slices", paths.len());
+        self.defer_file_task(label, move |app| {
+            match io::import_png_slices(&paths) {
+                Ok(world) => {
+                    app.replace_with_import(Scene::from_world(world));
+                    app.ui
+                        .set_status(format!("Imported {} PNG slices", paths.len()));
+                }
+                Err(e) => {
+                    log::error!("Failed to import PNG slices: {}", e);
+                    let detail = format!(
+                        "Couldn't import the PNG slices — {}.\n\nSelect every slice \
+                         of one stack; they must all be the same size.",
+                        e
+                    );
+                    app.show_error_dialog("Import failed", &detail);
+                    app.ui.set_status(format!("Import failed: {}", e));
+                }
+            }
+        });
+    }
+
+    /// Export the visible scene as one PNG per Y layer, named
+    /// `<stem>_000.png`, `<stem>_001.png`, … after the chosen file.
+    pub(super) fn export_png_slices(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Export)
+            .add_filter("PNG slices", &["png"])
+            .set_title("Export PNG Slices");
 
         let Some(path) = dialog.save_file() else {
             return;
         };
+        self.remember_dir(FileDirKind::Export, &path);
 
-        match std::fs::File::create(&path) {
-            Ok(mut file) => match io::export_vox(&self.world, &mut file) {
-                Ok(overflow) => {
-                    self.touch_recent(&path);
-                    let filename = path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("file");
-                    let msg = if overflow > 0 {
-                        format!(
-                            "Exported: {} ({} colors quantized — VOX is 255-color)",
-                            filename, overflow
-                        )
-                    } else {
-                        format!("Exported: {}", filename)
-                    };
-                    self.ui.set_status(msg);
-                    let mut notes = Vec::new();
-                    if overflow > 0 {
-                        notes.push(format!(
-                            "{} colors quantized to the nearest of 254 \
-                             palette slots",
-                            overflow
-                        ));
-                    }
-                    self.set_export_report(
+        let label = format!("Exporting {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            match io::export_png_slices(app.scene.view(), &path) {
+                Ok(paths) => {
+                    let first = paths.first().map(|p| file_label(p)).unwrap_or_default();
+                    app.ui.set_status(format!(
+                        "Exported {} PNG slices ({}…)",
+                        paths.len(),
+                        first
+                    ));
+                    app.set_export_report(
                         &path,
                         ExportReport {
-                            format: "MagicaVoxel (.vox)".into(),
-                            color_model: "254-color palette".into(),
-                            notes,
+                            format: "PNG slice stack".into(),
+                            color_model: "RGBA per voxel (transparent = air)".into(),
+                            notes: vec![
+                                format!("{} slices, one per Y layer, bottom first", paths.len()),
+                                "World offset is not stored; re-import puts the min \
+                                 corner at the origin"
+                                    .into(),
+                            ],
                             ..Default::default()
                         },
                     );
                 }
                 Err(e) => {
-                    log::error!("Failed to export VOX: {}", e);
-                    self.show_write_error("Export failed", &path, "export", &e);
-                    self.ui
+                    log::error!("Failed to export PNG slices: {}", e);
+                    app.show_write_error("Export failed", &path, "export", &e);
+                    app.ui
                         .set_status(format!("Export failed: {}", file_label(&path)));
                 }
-            },
+            }
+        });
+    }
+
+    /// Import a grayscale heightmap PNG as gradient-colored terrain,
+    /// replacing the scene.
+    pub(super) fn import_heightmap(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Import)
+            .add_filter("Heightmap", &["png"])
+            .set_title("Import Heightmap");
+
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Import, &path);
+
+        let label = format!("Importing {}", file_label(&path));
+        self.defer_file_task(label, move |app| {
+            let result = std::fs::read(&path)
+                .map_err(io::ImageIoError::from)
+                .and_then(|bytes| io::import_heightmap(&bytes, &io::HeightmapOptions::default()));
+            match result {
+                Ok(world) => {
+                    app.replace_with_import(Scene::from_world(world));
+                    app.ui
+                        .set_status(format!("Imported heightmap: {}", file_label(&path)));
+                }
+                Err(e) => {
+                    log::error!("Failed to import heightmap {:?}: {}", path, e);
+                    let detail = format!(
+                        "Couldn't import \"{}\" — {}.\n\nHeightmaps must be PNGs of \
+                         at most {} pixels per side.",
+                        file_label(&path),
+                        e,
+                        io::MAX_IMAGE_SIDE
+                    );
+                    app.show_error_dialog("Import failed", &detail);
+                    app.ui.set_status(format!("Import failed: {}", e));
+                }
+            }
+        });
+    }
+
+    /// Prompt for a palette source and replace the palette with it:
+    /// `.gpl` / `.pal` files, a `.vox` (its palette only — no geometry
+    /// is imported) or a `.png` swatch strip. The palette is saved with
+    /// the project, so this counts as an unsaved change.
+    pub(super) fn import_palette(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Import)
+            .add_filter("Palette", &["gpl", "pal", "vox", "png"])
+            .add_filter("GIMP Palette", &["gpl"])
+            .add_filter("JASC Palette", &["pal"])
+            .add_filter("MagicaVoxel (palette only)", &["vox"])
+            .add_filter("PNG swatch strip", &["png"])
+            .set_title("Import Palette");
+
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Import, &path);
+
+        match io::import_palette(&path) {
+            Ok(colors) => {
+                let count = colors.len();
+                let dropped = self.editor.replace_palette(colors);
+                self.unsaved_changes = true;
+                self.ui.set_status(if dropped > 0 {
+                    format!(
+                        "Imported palette: {} ({} colors, {} over the limit dropped)",
+                        file_label(&path),
+                        count - dropped,
+                        dropped
+                    )
+                } else {
+                    format!("Imported palette: {} ({} colors)", file_label(&path), count)
+                });
+            }
             Err(e) => {
-                log::error!("Failed to create file {:?}: {}", path, e);
-                self.show_write_error("Export failed", &path, "create", &e);
+                log::error!("Failed to import palette {:?}: {}", path, e);
+                self.show_error_dialog(
+                    "Import failed",
+                    &format!("Couldn't read \"{}\" as a palette — {}.", file_label(&path), e),
+                );
+                self.ui
+                    .set_status(format!("Import failed: {}", file_label(&path)));
+            }
+        }
+    }
+
+    /// Prompt for a path and write the palette as `.gpl` or `.pal`
+    /// (picked by the chosen extension; `.gpl` when there is none).
+    pub(super) fn export_palette(&mut self) {
+        let dialog = self.file_dialog(FileDirKind::Export)
+            .add_filter("GIMP Palette", &["gpl"])
+            .add_filter("JASC Palette", &["pal"])
+            .set_title("Export Palette");
+
+        let Some(mut path) = dialog.save_file() else {
+            return;
+        };
+        self.remember_dir(FileDirKind::Export, &path);
+        if !io::PaletteFormat::from_extension(&path).is_some_and(|f| f.can_export()) {
+            path.set_extension("gpl");
+        }
+
+        match io::export_palette(&path, &self.editor.palette) {
+            Ok(()) => {
+                self.ui.set_status(format!(
+                    "Exported palette: {} ({} colors)",
+                    file_label(&path),
+                    self.editor.palette.len()
+                ));
+            }
+            Err(e) => {
+                log::error!("Failed to export palette {:?}: {}", path, e);
+                self.show_write_error("Export failed", &path, "export", &e);
                 self.ui
                     .set_status(format!("Export failed: {}", file_label(&path)));
             }
@@ -619,6 +1769,38 @@ impl App {
     }
 }
 
+/// Color of a ghost OBJ's faces when its vertices carry none.
+const GHOST_OBJ_COLOR: [f32; 4] = [0.75, 0.8, 0.9, 1.0];
+
+/// `world`'s outer faces as one mesh, at full opacity.
+fn world_mesh(world: &World) -> ChunkMesh {
+    let mut voxels = Vec::new();
+    for (pos, chunk) in world.chunks() {
+        let (ox, oy, oz) = pos.world_origin();
+        for (local, voxel) in chunk.read().iter_solid() {
+            let p = (ox + local.x as i32, oy + local.y as i32, oz + local.z as i32);
+            voxels.push((p, *voxel));
+        }
+    }
+    patch_to_mesh(&voxels, 1.0)
+}
+
+/// Read the `.vox` at `path` as a brush stamp. The error is a
+/// status-bar message.
+pub(super) fn read_brush_stamp(path: &Path, axes: io::VoxAxes) -> Result<BrushStamp, String> {
+    let import = std::fs::File::open(path)
+        .map_err(|e| e.to_string())
+        .and_then(|mut file| io::import_vox_full(&mut file, axes).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Couldn't load stamp {}: {}", file_label(path), e))?;
+    BrushStamp::from_world(file_label(path), &import.world).ok_or_else(|| {
+        format!(
+            "{} can't be a stamp: it's empty or over {} voxels on a side",
+            file_label(path),
+            MAX_STAMP_SIDE
+        )
+    })
+}
+
 /// Export-report note line for emitted sockets, or empty when there
 /// are none. Keeps the post-export summary honest about the empty
 /// nodes that went into the .glb alongside the mesh.
@@ -635,13 +1817,12 @@ fn socket_note(count: usize) -> Vec<String> {
     }
 }
 
-/// Geometry-source label for the export report's smoothed (Marching
-/// Cubes) variants; `blur` is the heavy-vs-light flag the menu passes.
-fn smoothed_mesh_source(blur: bool) -> &'static str {
-    if blur {
-        "Marching Cubes (heavy)"
-    } else {
-        "Marching Cubes (light)"
+/// Geometry-source label for the export report.
+fn mesh_source(surface: io::MeshSurface) -> &'static str {
+    match surface {
+        io::MeshSurface::Blocky => "Greedy mesh",
+        io::MeshSurface::Smooth => "Marching Cubes (light)",
+        io::MeshSurface::Clay => "Marching Cubes (heavy)",
     }
 }
 
@@ -767,7 +1948,9 @@ fn describe_project_open_error(e: &io::ProjectError, path: &Path) -> (String, St
             format!("a read error: {}", inner),
             "Check the file still exists and isn't locked by another app.",
         ),
-        io::ProjectError::InvalidChunkData | io::ProjectError::DecompressionError => (
+        io::ProjectError::InvalidChunkData
+        | io::ProjectError::DecompressionError
+        | io::ProjectError::ThumbnailTooLarge(_) => (
             "corrupt voxel data".to_string(),
             "The project body is damaged — try a backup or autosave copy.",
         ),
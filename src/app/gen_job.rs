@@ -0,0 +1,385 @@
+//! Procgen job lifecycle on the main thread:
+//! - `start_generator_job` / `start_graph_job`: snapshot the panel's
+//!   generator (or the whole graph) and hand it to a `GenJob` worker.
+//! - `cancel_gen_job`: flip the job's cooperative-cancel flag.
+//! - `tick_gen_job`: per-frame; drains worker progress and, when the
+//!   run finishes, applies the patch as one `Command::set_voxels`.
+//!   Mirrors `app::ai_actions::tick_ai_job`.
+//!
+//! Unless `ProcgenSettings::apply_immediately` is set, a finished run
+//! isn't written straight away: it's *staged* — held here and drawn
+//! in the preview overlay — so the user can tweak parameters and
+//! regenerate until it looks right, then Apply (one undo step) or
+//! Discard it.
+//!
+//! The job is pinned to the layer that was active when it started. If
+//! that layer is no longer the edit target when the result arrives or
+//! is applied (layer switch, project opened), the result is dropped
+//! rather than written somewhere the user didn't ask for.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use voxelith::core::LayerId;
+use voxelith::editor::{Command, VoxelChange};
+use voxelith::procgen::{ErosionRegion, GenError, GenJob, VoxelGenerator, VoxelPatch};
+use voxelith::ui::{GenJobStatus, GeneratorChoice, StagedStatus};
+
+use super::App;
+
+/// Generator ids and seeds a run used (see `VoxelGenerator::seed`).
+type RunSeeds = Vec<(&'static str, u64)>;
+
+/// An in-flight generation plus the layer its result belongs to.
+pub(super) struct ActiveGenJob {
+    pub job: GenJob,
+    pub layer: LayerId,
+    pub seeds: RunSeeds,
+}
+
+/// Finished output held for review until Apply / Discard.
+pub(super) struct StagedGeneration {
+    pub label: String,
+    pub patch: VoxelPatch,
+    pub layer: LayerId,
+    pub seeds: RunSeeds,
+}
+
+impl App {
+    /// Run the procgen panel's currently-selected generator off-thread.
+    /// No-op while another generation is running.
+    pub(super) fn start_generator_job(&mut self) {
+        if self.gen_job.is_some() {
+            return;
+        }
+        // Clone the parameters so the panel stays editable while the
+        // worker runs; the job generates what was set at click time.
+        let procgen = &self.ui.procgen;
+        let (job, seeds) = match procgen.selected {
+            GeneratorChoice::Terrain => spawn_generator(procgen.selected, procgen.terrain.clone()),
+            GeneratorChoice::Tree => spawn_generator(procgen.selected, procgen.tree.clone()),
+            GeneratorChoice::Wfc => spawn_generator(procgen.selected, procgen.wfc.clone()),
+            GeneratorChoice::Cave => spawn_generator(procgen.selected, procgen.cave.clone()),
+        };
+        self.begin_gen_job(job, seeds);
+    }
+
+    /// Run a registered generator plugin with a fresh seed.
+    pub(super) fn start_plugin_generator_job(&mut self, id: &str) {
+        if self.gen_job.is_some() {
+            return;
+        }
+        let Some(plugin) = voxelith::plugin::registry().generator(id) else {
+            self.ui.set_status(format!("Plugin \"{id}\" is not registered"));
+            return;
+        };
+        let generator = plugin.create(rand::random());
+        let meta = generator.metadata();
+        let seeds = generator
+            .seed()
+            .map(|seed| vec![(meta.id, seed)])
+            .unwrap_or_default();
+        let job = GenJob::spawn(meta.name, generator.estimate_duration(), move |progress| {
+            generator.generate_with_progress(progress)
+        });
+        self.begin_gen_job(job, seeds);
+    }
+
+    /// Evaluate the pipeline graph off-thread.
+    pub(super) fn start_graph_job(&mut self) {
+        if self.gen_job.is_some() {
+            return;
+        }
+        let graph = self.ui.graph.clone();
+        let seeds = graph.seeds();
+        let job = GenJob::spawn("Graph", graph.estimate_duration(), move |progress| {
+            graph.evaluate_with_progress(progress)
+        });
+        self.begin_gen_job(job, seeds);
+    }
+
+    /// Erode the terrain surface inside the selection, using the
+    /// terrain generator's erosion settings and seed. The surface is
+    /// captured here (one column scan); the simulation runs on the
+    /// worker, and the result stages / applies like any generation.
+    pub(super) fn start_erosion_job(&mut self) {
+        if self.gen_job.is_some() {
+            return;
+        }
+        let Some(selection) = self.editor.selection else {
+            self.ui.set_status("Erosion: select a terrain region first");
+            return;
+        };
+        let region =
+            match ErosionRegion::from_world(self.scene.active_world(), selection.min, selection.max) {
+                Ok(r) => r,
+                Err(e) => {
+                    self.ui.set_status(format!("Erosion failed: {}", e));
+                    return;
+                }
+            };
+        let terrain = &self.ui.procgen.terrain;
+        let settings = terrain.erosion.clone();
+        let seed = terrain.seed as u64;
+        let columns = (selection.max.0 - selection.min.0 + 1) as u64
+            * (selection.max.2 - selection.min.2 + 1) as u64;
+        let estimate = Duration::from_micros(columns * settings.iterations as u64);
+        // Deposits take the palette's active brush color, so they read
+        // as "new material" against whatever the terrain was painted.
+        let sediment = self.editor.brush_color;
+        let job = GenJob::spawn("Erosion", estimate, move |progress| {
+            region.erode(&settings, seed, sediment, progress)
+        });
+        // No seed record: region erosion isn't a generator, and its
+        // input (the existing voxels) isn't reproducible from a seed.
+        self.begin_gen_job(job, Vec::new());
+    }
+
+    fn begin_gen_job(&mut self, job: GenJob, seeds: RunSeeds) {
+        self.ui.set_status(format!("{}: generating…", job.label()));
+        self.gen_job = Some(ActiveGenJob {
+            job,
+            layer: self.scene.active().id,
+            seeds,
+        });
+    }
+
+    /// Request cooperative cancellation. The worker stops at its next
+    /// checkpoint; `tick_gen_job` then reports "cancelled" and clears
+    /// the job without touching the world.
+    pub(super) fn cancel_gen_job(&mut self) {
+        if let Some(active) = &self.gen_job {
+            active.job.cancel();
+            self.ui.set_status(format!("{}: cancelling…", active.job.label()));
+        }
+    }
+
+    /// Drain worker events; apply the result once the job finishes.
+    /// Called every frame from `RedrawRequested`.
+    pub(super) fn tick_gen_job(&mut self) {
+        let Some(result) = self.gen_job.as_mut().and_then(|active| active.job.poll()) else {
+            return;
+        };
+        // Finished either way — the worker has exited and sent its last
+        // event, so the job can go.
+        let Some(ActiveGenJob { job, layer, seeds }) = self.gen_job.take() else {
+            return;
+        };
+        let label = job.label().to_string();
+        match result {
+            Ok(_) if layer != self.scene.active().id => {
+                self.ui.set_status(format!(
+                    "{}: discarded — the target layer is no longer active",
+                    label
+                ));
+            }
+            Ok(patch) if self.ui.procgen.apply_immediately => {
+                self.apply_generated_patch(&label, patch, &seeds)
+            }
+            Ok(patch) => self.stage_generated_patch(label, patch, layer, seeds),
+            Err(GenError::Cancelled) => {
+                self.ui.set_status(format!("{}: cancelled", label));
+            }
+            Err(e) => {
+                log::error!("{} failed: {}", label, e);
+                self.ui.set_status(format!("{} failed: {}", label, e));
+            }
+        }
+    }
+
+    /// Load a project's recorded seeds (`EditorState::procgen_seeds`)
+    /// and put them back on the panel's generators, so Generate
+    /// reproduces what the project was built with.
+    pub(super) fn restore_procgen_seeds(&mut self, seeds: BTreeMap<String, u64>) {
+        let procgen = &mut self.ui.procgen;
+        // Seeds are `u32` on the generators; the wider record leaves
+        // room for generators with 64-bit seeds.
+        if let Some(&s) = seeds.get(procgen.terrain.metadata().id) {
+            procgen.terrain.seed = s as u32;
+        }
+        if let Some(&s) = seeds.get(procgen.tree.metadata().id) {
+            procgen.tree.seed = s as u32;
+        }
+        if let Some(&s) = seeds.get(procgen.wfc.metadata().id) {
+            procgen.wfc.seed = s as u32;
+        }
+        if let Some(&s) = seeds.get(procgen.cave.metadata().id) {
+            procgen.cave.seed = s as u32;
+        }
+        self.procgen_seeds = seeds;
+    }
+
+    /// Snapshot of the running job for the procgen / graph panels.
+    pub(super) fn gen_job_status(&self) -> Option<GenJobStatus> {
+        let job = &self.gen_job.as_ref()?.job;
+        let progress = job.progress();
+        let elapsed = job.elapsed();
+        // Extrapolate from measured progress once there's enough of it
+        // to be meaningful; before that, fall back to the generator's
+        // own estimate.
+        let remaining = if progress >= 0.05 {
+            Some(elapsed.div_f32(progress).saturating_sub(elapsed))
+        } else if job.estimate() > Duration::ZERO {
+            Some(job.estimate().saturating_sub(elapsed))
+        } else {
+            None
+        };
+        Some(GenJobStatus {
+            label: job.label().to_string(),
+            progress,
+            cancelling: job.is_cancelling(),
+            remaining,
+        })
+    }
+
+    /// Hold `patch` for review, replacing anything already staged,
+    /// and show it in the preview overlay.
+    fn stage_generated_patch(
+        &mut self,
+        label: String,
+        patch: VoxelPatch,
+        layer: LayerId,
+        seeds: RunSeeds,
+    ) {
+        if patch.is_empty() {
+            self.discard_staged_generation();
+            self.ui.set_status(format!("{}: produced no voxels", label));
+            return;
+        }
+        let mut status = format!("{}: {} voxels staged — Apply or Discard", label, patch.len());
+        if !patch.notes.is_empty() {
+            status.push_str(" (");
+            status.push_str(&patch.notes.join("; "));
+            status.push(')');
+        }
+        self.ui.set_status(status);
+        self.show_staged_preview(&patch);
+        self.staged = Some(StagedGeneration {
+            label,
+            patch,
+            layer,
+            seeds,
+        });
+    }
+
+    /// Commit the staged output as one undo step.
+    pub(super) fn apply_staged_generation(&mut self) {
+        let Some(StagedGeneration {
+            label,
+            patch,
+            layer,
+            seeds,
+        }) = self.staged.take()
+        else {
+            return;
+        };
+        if layer != self.scene.active().id {
+            self.invalidate_preview();
+            self.ui.set_status(format!(
+                "{}: discarded — the target layer is no longer active",
+                label
+            ));
+            return;
+        }
+        self.apply_generated_patch(&label, patch, &seeds);
+        // `apply_generated_patch` skips the overlay reset when nothing
+        // changed; the staged overlay has to go either way.
+        self.invalidate_preview();
+    }
+
+    /// Drop the staged output; the live preview (if enabled) takes the
+    /// overlay back on the next tick.
+    pub(super) fn discard_staged_generation(&mut self) {
+        if self.staged.take().is_some() {
+            self.invalidate_preview();
+        }
+    }
+
+    /// Snapshot of the staged output for the procgen / graph panels.
+    pub(super) fn staged_status(&self) -> Option<StagedStatus> {
+        let staged = self.staged.as_ref()?;
+        Some(StagedStatus {
+            label: staged.label.clone(),
+            voxels: staged.patch.len(),
+        })
+    }
+
+    /// Apply a finished patch through `CommandHistory` as a single
+    /// `set_voxels` command, so one undo reverts the whole run, and
+    /// record the run's seeds for the project file.
+    fn apply_generated_patch(&mut self, label: &str, patch: VoxelPatch, seeds: &[(&str, u64)]) {
+        if patch.is_empty() {
+            self.ui.set_status(format!("{}: produced no voxels", label));
+            return;
+        }
+
+        // The current voxel at each position becomes `old_voxel` so
+        // undo restores the pre-generation state. It's read now, not
+        // when the job started, so edits made while the worker ran are
+        // what undo goes back to. Identity writes are dropped so we
+        // don't push a no-op command (e.g. re-running an unchanged
+        // generator over the same world).
+        let changes: Vec<VoxelChange> = patch
+            .voxels
+            .iter()
+            .filter_map(|&(pos, new_voxel)| {
+                let old_voxel = self.scene.active_world().get_voxel(pos.0, pos.1, pos.2);
+                if old_voxel == new_voxel {
+                    None
+                } else {
+                    Some(VoxelChange {
+                        pos,
+                        old_voxel,
+                        new_voxel,
+                    })
+                }
+            })
+            .collect();
+
+        if changes.is_empty() {
+            self.ui.set_status(format!(
+                "{}: no changes (output matches existing voxels)",
+                label
+            ));
+            return;
+        }
+
+        let count = changes.len();
+        // Remember the generated footprint for the "Frame Generated"
+        // camera action (uses the full patch, not just changed cells).
+        self.last_generated_bounds = super::bounds_of(patch.voxels.iter().map(|&(p, _)| p));
+        let cmd = Command::set_voxels(changes).named(label.to_string());
+        self.editor.history.execute(cmd, self.scene.active_world_mut());
+        for &(id, seed) in seeds {
+            self.procgen_seeds.insert(id.to_string(), seed);
+        }
+
+        let mut status = format!("{}: {} voxels", label, count);
+        if !patch.notes.is_empty() {
+            status.push_str(" (");
+            status.push_str(&patch.notes.join("; "));
+            status.push(')');
+        }
+        self.ui.set_status(status);
+
+        // The just-applied geometry would otherwise double-render with
+        // the preview overlay on top of it. Clear the preview; it'll
+        // regenerate on the next param change if still enabled.
+        self.invalidate_preview();
+    }
+}
+
+/// Move an owned generator onto a worker thread.
+fn spawn_generator<G>(choice: GeneratorChoice, generator: G) -> (GenJob, RunSeeds)
+where
+    G: VoxelGenerator + 'static,
+{
+    let seeds = generator
+        .seed()
+        .map(|seed| vec![(generator.metadata().id, seed)])
+        .unwrap_or_default();
+    let job = GenJob::spawn(choice.label(), generator.estimate_duration(), move |progress| {
+        generator.generate_with_progress(progress)
+    });
+    (job, seeds)
+}
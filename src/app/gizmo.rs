@@ -0,0 +1,269 @@
+//! Move gizmo: what it's attached to, grabbing a handle, the drag, and
+//! its overlay.
+//!
+//! The gizmo moves the first of these that applies:
+//! 1. a paste pinned with Enter during paste placement,
+//! 2. the light selected in the open Lights panel,
+//! 3. the selection,
+//! 4. the symmetry mirror planes, while a mirroring tool is active.
+//!
+//! A selection or pinned paste shows its ghost at the dragged offset
+//! and moves on release — the selection as one undoable Move. Lights
+//! and mirror planes follow the drag live; Esc puts them back. Every
+//! drag snaps to whole voxels.
+
+use glam::Vec3;
+use voxelith::editor::{Gizmo, GizmoHandle};
+
+use super::hud::tool_uses_symmetry;
+use super::App;
+
+/// Gizmo arm length per unit of distance from the camera, which keeps
+/// it about the same size on screen.
+const GIZMO_SCREEN_SCALE: f32 = 0.12;
+
+/// What the gizmo moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GizmoTarget {
+    Selection,
+    /// The pinned paste placement.
+    Paste,
+    /// `ui.lights[i]`.
+    Light(usize),
+    MirrorPlanes,
+}
+
+/// A gizmo handle held down.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct GizmoDrag {
+    pub target: GizmoTarget,
+    pub handle: GizmoHandle,
+    /// The gizmo as grabbed. Points are read on its handles, not on the
+    /// one drawn at the moving target.
+    pub gizmo: Gizmo,
+    /// Point on the handle's line or plane where it was grabbed.
+    pub start: Vec3,
+    /// Snapped offset so far.
+    pub delta: (i32, i32, i32),
+    /// Light position or mirror center at the grab, for the live
+    /// targets to offset from and for Esc to restore.
+    base: Vec3,
+}
+
+fn offset(base: Vec3, delta: (i32, i32, i32)) -> Vec3 {
+    base + Vec3::new(delta.0 as f32, delta.1 as f32, delta.2 as f32)
+}
+
+impl App {
+    /// What the gizmo is attached to right now, if anything.
+    pub(super) fn gizmo_target(&self) -> Option<GizmoTarget> {
+        if let Some(drag) = &self.gizmo_drag {
+            return Some(drag.target);
+        }
+        if !self.ui.viewport.move_gizmo {
+            return None;
+        }
+        if self.paste_placement.is_some() {
+            // An unpinned paste follows the cursor; nothing to grab.
+            return self.paste_pin.map(|_| GizmoTarget::Paste);
+        }
+        if self.ui.state.show_lights {
+            if let Some(i) = self.ui.selected_light.filter(|&i| i < self.ui.lights.len()) {
+                return Some(GizmoTarget::Light(i));
+            }
+        }
+        let dragging = self.selection_drag_anchor.is_some()
+            || self.selection_move_anchor.is_some()
+            || self.shape_drag.is_some();
+        if self.editor.selection.is_some() && !dragging {
+            return Some(GizmoTarget::Selection);
+        }
+        if self.editor.symmetry.any() && tool_uses_symmetry(self.editor.current_tool) {
+            return Some(GizmoTarget::MirrorPlanes);
+        }
+        None
+    }
+
+    /// The gizmo for `target` where it's drawn, sized for the main
+    /// camera. A dragged selection or paste draws at its ghost.
+    fn gizmo_for(&self, target: GizmoTarget) -> Option<Gizmo> {
+        let camera = &self.renderer.as_ref()?.camera;
+        let (origin, axes) = match target {
+            GizmoTarget::Selection => {
+                let sel = self.editor.selection?;
+                let min = Vec3::new(sel.min.0 as f32, sel.min.1 as f32, sel.min.2 as f32);
+                let max = Vec3::new(sel.max.0 as f32, sel.max.1 as f32, sel.max.2 as f32);
+                ((min + max + 1.0) * 0.5, [true; 3])
+            }
+            GizmoTarget::Paste => {
+                let pin = self.paste_pin?;
+                let (w, h, d) = self.paste_placement.as_ref()?.size;
+                let min = Vec3::new(pin.0 as f32, pin.1 as f32, pin.2 as f32);
+                (min + Vec3::new(w as f32, h as f32, d as f32) * 0.5, [true; 3])
+            }
+            GizmoTarget::Light(i) => (Vec3::from(self.ui.lights.get(i)?.position), [true; 3]),
+            GizmoTarget::MirrorPlanes => {
+                let sym = self.editor.symmetry;
+                let (x, y, z) = sym.center;
+                (Vec3::new(x as f32, y as f32, z as f32), [sym.x, sym.y, sym.z])
+            }
+        };
+        let origin = match self.gizmo_drag {
+            Some(drag) if matches!(target, GizmoTarget::Selection | GizmoTarget::Paste) => {
+                offset(origin, drag.delta)
+            }
+            _ => origin,
+        };
+        let size = (camera.position.distance(origin) * GIZMO_SCREEN_SCALE).max(0.5);
+        Some(Gizmo { axes, ..Gizmo::new(origin, size) })
+    }
+
+    /// Grab the gizmo handle under the cursor, if there is one. Returns
+    /// whether the press was taken — the caller then skips the tool.
+    pub(super) fn begin_gizmo_drag(&mut self) -> bool {
+        let Some(target) = self.gizmo_target() else {
+            return false;
+        };
+        let Some(gizmo) = self.gizmo_for(target) else {
+            return false;
+        };
+        let Some((ray, _)) = self.cursor_ray() else {
+            return false;
+        };
+        let Some(handle) = gizmo.handle_under(&ray) else {
+            return false;
+        };
+        let Some(start) = gizmo.drag_point(handle, &ray) else {
+            return false;
+        };
+        let base = match target {
+            GizmoTarget::Light(i) => Vec3::from(self.ui.lights[i].position),
+            GizmoTarget::MirrorPlanes => gizmo.origin,
+            GizmoTarget::Selection | GizmoTarget::Paste => Vec3::ZERO,
+        };
+        if target == GizmoTarget::Selection {
+            if let Some(sel) = self.editor.selection {
+                self.begin_move_ghost(sel);
+            }
+        }
+        self.gizmo_drag = Some(GizmoDrag {
+            target,
+            handle,
+            gizmo,
+            start,
+            delta: (0, 0, 0),
+            base,
+        });
+        self.gizmo_hover = Some(handle);
+        true
+    }
+
+    /// Follow the cursor: advance a held drag, or track which handle is
+    /// under the cursor for the highlight.
+    pub(super) fn update_gizmo_drag(&mut self) {
+        let Some(mut drag) = self.gizmo_drag else {
+            self.gizmo_hover = self.gizmo_target().and_then(|target| {
+                let gizmo = self.gizmo_for(target)?;
+                let (ray, _) = self.cursor_ray()?;
+                gizmo.handle_under(&ray)
+            });
+            return;
+        };
+        let Some(point) = self
+            .cursor_ray()
+            .and_then(|(ray, _)| drag.gizmo.drag_point(drag.handle, &ray))
+        else {
+            return;
+        };
+        let delta = drag.gizmo.drag_offset(drag.handle, drag.start, point);
+        if delta == drag.delta {
+            return;
+        }
+        drag.delta = delta;
+        self.gizmo_drag = Some(drag);
+        match drag.target {
+            GizmoTarget::Light(i) => {
+                if let Some(light) = self.ui.lights.get_mut(i) {
+                    light.position = offset(drag.base, delta).to_array();
+                }
+            }
+            GizmoTarget::MirrorPlanes => {
+                let c = offset(drag.base, delta);
+                self.editor.symmetry.center = (c.x as i32, c.y as i32, c.z as i32);
+            }
+            GizmoTarget::Selection | GizmoTarget::Paste => {
+                self.invalidate_selection_visualization();
+            }
+        }
+    }
+
+    /// Let go of the gizmo: move the selection or pinned paste by the
+    /// dragged offset. Lights and mirror planes are already there.
+    pub(super) fn end_gizmo_drag(&mut self) {
+        let Some(drag) = self.gizmo_drag.take() else {
+            return;
+        };
+        let (dx, dy, dz) = drag.delta;
+        match drag.target {
+            GizmoTarget::Selection => {
+                self.move_ghost_voxels.clear();
+                self.move_selection(drag.delta);
+                self.invalidate_selection_visualization();
+            }
+            GizmoTarget::Paste => {
+                if let Some(pin) = &mut self.paste_pin {
+                    *pin = (pin.0 + dx, pin.1 + dy, pin.2 + dz);
+                }
+                self.invalidate_selection_visualization();
+            }
+            GizmoTarget::Light(_) | GizmoTarget::MirrorPlanes => {}
+        }
+        if drag.delta != (0, 0, 0) {
+            self.ui.set_status(format!("Moved by ({}, {}, {})", dx, dy, dz));
+        }
+    }
+
+    /// Drop a held drag without moving anything (Esc).
+    pub(super) fn cancel_gizmo_drag(&mut self) {
+        let Some(drag) = self.gizmo_drag.take() else {
+            return;
+        };
+        match drag.target {
+            GizmoTarget::Light(i) => {
+                if let Some(light) = self.ui.lights.get_mut(i) {
+                    light.position = drag.base.to_array();
+                }
+            }
+            GizmoTarget::MirrorPlanes => {
+                let c = drag.base;
+                self.editor.symmetry.center = (c.x as i32, c.y as i32, c.z as i32);
+            }
+            GizmoTarget::Selection => self.move_ghost_voxels.clear(),
+            GizmoTarget::Paste => {}
+        }
+        self.invalidate_selection_visualization();
+        self.ui.set_status("Move canceled");
+    }
+
+    /// Refresh the gizmo overlay. Rebuilt whenever the gizmo, its
+    /// highlight or its size change — the size follows the camera, so
+    /// that's every frame the view moves while a gizmo shows.
+    pub(super) fn update_gizmo_visualization(&mut self) {
+        let target = self.gizmo_target();
+        let cur = target.and_then(|t| {
+            let gizmo = self.gizmo_for(t)?;
+            let hot = self.gizmo_hover.filter(|h| gizmo.handles().contains(h));
+            Some((gizmo, hot, t == GizmoTarget::MirrorPlanes))
+        });
+        if self.last_gizmo_viz == Some(cur) {
+            return;
+        }
+        self.last_gizmo_viz = Some(cur);
+        if let Some(r) = &mut self.renderer {
+            match cur {
+                Some((gizmo, hot, mirror)) => r.set_gizmo_mesh(&gizmo, hot, mirror),
+                None => r.clear_gizmo(),
+            }
+        }
+    }
+}
@@ -15,6 +15,7 @@ use winit::{
 };
 
 use voxelith::editor::Tool;
+use voxelith::render::zoom_ortho_half_height;
 
 use super::App;
 
@@ -52,6 +53,9 @@ impl ApplicationHandler for App {
         match event {
             WindowEvent::CloseRequested => {
                 self.save_prefs();
+                // Let a running save finish (and report a failure)
+                // before the process ends mid-write.
+                self.finish_background_save();
                 // Clean shutdown: drop the crash-recovery autosave so the
                 // next launch doesn't mistake this for a crash.
                 self.delete_autosave();
@@ -93,41 +97,39 @@ impl ApplicationHandler for App {
                 }
             }
 
-            WindowEvent::KeyboardInput { event, .. } => {
-                if !egui_consumed {
-                    if let PhysicalKey::Code(key) = event.physical_key {
-                        // Command chords (Ctrl/Super + key) are editor
-                        // shortcuts, not fly-camera input. Feeding the
-                        // chord's letter (e.g. the 'S' in Ctrl+S) to the
-                        // controller would dolly the camera while held —
-                        // and if a modal Save/Open dialog swallows the
-                        // key-release, the camera drifts forever. So drop
-                        // the *press* while a command modifier is held,
-                        // but always forward the *release* so a key pressed
-                        // before the modifier (hold W, then tap Ctrl) can
-                        // never get stuck "down". Sprint lives on Shift
-                        // (see `CameraController::update`), which isn't a
-                        // command modifier, so Shift+WASD is unaffected.
-                        let command_chord =
-                            self.modifiers.control_key() || self.modifiers.super_key();
-                        let skip_camera = command_chord && event.state.is_pressed();
-                        if !skip_camera {
-                            if let Some(renderer) = &mut self.renderer {
-                                renderer
-                                    .camera_controller
-                                    .process_keyboard(key, event.state);
-                            }
+            WindowEvent::KeyboardInput { event, .. } if !egui_consumed => {
+                if let PhysicalKey::Code(key) = event.physical_key {
+                    // Command chords (Ctrl/Super + key) are editor
+                    // shortcuts, not fly-camera input. Feeding the
+                    // chord's letter (e.g. the 'S' in Ctrl+S) to the
+                    // controller would dolly the camera while held —
+                    // and if a modal Save/Open dialog swallows the
+                    // key-release, the camera drifts forever. So drop
+                    // the *press* while a command modifier is held,
+                    // but always forward the *release* so a key pressed
+                    // before the modifier (hold W, then tap Ctrl) can
+                    // never get stuck "down". Sprint lives on Shift
+                    // (see `CameraController::update`), which isn't a
+                    // command modifier, so Shift+WASD is unaffected.
+                    let command_chord =
+                        self.modifiers.control_key() || self.modifiers.super_key();
+                    let skip_camera = command_chord && event.state.is_pressed();
+                    if !skip_camera {
+                        if let Some(renderer) = &mut self.renderer {
+                            renderer
+                                .camera_controller
+                                .process_keyboard(key, event.state);
                         }
+                    }
 
-                        if event.state.is_pressed() {
-                            self.handle_tool_shortcut(key);
-                        }
+                    if event.state.is_pressed() {
+                        self.handle_tool_shortcut(key);
+                    }
 
-                        if key == KeyCode::Escape && event.state.is_pressed() {
-                            self.cursor_captured = false;
-                            if let Some(window) = &self.window {
-                                window.set_cursor_visible(true);
-                            }
+                    if key == KeyCode::Escape && event.state.is_pressed() {
+                        self.cursor_captured = false;
+                        if let Some(window) = &self.window {
+                            window.set_cursor_visible(true);
                         }
                     }
                 }
@@ -169,10 +171,43 @@ impl ApplicationHandler for App {
                             &mut renderer.camera,
                         );
                     }
-                    if button == MouseButton::Left {
+                    if button == MouseButton::Left && self.begin_gizmo_drag() {
+                        // A gizmo handle owns the click: the drag runs
+                        // until release, no tool, no held latch.
+                    } else if button == MouseButton::Left && self.paste_placement.is_some() {
+                        // Paste placement owns the click: commit the
+                        // ghost and skip the tool entirely (no held
+                        // latch, so the release finalizes nothing).
+                        self.commit_paste_placement();
+                    } else if button == MouseButton::Left && self.ui.recolor.picking {
+                        // Same for Replace Color's Pick: the click picks
+                        // the color to find, no tool runs.
+                        self.pick_recolor_source();
+                    } else if button == MouseButton::Left {
                         // Brush tools apply on press, then drag-paint
                         // re-applies on motion. Shape / Select latch an
-                        // anchor here and commit on release.
+                        // anchor here and commit on release. A brush
+                        // press opens a held stroke so the whole drag is
+                        // one undo entry, pauses included.
+                        let tool = self.editor.current_tool;
+                        if !tool.is_shape() && !matches!(tool, Tool::Select) {
+                            self.editor.history.begin_stroke();
+                        }
+                        if self.editor.history.is_locked()
+                            && !matches!(
+                                tool,
+                                Tool::Eyedropper
+                                    | Tool::Select
+                                    | Tool::Wand
+                                    | Tool::Socket
+                                    | Tool::Measure
+                            )
+                        {
+                            self.ui.set_status(format!(
+                                "Layer \"{}\" is locked",
+                                self.scene.active().name
+                            ));
+                        }
                         self.apply_tool();
                         self.left_button_held = true;
                         self.last_stroke_voxel =
@@ -199,6 +234,7 @@ impl ApplicationHandler for App {
                         );
                     }
                     if button == MouseButton::Left {
+                        self.end_gizmo_drag();
                         // Finalize an in-progress interaction only if a press
                         // actually started one in the viewport; either way,
                         // clear every latch so nothing carries into the next
@@ -235,28 +271,36 @@ impl ApplicationHandler for App {
                 }
             }
 
-            WindowEvent::MouseWheel { delta, .. } => {
-                if !egui_consumed {
-                    // Compute the zoom anchor (cursor's 3D point on
-                    // geometry, with a target-depth-plane fallback) BEFORE
-                    // taking the mutable renderer borrow. Without zoom-to-
-                    // cursor, scroll-zooming over a voxel of interest
-                    // doesn't keep that voxel under the cursor — the camera
-                    // dollies along the camera→target axis, the voxel
-                    // drifts off-screen, and a subsequent middle-orbit
-                    // pivots around `target` (which is wherever it was
-                    // before, often underground or in mid-air relative to
-                    // the user's actual focus). Scaling around the cursor
-                    // anchor migrates `target` with the zoom so orbit
-                    // naturally circles the inspected feature.
-                    if let Some(anchor) = self.compute_zoom_anchor() {
-                        if let Some(renderer) = &mut self.renderer {
-                            renderer.camera_controller.process_scroll(
-                                delta,
-                                &mut renderer.camera,
-                                anchor,
-                            );
-                        }
+            WindowEvent::MouseWheel { delta, .. } if !egui_consumed => {
+                // Compute the zoom anchor (cursor's 3D point on
+                // geometry, with a target-depth-plane fallback) BEFORE
+                // taking the mutable renderer borrow. Without zoom-to-
+                // cursor, scroll-zooming over a voxel of interest
+                // doesn't keep that voxel under the cursor — the camera
+                // dollies along the camera→target axis, the voxel
+                // drifts off-screen, and a subsequent middle-orbit
+                // pivots around `target` (which is wherever it was
+                // before, often underground or in mid-air relative to
+                // the user's actual focus). Scaling around the cursor
+                // anchor migrates `target` with the zoom so orbit
+                // naturally circles the inspected feature.
+                //
+                // In quad view an orthographic pane zooms on its
+                // own (half-height only) so the other panes and the
+                // perspective camera stay put.
+                let ortho_index = self.hovered_pane().and_then(|p| p.ortho_index());
+                if let Some(i) = ortho_index {
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.quad_zoom[i] =
+                            zoom_ortho_half_height(renderer.quad_zoom[i], delta);
+                    }
+                } else if let Some(anchor) = self.compute_zoom_anchor() {
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.camera_controller.process_scroll(
+                            delta,
+                            &mut renderer.camera,
+                            anchor,
+                        );
                     }
                 }
             }
@@ -266,10 +310,11 @@ impl ApplicationHandler for App {
 
                 if !egui_consumed {
                     self.update_raycast();
+                    self.update_gizmo_drag();
 
                     // Drag-paint: while left button is held, re-apply
                     // the brush whenever the hover crosses into a new
-                    // voxel. Limited to brush-style tools — Eyedropper
+                    // voxel, filling any cells a fast flick skipped. Limited to brush-style tools — Eyedropper
                     // / Fill keep their click-only behavior to avoid
                     // spam (Fill especially would explode the
                     // history). A pixel dead-zone around the press
@@ -278,10 +323,10 @@ impl ApplicationHandler for App {
                     if self.left_button_held {
                         let drag_eligible = matches!(
                             self.editor.current_tool,
-                            Tool::Place | Tool::Remove | Tool::Paint
+                            Tool::Place | Tool::Remove | Tool::Paint | Tool::Sculpt
                         );
                         let past_dead_zone =
-                            self.stroke_start_screen_pos.map_or(false, |(sx, sy)| {
+                            self.stroke_start_screen_pos.is_some_and(|(sx, sy)| {
                                 let dx = self.cursor_pos.0 - sx;
                                 let dy = self.cursor_pos.1 - sy;
                                 dx * dx + dy * dy >= DRAG_THRESHOLD_PX_SQ
@@ -290,7 +335,7 @@ impl ApplicationHandler for App {
                             let current =
                                 self.editor.hovered_voxel.map(|h| h.voxel_pos);
                             if current.is_some() && current != self.last_stroke_voxel {
-                                self.apply_tool();
+                                self.apply_tool_from(self.last_stroke_voxel);
                                 self.last_stroke_voxel = current;
                             }
                         }
@@ -318,11 +363,26 @@ impl ApplicationHandler for App {
 
                 self.tick_preview();
                 self.tick_ai_job();
+                self.tick_gen_job();
+                self.tick_turntable();
+                self.tick_flythrough();
+                self.tick_playback();
+                self.tick_camera_path_preview();
+                self.tick_gpu_pick();
+                self.update_work_plane_visualization();
                 self.update_brush_preview();
                 self.update_selection_visualization();
                 self.update_socket_visualization();
+                self.update_bounds_visualization();
+                self.update_measure_visualization();
+                self.update_gizmo_visualization();
+                self.update_hover_highlight();
                 self.rebuild_all_meshes();
+                self.update_onion_skin();
+                self.update_pose_preview();
+                self.tick_background_save();
                 self.tick_autosave();
+                self.tick_streaming();
                 self.render_frame(dt);
 
                 if let Some(window) = &self.window {
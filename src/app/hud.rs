@@ -15,9 +15,11 @@
 //! `update_brush_preview`), and the move delta mirrors
 //! `update_selection_visualization`.
 
-use voxelith::editor::Tool;
+use glam::Vec3;
+use voxelith::editor::{Measurement, Tool};
 use voxelith::ui::hud::{
     delta_label, dims_label, drag_dims, plane_label, selection_label, symmetry_label,
+    work_plane_label,
 };
 use voxelith::ui::HudState;
 
@@ -34,7 +36,12 @@ impl App {
         let mut detail = None;
         let mut hints = None;
 
-        if tool.is_shape() {
+        if let Some(drag) = self.gizmo_drag {
+            // A gizmo drag runs over any tool.
+            phase = Some("Moving");
+            detail = Some(delta_label(drag.delta));
+            hints = Some("release: drop · Esc: cancel");
+        } else if tool.is_shape() {
             // `update_brush_preview` (which runs before the egui pass)
             // drops a shape drag stranded by a mid-drag tool switch,
             // so a live `shape_drag` here always belongs to `tool`.
@@ -91,6 +98,14 @@ impl App {
                 }
                 hints = Some("release: select");
             }
+        } else if tool == Tool::Measure {
+            if self.editor.measurement.is_some_and(|m| !m.is_complete()) {
+                phase = Some("End");
+                if let Some((start, end)) = self.measure_ends() {
+                    detail = Some(Measurement::label(start, end));
+                }
+                hints = Some("click: end · Esc: clear");
+            }
         } else if self.left_button_held {
             // Mid-stroke for a brush tool: surface the locked face
             // plane the drag-paint is pinned to.
@@ -105,6 +120,8 @@ impl App {
             None
         };
 
+        let work_plane = self.editor.active_work_plane().map(|p| work_plane_label(&p));
+
         // Select-tool-only — the status bar keeps the always-on copy
         // for other tools. Hidden mid-marquee-drag: the live size is
         // already in `detail`, and the stale pre-drag box would just
@@ -123,16 +140,57 @@ impl App {
             phase,
             detail,
             symmetry,
+            work_plane,
             selection,
             hints,
+            measure: self.measure_annotation(),
+        }
+    }
+
+    /// Screen position (egui points) and readout of the ruler's
+    /// dimension label: the midpoint between the two cell centers,
+    /// projected through the main camera. Skipped in quad view, where
+    /// each pane has its own camera, and when the midpoint is behind
+    /// the camera or off screen.
+    fn measure_annotation(&self) -> Option<([f32; 2], String)> {
+        let (start, end) = self.measure_ends()?;
+        if self.ui.viewport.quad_view && !self.ui.viewport.path_trace {
+            return None;
+        }
+        let renderer = self.renderer.as_ref()?;
+        let scale = self.window.as_ref()?.scale_factor() as f32;
+        let mid = Vec3::new(
+            (start.0 + end.0) as f32 * 0.5 + 0.5,
+            (start.1 + end.1) as f32 * 0.5 + 0.5,
+            (start.2 + end.2) as f32 * 0.5 + 0.5,
+        );
+        let clip = renderer.camera.view_projection_matrix() * mid.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let ndc = clip.truncate() / clip.w;
+        if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
+            return None;
         }
+        let x = (ndc.x * 0.5 + 0.5) * renderer.config.width as f32 / scale;
+        let y = (0.5 - ndc.y * 0.5) * renderer.config.height as f32 / scale;
+        Some(([x, y], Measurement::label(start, end)))
     }
 }
 
 /// Symmetry mirrors Place / Remove / Paint / Fill writes and shape
-/// commits; Eyedropper samples, Select reads, and Socket drops an
-/// un-mirrored anchor — a "Sym" line for those would imply an effect
-/// that won't happen.
-fn tool_uses_symmetry(t: Tool) -> bool {
-    !matches!(t, Tool::Eyedropper | Tool::Select | Tool::Socket)
+/// commits; Eyedropper samples, Select, Wand and Measure read, Socket
+/// drops an un-mirrored anchor, and Extrude follows the hovered
+/// surface — a "Sym" line for those would imply an effect that won't
+/// happen.
+pub(super) fn tool_uses_symmetry(t: Tool) -> bool {
+    !matches!(
+        t,
+        Tool::Eyedropper
+            | Tool::Select
+            | Tool::Wand
+            | Tool::Socket
+            | Tool::Extrude
+            | Tool::Measure
+    )
 }
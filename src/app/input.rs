@@ -5,15 +5,43 @@ use winit::keyboard::KeyCode;
 use std::collections::HashSet;
 
 use voxelith::editor::{
-    box_voxels, build_clear_changes, build_move_changes, build_paste_changes,
-    copy_selection_to_clipboard, cylinder_voxels, eyedrop, flood_fill, flood_fill_multi,
-    line_voxels, mirror_selection_changes, rotate_selection_changes, sphere_voxels, Axis,
-    BrushTool, Command, EditorTool, Quarter, Ray, RaycastHit, Selection, Tool, ToolContext,
-    VoxelChange, VoxelRaycast,
+    box_voxels, build_adjust_changes, build_clear_changes, build_crop_changes,
+    build_extrude_changes, build_move_changes, build_paste_changes, build_replace_changes,
+    build_smooth_changes, compute_fill_changes, copy_selection_to_clipboard, cylinder_voxels,
+    eyedrop, face_region, flood_fill_multi, hollow_box_voxels, line_voxels, mirror_pos,
+    mirror_selection_changes, origin_offset, rotate_pos, rotate_selection_changes,
+    scale_selection_changes, select_by_color, sphere_voxels, stroke_gap_hits, Axis, BrushTool,
+    Clipboard, ColorAdjust, Command, EditorTool, ExtrudeDirection, FillSeed, FillSettings,
+    Measurement, OriginAnchor, Quarter, Ray, RaycastHit, RaycastOptions, RecolorScope, Scale,
+    Selection, SelectionMask, Tool, ToolContext, VoxelChange, VoxelRaycast, WorkPlane,
+    MAX_EXTRUDE_CELLS, MAX_FILL_VOXELS, MAX_WAND_CELLS,
 };
 
+use voxelith::core::{CellBox, FrameId, LayerId, Voxel, World, WorldBounds};
+use voxelith::io::CameraBookmark;
+use voxelith::render::{
+    decode_pick_id, Camera, CameraPose, CameraView, PickRequest, ViewPane,
+};
+use voxelith::ui::hud::work_plane_label;
+use voxelith::ui::UiAction;
+
 use super::{build_stroke_plane, App, ShapeDrag, ShapePhase, StrokePlane};
 
+/// A Fill click too big to run without asking, waiting on the
+/// large-fill prompt. The prompt doesn't block the editor, so the fill
+/// is pinned to the layer and animation frame it was measured on and to
+/// their `CommandHistory::edit_count`; [`App::confirm_large_fill`]
+/// drops it if any of them changed.
+pub(super) struct PendingFill {
+    layer: LayerId,
+    frame: FrameId,
+    edits: u64,
+    seeds: Vec<FillSeed>,
+    color: Voxel,
+    work_plane: Option<WorkPlane>,
+    settings: FillSettings,
+}
+
 /// Maximum distance (in voxel units) the editor's mouse-hover ray
 /// will travel through the world looking for a hit. Caps DDA work
 /// per cursor move; also implicitly limits how far the user can
@@ -23,7 +51,54 @@ use super::{build_stroke_plane, App, ShapeDrag, ShapePhase, StrokePlane};
 /// anything you can clearly see.
 const RAYCAST_MAX_DIST: f32 = 500.0;
 
+/// The surface pixel under a cursor position.
+fn cursor_pixel(cursor_pos: (f32, f32)) -> [u32; 2] {
+    [cursor_pos.0 as u32, cursor_pos.1 as u32]
+}
+
 impl App {
+    /// The pane under the cursor in quad view, or `None` in the
+    /// single full-window view (which the path-traced preview forces).
+    pub(super) fn hovered_pane(&self) -> Option<ViewPane> {
+        if !self.ui.viewport.quad_view || self.ui.viewport.path_trace {
+            return None;
+        }
+        let renderer = self.renderer.as_ref()?;
+        Some(ViewPane::at(
+            self.cursor_pos,
+            renderer.config.width,
+            renderer.config.height,
+        ))
+    }
+
+    /// World-space ray through the cursor, plus the camera it was cast
+    /// from. In quad view the ray comes from whichever pane the cursor
+    /// is over — cursor position made pane-local, unprojected through
+    /// that pane's camera — so every pane picks what it draws.
+    /// `Ray::from_screen` unprojects near / far points, so the same
+    /// path yields parallel rays for the orthographic panes.
+    pub(super) fn cursor_ray(&self) -> Option<(Ray, Camera)> {
+        let renderer = self.renderer.as_ref()?;
+        let window = self.window.as_ref()?;
+        if let Some(pane) = self.hovered_pane() {
+            let [x, y, w, h] = pane.rect(renderer.config.width, renderer.config.height);
+            let camera = renderer.pane_camera(pane);
+            let ray = Ray::from_screen(
+                (self.cursor_pos.0 - x as f32, self.cursor_pos.1 - y as f32),
+                (w as f32, h as f32),
+                camera.view_projection_matrix().inverse(),
+            );
+            return Some((ray, camera));
+        }
+        let size = window.inner_size();
+        let ray = Ray::from_screen(
+            self.cursor_pos,
+            (size.width as f32, size.height as f32),
+            renderer.camera.view_projection_matrix().inverse(),
+        );
+        Some((ray, renderer.camera.clone()))
+    }
+
     /// Compute the 3D world anchor for a zoom-to-cursor scroll. Tries
     /// to raycast against world geometry first; if the cursor isn't
     /// over anything solid, falls back to the cursor ray's intersection
@@ -36,19 +111,14 @@ impl App {
     /// "no anchor" behavior makes sense (typically just no zoom).
     pub(super) fn compute_zoom_anchor(&self) -> Option<glam::Vec3> {
         let renderer = self.renderer.as_ref()?;
-        let window = self.window.as_ref()?;
-        let size = window.inner_size();
-        let view_proj_inv = renderer.camera.view_projection_matrix().inverse();
-        let ray = Ray::from_screen(
-            self.cursor_pos,
-            (size.width as f32, size.height as f32),
-            view_proj_inv,
-        );
+        let (ray, camera) = self.cursor_ray()?;
 
         // Real-geometry hit takes priority — this is the use case the
         // user described: "zoom in to inspect this voxel". Use the same
         // RAYCAST_MAX_DIST as editor picking so the reach is consistent.
-        if let Some(hit) = VoxelRaycast::cast(&ray, &self.world, RAYCAST_MAX_DIST) {
+        let y_max = self.ui.viewport.y_clip_max();
+        let hit = VoxelRaycast::cast_below(&ray, self.scene.view(), RAYCAST_MAX_DIST, y_max);
+        if let Some(hit) = hit {
             return Some(ray.at(hit.distance));
         }
 
@@ -57,7 +127,6 @@ impl App {
         // anchor at the same view-depth as the current orbit pivot so
         // the resulting target shift is purely lateral, not "into the
         // distance" (which would feel like an unintended dolly).
-        let camera = &renderer.camera;
         let view_dir = (camera.target - camera.position).normalize();
         let denom = ray.direction.dot(view_dir);
         if denom.abs() > 1e-6 {
@@ -69,7 +138,7 @@ impl App {
         // Degenerate (ray parallel to view plane or pointing the wrong
         // way). Falling back to `target` makes process_scroll behave
         // exactly like the pre-zoom-to-cursor "scale around target".
-        Some(camera.target)
+        Some(renderer.camera.target)
     }
 
     /// Compute the orbit pivot for a middle-mouse press, Unity-style:
@@ -93,9 +162,10 @@ impl App {
         let ray = Ray::new(camera.position, camera.forward());
         Some(VoxelRaycast::orbit_pivot(
             &ray,
-            &self.world,
+            self.scene.view(),
             RAYCAST_MAX_DIST,
             camera.target,
+            self.ui.viewport.y_clip_max(),
         ))
     }
 
@@ -104,7 +174,8 @@ impl App {
     /// view direction to the fit distance. Framing keeps the user's
     /// viewing angle — only target + distance change (Blender / Unity
     /// "frame" convention), so it never disorients by snapping to a new
-    /// orientation.
+    /// orientation. The move is animated (`CameraController::
+    /// animate_frame`) so the eye can follow where the camera went.
     ///
     /// Distance is clamped to the orbit zoom range `[2, 500]` so a
     /// following scroll behaves; a scene larger than that hits the cap
@@ -127,22 +198,19 @@ impl App {
         let camera = &mut renderer.camera;
         // Preserve the current orbit direction; fall back to a 3/4 view
         // when the camera sits on the target (direction undefined).
-        let mut dir = camera.position - camera.target;
+        let dir = camera.position - camera.target;
         if !dir.is_finite() || dir.length() < 1e-4 {
-            dir = glam::Vec3::new(1.0, 0.8, 1.0);
+            camera.position = camera.target + glam::Vec3::new(1.0, 0.8, 1.0);
         }
-        let dir = dir.normalize();
         let dist = camera.fit_distance(extent, 1.15).clamp(2.0, 500.0);
-        camera.target = center;
-        camera.position = center + dir * dist;
         renderer
             .camera_controller
-            .sync_orbit_state_from_camera(camera);
+            .animate_frame(camera, center, dist);
     }
 
     /// Frame the whole scene (AABB of every non-air voxel).
     pub(super) fn frame_all(&mut self) {
-        match self.world.scene_aabb() {
+        match self.scene.view().scene_aabb() {
             Some((min, max)) => {
                 self.frame_camera_on_aabb(min, max);
                 self.ui.set_status("Framed scene");
@@ -176,12 +244,66 @@ impl App {
         }
     }
 
+    /// Snap the orbit camera to a preset view (Viewport Settings
+    /// buttons, numpad keys).
+    pub(super) fn set_camera_view(&mut self, view: CameraView) {
+        if let Some(renderer) = &mut self.renderer {
+            renderer
+                .camera_controller
+                .apply_view(view, &mut renderer.camera);
+        }
+    }
+
+    /// Store the current camera in bookmark `slot` (0-based; the UI
+    /// and shortcuts number them 1..9). Bookmarks save with the
+    /// project, so storing one marks it unsaved.
+    pub(super) fn save_camera_bookmark(&mut self, slot: usize) {
+        let (Some(renderer), Some(entry)) = (&self.renderer, self.camera_bookmarks.get_mut(slot))
+        else {
+            return;
+        };
+        *entry = Some(CameraBookmark {
+            position: renderer.camera.position.to_array(),
+            target: renderer.camera.target.to_array(),
+            quad_zoom: renderer.quad_zoom,
+        });
+        self.unsaved_changes = true;
+        self.ui.set_status(format!("Saved camera bookmark {}", slot + 1));
+    }
+
+    /// Move the camera to bookmark `slot`. Tweened like the view
+    /// presets; the ortho pane zooms switch at once.
+    pub(super) fn recall_camera_bookmark(&mut self, slot: usize) {
+        let Some(renderer) = &mut self.renderer else {
+            return;
+        };
+        let Some(bookmark) = self.camera_bookmarks.get(slot).copied().flatten() else {
+            self.ui.set_status(format!(
+                "Camera bookmark {} is empty — Ctrl+Shift+{} saves one",
+                slot + 1,
+                slot + 1
+            ));
+            return;
+        };
+        let pose = CameraPose::looking_at(
+            glam::Vec3::from_array(bookmark.position),
+            glam::Vec3::from_array(bookmark.target),
+        );
+        renderer.quad_zoom = bookmark.quad_zoom;
+        renderer
+            .camera_controller
+            .animate_to(&mut renderer.camera, pose);
+        self.ui.set_status(format!("Camera bookmark {}", slot + 1));
+    }
+
     /// Update the editor's hovered voxel from the current cursor position.
     ///
     /// Tools that need an "anchor cell" to place new geometry (Place
     /// and the four shape tools) get a y=0 ground-plane fallback when
     /// the ray misses every voxel — that way they work in a freshly-
-    /// cleared (empty) world. Tools that read existing voxels
+    /// cleared (empty) world. The fallback stops at the edge of the
+    /// ground grid, so a click off toward the horizon doesn't drop a
+    /// voxel hundreds of cells away. Tools that read existing voxels
     /// (Remove/Paint/Eyedropper/Fill) stay strict: virtual hits would
     /// give confusing previews and either no-op or, worse, explode
     /// (Fill flooding a 3D air region).
@@ -191,7 +313,42 @@ impl App {
     /// the cursor casts ray-vs-plane against the locked face. This
     /// keeps the stroke on one face instead of stacking along the
     /// view direction as new voxels occlude the ray-vs-voxels hit.
+    ///
+    /// **Work-plane mode overrides both** for the tools that aim at
+    /// cells (brush, Fill, Eyedropper, an idle shape tool): they hover
+    /// the cell where the ray crosses the slice, so drawing a layer
+    /// isn't blocked by the layers in front of it. An active shape
+    /// drag keeps its own plane, which already lies on the slice.
+    ///
+    /// On a bounded layer a target outside the bounds is dropped: the
+    /// world would ignore the write, so no ghost should promise it.
     pub(super) fn update_raycast(&mut self) {
+        self.cast_hover();
+        let (Some(bounds), Some(hit)) =
+            (self.scene.active_world().bounds(), self.editor.hovered_voxel)
+        else {
+            return;
+        };
+        let (x, y, z) = if self.editor.current_tool.uses_ground_plane_fallback() {
+            hit.adjacent_pos
+        } else {
+            hit.voxel_pos
+        };
+        if !bounds.contains_voxel(x, y, z) {
+            self.editor.hovered_voxel = None;
+        }
+    }
+
+    /// `update_raycast` before the bounds check.
+    fn cast_hover(&mut self) {
+        if self.shape_drag.is_none() && self.editor.current_tool.aims_at_work_plane() {
+            if let Some(plane) = self.editor.active_work_plane() {
+                self.editor.hovered_voxel = self
+                    .cursor_ray()
+                    .and_then(|(ray, _)| plane.hit(&ray, RAYCAST_MAX_DIST));
+                return;
+            }
+        }
         if let Some(plane) = self.stroke_plane {
             self.editor.hovered_voxel = self.cast_ray_to_plane(&plane);
             return;
@@ -207,26 +364,101 @@ impl App {
             return;
         }
 
-        let Some(renderer) = &self.renderer else {
+        let Some((ray, camera)) = self.cursor_ray() else {
             return;
         };
-        let window = self.window.as_ref().unwrap();
-        let size = window.inner_size();
 
-        let view_proj = renderer.camera.view_projection_matrix();
-        let view_proj_inv = view_proj.inverse();
+        // Voxels hidden by the viewport's Y-clip don't block the
+        // cursor, nor (optionally) those of locked layers. Hidden
+        // layers aren't in the view to begin with.
+        let view = self.scene.view();
+        let ground = self.editor.current_tool.uses_ground_plane_fallback();
+        let scene = &self.scene;
+        let unlocked =
+            |x, y, z| scene.visible_layer_at(x, y, z).is_none_or(|layer| !layer.locked);
+        let options = RaycastOptions {
+            y_max: self.ui.viewport.y_clip_max(),
+            ground_plane: ground.then_some(0),
+            ground_half_extent: Some(self.ui.viewport.grid_half_extent()),
+            filter: self
+                .ui
+                .viewport
+                .click_through_locked
+                .then_some(&unlocked as &dyn Fn(i32, i32, i32) -> bool),
+        };
+        let cpu_cast = || VoxelRaycast::cast_with(&ray, view, RAYCAST_MAX_DIST, &options);
+        if !self.gpu_picking() {
+            self.editor.hovered_voxel = cpu_cast();
+            return;
+        }
+
+        // GPU picking: only a pick taken at this pixel through this
+        // camera counts. Until one lands the hover stays put;
+        // `tick_gpu_pick` calls back here when it does.
+        let current = self.gpu_pick.filter(|sample| {
+            sample.request.pixel == cursor_pixel(self.cursor_pos)
+                && sample.request.view_proj == camera.view_projection_matrix()
+        });
+        let Some(sample) = current else {
+            return;
+        };
+        self.editor.hovered_voxel = match decode_pick_id(sample.id, ray.origin) {
+            None => options.ground_hit(&ray, RAYCAST_MAX_DIST),
+            Some(cell) => match VoxelRaycast::cell_hit(&ray, cell) {
+                Some(hit)
+                    if hit.distance <= RAYCAST_MAX_DIST
+                        && options.hits(view, cell.0, cell.1, cell.2) =>
+                {
+                    Some(hit)
+                }
+                // Edited since the pick was drawn, a locked layer's
+                // voxel, or a mesh that doesn't follow the voxel grid:
+                // fall back to the CPU cast.
+                _ => cpu_cast(),
+            },
+        };
+    }
 
-        let ray = Ray::from_screen(
-            self.cursor_pos,
-            (size.width as f32, size.height as f32),
-            view_proj_inv,
-        );
+    /// Whether the hover comes from the GPU ID buffer (Viewport
+    /// Settings ▸ GPU Picking). The path-traced preview has no raster
+    /// pass to pick from.
+    fn gpu_picking(&self) -> bool {
+        self.ui.viewport.gpu_picking && !self.ui.viewport.path_trace
+    }
 
-        self.editor.hovered_voxel = if self.editor.current_tool.uses_ground_plane_fallback() {
-            VoxelRaycast::cast_with_ground_plane(&ray, &self.world, RAYCAST_MAX_DIST, 0)
-        } else {
-            VoxelRaycast::cast(&ray, &self.world, RAYCAST_MAX_DIST)
+    /// The GPU pick for the pixel under the cursor, through the camera
+    /// of the pane it's over — what `render_frame` submits after the
+    /// main pass. `None` with GPU picking off or the cursor outside
+    /// the window.
+    pub(super) fn gpu_pick_request(&self) -> Option<PickRequest> {
+        if !self.gpu_picking() || self.cursor_pos.0 < 0.0 || self.cursor_pos.1 < 0.0 {
+            return None;
+        }
+        let renderer = self.renderer.as_ref()?;
+        let (width, height) = (renderer.config.width, renderer.config.height);
+        let (viewport, camera) = match self.hovered_pane() {
+            Some(pane) => (pane.rect(width, height), renderer.pane_camera(pane)),
+            None => ([0, 0, width, height], renderer.camera.clone()),
+        };
+        Some(PickRequest {
+            pixel: cursor_pixel(self.cursor_pos),
+            viewport,
+            view_proj: camera.view_projection_matrix(),
+            y_clip: self.ui.viewport.y_clip_max(),
+            scene_revision: renderer.scene_revision,
+        })
+    }
+
+    /// Collect a GPU pick whose readback has landed and re-resolve the
+    /// hover with it.
+    pub(super) fn tick_gpu_pick(&mut self) {
+        let Some(sample) = self.renderer.as_mut().and_then(|r| r.poll_pick()) else {
+            return;
         };
+        self.gpu_pick = Some(sample);
+        if self.gpu_picking() {
+            self.update_raycast();
+        }
     }
 
     /// Synthesize a `RaycastHit` from a ray-vs-plane intersection
@@ -235,15 +467,7 @@ impl App {
     /// the plane or the intersection lies behind the camera (cursor
     /// pointing the wrong way).
     fn cast_ray_to_plane(&self, plane: &StrokePlane) -> Option<RaycastHit> {
-        let renderer = self.renderer.as_ref()?;
-        let window = self.window.as_ref()?;
-        let size = window.inner_size();
-        let view_proj_inv = renderer.camera.view_projection_matrix().inverse();
-        let ray = Ray::from_screen(
-            self.cursor_pos,
-            (size.width as f32, size.height as f32),
-            view_proj_inv,
-        );
+        let (ray, _) = self.cursor_ray()?;
 
         let dir_arr = ray.direction.to_array();
         let origin_arr = ray.origin.to_array();
@@ -276,13 +500,109 @@ impl App {
     }
 
     /// Apply the current tool at the hovered location.
+    /// Drag-paint step: apply the brush at every cell the cursor
+    /// skipped since `from` (the previous stroke cell), then at the
+    /// current hover. All writes land in the held stroke's single undo
+    /// entry via `execute_merge`.
+    pub(super) fn apply_tool_from(&mut self, from: Option<(i32, i32, i32)>) {
+        if let (Some(from), Some(hit)) = (from, self.editor.hovered_voxel) {
+            let brush = BrushTool::new(self.editor.current_tool);
+            let work_plane = self.editor.active_work_plane();
+            let mut ctx = ToolContext {
+                world: self.scene.active_world_mut(),
+                history: &mut self.editor.history,
+                brush_color: self.editor.brush_color,
+                brush_size: self.editor.brush_size,
+                symmetry: self.editor.symmetry,
+                paint: self.editor.paint,
+                brush: self.editor.brush.clone(),
+                sculpt: self.editor.sculpt,
+                work_plane,
+            };
+            for gap in stroke_gap_hits(from, &hit) {
+                // Fresh sculpt randomness per step, as in `apply_tool`.
+                ctx.sculpt.seed = ctx.sculpt.seed.wrapping_add(1);
+                brush.apply(&mut ctx, &gap);
+            }
+        }
+        self.apply_tool();
+    }
+
+    /// Fill from each seed in the brush color, as one undo entry. A
+    /// fill over [`MAX_FILL_VOXELS`] is held in `pending_fill` behind
+    /// the large-fill prompt instead.
+    pub(super) fn fill_from(
+        &mut self,
+        seeds: Vec<FillSeed>,
+        work_plane: Option<WorkPlane>,
+    ) {
+        let fill = PendingFill {
+            layer: self.scene.active().id,
+            frame: self.current_frame_id(),
+            edits: self.editor.history.edit_count(),
+            seeds,
+            color: self.editor.brush_color,
+            work_plane,
+            settings: self.editor.fill,
+        };
+        let changes = compute_fill_changes(
+            self.scene.active_world(),
+            &fill.seeds,
+            fill.color,
+            MAX_FILL_VOXELS + 1,
+            fill.work_plane,
+            fill.settings,
+        );
+        if changes.len() > MAX_FILL_VOXELS {
+            self.pending_fill = Some(fill);
+            self.ui.state.show_large_fill_prompt = true;
+        } else if !changes.is_empty() {
+            let cmd = Command::set_voxels(changes).named("Fill");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
+        }
+    }
+
+    /// Id of the animation frame being edited.
+    fn current_frame_id(&self) -> FrameId {
+        self.scene.frames()[self.scene.current_frame()].id
+    }
+
+    /// Large-fill prompt's Fill: run the held fill with no voxel cap
+    /// (`MAX_FILL_DIST` still bounds it). Dropped instead if another
+    /// layer or frame is now shown, or the layer was edited meanwhile.
+    pub(super) fn confirm_large_fill(&mut self) {
+        let Some(fill) = self.pending_fill.take() else {
+            return;
+        };
+        if fill.layer != self.scene.active().id || fill.frame != self.current_frame_id() {
+            self.ui
+                .set_status("Fill: discarded — the target layer or frame is no longer shown");
+            return;
+        }
+        if fill.edits != self.editor.history.edit_count() {
+            self.ui
+                .set_status("Fill: discarded — the layer was edited after the click");
+            return;
+        }
+        let count = flood_fill_multi(
+            self.scene.active_world_mut(),
+            &mut self.editor.history,
+            &fill.seeds,
+            fill.color,
+            usize::MAX,
+            fill.work_plane,
+            fill.settings,
+        );
+        self.ui.set_status(format!("Filled {} voxels", count));
+    }
+
     pub(super) fn apply_tool(&mut self) {
         let Some(hit) = self.editor.hovered_voxel else {
             return;
         };
 
         match self.editor.current_tool {
-            Tool::Place | Tool::Remove | Tool::Paint => {
+            Tool::Place | Tool::Remove | Tool::Paint | Tool::Sculpt => {
                 // Lock the stroke to the first hit's face plane.
                 // Subsequent CursorMoved events (drag-paint) will
                 // ray-vs-plane against this lock instead of the
@@ -293,17 +613,25 @@ impl App {
                     self.stroke_plane = build_stroke_plane(&hit);
                 }
                 let brush = BrushTool::new(self.editor.current_tool);
+                let work_plane = self.editor.active_work_plane();
                 let mut ctx = ToolContext {
-                    world: &mut self.world,
+                    world: self.scene.active_world_mut(),
                     history: &mut self.editor.history,
                     brush_color: self.editor.brush_color,
                     brush_size: self.editor.brush_size,
                     symmetry: self.editor.symmetry,
+                    paint: self.editor.paint,
+                    brush: self.editor.brush.clone(),
+                    sculpt: self.editor.sculpt,
+                    work_plane,
                 };
                 brush.apply(&mut ctx, &hit);
+                // Erode / Dilate pick different cells next step, so
+                // dabbing one spot keeps working it.
+                self.editor.sculpt.seed = self.editor.sculpt.seed.wrapping_add(1);
             }
             Tool::Eyedropper => {
-                if let Some(color) = eyedrop(&self.world, &hit) {
+                if let Some(color) = eyedrop(self.scene.view(), &hit) {
                     self.editor.brush_color = color;
                 }
             }
@@ -314,7 +642,7 @@ impl App {
                 // eat the entire 3D air region around the cursor (capped
                 // by `flood_fill`'s spatial limit, but still visually
                 // alarming and never what the user meant).
-                let v = self.world.get_voxel(
+                let v = self.scene.active_world().get_voxel(
                     hit.voxel_pos.0,
                     hit.voxel_pos.1,
                     hit.voxel_pos.2,
@@ -322,28 +650,16 @@ impl App {
                 if v.is_air() {
                     return;
                 }
+                // Combine all mirrored fills into one undo entry — a
+                // single click should be a single undo, even at 8-fold
+                // symmetry.
                 let symmetry = self.editor.symmetry;
-                if symmetry.any() {
-                    // Combine all mirrored fills into one undo entry —
-                    // a single click should be a single undo, even at
-                    // 8-fold symmetry.
-                    let starts = symmetry.mirror_positions(hit.voxel_pos);
-                    flood_fill_multi(
-                        &mut self.world,
-                        &mut self.editor.history,
-                        &starts,
-                        self.editor.brush_color,
-                        10000,
-                    );
-                } else {
-                    flood_fill(
-                        &mut self.world,
-                        &mut self.editor.history,
-                        hit.voxel_pos,
-                        self.editor.brush_color,
-                        10000,
-                    );
-                }
+                let seeds = symmetry
+                    .mirror_positions(hit.voxel_pos)
+                    .into_iter()
+                    .zip(symmetry.mirror_normals(hit.normal))
+                    .collect();
+                self.fill_from(seeds, self.editor.active_work_plane());
             }
             Tool::Line | Tool::Box | Tool::Sphere | Tool::Cylinder => {
                 // Shape press is two-phase:
@@ -437,9 +753,114 @@ impl App {
                     name, position[0], position[1], position[2]
                 ));
             }
+            Tool::Extrude => self.extrude_face(&hit),
+            Tool::Wand => self.magic_wand_pick(&hit),
+            Tool::Measure => self.measure_pick(&hit),
+        }
+    }
+
+    /// Measure tool click: the first click drops the ruler's start,
+    /// the second its end (reported in the status bar), a third starts
+    /// over. Reads only, so it works on locked layers too.
+    fn measure_pick(&mut self, hit: &RaycastHit) {
+        if hit.virtual_ground {
+            self.ui.set_status("Measure: click a voxel");
+            return;
+        }
+        let m = Measurement::click(self.editor.measurement, hit.voxel_pos);
+        self.editor.measurement = Some(m);
+        match m.end {
+            Some(end) => self
+                .ui
+                .set_status(format!("Measured {}", Measurement::label(m.start, end))),
+            None => self.ui.set_status(format!(
+                "Measure from ({}, {}, {}) — click the end voxel",
+                m.start.0, m.start.1, m.start.2
+            )),
         }
     }
 
+    /// Wand tool click: select the voxels of the active layer connected
+    /// to the clicked one and within `wand_tolerance` of its color.
+    /// Shift-click adds to a current wand pick. Like Select, not
+    /// undoable and fine on a locked layer — it only reads.
+    fn magic_wand_pick(&mut self, hit: &RaycastHit) {
+        let picked = (!hit.virtual_ground)
+            .then(|| {
+                select_by_color(
+                    self.scene.active_world(),
+                    hit.voxel_pos,
+                    self.editor.wand_tolerance,
+                    MAX_WAND_CELLS,
+                )
+            })
+            .flatten();
+        let Some(picked) = picked else {
+            self.ui
+                .set_status("Magic wand: click a voxel on the active layer");
+            return;
+        };
+        let capped = if picked.len() == MAX_WAND_CELLS { " (capped)" } else { "" };
+        let mask = match self.editor.active_mask() {
+            Some(current) if self.modifiers.shift_key() => current.union(&picked),
+            _ => picked,
+        };
+        let (w, h, d) = mask.bounds().size();
+        self.ui.set_status(format!(
+            "Selected {} voxels{capped} ({w}×{h}×{d})",
+            mask.len()
+        ));
+        self.editor.selection = Some(mask.bounds());
+        self.editor.selection_mask = Some(mask);
+    }
+
+    /// Extrude tool click: push the hovered face's connected coplanar
+    /// same-colored surface out (or carve it in) by the tool depth, as
+    /// one undo entry. Not drag-eligible — each click is one step, so a
+    /// held drag can't stack extrusions by accident.
+    fn extrude_face(&mut self, hit: &RaycastHit) {
+        if self.editor.history.is_locked() {
+            // `handler.rs` already put the lock message up.
+            return;
+        }
+        if hit.virtual_ground {
+            self.ui.set_status("Extrude: aim at a voxel face");
+            return;
+        }
+        let world = self.scene.active_world();
+        let region = face_region(world, hit.voxel_pos, hit.normal, MAX_EXTRUDE_CELLS);
+        if region.is_empty() {
+            // Degenerate normal, or the face belongs to another layer.
+            self.ui
+                .set_status("Extrude: aim at an exposed face on the active layer");
+            return;
+        }
+        let depth = self.editor.extrude_depth;
+        let direction = self.editor.extrude_direction;
+        let mut changes = build_extrude_changes(world, &region, hit.normal, depth, direction);
+        if let Some(plane) = self.editor.active_work_plane() {
+            changes = plane.clip(changes);
+        }
+        if changes.is_empty() {
+            self.ui.set_status("Extrude: blocked — nothing to change");
+            return;
+        }
+        let capped = if region.len() == MAX_EXTRUDE_CELLS { " (capped)" } else { "" };
+        let (verb, label) = match direction {
+            ExtrudeDirection::Out => ("Extruded", "Extrude"),
+            ExtrudeDirection::In => ("Inset", "Inset"),
+        };
+        self.ui.set_status(format!(
+            "{verb} {} face cells{capped} by {depth} ({} voxels changed)",
+            region.len(),
+            changes.len()
+        ));
+        self.editor.history.execute(
+            Command::set_voxels(changes).named(label),
+            self.scene.active_world_mut(),
+        );
+    }
+
     /// Commit the in-progress selection drag on left-button release.
     /// Two paths:
     /// - **Move drag** (`selection_move_anchor` set): translate the
@@ -487,7 +908,7 @@ impl App {
             return;
         };
         let end = Self::select_anchor_pos(&hit);
-        self.editor.selection = Some(Selection::from_corners(anchor, end));
+        self.editor.set_selection(Some(Selection::from_corners(anchor, end)));
     }
 
     /// Translate the active selection's non-air voxels by `delta` as
@@ -501,14 +922,16 @@ impl App {
         let Some(sel) = self.editor.selection else {
             return;
         };
-        let changes = build_move_changes(&self.world, sel, delta);
+        let ((), changes) = self.box_changes(sel, |w| ((), build_move_changes(w, sel, delta)));
         if !changes.is_empty() {
-            let cmd = Command::set_voxels(changes);
-            self.editor.history.execute(cmd, &mut self.world);
+            let cmd = Command::set_voxels(changes).named("Move");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
         }
         // Even an empty selection (all air) bumps its AABB so the
         // user can keyboard-nudge a marquee around empty space.
-        self.editor.selection = Some(sel.translated(delta));
+        self.carry_selection(sel.translated(delta), |p| {
+            (p.0 + delta.0, p.1 + delta.1, p.2 + delta.2)
+        });
     }
 
     /// Transition an in-progress shape drag from Footprint to
@@ -546,28 +969,88 @@ impl App {
             .set_status("Drag vertically to set height, click to commit (Esc cancels)");
     }
 
-    /// Rotate the active selection's contents around `axis` by
-    /// `quarter` (90° / -90° / 180°). The selection's AABB may
-    /// change footprint (Y-rotation swaps W ↔ D, etc.) but its
-    /// `min` corner stays put — see `editor::transform` for the
-    /// anchor convention. Result is one `Command::set_voxels` so
-    /// Ctrl+Z reverses the entire rotation.
-    pub(super) fn rotate_selection(&mut self, axis: Axis, quarter: Quarter) {
-        let Some(sel) = self.editor.selection else {
-            self.ui
-                .set_status("No selection — drag with the Select tool first");
+    /// The box a rotate / flip acts on: the selection, or — with
+    /// nothing selected — the bounding box of the active layer's whole
+    /// model, so turning the model doesn't need a Select All first.
+    /// The flag is true for the selection. `None` after a status
+    /// message when there's nothing to transform or the layer is
+    /// locked.
+    fn transform_target(&mut self) -> Option<(Selection, bool)> {
+        if self.editor.history.is_locked() {
+            self.ui.set_status(format!(
+                "Layer \"{}\" is locked",
+                self.scene.active().name
+            ));
+            return None;
+        }
+        if let Some(sel) = self.editor.selection {
+            return Some((sel, true));
+        }
+        match self.scene.active_world().bounding_box() {
+            Some(bounds) => Some((Selection::from(bounds), false)),
+            None => {
+                self.ui.set_status("Nothing to transform — the layer is empty");
+                None
+            }
+        }
+    }
+
+    /// Build an operation's changes over the box `target`. When a
+    /// magic-wand mask covers exactly `target`, `build` sees only the
+    /// picked voxels (`SelectionMask::isolate`) and its changes are
+    /// rebased onto the active layer, so the operation touches just
+    /// those cells; otherwise it reads the layer directly. `T` carries
+    /// any extra result, like a rotated box.
+    fn box_changes<T>(
+        &self,
+        target: Selection,
+        build: impl FnOnce(&World) -> (T, Vec<VoxelChange>),
+    ) -> (T, Vec<VoxelChange>) {
+        let world = self.scene.active_world();
+        match self.editor.active_mask().filter(|m| m.bounds() == target) {
+            Some(mask) => {
+                let (extra, changes) = build(&mask.isolate(world));
+                (extra, SelectionMask::rebase(world, changes))
+            }
+            None => build(world),
+        }
+    }
+
+    /// Point the selection at `moved` after its voxels were moved,
+    /// sending an active wand mask's cells through `f` so the picked
+    /// voxels stay picked.
+    fn carry_selection(
+        &mut self,
+        moved: Selection,
+        f: impl Fn((i32, i32, i32)) -> (i32, i32, i32),
+    ) {
+        let mask = self.editor.active_mask().map(|m| m.mapped(f));
+        self.editor.selection = Some(moved);
+        self.editor.selection_mask = mask;
+    }
+
+    /// Rotate the selection's contents — or the active layer's whole
+    /// model when nothing is selected — around `axis` by `quarter`
+    /// (90° / -90° / 180°). The box may change footprint (Y-rotation
+    /// swaps W ↔ D, etc.) but its `min` corner stays put — see
+    /// `editor::transform` for the anchor convention. Result is one
+    /// `Command::set_voxels` so Ctrl+Z reverses the entire rotation.
+    pub(super) fn rotate_voxels(&mut self, axis: Axis, quarter: Quarter) {
+        let Some((target, is_selection)) = self.transform_target() else {
             return;
         };
-        let (new_sel, changes) =
-            rotate_selection_changes(&self.world, sel, axis, quarter);
+        let (rotated, changes) =
+            self.box_changes(target, |w| rotate_selection_changes(w, target, axis, quarter));
         let count = changes.len();
         if !changes.is_empty() {
-            let cmd = Command::set_voxels(changes);
-            self.editor.history.execute(cmd, &mut self.world);
+            let cmd = Command::set_voxels(changes).named("Rotate");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
         }
         // Bump the selection AABB even when empty so a user rotating
         // an air-only marquee still sees the box reorient.
-        self.editor.selection = Some(new_sel);
+        if is_selection {
+            self.carry_selection(rotated, |p| rotate_pos(target, axis, quarter, p));
+        }
         let label = match (axis, quarter) {
             (Axis::X, Quarter::Cw) => "Rotate X 90°",
             (Axis::X, Quarter::Ccw) => "Rotate X -90°",
@@ -579,39 +1062,298 @@ impl App {
             (Axis::Z, Quarter::Ccw) => "Rotate Z -90°",
             (Axis::Z, Quarter::Half) => "Rotate Z 180°",
         };
+        let scope = if is_selection { "selection" } else { "model" };
         if count == 0 {
-            self.ui.set_status(format!("{} (selection empty)", label));
+            self.ui.set_status(format!("{} ({} empty)", label, scope));
         } else {
-            self.ui.set_status(format!("{} ({} cells)", label, count));
+            self.ui
+                .set_status(format!("{} {} ({} cells)", label, scope, count));
         }
     }
 
-    /// Mirror the active selection's contents across the midplane
-    /// perpendicular to `axis`. The AABB is unchanged. Single
+    /// Mirror the selection's contents — or the active layer's whole
+    /// model when nothing is selected — across the midplane
+    /// perpendicular to `axis`. The box is unchanged. Single
     /// `Command::set_voxels` so one Ctrl+Z reverses the flip.
-    pub(super) fn mirror_selection(&mut self, axis: Axis) {
-        let Some(sel) = self.editor.selection else {
-            self.ui
-                .set_status("No selection — drag with the Select tool first");
+    pub(super) fn mirror_voxels(&mut self, axis: Axis) {
+        let Some((target, is_selection)) = self.transform_target() else {
             return;
         };
-        let changes = mirror_selection_changes(&self.world, sel, axis);
+        let ((), changes) =
+            self.box_changes(target, |w| ((), mirror_selection_changes(w, target, axis)));
         let count = changes.len();
         if !changes.is_empty() {
-            let cmd = Command::set_voxels(changes);
-            self.editor.history.execute(cmd, &mut self.world);
+            let cmd = Command::set_voxels(changes).named("Mirror");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
+        }
+        if is_selection {
+            self.carry_selection(target, |p| mirror_pos(target, axis, p));
         }
         let label = match axis {
             Axis::X => "Flip X",
             Axis::Y => "Flip Y",
             Axis::Z => "Flip Z",
         };
+        let scope = if is_selection { "selection" } else { "model" };
         if count == 0 {
-            self.ui
-                .set_status(format!("{} (no change — selection is symmetric)", label));
+            self.ui.set_status(format!(
+                "{} (no change — {} is symmetric)",
+                label, scope
+            ));
         } else {
-            self.ui.set_status(format!("{} ({} cells)", label, count));
+            self.ui
+                .set_status(format!("{} {} ({} cells)", label, scope, count));
+        }
+    }
+
+    /// Scale the selection's contents — or the active layer's whole
+    /// model when nothing is selected — by an integer factor (Edit ▸
+    /// Transform ▸ Scale). The box's `min` corner stays put and a
+    /// scaled selection becomes the new box. One `SetVoxels`, so one
+    /// Ctrl+Z undoes it.
+    pub(super) fn scale_voxels(&mut self, scale: Scale) {
+        let Some((target, is_selection)) = self.transform_target() else {
+            return;
+        };
+        let (scaled, changes) =
+            self.box_changes(target, |w| scale_selection_changes(w, target, scale));
+        if !changes.is_empty() {
+            let cmd = Command::set_voxels(changes).named("Scale");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
+        }
+        // Scaled cells don't map one-to-one, so a wand pick becomes a
+        // plain box selection here.
+        if is_selection {
+            self.editor.set_selection(Some(scaled));
+        }
+        let label = match scale {
+            Scale::Up(k) => format!("Scale {k}×"),
+            Scale::Down(k, _) => format!("Scale 1/{k}"),
+        };
+        let scope = if is_selection { "selection" } else { "model" };
+        let (w, h, d) = scaled.size();
+        self.ui
+            .set_status(format!("{label} {scope} (now {w} × {h} × {d})"));
+    }
+
+    /// Translate the selection's contents — or the active layer's
+    /// whole model when nothing is selected — by `delta` (Edit ▸
+    /// Transform ▸ Move…). One `SetVoxels`, so one Ctrl+Z undoes it;
+    /// a moved selection follows its voxels.
+    pub(super) fn move_voxels(&mut self, delta: (i32, i32, i32)) {
+        if delta == (0, 0, 0) {
+            return;
+        }
+        let Some((target, is_selection)) = self.transform_target() else {
+            return;
+        };
+        self.translate_box(target, delta);
+        let scope = if is_selection { "selection" } else { "model" };
+        self.ui.set_status(format!(
+            "Moved {} by ({}, {}, {})",
+            scope, delta.0, delta.1, delta.2
+        ));
+    }
+
+    /// Shift the active layer's whole model so `anchor` of its bounding
+    /// box (center, or bottom-face center) sits on the world origin —
+    /// one undoable move. The selection, if any, moves along so it stays
+    /// over the same voxels.
+    pub(super) fn center_on_origin(&mut self, anchor: OriginAnchor) {
+        if self.editor.history.is_locked() {
+            self.ui.set_status(format!(
+                "Layer \"{}\" is locked",
+                self.scene.active().name
+            ));
+            return;
+        }
+        let Some(bounds) = self.scene.active_world().bounding_box() else {
+            self.ui.set_status("Nothing to move — the layer is empty");
+            return;
+        };
+        let bounds = Selection::from(bounds);
+        let delta = origin_offset(bounds, anchor);
+        if delta == (0, 0, 0) {
+            self.ui.set_status("Model is already on the origin");
+            return;
+        }
+        self.translate_box(bounds, delta);
+        self.ui.set_status(format!(
+            "Moved model by ({}, {}, {}) onto the origin",
+            delta.0, delta.1, delta.2
+        ));
+    }
+
+    /// Move the voxels in `target` by `delta` as one command and carry
+    /// the selection along.
+    fn translate_box(&mut self, target: Selection, delta: (i32, i32, i32)) {
+        let ((), changes) =
+            self.box_changes(target, |w| ((), build_move_changes(w, target, delta)));
+        if !changes.is_empty() {
+            let cmd = Command::set_voxels(changes).named("Move");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
+        }
+        if let Some(sel) = self.editor.selection {
+            self.carry_selection(sel.translated(delta), |p| {
+                (p.0 + delta.0, p.1 + delta.1, p.2 + delta.2)
+            });
+        }
+    }
+
+    /// Fill the Crop dialog's box from the selection, or from the
+    /// active layer's model bounds when nothing is selected. An empty
+    /// layer leaves the box as it was.
+    pub(super) fn fit_crop_box(&mut self) {
+        let target = self
+            .editor
+            .selection
+            .or_else(|| self.scene.active_world().bounding_box().map(Selection::from));
+        if let Some(target) = target {
+            self.ui.crop.fit(target);
+        }
+    }
+
+    /// Clear every voxel of the active layer outside `keep` as one
+    /// undoable command. With `bound_layer` the layer is then bounded
+    /// to `keep` rounded out to whole chunks. Undoing the crop could
+    /// restore voxels the bounds now reject, so bounding also drops the
+    /// layer's undo history.
+    pub(super) fn crop_to_box(&mut self, keep: Selection, bound_layer: bool) {
+        if self.editor.history.is_locked() {
+            self.ui.set_status(format!(
+                "Layer \"{}\" is locked",
+                self.scene.active().name
+            ));
+            return;
+        }
+        let changes = build_crop_changes(self.scene.active_world(), keep);
+        let removed = changes.len();
+        if !changes.is_empty() {
+            let cmd = Command::set_voxels(changes).named("Crop");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
+        }
+        let mut status = format!("Cropped {removed} voxels outside the box");
+        if bound_layer {
+            let bounds = WorldBounds::enclosing(keep.min, keep.max);
+            let index = self.scene.active_index();
+            self.scene.set_layer_bounds(index, Some(bounds));
+            self.editor.history.clear();
+            self.unsaved_changes = true;
+            let (x, y, z) = bounds.size_voxels();
+            status.push_str(&format!("; layer bounded to {x} × {y} × {z}"));
+        }
+        self.ui.set_status(status);
+    }
+
+    /// Lift the active layer's bounds. Nothing is lost, so the undo
+    /// history stays.
+    pub(super) fn unbound_layer(&mut self) {
+        if self.scene.active_world().bounds().is_none() {
+            return;
+        }
+        let index = self.scene.active_index();
+        self.scene.set_layer_bounds(index, None);
+        self.unsaved_changes = true;
+        self.ui.set_status(format!(
+            "Layer \"{}\" is unbounded",
+            self.scene.active().name
+        ));
+    }
+
+    /// Take the Replace Color dialog's color — and its Contiguous seed
+    /// — from the hovered voxel of the active layer. Called instead of
+    /// the tool while the dialog's Pick toggle is on.
+    pub(super) fn pick_recolor_source(&mut self) {
+        self.ui.recolor.picking = false;
+        let Some(hit) = self.editor.hovered_voxel else {
+            return;
+        };
+        let (x, y, z) = hit.voxel_pos;
+        let voxel = self.scene.active_world().get_voxel(x, y, z);
+        if voxel.is_air() {
+            self.ui.set_status("No voxel there on the active layer");
+            return;
+        }
+        self.ui.recolor.from = voxel.color();
+        self.ui.recolor.seed = Some(hit.voxel_pos);
+        let [r, g, b, _] = voxel.color();
+        self.ui
+            .set_status(format!("Replace: picked #{r:02X}{g:02X}{b:02X}"));
+    }
+
+    /// Recolor every voxel colored `from` in `scope` of the active
+    /// layer with the brush voxel, as one `SetVoxels`.
+    pub(super) fn replace_color(&mut self, from: [u8; 4], scope: RecolorScope) {
+        if self.editor.history.is_locked() {
+            self.ui.set_status(format!(
+                "Layer \"{}\" is locked",
+                self.scene.active().name
+            ));
+            return;
+        }
+        let brush = self.editor.brush_color;
+        let ((), changes) = match scope {
+            RecolorScope::Region(sel) => {
+                self.box_changes(sel, |w| ((), build_replace_changes(w, scope, from, brush)))
+            }
+            _ => ((), build_replace_changes(self.scene.active_world(), scope, from, brush)),
+        };
+        let count = changes.len();
+        if !changes.is_empty() {
+            let cmd = Command::set_voxels(changes).named("Replace Color");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
         }
+        self.ui.set_status(format!("Replaced {count} voxels"));
+    }
+
+    /// Shift hue / saturation / brightness of the selection's voxels —
+    /// or the active layer's whole model when nothing is selected — as
+    /// one `SetVoxels`.
+    pub(super) fn adjust_colors(&mut self, adjust: ColorAdjust) {
+        if self.editor.history.is_locked() {
+            self.ui.set_status(format!(
+                "Layer \"{}\" is locked",
+                self.scene.active().name
+            ));
+            return;
+        }
+        let region = self.editor.selection;
+        let ((), changes) = match region {
+            Some(sel) => self.box_changes(sel, |w| ((), build_adjust_changes(w, region, adjust))),
+            None => ((), build_adjust_changes(self.scene.active_world(), None, adjust)),
+        };
+        let count = changes.len();
+        if !changes.is_empty() {
+            let cmd = Command::set_voxels(changes).named("Adjust Colors");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
+        }
+        self.ui.set_status(format!("Adjusted {count} voxels"));
+    }
+
+    /// Blur colors of the selection's voxels — or the active layer's
+    /// whole model when nothing is selected — over `iterations`
+    /// neighbor-averaging passes, as one `SetVoxels`.
+    pub(super) fn smooth_colors(&mut self, iterations: u32) {
+        if self.editor.history.is_locked() {
+            self.ui.set_status(format!(
+                "Layer \"{}\" is locked",
+                self.scene.active().name
+            ));
+            return;
+        }
+        let region = self.editor.selection;
+        let ((), changes) = match region {
+            Some(sel) => {
+                self.box_changes(sel, |w| ((), build_smooth_changes(w, region, iterations)))
+            }
+            None => ((), build_smooth_changes(self.scene.active_world(), None, iterations)),
+        };
+        let count = changes.len();
+        if !changes.is_empty() {
+            let cmd = Command::set_voxels(changes).named("Smooth Colors");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
+        }
+        self.ui.set_status(format!("Smoothed {count} voxels"));
     }
 
     /// Step the selection by `delta` in response to an arrow-key
@@ -627,6 +1369,20 @@ impl App {
         self.move_selection(delta);
     }
 
+    /// Move the work plane `delta` slices along its axis. No-op while
+    /// work-plane mode is off, or mid shape drag (whose locked plane
+    /// would no longer lie on the slice).
+    fn step_work_plane(&mut self, delta: i32) {
+        if !self.editor.work_plane_enabled || self.shape_drag.is_some() {
+            return;
+        }
+        self.editor.work_plane = self.editor.work_plane.stepped(delta);
+        let plane = self.editor.work_plane;
+        self.ui.set_status(work_plane_label(&plane));
+        // The cursor hasn't moved, but what it hovers has.
+        self.update_raycast();
+    }
+
     /// Commit the in-progress shape drag. Called on the second
     /// click (after the user has dragged a footprint, released, and
     /// then optionally moved the cursor vertically to set height).
@@ -662,6 +1418,7 @@ impl App {
 
         let raw = match tool {
             Tool::Line => line_voxels(anchor, end),
+            Tool::Box if self.editor.box_hollow => hollow_box_voxels(anchor, end),
             Tool::Box => box_voxels(anchor, end),
             Tool::Sphere => sphere_voxels(anchor, end),
             Tool::Cylinder => cylinder_voxels(anchor, end),
@@ -689,15 +1446,19 @@ impl App {
             .into_iter()
             .map(|pos| VoxelChange {
                 pos,
-                old_voxel: self.world.get_voxel(pos.0, pos.1, pos.2),
+                old_voxel: self.scene.active_world().get_voxel(pos.0, pos.1, pos.2),
                 new_voxel: color,
             })
             .filter(|c| c.old_voxel != c.new_voxel)
             .collect();
+        let changes = match self.editor.active_work_plane() {
+            Some(plane) => plane.clip(changes),
+            None => changes,
+        };
 
         if !changes.is_empty() {
-            let cmd = Command::set_voxels(changes);
-            self.editor.history.execute(cmd, &mut self.world);
+            let cmd = Command::set_voxels(changes).named(self.editor.current_tool.name());
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
         }
     }
 
@@ -708,7 +1469,7 @@ impl App {
             self.ui.set_status("No selection — drag with the Select tool first");
             return;
         };
-        let clipboard = copy_selection_to_clipboard(&self.world, sel);
+        let clipboard = self.copy_box(sel);
         let count = clipboard.voxel_count();
         self.clipboard = Some(clipboard);
         if count == 0 {
@@ -718,6 +1479,16 @@ impl App {
         }
     }
 
+    /// The selection's voxels as a clipboard — just the picked cells
+    /// when a wand mask is active.
+    fn copy_box(&self, sel: Selection) -> Clipboard {
+        let world = self.scene.active_world();
+        match self.editor.active_mask() {
+            Some(mask) => copy_selection_to_clipboard(&mask.isolate(world), sel),
+            None => copy_selection_to_clipboard(world, sel),
+        }
+    }
+
     /// Cut: snapshot the selection into the clipboard, then clear
     /// every non-air cell inside the selection in a **single**
     /// `Command::set_voxels`. Critical that it's one Command — if we
@@ -728,14 +1499,14 @@ impl App {
             self.ui.set_status("No selection — drag with the Select tool first");
             return;
         };
-        let clipboard = copy_selection_to_clipboard(&self.world, sel);
+        let clipboard = self.copy_box(sel);
         let count = clipboard.voxel_count();
         self.clipboard = Some(clipboard);
 
-        let changes = build_clear_changes(&self.world, sel);
+        let ((), changes) = self.box_changes(sel, |w| ((), build_clear_changes(w, sel)));
         if !changes.is_empty() {
-            let cmd = Command::set_voxels(changes);
-            self.editor.history.execute(cmd, &mut self.world);
+            let cmd = Command::set_voxels(changes).named("Cut");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
         }
 
         if count == 0 {
@@ -752,11 +1523,11 @@ impl App {
             self.ui.set_status("No selection — drag with the Select tool first");
             return;
         };
-        let changes = build_clear_changes(&self.world, sel);
+        let ((), changes) = self.box_changes(sel, |w| ((), build_clear_changes(w, sel)));
         let count = changes.len();
         if !changes.is_empty() {
-            let cmd = Command::set_voxels(changes);
-            self.editor.history.execute(cmd, &mut self.world);
+            let cmd = Command::set_voxels(changes).named("Delete");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
         }
         if count == 0 {
             self.ui.set_status("Selection had no solid voxels to delete");
@@ -801,20 +1572,20 @@ impl App {
             return;
         };
 
-        let changes = build_paste_changes(&self.world, clipboard, dest);
+        let changes = build_paste_changes(self.scene.active_world(), clipboard, dest);
         let count = changes.len();
         if !changes.is_empty() {
-            let cmd = Command::set_voxels(changes);
-            self.editor.history.execute(cmd, &mut self.world);
+            let cmd = Command::set_voxels(changes).named("Paste");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
         }
 
         // Auto-select the destination AABB so the user can chain
         // Paste→drag→Paste without re-marqueeing.
         let (sw, sh, sd) = clipboard.size;
-        self.editor.selection = Some(Selection {
+        self.editor.set_selection(Some(Selection {
             min: dest,
             max: (dest.0 + sw - 1, dest.1 + sh - 1, dest.2 + sd - 1),
-        });
+        }));
 
         if count == 0 {
             self.ui.set_status("Pasted (no changes — destination already matched)");
@@ -823,13 +1594,109 @@ impl App {
         }
     }
 
+    /// Where the paste placement's `min` corner is: its pin, moved by
+    /// any gizmo drag in progress, else the hovered cell.
+    pub(super) fn paste_anchor(&self) -> Option<(i32, i32, i32)> {
+        match self.paste_pin {
+            Some(pin) => {
+                let (dx, dy, dz) = self.gizmo_drag.map_or((0, 0, 0), |drag| drag.delta);
+                Some((pin.0 + dx, pin.1 + dy, pin.2 + dz))
+            }
+            None => self.editor.hovered_voxel.map(|hit| Self::select_anchor_pos(&hit)),
+        }
+    }
+
+    /// Pin the paste placement's ghost where it is so the move gizmo
+    /// can nudge it, or commit it if it's already pinned (Enter).
+    pub(super) fn pin_paste_placement(&mut self) {
+        if self.paste_pin.is_some() {
+            self.commit_paste_placement();
+            return;
+        }
+        let Some(anchor) = self.paste_anchor() else {
+            self.ui.set_status("Move the cursor over the world to pin the paste");
+            return;
+        };
+        self.paste_pin = Some(anchor);
+        self.invalidate_selection_visualization();
+        self.ui.set_status("Paste pinned — drag the gizmo to nudge · click or Enter commits");
+    }
+
+    /// Enter paste placement: the clipboard follows the cursor as a
+    /// ghost until a left click commits it (see `paste_placement`).
+    pub(super) fn begin_paste_placement(&mut self) {
+        let Some(clipboard) = self.clipboard.as_ref().filter(|c| !c.is_empty()) else {
+            self.ui.set_status("Clipboard is empty — Copy / Cut a selection first");
+            return;
+        };
+        let placement = clipboard.clone();
+        self.move_ghost_voxels = placement.voxels.clone();
+        self.paste_placement = Some(placement);
+        self.paste_pin = None;
+        self.invalidate_selection_visualization();
+        self.ui.set_status(
+            "Placing paste — click to commit · Enter pins · R / Shift+R rotate · M mirror · \
+             Esc cancels",
+        );
+    }
+
+    /// Rotate or mirror the pending paste. No-op outside placement.
+    pub(super) fn transform_paste_placement(
+        &mut self,
+        f: impl FnOnce(&Clipboard) -> Clipboard,
+    ) {
+        let Some(placement) = &self.paste_placement else {
+            return;
+        };
+        let next = f(placement);
+        self.move_ghost_voxels = next.voxels.clone();
+        self.paste_placement = Some(next);
+        self.invalidate_selection_visualization();
+    }
+
+    /// Leave paste placement without writing anything.
+    pub(super) fn cancel_paste_placement(&mut self) {
+        if self.paste_placement.take().is_some() {
+            self.paste_pin = None;
+            self.move_ghost_voxels.clear();
+            self.ui.set_status("Paste canceled");
+        }
+    }
+
+    /// Commit the pending paste at its pin or the hovered anchor as one
+    /// undoable `SetVoxels`, select the destination, and leave
+    /// placement.
+    pub(super) fn commit_paste_placement(&mut self) {
+        let Some(dest) = self.paste_anchor() else {
+            self.ui.set_status("Move the cursor over the world to paste");
+            return;
+        };
+        let Some(placement) = self.paste_placement.take() else {
+            return;
+        };
+        self.paste_pin = None;
+        self.move_ghost_voxels.clear();
+        let changes = build_paste_changes(self.scene.active_world(), &placement, dest);
+        let count = changes.len();
+        if !changes.is_empty() {
+            let cmd = Command::set_voxels(changes).named("Paste");
+            self.editor.history.execute(cmd, self.scene.active_world_mut());
+        }
+        let (sw, sh, sd) = placement.size;
+        self.editor.set_selection(Some(Selection {
+            min: dest,
+            max: (dest.0 + sw - 1, dest.1 + sh - 1, dest.2 + sd - 1),
+        }));
+        self.ui.set_status(format!("Pasted {} voxels", count));
+    }
+
     /// Set the selection to the AABB of every non-air voxel in the
     /// world. Walks loaded chunks, skipping empty ones via
     /// `Chunk::is_empty`. Surfaces "world is empty" if there's
     /// nothing to select.
     pub(super) fn select_all_solid(&mut self) {
-        let mut bounds: Option<((i32, i32, i32), (i32, i32, i32))> = None;
-        for (chunk_pos, chunk) in self.world.chunks() {
+        let mut bounds: Option<CellBox> = None;
+        for (chunk_pos, chunk) in self.scene.active_world().chunks() {
             let chunk = chunk.read();
             if chunk.is_empty() {
                 continue;
@@ -852,107 +1719,182 @@ impl App {
         }
         match bounds {
             Some((min, max)) => {
-                self.editor.selection = Some(Selection { min, max });
+                self.editor.set_selection(Some(Selection { min, max }));
                 let (w, h, d) = (max.0 - min.0 + 1, max.1 - min.1 + 1, max.2 - min.2 + 1);
                 self.ui.set_status(format!("Selected all: {}×{}×{}", w, h, d));
             }
             None => {
-                self.editor.selection = None;
+                self.editor.set_selection(None);
                 self.ui.set_status("World is empty — nothing to select");
             }
         }
     }
 
     /// Handle keyboard shortcuts (tools, undo/redo, file ops,
-    /// selection).
+    /// selection). Shortcuts listed in the command registry
+    /// (`ui::registry`) run the same `UiAction` as its menu entry and
+    /// palette row; the rest (placement keys, arrow nudges, …)
+    /// depend on the gesture in progress and act directly.
     pub(super) fn handle_tool_shortcut(&mut self, key: KeyCode) {
+        // Ctrl+1..9 recalls a camera bookmark, Ctrl+Shift+1..9 saves
+        // one. Checked before the bare digits, which pick tools.
+        if self.modifiers.control_key() {
+            if let Some(slot) = bookmark_slot(key) {
+                if self.modifiers.shift_key() {
+                    self.save_camera_bookmark(slot);
+                } else {
+                    self.recall_camera_bookmark(slot);
+                }
+                return;
+            }
+        }
+        let digit_tool = match key {
+            KeyCode::Digit1 => Some(Tool::Place),
+            KeyCode::Digit2 => Some(Tool::Remove),
+            KeyCode::Digit3 => Some(Tool::Paint),
+            KeyCode::Digit4 => Some(Tool::Eyedropper),
+            KeyCode::Digit5 => Some(Tool::Fill),
+            KeyCode::Digit6 => Some(Tool::Line),
+            KeyCode::Digit7 => Some(Tool::Box),
+            KeyCode::Digit8 => Some(Tool::Sphere),
+            KeyCode::Digit9 => Some(Tool::Cylinder),
+            KeyCode::Digit0 => Some(Tool::Select),
+            _ => None,
+        };
+        if let Some(tool) = digit_tool {
+            self.handle_ui_action(UiAction::SelectTool(tool));
+            return;
+        }
         match key {
-            KeyCode::Digit1 => self.editor.current_tool = Tool::Place,
-            KeyCode::Digit2 => self.editor.current_tool = Tool::Remove,
-            KeyCode::Digit3 => self.editor.current_tool = Tool::Paint,
-            KeyCode::Digit4 => self.editor.current_tool = Tool::Eyedropper,
-            KeyCode::Digit5 => self.editor.current_tool = Tool::Fill,
-            KeyCode::Digit6 => self.editor.current_tool = Tool::Line,
-            KeyCode::Digit7 => self.editor.current_tool = Tool::Box,
-            KeyCode::Digit8 => self.editor.current_tool = Tool::Sphere,
-            KeyCode::Digit9 => self.editor.current_tool = Tool::Cylinder,
-            KeyCode::Digit0 => self.editor.current_tool = Tool::Select,
             KeyCode::KeyZ if self.modifiers.control_key() => {
                 if self.modifiers.shift_key() {
-                    self.editor.redo(&mut self.world);
+                    self.handle_ui_action(UiAction::Redo);
                 } else {
-                    self.editor.undo(&mut self.world);
+                    self.handle_ui_action(UiAction::Undo);
                 }
             }
             KeyCode::KeyY if self.modifiers.control_key() => {
-                self.editor.redo(&mut self.world);
+                self.handle_ui_action(UiAction::Redo);
             }
             KeyCode::KeyS if self.modifiers.control_key() => {
                 if self.modifiers.shift_key() {
-                    self.save_project_as();
+                    self.handle_ui_action(UiAction::SaveAs);
                 } else {
-                    self.save_project();
+                    self.handle_ui_action(UiAction::SaveProject);
                 }
             }
             KeyCode::KeyO if self.modifiers.control_key() => {
-                self.open_project();
+                self.handle_ui_action(UiAction::OpenProject);
             }
             KeyCode::KeyN if self.modifiers.control_key() => {
-                self.new_project();
+                self.handle_ui_action(UiAction::NewProject);
             }
             // Esc deselects (Photoshop / image-editor convention).
             // Ctrl+D matches the same convention for users coming
             // from PS / vengi (`Ctrl+D` = select none). Both also
             // abort an in-progress Select drag so the user can bail
             // mid-gesture without committing a stray AABB.
+            KeyCode::Escape if self.gizmo_drag.is_some() => {
+                self.cancel_gizmo_drag();
+            }
+            KeyCode::Escape if self.paste_placement.is_some() => {
+                self.cancel_paste_placement();
+            }
+            KeyCode::Enter | KeyCode::NumpadEnter if self.paste_placement.is_some() => {
+                self.pin_paste_placement();
+            }
             KeyCode::Escape => {
                 self.selection_drag_anchor = None;
-                self.editor.selection = None;
+                self.editor.set_selection(None);
+                self.editor.measurement = None;
                 if self.shape_drag.is_some() {
                     self.shape_drag = None;
                     self.ui.set_status("Shape canceled");
                 }
             }
             KeyCode::KeyD if self.modifiers.control_key() => {
-                self.selection_drag_anchor = None;
-                self.editor.selection = None;
+                self.handle_ui_action(UiAction::Deselect);
             }
             // Selection clipboard ops. Ctrl+Shift+V forces "paste
             // at cursor" (vengi-style two-channel paste); plain
             // Ctrl+V uses the selection's origin if one exists.
             KeyCode::KeyC if self.modifiers.control_key() => {
-                self.copy_selection();
+                self.handle_ui_action(UiAction::CopySelection);
             }
             KeyCode::KeyX if self.modifiers.control_key() => {
-                self.cut_selection();
+                self.handle_ui_action(UiAction::CutSelection);
+            }
+            KeyCode::KeyV if self.modifiers.control_key() && self.modifiers.alt_key() => {
+                self.handle_ui_action(UiAction::BeginPastePlacement);
             }
             KeyCode::KeyV if self.modifiers.control_key() => {
                 let prefer_cursor = self.modifiers.shift_key();
                 self.paste_clipboard(prefer_cursor);
             }
             KeyCode::Delete => {
-                self.delete_selection();
+                self.handle_ui_action(UiAction::DeleteSelection);
             }
             // Ctrl+A = select-all-solid: AABB of every non-air
             // voxel in the world. Standard image-editor convention.
             KeyCode::KeyA if self.modifiers.control_key() => {
-                self.select_all_solid();
-            }
-            // Rotate / mirror the active selection (no-op with a status
-            // hint if there's none). R spins around Y — the common
-            // "turn it around" — and Shift+R reverses; M flips
-            // left-right across X. The full axis × angle set lives in
-            // the Selection menu. Guarded against Ctrl so a stray
-            // Ctrl+R / Ctrl+M can't silently transform geometry.
+                self.handle_ui_action(UiAction::SelectAllSolid);
+            }
+            // Rotate / mirror the active selection, or the whole model
+            // of the active layer when nothing is selected. R spins
+            // around Y — the common "turn it around" — and Shift+R
+            // reverses; M flips left-right across X. Alt+X / Y / Z
+            // rotate 90° around that axis (Shift reverses) and
+            // Shift+X / Y / Z flip along it. The 180° turns live in
+            // Edit ▸ Transform. Guarded against Ctrl so a stray
+            // Ctrl+R / Ctrl+M / Ctrl+Z can't silently transform
+            // geometry. While placing a paste, the same keys orient
+            // the pending blob instead.
+            KeyCode::KeyR if !self.modifiers.control_key() && self.paste_placement.is_some() => {
+                let quarter = if self.modifiers.shift_key() {
+                    Quarter::Ccw
+                } else {
+                    Quarter::Cw
+                };
+                self.transform_paste_placement(|c| c.rotated(Axis::Y, quarter));
+            }
+            KeyCode::KeyM if !self.modifiers.control_key() && self.paste_placement.is_some() => {
+                self.transform_paste_placement(|c| c.mirrored(Axis::X));
+            }
             KeyCode::KeyR if !self.modifiers.control_key() => {
-                if self.modifiers.shift_key() {
-                    self.rotate_selection(Axis::Y, Quarter::Ccw);
+                let quarter = if self.modifiers.shift_key() {
+                    Quarter::Ccw
                 } else {
-                    self.rotate_selection(Axis::Y, Quarter::Cw);
-                }
+                    Quarter::Cw
+                };
+                self.handle_ui_action(UiAction::Rotate {
+                    axis: Axis::Y,
+                    quarter,
+                });
             }
             KeyCode::KeyM if !self.modifiers.control_key() => {
-                self.mirror_selection(Axis::X);
+                self.handle_ui_action(UiAction::Mirror { axis: Axis::X });
+            }
+            KeyCode::KeyX | KeyCode::KeyY | KeyCode::KeyZ
+                if !self.modifiers.control_key()
+                    && (self.modifiers.alt_key() || self.modifiers.shift_key()) =>
+            {
+                let axis = match key {
+                    KeyCode::KeyX => Axis::X,
+                    KeyCode::KeyY => Axis::Y,
+                    _ => Axis::Z,
+                };
+                let quarter = if self.modifiers.shift_key() {
+                    Quarter::Ccw
+                } else {
+                    Quarter::Cw
+                };
+                let rotate = self.modifiers.alt_key();
+                match (rotate, self.paste_placement.is_some()) {
+                    (true, true) => self.transform_paste_placement(|c| c.rotated(axis, quarter)),
+                    (true, false) => self.handle_ui_action(UiAction::Rotate { axis, quarter }),
+                    (false, true) => self.transform_paste_placement(|c| c.mirrored(axis)),
+                    (false, false) => self.handle_ui_action(UiAction::Mirror { axis }),
+                }
             }
             // Arrow-key selection nudge. ←→ = X axis, ↑↓ = Z axis
             // (matches "screen up = away from camera" for the
@@ -995,12 +1937,54 @@ impl App {
             // Recovery hatch for WASD-flying / panning off the model.
             KeyCode::KeyF => {
                 if self.editor.selection.is_some() {
-                    self.frame_selected();
+                    self.handle_ui_action(UiAction::FrameSelected);
                 } else {
-                    self.frame_all();
+                    self.handle_ui_action(UiAction::FrameAll);
                 }
             }
+            // Numpad view presets, Blender layout: 1 / 3 / 7 look
+            // from the front / right / top, Ctrl flips to the opposite
+            // side, 5 is isometric.
+            KeyCode::Numpad1 | KeyCode::Numpad3 | KeyCode::Numpad7 => {
+                let flip = self.modifiers.control_key();
+                let view = match (key, flip) {
+                    (KeyCode::Numpad1, false) => CameraView::Front,
+                    (KeyCode::Numpad1, true) => CameraView::Back,
+                    (KeyCode::Numpad3, false) => CameraView::Right,
+                    (KeyCode::Numpad3, true) => CameraView::Left,
+                    (_, false) => CameraView::Top,
+                    (_, true) => CameraView::Bottom,
+                };
+                self.handle_ui_action(UiAction::SetCameraView(view));
+            }
+            KeyCode::Numpad5 => {
+                self.handle_ui_action(UiAction::SetCameraView(CameraView::Isometric));
+            }
+            // PageUp / PageDown walk the work plane through its slices
+            // (Shift: 10 at a time), like flipping layers in a pixel-
+            // art editor.
+            KeyCode::PageUp | KeyCode::PageDown => {
+                let step = if self.modifiers.shift_key() { 10 } else { 1 };
+                let sign = if key == KeyCode::PageUp { 1 } else { -1 };
+                self.step_work_plane(sign * step);
+            }
             _ => {}
         }
     }
 }
+
+/// Bookmark slot (0-based) for digit keys 1..9.
+fn bookmark_slot(key: KeyCode) -> Option<usize> {
+    const DIGITS: [KeyCode; 9] = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    DIGITS.iter().position(|&d| d == key)
+}
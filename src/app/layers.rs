@@ -0,0 +1,220 @@
+//! Scene layers: switching the edit target and applying the Layers
+//! panel's actions.
+//!
+//! Each layer keeps its own undo history. `editor.history` always
+//! belongs to the active layer (every tool writes through it), and
+//! the others wait in `App::layer_histories` until they are selected
+//! again — so Ctrl+Z never rewinds a layer the user can't see is
+//! being edited. A locked layer is enforced by locking its history.
+//! Histories are per frame as well; see `animation.rs`.
+
+use voxelith::core::{Background, MaterialTable, Scene, WorldBounds};
+use voxelith::editor::{CommandHistory, UNDO_DEPTH};
+use voxelith::render::CameraPath;
+use voxelith::ui::UiAction;
+
+use super::App;
+
+impl App {
+    /// Replace the whole document (new / open / import / clear). Every
+    /// stored history belongs to the old layers, so all of them go.
+    pub(super) fn set_scene(&mut self, scene: Scene) {
+        // A running or staged generation targets a layer of the outgoing
+        // scene; layer ids restart per scene, so it can't be matched up
+        // later.
+        if let Some(active) = self.gen_job.take() {
+            active.job.cancel();
+        }
+        self.discard_staged_generation();
+        // A turntable frames the outgoing model; stop rather than spin
+        // the rest of the turn around a different scene.
+        self.cancel_turntable();
+        self.cancel_flythrough();
+        self.camera_path_preview = None;
+        // A held large fill was measured against the outgoing scene.
+        self.pending_fill = None;
+        self.ui.state.show_large_fill_prompt = false;
+        self.pending_world_bounds = None;
+        self.ui.state.world_bounds_prompt = None;
+        // Seeds describe how the outgoing scene was generated; an
+        // opened project restores its own right after this.
+        self.procgen_seeds.clear();
+        self.camera_bookmarks = Default::default();
+        self.camera_path = CameraPath::default();
+        self.vox_chunks.clear();
+        self.restore_materials(MaterialTable::new());
+        self.restore_background(Background::default());
+        self.restore_lights(Vec::new());
+        self.restore_references(Vec::new());
+        self.restore_reference_model(None);
+        self.chunk_lods.clear();
+        self.model_info_stale = true;
+        self.slice_view_stale = true;
+        self.scene = scene;
+        self.layer_histories.clear();
+        self.frame_histories.clear();
+        self.playback = None;
+        self.onion_skin_stale = true;
+        self.pose_preview_stale = true;
+        self.editor.history.clear();
+        self.sync_history_lock();
+    }
+
+    /// Bound every layer in every animation frame to `bounds`, or
+    /// unbind them all with `None` (File ▸ Project Settings…). Undoing
+    /// could restore voxels the bounds now reject, so bounding drops
+    /// every undo history, as Crop's "bound the layer" does for one.
+    /// Bounds that would delete voxels are held for the world-bounds
+    /// prompt instead.
+    pub(super) fn set_world_bounds(&mut self, bounds: Option<WorldBounds>) {
+        let unchanged = match bounds {
+            Some(_) => self.scene.world_bounds() == bounds,
+            None => self.scene.layers().iter().all(|l| l.world.bounds().is_none()),
+        };
+        if unchanged {
+            return;
+        }
+        if let Some(held) = bounds {
+            let doomed = self.scene.voxels_outside(&held);
+            if doomed > 0 {
+                self.pending_world_bounds = Some(held);
+                self.ui.state.world_bounds_prompt = Some(doomed);
+                return;
+            }
+        }
+        self.apply_world_bounds(bounds);
+    }
+
+    /// World-bounds prompt's Delete: apply the held bounds, whatever
+    /// now lies outside them.
+    pub(super) fn confirm_world_bounds(&mut self) {
+        if let Some(bounds) = self.pending_world_bounds.take() {
+            self.apply_world_bounds(Some(bounds));
+        }
+    }
+
+    fn apply_world_bounds(&mut self, bounds: Option<WorldBounds>) {
+        self.scene.set_world_bounds(bounds);
+        if bounds.is_some() {
+            self.editor.history.clear();
+            self.layer_histories.clear();
+            self.frame_histories.clear();
+        }
+        self.unsaved_changes = true;
+        self.model_info_stale = true;
+        match bounds {
+            Some(bounds) => {
+                let (x, y, z) = bounds.size_voxels();
+                self.ui.set_status(format!("World bounded to {x} × {y} × {z}"));
+            }
+            None => self.ui.set_status("World is unbounded"),
+        }
+    }
+
+    /// Make layer `index` the edit target, parking the current
+    /// history and restoring the target's.
+    fn activate_layer(&mut self, index: usize) {
+        if index >= self.scene.len() || index == self.scene.active_index() {
+            return;
+        }
+        self.editor.history.end_stroke();
+        let incoming = self.scene.layers()[index].id;
+        let restored = self
+            .layer_histories
+            .remove(&incoming)
+            .unwrap_or_else(|| CommandHistory::new(UNDO_DEPTH));
+        let parked = std::mem::replace(&mut self.editor.history, restored);
+        self.layer_histories.insert(self.scene.active().id, parked);
+        self.scene.set_active(index);
+        self.sync_history_lock();
+    }
+
+    /// Mirror the active layer's lock onto `editor.history`.
+    pub(super) fn sync_history_lock(&mut self) {
+        let locked = self.scene.active().locked;
+        self.editor.history.set_locked(locked);
+    }
+
+    /// Apply one of the Layers panel's actions; other actions are
+    /// ignored (`handle_ui_actions` only routes layer actions here).
+    pub(super) fn handle_layer_action(&mut self, action: UiAction) {
+        match action {
+            UiAction::AddLayer => {
+                // The new layer becomes active; park the current history
+                // the same way `activate_layer` does.
+                self.editor.history.end_stroke();
+                let parked = std::mem::replace(
+                    &mut self.editor.history,
+                    CommandHistory::new(UNDO_DEPTH),
+                );
+                self.layer_histories.insert(self.scene.active().id, parked);
+                let name = self.scene.next_layer_name();
+                self.scene.add_layer(name);
+                self.sync_history_lock();
+                self.unsaved_changes = true;
+            }
+            UiAction::SelectLayer(index) => self.activate_layer(index),
+            UiAction::RenameLayer(index, name) => {
+                self.scene.rename_layer(index, name);
+                self.unsaved_changes = true;
+            }
+            UiAction::SetLayerVisible(index, visible) => {
+                self.scene.set_visible(index, visible);
+                self.unsaved_changes = true;
+            }
+            UiAction::SetLayerLocked(index, locked) => {
+                self.scene.set_locked(index, locked);
+                self.sync_history_lock();
+                self.unsaved_changes = true;
+            }
+            UiAction::MoveLayer { from, to } => {
+                self.scene.move_layer(from, to);
+                self.unsaved_changes = true;
+            }
+            UiAction::MergeLayerDown(index) => {
+                if index == 0 || index >= self.scene.len() {
+                    return;
+                }
+                // The merged pair's undo steps would replay against a
+                // world that no longer matches them; start clean.
+                if index == self.scene.active_index() {
+                    self.activate_layer(index - 1);
+                }
+                let upper = self.scene.layers()[index].id;
+                let lower = self.scene.layers()[index - 1].id;
+                self.scene.merge_down(index);
+                // Every frame was merged, so their stored steps go too.
+                for histories in std::iter::once(&mut self.layer_histories)
+                    .chain(self.frame_histories.values_mut())
+                {
+                    histories.remove(&upper);
+                    histories.remove(&lower);
+                }
+                if self.scene.active().id == lower {
+                    self.editor.history.clear();
+                }
+                self.sync_history_lock();
+                self.unsaved_changes = true;
+            }
+            UiAction::RemoveLayer(index) => {
+                if self.scene.len() <= 1 || index >= self.scene.len() {
+                    return;
+                }
+                if index == self.scene.active_index() {
+                    let fallback = if index > 0 { index - 1 } else { 1 };
+                    self.activate_layer(fallback);
+                }
+                if let Some(layer) = self.scene.remove_layer(index) {
+                    self.layer_histories.remove(&layer.id);
+                    for histories in self.frame_histories.values_mut() {
+                        histories.remove(&layer.id);
+                    }
+                    self.ui.set_status(format!("Deleted layer \"{}\"", layer.name));
+                }
+                self.sync_history_lock();
+                self.unsaved_changes = true;
+            }
+            _ => {}
+        }
+    }
+}
@@ -8,50 +8,76 @@
 //! - `file_ops` — new/save/open/import/export
 //! - `shapes`   — built-in sphere/pyramid generators
 //! - `input`    — raycast, tool apply, keyboard shortcuts
+//! - `layers`   — scene layer switching and the Layers panel actions
 //! - `ui_actions` — drains `UiAction`s queued by the egui layer
 //! - `render`   — per-frame wgpu pass
 //! - `handler`  — winit `ApplicationHandler`
 
 mod ai_actions;
+mod animation;
+mod camera_path;
 mod file_ops;
+mod gen_job;
+mod gizmo;
 mod handler;
 mod hud;
 mod input;
+mod layers;
 mod preview;
 mod render;
+mod rig;
 mod shapes;
+mod slice_view;
+mod tasks;
+mod turntable;
 mod ui_actions;
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use rayon::prelude::*;
 use winit::{keyboard::ModifiersState, window::Window};
 
 use std::collections::HashSet;
 
 use voxelith::{
     ai::{AiJobState, AiProvider, AiRuntime, FalHunyuanProvider, JobEvent, JobHandle},
-    core::{Voxel, World},
+    core::{
+        Background, CellBox, ChunkPos, FrameId, LayerId, Light, LightKind, MaterialTable,
+        ReferenceImage, ReferenceModel, Scene, Voxel, WorldBounds, CHUNK_SIZE,
+    },
     editor::{
-        box_voxels, cylinder_voxels, line_voxels, sphere_voxels, BrushTool, Clipboard, Editor,
-        EditorTool, RaycastHit, Selection, SymmetryAxes, Tool,
+        box_voxels, cylinder_voxels, hollow_box_voxels, line_voxels, sphere_voxels, BrushShape, BrushTool,
+        build_extrude_changes, face_region, Clipboard, CommandHistory, Editor, EditorTool,
+        ExtrudeDirection, Gizmo, GizmoHandle, RaycastHit, Selection, SelectionStats, SymmetryAxes, Tool, WorkPlane,
+        MAX_EXTRUDE_CELLS, MAX_EXTRUDE_DEPTH,
     },
-    mesh::{patch_to_mesh, GreedyMesher, Mesher},
-    prefs::{EditorPrefs, PanelVisibility, Prefs, WindowPrefs},
-    render::Renderer,
-    ui::{RenderStats, Ui},
+    mesh::{patch_to_mesh, ChunkMesh, LodMesher, MeshSettings, MeshWorker},
+    prefs::{EditorPrefs, LastDirs, PanelVisibility, Prefs, WindowPrefs},
+    render::{hover_inset, PickSample, Renderer},
+    ui::{CustomTheme, FrameSummary, LayerSummary, ModelInfo, RenderStats, Theme, Ui},
 };
 
+use gen_job::{ActiveGenJob, StagedGeneration};
+use gizmo::GizmoDrag;
+use file_ops::PendingSave;
+pub use file_ops::StartupFile;
+use tasks::FileTask;
 use preview::PreviewState;
+use turntable::TurntableJob;
+use camera_path::FlythroughJob;
 
 /// Alpha applied to the brush hover overlay. Higher than the procgen
 /// preview (0.5) so the brush hint stays legible against existing
 /// voxels of similar color.
 const BRUSH_PREVIEW_ALPHA: f32 = 0.75;
 
+/// How far voxels off the work plane fade toward the background when
+/// `Editor::work_plane_dim` is on: enough that the slice pops, while
+/// the rest of the model still reads as context.
+const WORK_PLANE_DIM: f32 = 0.7;
+
 /// Alpha applied to the move-drag voxel ghost — the translucent copy
 /// of a selection's content that follows the cursor while it's being
 /// relocated. A touch lighter than the brush hint (0.75) so it reads
@@ -59,17 +85,18 @@ const BRUSH_PREVIEW_ALPHA: f32 = 0.75;
 /// visible against the voxels it slides over.
 const MOVE_GHOST_ALPHA: f32 = 0.55;
 
-/// How often `tick_autosave` writes the crash-recovery file while there
-/// are unsaved changes. Long enough that saving a big world doesn't
-/// hitch editing, short enough that a crash loses little work.
-const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// Shortest gap between Model Info refreshes while voxels keep changing
+/// (a brush stroke): the color histogram visits every voxel.
+const MODEL_INFO_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Time between streaming passes: often enough to keep up with a flying
+/// camera, rare enough that walking every chunk's lock doesn't show.
+const STREAM_INTERVAL: Duration = Duration::from_secs(2);
 
 /// Inclusive AABB `(min, max)` enclosing a set of cell positions, or
 /// `None` for an empty set. Used to remember a generation's footprint
 /// for the "Frame Generated" camera action.
-pub(super) fn bounds_of(
-    positions: impl IntoIterator<Item = (i32, i32, i32)>,
-) -> Option<((i32, i32, i32), (i32, i32, i32))> {
+pub(super) fn bounds_of(positions: impl IntoIterator<Item = (i32, i32, i32)>) -> Option<CellBox> {
     let mut it = positions.into_iter();
     let first = it.next()?;
     let (mut min, mut max) = (first, first);
@@ -87,23 +114,54 @@ pub struct App {
     egui_state: Option<egui_winit::State>,
     egui_renderer: Option<egui_wgpu::Renderer>,
 
-    world: World,
-    mesher: GreedyMesher,
+    /// Document layers; edits go to the active one, rendering and
+    /// export read the composited visible view.
+    scene: Scene,
+    /// Undo histories of the inactive layers, keyed by layer id. The
+    /// active layer's history lives in `editor.history`; switching
+    /// layers swaps it in and out (see `activate_layer`).
+    layer_histories: HashMap<LayerId, CommandHistory>,
+    /// Every layer's undo history in the animation frames that aren't
+    /// shown, keyed by frame id then layer id. Showing a frame moves
+    /// `layer_histories` (plus the active one) in here and takes the
+    /// incoming frame's out (see `animation.rs`).
+    frame_histories: HashMap<FrameId, HashMap<LayerId, CommandHistory>>,
+    /// When the shown frame came up during playback; `None` while
+    /// stopped.
+    playback: Option<Instant>,
+    /// The onion-skin ghosts need rebuilding: the shown frame, the
+    /// frame list or the view's voxels changed.
+    onion_skin_stale: bool,
+    /// The pose-preview ghost needs rebuilding: the rig or the view's
+    /// voxels changed.
+    pose_preview_stale: bool,
+    mesher: MeshSettings,
     editor: Editor,
     ui: Ui,
 
     last_frame: Instant,
     frame_times: VecDeque<f32>,
 
-    /// `(milliseconds, chunks)` of the most recent non-empty
-    /// dirty-chunk rebuild — mesh generation + GPU upload + dirty-flag
-    /// clear, i.e. the cost a big edit adds to its frame. `None` until
-    /// the first rebuild. Surfaced by the perf HUD via
-    /// `calculate_stats`.
+    /// `(milliseconds, chunks)` of the most recent meshing batch —
+    /// from the first dirty-chunk submit until the worker delivered the
+    /// last mesh, i.e. how long a big edit takes to fully appear.
+    /// `None` until the first batch drains. Surfaced by the perf HUD
+    /// via `calculate_stats`.
     last_rebuild: Option<(f32, usize)>,
+    /// Background pool that meshes dirty chunks off the UI thread;
+    /// `rebuild_all_meshes` submits to it and uploads what comes back.
+    mesh_worker: MeshWorker,
+    /// Level of detail each chunk was last submitted at, for chunks
+    /// not at full detail (absent = 0). Picked by distance with
+    /// hysteresis, so it's the chunk's current level as well as the
+    /// input to choosing the next one (`chunk_lod`).
+    chunk_lods: HashMap<ChunkPos, u8>,
 
     cursor_captured: bool,
     cursor_pos: (f32, f32),
+    /// Latest GPU pick read back (Viewport Settings ▸ GPU Picking);
+    /// `update_raycast` uses it while it still matches the cursor.
+    gpu_pick: Option<PickSample>,
     modifiers: ModifiersState,
 
     /// True between left-button press and release; gates drag-paint
@@ -131,21 +189,16 @@ pub struct App {
 
     /// Cache key for the brush hover overlay so we don't regenerate
     /// its mesh on every CursorMoved when nothing meaningful changed.
-    /// `(active cell, tool, brush color, brush size, symmetry, shape
-    /// drag key)`. The "active cell" is `hover.voxel_pos` for
+    /// `(active cell, tool, brush color, brush size, symmetry, box
+    /// hollow, shape drag key)`. The "active cell" is `hover.voxel_pos` for
     /// brush tools and `hover.adjacent_pos` for shape tools (so
     /// shapes lock to the ground-plane fallback when the world is
     /// empty). The trailing `Option<ShapeDragKey>` carries the
     /// shape drag's enough-to-detect-change snapshot during a
-    /// Footprint or Height phase.
-    last_brush_preview_key: Option<(
-        (i32, i32, i32),
-        Tool,
-        Voxel,
-        u8,
-        SymmetryAxes,
-        Option<ShapeDragKey>,
-    )>,
+    /// Footprint or Height phase; the `Option<ExtrudeKey>` after it the
+    /// Extrude tool's hovered face normal and settings, and the last,
+    /// `Option<BrushKey>`, the Place / Remove / Paint brush's.
+    last_brush_preview_key: Option<BrushPreviewKey>,
 
     /// In-progress shape drag (Line / Box / Sphere / Cylinder).
     /// Two-phase: Footprint while the left button is held (cursor
@@ -201,6 +254,29 @@ pub struct App {
     /// loaded, not every frame. Names don't affect the gizmo, so
     /// renaming a socket doesn't invalidate this.
     last_socket_viz: Vec<([f32; 3], [f32; 3])>,
+    /// World bounds the bounds wireframe was last built for (`None` =
+    /// never built), so `update_bounds_visualization` only touches the
+    /// GPU when the box changes.
+    last_bounds_viz: Option<Option<WorldBounds>>,
+    /// Live counts of the active selection and the layer index they
+    /// were taken on. Kept current chunk by chunk: `rebuild_all_meshes`
+    /// invalidates the chunks each edit touched and
+    /// `refresh_selection_stats` recounts just those.
+    selection_stats: Option<(usize, SelectionStats)>,
+    /// Ruler ends the measure overlay was last built for (`None` =
+    /// never built), like `last_bounds_viz`.
+    last_measure_viz: Option<Option<CellBox>>,
+
+    /// Cache key for the hovered-voxel highlight — `(cell, normal,
+    /// inset bits)` — so `update_hover_highlight` only rebuilds its
+    /// buffers when the hover or the distance-scaled inset changes.
+    last_hover_key: Option<HoverKey>,
+
+    /// Cache key for the work-plane overlay and dimming — `(plane,
+    /// dim, overlay half-extent)`, `None` while the mode is off — so
+    /// `update_work_plane_visualization` only touches the GPU when one
+    /// of them changes.
+    last_work_plane_key: Option<(WorkPlane, bool, i32)>,
 
     /// Locked face plane for drag-paint. Captured on the first
     /// `apply_tool` of a brush stroke (Place / Remove / Paint) and
@@ -218,6 +294,26 @@ pub struct App {
     /// matches the convention in MagicaVoxel / Goxel / vengi.
     pub(super) clipboard: Option<Clipboard>,
 
+    /// Working copy of the clipboard while **paste placement** is
+    /// active (Edit ▸ Place Paste, Ctrl+Alt+V): the blob follows the
+    /// cursor as a ghost, R / Shift+R / M rotate or mirror this copy,
+    /// and a left click commits it as one undoable `SetVoxels`. The
+    /// stored `clipboard` keeps its original orientation. `None` when
+    /// not placing.
+    pub(super) paste_placement: Option<Clipboard>,
+
+    /// Where the paste placement's ghost is pinned (its `min` corner)
+    /// after Enter, for the move gizmo to nudge; `None` while it follows
+    /// the cursor.
+    pub(super) paste_pin: Option<(i32, i32, i32)>,
+
+    /// Move gizmo handle held down (see `gizmo.rs`).
+    gizmo_drag: Option<GizmoDrag>,
+    /// Gizmo handle under the cursor, drawn highlighted.
+    gizmo_hover: Option<GizmoHandle>,
+    /// Cache key for the gizmo overlay, like `last_measure_viz`.
+    last_gizmo_viz: Option<Option<(Gizmo, Option<GizmoHandle>, bool)>>,
+
     /// Persisted user preferences. Loaded at startup, dehydrated and
     /// written back on close. The recent-files MRU lives here.
     prefs: Prefs,
@@ -241,6 +337,50 @@ pub struct App {
     /// dropping it doesn't cancel — the worker checks the AtomicBool
     /// at safe points (see `MockProvider`).
     pub(super) ai_handle: Option<JobHandle>,
+    /// Procgen panel / graph run in flight on a worker thread, if any.
+    /// Started by `GenerateProcedural` / `RunGraph`, polled each frame
+    /// by `tick_gen_job`, which applies the result as one undo step.
+    gen_job: Option<ActiveGenJob>,
+    /// Finished generation awaiting Apply / Discard. While set it owns
+    /// the preview overlay and the live preview is paused.
+    staged: Option<StagedGeneration>,
+    /// Render ▸ Turntable in progress; `tick_turntable` renders and
+    /// writes one frame of it per app frame.
+    turntable: Option<TurntableJob>,
+    /// Render ▸ Camera Path render in progress; `tick_flythrough`
+    /// renders and writes one frame of it per app frame.
+    flythrough: Option<FlythroughJob>,
+    /// Keyframed fly-through (Render ▸ Camera Path). Part of the
+    /// project like `camera_bookmarks`, and cleared with them.
+    camera_path: voxelith::render::CameraPath,
+    /// When the viewport started flying the camera path (preview);
+    /// `None` while not previewing.
+    camera_path_preview: Option<Instant>,
+    /// Import / export / project open announced in the task list and
+    /// run at the start of the next frame (`tasks::FileTask`).
+    pending_file_task: Option<FileTask>,
+    /// Fill click waiting on the large-fill prompt.
+    pending_fill: Option<input::PendingFill>,
+    /// Project Settings bounds waiting on the world-bounds prompt.
+    pending_world_bounds: Option<WorldBounds>,
+    /// File named on the command line; opened right after the initial
+    /// scene, once the window exists.
+    startup_file: Option<StartupFile>,
+    /// Project save or autosave being written on its own thread; see
+    /// `file_ops`.
+    background_save: Option<PendingSave>,
+    /// Seeds of the procgen runs applied to this project, by generator
+    /// id. Saved as `EditorState::procgen_seeds`; cleared by `set_scene`
+    /// (New / import / open, which then restores the file's).
+    procgen_seeds: std::collections::BTreeMap<String, u64>,
+    /// Saved camera poses, one per Ctrl+1..9 slot. Part of the project
+    /// (`EditorState::camera_bookmarks`); cleared by `set_scene` like
+    /// `procgen_seeds`.
+    camera_bookmarks: [Option<voxelith::io::CameraBookmark>; voxelith::io::CAMERA_BOOKMARK_SLOTS],
+    /// Chunks of the imported .vox that Voxelith doesn't interpret,
+    /// written back by VOX export. Cleared by `set_scene`; not saved in
+    /// the project.
+    vox_chunks: Vec<voxelith::io::VoxChunk>,
     /// Cached "is an API key in the keychain?" so the UI doesn't hit
     /// the keyring every frame. Refreshed by save / clear actions.
     pub(super) ai_has_key: bool,
@@ -252,8 +392,39 @@ pub struct App {
     /// bothers to write.
     pub(super) unsaved_changes: bool,
     /// When the last autosave ran. `tick_autosave` rate-limits writes to
-    /// `AUTOSAVE_INTERVAL`.
+    /// the Preferences autosave interval.
     pub(super) last_autosave: Instant,
+    /// When `tick_streaming` last ran a pass.
+    pub(super) last_stream_pass: Instant,
+    /// Theme, custom colors and UI scale last handed to egui; `None`
+    /// until the first frame applies them.
+    pub(super) applied_appearance: Option<(Theme, CustomTheme, f32)>,
+    /// Background last handed to the renderer; `None` until the first
+    /// frame applies it, and again after a project or a new scene
+    /// brings its own (which isn't an edit to save).
+    pub(super) applied_background: Option<Background>,
+    /// Lights, selected light and marker toggle last handed to the
+    /// renderer; `None` like `applied_background`. Only a change to the
+    /// lights themselves is an unsaved edit.
+    pub(super) applied_lights: Option<(Vec<Light>, Option<usize>, bool)>,
+    /// Background image the renderer shows or is about to load; the
+    /// file is only decoded again when this changes.
+    pub(super) background_image: Option<PathBuf>,
+    /// Reference planes last handed to the renderer; `None` like
+    /// `applied_background`, and again once new images have loaded.
+    pub(super) applied_references: Option<Vec<ReferenceImage>>,
+    /// Reference image files the renderer holds or is loading; each is
+    /// decoded once, until no plane shows it any more.
+    pub(super) reference_images: HashSet<PathBuf>,
+    /// Ghost model last handed to the renderer; `None` like
+    /// `applied_references`.
+    pub(super) applied_reference_model: Option<Option<ReferenceModel>>,
+    /// Ghost model file read or being read; the file is only read again
+    /// when this changes.
+    pub(super) reference_model_file: Option<PathBuf>,
+    /// The ghost model's file as read — unplaced, at full opacity — to
+    /// re-place on every edit without reading it again.
+    pub(super) reference_model_source: Option<(PathBuf, ChunkMesh)>,
 
     /// World-space AABB (inclusive cell coords) of the most recent
     /// procgen / graph / AI generation, powering the "Frame Generated"
@@ -261,7 +432,22 @@ pub struct App {
     /// set at each generation chokepoint. Not cleared on undo — framing
     /// stale bounds just frames where the geometry was, and the action
     /// guards on `None`.
-    pub(super) last_generated_bounds: Option<((i32, i32, i32), (i32, i32, i32))>,
+    pub(super) last_generated_bounds: Option<CellBox>,
+
+    /// Voxels changed since `ui.model_info` was measured. Set by
+    /// `rebuild_all_meshes` and `set_scene`; cleared by
+    /// `refresh_model_info`.
+    pub(super) model_info_stale: bool,
+    /// Voxels changed since `ui.slice_image` was sampled. Set alongside
+    /// `model_info_stale`; cleared by `refresh_slice_view`.
+    pub(super) slice_view_stale: bool,
+    /// When `ui.model_info` was last measured.
+    model_info_at: Instant,
+
+    /// The material table the voxels were last stamped with. When
+    /// `editor.materials` drifts from it (a Materials-panel edit),
+    /// `sync_materials` re-stamps the affected voxels.
+    stamped_materials: MaterialTable,
 }
 
 impl App {
@@ -283,7 +469,26 @@ impl App {
             x: prefs.editor.symmetry[0],
             y: prefs.editor.symmetry[1],
             z: prefs.editor.symmetry[2],
+            center: prefs.editor.symmetry_center.into(),
         };
+        editor.box_hollow = prefs.editor.box_hollow;
+        editor.paint = prefs.editor.paint;
+        editor.brush = prefs.editor.brush.clone();
+        editor.sculpt = prefs.editor.sculpt;
+        editor.fill = prefs.editor.fill;
+        // The stamp itself isn't in prefs; read it back from its file.
+        // One that's gone or broken just leaves the Stamp shape empty.
+        if let Some(path) = &editor.brush.stamp_path {
+            editor.brush.stamp = file_ops::read_brush_stamp(path, prefs.general.vox_axes)
+                .ok()
+                .map(Arc::new);
+        }
+        editor.extrude_depth = prefs.editor.extrude_depth.clamp(1, MAX_EXTRUDE_DEPTH);
+        editor.extrude_direction = prefs.editor.extrude_direction;
+        editor.wand_tolerance = prefs.editor.wand_tolerance;
+        editor.work_plane = prefs.editor.work_plane;
+        editor.work_plane_enabled = prefs.editor.work_plane_enabled;
+        editor.work_plane_dim = prefs.editor.work_plane_dim;
         if !prefs.editor.palette.is_empty() {
             editor.palette = prefs
                 .editor
@@ -300,7 +505,18 @@ impl App {
         ui.state.show_viewport_settings = prefs.panels.show_viewport_settings;
         ui.state.show_procgen = prefs.panels.show_procgen;
         ui.state.show_graph = prefs.panels.show_graph;
+        ui.state.show_layers = prefs.panels.show_layers;
+        ui.state.show_timeline = prefs.panels.show_timeline;
+        ui.state.show_rig = prefs.panels.show_rig;
+        ui.state.show_model_info = prefs.panels.show_model_info;
+        ui.state.show_history = prefs.panels.show_history;
+        ui.state.show_materials = prefs.panels.show_materials;
+        ui.state.show_slice_view = prefs.panels.show_slice_view;
         ui.viewport = prefs.viewport.clone();
+        ui.general = prefs.general.clone();
+        ui.set_plugins(voxelith::plugin::registry().infos());
+        ui.dock = prefs.layout.clone();
+        ui.dock.normalize();
         ui.procgen = prefs.procgen.clone();
         ui.graph = prefs.graph.clone();
         // Pre-position-field prefs deserialize every node at [0, 0].
@@ -319,15 +535,23 @@ impl App {
             renderer: None,
             egui_state: None,
             egui_renderer: None,
-            world: World::new(),
-            mesher: GreedyMesher::new(),
+            scene: Scene::new(),
+            layer_histories: HashMap::new(),
+            frame_histories: HashMap::new(),
+            playback: None,
+            onion_skin_stale: true,
+            pose_preview_stale: true,
+            mesher: MeshSettings::default(),
             editor,
             ui,
             last_frame: Instant::now(),
             frame_times: VecDeque::with_capacity(60),
             last_rebuild: None,
+            mesh_worker: MeshWorker::new(),
+            chunk_lods: HashMap::new(),
             cursor_captured: false,
             cursor_pos: (0.0, 0.0),
+            gpu_pick: None,
             modifiers: ModifiersState::empty(),
             left_button_held: false,
             last_stroke_voxel: None,
@@ -344,8 +568,18 @@ impl App {
             last_selection_box: None,
             last_ghost_delta: None,
             last_socket_viz: Vec::new(),
+            last_bounds_viz: None,
+            last_measure_viz: None,
+            selection_stats: None,
+            last_hover_key: None,
+            last_work_plane_key: None,
             stroke_plane: None,
             clipboard: None,
+            paste_placement: None,
+            paste_pin: None,
+            gizmo_drag: None,
+            gizmo_hover: None,
+            last_gizmo_viz: None,
             prefs,
             ai_runtime: AiRuntime::new(),
             ai_provider: Arc::new(FalHunyuanProvider::new()),
@@ -355,7 +589,35 @@ impl App {
             ai_has_key: voxelith::ai::has_api_key("fal_ai"),
             unsaved_changes: false,
             last_autosave: Instant::now(),
+            last_stream_pass: Instant::now(),
+            applied_appearance: None,
+            applied_background: None,
+            applied_lights: None,
+            background_image: None,
+            applied_references: None,
+            reference_images: HashSet::new(),
+            applied_reference_model: None,
+            reference_model_file: None,
+            reference_model_source: None,
             last_generated_bounds: None,
+            model_info_stale: true,
+            slice_view_stale: true,
+            model_info_at: Instant::now(),
+            stamped_materials: MaterialTable::new(),
+            gen_job: None,
+            staged: None,
+            turntable: None,
+            flythrough: None,
+            camera_path: Default::default(),
+            camera_path_preview: None,
+            pending_file_task: None,
+            pending_fill: None,
+            pending_world_bounds: None,
+            startup_file: None,
+            background_save: None,
+            procgen_seeds: Default::default(),
+            camera_bookmarks: Default::default(),
+            vox_chunks: Vec::new(),
         }
     }
 
@@ -377,14 +639,25 @@ impl App {
     }
 
     /// Push the current path to the recent-files MRU. Called from
-    /// file_ops after a successful open/save/import/export. Mirrors
-    /// the updated list to `ui.recent_files` so the next frame's
-    /// Open Recent menu reflects it.
+    /// file_ops after a successful project open/save only — Open
+    /// Recent reopens entries as `.vxlt` projects, so import/export
+    /// paths would just be dead links. Mirrors the updated list to
+    /// `ui.recent_files` so the next frame's Open Recent menu reflects
+    /// it.
     pub(super) fn touch_recent(&mut self, path: &std::path::Path) {
         self.prefs.touch_recent(path);
         self.ui.recent_files = self.prefs.recent_files.clone();
     }
 
+    /// Empty the recent-files MRU (File ▸ Open Recent ▸ Clear Recent)
+    /// and persist right away, so the list stays cleared even if the
+    /// app doesn't exit cleanly.
+    pub(super) fn clear_recent(&mut self) {
+        self.prefs.recent_files.clear();
+        self.ui.recent_files.clear();
+        self.save_prefs();
+    }
+
     /// Push a prompt to the AI-prompts MRU and mirror it to the UI so
     /// the AI panel's History dropdown reflects it next frame. Mirrors
     /// `touch_recent` for the recent-files list.
@@ -393,6 +666,12 @@ impl App {
         self.ui.recent_ai_prompts = self.prefs.recent_ai_prompts.clone();
     }
 
+    /// Preferences ▸ Forget Last-Used Folders.
+    pub(super) fn forget_last_dirs(&mut self) {
+        self.prefs.last_dirs = LastDirs::default();
+        self.ui.set_status("File dialogs will open in their default folders");
+    }
+
     /// Snapshot live UI/editor/window state into `self.prefs`, then
     /// write the file. Called on app exit.
     pub(super) fn save_prefs(&mut self) {
@@ -403,8 +682,17 @@ impl App {
             show_viewport_settings: self.ui.state.show_viewport_settings,
             show_procgen: self.ui.state.show_procgen,
             show_graph: self.ui.state.show_graph,
+            show_layers: self.ui.state.show_layers,
+            show_timeline: self.ui.state.show_timeline,
+            show_rig: self.ui.state.show_rig,
+            show_model_info: self.ui.state.show_model_info,
+            show_history: self.ui.state.show_history,
+            show_materials: self.ui.state.show_materials,
+            show_slice_view: self.ui.state.show_slice_view,
         };
         self.prefs.viewport = self.ui.viewport.clone();
+        self.prefs.general = self.ui.general.clone();
+        self.prefs.layout = self.ui.dock.clone();
         self.prefs.procgen = self.ui.procgen.clone();
         self.prefs.graph = self.ui.graph.clone();
         self.prefs.editor = EditorPrefs {
@@ -427,8 +715,20 @@ impl App {
                 self.editor.symmetry.y,
                 self.editor.symmetry.z,
             ],
+            symmetry_center: self.editor.symmetry.center.into(),
             brush_flags: self.editor.brush_color.flags,
             brush_tint_zone: self.editor.brush_color.tint_zone(),
+            box_hollow: self.editor.box_hollow,
+            paint: self.editor.paint,
+            brush: self.editor.brush.clone(),
+            sculpt: self.editor.sculpt,
+            fill: self.editor.fill,
+            extrude_depth: self.editor.extrude_depth,
+            extrude_direction: self.editor.extrude_direction,
+            wand_tolerance: self.editor.wand_tolerance,
+            work_plane: self.editor.work_plane,
+            work_plane_enabled: self.editor.work_plane_enabled,
+            work_plane_dim: self.editor.work_plane_dim,
         };
         if let Some(window) = &self.window {
             // `inner_size()` returns physical pixels; `WindowPrefs` is
@@ -590,6 +890,31 @@ pub(super) enum ShapeDragKey {
     },
 }
 
+/// Brush ghost cache key; see `App::last_brush_preview_key`.
+pub(super) type BrushPreviewKey = (
+    (i32, i32, i32),
+    Tool,
+    Voxel,
+    u8,
+    SymmetryAxes,
+    bool,
+    Option<ShapeDragKey>,
+    Option<ExtrudeKey>,
+    Option<BrushKey>,
+);
+
+/// Hover highlight cache key; see `App::last_hover_key`.
+pub(super) type HoverKey = ((i32, i32, i32), (i32, i32, i32), u32);
+
+/// Extrude ghost inputs beyond the hovered cell: `(face normal, depth,
+/// direction)`.
+pub(super) type ExtrudeKey = ((i32, i32, i32), u8, ExtrudeDirection);
+
+/// Brush ghost inputs beyond the hovered cell: `(face normal, shape,
+/// stamp identity)`. The normal matters for Place, the disc and the
+/// stamp; the stamp is told apart by its allocation.
+pub(super) type BrushKey = ((i32, i32, i32), BrushShape, Option<usize>);
+
 impl ShapeDrag {
     /// Build the cache key for `update_brush_preview`. `hovered_cell`
     /// is the cursor's current plane-locked `adjacent_pos` (used in
@@ -654,6 +979,10 @@ fn tool_from_index(idx: u8) -> Tool {
         8 => Tool::Cylinder,
         9 => Tool::Select,
         10 => Tool::Socket,
+        11 => Tool::Extrude,
+        12 => Tool::Wand,
+        13 => Tool::Measure,
+        14 => Tool::Sculpt,
         _ => Tool::Place,
     }
 }
@@ -671,6 +1000,10 @@ fn tool_to_index(t: Tool) -> u8 {
         Tool::Cylinder => 8,
         Tool::Select => 9,
         Tool::Socket => 10,
+        Tool::Extrude => 11,
+        Tool::Wand => 12,
+        Tool::Measure => 13,
+        Tool::Sculpt => 14,
     }
 }
 
@@ -743,12 +1076,21 @@ impl App {
         if Self::autosave_path().is_some_and(|p| p.exists()) {
             self.ui.state.show_recovery_prompt = true;
         }
+        if let Some(file) = self.startup_file.take() {
+            self.open_startup_file(file);
+        }
+    }
+
+    /// Open `file` once the window is up, instead of leaving the
+    /// initial test scene (`voxelith <file>`).
+    pub fn open_on_startup(&mut self, file: StartupFile) {
+        self.startup_file = Some(file);
     }
 
     /// Create the initial test scene shown on startup.
     fn create_initial_scene(&mut self) {
-        self.world.create_test_cube((0, 8, 0), 4);
-        self.world.create_test_ground(20, 2);
+        self.scene.active_world_mut().create_test_cube((0, 8, 0), 4);
+        self.scene.active_world_mut().create_test_ground(20, 2);
         self.rebuild_all_meshes();
         // Anchor the orbit pivot on the actual scene rather than the
         // hardcoded (0,0,0) target from `Camera::new`. Without this,
@@ -765,18 +1107,25 @@ impl App {
     }
 
     /// Per-frame autosave tick. Cheap when idle (one bool + one elapsed
-    /// check). Writes at most once per `AUTOSAVE_INTERVAL`, and only when
-    /// there are unsaved changes to a non-empty world. Clears
-    /// `unsaved_changes` on a successful write so we don't rewrite an
-    /// unchanged world every interval; a failed write is logged and
-    /// retried next interval.
+    /// check). Writes at most once per Preferences autosave interval
+    /// (never when it's off), and only when there are unsaved changes
+    /// to a non-empty world and no other save is running. Clears
+    /// `unsaved_changes` when the write starts so we don't rewrite an
+    /// unchanged world every interval; a failed write sets it again
+    /// and is retried next interval.
     pub(super) fn tick_autosave(&mut self) {
-        if !self.unsaved_changes || self.last_autosave.elapsed() < AUTOSAVE_INTERVAL {
+        let Some(interval) = self.ui.general.autosave_interval() else {
+            return;
+        };
+        if !self.unsaved_changes
+            || self.last_autosave.elapsed() < interval
+            || self.background_save.is_some()
+        {
             return;
         }
         // Don't autosave (or offer to recover) an empty scene — e.g. just
         // after Clear All. Reset the timer so we don't re-check every frame.
-        if self.world.scene_center().is_none() {
+        if self.scene.view().scene_center().is_none() {
             self.unsaved_changes = false;
             self.last_autosave = Instant::now();
             return;
@@ -787,27 +1136,43 @@ impl App {
         if let Some(parent) = path.parent() {
             let _ = std::fs::create_dir_all(parent);
         }
-        let state = self.current_editor_state();
-        // Atomic write: serialize to a temp file, then rename it over the
-        // real autosave. A crash mid-write then leaves at most a stale
-        // `autosave.tmp`, never a half-written `autosave.vxlt` — so
-        // recovery always loads a COMPLETE last state. `fs::rename`
-        // replaces the destination on Windows (MoveFileEx) as on POSIX,
-        // and both files share the dir so it's a same-volume move.
-        let tmp = path.with_extension("tmp");
-        let result = voxelith::io::save_world_with_state(&self.world, state, &tmp)
-            .and_then(|()| std::fs::rename(&tmp, &path).map_err(Into::into));
-        match result {
-            Ok(()) => {
-                log::info!("Autosaved to {}", path.display());
-                self.unsaved_changes = false;
-            }
+        // Written like a project save: off the main thread, through a
+        // temp file renamed over the real autosave, so a crash mid-write
+        // leaves at most a stale `autosave.vxlt.tmp`, never a half-
+        // written `autosave.vxlt` — recovery always loads a COMPLETE
+        // last state.
+        self.start_autosave(path);
+        self.last_autosave = Instant::now();
+    }
+
+    /// Per-frame streaming tick: with streaming on in Preferences, cool
+    /// chunks far from the camera every [`STREAM_INTERVAL`]. A spill
+    /// file that can't be written is reported and retried next pass;
+    /// chunks stay readable either way.
+    pub(super) fn tick_streaming(&mut self) {
+        let settings = self.ui.general.streaming;
+        if !settings.enabled || self.last_stream_pass.elapsed() < STREAM_INTERVAL {
+            return;
+        }
+        self.last_stream_pass = Instant::now();
+        let Some(eye) = self.renderer.as_ref().map(|r| r.camera.position) else {
+            return;
+        };
+        let eye = eye.floor().as_ivec3();
+        let center = ChunkPos::from_world_pos(eye.x, eye.y, eye.z);
+        match self.scene.stream_out(center, &settings) {
+            Ok(stats) if stats.cooled > 0 => log::debug!(
+                "Streamed out {} chunks, {:.1} MB resident",
+                stats.cooled,
+                stats.resident_bytes as f64 / (1024.0 * 1024.0)
+            ),
+            Ok(_) => {}
             Err(e) => {
-                log::warn!("Autosave failed: {}", e);
-                let _ = std::fs::remove_file(&tmp); // drop a partial temp
+                log::warn!("Streaming pass failed: {}", e);
+                self.ui
+                    .set_status(format!("Couldn't spill chunks to disk: {}", e));
             }
         }
-        self.last_autosave = Instant::now();
     }
 
     /// Remove the crash-recovery autosave. Called on a clean exit (so the
@@ -831,7 +1196,7 @@ impl App {
     ///
     /// No-op when the world is empty (nothing meaningful to focus on).
     pub(super) fn recenter_camera_on_scene(&mut self) {
-        let Some(center) = self.world.scene_center() else { return };
+        let Some(center) = self.scene.view().scene_center() else { return };
         let Some(renderer) = &mut self.renderer else { return };
         renderer.camera.target = center;
         renderer
@@ -839,51 +1204,128 @@ impl App {
             .sync_orbit_state_from_camera(&renderer.camera);
     }
 
-    /// Rebuild meshes for all dirty chunks and upload them to the GPU.
+    /// Submit dirty chunks to the background mesh worker and upload
+    /// whatever meshes have finished since the last call. Runs every
+    /// frame, so a big edit streams in over a few frames instead of
+    /// stalling one.
     ///
-    /// Mesh generation runs on rayon's thread pool. Uploads stay on
-    /// the calling thread because wgpu device/queue handles aren't
-    /// trivially shareable with workers and uploads are cheap
-    /// relative to mesh construction.
+    /// Uploads stay on the calling thread because wgpu device/queue
+    /// handles aren't trivially shareable with workers and uploads are
+    /// cheap relative to mesh construction.
     pub(super) fn rebuild_all_meshes(&mut self) {
-        let Some(renderer) = &mut self.renderer else {
+        let Some(eye) = self.renderer.as_ref().map(|r| r.camera.position) else {
             return;
         };
 
-        let dirty = self.world.dirty_chunks();
-        if dirty.is_empty() {
+        let dirty = self.scene.take_dirty_view_chunks();
+        if !dirty.is_empty() {
+            // Dirty chunks this frame ⟺ voxel data changed (a write marks
+            // its chunk dirty; boundary writes also mark neighbors). This
+            // is the single chokepoint every edit / generation / AI /
+            // paste funnels through, so it's where we flag the document
+            // for autosave. The load / new / initial-scene paths clear
+            // the flag again after their own rebuild.
+            self.unsaved_changes = true;
+            self.model_info_stale = true;
+            self.slice_view_stale = true;
+            self.onion_skin_stale = true;
+            self.pose_preview_stale = true;
+            if let Some((_, stats)) = &mut self.selection_stats {
+                stats.invalidate(&dirty);
+            }
+            // The Extrude ghost and a surface-only brush's are computed
+            // from the voxels under the cursor, so they're stale now
+            // even if the cursor isn't.
+            let brush = &self.editor.brush;
+            if self.editor.current_tool == Tool::Extrude
+                || brush.surface_snap
+                || brush.top_layer_only
+            {
+                self.last_brush_preview_key = None;
+            }
+
+            // Flags are cleared at submit time: an edit landing while the
+            // job runs re-dirties the chunk, and the resubmitted job's
+            // ticket supersedes the in-flight one. Jobs mesh the
+            // composited view, so hidden layers never reach the GPU.
+            for &pos in &dirty {
+                let lod = self.chunk_lod(pos, eye);
+                let mesher = LodMesher::new(self.mesher, lod);
+                self.mesh_worker.submit(self.scene.view(), pos, mesher);
+            }
+        }
+        self.update_chunk_lods(eye);
+
+        let Some(renderer) = &mut self.renderer else {
             return;
+        };
+        for mesh in self.mesh_worker.poll() {
+            renderer.upload_mesh(&mesh);
         }
-        let started = Instant::now();
-
-        // Dirty chunks this frame ⟺ voxel data changed (a write marks its
-        // chunk dirty; boundary writes also mark neighbors). This is the
-        // single chokepoint every edit / generation / AI / paste funnels
-        // through, so it's where we flag the document for autosave. The
-        // load / new / initial-scene paths clear the flag again after
-        // their own rebuild.
-        self.unsaved_changes = true;
-
-        // Concurrent reads only: mesher acquires read locks on the
-        // dirty chunk + 6 neighbors. Multiple workers operating on
-        // disjoint chunks share-read those neighbors fine.
-        let mesher = &self.mesher;
-        let world = &self.world;
-        let meshes: Vec<_> = dirty
-            .par_iter()
-            .map(|&pos| mesher.generate(world, pos))
-            .collect();
+        if let Some(batch) = self.mesh_worker.take_finished_batch() {
+            self.last_rebuild = Some(batch);
+        }
+    }
 
-        for mesh in &meshes {
-            renderer.upload_mesh(mesh);
+    /// Re-mesh every chunk with the current `mesher` — the mesher was
+    /// switched, so every uploaded mesh is stale even though no voxel
+    /// changed. Unlike `rebuild_all_meshes` this leaves dirty flags and
+    /// the unsaved-changes flag alone; the results are uploaded by the
+    /// next `rebuild_all_meshes` poll.
+    pub(super) fn remesh_all_chunks(&mut self) {
+        if self.renderer.is_none() {
+            return;
+        }
+        let positions: Vec<_> = self.scene.view().chunk_positions().copied().collect();
+        for pos in positions {
+            let lod = self.chunk_lods.get(&pos).copied().unwrap_or(0);
+            let mesher = LodMesher::new(self.mesher, lod);
+            self.mesh_worker.submit(self.scene.view(), pos, mesher);
         }
+    }
 
-        self.world.clear_dirty_flags();
+    /// Level of detail chunk `pos` should be meshed at: picked from its
+    /// center's distance to `eye` and its current level (hysteresis),
+    /// and recorded in `chunk_lods`. Always 0 with LOD off.
+    fn chunk_lod(&mut self, pos: ChunkPos, eye: glam::Vec3) -> u8 {
+        let current = self.chunk_lods.get(&pos).copied().unwrap_or(0);
+        let level = if self.ui.viewport.lod {
+            let half = CHUNK_SIZE as f32 / 2.0;
+            let (x, y, z) = pos.world_origin();
+            let center = glam::Vec3::new(x as f32 + half, y as f32 + half, z as f32 + half);
+            self.ui.viewport.lod_settings.select(eye.distance(center), current)
+        } else {
+            0
+        };
+        if level == 0 {
+            self.chunk_lods.remove(&pos);
+        } else {
+            self.chunk_lods.insert(pos, level);
+        }
+        level
+    }
 
-        self.last_rebuild = Some((
-            started.elapsed().as_secs_f32() * 1000.0,
-            dirty.len(),
-        ));
+    /// Re-mesh the chunks whose level of detail changed since they were
+    /// submitted — the camera moved, or LOD was switched on or off. The
+    /// old mesh stays up until the new one arrives. Costs nothing while
+    /// LOD is off and every chunk is back at full detail.
+    fn update_chunk_lods(&mut self, eye: glam::Vec3) {
+        if !self.ui.viewport.lod && self.chunk_lods.is_empty() {
+            return;
+        }
+        let positions: Vec<_> = self.scene.view().chunk_positions().copied().collect();
+        for pos in positions {
+            let before = self.chunk_lods.get(&pos).copied().unwrap_or(0);
+            let lod = self.chunk_lod(pos, eye);
+            if lod != before {
+                let mesher = LodMesher::new(self.mesher, lod);
+                self.mesh_worker.submit(self.scene.view(), pos, mesher);
+            }
+        }
+        if !self.ui.viewport.lod {
+            // Whatever is left belongs to chunks that no longer exist.
+            self.chunk_lods.clear();
+        }
     }
 
     /// Refresh the translucent brush/shape hover overlay. Called every
@@ -897,6 +1339,9 @@ impl App {
     ///    `adjacent_pos` (the cell where the next press would anchor).
     /// 3. **Shape tools, dragging** (left held with anchor set): full
     ///    shape voxel set from anchor to current cell, plus mirrors.
+    /// 4. **Extrude**: the cells a click would fill (in the face's own
+    ///    colors) or carve, for the hovered face. It reads the world,
+    ///    so `rebuild_all_meshes` drops the cache key on every edit.
     ///
     /// Eyedropper has no preview (its color != the sampled color would
     /// mislead).
@@ -914,13 +1359,19 @@ impl App {
         let symmetry = self.editor.symmetry;
         let color = self.editor.brush_color;
         let size = self.editor.brush_size;
+        let box_hollow = self.editor.box_hollow;
         let cursor_y = self.cursor_pos.1;
 
-        // Eyedropper, Select, and Socket skip the brush-style hover
-        // overlay entirely. Eyedropper would mislead (brush color !=
-        // sampled color); Select draws its own AABB wireframe; Socket
-        // draws its own gizmo overlay (`update_socket_visualization`).
-        let show = !matches!(tool, Tool::Eyedropper | Tool::Select | Tool::Socket);
+        // Eyedropper, Select, Wand, Socket, and Measure skip the
+        // brush-style hover overlay entirely. Eyedropper would mislead
+        // (brush color != sampled color); Select and Wand draw the
+        // selection's AABB wireframe; Socket and Measure draw their own
+        // overlays (`update_socket_visualization`,
+        // `update_measure_visualization`).
+        let show = !matches!(
+            tool,
+            Tool::Eyedropper | Tool::Select | Tool::Wand | Tool::Socket | Tool::Measure
+        );
 
         // Cache key. `cell` is hover-derived for non-shape tools and
         // for idle shapes; for an active ShapeDrag, `cell` is fixed
@@ -937,12 +1388,26 @@ impl App {
                     color,
                     size,
                     symmetry,
+                    box_hollow,
                     drag_key,
+                    None,
+                    None,
                 ))
             } else {
                 self.editor.hovered_voxel.map(|h| {
                     let cell = if tool.is_shape() { h.adjacent_pos } else { h.voxel_pos };
-                    (cell, tool, color, size, symmetry, None)
+                    let extrude_key = (tool == Tool::Extrude).then_some((
+                        h.normal,
+                        self.editor.extrude_depth,
+                        self.editor.extrude_direction,
+                    ));
+                    let brush = &self.editor.brush;
+                    let brushes = [Tool::Place, Tool::Remove, Tool::Paint, Tool::Sculpt];
+                    let brush_key = brushes.contains(&tool).then(|| {
+                        let stamp = brush.stamp.as_ref().map(|s| Arc::as_ptr(s) as usize);
+                        (h.normal, brush.shape, stamp)
+                    });
+                    (cell, tool, color, size, symmetry, box_hollow, None, extrude_key, brush_key)
                 })
             }
         } else {
@@ -961,11 +1426,50 @@ impl App {
             return;
         }
 
+        if tool == Tool::Extrude {
+            // Ghost of the writes themselves: grown cells take their
+            // source voxel's color, carved cells show what goes away.
+            // Symmetry doesn't apply — the region is a property of the
+            // hovered surface, not a brush shape.
+            let voxels: Vec<((i32, i32, i32), Voxel)> = match self.editor.hovered_voxel {
+                Some(hit) if !hit.virtual_ground => {
+                    let world = self.scene.active_world();
+                    let region = face_region(world, hit.voxel_pos, hit.normal, MAX_EXTRUDE_CELLS);
+                    let changes = build_extrude_changes(
+                        world,
+                        &region,
+                        hit.normal,
+                        self.editor.extrude_depth,
+                        self.editor.extrude_direction,
+                    );
+                    match self.editor.active_work_plane() {
+                        Some(plane) => plane.clip(changes),
+                        None => changes,
+                    }
+                    .into_iter()
+                    .map(|c| {
+                        let shown = if c.new_voxel.is_air() { c.old_voxel } else { c.new_voxel };
+                        (c.pos, shown)
+                    })
+                    .collect()
+                }
+                _ => Vec::new(),
+            };
+            if let Some(r) = &mut self.renderer {
+                if voxels.is_empty() {
+                    r.clear_brush_preview();
+                } else {
+                    r.set_brush_preview_mesh(&patch_to_mesh(&voxels, BRUSH_PREVIEW_ALPHA));
+                }
+            }
+            return;
+        }
+
         // Compute the preview cell list. Active shape drag has its
         // own dedicated branch (no dependency on `hovered_voxel` in
         // Height phase, since the cursor lives in screen space); all
         // other modes need a real hover.
-        let positions: Vec<(i32, i32, i32)> = if let Some(drag) = self.shape_drag {
+        let mut positions: Vec<(i32, i32, i32)> = if let Some(drag) = self.shape_drag {
             let (anchor, end_3d) = match drag.phase {
                 ShapePhase::Footprint => {
                     // Footprint: cursor's plane-locked hit is the
@@ -988,6 +1492,7 @@ impl App {
             };
             let raw = match tool {
                 Tool::Line => line_voxels(anchor, end_3d),
+                Tool::Box if box_hollow => hollow_box_voxels(anchor, end_3d),
                 Tool::Box => box_voxels(anchor, end_3d),
                 Tool::Sphere => sphere_voxels(anchor, end_3d),
                 Tool::Cylinder => cylinder_voxels(anchor, end_3d),
@@ -1012,8 +1517,18 @@ impl App {
                 return;
             };
             let brush = BrushTool::new(tool);
-            brush.preview_positions(&hit, size, symmetry)
+            brush.preview_positions(
+                self.scene.active_world(),
+                &hit,
+                size,
+                &self.editor.brush,
+                symmetry,
+            )
         };
+        // Show only what the work-plane clip will let through.
+        if let Some(plane) = self.editor.active_work_plane() {
+            positions.retain(|&p| plane.contains(p));
+        }
 
         if positions.is_empty() {
             if let Some(r) = &mut self.renderer {
@@ -1032,7 +1547,7 @@ impl App {
     }
 
     /// Refresh the box-selection wireframe **and** the move-drag voxel
-    /// ghost. Both overlays are driven from the same four states and
+    /// ghost. Both overlays are driven from the same five states and
     /// share one cache gate:
     ///
     /// 1. **New-selection drag** (`selection_drag_anchor` set):
@@ -1040,8 +1555,12 @@ impl App {
     /// 2. **Move-selection drag** (`selection_move_anchor` set):
     ///    existing AABB translated by `current - anchor`, plus a
     ///    translucent ghost of the picked-up voxels at the same delta.
-    /// 3. **Idle with a committed selection**: static AABB, no ghost.
-    /// 4. **Nothing**: clear both slots.
+    ///    A gizmo drag of the selection shows the same way.
+    /// 3. **Paste placement** (`paste_placement` set): the pending
+    ///    paste's footprint plus its ghost at the hovered anchor, or
+    ///    at its pin.
+    /// 4. **Idle with a committed selection**: static AABB, no ghost.
+    /// 5. **Nothing**: clear both slots.
     ///
     /// Cached against `(last_selection_box, last_ghost_delta)` so
     /// dragging inside the same cell doesn't rebuild either buffer.
@@ -1050,7 +1569,28 @@ impl App {
     pub(super) fn update_selection_visualization(&mut self) {
         // Resolve the wireframe box and, for a move drag, the live
         // translation delta the ghost follows.
-        let (preview, ghost_delta) = if let Some(anchor) = self.selection_drag_anchor {
+        let gizmo_delta = self.gizmo_drag.map(|drag| drag.delta);
+        let (preview, ghost_delta) = if let Some(placement) = &self.paste_placement {
+            // Paste placement — the blob's footprint at the hovered
+            // anchor, or where it's pinned (plus any gizmo drag).
+            // `move_ghost_voxels` holds the placement's *relative*
+            // voxels, so the anchor itself is the delta.
+            match self.paste_anchor() {
+                Some(dest) => {
+                    let (w, h, d) = placement.size;
+                    let box_ = Selection {
+                        min: dest,
+                        max: (dest.0 + w - 1, dest.1 + h - 1, dest.2 + d - 1),
+                    };
+                    (Some(box_), Some(dest))
+                }
+                None => (None, None),
+            }
+        } else if let (Some(delta), Some(sel)) = (gizmo_delta, self.editor.selection) {
+            // Gizmo drag of the selection — like a move drag, with the
+            // gizmo's snapped offset as the delta.
+            (Some(sel.translated(delta)), Some(delta))
+        } else if let Some(anchor) = self.selection_drag_anchor {
             // New-selection drag — anchor → current end cell.
             let box_ = self
                 .editor
@@ -1111,17 +1651,28 @@ impl App {
         }
     }
 
+    /// Force the next `update_selection_visualization` to rebuild both
+    /// overlays — for ghost content changes (paste placement rotated /
+    /// mirrored) that leave the box and delta cache key unchanged.
+    pub(super) fn invalidate_selection_visualization(&mut self) {
+        self.last_selection_box = None;
+        self.last_ghost_delta = None;
+    }
+
     /// Snapshot the selection's non-air voxels (world-space) at the
     /// start of a move drag, so the per-frame ghost just translates
     /// the captured set by the live delta rather than re-reading the
     /// world every time the cursor crosses a cell. Extracts the same
     /// content as `copy_selection_to_clipboard`, but keeps absolute
-    /// positions since the ghost renders in world space.
+    /// positions since the ghost renders in world space. Only a wand
+    /// mask's cells when one is active, matching what the move takes.
     pub(super) fn begin_move_ghost(&mut self, sel: Selection) {
+        let mask = self.editor.active_mask();
         self.move_ghost_voxels = sel
             .iter_cells()
+            .filter(|&p| mask.is_none_or(|m| m.contains(p)))
             .filter_map(|(x, y, z)| {
-                let v = self.world.get_voxel(x, y, z);
+                let v = self.scene.active_world().get_voxel(x, y, z);
                 (!v.is_air()).then_some(((x, y, z), v))
             })
             .collect();
@@ -1153,6 +1704,133 @@ impl App {
         }
     }
 
+    /// Keep the world-bounds wireframe on the active layer's bounds
+    /// (none for an unbounded layer or with Viewport Settings ▸ World
+    /// Bounds off). Rebuilt only when the box changes.
+    pub(super) fn update_bounds_visualization(&mut self) {
+        let cur = self
+            .ui
+            .viewport
+            .show_bounds
+            .then(|| self.scene.active_world().bounds().copied())
+            .flatten();
+        if self.last_bounds_viz == Some(cur) {
+            return;
+        }
+        self.last_bounds_viz = Some(cur);
+        if let Some(r) = &mut self.renderer {
+            r.set_bounds_mesh(cur.as_ref());
+        }
+    }
+
+    /// The ruler to draw, if any: the picked measurement while the
+    /// Measure tool is active — rubber-banding to the hovered voxel
+    /// until the end is clicked — or, with Viewport Settings ▸
+    /// Dimension Annotation on, the last completed one under any tool.
+    pub(super) fn measure_ends(&self) -> Option<CellBox> {
+        let m = self.editor.measurement?;
+        if self.editor.current_tool == Tool::Measure {
+            let hover = self
+                .editor
+                .hovered_voxel
+                .filter(|h| !h.virtual_ground)
+                .map(|h| h.voxel_pos);
+            return Some((m.start, m.end.or(hover)?));
+        }
+        let end = m.end.filter(|_| self.ui.viewport.show_measurement)?;
+        Some((m.start, end))
+    }
+
+    /// Keep the measure overlay on [`Self::measure_ends`]. Rebuilt
+    /// only when the ends change.
+    pub(super) fn update_measure_visualization(&mut self) {
+        let cur = self.measure_ends();
+        if self.last_measure_viz == Some(cur) {
+            return;
+        }
+        self.last_measure_viz = Some(cur);
+        if let Some(r) = &mut self.renderer {
+            match cur {
+                Some((start, end)) => r.set_measure_mesh(start, end),
+                None => r.clear_measure(),
+            }
+        }
+    }
+
+    /// Refresh the hovered-voxel highlight (outline + hit-face tint)
+    /// from `editor.hovered_voxel`. Hidden for virtual-ground hits —
+    /// there's no real voxel to outline, and the brush ghost already
+    /// marks the ground cell — and during paste placement, whose own
+    /// footprint box owns the cursor.
+    pub(super) fn update_hover_highlight(&mut self) {
+        // The Slice Editor's hovered cell wins while the cursor is over
+        // its grid, so the viewport shows where a 2D click will land.
+        let slice_hover = self
+            .ui
+            .state
+            .show_slice_view
+            .then_some(self.ui.slice_view.hover)
+            .flatten()
+            .zip(self.renderer.as_ref().map(|r| r.camera.position));
+        let key = match (slice_hover, self.editor.hovered_voxel) {
+            (Some((cell, eye)), _) => {
+                let hit = self.editor.work_plane.cell_hit(cell);
+                let center = glam::Vec3::new(cell.0 as f32, cell.1 as f32, cell.2 as f32) + 0.5;
+                let inset = hover_inset(eye.distance(center));
+                Some((hit.voxel_pos, hit.normal, inset.to_bits()))
+            }
+            (None, Some(hit)) if !hit.virtual_ground && self.paste_placement.is_none() => {
+                Some((hit.voxel_pos, hit.normal, hover_inset(hit.distance).to_bits()))
+            }
+            _ => None,
+        };
+        if key == self.last_hover_key {
+            return;
+        }
+        self.last_hover_key = key;
+        if let Some(r) = &mut self.renderer {
+            match key {
+                Some((cell, normal, inset)) => {
+                    r.set_hover_mesh(cell, normal, f32::from_bits(inset))
+                }
+                None => r.clear_hover(),
+            }
+        }
+    }
+
+    /// Refresh the work-plane overlay and the shader's off-slice
+    /// dimming from the editor's work-plane settings. The overlay spans
+    /// the ground grid's extent around the origin. A change also drops
+    /// the brush preview's cache key, since the preview is clipped to
+    /// the slice.
+    pub(super) fn update_work_plane_visualization(&mut self) {
+        let viewport = &self.ui.viewport;
+        let half = (viewport.grid_size as f32 * viewport.grid_spacing / 2.0).ceil() as i32;
+        let key = self
+            .editor
+            .active_work_plane()
+            .map(|plane| (plane, self.editor.work_plane_dim, half.max(1)));
+        if key == self.last_work_plane_key {
+            return;
+        }
+        self.last_work_plane_key = key;
+        self.last_brush_preview_key = None;
+        if let Some(r) = &mut self.renderer {
+            match key {
+                Some((plane, dim, half)) => {
+                    let axis = plane.axis_index();
+                    r.set_work_plane_mesh(axis, plane.coord, [-half; 2], [half; 2]);
+                    let slice = dim.then_some((axis, plane.coord));
+                    r.pipeline.set_slice_dim(slice, WORK_PLANE_DIM);
+                }
+                None => {
+                    r.clear_work_plane();
+                    r.pipeline.set_slice_dim(None, 0.0);
+                }
+            }
+        }
+    }
+
     /// Resolve the cell a Select-tool gesture should anchor at for a
     /// given raycast hit. Real-voxel hits select the hit cell itself
     /// (so clicking a tree trunk grabs the trunk); virtual-ground
@@ -1167,6 +1845,135 @@ impl App {
         }
     }
 
+    /// Re-measure the visible model for the Model Info window when it's
+    /// open and the voxels changed, at most every `MODEL_INFO_INTERVAL`.
+    pub(super) fn refresh_model_info(&mut self) {
+        // The Project Settings dialog reads it for its .vox warnings.
+        if !self.ui.state.show_model_info && !self.ui.state.show_project_settings {
+            return;
+        }
+        let due = self.model_info_stale && self.model_info_at.elapsed() >= MODEL_INFO_INTERVAL;
+        if self.ui.model_info.is_some() && !due {
+            return;
+        }
+        let view = self.scene.view();
+        self.ui.model_info = Some(ModelInfo {
+            bounds: view.bounding_box(),
+            voxels: view.solid_voxel_count(),
+            colors: view.color_histogram(),
+            chunks: view.chunk_count(),
+            memory_bytes: self.scene.heap_bytes(),
+        });
+        self.model_info_stale = false;
+        self.model_info_at = Instant::now();
+    }
+
+    /// Bring the selection's solid-voxel and color counts up to date
+    /// for the UI: a full count of the box when the selection, its
+    /// wand mask, or the active layer changed, otherwise a recount of
+    /// just the chunks edited since the last frame.
+    pub(super) fn refresh_selection_stats(&mut self) {
+        let Some(selection) = self.editor.selection else {
+            self.selection_stats = None;
+            self.ui.selection_counts = None;
+            return;
+        };
+        let mask = self.editor.active_mask();
+        let layer = self.scene.active_index();
+        let world = self.scene.active_world();
+        match &mut self.selection_stats {
+            Some((l, stats)) if *l == layer && stats.matches(selection, mask) => {
+                stats.refresh(world, mask);
+            }
+            _ => {
+                self.selection_stats = Some((layer, SelectionStats::scan(world, selection, mask)));
+            }
+        }
+        self.ui.selection_counts = self.selection_stats.as_ref().map(|(_, s)| s.counts());
+    }
+
+    /// Apply Materials-panel edits: re-stamp the voxels of every
+    /// material whose baked properties changed, in every layer (see
+    /// `MaterialTable::restamp`), keep the brush in step with its
+    /// material, and upload the shading table. Called once per frame
+    /// after the UI pass; cheap while the table is unchanged.
+    pub(super) fn sync_materials(&mut self) {
+        let materials = &self.editor.materials;
+        self.editor.brush_color = materials.stamp(self.editor.brush_color);
+        if *materials != self.stamped_materials {
+            for world in self.scene.worlds_mut() {
+                materials.restamp(&self.stamped_materials, world);
+            }
+            self.stamped_materials = materials.clone();
+            self.unsaved_changes = true;
+        }
+        if let Some(renderer) = &mut self.renderer {
+            renderer.pipeline.update_materials(&renderer.queue, materials);
+        }
+    }
+
+    /// Swap in a loaded (or fresh) material table without re-stamping:
+    /// the voxels already carry what it stamps, and pre-material files
+    /// keep their hand-set flags.
+    pub(super) fn restore_materials(&mut self, materials: MaterialTable) {
+        self.stamped_materials = materials.clone();
+        self.editor.materials = materials;
+    }
+
+    /// Swap in a loaded (or fresh) background; the next frame applies
+    /// it without counting it as an unsaved change.
+    pub(super) fn restore_background(&mut self, background: Background) {
+        self.ui.background = background;
+        self.applied_background = None;
+    }
+
+    /// Swap in a loaded (or fresh) set of lights, like
+    /// `restore_background`.
+    pub(super) fn restore_lights(&mut self, lights: Vec<Light>) {
+        self.ui.lights = lights;
+        self.ui.selected_light = None;
+        self.applied_lights = None;
+    }
+
+    /// Swap in a loaded (or fresh) set of reference planes, like
+    /// `restore_background`.
+    pub(super) fn restore_references(&mut self, references: Vec<ReferenceImage>) {
+        self.ui.references = references;
+        self.ui.selected_reference = None;
+        self.applied_references = None;
+    }
+
+    /// Swap in a loaded (or no) ghost model, like `restore_background`.
+    pub(super) fn restore_reference_model(&mut self, model: Option<ReferenceModel>) {
+        self.ui.reference_model = model;
+        self.applied_reference_model = None;
+    }
+
+    /// Add a `kind` light a little above the orbit center and select
+    /// it, so it lands in view whatever the model's size.
+    pub(super) fn add_light(&mut self, kind: LightKind) {
+        let Some(renderer) = &self.renderer else {
+            return;
+        };
+        let position = renderer.camera.target + glam::Vec3::new(0.0, 8.0, 0.0);
+        let name = format!("Light {}", self.ui.lights.len() + 1);
+        self.ui.lights.push(Light::new(name, kind, position.to_array()));
+        self.ui.selected_light = Some(self.ui.lights.len() - 1);
+        self.ui.set_status(format!("Added {} light", kind.label().to_lowercase()));
+    }
+
+    /// Move the selected light to the camera, pointing where it looks.
+    pub(super) fn light_from_view(&mut self) {
+        let Some(renderer) = &self.renderer else {
+            return;
+        };
+        let Some(light) = self.ui.selected_light.and_then(|i| self.ui.lights.get_mut(i)) else {
+            return;
+        };
+        light.position = renderer.camera.position.to_array();
+        light.direction = renderer.camera.forward().to_array();
+    }
+
     /// Compute frame statistics for the UI overlay.
     pub(super) fn calculate_stats(&self) -> RenderStats {
         let avg_frame_time = if self.frame_times.is_empty() {
@@ -1182,9 +1989,44 @@ impl App {
             fps: 1000.0 / avg_frame_time,
             frame_time_ms: avg_frame_time,
             triangles: renderer.total_triangles(),
-            chunks: self.world.chunk_count(),
+            chunks: self.scene.view().chunk_count(),
+            draw_calls: renderer.chunks.take_draw_calls(),
             camera_pos: (camera_pos.x, camera_pos.y, camera_pos.z),
             last_rebuild: self.last_rebuild,
+            meshing: self.mesh_worker.pending(),
+            layers: self
+                .scene
+                .layers()
+                .iter()
+                .map(|l| LayerSummary {
+                    name: l.name.clone(),
+                    visible: l.visible,
+                    locked: l.locked,
+                    bounds: l.world.bounds().copied(),
+                })
+                .collect(),
+            active_layer: self.scene.active_index(),
+            frames: self
+                .scene
+                .frames()
+                .iter()
+                .map(|f| FrameSummary {
+                    name: f.name.clone(),
+                    duration_ms: f.duration_ms,
+                })
+                .collect(),
+            current_frame: self.scene.current_frame(),
+            playing: self.playback.is_some(),
+            path_trace: renderer
+                .path_tracer
+                .as_ref()
+                .filter(|t| self.ui.viewport.path_trace && t.has_volume())
+                .map(|t| {
+                    (
+                        t.sample_count(),
+                        self.ui.viewport.path_trace_settings.max_samples,
+                    )
+                }),
         }
     }
 }
@@ -14,7 +14,8 @@ use std::time::{Duration, Instant};
 
 use voxelith::mesh::patch_to_mesh;
 use voxelith::procgen::{
-    LSystemTree, PerlinTerrain, PipelineGraph, VoxelGenerator, WfcGenerator,
+    CaveGenerator, LSystemTree, PerlinTerrain, PipelineGraph, VoxelGenerator, VoxelPatch,
+    WfcGenerator,
 };
 use voxelith::ui::GeneratorChoice;
 
@@ -36,6 +37,7 @@ pub(super) struct PreviewState {
     pub last_terrain: PerlinTerrain,
     pub last_tree: LSystemTree,
     pub last_wfc: WfcGenerator,
+    pub last_cave: CaveGenerator,
     pub last_selected: GeneratorChoice,
     pub single_enabled: bool,
     pub single_last_change: Option<Instant>,
@@ -54,6 +56,7 @@ impl PreviewState {
             last_terrain: PerlinTerrain::default(),
             last_tree: LSystemTree::default(),
             last_wfc: WfcGenerator::default(),
+            last_cave: CaveGenerator::default(),
             last_selected: GeneratorChoice::default(),
             single_enabled: false,
             single_last_change: None,
@@ -67,8 +70,14 @@ impl PreviewState {
 }
 
 impl App {
-    /// Drive both preview state machines once per frame.
+    /// Drive both preview state machines once per frame. Paused while
+    /// a generation is staged — the overlay shows that result until
+    /// it's applied or discarded (both call `invalidate_preview`, so
+    /// the live preview re-renders when it resumes).
     pub(super) fn tick_preview(&mut self) {
+        if self.staged.is_some() {
+            return;
+        }
         self.tick_single_preview();
         self.tick_graph_preview();
     }
@@ -104,6 +113,7 @@ impl App {
             self.preview.last_terrain = self.ui.procgen.terrain.clone();
             self.preview.last_tree = self.ui.procgen.tree.clone();
             self.preview.last_wfc = self.ui.procgen.wfc.clone();
+            self.preview.last_cave = self.ui.procgen.cave.clone();
             self.preview.last_selected = self.ui.procgen.selected;
             self.preview.single_last_change = Some(Instant::now());
             self.preview.single_needs_regen = true;
@@ -113,11 +123,13 @@ impl App {
         let changed = self.ui.procgen.terrain != self.preview.last_terrain
             || self.ui.procgen.tree != self.preview.last_tree
             || self.ui.procgen.wfc != self.preview.last_wfc
+            || self.ui.procgen.cave != self.preview.last_cave
             || self.ui.procgen.selected != self.preview.last_selected;
         if changed {
             self.preview.last_terrain = self.ui.procgen.terrain.clone();
             self.preview.last_tree = self.ui.procgen.tree.clone();
             self.preview.last_wfc = self.ui.procgen.wfc.clone();
+            self.preview.last_cave = self.ui.procgen.cave.clone();
             self.preview.last_selected = self.ui.procgen.selected;
             self.preview.single_last_change = Some(Instant::now());
             self.preview.single_needs_regen = true;
@@ -198,6 +210,7 @@ impl App {
             GeneratorChoice::Terrain => self.ui.procgen.terrain.generate(),
             GeneratorChoice::Tree => self.ui.procgen.tree.generate(),
             GeneratorChoice::Wfc => self.ui.procgen.wfc.generate(),
+            GeneratorChoice::Cave => self.ui.procgen.cave.generate(),
         };
 
         let patch = match result {
@@ -253,6 +266,14 @@ impl App {
         }
     }
 
+    /// Show staged generator output in the overlay slot.
+    pub(super) fn show_staged_preview(&mut self, patch: &VoxelPatch) {
+        let mesh = patch_to_mesh(&patch.voxels, PREVIEW_ALPHA);
+        if let Some(r) = &mut self.renderer {
+            r.set_preview_mesh(&mesh);
+        }
+    }
+
     /// Clear the overlay and force both preview state machines to
     /// re-snapshot on the next tick. Called after a generator (single
     /// or graph) writes into the world so the just-applied geometry
@@ -2,13 +2,46 @@
 //!
 //! The flow is: drive the egui pass → drain UI actions → grid/axes/voxel
 //! main pass → egui overlay pass → submit. Wireframe replaces the voxel
-//! pipeline when enabled (and supported by the GPU).
+//! pipeline when enabled (and supported by the GPU). In quad view the
+//! main pass runs once per `ViewPane`, viewport-clipped to its quadrant.
+//! The path-traced preview replaces the main pass outright with one
+//! compute sample + a fullscreen resolve. GI, when on, is a CPU-built
+//! light volume re-uploaded after scene edits and sampled by the voxel
+//! shader's ambient term. Toon shading bands the voxel lighting and
+//! adds an outline pass after each pane's main pass. A gradient or
+//! image background is drawn first in each pane's main pass.
+
+use std::time::{Duration, Instant};
+
+use voxelith::mesh::MeshSettings;
+use voxelith::render::{
+    ghost_mesh, Camera, LightVolume, Renderer, ViewPane, VolumeError, VoxelVolume,
+    PATH_TRACE_MAX_CELLS,
+};
 
 use super::App;
 
+/// Minimum time between GI volume rebuilds. A brush stroke re-meshes
+/// every frame; rebuilding the volume that often would cost more than
+/// the coarse result is worth, so mid-stroke light lags by up to this.
+const GI_REBUILD_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Behind the path-traced preview, which draws its own sky over it.
+/// The raster view clears to the project's background instead
+/// (`Renderer::clear_color`).
+const CLEAR_COLOR: wgpu::Color = wgpu::Color {
+    r: 0.1,
+    g: 0.1,
+    b: 0.15,
+    a: 1.0,
+};
+
 impl App {
     /// Render a single frame.
     pub(super) fn render_frame(&mut self, dt: f32) {
+        // Last frame's status bar announced it; the stall starts now.
+        self.run_pending_file_task();
+
         let window = self.window.as_ref().unwrap().clone();
         let egui_state = self.egui_state.as_mut().unwrap();
 
@@ -16,6 +49,20 @@ impl App {
         let raw_input = egui_state.take_egui_input(&window);
         let egui_ctx = egui_state.egui_ctx().clone();
         egui_ctx.begin_pass(raw_input);
+        // Theme or UI scale changed (or restored from prefs on the
+        // first frame).
+        let general = &self.ui.general;
+        let appearance = (general.theme, general.custom_theme, general.ui_scale());
+        if self.applied_appearance != Some(appearance) {
+            if self.applied_appearance.is_none() {
+                // The UI scale slider is the one zoom control; egui's
+                // own Ctrl +/- would drift from it unseen.
+                egui_ctx.options_mut(|options| options.zoom_with_keyboard = false);
+            }
+            egui_ctx.set_visuals(general.visuals());
+            egui_ctx.set_zoom_factor(appearance.2);
+            self.applied_appearance = Some(appearance);
+        }
 
         let stats = self.calculate_stats();
         // Mirror clipboard presence into Ui so Tools-panel buttons can
@@ -23,10 +70,30 @@ impl App {
         // copy) and avoids leaking App::clipboard across the UI
         // boundary.
         self.ui.has_clipboard = self.clipboard.is_some();
+        self.ui.camera_bookmarks = self.camera_bookmarks.map(|b| b.is_some());
         // Same pattern for AI panel: mirror state owned by App so the
         // panel reads them off `Ui` without needing a borrow back.
         self.ui.ai_job = self.ai_job.clone();
         self.ui.ai_has_key = self.ai_has_key;
+        self.ui.gen_job = self.gen_job_status();
+        self.ui.turntable_job = self.turntable_status();
+        self.ui.flythrough_job = self.flythrough_status();
+        self.ui.camera_path.clone_from(&self.camera_path);
+        self.ui.camera_path_previewing = self.camera_path_preview.is_some();
+        self.ui.staged = self.staged_status();
+        self.ui.tasks.tasks = self.collect_tasks();
+        self.refresh_model_info();
+        self.refresh_selection_stats();
+        self.ui.hovered_voxel = self
+            .editor
+            .hovered_voxel
+            .filter(|h| !h.virtual_ground)
+            .map(|h| {
+                let (x, y, z) = h.voxel_pos;
+                (h.voxel_pos, self.scene.view().get_voxel(x, y, z))
+            })
+            .filter(|(_, v)| v.is_solid());
+        self.refresh_slice_view();
         // Viewport-HUD snapshot: gesture state (shape drag, move
         // anchors, stroke plane) lives on App, so condense it here
         // and hand the display-ready struct across the UI boundary.
@@ -37,18 +104,176 @@ impl App {
 
         // Drain UI actions before touching wgpu state
         self.handle_ui_actions();
+        self.sync_materials();
 
         let egui_state = self.egui_state.as_mut().unwrap();
         egui_state.handle_platform_output(&window, full_output.platform_output);
 
+        // Mesher or its AO / normals toggles changed in Viewport
+        // Settings (or were restored from prefs on the first frame) —
+        // every chunk mesh is stale.
+        let mesher = MeshSettings {
+            kind: self.ui.viewport.mesher,
+            ambient_occlusion: self.ui.viewport.ambient_occlusion,
+            smooth_normals: self.ui.viewport.smooth_normals,
+        };
+        if mesher != self.mesher {
+            self.mesher = mesher;
+            self.remesh_all_chunks();
+        }
+
+        // Anti-aliasing level changed (or restored from prefs on the
+        // first frame). An unsupported level falls back; show what the
+        // adapter actually gave us.
+        let requested_msaa = self.ui.viewport.msaa;
+        if let Some(renderer) = self.renderer.as_mut() {
+            if renderer.msaa != requested_msaa {
+                let applied = renderer.set_msaa(requested_msaa);
+                if applied != requested_msaa {
+                    self.ui.viewport.msaa = applied;
+                    self.ui.set_status(format!(
+                        "{} isn't supported on this GPU; using {}",
+                        requested_msaa.label(),
+                        applied.label()
+                    ));
+                }
+            }
+        }
+
+        // Wireframe toggled: edge buffers are only built while it's on,
+        // so turning it on re-meshes every chunk to get them.
+        let wants_edges = self.ui.viewport.wireframe_mode;
+        if let Some(renderer) = self.renderer.as_mut() {
+            if renderer.edge_buffers != wants_edges {
+                renderer.set_edge_buffers(wants_edges);
+                if wants_edges {
+                    self.remesh_all_chunks();
+                }
+            }
+        }
+
+        // Background edited, or restored with a project. The image is
+        // decoded only when its file changes, on a later frame once the
+        // status bar has said so.
+        if self.applied_background.as_ref() != Some(&self.ui.background) {
+            let background = self.ui.background.clone();
+            let image = background.hdri_path.clone().filter(|_| background.uses_image());
+            if image != self.background_image {
+                self.background_image.clone_from(&image);
+                match image {
+                    Some(path) => self.load_background_image(path),
+                    None => {
+                        if let Some(renderer) = self.renderer.as_mut() {
+                            renderer.set_environment_map(None);
+                        }
+                    }
+                }
+            }
+            if let Some(renderer) = self.renderer.as_mut() {
+                renderer.set_background(&background);
+            }
+            if self.applied_background.is_some() {
+                self.unsaved_changes = true;
+            }
+            self.applied_background = Some(background);
+        }
+
+        // Lights edited, selected or their markers toggled.
+        let lights_state = (
+            self.ui.lights.clone(),
+            self.ui.selected_light,
+            self.ui.viewport.show_lights,
+        );
+        if self.applied_lights.as_ref() != Some(&lights_state) {
+            if let Some(renderer) = self.renderer.as_mut() {
+                let (lights, selected, show) = &lights_state;
+                renderer.pipeline.update_lights(&renderer.queue, lights);
+                if *show {
+                    renderer.set_light_mesh(lights, *selected);
+                } else {
+                    renderer.clear_lights();
+                }
+            }
+            if let Some((lights, _, _)) = &self.applied_lights {
+                if *lights != lights_state.0 {
+                    self.unsaved_changes = true;
+                }
+            }
+            self.applied_lights = Some(lights_state);
+        }
+
+        // Reference planes edited, or restored with a project. New image
+        // files are decoded together on a later frame, once no other
+        // file task is waiting (a project's background image may be).
+        if self.pending_file_task.is_none() {
+            let mut missing = Vec::new();
+            for reference in &self.ui.references {
+                if self.reference_images.insert(reference.path.clone()) {
+                    missing.push(reference.path.clone());
+                }
+            }
+            if !missing.is_empty() {
+                self.load_reference_images(missing);
+            }
+        }
+        if self.applied_references.as_ref() != Some(&self.ui.references) {
+            let references = self.ui.references.clone();
+            self.reference_images
+                .retain(|path| references.iter().any(|r| &r.path == path));
+            if let Some(renderer) = self.renderer.as_mut() {
+                renderer.set_reference_planes(&references);
+            }
+            if self.applied_references.is_some() {
+                self.unsaved_changes = true;
+            }
+            self.applied_references = Some(references);
+        }
+
+        // Ghost model edited, or restored with a project. Its file is
+        // read once, on a later frame; edits re-place the mesh already
+        // read.
+        let model_file = self.ui.reference_model.as_ref().map(|m| m.path.clone());
+        if model_file != self.reference_model_file && self.pending_file_task.is_none() {
+            self.reference_model_file.clone_from(&model_file);
+            self.reference_model_source = None;
+            if let Some(path) = model_file {
+                self.load_reference_model(path);
+            }
+        }
+        if self.applied_reference_model.as_ref() != Some(&self.ui.reference_model) {
+            let model = self.ui.reference_model.clone();
+            if let Some(renderer) = self.renderer.as_mut() {
+                match (&model, &self.reference_model_source) {
+                    (Some(m), Some((path, source))) if m.visible && &m.path == path => {
+                        renderer.set_ghost_model_mesh(&ghost_mesh(source, m));
+                    }
+                    _ => renderer.clear_ghost_model(),
+                }
+            }
+            if self.applied_reference_model.is_some() {
+                self.unsaved_changes = true;
+            }
+            self.applied_reference_model = Some(model);
+        }
+
         // Snapshot viewport settings before borrowing renderer mutably
         let show_grid = self.ui.viewport.show_grid;
         let show_axes = self.ui.viewport.show_axes;
         let grid_size = self.ui.viewport.grid_size;
         let grid_spacing = self.ui.viewport.grid_spacing;
         let wireframe_mode = self.ui.viewport.wireframe_mode;
+        let wireframe_shaded = self.ui.viewport.wireframe_shaded;
+        let quad_view = self.ui.viewport.quad_view;
+        let path_trace = self.ui.viewport.path_trace;
+        let path_trace_settings = self.ui.viewport.path_trace_settings.clone();
+        let gi_enabled = self.ui.viewport.gi;
+        let gi_settings = self.ui.viewport.gi_settings.clone();
+        let y_clip = self.ui.viewport.y_clip_max();
+        let toon = self.ui.viewport.toon.then(|| self.ui.viewport.toon_settings.clone());
 
         let renderer = self.renderer.as_mut().unwrap();
+        renderer.pipeline.set_y_clip(y_clip);
+        renderer.set_toon(toon.as_ref());
 
         // Refresh grid mesh if settings changed
         if grid_size != self.last_grid_size
@@ -60,7 +285,10 @@ impl App {
         }
         let egui_renderer = self.egui_renderer.as_mut().unwrap();
 
-        // Update camera (WASD movement etc.)
+        // Update camera (WASD movement, running tween etc.)
+        renderer.camera_controller.tween_duration = self.ui.viewport.camera_tween_secs;
+        renderer.camera_controller.sensitivity_scale = self.ui.general.camera;
+        renderer.camera_controller.tween_easing = self.ui.viewport.camera_easing;
         renderer.camera_controller.update(&mut renderer.camera, dt);
 
         let output = match renderer.surface.get_current_texture() {
@@ -80,96 +308,171 @@ impl App {
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        renderer
-            .pipeline
-            .update_camera(&renderer.queue, &renderer.camera);
-
-        let mut encoder = renderer
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
-            });
-
-        // Main pass: grid → axes → voxels
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Main Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.1,
-                            b: 0.15,
-                            a: 1.0,
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &renderer.depth_texture,
-                    depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                    stencil_ops: None,
-                }),
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-
-            if show_grid {
-                renderer.draw_grid(&mut render_pass);
-            }
-            if show_axes {
-                renderer.draw_axes(&mut render_pass);
+        if path_trace {
+            // Re-pack the world whenever a mesh upload says it changed.
+            // An empty scene just shows the background; an oversized
+            // one says why in the status bar (once per change).
+            let revision = renderer.scene_revision;
+            if renderer.path_tracer_mut().scene_revision != Some(revision) {
+                let volume = VoxelVolume::from_world(self.scene.view(), PATH_TRACE_MAX_CELLS);
+                let device = renderer.device.clone();
+                let tracer = renderer.path_tracer_mut();
+                match volume {
+                    Ok(volume) => tracer.upload_volume(&device, &volume),
+                    Err(e) => {
+                        tracer.clear_volume();
+                        if e != VolumeError::Empty {
+                            self.ui.set_status(format!("Path trace: {}", e));
+                        }
+                    }
+                }
+                tracer.scene_revision = Some(revision);
             }
 
-            let use_wireframe =
-                wireframe_mode && renderer.pipeline.wireframe_pipeline.is_some();
-            if use_wireframe {
-                render_pass
-                    .set_pipeline(renderer.pipeline.wireframe_pipeline.as_ref().unwrap());
-            } else {
-                render_pass.set_pipeline(&renderer.pipeline.render_pipeline);
+            let tracer = renderer.path_tracer.as_mut().unwrap();
+            let mut encoder =
+                renderer
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("Path Trace Encoder"),
+                    });
+            tracer.trace(&renderer.queue, &mut encoder, &renderer.camera, &path_trace_settings);
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Path Present Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(CLEAR_COLOR),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                tracer.present(&mut render_pass);
             }
-            render_pass.set_bind_group(0, &renderer.pipeline.camera_bind_group, &[]);
+            renderer.queue.submit(std::iter::once(encoder.finish()));
+        }
 
-            for mesh in renderer.chunk_meshes.values() {
-                mesh.draw(&mut render_pass);
+        // GI volume: rebuilt when the scene or the step count changed
+        // since the last build, throttled during continuous edits. The
+        // uniform goes out every frame (intensity 0 when off) and is
+        // shared by every pane.
+        let gi = &mut renderer.pipeline.gi;
+        if gi_enabled && !path_trace {
+            let key = (renderer.scene_revision, gi_settings.propagation_steps);
+            let due = gi
+                .built_at
+                .is_none_or(|t| t.elapsed() >= GI_REBUILD_INTERVAL);
+            if gi.built_for != Some(key) && due {
+                match LightVolume::build(self.scene.view(), gi_settings.propagation_steps) {
+                    Some(volume) => gi.upload(&renderer.device, &renderer.queue, &volume),
+                    None => gi.clear(),
+                }
+                gi.built_for = Some(key);
+                gi.built_at = Some(Instant::now());
             }
-
-            // Box-selection wireframe (yellow AABB). Drawn after
-            // opaque chunks but before the translucent overlays so
-            // brush hover hints stay readable on top of the selection.
-            // Uses `LinePipeline` (depth-test on, depth-write off) —
-            // the wireframe is correctly occluded by intervening
-            // voxels, matching how Goxel renders its selection.
-            renderer.draw_selection(&mut render_pass);
-
-            // Socket gizmos (magenta attachment-point pins). Same line
-            // pipeline + depth rules as the selection wireframe, so a
-            // socket tucked behind solid voxels is occluded too.
-            renderer.draw_socket(&mut render_pass);
-
-            // Procgen preview overlay (alpha-blended). Drawn after
-            // opaque chunks so the depth buffer already correctly
-            // gates it; the transparent pipeline reads but does not
-            // write depth so multiple translucent fragments composite.
-            renderer.draw_preview(&mut render_pass);
-
-            // Brush hover overlay — shows where the next click will
-            // land. Same transparent-pipeline rules.
-            renderer.draw_brush_preview(&mut render_pass);
-
-            // Move-drag voxel ghost — the selection's content trailing
-            // the cursor while it's relocated. Same transparent rules;
-            // during a move drag the brush hover slot above is empty
-            // (Select tool), so the two never fight for the frame.
-            renderer.draw_move_ghost(&mut render_pass);
+        }
+        let gi_intensity = if gi_enabled { gi_settings.intensity } else { 0.0 };
+        gi.write_params(&renderer.queue, gi_intensity);
+
+        // Main pass: grid → axes → voxels, once per pane. Single view
+        // is one full-surface pane driven by the editor camera.
+        //
+        // Each pane gets its own encoder + submit because the camera
+        // uniform is a single buffer: `queue.write_buffer` lands at the
+        // start of the *next* submit, so write → submit per pane keeps
+        // every pane's draws paired with its own view-projection. Only
+        // the first pane clears color (a clear ignores the scissor and
+        // would wipe the panes already drawn); depth is cleared per
+        // pane since a finished pane never reads it again.
+        let panes: Vec<Option<ViewPane>> = if path_trace {
+            Vec::new()
+        } else if quad_view {
+            ViewPane::ALL.iter().copied().map(Some).collect()
+        } else {
+            vec![None]
+        };
+        for (i, pane) in panes.into_iter().enumerate() {
+            let camera = match pane {
+                Some(p) => renderer.pane_camera(p),
+                None => renderer.camera.clone(),
+            };
+            renderer.pipeline.update_camera(&renderer.queue, &camera);
+
+            let mut encoder =
+                renderer
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("Render Encoder"),
+                    });
+            {
+                let load = if i == 0 {
+                    wgpu::LoadOp::Clear(renderer.clear_color())
+                } else {
+                    wgpu::LoadOp::Load
+                };
+                // With MSAA the panes draw into the multisampled target
+                // (stored, so later panes can load it) and each pass
+                // resolves the whole target into the surface.
+                let (target, resolve_target) = renderer.color_target(&view);
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Main Render Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: target,
+                        resolve_target,
+                        ops: wgpu::Operations {
+                            load,
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: &renderer.depth_texture,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(1.0),
+                            store: wgpu::StoreOp::Store,
+                        }),
+                        stencil_ops: None,
+                    }),
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                if let Some(p) = pane {
+                    let [x, y, w, h] = p.rect(renderer.config.width, renderer.config.height);
+                    render_pass.set_viewport(
+                        x as f32, y as f32, w as f32, h as f32, 0.0, 1.0,
+                    );
+                    render_pass.set_scissor_rect(x, y, w, h);
+                }
+
+                draw_scene(
+                    renderer,
+                    &mut render_pass,
+                    &camera,
+                    show_grid,
+                    show_axes,
+                    wireframe_mode,
+                    wireframe_shaded,
+                );
+            }
+            // Toon outlines read this pane's depth, so they go before
+            // the next pane clears it.
+            let (width, height) = (renderer.config.width, renderer.config.height);
+            let rect = pane.map_or([0, 0, width, height], |p| p.rect(width, height));
+            renderer.draw_toon_outline(&mut encoder, &view, &camera, rect);
+            renderer.queue.submit(std::iter::once(encoder.finish()));
         }
 
+        let mut encoder = renderer
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Egui Encoder"),
+            });
+
         // egui overlay pass
         let screen_descriptor = egui_wgpu::ScreenDescriptor {
             size_in_pixels: [renderer.config.width, renderer.config.height],
@@ -220,5 +523,123 @@ impl App {
 
         renderer.queue.submit(std::iter::once(encoder.finish()));
         output.present();
+
+        // GPU picking: the ID buffer for the cursor pixel, read back by
+        // `tick_gpu_pick` on a later frame.
+        if let Some(request) = self.gpu_pick_request() {
+            self.renderer.as_mut().unwrap().pick(request);
+        }
     }
 }
+
+/// Record the scene's draw calls into `render_pass`: grid, axes, opaque
+/// chunk meshes, line overlays, translucent voxels (sorted for
+/// `camera`), then the translucent overlays. Shared by the single view
+/// and every quad-view pane, so all panes show the same overlays.
+fn draw_scene<'a>(
+    renderer: &'a Renderer,
+    render_pass: &mut wgpu::RenderPass<'a>,
+    camera: &Camera,
+    show_grid: bool,
+    show_axes: bool,
+    wireframe_mode: bool,
+    wireframe_shaded: bool,
+) {
+    renderer.draw_background(render_pass, camera);
+    if show_grid {
+        renderer.draw_grid(render_pass);
+    }
+    if show_axes {
+        renderer.draw_axes(render_pass);
+    }
+
+    // Lines-only wireframe replaces the shaded meshes (translucent
+    // range included). Otherwise the opaque range goes here, the
+    // translucent one after the line overlays below, and in "shaded
+    // with edges" the dark edge overlay right on top of the opaque pass.
+    let lines_only = wireframe_mode && !wireframe_shaded;
+    if lines_only {
+        renderer.draw_chunk_edges(render_pass, false);
+    } else {
+        renderer.draw_opaque_chunks(render_pass);
+        if wireframe_mode {
+            renderer.draw_chunk_edges(render_pass, true);
+        }
+    }
+
+    // Box-selection wireframe (yellow AABB). Drawn after
+    // opaque chunks but before the translucent overlays so
+    // brush hover hints stay readable on top of the selection.
+    // Uses `LinePipeline` (depth-test on, depth-write off) —
+    // the wireframe is correctly occluded by intervening
+    // voxels, matching how Goxel renders its selection.
+    renderer.draw_selection(render_pass);
+
+    // World bounds (blue box). Same line pipeline and depth rules.
+    renderer.draw_bounds(render_pass);
+
+    // Measure ruler (green). Same line pipeline and depth rules.
+    renderer.draw_measure(render_pass);
+
+    // Socket gizmos (magenta attachment-point pins). Same line
+    // pipeline + depth rules as the selection wireframe, so a
+    // socket tucked behind solid voxels is occluded too.
+    renderer.draw_socket(render_pass);
+
+    // Light markers. Same line pipeline and depth rules.
+    renderer.draw_lights(render_pass);
+
+    // Reference image planes: alpha-blended, depth-tested without
+    // writing depth, so voxels in front hide them and they never hide
+    // the translucent voxels drawn next.
+    renderer.draw_references(render_pass);
+
+    // Translucent voxels (glass, water): alpha-blended, no depth
+    // writes, chunks back to front. After every opaque draw so what
+    // sits behind them is already in the color and depth buffers.
+    if !lines_only {
+        renderer.draw_transparent_chunks(render_pass, camera);
+    }
+
+    // Work-plane sheet and grid. After the translucent voxels so
+    // glass on the slice doesn't hide the grid behind it, before the
+    // hover tint and brush ghost so those read on top of the sheet.
+    renderer.draw_work_plane(render_pass);
+
+    // Hovered-voxel outline + hit-face tint. Its translucent face
+    // goes before the brush ghost so a Place ghost sitting on that
+    // face composites over the tint, not under it.
+    renderer.draw_hover(render_pass);
+
+    // Ghost reference model: see-through, never depth-written, so the
+    // edit hints below still read through it.
+    renderer.draw_ghost_model(render_pass);
+
+    // Onion skin — the neighboring animation frames as tinted ghosts.
+    // Drawn first of the translucent overlays so the edit hints below
+    // composite over it.
+    renderer.draw_onion_skin(render_pass);
+
+    // Rig pose preview — where the posed bones' voxels would land.
+    renderer.draw_pose_preview(render_pass);
+
+    // Procgen preview overlay (alpha-blended). Drawn after
+    // opaque chunks so the depth buffer already correctly
+    // gates it; the transparent pipeline reads but does not
+    // write depth so multiple translucent fragments composite.
+    renderer.draw_preview(render_pass);
+
+    // Brush hover overlay — shows where the next click will
+    // land. Same transparent-pipeline rules.
+    renderer.draw_brush_preview(render_pass);
+
+    // Move-drag voxel ghost — the selection's content trailing
+    // the cursor while it's relocated. Same transparent rules;
+    // during a move drag the brush hover slot above is empty
+    // (Select tool), so the two never fight for the frame.
+    renderer.draw_move_ghost(render_pass);
+
+    // Move gizmo, last: drawn over everything so its handles can be
+    // grabbed wherever the target sits.
+    renderer.draw_gizmo(render_pass);
+}
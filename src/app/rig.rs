@@ -0,0 +1,162 @@
+//! Rigging: the Rig panel's actions and the pose-preview overlay.
+//!
+//! Bones are document data on `editor.rig` (like the sockets), so none
+//! of these actions go through the undo history except Bake, which is
+//! an ordinary voxel edit of the active layer. Bones are built from the
+//! active layer's voxels; the preview and the rigged export read the
+//! composited view.
+
+use voxelith::editor::{next_bone_name, Cell, Command, SelectionMask};
+use voxelith::mesh::patch_to_mesh;
+use voxelith::ui::UiAction;
+
+use super::App;
+
+/// Alpha of the pose-preview ghost: solid enough to judge the pose,
+/// see-through enough to show the rest voxels it replaces.
+const POSE_PREVIEW_ALPHA: f32 = 0.6;
+
+/// Posed voxels beyond this are left out of the preview, as with
+/// `ONION_SKIN_MAX_VOXELS`.
+const POSE_PREVIEW_MAX_VOXELS: usize = 200_000;
+
+impl App {
+    /// The active layer's solid voxels inside the selection (its wand
+    /// mask, when one is in effect). Empty without a selection.
+    fn selected_solid_cells(&self) -> Vec<Cell> {
+        let Some(selection) = self.editor.selection else {
+            return Vec::new();
+        };
+        let mask = self.editor.active_mask();
+        let world = self.scene.active_world();
+        selection
+            .iter_cells()
+            .filter(|&c| mask.is_none_or(|m| m.contains(c)))
+            .filter(|&(x, y, z)| world.get_voxel(x, y, z).is_solid())
+            .collect()
+    }
+
+    /// Apply one of the Rig panel's actions; other actions are ignored
+    /// (`handle_ui_action` only routes rig actions here).
+    pub(super) fn handle_rig_action(&mut self, action: UiAction) {
+        match action {
+            UiAction::AddBone => {
+                let cells = self.selected_solid_cells();
+                let Some(selection) = self.editor.selection.filter(|_| !cells.is_empty()) else {
+                    self.ui
+                        .set_status("Add Bone: select voxels on the active layer first");
+                    return;
+                };
+                // Center of the selection box, on the cell-corner lattice.
+                let center = |lo: i32, hi: i32| (lo + hi + 1).div_euclid(2);
+                let pivot = [
+                    center(selection.min.0, selection.max.0),
+                    center(selection.min.1, selection.max.1),
+                    center(selection.min.2, selection.max.2),
+                ];
+                let name = next_bone_name(&self.editor.rig);
+                let count = cells.len();
+                let index = self.editor.rig.add_bone(name.clone(), pivot, cells);
+                self.ui.state.selected_bone = Some(index);
+                self.ui
+                    .set_status(format!("Added bone \"{name}\" ({count} voxels)"));
+            }
+            UiAction::AssignSelectionToBone(index) => {
+                let cells = self.selected_solid_cells();
+                if cells.is_empty() {
+                    self.ui
+                        .set_status("Assign: select voxels on the active layer first");
+                    return;
+                }
+                self.editor.rig.assign(index, cells);
+            }
+            UiAction::SelectBoneVoxels(index) => {
+                let Some(bone) = self.editor.rig.bones().get(index) else {
+                    return;
+                };
+                let Some(mask) = SelectionMask::new(bone.cells().iter().copied().collect()) else {
+                    self.ui
+                        .set_status(format!("Bone \"{}\" has no voxels", bone.name));
+                    return;
+                };
+                self.ui
+                    .set_status(format!("Selected {} voxels", mask.len()));
+                self.editor.selection = Some(mask.bounds());
+                self.editor.selection_mask = Some(mask);
+                // Nothing else to mark: selection isn't document data.
+                return;
+            }
+            UiAction::RenameBone(index, name) => self.editor.rig.rename(index, name),
+            UiAction::SetBoneParent(index, parent) => {
+                if !self.editor.rig.set_parent(index, parent) {
+                    self.ui
+                        .set_status("A bone can't be parented to itself or its children");
+                    return;
+                }
+            }
+            UiAction::SetBonePivot(index, pivot) => self.editor.rig.set_pivot(index, pivot),
+            UiAction::SetBonePose(index, pose) => self.editor.rig.set_pose(index, pose),
+            UiAction::RemoveBone(index) => {
+                let Some(bone) = self.editor.rig.remove_bone(index) else {
+                    return;
+                };
+                self.ui.state.selected_bone = None;
+                self.ui
+                    .set_status(format!("Deleted bone \"{}\"", bone.name));
+            }
+            UiAction::ResetPose => self.editor.rig.reset_pose(),
+            UiAction::BakePose => {
+                if !self.editor.rig.is_posed() {
+                    return;
+                }
+                if self.editor.history.is_locked() {
+                    self.ui
+                        .set_status(format!("Layer \"{}\" is locked", self.scene.active().name));
+                    return;
+                }
+                let changes = self.editor.rig.bake(self.scene.active_world());
+                let count = changes.len();
+                if count > 0 {
+                    let command = Command::set_voxels(changes).named("Bake Pose");
+                    self.editor
+                        .history
+                        .execute(command, self.scene.active_world_mut());
+                }
+                self.ui
+                    .set_status(format!("Baked pose ({count} voxels changed)"));
+            }
+            _ => return,
+        }
+        self.pose_preview_stale = true;
+        self.unsaved_changes = true;
+    }
+
+    /// Rebuild or clear the pose-preview ghost: every posed bone's
+    /// voxels from the view at their posed positions.
+    pub(super) fn update_pose_preview(&mut self) {
+        let enabled = self.ui.state.pose_preview && self.editor.rig.is_posed();
+        let Some(renderer) = &mut self.renderer else {
+            return;
+        };
+        if !enabled {
+            if renderer.pose_preview_mesh.is_some() {
+                renderer.clear_pose_preview();
+            }
+            // Rebuild from scratch the next time it's needed.
+            self.pose_preview_stale = true;
+            return;
+        }
+        if !self.pose_preview_stale {
+            return;
+        }
+        self.pose_preview_stale = false;
+
+        let mut voxels = self.editor.rig.posed_voxels(self.scene.view());
+        voxels.truncate(POSE_PREVIEW_MAX_VOXELS);
+        if voxels.is_empty() {
+            renderer.clear_pose_preview();
+        } else {
+            renderer.set_pose_preview_mesh(&patch_to_mesh(&voxels, POSE_PREVIEW_ALPHA));
+        }
+    }
+}
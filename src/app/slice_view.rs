@@ -0,0 +1,103 @@
+//! App side of the Slice Editor window (`ui::slice_view`): sampling
+//! the work-plane slice for it, and running its paint / fill / pick
+//! requests through the same tools and undo history as the viewport.
+
+use voxelith::editor::{eyedrop, BrushTool, EditorTool, ToolContext};
+use voxelith::ui::SliceImage;
+
+use super::App;
+
+impl App {
+    /// Re-sample the Slice Editor's grid when the window is open and
+    /// the voxels, the slice, or the grid extent changed. Samples the
+    /// composited view, so hidden layers drop out as in the viewport.
+    pub(super) fn refresh_slice_view(&mut self) {
+        if !self.ui.state.show_slice_view {
+            return;
+        }
+        let viewport = &self.ui.viewport;
+        let half = ((viewport.grid_size as f32 * viewport.grid_spacing / 2.0).ceil() as i32).max(1);
+        let plane = self.editor.work_plane;
+        let current = self
+            .ui
+            .slice_image
+            .as_ref()
+            .is_some_and(|image| image.plane == plane && image.half == half);
+        if current && !self.slice_view_stale {
+            return;
+        }
+        let view = self.scene.view();
+        self.ui.slice_image = Some(SliceImage::new(plane, half, |(x, y, z)| {
+            let voxel = view.get_voxel(x, y, z);
+            (!voxel.is_air()).then(|| voxel.color())
+        }));
+        self.slice_view_stale = false;
+    }
+
+    /// One Slice Editor brush dab: the current brush tool applied at
+    /// each of `cells`, clipped to the slice whether or not work-plane
+    /// mode is on. `begin` opens a new undo stroke; the dabs after it
+    /// merge into that entry until the window reports the release.
+    pub(super) fn slice_paint(&mut self, cells: &[(i32, i32, i32)], begin: bool) {
+        if begin {
+            self.editor.history.begin_stroke();
+        }
+        if self.editor.history.is_locked() {
+            self.ui
+                .set_status(format!("Layer \"{}\" is locked", self.scene.active().name));
+            return;
+        }
+        let plane = self.editor.work_plane;
+        let brush = BrushTool::new(self.editor.current_tool);
+        let mut ctx = ToolContext {
+            world: self.scene.active_world_mut(),
+            history: &mut self.editor.history,
+            brush_color: self.editor.brush_color,
+            brush_size: self.editor.brush_size,
+            symmetry: self.editor.symmetry,
+            paint: self.editor.paint,
+            brush: self.editor.brush.clone(),
+            sculpt: self.editor.sculpt,
+            work_plane: Some(plane),
+        };
+        for &cell in cells {
+            brush.apply(&mut ctx, &plane.cell_hit(cell));
+        }
+    }
+
+    /// Slice Editor flood fill from `cell`, kept to the slice. Like the
+    /// viewport Fill, an air seed is refused rather than flooding the
+    /// empty part of the slice.
+    pub(super) fn slice_fill(&mut self, cell: (i32, i32, i32)) {
+        if self.editor.history.is_locked() {
+            self.ui
+                .set_status(format!("Layer \"{}\" is locked", self.scene.active().name));
+            return;
+        }
+        if self
+            .scene
+            .active_world()
+            .get_voxel(cell.0, cell.1, cell.2)
+            .is_air()
+        {
+            return;
+        }
+        let plane = self.editor.work_plane;
+        let symmetry = self.editor.symmetry;
+        let seeds = symmetry
+            .mirror_positions(cell)
+            .into_iter()
+            .zip(symmetry.mirror_normals(plane.cell_hit(cell).normal))
+            .collect();
+        self.fill_from(seeds, Some(plane));
+    }
+
+    /// Slice Editor eyedropper: take `cell`'s visible color as the
+    /// brush. Air leaves the brush alone.
+    pub(super) fn slice_pick(&mut self, cell: (i32, i32, i32)) {
+        let hit = self.editor.work_plane.cell_hit(cell);
+        if let Some(color) = eyedrop(self.scene.view(), &hit) {
+            self.editor.brush_color = color;
+        }
+    }
+}
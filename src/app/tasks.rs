@@ -0,0 +1,157 @@
+//! Background task bookkeeping for the status-bar indicator.
+//!
+//! `collect_tasks` condenses every piece of running work — procgen /
+//! graph jobs, the mesh worker's current batch, turntable renders, AI
+//! jobs — into the `TaskStatus` rows the UI draws, with the
+//! `UiAction` that cancels each one where cancelling is supported.
+//!
+//! Project saves and autosaves are written on their own thread and get
+//! a row while they run. Imports, exports and project opens still run
+//! on the main thread: the scene, the mesh worker and the renderer all
+//! have to be swapped together. `defer_file_task` holds one back until
+//! the frame after it first appears in the task list, so the status
+//! bar names the file before the window stalls on it instead of
+//! freezing with no word.
+
+use std::time::Duration;
+
+use voxelith::ai::AiJobState;
+use voxelith::ui::{GenJobStatus, TaskKind, TaskStatus, UiAction};
+
+use super::file_ops::SaveKind;
+use super::App;
+
+/// Mesh batches shorter than this don't get a row: a single-chunk
+/// brush edit re-meshes every frame, and a task that flickers in and
+/// out of the status bar is noise.
+const MESHING_TASK_DELAY: Duration = Duration::from_millis(300);
+
+/// An import / export / project open waiting for its turn.
+pub(super) struct FileTask {
+    label: String,
+    /// Included in the task list drawn last frame.
+    shown: bool,
+    run: Box<dyn FnOnce(&mut App)>,
+}
+
+impl App {
+    /// Run `run` at the start of the frame after next, once the status
+    /// bar has shown `label`. Replaces any file task still waiting —
+    /// only one dialog can be open at a time, so that only happens if
+    /// two were picked within a frame.
+    pub(super) fn defer_file_task(&mut self, label: String, run: impl FnOnce(&mut App) + 'static) {
+        self.pending_file_task = Some(FileTask {
+            label,
+            shown: false,
+            run: Box::new(run),
+        });
+    }
+
+    /// Start of frame: run the deferred file task if the UI has had a
+    /// frame to announce it.
+    pub(super) fn run_pending_file_task(&mut self) {
+        if self
+            .pending_file_task
+            .as_ref()
+            .is_some_and(|task| task.shown)
+        {
+            if let Some(task) = self.pending_file_task.take() {
+                (task.run)(self);
+            }
+        }
+    }
+
+    /// Everything running right now, for `Ui::tasks`. Expects the
+    /// `gen_job` / `turntable_job` / `ai_job` mirrors on `self.ui` to
+    /// be current for this frame.
+    pub(super) fn collect_tasks(&mut self) -> Vec<TaskStatus> {
+        let mut tasks = Vec::new();
+        if let Some(task) = &mut self.pending_file_task {
+            task.shown = true;
+            tasks.push(TaskStatus {
+                kind: TaskKind::File,
+                label: task.label.clone(),
+                progress: None,
+                detail: None,
+                cancel: None,
+            });
+        }
+        if let Some(pending) = &self.background_save {
+            let label = match pending.kind {
+                SaveKind::Autosave => "Autosaving".to_string(),
+                SaveKind::Project { .. } => {
+                    let path = pending.save.path();
+                    let name = path.file_name().unwrap_or(path.as_os_str());
+                    format!("Saving {}", name.to_string_lossy())
+                }
+            };
+            tasks.push(TaskStatus {
+                kind: TaskKind::File,
+                label,
+                progress: None,
+                detail: None,
+                cancel: None,
+            });
+        }
+        if let Some(job) = &self.ui.gen_job {
+            tasks.push(job_task(
+                TaskKind::Generation,
+                job,
+                UiAction::CancelGeneration,
+            ));
+        }
+        if let Some(job) = &self.ui.turntable_job {
+            tasks.push(job_task(
+                TaskKind::Turntable,
+                job,
+                UiAction::CancelTurntable,
+            ));
+        }
+        let ai = match &self.ui.ai_job {
+            AiJobState::Submitting => Some(("Submitting", None)),
+            AiJobState::Polling { progress } => Some(("Generating", Some(*progress))),
+            AiJobState::Voxelizing => Some(("Voxelizing", None)),
+            _ => None,
+        };
+        if let Some((stage, progress)) = ai {
+            tasks.push(TaskStatus {
+                kind: TaskKind::Ai,
+                label: format!("AI: {stage}"),
+                progress,
+                detail: None,
+                cancel: Some(UiAction::AiCancel),
+            });
+        }
+        if let Some((elapsed, delivered, pending)) = self.mesh_worker.batch_progress() {
+            if elapsed >= MESHING_TASK_DELAY {
+                let total = delivered + pending;
+                tasks.push(TaskStatus {
+                    kind: TaskKind::Meshing,
+                    label: "Meshing".to_string(),
+                    progress: Some(delivered as f32 / total.max(1) as f32),
+                    detail: Some(format!("{delivered} / {total} chunks")),
+                    cancel: None,
+                });
+            }
+        }
+        tasks
+    }
+}
+
+/// Row for a procgen or turntable job: its progress, ETA and Cancel.
+fn job_task(kind: TaskKind, job: &GenJobStatus, cancel: UiAction) -> TaskStatus {
+    let detail = match job.remaining {
+        _ if job.cancelling => Some("Cancelling…".to_string()),
+        Some(left) if left.as_secs_f32() >= 1.0 => {
+            Some(format!("~{:.0}s left", left.as_secs_f32().ceil()))
+        }
+        _ => None,
+    };
+    TaskStatus {
+        kind,
+        label: job.label.clone(),
+        progress: Some(job.progress),
+        detail,
+        cancel: (!job.cancelling).then_some(cancel),
+    }
+}
@@ -0,0 +1,219 @@
+//! Render ▸ Turntable: spin the camera 360° around the model and write
+//! the frames out as a PNG sequence or an animated GIF.
+//!
+//! Rendering needs the GPU, so it runs on the main thread — one frame
+//! per app frame (`tick_turntable`), which keeps the window responsive
+//! and the progress bar moving, and makes Cancel take effect before
+//! the next frame. Each frame goes through `Renderer::render_offscreen`,
+//! framed by `render::turntable_camera` from the editor camera's
+//! direction at the moment Render was clicked.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+use voxelith::prefs::FileDirKind;
+use voxelith::render::{turntable_camera, Camera, OffscreenStyle};
+use voxelith::ui::{GenJobStatus, TurntableFormat, TurntableSettings};
+
+use super::App;
+
+/// Where finished frames go.
+enum FrameSink {
+    /// `turntable_0000.png`, ... in `dir`.
+    Png { dir: PathBuf },
+    /// Frames appended to an open GIF; the trailer is written when the
+    /// encoder drops.
+    Gif {
+        path: PathBuf,
+        encoder: GifEncoder<BufWriter<File>>,
+    },
+}
+
+/// A turntable render in progress.
+pub(super) struct TurntableJob {
+    settings: TurntableSettings,
+    /// Model AABB (inclusive voxel coords) captured at start.
+    bounds: (glam::Vec3, glam::Vec3),
+    /// Editor camera at start: the spin begins from its direction.
+    main: Camera,
+    /// Frames written so far.
+    done: u32,
+    sink: FrameSink,
+    started: Instant,
+}
+
+impl TurntableJob {
+    fn write_frame(&mut self, image: RgbaImage) -> Result<(), String> {
+        match &mut self.sink {
+            FrameSink::Png { dir } => {
+                let path = dir.join(frame_file_name(self.done));
+                image
+                    .save_with_format(&path, image::ImageFormat::Png)
+                    .map_err(|e| format!("couldn't write {}: {}", path.display(), e))
+            }
+            FrameSink::Gif { encoder, .. } => {
+                let delay = Delay::from_numer_denom_ms(1000, self.settings.fps.max(1));
+                encoder
+                    .encode_frame(Frame::from_parts(image, 0, 0, delay))
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /// Drop the job, deleting a half-written GIF (a truncated one
+    /// wouldn't loop). PNG frames already written are kept.
+    fn abort(self) {
+        if let FrameSink::Gif { path, encoder } = self.sink {
+            drop(encoder);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Where the output went, for the status bar.
+    fn output_label(&self) -> String {
+        match &self.sink {
+            FrameSink::Png { dir } => dir.display().to_string(),
+            FrameSink::Gif { path, .. } => path.display().to_string(),
+        }
+    }
+}
+
+/// File name of sequence frame `index`. Zero-padded so the frames sort
+/// in order in file browsers and video editors' image-sequence import.
+fn frame_file_name(index: u32) -> String {
+    format!("turntable_{:04}.png", index)
+}
+
+impl App {
+    /// Ask for the output location and start a turntable render with
+    /// the Turntable window's settings. No-op while one is running.
+    pub(super) fn start_turntable(&mut self) {
+        if self.turntable.is_some() {
+            return;
+        }
+        let Some((min, max)) = self.scene.view().scene_aabb() else {
+            self.ui.set_status("World is empty — nothing to render");
+            return;
+        };
+        let Some(renderer) = &self.renderer else {
+            return;
+        };
+        let main = renderer.camera.clone();
+        let settings = self.ui.turntable.clone();
+        let sink = match settings.format {
+            TurntableFormat::PngSequence => {
+                let Some(dir) = self
+                    .file_dialog(FileDirKind::Render)
+                    .set_title("Turntable Output Folder")
+                    .pick_folder()
+                else {
+                    return;
+                };
+                self.prefs.last_dirs.remember_folder(FileDirKind::Render, &dir);
+                FrameSink::Png { dir }
+            }
+            TurntableFormat::Gif => {
+                let Some(path) = self
+                    .file_dialog(FileDirKind::Render)
+                    .add_filter("Animated GIF", &["gif"])
+                    .set_file_name("turntable.gif")
+                    .set_title("Save Turntable GIF")
+                    .save_file()
+                else {
+                    return;
+                };
+                self.remember_dir(FileDirKind::Render, &path);
+                let file = match File::create(&path) {
+                    Ok(file) => file,
+                    Err(e) => {
+                        self.show_write_error("Turntable Failed", &path, "create", &e);
+                        return;
+                    }
+                };
+                let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), 10);
+                if let Err(e) = encoder.set_repeat(Repeat::Infinite) {
+                    self.show_write_error("Turntable Failed", &path, "write", &e);
+                    return;
+                }
+                FrameSink::Gif { path, encoder }
+            }
+        };
+        let to_vec = |(x, y, z): (i32, i32, i32)| glam::Vec3::new(x as f32, y as f32, z as f32);
+        self.turntable = Some(TurntableJob {
+            settings,
+            bounds: (to_vec(min), to_vec(max)),
+            main,
+            done: 0,
+            sink,
+            started: Instant::now(),
+        });
+    }
+
+    /// Stop the running turntable render.
+    pub(super) fn cancel_turntable(&mut self) {
+        if let Some(job) = self.turntable.take() {
+            let done = job.done;
+            job.abort();
+            self.ui
+                .set_status(format!("Turntable cancelled after {} frames", done));
+        }
+    }
+
+    /// Render and write the next turntable frame; finish the job after
+    /// the last. Called every frame from `RedrawRequested`.
+    pub(super) fn tick_turntable(&mut self) {
+        let (Some(job), Some(renderer)) = (&mut self.turntable, &mut self.renderer) else {
+            return;
+        };
+        let TurntableSettings {
+            width,
+            height,
+            frames,
+            ..
+        } = job.settings;
+        let angle = std::f32::consts::TAU * job.done as f32 / frames as f32;
+        let (min, max) = job.bounds;
+        let camera = turntable_camera(&job.main, min, max, width as f32 / height as f32, angle);
+        let written = match renderer.render_offscreen(&camera, width, height, OffscreenStyle::default()) {
+            Some(image) => job.write_frame(image),
+            None => Err(format!("the GPU couldn't render a {}×{} frame", width, height)),
+        };
+        if let Err(e) = written {
+            log::error!("Turntable failed: {}", e);
+            if let Some(job) = self.turntable.take() {
+                job.abort();
+            }
+            self.show_error_dialog("Turntable Failed", &format!("Rendering stopped: {}.", e));
+            return;
+        }
+        job.done += 1;
+        if job.done >= frames {
+            if let Some(job) = self.turntable.take() {
+                let output = job.output_label();
+                // Dropping the job drops the GIF encoder, which writes
+                // the trailer.
+                drop(job);
+                self.ui
+                    .set_status(format!("Turntable saved: {} frames → {}", frames, output));
+            }
+        }
+    }
+
+    /// Snapshot of the running render for the Turntable window.
+    pub(super) fn turntable_status(&self) -> Option<GenJobStatus> {
+        let job = self.turntable.as_ref()?;
+        let progress = job.done as f32 / job.settings.frames.max(1) as f32;
+        let elapsed = job.started.elapsed();
+        Some(GenJobStatus {
+            label: format!("Turntable frame {} / {}", job.done, job.settings.frames),
+            progress,
+            cancelling: false,
+            remaining: (job.done > 0).then(|| elapsed.div_f32(progress).saturating_sub(elapsed)),
+        })
+    }
+}
+
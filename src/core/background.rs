@@ -0,0 +1,78 @@
+//! The viewport background behind the model, saved with the project.
+//!
+//! A solid color, a vertical gradient, or an equirectangular ("HDRI")
+//! image wrapped around the scene. The image can also light the model:
+//! the renderer folds it into a per-direction ambient color, so a warm
+//! sunset sky tints the tops of the voxels and a dark floor leaves their
+//! undersides dim. Only the image's path is stored; the pixels stay in
+//! their own file.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Which kind of background is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BackgroundKind {
+    #[default]
+    Solid,
+    /// `top` at the top of the view blending to `bottom` at the bottom.
+    Gradient,
+    /// Equirectangular image around the scene.
+    Hdri,
+}
+
+impl BackgroundKind {
+    pub const ALL: [BackgroundKind; 3] = [Self::Solid, Self::Gradient, Self::Hdri];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Solid => "Solid Color",
+            Self::Gradient => "Gradient",
+            Self::Hdri => "HDRI Image",
+        }
+    }
+}
+
+/// Background settings. Colors are linear RGB.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Background {
+    pub kind: BackgroundKind,
+    /// `Solid` color; also what shows while an image is loading or
+    /// failed to load.
+    pub color: [f32; 3],
+    pub top: [f32; 3],
+    pub bottom: [f32; 3],
+    /// Equirectangular image for `Hdri` (Radiance `.hdr`, PNG or JPEG).
+    pub hdri_path: Option<PathBuf>,
+    /// Brightness multiplier for the image, as seen and as light.
+    pub hdri_intensity: f32,
+    /// Turn of the image around the vertical axis, in degrees.
+    pub hdri_rotation: f32,
+    /// Light the model with the image instead of the flat ambient.
+    pub hdri_lighting: bool,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self {
+            kind: BackgroundKind::Solid,
+            // The editor's long-standing dark blue.
+            color: [0.1, 0.1, 0.15],
+            top: [0.25, 0.35, 0.55],
+            bottom: [0.05, 0.05, 0.08],
+            hdri_path: None,
+            hdri_intensity: 1.0,
+            hdri_rotation: 0.0,
+            hdri_lighting: true,
+        }
+    }
+}
+
+impl Background {
+    /// Whether the image should be loaded and drawn.
+    pub fn uses_image(&self) -> bool {
+        self.kind == BackgroundKind::Hdri && self.hdri_path.is_some()
+    }
+}
@@ -2,10 +2,34 @@
 //!
 //! Chunks are the basic unit of voxel storage and rendering.
 //! They provide efficient spatial access and modification of voxels.
+//!
+//! A flat 32³ array of 8-byte voxels is 256 KB, yet most chunks hold
+//! a handful of distinct voxels — often just air, or air plus a few
+//! terrain colors. Storage is therefore compact and picks its own
+//! representation behind `get` / `set`:
+//!
+//! - **Uniform**: one voxel for the whole chunk, no allocation. Every
+//!   new chunk starts here, and so does the inside of solid ground.
+//! - **Palette**: the distinct voxels in a list plus a 1, 2, 4 or 8-bit
+//!   palette index per cell, packed into `u64` words (4–32 KB).
+//! - **Dense**: the flat array, once a chunk needs more than
+//!   [`MAX_PALETTE`] distinct voxels (textured AI imports, gradients).
+//!
+//! A write that doesn't fit the current palette first drops entries
+//! no cell uses any more, then widens the indices (or goes dense).
+//! Palettes never shrink on their own; [`Chunk::compact`] repacks a
+//! chunk to its smallest form after bulk edits.
+//!
+//! Streaming (see [`super::streaming`]) adds a fourth, **cold** form:
+//! the voxels run-length encoded in RAM or in a spill file, decoded
+//! again on the first read and thawed for good by the first write.
 
-use super::Voxel;
+use super::{SpillFile, Voxel};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io;
 use std::ops::Index;
+use std::sync::{Arc, OnceLock};
 
 // Note: Chunk does not derive Serialize/Deserialize because of the large voxel array.
 // Custom serialization will be implemented in the io module.
@@ -123,14 +147,280 @@ impl LocalPos {
     }
 }
 
+/// Most distinct voxels a palette-packed chunk holds (8-bit indices).
+/// Past this a chunk stores its voxels flat: wider indices plus a big
+/// palette save little, and every write would search the palette.
+pub const MAX_PALETTE: usize = 256;
+
+/// Palette indices for a chunk, `bits` (1, 2, 4 or 8) per cell. Widths
+/// divide 64, so an index never straddles two words.
+#[derive(Clone)]
+struct Palette {
+    voxels: Vec<Voxel>,
+    bits: u32,
+    words: Box<[u64]>,
+}
+
+impl Palette {
+    /// All cells pointing at entry 0.
+    fn new(voxels: Vec<Voxel>, bits: u32) -> Self {
+        Self {
+            voxels,
+            bits,
+            words: vec![0; CHUNK_VOLUME * bits as usize / 64].into_boxed_slice(),
+        }
+    }
+
+    /// Smallest index width addressing `entries`, or `None` past
+    /// [`MAX_PALETTE`].
+    fn bits_for(entries: usize) -> Option<u32> {
+        [1, 2, 4, 8].into_iter().find(|&bits| entries <= 1 << bits)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        1 << self.bits
+    }
+
+    #[inline]
+    fn index(&self, i: usize) -> usize {
+        let bit = i * self.bits as usize;
+        let mask = (1u64 << self.bits) - 1;
+        ((self.words[bit / 64] >> (bit % 64)) & mask) as usize
+    }
+
+    #[inline]
+    fn set_index(&mut self, i: usize, entry: usize) {
+        let bit = i * self.bits as usize;
+        let mask = (1u64 << self.bits) - 1;
+        let word = &mut self.words[bit / 64];
+        *word = (*word & !(mask << (bit % 64))) | ((entry as u64) << (bit % 64));
+    }
+}
+
+/// Bytes per run in a cold chunk's encoding: a `u16` length, then the
+/// voxel.
+const RUN_BYTES: usize = 2 + std::mem::size_of::<Voxel>();
+
+/// Where a cold chunk's encoded voxels are kept.
+#[derive(Clone)]
+enum ColdSource {
+    Memory(Box<[u8]>),
+    Spilled {
+        file: Arc<SpillFile>,
+        offset: u64,
+        len: usize,
+    },
+}
+
+/// A chunk cooled by streaming: its encoded voxels, plus their decoded
+/// form once something reads them (dropped again by the next
+/// [`Chunk::cool`]). A decode that failed is remembered as `None`
+/// until then, so reads don't retry it cell by cell.
+#[derive(Clone)]
+struct Cold {
+    source: ColdSource,
+    warm: OnceLock<Option<Storage>>,
+}
+
+impl Cold {
+    /// The decoded voxels, decoding them on first use; `None` if they
+    /// can't be decoded.
+    fn warm(&self) -> Option<&Storage> {
+        self.warm
+            .get_or_init(|| {
+                self.decode()
+                    .map_err(|e| log::error!("Couldn't thaw a cold chunk: {}", e))
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    /// The decoded voxels, to become the chunk's storage again; `None`
+    /// (leaving the chunk cold) if they can't be decoded.
+    fn thaw(&mut self) -> Option<Storage> {
+        self.warm()?;
+        self.warm.take().flatten()
+    }
+
+    /// Decode the source. Fails if the spill file can't be read back or
+    /// the encoding is corrupt.
+    fn decode(&self) -> io::Result<Storage> {
+        let bytes = match &self.source {
+            ColdSource::Memory(bytes) => Cow::Borrowed(&bytes[..]),
+            ColdSource::Spilled { file, offset, len } => Cow::Owned(file.read(*offset, *len)?),
+        };
+        decode_runs(&bytes).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "cold chunk data is corrupt")
+        })
+    }
+}
+
+/// Run-length encode `storage` in x-fastest cell order.
+fn encode_runs(storage: &Storage) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut i = 0;
+    while i < CHUNK_VOLUME {
+        let voxel = *storage.get(i);
+        let start = i;
+        while i < CHUNK_VOLUME && *storage.get(i) == voxel {
+            i += 1;
+        }
+        // A run is at most CHUNK_VOLUME (32768) cells long.
+        bytes.extend_from_slice(&((i - start) as u16).to_le_bytes());
+        bytes.extend_from_slice(bytemuck::bytes_of(&voxel));
+    }
+    bytes
+}
+
+/// Inverse of [`encode_runs`], packed to the smallest storage; `None`
+/// unless the runs cover the chunk exactly.
+fn decode_runs(bytes: &[u8]) -> Option<Storage> {
+    if !bytes.len().is_multiple_of(RUN_BYTES) {
+        return None;
+    }
+    let mut voxels = Vec::with_capacity(CHUNK_VOLUME);
+    for run in bytes.chunks_exact(RUN_BYTES) {
+        let count = u16::from_le_bytes([run[0], run[1]]) as usize;
+        let voxel: Voxel = bytemuck::pod_read_unaligned(&run[2..]);
+        if voxels.len() + count > CHUNK_VOLUME {
+            return None;
+        }
+        voxels.resize(voxels.len() + count, voxel);
+    }
+    (voxels.len() == CHUNK_VOLUME).then(|| Storage::Dense(voxels.into()).packed(false))
+}
+
+/// How a chunk's voxels are held; see the module docs.
+#[derive(Clone)]
+enum Storage {
+    Uniform(Voxel),
+    Palette(Palette),
+    Dense(Box<[Voxel]>),
+    Cold(Box<Cold>),
+}
+
+impl Storage {
+    #[inline]
+    fn get(&self, i: usize) -> &Voxel {
+        match self {
+            Storage::Uniform(voxel) => voxel,
+            Storage::Palette(palette) => &palette.voxels[palette.index(i)],
+            Storage::Dense(voxels) => &voxels[i],
+            Storage::Cold(cold) => cold.warm().map_or(&Voxel::AIR, |warm| warm.get(i)),
+        }
+    }
+
+    /// Write `voxel` at cell `i` and return the voxel it replaced. A
+    /// cold chunk that can't be decoded drops the write (returning
+    /// `voxel`) and keeps its encoded voxels.
+    fn set(&mut self, i: usize, voxel: Voxel) -> Voxel {
+        if let Storage::Cold(cold) = self {
+            match cold.thaw() {
+                Some(warm) => *self = warm,
+                None => return voxel,
+            }
+        }
+        let old = *self.get(i);
+        if old == voxel {
+            return old;
+        }
+        match self {
+            Storage::Uniform(_) => {
+                let mut palette = Palette::new(vec![old, voxel], 1);
+                palette.set_index(i, 1);
+                *self = Storage::Palette(palette);
+            }
+            Storage::Palette(palette) => {
+                let entry = match palette.voxels.iter().position(|v| *v == voxel) {
+                    Some(entry) => Some(entry),
+                    None if palette.voxels.len() < palette.capacity() => {
+                        palette.voxels.push(voxel);
+                        Some(palette.voxels.len() - 1)
+                    }
+                    None => None,
+                };
+                match entry {
+                    Some(entry) => palette.set_index(i, entry),
+                    None => {
+                        // Full: drop stale entries, leaving a quarter
+                        // spare so the next new voxels don't repack
+                        // again right away.
+                        *self = self.packed(true);
+                        self.set(i, voxel);
+                    }
+                }
+            }
+            Storage::Dense(voxels) => voxels[i] = voxel,
+            Storage::Cold(_) => unreachable!("thawed above"),
+        }
+        old
+    }
+
+    /// The same voxels in their smallest representation, with room for
+    /// a quarter more palette entries when `grow` is set.
+    fn packed(&self, grow: bool) -> Storage {
+        let mut voxels: Vec<Voxel> = Vec::new();
+        for i in 0..CHUNK_VOLUME {
+            let voxel = *self.get(i);
+            if !voxels.contains(&voxel) {
+                if voxels.len() == MAX_PALETTE {
+                    return Storage::Dense((0..CHUNK_VOLUME).map(|i| *self.get(i)).collect());
+                }
+                voxels.push(voxel);
+            }
+        }
+        if voxels.len() == 1 {
+            return Storage::Uniform(voxels[0]);
+        }
+        let entries = if grow {
+            (voxels.len() + voxels.len() / 4 + 1)
+                .min(MAX_PALETTE)
+                .max(voxels.len() + 1)
+        } else {
+            voxels.len()
+        };
+        let Some(bits) = Palette::bits_for(entries) else {
+            return Storage::Dense((0..CHUNK_VOLUME).map(|i| *self.get(i)).collect());
+        };
+        let mut palette = Palette::new(voxels, bits);
+        for i in 0..CHUNK_VOLUME {
+            let voxel = self.get(i);
+            let entry = palette.voxels.iter().position(|v| v == voxel).unwrap_or(0);
+            palette.set_index(i, entry);
+        }
+        Storage::Palette(palette)
+    }
+
+    fn heap_bytes(&self) -> usize {
+        match self {
+            Storage::Uniform(_) => 0,
+            Storage::Palette(palette) => {
+                palette.voxels.capacity() * std::mem::size_of::<Voxel>()
+                    + palette.words.len() * std::mem::size_of::<u64>()
+            }
+            Storage::Dense(voxels) => std::mem::size_of_val(&**voxels),
+            Storage::Cold(cold) => {
+                let encoded = match &cold.source {
+                    ColdSource::Memory(bytes) => bytes.len(),
+                    ColdSource::Spilled { .. } => 0,
+                };
+                let warm = cold.warm.get().and_then(Option::as_ref);
+                encoded + warm.map_or(0, Storage::heap_bytes)
+            }
+        }
+    }
+}
+
 /// A chunk containing a 3D grid of voxels.
 ///
-/// Voxels are stored in a flat array for cache efficiency.
-/// Layout: x + y*SIZE + z*SIZE*SIZE (x varies fastest)
+/// Cells are addressed in x + y*SIZE + z*SIZE*SIZE order (x varies
+/// fastest); the voxels themselves sit in compact storage (see the
+/// module docs).
 #[derive(Clone)]
 pub struct Chunk {
-    /// Flat array of voxels (using Vec for serde compatibility)
-    voxels: Vec<Voxel>,
+    /// Uniform, palette-packed, flat or cold voxels
+    storage: Storage,
     /// Number of non-air voxels (for quick empty check)
     solid_count: u32,
     /// Flag indicating mesh needs rebuild
@@ -147,7 +437,7 @@ impl Chunk {
     /// Create a new empty chunk (all air)
     pub fn new() -> Self {
         Self {
-            voxels: vec![Voxel::AIR; CHUNK_VOLUME],
+            storage: Storage::Uniform(Voxel::AIR),
             solid_count: 0,
             dirty: true,
         }
@@ -161,7 +451,7 @@ impl Chunk {
             0
         };
         Self {
-            voxels: vec![voxel; CHUNK_VOLUME],
+            storage: Storage::Uniform(voxel),
             solid_count,
             dirty: true,
         }
@@ -207,19 +497,14 @@ impl Chunk {
     #[inline]
     pub fn get(&self, x: usize, y: usize, z: usize) -> Voxel {
         debug_assert!(x < CHUNK_SIZE && y < CHUNK_SIZE && z < CHUNK_SIZE);
-        self.voxels[x + y * CHUNK_SIZE + z * CHUNK_SIZE * CHUNK_SIZE]
+        *self.storage.get(x + y * CHUNK_SIZE + z * CHUNK_SIZE * CHUNK_SIZE)
     }
 
     /// Get voxel at local position (safe version with bounds check)
     #[inline]
     pub fn get_safe(&self, x: i32, y: i32, z: i32) -> Option<Voxel> {
-        if x >= 0
-            && x < CHUNK_SIZE_I32
-            && y >= 0
-            && y < CHUNK_SIZE_I32
-            && z >= 0
-            && z < CHUNK_SIZE_I32
-        {
+        let range = 0..CHUNK_SIZE_I32;
+        if range.contains(&x) && range.contains(&y) && range.contains(&z) {
             Some(self.get(x as usize, y as usize, z as usize))
         } else {
             None
@@ -231,7 +516,7 @@ impl Chunk {
     pub fn set(&mut self, x: usize, y: usize, z: usize, voxel: Voxel) {
         debug_assert!(x < CHUNK_SIZE && y < CHUNK_SIZE && z < CHUNK_SIZE);
         let index = x + y * CHUNK_SIZE + z * CHUNK_SIZE * CHUNK_SIZE;
-        let old = &mut self.voxels[index];
+        let old = self.storage.set(index, voxel);
 
         // Update solid count
         if old.is_solid() && voxel.is_air() {
@@ -240,21 +525,85 @@ impl Chunk {
             self.solid_count += 1;
         }
 
-        *old = voxel;
         self.dirty = true;
     }
 
-    /// Get raw voxel slice (for mesh generation)
-    #[inline]
-    pub fn voxels(&self) -> &[Voxel] {
-        &self.voxels
+    /// Repack the voxels into their smallest representation, dropping
+    /// palette entries left behind by edits. Doesn't change any voxel
+    /// or the dirty flag. Cold chunks are left as they are.
+    pub fn compact(&mut self) {
+        if !self.is_cold() {
+            self.storage = self.storage.packed(false);
+        }
+    }
+
+    /// Whether the voxels are in the streaming cold form ([`Self::cool`]).
+    pub fn is_cold(&self) -> bool {
+        matches!(self.storage, Storage::Cold(_))
+    }
+
+    /// Whether this cold chunk failed to decode when last read: its
+    /// voxels read as air and writes to it are dropped, but the encoded
+    /// voxels are kept, and the next [`Self::cool`] lets a read retry.
+    /// Saving such a chunk would write the air over its real contents.
+    pub fn is_unreadable(&self) -> bool {
+        match &self.storage {
+            Storage::Cold(cold) => matches!(cold.warm.get(), Some(None)),
+            _ => false,
+        }
+    }
+
+    /// Move the voxels into the cold form: run-length encoded, appended
+    /// to `spill` if given, else kept in RAM. An already cold chunk
+    /// just drops its decoded copy (and moves to `spill`). Uniform
+    /// chunks cost nothing already and stay as they are, as do chunks
+    /// the encoding wouldn't make smaller. Returns whether anything
+    /// changed; fails only when writing `spill` does.
+    pub fn cool(&mut self, spill: Option<&Arc<SpillFile>>) -> io::Result<bool> {
+        let bytes = match &mut self.storage {
+            Storage::Uniform(_) => return Ok(false),
+            Storage::Cold(cold) => {
+                let was_warm = cold.warm.take().is_some();
+                match (&cold.source, spill) {
+                    (ColdSource::Memory(bytes), Some(_)) => bytes.to_vec(),
+                    _ => return Ok(was_warm),
+                }
+            }
+            storage => {
+                let bytes = encode_runs(storage);
+                if spill.is_none() && bytes.len() >= storage.heap_bytes() {
+                    return Ok(false);
+                }
+                bytes
+            }
+        };
+        let source = match spill {
+            Some(file) => {
+                let offset = file.append(&bytes)?;
+                ColdSource::Spilled {
+                    file: Arc::clone(file),
+                    offset,
+                    len: bytes.len(),
+                }
+            }
+            None => ColdSource::Memory(bytes.into_boxed_slice()),
+        };
+        self.storage = Storage::Cold(Box::new(Cold {
+            source,
+            warm: OnceLock::new(),
+        }));
+        Ok(true)
+    }
+
+    /// Heap memory held by the voxel storage, in bytes (0 for a
+    /// uniform chunk, 256 KB for a flat one).
+    pub fn heap_bytes(&self) -> usize {
+        self.storage.heap_bytes()
     }
 
     /// Iterate over all voxels with their positions
     pub fn iter_voxels(&self) -> impl Iterator<Item = (LocalPos, &Voxel)> {
-        self.voxels.iter().enumerate().map(|(i, v)| {
-            (LocalPos::from_index(i), v)
-        })
+        (0..CHUNK_VOLUME).map(|i| (LocalPos::from_index(i), self.storage.get(i)))
     }
 
     /// Iterate over all solid voxels with their positions
@@ -284,7 +633,7 @@ impl Index<LocalPos> for Chunk {
 
     #[inline]
     fn index(&self, pos: LocalPos) -> &Self::Output {
-        &self.voxels[pos.to_index()]
+        self.storage.get(pos.to_index())
     }
 }
 
@@ -297,7 +646,7 @@ impl Index<(usize, usize, usize)> for Chunk {
 
     #[inline]
     fn index(&self, (x, y, z): (usize, usize, usize)) -> &Self::Output {
-        &self.voxels[x + y * CHUNK_SIZE + z * CHUNK_SIZE * CHUNK_SIZE]
+        self.storage.get(x + y * CHUNK_SIZE + z * CHUNK_SIZE * CHUNK_SIZE)
     }
 }
 
@@ -332,6 +681,161 @@ mod tests {
         assert_eq!(chunk.solid_count(), 0);
     }
 
+    #[test]
+    fn storage_matches_a_flat_array_through_every_representation() {
+        let mut chunk = Chunk::new();
+        let mut reference = vec![Voxel::AIR; CHUNK_VOLUME];
+        let mut seed = 0x9e37_79b9u32;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize
+        };
+        // Widen one distinct voxel at a time: uniform, then 1 → 8-bit
+        // palettes, then flat once the palette is exhausted.
+        for colors in [2, 3, 5, 17, 200, MAX_PALETTE + 40] {
+            for _ in 0..4000 {
+                let i = next() % CHUNK_VOLUME;
+                let c = next() % colors;
+                let voxel = if c == 0 {
+                    Voxel::AIR
+                } else {
+                    Voxel::from_rgb(c as u8, (c >> 8) as u8, 7)
+                };
+                let pos = LocalPos::from_index(i);
+                chunk.set(pos.x as usize, pos.y as usize, pos.z as usize, voxel);
+                reference[i] = voxel;
+            }
+            for (i, expected) in reference.iter().enumerate() {
+                assert_eq!(chunk[LocalPos::from_index(i)], *expected);
+            }
+            let solid = reference.iter().filter(|v| v.is_solid()).count();
+            assert_eq!(chunk.solid_count() as usize, solid);
+        }
+        assert_eq!(chunk.heap_bytes(), 262144);
+
+        // Clearing back to a couple of voxels and compacting shrinks
+        // the storage again without touching a voxel.
+        for (i, slot) in reference.iter_mut().enumerate() {
+            let pos = LocalPos::from_index(i);
+            let voxel = if pos.y < 4 { Voxel::from_rgb(90, 60, 30) } else { Voxel::AIR };
+            chunk.set(pos.x as usize, pos.y as usize, pos.z as usize, voxel);
+            *slot = voxel;
+        }
+        chunk.compact();
+        assert!(chunk.heap_bytes() < 8192, "{} bytes", chunk.heap_bytes());
+        assert!(chunk.iter_voxels().all(|(pos, v)| *v == reference[pos.to_index()]));
+    }
+
+    #[test]
+    fn typical_chunks_stay_far_below_a_flat_array() {
+        assert_eq!(Chunk::new().heap_bytes(), 0);
+        assert_eq!(Chunk::filled(Voxel::from_rgb(120, 120, 120)).heap_bytes(), 0);
+
+        // Terrain: stone, dirt, grass and air → a 2-bit palette.
+        let mut chunk = Chunk::new();
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let top = 10 + (x * 7 + z * 3) % 6;
+                for y in 0..=top {
+                    let voxel = match top - y {
+                        0 => Voxel::from_rgb(76, 153, 0),
+                        1..=3 => Voxel::from_rgb(139, 90, 43),
+                        _ => Voxel::from_rgb(128, 128, 128),
+                    };
+                    chunk.set(x, y, z, voxel);
+                }
+            }
+        }
+        assert!(chunk.heap_bytes() <= 8192 + 64, "{} bytes", chunk.heap_bytes());
+
+        // Painting the same cell over and over leaves stale palette
+        // entries behind; repacking on overflow keeps the width down.
+        for c in 0..1000u32 {
+            chunk.set(0, 31, 0, Voxel::from_rgb(c as u8, (c >> 8) as u8, 1));
+        }
+        assert!(chunk.heap_bytes() <= 16384 + 256, "{} bytes", chunk.heap_bytes());
+        assert_eq!(chunk.get(0, 31, 0), Voxel::from_rgb(231, 3, 1));
+    }
+
+    #[test]
+    fn cooled_chunks_read_the_same_and_thaw_on_write() {
+        // Terrain-like: colored strata under air, a few runs per row.
+        let mut chunk = Chunk::new();
+        chunk.fill_region((0, 0, 0), (31, 11, 31), Voxel::from_rgb(90, 60, 30));
+        for x in (0..CHUNK_SIZE).step_by(8) {
+            let grass = Voxel::from_rgb(40, 150 + x as u8, 40);
+            chunk.fill_region((x, 11, 0), (x + 3, 11, 31), grass);
+        }
+        let reference = chunk.clone();
+        let same = |chunk: &Chunk| {
+            (0..CHUNK_VOLUME).all(|i| {
+                let local = LocalPos::from_index(i);
+                chunk[local] == reference[local]
+            })
+        };
+
+        // Uniform chunks have nothing to give back.
+        assert!(!Chunk::filled(Voxel::from_rgb(1, 2, 3)).cool(None).unwrap());
+
+        let resident = chunk.heap_bytes();
+        assert!(chunk.cool(None).unwrap());
+        assert!(chunk.is_cold());
+        assert!(chunk.heap_bytes() < resident);
+        assert!(same(&chunk));
+        assert_eq!(chunk.solid_count(), reference.solid_count());
+        // Reading kept a decoded copy; the next pass drops only that.
+        assert!(chunk.heap_bytes() > resident);
+        assert!(chunk.cool(None).unwrap());
+        assert!(!chunk.cool(None).unwrap());
+
+        let spill = Arc::new(SpillFile::create().unwrap());
+        assert!(chunk.cool(Some(&spill)).unwrap());
+        assert_eq!(chunk.heap_bytes(), 0);
+        assert!(!spill.is_empty());
+        assert!(same(&chunk));
+
+        // A write turns it back into an ordinary chunk.
+        chunk.set(0, 0, 0, Voxel::AIR);
+        assert!(!chunk.is_cold());
+        assert!(chunk[(0, 0, 0)].is_air());
+        assert_eq!(chunk[(1, 11, 0)], reference[(1, 11, 0)]);
+    }
+
+    #[test]
+    fn undecodable_cold_chunks_stay_cold_and_drop_writes() {
+        let stone = Voxel::from_rgb(90, 90, 90);
+        let mut chunk = Chunk::new();
+        chunk.fill_region((0, 0, 0), (31, 7, 31), stone);
+        chunk.set(5, 20, 5, Voxel::from_rgb(200, 0, 0));
+        assert!(chunk.cool(None).unwrap());
+        let Storage::Cold(cold) = &mut chunk.storage else {
+            panic!("chunk should be cold");
+        };
+        let ColdSource::Memory(good) = cold.source.clone() else {
+            panic!("cooled without a spill file");
+        };
+        cold.source = ColdSource::Memory(good[..RUN_BYTES].into());
+
+        assert!(!chunk.is_unreadable());
+        assert!(chunk[(0, 0, 0)].is_air());
+        assert!(chunk.is_unreadable());
+        // The write is dropped rather than thawing the chunk as air.
+        chunk.set(0, 0, 0, Voxel::from_rgb(1, 2, 3));
+        assert!(chunk.is_cold());
+        assert!(chunk.is_unreadable());
+        assert_eq!(chunk.solid_count(), 32 * 32 * 8 + 1);
+
+        // With its data back, the next cooling pass lets a read retry.
+        if let Storage::Cold(cold) = &mut chunk.storage {
+            cold.source = ColdSource::Memory(good);
+        }
+        assert!(chunk.cool(None).unwrap());
+        assert_eq!(chunk[(0, 0, 0)], stone);
+        assert!(!chunk.is_unreadable());
+    }
+
     #[test]
     fn test_chunk_pos_from_world() {
         assert_eq!(ChunkPos::from_world_pos(0, 0, 0), ChunkPos::new(0, 0, 0));
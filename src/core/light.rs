@@ -0,0 +1,163 @@
+//! Point and spot lights placed in the scene, saved with the project.
+//!
+//! They light the realtime view on top of the fixed sun and the
+//! ambient term (see `render::lights` for the GPU side). A light is
+//! plain data in world units; a voxel is one unit.
+
+use serde::{Deserialize, Serialize};
+
+/// Most lights the viewport shader takes. Lights past this, or
+/// switched off, are kept in the project but don't shine.
+pub const MAX_LIGHTS: usize = 16;
+
+/// How a light spreads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum LightKind {
+    /// Shines every way from its position.
+    #[default]
+    Point,
+    /// Shines in a cone around its direction.
+    Spot,
+}
+
+impl LightKind {
+    pub const ALL: [LightKind; 2] = [Self::Point, Self::Spot];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Point => "Point",
+            Self::Spot => "Spot",
+        }
+    }
+}
+
+/// One light. Colors are linear RGB.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Light {
+    pub name: String,
+    pub kind: LightKind,
+    pub enabled: bool,
+    pub position: [f32; 3],
+    /// Where a spot light points; need not be unit length.
+    pub direction: [f32; 3],
+    pub color: [f32; 3],
+    /// Brightness at the light; at 1 a face turned straight toward it
+    /// is lit about as brightly as by the sun.
+    pub intensity: f32,
+    /// Distance at which the light has faded to nothing.
+    pub range: f32,
+    /// Half-angle of a spot light's cone, in degrees.
+    pub spot_angle: f32,
+    /// Fraction of the cone's half-angle, from the rim in, over which a
+    /// spot light fades (0 = hard edge).
+    pub spot_softness: f32,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self {
+            name: "Light".into(),
+            kind: LightKind::Point,
+            enabled: true,
+            position: [0.0, 10.0, 0.0],
+            direction: [0.0, -1.0, 0.0],
+            color: [1.0, 0.9, 0.75],
+            intensity: 1.0,
+            range: 24.0,
+            spot_angle: 30.0,
+            spot_softness: 0.2,
+        }
+    }
+}
+
+impl Light {
+    /// A default `kind` light at `position`.
+    pub fn new(name: impl Into<String>, kind: LightKind, position: [f32; 3]) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            position,
+            ..Self::default()
+        }
+    }
+
+    /// Fraction of the light left at `distance`: 1 at the light, easing
+    /// to 0 at `range` with no hard edge. Must match `light_falloff` in
+    /// `voxel.wgsl`.
+    pub fn falloff(&self, distance: f32) -> f32 {
+        if self.range <= 0.0 {
+            return 0.0;
+        }
+        let x = (distance / self.range).clamp(0.0, 1.0);
+        let window = 1.0 - x * x;
+        window * window
+    }
+
+    /// Cosines of the spot cone's rim and of where its soft edge starts,
+    /// `(outer, inner)`, for the shader's smoothstep. Kept a hair apart
+    /// so a hard edge doesn't divide by zero.
+    pub fn cone_cosines(&self) -> (f32, f32) {
+        let half = self.spot_angle.clamp(1.0, 89.0).to_radians();
+        let inner = half * (1.0 - self.spot_softness.clamp(0.0, 1.0));
+        let outer = half.cos();
+        (outer, inner.cos().max(outer + 1e-4))
+    }
+
+    /// `direction` as a unit vector (straight down if it's zero).
+    pub fn unit_direction(&self) -> [f32; 3] {
+        let [x, y, z] = self.direction;
+        let len = (x * x + y * y + z * z).sqrt();
+        if len < 1e-6 {
+            return [0.0, -1.0, 0.0];
+        }
+        [x / len, y / len, z / len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falloff_fades_smoothly_to_range() {
+        let light = Light::default();
+        assert_eq!(light.falloff(0.0), 1.0);
+        assert!(light.falloff(light.range * 0.5) > 0.5);
+        assert_eq!(light.falloff(light.range), 0.0);
+        assert_eq!(light.falloff(light.range * 2.0), 0.0);
+        let zero = Light {
+            range: 0.0,
+            ..Light::default()
+        };
+        assert_eq!(zero.falloff(0.0), 0.0);
+    }
+
+    #[test]
+    fn cone_cosines_order_and_hard_edge() {
+        let soft = Light::new("Spot", LightKind::Spot, [0.0; 3]);
+        let (outer, inner) = soft.cone_cosines();
+        assert!((outer - 30f32.to_radians().cos()).abs() < 1e-6);
+        assert!(inner > outer);
+        let hard = Light {
+            spot_softness: 0.0,
+            ..soft
+        };
+        let (outer, inner) = hard.cone_cosines();
+        assert!(inner > outer && inner - outer < 1e-3);
+    }
+
+    #[test]
+    fn zero_direction_points_down() {
+        let light = Light {
+            direction: [0.0; 3],
+            ..Light::default()
+        };
+        assert_eq!(light.unit_direction(), [0.0, -1.0, 0.0]);
+        let light = Light {
+            direction: [3.0, 0.0, 4.0],
+            ..Light::default()
+        };
+        assert_eq!(light.unit_direction(), [0.6, 0.0, 0.8]);
+    }
+}
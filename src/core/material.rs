@@ -0,0 +1,353 @@
+//! Named materials: what a voxel's `material` id means.
+//!
+//! Every solid voxel carries a `material` id (1 = the default material).
+//! The [`MaterialTable`] maps ids to a [`MaterialDef`] — a name plus
+//! surface properties — and is saved with the project. The properties
+//! reach the rest of the editor two ways:
+//!
+//! - The viewport shader reads roughness, metallic and emissive strength
+//!   per fragment from a small uniform table indexed by the id
+//!   ([`MaterialTable::shading_table`]), so editing a material reshades
+//!   the model at once without re-meshing.
+//! - Everything that only looks at a voxel (GI, the path tracer, GLB
+//!   export, the transparent pass) keeps reading the voxel's own
+//!   emissive / metallic flags and alpha. [`MaterialTable::stamp`] writes
+//!   a material's properties into those, and [`MaterialTable::restamp`]
+//!   re-applies a changed material to every voxel that uses it.
+//!
+//! Voxels whose flags were set by hand before materials existed keep
+//! them until their material is edited.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Voxel, World};
+
+/// Id of the material new voxels get (`Voxel::from_rgb`). Always present
+/// in a [`MaterialTable`] and can't be removed.
+pub const DEFAULT_MATERIAL: u16 = 1;
+
+/// Slots in the shader's material table. Ids `1..MATERIAL_SLOTS` can be
+/// defined; slot 0 is air.
+pub const MATERIAL_SLOTS: usize = 256;
+
+/// Metallic value from which a material stamps the voxel metallic flag
+/// (what GLB export and the path tracer see).
+const METALLIC_FLAG_THRESHOLD: f32 = 0.5;
+
+/// A named material and its surface properties. All properties are in
+/// `[0, 1]` except `emissive`, a light strength in `[0, MAX_EMISSIVE]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaterialDef {
+    pub name: String,
+    /// 0 = mirror-sharp highlights, 1 = fully matte (no highlight).
+    pub roughness: f32,
+    /// 0 = dielectric, 1 = metal: tinted highlights, dark diffuse.
+    pub metallic: f32,
+    /// Self-illumination added on top of the lit color; 0 = none.
+    pub emissive: f32,
+    /// 0 = opaque, 1 = invisible. Stamped into the voxel alpha.
+    pub transparency: f32,
+}
+
+impl MaterialDef {
+    /// Upper end of the emissive strength slider.
+    pub const MAX_EMISSIVE: f32 = 4.0;
+
+    /// A matte, opaque, non-emissive material called `name` — the look
+    /// voxels had before materials existed.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            roughness: 1.0,
+            metallic: 0.0,
+            emissive: 0.0,
+            transparency: 0.0,
+        }
+    }
+
+    /// Voxel alpha for this material's transparency.
+    pub fn alpha(&self) -> u8 {
+        (255.0 * (1.0 - self.transparency.clamp(0.0, 1.0))).round() as u8
+    }
+
+    /// The parts of the material baked into voxels by
+    /// [`MaterialTable::stamp`]; a change to anything else (name,
+    /// roughness, emissive strength) only needs the shader table.
+    fn stamp_key(&self) -> (bool, bool, Option<u8>) {
+        (
+            self.emissive > 0.0,
+            self.metallic >= METALLIC_FLAG_THRESHOLD,
+            (self.transparency > 0.0).then(|| self.alpha()),
+        )
+    }
+}
+
+impl Default for MaterialDef {
+    fn default() -> Self {
+        Self::new("Default")
+    }
+}
+
+/// Material definitions by id. Always holds [`DEFAULT_MATERIAL`]; ids
+/// without a definition resolve to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<u16, MaterialDef>", into = "BTreeMap<u16, MaterialDef>")]
+pub struct MaterialTable {
+    materials: BTreeMap<u16, MaterialDef>,
+}
+
+impl Default for MaterialTable {
+    fn default() -> Self {
+        Self::from(BTreeMap::new())
+    }
+}
+
+impl From<BTreeMap<u16, MaterialDef>> for MaterialTable {
+    /// Drops ids outside `1..MATERIAL_SLOTS` and adds the default
+    /// material if missing, so a hand-edited project still loads.
+    fn from(mut materials: BTreeMap<u16, MaterialDef>) -> Self {
+        materials.retain(|&id, _| id != 0 && (id as usize) < MATERIAL_SLOTS);
+        materials.entry(DEFAULT_MATERIAL).or_default();
+        Self { materials }
+    }
+}
+
+impl From<MaterialTable> for BTreeMap<u16, MaterialDef> {
+    fn from(table: MaterialTable) -> Self {
+        table.materials
+    }
+}
+
+impl MaterialTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of defined materials (at least 1).
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    /// Always false: the default material is always defined.
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    /// Materials in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &MaterialDef)> {
+        self.materials.iter().map(|(&id, def)| (id, def))
+    }
+
+    pub fn contains(&self, id: u16) -> bool {
+        self.materials.contains_key(&id)
+    }
+
+    pub fn get(&self, id: u16) -> Option<&MaterialDef> {
+        self.materials.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: u16) -> Option<&mut MaterialDef> {
+        self.materials.get_mut(&id)
+    }
+
+    /// The definition voxels with `id` are drawn with: its own, or the
+    /// default material's when `id` isn't defined.
+    pub fn resolve(&self, id: u16) -> &MaterialDef {
+        self.materials
+            .get(&id)
+            .or_else(|| self.materials.get(&DEFAULT_MATERIAL))
+            .expect("material table always holds the default material")
+    }
+
+    /// Define `def` under the lowest free id. `None` when every slot is
+    /// taken.
+    pub fn add(&mut self, def: MaterialDef) -> Option<u16> {
+        let id = (DEFAULT_MATERIAL..MATERIAL_SLOTS as u16).find(|id| !self.materials.contains_key(id))?;
+        self.materials.insert(id, def);
+        Some(id)
+    }
+
+    /// Remove material `id`. Its voxels fall back to the default
+    /// material on the next [`Self::restamp`]. The default material
+    /// itself can't be removed.
+    pub fn remove(&mut self, id: u16) -> Option<MaterialDef> {
+        if id == DEFAULT_MATERIAL {
+            return None;
+        }
+        self.materials.remove(&id)
+    }
+
+    /// `voxel` with its material's properties written into the flags
+    /// and alpha: emissive when the strength is above zero, metallic
+    /// from [`METALLIC_FLAG_THRESHOLD`], and — for a translucent
+    /// material only — the alpha, so an opaque material leaves a
+    /// hand-picked alpha alone. An undefined id becomes
+    /// [`DEFAULT_MATERIAL`]. Air is returned unchanged.
+    pub fn stamp(&self, mut voxel: Voxel) -> Voxel {
+        if voxel.is_air() {
+            return voxel;
+        }
+        if !self.contains(voxel.material) {
+            voxel.material = DEFAULT_MATERIAL;
+        }
+        let (emissive, metallic, alpha) = self.resolve(voxel.material).stamp_key();
+        voxel.set_emissive(emissive);
+        voxel.set_metallic(metallic);
+        if let Some(alpha) = alpha {
+            voxel.a = alpha;
+        }
+        voxel
+    }
+
+    /// Ids whose stamped properties differ between `previous` and this
+    /// table, including ids that were added or removed.
+    pub fn changed_ids(&self, previous: &MaterialTable) -> Vec<u16> {
+        let key = |table: &MaterialTable, id| table.get(id).map(MaterialDef::stamp_key);
+        let mut ids: Vec<u16> = self
+            .materials
+            .keys()
+            .chain(previous.materials.keys())
+            .copied()
+            .filter(|&id| key(self, id) != key(previous, id))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Re-stamp every voxel of `world` whose material changed since
+    /// `previous` (see [`Self::changed_ids`]). A material that stops
+    /// being translucent makes its voxels opaque again rather than
+    /// leaving them at the old alpha. Returns whether anything was
+    /// looked at; written chunks are marked dirty as usual.
+    pub fn restamp(&self, previous: &MaterialTable, world: &mut World) -> bool {
+        let ids = self.changed_ids(previous);
+        if ids.is_empty() {
+            return false;
+        }
+        let Some(bounds) = world.bounding_box() else {
+            return false;
+        };
+        world.par_apply_region(bounds.min, bounds.max, |_, voxel| {
+            if voxel.is_air() || ids.binary_search(&voxel.material).is_err() {
+                return voxel;
+            }
+            let mut voxel = voxel;
+            if previous.resolve(voxel.material).transparency > 0.0
+                && self.resolve(voxel.material).transparency <= 0.0
+            {
+                voxel.a = 255;
+            }
+            self.stamp(voxel)
+        });
+        true
+    }
+
+    /// Per-slot shading properties for the viewport shader:
+    /// `[roughness, metallic, emissive, transparency]`, undefined slots
+    /// holding the default material's.
+    pub fn shading_table(&self) -> Vec<[f32; 4]> {
+        (0..MATERIAL_SLOTS)
+            .map(|id| {
+                let def = self.resolve(id as u16);
+                [def.roughness, def.metallic, def.emissive, def.transparency]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_keeps_the_default_material() {
+        let mut table = MaterialTable::new();
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.remove(DEFAULT_MATERIAL), None);
+        let glass = table.add(MaterialDef::new("Glass")).unwrap();
+        assert_eq!(glass, 2);
+        assert_eq!(table.resolve(99).name, "Default");
+        assert!(table.remove(glass).is_some());
+        assert_eq!(table.add(MaterialDef::new("Metal")), Some(2), "freed id reused");
+
+        // Round-trips through JSON, and a table missing the default
+        // gets it back on load.
+        let json = serde_json::to_string(&table).unwrap();
+        let back: MaterialTable = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, table);
+        let partial: MaterialTable = serde_json::from_str(r#"{"0":{},"7":{"name":"Lava"}}"#).unwrap();
+        assert_eq!(partial.iter().map(|(id, _)| id).collect::<Vec<_>>(), vec![1, 7]);
+        assert_eq!(partial.get(7).unwrap().roughness, 1.0, "missing fields default");
+    }
+
+    #[test]
+    fn stamp_writes_flags_and_alpha() {
+        let mut table = MaterialTable::new();
+        let lamp = table
+            .add(MaterialDef {
+                emissive: 2.0,
+                metallic: 0.8,
+                transparency: 0.5,
+                ..MaterialDef::new("Lamp")
+            })
+            .unwrap();
+        let voxel = table.stamp(Voxel::new(lamp, 255, 200, 100));
+        assert!(voxel.is_emissive() && voxel.is_metallic());
+        assert_eq!(voxel.a, 128);
+        // The default material is opaque: a hand-picked alpha stays.
+        let glassy = table.stamp(Voxel::from_rgba(10, 20, 30, 90));
+        assert_eq!(glassy.a, 90);
+        assert!(!glassy.is_emissive());
+        // Unknown ids fall back to the default material; air is left be.
+        assert_eq!(table.stamp(Voxel::new(42, 1, 2, 3)).material, DEFAULT_MATERIAL);
+        assert_eq!(table.stamp(Voxel::AIR), Voxel::AIR);
+        assert_eq!(table.shading_table()[lamp as usize], [1.0, 0.8, 2.0, 0.5]);
+        assert_eq!(table.shading_table().len(), MATERIAL_SLOTS);
+    }
+
+    #[test]
+    fn restamp_updates_only_changed_materials() {
+        let mut table = MaterialTable::new();
+        let glass = table
+            .add(MaterialDef {
+                transparency: 0.75,
+                ..MaterialDef::new("Glass")
+            })
+            .unwrap();
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, table.stamp(Voxel::new(glass, 200, 220, 255)));
+        world.set_voxel(40, 0, 0, Voxel::from_rgba(1, 2, 3, 100));
+
+        // Renaming doesn't touch voxels.
+        let before = table.clone();
+        table.get_mut(glass).unwrap().name = "Clear glass".into();
+        assert!(table.changed_ids(&before).is_empty());
+        assert!(!table.restamp(&before, &mut world));
+
+        // Going opaque and metallic rewrites the glass voxels only.
+        let before = table.clone();
+        let def = table.get_mut(glass).unwrap();
+        def.transparency = 0.0;
+        def.metallic = 1.0;
+        assert_eq!(table.changed_ids(&before), vec![glass]);
+        world.clear_dirty_flags();
+        assert!(table.restamp(&before, &mut world));
+        let voxel = world.get_voxel(0, 0, 0);
+        assert_eq!(voxel.a, 255);
+        assert!(voxel.is_metallic());
+        assert_eq!(world.get_voxel(40, 0, 0).a, 100);
+        assert!(!world.dirty_chunks().is_empty());
+
+        // Removing the material moves its voxels to the default one.
+        let before = table.clone();
+        table.remove(glass);
+        table.restamp(&before, &mut world);
+        let voxel = world.get_voxel(0, 0, 0);
+        assert_eq!(voxel.material, DEFAULT_MATERIAL);
+        assert!(!voxel.is_metallic());
+    }
+}
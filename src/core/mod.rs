@@ -4,11 +4,32 @@
 //! - `Voxel`: Individual voxel data (material, color)
 //! - `Chunk`: Fixed-size 3D grid of voxels
 //! - `World`: Collection of chunks with spatial indexing
+//! - `MaterialTable`: What each voxel material id means (name, shading)
+//! - `Background`: The viewport background saved with the project
+//! - `Light`: Point and spot lights saved with the project
+//! - `ReferenceImage` / `ReferenceModel`: Concept-art planes and ghost
+//!   models to model against, saved with the project
+//! - `Scene`: Ordered stack of named `World` layers plus their composite,
+//!   and the animation frames they hold
+//! - `StreamingSettings` / `SpillFile`: cooling distant chunks of huge
+//!   worlds to RLE in RAM or on disk
 
 mod voxel;
 mod chunk;
 mod world;
+mod scene;
+mod material;
+mod background;
+mod light;
+mod reference;
+mod streaming;
 
 pub use voxel::{Voxel, Material};
-pub use chunk::{Chunk, ChunkPos, LocalPos, CHUNK_SIZE, CHUNK_SIZE_I32, CHUNK_VOLUME};
-pub use world::World;
+pub use chunk::{Chunk, ChunkPos, LocalPos, CHUNK_SIZE, CHUNK_SIZE_I32, CHUNK_VOLUME, MAX_PALETTE};
+pub use world::{BoundingBox, CellBox, World, WorldBounds};
+pub use scene::{Frame, FrameId, Layer, LayerId, Scene, DEFAULT_FRAME_MS};
+pub use material::{MaterialDef, MaterialTable, DEFAULT_MATERIAL, MATERIAL_SLOTS};
+pub use background::{Background, BackgroundKind};
+pub use light::{Light, LightKind, MAX_LIGHTS};
+pub use reference::{ReferenceImage, ReferenceModel, ReferenceView};
+pub use streaming::{SpillFile, StreamStats, StreamingSettings};
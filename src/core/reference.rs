@@ -0,0 +1,228 @@
+//! Reference images: concept art standing in the scene as flat,
+//! see-through planes to model against. Saved with the project; only
+//! the image's path is stored, like the background image.
+//!
+//! A [`ReferenceModel`] does the same for a whole model: a `.vox` or
+//! `.obj` shown as a see-through ghost that's never part of the world,
+//! so tools and picking pass straight through it.
+//!
+//! Each plane faces one of the axis views, oriented the way that view's
+//! camera preset (`render::CameraView`) sees it, so an image placed for
+//! Front reads the right way round when looking from the front.
+
+use std::path::PathBuf;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Which view a reference plane faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ReferenceView {
+    /// Upright in the YZ plane, seen from +X.
+    #[default]
+    Front,
+    /// Upright in the XY plane, seen from +Z.
+    Side,
+    /// Flat in the XZ plane, seen from above.
+    Top,
+}
+
+impl ReferenceView {
+    pub const ALL: [ReferenceView; 3] = [Self::Front, Self::Side, Self::Top];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Front => "Front",
+            Self::Side => "Side",
+            Self::Top => "Top",
+        }
+    }
+
+    /// World directions of the image's right and up edges as seen from
+    /// this view.
+    pub fn axes(self) -> (Vec3, Vec3) {
+        match self {
+            Self::Front => (Vec3::NEG_Z, Vec3::Y),
+            Self::Side => (Vec3::X, Vec3::Y),
+            Self::Top => (Vec3::NEG_Z, Vec3::NEG_X),
+        }
+    }
+}
+
+/// One reference image plane.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReferenceImage {
+    /// Image file (PNG, JPEG, …).
+    pub path: PathBuf,
+    pub view: ReferenceView,
+    pub visible: bool,
+    /// 0 = invisible, 1 = opaque.
+    pub opacity: f32,
+    /// Width of the image in voxels; the height follows its aspect.
+    pub width: f32,
+    /// Where the image's bottom edge is centered — it stands on this
+    /// point for Front and Side, and is centered on it for Top.
+    pub position: [f32; 3],
+}
+
+impl Default for ReferenceImage {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::new(),
+            view: ReferenceView::Front,
+            visible: true,
+            opacity: 0.5,
+            width: 32.0,
+            position: [0.0; 3],
+        }
+    }
+}
+
+impl ReferenceImage {
+    /// A default plane showing `path` for `view`.
+    pub fn new(path: PathBuf, view: ReferenceView) -> Self {
+        Self {
+            path,
+            view,
+            ..Self::default()
+        }
+    }
+
+    /// File name for lists and status messages.
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Corners of the plane for an image `aspect` (width / height)
+    /// wide: bottom-left, bottom-right, top-right, top-left as the view
+    /// sees them.
+    pub fn corners(&self, aspect: f32) -> [Vec3; 4] {
+        let (right, up) = self.view.axes();
+        let half_w = right * self.width.max(0.0) * 0.5;
+        let height = up * self.width.max(0.0) / aspect.max(1e-6);
+        let base = match self.view {
+            ReferenceView::Front | ReferenceView::Side => Vec3::from(self.position),
+            ReferenceView::Top => Vec3::from(self.position) - height * 0.5,
+        };
+        [
+            base - half_w,
+            base + half_w,
+            base + half_w + height,
+            base - half_w + height,
+        ]
+    }
+}
+
+/// A model shown as a ghost to rebuild or extend against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReferenceModel {
+    /// MagicaVoxel (`.vox`) or Wavefront (`.obj`) file.
+    pub path: PathBuf,
+    pub visible: bool,
+    /// 0 = invisible, 1 = opaque.
+    pub opacity: f32,
+    /// Scale about the file's origin: a `.vox` is in voxels already, an
+    /// `.obj` in whatever units it was made in.
+    pub scale: f32,
+    /// Where the file's origin lands in the world.
+    pub offset: [f32; 3],
+}
+
+impl Default for ReferenceModel {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::new(),
+            visible: true,
+            opacity: 0.35,
+            scale: 1.0,
+            offset: [0.0; 3],
+        }
+    }
+}
+
+impl ReferenceModel {
+    /// A default ghost of the model at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            ..Self::default()
+        }
+    }
+
+    /// File name for the panel and status messages.
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Whether the file is read as OBJ (by extension); anything else is
+    /// read as VOX.
+    pub fn is_obj(&self) -> bool {
+        self.path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("obj"))
+    }
+
+    /// Where the file's point `p` shows in the world.
+    pub fn place(&self, p: Vec3) -> Vec3 {
+        p * self.scale + Vec3::from(self.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn front_plane_stands_on_its_position() {
+        let r = ReferenceImage {
+            width: 20.0,
+            position: [0.0, 0.0, 0.0],
+            ..ReferenceImage::new("a.png".into(), ReferenceView::Front)
+        };
+        let [bl, br, tr, tl] = r.corners(2.0);
+        // Seen from +X, the image's right runs toward -Z.
+        assert_eq!(bl, Vec3::new(0.0, 0.0, 10.0));
+        assert_eq!(br, Vec3::new(0.0, 0.0, -10.0));
+        assert_eq!(tr, Vec3::new(0.0, 10.0, -10.0));
+        assert_eq!(tl, Vec3::new(0.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn top_plane_is_centered_and_flat() {
+        let r = ReferenceImage {
+            width: 8.0,
+            position: [1.0, 2.0, 3.0],
+            ..ReferenceImage::new("a.png".into(), ReferenceView::Top)
+        };
+        let corners = r.corners(1.0);
+        assert!(corners.iter().all(|c| c.y == 2.0));
+        let center = corners.iter().copied().sum::<Vec3>() / 4.0;
+        assert_eq!(center, Vec3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn model_is_scaled_then_offset() {
+        let m = ReferenceModel {
+            scale: 2.0,
+            offset: [1.0, 0.0, -1.0],
+            ..ReferenceModel::new("ship.OBJ".into())
+        };
+        assert!(m.is_obj());
+        assert!(!ReferenceModel::new("ship.vox".into()).is_obj());
+        assert_eq!(m.place(Vec3::new(1.0, 2.0, 3.0)), Vec3::new(3.0, 4.0, 5.0));
+    }
+
+    #[test]
+    fn name_is_the_file_name() {
+        let r = ReferenceImage::new(PathBuf::from("art").join("hero.png"), ReferenceView::Side);
+        assert_eq!(r.name(), "hero.png");
+    }
+}
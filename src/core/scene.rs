@@ -0,0 +1,951 @@
+//! Scene: an ordered stack of named voxel layers.
+//!
+//! Each [`Layer`] owns a full [`World`], so a layer is an independent
+//! model — a character's body and armor, a building and its props —
+//! that can be hidden, locked, renamed, reordered and merged without
+//! touching the others. Editing always targets the *active* layer.
+//!
+//! Everything that consumes "the scene as seen" — meshing, raycasting,
+//! exports, the path tracer and GI — reads [`Scene::view`], a
+//! composite `World` of the visible layers, bottom to top, where an
+//! upper layer's solid voxel wins a shared cell. Keeping the composite
+//! as a plain `World` means none of those consumers need to know layers
+//! exist.
+//!
+//! The view is maintained incrementally. A layer write marks its chunk
+//! dirty in the layer's own world (the usual `World` bookkeeping);
+//! structural changes (visibility, reorder, remove, merge) queue the
+//! chunk positions they affect. [`Scene::take_dirty_view_chunks`]
+//! recomposes just those chunks into the view and hands back the view
+//! chunks that now need re-meshing.
+//!
+//! A scene is also a flipbook: an ordered list of [`Frame`]s, each a
+//! full snapshot of every layer's voxels. The layers always hold the
+//! *current* frame, so editing, the view and everything downstream
+//! only ever see one frame; [`Scene::set_current_frame`] swaps the
+//! layers' worlds with the stored ones and queues the affected chunks
+//! like any other structural change. Layer names, flags and order are
+//! shared by all frames.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::sync::Arc;
+
+use super::{
+    Chunk, ChunkPos, SpillFile, StreamStats, StreamingSettings, World, WorldBounds,
+};
+
+/// Stable identifier for a layer. Survives reorder / rename, so state
+/// keyed on a layer (e.g. its undo history) follows it around.
+pub type LayerId = u32;
+
+/// One named, independently editable model in a [`Scene`].
+pub struct Layer {
+    pub id: LayerId,
+    pub name: String,
+    /// Hidden layers are left out of [`Scene::view`] — not rendered,
+    /// not hit by raycasts, not exported.
+    pub visible: bool,
+    /// Locked layers stay visible but refuse edits. The scene only
+    /// stores the flag; the editor enforces it.
+    pub locked: bool,
+    pub world: World,
+}
+
+impl Layer {
+    /// A visible, unlocked layer. The id is assigned by the scene.
+    pub fn new(name: impl Into<String>, world: World) -> Self {
+        Self {
+            id: 0,
+            name: name.into(),
+            visible: true,
+            locked: false,
+            world,
+        }
+    }
+}
+
+/// Stable identifier for an animation frame. Like [`LayerId`], it
+/// survives reorder / rename, so state keyed on a frame (its undo
+/// histories) follows it around.
+pub type FrameId = u32;
+
+/// Playback hold time of a new frame, in milliseconds.
+pub const DEFAULT_FRAME_MS: u32 = 100;
+
+/// One flipbook frame of a [`Scene`].
+///
+/// The current frame's voxels live in the layers themselves; every
+/// other frame keeps its layer worlds here, keyed by layer id, until
+/// it's made current. A layer with no entry — added while another
+/// frame was current — is empty in this frame.
+pub struct Frame {
+    pub id: FrameId,
+    pub name: String,
+    /// How long playback holds this frame, in milliseconds.
+    pub duration_ms: u32,
+    worlds: HashMap<LayerId, World>,
+}
+
+/// Ordered layer stack (index 0 = bottom) plus its composited view,
+/// and the animation frames the layers can be switched between.
+pub struct Scene {
+    layers: Vec<Layer>,
+    active: usize,
+    next_id: LayerId,
+    /// Always at least one; `frames[current_frame].worlds` is empty
+    /// because its voxels are in `layers`.
+    frames: Vec<Frame>,
+    current_frame: usize,
+    next_frame_id: FrameId,
+    view: World,
+    /// Chunk positions whose composite must be rebuilt because of a
+    /// structural change rather than a voxel write.
+    stale: HashSet<ChunkPos>,
+    /// Where [`Self::stream_out`] spills chunks, created on first use.
+    spill: Option<Arc<SpillFile>>,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene {
+    /// A scene with one empty layer.
+    pub fn new() -> Self {
+        Self::from_world(World::new())
+    }
+
+    /// A single-layer scene holding `world` — what loading a
+    /// pre-layers project or importing a `.vox` produces.
+    pub fn from_world(world: World) -> Self {
+        Self::from_layers(vec![Layer::new("Layer 1", world)])
+    }
+
+    /// A scene from an explicit layer list (bottom first); ids are
+    /// reassigned. An empty list yields one empty layer.
+    pub fn from_layers(layers: Vec<Layer>) -> Self {
+        let mut scene = Self {
+            layers: Vec::new(),
+            active: 0,
+            next_id: 0,
+            frames: Vec::new(),
+            current_frame: 0,
+            next_frame_id: 0,
+            view: World::new(),
+            stale: HashSet::new(),
+            spill: None,
+        };
+        scene.insert_frame(0, HashMap::new());
+        for mut layer in layers {
+            layer.id = scene.alloc_id();
+            scene.stale.extend(layer.world.chunk_positions().copied());
+            scene.layers.push(layer);
+        }
+        if scene.layers.is_empty() {
+            let id = scene.alloc_id();
+            scene.layers.push(Layer { id, ..Layer::new("Layer 1", World::new()) });
+        }
+        scene
+    }
+
+    fn alloc_id(&mut self) -> LayerId {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Layers, bottom first.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// The layer whose voxel the view shows at `(x, y, z)`: the topmost
+    /// visible layer solid there, or `None` for air.
+    pub fn visible_layer_at(&self, x: i32, y: i32, z: i32) -> Option<&Layer> {
+        self.layers
+            .iter()
+            .rev()
+            .filter(|l| l.visible)
+            .find(|l| !l.world.get_voxel(x, y, z).is_air())
+    }
+
+    /// Number of layers (always at least one).
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Always false — a scene keeps at least one layer. Present for
+    /// API symmetry with `len`.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Index of the layer edits go to.
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    pub fn active(&self) -> &Layer {
+        &self.layers[self.active]
+    }
+
+    /// The active layer's world — the edit target.
+    pub fn active_world(&self) -> &World {
+        &self.layers[self.active].world
+    }
+
+    /// Mutable edit target. Writes mark chunks dirty as usual; the
+    /// next [`Self::take_dirty_view_chunks`] folds them into the view.
+    pub fn active_world_mut(&mut self) -> &mut World {
+        &mut self.layers[self.active].world
+    }
+
+    /// Make layer `index` the edit target. Out-of-range is ignored.
+    pub fn set_active(&mut self, index: usize) {
+        if index < self.layers.len() {
+            self.active = index;
+        }
+    }
+
+    /// Rename layer `index`. Out-of-range is ignored.
+    pub fn rename_layer(&mut self, index: usize, name: impl Into<String>) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.name = name.into();
+        }
+    }
+
+    /// Show / hide layer `index`, queueing its chunks for recompose.
+    pub fn set_visible(&mut self, index: usize, visible: bool) {
+        let Some(layer) = self.layers.get_mut(index) else {
+            return;
+        };
+        if layer.visible != visible {
+            layer.visible = visible;
+            self.stale.extend(layer.world.chunk_positions().copied());
+        }
+    }
+
+    /// Lock / unlock layer `index`. Doesn't affect the view.
+    pub fn set_locked(&mut self, index: usize, locked: bool) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.locked = locked;
+        }
+    }
+
+    /// First "Layer N" name not already taken.
+    pub fn next_layer_name(&self) -> String {
+        (1..)
+            .map(|n| format!("Layer {n}"))
+            .find(|name| self.layers.iter().all(|l| &l.name != name))
+            .expect("unbounded range always yields a free name")
+    }
+
+    /// Insert an empty layer directly above the active one and make it
+    /// active. Returns its index.
+    pub fn add_layer(&mut self, name: impl Into<String>) -> usize {
+        let id = self.alloc_id();
+        let index = self.active + 1;
+        // Within project-wide bounds a new layer is bounded like the rest.
+        let world = blank_world(self.world_bounds());
+        self.layers.insert(index, Layer { id, ..Layer::new(name, world) });
+        self.active = index;
+        index
+    }
+
+    /// Remove layer `index` and return it. Refuses (returns `None`) for
+    /// the last remaining layer or an out-of-range index. The active
+    /// index stays on the same layer when possible, else moves down.
+    pub fn remove_layer(&mut self, index: usize) -> Option<Layer> {
+        if self.layers.len() <= 1 || index >= self.layers.len() {
+            return None;
+        }
+        let layer = self.layers.remove(index);
+        self.stale.extend(layer.world.chunk_positions().copied());
+        for frame in &mut self.frames {
+            frame.worlds.remove(&layer.id);
+        }
+        if self.active > index || self.active == self.layers.len() {
+            self.active -= 1;
+        }
+        Some(layer)
+    }
+
+    /// Move layer `from` to position `to` in the stack. The active
+    /// layer stays active wherever it ends up.
+    pub fn move_layer(&mut self, from: usize, to: usize) {
+        let n = self.layers.len();
+        if from >= n || to >= n || from == to {
+            return;
+        }
+        let active_id = self.layers[self.active].id;
+        let layer = self.layers.remove(from);
+        // Draw order changed, so every cell this layer covers may now
+        // composite differently.
+        self.stale.extend(layer.world.chunk_positions().copied());
+        self.layers.insert(to, layer);
+        self.active = self
+            .layers
+            .iter()
+            .position(|l| l.id == active_id)
+            .expect("active layer still present");
+    }
+
+    /// Merge layer `index` into the one below it: its solid voxels are
+    /// written over the lower layer (upper wins, as in the view) and
+    /// the layer is removed, in every frame. Returns the id of the
+    /// layer merged into, or `None` for the bottom layer / out of
+    /// range.
+    pub fn merge_down(&mut self, index: usize) -> Option<LayerId> {
+        if index == 0 || index >= self.layers.len() {
+            return None;
+        }
+        let upper = self.layers.remove(index);
+        let lower = &mut self.layers[index - 1];
+        self.stale.extend(merge_solid(&mut lower.world, &upper.world));
+        // Merging a hidden layer into a visible one (or vice versa)
+        // changes what the view shows across both layers.
+        self.stale.extend(lower.world.chunk_positions().copied());
+        let into = lower.id;
+        let bounds = lower.world.bounds().copied();
+        for frame in &mut self.frames {
+            if let Some(upper_world) = frame.worlds.remove(&upper.id) {
+                let lower_world = frame
+                    .worlds
+                    .entry(into)
+                    .or_insert_with(|| blank_world(bounds));
+                merge_solid(lower_world, &upper_world);
+            }
+        }
+        if self.active >= index {
+            self.active -= 1;
+        }
+        Some(into)
+    }
+
+    /// Bound layer `index`'s world to `bounds` (`None` lifts the
+    /// limit), in every animation frame. Content in chunks outside the
+    /// new bounds is discarded and those cells are recomposed without
+    /// it.
+    pub fn set_layer_bounds(&mut self, index: usize, bounds: Option<WorldBounds>) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            self.stale.extend(layer.world.set_bounds(bounds));
+            for frame in &mut self.frames {
+                if let Some(world) = frame.worlds.get_mut(&layer.id) {
+                    world.set_bounds(bounds);
+                }
+            }
+        }
+    }
+
+    /// The project's world bounds: the bounds every layer shares, or
+    /// `None` when some layer is unbounded or bounded differently (a
+    /// layer bounded on its own by Crop).
+    pub fn world_bounds(&self) -> Option<WorldBounds> {
+        let first = self.layers.first()?.world.bounds().copied()?;
+        self.layers
+            .iter()
+            .all(|l| l.world.bounds() == Some(&first))
+            .then_some(first)
+    }
+
+    /// Bound every layer to `bounds` (`None` unbounds them all), as
+    /// [`Self::set_layer_bounds`] does for one.
+    pub fn set_world_bounds(&mut self, bounds: Option<WorldBounds>) {
+        for index in 0..self.layers.len() {
+            self.set_layer_bounds(index, bounds);
+        }
+    }
+
+    /// Solid voxels [`Self::set_world_bounds`] would discard for
+    /// `bounds`, across every layer and animation frame.
+    pub fn voxels_outside(&self, bounds: &WorldBounds) -> u64 {
+        let layers: u64 = self.layers.iter().map(|l| l.world.solid_voxels_outside(bounds)).sum();
+        let frames: u64 = self
+            .frames
+            .iter()
+            .flat_map(|f| f.worlds.values())
+            .map(|w| w.solid_voxels_outside(bounds))
+            .sum();
+        layers + frames
+    }
+
+    /// Every layer's world in every frame, for whole-document passes
+    /// that aren't layer edits (re-stamping materials). Writes to the
+    /// current frame reach the view through the usual dirty tracking,
+    /// as with [`Self::active_world_mut`].
+    pub fn worlds_mut(&mut self) -> impl Iterator<Item = &mut World> {
+        let stored = self.frames.iter_mut().flat_map(|f| f.worlds.values_mut());
+        self.layers
+            .iter_mut()
+            .map(|layer| &mut layer.world)
+            .chain(stored)
+    }
+
+    /// Animation frames in playback order. Never empty.
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Index of the frame the layers currently hold.
+    pub fn current_frame(&self) -> usize {
+        self.current_frame
+    }
+
+    /// Make frame `index` current: each layer's world is stored into
+    /// the outgoing frame and replaced with the incoming frame's (empty
+    /// where it has none). Out-of-range is ignored.
+    pub fn set_current_frame(&mut self, index: usize) {
+        if index >= self.frames.len() || index == self.current_frame {
+            return;
+        }
+        let outgoing = &mut self.frames[self.current_frame].worlds;
+        for layer in &mut self.layers {
+            let blank = blank_world(layer.world.bounds().copied());
+            let world = std::mem::replace(&mut layer.world, blank);
+            self.stale.extend(world.chunk_positions().copied());
+            outgoing.insert(layer.id, world);
+        }
+        // Entries of since-removed layers are dropped with the map.
+        let mut incoming = std::mem::take(&mut self.frames[index].worlds);
+        for layer in &mut self.layers {
+            if let Some(world) = incoming.remove(&layer.id) {
+                self.stale.extend(world.chunk_positions().copied());
+                layer.world = world;
+            }
+        }
+        self.current_frame = index;
+    }
+
+    /// Insert an empty frame after the current one and make it
+    /// current. Returns its index.
+    pub fn add_frame(&mut self) -> usize {
+        let index = self.insert_frame(self.current_frame + 1, HashMap::new());
+        self.set_current_frame(index);
+        index
+    }
+
+    /// Insert a copy of the current frame after it and make the copy
+    /// current. Returns its index.
+    pub fn duplicate_frame(&mut self) -> usize {
+        let worlds = self
+            .layers
+            .iter()
+            .map(|layer| (layer.id, layer.world.duplicate()))
+            .collect();
+        let duration_ms = self.frames[self.current_frame].duration_ms;
+        let index = self.insert_frame(self.current_frame + 1, worlds);
+        self.frames[index].duration_ms = duration_ms;
+        self.set_current_frame(index);
+        index
+    }
+
+    /// Append a frame holding `worlds` — one per layer, bottom first;
+    /// missing ones are empty — without changing the current frame.
+    /// How project loading restores the frames that weren't current.
+    pub fn push_frame(&mut self, name: impl Into<String>, duration_ms: u32, worlds: Vec<World>) {
+        let worlds = self.layers.iter().map(|l| l.id).zip(worlds).collect();
+        let index = self.insert_frame(self.frames.len(), worlds);
+        let frame = &mut self.frames[index];
+        frame.name = name.into();
+        frame.duration_ms = duration_ms.max(1);
+    }
+
+    /// Remove frame `index` and return its id. Refuses (returns `None`)
+    /// for the last remaining frame or an out-of-range index. Removing
+    /// the current frame first makes the previous one (or the next,
+    /// for the first frame) current.
+    pub fn remove_frame(&mut self, index: usize) -> Option<FrameId> {
+        if self.frames.len() <= 1 || index >= self.frames.len() {
+            return None;
+        }
+        if index == self.current_frame {
+            self.set_current_frame(if index > 0 { index - 1 } else { 1 });
+        }
+        let frame = self.frames.remove(index);
+        if self.current_frame > index {
+            self.current_frame -= 1;
+        }
+        Some(frame.id)
+    }
+
+    /// Move frame `from` to position `to`. The current frame stays
+    /// current wherever it ends up.
+    pub fn move_frame(&mut self, from: usize, to: usize) {
+        let n = self.frames.len();
+        if from >= n || to >= n || from == to {
+            return;
+        }
+        let current_id = self.frames[self.current_frame].id;
+        let frame = self.frames.remove(from);
+        self.frames.insert(to, frame);
+        self.current_frame = self
+            .frames
+            .iter()
+            .position(|f| f.id == current_id)
+            .expect("current frame still present");
+    }
+
+    /// Rename frame `index`. Out-of-range is ignored.
+    pub fn rename_frame(&mut self, index: usize, name: impl Into<String>) {
+        if let Some(frame) = self.frames.get_mut(index) {
+            frame.name = name.into();
+        }
+    }
+
+    /// Set frame `index`'s playback hold time (at least 1 ms).
+    /// Out-of-range is ignored.
+    pub fn set_frame_duration(&mut self, index: usize, duration_ms: u32) {
+        if let Some(frame) = self.frames.get_mut(index) {
+            frame.duration_ms = duration_ms.max(1);
+        }
+    }
+
+    /// Layer `layer`'s world in frame `frame`: the live world for the
+    /// current frame, the stored one otherwise. `None` where the layer
+    /// is empty in that frame or either index is out of range.
+    pub fn frame_layer_world(&self, frame: usize, layer: usize) -> Option<&World> {
+        let layer = self.layers.get(layer)?;
+        if frame == self.current_frame {
+            return Some(&layer.world);
+        }
+        self.frames.get(frame)?.worlds.get(&layer.id)
+    }
+
+    /// Frame `index`'s visible layers flattened into a fresh world —
+    /// [`Self::flatten`] for any frame. Empty when out of range.
+    pub fn frame_world(&self, index: usize) -> World {
+        if index == self.current_frame {
+            return self.flatten();
+        }
+        let mut world = World::new();
+        let Some(frame) = self.frames.get(index) else {
+            return world;
+        };
+        let positions: HashSet<ChunkPos> = frame
+            .worlds
+            .values()
+            .flat_map(|w| w.chunk_positions().copied())
+            .collect();
+        for pos in positions {
+            if let Some(chunk) = self.compose_chunk_with(pos, |l| frame.worlds.get(&l.id)) {
+                world.replace_chunk(pos, chunk);
+            }
+        }
+        world
+    }
+
+    /// Insert a frame named after the first free "Frame N" at `index`
+    /// and return `index`.
+    fn insert_frame(&mut self, index: usize, worlds: HashMap<LayerId, World>) -> usize {
+        let name = (1..)
+            .map(|n| format!("Frame {n}"))
+            .find(|name| self.frames.iter().all(|f| &f.name != name))
+            .expect("unbounded range always yields a free name");
+        let id = self.next_frame_id;
+        self.next_frame_id += 1;
+        self.frames.insert(
+            index,
+            Frame {
+                id,
+                name,
+                duration_ms: DEFAULT_FRAME_MS,
+                worlds,
+            },
+        );
+        index
+    }
+
+    /// The visible layers composited into one world. Read-only: edit
+    /// through [`Self::active_world_mut`].
+    pub fn view(&self) -> &World {
+        &self.view
+    }
+
+    /// Heap memory of voxel storage across every layer plus the
+    /// composited view ([`World::heap_bytes`]).
+    pub fn heap_bytes(&self) -> usize {
+        let layers: usize = self.layers.iter().map(|l| l.world.heap_bytes()).sum();
+        let frames: usize = self
+            .frames
+            .iter()
+            .flat_map(|f| f.worlds.values())
+            .map(World::heap_bytes)
+            .sum();
+        layers + frames + self.view.heap_bytes()
+    }
+
+    /// Cold chunks that failed to decode across every layer and stored
+    /// animation frame ([`World::unreadable_chunks`]). The view is left
+    /// out: it's rebuilt from the layers and never saved.
+    pub fn unreadable_chunks(&self) -> usize {
+        let layers: usize = self.layers.iter().map(|l| l.world.unreadable_chunks()).sum();
+        let frames: usize = self
+            .frames
+            .iter()
+            .flat_map(|f| f.worlds.values())
+            .map(World::unreadable_chunks)
+            .sum();
+        layers + frames
+    }
+
+    /// Run a streaming pass ([`World::stream_out`]) over every layer,
+    /// stored animation frame and the view, around `center`.
+    pub fn stream_out(
+        &mut self,
+        center: ChunkPos,
+        settings: &StreamingSettings,
+    ) -> io::Result<StreamStats> {
+        let spill = match (&self.spill, settings.spill_to_disk) {
+            (_, false) => None,
+            (Some(file), true) => Some(Arc::clone(file)),
+            (None, true) => {
+                let file = Arc::new(SpillFile::create()?);
+                self.spill = Some(Arc::clone(&file));
+                Some(file)
+            }
+        };
+        let spill = spill.as_ref();
+        let mut stats = StreamStats::default();
+        for layer in &mut self.layers {
+            stats += layer.world.stream_out(center, settings, spill)?;
+        }
+        for frame in &mut self.frames {
+            for world in frame.worlds.values_mut() {
+                stats += world.stream_out(center, settings, spill)?;
+            }
+        }
+        stats += self.view.stream_out(center, settings, spill)?;
+        Ok(stats)
+    }
+
+    /// Every visible layer flattened into a fresh standalone world.
+    /// Unlike [`Self::view`] this doesn't depend on the incremental
+    /// sync having run.
+    pub fn flatten(&self) -> World {
+        let mut world = World::new();
+        for pos in self.all_chunk_positions() {
+            if let Some(chunk) = self.compose_chunk(pos) {
+                world.replace_chunk(pos, chunk);
+            }
+        }
+        world
+    }
+
+    /// Recompose every chunk touched since the last call into the view
+    /// and return the view chunks that need re-meshing, clearing both
+    /// the layers' and the view's dirty flags.
+    pub fn take_dirty_view_chunks(&mut self) -> Vec<ChunkPos> {
+        let mut positions = std::mem::take(&mut self.stale);
+        for layer in &mut self.layers {
+            positions.extend(layer.world.take_dirty_chunks());
+        }
+        let multi = self.layers.len() > 1;
+        for &pos in &positions {
+            match self.compose_chunk(pos) {
+                Some(chunk) => self.view.replace_chunk(pos, chunk),
+                // Nothing left here: keep an empty (dirty) chunk so its
+                // old mesh is replaced by an empty one.
+                None if self.view.has_chunk(pos) => self.view.replace_chunk(pos, Chunk::new()),
+                None => {}
+            }
+            // A single layer's own writes already dirty boundary
+            // neighbors. With several layers the neighbor may only
+            // exist in another layer, so its seam faces need a re-mesh
+            // too.
+            if multi {
+                self.view.mark_neighbors_dirty(pos);
+            }
+        }
+        self.view.take_dirty_chunks()
+    }
+
+    /// Composite of the visible layers at `pos`, or `None` if none of
+    /// them has a solid voxel there.
+    fn compose_chunk(&self, pos: ChunkPos) -> Option<Chunk> {
+        self.compose_chunk_with(pos, |layer| Some(&layer.world))
+    }
+
+    /// [`Self::compose_chunk`] reading each layer's voxels from
+    /// `world_of` (`None` = empty), so stored frames composite the same
+    /// way.
+    fn compose_chunk_with<'a>(
+        &'a self,
+        pos: ChunkPos,
+        world_of: impl Fn(&'a Layer) -> Option<&'a World>,
+    ) -> Option<Chunk> {
+        let mut out: Option<Chunk> = None;
+        for layer in self.layers.iter().filter(|l| l.visible) {
+            let Some(chunk) = world_of(layer).and_then(|w| w.get_chunk(pos)) else {
+                continue;
+            };
+            let chunk = chunk.read();
+            if chunk.is_empty() {
+                continue;
+            }
+            match &mut out {
+                None => out = Some(chunk.clone()),
+                Some(acc) => {
+                    for (lp, &voxel) in chunk.iter_solid() {
+                        acc.set(lp.x as usize, lp.y as usize, lp.z as usize, voxel);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn all_chunk_positions(&self) -> HashSet<ChunkPos> {
+        self.layers
+            .iter()
+            .flat_map(|l| l.world.chunk_positions().copied())
+            .collect()
+    }
+}
+
+/// An empty world with `bounds`.
+fn blank_world(bounds: Option<WorldBounds>) -> World {
+    match bounds {
+        Some(bounds) => World::bounded(bounds),
+        None => World::new(),
+    }
+}
+
+/// Write `upper`'s solid voxels over `lower` and return the chunk
+/// positions they touched.
+fn merge_solid(lower: &mut World, upper: &World) -> Vec<ChunkPos> {
+    let mut touched = Vec::new();
+    for (&pos, chunk) in upper.chunks() {
+        let chunk = chunk.read();
+        if chunk.is_empty() {
+            continue;
+        }
+        let (ox, oy, oz) = pos.world_origin();
+        for (lp, &voxel) in chunk.iter_solid() {
+            lower.set_voxel(ox + lp.x as i32, oy + lp.y as i32, oz + lp.z as i32, voxel);
+        }
+        touched.push(pos);
+    }
+    touched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Voxel;
+
+    fn red() -> Voxel {
+        Voxel::from_rgb(255, 0, 0)
+    }
+    fn blue() -> Voxel {
+        Voxel::from_rgb(0, 0, 255)
+    }
+
+    #[test]
+    fn view_composites_visible_layers_upper_wins() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, red());
+        scene.active_world_mut().set_voxel(1, 0, 0, red());
+        scene.add_layer("Top");
+        scene.active_world_mut().set_voxel(1, 0, 0, blue());
+        scene.active_world_mut().set_voxel(40, 0, 0, blue());
+
+        let dirty = scene.take_dirty_view_chunks();
+        assert!(!dirty.is_empty());
+        let view = scene.view();
+        assert_eq!(view.get_voxel(0, 0, 0), red());
+        assert_eq!(view.get_voxel(1, 0, 0), blue());
+        assert_eq!(view.get_voxel(40, 0, 0), blue());
+        assert!(scene.take_dirty_view_chunks().is_empty());
+    }
+
+    #[test]
+    fn bounding_a_layer_drops_outside_chunks_from_the_view() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, red());
+        scene.active_world_mut().set_voxel(-40, 0, 0, red());
+        scene.take_dirty_view_chunks();
+
+        scene.set_layer_bounds(0, Some(WorldBounds::single_chunk()));
+        let dirty = scene.take_dirty_view_chunks();
+        assert!(dirty.contains(&ChunkPos::new(-2, 0, 0)));
+        assert!(scene.view().get_voxel(-40, 0, 0).is_air());
+        assert_eq!(scene.view().get_voxel(0, 0, 0), red());
+    }
+
+    #[test]
+    fn hiding_a_layer_removes_it_from_the_view() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, red());
+        scene.add_layer("Top");
+        scene.active_world_mut().set_voxel(0, 0, 0, blue());
+        scene.take_dirty_view_chunks();
+
+        scene.set_visible(1, false);
+        assert!(!scene.take_dirty_view_chunks().is_empty());
+        assert_eq!(scene.view().get_voxel(0, 0, 0), red());
+
+        scene.set_visible(0, false);
+        scene.take_dirty_view_chunks();
+        assert!(scene.view().get_voxel(0, 0, 0).is_air());
+        assert!(scene.flatten().get_voxel(0, 0, 0).is_air());
+    }
+
+    #[test]
+    fn world_bounds_apply_to_every_layer_and_new_ones() {
+        let mut scene = Scene::new();
+        scene.add_layer("Top");
+        scene.active_world_mut().set_voxel(100, 0, 0, red());
+        // Another frame, stored while the first is current.
+        scene.add_frame();
+        scene.active_world_mut().set_voxel(0, 0, 100, blue());
+        scene.active_world_mut().set_voxel(1, 0, 100, blue());
+        scene.set_current_frame(0);
+        let bounds = WorldBounds::on_ground((2, 1, 2));
+        assert_eq!(scene.voxels_outside(&bounds), 3);
+        scene.set_world_bounds(Some(bounds));
+        assert_eq!(scene.world_bounds(), Some(bounds));
+        assert!(scene.active_world().get_voxel(100, 0, 0).is_air());
+        assert_eq!(scene.voxels_outside(&bounds), 0);
+        scene.set_current_frame(1);
+        assert!(scene.active_world().get_voxel(0, 0, 100).is_air());
+        assert_eq!(scene.active_world().bounds(), Some(&bounds));
+        scene.set_current_frame(0);
+
+        scene.add_layer("New");
+        assert_eq!(scene.active_world().bounds(), Some(&bounds));
+        scene.set_layer_bounds(0, None);
+        assert_eq!(scene.world_bounds(), None);
+    }
+
+    #[test]
+    fn visible_layer_at_finds_the_topmost_visible_owner() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, red());
+        scene.add_layer("Top");
+        scene.active_world_mut().set_voxel(0, 0, 0, blue());
+        assert_eq!(scene.visible_layer_at(0, 0, 0).map(|l| l.name.as_str()), Some("Top"));
+
+        scene.set_visible(1, false);
+        assert_eq!(scene.visible_layer_at(0, 0, 0).map(|l| l.id), Some(scene.layers()[0].id));
+        assert!(scene.visible_layer_at(1, 0, 0).is_none());
+    }
+
+    #[test]
+    fn merge_down_keeps_upper_voxels_and_drops_layer() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, red());
+        scene.add_layer("Top");
+        scene.active_world_mut().set_voxel(0, 0, 0, blue());
+        scene.active_world_mut().set_voxel(5, 0, 0, blue());
+        let bottom_id = scene.layers()[0].id;
+
+        assert_eq!(scene.merge_down(1), Some(bottom_id));
+        assert_eq!(scene.len(), 1);
+        assert_eq!(scene.active_index(), 0);
+        assert_eq!(scene.active_world().get_voxel(0, 0, 0), blue());
+        assert_eq!(scene.active_world().get_voxel(5, 0, 0), blue());
+        assert_eq!(scene.merge_down(0), None);
+    }
+
+    #[test]
+    fn remove_and_move_keep_active_layer() {
+        let mut scene = Scene::new();
+        scene.add_layer("B");
+        scene.add_layer("C");
+        // Stack: Layer 1, B, C (active).
+        let c = scene.active().id;
+        scene.move_layer(2, 0);
+        assert_eq!(scene.active().id, c);
+        assert_eq!(scene.active_index(), 0);
+
+        scene.set_active(2);
+        assert!(scene.remove_layer(2).is_some());
+        assert_eq!(scene.active_index(), 1);
+        scene.remove_layer(0);
+        assert!(scene.remove_layer(0).is_none(), "last layer stays");
+        assert_eq!(scene.len(), 1);
+    }
+
+    #[test]
+    fn removing_a_layer_clears_its_cells_from_the_view() {
+        let mut scene = Scene::new();
+        scene.add_layer("Top");
+        scene.active_world_mut().set_voxel(3, 3, 3, blue());
+        scene.take_dirty_view_chunks();
+        assert!(scene.view().get_voxel(3, 3, 3).is_solid());
+
+        scene.remove_layer(1);
+        let dirty = scene.take_dirty_view_chunks();
+        assert!(dirty.contains(&ChunkPos::ZERO));
+        assert!(scene.view().get_voxel(3, 3, 3).is_air());
+    }
+
+    #[test]
+    fn next_layer_name_skips_taken_names() {
+        let mut scene = Scene::new();
+        assert_eq!(scene.next_layer_name(), "Layer 2");
+        scene.add_layer("Layer 2");
+        assert_eq!(scene.next_layer_name(), "Layer 3");
+    }
+
+    #[test]
+    fn frames_swap_layer_contents_in_and_out() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, red());
+        scene.take_dirty_view_chunks();
+
+        assert_eq!(scene.add_frame(), 1);
+        assert_eq!(scene.frames()[1].name, "Frame 2");
+        assert!(scene.take_dirty_view_chunks().contains(&ChunkPos::ZERO));
+        assert!(scene.view().get_voxel(0, 0, 0).is_air());
+        scene.active_world_mut().set_voxel(40, 0, 0, blue());
+
+        let copy = scene.duplicate_frame();
+        assert_eq!((copy, scene.current_frame()), (2, 2));
+        scene.active_world_mut().set_voxel(41, 0, 0, blue());
+
+        scene.set_current_frame(0);
+        scene.take_dirty_view_chunks();
+        assert_eq!(scene.view().get_voxel(0, 0, 0), red());
+        assert!(scene.view().get_voxel(40, 0, 0).is_air());
+
+        // Stored frames flatten without becoming current, and the
+        // duplicate doesn't share voxels with its source.
+        let second = scene.frame_world(1);
+        assert_eq!(second.get_voxel(40, 0, 0), blue());
+        assert!(second.get_voxel(41, 0, 0).is_air());
+        assert_eq!(scene.frame_world(2).get_voxel(41, 0, 0), blue());
+        assert_eq!(scene.frame_layer_world(0, 0).map(|w| w.get_voxel(0, 0, 0)), Some(red()));
+    }
+
+    #[test]
+    fn frame_list_edits_keep_current_frame_and_reach_every_frame() {
+        let mut scene = Scene::new();
+        scene.add_layer("Top");
+        scene.active_world_mut().set_voxel(0, 0, 0, blue());
+        scene.add_frame();
+        scene.active_world_mut().set_voxel(1, 0, 0, blue());
+        scene.add_frame();
+        let third = scene.frames()[2].id;
+
+        scene.move_frame(2, 0);
+        assert_eq!(scene.current_frame(), 0);
+        assert_eq!(scene.frames()[0].id, third);
+
+        // Merging layers merges them in the stored frames too.
+        scene.merge_down(1);
+        assert_eq!(scene.frame_layer_world(1, 0).map(|w| w.get_voxel(0, 0, 0)), Some(blue()));
+        assert_eq!(scene.frame_layer_world(2, 0).map(|w| w.get_voxel(1, 0, 0)), Some(blue()));
+
+        assert_eq!(scene.remove_frame(0), Some(third));
+        assert_eq!(scene.current_frame(), 0);
+        scene.take_dirty_view_chunks();
+        assert_eq!(scene.view().get_voxel(0, 0, 0), blue());
+        scene.remove_frame(0);
+        assert!(scene.remove_frame(0).is_none(), "last frame stays");
+        assert_eq!(scene.active_world().get_voxel(1, 0, 0), blue());
+    }
+}
@@ -0,0 +1,162 @@
+//! Streaming: keeping huge worlds within memory.
+//!
+//! A 1000-chunk terrain easily holds a few hundred MB of palette
+//! storage, and layers, animation frames and the composited view each
+//! add their own copy. With streaming on, a periodic pass
+//! ([`crate::core::Scene::stream_out`]) *cools* chunks far from the
+//! camera — and, over a memory budget, nearer ones, farthest first:
+//! their voxels are run-length encoded, in RAM or appended to a
+//! [`SpillFile`] on disk.
+//!
+//! Cooling is invisible to readers. The first read of a cold chunk
+//! decodes it and keeps the decoded copy until the next pass drops it
+//! again; the first write turns it back into an ordinary chunk. Chunks
+//! waiting to be re-meshed are left alone, since the mesher is about to
+//! read them anyway.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Streaming options, edited in Preferences ▸ Large Worlds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamingSettings {
+    pub enabled: bool,
+    /// Chunks at most this many chunks from the camera's (along any
+    /// axis) are left as they are unless the budget is exceeded.
+    pub keep_radius: u32,
+    /// Voxel memory, in MB, past which nearer chunks are cooled too,
+    /// farthest first; 0 means no budget.
+    pub budget_mb: u32,
+    /// Write cooled chunks to a temporary file instead of keeping
+    /// them encoded in RAM.
+    pub spill_to_disk: bool,
+}
+
+impl Default for StreamingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keep_radius: 8,
+            budget_mb: 0,
+            spill_to_disk: false,
+        }
+    }
+}
+
+impl StreamingSettings {
+    /// `budget_mb` in bytes, `None` without a budget.
+    pub fn budget_bytes(&self) -> Option<usize> {
+        (self.budget_mb > 0).then(|| self.budget_mb as usize * 1024 * 1024)
+    }
+}
+
+/// What a streaming pass did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    /// Chunks cooled, or whose decoded copy was dropped again.
+    pub cooled: usize,
+    /// Voxel memory left afterwards ([`crate::core::World::heap_bytes`]).
+    pub resident_bytes: usize,
+}
+
+impl std::ops::AddAssign for StreamStats {
+    fn add_assign(&mut self, other: Self) {
+        self.cooled += other.cooled;
+        self.resident_bytes += other.resident_bytes;
+    }
+}
+
+/// Append-only temporary file holding spilled chunks, deleted when the
+/// last chunk referring to it is dropped. Space of chunks that were
+/// edited again isn't reclaimed until then.
+pub struct SpillFile {
+    path: PathBuf,
+    /// The file and its length.
+    file: Mutex<(File, u64)>,
+}
+
+impl SpillFile {
+    /// A new, empty file in the system temp directory.
+    pub fn create() -> io::Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let name = format!(
+            "voxelith-spill-{}-{}.bin",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = std::env::temp_dir().join(name);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new((file, 0)),
+        })
+    }
+
+    /// Append `bytes`, returning the offset they were written at.
+    pub fn append(&self, bytes: &[u8]) -> io::Result<u64> {
+        let mut guard = self.file.lock();
+        let (file, len) = &mut *guard;
+        let offset = *len;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(bytes)?;
+        *len += bytes.len() as u64;
+        Ok(offset)
+    }
+
+    /// The `len` bytes written at `offset`.
+    pub fn read(&self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let mut guard = self.file.lock();
+        let (file, _) = &mut *guard;
+        let mut bytes = vec![0; len];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Bytes written so far.
+    pub fn len(&self) -> u64 {
+        self.file.lock().1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            log::warn!("Couldn't remove {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spill_file_reads_back_appends_and_cleans_up() {
+        let file = SpillFile::create().unwrap();
+        let path = file.path.clone();
+        assert!(file.is_empty());
+        let a = file.append(b"hello").unwrap();
+        let b = file.append(b"world!").unwrap();
+        assert_eq!((a, b, file.len()), (0, 5, 11));
+        assert_eq!(file.read(b, 6).unwrap(), b"world!");
+        assert_eq!(file.read(a, 5).unwrap(), b"hello");
+        assert!(file.read(8, 10).is_err());
+        drop(file);
+        assert!(!path.exists());
+    }
+}
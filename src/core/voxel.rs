@@ -106,6 +106,20 @@ impl Voxel {
         self.material != 0
     }
 
+    /// Solid and fully opaque (`a == 255`). Only opaque voxels hide the
+    /// faces of their neighbors.
+    #[inline]
+    pub fn is_opaque(&self) -> bool {
+        self.is_solid() && self.a == 255
+    }
+
+    /// Solid but translucent (`a < 255`) — glass, water. Meshed into the
+    /// chunk's transparent range and drawn in the alpha-blended pass.
+    #[inline]
+    pub fn is_transparent(&self) -> bool {
+        self.is_solid() && self.a < 255
+    }
+
     /// Get color as [r, g, b, a] array
     #[inline]
     pub fn color(&self) -> [u8; 4] {
@@ -191,6 +205,16 @@ mod tests {
         assert!(solid.is_solid());
     }
 
+    #[test]
+    fn test_opacity_classes() {
+        let opaque = Voxel::from_rgb(255, 0, 0);
+        assert!(opaque.is_opaque() && !opaque.is_transparent());
+        let glass = Voxel::from_rgba(200, 220, 255, 96);
+        assert!(glass.is_transparent() && !glass.is_opaque());
+        // Air is neither, whatever its alpha byte says.
+        assert!(!Voxel::AIR.is_opaque() && !Voxel::AIR.is_transparent());
+    }
+
     #[test]
     fn test_material_flags_independent() {
         let mut v = Voxel::from_rgb(10, 20, 30);
@@ -3,11 +3,16 @@
 //! The World provides a unified interface for accessing voxels across
 //! multiple chunks, handling chunk boundaries transparently.
 
-use super::{Chunk, ChunkPos, Voxel, CHUNK_SIZE, CHUNK_SIZE_I32};
+use super::{
+    Chunk, ChunkPos, LocalPos, SpillFile, StreamStats, StreamingSettings, Voxel, CHUNK_SIZE,
+    CHUNK_SIZE_I32,
+};
 use glam::Vec3;
 use parking_lot::RwLock;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io;
 use std::sync::Arc;
 
 /// A world containing multiple chunks.
@@ -20,12 +25,139 @@ pub struct World {
     chunks: HashMap<ChunkPos, Arc<RwLock<Chunk>>>,
     /// World bounds (None = unbounded/infinite)
     bounds: Option<WorldBounds>,
-    /// Flag for tracking if any chunk is dirty
-    any_dirty: bool,
+    /// Chunk positions whose mesh is stale, kept up to date by every
+    /// write through the world. Answering "what needs re-meshing" from
+    /// this set costs what the edits touched rather than a lock per
+    /// loaded chunk; the chunks' own dirty flags aren't consulted.
+    /// After [`Self::clear`] it holds the dropped positions, so their
+    /// old meshes get replaced by empty ones.
+    dirty: HashSet<ChunkPos>,
 }
 
+/// Bit for the chunk at offset `(dx, dy, dz)` (each in `-1..=1`) in a
+/// neighbor mask. Bit 13 is the chunk itself and is never set.
+#[inline]
+fn neighbor_bit(dx: i32, dy: i32, dz: i32) -> u32 {
+    1 << ((dx + 1) + (dy + 1) * 3 + (dz + 1) * 9)
+}
+
+/// Mask of the neighbors whose mesh a change to local cell `(x, y, z)`
+/// can affect: every chunk across a face, edge or corner the cell lies
+/// on. Meshing reads one cell past the chunk, diagonals included (for
+/// ambient occlusion), so an edge cell matters to the chunk across
+/// that edge too. Zero for an interior cell.
+#[inline]
+fn boundary_neighbors(x: usize, y: usize, z: usize) -> u32 {
+    let last = CHUNK_SIZE - 1;
+    let offsets = |c: usize| match c {
+        0 => -1..=0,
+        c if c == last => 0..=1,
+        _ => 0..=0,
+    };
+    let mut mask = 0;
+    for dz in offsets(z) {
+        for dy in offsets(y) {
+            for dx in offsets(x) {
+                mask |= neighbor_bit(dx, dy, dz);
+            }
+        }
+    }
+    mask & !neighbor_bit(0, 0, 0)
+}
+
+/// A chunk a batched write goes to.
+struct WriteTarget {
+    pos: ChunkPos,
+    chunk: Arc<RwLock<Chunk>>,
+    /// Created for this batch (dropped again if left empty).
+    created: bool,
+}
+
+/// What a batched write did to one chunk.
+#[derive(Default)]
+struct ChunkWrite {
+    /// Any voxel changed.
+    changed: bool,
+    /// [`boundary_neighbors`] of every changed cell: the neighbors
+    /// whose seam faces or corner shading may change.
+    neighbors: u32,
+}
+
+impl ChunkWrite {
+    /// Set `voxel` at `local` unless it's already there.
+    #[inline]
+    fn record(&mut self, chunk: &mut Chunk, local: LocalPos, voxel: Voxel) {
+        let (x, y, z) = (local.x as usize, local.y as usize, local.z as usize);
+        if chunk.get(x, y, z) == voxel {
+            return;
+        }
+        chunk.set(x, y, z, voxel);
+        self.changed = true;
+        self.neighbors |= boundary_neighbors(x, y, z);
+    }
+}
+
+/// Apply `f` to the cells of the inclusive box `min..=max` that fall in
+/// the chunk at `pos`.
+fn apply_in_chunk<F>(
+    chunk: &mut Chunk,
+    pos: ChunkPos,
+    min: (i32, i32, i32),
+    max: (i32, i32, i32),
+    f: &mut F,
+) -> ChunkWrite
+where
+    F: FnMut((i32, i32, i32), Voxel) -> Voxel,
+{
+    let (ox, oy, oz) = pos.world_origin();
+    let span = |lo: i32, hi: i32, origin: i32| {
+        (lo - origin).max(0)..=(hi - origin).min(CHUNK_SIZE_I32 - 1)
+    };
+    let mut write = ChunkWrite::default();
+    for z in span(min.2, max.2, oz) {
+        for y in span(min.1, max.1, oy) {
+            for x in span(min.0, max.0, ox) {
+                let local = LocalPos::new(x as u8, y as u8, z as u8);
+                let old = chunk.get(x as usize, y as usize, z as usize);
+                let voxel = f((ox + x, oy + y, oz + z), old);
+                write.record(chunk, local, voxel);
+            }
+        }
+    }
+    write
+}
+
+/// Inclusive box of voxel cells, e.g. around a world's solid voxels
+/// ([`World::bounding_box`]). Cell `n` spans `[n, n + 1)`, so the box
+/// covers `[min, max + 1)` in continuous space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min: (i32, i32, i32),
+    pub max: (i32, i32, i32),
+}
+
+impl BoundingBox {
+    /// Extent in voxels along X, Y and Z.
+    pub fn size(&self) -> (u32, u32, u32) {
+        (
+            (self.max.0 - self.min.0 + 1) as u32,
+            (self.max.1 - self.min.1 + 1) as u32,
+            (self.max.2 - self.min.2 + 1) as u32,
+        )
+    }
+
+    /// Cells in the box, solid or not.
+    pub fn volume(&self) -> u64 {
+        let (x, y, z) = self.size();
+        x as u64 * y as u64 * z as u64
+    }
+}
+
+/// Inclusive `(min, max)` corners of a box of cells.
+pub type CellBox = ((i32, i32, i32), (i32, i32, i32));
+
 /// Bounds for a finite world
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct WorldBounds {
     pub min: ChunkPos,
     pub max: ChunkPos,
@@ -52,6 +184,45 @@ impl WorldBounds {
         }
     }
 
+    /// Smallest chunk-aligned bounds holding every cell of the
+    /// inclusive voxel box `min..=max` — bounds can only grow or shrink
+    /// a whole chunk at a time.
+    pub fn enclosing(min: (i32, i32, i32), max: (i32, i32, i32)) -> Self {
+        Self {
+            min: ChunkPos::from_world_pos(min.0, min.1, min.2),
+            max: ChunkPos::from_world_pos(max.0, max.1, max.2),
+        }
+    }
+
+    /// Bounds of `chunks` (x, y, z) chunks standing on `y = 0` and
+    /// centered on the origin in X and Z (an odd count puts the extra
+    /// chunk on the negative side) — the Project Settings layout.
+    pub fn on_ground(chunks: (u32, u32, u32)) -> Self {
+        let span = |n: u32| {
+            let n = n.max(1) as i32;
+            let min = -(n + 1) / 2;
+            (min, min + n - 1)
+        };
+        let (x0, x1) = span(chunks.0);
+        let (z0, z1) = span(chunks.2);
+        Self {
+            min: ChunkPos::new(x0, 0, z0),
+            max: ChunkPos::new(x1, chunks.1.max(1) as i32 - 1, z1),
+        }
+    }
+
+    /// The inclusive voxel box `(min, max)` the bounds cover.
+    pub fn voxel_box(&self) -> ((i32, i32, i32), (i32, i32, i32)) {
+        let (x1, y1, z1) = self.max.world_origin();
+        let last = CHUNK_SIZE as i32 - 1;
+        (self.min.world_origin(), (x1 + last, y1 + last, z1 + last))
+    }
+
+    /// Whether voxel `(x, y, z)` lies inside the bounds.
+    pub fn contains_voxel(&self, x: i32, y: i32, z: i32) -> bool {
+        self.contains(ChunkPos::from_world_pos(x, y, z))
+    }
+
     /// Check if a chunk position is within bounds
     pub fn contains(&self, pos: ChunkPos) -> bool {
         pos.x >= self.min.x
@@ -93,7 +264,7 @@ impl World {
         Self {
             chunks: HashMap::new(),
             bounds: Some(bounds),
-            any_dirty: false,
+            dirty: HashSet::new(),
         }
     }
 
@@ -111,6 +282,42 @@ impl World {
         self.bounds.as_ref()
     }
 
+    /// Make the world bounded by `bounds` (or unbounded with `None`).
+    /// Chunks outside the new bounds are discarded with their contents;
+    /// their loaded neighbors are marked dirty since the seam faces
+    /// toward them are now exposed. Returns the discarded chunk
+    /// positions so a caller compositing this world can drop them too.
+    pub fn set_bounds(&mut self, bounds: Option<WorldBounds>) -> Vec<ChunkPos> {
+        self.bounds = bounds;
+        let Some(bounds) = bounds else {
+            return Vec::new();
+        };
+        let dropped: Vec<ChunkPos> = self
+            .chunks
+            .keys()
+            .copied()
+            .filter(|&pos| !bounds.contains(pos))
+            .collect();
+        for pos in &dropped {
+            self.chunks.remove(pos);
+            self.dirty.remove(pos);
+        }
+        for &pos in &dropped {
+            self.mark_neighbors_dirty(pos);
+        }
+        dropped
+    }
+
+    /// Solid voxels [`Self::set_bounds`] would discard for `bounds`:
+    /// those in chunks outside it.
+    pub fn solid_voxels_outside(&self, bounds: &WorldBounds) -> u64 {
+        self.chunks
+            .iter()
+            .filter(|(pos, _)| !bounds.contains(**pos))
+            .map(|(_, chunk)| chunk.read().solid_count() as u64)
+            .sum()
+    }
+
     /// Check if a chunk exists at the given position
     pub fn has_chunk(&self, pos: ChunkPos) -> bool {
         self.chunks.contains_key(&pos)
@@ -123,6 +330,8 @@ impl World {
 
     /// Get or create chunk at position.
     /// Returns None if the world is bounded and the position is outside the bounds.
+    /// Writes through the returned lock bypass dirty tracking; follow
+    /// them with [`Self::mark_chunk_dirty`].
     pub fn get_or_create_chunk(&mut self, pos: ChunkPos) -> Option<Arc<RwLock<Chunk>>> {
         // Check bounds if set
         if let Some(bounds) = &self.bounds {
@@ -163,53 +372,249 @@ impl World {
         let lz = z.rem_euclid(CHUNK_SIZE_I32) as usize;
 
         chunk.write().set(lx, ly, lz, voxel);
-        self.any_dirty = true;
-
-        // If the write touched a chunk-boundary cell, the affected
-        // boundary face on the neighbor chunk's mesh may flip
-        // visibility. Mark loaded neighbors dirty so they re-mesh.
-        // Missing neighbors aren't created — there's nothing to
-        // re-mesh and we don't want to spawn empty chunks.
-        self.mark_boundary_neighbors_dirty(chunk_pos, lx, ly, lz);
-    }
-
-    /// Mark the (up to three) face-neighbors of a boundary write dirty.
-    fn mark_boundary_neighbors_dirty(
-        &self,
-        chunk_pos: ChunkPos,
-        lx: usize,
-        ly: usize,
-        lz: usize,
-    ) {
-        let last = CHUNK_SIZE - 1;
-        let candidates: [(bool, i32, i32, i32); 6] = [
-            (lx == 0, -1, 0, 0),
-            (lx == last, 1, 0, 0),
-            (ly == 0, 0, -1, 0),
-            (ly == last, 0, 1, 0),
-            (lz == 0, 0, 0, -1),
-            (lz == last, 0, 0, 1),
-        ];
-        for (active, dx, dy, dz) in candidates {
-            if !active {
-                continue;
-            }
-            let neighbor_pos = chunk_pos.neighbor(dx, dy, dz);
-            if let Some(neighbor) = self.chunks.get(&neighbor_pos) {
-                neighbor.write().mark_dirty();
+        self.dirty.insert(chunk_pos);
+
+        // If the write touched a chunk-boundary cell, the neighbor
+        // chunks across that face, edge or corner may flip a seam face
+        // or shade a corner differently. Mark loaded ones dirty so they
+        // re-mesh. Missing neighbors aren't created — there's nothing
+        // to re-mesh and we don't want to spawn empty chunks.
+        self.mark_masked_neighbors_dirty(chunk_pos, boundary_neighbors(lx, ly, lz));
+    }
+
+    /// Mark the loaded neighbors of `pos` selected by `mask` (see
+    /// [`neighbor_bit`]) dirty.
+    fn mark_masked_neighbors_dirty(&mut self, pos: ChunkPos, mask: u32) {
+        if mask == 0 {
+            return;
+        }
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if mask & neighbor_bit(dx, dy, dz) != 0 {
+                        self.mark_chunk_dirty(pos.neighbor(dx, dy, dz));
+                    }
+                }
             }
         }
     }
 
     /// Fill a region with a voxel
     pub fn fill_region(&mut self, min: (i32, i32, i32), max: (i32, i32, i32), voxel: Voxel) {
-        for z in min.2..=max.2 {
-            for y in min.1..=max.1 {
-                for x in min.0..=max.0 {
-                    self.set_voxel(x, y, z, voxel);
+        self.par_apply_region(min, max, |_, _| voxel);
+    }
+
+    /// Rewrite every cell of the inclusive box `min..=max`: `f` gets
+    /// the world position and current voxel and returns the new one.
+    /// Unlike a `set_voxel` loop, each chunk is looked up and locked
+    /// once, and only cells whose voxel actually changes are written
+    /// (so untouched chunks stay clean). Cells outside a bounded world
+    /// are skipped; chunks created for the box that end up empty are
+    /// dropped again. Cells are visited chunk by chunk, x fastest
+    /// within a chunk.
+    pub fn apply_region<F>(&mut self, min: (i32, i32, i32), max: (i32, i32, i32), mut f: F)
+    where
+        F: FnMut((i32, i32, i32), Voxel) -> Voxel,
+    {
+        let targets = self.region_chunks(min, max);
+        let writes = targets
+            .iter()
+            .map(|target| apply_in_chunk(&mut target.chunk.write(), target.pos, min, max, &mut f))
+            .collect();
+        self.finish_writes(targets, writes);
+    }
+
+    /// [`Self::apply_region`] with the chunks processed in parallel on
+    /// the rayon pool, for fills and generators over large boxes. `f`
+    /// must not depend on visiting order.
+    pub fn par_apply_region<F>(&mut self, min: (i32, i32, i32), max: (i32, i32, i32), f: F)
+    where
+        F: Fn((i32, i32, i32), Voxel) -> Voxel + Sync,
+    {
+        let targets = self.region_chunks(min, max);
+        let writes = targets
+            .par_iter()
+            .map(|target| {
+                apply_in_chunk(&mut target.chunk.write(), target.pos, min, max, &mut |p, v| f(p, v))
+            })
+            .collect();
+        self.finish_writes(targets, writes);
+    }
+
+    /// Write many scattered voxels at once — generator patches,
+    /// importers, undo / redo. Writes are grouped by chunk and each
+    /// chunk is locked once, with the chunks written in parallel; the
+    /// last write to a cell wins, as with a `set_voxel` loop.
+    pub fn set_voxels<I>(&mut self, voxels: I)
+    where
+        I: IntoIterator<Item = ((i32, i32, i32), Voxel)>,
+    {
+        let mut groups: HashMap<ChunkPos, Vec<(usize, Voxel)>> = HashMap::new();
+        for ((x, y, z), voxel) in voxels {
+            let local = LocalPos::from_world_pos(x, y, z);
+            groups
+                .entry(ChunkPos::from_world_pos(x, y, z))
+                .or_default()
+                .push((local.to_index(), voxel));
+        }
+        let mut targets = Vec::with_capacity(groups.len());
+        let mut cells = Vec::with_capacity(groups.len());
+        for (pos, group) in groups {
+            if let Some(target) = self.write_target(pos) {
+                targets.push(target);
+                cells.push(group);
+            }
+        }
+        let writes = targets
+            .par_iter()
+            .zip(cells)
+            .map(|(target, group)| {
+                let mut chunk = target.chunk.write();
+                let mut write = ChunkWrite::default();
+                for (index, voxel) in group {
+                    let local = LocalPos::from_index(index);
+                    write.record(&mut chunk, local, voxel);
+                }
+                write
+            })
+            .collect();
+        self.finish_writes(targets, writes);
+    }
+
+    /// The chunk at `pos` for a batched write, created if needed;
+    /// `None` outside a bounded world.
+    fn write_target(&mut self, pos: ChunkPos) -> Option<WriteTarget> {
+        let created = !self.chunks.contains_key(&pos);
+        let chunk = self.get_or_create_chunk(pos)?;
+        Some(WriteTarget { pos, chunk, created })
+    }
+
+    /// Write targets for every chunk the inclusive box overlaps.
+    fn region_chunks(&mut self, min: (i32, i32, i32), max: (i32, i32, i32)) -> Vec<WriteTarget> {
+        if min.0 > max.0 || min.1 > max.1 || min.2 > max.2 {
+            return Vec::new();
+        }
+        let lo = ChunkPos::from_world_pos(min.0, min.1, min.2);
+        let hi = ChunkPos::from_world_pos(max.0, max.1, max.2);
+        let mut targets = Vec::new();
+        for z in lo.z..=hi.z {
+            for y in lo.y..=hi.y {
+                for x in lo.x..=hi.x {
+                    targets.extend(self.write_target(ChunkPos::new(x, y, z)));
+                }
+            }
+        }
+        targets
+    }
+
+    /// Bookkeeping after a batched write: drop chunks the batch
+    /// created but left empty, and propagate dirtiness like
+    /// `set_voxel` does — to the world and to neighbors across any
+    /// face, edge or corner whose boundary cells changed.
+    fn finish_writes(&mut self, targets: Vec<WriteTarget>, writes: Vec<ChunkWrite>) {
+        for (target, write) in targets.into_iter().zip(writes) {
+            if target.created && target.chunk.read().is_empty() {
+                self.chunks.remove(&target.pos);
+            }
+            if !write.changed {
+                continue;
+            }
+            self.dirty.insert(target.pos);
+            self.mark_masked_neighbors_dirty(target.pos, write.neighbors);
+        }
+    }
+
+    /// A world holding only the 3×3×3 block of chunks around `center`,
+    /// sharing their `Arc`s with `self` (no voxel data is copied).
+    ///
+    /// Meshing a chunk never reads further than one chunk past its
+    /// edges, so this is enough for [`crate::mesh::MeshWorker`] jobs,
+    /// which need an owned, `Send` world to run off the UI thread.
+    /// Reads through the view see later writes to the shared chunks.
+    pub fn neighborhood(&self, center: ChunkPos) -> World {
+        let mut chunks = HashMap::new();
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let pos = center.neighbor(dx, dy, dz);
+                    if let Some(chunk) = self.chunks.get(&pos) {
+                        chunks.insert(pos, Arc::clone(chunk));
+                    }
                 }
             }
         }
+        World {
+            chunks,
+            bounds: self.bounds,
+            dirty: HashSet::new(),
+        }
+    }
+
+    /// Overwrite the chunk at `pos` with `chunk` (creating it if
+    /// needed) and mark it dirty. Whole-chunk writes are how
+    /// [`crate::core::Scene`] composites layers into its view; ignored
+    /// outside a bounded world's bounds, like `set_voxel`.
+    pub fn replace_chunk(&mut self, pos: ChunkPos, mut chunk: Chunk) {
+        let Some(slot) = self.get_or_create_chunk(pos) else {
+            return;
+        };
+        chunk.mark_dirty();
+        *slot.write() = chunk;
+        self.dirty.insert(pos);
+    }
+
+    /// A copy of the chunk at `pos`, or an empty chunk if none is
+    /// loaded. The copy keeps the chunk's packed storage, so it costs
+    /// what the chunk does — nothing for uniform ones. Undo snapshots
+    /// ([`crate::editor::Command::ChunkSnapshot`]) are built from these.
+    pub fn chunk_copy(&self, pos: ChunkPos) -> Chunk {
+        self.chunks
+            .get(&pos)
+            .map_or_else(Chunk::new, |chunk| chunk.read().clone())
+    }
+
+    /// A deep copy: same bounds, every chunk copied rather than shared,
+    /// nothing dirty. Duplicating an animation frame starts from this.
+    pub fn duplicate(&self) -> World {
+        World {
+            chunks: self
+                .chunks
+                .iter()
+                .map(|(&pos, chunk)| {
+                    let mut copy = chunk.read().clone();
+                    copy.clear_dirty();
+                    (pos, Arc::new(RwLock::new(copy)))
+                })
+                .collect(),
+            bounds: self.bounds,
+            dirty: HashSet::new(),
+        }
+    }
+
+    /// Put a [`World::chunk_copy`] back at `pos`, marking it and its
+    /// neighbors dirty so faces across its borders re-mesh. Putting an
+    /// empty chunk where none is loaded does nothing.
+    pub fn restore_chunk(&mut self, pos: ChunkPos, chunk: Chunk) {
+        if chunk.is_empty() && !self.has_chunk(pos) {
+            return;
+        }
+        self.replace_chunk(pos, chunk);
+        self.mark_neighbors_dirty(pos);
+    }
+
+    /// Mark all 26 loaded neighbors of `pos` dirty — across faces,
+    /// edges and corners — for when any of its boundary cells may have
+    /// changed.
+    pub fn mark_neighbors_dirty(&mut self, pos: ChunkPos) {
+        self.mark_masked_neighbors_dirty(pos, !neighbor_bit(0, 0, 0));
+    }
+
+    /// Mark the chunk at `pos` dirty if it's loaded, so it re-meshes
+    /// even though none of its own voxels changed.
+    pub fn mark_chunk_dirty(&mut self, pos: ChunkPos) {
+        if self.chunks.contains_key(&pos) {
+            self.dirty.insert(pos);
+        }
     }
 
     /// Get all loaded chunk positions
@@ -232,35 +637,72 @@ impl World {
     /// voxels. The box spans `[min, max + 1)` in continuous space (each
     /// cell occupies `[n, n+1)`).
     ///
-    /// Iterates every solid voxel in every loaded chunk; intended for
-    /// occasional UI events (recenter, frame, select-all), not per-frame
-    /// use. Shared by [`Self::scene_center`] and the camera-framing path.
-    pub fn scene_aabb(&self) -> Option<((i32, i32, i32), (i32, i32, i32))> {
-        let mut bounds: Option<((i32, i32, i32), (i32, i32, i32))> = None;
+    /// Tuple form of [`Self::bounding_box`]; intended for occasional UI
+    /// events (recenter, frame, select-all), not per-frame use. Shared
+    /// by [`Self::scene_center`] and the camera-framing path.
+    pub fn scene_aabb(&self) -> Option<CellBox> {
+        self.bounding_box().map(|b| (b.min, b.max))
+    }
+
+    /// Box around every solid voxel, `None` for an empty world.
+    ///
+    /// Iterates every solid voxel of every partly filled chunk (a full
+    /// chunk contributes its corners directly), so it's meant for
+    /// occasional use — framing, exporters, Model Info — not per frame.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut bounds: Option<BoundingBox> = None;
+        let mut include = |p: (i32, i32, i32)| {
+            bounds = Some(match bounds {
+                Some(b) => BoundingBox {
+                    min: (b.min.0.min(p.0), b.min.1.min(p.1), b.min.2.min(p.2)),
+                    max: (b.max.0.max(p.0), b.max.1.max(p.1), b.max.2.max(p.2)),
+                },
+                None => BoundingBox { min: p, max: p },
+            });
+        };
         for (chunk_pos, chunk) in self.chunks() {
             let chunk = chunk.read();
             if chunk.is_empty() {
                 continue;
             }
             let (ox, oy, oz) = chunk_pos.world_origin();
+            if chunk.is_full() {
+                let last = CHUNK_SIZE_I32 - 1;
+                include((ox, oy, oz));
+                include((ox + last, oy + last, oz + last));
+                continue;
+            }
             for (lp, _) in chunk.iter_solid() {
-                let p = (
-                    ox + lp.x as i32,
-                    oy + lp.y as i32,
-                    oz + lp.z as i32,
-                );
-                bounds = Some(match bounds {
-                    Some((mn, mx)) => (
-                        (mn.0.min(p.0), mn.1.min(p.1), mn.2.min(p.2)),
-                        (mx.0.max(p.0), mx.1.max(p.1), mx.2.max(p.2)),
-                    ),
-                    None => (p, p),
-                });
+                include((ox + lp.x as i32, oy + lp.y as i32, oz + lp.z as i32));
             }
         }
         bounds
     }
 
+    /// Number of solid (non-air) voxels. Cheap: sums each chunk's
+    /// running count.
+    pub fn solid_voxel_count(&self) -> u64 {
+        self.chunks
+            .values()
+            .map(|chunk| chunk.read().solid_count() as u64)
+            .sum()
+    }
+
+    /// How many solid voxels use each RGBA color, most used first (ties
+    /// by color). Its length is the number of unique colors — what
+    /// palette exporters have to fit. Visits every solid voxel.
+    pub fn color_histogram(&self) -> Vec<([u8; 4], u64)> {
+        let mut counts: HashMap<[u8; 4], u64> = HashMap::new();
+        for chunk in self.chunks.values() {
+            for (_, voxel) in chunk.read().iter_solid() {
+                *counts.entry([voxel.r, voxel.g, voxel.b, voxel.a]).or_default() += 1;
+            }
+        }
+        let mut histogram: Vec<_> = counts.into_iter().collect();
+        histogram.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        histogram
+    }
+
     /// Center of the AABB of all non-air voxels, in continuous world
     /// coordinates. `None` when the world has no non-air voxels.
     ///
@@ -285,41 +727,110 @@ impl World {
         })
     }
 
-    /// Check if any chunk needs mesh rebuild
+    /// Check if any chunk needs mesh rebuild. Cheap enough for per-frame
+    /// and stats queries: no chunk is locked.
     pub fn has_dirty_chunks(&self) -> bool {
-        self.any_dirty
-            || self
-                .chunks
-                .values()
-                .any(|c| c.read().is_dirty())
+        !self.dirty.is_empty()
+    }
+
+    /// Whether the chunk at `pos` needs a mesh rebuild
+    pub fn is_chunk_dirty(&self, pos: ChunkPos) -> bool {
+        self.dirty.contains(&pos)
     }
 
     /// Get all dirty chunks
     pub fn dirty_chunks(&self) -> Vec<ChunkPos> {
-        self.chunks
-            .iter()
-            .filter(|(_, c)| c.read().is_dirty())
-            .map(|(pos, _)| *pos)
-            .collect()
+        self.dirty.iter().copied().collect()
+    }
+
+    /// Return the dirty chunks and mark them clean in one step — what a
+    /// re-mesh pass wants. Costs what was edited since the last call,
+    /// not the world's size.
+    pub fn take_dirty_chunks(&mut self) -> Vec<ChunkPos> {
+        self.dirty.drain().collect()
+    }
+
+    /// Mark the chunk at `pos` clean, leaving the others dirty
+    pub fn clear_chunk_dirty(&mut self, pos: ChunkPos) {
+        self.dirty.remove(&pos);
     }
 
     /// Clear all dirty flags
     pub fn clear_dirty_flags(&mut self) {
-        for chunk in self.chunks.values() {
-            chunk.write().clear_dirty();
-        }
-        self.any_dirty = false;
+        self.dirty.clear();
     }
 
-    /// Remove empty chunks to free memory
+    /// Remove empty chunks to free memory, and repack the rest into
+    /// their smallest storage ([`Chunk::compact`])
     pub fn prune_empty_chunks(&mut self) {
         self.chunks.retain(|_, chunk| !chunk.read().is_empty());
+        for chunk in self.chunks.values() {
+            chunk.write().compact();
+        }
+    }
+
+    /// Heap memory held by voxel storage across all chunks, in bytes
+    /// ([`Chunk::heap_bytes`])
+    pub fn heap_bytes(&self) -> usize {
+        self.chunks.values().map(|chunk| chunk.read().heap_bytes()).sum()
+    }
+
+    /// Number of cold chunks that failed to decode
+    /// ([`Chunk::is_unreadable`])
+    pub fn unreadable_chunks(&self) -> usize {
+        self.chunks.values().filter(|chunk| chunk.read().is_unreadable()).count()
+    }
+
+    /// One streaming pass ([`crate::core::streaming`]): cool every chunk
+    /// further than `settings.keep_radius` chunks from `center`, then,
+    /// while voxel memory is over the budget, nearer ones too, farthest
+    /// first. Chunks waiting to be re-meshed are skipped. `spill` is
+    /// where cooled chunks go when spilling to disk.
+    pub fn stream_out(
+        &mut self,
+        center: ChunkPos,
+        settings: &StreamingSettings,
+        spill: Option<&Arc<SpillFile>>,
+    ) -> io::Result<StreamStats> {
+        let distance = |p: &ChunkPos| {
+            let d = (p.x - center.x)
+                .abs()
+                .max((p.y - center.y).abs())
+                .max((p.z - center.z).abs());
+            d as u32
+        };
+        let mut order: Vec<(u32, ChunkPos)> = self
+            .chunks
+            .keys()
+            .filter(|pos| !self.dirty.contains(pos))
+            .map(|pos| (distance(pos), *pos))
+            .collect();
+        order.sort_unstable_by_key(|&(distance, _)| std::cmp::Reverse(distance));
+
+        let budget = settings.budget_bytes();
+        let mut stats = StreamStats {
+            cooled: 0,
+            resident_bytes: self.heap_bytes(),
+        };
+        for (distance, pos) in order {
+            let over_budget = budget.is_some_and(|b| stats.resident_bytes > b);
+            if distance <= settings.keep_radius && !over_budget {
+                break;
+            }
+            let mut chunk = self.chunks[&pos].write();
+            let before = chunk.heap_bytes();
+            if chunk.cool(spill)? {
+                stats.cooled += 1;
+                stats.resident_bytes = stats.resident_bytes - before + chunk.heap_bytes();
+            }
+        }
+        Ok(stats)
     }
 
-    /// Clear all chunks
+    /// Clear all chunks. Their positions stay dirty so whatever was
+    /// meshed there is re-meshed as empty.
     pub fn clear(&mut self) {
-        self.chunks.clear();
-        self.any_dirty = true;
+        self.dirty.extend(self.chunks.drain().map(|(pos, _)| pos));
     }
 
     /// Create a simple test world with a ground plane
@@ -355,7 +866,7 @@ impl World {
             for y in -half_size..=half_size {
                 for x in -half_size..=half_size {
                     // Choose color based on position
-                    let color_idx = ((x + y + z).abs() as usize) % colors.len();
+                    let color_idx = (x + y + z).unsigned_abs() as usize % colors.len();
                     let (r, g, b) = colors[color_idx];
                     let voxel = Voxel::from_rgb(r, g, b);
                     self.set_voxel(
@@ -374,6 +885,18 @@ impl World {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bounds_on_ground_center_xz_and_start_at_zero() {
+        let bounds = WorldBounds::on_ground((2, 3, 1));
+        assert_eq!(bounds.size(), (2, 3, 1));
+        let (min, max) = bounds.voxel_box();
+        assert_eq!(min, (-32, 0, -32));
+        assert_eq!(max, (31, 95, -1));
+        assert!(bounds.contains_voxel(-32, 0, -1));
+        assert!(!bounds.contains_voxel(0, -1, -1));
+        assert!(!bounds.contains_voxel(32, 0, -1));
+    }
+
     #[test]
     fn test_world_get_set() {
         let mut world = World::new();
@@ -418,6 +941,144 @@ mod tests {
         assert!(dirty.contains(&ChunkPos::new(1, 0, 0)));
     }
 
+    #[test]
+    fn corner_and_edge_writes_dirty_diagonal_neighbors() {
+        let red = Voxel::from_rgb(255, 0, 0);
+        let mut world = World::new();
+        // Load the 2×2×2 block of chunks around the origin corner.
+        world.fill_region((-1, -1, -1), (0, 0, 0), red);
+        assert_eq!(world.chunk_count(), 8);
+        world.clear_dirty_flags();
+
+        // The corner cell touches all seven other chunks.
+        world.set_voxel(0, 0, 0, Voxel::AIR);
+        assert_eq!(world.dirty_chunks().len(), 8);
+        world.clear_dirty_flags();
+
+        // An edge cell (x = 0, y = 0, mid z) touches the chunks across
+        // the two faces and the one across the edge — not the others.
+        world.set_voxel(0, 0, 16, red);
+        let mut dirty = world.dirty_chunks();
+        dirty.sort_by_key(|p| (p.x, p.y, p.z));
+        let expected = [(-1, -1, 0), (-1, 0, 0), (0, -1, 0), (0, 0, 0)];
+        assert_eq!(dirty, expected.map(|(x, y, z)| ChunkPos::new(x, y, z)));
+        world.clear_dirty_flags();
+
+        // Batched writes propagate the same way.
+        world.set_voxels([((5, 5, 5), red)]);
+        assert_eq!(world.dirty_chunks(), vec![ChunkPos::ZERO]);
+        // (31, 31, 31)'s neighbors across it aren't loaded.
+        world.set_voxels([((31, 31, 31), red)]);
+        assert_eq!(world.dirty_chunks(), vec![ChunkPos::ZERO]);
+        world.set_voxels([((-1, -1, -1), Voxel::AIR)]);
+        assert_eq!(world.dirty_chunks().len(), 8);
+    }
+
+    #[test]
+    fn region_and_batch_writes_match_a_set_voxel_loop() {
+        let (min, max) = ((-37, -3, 5), (40, 9, 70));
+        let shade = |(x, y, z): (i32, i32, i32)| {
+            if (x + y + z).rem_euclid(3) == 0 {
+                Voxel::AIR
+            } else {
+                Voxel::from_rgb(x as u8, y as u8, z as u8)
+            }
+        };
+        let mut looped = World::new();
+        for z in min.2..=max.2 {
+            for y in min.1..=max.1 {
+                for x in min.0..=max.0 {
+                    looped.set_voxel(x, y, z, shade((x, y, z)));
+                }
+            }
+        }
+        let mut serial = World::new();
+        serial.apply_region(min, max, |pos, _| shade(pos));
+        let mut parallel = World::new();
+        parallel.par_apply_region(min, max, |pos, _| shade(pos));
+        let mut batched = World::new();
+        // Two writes to one cell: the later wins.
+        batched.set_voxels([((0, 0, 6), Voxel::from_rgb(1, 1, 1))]);
+        let cells = (min.2..=max.2).flat_map(|z| {
+            (min.1..=max.1).flat_map(move |y| (min.0..=max.0).map(move |x| (x, y, z)))
+        });
+        batched.set_voxels(cells.map(|pos| (pos, shade(pos))));
+
+        for world in [&serial, &parallel, &batched] {
+            assert_eq!(world.chunk_count(), looped.chunk_count());
+            for z in min.2 - 1..=max.2 + 1 {
+                for y in min.1 - 1..=max.1 + 1 {
+                    for x in min.0 - 1..=max.0 + 1 {
+                        assert_eq!(world.get_voxel(x, y, z), looped.get_voxel(x, y, z));
+                    }
+                }
+            }
+        }
+
+        // `f` sees the current voxel: recolor only what's solid.
+        let red = Voxel::from_rgb(255, 0, 0);
+        serial.apply_region(min, max, |_, v| if v.is_solid() { red } else { v });
+        assert_eq!(serial.get_voxel(-37, -3, 6), red);
+        assert!(serial.get_voxel(-37, -3, 7).is_air());
+    }
+
+    #[test]
+    fn region_writes_dirty_only_what_changed() {
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (63, 3, 3), Voxel::from_rgb(9, 9, 9));
+        assert_eq!(world.chunk_count(), 2);
+        world.clear_dirty_flags();
+
+        // Rewriting the same voxel changes nothing.
+        world.fill_region((0, 0, 0), (63, 3, 3), Voxel::from_rgb(9, 9, 9));
+        assert!(world.dirty_chunks().is_empty());
+
+        // A change on chunk 0's +X face dirties the neighbor too.
+        world.fill_region((31, 0, 0), (31, 0, 0), Voxel::from_rgb(1, 2, 3));
+        let mut dirty = world.dirty_chunks();
+        dirty.sort_by_key(|p| p.x);
+        assert_eq!(dirty, vec![ChunkPos::new(0, 0, 0), ChunkPos::new(1, 0, 0)]);
+
+        // Writing air into unloaded space doesn't leave empty chunks.
+        world.fill_region((200, 0, 0), (260, 40, 0), Voxel::AIR);
+        assert_eq!(world.chunk_count(), 2);
+
+        // Cells outside a bounded world are skipped.
+        let mut bounded = World::bounded(WorldBounds::single_chunk());
+        bounded.fill_region((-4, 0, 0), (35, 0, 0), Voxel::from_rgb(9, 9, 9));
+        assert_eq!(bounded.chunk_count(), 1);
+        assert!(!bounded.get_voxel(31, 0, 0).is_air());
+    }
+
+    #[test]
+    fn bounding_box_count_and_histogram_describe_the_model() {
+        let mut world = World::new();
+        assert_eq!(world.bounding_box(), None);
+        assert_eq!(world.solid_voxel_count(), 0);
+        assert!(world.color_histogram().is_empty());
+
+        // A full chunk (corners only) plus a few voxels in the negative
+        // octant, and air writes that mustn't count.
+        let (red, blue) = (Voxel::from_rgb(255, 0, 0), Voxel::from_rgb(0, 0, 255));
+        world.fill_region((0, 0, 0), (31, 31, 31), red);
+        world.set_voxel(-5, 40, -2, blue);
+        world.set_voxel(-5, 41, -2, blue);
+        world.set_voxel(100, 100, 100, Voxel::AIR);
+
+        let bounds = world.bounding_box().unwrap();
+        assert_eq!(bounds.min, (-5, 0, -2));
+        assert_eq!(bounds.max, (31, 41, 31));
+        assert_eq!(bounds.size(), (37, 42, 34));
+        assert_eq!(bounds.volume(), 37 * 42 * 34);
+        assert_eq!(world.scene_aabb(), Some((bounds.min, bounds.max)));
+
+        assert_eq!(world.solid_voxel_count(), 32 * 32 * 32 + 2);
+        assert_eq!(
+            world.color_histogram(),
+            vec![([255, 0, 0, 255], 32 * 32 * 32), ([0, 0, 255, 255], 2)]
+        );
+    }
+
     #[test]
     fn test_bounded_world() {
         let bounds = WorldBounds::centered(1);
@@ -431,6 +1092,51 @@ mod tests {
         assert!(world.get_voxel(1000, 1000, 1000).is_air());
     }
 
+    #[test]
+    fn set_bounds_discards_chunks_outside() {
+        let red = Voxel::from_rgb(255, 0, 0);
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, red);
+        world.set_voxel(40, 0, 0, red);
+        world.set_voxel(-1, 0, 0, red);
+        world.clear_dirty_flags();
+
+        let bounds = WorldBounds::enclosing((0, 0, 0), (33, 5, 5));
+        assert_eq!(bounds, WorldBounds::new(ChunkPos::ZERO, ChunkPos::new(1, 0, 0)));
+        let mut dropped = world.set_bounds(Some(bounds));
+        dropped.sort_by_key(|p| p.x);
+        assert_eq!(dropped, vec![ChunkPos::new(-1, 0, 0)]);
+        assert_eq!(world.bounds(), Some(&bounds));
+        assert!(world.get_voxel(-1, 0, 0).is_air());
+        assert!(!world.get_voxel(40, 0, 0).is_air());
+        // The kept neighbor's -X seam is exposed now.
+        assert_eq!(world.dirty_chunks(), vec![ChunkPos::ZERO]);
+
+        // Writes beyond the bounds are ignored until they're lifted.
+        world.set_voxel(100, 0, 0, red);
+        assert!(world.get_voxel(100, 0, 0).is_air());
+        assert!(world.set_bounds(None).is_empty());
+        world.set_voxel(100, 0, 0, red);
+        assert!(!world.get_voxel(100, 0, 0).is_air());
+    }
+
+    #[test]
+    fn test_neighborhood_shares_adjacent_chunks_only() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(-1, 40, 0, Voxel::from_rgb(0, 255, 0));
+        world.set_voxel(100, 0, 0, Voxel::from_rgb(0, 0, 255));
+
+        let view = world.neighborhood(ChunkPos::ZERO);
+        assert_eq!(view.chunk_count(), 2);
+        assert!(!view.get_voxel(-1, 40, 0).is_air());
+        assert!(view.get_voxel(100, 0, 0).is_air());
+
+        // Shared, not copied: later writes show through the view.
+        world.set_voxel(1, 0, 0, Voxel::from_rgb(9, 9, 9));
+        assert!(!view.get_voxel(1, 0, 0).is_air());
+    }
+
     #[test]
     fn scene_center_is_none_for_empty_world() {
         let world = World::new();
@@ -496,4 +1202,83 @@ mod tests {
             center
         );
     }
+
+    #[test]
+    fn dirty_set_tracks_edits_and_clears_incrementally() {
+        let red = Voxel::from_rgb(255, 0, 0);
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (95, 3, 3), red);
+        assert_eq!(world.dirty_chunks().len(), 3);
+        world.clear_dirty_flags();
+        assert!(!world.has_dirty_chunks());
+
+        // An interior write dirties only its own chunk.
+        world.set_voxel(40, 1, 1, Voxel::AIR);
+        assert_eq!(world.dirty_chunks(), vec![ChunkPos::new(1, 0, 0)]);
+        world.set_voxel(64, 1, 1, Voxel::AIR);
+        assert!(world.is_chunk_dirty(ChunkPos::new(2, 0, 0)));
+        assert!(world.is_chunk_dirty(ChunkPos::new(1, 0, 0)));
+        assert!(!world.is_chunk_dirty(ChunkPos::ZERO));
+
+        world.clear_chunk_dirty(ChunkPos::new(2, 0, 0));
+        assert_eq!(world.dirty_chunks(), vec![ChunkPos::new(1, 0, 0)]);
+        assert_eq!(world.take_dirty_chunks(), vec![ChunkPos::new(1, 0, 0)]);
+        assert!(!world.has_dirty_chunks());
+
+        // Clearing the world leaves its old chunks to be re-meshed empty.
+        world.clear();
+        let mut dirty = world.dirty_chunks();
+        dirty.sort_by_key(|p| p.x);
+        assert_eq!(dirty, (0..3).map(|x| ChunkPos::new(x, 0, 0)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stream_out_cools_far_chunks_first() {
+        // A row of five chunks of ground under air, too colorful for a
+        // palette (256 KB each) but a single run per row.
+        let ground = || {
+            let mut world = World::new();
+            world.par_apply_region((0, 0, 0), (159, 11, 31), |(_, y, z), _| {
+                Voxel::from_rgb(y as u8, z as u8, 255)
+            });
+            world
+        };
+        let cold = |world: &World| {
+            (0..5)
+                .filter(|&x| world.get_chunk(ChunkPos::new(x, 0, 0)).unwrap().read().is_cold())
+                .collect::<Vec<_>>()
+        };
+        let mut settings = StreamingSettings {
+            enabled: true,
+            keep_radius: 1,
+            ..Default::default()
+        };
+
+        // Dirty chunks are about to be re-meshed, so they're left alone.
+        let mut world = ground();
+        assert_eq!(world.stream_out(ChunkPos::ZERO, &settings, None).unwrap().cooled, 0);
+        world.clear_dirty_flags();
+        let stats = world.stream_out(ChunkPos::ZERO, &settings, None).unwrap();
+        assert_eq!(stats.cooled, 3);
+        assert_eq!(cold(&world), vec![2, 3, 4]);
+        assert_eq!(stats.resident_bytes, world.heap_bytes());
+        assert_eq!(world.get_voxel(130, 4, 6), Voxel::from_rgb(4, 6, 255));
+        assert!(world.get_voxel(130, 20, 7).is_air());
+
+        // Writes thaw the chunk and dirty it as usual.
+        world.set_voxel(130, 20, 7, Voxel::from_rgb(1, 2, 3));
+        assert_eq!(cold(&world), vec![2, 3]);
+        assert_eq!(world.dirty_chunks(), vec![ChunkPos::new(4, 0, 0)]);
+
+        // Over the budget, nearer chunks go too, farthest first, until
+        // it's met: 5 × 256 KB is over 1 MB, 3 × 256 KB isn't.
+        settings.keep_radius = 4;
+        settings.budget_mb = 1;
+        let mut world = ground();
+        world.clear_dirty_flags();
+        let stats = world.stream_out(ChunkPos::ZERO, &settings, None).unwrap();
+        assert_eq!(cold(&world), vec![3, 4]);
+        assert!(stats.resident_bytes <= 1024 * 1024);
+        assert_eq!(world.solid_voxel_count(), 160 * 12 * 32);
+    }
 }
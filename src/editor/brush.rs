@@ -0,0 +1,286 @@
+//! Brush shapes: which cells Place / Remove / Paint cover around the
+//! target cell.
+//!
+//! A sphere, a cube, a one-cell-thick disc lying on the hit face, or a
+//! stamp — a small model loaded from a `.vox` that Place and Paint
+//! write in its own colors. The brush size is the radius for the first
+//! three; a stamp is always its own size.
+//!
+//! Symmetry mirrors each covered cell rather than the center, so a disc
+//! or stamp comes out mirrored, not just moved.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Voxel, World};
+
+use super::SymmetryAxes;
+
+/// Largest stamp side, in voxels. Stamps are brushes; anything bigger
+/// belongs in a paste.
+pub const MAX_STAMP_SIDE: u32 = 64;
+
+/// Footprint of the brush.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BrushShape {
+    #[default]
+    Sphere,
+    Cube,
+    /// Flat round brush lying on the face under the cursor.
+    Disc,
+    /// The loaded [`BrushStamp`].
+    Stamp,
+}
+
+impl BrushShape {
+    pub const ALL: [Self; 4] = [Self::Sphere, Self::Cube, Self::Disc, Self::Stamp];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Sphere => "Sphere",
+            Self::Cube => "Cube",
+            Self::Disc => "Disc",
+            Self::Stamp => "Stamp",
+        }
+    }
+}
+
+/// A small model used as a brush: its cells relative to its center,
+/// with their colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrushStamp {
+    pub name: String,
+    pub cells: Vec<((i32, i32, i32), Voxel)>,
+}
+
+impl BrushStamp {
+    /// The solid voxels of `world`, centered on their bounding box.
+    /// `None` if there are none, or they span more than
+    /// [`MAX_STAMP_SIDE`] on any axis.
+    pub fn from_world(name: impl Into<String>, world: &World) -> Option<Self> {
+        let bounds = world.bounding_box()?;
+        let (w, h, d) = bounds.size();
+        if w.max(h).max(d) > MAX_STAMP_SIDE {
+            return None;
+        }
+        let (min, max) = (bounds.min, bounds.max);
+        let center = (
+            (min.0 + max.0).div_euclid(2),
+            (min.1 + max.1).div_euclid(2),
+            (min.2 + max.2).div_euclid(2),
+        );
+        let mut cells = Vec::new();
+        for (pos, chunk) in world.chunks() {
+            let (ox, oy, oz) = pos.world_origin();
+            for (local, voxel) in chunk.read().iter_solid() {
+                let p = (
+                    ox + local.x as i32 - center.0,
+                    oy + local.y as i32 - center.1,
+                    oz + local.z as i32 - center.2,
+                );
+                cells.push((p, *voxel));
+            }
+        }
+        cells.sort_by_key(|&(p, _)| p);
+        Some(Self {
+            name: name.into(),
+            cells,
+        })
+    }
+
+    /// Extent in voxels along X, Y and Z.
+    pub fn size(&self) -> (u32, u32, u32) {
+        let span = |f: fn(&(i32, i32, i32)) -> i32| {
+            let values = self.cells.iter().map(|(p, _)| f(p));
+            let lo = values.clone().min().unwrap_or(0);
+            let hi = values.max().unwrap_or(-1);
+            (hi - lo + 1) as u32
+        };
+        (span(|p| p.0), span(|p| p.1), span(|p| p.2))
+    }
+}
+
+/// Brush options beyond its size. Persisted with the brush in prefs;
+/// the stamp by its file, which the App reads again at startup.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrushSettings {
+    pub shape: BrushShape,
+    /// `.vox` the stamp was loaded from.
+    pub stamp_path: Option<PathBuf>,
+    #[serde(skip)]
+    pub stamp: Option<Arc<BrushStamp>>,
+    /// Surface snap: Place only fills empty cells resting on the
+    /// model, and Paint only touches voxels facing out the way the hit
+    /// face does, so a drag stays on the surface instead of tunneling
+    /// into the model.
+    pub surface_snap: bool,
+    /// Paint and Remove only touch voxels with an open face — the
+    /// model's outer layer.
+    pub top_layer_only: bool,
+}
+
+impl BrushSettings {
+    /// Cells the brush covers at `center`, with the color a stamp
+    /// writes there (`None` for the shapes, which use the brush color).
+    /// `normal` is the hit face's: the disc lies across it, and with
+    /// `rest_on_face` a stamp is shifted along it so it sits on the face
+    /// instead of sinking halfway in. A stamp shape with no stamp
+    /// loaded covers nothing.
+    pub fn cells(
+        &self,
+        center: (i32, i32, i32),
+        size: u8,
+        normal: (i32, i32, i32),
+        rest_on_face: bool,
+    ) -> Vec<((i32, i32, i32), Option<Voxel>)> {
+        let r = (size as i32 - 1).max(0);
+        let at = |(dx, dy, dz): (i32, i32, i32)| (center.0 + dx, center.1 + dy, center.2 + dz);
+        let mut out = Vec::new();
+        match self.shape {
+            BrushShape::Sphere | BrushShape::Cube | BrushShape::Disc => {
+                let n = if normal == (0, 0, 0) { (0, 1, 0) } else { normal };
+                let radius_sq = (r as f32 + 0.5).powi(2);
+                for dz in -r..=r {
+                    for dy in -r..=r {
+                        for dx in -r..=r {
+                            let dist_sq = (dx * dx + dy * dy + dz * dz) as f32;
+                            let inside = match self.shape {
+                                BrushShape::Cube => true,
+                                BrushShape::Disc => {
+                                    dx * n.0 + dy * n.1 + dz * n.2 == 0 && dist_sq <= radius_sq
+                                }
+                                _ => dist_sq <= radius_sq,
+                            };
+                            if inside {
+                                out.push((at((dx, dy, dz)), None));
+                            }
+                        }
+                    }
+                }
+            }
+            BrushShape::Stamp => {
+                let Some(stamp) = &self.stamp else {
+                    return out;
+                };
+                let depth = |p: &(i32, i32, i32)| p.0 * normal.0 + p.1 * normal.1 + p.2 * normal.2;
+                let lift = if rest_on_face {
+                    -stamp.cells.iter().map(|(p, _)| depth(p)).min().unwrap_or(0)
+                } else {
+                    0
+                };
+                for &(p, voxel) in &stamp.cells {
+                    let p = (
+                        p.0 + normal.0 * lift,
+                        p.1 + normal.1 * lift,
+                        p.2 + normal.2 * lift,
+                    );
+                    out.push((at(p), Some(voxel)));
+                }
+            }
+        }
+        out
+    }
+
+    /// [`Self::cells`] plus every mirror implied by `symmetry`, each
+    /// mirrored cell keeping its source's color. Deduped, the first
+    /// copy of a cell winning.
+    pub fn mirrored_cells(
+        &self,
+        center: (i32, i32, i32),
+        size: u8,
+        normal: (i32, i32, i32),
+        rest_on_face: bool,
+        symmetry: SymmetryAxes,
+    ) -> Vec<((i32, i32, i32), Option<Voxel>)> {
+        let cells = self.cells(center, size, normal, rest_on_face);
+        if !symmetry.any() {
+            return cells;
+        }
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        for (p, color) in cells {
+            for q in symmetry.mirror_positions(p) {
+                if seen.insert(q) {
+                    out.push((q, color));
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(shape: BrushShape) -> BrushSettings {
+        BrushSettings {
+            shape,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sphere_cube_and_disc_sizes() {
+        let up = (0, 1, 0);
+        assert_eq!(shape(BrushShape::Sphere).cells((0, 0, 0), 1, up, false).len(), 1);
+        assert_eq!(shape(BrushShape::Cube).cells((0, 0, 0), 2, up, false).len(), 27);
+        assert_eq!(shape(BrushShape::Sphere).cells((0, 0, 0), 2, up, false).len(), 19);
+        // A disc on a side face stands upright, across X.
+        let disc = shape(BrushShape::Disc).cells((5, 5, 5), 3, (1, 0, 0), false);
+        assert!(disc.iter().all(|&((x, _, _), c)| x == 5 && c.is_none()));
+        assert_eq!(disc.len(), 21);
+    }
+
+    #[test]
+    fn stamp_rests_on_the_face_in_its_colors() {
+        let mut world = World::new();
+        let red = Voxel::from_rgb(255, 0, 0);
+        for y in 10..13 {
+            world.set_voxel(4, y, 4, red);
+        }
+        let stamp = BrushStamp::from_world("pole", &world).unwrap();
+        assert_eq!(stamp.size(), (1, 3, 1));
+        let brush = BrushSettings {
+            shape: BrushShape::Stamp,
+            stamp: Some(Arc::new(stamp)),
+            ..Default::default()
+        };
+        let mut cells = brush.cells((0, 1, 0), 1, (0, 1, 0), true);
+        cells.sort_by_key(|&(p, _)| p);
+        assert_eq!(
+            cells,
+            vec![((0, 1, 0), Some(red)), ((0, 2, 0), Some(red)), ((0, 3, 0), Some(red))]
+        );
+        // Centered when not resting on a face.
+        let centered = brush.cells((0, 1, 0), 1, (0, 1, 0), false);
+        assert!(centered.iter().any(|&(p, _)| p == (0, 0, 0)));
+        // No stamp loaded: nothing.
+        assert!(shape(BrushShape::Stamp).cells((0, 0, 0), 3, (0, 1, 0), true).is_empty());
+    }
+
+    #[test]
+    fn oversized_or_empty_models_are_not_stamps() {
+        assert!(BrushStamp::from_world("empty", &World::new()).is_none());
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(1, 1, 1));
+        world.set_voxel(MAX_STAMP_SIDE as i32, 0, 0, Voxel::from_rgb(1, 1, 1));
+        assert!(BrushStamp::from_world("wide", &world).is_none());
+    }
+
+    #[test]
+    fn mirrored_disc_keeps_its_orientation() {
+        let symmetry = SymmetryAxes {
+            x: true,
+            ..Default::default()
+        };
+        let cells = shape(BrushShape::Disc).mirrored_cells((3, 0, 0), 2, (0, 0, 1), false, symmetry);
+        // Two discs of 9 cells: x = 2..=4 and its mirror x = -5..=-3.
+        assert_eq!(cells.len(), 18);
+        assert!(cells.iter().all(|&((_, _, z), _)| z == 0));
+    }
+}
@@ -8,7 +8,8 @@
 
 use crate::core::{Voxel, World};
 
-use super::{transform::build_remap_changes, Selection, VoxelChange};
+use super::transform::{build_remap_changes, mirror_pos, rotate_pos, rotated_aabb, Axis, Quarter};
+use super::{Selection, VoxelChange};
 
 /// Voxel data extracted from a selection, stored relative to its
 /// local origin so it can be pasted anywhere.
@@ -37,6 +38,44 @@ impl Clipboard {
     pub fn voxel_count(&self) -> usize {
         self.voxels.len()
     }
+
+    /// The clipboard's footprint as a selection anchored at the local
+    /// origin — the frame `rotate_pos` / `mirror_pos` work in.
+    fn local_bounds(&self) -> Selection {
+        let (w, h, d) = self.size;
+        Selection::from_corners((0, 0, 0), (w - 1, h - 1, d - 1))
+    }
+
+    /// Rotate the contents around `axis`, keeping the local origin at
+    /// the min corner (same convention as the Selection ▸ Rotate
+    /// menu). `size` swaps the two extents perpendicular to `axis` for
+    /// quarter turns. Used by paste placement to orient the blob
+    /// before committing.
+    pub fn rotated(&self, axis: Axis, quarter: Quarter) -> Clipboard {
+        let bounds = self.local_bounds();
+        Clipboard {
+            voxels: self
+                .voxels
+                .iter()
+                .map(|&(p, v)| (rotate_pos(bounds, axis, quarter, p), v))
+                .collect(),
+            size: rotated_aabb(bounds, axis, quarter).size(),
+        }
+    }
+
+    /// Mirror the contents across the midplane perpendicular to `axis`.
+    /// `size` is unchanged.
+    pub fn mirrored(&self, axis: Axis) -> Clipboard {
+        let bounds = self.local_bounds();
+        Clipboard {
+            voxels: self
+                .voxels
+                .iter()
+                .map(|&(p, v)| (mirror_pos(bounds, axis, p), v))
+                .collect(),
+            size: self.size,
+        }
+    }
 }
 
 /// Extract non-air voxels from `world` that lie inside `selection`,
@@ -405,4 +444,71 @@ mod tests {
         assert_eq!(world.get_voxel(0, 0, 0), voxel(255, 0, 0));
         assert_eq!(world.get_voxel(1, 0, 0), voxel(0, 255, 0));
     }
+
+    #[test]
+    fn paste_over_existing_then_undo_redo() {
+        // Paste composites over solid destination cells; a single undo
+        // must bring back the overwritten voxels (not air), and redo
+        // must re-apply the paste.
+        let mut world = World::new();
+        let mut history = crate::editor::CommandHistory::new(100);
+        let red = voxel(255, 0, 0);
+        let green = voxel(0, 255, 0);
+        world.set_voxel(0, 0, 0, red);
+        world.set_voxel(1, 0, 0, red);
+        world.set_voxel(10, 0, 0, green);
+
+        let sel = Selection::from_corners((0, 0, 0), (1, 0, 0));
+        let cb = copy_selection_to_clipboard(&world, sel);
+        let changes = build_paste_changes(&world, &cb, (10, 0, 0));
+        history.execute(Command::set_voxels(changes), &mut world);
+        assert_eq!(world.get_voxel(10, 0, 0), red);
+        assert_eq!(world.get_voxel(11, 0, 0), red);
+
+        history.undo(&mut world);
+        assert_eq!(world.get_voxel(10, 0, 0), green);
+        assert!(world.get_voxel(11, 0, 0).is_air());
+
+        history.redo(&mut world);
+        assert_eq!(world.get_voxel(10, 0, 0), red);
+        assert_eq!(world.get_voxel(11, 0, 0), red);
+    }
+
+    #[test]
+    fn rotated_quarter_swaps_extents_and_stays_in_bounds() {
+        // 3×1×2 L-ish blob; a Y quarter turn makes it 2×1×3 and every
+        // voxel must land inside the new footprint.
+        let cb = Clipboard {
+            voxels: vec![
+                ((0, 0, 0), voxel(255, 0, 0)),
+                ((2, 0, 0), voxel(0, 255, 0)),
+                ((2, 0, 1), voxel(0, 0, 255)),
+            ],
+            size: (3, 1, 2),
+        };
+        let r = cb.rotated(Axis::Y, Quarter::Cw);
+        assert_eq!(r.size, (2, 1, 3));
+        assert_eq!(r.voxel_count(), 3);
+        for &((x, y, z), _) in &r.voxels {
+            assert!((0..2).contains(&x) && y == 0 && (0..3).contains(&z));
+        }
+        // Four quarter turns come back to the original.
+        let back = r
+            .rotated(Axis::Y, Quarter::Cw)
+            .rotated(Axis::Y, Quarter::Cw)
+            .rotated(Axis::Y, Quarter::Cw);
+        assert_eq!(back, cb);
+    }
+
+    #[test]
+    fn mirrored_flips_one_axis_and_is_self_inverse() {
+        let cb = Clipboard {
+            voxels: vec![((0, 1, 0), voxel(9, 9, 9))],
+            size: (4, 2, 1),
+        };
+        let m = cb.mirrored(Axis::X);
+        assert_eq!(m.voxels, vec![((3, 1, 0), voxel(9, 9, 9))]);
+        assert_eq!(m.size, cb.size);
+        assert_eq!(m.mirrored(Axis::X), cb);
+    }
 }
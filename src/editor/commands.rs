@@ -11,11 +11,41 @@
 //! the whole stroke even if the user painted the same cell multiple
 //! times. Merging requires `stroke_open` (set by `execute_merge`,
 //! cleared by `execute` / `end_stroke` / `undo` / `redo`).
+//!
+//! The window alone splits a stroke whenever the user pauses mid-drag,
+//! so the app also brackets each mouse-held drag with
+//! [`CommandHistory::begin_stroke`] / [`CommandHistory::end_stroke`]:
+//! while the button is down, merging ignores the window entirely.
+//!
+//! Every entry has a display name for the Edit menu ("Undo Paste") and
+//! the History panel: callers attach one with [`Command::named`], and
+//! unnamed commands fall back to a generic one per variant. An edit
+//! made of several parts that should undo together is built with
+//! [`Command::compound`] and pushed as one entry.
+//!
+//! Bulk edits — generator output, big pastes, transforms of a whole
+//! model — can change millions of voxels, and a `VoxelChange` per voxel
+//! makes such an entry hundreds of megabytes and slow to replay.
+//! [`CommandHistory::execute`] stores those as a
+//! [`Command::ChunkSnapshot`] instead: a copy of every touched chunk
+//! before and after, in the chunks' own packed storage, swapped back in
+//! whole on undo / redo. See [`SNAPSHOT_MIN_CHANGES`] for when.
 
-use crate::core::{Voxel, World};
-use std::collections::{HashMap, VecDeque};
+use crate::core::{Chunk, ChunkPos, Voxel, World};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::{Duration, Instant};
 
+/// `SetVoxels` entries with at least this many changes are stored as
+/// chunk snapshots, provided they also average
+/// [`SNAPSHOT_MIN_CHANGES_PER_CHUNK`] per touched chunk.
+pub const SNAPSHOT_MIN_CHANGES: usize = 32_768;
+
+/// A `VoxelChange` is 28 bytes and a palette-packed chunk copy 4–32 KB,
+/// twice over for before and after; below about a thousand changes a
+/// chunk, the change list is the smaller of the two.
+pub const SNAPSHOT_MIN_CHANGES_PER_CHUNK: usize = 1024;
+
 /// A reversible edit command
 #[derive(Debug, Clone)]
 pub enum Command {
@@ -36,6 +66,39 @@ pub enum Command {
         old_voxels: Vec<((i32, i32, i32), Voxel)>,
         new_voxel: Voxel,
     },
+    /// Whole chunks before and after a bulk edit. Built by
+    /// [`CommandHistory::execute`] from a large `SetVoxels`, never
+    /// directly.
+    ChunkSnapshot(Vec<ChunkSnapshot>),
+    /// Several commands as one undo entry: executed in order, undone
+    /// in reverse. Each part is built against the world as the parts
+    /// before it leave it.
+    Compound(Vec<Command>),
+    /// `command` under the name shown in the Edit menu and the History
+    /// panel ("Paste", "Rotate").
+    Named {
+        label: Cow<'static, str>,
+        command: Box<Command>,
+    },
+}
+
+/// One chunk of a [`Command::ChunkSnapshot`]. A chunk that didn't
+/// exist on one side is stored empty.
+#[derive(Clone)]
+pub struct ChunkSnapshot {
+    pub pos: ChunkPos,
+    pub before: Chunk,
+    pub after: Chunk,
+}
+
+impl std::fmt::Debug for ChunkSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkSnapshot")
+            .field("pos", &self.pos)
+            .field("before_solid", &self.before.solid_count())
+            .field("after_solid", &self.after.solid_count())
+            .finish()
+    }
 }
 
 /// Single voxel change record
@@ -81,6 +144,44 @@ impl Command {
         }
     }
 
+    /// Group `parts` into one undo entry. Noop parts are dropped, and a
+    /// single remaining part is returned as-is.
+    pub fn compound(parts: Vec<Command>) -> Self {
+        let mut parts: Vec<Command> = parts.into_iter().filter(|c| !c.is_noop()).collect();
+        if parts.len() == 1 {
+            parts.pop().unwrap()
+        } else {
+            Command::Compound(parts)
+        }
+    }
+
+    /// This command under `label` in the Edit menu and History panel.
+    /// Renames an already-named command rather than nesting it.
+    pub fn named(self, label: impl Into<Cow<'static, str>>) -> Self {
+        let command = match self {
+            Command::Named { command, .. } => command,
+            other => Box::new(other),
+        };
+        Command::Named {
+            label: label.into(),
+            command,
+        }
+    }
+
+    /// Display name: the one given to [`Command::named`], or a generic
+    /// one for the variant.
+    pub fn label(&self) -> Cow<'_, str> {
+        match self {
+            Command::SetVoxel { .. } => Cow::Borrowed("Set Voxel"),
+            Command::SetVoxels { changes } if changes.len() == 1 => Cow::Borrowed("Edit 1 Voxel"),
+            Command::SetVoxels { changes } => Cow::Owned(format!("Edit {} Voxels", changes.len())),
+            Command::FillRegion { .. } => Cow::Borrowed("Fill Region"),
+            Command::ChunkSnapshot(chunks) => Cow::Owned(format!("Edit {} Chunks", chunks.len())),
+            Command::Compound(parts) => Cow::Owned(format!("{} Grouped Edits", parts.len())),
+            Command::Named { label, .. } => Cow::Borrowed(label),
+        }
+    }
+
     /// Execute the command (apply changes)
     pub fn execute(&self, world: &mut World) {
         match self {
@@ -88,13 +189,22 @@ impl Command {
                 world.set_voxel(pos.0, pos.1, pos.2, *new_voxel);
             }
             Command::SetVoxels { changes } => {
-                for change in changes {
-                    world.set_voxel(change.pos.0, change.pos.1, change.pos.2, change.new_voxel);
-                }
+                world.set_voxels(changes.iter().map(|change| (change.pos, change.new_voxel)));
             }
             Command::FillRegion { min, max, new_voxel, .. } => {
                 world.fill_region(*min, *max, *new_voxel);
             }
+            Command::ChunkSnapshot(chunks) => {
+                for chunk in chunks {
+                    world.restore_chunk(chunk.pos, chunk.after.clone());
+                }
+            }
+            Command::Compound(parts) => {
+                for part in parts {
+                    part.execute(world);
+                }
+            }
+            Command::Named { command, .. } => command.execute(world),
         }
     }
 
@@ -105,15 +215,22 @@ impl Command {
                 world.set_voxel(pos.0, pos.1, pos.2, *old_voxel);
             }
             Command::SetVoxels { changes } => {
-                for change in changes {
-                    world.set_voxel(change.pos.0, change.pos.1, change.pos.2, change.old_voxel);
-                }
+                world.set_voxels(changes.iter().map(|change| (change.pos, change.old_voxel)));
             }
             Command::FillRegion { old_voxels, .. } => {
-                for (pos, old_voxel) in old_voxels {
-                    world.set_voxel(pos.0, pos.1, pos.2, *old_voxel);
+                world.set_voxels(old_voxels.iter().copied());
+            }
+            Command::ChunkSnapshot(chunks) => {
+                for chunk in chunks {
+                    world.restore_chunk(chunk.pos, chunk.before.clone());
+                }
+            }
+            Command::Compound(parts) => {
+                for part in parts.iter().rev() {
+                    part.undo(world);
                 }
             }
+            Command::Named { command, .. } => command.undo(world),
         }
     }
 
@@ -127,17 +244,88 @@ impl Command {
             Command::FillRegion { old_voxels, new_voxel, .. } => {
                 old_voxels.iter().all(|(_, old)| old == new_voxel)
             }
+            Command::ChunkSnapshot(chunks) => chunks.is_empty(),
+            Command::Compound(parts) => parts.iter().all(Command::is_noop),
+            Command::Named { command, .. } => command.is_noop(),
+        }
+    }
+
+    /// Execute the command and return the form to keep on the undo
+    /// stack: itself, or — for a `SetVoxels` big and dense enough (see
+    /// [`SNAPSHOT_MIN_CHANGES`]) — a `ChunkSnapshot` of the chunks it
+    /// touched. Names and compound parts carry over.
+    fn execute_for_history(self, world: &mut World) -> Command {
+        match self {
+            Command::SetVoxels { changes } if changes.len() >= SNAPSHOT_MIN_CHANGES => {
+                let touched: Vec<ChunkPos> = changes
+                    .iter()
+                    .map(|c| ChunkPos::from_world_pos(c.pos.0, c.pos.1, c.pos.2))
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect();
+                if changes.len() < touched.len() * SNAPSHOT_MIN_CHANGES_PER_CHUNK {
+                    let command = Command::SetVoxels { changes };
+                    command.execute(world);
+                    return command;
+                }
+                let before: Vec<Chunk> = touched.iter().map(|&pos| world.chunk_copy(pos)).collect();
+                world.set_voxels(changes.into_iter().map(|c| (c.pos, c.new_voxel)));
+                let chunks = touched
+                    .into_iter()
+                    .zip(before)
+                    .map(|(pos, before)| ChunkSnapshot {
+                        pos,
+                        before,
+                        after: world.chunk_copy(pos),
+                    })
+                    .collect();
+                Command::ChunkSnapshot(chunks)
+            }
+            Command::Compound(parts) => Command::Compound(
+                parts
+                    .into_iter()
+                    .map(|part| part.execute_for_history(world))
+                    .collect(),
+            ),
+            Command::Named { label, command } => Command::Named {
+                label,
+                command: Box::new(command.execute_for_history(world)),
+            },
+            command => {
+                command.execute(world);
+                command
+            }
         }
     }
 
     /// Try to absorb `other` into `self` in place.
     ///
-    /// Only `SetVoxels` + `SetVoxels` is mergeable. For each position,
-    /// the earliest `old_voxel` is preserved (so undo restores the
-    /// pre-stroke state) and the latest `new_voxel` is taken (so the
-    /// stroke ends in its final visible state). If the merge isn't
-    /// possible the original `other` is returned unchanged in `Err`.
+    /// Only `SetVoxels` + `SetVoxels` is mergeable, bare or both named
+    /// the same. For each position, the earliest `old_voxel` is
+    /// preserved (so undo restores the pre-stroke state) and the latest
+    /// `new_voxel` is taken (so the stroke ends in its final visible
+    /// state). If the merge isn't possible the original `other` is
+    /// returned unchanged in `Err`.
     pub fn try_merge_with(&mut self, other: Command) -> Result<(), Command> {
+        if let (
+            Command::Named { label, command },
+            Command::Named {
+                label: other_label,
+                command: other_command,
+            },
+        ) = (&mut *self, &other)
+        {
+            if label != other_label || !matches!(**other_command, Command::SetVoxels { .. }) {
+                return Err(other);
+            }
+            let Command::Named { command: inner, .. } = other else {
+                unreachable!()
+            };
+            return command.try_merge_with(*inner).map_err(|inner| Command::Named {
+                label: label.clone(),
+                command: Box::new(inner),
+            });
+        }
         if !matches!(
             (&*self, &other),
             (Command::SetVoxels { .. }, Command::SetVoxels { .. })
@@ -188,6 +376,15 @@ pub struct CommandHistory {
     /// next `end_stroke` / `execute` / `undo` / `redo` (which closes
     /// it). Required for `execute_merge` to merge instead of push.
     stroke_open: bool,
+    /// True between `begin_stroke` (mouse-down) and `end_stroke`
+    /// (mouse-up). While held, an open stroke merges regardless of
+    /// `merge_window`, so pausing mid-drag doesn't split the undo.
+    stroke_held: bool,
+    /// Set while the edited layer is locked: new commands are dropped
+    /// without touching the world, and undo / redo are refused.
+    locked: bool,
+    /// See [`Self::edit_count`].
+    edits: u64,
 }
 
 impl CommandHistory {
@@ -199,40 +396,48 @@ impl CommandHistory {
             max_size,
             last_push_at: None,
             stroke_open: false,
+            stroke_held: false,
+            locked: false,
+            edits: 0,
         }
     }
 
     /// Execute a command and push it as a fresh undo entry.
     /// Use this for one-shot operations (single click, fill, paste).
+    /// Bulk `SetVoxels` are stored as chunk snapshots (see the module
+    /// docs).
     pub fn execute(&mut self, command: Command, world: &mut World) {
-        if command.is_noop() {
+        if self.locked || command.is_noop() {
             return;
         }
-        command.execute(world);
+        let command = command.execute_for_history(world);
+        self.edits += 1;
         self.push_new(command);
         // Single-shot: don't let the next execute_merge fold into us.
         self.stroke_open = false;
     }
 
     /// Execute a command, merging into the most recent undo entry if
-    /// it's part of an open stroke and within `merge_window`. Falls
-    /// back to a fresh push otherwise. Use this for brush-style tools.
+    /// it's part of an open stroke that is either held (see
+    /// [`Self::begin_stroke`]) or within `merge_window`. Falls back to
+    /// a fresh push otherwise. Use this for brush-style tools.
     pub fn execute_merge(
         &mut self,
         command: Command,
         world: &mut World,
         merge_window: Duration,
     ) {
-        if command.is_noop() {
+        if self.locked || command.is_noop() {
             return;
         }
         command.execute(world);
+        self.edits += 1;
 
         let in_window = self
             .last_push_at
-            .map_or(false, |t| t.elapsed() < merge_window);
+            .is_some_and(|t| t.elapsed() < merge_window);
 
-        if self.stroke_open && in_window {
+        if self.stroke_open && (self.stroke_held || in_window) {
             if let Some(prev) = self.undo_stack.back_mut() {
                 match prev.try_merge_with(command) {
                     Ok(()) => {
@@ -257,11 +462,21 @@ impl CommandHistory {
         self.stroke_open = true;
     }
 
+    /// Start a held stroke. Closes whatever stroke was open (a fresh
+    /// press never folds into the previous gesture) and keeps the next
+    /// one merging until [`Self::end_stroke`], however long the user
+    /// pauses in between. Wire this to mouse-down.
+    pub fn begin_stroke(&mut self) {
+        self.stroke_open = false;
+        self.stroke_held = true;
+    }
+
     /// Force-finalize the current stroke. Subsequent `execute_merge`
     /// calls open a new stroke instead of folding into the previous
     /// command. Wire this to mouse-up.
     pub fn end_stroke(&mut self) {
         self.stroke_open = false;
+        self.stroke_held = false;
     }
 
     /// Internal: push a fully-prepared command onto the undo stack,
@@ -275,10 +490,26 @@ impl CommandHistory {
         self.last_push_at = Some(Instant::now());
     }
 
+    /// Lock or unlock the history. Every edit funnels through
+    /// `execute` / `execute_merge`, so this is the one place a locked
+    /// layer needs to be enforced.
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+    }
+
+    /// Whether edits are currently refused.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
     /// Undo the last command
     pub fn undo(&mut self, world: &mut World) -> bool {
+        if self.locked {
+            return false;
+        }
         if let Some(command) = self.undo_stack.pop_back() {
             command.undo(world);
+            self.edits += 1;
             self.redo_stack.push_back(command);
             // Any active stroke is no longer at the top of undo.
             self.stroke_open = false;
@@ -290,8 +521,12 @@ impl CommandHistory {
 
     /// Redo the last undone command
     pub fn redo(&mut self, world: &mut World) -> bool {
+        if self.locked {
+            return false;
+        }
         if let Some(command) = self.redo_stack.pop_back() {
             command.execute(world);
+            self.edits += 1;
             self.undo_stack.push_back(command);
             self.stroke_open = false;
             true
@@ -300,6 +535,21 @@ impl CommandHistory {
         }
     }
 
+    /// Undo or redo until `applied` entries are on the undo stack —
+    /// the History panel's click-to-jump. Stops early if the history is
+    /// locked or runs out of entries.
+    pub fn jump_to(&mut self, applied: usize, world: &mut World) {
+        while self.undo_stack.len() > applied && self.undo(world) {}
+        while self.undo_stack.len() < applied && self.redo(world) {}
+    }
+
+    /// How many times this history has changed the world — executes,
+    /// merges, undos and redos. Two equal readings mean no edit went
+    /// through it in between.
+    pub fn edit_count(&self) -> u64 {
+        self.edits
+    }
+
     /// Check if undo is available
     pub fn can_undo(&self) -> bool {
         !self.undo_stack.is_empty()
@@ -320,12 +570,33 @@ impl CommandHistory {
         self.redo_stack.len()
     }
 
+    /// Name of the entry the next `undo` reverts, for "Undo Paste".
+    pub fn undo_label(&self) -> Option<Cow<'_, str>> {
+        self.undo_stack.back().map(Command::label)
+    }
+
+    /// Name of the entry the next `redo` re-applies.
+    pub fn redo_label(&self) -> Option<Cow<'_, str>> {
+        self.redo_stack.back().map(Command::label)
+    }
+
+    /// Undo entries, oldest first — the last one is the current state.
+    pub fn undo_entries(&self) -> impl Iterator<Item = &Command> {
+        self.undo_stack.iter()
+    }
+
+    /// Redo entries in the order `redo` would re-apply them.
+    pub fn redo_entries(&self) -> impl Iterator<Item = &Command> {
+        self.redo_stack.iter().rev()
+    }
+
     /// Clear all history
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
         self.last_push_at = None;
         self.stroke_open = false;
+        self.stroke_held = false;
     }
 }
 
@@ -541,4 +812,213 @@ mod tests {
         history.execute_merge(cmd3, &mut world, win);
         assert_eq!(history.undo_count(), 3);
     }
+
+    #[test]
+    fn test_held_stroke_merges_outside_window() {
+        // Zero window would never merge on its own; holding the
+        // button keeps the whole drag in one undo entry.
+        let mut world = World::new();
+        let mut history = CommandHistory::new(100);
+        let win = Duration::ZERO;
+
+        history.begin_stroke();
+        for x in 0..3 {
+            let cmd = Command::set_voxels(vec![VoxelChange {
+                pos: (x, 0, 0),
+                old_voxel: Voxel::AIR,
+                new_voxel: voxel(1),
+            }]);
+            history.execute_merge(cmd, &mut world, win);
+        }
+        assert_eq!(history.undo_count(), 1);
+
+        // Release, then a new press starts a separate entry.
+        history.end_stroke();
+        history.begin_stroke();
+        let cmd = Command::set_voxels(vec![VoxelChange {
+            pos: (9, 0, 0),
+            old_voxel: Voxel::AIR,
+            new_voxel: voxel(2),
+        }]);
+        history.execute_merge(cmd, &mut world, win);
+        assert_eq!(history.undo_count(), 2);
+
+        history.undo(&mut world);
+        history.undo(&mut world);
+        assert!((0..3).all(|x| world.get_voxel(x, 0, 0).is_air()));
+    }
+
+    #[test]
+    fn test_locked_history_refuses_edits_and_undo() {
+        let mut world = World::new();
+        let mut history = CommandHistory::new(100);
+        let set = |x| {
+            Command::set_voxels(vec![VoxelChange {
+                pos: (x, 0, 0),
+                old_voxel: Voxel::AIR,
+                new_voxel: voxel(1),
+            }])
+        };
+        history.execute(set(0), &mut world);
+
+        history.set_locked(true);
+        history.execute(set(1), &mut world);
+        history.execute_merge(set(2), &mut world, Duration::from_secs(1));
+        assert!(world.get_voxel(1, 0, 0).is_air());
+        assert!(world.get_voxel(2, 0, 0).is_air());
+        assert!(!history.undo(&mut world));
+        assert!(world.get_voxel(0, 0, 0).is_solid());
+
+        history.set_locked(false);
+        assert!(history.undo(&mut world));
+        assert!(world.get_voxel(0, 0, 0).is_air());
+    }
+
+    #[test]
+    fn test_edit_count_tracks_every_world_change() {
+        let mut world = World::new();
+        let mut history = CommandHistory::new(100);
+        let window = Duration::from_secs(60);
+        history.execute(place(0, Voxel::AIR, voxel(1)), &mut world);
+        history.execute_merge(place(1, Voxel::AIR, voxel(1)), &mut world, window);
+        history.execute_merge(place(2, Voxel::AIR, voxel(1)), &mut world, window);
+        assert_eq!(history.edit_count(), 3, "merged edits count too");
+        history.undo(&mut world);
+        history.redo(&mut world);
+        assert_eq!(history.edit_count(), 5);
+        // Nothing to redo, a no-op and a locked edit change nothing.
+        history.redo(&mut world);
+        history.execute(Command::set_voxels(Vec::new()), &mut world);
+        history.set_locked(true);
+        history.execute(place(3, Voxel::AIR, voxel(1)), &mut world);
+        assert_eq!(history.edit_count(), 5);
+    }
+
+    fn place(x: i32, old: Voxel, new: Voxel) -> Command {
+        Command::set_voxels(vec![VoxelChange {
+            pos: (x, 0, 0),
+            old_voxel: old,
+            new_voxel: new,
+        }])
+    }
+
+    #[test]
+    fn test_compound_is_one_named_entry() {
+        let mut world = World::new();
+        let mut history = CommandHistory::new(100);
+        history.execute(place(9, Voxel::AIR, voxel(9)).named("Place"), &mut world);
+
+        // The second part overwrites the first's cell, so undo has to
+        // run the parts in reverse to get back to air.
+        let group = Command::compound(vec![
+            place(0, Voxel::AIR, voxel(1)),
+            place(0, voxel(1), voxel(2)),
+            place(1, Voxel::AIR, Voxel::AIR),
+        ])
+        .named("Mirror + Paste");
+        history.execute(group, &mut world);
+        assert_eq!(history.undo_count(), 2);
+        assert_eq!(history.undo_label().as_deref(), Some("Mirror + Paste"));
+        assert_eq!(world.get_voxel(0, 0, 0), voxel(2));
+
+        history.undo(&mut world);
+        assert!(world.get_voxel(0, 0, 0).is_air());
+        assert_eq!(history.undo_label().as_deref(), Some("Place"));
+        assert_eq!(history.redo_label().as_deref(), Some("Mirror + Paste"));
+        history.redo(&mut world);
+        assert_eq!(world.get_voxel(0, 0, 0), voxel(2));
+
+        let labels: Vec<_> = history.undo_entries().map(|c| c.label()).collect();
+        assert_eq!(labels, ["Place", "Mirror + Paste"]);
+        history.jump_to(0, &mut world);
+        assert!(world.get_voxel(9, 0, 0).is_air());
+        assert_eq!(history.redo_count(), 2);
+        history.jump_to(2, &mut world);
+        assert_eq!(world.get_voxel(0, 0, 0), voxel(2));
+        // Unnamed commands still get a name.
+        assert_eq!(place(0, Voxel::AIR, voxel(1)).label(), "Edit 1 Voxel");
+    }
+
+    #[test]
+    fn test_named_strokes_merge_only_under_the_same_name() {
+        let mut world = World::new();
+        let mut history = CommandHistory::new(100);
+        let win = Duration::from_secs(60);
+        let stroke = |x| place(x, Voxel::AIR, voxel(1)).named("Place Stroke");
+        history.execute_merge(stroke(0), &mut world, win);
+        history.execute_merge(stroke(1), &mut world, win);
+        assert_eq!(history.undo_count(), 1);
+        assert_eq!(history.undo_label().as_deref(), Some("Place Stroke"));
+
+        let paint = place(2, Voxel::AIR, voxel(1)).named("Paint Stroke");
+        history.execute_merge(paint, &mut world, win);
+        assert_eq!(history.undo_count(), 2);
+    }
+
+    /// Changes filling the box `min..=max` with `voxel`, read against
+    /// `world`.
+    fn box_changes(
+        world: &World,
+        min: (i32, i32, i32),
+        max: (i32, i32, i32),
+        voxel: Voxel,
+    ) -> Command {
+        let mut changes = Vec::new();
+        for z in min.2..=max.2 {
+            for y in min.1..=max.1 {
+                for x in min.0..=max.0 {
+                    changes.push(VoxelChange {
+                        pos: (x, y, z),
+                        old_voxel: world.get_voxel(x, y, z),
+                        new_voxel: voxel,
+                    });
+                }
+            }
+        }
+        Command::set_voxels(changes)
+    }
+
+    #[test]
+    fn test_bulk_edit_is_stored_as_chunk_snapshot() {
+        let mut world = World::new();
+        let mut history = CommandHistory::new(100);
+        world.fill_region((0, 0, 0), (3, 3, 3), voxel(7));
+        world.clear_dirty_flags();
+
+        // 64 × 32 × 32: two full chunks, one of them new.
+        let bulk = box_changes(&world, (0, 0, 0), (63, 31, 31), voxel(1)).named("Terrain");
+        history.execute(bulk, &mut world);
+        let entry = history.undo_entries().last().unwrap();
+        let Command::Named { command, .. } = entry else {
+            panic!("name dropped");
+        };
+        assert!(matches!(&**command, Command::ChunkSnapshot(chunks) if chunks.len() == 2));
+        assert_eq!(history.undo_label().as_deref(), Some("Terrain"));
+        assert_eq!(world.get_voxel(40, 5, 5), voxel(1));
+
+        world.clear_dirty_flags();
+        history.undo(&mut world);
+        assert_eq!(world.get_voxel(2, 2, 2), voxel(7));
+        assert!(world.get_voxel(10, 10, 10).is_air());
+        assert!(world.get_voxel(40, 5, 5).is_air());
+        assert!(world.has_dirty_chunks());
+        history.redo(&mut world);
+        assert_eq!(world.get_voxel(2, 2, 2), voxel(1));
+        assert_eq!(world.get_voxel(63, 31, 31), voxel(1));
+    }
+
+    #[test]
+    fn test_sparse_bulk_edit_keeps_voxel_changes() {
+        let mut world = World::new();
+        let mut history = CommandHistory::new(100);
+        // A long thin line: many changes, but few per chunk.
+        let n = SNAPSHOT_MIN_CHANGES as i32;
+        history.execute(box_changes(&world, (0, 0, 0), (n - 1, 0, 0), voxel(1)), &mut world);
+        assert!(matches!(
+            history.undo_entries().last(),
+            Some(Command::SetVoxels { .. })
+        ));
+        history.undo(&mut world);
+        assert!(world.get_voxel(n - 1, 0, 0).is_air());
+    }
 }
@@ -0,0 +1,200 @@
+//! Face extrusion, as in mesh editors: the hovered face picks out the
+//! connected, coplanar, same-colored patch of surface around it, and
+//! that patch is pushed outward (new voxels stacked on it) or inward
+//! (the voxels under it carved away) by a number of cells.
+//!
+//! A surface cell is a solid voxel whose neighbor along the face normal
+//! is air — so the patch follows exactly what the user sees of that
+//! face, and stops at edges, color changes, and anything sitting on it.
+
+use std::collections::{HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Voxel, World};
+
+use super::VoxelChange;
+
+/// Most surface cells one extrusion gathers: a 64×64 face. Keeps the
+/// per-frame preview walk cheap when hovering a large flat floor.
+pub const MAX_EXTRUDE_CELLS: usize = 64 * 64;
+
+/// Deepest extrusion the Tools panel offers.
+pub const MAX_EXTRUDE_DEPTH: u8 = 32;
+
+/// Which way the patch moves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExtrudeDirection {
+    /// Grow the patch along its normal, copying each surface voxel.
+    #[default]
+    Out,
+    /// Carve into the model under the patch.
+    In,
+}
+
+/// Whether `normal` is one of the six unit face normals.
+fn is_face_normal(normal: (i32, i32, i32)) -> bool {
+    normal.0.abs() + normal.1.abs() + normal.2.abs() == 1
+}
+
+/// The surface cells connected to `seed` across its `normal` face:
+/// solid, colored like `seed`, with air in front, reached through
+/// in-plane edge neighbors. Empty when `seed` isn't such a cell itself
+/// or `normal` isn't axis-aligned. Stops growing at `max_cells`.
+pub fn face_region(
+    world: &World,
+    seed: (i32, i32, i32),
+    normal: (i32, i32, i32),
+    max_cells: usize,
+) -> Vec<(i32, i32, i32)> {
+    if !is_face_normal(normal) {
+        return Vec::new();
+    }
+    let color = world.get_voxel(seed.0, seed.1, seed.2).color();
+    let on_surface = |p: (i32, i32, i32)| {
+        let v = world.get_voxel(p.0, p.1, p.2);
+        !v.is_air()
+            && v.color() == color
+            && world
+                .get_voxel(p.0 + normal.0, p.1 + normal.1, p.2 + normal.2)
+                .is_air()
+    };
+    if !on_surface(seed) {
+        return Vec::new();
+    }
+
+    // The four neighbors that stay in the face's plane.
+    let steps: Vec<(i32, i32, i32)> = [
+        (1, 0, 0),
+        (-1, 0, 0),
+        (0, 1, 0),
+        (0, -1, 0),
+        (0, 0, 1),
+        (0, 0, -1),
+    ]
+    .into_iter()
+    .filter(|d| d.0 * normal.0 + d.1 * normal.1 + d.2 * normal.2 == 0)
+    .collect();
+
+    // Breadth-first, so a capped region is a compact patch around the
+    // seed rather than one long arm.
+    let mut region = Vec::new();
+    let mut visited = HashSet::from([seed]);
+    let mut queue = VecDeque::from([seed]);
+    while let Some(pos) = queue.pop_front() {
+        if region.len() >= max_cells {
+            break;
+        }
+        region.push(pos);
+        for &(dx, dy, dz) in &steps {
+            let next = (pos.0 + dx, pos.1 + dy, pos.2 + dz);
+            if visited.insert(next) && on_surface(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    region
+}
+
+/// The writes that extrude `region` (from [`face_region`]) by `depth`
+/// cells. `Out` copies each surface voxel into the air in front of it,
+/// one column per cell, stopping a column early at anything already
+/// solid; `In` clears each column from the surface voxel inward,
+/// stopping at the first air so a thin wall doesn't punch into the
+/// space behind it.
+pub fn build_extrude_changes(
+    world: &World,
+    region: &[(i32, i32, i32)],
+    normal: (i32, i32, i32),
+    depth: u8,
+    direction: ExtrudeDirection,
+) -> Vec<VoxelChange> {
+    let mut changes = Vec::new();
+    for &p in region {
+        let source = world.get_voxel(p.0, p.1, p.2);
+        for k in 0..depth as i32 {
+            let step = match direction {
+                ExtrudeDirection::Out => k + 1,
+                ExtrudeDirection::In => -k,
+            };
+            let pos = (
+                p.0 + normal.0 * step,
+                p.1 + normal.1 * step,
+                p.2 + normal.2 * step,
+            );
+            let old = world.get_voxel(pos.0, pos.1, pos.2);
+            let (new_voxel, keep_going) = match direction {
+                ExtrudeDirection::Out => (source, old.is_air()),
+                ExtrudeDirection::In => (Voxel::AIR, !old.is_air()),
+            };
+            if !keep_going {
+                break;
+            }
+            changes.push(VoxelChange {
+                pos,
+                old_voxel: old,
+                new_voxel,
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4×4 red floor at y = 0 with one blue tile at (3, 0, 3) and a
+    /// red block sitting on (0, 0, 0).
+    fn floor() -> World {
+        let mut world = World::new();
+        let red = Voxel::from_rgb(255, 0, 0);
+        for x in 0..4 {
+            for z in 0..4 {
+                world.set_voxel(x, 0, z, red);
+            }
+        }
+        world.set_voxel(3, 0, 3, Voxel::from_rgb(0, 0, 255));
+        world.set_voxel(0, 1, 0, red);
+        world
+    }
+
+    #[test]
+    fn region_follows_the_visible_same_colored_face() {
+        let world = floor();
+        let top = face_region(&world, (1, 0, 1), (0, 1, 0), MAX_EXTRUDE_CELLS);
+        // 16 tiles, minus the blue one and the covered one.
+        assert_eq!(top.len(), 14);
+        assert!(!top.contains(&(3, 0, 3)));
+        assert!(!top.contains(&(0, 0, 0)));
+        // A side face is the floor's edge row plus the flush side of the
+        // block on top, not the whole slab.
+        assert_eq!(face_region(&world, (1, 0, 0), (0, 0, -1), 100).len(), 5);
+        // Capped, and empty for a buried cell or a bad normal.
+        assert_eq!(face_region(&world, (1, 0, 1), (0, 1, 0), 5).len(), 5);
+        assert!(face_region(&world, (0, 0, 0), (0, 1, 0), 100).is_empty());
+        assert!(face_region(&world, (1, 0, 1), (0, 0, 0), 100).is_empty());
+    }
+
+    #[test]
+    fn extrude_out_and_in_stop_at_obstacles() {
+        let mut world = floor();
+        // Something hanging two cells above (1, 0, 1).
+        world.set_voxel(1, 3, 1, Voxel::from_rgb(0, 255, 0));
+        let region = face_region(&world, (1, 0, 1), (0, 1, 0), MAX_EXTRUDE_CELLS);
+
+        let out = build_extrude_changes(&world, &region, (0, 1, 0), 3, ExtrudeDirection::Out);
+        // 13 full columns of 3, plus the 2 cells under the hanging one.
+        assert_eq!(out.len(), 13 * 3 + 2);
+        assert!(out
+            .iter()
+            .all(|c| c.old_voxel.is_air() && c.new_voxel.color() == [255, 0, 0, 255]));
+        assert!(!out.iter().any(|c| c.pos == (1, 3, 1)));
+
+        // Inset into the one-voxel floor clears just the floor, however
+        // deep.
+        let inset = build_extrude_changes(&world, &region, (0, 1, 0), 3, ExtrudeDirection::In);
+        assert_eq!(inset.len(), region.len());
+        assert!(inset.iter().all(|c| c.pos.1 == 0 && c.new_voxel.is_air()));
+    }
+}
@@ -0,0 +1,102 @@
+//! Flood-fill options: which cells a fill may spread into and across
+//! which neighbors.
+//!
+//! The default is the original behavior — every same-colored cell
+//! reachable across faces, in 3D. A fill can instead keep to the layer
+//! it was clicked in, or to the surface facing the clicked side, and
+//! can also spread across edges or corners, which lets a surface fill
+//! climb steps in terrain.
+
+use serde::{Deserialize, Serialize};
+
+/// Where a fill may spread, relative to the clicked face.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FillRegion {
+    /// The whole connected same-colored volume.
+    #[default]
+    Volume,
+    /// Only the layer of cells the clicked voxel sits in, across the
+    /// clicked face's normal.
+    Slice,
+    /// Only voxels showing a face the way the clicked one does: the
+    /// same-colored skin of the model on that side.
+    Surface,
+}
+
+impl FillRegion {
+    pub const ALL: [Self; 3] = [Self::Volume, Self::Slice, Self::Surface];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Volume => "Volume",
+            Self::Slice => "Slice",
+            Self::Surface => "Surface",
+        }
+    }
+}
+
+/// Which neighbors a fill spreads to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FillConnectivity {
+    /// The 6 cells sharing a face.
+    #[default]
+    Face,
+    /// Also the 12 sharing an edge (18 in all).
+    Edge,
+    /// Also the 8 sharing a corner (26 in all).
+    Corner,
+}
+
+impl FillConnectivity {
+    pub const ALL: [Self; 3] = [Self::Face, Self::Edge, Self::Corner];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Face => "Faces",
+            Self::Edge => "Edges",
+            Self::Corner => "Corners",
+        }
+    }
+
+    /// Offsets to the neighbors a fill spreads to.
+    pub fn offsets(self) -> Vec<(i32, i32, i32)> {
+        // Taxicab distance: 1 for a face neighbor, 2 edge, 3 corner.
+        let reach = match self {
+            Self::Face => 1,
+            Self::Edge => 2,
+            Self::Corner => 3,
+        };
+        let mut out = Vec::new();
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let steps = dx * dx + dy * dy + dz * dz;
+                    if steps > 0 && steps <= reach {
+                        out.push((dx, dy, dz));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Fill tool options. Persisted with the brush in prefs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FillSettings {
+    pub region: FillRegion,
+    pub connectivity: FillConnectivity,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connectivity_neighbor_counts() {
+        assert_eq!(FillConnectivity::Face.offsets().len(), 6);
+        assert_eq!(FillConnectivity::Edge.offsets().len(), 18);
+        assert_eq!(FillConnectivity::Corner.offsets().len(), 26);
+    }
+}
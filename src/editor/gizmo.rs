@@ -0,0 +1,261 @@
+//! Translate gizmo: three axis arrows and three plane squares around a
+//! point, hit-tested against the cursor ray.
+//!
+//! The gizmo is plain world-space geometry. The App decides what it
+//! moves (the selection, a pinned paste, a light, the mirror planes)
+//! and how big it is — it scales it with camera distance so it stays
+//! about the same size on screen. A drag reads the point under the
+//! cursor on the grabbed handle's line or plane; [`Gizmo::drag_offset`]
+//! turns two such points into a whole-voxel offset, so every target
+//! moves on the voxel grid.
+
+use glam::Vec3;
+
+use super::{Axis, Ray};
+
+/// The three axes in `(x, y, z)` order; a handle's axis index is its
+/// position here.
+pub const GIZMO_AXES: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+
+/// Where a plane square starts and ends along its two axes, as
+/// fractions of [`Gizmo::size`]. Arrows run from 0 to 1.
+pub const PLANE_SPAN: (f32, f32) = (0.25, 0.5);
+
+/// How close the ray must pass an arrow to grab it, as a fraction of
+/// [`Gizmo::size`].
+const PICK_RADIUS: f32 = 0.08;
+
+/// Below this (sine squared of the angle between the ray and an arrow,
+/// or cosine to a plane's normal) the handle is seen edge-on and can't
+/// be dragged meaningfully.
+const EDGE_ON: f32 = 1e-3;
+
+/// One grabbable part of the gizmo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GizmoHandle {
+    /// Arrow: moves along this axis only.
+    Axis(Axis),
+    /// Square: moves in the plane perpendicular to this axis.
+    Plane(Axis),
+}
+
+/// A translate gizmo at `origin`, arrows `size` long.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gizmo {
+    pub origin: Vec3,
+    pub size: f32,
+    /// Axes the target moves along. Arrows of the others are left out,
+    /// and so is every square that spans one of them.
+    pub axes: [bool; 3],
+}
+
+/// Index of `axis` into `(x, y, z)`.
+pub fn axis_index(axis: Axis) -> usize {
+    match axis {
+        Axis::X => 0,
+        Axis::Y => 1,
+        Axis::Z => 2,
+    }
+}
+
+/// Unit vector along `axis`.
+pub fn axis_vec(axis: Axis) -> Vec3 {
+    match axis {
+        Axis::X => Vec3::X,
+        Axis::Y => Vec3::Y,
+        Axis::Z => Vec3::Z,
+    }
+}
+
+impl Gizmo {
+    /// A gizmo moving along every axis.
+    pub fn new(origin: Vec3, size: f32) -> Self {
+        Self {
+            origin,
+            size,
+            axes: [true; 3],
+        }
+    }
+
+    /// Handles shown, arrows first.
+    pub fn handles(&self) -> Vec<GizmoHandle> {
+        let arrows = GIZMO_AXES
+            .into_iter()
+            .filter(|&a| self.axes[axis_index(a)])
+            .map(GizmoHandle::Axis);
+        let squares = GIZMO_AXES
+            .into_iter()
+            .filter(|&n| {
+                let i = axis_index(n);
+                self.axes[(i + 1) % 3] && self.axes[(i + 2) % 3]
+            })
+            .map(GizmoHandle::Plane);
+        arrows.chain(squares).collect()
+    }
+
+    /// The handle `ray` passes over, the one nearest the camera if it
+    /// crosses several.
+    pub fn handle_under(&self, ray: &Ray) -> Option<GizmoHandle> {
+        let mut best: Option<(f32, GizmoHandle)> = None;
+        for handle in self.handles() {
+            let t = match handle {
+                GizmoHandle::Axis(axis) => self
+                    .arrow_distance(ray, axis)
+                    .filter(|&(dist, _)| dist <= PICK_RADIUS * self.size)
+                    .map(|(_, t)| t),
+                GizmoHandle::Plane(normal) => self.square_hit(ray, normal),
+            };
+            if let Some(t) = t {
+                if best.is_none_or(|(b, _)| t < b) {
+                    best = Some((t, handle));
+                }
+            }
+        }
+        best.map(|(_, handle)| handle)
+    }
+
+    /// Point under `ray` on the line or plane `handle` moves along, or
+    /// `None` when the handle is seen edge-on or lies behind the ray.
+    pub fn drag_point(&self, handle: GizmoHandle, ray: &Ray) -> Option<Vec3> {
+        match handle {
+            GizmoHandle::Axis(axis) => {
+                let a = axis_vec(axis);
+                let w = ray.origin - self.origin;
+                let b = ray.direction.dot(a);
+                let denom = 1.0 - b * b;
+                if denom < EDGE_ON {
+                    return None;
+                }
+                let s = (a.dot(w) - b * ray.direction.dot(w)) / denom;
+                Some(self.origin + a * s)
+            }
+            GizmoHandle::Plane(normal) => {
+                let t = self.plane_t(ray, axis_vec(normal))?;
+                Some(ray.at(t))
+            }
+        }
+    }
+
+    /// Whole-voxel offset for a drag of `handle` from `from` to `to`
+    /// (both from [`Gizmo::drag_point`]), with the axes the handle
+    /// doesn't move along zeroed.
+    pub fn drag_offset(&self, handle: GizmoHandle, from: Vec3, to: Vec3) -> (i32, i32, i32) {
+        let d = (to - from).round();
+        let mut keep = [false; 3];
+        match handle {
+            GizmoHandle::Axis(axis) => keep[axis_index(axis)] = true,
+            GizmoHandle::Plane(normal) => {
+                keep = [true; 3];
+                keep[axis_index(normal)] = false;
+            }
+        }
+        let pick = |i: usize, v: f32| if keep[i] && self.axes[i] { v as i32 } else { 0 };
+        (pick(0, d.x), pick(1, d.y), pick(2, d.z))
+    }
+
+    /// Distance from `ray` to the arrow along `axis`, and how far along
+    /// the ray the closest point lies. `None` if the ray runs parallel
+    /// to the arrow or the arrow is behind it.
+    fn arrow_distance(&self, ray: &Ray, axis: Axis) -> Option<(f32, f32)> {
+        let a = axis_vec(axis);
+        let w = ray.origin - self.origin;
+        let b = ray.direction.dot(a);
+        let d = ray.direction.dot(w);
+        let denom = 1.0 - b * b;
+        if denom < EDGE_ON {
+            return None;
+        }
+        let s = ((a.dot(w) - b * d) / denom).clamp(0.0, self.size);
+        let t = s * b - d;
+        if t < 0.0 {
+            return None;
+        }
+        Some((ray.at(t).distance(self.origin + a * s), t))
+    }
+
+    /// Ray distance to the square perpendicular to `normal`, if the ray
+    /// crosses it.
+    fn square_hit(&self, ray: &Ray, normal: Axis) -> Option<f32> {
+        let i = axis_index(normal);
+        let t = self.plane_t(ray, axis_vec(normal))?;
+        let local = (ray.at(t) - self.origin) / self.size;
+        let (lo, hi) = PLANE_SPAN;
+        let inside = |v: f32| (lo..=hi).contains(&v);
+        (inside(local[(i + 1) % 3]) && inside(local[(i + 2) % 3])).then_some(t)
+    }
+
+    /// Ray distance to the plane through `origin` perpendicular to `n`.
+    fn plane_t(&self, ray: &Ray, n: Vec3) -> Option<f32> {
+        let denom = ray.direction.dot(n);
+        if denom.abs() < EDGE_ON {
+            return None;
+        }
+        let t = (self.origin - ray.origin).dot(n) / denom;
+        (t >= 0.0).then_some(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gizmo() -> Gizmo {
+        Gizmo::new(Vec3::ZERO, 4.0)
+    }
+
+    #[test]
+    fn ray_picks_the_arrow_it_crosses() {
+        // Looking straight down at the X arrow's middle.
+        let ray = Ray::new(Vec3::new(2.0, 10.0, 0.1), Vec3::NEG_Y);
+        assert_eq!(gizmo().handle_under(&ray), Some(GizmoHandle::Axis(Axis::X)));
+        // Past the arrow's tip: nothing.
+        let ray = Ray::new(Vec3::new(6.0, 10.0, 0.0), Vec3::NEG_Y);
+        assert_eq!(gizmo().handle_under(&ray), None);
+    }
+
+    #[test]
+    fn ray_picks_the_square_it_crosses() {
+        // The XZ square (normal Y) spans 1..2 along X and Z at size 4.
+        let ray = Ray::new(Vec3::new(1.5, 10.0, 1.5), Vec3::NEG_Y);
+        assert_eq!(gizmo().handle_under(&ray), Some(GizmoHandle::Plane(Axis::Y)));
+    }
+
+    #[test]
+    fn hidden_axes_drop_their_handles() {
+        let g = Gizmo {
+            axes: [true, false, true],
+            ..gizmo()
+        };
+        assert_eq!(
+            g.handles(),
+            vec![
+                GizmoHandle::Axis(Axis::X),
+                GizmoHandle::Axis(Axis::Z),
+                GizmoHandle::Plane(Axis::Y),
+            ]
+        );
+        let ray = Ray::new(Vec3::new(0.1, 2.0, 10.0), Vec3::NEG_Z);
+        assert_eq!(g.handle_under(&ray), None);
+    }
+
+    #[test]
+    fn axis_drag_follows_the_ray_along_the_axis() {
+        let g = gizmo();
+        let handle = GizmoHandle::Axis(Axis::X);
+        let from = g.drag_point(handle, &Ray::new(Vec3::new(1.0, 10.0, 3.0), Vec3::NEG_Y));
+        let to = g.drag_point(handle, &Ray::new(Vec3::new(3.6, 10.0, -2.0), Vec3::NEG_Y));
+        let (from, to) = (from.unwrap(), to.unwrap());
+        assert!((from - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-5);
+        assert_eq!(g.drag_offset(handle, from, to), (3, 0, 0));
+        // Looking along the arrow: no drag.
+        assert!(g.drag_point(handle, &Ray::new(Vec3::new(-10.0, 0.0, 0.0), Vec3::X)).is_none());
+    }
+
+    #[test]
+    fn plane_drag_keeps_the_normal_fixed() {
+        let g = gizmo();
+        let handle = GizmoHandle::Plane(Axis::Y);
+        let offset = g.drag_offset(handle, Vec3::ZERO, Vec3::new(2.4, 5.0, -1.6));
+        assert_eq!(offset, (2, 0, -2));
+    }
+}
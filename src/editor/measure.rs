@@ -0,0 +1,105 @@
+//! Ruler for the `Measure` tool: the offset and distance between two
+//! clicked voxels.
+//!
+//! The first click drops the start cell, the second the end; a third
+//! click starts over. Distances run between cell centers, so two
+//! neighbouring voxels are 1 apart, and the span counts the cells
+//! covered end to end inclusive — the number to match when copying a
+//! proportion from one part of a model to another. Like the selection
+//! this is ephemeral UI state: not saved, not undoable.
+
+/// A measurement between two voxel cells, `end` still open while the
+/// user picks it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Measurement {
+    pub start: (i32, i32, i32),
+    pub end: Option<(i32, i32, i32)>,
+}
+
+impl Measurement {
+    /// A measurement with only its start cell picked.
+    pub fn start(cell: (i32, i32, i32)) -> Self {
+        Self { start: cell, end: None }
+    }
+
+    /// Whether both ends are picked.
+    pub fn is_complete(&self) -> bool {
+        self.end.is_some()
+    }
+
+    /// Feed a click: completes an open measurement, or starts a new
+    /// one at `cell` once the current one is complete.
+    pub fn click(current: Option<Measurement>, cell: (i32, i32, i32)) -> Measurement {
+        match current {
+            Some(m) if !m.is_complete() => Measurement { end: Some(cell), ..m },
+            _ => Measurement::start(cell),
+        }
+    }
+
+    /// The end cell, or `hover` while the end is still open (the live
+    /// rubber band).
+    pub fn end_or(&self, hover: (i32, i32, i32)) -> (i32, i32, i32) {
+        self.end.unwrap_or(hover)
+    }
+
+    /// Axis-aligned offset from `start` to `end`.
+    pub fn delta(start: (i32, i32, i32), end: (i32, i32, i32)) -> (i32, i32, i32) {
+        (end.0 - start.0, end.1 - start.1, end.2 - start.2)
+    }
+
+    /// Euclidean distance between the two cell centers.
+    pub fn distance(start: (i32, i32, i32), end: (i32, i32, i32)) -> f32 {
+        let (dx, dy, dz) = Self::delta(start, end);
+        ((dx * dx + dy * dy + dz * dz) as f32).sqrt()
+    }
+
+    /// Cells covered per axis, both ends included.
+    pub fn span(start: (i32, i32, i32), end: (i32, i32, i32)) -> (u32, u32, u32) {
+        let (dx, dy, dz) = Self::delta(start, end);
+        (
+            dx.unsigned_abs() + 1,
+            dy.unsigned_abs() + 1,
+            dz.unsigned_abs() + 1,
+        )
+    }
+
+    /// One-line readout for the status bar and the viewport label:
+    /// "Δ +3, +0, -4 · 5.00 · 4×1×5" (explicit signs, like the HUD's
+    /// move delta).
+    pub fn label(start: (i32, i32, i32), end: (i32, i32, i32)) -> String {
+        let (dx, dy, dz) = Self::delta(start, end);
+        let (w, h, d) = Self::span(start, end);
+        format!(
+            "Δ {dx:+}, {dy:+}, {dz:+} · {:.2} · {w}×{h}×{d}",
+            Self::distance(start, end)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicks_alternate_between_start_and_end() {
+        let m = Measurement::click(None, (1, 2, 3));
+        assert_eq!(m, Measurement::start((1, 2, 3)));
+        let m = Measurement::click(Some(m), (4, 2, -1));
+        assert_eq!(m.end, Some((4, 2, -1)));
+        assert!(m.is_complete());
+        let m = Measurement::click(Some(m), (0, 0, 0));
+        assert_eq!(m, Measurement::start((0, 0, 0)));
+    }
+
+    #[test]
+    fn distance_and_span_between_cells() {
+        let (a, b) = ((0, 0, 0), (3, 0, -4));
+        assert_eq!(Measurement::delta(a, b), (3, 0, -4));
+        assert_eq!(Measurement::distance(a, b), 5.0);
+        assert_eq!(Measurement::span(a, b), (4, 1, 5));
+        assert_eq!(Measurement::label(a, b), "Δ +3, +0, -4 · 5.00 · 4×1×5");
+        // Same cell: no offset, one cell of span.
+        assert_eq!(Measurement::distance(b, b), 0.0);
+        assert_eq!(Measurement::span(b, b), (1, 1, 1));
+    }
+}
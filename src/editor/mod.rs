@@ -3,55 +3,100 @@
 //! This module contains:
 //! - Ray casting for voxel picking
 //! - Tool implementations (place, remove, paint)
+//! - Brush shapes and stamps
+//! - Sculpt brushes (erode, dilate, roughen)
+//! - The translate gizmo's hit-testing and snapping
 //! - Command pattern for undo/redo
 //! - History management
 
+mod brush;
 mod clipboard;
 mod commands;
+mod extrude;
+mod fill;
+mod gizmo;
+mod measure;
+mod paint;
 mod raycast;
+mod recolor;
+mod rig;
+mod sculpt;
 mod selection;
 mod shapes;
 mod socket;
 mod tools;
 mod transform;
+mod work_plane;
 
+pub use brush::{BrushSettings, BrushShape, BrushStamp, MAX_STAMP_SIDE};
 pub use clipboard::{
     build_clear_changes, build_move_changes, build_paste_changes,
     copy_selection_to_clipboard, Clipboard,
 };
-pub use commands::{Command, CommandHistory, VoxelChange};
-pub use raycast::{Ray, RaycastHit, VoxelRaycast};
-pub use selection::Selection;
-pub use shapes::{box_voxels, cylinder_voxels, line_voxels, sphere_voxels};
+pub use commands::{
+    ChunkSnapshot, Command, CommandHistory, VoxelChange, SNAPSHOT_MIN_CHANGES,
+    SNAPSHOT_MIN_CHANGES_PER_CHUNK,
+};
+pub use extrude::{
+    build_extrude_changes, face_region, ExtrudeDirection, MAX_EXTRUDE_CELLS, MAX_EXTRUDE_DEPTH,
+};
+pub use fill::{FillConnectivity, FillRegion, FillSettings};
+pub use gizmo::{axis_index, axis_vec, Gizmo, GizmoHandle, GIZMO_AXES, PLANE_SPAN};
+pub use measure::Measurement;
+pub use paint::{PaintPattern, PaintSettings};
+pub use raycast::{Ray, RaycastHit, RaycastOptions, VoxelRaycast};
+pub use recolor::{
+    build_adjust_changes, build_replace_changes, build_smooth_changes, ColorAdjust, RecolorScope,
+};
+pub use rig::{next_bone_name, Bone, BonePose, Cell, Rig};
+pub use sculpt::{build_sculpt_changes, SculptMode, SculptSettings};
+pub use selection::{
+    select_by_color, Selection, SelectionCounts, SelectionMask, SelectionStats, MAX_WAND_CELLS,
+};
+pub use shapes::{box_voxels, cylinder_voxels, hollow_box_voxels, line_voxels, sphere_voxels};
 pub use socket::{next_socket_name, Socket};
 pub use tools::{
-    compute_flood_fill_changes, eyedrop, flood_fill, flood_fill_multi, BrushTool, EditorTool,
-    Tool, ToolContext,
+    compute_fill_changes, compute_flood_fill_changes, eyedrop, flood_fill, flood_fill_multi,
+    stroke_gap_hits, BrushTool, EditorTool, FillSeed, Tool, ToolContext, MAX_FILL_VOXELS,
 };
 pub use transform::{
-    build_remap_changes, mirror_pos, mirror_selection_changes, rotate_pos,
-    rotate_selection_changes, rotated_aabb, Axis, Quarter,
+    build_crop_changes, build_remap_changes, mirror_pos, mirror_selection_changes, origin_offset,
+    rotate_pos, rotate_selection_changes, rotated_aabb, scale_selection_changes, scaled_aabb, Axis,
+    Downsample, OriginAnchor, Quarter, Scale,
 };
+pub use work_plane::WorkPlane;
+
+use crate::core::{MaterialTable, Voxel};
+
+/// Undo steps kept per history. Each scene layer gets its own
+/// [`CommandHistory`] of this depth.
+pub const UNDO_DEPTH: usize = 100;
 
-use crate::core::Voxel;
+/// Most colors the palette holds. Matches the 256 entries of a typical
+/// GIMP / JASC palette, so an imported palette fits whole.
+pub const MAX_PALETTE_COLORS: usize = 256;
 
-/// Symmetric mirroring of brush effects across world-origin planes.
+/// Symmetric mirroring of brush effects across axis-aligned planes.
 ///
 /// Each enabled axis mirrors the brush's writes across the corresponding
-/// plane through the world origin (`x = 0` / `y = 0` / `z = 0`). With
-/// multiple flags on, the brush replicates across every combination —
-/// 1 plane → 2-fold, 2 planes → 4-fold, 3 planes → 8-fold (octahedral)
-/// symmetry.
+/// plane through `center` (`x = center.0` / …; the world origin unless
+/// the planes were moved with the gizmo). With multiple flags on, the
+/// brush replicates across every combination — 1 plane → 2-fold,
+/// 2 planes → 4-fold, 3 planes → 8-fold (octahedral) symmetry.
 ///
-/// Mirroring is cell-aligned: cell `n` reflects to cell `-n - 1` so the
-/// symmetry plane lies *between* cells rather than through one. Without
-/// this offset, a cell at `n = 0` would mirror to itself and the brush
-/// would have no visible mirror partner there.
+/// Mirroring is cell-aligned: with the plane at `c`, cell `n` reflects
+/// to cell `2c - n - 1` so the symmetry plane lies *between* cells
+/// rather than through one. Without this offset, the cell at `n = c`
+/// would mirror to itself and the brush would have no visible mirror
+/// partner there.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct SymmetryAxes {
     pub x: bool,
     pub y: bool,
     pub z: bool,
+    /// Where the planes cross: the X plane is the boundary between
+    /// cells `center.0 - 1` and `center.0`, and so on.
+    pub center: (i32, i32, i32),
 }
 
 impl SymmetryAxes {
@@ -69,24 +114,45 @@ impl SymmetryAxes {
     /// always `pos` itself; subsequent elements come from each enabled
     /// axis flip applied in order. Result length matches `count()`.
     pub fn mirror_positions(&self, pos: (i32, i32, i32)) -> Vec<(i32, i32, i32)> {
+        let c = self.center;
         let mut out = Vec::with_capacity(self.count());
         out.push(pos);
         if self.x {
             for i in 0..out.len() {
                 let p = out[i];
-                out.push((-p.0 - 1, p.1, p.2));
+                out.push((2 * c.0 - p.0 - 1, p.1, p.2));
             }
         }
         if self.y {
             for i in 0..out.len() {
                 let p = out[i];
-                out.push((p.0, -p.1 - 1, p.2));
+                out.push((p.0, 2 * c.1 - p.1 - 1, p.2));
             }
         }
         if self.z {
             for i in 0..out.len() {
                 let p = out[i];
-                out.push((p.0, p.1, -p.2 - 1));
+                out.push((p.0, p.1, 2 * c.2 - p.2 - 1));
+            }
+        }
+        out
+    }
+
+    /// A face normal as seen from each of [`Self::mirror_positions`]'
+    /// copies, in the same order: flipped along every mirrored axis.
+    pub fn mirror_normals(&self, normal: (i32, i32, i32)) -> Vec<(i32, i32, i32)> {
+        let mut out = Vec::with_capacity(self.count());
+        out.push(normal);
+        for (on, flip) in [
+            (self.x, (-1, 1, 1)),
+            (self.y, (1, -1, 1)),
+            (self.z, (1, 1, -1)),
+        ] {
+            if on {
+                for i in 0..out.len() {
+                    let n = out[i];
+                    out.push((n.0 * flip.0, n.1 * flip.1, n.2 * flip.2));
+                }
             }
         }
         out
@@ -103,6 +169,16 @@ pub struct Editor {
     pub brush_color: Voxel,
     /// Brush size (radius)
     pub brush_size: u8,
+    /// How Place / Paint color the cells they write (gradient, jitter,
+    /// checker, dither). A brush option, so it persists via prefs.
+    pub paint: PaintSettings,
+    /// Shape of the Place / Remove / Paint brush, and the loaded
+    /// stamp. Persists via prefs like `paint`.
+    pub brush: BrushSettings,
+    /// Sculpt tool mode and strength.
+    pub sculpt: SculptSettings,
+    /// Fill tool region and connectivity.
+    pub fill: FillSettings,
     /// Currently hovered voxel (if any)
     pub hovered_voxel: Option<RaycastHit>,
     /// Color palette
@@ -113,18 +189,54 @@ pub struct Editor {
     /// Paint / Fill all honor it; Eyedropper doesn't write so it's
     /// exempt). Persists across sessions via prefs.
     pub symmetry: SymmetryAxes,
+    /// `Box` tool draws only the six faces instead of a solid block.
+    /// A tool option, so it persists across sessions via prefs.
+    pub box_hollow: bool,
+    /// `Extrude` tool: how many cells a click pushes the face…
+    pub extrude_depth: u8,
+    /// …and whether it grows the model or carves into it. Tool
+    /// options, persisted via prefs like `box_hollow`.
+    pub extrude_direction: ExtrudeDirection,
     /// Active box selection, if any. Set by the `Select` tool's
     /// click-drag-release lifecycle and cleared via Esc / Ctrl+D.
     /// Selection state is *not* persisted across sessions and *not*
     /// pushed onto the undo stack — it's an ephemeral marquee, like
     /// in image editors.
     pub selection: Option<Selection>,
+    /// Exact cells the magic wand picked, when `selection` came from
+    /// it. Only in effect while `selection` still equals its bounds
+    /// (see [`Self::active_mask`]), so any code that replaces the
+    /// selection box drops back to a plain box selection for free.
+    pub selection_mask: Option<SelectionMask>,
+    /// Ruler picked with the `Measure` tool, if any. Ephemeral like
+    /// the selection: Esc clears it, nothing saves it.
+    pub measurement: Option<Measurement>,
+    /// `Wand` tool: largest per-channel color difference from the
+    /// clicked voxel that still counts as the same color. Persisted
+    /// via prefs like the other tool options.
+    pub wand_tolerance: u8,
+    /// Slice that edits are restricted to while `work_plane_enabled`
+    /// is on (see [`WorkPlane`]). Kept when the mode is off so turning
+    /// it back on returns to the same layer.
+    pub work_plane: WorkPlane,
+    pub work_plane_enabled: bool,
+    /// Darken voxels off the work plane in the viewport so the layer
+    /// being drawn stands out. Display only; edits are clipped either
+    /// way. All three work-plane fields persist via prefs.
+    pub work_plane_dim: bool,
     /// Named attachment points placed with the `Socket` tool. Unlike
     /// the selection these *are* document data — they persist in
     /// `.vxlt` and export to glTF as empty nodes — but, like it, they
     /// stay out of the undo history (managed via the Tools panel). See
     /// [`Socket`].
     pub sockets: Vec<Socket>,
+    /// What each voxel material id means (Materials panel). Document
+    /// data like the sockets: saved in `.vxlt`, outside the undo
+    /// history. The app re-stamps voxels when it changes.
+    pub materials: MaterialTable,
+    /// Bones posing parts of the model (Rig panel). Document data like
+    /// the sockets. See [`Rig`].
+    pub rig: Rig,
 }
 
 impl Default for Editor {
@@ -137,15 +249,30 @@ impl Editor {
     pub fn new() -> Self {
         Self {
             current_tool: Tool::Place,
-            history: CommandHistory::new(100),
+            history: CommandHistory::new(UNDO_DEPTH),
             brush_color: Voxel::from_rgb(200, 100, 50),
             brush_size: 1,
+            paint: PaintSettings::default(),
+            brush: BrushSettings::default(),
+            sculpt: SculptSettings::default(),
+            fill: FillSettings::default(),
             hovered_voxel: None,
             palette: Self::default_palette(),
             tool_before_alt: None,
             symmetry: SymmetryAxes::default(),
+            box_hollow: false,
+            extrude_depth: 1,
+            extrude_direction: ExtrudeDirection::Out,
             selection: None,
+            selection_mask: None,
+            measurement: None,
+            wand_tolerance: 0,
+            work_plane: WorkPlane::default(),
+            work_plane_enabled: false,
+            work_plane_dim: true,
             sockets: Vec::new(),
+            materials: MaterialTable::new(),
+            rig: Rig::new(),
         }
     }
 
@@ -184,6 +311,28 @@ impl Editor {
         self.current_tool = tool;
     }
 
+    /// Replace the selection box, dropping any magic-wand mask — for
+    /// code that draws, clears, or recomputes the box rather than
+    /// carrying the picked cells along.
+    pub fn set_selection(&mut self, selection: Option<Selection>) {
+        self.selection = selection;
+        self.selection_mask = None;
+    }
+
+    /// The magic-wand mask, if it still describes the current
+    /// selection. A stale mask (the box was redrawn, cleared, or
+    /// scaled since) is ignored rather than applied to the wrong box.
+    pub fn active_mask(&self) -> Option<&SelectionMask> {
+        self.selection_mask
+            .as_ref()
+            .filter(|m| Some(m.bounds()) == self.selection)
+    }
+
+    /// The slice edits are clipped to, if work-plane mode is on.
+    pub fn active_work_plane(&self) -> Option<WorkPlane> {
+        self.work_plane_enabled.then_some(self.work_plane)
+    }
+
     /// Set brush color from palette index. Preserves the brush's
     /// material flags (emissive / metallic) — those behave like a brush
     /// mode (e.g. symmetry), so picking a palette color shouldn't clear
@@ -197,6 +346,49 @@ impl Editor {
         }
     }
 
+    /// Append `color` to the palette (RGBA only — material flags are a
+    /// brush property). Returns `false` when an entry with the same
+    /// RGB already exists or the palette is full.
+    pub fn add_palette_color(&mut self, color: Voxel) -> bool {
+        let exists = self
+            .palette
+            .iter()
+            .any(|v| v.r == color.r && v.g == color.g && v.b == color.b);
+        if exists || self.palette.len() >= MAX_PALETTE_COLORS {
+            return false;
+        }
+        self.palette.push(Voxel::from_rgba(color.r, color.g, color.b, color.a));
+        true
+    }
+
+    /// Remove palette entry `index`. Out-of-range is a no-op.
+    pub fn remove_palette_color(&mut self, index: usize) {
+        if index < self.palette.len() {
+            self.palette.remove(index);
+        }
+    }
+
+    /// Move palette entry `from` so it ends up at index `to` (the
+    /// drag-reorder in the Palette panel). Out-of-range is a no-op.
+    pub fn move_palette_color(&mut self, from: usize, to: usize) {
+        let n = self.palette.len();
+        if from >= n || to >= n || from == to {
+            return;
+        }
+        let color = self.palette.remove(from);
+        self.palette.insert(to, color);
+    }
+
+    /// Replace the palette (palette import / project load), keeping at
+    /// most [`MAX_PALETTE_COLORS`] entries. Returns how many were
+    /// dropped.
+    pub fn replace_palette(&mut self, mut colors: Vec<Voxel>) -> usize {
+        let dropped = colors.len().saturating_sub(MAX_PALETTE_COLORS);
+        colors.truncate(MAX_PALETTE_COLORS);
+        self.palette = colors;
+        dropped
+    }
+
     /// Undo last action
     pub fn undo(&mut self, world: &mut crate::core::World) {
         self.history.undo(world);
@@ -241,7 +433,7 @@ mod symmetry_tests {
 
     #[test]
     fn test_all_axes_octuple() {
-        let s = SymmetryAxes { x: true, y: true, z: true };
+        let s = SymmetryAxes { x: true, y: true, z: true, ..Default::default() };
         assert_eq!(s.count(), 8);
         let result = s.mirror_positions((5, 7, 11));
         assert_eq!(result.len(), 8);
@@ -267,12 +459,20 @@ mod symmetry_tests {
         assert_eq!(s.mirror_positions((-1, 5, 5)), vec![(-1, 5, 5), (0, 5, 5)]);
     }
 
+    #[test]
+    fn test_moved_plane_mirrors_around_its_center() {
+        // Plane between x = 3 and x = 4: 4 ↔ 3, 6 ↔ 1.
+        let s = SymmetryAxes { x: true, center: (4, 0, 0), ..Default::default() };
+        assert_eq!(s.mirror_positions((4, 5, 5)), vec![(4, 5, 5), (3, 5, 5)]);
+        assert_eq!(s.mirror_positions((6, 5, 5)), vec![(6, 5, 5), (1, 5, 5)]);
+    }
+
     #[test]
     fn test_count_matches_axis_combinations() {
         for x in [false, true] {
             for y in [false, true] {
                 for z in [false, true] {
-                    let s = SymmetryAxes { x, y, z };
+                    let s = SymmetryAxes { x, y, z, ..Default::default() };
                     let expected = 1 << (x as u32 + y as u32 + z as u32);
                     assert_eq!(s.count(), expected);
                     assert_eq!(s.mirror_positions((1, 2, 3)).len(), expected);
@@ -289,3 +489,48 @@ mod symmetry_tests {
         assert!(SymmetryAxes { z: true, ..Default::default() }.any());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(editor: &Editor) -> Vec<(u8, u8, u8)> {
+        editor.palette.iter().map(|c| (c.r, c.g, c.b)).collect()
+    }
+
+    #[test]
+    fn test_palette_add_dedupes_and_caps() {
+        let mut editor = Editor::new();
+        editor.palette.clear();
+        assert!(editor.add_palette_color(Voxel::from_rgb(1, 2, 3)));
+        assert!(!editor.add_palette_color(Voxel::from_rgb(1, 2, 3)));
+        assert_eq!(editor.palette.len(), 1);
+
+        let full: Vec<_> = (0..MAX_PALETTE_COLORS as u32)
+            .map(|i| Voxel::from_rgb(i as u8, (i >> 8) as u8, 9))
+            .collect();
+        assert_eq!(editor.replace_palette(full.clone()), 0);
+        assert!(!editor.add_palette_color(Voxel::from_rgb(250, 250, 250)));
+        let mut over = full;
+        over.push(Voxel::from_rgb(250, 250, 250));
+        assert_eq!(editor.replace_palette(over), 1);
+        assert_eq!(editor.palette.len(), MAX_PALETTE_COLORS);
+    }
+
+    #[test]
+    fn test_palette_remove_and_move() {
+        let mut editor = Editor::new();
+        editor.palette = vec![
+            Voxel::from_rgb(1, 0, 0),
+            Voxel::from_rgb(2, 0, 0),
+            Voxel::from_rgb(3, 0, 0),
+        ];
+        editor.move_palette_color(0, 2);
+        assert_eq!(rgb(&editor), vec![(2, 0, 0), (3, 0, 0), (1, 0, 0)]);
+        editor.move_palette_color(2, 0);
+        assert_eq!(rgb(&editor), vec![(1, 0, 0), (2, 0, 0), (3, 0, 0)]);
+        editor.remove_palette_color(1);
+        editor.remove_palette_color(7);
+        assert_eq!(rgb(&editor), vec![(1, 0, 0), (3, 0, 0)]);
+    }
+}
@@ -0,0 +1,329 @@
+//! Brush paint modes: instead of one flat color, Place / Paint can
+//! color each cell they write from its world position — a gradient
+//! along an axis, random jitter, or a checker / dither of two colors.
+//!
+//! Colors depend only on the cell position (and a seed), never on the
+//! order cells are painted in, so re-painting a cell during the same
+//! drag yields the same color and symmetric strokes stay consistent
+//! with their mirrors' positions.
+//!
+//! Soft paint is the one exception: the Paint tool then blends that
+//! color into what the cell already has, strongest at the brush center
+//! and fading toward its edge, so repeated strokes build up shading.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::Voxel;
+
+use super::{Axis, ColorAdjust};
+
+/// How the brush picks each cell's color.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PaintPattern {
+    /// The brush color everywhere.
+    #[default]
+    Solid,
+    /// Blend from the brush color to the second color along an axis.
+    Gradient,
+    /// The brush color with random hue / brightness variance per cell.
+    Jitter,
+    /// 3D checkerboard of the brush and second colors.
+    Checker,
+    /// Ordered (Bayer) dither mixing the second color into the brush's.
+    Dither,
+}
+
+impl PaintPattern {
+    pub const ALL: [Self; 5] = [
+        Self::Solid,
+        Self::Gradient,
+        Self::Jitter,
+        Self::Checker,
+        Self::Dither,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Solid => "Solid",
+            Self::Gradient => "Gradient",
+            Self::Jitter => "Jitter",
+            Self::Checker => "Checker",
+            Self::Dither => "Dither",
+        }
+    }
+}
+
+/// The active [`PaintPattern`] plus every pattern's settings, so
+/// switching patterns in the Tools panel doesn't lose the others'.
+/// Persisted with the brush in prefs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaintSettings {
+    pub pattern: PaintPattern,
+    /// Second color (RGBA) for Gradient / Checker / Dither.
+    pub second: [u8; 4],
+    /// Gradient direction.
+    pub axis: Axis,
+    /// World coordinate along `axis` where the gradient is pure brush
+    /// color…
+    pub gradient_start: i32,
+    /// …and where it reaches the second color. Cells beyond either end
+    /// keep that end's color.
+    pub gradient_end: i32,
+    /// Jitter: most brightness change, in percent either way.
+    pub jitter_brightness: u8,
+    /// Jitter: most hue change, in degrees either way.
+    pub jitter_hue: u8,
+    /// Jitter: varies the random pattern.
+    pub seed: u32,
+    /// Checker: side of one checker square, in cells.
+    pub checker_size: u8,
+    /// Dither: share of cells taking the second color, in percent.
+    pub dither_mix: u8,
+    /// Paint tool blends into the existing colors instead of
+    /// replacing them (see [`Self::soft_weight`]).
+    pub soft: bool,
+    /// Soft paint: how much of the new color one pass lays down at the
+    /// brush center, in percent.
+    pub soft_strength: u8,
+}
+
+impl Default for PaintSettings {
+    fn default() -> Self {
+        Self {
+            pattern: PaintPattern::Solid,
+            second: [255, 255, 255, 255],
+            axis: Axis::Y,
+            gradient_start: 0,
+            gradient_end: 16,
+            jitter_brightness: 10,
+            jitter_hue: 0,
+            seed: 0,
+            checker_size: 1,
+            dither_mix: 50,
+            soft: false,
+            soft_strength: 30,
+        }
+    }
+}
+
+/// 4×4 Bayer threshold matrix (values 0..16).
+const BAYER4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+impl PaintSettings {
+    /// The voxel the brush writes at `pos` for brush voxel `brush`.
+    /// Only the color varies; material and flags stay the brush's.
+    pub fn color_at(&self, brush: Voxel, pos: (i32, i32, i32)) -> Voxel {
+        let (x, y, z) = pos;
+        match self.pattern {
+            PaintPattern::Solid => brush,
+            PaintPattern::Gradient => {
+                let along = match self.axis {
+                    Axis::X => x,
+                    Axis::Y => y,
+                    Axis::Z => z,
+                };
+                let (start, end) = (self.gradient_start, self.gradient_end);
+                let t = if start == end {
+                    if along < start {
+                        0.0
+                    } else {
+                        1.0
+                    }
+                } else {
+                    ((along - start) as f32 / (end - start) as f32).clamp(0.0, 1.0)
+                };
+                let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                let [r, g, b, a] = self.second;
+                Voxel {
+                    r: mix(brush.r, r),
+                    g: mix(brush.g, g),
+                    b: mix(brush.b, b),
+                    a: mix(brush.a, a),
+                    ..brush
+                }
+            }
+            PaintPattern::Jitter => {
+                let h = cell_hash(pos, self.seed);
+                // Two independent values in -1..=1 from the hash halves.
+                let unit = |bits: u32| (bits & 0xFFFF) as f32 / 32767.5 - 1.0;
+                let adjust = ColorAdjust {
+                    hue: (unit(h) * self.jitter_hue as f32).round() as i32,
+                    saturation: 0,
+                    brightness: (unit(h >> 16) * self.jitter_brightness as f32).round() as i32,
+                };
+                adjust.apply(brush)
+            }
+            PaintPattern::Checker => {
+                let size = self.checker_size.max(1) as i32;
+                let parity = x.div_euclid(size) + y.div_euclid(size) + z.div_euclid(size);
+                if parity.rem_euclid(2) == 0 {
+                    brush
+                } else {
+                    self.with_second(brush)
+                }
+            }
+            PaintPattern::Dither => {
+                // Index by (x + z, y + z) so every axis-aligned face
+                // sees the whole 4×4 matrix, not a smeared row of it.
+                let i = (x + z).rem_euclid(4) as usize;
+                let j = (y + z).rem_euclid(4) as usize;
+                let threshold = BAYER4[j][i] as u32;
+                if threshold * 100 < self.dither_mix.min(100) as u32 * 16 {
+                    self.with_second(brush)
+                } else {
+                    brush
+                }
+            }
+        }
+    }
+
+    /// Soft paint: share of the new color a cell `dist` cells from the
+    /// brush center takes, for a brush of `size` (its radius in cells,
+    /// as on the Tools panel). [`Self::soft_strength`] at the center,
+    /// falling off linearly to nothing just past the brush's edge.
+    pub fn soft_weight(&self, dist: f32, size: u8) -> f32 {
+        let reach = size.max(1) as f32;
+        let falloff = (1.0 - dist / reach).clamp(0.0, 1.0);
+        self.soft_strength.min(100) as f32 / 100.0 * falloff
+    }
+
+    /// `new` with its color moved only `weight` (0..=1) of the way from
+    /// `old`'s. Material and flags are `new`'s, as with a hard paint.
+    pub fn soft_blend(old: Voxel, new: Voxel, weight: f32) -> Voxel {
+        let t = weight.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Voxel {
+            r: mix(old.r, new.r),
+            g: mix(old.g, new.g),
+            b: mix(old.b, new.b),
+            a: mix(old.a, new.a),
+            ..new
+        }
+    }
+
+    fn with_second(&self, brush: Voxel) -> Voxel {
+        let [r, g, b, a] = self.second;
+        Voxel {
+            r,
+            g,
+            b,
+            a,
+            ..brush
+        }
+    }
+}
+
+/// Well-mixed 32-bit hash of a cell and seed.
+pub(super) fn cell_hash(pos: (i32, i32, i32), seed: u32) -> u32 {
+    let mut h = seed ^ 0x9E37_79B9;
+    for v in [pos.0, pos.1, pos.2] {
+        h = (h ^ v as u32).wrapping_mul(0x85EB_CA6B);
+        h ^= h >> 13;
+    }
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^ (h >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brush() -> Voxel {
+        let mut v = Voxel::from_rgb(0, 0, 0);
+        v.set_emissive(true);
+        v
+    }
+
+    #[test]
+    fn gradient_and_checker_follow_position() {
+        let gradient = PaintSettings {
+            pattern: PaintPattern::Gradient,
+            second: [200, 100, 0, 255],
+            axis: Axis::X,
+            gradient_start: 0,
+            gradient_end: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            gradient.color_at(brush(), (-3, 9, 9)).color(),
+            [0, 0, 0, 255]
+        );
+        assert_eq!(
+            gradient.color_at(brush(), (2, 0, 0)).color(),
+            [100, 50, 0, 255]
+        );
+        assert_eq!(
+            gradient.color_at(brush(), (10, 0, 0)).color(),
+            [200, 100, 0, 255]
+        );
+        assert!(gradient.color_at(brush(), (2, 0, 0)).is_emissive());
+
+        let checker = PaintSettings {
+            pattern: PaintPattern::Checker,
+            checker_size: 2,
+            ..Default::default()
+        };
+        let black = brush().color();
+        assert_eq!(checker.color_at(brush(), (0, 0, 0)).color(), black);
+        assert_eq!(checker.color_at(brush(), (1, 1, -1)).color(), [255; 4]);
+        assert_eq!(checker.color_at(brush(), (2, 0, 0)).color(), [255; 4]);
+        assert_eq!(checker.color_at(brush(), (2, 2, 0)).color(), black);
+    }
+
+    #[test]
+    fn dither_and_jitter_mix_as_configured() {
+        let dither = |mix| PaintSettings {
+            pattern: PaintPattern::Dither,
+            dither_mix: mix,
+            ..Default::default()
+        };
+        let seconds = |settings: PaintSettings| {
+            (0..4)
+                .flat_map(|y| (0..4).map(move |x| (x, y, 7)))
+                .filter(|&p| settings.color_at(brush(), p).color() == [255; 4])
+                .count()
+        };
+        assert_eq!(seconds(dither(0)), 0);
+        assert_eq!(seconds(dither(25)), 4);
+        assert_eq!(seconds(dither(50)), 8);
+        assert_eq!(seconds(dither(100)), 16);
+
+        let base = Voxel::from_rgb(100, 150, 200);
+        let jitter = PaintSettings {
+            pattern: PaintPattern::Jitter,
+            jitter_brightness: 20,
+            ..Default::default()
+        };
+        let a = jitter.color_at(base, (3, 4, 5));
+        // Deterministic per cell, and within ±20% brightness.
+        assert_eq!(a, jitter.color_at(base, (3, 4, 5)));
+        assert!((160..=240).contains(&a.b));
+        let varied = (0..32)
+            .filter(|&x| jitter.color_at(base, (x, 0, 0)) != base)
+            .count();
+        assert!(varied > 16);
+        let reseeded = PaintSettings { seed: 1, ..jitter };
+        assert!(
+            (0..32).any(|x| reseeded.color_at(base, (x, 0, 0)) != jitter.color_at(base, (x, 0, 0)))
+        );
+    }
+
+    #[test]
+    fn soft_paint_falls_off_from_the_center() {
+        let soft = PaintSettings {
+            soft: true,
+            soft_strength: 50,
+            ..Default::default()
+        };
+        assert_eq!(soft.soft_weight(0.0, 1), 0.5);
+        assert_eq!(soft.soft_weight(1.0, 2), 0.25);
+        assert_eq!(soft.soft_weight(3.0, 2), 0.0);
+
+        let old = Voxel::from_rgb(0, 100, 200);
+        let blended = PaintSettings::soft_blend(old, brush(), 0.5);
+        assert_eq!(blended.color(), [0, 50, 100, 255]);
+        assert!(blended.is_emissive());
+        assert_eq!(PaintSettings::soft_blend(old, brush(), 0.0).color(), old.color());
+    }
+}
@@ -84,6 +84,46 @@ pub struct RaycastHit {
     pub virtual_ground: bool,
 }
 
+/// What a [`VoxelRaycast::cast_with`] may hit besides "the first solid
+/// voxel". The default is a plain [`VoxelRaycast::cast`].
+#[derive(Clone, Copy, Default)]
+pub struct RaycastOptions<'a> {
+    /// Treat every cell above this height as air — the viewport's
+    /// Y-clip hides those voxels, so the cursor reaches through them
+    /// to what is drawn.
+    pub y_max: Option<i32>,
+    /// When no voxel is hit, fall back to a virtual hit on the plane
+    /// `y = ground_plane` (see [`VoxelRaycast::cast_with_ground_plane`]).
+    pub ground_plane: Option<i32>,
+    /// Limit that virtual hit to `|x|, |z| <= ground_half_extent` — the
+    /// square the viewport grid covers. `None` = the whole plane.
+    pub ground_half_extent: Option<f32>,
+    /// Cells this rejects are air to the cast, e.g. voxels owned by a
+    /// locked layer.
+    pub filter: Option<&'a dyn Fn(i32, i32, i32) -> bool>,
+}
+
+impl RaycastOptions<'_> {
+    /// Whether the cast stops at `(x, y, z)` in `world`.
+    pub fn hits(&self, world: &World, x: i32, y: i32, z: i32) -> bool {
+        self.y_max.is_none_or(|top| y <= top)
+            && !world.get_voxel(x, y, z).is_air()
+            && self.filter.is_none_or(|accept| accept(x, y, z))
+    }
+
+    /// The virtual ground-plane hit for a `ray` that missed every
+    /// voxel, within `ground_half_extent`. `None` without a
+    /// `ground_plane`.
+    pub fn ground_hit(&self, ray: &Ray, max_distance: f32) -> Option<RaycastHit> {
+        let hit = VoxelRaycast::ground_plane(ray, max_distance, self.ground_plane?)?;
+        let inside = self.ground_half_extent.is_none_or(|half| {
+            let p = ray.at(hit.distance);
+            p.x.abs() <= half && p.z.abs() <= half
+        });
+        inside.then_some(hit)
+    }
+}
+
 /// Voxel raycaster using DDA algorithm
 pub struct VoxelRaycast;
 
@@ -92,6 +132,44 @@ impl VoxelRaycast {
     ///
     /// max_distance: Maximum distance to check (in voxel units)
     pub fn cast(ray: &Ray, world: &World, max_distance: f32) -> Option<RaycastHit> {
+        Self::cast_with(ray, world, max_distance, &RaycastOptions::default())
+    }
+
+    /// [`Self::cast`], treating every cell above `y_max` as air — the
+    /// viewport's Y-clip hides those voxels, so the cursor reaches
+    /// through them to what is drawn. `None` doesn't clip.
+    pub fn cast_below(
+        ray: &Ray,
+        world: &World,
+        max_distance: f32,
+        y_max: Option<i32>,
+    ) -> Option<RaycastHit> {
+        let options = RaycastOptions {
+            y_max,
+            ..Default::default()
+        };
+        Self::cast_with(ray, world, max_distance, &options)
+    }
+
+    /// [`Self::cast`] with the clipping, filtering and ground-plane
+    /// fallback of `options`.
+    pub fn cast_with(
+        ray: &Ray,
+        world: &World,
+        max_distance: f32,
+        options: &RaycastOptions,
+    ) -> Option<RaycastHit> {
+        Self::cast_solid(ray, max_distance, |x, y, z| options.hits(world, x, y, z))
+            .or_else(|| options.ground_hit(ray, max_distance))
+    }
+
+    /// DDA walk to the first cell `solid` accepts.
+    fn cast_solid(
+        ray: &Ray,
+        max_distance: f32,
+        solid: impl Fn(i32, i32, i32) -> bool,
+    ) -> Option<RaycastHit> {
+
         // Current voxel position
         let mut x = ray.origin.x.floor() as i32;
         let mut y = ray.origin.y.floor() as i32;
@@ -150,7 +228,7 @@ impl VoxelRaycast {
         let mut distance = 0.0f32;
 
         // Check starting voxel
-        if !world.get_voxel(x, y, z).is_air() {
+        if solid(x, y, z) {
             return Some(RaycastHit {
                 voxel_pos: (x, y, z),
                 adjacent_pos: (x, y, z), // Same position if we started inside
@@ -195,7 +273,7 @@ impl VoxelRaycast {
             }
 
             // Check if we hit a solid voxel
-            if !world.get_voxel(x, y, z).is_air() {
+            if solid(x, y, z) {
                 return Some(RaycastHit {
                     voxel_pos: (x, y, z),
                     adjacent_pos: (prev_x, prev_y, prev_z),
@@ -226,15 +304,26 @@ impl VoxelRaycast {
     /// Only fires when the camera is above the plane and looking down;
     /// looking sideways or up at the plane gives no synthetic hit
     /// (avoids the cursor "snapping" to the plane behind the user).
+    ///
+    /// `y_max` clips the voxel cast as in [`Self::cast_below`].
     pub fn cast_with_ground_plane(
         ray: &Ray,
         world: &World,
         max_distance: f32,
         plane_y: i32,
+        y_max: Option<i32>,
     ) -> Option<RaycastHit> {
-        if let Some(hit) = Self::cast(ray, world, max_distance) {
-            return Some(hit);
-        }
+        let options = RaycastOptions {
+            y_max,
+            ground_plane: Some(plane_y),
+            ..Default::default()
+        };
+        Self::cast_with(ray, world, max_distance, &options)
+    }
+
+    /// The virtual `y = plane_y` hit of [`Self::cast_with_ground_plane`]
+    /// on its own, whatever voxels lie along the ray.
+    pub fn ground_plane(ray: &Ray, max_distance: f32, plane_y: i32) -> Option<RaycastHit> {
         let plane_y_f = plane_y as f32;
         // Camera must be above the plane and the ray must head downward.
         // The 1e-6 epsilon catches near-parallel rays that would otherwise
@@ -258,6 +347,64 @@ impl VoxelRaycast {
         })
     }
 
+    /// Where `ray` enters the cube of `cell`, as the hit a cast would
+    /// report if that cell were the first solid one: the entry face's
+    /// normal, the neighbor across it and the distance to it. `None`
+    /// if the ray misses the cell or it lies behind the origin. A ray
+    /// starting inside reports the cell itself, as [`Self::cast`] does.
+    ///
+    /// Turns a cell found some other way (GPU picking) into a hit.
+    pub fn cell_hit(ray: &Ray, cell: (i32, i32, i32)) -> Option<RaycastHit> {
+        let lo = Vec3::new(cell.0 as f32, cell.1 as f32, cell.2 as f32);
+        let hi = lo + Vec3::ONE;
+        let origin = ray.origin.to_array();
+        let direction = ray.direction.to_array();
+        let (lo, hi) = (lo.to_array(), hi.to_array());
+        let mut t_near = f32::NEG_INFINITY;
+        let mut t_far = f32::INFINITY;
+        let mut entry_axis = None;
+        for axis in 0..3 {
+            if direction[axis].abs() < 1e-10 {
+                if origin[axis] < lo[axis] || origin[axis] > hi[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let inv = 1.0 / direction[axis];
+            let (t0, t1) = {
+                let a = (lo[axis] - origin[axis]) * inv;
+                let b = (hi[axis] - origin[axis]) * inv;
+                (a.min(b), a.max(b))
+            };
+            if t0 > t_near {
+                t_near = t0;
+                entry_axis = Some(axis);
+            }
+            t_far = t_far.min(t1);
+        }
+        if t_near > t_far || t_far < 0.0 {
+            return None;
+        }
+        let Some(axis) = entry_axis.filter(|_| t_near > 0.0) else {
+            return Some(RaycastHit {
+                voxel_pos: cell,
+                adjacent_pos: cell,
+                normal: (0, 0, 0),
+                distance: 0.0,
+                virtual_ground: false,
+            });
+        };
+        let mut normal = [0i32; 3];
+        normal[axis] = if direction[axis] > 0.0 { -1 } else { 1 };
+        Some(RaycastHit {
+            voxel_pos: cell,
+            adjacent_pos: (cell.0 + normal[0], cell.1 + normal[1], cell.2 + normal[2]),
+            normal: (normal[0], normal[1], normal[2]),
+            distance: t_near,
+            virtual_ground: false,
+        })
+    }
+
     /// Resolve an orbit pivot from a camera-forward `ray` (origin =
     /// camera position, direction = camera forward), Unity-style:
     ///
@@ -277,8 +424,17 @@ impl VoxelRaycast {
     /// camera target onto it preserves the view direction — the orbit
     /// re-anchors without any visible camera jump, only `distance`
     /// changes. Returns a continuous world point, not a grid cell.
-    pub fn orbit_pivot(ray: &Ray, world: &World, max_distance: f32, fallback: Vec3) -> Vec3 {
-        if let Some(hit) = Self::cast(ray, world, max_distance) {
+    ///
+    /// `y_max` clips the voxel cast as in [`Self::cast_below`], so the
+    /// pivot lands on what's drawn rather than on a hidden roof.
+    pub fn orbit_pivot(
+        ray: &Ray,
+        world: &World,
+        max_distance: f32,
+        fallback: Vec3,
+        y_max: Option<i32>,
+    ) -> Vec3 {
+        if let Some(hit) = Self::cast_below(ray, world, max_distance, y_max) {
             return ray.at(hit.distance);
         }
         // Ground-plane intersection. `1e-4` rejects rays parallel
@@ -340,6 +496,74 @@ mod tests {
         assert_eq!(hit.normal, (-1, 0, 0)); // Hit from negative X side
     }
 
+    #[test]
+    fn test_cell_hit_matches_cast() {
+        let mut world = World::new();
+        world.set_voxel(3, -2, 4, Voxel::from_rgb(255, 0, 0));
+        let ray = Ray::new(Vec3::new(-4.0, 6.0, -1.0), Vec3::new(7.5, -7.5, 5.5));
+        let cast = VoxelRaycast::cast(&ray, &world, 100.0).unwrap();
+        let hit = VoxelRaycast::cell_hit(&ray, (3, -2, 4)).unwrap();
+        assert_eq!(hit.voxel_pos, cast.voxel_pos);
+        assert_eq!(hit.adjacent_pos, cast.adjacent_pos);
+        assert_eq!(hit.normal, cast.normal);
+        assert!((hit.distance - cast.distance).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_cell_hit_misses_cells_off_the_ray() {
+        let ray = Ray::new(Vec3::new(0.5, 0.5, 0.5), Vec3::X);
+        assert!(VoxelRaycast::cell_hit(&ray, (4, 1, 0)).is_none());
+        assert!(VoxelRaycast::cell_hit(&ray, (-3, 0, 0)).is_none());
+        assert_eq!(VoxelRaycast::cell_hit(&ray, (4, 0, 0)).unwrap().normal, (-1, 0, 0));
+    }
+
+    #[test]
+    fn test_cast_with_filter_skips_rejected_cells() {
+        let mut world = World::new();
+        world.set_voxel(3, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(6, 0, 0, Voxel::from_rgb(0, 0, 255));
+        let ray = Ray::new(Vec3::new(0.5, 0.5, 0.5), Vec3::X);
+        let skip_near = |x: i32, _: i32, _: i32| x != 3;
+        let options = RaycastOptions {
+            filter: Some(&skip_near),
+            ..Default::default()
+        };
+        let hit = VoxelRaycast::cast_with(&ray, &world, 100.0, &options).unwrap();
+        assert_eq!(hit.voxel_pos, (6, 0, 0));
+        assert_eq!(hit.adjacent_pos, (5, 0, 0));
+    }
+
+    #[test]
+    fn test_cast_with_y_clip_and_ground_plane() {
+        let mut world = World::new();
+        world.set_voxel(0, 5, 0, Voxel::from_rgb(255, 0, 0));
+        let ray = Ray::new(Vec3::new(0.5, 10.0, 0.5), Vec3::NEG_Y);
+        let options = RaycastOptions {
+            y_max: Some(4),
+            ground_plane: Some(0),
+            ..Default::default()
+        };
+        let hit = VoxelRaycast::cast_with(&ray, &world, 100.0, &options).unwrap();
+        assert!(hit.virtual_ground);
+        assert_eq!(hit.adjacent_pos, (0, 0, 0));
+    }
+
+    #[test]
+    fn test_ground_hit_stays_inside_the_grid() {
+        let world = World::new();
+        let options = RaycastOptions {
+            ground_plane: Some(0),
+            ground_half_extent: Some(10.0),
+            ..Default::default()
+        };
+        let down = Vec3::new(0.0, -1.0, 0.0);
+        let inside = Ray::new(Vec3::new(9.5, 5.0, -9.5), down);
+        let hit = VoxelRaycast::cast_with(&inside, &world, 100.0, &options).unwrap();
+        assert_eq!(hit.adjacent_pos, (9, 0, -10));
+        let outside = Ray::new(Vec3::new(10.5, 5.0, 0.0), down);
+        assert!(VoxelRaycast::cast_with(&outside, &world, 100.0, &options).is_none());
+    }
+
     #[test]
     fn test_raycast_miss() {
         let world = World::new();
@@ -354,7 +578,7 @@ mod tests {
         let world = World::new();
         // Camera at (5, 10, 5), looking down toward origin (-1, -2, -1).
         let ray = Ray::new(Vec3::new(5.0, 10.0, 5.0), Vec3::new(-1.0, -2.0, -1.0));
-        let hit = VoxelRaycast::cast_with_ground_plane(&ray, &world, 100.0, 0)
+        let hit = VoxelRaycast::cast_with_ground_plane(&ray, &world, 100.0, 0, None)
             .expect("ground plane fallback should fire on empty world");
         // Adjacent position lands on the plane (y = 0).
         assert_eq!(hit.adjacent_pos.1, 0);
@@ -368,7 +592,7 @@ mod tests {
         let world = World::new();
         // Camera below or above plane, ray heading up — no synthesis.
         let ray = Ray::new(Vec3::new(0.0, 5.0, 0.0), Vec3::Y);
-        assert!(VoxelRaycast::cast_with_ground_plane(&ray, &world, 100.0, 0).is_none());
+        assert!(VoxelRaycast::cast_with_ground_plane(&ray, &world, 100.0, 0, None).is_none());
     }
 
     #[test]
@@ -377,7 +601,7 @@ mod tests {
         // Origin below plane, ray heading down — no synthesis (would
         // hit plane behind / from underneath).
         let ray = Ray::new(Vec3::new(0.0, -5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
-        assert!(VoxelRaycast::cast_with_ground_plane(&ray, &world, 100.0, 0).is_none());
+        assert!(VoxelRaycast::cast_with_ground_plane(&ray, &world, 100.0, 0, None).is_none());
     }
 
     #[test]
@@ -386,7 +610,7 @@ mod tests {
         world.set_voxel(0, 5, 0, Voxel::from_rgb(255, 0, 0));
         // Ray from above heading straight down through that voxel.
         let ray = Ray::new(Vec3::new(0.5, 10.0, 0.5), Vec3::new(0.0, -1.0, 0.0));
-        let hit = VoxelRaycast::cast_with_ground_plane(&ray, &world, 100.0, 0).unwrap();
+        let hit = VoxelRaycast::cast_with_ground_plane(&ray, &world, 100.0, 0, None).unwrap();
         // Real voxel hit, not the plane fallback.
         assert_eq!(hit.voxel_pos, (0, 5, 0));
     }
@@ -400,7 +624,7 @@ mod tests {
         // Forward ray straight down +X from origin; hits the voxel's
         // near (-X) face at x = 10, so the pivot sits on that face.
         let ray = Ray::new(Vec3::ZERO, Vec3::X);
-        let pivot = VoxelRaycast::orbit_pivot(&ray, &world, 100.0, Vec3::splat(999.0));
+        let pivot = VoxelRaycast::orbit_pivot(&ray, &world, 100.0, Vec3::splat(999.0), None);
         assert!(
             (pivot.x - 10.0).abs() < 1e-3,
             "pivot should land on the hit face at x=10, got {:?}",
@@ -416,7 +640,7 @@ mod tests {
         // Camera at y=10 looking down-forward; no voxels, so the pivot
         // is the y=0 crossing.
         let ray = Ray::new(Vec3::new(0.0, 10.0, 0.0), Vec3::new(1.0, -1.0, 0.0));
-        let pivot = VoxelRaycast::orbit_pivot(&ray, &world, 100.0, Vec3::splat(999.0));
+        let pivot = VoxelRaycast::orbit_pivot(&ray, &world, 100.0, Vec3::splat(999.0), None);
         assert!(
             pivot.y.abs() < 1e-3,
             "ground fallback should land on y=0, got {:?}",
@@ -433,7 +657,7 @@ mod tests {
         // fall back to the supplied target (no-jump view-depth plane).
         let fallback = Vec3::new(3.0, 4.0, 5.0);
         let ray = Ray::new(Vec3::new(0.0, 8.0, 0.0), Vec3::X);
-        let pivot = VoxelRaycast::orbit_pivot(&ray, &world, 100.0, fallback);
+        let pivot = VoxelRaycast::orbit_pivot(&ray, &world, 100.0, fallback, None);
         assert_eq!(pivot, fallback);
     }
 
@@ -444,7 +668,24 @@ mod tests {
         // reach cap rejects it so we don't pivot around the horizon.
         let fallback = Vec3::new(-1.0, -2.0, -3.0);
         let ray = Ray::new(Vec3::new(0.0, 5.0, 0.0), Vec3::new(1.0, -0.01, 0.0));
-        let pivot = VoxelRaycast::orbit_pivot(&ray, &world, 50.0, fallback);
+        let pivot = VoxelRaycast::orbit_pivot(&ray, &world, 50.0, fallback, None);
         assert_eq!(pivot, fallback);
     }
+
+    #[test]
+    fn cast_below_reaches_through_clipped_voxels() {
+        let mut world = World::new();
+        // A roof at y = 5 over a floor at y = 0.
+        world.set_voxel(0, 5, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(0, 255, 0));
+        let ray = Ray::new(Vec3::new(0.5, 10.0, 0.5), Vec3::NEG_Y);
+        let roof = VoxelRaycast::cast_below(&ray, &world, 100.0, None).unwrap();
+        assert_eq!(roof.voxel_pos, (0, 5, 0));
+        let floor = VoxelRaycast::cast_below(&ray, &world, 100.0, Some(4)).unwrap();
+        assert_eq!(floor.voxel_pos, (0, 0, 0));
+        assert_eq!(floor.adjacent_pos, (0, 1, 0));
+        // The clip is inclusive: the top visible layer still blocks.
+        let at = VoxelRaycast::cast_below(&ray, &world, 100.0, Some(5)).unwrap();
+        assert_eq!(at.voxel_pos, (0, 5, 0));
+    }
 }
@@ -0,0 +1,327 @@
+//! Whole-model color edits: replace every voxel of one color with
+//! another, shift hue / saturation / brightness, and smooth colors
+//! across neighbors. Each builds a `Vec<VoxelChange>` the caller wraps
+//! in `Command::set_voxels`, so one Ctrl+Z reverts the whole recolor.
+//!
+//! Colors are matched on RGBA only: a voxel's material and flags don't
+//! keep it from being picked up by a replace.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::core::{Voxel, World};
+
+use super::{Selection, VoxelChange};
+
+/// The six face-neighbor offsets.
+const FACE_OFFSETS: [(i32, i32, i32); 6] =
+    [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+
+/// Which voxels a color replace looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecolorScope {
+    /// Every voxel of the world.
+    All,
+    /// The voxels inside a box.
+    Region(Selection),
+    /// The face-connected run of matching voxels around a seed cell.
+    Contiguous((i32, i32, i32)),
+}
+
+/// Hue / saturation / brightness shift for [`build_adjust_changes`].
+/// Integer steps so the adjustment can ride in a `UiAction`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ColorAdjust {
+    /// Hue rotation in degrees.
+    pub hue: i32,
+    /// Saturation change in percent of the current saturation
+    /// (-100 = grayscale).
+    pub saturation: i32,
+    /// Brightness (HSV value) change in percent of the current value
+    /// (-100 = black).
+    pub brightness: i32,
+}
+
+impl ColorAdjust {
+    /// True when applying it changes nothing.
+    pub fn is_identity(&self) -> bool {
+        self.hue.rem_euclid(360) == 0 && self.saturation == 0 && self.brightness == 0
+    }
+
+    /// `voxel` with its color adjusted; alpha, material and flags are
+    /// kept.
+    pub fn apply(&self, voxel: Voxel) -> Voxel {
+        let (h, s, v) = rgb_to_hsv(voxel.r, voxel.g, voxel.b);
+        let scale = |x: f32, percent: i32| (x * (1.0 + percent as f32 / 100.0)).clamp(0.0, 1.0);
+        let h = (h + self.hue as f32).rem_euclid(360.0);
+        let [r, g, b] = hsv_to_rgb(h, scale(s, self.saturation), scale(v, self.brightness));
+        Voxel { r, g, b, ..voxel }
+    }
+}
+
+/// Build the changes that turn every voxel colored `from` (RGBA) in
+/// `scope` into `to`. Voxels already equal to `to` are skipped.
+pub fn build_replace_changes(
+    world: &World,
+    scope: RecolorScope,
+    from: [u8; 4],
+    to: Voxel,
+) -> Vec<VoxelChange> {
+    let matches = |v: Voxel| !v.is_air() && v.color() == from && v != to;
+    let cells = match scope {
+        RecolorScope::All => solid_voxels(world, None),
+        RecolorScope::Region(sel) => solid_voxels(world, Some(sel)),
+        RecolorScope::Contiguous(seed) => contiguous_voxels(world, seed, from),
+    };
+    cells
+        .into_iter()
+        .filter(|&(_, v)| matches(v))
+        .map(|(pos, old_voxel)| VoxelChange { pos, old_voxel, new_voxel: to })
+        .collect()
+}
+
+/// Build the changes that apply `adjust` to every solid voxel of
+/// `world`, or only those inside `region`.
+pub fn build_adjust_changes(
+    world: &World,
+    region: Option<Selection>,
+    adjust: ColorAdjust,
+) -> Vec<VoxelChange> {
+    solid_voxels(world, region)
+        .into_iter()
+        .filter_map(|(pos, old_voxel)| {
+            let new_voxel = adjust.apply(old_voxel);
+            (new_voxel != old_voxel).then_some(VoxelChange { pos, old_voxel, new_voxel })
+        })
+        .collect()
+}
+
+/// Build the changes that blur colors: each pass sets every solid voxel
+/// in `region` (or the whole world) to the mean RGB of itself and its
+/// solid face neighbors, `iterations` times. Neighbors outside the
+/// region are read but never written, so the edge blends into its
+/// surroundings. Alpha, material and flags are kept.
+pub fn build_smooth_changes(
+    world: &World,
+    region: Option<Selection>,
+    iterations: u32,
+) -> Vec<VoxelChange> {
+    let cells = solid_voxels(world, region);
+    let index: HashMap<(i32, i32, i32), usize> =
+        cells.iter().enumerate().map(|(i, &(pos, _))| (pos, i)).collect();
+    let rgb = |v: Voxel| [v.r as f32, v.g as f32, v.b as f32];
+    let mut colors: Vec<[f32; 3]> = cells.iter().map(|&(_, v)| rgb(v)).collect();
+    for _ in 0..iterations {
+        colors = cells
+            .iter()
+            .enumerate()
+            .map(|(i, &(pos, _))| {
+                let mut sum = colors[i];
+                let mut count = 1.0;
+                for (dx, dy, dz) in FACE_OFFSETS {
+                    let next = (pos.0 + dx, pos.1 + dy, pos.2 + dz);
+                    let neighbor = match index.get(&next) {
+                        Some(&j) => colors[j],
+                        None => {
+                            let v = world.get_voxel(next.0, next.1, next.2);
+                            if v.is_air() {
+                                continue;
+                            }
+                            rgb(v)
+                        }
+                    };
+                    for (acc, c) in sum.iter_mut().zip(neighbor) {
+                        *acc += c;
+                    }
+                    count += 1.0;
+                }
+                sum.map(|c| c / count)
+            })
+            .collect();
+    }
+    cells
+        .into_iter()
+        .zip(colors)
+        .filter_map(|((pos, old_voxel), [r, g, b])| {
+            let round = |c: f32| c.round() as u8;
+            let new_voxel = Voxel { r: round(r), g: round(g), b: round(b), ..old_voxel };
+            (new_voxel != old_voxel).then_some(VoxelChange { pos, old_voxel, new_voxel })
+        })
+        .collect()
+}
+
+/// Solid voxels of `world`, optionally limited to `region`. Walks the
+/// chunks rather than the box, so an oversized region costs no more
+/// than the model.
+fn solid_voxels(world: &World, region: Option<Selection>) -> Vec<((i32, i32, i32), Voxel)> {
+    let mut voxels = Vec::new();
+    for (chunk_pos, chunk) in world.chunks() {
+        let chunk = chunk.read();
+        let (ox, oy, oz) = chunk_pos.world_origin();
+        for (lp, &voxel) in chunk.iter_solid() {
+            let pos = (ox + lp.x as i32, oy + lp.y as i32, oz + lp.z as i32);
+            if region.is_none_or(|sel| sel.contains(pos)) {
+                voxels.push((pos, voxel));
+            }
+        }
+    }
+    voxels
+}
+
+/// The 6-connected voxels colored `color` reachable from `seed` (empty
+/// when the seed itself doesn't match). Unlike the Fill tool's flood
+/// there's no size cap: only solid voxels spread, so the walk ends at
+/// the model's edge.
+fn contiguous_voxels(
+    world: &World,
+    seed: (i32, i32, i32),
+    color: [u8; 4],
+) -> Vec<((i32, i32, i32), Voxel)> {
+    let mut found = Vec::new();
+    let mut visited = HashSet::from([seed]);
+    let mut stack = vec![seed];
+    while let Some(pos) = stack.pop() {
+        let voxel = world.get_voxel(pos.0, pos.1, pos.2);
+        if voxel.is_air() || voxel.color() != color {
+            continue;
+        }
+        found.push((pos, voxel));
+        for (dx, dy, dz) in FACE_OFFSETS {
+            let next = (pos.0 + dx, pos.1 + dy, pos.2 + dz);
+            if visited.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    found
+}
+
+/// RGB to `(hue degrees, saturation 0..=1, value 0..=1)`.
+fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let [r, g, b] = [r, g, b].map(|c| c as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
+
+/// Inverse of [`rgb_to_hsv`], rounded to 8-bit channels.
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [u8; 3] {
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    [r, g, b].map(|ch| ((ch + m) * 255.0).round() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::Command;
+
+    #[test]
+    fn replace_honors_each_scope() {
+        let red = Voxel::from_rgb(255, 0, 0);
+        let blue = Voxel::from_rgb(0, 0, 255);
+        let mut world = World::new();
+        // Two red runs split by a blue voxel, plus one more red far off.
+        for x in 0..5 {
+            world.set_voxel(x, 0, 0, if x == 2 { blue } else { red });
+        }
+        world.set_voxel(100, 0, 0, red);
+        let green = Voxel::from_rgb(0, 255, 0);
+        let from = red.color();
+
+        let all = build_replace_changes(&world, RecolorScope::All, from, green);
+        assert_eq!(all.len(), 5);
+        let sel = Selection::from_corners((0, 0, 0), (3, 0, 0));
+        let region = build_replace_changes(&world, RecolorScope::Region(sel), from, green);
+        assert_eq!(region.len(), 3);
+        let run = build_replace_changes(&world, RecolorScope::Contiguous((4, 0, 0)), from, green);
+        let mut cells: Vec<_> = run.iter().map(|c| c.pos).collect();
+        cells.sort();
+        assert_eq!(cells, vec![(3, 0, 0), (4, 0, 0)]);
+        assert!(build_replace_changes(&world, RecolorScope::Contiguous((2, 0, 0)), from, green)
+            .is_empty());
+
+        let cmd = Command::set_voxels(all);
+        cmd.execute(&mut world);
+        assert_eq!(world.get_voxel(100, 0, 0), green);
+        assert_eq!(world.get_voxel(2, 0, 0), blue);
+        cmd.undo(&mut world);
+        assert_eq!(world.get_voxel(100, 0, 0), red);
+    }
+
+    #[test]
+    fn adjust_shifts_hue_saturation_and_brightness() {
+        let mut red = Voxel::from_rgba(255, 0, 0, 128);
+        red.set_metallic(true);
+        let hue = ColorAdjust { hue: 120, ..Default::default() };
+        let shifted = hue.apply(red);
+        assert_eq!(shifted.color(), [0, 255, 0, 128]);
+        assert!(shifted.is_metallic());
+
+        let gray = ColorAdjust { saturation: -100, ..Default::default() };
+        assert_eq!(gray.apply(red).color(), [255, 255, 255, 128]);
+        let dim = ColorAdjust { brightness: -50, ..Default::default() };
+        assert_eq!(dim.apply(red).color(), [128, 0, 0, 128]);
+        assert!(ColorAdjust { hue: 360, ..Default::default() }.is_identity());
+
+        // Only voxels the adjustment changes are recorded.
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, red);
+        world.set_voxel(1, 0, 0, Voxel::from_rgb(128, 128, 128));
+        let changes = build_adjust_changes(&world, None, hue);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].pos, (0, 0, 0));
+        let sel = Selection::from_corners((1, 0, 0), (1, 0, 0));
+        assert_eq!(build_adjust_changes(&world, Some(sel), dim).len(), 1);
+    }
+
+    #[test]
+    fn smooth_blends_neighbors_inside_the_region() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(0, 0, 0));
+        world.set_voxel(1, 0, 0, Voxel::from_rgba(90, 0, 0, 128));
+        world.set_voxel(2, 0, 0, Voxel::from_rgb(0, 0, 0));
+        // A lone voxel has nothing to blend with.
+        world.set_voxel(10, 0, 0, Voxel::from_rgb(255, 0, 0));
+
+        let changes = build_smooth_changes(&world, None, 1);
+        let by_pos: HashMap<_, _> = changes.iter().map(|c| (c.pos, c.new_voxel)).collect();
+        assert_eq!(by_pos.len(), 3);
+        assert_eq!(by_pos[&(1, 0, 0)].color(), [30, 0, 0, 128]);
+        assert_eq!(by_pos[&(0, 0, 0)].color(), [45, 0, 0, 255]);
+
+        // Limited to a region, outside neighbors are read, not written.
+        let sel = Selection::from_corners((0, 0, 0), (0, 0, 0));
+        let changes = build_smooth_changes(&world, Some(sel), 3);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].pos, (0, 0, 0));
+
+        // More passes converge further.
+        let once = build_smooth_changes(&world, None, 1);
+        let many = build_smooth_changes(&world, None, 8);
+        let spread = |changes: &[VoxelChange]| {
+            let reds: Vec<i32> = changes.iter().map(|c| c.new_voxel.r as i32).collect();
+            reds.iter().max().unwrap() - reds.iter().min().unwrap()
+        };
+        assert!(spread(&many) < spread(&once));
+        assert!(build_smooth_changes(&world, None, 0).is_empty());
+    }
+}
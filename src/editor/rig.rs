@@ -0,0 +1,449 @@
+//! Voxel rigging: named bones that own voxel cells and pose them.
+//!
+//! A [`Bone`] claims a set of cells (each cell belongs to at most one
+//! bone; the rest of the model is the unrigged body), a pivot and an
+//! optional parent. Its [`BonePose`] rotates the cells about the pivot
+//! and then offsets them; a child follows every transform of its
+//! parent chain, so posing an upper arm carries the forearm along.
+//!
+//! Posing is non-destructive — the voxels stay where they are and the
+//! editor shows the posed result as an overlay ([`Rig::posed_voxels`]).
+//! [`Rig::bake`] writes the pose into the world as ordinary voxel
+//! changes and makes it the new rest pose. The rigged glTF export
+//! instead keeps each bone as its own node carrying the pose as a node
+//! transform ([`Rig::node_transform`]), ready for an engine to animate.
+//!
+//! Like sockets, the rig is document data: it is saved in `.vxlt` but
+//! stays out of the undo history. Undoing a bake restores the voxels,
+//! not the bones' cells.
+//!
+//! Posed voxels are found by inverse sampling: every cell in the posed
+//! bounds looks up the rest cell its center came from, so arbitrary
+//! angles leave no holes (at the price of nearest-cell aliasing).
+
+use std::collections::{BTreeSet, HashMap};
+
+use glam::{Affine3A, EulerRot, Quat, Vec3};
+
+use super::VoxelChange;
+use crate::core::{Voxel, World};
+
+/// A voxel cell position.
+pub type Cell = (i32, i32, i32);
+
+/// A bone's offset from its rest pose. Integer cells and degrees keep
+/// poses exact and easy to type; rotation is applied X, then Y, then Z
+/// about the pivot, the translation after it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BonePose {
+    pub translation: [i32; 3],
+    /// Euler angles in degrees.
+    pub rotation: [i32; 3],
+}
+
+impl BonePose {
+    /// True for the rest pose.
+    pub fn is_rest(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The rotation as a quaternion.
+    pub fn quat(&self) -> Quat {
+        let [x, y, z] = self.rotation.map(|d| (d as f32).to_radians());
+        Quat::from_euler(EulerRot::ZYX, z, y, x)
+    }
+}
+
+/// A named group of cells posed as one rigid part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bone {
+    pub name: String,
+    /// Index of the parent bone, `None` for a bone hanging off the body.
+    pub parent: Option<usize>,
+    /// Rotation center on the cell-corner lattice: `(x, y, z)` is the
+    /// corner shared by cells `x-1..=x` etc., where joints sit between
+    /// neighboring voxels.
+    pub pivot: [i32; 3],
+    pub pose: BonePose,
+    cells: BTreeSet<Cell>,
+}
+
+impl Bone {
+    /// The cells this bone owns, sorted.
+    pub fn cells(&self) -> &BTreeSet<Cell> {
+        &self.cells
+    }
+
+    /// Inclusive `(min, max)` cell bounds, `None` without cells.
+    pub fn bounds(&self) -> Option<(Cell, Cell)> {
+        let first = *self.cells.first()?;
+        Some(self.cells.iter().fold((first, first), |(lo, hi), &c| {
+            (
+                (lo.0.min(c.0), lo.1.min(c.1), lo.2.min(c.2)),
+                (hi.0.max(c.0), hi.1.max(c.1), hi.2.max(c.2)),
+            )
+        }))
+    }
+}
+
+/// The bones of a model. Bone indices are stable until a bone is
+/// removed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rig {
+    bones: Vec<Bone>,
+}
+
+impl Rig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bones(&self) -> &[Bone] {
+        &self.bones
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bones.is_empty()
+    }
+
+    /// Add a bone owning `cells` (taken from whichever bones had them)
+    /// and return its index.
+    pub fn add_bone(
+        &mut self,
+        name: impl Into<String>,
+        pivot: [i32; 3],
+        cells: impl IntoIterator<Item = Cell>,
+    ) -> usize {
+        self.bones.push(Bone {
+            name: name.into(),
+            parent: None,
+            pivot,
+            pose: BonePose::default(),
+            cells: BTreeSet::new(),
+        });
+        let index = self.bones.len() - 1;
+        self.assign(index, cells);
+        index
+    }
+
+    /// Hand `cells` to bone `index`, taking them from other bones.
+    /// Out-of-range is ignored.
+    pub fn assign(&mut self, index: usize, cells: impl IntoIterator<Item = Cell>) {
+        if index >= self.bones.len() {
+            return;
+        }
+        let cells: BTreeSet<Cell> = cells.into_iter().collect();
+        for (i, bone) in self.bones.iter_mut().enumerate() {
+            if i != index {
+                bone.cells.retain(|c| !cells.contains(c));
+            }
+        }
+        self.bones[index].cells.extend(cells);
+    }
+
+    /// The bone owning `cell`, if any.
+    pub fn bone_of(&self, cell: Cell) -> Option<usize> {
+        self.bones.iter().position(|b| b.cells.contains(&cell))
+    }
+
+    pub fn rename(&mut self, index: usize, name: impl Into<String>) {
+        if let Some(bone) = self.bones.get_mut(index) {
+            bone.name = name.into();
+        }
+    }
+
+    pub fn set_pivot(&mut self, index: usize, pivot: [i32; 3]) {
+        if let Some(bone) = self.bones.get_mut(index) {
+            bone.pivot = pivot;
+        }
+    }
+
+    pub fn set_pose(&mut self, index: usize, pose: BonePose) {
+        if let Some(bone) = self.bones.get_mut(index) {
+            bone.pose = pose;
+        }
+    }
+
+    /// Parent bone `index` to `parent`. Refuses (returns `false`) an
+    /// out-of-range index or a parent that would make a cycle.
+    pub fn set_parent(&mut self, index: usize, parent: Option<usize>) -> bool {
+        if index >= self.bones.len() {
+            return false;
+        }
+        if let Some(p) = parent {
+            if p >= self.bones.len() || self.chain(p).any(|i| i == index) {
+                return false;
+            }
+        }
+        self.bones[index].parent = parent;
+        true
+    }
+
+    /// Remove bone `index`; its cells go back to the body and its
+    /// children to its own parent.
+    pub fn remove_bone(&mut self, index: usize) -> Option<Bone> {
+        if index >= self.bones.len() {
+            return None;
+        }
+        let removed = self.bones.remove(index);
+        for bone in &mut self.bones {
+            bone.parent = match bone.parent {
+                Some(p) if p == index => removed.parent,
+                Some(p) => Some(p),
+                None => None,
+            }
+            .map(|p| if p > index { p - 1 } else { p });
+        }
+        Some(removed)
+    }
+
+    /// True when any bone is out of its rest pose.
+    pub fn is_posed(&self) -> bool {
+        self.bones.iter().any(|b| !b.pose.is_rest())
+    }
+
+    /// Put every bone back in its rest pose.
+    pub fn reset_pose(&mut self) {
+        for bone in &mut self.bones {
+            bone.pose = BonePose::default();
+        }
+    }
+
+    /// `index`, then its parent, grandparent, ...
+    fn chain(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(index), |&i| self.bones[i].parent)
+    }
+
+    /// Bone `index`'s own pose as a transform of model space: rotate
+    /// about the pivot, then offset.
+    fn local_transform(&self, index: usize) -> Affine3A {
+        let bone = &self.bones[index];
+        let pivot = Vec3::from(bone.pivot.map(|v| v as f32));
+        let offset = Vec3::from(bone.pose.translation.map(|v| v as f32));
+        Affine3A::from_translation(pivot + offset)
+            * Affine3A::from_quat(bone.pose.quat())
+            * Affine3A::from_translation(-pivot)
+    }
+
+    /// Where bone `index`'s rest geometry ends up: its own pose inside
+    /// every ancestor's.
+    pub fn transform(&self, index: usize) -> Affine3A {
+        self.chain(index)
+            .fold(Affine3A::IDENTITY, |acc, i| self.local_transform(i) * acc)
+    }
+
+    /// Bone `index`'s own pose as glTF node `(translation, rotation)`
+    /// relative to its parent node, for geometry meshed in model
+    /// space: `translation = pivot + offset - R·pivot`.
+    pub fn node_transform(&self, index: usize) -> ([f32; 3], [f32; 4]) {
+        let (_, rotation, translation) =
+            self.local_transform(index).to_scale_rotation_translation();
+        (translation.into(), rotation.into())
+    }
+
+    /// Each bone's voxels from `world` at their posed positions, per
+    /// bone. Bones whose transform is the identity contribute nothing
+    /// (their voxels are already in place).
+    fn posed_by_bone(&self, world: &World) -> Vec<Vec<(Cell, Voxel)>> {
+        (0..self.bones.len())
+            .map(|i| {
+                let transform = self.transform(i);
+                if transform.abs_diff_eq(Affine3A::IDENTITY, 1e-5) {
+                    return Vec::new();
+                }
+                let bone = &self.bones[i];
+                let Some((lo, hi)) = bone.bounds() else {
+                    return Vec::new();
+                };
+                // Posed bounds: the transformed corners of the rest box.
+                let mut min = Vec3::splat(f32::INFINITY);
+                let mut max = Vec3::splat(f32::NEG_INFINITY);
+                for corner in 0..8 {
+                    let pick = |bit: usize, a: i32, b: i32| {
+                        (if corner & bit == 0 { a } else { b + 1 }) as f32
+                    };
+                    let p = transform.transform_point3(Vec3::new(
+                        pick(1, lo.0, hi.0),
+                        pick(2, lo.1, hi.1),
+                        pick(4, lo.2, hi.2),
+                    ));
+                    min = min.min(p);
+                    max = max.max(p);
+                }
+                let inverse = transform.inverse();
+                let (min, max) = (min.floor().as_ivec3(), max.ceil().as_ivec3());
+                let mut posed = Vec::new();
+                for z in min.z..max.z {
+                    for y in min.y..max.y {
+                        for x in min.x..max.x {
+                            let center = Vec3::new(x as f32, y as f32, z as f32) + 0.5;
+                            let source = inverse.transform_point3(center).floor().as_ivec3();
+                            let cell = (source.x, source.y, source.z);
+                            if !bone.cells.contains(&cell) {
+                                continue;
+                            }
+                            let voxel = world.get_voxel(cell.0, cell.1, cell.2);
+                            if voxel.is_solid() {
+                                posed.push(((x, y, z), voxel));
+                            }
+                        }
+                    }
+                }
+                posed
+            })
+            .collect()
+    }
+
+    /// Every moved bone's voxels from `world` at their posed positions —
+    /// the pose preview. Later bones win where two land on one cell.
+    pub fn posed_voxels(&self, world: &World) -> Vec<(Cell, Voxel)> {
+        self.posed_by_bone(world).into_iter().flatten().collect()
+    }
+
+    /// Write the pose into `world`'s voxels: the changes clear every
+    /// moved bone's rest cells and place its posed voxels. The rig then
+    /// adopts the result as its rest pose — cells and pivots move with
+    /// their bones and every pose resets. Returns no changes when
+    /// nothing is posed.
+    pub fn bake(&mut self, world: &World) -> Vec<VoxelChange> {
+        let posed = self.posed_by_bone(world);
+        let mut target: HashMap<Cell, Voxel> = HashMap::new();
+        for (bone, voxels) in self.bones.iter().zip(&posed) {
+            if !voxels.is_empty() {
+                target.extend(bone.cells.iter().map(|&c| (c, Voxel::AIR)));
+            }
+        }
+        for &(cell, voxel) in posed.iter().flatten() {
+            target.insert(cell, voxel);
+        }
+
+        let pivots: Vec<[i32; 3]> = (0..self.bones.len())
+            .map(|i| {
+                let pivot = Vec3::from(self.bones[i].pivot.map(|v| v as f32));
+                let moved = self.transform(i).transform_point3(pivot).round();
+                [moved.x as i32, moved.y as i32, moved.z as i32]
+            })
+            .collect();
+        for ((bone, voxels), pivot) in self.bones.iter_mut().zip(posed).zip(pivots) {
+            if !voxels.is_empty() {
+                bone.cells = voxels.into_iter().map(|(c, _)| c).collect();
+            }
+            bone.pivot = pivot;
+            bone.pose = BonePose::default();
+        }
+
+        let mut changes: Vec<VoxelChange> = target
+            .into_iter()
+            .filter_map(|(pos, new_voxel)| {
+                let old_voxel = world.get_voxel(pos.0, pos.1, pos.2);
+                (old_voxel != new_voxel).then_some(VoxelChange {
+                    pos,
+                    old_voxel,
+                    new_voxel,
+                })
+            })
+            .collect();
+        changes.sort_unstable_by_key(|c| (c.pos.2, c.pos.1, c.pos.0));
+        changes
+    }
+}
+
+/// Pick the smallest `Bone_N` (N ≥ 1) name not already used, like
+/// [`super::next_socket_name`]; bone names become glTF node names.
+pub fn next_bone_name(rig: &Rig) -> String {
+    let mut n = 1usize;
+    loop {
+        let candidate = format!("Bone_{n}");
+        if !rig.bones.iter().any(|b| b.name == candidate) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(world: &mut World, len: i32, color: Voxel) -> Vec<Cell> {
+        (0..len)
+            .map(|x| {
+                world.set_voxel(x, 0, 0, color);
+                (x, 0, 0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cells_belong_to_one_bone_and_removal_reparents() {
+        let mut rig = Rig::new();
+        let a = rig.add_bone("a", [0, 0, 0], [(0, 0, 0), (1, 0, 0)]);
+        let b = rig.add_bone("b", [0, 0, 0], [(1, 0, 0), (2, 0, 0)]);
+        let c = rig.add_bone("c", [0, 0, 0], [(3, 0, 0)]);
+        assert_eq!(rig.bone_of((1, 0, 0)), Some(b));
+        assert_eq!(rig.bones()[a].cells().len(), 1);
+
+        assert!(rig.set_parent(b, Some(a)));
+        assert!(rig.set_parent(c, Some(b)));
+        assert!(!rig.set_parent(a, Some(c)), "cycle");
+        rig.remove_bone(b);
+        // `c` moved down to index 1 and now hangs off `a`.
+        assert_eq!(rig.bones()[1].name, "c");
+        assert_eq!(rig.bones()[1].parent, Some(0));
+        assert_eq!(next_bone_name(&rig), "Bone_1");
+    }
+
+    #[test]
+    fn child_follows_parent_and_bake_moves_voxels() {
+        let red = Voxel::from_rgb(255, 0, 0);
+        let blue = Voxel::from_rgb(0, 0, 255);
+        let mut world = World::new();
+        let arm = bar(&mut world, 2, red);
+        world.set_voxel(2, 0, 0, blue);
+        let mut rig = Rig::new();
+        let upper = rig.add_bone("upper", [0, 0, 0], arm);
+        let hand = rig.add_bone("hand", [2, 0, 0], [(2, 0, 0)]);
+        rig.set_parent(hand, Some(upper));
+
+        // Swing the arm up 90° about Z around the shoulder corner.
+        rig.set_pose(
+            upper,
+            BonePose {
+                translation: [0, 0, 0],
+                rotation: [0, 0, 90],
+            },
+        );
+        let mut posed = rig.posed_voxels(&world);
+        posed.sort_unstable_by_key(|&(c, _)| c);
+        // The hand rides along to the top of the raised arm.
+        assert_eq!(
+            posed,
+            [((-1, 0, 0), red), ((-1, 1, 0), red), ((-1, 2, 0), blue),]
+        );
+
+        let changes = rig.bake(&world);
+        crate::editor::Command::set_voxels(changes).execute(&mut world);
+        assert!(world.get_voxel(0, 0, 0).is_air());
+        assert_eq!(world.get_voxel(-1, 2, 0), blue);
+        assert!(!rig.is_posed());
+        assert_eq!(rig.bone_of((-1, 2, 0)), Some(hand));
+        assert_eq!(rig.bones()[hand].pivot, [0, 2, 0]);
+    }
+
+    #[test]
+    fn node_transform_rotates_about_the_pivot() {
+        let mut rig = Rig::new();
+        let bone = rig.add_bone("b", [2, 0, 0], [(2, 0, 0)]);
+        rig.set_pose(
+            bone,
+            BonePose {
+                translation: [0, 1, 0],
+                rotation: [0, 180, 0],
+            },
+        );
+        let (t, r) = rig.node_transform(bone);
+        let q = Quat::from_array(r);
+        let moved = q * Vec3::new(2.0, 0.0, 0.0) + Vec3::from(t);
+        assert!(moved.abs_diff_eq(Vec3::new(2.0, 1.0, 0.0), 1e-4), "{moved}");
+    }
+}
@@ -0,0 +1,216 @@
+//! Sculpt brushes for organic detail: Erode eats random surface voxels
+//! under the brush, Dilate grows the surface outward, and Roughen
+//! pushes the surface in and out along a noise field.
+//!
+//! Every step is decided against the world as it was before the step,
+//! so a cell grown this step is never eroded in the same one. Erode and
+//! Dilate draw fresh random numbers each step (the App advances
+//! [`SculptSettings::seed`]), so dabbing one spot keeps working it;
+//! Roughen's noise is fixed in space, so passes deepen the same bumps.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Voxel, World};
+
+use super::paint::cell_hash;
+use super::VoxelChange;
+
+/// What the Sculpt tool does to the surface under the brush.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SculptMode {
+    /// Remove random surface voxels.
+    #[default]
+    Erode,
+    /// Fill random empty cells touching the surface.
+    Dilate,
+    /// Erode where the noise is low and dilate where it's high.
+    Roughen,
+}
+
+impl SculptMode {
+    pub const ALL: [Self; 3] = [Self::Erode, Self::Dilate, Self::Roughen];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Erode => "Erode",
+            Self::Dilate => "Dilate",
+            Self::Roughen => "Roughen",
+        }
+    }
+}
+
+/// Sculpt tool options. Persisted with the brush in prefs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SculptSettings {
+    pub mode: SculptMode,
+    /// Share of eligible cells one step changes, in percent. For
+    /// Roughen, how much of the noise range moves the surface.
+    pub strength: u8,
+    /// Varies Erode / Dilate's pick between steps.
+    #[serde(skip)]
+    pub seed: u32,
+}
+
+impl Default for SculptSettings {
+    fn default() -> Self {
+        Self {
+            mode: SculptMode::Erode,
+            strength: 30,
+            seed: 0,
+        }
+    }
+}
+
+/// Cells sharing a face with a voxel.
+const FACES: [(i32, i32, i32); 6] =
+    [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+
+/// Lattice spacing of Roughen's noise, in cells.
+const ROUGHEN_SCALE: i32 = 3;
+
+/// The changes one sculpt step makes to the brush `cells`.
+pub fn build_sculpt_changes(
+    world: &World,
+    cells: &[(i32, i32, i32)],
+    settings: SculptSettings,
+) -> Vec<VoxelChange> {
+    let strength = settings.strength.min(100) as f32 / 100.0;
+    let get = |(x, y, z): (i32, i32, i32)| world.get_voxel(x, y, z);
+    let neighbors =
+        |(x, y, z): (i32, i32, i32)| FACES.map(|(dx, dy, dz)| (x + dx, y + dy, z + dz));
+    let chance = |pos| (cell_hash(pos, settings.seed) & 0xFFFF) as f32 / 65536.0;
+    let mut changes = Vec::new();
+    for &pos in cells {
+        let old = get(pos);
+        let (erode, dilate) = match settings.mode {
+            SculptMode::Erode => (chance(pos) < strength, false),
+            SculptMode::Dilate => (false, chance(pos) < strength),
+            SculptMode::Roughen => {
+                let n = value_noise(pos);
+                (n < strength - 1.0, n > 1.0 - strength)
+            }
+        };
+        if old.is_solid() {
+            // Only surface voxels erode, never buried ones.
+            if erode && neighbors(pos).into_iter().any(|p| get(p).is_air()) {
+                changes.push(VoxelChange {
+                    pos,
+                    old_voxel: old,
+                    new_voxel: Voxel::AIR,
+                });
+            }
+        } else if dilate {
+            // Grow from the first solid neighbor, in its color.
+            if let Some(grown) = neighbors(pos).into_iter().map(get).find(|v| v.is_solid()) {
+                changes.push(VoxelChange {
+                    pos,
+                    old_voxel: old,
+                    new_voxel: grown,
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// Smooth noise in -1..=1: random values on a [`ROUGHEN_SCALE`]
+/// lattice, blended trilinearly.
+fn value_noise((x, y, z): (i32, i32, i32)) -> f32 {
+    let s = ROUGHEN_SCALE;
+    let split = |v: i32| (v.div_euclid(s), v.rem_euclid(s) as f32 / s as f32);
+    let ((ix, fx), (iy, fy), (iz, fz)) = (split(x), split(y), split(z));
+    let corner = |dx, dy, dz| {
+        let h = cell_hash((ix + dx, iy + dy, iz + dz), 0x5C01_97ED);
+        (h & 0xFFFF) as f32 / 32767.5 - 1.0
+    };
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let along_x = |dy, dz| lerp(corner(0, dy, dz), corner(1, dy, dz), fx);
+    lerp(
+        lerp(along_x(0, 0), along_x(1, 0), fy),
+        lerp(along_x(0, 1), along_x(1, 1), fy),
+        fz,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slab() -> World {
+        let mut world = World::new();
+        for x in -4..=4 {
+            for y in -4..=0 {
+                for z in -4..=4 {
+                    world.set_voxel(x, y, z, Voxel::from_rgb(90, 120, 60));
+                }
+            }
+        }
+        world
+    }
+
+    fn brush() -> Vec<(i32, i32, i32)> {
+        let mut cells = Vec::new();
+        for x in -2..=2 {
+            for y in -2..=2 {
+                for z in -2..=2 {
+                    cells.push((x, y, z));
+                }
+            }
+        }
+        cells
+    }
+
+    fn settings(mode: SculptMode, strength: u8) -> SculptSettings {
+        SculptSettings {
+            mode,
+            strength,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn erode_takes_surface_voxels_only() {
+        let world = slab();
+        let all = build_sculpt_changes(&world, &brush(), settings(SculptMode::Erode, 100));
+        // Full strength: exactly the 5×5 top layer under the brush.
+        assert_eq!(all.len(), 25);
+        assert!(all.iter().all(|c| c.pos.1 == 0 && c.new_voxel.is_air()));
+        let some = build_sculpt_changes(&world, &brush(), settings(SculptMode::Erode, 40));
+        assert!(!some.is_empty() && some.len() < 25);
+        assert!(build_sculpt_changes(&world, &brush(), settings(SculptMode::Erode, 0)).is_empty());
+    }
+
+    #[test]
+    fn dilate_grows_in_the_surface_color() {
+        let world = slab();
+        let grown = build_sculpt_changes(&world, &brush(), settings(SculptMode::Dilate, 100));
+        assert_eq!(grown.len(), 25);
+        assert!(grown
+            .iter()
+            .all(|c| c.pos.1 == 1 && c.new_voxel == world.get_voxel(0, 0, 0)));
+    }
+
+    #[test]
+    fn roughen_moves_the_surface_both_ways() {
+        let world = slab();
+        let mut cells = Vec::new();
+        for x in -4..=4 {
+            for z in -4..=4 {
+                cells.push((x, 0, z));
+                cells.push((x, 1, z));
+            }
+        }
+        let changes = build_sculpt_changes(&world, &cells, settings(SculptMode::Roughen, 80));
+        assert!(changes.iter().any(|c| c.new_voxel.is_air()));
+        assert!(changes.iter().any(|c| c.new_voxel.is_solid()));
+        // The noise is fixed in space: a different seed changes nothing.
+        let reseeded = SculptSettings { seed: 99, ..settings(SculptMode::Roughen, 80) };
+        let cells_of = |changes: &[VoxelChange]| changes.iter().map(|c| c.pos).collect::<Vec<_>>();
+        assert_eq!(
+            cells_of(&build_sculpt_changes(&world, &cells, reseeded)),
+            cells_of(&changes)
+        );
+        assert!(build_sculpt_changes(&world, &cells, settings(SculptMode::Roughen, 0)).is_empty());
+    }
+}
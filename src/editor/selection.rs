@@ -6,6 +6,26 @@
 //! actual voxel contents inside the box: copy / cut read the world at
 //! command-build time so they always see the latest state, and a
 //! selection survives unrelated edits without going stale.
+//!
+//! The magic wand adds a [`SelectionMask`] on top: the exact cells it
+//! picked, with the box as their bounds. Operations read their source
+//! voxels from [`SelectionMask::isolate`] instead of the layer, so the
+//! box-based code paths (copy, delete, move, recolor, rotate) act on
+//! just the picked cells without knowing about masks.
+//!
+//! [`SelectionStats`] keeps the live counts the status bar shows for a
+//! selection (solid voxels, unique colors), tallied per chunk so an
+//! edit only recounts the chunks it touched.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::core::{BoundingBox, ChunkPos, Voxel, World, CHUNK_SIZE_I32};
+
+use super::VoxelChange;
+
+/// Most cells one magic-wand pick gathers (a 64³ block's worth).
+pub const MAX_WAND_CELLS: usize = 64 * 64 * 64;
 
 /// Axis-aligned closed selection box in world cell coordinates.
 /// Inclusive on both corners.
@@ -15,6 +35,17 @@ pub struct Selection {
     pub max: (i32, i32, i32),
 }
 
+impl From<BoundingBox> for Selection {
+    /// The box around a whole model — what rotate / flip act on when
+    /// nothing is selected.
+    fn from(bounds: BoundingBox) -> Self {
+        Self {
+            min: bounds.min,
+            max: bounds.max,
+        }
+    }
+}
+
 impl Selection {
     /// Build a selection from any two opposite corners. Either order
     /// of `(anchor, end)` produces the same box — corners are sorted.
@@ -79,9 +110,297 @@ impl Selection {
     }
 }
 
+/// An irregular selection: a set of cells and the [`Selection`] box
+/// around them. The box is what `Editor::selection` holds while the
+/// mask is in effect — see `Editor::active_mask`.
+#[derive(Debug, Clone)]
+pub struct SelectionMask {
+    bounds: Selection,
+    cells: HashSet<(i32, i32, i32)>,
+    id: u64,
+}
+
+impl PartialEq for SelectionMask {
+    fn eq(&self, other: &Self) -> bool {
+        self.bounds == other.bounds && self.cells == other.cells
+    }
+}
+
+impl Eq for SelectionMask {}
+
+/// Source of [`SelectionMask::id`]s.
+static NEXT_MASK_ID: AtomicU64 = AtomicU64::new(1);
+
+impl SelectionMask {
+    /// Mask of `cells`, or `None` when there are none.
+    pub fn new(cells: HashSet<(i32, i32, i32)>) -> Option<Self> {
+        let mut it = cells.iter();
+        let &first = it.next()?;
+        let bounds = it.fold(Selection::from_corners(first, first), |b, &p| Selection {
+            min: (b.min.0.min(p.0), b.min.1.min(p.1), b.min.2.min(p.2)),
+            max: (b.max.0.max(p.0), b.max.1.max(p.1), b.max.2.max(p.2)),
+        });
+        let id = NEXT_MASK_ID.fetch_add(1, Ordering::Relaxed);
+        Some(Self { bounds, cells, id })
+    }
+
+    /// Identity of this pick, shared by its clones and new for every
+    /// mask built since — a constant-time "is it still the same mask"
+    /// check, where comparing the cells would walk the whole set.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The box around every masked cell.
+    pub fn bounds(&self) -> Selection {
+        self.bounds
+    }
+
+    pub fn contains(&self, pos: (i32, i32, i32)) -> bool {
+        self.cells.contains(&pos)
+    }
+
+    /// Number of masked cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// This mask plus `other`'s cells (Shift-click with the wand).
+    pub fn union(&self, other: &SelectionMask) -> SelectionMask {
+        let cells = self.cells.union(&other.cells).copied().collect();
+        Self::new(cells).expect("union of non-empty masks")
+    }
+
+    /// The mask with every cell sent through `f` — follows the picked
+    /// voxels through a move / rotate / mirror.
+    pub fn mapped(&self, f: impl Fn((i32, i32, i32)) -> (i32, i32, i32)) -> SelectionMask {
+        Self::new(self.cells.iter().map(|&p| f(p)).collect()).expect("mask is non-empty")
+    }
+
+    /// A scratch world holding only the masked cells' voxels from
+    /// `world`. Build a box operation's changes against it, then pass
+    /// them through [`Self::rebase`].
+    pub fn isolate(&self, world: &World) -> World {
+        let mut isolated = World::new();
+        for &(x, y, z) in &self.cells {
+            let v = world.get_voxel(x, y, z);
+            if !v.is_air() {
+                isolated.set_voxel(x, y, z, v);
+            }
+        }
+        isolated
+    }
+
+    /// Re-read each change's `old_voxel` from the real `world` (the
+    /// isolated one reports air outside the mask) and drop writes that
+    /// turn out to be no-ops there, so undo restores exactly.
+    pub fn rebase(world: &World, changes: Vec<VoxelChange>) -> Vec<VoxelChange> {
+        changes
+            .into_iter()
+            .filter_map(|c| {
+                let old_voxel = world.get_voxel(c.pos.0, c.pos.1, c.pos.2);
+                (old_voxel != c.new_voxel).then_some(VoxelChange { old_voxel, ..c })
+            })
+            .collect()
+    }
+}
+
+/// Magic wand: the solid voxels 6-connected to `seed` whose color is
+/// within `tolerance` of the seed's on every RGBA channel. Matching
+/// against the seed rather than each neighbor keeps a gradient from
+/// leaking across the whole model. `None` when `seed` is air. Stops
+/// growing at `max_cells`.
+pub fn select_by_color(
+    world: &World,
+    seed: (i32, i32, i32),
+    tolerance: u8,
+    max_cells: usize,
+) -> Option<SelectionMask> {
+    let target = world.get_voxel(seed.0, seed.1, seed.2);
+    if target.is_air() {
+        return None;
+    }
+    let target = target.color();
+    let matches = |v: Voxel| {
+        !v.is_air()
+            && v.color()
+                .iter()
+                .zip(target)
+                .all(|(&a, b)| a.abs_diff(b) <= tolerance)
+    };
+
+    let mut cells = HashSet::new();
+    let mut visited = HashSet::from([seed]);
+    let mut queue = VecDeque::from([seed]);
+    while let Some(pos) = queue.pop_front() {
+        if cells.len() >= max_cells {
+            break;
+        }
+        cells.insert(pos);
+        for (dx, dy, dz) in [
+            (1, 0, 0),
+            (-1, 0, 0),
+            (0, 1, 0),
+            (0, -1, 0),
+            (0, 0, 1),
+            (0, 0, -1),
+        ] {
+            let next = (pos.0 + dx, pos.1 + dy, pos.2 + dz);
+            if visited.insert(next) && matches(world.get_voxel(next.0, next.1, next.2)) {
+                queue.push_back(next);
+            }
+        }
+    }
+    SelectionMask::new(cells)
+}
+
+/// Solid voxel and unique color counts of a selection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SelectionCounts {
+    pub solid: u64,
+    pub colors: usize,
+}
+
+/// Live [`SelectionCounts`] of one selection (box plus optional wand
+/// mask) over one world.
+///
+/// The counts are kept per chunk: [`Self::invalidate`] marks the
+/// chunks an edit touched and [`Self::refresh`] recounts only those,
+/// so keeping the status bar current costs the size of the edit, not
+/// of the selection. A different selection or mask needs a fresh
+/// [`Self::scan`] — see [`Self::matches`].
+#[derive(Debug, Clone)]
+pub struct SelectionStats {
+    selection: Selection,
+    mask_id: Option<u64>,
+    per_chunk: HashMap<ChunkPos, HashMap<[u8; 4], u64>>,
+    colors: HashMap<[u8; 4], u64>,
+    solid: u64,
+    stale: HashSet<ChunkPos>,
+}
+
+impl SelectionStats {
+    /// Count `selection` (only the cells of `mask`, when given) in
+    /// `world`.
+    pub fn scan(world: &World, selection: Selection, mask: Option<&SelectionMask>) -> Self {
+        let mut stats = Self {
+            selection,
+            mask_id: mask.map(SelectionMask::id),
+            per_chunk: HashMap::new(),
+            colors: HashMap::new(),
+            solid: 0,
+            stale: HashSet::new(),
+        };
+        let (lo, hi) = stats.chunk_range();
+        for z in lo.z..=hi.z {
+            for y in lo.y..=hi.y {
+                for x in lo.x..=hi.x {
+                    stats.recount(world, ChunkPos::new(x, y, z), mask);
+                }
+            }
+        }
+        stats
+    }
+
+    /// Whether these stats describe `selection` with `mask`.
+    pub fn matches(&self, selection: Selection, mask: Option<&SelectionMask>) -> bool {
+        self.selection == selection && self.mask_id == mask.map(SelectionMask::id)
+    }
+
+    /// Mark chunks whose voxels changed; those overlapping the
+    /// selection are recounted on the next [`Self::refresh`].
+    pub fn invalidate(&mut self, chunks: &[ChunkPos]) {
+        let (lo, hi) = self.chunk_range();
+        self.stale.extend(chunks.iter().copied().filter(|c| {
+            (lo.x..=hi.x).contains(&c.x)
+                && (lo.y..=hi.y).contains(&c.y)
+                && (lo.z..=hi.z).contains(&c.z)
+        }));
+    }
+
+    /// Recount the chunks marked by [`Self::invalidate`]. `mask` must
+    /// be the one the stats were scanned with.
+    pub fn refresh(&mut self, world: &World, mask: Option<&SelectionMask>) {
+        for pos in std::mem::take(&mut self.stale) {
+            self.recount(world, pos, mask);
+        }
+    }
+
+    pub fn counts(&self) -> SelectionCounts {
+        SelectionCounts {
+            solid: self.solid,
+            colors: self.colors.len(),
+        }
+    }
+
+    /// Chunks the selection box overlaps, as inclusive min / max.
+    fn chunk_range(&self) -> (ChunkPos, ChunkPos) {
+        let (min, max) = (self.selection.min, self.selection.max);
+        (
+            ChunkPos::from_world_pos(min.0, min.1, min.2),
+            ChunkPos::from_world_pos(max.0, max.1, max.2),
+        )
+    }
+
+    /// Replace chunk `pos`'s share of the counts with a fresh tally.
+    fn recount(&mut self, world: &World, pos: ChunkPos, mask: Option<&SelectionMask>) {
+        if let Some(old) = self.per_chunk.remove(&pos) {
+            for (color, n) in old {
+                self.solid -= n;
+                if let Some(total) = self.colors.get_mut(&color) {
+                    *total -= n;
+                    if *total == 0 {
+                        self.colors.remove(&color);
+                    }
+                }
+            }
+        }
+        let Some(chunk) = world.get_chunk(pos) else {
+            return;
+        };
+        let chunk = chunk.read();
+        if chunk.is_empty() {
+            return;
+        }
+        // The selection clipped to this chunk, in local coordinates.
+        let origin = pos.world_origin();
+        let (sel_min, sel_max) = (self.selection.min, self.selection.max);
+        let lo = |o: i32, m: i32| (m - o).max(0);
+        let hi = |o: i32, m: i32| (m - o).min(CHUNK_SIZE_I32 - 1);
+        let mut tally: HashMap<[u8; 4], u64> = HashMap::new();
+        for z in lo(origin.2, sel_min.2)..=hi(origin.2, sel_max.2) {
+            for y in lo(origin.1, sel_min.1)..=hi(origin.1, sel_max.1) {
+                for x in lo(origin.0, sel_min.0)..=hi(origin.0, sel_max.0) {
+                    let voxel = chunk.get(x as usize, y as usize, z as usize);
+                    if voxel.is_air() {
+                        continue;
+                    }
+                    let cell = (origin.0 + x, origin.1 + y, origin.2 + z);
+                    if mask.is_some_and(|m| !m.contains(cell)) {
+                        continue;
+                    }
+                    *tally.entry(voxel.color()).or_default() += 1;
+                }
+            }
+        }
+        for (&color, &n) in &tally {
+            self.solid += n;
+            *self.colors.entry(color).or_default() += n;
+        }
+        if !tally.is_empty() {
+            self.per_chunk.insert(pos, tally);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::editor::{build_clear_changes, build_move_changes};
 
     #[test]
     fn from_corners_unordered() {
@@ -155,4 +474,106 @@ mod tests {
             assert!(set.contains(c));
         }
     }
+
+    #[test]
+    fn wand_picks_contiguous_cells_within_tolerance() {
+        let mut world = World::new();
+        // A reddish run at x = 0..4 broken by blue at x = 4, then red
+        // again past it; plus one near-red voxel above x = 0.
+        for x in 0..4 {
+            world.set_voxel(x, 0, 0, Voxel::from_rgb(200 + x as u8, 0, 0));
+        }
+        world.set_voxel(4, 0, 0, Voxel::from_rgb(0, 0, 255));
+        world.set_voxel(5, 0, 0, Voxel::from_rgb(200, 0, 0));
+        world.set_voxel(0, 1, 0, Voxel::from_rgb(220, 0, 0));
+
+        let exact = select_by_color(&world, (0, 0, 0), 0, MAX_WAND_CELLS).unwrap();
+        assert_eq!(exact.len(), 1);
+        let loose = select_by_color(&world, (0, 0, 0), 5, MAX_WAND_CELLS).unwrap();
+        assert_eq!(loose.len(), 4);
+        assert!(!loose.contains((5, 0, 0)));
+        assert_eq!(loose.bounds(), Selection::from_corners((0, 0, 0), (3, 0, 0)));
+        let looser = select_by_color(&world, (0, 0, 0), 20, MAX_WAND_CELLS).unwrap();
+        assert_eq!(looser.len(), 5);
+        assert!(select_by_color(&world, (0, 5, 0), 255, MAX_WAND_CELLS).is_none());
+        assert_eq!(select_by_color(&world, (0, 0, 0), 20, 2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn isolated_box_ops_touch_only_masked_cells() {
+        let mut world = World::new();
+        let red = Voxel::from_rgb(255, 0, 0);
+        let blue = Voxel::from_rgb(0, 0, 255);
+        world.set_voxel(0, 0, 0, red);
+        world.set_voxel(1, 0, 0, blue);
+        world.set_voxel(2, 0, 0, red);
+        world.set_voxel(5, 0, 0, blue);
+        let mask = SelectionMask::new(HashSet::from([(0, 0, 0), (2, 0, 0)])).unwrap();
+        let sel = mask.bounds();
+
+        // Delete clears the two red cells, not the blue one between.
+        let isolated = mask.isolate(&world);
+        let cleared = SelectionMask::rebase(&world, build_clear_changes(&isolated, sel));
+        let mut cells: Vec<_> = cleared.iter().map(|c| c.pos).collect();
+        cells.sort();
+        assert_eq!(cells, vec![(0, 0, 0), (2, 0, 0)]);
+
+        // Moving onto (5, 0, 0) records its real old value for undo.
+        let moved = SelectionMask::rebase(&world, build_move_changes(&isolated, sel, (3, 0, 0)));
+        let onto = moved.iter().find(|c| c.pos == (5, 0, 0)).unwrap();
+        assert_eq!((onto.old_voxel, onto.new_voxel), (blue, red));
+        assert!(!moved.iter().any(|c| c.pos == (1, 0, 0)));
+        assert_eq!(
+            mask.mapped(|p| (p.0 + 3, p.1, p.2)).bounds(),
+            sel.translated((3, 0, 0))
+        );
+    }
+
+    #[test]
+    fn stats_recount_only_invalidated_chunks() {
+        let mut world = World::new();
+        let red = Voxel::from_rgb(255, 0, 0);
+        let blue = Voxel::from_rgb(0, 0, 255);
+        world.set_voxel(0, 0, 0, red);
+        world.set_voxel(1, 0, 0, red);
+        world.set_voxel(40, 0, 0, blue);
+        // Outside the box: never counted.
+        world.set_voxel(50, 0, 0, blue);
+        let sel = Selection::from_corners((0, 0, 0), (45, 3, 3));
+        let mut stats = SelectionStats::scan(&world, sel, None);
+        assert_eq!(stats.counts(), SelectionCounts { solid: 3, colors: 2 });
+
+        // An edit in the second chunk, reported: recounted.
+        world.set_voxel(40, 0, 0, red);
+        world.set_voxel(41, 1, 1, red);
+        stats.invalidate(&[ChunkPos::from_world_pos(40, 0, 0)]);
+        stats.refresh(&world, None);
+        assert_eq!(stats.counts(), SelectionCounts { solid: 4, colors: 1 });
+
+        // Not reported: the stats keep their last tally.
+        world.set_voxel(2, 0, 0, blue);
+        stats.refresh(&world, None);
+        assert_eq!(stats.counts().solid, 4);
+        assert_eq!(
+            SelectionStats::scan(&world, sel, None).counts(),
+            SelectionCounts { solid: 5, colors: 2 }
+        );
+    }
+
+    #[test]
+    fn stats_follow_the_mask() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(1, 0, 0, Voxel::from_rgb(0, 255, 0));
+        world.set_voxel(2, 0, 0, Voxel::from_rgb(0, 0, 255));
+        let mask = SelectionMask::new(HashSet::from([(0, 0, 0), (2, 0, 0)])).unwrap();
+        let stats = SelectionStats::scan(&world, mask.bounds(), Some(&mask));
+        assert_eq!(stats.counts(), SelectionCounts { solid: 2, colors: 2 });
+        assert!(stats.matches(mask.bounds(), Some(&mask.clone())));
+        // Same cells, new pick: a different mask to the stats.
+        let again = SelectionMask::new(HashSet::from([(0, 0, 0), (2, 0, 0)])).unwrap();
+        assert_eq!(again, mask);
+        assert!(!stats.matches(mask.bounds(), Some(&again)));
+        assert!(!stats.matches(mask.bounds(), None));
+    }
 }
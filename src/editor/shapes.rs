@@ -107,6 +107,22 @@ pub fn box_voxels(a: (i32, i32, i32), b: (i32, i32, i32)) -> Vec<(i32, i32, i32)
     out
 }
 
+/// Shell of the closed AABB `[a, b]`: every cell of [`box_voxels`]
+/// that lies on one of the six faces. A box that is one or two cells
+/// thick along any axis has no interior, so the shell equals the
+/// filled box there.
+pub fn hollow_box_voxels(a: (i32, i32, i32), b: (i32, i32, i32)) -> Vec<(i32, i32, i32)> {
+    let (x0, x1) = (a.0.min(b.0), a.0.max(b.0));
+    let (y0, y1) = (a.1.min(b.1), a.1.max(b.1));
+    let (z0, z1) = (a.2.min(b.2), a.2.max(b.2));
+    box_voxels(a, b)
+        .into_iter()
+        .filter(|&(x, y, z)| {
+            x == x0 || x == x1 || y == y0 || y == y1 || z == z0 || z == z1
+        })
+        .collect()
+}
+
 /// Filled ellipsoid fitting in the closed AABB `[a, b]`. Square-ish
 /// drag → sphere; oblong drag → ellipsoid. Test is at cell centers
 /// (offset 0.5) so a 1-cell-thick AABB still emits voxels.
@@ -264,6 +280,24 @@ mod tests {
         assert!(set.contains(&(0, 1, 1)));
     }
 
+    #[test]
+    fn test_hollow_box_drops_interior() {
+        let v = hollow_box_voxels((0, 0, 0), (2, 2, 2));
+        // 27 cells minus the single interior cell.
+        assert_eq!(v.len(), 26);
+        assert!(!v.contains(&(1, 1, 1)));
+        // 5×4×3: 60 cells, interior 3×2×1 = 6.
+        assert_eq!(hollow_box_voxels((4, 3, 2), (0, 0, 0)).len(), 54);
+    }
+
+    #[test]
+    fn test_hollow_box_thin_equals_filled() {
+        // A wall one or two cells thick has no interior to remove.
+        let filled: HashSet<_> = box_voxels((0, 0, 0), (5, 4, 1)).into_iter().collect();
+        let hollow: HashSet<_> = hollow_box_voxels((0, 0, 0), (5, 4, 1)).into_iter().collect();
+        assert_eq!(filled, hollow);
+    }
+
     #[test]
     fn test_box_unordered_corners() {
         // Either pair of opposite corners produces the same set.
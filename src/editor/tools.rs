@@ -5,7 +5,11 @@
 use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
-use super::{Command, CommandHistory, RaycastHit, SymmetryAxes, VoxelChange};
+use super::{
+    build_sculpt_changes, line_voxels, BrushSettings, BrushShape, Command, CommandHistory,
+    FillRegion, FillSettings, PaintSettings, RaycastHit, SculptSettings, SymmetryAxes, VoxelChange,
+    WorkPlane,
+};
 use crate::core::{Voxel, World};
 
 /// Time window within which consecutive brush writes coalesce into a
@@ -29,7 +33,7 @@ pub const MAX_FILL_DIST: i32 = 64;
 /// in one `Command` on mouse-up. The `Select` tool follows the same
 /// click-drag-release gesture as shapes, but commits a `Selection`
 /// AABB into `Editor::selection` instead of writing voxels.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Tool {
     /// Place voxels
     Place,
@@ -61,6 +65,23 @@ pub enum Tool {
     /// **last** in the enum so the `current_tool as usize` discriminant
     /// in `.vxlt` / prefs stays stable for the existing tools.
     Socket,
+    /// Extrude / inset a face: a click pushes the connected coplanar
+    /// same-colored surface under the cursor out (or in) by
+    /// `Editor::extrude_depth` cells. Single click, one undo entry;
+    /// appended after `Socket` for the same discriminant reason.
+    Extrude,
+    /// Magic wand: a click selects the voxels connected to the clicked
+    /// one whose color is within `Editor::wand_tolerance` of it, as a
+    /// `SelectionMask` over the box selection. Shift-click adds to the
+    /// current pick.
+    Wand,
+    /// Ruler: click two voxels to read the offset and distance between
+    /// them (see `Measurement`). Reads only.
+    Measure,
+    /// Sculpt brush: erodes, dilates or roughens the surface under the
+    /// brush (see `SculptSettings`). Strokes like Place / Remove /
+    /// Paint, one undo entry per stroke.
+    Sculpt,
 }
 
 impl Tool {
@@ -78,6 +99,10 @@ impl Tool {
             Tool::Cylinder => "Cylinder",
             Tool::Select => "Select",
             Tool::Socket => "Socket",
+            Tool::Extrude => "Extrude",
+            Tool::Wand => "Magic Wand",
+            Tool::Measure => "Measure",
+            Tool::Sculpt => "Sculpt",
         }
     }
 
@@ -95,7 +120,7 @@ impl Tool {
             Tool::Cylinder => "9",
             Tool::Select => "0",
             // No digit free; placed from the toolbar / Tools panel.
-            Tool::Socket => "",
+            Tool::Socket | Tool::Extrude | Tool::Wand | Tool::Measure | Tool::Sculpt => "",
         }
     }
 
@@ -130,6 +155,18 @@ impl Tool {
         // only on an existing voxel face.
         matches!(self, Tool::Place | Tool::Select | Tool::Socket) || self.is_shape()
     }
+
+    /// Whether this tool hovers the work-plane slice instead of the
+    /// first voxel on the ray while work-plane mode is on. The tools
+    /// that target a cell do; Select, Socket, Extrude, and Wand pick
+    /// out existing surfaces and keep the normal raycast (their
+    /// writes, if any, are still clipped to the slice).
+    pub fn aims_at_work_plane(&self) -> bool {
+        matches!(
+            self,
+            Tool::Place | Tool::Remove | Tool::Paint | Tool::Eyedropper | Tool::Fill | Tool::Sculpt
+        ) || self.is_shape()
+    }
 }
 
 /// Context passed to tools during execution
@@ -139,6 +176,14 @@ pub struct ToolContext<'a> {
     pub brush_color: Voxel,
     pub brush_size: u8,
     pub symmetry: SymmetryAxes,
+    /// Per-cell color pattern for Place / Paint.
+    pub paint: PaintSettings,
+    /// Brush shape (and stamp) for Place / Remove / Paint / Sculpt.
+    pub brush: BrushSettings,
+    /// What the Sculpt tool does, and how strongly.
+    pub sculpt: SculptSettings,
+    /// Slice the writes are clipped to (work-plane mode), if any.
+    pub work_plane: Option<WorkPlane>,
 }
 
 /// Trait for tool implementations
@@ -148,12 +193,15 @@ pub trait EditorTool {
 
     /// Positions the brush hover overlay should highlight, including
     /// any symmetry-mirrored copies (deduped). Caller passes its
-    /// current `symmetry` so a single source of truth drives both this
-    /// preview and the matching `apply` call.
+    /// current `brush` and `symmetry` so a single source of truth
+    /// drives both this preview and the matching `apply` call. `world`
+    /// is read for the brush's surface-only filters.
     fn preview_positions(
         &self,
+        world: &World,
         hit: &RaycastHit,
         brush_size: u8,
+        brush: &BrushSettings,
         symmetry: SymmetryAxes,
     ) -> Vec<(i32, i32, i32)>;
 }
@@ -167,41 +215,16 @@ impl BrushTool {
     pub fn new(mode: Tool) -> Self {
         Self { mode }
     }
-
-    /// Get affected positions for a spherical brush
-    fn get_brush_positions(center: (i32, i32, i32), size: u8) -> Vec<(i32, i32, i32)> {
-        let mut positions = Vec::new();
-        let radius = (size as i32 - 1).max(0);
-        let radius_sq = (radius as f32 + 0.5).powi(2);
-
-        for dz in -radius..=radius {
-            for dy in -radius..=radius {
-                for dx in -radius..=radius {
-                    let dist_sq = (dx * dx + dy * dy + dz * dz) as f32;
-                    if dist_sq <= radius_sq {
-                        positions.push((
-                            center.0 + dx,
-                            center.1 + dy,
-                            center.2 + dz,
-                        ));
-                    }
-                }
-            }
-        }
-
-        positions
-    }
 }
 
 impl EditorTool for BrushTool {
     fn apply(&self, ctx: &mut ToolContext, hit: &RaycastHit) {
-        let center = match self.mode {
-            Tool::Place => hit.adjacent_pos,
-            Tool::Remove | Tool::Paint => hit.voxel_pos,
-            // Eyedropper / Fill go through input.rs's tool dispatch,
-            // not BrushTool. Shape tools and Select have their own
-            // click-anchor / drag / commit lifecycle and never call
-            // this path.
+        match self.mode {
+            Tool::Place | Tool::Remove | Tool::Paint | Tool::Sculpt => {}
+            // Eyedropper / Fill / Extrude / Wand go through input.rs's
+            // tool dispatch, not BrushTool. Shape tools and Select
+            // have their own click-anchor / drag / commit lifecycle
+            // and never call this path.
             Tool::Eyedropper
             | Tool::Fill
             | Tool::Line
@@ -209,28 +232,63 @@ impl EditorTool for BrushTool {
             | Tool::Sphere
             | Tool::Cylinder
             | Tool::Select
-            | Tool::Socket => return,
-        };
+            | Tool::Socket
+            | Tool::Extrude
+            | Tool::Wand
+            | Tool::Measure => return,
+        }
 
-        // Expand the brush sphere across symmetry mirrors. Spheres that
-        // overlap near a symmetry plane would double-count cells, so we
-        // dedup via HashSet — both for efficiency and so the resulting
-        // change set has each position exactly once.
-        let positions = Self::affected_positions(center, ctx.brush_size, ctx.symmetry);
+        // Expand the brush across symmetry mirrors, deduped so the
+        // change set has each position exactly once. A stamp's cells
+        // carry their own colors; the other shapes use the paint color.
+        let mut cells = Self::affected_cells(
+            ctx.world,
+            self.mode,
+            hit,
+            ctx.brush_size,
+            &ctx.brush,
+            ctx.symmetry,
+        );
+        if let Some(plane) = ctx.work_plane {
+            cells.retain(|&(p, _)| plane.contains(p));
+        }
+        let color_at = |pos, stamp: Option<Voxel>| {
+            stamp.unwrap_or_else(|| ctx.paint.color_at(ctx.brush_color, pos))
+        };
+        // Soft paint fades with the distance to the nearest brush
+        // center, mirrors included; a stamp reaches as far as its own
+        // half-width instead of the brush size.
+        let centers = ctx.symmetry.mirror_positions(hit.voxel_pos);
+        let distance = |(x, y, z): (i32, i32, i32)| {
+            centers
+                .iter()
+                .map(|c| {
+                    let d = ((x - c.0).pow(2) + (y - c.1).pow(2) + (z - c.2).pow(2)) as f32;
+                    d.sqrt()
+                })
+                .fold(f32::INFINITY, f32::min)
+        };
+        let reach = match (&ctx.brush.shape, &ctx.brush.stamp) {
+            (BrushShape::Stamp, Some(stamp)) => {
+                let (x, y, z) = stamp.size();
+                (x.max(y).max(z) / 2 + 1).min(u8::MAX as u32) as u8
+            }
+            _ => ctx.brush_size,
+        };
 
         let changes: Vec<VoxelChange> = match self.mode {
-            Tool::Place => positions
+            Tool::Place => cells
                 .into_iter()
-                .map(|pos| VoxelChange {
+                .map(|(pos, stamp)| VoxelChange {
                     pos,
                     old_voxel: ctx.world.get_voxel(pos.0, pos.1, pos.2),
-                    new_voxel: ctx.brush_color,
+                    new_voxel: color_at(pos, stamp),
                 })
                 .filter(|c| c.old_voxel != c.new_voxel)
                 .collect(),
-            Tool::Remove => positions
+            Tool::Remove => cells
                 .into_iter()
-                .filter_map(|pos| {
+                .filter_map(|(pos, _)| {
                     let old = ctx.world.get_voxel(pos.0, pos.1, pos.2);
                     if old.is_air() {
                         None
@@ -239,74 +297,157 @@ impl EditorTool for BrushTool {
                     }
                 })
                 .collect(),
-            Tool::Paint => positions
+            Tool::Paint => cells
                 .into_iter()
-                .filter_map(|pos| {
+                .filter_map(|(pos, stamp)| {
                     let old = ctx.world.get_voxel(pos.0, pos.1, pos.2);
-                    if !old.is_air() && old != ctx.brush_color {
-                        Some(VoxelChange { pos, old_voxel: old, new_voxel: ctx.brush_color })
+                    let mut new_voxel = color_at(pos, stamp);
+                    if ctx.paint.soft && !old.is_air() {
+                        let weight = ctx.paint.soft_weight(distance(pos), reach);
+                        new_voxel = PaintSettings::soft_blend(old, new_voxel, weight);
+                    }
+                    if !old.is_air() && old != new_voxel {
+                        Some(VoxelChange { pos, old_voxel: old, new_voxel })
                     } else {
                         None
                     }
                 })
                 .collect(),
+            Tool::Sculpt => {
+                let cells: Vec<_> = cells.into_iter().map(|(p, _)| p).collect();
+                build_sculpt_changes(ctx.world, &cells, ctx.sculpt)
+            }
             _ => return,
         };
 
         if !changes.is_empty() {
-            let cmd = Command::set_voxels(changes);
+            let cmd = Command::set_voxels(changes).named(format!("{} Stroke", self.mode.name()));
             ctx.history.execute_merge(cmd, ctx.world, STROKE_MERGE_WINDOW);
         }
     }
 
     fn preview_positions(
         &self,
+        world: &World,
         hit: &RaycastHit,
         brush_size: u8,
+        brush: &BrushSettings,
         symmetry: SymmetryAxes,
     ) -> Vec<(i32, i32, i32)> {
         match self.mode {
-            Tool::Place => Self::affected_positions(hit.adjacent_pos, brush_size, symmetry),
-            Tool::Remove | Tool::Paint => {
-                Self::affected_positions(hit.voxel_pos, brush_size, symmetry)
+            Tool::Place | Tool::Remove | Tool::Paint | Tool::Sculpt => {
+                Self::affected_cells(world, self.mode, hit, brush_size, brush, symmetry)
+                    .into_iter()
+                    .map(|(p, _)| p)
+                    .collect()
             }
             // Fill marks just the seed cell(s) — full flood region would
             // be too expensive to compute every frame.
             Tool::Fill => symmetry.mirror_positions(hit.voxel_pos),
             Tool::Eyedropper => vec![hit.voxel_pos],
-            // Shape tools and Select have their own preview path
-            // (App::update_brush_preview for shapes; the dedicated
-            // selection-mesh slot for Select). BrushTool's preview is
-            // bypassed for them. Empty here keeps the trait satisfied
+            // Shape tools, Extrude, Select and Wand have their own
+            // preview path (App::update_brush_preview for shapes and
+            // the extrusion ghost; the dedicated selection-mesh slot
+            // for Select / Wand). BrushTool's preview is bypassed for
+            // them. Empty here keeps the trait satisfied
             // without contributing stray cells if someone ever calls
             // this for a non-brush tool by mistake.
             Tool::Line | Tool::Box | Tool::Sphere | Tool::Cylinder | Tool::Select
-            | Tool::Socket => Vec::new(),
+            | Tool::Socket | Tool::Extrude | Tool::Wand | Tool::Measure => Vec::new(),
         }
     }
 }
 
 impl BrushTool {
-    /// Brush sphere positions centered at `center` plus every mirror
-    /// implied by `symmetry`, deduped. Pulled out so both `apply` and
-    /// `preview_positions` go through the same expansion path.
-    fn affected_positions(
-        center: (i32, i32, i32),
+    /// Brush cells for `mode` at `hit` plus every mirror implied by
+    /// `symmetry`, deduped. Place centers on the adjacent cell and
+    /// rests a stamp on the clicked face; Remove / Paint center on the
+    /// hit voxel itself. Then [`BrushSettings::surface_snap`] and
+    /// [`BrushSettings::top_layer_only`] drop the cells they rule out.
+    /// Pulled out so both `apply` and `preview_positions` go through
+    /// the same expansion path.
+    fn affected_cells(
+        world: &World,
+        mode: Tool,
+        hit: &RaycastHit,
         brush_size: u8,
+        brush: &BrushSettings,
         symmetry: SymmetryAxes,
-    ) -> Vec<(i32, i32, i32)> {
-        if !symmetry.any() {
-            // Common path: skip the HashSet allocation when no mirroring.
-            return Self::get_brush_positions(center, brush_size);
-        }
-        let mut out: HashSet<(i32, i32, i32)> = HashSet::new();
-        for c in symmetry.mirror_positions(center) {
-            for p in Self::get_brush_positions(c, brush_size) {
-                out.insert(p);
+    ) -> Vec<((i32, i32, i32), Option<Voxel>)> {
+        let (center, rest_on_face) = match mode {
+            Tool::Place => (hit.adjacent_pos, true),
+            _ => (hit.voxel_pos, false),
+        };
+        let mut cells =
+            brush.mirrored_cells(center, brush_size, hit.normal, rest_on_face, symmetry);
+        let solid = |(x, y, z): (i32, i32, i32)| world.get_voxel(x, y, z).is_solid();
+        let step = |p: (i32, i32, i32), n: (i32, i32, i32), k: i32| {
+            (p.0 + n.0 * k, p.1 + n.1 * k, p.2 + n.2 * k)
+        };
+        if brush.surface_snap && hit.normal != (0, 0, 0) {
+            // A mirrored copy faces the mirrored way, so a cell passes
+            // against any of the normal's mirror images.
+            let normals = symmetry_normals(hit.normal, symmetry);
+            match mode {
+                // Only empty cells resting on the model: no overwriting
+                // it, no blobs floating off it. The virtual ground has
+                // nothing to rest on, so it's left alone.
+                Tool::Place if !hit.virtual_ground => cells.retain(|&(p, _)| {
+                    !solid(p) && normals.iter().any(|&n| solid(step(p, n, -1)))
+                }),
+                // Only solid cells facing out the way the hit face does.
+                Tool::Paint => cells.retain(|&(p, _)| {
+                    solid(p) && normals.iter().any(|&n| !solid(step(p, n, 1)))
+                }),
+                _ => {}
             }
         }
-        out.into_iter().collect()
+        if brush.top_layer_only && matches!(mode, Tool::Remove | Tool::Paint) {
+            const FACES: [(i32, i32, i32); 6] =
+                [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+            cells.retain(|&(p, _)| FACES.iter().any(|&n| !solid(step(p, n, 1))));
+        }
+        cells
+    }
+}
+
+/// `normal` and its reflections across the enabled symmetry planes,
+/// deduped.
+fn symmetry_normals(normal: (i32, i32, i32), symmetry: SymmetryAxes) -> Vec<(i32, i32, i32)> {
+    let mut out = symmetry.mirror_normals(normal);
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+/// Hits for the cells a fast drag skipped between the previous stroke
+/// cell `from` and the current `hit`, in stroke order, excluding both
+/// endpoints (`from` was already painted; the caller applies `hit`
+/// itself). Cursor motion events arrive once per frame, so a quick
+/// flick can jump several cells — re-applying the brush at each cell
+/// of the 3D line between them keeps the stroke gap-free.
+///
+/// Each synthesized hit reuses `hit`'s face normal and its
+/// voxel→adjacent offset, so `Place` keeps stacking on the same face
+/// the stroke plane is locked to.
+pub fn stroke_gap_hits(from: (i32, i32, i32), hit: &RaycastHit) -> Vec<RaycastHit> {
+    let off = (
+        hit.adjacent_pos.0 - hit.voxel_pos.0,
+        hit.adjacent_pos.1 - hit.voxel_pos.1,
+        hit.adjacent_pos.2 - hit.voxel_pos.2,
+    );
+    let cells = line_voxels(from, hit.voxel_pos);
+    if cells.len() <= 2 {
+        return Vec::new();
     }
+    cells[1..cells.len() - 1]
+        .iter()
+        .map(|&p| RaycastHit {
+            voxel_pos: p,
+            adjacent_pos: (p.0 + off.0, p.1 + off.1, p.2 + off.2),
+            ..*hit
+        })
+        .collect()
 }
 
 /// Pick color from a voxel
@@ -319,24 +460,51 @@ pub fn eyedrop(world: &World, hit: &RaycastHit) -> Option<Voxel> {
     }
 }
 
+/// A fill's starting cell and the normal of the face it was clicked
+/// on.
+pub type FillSeed = ((i32, i32, i32), (i32, i32, i32));
+
+/// Most voxels a Fill click writes before asking. The App prompts
+/// before a fill bigger than this; scripted fills just stop at it.
+pub const MAX_FILL_VOXELS: usize = 10_000;
+
 /// Compute the changes a flood-fill would make from `start`, without
 /// applying them. Pulled out of `flood_fill` so callers that need to
 /// batch multiple fills into a single undo entry (notably the symmetric
 /// fill path in `app::input::apply_tool`) can collect changes from
 /// several seeds and submit one combined `Command`.
 ///
+/// `normal` is the clicked face's: `fill.region` keeps the flood to the
+/// slice across it or the surface facing along it (a zero normal
+/// leaves the volume fill). With a `work_plane` the flood spreads only
+/// within that slice, so filling a region of one layer leaves the
+/// same-colored layers on either side alone.
+///
 /// Returns an empty `Vec` if `start` already holds `new_voxel` or
 /// would produce no writes for any reason.
 pub fn compute_flood_fill_changes(
     world: &World,
     start: (i32, i32, i32),
+    normal: (i32, i32, i32),
     new_voxel: Voxel,
     max_voxels: usize,
+    work_plane: Option<WorkPlane>,
+    fill: FillSettings,
 ) -> Vec<VoxelChange> {
     let target_voxel = world.get_voxel(start.0, start.1, start.2);
     if target_voxel == new_voxel {
         return Vec::new();
     }
+    let region = if normal == (0, 0, 0) { FillRegion::Volume } else { fill.region };
+    let depth = |p: (i32, i32, i32)| p.0 * normal.0 + p.1 * normal.1 + p.2 * normal.2;
+    let in_region = |p: (i32, i32, i32)| match region {
+        FillRegion::Volume => true,
+        FillRegion::Slice => depth(p) == depth(start),
+        FillRegion::Surface => world
+            .get_voxel(p.0 + normal.0, p.1 + normal.1, p.2 + normal.2)
+            .is_air(),
+    };
+    let offsets = fill.connectivity.offsets();
 
     let mut changes = Vec::new();
     let mut visited = HashSet::new();
@@ -359,9 +527,12 @@ pub fn compute_flood_fill_changes(
         {
             continue;
         }
+        if work_plane.is_some_and(|plane| !plane.contains(pos)) {
+            continue;
+        }
 
         let current = world.get_voxel(pos.0, pos.1, pos.2);
-        if current != target_voxel {
+        if current != target_voxel || !in_region(pos) {
             continue;
         }
 
@@ -372,16 +543,8 @@ pub fn compute_flood_fill_changes(
             new_voxel,
         });
 
-        // 6-connectivity expansion.
-        let neighbors = [
-            (pos.0 + 1, pos.1, pos.2),
-            (pos.0 - 1, pos.1, pos.2),
-            (pos.0, pos.1 + 1, pos.2),
-            (pos.0, pos.1 - 1, pos.2),
-            (pos.0, pos.1, pos.2 + 1),
-            (pos.0, pos.1, pos.2 - 1),
-        ];
-        for neighbor in neighbors {
+        for &(dx, dy, dz) in &offsets {
+            let neighbor = (pos.0 + dx, pos.1 + dy, pos.2 + dz);
             if !visited.contains(&neighbor) {
                 stack.push(neighbor);
             }
@@ -394,49 +557,71 @@ pub fn compute_flood_fill_changes(
 /// Flood fill from a single seed: thin wrapper that computes the
 /// changes via `compute_flood_fill_changes` and pushes one `Command`
 /// onto `history`. Returns the number of voxels written.
+#[allow(clippy::too_many_arguments)]
 pub fn flood_fill(
     world: &mut World,
     history: &mut CommandHistory,
     start: (i32, i32, i32),
+    normal: (i32, i32, i32),
     new_voxel: Voxel,
     max_voxels: usize,
+    work_plane: Option<WorkPlane>,
+    fill: FillSettings,
 ) -> usize {
-    let changes = compute_flood_fill_changes(world, start, new_voxel, max_voxels);
+    let changes =
+        compute_flood_fill_changes(world, start, normal, new_voxel, max_voxels, work_plane, fill);
     let count = changes.len();
     if !changes.is_empty() {
-        let cmd = Command::set_voxels(changes);
+        let cmd = Command::set_voxels(changes).named("Fill");
         history.execute(cmd, world);
     }
     count
 }
 
-/// Flood fill from multiple seeds, batching all resulting writes into
-/// a single `Command` so the whole symmetric stroke is one undo entry.
-/// Each seed's flood is computed against the *original* world snapshot
-/// (not the cumulative one), so two seeds spreading toward the same
-/// region won't surprise each other; the per-position dedup keeps the
-/// first occurrence (any later mirror writing the same cell would
-/// produce the same `new_voxel` anyway, so the choice is benign).
-pub fn flood_fill_multi(
-    world: &mut World,
-    history: &mut CommandHistory,
-    starts: &[(i32, i32, i32)],
+/// The combined changes of a fill from every seed. Each seed floods
+/// the original world, not the result of the seeds before it, and a
+/// cell reached by several keeps its first change — all write the same
+/// `new_voxel`. `max_voxels` caps each seed's flood.
+pub fn compute_fill_changes(
+    world: &World,
+    seeds: &[FillSeed],
     new_voxel: Voxel,
     max_voxels: usize,
-) -> usize {
+    work_plane: Option<WorkPlane>,
+    fill: FillSettings,
+) -> Vec<VoxelChange> {
     let mut combined: HashMap<(i32, i32, i32), VoxelChange> = HashMap::new();
-    for &start in starts {
+    for &(start, normal) in seeds {
         // Skip air seeds defensively — Fill semantics don't extend air.
         if world.get_voxel(start.0, start.1, start.2).is_air() {
             continue;
         }
-        for change in compute_flood_fill_changes(world, start, new_voxel, max_voxels) {
+        let changes = compute_flood_fill_changes(
+            world, start, normal, new_voxel, max_voxels, work_plane, fill,
+        );
+        for change in changes {
             combined.entry(change.pos).or_insert(change);
         }
     }
-    let count = combined.len();
+    combined.into_values().collect()
+}
+
+/// Flood fill from multiple seeds, batching all resulting writes into
+/// a single `Command` so the whole symmetric stroke is one undo entry.
+/// See [`compute_fill_changes`].
+pub fn flood_fill_multi(
+    world: &mut World,
+    history: &mut CommandHistory,
+    seeds: &[FillSeed],
+    new_voxel: Voxel,
+    max_voxels: usize,
+    work_plane: Option<WorkPlane>,
+    fill: FillSettings,
+) -> usize {
+    let changes = compute_fill_changes(world, seeds, new_voxel, max_voxels, work_plane, fill);
+    let count = changes.len();
     if count > 0 {
-        let cmd = Command::set_voxels(combined.into_values().collect());
+        let cmd = Command::set_voxels(changes).named("Fill");
         history.execute(cmd, world);
     }
     count
@@ -445,17 +630,169 @@ pub fn flood_fill_multi(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::editor::{Axis, FillConnectivity};
 
     #[test]
     fn test_brush_positions() {
-        let positions = BrushTool::get_brush_positions((0, 0, 0), 1);
+        let sphere = BrushSettings::default();
+        let positions = sphere.cells((0, 0, 0), 1, (0, 1, 0), false);
         assert_eq!(positions.len(), 1);
-        assert!(positions.contains(&(0, 0, 0)));
+        assert_eq!(positions[0], ((0, 0, 0), None));
 
-        let positions = BrushTool::get_brush_positions((0, 0, 0), 2);
+        let positions = sphere.cells((0, 0, 0), 2, (0, 1, 0), false);
         assert!(positions.len() > 1);
     }
 
+    #[test]
+    fn test_stroke_gap_hits_fill_between_endpoints() {
+        let hit = RaycastHit {
+            voxel_pos: (5, 0, 2),
+            adjacent_pos: (5, 1, 2),
+            normal: (0, 1, 0),
+            distance: 3.0,
+            virtual_ground: false,
+        };
+        let gaps = stroke_gap_hits((0, 0, 0), &hit);
+        // 6 cells on the x-major line, minus both endpoints.
+        assert_eq!(gaps.len(), 4);
+        for (g, x) in gaps.iter().zip(1..) {
+            assert_eq!(g.voxel_pos.0, x);
+            assert_eq!(g.voxel_pos.1, 0);
+            assert_eq!(g.adjacent_pos, (g.voxel_pos.0, 1, g.voxel_pos.2));
+            assert_eq!(g.normal, hit.normal);
+        }
+        // Neighbouring cells leave nothing to fill.
+        assert!(stroke_gap_hits((4, 0, 2), &hit).is_empty());
+    }
+
+    #[test]
+    fn test_preview_positions_match_applied_cells() {
+        // The hover ghost must show exactly what a click would write,
+        // symmetry mirrors included.
+        let hit = RaycastHit {
+            voxel_pos: (3, 0, 2),
+            adjacent_pos: (3, 1, 2),
+            normal: (0, 1, 0),
+            distance: 1.0,
+            virtual_ground: false,
+        };
+        let symmetry = SymmetryAxes { x: true, ..Default::default() };
+        let brush = BrushTool::new(Tool::Place);
+        let preview: HashSet<_> = brush
+            .preview_positions(&World::new(), &hit, 2, &BrushSettings::default(), symmetry)
+            .into_iter()
+            .collect();
+
+        let mut world = World::new();
+        let mut history = CommandHistory::new(10);
+        let mut ctx = ToolContext {
+            world: &mut world,
+            history: &mut history,
+            brush_color: Voxel::from_rgb(10, 20, 30),
+            brush_size: 2,
+            symmetry,
+            paint: PaintSettings::default(),
+            brush: BrushSettings::default(),
+            sculpt: SculptSettings::default(),
+            work_plane: None,
+        };
+        brush.apply(&mut ctx, &hit);
+        let written: HashSet<_> = preview
+            .iter()
+            .copied()
+            .filter(|&(x, y, z)| !world.get_voxel(x, y, z).is_air())
+            .collect();
+        assert_eq!(written, preview);
+        let solid: u32 = world.chunks().map(|(_, c)| c.read().solid_count()).sum();
+        assert_eq!(solid as usize, preview.len());
+    }
+
+    #[test]
+    fn test_soft_paint_blends_toward_the_brush_color() {
+        let mut world = World::new();
+        let gray = Voxel::from_rgb(100, 100, 100);
+        for x in -3..=3 {
+            world.set_voxel(x, 0, 0, gray);
+        }
+        let mut history = CommandHistory::new(10);
+        let mut ctx = ToolContext {
+            world: &mut world,
+            history: &mut history,
+            brush_color: Voxel::from_rgb(200, 100, 100),
+            brush_size: 2,
+            symmetry: SymmetryAxes::default(),
+            paint: PaintSettings {
+                soft: true,
+                soft_strength: 100,
+                ..Default::default()
+            },
+            brush: BrushSettings::default(),
+            sculpt: SculptSettings::default(),
+            work_plane: None,
+        };
+        let hit = RaycastHit {
+            voxel_pos: (0, 0, 0),
+            adjacent_pos: (0, 1, 0),
+            normal: (0, 1, 0),
+            distance: 1.0,
+            virtual_ground: false,
+        };
+        BrushTool::new(Tool::Paint).apply(&mut ctx, &hit);
+        // Full color at the center, halfway one cell out, untouched
+        // outside the brush.
+        assert_eq!(world.get_voxel(0, 0, 0).r, 200);
+        assert_eq!(world.get_voxel(1, 0, 0).r, 150);
+        assert_eq!(world.get_voxel(-1, 0, 0).r, 150);
+        assert_eq!(world.get_voxel(2, 0, 0).r, 100);
+    }
+
+    #[test]
+    fn test_surface_modes_keep_strokes_on_the_surface() {
+        // A 5×3×5 slab, hit on its top face at the center.
+        let mut world = World::new();
+        let gray = Voxel::from_rgb(100, 100, 100);
+        for x in -2..=2 {
+            for y in -2..=0 {
+                for z in -2..=2 {
+                    world.set_voxel(x, y, z, gray);
+                }
+            }
+        }
+        let hit = RaycastHit {
+            voxel_pos: (0, 0, 0),
+            adjacent_pos: (0, 1, 0),
+            normal: (0, 1, 0),
+            distance: 1.0,
+            virtual_ground: false,
+        };
+        let snap = BrushSettings {
+            shape: BrushShape::Cube,
+            surface_snap: true,
+            ..Default::default()
+        };
+        let none = SymmetryAxes::default();
+        // Paint: only the top face, none of the cells beneath it.
+        let paint = BrushTool::new(Tool::Paint).preview_positions(&world, &hit, 2, &snap, none);
+        assert_eq!(paint.len(), 9);
+        assert!(paint.iter().all(|&(_, y, _)| y == 0));
+        // Place: one layer on top, nothing floating above it.
+        let place = BrushTool::new(Tool::Place).preview_positions(&world, &hit, 2, &snap, none);
+        assert_eq!(place.len(), 9);
+        assert!(place.iter().all(|&(_, y, _)| y == 1));
+
+        // Top layer: Remove skips the buried center cell.
+        let top = BrushSettings {
+            shape: BrushShape::Cube,
+            top_layer_only: true,
+            ..Default::default()
+        };
+        let inner = RaycastHit { voxel_pos: (0, -1, 0), ..hit };
+        let remove = BrushTool::new(Tool::Remove).preview_positions(&world, &inner, 2, &top, none);
+        assert!(!remove.contains(&(0, -1, 0)));
+        assert!(remove.contains(&(0, 0, 0)));
+        assert!(remove.contains(&(0, -2, 0)));
+    }
+
     #[test]
     fn test_flood_fill() {
         let mut world = World::new();
@@ -474,14 +811,50 @@ mod tests {
             &mut world,
             &mut history,
             (1, 0, 1),
+            (0, 1, 0),
             Voxel::from_rgb(255, 0, 0),
             1000,
+            None,
+            FillSettings::default(),
         );
 
         assert_eq!(count, 9);
         assert_eq!(world.get_voxel(0, 0, 0).r, 255);
     }
 
+    #[test]
+    fn test_flood_fill_stays_on_work_plane() {
+        let mut world = World::new();
+        let mut history = CommandHistory::new(100);
+        // Two stacked 3×3 layers of the same color.
+        for x in 0..3 {
+            for y in 0..2 {
+                for z in 0..3 {
+                    world.set_voxel(x, y, z, Voxel::from_rgb(100, 100, 100));
+                }
+            }
+        }
+
+        let plane = WorkPlane {
+            axis: Axis::Y,
+            coord: 1,
+        };
+        let count = flood_fill(
+            &mut world,
+            &mut history,
+            (1, 1, 1),
+            (0, 1, 0),
+            Voxel::from_rgb(255, 0, 0),
+            1000,
+            Some(plane),
+            FillSettings::default(),
+        );
+
+        assert_eq!(count, 9);
+        assert_eq!(world.get_voxel(0, 1, 0).r, 255);
+        assert_eq!(world.get_voxel(0, 0, 0).r, 100);
+    }
+
     #[test]
     fn test_flood_fill_bounding_box_caps() {
         // A long thin connected strip extending past MAX_FILL_DIST.
@@ -501,8 +874,11 @@ mod tests {
             &mut world,
             &mut history,
             (0, 0, 0),
+            (0, 1, 0),
             Voxel::from_rgb(255, 0, 0),
             1_000_000, // generous voxel cap so spatial cap is what bites
+            None,
+            FillSettings::default(),
         );
 
         // From start (0,0,0), reachable along +X is x ∈ [0, MAX_FILL_DIST].
@@ -520,4 +896,47 @@ mod tests {
             255
         );
     }
+
+    #[test]
+    fn test_fill_regions_and_connectivity() {
+        // A 3×3×3 block with a 2×1×2 step on top at one corner, and a
+        // lone voxel touching the block only at a corner.
+        let gray = Voxel::from_rgb(100, 100, 100);
+        let red = Voxel::from_rgb(255, 0, 0);
+        let mut world = World::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    world.set_voxel(x, y, z, gray);
+                }
+            }
+        }
+        for (x, z) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            world.set_voxel(x, 3, z, gray);
+        }
+        world.set_voxel(3, 3, 3, gray);
+        let up = (0, 1, 0);
+        let fill = |region, connectivity| {
+            compute_flood_fill_changes(
+                &world,
+                (2, 2, 2),
+                up,
+                red,
+                1000,
+                None,
+                FillSettings { region, connectivity },
+            )
+            .len()
+        };
+        // Volume: block and step across faces; the corner voxel only
+        // joins with corner connectivity.
+        assert_eq!(fill(FillRegion::Volume, FillConnectivity::Face), 31);
+        assert_eq!(fill(FillRegion::Volume, FillConnectivity::Corner), 32);
+        // Slice: the clicked layer only.
+        assert_eq!(fill(FillRegion::Slice, FillConnectivity::Face), 9);
+        // Surface: the uncovered top of the block, and with edges the
+        // step's top too.
+        assert_eq!(fill(FillRegion::Surface, FillConnectivity::Face), 5);
+        assert_eq!(fill(FillRegion::Surface, FillConnectivity::Edge), 9);
+    }
 }
@@ -1,5 +1,8 @@
 //! Selection transforms: 90° rotations around an axis-aligned axis and
-//! mirror flips. Both produce an overlap-safe `Vec<VoxelChange>` the
+//! mirror flips, plus [`origin_offset`] for re-centering a model on the
+//! world origin (applied as a move), [`scale_selection_changes`] for
+//! integer up / down scaling and [`build_crop_changes`] for cropping a
+//! model to a box. Rotations and flips produce an overlap-safe `Vec<VoxelChange>` the
 //! caller wraps in `Command::set_voxels` so a single Ctrl+Z reverses
 //! the whole transform.
 //!
@@ -20,13 +23,15 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use crate::core::{Voxel, World};
 
 use super::{Selection, VoxelChange};
 
 /// World-aligned axis. Used for both rotation (axis of revolution) and
 /// mirroring (axis along which positions are reversed).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Axis {
     X,
     Y,
@@ -139,10 +144,14 @@ pub fn build_remap_changes<F>(
 where
     F: Fn((i32, i32, i32)) -> (i32, i32, i32),
 {
-    let mut by_pos: HashMap<(i32, i32, i32), (Voxel, Voxel)> = HashMap::new();
+    let originals = solid_cells(world, sel);
+    let writes: Vec<_> = originals.iter().map(|&(src, vox)| (mapping(src), vox)).collect();
+    rewrite_changes(world, &originals, writes)
+}
 
-    let originals: Vec<((i32, i32, i32), Voxel)> = sel
-        .iter_cells()
+/// The non-air voxels in `sel`, in [`Selection::iter_cells`] order.
+fn solid_cells(world: &World, sel: Selection) -> Vec<((i32, i32, i32), Voxel)> {
+    sel.iter_cells()
         .filter_map(|p| {
             let v = world.get_voxel(p.0, p.1, p.2);
             if v.is_air() {
@@ -151,20 +160,30 @@ where
                 Some((p, v))
             }
         })
-        .collect();
+        .collect()
+}
+
+/// Changes that clear every `originals` cell to AIR and then apply
+/// `writes` on top — the overlap bookkeeping shared by remaps and
+/// [`scale_selection_changes`].
+fn rewrite_changes(
+    world: &World,
+    originals: &[((i32, i32, i32), Voxel)],
+    writes: Vec<((i32, i32, i32), Voxel)>,
+) -> Vec<VoxelChange> {
+    let mut by_pos: HashMap<(i32, i32, i32), (Voxel, Voxel)> = HashMap::new();
 
     // Step 1: source positions clear to AIR, with their pre-transform
     // value pinned as old_voxel.
-    for &(p, old) in &originals {
+    for &(p, old) in originals {
         by_pos.insert(p, (old, Voxel::AIR));
     }
 
-    // Step 2: destination positions receive the moved voxel. For new
+    // Step 2: destination positions receive the written voxel. For new
     // (non-overlapping) destinations, read old_voxel from the world
     // now. For overlap destinations, `entry().or_insert` preserves
     // the world-old that Step 1 stored — undo restores exactly.
-    for &(src, vox) in &originals {
-        let dest = mapping(src);
+    for (dest, vox) in writes {
         let world_old = world.get_voxel(dest.0, dest.1, dest.2);
         let entry = by_pos.entry(dest).or_insert((world_old, Voxel::AIR));
         entry.1 = vox;
@@ -208,6 +227,196 @@ pub fn mirror_selection_changes(
     build_remap_changes(world, sel, |p| mirror_pos(sel, axis, p))
 }
 
+/// How a downscale picks the color of a cell made from a block of
+/// source cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Downsample {
+    /// The block's most common voxel, unchanged — keeps a palette
+    /// model on its palette.
+    Majority,
+    /// The mean RGBA of the block's solid voxels; material and flags
+    /// come from the most common one.
+    Average,
+}
+
+/// Integer resampling for [`scale_selection_changes`]. Factors below 2
+/// are treated as 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scale {
+    /// Every cell becomes a `factor`³ cube of itself.
+    Up(u8),
+    /// Every `factor`³ block becomes one cell, solid when at least half
+    /// of the block (clipped to the box) is, colored per [`Downsample`].
+    Down(u8, Downsample),
+}
+
+impl Scale {
+    fn factor(self) -> i32 {
+        match self {
+            Self::Up(k) | Self::Down(k, _) => k.max(2) as i32,
+        }
+    }
+}
+
+/// AABB of `sel` after [`Scale`]. `sel.min` is preserved like for the
+/// rotations; a downscale rounds a partial block at the `max` end up
+/// to a whole cell.
+pub fn scaled_aabb(sel: Selection, scale: Scale) -> Selection {
+    let k = scale.factor();
+    let extent = |n: i32| match scale {
+        Scale::Up(_) => n * k,
+        Scale::Down(..) => (n + k - 1) / k,
+    };
+    let (w, h, d) = sel.size();
+    Selection {
+        min: sel.min,
+        max: (
+            sel.min.0 + extent(w) - 1,
+            sel.min.1 + extent(h) - 1,
+            sel.min.2 + extent(d) - 1,
+        ),
+    }
+}
+
+/// Scale `sel`'s contents by an integer factor, returning
+/// `(new_selection_aabb, voxel_changes)` like
+/// [`rotate_selection_changes`]. Scaling up 2× then down 2× with
+/// [`Downsample::Majority`] restores the original exactly.
+pub fn scale_selection_changes(
+    world: &World,
+    sel: Selection,
+    scale: Scale,
+) -> (Selection, Vec<VoxelChange>) {
+    let new_sel = scaled_aabb(sel, scale);
+    let k = scale.factor();
+    let originals = solid_cells(world, sel);
+    let local = |p: (i32, i32, i32)| (p.0 - sel.min.0, p.1 - sel.min.1, p.2 - sel.min.2);
+    let at = |l: (i32, i32, i32)| (sel.min.0 + l.0, sel.min.1 + l.1, sel.min.2 + l.2);
+
+    let writes = match scale {
+        Scale::Up(_) => {
+            let mut writes = Vec::with_capacity(originals.len() * (k * k * k) as usize);
+            for &(src, vox) in &originals {
+                let (lx, ly, lz) = local(src);
+                for dz in 0..k {
+                    for dy in 0..k {
+                        for dx in 0..k {
+                            writes.push((at((lx * k + dx, ly * k + dy, lz * k + dz)), vox));
+                        }
+                    }
+                }
+            }
+            writes
+        }
+        Scale::Down(_, mode) => {
+            // Solid voxels per destination cell, in source scan order
+            // so ties resolve the same way every time.
+            let mut blocks: HashMap<(i32, i32, i32), Vec<Voxel>> = HashMap::new();
+            let mut order = Vec::new();
+            for &(src, vox) in &originals {
+                let (lx, ly, lz) = local(src);
+                let dest = (lx / k, ly / k, lz / k);
+                blocks
+                    .entry(dest)
+                    .or_insert_with(|| {
+                        order.push(dest);
+                        Vec::new()
+                    })
+                    .push(vox);
+            }
+            let (w, h, d) = sel.size();
+            let clipped = |n: i32, i: i32| (n - i * k).min(k);
+            order
+                .into_iter()
+                .filter_map(|dest| {
+                    let solid = &blocks[&dest];
+                    let cells = clipped(w, dest.0) * clipped(h, dest.1) * clipped(d, dest.2);
+                    (solid.len() as i32 * 2 >= cells)
+                        .then(|| (at(dest), downsample(solid, mode)))
+                })
+                .collect()
+        }
+    };
+    (new_sel, rewrite_changes(world, &originals, writes))
+}
+
+/// One voxel standing in for the solid voxels of a downscaled block.
+fn downsample(solid: &[Voxel], mode: Downsample) -> Voxel {
+    let mut counts: Vec<(Voxel, usize)> = Vec::new();
+    for &vox in solid {
+        match counts.iter_mut().find(|(v, _)| *v == vox) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((vox, 1)),
+        }
+    }
+    // `max_by_key` keeps the last of equal maxima; reverse so the
+    // first-seen voxel wins ties.
+    let majority = counts
+        .iter()
+        .rev()
+        .max_by_key(|(_, n)| *n)
+        .map(|&(v, _)| v)
+        .expect("downsampled blocks have a solid voxel");
+    match mode {
+        Downsample::Majority => majority,
+        Downsample::Average => {
+            let mut sum = [0u32; 4];
+            for vox in solid {
+                for (acc, c) in sum.iter_mut().zip(vox.color()) {
+                    *acc += c as u32;
+                }
+            }
+            let n = solid.len() as u32;
+            let mean = sum.map(|c| ((c + n / 2) / n) as u8);
+            Voxel { r: mean[0], g: mean[1], b: mean[2], a: mean[3], ..majority }
+        }
+    }
+}
+
+/// Which point of a model's bounding box [`origin_offset`] moves onto
+/// the world origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OriginAnchor {
+    /// The box center.
+    Center,
+    /// The center of the box's bottom face: X / Z centered, the lowest
+    /// layer of voxels on `y = 0` — a model standing on the ground.
+    Base,
+}
+
+/// Translation that puts `anchor` of `bounds` on the world origin.
+/// Voxel cells span `[i, i + 1)`, so a box with an even side ends up
+/// exactly centered on that axis (`-n/2 ..= n/2 - 1`); an odd side
+/// can't be and lands half a cell toward +.
+pub fn origin_offset(bounds: Selection, anchor: OriginAnchor) -> (i32, i32, i32) {
+    let (w, h, d) = bounds.size();
+    let center = |min: i32, size: i32| -(min + size / 2);
+    let y = match anchor {
+        OriginAnchor::Center => center(bounds.min.1, h),
+        OriginAnchor::Base => -bounds.min.1,
+    };
+    (center(bounds.min.0, w), y, center(bounds.min.2, d))
+}
+
+/// Build the `VoxelChange` list that crops `world` to `keep`: every
+/// solid voxel outside the box clears to AIR, everything inside is
+/// untouched. Walks the solid voxels chunk by chunk, so the cost is the
+/// model's size rather than the (possibly huge) box's.
+pub fn build_crop_changes(world: &World, keep: Selection) -> Vec<VoxelChange> {
+    let mut changes = Vec::new();
+    for (chunk_pos, chunk) in world.chunks() {
+        let chunk = chunk.read();
+        let (ox, oy, oz) = chunk_pos.world_origin();
+        for (lp, &old_voxel) in chunk.iter_solid() {
+            let pos = (ox + lp.x as i32, oy + lp.y as i32, oz + lp.z as i32);
+            if !keep.contains(pos) {
+                changes.push(VoxelChange { pos, old_voxel, new_voxel: Voxel::AIR });
+            }
+        }
+    }
+    changes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -420,6 +629,144 @@ mod tests {
         assert!(world.get_voxel(0, 0, 1).is_air());
     }
 
+    #[test]
+    fn whole_model_rotates_and_flips_within_its_bounds() {
+        // No selection: the model's bounding box is the transform box.
+        let mut world = World::new();
+        world.set_voxel(-2, 0, 5, voxel(255, 0, 0));
+        world.set_voxel(1, 3, 5, voxel(0, 255, 0));
+        world.set_voxel(1, 0, 6, voxel(0, 0, 255));
+        // Covers both the original and the rotated box.
+        let snapshot = |world: &World| -> Vec<Voxel> {
+            Selection::from_corners((-2, 0, 5), (2, 4, 7))
+                .iter_cells()
+                .map(|p| world.get_voxel(p.0, p.1, p.2))
+                .collect()
+        };
+        let before = snapshot(&world);
+        let bounds = Selection::from(world.bounding_box().unwrap());
+        assert_eq!(bounds, Selection::from_corners((-2, 0, 5), (1, 3, 6)));
+
+        let (rotated, changes) = rotate_selection_changes(&world, bounds, Axis::Z, Quarter::Cw);
+        let cmd = Command::set_voxels(changes);
+        cmd.execute(&mut world);
+        // The model's new bounds are the rotated box: still on the
+        // same min corner, W and H swapped.
+        assert_eq!(Selection::from(world.bounding_box().unwrap()), rotated);
+        assert_eq!(rotated.size(), (4, 4, 2));
+        cmd.undo(&mut world);
+        assert_eq!(snapshot(&world), before);
+
+        let flip = Command::set_voxels(mirror_selection_changes(&world, bounds, Axis::Y));
+        flip.execute(&mut world);
+        assert_eq!(world.get_voxel(-2, 3, 5), voxel(255, 0, 0));
+        assert_eq!(world.get_voxel(1, 0, 5), voxel(0, 255, 0));
+        assert_eq!(Selection::from(world.bounding_box().unwrap()), bounds);
+    }
+
+    #[test]
+    fn origin_offset_centers_or_grounds_the_box() {
+        // 4×3×2 box: even sides center exactly, the odd one leans +.
+        let s = Selection::from_corners((10, 5, -7), (13, 7, -6));
+        let center = s.translated(origin_offset(s, OriginAnchor::Center));
+        assert_eq!(center, Selection::from_corners((-2, -1, -1), (1, 1, 0)));
+        // Base: same X / Z, bottom layer on y = 0.
+        let base = s.translated(origin_offset(s, OriginAnchor::Base));
+        assert_eq!(base, Selection::from_corners((-2, 0, -1), (1, 2, 0)));
+        // Already there: no move.
+        assert_eq!(origin_offset(center, OriginAnchor::Center), (0, 0, 0));
+    }
+
+    #[test]
+    fn crop_clears_only_voxels_outside_the_box() {
+        let mut world = World::new();
+        for x in -3..40 {
+            world.set_voxel(x, 0, 0, voxel(255, 0, 0));
+        }
+        world.set_voxel(0, 9, 0, voxel(0, 255, 0));
+        let keep = Selection::from_corners((0, 0, 0), (35, 2, 2));
+
+        let changes = build_crop_changes(&world, keep);
+        assert_eq!(changes.len(), 3 + 4 + 1);
+        assert!(changes.iter().all(|c| !keep.contains(c.pos) && c.new_voxel.is_air()));
+
+        let cmd = Command::set_voxels(changes);
+        cmd.execute(&mut world);
+        let bounds = world.bounding_box().unwrap();
+        assert_eq!((bounds.min, bounds.max), ((0, 0, 0), (35, 0, 0)));
+        cmd.undo(&mut world);
+        assert_eq!(world.get_voxel(-3, 0, 0), voxel(255, 0, 0));
+        assert_eq!(world.get_voxel(0, 9, 0), voxel(0, 255, 0));
+        assert!(build_crop_changes(&world, Selection::from_corners((-3, 0, 0), (39, 9, 0))).is_empty());
+    }
+
+    #[test]
+    fn scale_up_then_down_round_trips() {
+        let mut world = World::new();
+        world.set_voxel(5, 0, 0, voxel(255, 0, 0));
+        world.set_voxel(6, 0, 0, voxel(0, 255, 0));
+        world.set_voxel(6, 1, 0, voxel(0, 0, 255));
+        let s = Selection::from_corners((5, 0, 0), (6, 1, 0));
+
+        let (up, changes) = scale_selection_changes(&world, s, Scale::Up(3));
+        assert_eq!(up, Selection::from_corners((5, 0, 0), (10, 5, 2)));
+        let cmd = Command::set_voxels(changes);
+        cmd.execute(&mut world);
+        assert_eq!(world.solid_voxel_count(), 3 * 27);
+        assert_eq!(world.get_voxel(7, 2, 2), voxel(255, 0, 0));
+        assert_eq!(world.get_voxel(8, 3, 0), voxel(0, 0, 255));
+        assert!(world.get_voxel(5, 3, 0).is_air());
+
+        let (down, changes) =
+            scale_selection_changes(&world, up, Scale::Down(3, Downsample::Majority));
+        assert_eq!(down, s);
+        Command::set_voxels(changes).execute(&mut world);
+        assert_eq!(world.solid_voxel_count(), 3);
+        assert_eq!(world.get_voxel(6, 1, 0), voxel(0, 0, 255));
+
+        // Undo of the upscale still restores the pre-scale world.
+        let mut fresh = World::new();
+        fresh.set_voxel(5, 0, 0, voxel(255, 0, 0));
+        let (_, changes) = scale_selection_changes(&fresh, s, Scale::Up(2));
+        let cmd = Command::set_voxels(changes);
+        cmd.execute(&mut fresh);
+        cmd.undo(&mut fresh);
+        assert_eq!(fresh.solid_voxel_count(), 1);
+    }
+
+    #[test]
+    fn downscale_keeps_half_full_blocks_and_picks_a_color() {
+        let mut world = World::new();
+        // Block 0 (2×2×2 at the origin): 3 red + 1 blue, half full.
+        world.set_voxel(0, 0, 0, voxel(200, 0, 0));
+        world.set_voxel(1, 0, 0, voxel(200, 0, 0));
+        world.set_voxel(0, 1, 0, voxel(200, 0, 0));
+        world.set_voxel(1, 1, 0, voxel(0, 0, 200));
+        // Block 1: a single voxel, too sparse to survive.
+        world.set_voxel(2, 0, 0, voxel(0, 200, 0));
+        // Block 2 is clipped to 1×2×2 cells by the box: half full.
+        world.set_voxel(4, 0, 0, voxel(0, 200, 0));
+        world.set_voxel(4, 1, 0, voxel(0, 200, 0));
+        let s = Selection::from_corners((0, 0, 0), (4, 1, 1));
+
+        let (down, changes) =
+            scale_selection_changes(&world, s, Scale::Down(2, Downsample::Majority));
+        assert_eq!(down, Selection::from_corners((0, 0, 0), (2, 0, 0)));
+        let mut majority = World::new();
+        for (x, y, z) in s.iter_cells() {
+            majority.set_voxel(x, y, z, world.get_voxel(x, y, z));
+        }
+        Command::set_voxels(changes).execute(&mut majority);
+        assert_eq!(majority.get_voxel(0, 0, 0), voxel(200, 0, 0));
+        assert!(majority.get_voxel(1, 0, 0).is_air());
+        assert_eq!(majority.get_voxel(2, 0, 0), voxel(0, 200, 0));
+        assert_eq!(majority.solid_voxel_count(), 2);
+
+        let (_, changes) = scale_selection_changes(&world, s, Scale::Down(2, Downsample::Average));
+        Command::set_voxels(changes).execute(&mut world);
+        assert_eq!(world.get_voxel(0, 0, 0), voxel(150, 0, 50));
+    }
+
     #[test]
     fn rotate_then_undo_round_trips() {
         // The acid test: apply rotate, then undo, world must be byte-
@@ -0,0 +1,163 @@
+//! Work plane ("slice") editing: every write is restricted to one
+//! axis-aligned layer of cells, `axis == coord`, the way pixel-art
+//! voxel models are drawn one layer at a time.
+//!
+//! While the plane is on, the brush and shape tools don't aim at the
+//! first voxel the cursor ray meets — that would usually be a cell in
+//! front of the slice, which the clip then throws away. They aim at
+//! where the ray crosses the slice instead ([`WorkPlane::hit`]), so
+//! Place draws into empty air on the layer and Remove / Paint / Fill
+//! reach cells that other layers hide.
+
+use serde::{Deserialize, Serialize};
+
+use super::{Axis, Ray, RaycastHit, VoxelChange};
+
+/// One axis-aligned layer of cells: those whose `axis` coordinate is
+/// `coord`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WorkPlane {
+    pub axis: Axis,
+    pub coord: i32,
+}
+
+impl Default for WorkPlane {
+    /// The ground layer — where a new model usually starts.
+    fn default() -> Self {
+        Self {
+            axis: Axis::Y,
+            coord: 0,
+        }
+    }
+}
+
+impl WorkPlane {
+    /// `axis` as an index into `(x, y, z)`.
+    pub fn axis_index(&self) -> usize {
+        match self.axis {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+
+    /// Whether `pos` lies on the layer.
+    pub fn contains(&self, pos: (i32, i32, i32)) -> bool {
+        [pos.0, pos.1, pos.2][self.axis_index()] == self.coord
+    }
+
+    /// `changes` minus any that would write off the layer.
+    pub fn clip(&self, changes: Vec<VoxelChange>) -> Vec<VoxelChange> {
+        changes
+            .into_iter()
+            .filter(|c| self.contains(c.pos))
+            .collect()
+    }
+
+    /// The same plane moved `delta` layers along its axis.
+    pub fn stepped(self, delta: i32) -> Self {
+        Self {
+            coord: self.coord.saturating_add(delta),
+            ..self
+        }
+    }
+
+    /// Where `ray` crosses the middle of the layer, as a hit on the
+    /// cell there: `voxel_pos` and `adjacent_pos` are both that cell,
+    /// so Place and Remove / Paint target the same spot, and `normal`
+    /// faces back toward the ray. `None` when the ray runs parallel to
+    /// the layer, or crosses it behind the origin or past `max_dist`.
+    pub fn hit(&self, ray: &Ray, max_dist: f32) -> Option<RaycastHit> {
+        let axis = self.axis_index();
+        let dir = ray.direction.to_array()[axis];
+        if dir.abs() < 1e-6 {
+            return None;
+        }
+        let t = (self.coord as f32 + 0.5 - ray.origin.to_array()[axis]) / dir;
+        if t <= 0.0 || t > max_dist {
+            return None;
+        }
+        let p = ray.at(t).to_array();
+        let mut cell = [
+            p[0].floor() as i32,
+            p[1].floor() as i32,
+            p[2].floor() as i32,
+        ];
+        cell[axis] = self.coord;
+        let mut normal = [0; 3];
+        normal[axis] = if dir > 0.0 { -1 } else { 1 };
+        Some(RaycastHit {
+            normal: (normal[0], normal[1], normal[2]),
+            distance: t,
+            ..self.cell_hit((cell[0], cell[1], cell[2]))
+        })
+    }
+
+    /// A hit on `cell` (assumed to lie on the layer) from the layer's
+    /// positive side, for writing to a cell picked some other way than
+    /// by ray — the 2D slice editor's grid.
+    pub fn cell_hit(&self, cell: (i32, i32, i32)) -> RaycastHit {
+        let mut normal = [0; 3];
+        normal[self.axis_index()] = 1;
+        RaycastHit {
+            voxel_pos: cell,
+            adjacent_pos: cell,
+            normal: (normal[0], normal[1], normal[2]),
+            distance: 0.0,
+            virtual_ground: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Voxel;
+    use glam::Vec3;
+
+    #[test]
+    fn clip_keeps_only_the_layer() {
+        let plane = WorkPlane {
+            axis: Axis::Z,
+            coord: -2,
+        };
+        let change = |pos| VoxelChange {
+            pos,
+            old_voxel: Voxel::AIR,
+            new_voxel: Voxel::from_rgb(1, 2, 3),
+        };
+        let kept = plane.clip(vec![
+            change((5, 5, -2)),
+            change((5, 5, -1)),
+            change((0, 0, 2)),
+        ]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].pos, (5, 5, -2));
+        assert!(plane.stepped(1).contains((9, 9, -1)));
+        assert!(!plane.stepped(1).contains((9, 9, -2)));
+    }
+
+    #[test]
+    fn hit_lands_on_the_layer_facing_the_ray() {
+        let plane = WorkPlane {
+            axis: Axis::Y,
+            coord: 3,
+        };
+        // Looking straight down from above.
+        let down = Ray::new(Vec3::new(1.5, 10.0, -0.5), Vec3::NEG_Y);
+        let hit = plane.hit(&down, 100.0).unwrap();
+        assert_eq!(hit.voxel_pos, (1, 3, -1));
+        assert_eq!(hit.adjacent_pos, hit.voxel_pos);
+        assert_eq!(hit.normal, (0, 1, 0));
+        assert!((hit.distance - 6.5).abs() < 1e-4);
+        // From below, the normal flips.
+        let up = Ray::new(Vec3::new(1.5, -10.0, -0.5), Vec3::Y);
+        assert_eq!(plane.hit(&up, 100.0).unwrap().normal, (0, -1, 0));
+        // Parallel, behind, and out of reach all miss.
+        assert!(plane.hit(&Ray::new(Vec3::ZERO, Vec3::X), 100.0).is_none());
+        assert!(plane
+            .hit(&Ray::new(Vec3::ZERO, Vec3::NEG_Y), 100.0)
+            .is_none());
+        assert!(plane.hit(&down, 5.0).is_none());
+    }
+}
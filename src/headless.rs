@@ -0,0 +1,471 @@
+//! Headless editing: build and export voxel models from code.
+//!
+//! [`Session`] is the editor without the editor — a [`World`], its
+//! undo history and the brush settings the tools read — for using
+//! Voxelith as a library in an asset pipeline. Nothing here touches
+//! winit, wgpu or egui, so it runs on a build server with no display
+//! and no GPU, like the `voxelith bake` command (see [`crate::bake`]).
+//!
+//! Edits go through the same [`Command`]s and [`CommandHistory`] as
+//! interactive editing, so brushes, fills, shapes and generators behave
+//! exactly as they do in the window, [`Session::undo`] works, and a
+//! saved project opens in the editor with nothing lost. The edit
+//! methods return `&mut Session` so a model can be written as one
+//! chain:
+//!
+//! ```no_run
+//! use voxelith::editor::Tool;
+//! use voxelith::headless::Session;
+//! use voxelith::procgen::LSystemTree;
+//! use voxelith::Voxel;
+//!
+//! # fn main() -> Result<(), voxelith::headless::HeadlessError> {
+//! let mut session = Session::new();
+//! session
+//!     .set_color(Voxel::from_rgb(90, 140, 60))
+//!     .shape(Tool::Box, (-8, 0, -8), (7, 0, 7))
+//!     .generate(&LSystemTree::default())?;
+//! session.export_glb("tree.glb".as_ref())?;
+//! session.save("tree.vxlt".as_ref())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A session edits one world. Open a layered project with
+//! [`Session::open`] and its visible layers are flattened, the same as
+//! the bake does.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::core::{MaterialTable, Voxel, World};
+use crate::editor::{
+    box_voxels, cylinder_voxels, flood_fill_multi, line_voxels, sphere_voxels, BrushSettings,
+    BrushTool, Command, CommandHistory, EditorTool, FillSettings, PaintSettings, RaycastHit,
+    SculptSettings, SymmetryAxes, Tool, ToolContext, VoxelChange, UNDO_DEPTH,
+};
+use crate::io::{
+    self, EditorState, GlbError, GlbStats, ObjError, ObjStats, ProjectError, VoxAxes, VoxError,
+};
+use crate::mesh::{ChunkMesh, Mesher};
+use crate::plugin::{self, PluginError};
+use crate::procgen::{GenError, VoxelGenerator};
+
+/// Most cells one [`Session::fill`] writes, the same cap the editor's
+/// Fill tool uses (there it asks before going past it).
+pub use crate::editor::MAX_FILL_VOXELS;
+
+/// Errors from a headless session.
+#[derive(Debug, Error)]
+pub enum HeadlessError {
+    #[error("project: {0}")]
+    Project(#[from] ProjectError),
+    #[error("generation: {0}")]
+    Generation(#[from] GenError),
+    #[error("glTF export: {0}")]
+    Glb(#[from] GlbError),
+    #[error("OBJ export: {0}")]
+    Obj(#[from] ObjError),
+    #[error("VOX export: {0}")]
+    Vox(#[from] VoxError),
+    #[error("plugin: {0}")]
+    Plugin(#[from] PluginError),
+    /// No plugin of the requested kind has this id.
+    #[error("no plugin registered with id \"{0}\"")]
+    UnknownPlugin(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// One world being edited from code. See the module docs.
+pub struct Session {
+    world: World,
+    materials: MaterialTable,
+    history: CommandHistory,
+    brush_color: Voxel,
+    brush_size: u8,
+    symmetry: SymmetryAxes,
+    paint: PaintSettings,
+    brush: BrushSettings,
+    sculpt: SculptSettings,
+    fill: FillSettings,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    /// An empty, unbounded world with the editor's default brush.
+    pub fn new() -> Self {
+        Self::from_world(World::new())
+    }
+
+    /// Edit an existing world.
+    pub fn from_world(world: World) -> Self {
+        Self {
+            world,
+            materials: MaterialTable::new(),
+            history: CommandHistory::new(UNDO_DEPTH),
+            brush_color: Voxel::from_rgb(200, 200, 200),
+            brush_size: 1,
+            symmetry: SymmetryAxes::default(),
+            paint: PaintSettings::default(),
+            brush: BrushSettings::default(),
+            sculpt: SculptSettings::default(),
+            fill: FillSettings::default(),
+        }
+    }
+
+    /// Load a `.vxlt` project, flattening its visible layers, with its
+    /// material table.
+    pub fn open(path: &Path) -> Result<Self, HeadlessError> {
+        let (world, state) = io::load_world_with_state(path)?;
+        let mut session = Self::from_world(world);
+        session.materials = state.materials;
+        Ok(session)
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Materials the world's voxels refer to, saved with the project
+    /// and written to `.vox` as `MATL`.
+    pub fn materials(&self) -> &MaterialTable {
+        &self.materials
+    }
+
+    pub fn set_materials(&mut self, materials: MaterialTable) -> &mut Self {
+        self.materials = materials;
+        self
+    }
+
+    /// Give up the session and keep the world.
+    pub fn into_world(self) -> World {
+        self.world
+    }
+
+    pub fn history(&self) -> &CommandHistory {
+        &self.history
+    }
+
+    /// Color written by brushes, fills and shapes.
+    pub fn set_color(&mut self, color: Voxel) -> &mut Self {
+        self.brush_color = color;
+        self
+    }
+
+    /// Brush radius in cells, as on the Tools panel (1 = one cell).
+    pub fn set_brush_size(&mut self, size: u8) -> &mut Self {
+        self.brush_size = size.max(1);
+        self
+    }
+
+    /// Mirror brushes, fills and shapes across the origin planes.
+    pub fn set_symmetry(&mut self, symmetry: SymmetryAxes) -> &mut Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    /// Per-cell color pattern for Place and Paint strokes.
+    pub fn set_paint(&mut self, paint: PaintSettings) -> &mut Self {
+        self.paint = paint;
+        self
+    }
+
+    /// Shape of the Place / Remove / Paint brush, and the stamp the
+    /// Stamp shape uses (see [`crate::editor::BrushStamp::from_world`]).
+    pub fn set_brush(&mut self, brush: BrushSettings) -> &mut Self {
+        self.brush = brush;
+        self
+    }
+
+    /// What [`Tool::Sculpt`] does, and how strongly.
+    pub fn set_sculpt(&mut self, sculpt: SculptSettings) -> &mut Self {
+        self.sculpt = sculpt;
+        self
+    }
+
+    /// Where [`Self::fill`] spreads and across which neighbors.
+    pub fn set_fill(&mut self, fill: FillSettings) -> &mut Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Run `command` as one undo entry.
+    pub fn execute(&mut self, command: Command) -> &mut Self {
+        self.history.execute(command, &mut self.world);
+        self
+    }
+
+    /// Write one cell. `Voxel::AIR` clears it.
+    pub fn set_voxel(&mut self, pos: (i32, i32, i32), voxel: Voxel) -> &mut Self {
+        let old_voxel = self.world.get_voxel(pos.0, pos.1, pos.2);
+        if old_voxel != voxel {
+            let change = VoxelChange {
+                pos,
+                old_voxel,
+                new_voxel: voxel,
+            };
+            self.execute(Command::set_voxels(vec![change]).named("Set Voxel"));
+        }
+        self
+    }
+
+    /// Apply a brush tool (Place, Remove, Paint or Sculpt) at `hit`, as
+    /// if the user clicked there. Other tools are ignored; use
+    /// [`Self::fill`] and [`Self::shape`] for those.
+    pub fn apply_tool(&mut self, tool: Tool, hit: &RaycastHit) -> &mut Self {
+        let mut ctx = ToolContext {
+            world: &mut self.world,
+            history: &mut self.history,
+            brush_color: self.brush_color,
+            brush_size: self.brush_size,
+            symmetry: self.symmetry,
+            paint: self.paint,
+            brush: self.brush.clone(),
+            sculpt: self.sculpt,
+            work_plane: None,
+        };
+        BrushTool::new(tool).apply(&mut ctx, hit);
+        self.sculpt.seed = self.sculpt.seed.wrapping_add(1);
+        // Each call is its own undo entry, not part of a stroke.
+        self.history.end_stroke();
+        self
+    }
+
+    /// [`Self::apply_tool`] on the top face of the cell at `pos`: Place
+    /// builds on top of it, Remove and Paint act on it.
+    pub fn brush(&mut self, tool: Tool, pos: (i32, i32, i32)) -> &mut Self {
+        let hit = RaycastHit {
+            voxel_pos: pos,
+            adjacent_pos: (pos.0, pos.1 + 1, pos.2),
+            normal: (0, 1, 0),
+            distance: 0.0,
+            virtual_ground: false,
+        };
+        self.apply_tool(tool, &hit)
+    }
+
+    /// Flood-fill the connected region of same-colored solid cells
+    /// around `pos` with the brush color, as if clicked on its top
+    /// face (which only matters for the Slice and Surface fill
+    /// regions). Does nothing on air.
+    pub fn fill(&mut self, pos: (i32, i32, i32)) -> &mut Self {
+        if self.world.get_voxel(pos.0, pos.1, pos.2).is_air() {
+            return self;
+        }
+        let seeds: Vec<_> = self
+            .symmetry
+            .mirror_positions(pos)
+            .into_iter()
+            .zip(self.symmetry.mirror_normals((0, 1, 0)))
+            .collect();
+        flood_fill_multi(
+            &mut self.world,
+            &mut self.history,
+            &seeds,
+            self.brush_color,
+            MAX_FILL_VOXELS,
+            None,
+            self.fill,
+        );
+        self
+    }
+
+    /// Draw a Line, Box, Sphere or Cylinder between corners `a` and `b`
+    /// in the brush color, like the shape tools. Other tools are
+    /// ignored.
+    pub fn shape(&mut self, tool: Tool, a: (i32, i32, i32), b: (i32, i32, i32)) -> &mut Self {
+        let cells = match tool {
+            Tool::Line => line_voxels(a, b),
+            Tool::Box => box_voxels(a, b),
+            Tool::Sphere => sphere_voxels(a, b),
+            Tool::Cylinder => cylinder_voxels(a, b),
+            _ => return self,
+        };
+        let cells: HashSet<(i32, i32, i32)> = cells
+            .into_iter()
+            .flat_map(|cell| self.symmetry.mirror_positions(cell))
+            .collect();
+        let color = self.brush_color;
+        let changes = self.changes_to(cells.into_iter().map(|pos| (pos, color)));
+        if !changes.is_empty() {
+            self.execute(Command::set_voxels(changes).named(tool.name()));
+        }
+        self
+    }
+
+    /// Run `generator` and apply its output as one undo entry named
+    /// after it. Cells the output doesn't mention are left alone.
+    pub fn generate(&mut self, generator: &dyn VoxelGenerator) -> Result<&mut Self, HeadlessError> {
+        let patch = generator.generate()?;
+        let changes = self.changes_to(patch.voxels.iter().copied());
+        if !changes.is_empty() {
+            let label = generator.metadata().name;
+            self.execute(Command::set_voxels(changes).named(label));
+        }
+        Ok(self)
+    }
+
+    /// [`Self::generate`] with a generator plugin from the process-wide
+    /// registry (see [`crate::plugin`]).
+    pub fn generate_plugin(&mut self, id: &str, seed: u64) -> Result<&mut Self, HeadlessError> {
+        let plugin = plugin::registry()
+            .generator(id)
+            .ok_or_else(|| HeadlessError::UnknownPlugin(id.to_string()))?;
+        self.generate(plugin.create(seed).as_ref())
+    }
+
+    /// Undo the last edit. Returns false when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        self.history.undo(&mut self.world)
+    }
+
+    /// Redo the last undone edit. Returns false when there is nothing
+    /// to redo.
+    pub fn redo(&mut self) -> bool {
+        self.history.redo(&mut self.world)
+    }
+
+    /// Mesh every chunk with `mesher` (e.g. [`crate::mesh::GreedyMesher`]
+    /// or [`crate::mesh::MeshSettings`]), skipping empty ones. Vertex
+    /// positions are in world units, so the meshes can be concatenated
+    /// as is.
+    pub fn meshes(&self, mesher: &dyn Mesher) -> Vec<ChunkMesh> {
+        self.world
+            .chunk_positions()
+            .map(|&pos| mesher.generate(&self.world, pos))
+            .filter(|mesh| !mesh.indices.is_empty())
+            .collect()
+    }
+
+    /// Save as a `.vxlt` project, with the session's materials.
+    pub fn save(&self, path: &Path) -> Result<(), HeadlessError> {
+        let state = EditorState {
+            materials: self.materials.clone(),
+            ..EditorState::default()
+        };
+        io::save_world_with_state(&self.world, state, path)?;
+        Ok(())
+    }
+
+    /// Export a glTF binary, greedy-meshed, with identity placement.
+    pub fn export_glb(&self, path: &Path) -> Result<GlbStats, HeadlessError> {
+        Ok(io::export_glb(&self.world, &[], path)?)
+    }
+
+    /// Export a Wavefront OBJ, greedy-meshed.
+    pub fn export_obj(&self, path: &Path) -> Result<ObjStats, HeadlessError> {
+        Ok(io::export_obj(&self.world, path)?)
+    }
+
+    /// Export a MagicaVoxel file with the session's materials, mapping
+    /// axes by `axes` (the editor defaults to [`VoxAxes::ZUp`]).
+    /// Returns how many colors had to be quantized into its 255-color
+    /// palette.
+    pub fn export_vox(&self, path: &Path, axes: VoxAxes) -> Result<u32, HeadlessError> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let stats = io::export_vox_with_chunks(&self.world, &self.materials, &[], axes, &mut file)?;
+        Ok(stats.palette_overflow)
+    }
+
+    /// Export through an exporter plugin from the process-wide registry.
+    pub fn export_plugin(&self, id: &str, path: &Path) -> Result<(), HeadlessError> {
+        let plugin = plugin::registry()
+            .exporter(id)
+            .ok_or_else(|| HeadlessError::UnknownPlugin(id.to_string()))?;
+        plugin.export(&self.world, path)?;
+        Ok(())
+    }
+
+    /// `VoxelChange`s writing `voxels` over the current world, identity
+    /// writes dropped.
+    fn changes_to(
+        &self,
+        voxels: impl Iterator<Item = ((i32, i32, i32), Voxel)>,
+    ) -> Vec<VoxelChange> {
+        voxels
+            .filter_map(|(pos, new_voxel)| {
+                let old_voxel = self.world.get_voxel(pos.0, pos.1, pos.2);
+                (old_voxel != new_voxel).then_some(VoxelChange {
+                    pos,
+                    old_voxel,
+                    new_voxel,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MaterialDef;
+    use crate::mesh::GreedyMesher;
+    use crate::procgen::LSystemTree;
+
+    #[test]
+    fn chained_edits_are_undoable_steps() {
+        let red = Voxel::from_rgb(255, 0, 0);
+        let green = Voxel::from_rgb(0, 255, 0);
+        let blue = Voxel::from_rgb(0, 0, 255);
+        let mut session = Session::new();
+        session
+            .set_color(red)
+            .shape(Tool::Box, (0, 0, 0), (3, 0, 3))
+            .set_color(blue)
+            .brush(Tool::Place, (1, 0, 1))
+            .set_color(green)
+            .fill((0, 0, 0));
+
+        // The fill stops at the differently colored cell on top.
+        let world = session.world();
+        assert_eq!(world.solid_voxel_count(), 17);
+        assert_eq!(world.get_voxel(3, 0, 3), green);
+        assert_eq!(world.get_voxel(1, 1, 1), blue);
+        assert_eq!(session.history().undo_label().as_deref(), Some("Fill"));
+        assert_eq!(session.history().undo_count(), 3);
+
+        assert!(session.undo());
+        assert_eq!(session.world().get_voxel(3, 0, 3), red);
+        assert!(session.undo() && session.undo());
+        assert_eq!(session.world().solid_voxel_count(), 0);
+        assert!(!session.undo());
+        assert!(session.redo());
+        assert_eq!(session.world().solid_voxel_count(), 16);
+    }
+
+    #[test]
+    fn generate_meshes_and_round_trips_through_a_project() {
+        let mut session = Session::new();
+        session.generate(&LSystemTree::default()).unwrap();
+        let solid = session.world().solid_voxel_count();
+        assert!(solid > 0);
+        assert_eq!(session.history().undo_count(), 1);
+        assert!(!session.meshes(&GreedyMesher::new()).is_empty());
+
+        let path =
+            std::env::temp_dir().join(format!("voxelith_headless_{}.vxlt", std::process::id()));
+        let mut materials = MaterialTable::new();
+        materials.add(MaterialDef {
+            metallic: 1.0,
+            ..MaterialDef::new("Gold")
+        });
+        session.set_materials(materials);
+        session.save(&path).unwrap();
+        let reopened = Session::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(reopened.world().solid_voxel_count(), solid);
+        assert_eq!(reopened.materials(), session.materials());
+        assert!(matches!(
+            session.export_plugin("missing", &path),
+            Err(HeadlessError::UnknownPlugin(id)) if id == "missing"
+        ));
+    }
+}
@@ -0,0 +1,333 @@
+//! Options shared by the mesh exporters (OBJ and GLB).
+//!
+//! [`ExportOptions`] is what the Export dialog edits: which surface to
+//! mesh, where to put the origin, which way is up, the unit scale, the
+//! vertex-color mode and whether chunks merge into one mesh. Both
+//! `export_obj_with_options` and `export_glb_with_options` mesh the
+//! world through [`mesh_parts`] so the two formats agree on geometry;
+//! they differ only in how placement is stored — GLB keeps it in a root
+//! node (lossless), OBJ has no nodes and bakes it into the vertices.
+//!
+//! With [`ColorStorage::Atlas`] the colors move from the vertices into a
+//! small texture: [`bake_atlas`] gives every face (triangle) one texel,
+//! shared between faces of the same color, and points the face's UVs at
+//! that texel's center.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use ::image::{ImageFormat, RgbaImage};
+
+use crate::core::World;
+use crate::mesh::{
+    mesh_chunk_by_material, mesh_world_smoothed, optimize, smooth_normals_joint, ChunkMesh,
+    OptimizeSettings, Vertex,
+};
+
+use super::gltf::{rotate_vec_by_quat, union_bounds, Bounds};
+use super::{ExportTransform, GlbColors};
+
+/// Which surface the exporter meshes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeshSurface {
+    /// Greedy-meshed voxel faces, exactly as the editor draws them.
+    #[default]
+    Blocky,
+    /// Marching Cubes over the raw density — rounded voxel edges, thin
+    /// features kept.
+    Smooth,
+    /// Marching Cubes after a 3×3×3 density blur — clay-like blobs;
+    /// thin features may dissolve.
+    Clay,
+}
+
+impl MeshSurface {
+    pub const ALL: [MeshSurface; 3] = [MeshSurface::Blocky, MeshSurface::Smooth, MeshSurface::Clay];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MeshSurface::Blocky => "Blocky",
+            MeshSurface::Smooth => "Smoothed, light",
+            MeshSurface::Clay => "Smoothed, heavy",
+        }
+    }
+}
+
+/// Where exported colors live.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorStorage {
+    /// Per-vertex colors (`COLOR_0` / OBJ `v x y z r g b`).
+    #[default]
+    Vertex,
+    /// A texture atlas with one texel per face color and per-face UVs,
+    /// for engines that ignore vertex colors. Per-vertex AO can't live
+    /// in a one-texel face, so only the face shading carries over.
+    Atlas,
+}
+
+impl ColorStorage {
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorStorage::Vertex => "Vertex colors",
+            ColorStorage::Atlas => "Texture atlas",
+        }
+    }
+}
+
+/// Per-export settings for the OBJ / GLB exporters. The default
+/// (blocky, identity transform, shaded colors, merged) matches the
+/// plain `export_glb`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportOptions {
+    pub surface: MeshSurface,
+    /// Pivot, up-axis and unit scale.
+    pub transform: ExportTransform,
+    /// Baked shading + AO, or raw voxel colors (see [`GlbColors`]).
+    pub colors: GlbColors,
+    /// Vertex colors or a baked texture atlas.
+    pub color_storage: ColorStorage,
+    /// One mesh for the whole model, or one per chunk
+    /// (`chunk_x_y_z`). Smoothed surfaces are always a single mesh.
+    pub merge_chunks: bool,
+    /// Replace the mesher's normals (hard faces, or Marching Cubes'
+    /// density gradient) with area-weighted averages across the whole
+    /// model (see [`crate::mesh::smooth_normals`]).
+    pub smooth_normals: bool,
+    /// Weld vertices, drop degenerate triangles and cache-order each
+    /// mesh (see [`crate::mesh::optimize`]).
+    pub optimize: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            surface: MeshSurface::Blocky,
+            transform: ExportTransform::default(),
+            colors: GlbColors::Shaded,
+            color_storage: ColorStorage::Vertex,
+            merge_chunks: true,
+            smooth_normals: false,
+            optimize: true,
+        }
+    }
+}
+
+/// One named output mesh: its geometry split by material group id
+/// (bit0 emissive, bit1 metallic), empty groups dropped.
+pub(super) struct MeshPart {
+    pub name: String,
+    pub groups: Vec<(u8, ChunkMesh)>,
+    /// Atlas UV per vertex, parallel to each group's vertices; empty
+    /// until [`bake_atlas`] runs.
+    pub uvs: Vec<Vec<[f32; 2]>>,
+}
+
+/// Mesh `world` per `options` into the parts the exporters write, plus
+/// how many chunks had geometry (1 for a non-empty smoothed surface).
+/// Per-chunk parts come out in chunk-coordinate order so repeated
+/// exports are identical.
+pub(super) fn mesh_parts(world: &World, options: &ExportOptions) -> (Vec<MeshPart>, usize) {
+    let (mut parts, chunk_count) = mesh_raw_parts(world, options);
+    if options.smooth_normals {
+        let mut meshes: Vec<&mut ChunkMesh> = parts
+            .iter_mut()
+            .flat_map(|p| p.groups.iter_mut().map(|(_, mesh)| mesh))
+            .collect();
+        smooth_normals_joint(&mut meshes);
+    }
+    if options.optimize {
+        for (_, mesh) in parts.iter_mut().flat_map(|p| &mut p.groups) {
+            optimize(mesh, OptimizeSettings::default());
+        }
+    }
+    (parts, chunk_count)
+}
+
+/// [`mesh_parts`] before optimization.
+fn mesh_raw_parts(world: &World, options: &ExportOptions) -> (Vec<MeshPart>, usize) {
+    if options.surface != MeshSurface::Blocky {
+        let mesh = mesh_world_smoothed(world, options.surface == MeshSurface::Clay);
+        if mesh.is_empty() {
+            return (Vec::new(), 0);
+        }
+        // MC output carries no material flags — a single plain group.
+        let part = MeshPart {
+            name: "Voxelith".to_string(),
+            groups: vec![(0, mesh)],
+            uvs: Vec::new(),
+        };
+        return (vec![part], 1);
+    }
+
+    let shaded = options.colors == GlbColors::Shaded;
+    let mut chunk_positions: Vec<_> = world.chunks().map(|(pos, _)| *pos).collect();
+    chunk_positions.sort_by_key(|p| (p.x, p.y, p.z));
+
+    let mut parts = Vec::new();
+    let mut merged: Vec<(u8, ChunkMesh)> = Vec::new();
+    let mut chunk_count = 0usize;
+    for pos in chunk_positions {
+        let per_material = mesh_chunk_by_material(world, pos, shaded);
+        if per_material.is_empty() {
+            continue;
+        }
+        chunk_count += 1;
+        if !options.merge_chunks {
+            parts.push(MeshPart {
+                name: format!("chunk_{}_{}_{}", pos.x, pos.y, pos.z),
+                groups: per_material,
+                uvs: Vec::new(),
+            });
+            continue;
+        }
+        for (gid, mesh) in per_material {
+            let slot = match merged.iter().position(|(g, _)| *g == gid) {
+                Some(slot) => slot,
+                None => {
+                    merged.push((gid, ChunkMesh::new(pos)));
+                    merged.len() - 1
+                }
+            };
+            let target = &mut merged[slot].1;
+            let base = target.vertices.len() as u32;
+            target.vertices.extend_from_slice(&mesh.vertices);
+            target.indices.extend(mesh.indices.iter().map(|&i| base + i));
+        }
+    }
+    if options.merge_chunks && !merged.is_empty() {
+        merged.sort_by_key(|(gid, _)| *gid);
+        parts.push(MeshPart {
+            name: "Voxelith".to_string(),
+            groups: merged,
+            uvs: Vec::new(),
+        });
+    }
+    (parts, chunk_count)
+}
+
+/// Apply `transform` to every vertex of `parts` in place (positions get
+/// scale → rotation → pivot offset, normals the rotation only), for
+/// formats with no node hierarchy to carry it. The pivot is measured
+/// over all parts together, so a per-chunk export still moves as one
+/// model.
+pub(super) fn bake_transform(parts: &mut [MeshPart], transform: ExportTransform) {
+    if transform.is_identity() {
+        return;
+    }
+    let mut bounds: Option<Bounds> = None;
+    for (_, mesh) in parts.iter().flat_map(|p| &p.groups) {
+        for v in &mesh.vertices {
+            bounds = union_bounds(bounds, Some((v.position, v.position)));
+        }
+    }
+    let (translation, rotation, scale) = transform.placement(bounds);
+    for (_, mesh) in parts.iter_mut().flat_map(|p| &mut p.groups) {
+        for v in &mut mesh.vertices {
+            let scaled = v.position.map(|c| c * scale);
+            let rotated = rotate_vec_by_quat(scaled, rotation);
+            v.position = [
+                rotated[0] + translation[0],
+                rotated[1] + translation[1],
+                rotated[2] + translation[2],
+            ];
+            v.normal = rotate_vec_by_quat(v.normal, rotation);
+        }
+    }
+}
+
+/// Baked color texture: `width × height` RGBA8 texels, row-major from
+/// the top-left, with unused trailing texels transparent black.
+pub(super) struct Atlas {
+    pub width: u32,
+    pub height: u32,
+    pub texels: Vec<[u8; 4]>,
+}
+
+impl Atlas {
+    /// The atlas as a PNG file.
+    pub fn png_bytes(&self) -> Result<Vec<u8>, ::image::ImageError> {
+        let mut raw: Vec<u8> = self.texels.iter().flatten().copied().collect();
+        raw.resize((self.width * self.height * 4) as usize, 0);
+        let image = RgbaImage::from_raw(self.width, self.height, raw)
+            .expect("atlas buffer matches its dimensions");
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+        Ok(bytes)
+    }
+}
+
+/// Move `parts`' colors into an [`Atlas`]: each triangle gets the
+/// average of its corners' colors (face shading included when the parts
+/// were meshed with it, AO left out), faces of equal color share one texel, and every
+/// corner's UV lands on its face's texel center (glTF orientation, V
+/// down). A vertex shared by faces of different colors is split so each
+/// keeps its own UV. Greedy quads are a single color, so they stay
+/// welded and cost one texel per distinct color.
+pub(super) fn bake_atlas(parts: &mut [MeshPart]) -> Atlas {
+    let mut texel_of: HashMap<[u8; 4], u32> = HashMap::new();
+    let mut texels: Vec<[u8; 4]> = Vec::new();
+    // Texel index per new vertex, turned into UVs once the size is known.
+    let mut vertex_texels: Vec<Vec<Vec<u32>>> = Vec::with_capacity(parts.len());
+    for part in parts.iter_mut() {
+        let mut part_texels = Vec::with_capacity(part.groups.len());
+        for (_, mesh) in &mut part.groups {
+            let mut vertices: Vec<Vertex> = Vec::with_capacity(mesh.vertices.len());
+            let mut indices = Vec::with_capacity(mesh.indices.len());
+            let mut group_texels = Vec::with_capacity(mesh.vertices.len());
+            let mut remap: HashMap<(u32, u32), u32> = HashMap::new();
+            for tri in mesh.indices.chunks_exact(3) {
+                let mut sum = [0.0f32; 4];
+                for &i in tri {
+                    let c = mesh.vertices[i as usize].color;
+                    for (s, c) in sum.iter_mut().zip(c) {
+                        *s += c / 3.0;
+                    }
+                }
+                let key = sum.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                let texel = *texel_of.entry(key).or_insert_with(|| {
+                    texels.push(key);
+                    texels.len() as u32 - 1
+                });
+                for &i in tri {
+                    let index = *remap.entry((i, texel)).or_insert_with(|| {
+                        vertices.push(mesh.vertices[i as usize]);
+                        group_texels.push(texel);
+                        vertices.len() as u32 - 1
+                    });
+                    indices.push(index);
+                }
+            }
+            mesh.vertices = vertices;
+            mesh.indices = indices;
+            mesh.transparent_index_count = 0;
+            part_texels.push(group_texels);
+        }
+        vertex_texels.push(part_texels);
+    }
+
+    // Smallest near-square grid holding every texel.
+    let count = texels.len().max(1) as u32;
+    let width = (count as f64).sqrt().ceil() as u32;
+    let height = count.div_ceil(width);
+    for (part, part_texels) in parts.iter_mut().zip(vertex_texels) {
+        part.uvs = part_texels
+            .into_iter()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .map(|t| {
+                        [
+                            ((t % width) as f32 + 0.5) / width as f32,
+                            ((t / width) as f32 + 0.5) / height as f32,
+                        ]
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+    Atlas {
+        width,
+        height,
+        texels,
+    }
+}
@@ -8,7 +8,8 @@
 //! every model viewer that handles the standard.
 //!
 //! Each primitive emits POSITION (vec3 f32), NORMAL (vec3 f32),
-//! COLOR_0 (vec4 f32, with AO baked in), the custom `_TINTZONE`
+//! COLOR_0 (vec4 f32, with face shading + AO baked in unless
+//! [`GlbColors::Flat`] is requested), the custom `_TINTZONE`
 //! (scalar f32 faction zone), and TEXCOORD_0 (vec2 f32 — the same
 //! zone in `.x`, so Unity glTFast, which drops custom attributes,
 //! can still read it). All deinterleaved so JSON descriptors stay
@@ -36,6 +37,12 @@
 //!
 //! Both chunks must be 4-byte aligned per spec; JSON pads with
 //! ASCII space (0x20), BIN pads with zero.
+//!
+//! [`export_glb_animation`] writes a frame sequence into the same
+//! container: one mesh + node per frame and a `Flipbook` animation that
+//! switches them by node scale. [`export_glb_parts`] writes a rigged
+//! model the same way: one mesh + node per bone, nested by parent, with
+//! the pose in the node transforms.
 
 use std::fs::File;
 use std::io::{BufWriter, Write};
@@ -47,12 +54,16 @@ use thiserror::Error;
 use crate::core::World;
 use crate::mesh::{mesh_chunk_by_material, mesh_world_smoothed, Vertex};
 
+use super::export::{bake_atlas, mesh_parts, Atlas, ColorStorage, ExportOptions};
+
 #[derive(Debug, Error)]
 pub enum GlbError {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
     #[error("JSON serialization failed: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("texture encoding failed: {0}")]
+    Image(#[from] ::image::ImageError),
 }
 
 /// Stats reported back to the UI after a successful export.
@@ -62,6 +73,9 @@ pub struct GlbStats {
     pub triangle_count: usize,
     pub chunk_count: usize,
     pub byte_size: usize,
+    /// Width × height of the baked color atlas, for
+    /// [`ColorStorage::Atlas`] exports.
+    pub atlas_size: Option<[u32; 2]>,
 }
 
 /// Where to move the asset's local origin before export, so a consumer
@@ -119,6 +133,54 @@ impl ExportTransform {
             && self.up_axis == UpAxis::Y
             && (self.unit_scale - 1.0).abs() < 1e-9
     }
+
+    /// Resolve this transform against geometry `bounds` (mesh space, or
+    /// `None` for a geometry-free scene, where every pivot falls back to
+    /// the origin) into `(translation, rotation, scale)`: scale first,
+    /// then rotate, then translate, with the translation chosen so the
+    /// pivot lands at the origin.
+    pub(super) fn placement(&self, bounds: Option<Bounds>) -> ([f32; 3], [f32; 4], f32) {
+        let s = self.unit_scale;
+        let pivot = match (self.pivot, bounds) {
+            (Pivot::BaseCenter, Some((lo, hi))) => {
+                [(lo[0] + hi[0]) * 0.5, lo[1], (lo[2] + hi[2]) * 0.5]
+            }
+            (Pivot::Center, Some((lo, hi))) => [
+                (lo[0] + hi[0]) * 0.5,
+                (lo[1] + hi[1]) * 0.5,
+                (lo[2] + hi[2]) * 0.5,
+            ],
+            // `Origin`, or any pivot with no geometry to measure.
+            _ => [0.0, 0.0, 0.0],
+        };
+        let rotation = match self.up_axis {
+            UpAxis::Y => [0.0, 0.0, 0.0, 1.0],
+            // +90° about X maps model +Y onto world +Z, for Z-up engines.
+            UpAxis::Z => [
+                std::f32::consts::FRAC_1_SQRT_2,
+                0.0,
+                0.0,
+                std::f32::consts::FRAC_1_SQRT_2,
+            ],
+        };
+        // translation = -(R · (scale · pivot)) so the pivot lands at origin.
+        let scaled = [pivot[0] * s, pivot[1] * s, pivot[2] * s];
+        let rotated = rotate_vec_by_quat(scaled, rotation);
+        ([-rotated[0], -rotated[1], -rotated[2]], rotation, s)
+    }
+}
+
+/// What goes into COLOR_0. `Shaded` (the default, and what the editor
+/// shows) bakes the directional per-face shading and per-vertex AO into
+/// the color so the model looks right in a plain unlit viewer. `Flat`
+/// writes the raw voxel color — every face of a voxel gets the same
+/// value — for engines that do all lighting themselves and would
+/// otherwise double-darken sides and crevices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlbColors {
+    #[default]
+    Shaded,
+    Flat,
 }
 
 /// A named attachment point to emit as an empty glTF node (no mesh).
@@ -144,6 +206,13 @@ const COMPONENT_TYPE_UINT: u32 = 5125;
 const TARGET_ARRAY_BUFFER: u32 = 34962;
 const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
 const PRIMITIVE_MODE_TRIANGLES: u32 = 4;
+// Sampler settings for the color atlas: one texel per face color, so
+// never blend neighbours (§3.8.4).
+const FILTER_NEAREST: u32 = 9728;
+const WRAP_CLAMP_TO_EDGE: u32 = 33071;
+
+/// An axis-aligned box in mesh space, `(min, max)`.
+pub(super) type Bounds = ([f32; 3], [f32; 3]);
 
 /// One material group's combined geometry for GLB export.
 struct GroupBuffers {
@@ -151,6 +220,9 @@ struct GroupBuffers {
     group_id: u8,
     vertices: Vec<Vertex>,
     indices: Vec<u32>,
+    /// Texture-atlas UV per vertex ([`ColorStorage::Atlas`] exports);
+    /// empty for vertex-colored groups.
+    uvs: Vec<[f32; 2]>,
 }
 
 impl GroupBuffers {
@@ -159,6 +231,7 @@ impl GroupBuffers {
             group_id,
             vertices: Vec::new(),
             indices: Vec::new(),
+            uvs: Vec::new(),
         }
     }
 }
@@ -179,7 +252,10 @@ fn material_name(group_id: u8) -> &'static str {
 /// metallic voxels get `metallicFactor = 1` + a lower roughness; emissive
 /// voxels get a constant white `emissiveFactor` (core glTF can't express
 /// per-vertex emissive color — vertex color only multiplies base color).
-fn material_json(group_id: u8) -> serde_json::Value {
+///
+/// `textured` materials sample the color atlas (texture 0) through
+/// `TEXCOORD_1`; `TEXCOORD_0` stays the tint zone.
+fn material_json(group_id: u8, textured: bool) -> serde_json::Value {
     let metallic = group_id & 0b10 != 0;
     let emissive = group_id & 0b01 != 0;
     let mut m = json!({
@@ -189,6 +265,9 @@ fn material_json(group_id: u8) -> serde_json::Value {
             "roughnessFactor": if metallic { 0.4 } else { 1.0 },
         },
     });
+    if textured {
+        m["pbrMetallicRoughness"]["baseColorTexture"] = json!({ "index": 0, "texCoord": 1 });
+    }
     if emissive {
         m["emissiveFactor"] = json!([1.0, 1.0, 1.0]);
     }
@@ -212,6 +291,17 @@ pub fn export_glb(
     export_glb_with_transform(world, sockets, path, ExportTransform::default())
 }
 
+/// Like [`export_glb`] but writes COLOR_0 per `colors` (see
+/// [`GlbColors`]). `GlbColors::Shaded` is identical to [`export_glb`].
+pub fn export_glb_with_colors(
+    world: &World,
+    sockets: &[SocketNode],
+    path: &Path,
+    colors: GlbColors,
+) -> Result<GlbStats, GlbError> {
+    export_greedy_glb(world, sockets, path, ExportTransform::default(), colors)
+}
+
 /// Like [`export_glb`] but applies a deterministic placement
 /// [`ExportTransform`] (pivot / up-axis / uniform scale) as a single
 /// root node wrapping the mesh and socket nodes. The default transform
@@ -225,11 +315,31 @@ pub fn export_glb_with_transform(
     path: &Path,
     transform: ExportTransform,
 ) -> Result<GlbStats, GlbError> {
+    export_greedy_glb(world, sockets, path, transform, GlbColors::Shaded)
+}
+
+/// Shared body of the greedy (non-smoothed) exports.
+fn export_greedy_glb(
+    world: &World,
+    sockets: &[SocketNode],
+    path: &Path,
+    transform: ExportTransform,
+    colors: GlbColors,
+) -> Result<GlbStats, GlbError> {
+    let (groups, chunk_count) = greedy_groups(world, colors);
+    write_glb_groups(&groups, sockets, chunk_count, path, transform, colors)
+}
+
+/// Greedy-mesh every chunk of `world` into combined buffers per
+/// material group, dropping empty groups (the rest stay in id order).
+/// Also returns how many chunks had geometry.
+fn greedy_groups(world: &World, colors: GlbColors) -> (Vec<GroupBuffers>, usize) {
+    let shaded = colors == GlbColors::Shaded;
     // Accumulate combined vertex / index buffers per material group.
     let mut groups: Vec<GroupBuffers> = (0u8..4).map(GroupBuffers::new).collect();
     let mut chunk_count = 0usize;
     for (chunk_pos, _) in world.chunks() {
-        let per_material = mesh_chunk_by_material(world, *chunk_pos);
+        let per_material = mesh_chunk_by_material(world, *chunk_pos, shaded);
         if !per_material.is_empty() {
             chunk_count += 1;
         }
@@ -242,7 +352,224 @@ pub fn export_glb_with_transform(
     }
     // Drop empty groups; the rest become primitives in id order.
     groups.retain(|g| !g.vertices.is_empty());
-    write_glb_groups(&groups, sockets, chunk_count, path, transform)
+    (groups, chunk_count)
+}
+
+/// One frame of an [`export_glb_animation`] sequence.
+#[derive(Clone, Copy)]
+pub struct GlbFrame<'a> {
+    /// Node name, e.g. the timeline's "Frame 3".
+    pub name: &'a str,
+    pub world: &'a World,
+    /// How long the frame is shown, in milliseconds.
+    pub duration_ms: u32,
+}
+
+/// Export a flipbook as one binary glTF: every frame is greedy-meshed
+/// into its own mesh and node (children of a `Voxelith` root, in
+/// sequence order), and an animation named `Flipbook` switches between
+/// them.
+///
+/// Core glTF has no node visibility, so switching uses the scale
+/// convention engines already play back: a frame's node is scaled 1
+/// while it's shown and 0 otherwise, keyed with `STEP` interpolation at
+/// the frame boundaries (times in seconds, from the cumulative
+/// `duration_ms`). Nodes default to the first frame's pose — frame 0 at
+/// scale 1, the rest at 0 — so a static viewer shows frame 0. Each
+/// frame node also carries `extras.frame` / `extras.duration_ms` for
+/// importers that would rather swap meshes themselves. A single frame
+/// gets no animation. Materials are shared across frames.
+pub fn export_glb_animation(
+    frames: &[GlbFrame],
+    path: &Path,
+    colors: GlbColors,
+) -> Result<GlbStats, GlbError> {
+    let mut builder = GlbBuilder::default();
+    let mut meshes = Vec::new();
+    let mut nodes = vec![json!({ "name": "Voxelith", "children": [] })];
+    let mut chunk_count = 0;
+    for (i, frame) in frames.iter().enumerate() {
+        let (groups, chunks) = greedy_groups(frame.world, colors);
+        chunk_count += chunks;
+        let mut node = json!({
+            "name": frame.name,
+            "extras": { "frame": i, "duration_ms": frame.duration_ms.max(1) },
+        });
+        if i > 0 {
+            node["scale"] = json!([0.0, 0.0, 0.0]);
+        }
+        if !groups.is_empty() {
+            let (primitives, _) = builder.add_mesh(&groups, colors);
+            node["mesh"] = json!(meshes.len());
+            meshes.push(json!({ "name": frame.name, "primitives": primitives }));
+        }
+        nodes.push(node);
+    }
+    nodes[0]["children"] = json!((1..nodes.len()).collect::<Vec<_>>());
+
+    // Per frame node: a scale channel over its own keyframes — shown
+    // from its start, hidden from its end (and before its start when it
+    // isn't first). Times must strictly increase, hence the skipped
+    // leading key for frame 0.
+    let mut samplers = Vec::new();
+    let mut channels = Vec::new();
+    if frames.len() > 1 {
+        let mut start_ms = 0u64;
+        for (i, frame) in frames.iter().enumerate() {
+            let end_ms = start_ms + frame.duration_ms.max(1) as u64;
+            let mut keys = Vec::with_capacity(3);
+            if start_ms > 0 {
+                keys.push((0.0, 0.0));
+            }
+            keys.push((start_ms as f32 / 1000.0, 1.0));
+            keys.push((end_ms as f32 / 1000.0, 0.0));
+            start_ms = end_ms;
+
+            let times: Vec<f32> = keys.iter().map(|&(t, _)| t).collect();
+            let scales: Vec<f32> = keys.iter().flat_map(|&(_, s)| [s, s, s]).collect();
+            let input = builder.push(
+                bytemuck::cast_slice(&times),
+                None,
+                COMPONENT_TYPE_FLOAT,
+                times.len(),
+                "SCALAR",
+            );
+            // Animation inputs REQUIRE `min` / `max` (spec §3.11).
+            builder.accessors[input]["min"] = json!([times[0]]);
+            builder.accessors[input]["max"] = json!([times[times.len() - 1]]);
+            let output = builder.push(
+                bytemuck::cast_slice(&scales),
+                None,
+                COMPONENT_TYPE_FLOAT,
+                times.len(),
+                "VEC3",
+            );
+            channels.push(json!({
+                "sampler": samplers.len(),
+                "target": { "node": i + 1, "path": "scale" },
+            }));
+            samplers.push(json!({ "input": input, "output": output, "interpolation": "STEP" }));
+        }
+    }
+
+    let mut json_value = json!({
+        "asset": { "version": "2.0", "generator": "Voxelith" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": nodes,
+    });
+    if !meshes.is_empty() {
+        json_value["meshes"] = json!(meshes);
+    }
+    if !channels.is_empty() {
+        json_value["animations"] =
+            json!([{ "name": "Flipbook", "channels": channels, "samplers": samplers }]);
+    }
+
+    let (vertex_count, index_count) = (builder.vertex_count, builder.index_count);
+    let byte_size = builder.write(json_value, path)?;
+    Ok(GlbStats {
+        vertex_count,
+        triangle_count: index_count / 3,
+        chunk_count,
+        byte_size,
+        atlas_size: None,
+    })
+}
+
+/// One rigid part of an [`export_glb_parts`] hierarchy.
+#[derive(Clone, Copy)]
+pub struct GlbPart<'a> {
+    /// Node (and mesh) name, e.g. a bone's name.
+    pub name: &'a str,
+    /// The part's voxels, at their rest positions in model space.
+    pub world: &'a World,
+    /// Index of the parent part, `None` for a scene root.
+    pub parent: Option<usize>,
+    /// Node transform relative to the parent, glTF `[x, y, z]` and
+    /// quaternion `[x, y, z, w]`.
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+}
+
+/// Export separately meshed parts as a node hierarchy — the rigged
+/// export. Every part is greedy-meshed in model space into its own mesh
+/// and node; children hang off their `parent` part's node and the node
+/// transforms carry the pose, so an engine can re-pose the model by
+/// animating those nodes. Materials are shared across parts, empty
+/// parts still get a node (to keep the hierarchy), and `sockets` follow
+/// as extra scene roots as in [`export_glb`]. A `parent` that is out of
+/// range or not earlier in the list is treated as `None`, which keeps
+/// the hierarchy acyclic.
+pub fn export_glb_parts(
+    parts: &[GlbPart],
+    sockets: &[SocketNode],
+    path: &Path,
+    colors: GlbColors,
+) -> Result<GlbStats, GlbError> {
+    let mut builder = GlbBuilder::default();
+    let mut meshes = Vec::new();
+    let mut nodes: Vec<serde_json::Value> = Vec::new();
+    let mut scene_nodes = Vec::new();
+    let mut chunk_count = 0;
+    for (i, part) in parts.iter().enumerate() {
+        let (groups, chunks) = greedy_groups(part.world, colors);
+        chunk_count += chunks;
+        let mut node = json!({ "name": part.name });
+        if part.translation != [0.0; 3] {
+            node["translation"] = json!(part.translation);
+        }
+        if part.rotation != [0.0, 0.0, 0.0, 1.0] {
+            node["rotation"] = json!(part.rotation);
+        }
+        if !groups.is_empty() {
+            let (primitives, _) = builder.add_mesh(&groups, colors);
+            node["mesh"] = json!(meshes.len());
+            meshes.push(json!({ "name": part.name, "primitives": primitives }));
+        }
+        match part.parent.filter(|&p| p < i) {
+            Some(parent) => {
+                let children = nodes[parent]
+                    .as_object_mut()
+                    .expect("part nodes are objects")
+                    .entry("children")
+                    .or_insert_with(|| json!([]));
+                children.as_array_mut().expect("children is an array").push(json!(i));
+            }
+            None => scene_nodes.push(i),
+        }
+        nodes.push(node);
+    }
+    for sock in sockets {
+        scene_nodes.push(nodes.len());
+        nodes.push(json!({
+            "name": sock.name,
+            "translation": sock.translation,
+            "rotation": sock.rotation,
+        }));
+    }
+
+    let mut json_value = json!({
+        "asset": { "version": "2.0", "generator": "Voxelith" },
+        "scene": 0,
+        "scenes": [{ "nodes": scene_nodes }],
+    });
+    if !nodes.is_empty() {
+        json_value["nodes"] = json!(nodes);
+    }
+    if !meshes.is_empty() {
+        json_value["meshes"] = json!(meshes);
+    }
+
+    let (vertex_count, index_count) = (builder.vertex_count, builder.index_count);
+    let byte_size = builder.write(json_value, path)?;
+    Ok(GlbStats {
+        vertex_count,
+        triangle_count: index_count / 3,
+        chunk_count,
+        byte_size,
+        atlas_size: None,
+    })
 }
 
 /// Export the world as a glTF Binary with Marching-Cubes smoothing.
@@ -287,199 +614,375 @@ pub fn export_glb_smoothed_with_transform(
             group_id: 0,
             vertices: mesh.vertices,
             indices: mesh.indices,
+            uvs: Vec::new(),
         }]
     };
-    write_glb_groups(&groups, sockets, chunk_count, path, transform)
+    write_glb_groups(&groups, sockets, chunk_count, path, transform, GlbColors::Shaded)
 }
 
-/// Write one or more material groups to a binary glTF 2.0 file. Each
-/// group becomes a primitive (POSITION / NORMAL / COLOR_0 / _TINTZONE /
-/// TEXCOORD_0 / indices) plus a material; the BIN payload lays the groups
-/// out back to back. An empty
-/// `groups` slice produces a valid geometry-free glTF (no BIN chunk) —
-/// which `sockets` can still populate with empty nodes. `chunk_count` is
-/// passed through to the returned stats. Per-vertex AO is baked into the
-/// exported color (see `Vertex::baked_color`).
-fn write_glb_groups(
-    groups: &[GroupBuffers],
+/// Export per the Export dialog's [`ExportOptions`]: the chosen surface
+/// (blocky or smoothed), color mode, and either one merged mesh or one
+/// mesh + node per chunk (`chunk_x_y_z`). Placement goes in a root node
+/// exactly as in [`export_glb_with_transform`], so the default options
+/// produce the same scene as [`export_glb`].
+///
+/// With [`ColorStorage::Atlas`] the colors are baked into a PNG atlas
+/// embedded in the BIN chunk (nearest filtering, clamped), sampled
+/// through `TEXCOORD_1`; `COLOR_0` is left out so engines don't multiply
+/// the color in twice.
+pub fn export_glb_with_options(
+    world: &World,
     sockets: &[SocketNode],
-    chunk_count: usize,
     path: &Path,
-    transform: ExportTransform,
+    options: &ExportOptions,
 ) -> Result<GlbStats, GlbError> {
-    // Per-group byte sections within the BIN, plus POSITION bounds.
-    struct Section {
-        pos: (usize, usize),
-        normal: (usize, usize),
-        color: (usize, usize),
-        tintzone: (usize, usize),
-        texcoord: (usize, usize),
-        index: (usize, usize),
-        min: [f32; 3],
-        max: [f32; 3],
-    }
+    let (mut parts, chunk_count) = mesh_parts(world, options);
+    let atlas = match options.color_storage {
+        ColorStorage::Vertex => None,
+        ColorStorage::Atlas if parts.is_empty() => None,
+        ColorStorage::Atlas => Some(bake_atlas(&mut parts)),
+    };
+    let parts: Vec<(String, Vec<GroupBuffers>)> = parts
+        .into_iter()
+        .map(|part| {
+            let mut uvs = part.uvs.into_iter();
+            let groups = part
+                .groups
+                .into_iter()
+                .map(|(group_id, mesh)| GroupBuffers {
+                    group_id,
+                    vertices: mesh.vertices,
+                    indices: mesh.indices,
+                    uvs: uvs.next().unwrap_or_default(),
+                })
+                .collect();
+            (part.name, groups)
+        })
+        .collect();
+    let meshes: Vec<(String, &[GroupBuffers])> = parts
+        .iter()
+        .map(|(name, groups)| (name.clone(), groups.as_slice()))
+        .collect();
+    write_glb_meshes(
+        &meshes,
+        sockets,
+        chunk_count,
+        path,
+        options.transform,
+        options.colors,
+        atlas.as_ref(),
+    )
+}
 
-    let mut bin = Vec::<u8>::new();
-    let mut sections: Vec<Section> = Vec::with_capacity(groups.len());
-    let mut total_vertices = 0usize;
-    let mut total_indices = 0usize;
+/// Accumulates meshes into one BIN payload plus the accessor /
+/// bufferView / material tables describing it, so a file can carry one
+/// mesh (the plain exports) or one per animation frame
+/// ([`export_glb_animation`]). Accessor `i` and bufferView `i` are
+/// pushed together, so a single mesh's layout matches the per-group
+/// `[6i .. 6i+6)` numbering the docs describe.
+#[derive(Default)]
+struct GlbBuilder {
+    bin: Vec<u8>,
+    accessors: Vec<serde_json::Value>,
+    buffer_views: Vec<serde_json::Value>,
+    materials: Vec<serde_json::Value>,
+    /// `materials` index of each material group id, once emitted.
+    material_slots: [Option<usize>; 4],
+    /// Groups carry atlas UVs and materials sample texture 0 instead
+    /// of vertex colors.
+    textured: bool,
+    /// bufferView holding the atlas PNG, once added.
+    atlas_view: Option<usize>,
+    vertex_count: usize,
+    index_count: usize,
+}
 
-    for g in groups {
-        let pos_offset = bin.len();
-        for v in &g.vertices {
-            bin.extend_from_slice(bytemuck::bytes_of(&v.position));
+impl GlbBuilder {
+    /// Append `bytes` to the BIN (always 4-byte aligned: everything is
+    /// f32 / u32) as a new bufferView plus an accessor over it. Returns
+    /// the accessor index.
+    fn push(
+        &mut self,
+        bytes: &[u8],
+        target: Option<u32>,
+        component_type: u32,
+        count: usize,
+        kind: &str,
+    ) -> usize {
+        let offset = self.bin.len();
+        self.bin.extend_from_slice(bytes);
+        let mut view = json!({ "buffer": 0, "byteOffset": offset, "byteLength": bytes.len() });
+        if let Some(target) = target {
+            view["target"] = json!(target);
         }
-        let pos_len = bin.len() - pos_offset;
+        self.buffer_views.push(view);
+        self.accessors.push(json!({
+            "bufferView": self.buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": kind,
+        }));
+        self.accessors.len() - 1
+    }
 
-        let normal_offset = bin.len();
-        for v in &g.vertices {
-            bin.extend_from_slice(bytemuck::bytes_of(&v.normal));
+    /// Append the atlas PNG as a bufferView (no accessor; images are
+    /// referenced by view). Pads the BIN back to 4-byte alignment for
+    /// anything pushed after it.
+    fn add_atlas(&mut self, png: &[u8]) {
+        let offset = self.bin.len();
+        self.bin.extend_from_slice(png);
+        while !self.bin.len().is_multiple_of(4) {
+            self.bin.push(0);
         }
-        let normal_len = bin.len() - normal_offset;
+        self.buffer_views
+            .push(json!({ "buffer": 0, "byteOffset": offset, "byteLength": png.len() }));
+        self.atlas_view = Some(self.buffer_views.len() - 1);
+    }
+
+    /// [`Self::push`] for a per-vertex f32 attribute.
+    fn push_attribute(&mut self, bytes: &[u8], count: usize, kind: &str) -> usize {
+        self.push(bytes, Some(TARGET_ARRAY_BUFFER), COMPONENT_TYPE_FLOAT, count, kind)
+    }
 
-        let color_offset = bin.len();
-        for v in &g.vertices {
+    /// Add `groups` as the primitives of one glTF mesh (POSITION /
+    /// NORMAL / COLOR_0 / _TINTZONE / TEXCOORD_0 + indices each, one
+    /// shared material per group id). Returns the primitives and the
+    /// groups' combined POSITION bounds (`None` without geometry).
+    /// With `GlbColors::Shaded` per-vertex AO is baked into the color
+    /// (see `Vertex::baked_color`); `Flat` writes `Vertex::color`.
+    fn add_mesh(
+        &mut self,
+        groups: &[GroupBuffers],
+        colors: GlbColors,
+    ) -> (Vec<serde_json::Value>, Option<Bounds>) {
+        let mut primitives = Vec::with_capacity(groups.len());
+        let mut bounds: Option<Bounds> = None;
+        for g in groups {
+            let count = g.vertices.len();
+            let bytes: Vec<u8> = g
+                .vertices
+                .iter()
+                .flat_map(|v| bytemuck::bytes_of(&v.position).to_vec())
+                .collect();
+            let position = self.push_attribute(&bytes, count, "VEC3");
+            let bytes: Vec<u8> = g
+                .vertices
+                .iter()
+                .flat_map(|v| bytemuck::bytes_of(&v.normal).to_vec())
+                .collect();
+            let normal = self.push_attribute(&bytes, count, "VEC3");
             // Bake per-vertex AO into the exported color (see
             // `Vertex::baked_color`); MC-smoothed meshes carry ao = 1.0.
-            bin.extend_from_slice(bytemuck::bytes_of(&v.baked_color()));
-        }
-        let color_len = bin.len() - color_offset;
-
-        let tintzone_offset = bin.len();
-        for v in &g.vertices {
-            bin.extend_from_slice(bytemuck::bytes_of(&v.tint_zone));
-        }
-        let tintzone_len = bin.len() - tintzone_offset;
-
-        // Tint zone ALSO as TEXCOORD_0 = vec2(zone, 0). Unity glTFast drops
-        // custom attributes (so it can't read `_TINTZONE`) but imports UV
-        // sets, so this is the channel a stock-Unity uber-shader reads. See
-        // docs/ENGINE_CONTRACT.md §6.2 (incl. the glTFast UV-pruning caveat).
-        let texcoord_offset = bin.len();
-        for v in &g.vertices {
-            bin.extend_from_slice(bytemuck::bytes_of(&[v.tint_zone, 0.0f32]));
-        }
-        let texcoord_len = bin.len() - texcoord_offset;
-
-        let index_offset = bin.len();
-        bin.extend_from_slice(bytemuck::cast_slice(&g.indices));
-        let index_len = bin.len() - index_offset;
+            // Textured groups take their color from the atlas instead.
+            let color = (!self.textured).then(|| {
+                let bytes: Vec<u8> = g
+                    .vertices
+                    .iter()
+                    .flat_map(|v| {
+                        let c = match colors {
+                            GlbColors::Shaded => v.baked_color(),
+                            GlbColors::Flat => v.color,
+                        };
+                        bytemuck::bytes_of(&c).to_vec()
+                    })
+                    .collect();
+                self.push_attribute(&bytes, count, "VEC4")
+            });
+            // _TINTZONE: per-vertex faction recolor zone (SCALAR f32).
+            let bytes: Vec<u8> = g
+                .vertices
+                .iter()
+                .flat_map(|v| bytemuck::bytes_of(&v.tint_zone).to_vec())
+                .collect();
+            let tintzone = self.push_attribute(&bytes, count, "SCALAR");
+            // Tint zone ALSO as TEXCOORD_0 = vec2(zone, 0). Unity glTFast drops
+            // custom attributes (so it can't read `_TINTZONE`) but imports UV
+            // sets, so this is the channel a stock-Unity uber-shader reads. See
+            // docs/ENGINE_CONTRACT.md §6.2 (incl. the glTFast UV-pruning caveat).
+            let bytes: Vec<u8> = g
+                .vertices
+                .iter()
+                .flat_map(|v| bytemuck::bytes_of(&[v.tint_zone, 0.0f32]).to_vec())
+                .collect();
+            let texcoord = self.push_attribute(&bytes, count, "VEC2");
+            let atlas_uv = self
+                .textured
+                .then(|| self.push_attribute(bytemuck::cast_slice(&g.uvs), count, "VEC2"));
+            let indices = self.push(
+                bytemuck::cast_slice(&g.indices),
+                Some(TARGET_ELEMENT_ARRAY_BUFFER),
+                COMPONENT_TYPE_UINT,
+                g.indices.len(),
+                "SCALAR",
+            );
 
-        // POSITION accessor REQUIRES `min` / `max` per spec §3.6.2.5.
-        let mut min = [f32::INFINITY; 3];
-        let mut max = [f32::NEG_INFINITY; 3];
-        for v in &g.vertices {
-            for axis in 0..3 {
-                if v.position[axis] < min[axis] {
-                    min[axis] = v.position[axis];
+            // POSITION accessor REQUIRES `min` / `max` per spec §3.6.2.5.
+            let mut min = [f32::INFINITY; 3];
+            let mut max = [f32::NEG_INFINITY; 3];
+            for v in &g.vertices {
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(v.position[axis]);
+                    max[axis] = max[axis].max(v.position[axis]);
                 }
-                if v.position[axis] > max[axis] {
-                    max[axis] = v.position[axis];
+            }
+            self.accessors[position]["min"] = json!(min);
+            self.accessors[position]["max"] = json!(max);
+            bounds = union_bounds(bounds, Some((min, max)));
+
+            let material = match self.material_slots[g.group_id as usize] {
+                Some(slot) => slot,
+                None => {
+                    self.materials.push(material_json(g.group_id, self.textured));
+                    let slot = self.materials.len() - 1;
+                    self.material_slots[g.group_id as usize] = Some(slot);
+                    slot
                 }
+            };
+            let mut attributes = json!({
+                "POSITION": position,
+                "NORMAL": normal,
+                "_TINTZONE": tintzone,
+                "TEXCOORD_0": texcoord,
+            });
+            if let Some(color) = color {
+                attributes["COLOR_0"] = json!(color);
             }
+            if let Some(atlas_uv) = atlas_uv {
+                attributes["TEXCOORD_1"] = json!(atlas_uv);
+            }
+            primitives.push(json!({
+                "attributes": attributes,
+                "indices": indices,
+                "material": material,
+                "mode": PRIMITIVE_MODE_TRIANGLES,
+            }));
+            self.vertex_count += g.vertices.len();
+            self.index_count += g.indices.len();
         }
-
-        sections.push(Section {
-            pos: (pos_offset, pos_len),
-            normal: (normal_offset, normal_len),
-            color: (color_offset, color_len),
-            tintzone: (tintzone_offset, tintzone_len),
-            texcoord: (texcoord_offset, texcoord_len),
-            index: (index_offset, index_len),
-            min,
-            max,
-        });
-        total_vertices += g.vertices.len();
-        total_indices += g.indices.len();
+        (primitives, bounds)
     }
 
-    // BIN must be 4-byte aligned. Pad with zeros (spec §3.4.2).
-    while bin.len() % 4 != 0 {
-        bin.push(0);
-    }
+    /// Attach the geometry tables to `json_value` (nothing when no
+    /// geometry was added) and write the file. Returns its length.
+    fn write(mut self, mut json_value: serde_json::Value, path: &Path) -> Result<usize, GlbError> {
+        // BIN must be 4-byte aligned. Pad with zeros (spec §3.4.2).
+        while !self.bin.len().is_multiple_of(4) {
+            self.bin.push(0);
+        }
+        let has_bin = !self.bin.is_empty();
+        if has_bin {
+            json_value["materials"] = json!(self.materials);
+            json_value["accessors"] = json!(self.accessors);
+            json_value["bufferViews"] = json!(self.buffer_views);
+            json_value["buffers"] = json!([{ "byteLength": self.bin.len() }]);
+            if let Some(view) = self.atlas_view {
+                json_value["images"] = json!([{ "bufferView": view, "mimeType": "image/png" }]);
+                json_value["samplers"] = json!([{
+                    "magFilter": FILTER_NEAREST,
+                    "minFilter": FILTER_NEAREST,
+                    "wrapS": WRAP_CLAMP_TO_EDGE,
+                    "wrapT": WRAP_CLAMP_TO_EDGE,
+                }]);
+                json_value["textures"] = json!([{ "sampler": 0, "source": 0 }]);
+            }
+        }
 
-    // Build per-group geometry descriptors (empty when there's no
-    // geometry): one primitive + material + 6 accessors / bufferViews
-    // per group (POSITION, NORMAL, COLOR_0, _TINTZONE, TEXCOORD_0,
-    // indices), all sharing buffer 0.
-    let mut accessors = Vec::with_capacity(groups.len() * 6);
-    let mut buffer_views = Vec::with_capacity(groups.len() * 6);
-    let mut primitives = Vec::with_capacity(groups.len());
-    let mut materials = Vec::with_capacity(groups.len());
-
-    for (i, (g, s)) in groups.iter().zip(&sections).enumerate() {
-        // Group i owns accessors / bufferViews [6i .. 6i+6):
-        // POSITION, NORMAL, COLOR_0, _TINTZONE, TEXCOORD_0, indices.
-        let base = (i * 6) as u32;
-        accessors.push(json!({
-            "bufferView": base,
-            "componentType": COMPONENT_TYPE_FLOAT,
-            "count": g.vertices.len(),
-            "type": "VEC3",
-            "min": [s.min[0], s.min[1], s.min[2]],
-            "max": [s.max[0], s.max[1], s.max[2]],
-        }));
-        accessors.push(json!({
-            "bufferView": base + 1,
-            "componentType": COMPONENT_TYPE_FLOAT,
-            "count": g.vertices.len(),
-            "type": "VEC3",
-        }));
-        accessors.push(json!({
-            "bufferView": base + 2,
-            "componentType": COMPONENT_TYPE_FLOAT,
-            "count": g.vertices.len(),
-            "type": "VEC4",
-        }));
-        // _TINTZONE: per-vertex faction recolor zone (SCALAR f32).
-        accessors.push(json!({
-            "bufferView": base + 3,
-            "componentType": COMPONENT_TYPE_FLOAT,
-            "count": g.vertices.len(),
-            "type": "SCALAR",
-        }));
-        // TEXCOORD_0: the same zone in .x (VEC2 f32) — the glTFast-readable
-        // mirror of _TINTZONE (see docs/GAME_PIPELINE_ROADMAP.md §3.2).
-        accessors.push(json!({
-            "bufferView": base + 4,
-            "componentType": COMPONENT_TYPE_FLOAT,
-            "count": g.vertices.len(),
-            "type": "VEC2",
-        }));
-        accessors.push(json!({
-            "bufferView": base + 5,
-            "componentType": COMPONENT_TYPE_UINT,
-            "count": g.indices.len(),
-            "type": "SCALAR",
-        }));
+        let mut json_bytes = serde_json::to_vec(&json_value)?;
+        // JSON chunk also 4-byte aligned. Pad with ASCII space (0x20).
+        while !json_bytes.len().is_multiple_of(4) {
+            json_bytes.push(b' ');
+        }
 
-        buffer_views.push(json!({ "buffer": 0, "byteOffset": s.pos.0, "byteLength": s.pos.1, "target": TARGET_ARRAY_BUFFER }));
-        buffer_views.push(json!({ "buffer": 0, "byteOffset": s.normal.0, "byteLength": s.normal.1, "target": TARGET_ARRAY_BUFFER }));
-        buffer_views.push(json!({ "buffer": 0, "byteOffset": s.color.0, "byteLength": s.color.1, "target": TARGET_ARRAY_BUFFER }));
-        buffer_views.push(json!({ "buffer": 0, "byteOffset": s.tintzone.0, "byteLength": s.tintzone.1, "target": TARGET_ARRAY_BUFFER }));
-        buffer_views.push(json!({ "buffer": 0, "byteOffset": s.texcoord.0, "byteLength": s.texcoord.1, "target": TARGET_ARRAY_BUFFER }));
-        buffer_views.push(json!({ "buffer": 0, "byteOffset": s.index.0, "byteLength": s.index.1, "target": TARGET_ELEMENT_ARRAY_BUFFER }));
-
-        primitives.push(json!({
-            "attributes": { "POSITION": base, "NORMAL": base + 1, "COLOR_0": base + 2, "_TINTZONE": base + 3, "TEXCOORD_0": base + 4 },
-            "indices": base + 5,
-            "material": i,
-            "mode": PRIMITIVE_MODE_TRIANGLES,
-        }));
+        // Total file length: header (12) + JSON chunk (8 + json_bytes) +
+        // optional BIN chunk (8 + bin), which is only emitted when
+        // there's actual geometry.
+        let total_len: u32 = (12
+            + 8
+            + json_bytes.len()
+            + if has_bin { 8 + self.bin.len() } else { 0 }) as u32;
+
+        let file = File::create(path)?;
+        let mut writer = BufWriter::new(file);
+
+        // ===== Header (12 bytes) =====
+        writer.write_all(b"glTF")?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        writer.write_all(&total_len.to_le_bytes())?;
+
+        // ===== JSON chunk =====
+        writer.write_all(&(json_bytes.len() as u32).to_le_bytes())?;
+        writer.write_all(b"JSON")?;
+        writer.write_all(&json_bytes)?;
+
+        // ===== BIN chunk =====
+        if has_bin {
+            writer.write_all(&(self.bin.len() as u32).to_le_bytes())?;
+            writer.write_all(b"BIN\0")?;
+            writer.write_all(&self.bin)?;
+        }
 
-        materials.push(material_json(g.group_id));
+        writer.flush()?;
+        Ok(total_len as usize)
     }
+}
+
+/// Write one or more material groups to a binary glTF 2.0 file. Each
+/// group becomes a primitive (POSITION / NORMAL / COLOR_0 / _TINTZONE /
+/// TEXCOORD_0 / indices) plus a material; the BIN payload lays the groups
+/// out back to back (see [`GlbBuilder`]). An empty
+/// `groups` slice produces a valid geometry-free glTF (no BIN chunk) —
+/// which `sockets` can still populate with empty nodes. `chunk_count` is
+/// passed through to the returned stats.
+fn write_glb_groups(
+    groups: &[GroupBuffers],
+    sockets: &[SocketNode],
+    chunk_count: usize,
+    path: &Path,
+    transform: ExportTransform,
+    colors: GlbColors,
+) -> Result<GlbStats, GlbError> {
+    let meshes = [("Voxelith".to_string(), groups)];
+    write_glb_meshes(&meshes, sockets, chunk_count, path, transform, colors, None)
+}
+
+/// [`write_glb_groups`] for several named meshes, each emitted as its
+/// own mesh + node in order (meshes without groups are skipped), plus
+/// the color `atlas` the groups' UVs point into, if any. A single mesh
+/// named "Voxelith" without an atlas writes exactly what
+/// [`write_glb_groups`] always has.
+#[allow(clippy::too_many_arguments)]
+fn write_glb_meshes(
+    meshes: &[(String, &[GroupBuffers])],
+    sockets: &[SocketNode],
+    chunk_count: usize,
+    path: &Path,
+    transform: ExportTransform,
+    colors: GlbColors,
+    atlas: Option<&Atlas>,
+) -> Result<GlbStats, GlbError> {
+    let mut builder = GlbBuilder {
+        textured: atlas.is_some(),
+        ..Default::default()
+    };
 
-    // Assemble the scene's node list: the mesh node (node 0) when there
-    // is geometry, then one empty node per socket — `name` +
+    // Assemble the scene's node list: one node per mesh with geometry
+    // (nodes 0..n), then one empty node per socket — `name` +
     // `translation` + `rotation`, no `mesh` — which is the standard
     // glTF representation of an attachment point. Sockets export even
     // for an empty world (a sockets-only glTF is valid).
     let mut nodes: Vec<serde_json::Value> = Vec::new();
     let mut scene_nodes: Vec<usize> = Vec::new();
-    if !groups.is_empty() {
-        nodes.push(json!({ "mesh": 0, "name": "Voxelith" }));
-        scene_nodes.push(0);
+    let mut mesh_json: Vec<serde_json::Value> = Vec::new();
+    let mut bounds: Option<Bounds> = None;
+    for (name, groups) in meshes.iter().filter(|(_, groups)| !groups.is_empty()) {
+        let (primitives, mesh_bounds) = builder.add_mesh(groups, colors);
+        bounds = union_bounds(bounds, mesh_bounds);
+        scene_nodes.push(nodes.len());
+        nodes.push(json!({ "mesh": mesh_json.len(), "name": name }));
+        mesh_json.push(json!({ "name": name, "primitives": primitives }));
+    }
+    if let Some(atlas) = atlas {
+        builder.add_atlas(&atlas.png_bytes()?);
     }
     for sock in sockets {
         scene_nodes.push(nodes.len());
@@ -491,33 +994,20 @@ fn write_glb_groups(
     }
 
     // Deterministic placement (§3.5): for a non-identity transform, wrap
-    // every scene root (mesh + sockets) under one parent node carrying the
-    // pivot offset, up-axis rotation, and uniform scale, so geometry and
-    // sockets move together and the asset's local origin becomes the chosen
-    // pivot. An identity transform adds nothing, leaving the output
+    // every scene root (meshes + sockets) under one parent node carrying
+    // the pivot offset, up-axis rotation, and uniform scale, so geometry
+    // and sockets move together and the asset's local origin becomes the
+    // chosen pivot. An identity transform adds nothing, leaving the output
     // byte-for-byte identical to the plain export.
     if !transform.is_identity() && !scene_nodes.is_empty() {
-        let bounds = sections.iter().fold(
-            None,
-            |acc: Option<([f32; 3], [f32; 3])>, s| match acc {
-                None => Some((s.min, s.max)),
-                Some((mut lo, mut hi)) => {
-                    for a in 0..3 {
-                        lo[a] = lo[a].min(s.min[a]);
-                        hi[a] = hi[a].max(s.max[a]);
-                    }
-                    Some((lo, hi))
-                }
-            },
-        );
         let root = root_transform_node(&scene_nodes, bounds, transform);
         scene_nodes = vec![nodes.len()];
         nodes.push(root);
     }
 
-    // Base document; geometry-only keys (meshes/materials/accessors/
-    // bufferViews/buffers) are attached only when groups exist, and
-    // `nodes` only when there's at least one node (mesh or socket).
+    // Base document; geometry-only keys (meshes + the builder's tables)
+    // are attached only when there is geometry, and `nodes` only when
+    // there's at least one node (mesh or socket).
     let mut json_value = json!({
         "asset": { "version": "2.0", "generator": "Voxelith" },
         "scene": 0,
@@ -526,60 +1016,35 @@ fn write_glb_groups(
     if !nodes.is_empty() {
         json_value["nodes"] = json!(nodes);
     }
-    if !groups.is_empty() {
-        json_value["meshes"] = json!([{ "name": "Voxelith", "primitives": primitives }]);
-        json_value["materials"] = json!(materials);
-        json_value["accessors"] = json!(accessors);
-        json_value["bufferViews"] = json!(buffer_views);
-        json_value["buffers"] = json!([{ "byteLength": bin.len() }]);
-    }
-
-    let mut json_bytes = serde_json::to_vec(&json_value)?;
-    // JSON chunk also 4-byte aligned. Pad with ASCII space (0x20).
-    while json_bytes.len() % 4 != 0 {
-        json_bytes.push(b' ');
-    }
-
-    // Emit BIN chunk only when there's actual geometry.
-    let has_bin = !bin.is_empty() && !groups.is_empty();
-
-    // Total file length: header (12) + JSON chunk (8 + json_bytes) +
-    // optional BIN chunk (8 + bin).
-    let total_len: u32 = (12
-        + 8
-        + json_bytes.len()
-        + if has_bin { 8 + bin.len() } else { 0 }) as u32;
-
-    let file = File::create(path)?;
-    let mut writer = BufWriter::new(file);
-
-    // ===== Header (12 bytes) =====
-    writer.write_all(b"glTF")?;
-    writer.write_all(&2u32.to_le_bytes())?;
-    writer.write_all(&total_len.to_le_bytes())?;
-
-    // ===== JSON chunk =====
-    writer.write_all(&(json_bytes.len() as u32).to_le_bytes())?;
-    writer.write_all(b"JSON")?;
-    writer.write_all(&json_bytes)?;
-
-    // ===== BIN chunk =====
-    if has_bin {
-        writer.write_all(&(bin.len() as u32).to_le_bytes())?;
-        writer.write_all(b"BIN\0")?;
-        writer.write_all(&bin)?;
+    if !mesh_json.is_empty() {
+        json_value["meshes"] = json!(mesh_json);
     }
 
-    writer.flush()?;
-
+    let (vertex_count, index_count) = (builder.vertex_count, builder.index_count);
+    let byte_size = builder.write(json_value, path)?;
     Ok(GlbStats {
-        vertex_count: total_vertices,
-        triangle_count: total_indices / 3,
+        vertex_count,
+        triangle_count: index_count / 3,
         chunk_count,
-        byte_size: total_len as usize,
+        byte_size,
+        atlas_size: atlas.map(|a| [a.width, a.height]),
     })
 }
 
+/// Smallest box holding both `a` and `b` (either may be absent).
+pub(super) fn union_bounds(a: Option<Bounds>, b: Option<Bounds>) -> Option<Bounds> {
+    match (a, b) {
+        (Some((mut lo, mut hi)), Some((min, max))) => {
+            for axis in 0..3 {
+                lo[axis] = lo[axis].min(min[axis]);
+                hi[axis] = hi[axis].max(max[axis]);
+            }
+            Some((lo, hi))
+        }
+        (a, b) => a.or(b),
+    }
+}
+
 /// Build the parent node that applies an [`ExportTransform`] to all
 /// `children` (the existing scene roots). Translation places the chosen
 /// pivot at the local origin (after scale + rotation), rotation is the
@@ -589,36 +1054,10 @@ fn write_glb_groups(
 /// lives entirely in this node, so the export stays lossless.
 fn root_transform_node(
     children: &[usize],
-    bounds: Option<([f32; 3], [f32; 3])>,
+    bounds: Option<Bounds>,
     t: ExportTransform,
 ) -> serde_json::Value {
-    let s = t.unit_scale;
-    let pivot = match (t.pivot, bounds) {
-        (Pivot::BaseCenter, Some((lo, hi))) => {
-            [(lo[0] + hi[0]) * 0.5, lo[1], (lo[2] + hi[2]) * 0.5]
-        }
-        (Pivot::Center, Some((lo, hi))) => [
-            (lo[0] + hi[0]) * 0.5,
-            (lo[1] + hi[1]) * 0.5,
-            (lo[2] + hi[2]) * 0.5,
-        ],
-        // `Origin`, or any pivot with no geometry to measure.
-        _ => [0.0, 0.0, 0.0],
-    };
-    let rotation = match t.up_axis {
-        UpAxis::Y => [0.0, 0.0, 0.0, 1.0],
-        // +90° about X maps model +Y onto world +Z, for Z-up engines.
-        UpAxis::Z => [
-            std::f32::consts::FRAC_1_SQRT_2,
-            0.0,
-            0.0,
-            std::f32::consts::FRAC_1_SQRT_2,
-        ],
-    };
-    // translation = -(R · (scale · pivot)) so the pivot lands at origin.
-    let scaled = [pivot[0] * s, pivot[1] * s, pivot[2] * s];
-    let rotated = rotate_vec_by_quat(scaled, rotation);
-    let translation = [-rotated[0], -rotated[1], -rotated[2]];
+    let (translation, rotation, s) = t.placement(bounds);
     json!({
         "name": "Voxelith_root",
         "translation": translation,
@@ -631,7 +1070,7 @@ fn root_transform_node(
 /// Rotate a vector by a unit quaternion `[x, y, z, w]`
 /// (`v' = v + 2·q_xyz × (q_xyz × v + w·v)`). Kept local so this module
 /// stays free of a math-library dependency.
-fn rotate_vec_by_quat(v: [f32; 3], q: [f32; 4]) -> [f32; 3] {
+pub(super) fn rotate_vec_by_quat(v: [f32; 3], q: [f32; 4]) -> [f32; 3] {
     let (qx, qy, qz, qw) = (q[0], q[1], q[2], q[3]);
     // t = 2 · (q_xyz × v)
     let tx = 2.0 * (qy * v[2] - qz * v[1]);
@@ -886,6 +1325,41 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn test_flat_colors_skip_shading_and_ao() {
+        // Same concave floor + wall seam as above: shaded export darkens
+        // sides and corners, flat export must write the source color on
+        // every vertex of every face.
+        let mut world = World::new();
+        for a in 0..4 {
+            for b in 0..4 {
+                world.set_voxel(a, 0, b, Voxel::from_rgb(255, 0, 0));
+                world.set_voxel(0, a, b, Voxel::from_rgb(255, 0, 0));
+            }
+        }
+        world.clear_dirty_flags();
+        let path = std::env::temp_dir().join("voxelith_flat_colors.glb");
+        let shaded_path = std::env::temp_dir().join("voxelith_flat_colors_ref.glb");
+        let stats = export_glb_with_colors(&world, &[], &path, GlbColors::Flat).unwrap();
+        let shaded = export_glb(&world, &[], &shaded_path).unwrap();
+        // Flat colors only change COLOR_0, never the geometry.
+        assert_eq!(stats.triangle_count, shaded.triangle_count);
+
+        let (json_bytes, bin) = read_glb(&path);
+        let bin = bin.expect("non-empty world must have BIN chunk");
+        let json: serde_json::Value = serde_json::from_slice(&json_bytes).unwrap();
+        let view = &json["bufferViews"][2];
+        let off = view["byteOffset"].as_u64().unwrap() as usize;
+        let len = view["byteLength"].as_u64().unwrap() as usize;
+        for rgba in bin[off..off + len].chunks_exact(16) {
+            let r = f32::from_le_bytes(rgba[0..4].try_into().unwrap());
+            assert!((r - 1.0).abs() < 1e-6, "flat export darkened R to {r}");
+        }
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&shaded_path);
+    }
+
     #[test]
     fn test_export_groups_by_material_into_primitives_and_materials() {
         let mut world = World::new();
@@ -1170,4 +1644,192 @@ mod tests {
 
         let _ = std::fs::remove_file(&path);
     }
+
+    #[test]
+    fn test_animation_switches_frame_nodes_by_scale() {
+        let mut first = World::new();
+        first.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        let mut second = World::new();
+        second.set_voxel(0, 1, 0, Voxel::from_rgb(0, 255, 0));
+        let empty = World::new();
+        let frames = [
+            GlbFrame { name: "A", world: &first, duration_ms: 100 },
+            GlbFrame { name: "B", world: &empty, duration_ms: 250 },
+            GlbFrame { name: "C", world: &second, duration_ms: 150 },
+        ];
+        let path = std::env::temp_dir().join("voxelith_flipbook.glb");
+        let stats = export_glb_animation(&frames, &path, GlbColors::Shaded).unwrap();
+        assert_eq!(stats.triangle_count, 24);
+
+        let (json_bytes, bin) = read_glb(&path);
+        let bin = bin.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json_bytes).unwrap();
+        assert_eq!(json["nodes"][0]["children"], json!([1, 2, 3]));
+        assert_eq!(json["meshes"].as_array().unwrap().len(), 2);
+        assert_eq!(json["materials"].as_array().unwrap().len(), 1);
+        // The empty frame still gets a node, just no mesh.
+        assert!(json["nodes"][2]["mesh"].is_null());
+        assert_eq!(json["nodes"][3]["mesh"], 1);
+        assert!(json["nodes"][1]["scale"].is_null());
+        assert_eq!(json["nodes"][3]["scale"], json!([0.0, 0.0, 0.0]));
+        assert_eq!(json["nodes"][2]["extras"]["duration_ms"], 250);
+
+        let animation = &json["animations"][0];
+        assert_eq!(animation["channels"].as_array().unwrap().len(), 3);
+        assert_eq!(animation["channels"][2]["target"]["node"], 3);
+        let sampler = &animation["samplers"][2];
+        assert_eq!(sampler["interpolation"], "STEP");
+
+        // Frame C: hidden, shown at 0.35 s, hidden at 0.5 s (loop end).
+        let read_floats = |accessor: &serde_json::Value| -> Vec<f32> {
+            let view = &json["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+            let offset = view["byteOffset"].as_u64().unwrap() as usize;
+            let len = view["byteLength"].as_u64().unwrap() as usize;
+            bytemuck::cast_slice(&bin[offset..offset + len]).to_vec()
+        };
+        let input = &json["accessors"][sampler["input"].as_u64().unwrap() as usize];
+        assert_eq!(read_floats(input), [0.0, 0.35, 0.5]);
+        assert_eq!(input["max"], json!([0.5]));
+        let output = &json["accessors"][sampler["output"].as_u64().unwrap() as usize];
+        assert_eq!(read_floats(output), [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_parts_nest_under_their_parents() {
+        let mut body = World::new();
+        body.set_voxel(0, 0, 0, Voxel::from_rgb(200, 200, 200));
+        let mut arm = World::new();
+        arm.set_voxel(1, 0, 0, Voxel::from_rgb(255, 0, 0));
+        let empty = World::new();
+        let quarter = std::f32::consts::FRAC_1_SQRT_2;
+        let parts = [
+            GlbPart {
+                name: "Body",
+                world: &body,
+                parent: None,
+                translation: [0.0; 3],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+            },
+            GlbPart {
+                name: "Arm",
+                world: &arm,
+                parent: Some(0),
+                translation: [1.0, -1.0, 0.0],
+                rotation: [0.0, 0.0, quarter, quarter],
+            },
+            GlbPart {
+                name: "Hand",
+                world: &empty,
+                parent: Some(1),
+                translation: [0.0; 3],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+            },
+        ];
+        let sockets = vec![SocketNode {
+            name: "grip".to_string(),
+            translation: [2.0, 0.5, 0.5],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+        }];
+        let path = std::env::temp_dir().join("voxelith_parts.glb");
+        let stats = export_glb_parts(&parts, &sockets, &path, GlbColors::Shaded).unwrap();
+        assert_eq!(stats.triangle_count, 24);
+
+        let (json_bytes, _) = read_glb(&path);
+        let json: serde_json::Value = serde_json::from_slice(&json_bytes).unwrap();
+        assert_eq!(json["scenes"][0]["nodes"], json!([0, 3]));
+        assert_eq!(json["nodes"][0]["children"], json!([1]));
+        assert_eq!(json["nodes"][1]["children"], json!([2]));
+        assert_eq!(json["nodes"][1]["mesh"], 1);
+        assert_eq!(json["nodes"][1]["translation"], json!([1.0, -1.0, 0.0]));
+        assert!(json["nodes"][0]["rotation"].is_null());
+        assert!(json["nodes"][2]["mesh"].is_null());
+        assert_eq!(json["nodes"][3]["name"], "grip");
+        assert_eq!(json["materials"].as_array().unwrap().len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_options_split_chunks_into_named_meshes() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(40, 0, 0, Voxel::from_rgb(0, 255, 0));
+        world.clear_dirty_flags();
+
+        let path = std::env::temp_dir().join("voxelith_options_split.glb");
+        let options = ExportOptions {
+            merge_chunks: false,
+            ..Default::default()
+        };
+        let stats = export_glb_with_options(&world, &[], &path, &options).unwrap();
+        assert_eq!(stats.chunk_count, 2);
+        assert_eq!(stats.triangle_count, 24);
+
+        let (json_bytes, _) = read_glb(&path);
+        let json: serde_json::Value = serde_json::from_slice(&json_bytes).unwrap();
+        let names: Vec<&str> = json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["chunk_0_0_0", "chunk_1_0_0"]);
+        assert_eq!(json["meshes"].as_array().unwrap().len(), 2);
+        assert_eq!(json["nodes"][1]["mesh"], 1);
+        // Both meshes share the one plain material.
+        assert_eq!(json["materials"].as_array().unwrap().len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_atlas_embeds_png_and_samples_texcoord1() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(3, 0, 0, Voxel::from_rgb(0, 0, 255));
+        world.clear_dirty_flags();
+
+        let path = std::env::temp_dir().join("voxelith_atlas.glb");
+        let options = ExportOptions {
+            colors: GlbColors::Flat,
+            color_storage: ColorStorage::Atlas,
+            ..Default::default()
+        };
+        let stats = export_glb_with_options(&world, &[], &path, &options).unwrap();
+        // Two flat colors → two texels.
+        assert_eq!(stats.atlas_size, Some([2, 1]));
+
+        let (json_bytes, bin) = read_glb(&path);
+        let json: serde_json::Value = serde_json::from_slice(&json_bytes).unwrap();
+        let attributes = &json["meshes"][0]["primitives"][0]["attributes"];
+        assert!(attributes.get("COLOR_0").is_none(), "atlas replaces COLOR_0");
+        assert!(attributes.get("TEXCOORD_0").is_some(), "tint zone stays");
+        let uv_accessor = attributes["TEXCOORD_1"].as_u64().unwrap() as usize;
+        assert_eq!(
+            json["materials"][0]["pbrMetallicRoughness"]["baseColorTexture"]["texCoord"],
+            1
+        );
+
+        // The image's bufferView holds a PNG.
+        let view = &json["bufferViews"][json["images"][0]["bufferView"].as_u64().unwrap() as usize];
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+        let bin = bin.unwrap();
+        assert_eq!(&bin[offset + 1..offset + 4], b"PNG");
+
+        // Every UV sits on a texel center: u ∈ {0.25, 0.75}, v = 0.5.
+        let view = &json["bufferViews"][json["accessors"][uv_accessor]["bufferView"]
+            .as_u64()
+            .unwrap() as usize];
+        let start = view["byteOffset"].as_u64().unwrap() as usize;
+        let len = view["byteLength"].as_u64().unwrap() as usize;
+        let uvs: &[f32] = bytemuck::cast_slice(&bin[start..start + len]);
+        for uv in uvs.chunks_exact(2) {
+            assert!(uv[0] == 0.25 || uv[0] == 0.75, "u = {}", uv[0]);
+            assert_eq!(uv[1], 0.5);
+        }
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
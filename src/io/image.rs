@@ -0,0 +1,329 @@
+//! PNG interchange with 2D tools: per-layer slice stacks and grayscale
+//! heightmaps.
+//!
+//! - **Slice stacks**: one RGBA image per Y layer, bottom first. Pixel
+//!   `(u, v)` of slice `i` is voxel `(min.x + u, min.y + i, min.z + v)`;
+//!   transparent pixels are air. Export writes `<stem>_000.png`,
+//!   `<stem>_001.png`, … next to the chosen path, so the stack sorts
+//!   correctly by name in any file browser or image editor. Import
+//!   takes the files in name order and rebuilds the model with its
+//!   min corner at the origin — the world offset isn't stored in a
+//!   plain PNG.
+//! - **Heightmaps**: a grayscale (8- or 16-bit) PNG where brightness is
+//!   column height. Each pixel becomes a solid column from `y = 0`,
+//!   colored per voxel by a height gradient (sand → grass → rock →
+//!   snow by default), centered on the origin like the Perlin terrain
+//!   generator. Color images are converted to luminance.
+//!
+//! Both readers cap image dimensions so a hostile file can't make us
+//! fill hundreds of millions of voxels.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ::image::{DynamicImage, ImageFormat, ImageReader, Rgba, RgbaImage};
+use thiserror::Error;
+
+use crate::core::{Voxel, World};
+
+/// Largest accepted slice / heightmap side, in pixels.
+pub const MAX_IMAGE_SIDE: u32 = 1024;
+/// Most slices accepted in one stack.
+pub const MAX_SLICES: usize = 1024;
+
+/// Errors that can occur when reading/writing PNG slices or heightmaps
+#[derive(Debug, Error)]
+pub enum ImageIoError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Image error: {0}")]
+    Image(#[from] ::image::ImageError),
+    #[error("Image is {width}×{height}; at most {MAX_IMAGE_SIDE} per side is supported")]
+    ImageTooLarge { width: u32, height: u32 },
+    #[error("Slice {index} is {found:?} but the first slice is {expected:?}")]
+    SliceSizeMismatch {
+        index: usize,
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+    #[error("Too many slices ({0}); at most {MAX_SLICES} are supported")]
+    TooManySlices(usize),
+    #[error("No voxel data found")]
+    NoVoxelData,
+}
+
+/// Height → color gradient stop: `(t, rgb)` with `t` in `[0, 1]`.
+pub type GradientStop = (f32, [u8; 3]);
+
+/// How a heightmap turns into terrain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeightmapOptions {
+    /// Height in voxels of a fully white pixel. Black is a single
+    /// ground voxel at `y = 0`.
+    pub max_height: u32,
+    /// Color stops by relative height, ascending `t`. Empty means a
+    /// flat gray.
+    pub gradient: Vec<GradientStop>,
+}
+
+impl Default for HeightmapOptions {
+    fn default() -> Self {
+        Self {
+            max_height: 32,
+            gradient: vec![
+                (0.0, [194, 178, 128]), // sand
+                (0.25, [76, 153, 0]),   // grass
+                (0.7, [128, 128, 128]), // rock
+                (0.9, [240, 240, 240]), // snow
+            ],
+        }
+    }
+}
+
+/// Sample `gradient` at `t`, linearly interpolating between stops and
+/// clamping outside them.
+fn sample_gradient(gradient: &[GradientStop], t: f32) -> [u8; 3] {
+    let Some(first) = gradient.first() else {
+        return [128, 128, 128];
+    };
+    if t <= first.0 {
+        return first.1;
+    }
+    for pair in gradient.windows(2) {
+        let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+        if t <= t1 {
+            let f = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1.0 };
+            let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+            return [lerp(c0[0], c1[0]), lerp(c0[1], c1[1]), lerp(c0[2], c1[2])];
+        }
+    }
+    gradient[gradient.len() - 1].1
+}
+
+fn check_size(width: u32, height: u32) -> Result<(), ImageIoError> {
+    if width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+        return Err(ImageIoError::ImageTooLarge { width, height });
+    }
+    Ok(())
+}
+
+/// Decode a PNG, checking its size from the header first so an
+/// oversized image is refused before its pixels are allocated.
+fn decode_png(bytes: &[u8]) -> Result<DynamicImage, ImageIoError> {
+    let reader = || ImageReader::with_format(Cursor::new(bytes), ImageFormat::Png);
+    let (width, height) = reader().into_dimensions()?;
+    check_size(width, height)?;
+    Ok(reader().decode()?)
+}
+
+/// Build terrain from an already decoded heightmap image.
+pub fn heightmap_to_world(image: &DynamicImage, options: &HeightmapOptions) -> Result<World, ImageIoError> {
+    let (width, depth) = (image.width(), image.height());
+    check_size(width, depth)?;
+    if width == 0 || depth == 0 {
+        return Err(ImageIoError::NoVoxelData);
+    }
+    // 16-bit luminance keeps the precision of 16-bit heightmaps; 8-bit
+    // sources are scaled up losslessly.
+    let luma = image.to_luma16();
+    let max_height = options.max_height.max(1);
+    // Same centering as `PerlinTerrain`: exactly width × depth columns.
+    let (half_w, half_d) = ((width / 2) as i32, (depth / 2) as i32);
+    let colors: Vec<Voxel> = (0..=max_height)
+        .map(|y| {
+            let [r, g, b] = sample_gradient(&options.gradient, y as f32 / max_height as f32);
+            Voxel::from_rgb(r, g, b)
+        })
+        .collect();
+
+    let colors = &colors;
+    let mut world = World::new();
+    world.set_voxels(luma.enumerate_pixels().flat_map(|(px, pz, pixel)| {
+        let h = (pixel.0[0] as f32 / u16::MAX as f32 * max_height as f32).round() as u32;
+        let (x, z) = (px as i32 - half_w, pz as i32 - half_d);
+        (0..=h).map(move |y| ((x, y as i32, z), colors[y as usize]))
+    }));
+    Ok(world)
+}
+
+/// Import a heightmap PNG as terrain.
+pub fn import_heightmap(bytes: &[u8], options: &HeightmapOptions) -> Result<World, ImageIoError> {
+    heightmap_to_world(&decode_png(bytes)?, options)
+}
+
+/// Slice `world` into one RGBA image per Y layer of its AABB, bottom
+/// first. Empty worlds yield no slices.
+pub fn world_to_slices(world: &World) -> Vec<RgbaImage> {
+    let Some((min, max)) = world.scene_aabb() else {
+        return Vec::new();
+    };
+    let width = (max.0 - min.0 + 1) as u32;
+    let depth = (max.2 - min.2 + 1) as u32;
+    (min.1..=max.1)
+        .map(|y| {
+            RgbaImage::from_fn(width, depth, |u, v| {
+                let voxel = world.get_voxel(min.0 + u as i32, y, min.2 + v as i32);
+                if voxel.is_air() {
+                    Rgba([0, 0, 0, 0])
+                } else {
+                    Rgba([voxel.r, voxel.g, voxel.b, 255])
+                }
+            })
+        })
+        .collect()
+}
+
+/// Rebuild a world from a slice stack (bottom first), min corner at the
+/// origin. Every slice must have the first one's dimensions; pixels
+/// with zero alpha are air.
+pub fn slices_to_world(slices: &[RgbaImage]) -> Result<World, ImageIoError> {
+    if slices.len() > MAX_SLICES {
+        return Err(ImageIoError::TooManySlices(slices.len()));
+    }
+    let Some(first) = slices.first() else {
+        return Err(ImageIoError::NoVoxelData);
+    };
+    let expected = first.dimensions();
+    check_size(expected.0, expected.1)?;
+
+    let mut world = World::new();
+    for (index, slice) in slices.iter().enumerate() {
+        if slice.dimensions() != expected {
+            return Err(ImageIoError::SliceSizeMismatch {
+                index,
+                expected,
+                found: slice.dimensions(),
+            });
+        }
+        for (u, v, pixel) in slice.enumerate_pixels() {
+            let [r, g, b, a] = pixel.0;
+            if a > 0 {
+                world.set_voxel(u as i32, index as i32, v as i32, Voxel::from_rgb(r, g, b));
+            }
+        }
+    }
+    if world.scene_aabb().is_none() {
+        return Err(ImageIoError::NoVoxelData);
+    }
+    Ok(world)
+}
+
+/// File names for an `n`-slice stack based on `path`: `<stem>_000.png`
+/// … in `path`'s directory (see [`super::numbered_paths`]).
+pub fn slice_paths(path: &Path, n: usize) -> Vec<PathBuf> {
+    super::numbered_paths(path, n, "slice", "png")
+}
+
+/// Export `world` as a PNG slice stack named after `path` (see
+/// [`slice_paths`]). Returns the written paths.
+pub fn export_png_slices(world: &World, path: &Path) -> Result<Vec<PathBuf>, ImageIoError> {
+    let slices = world_to_slices(world);
+    if slices.is_empty() {
+        return Err(ImageIoError::NoVoxelData);
+    }
+    let paths = slice_paths(path, slices.len());
+    for (slice, path) in slices.iter().zip(&paths) {
+        slice.save_with_format(path, ImageFormat::Png)?;
+    }
+    Ok(paths)
+}
+
+/// Import a PNG slice stack. Files are taken in file-name order, the
+/// first being the bottom layer.
+pub fn import_png_slices(paths: &[PathBuf]) -> Result<World, ImageIoError> {
+    if paths.len() > MAX_SLICES {
+        return Err(ImageIoError::TooManySlices(paths.len()));
+    }
+    let mut sorted: Vec<&PathBuf> = paths.iter().collect();
+    sorted.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    let mut slices = Vec::with_capacity(sorted.len());
+    for path in sorted {
+        let bytes = std::fs::read(path)?;
+        slices.push(decode_png(&bytes)?.to_rgba8());
+    }
+    slices_to_world(&slices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::image::{GrayImage, Luma};
+
+    #[test]
+    fn test_slices_roundtrip_moves_min_to_origin() {
+        let mut world = World::new();
+        world.set_voxel(-3, 2, 5, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(-1, 4, 6, Voxel::from_rgb(0, 0, 255));
+        let slices = world_to_slices(&world);
+        assert_eq!(slices.len(), 3);
+        assert_eq!(slices[0].dimensions(), (3, 2));
+
+        let back = slices_to_world(&slices).unwrap();
+        assert_eq!(back.scene_aabb(), Some(((0, 0, 0), (2, 2, 1))));
+        let v = back.get_voxel(0, 0, 0);
+        assert_eq!((v.r, v.g, v.b), (255, 0, 0));
+        let v = back.get_voxel(2, 2, 1);
+        assert_eq!((v.r, v.g, v.b), (0, 0, 255));
+        assert!(back.get_voxel(1, 1, 0).is_air());
+    }
+
+    #[test]
+    fn test_slices_reject_mismatched_sizes() {
+        let slices = vec![RgbaImage::new(2, 2), RgbaImage::new(3, 2)];
+        assert!(matches!(
+            slices_to_world(&slices),
+            Err(ImageIoError::SliceSizeMismatch { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_heightmap_columns_and_gradient() {
+        let mut map = GrayImage::new(2, 1);
+        map.put_pixel(0, 0, Luma([0]));
+        map.put_pixel(1, 0, Luma([255]));
+        let options = HeightmapOptions {
+            max_height: 10,
+            ..Default::default()
+        };
+        let world = heightmap_to_world(&DynamicImage::ImageLuma8(map), &options).unwrap();
+        // Centered: columns at x = -1 and x = 0.
+        assert!(!world.get_voxel(-1, 0, 0).is_air());
+        assert!(world.get_voxel(-1, 1, 0).is_air());
+        assert!(!world.get_voxel(0, 10, 0).is_air());
+        assert!(world.get_voxel(0, 11, 0).is_air());
+        let bottom = world.get_voxel(0, 0, 0);
+        assert_eq!((bottom.r, bottom.g, bottom.b), (194, 178, 128));
+        let top = world.get_voxel(0, 10, 0);
+        assert_eq!((top.r, top.g, top.b), (240, 240, 240));
+    }
+
+    #[test]
+    fn test_slice_paths_pad_to_stack_size() {
+        let paths = slice_paths(Path::new("out/tree.png"), 2);
+        assert_eq!(paths[1], Path::new("out/tree_001.png"));
+        let paths = slice_paths(Path::new("tree.png"), 1200);
+        assert_eq!(paths[7], Path::new("tree_0007.png"));
+    }
+
+    #[test]
+    fn test_oversized_png_is_refused_from_its_header() {
+        let mut bytes = Vec::new();
+        GrayImage::new(1, 1)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        // Claim 60000 × 60000 in IHDR and fix up its CRC; the pixel
+        // data stays 1 × 1.
+        bytes[16..20].copy_from_slice(&60_000u32.to_be_bytes());
+        bytes[20..24].copy_from_slice(&60_000u32.to_be_bytes());
+        let mut crc = flate2::Crc::new();
+        crc.update(&bytes[12..29]);
+        bytes[29..33].copy_from_slice(&crc.sum().to_be_bytes());
+        assert!(matches!(
+            import_heightmap(&bytes, &HeightmapOptions::default()),
+            Err(ImageIoError::ImageTooLarge {
+                width: 60_000,
+                height: 60_000
+            })
+        ));
+    }
+}
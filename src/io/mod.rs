@@ -3,31 +3,63 @@
 //! Supported formats:
 //! - Native project format (.vxlt) - compressed binary with metadata
 //! - MagicaVoxel (.vox) - import/export
-//! - Wavefront OBJ (.obj) - export (geometry + vertex colors)
-//! - glTF Binary (.glb) - export (single-file, native vertex colors)
-
+//! - Wavefront OBJ (.obj) - export (geometry + vertex colors, or a
+//!   texture atlas with a .mtl); triangles read back for ghost models
+//! - glTF Binary (.glb) - export (single-file, native vertex colors or
+//!   an embedded texture atlas;
+//!   animation frames as a node-switching flipbook, rigs as a bone node
+//!   hierarchy)
+//! - GIMP (.gpl) / JASC (.pal) palettes - import/export; .vox / .png
+//!   palettes - import
+//! - Qubicle (.qb / .qbt) - import/export, one matrix per layer
+//! - PNG - per-Y-layer slice stacks (import/export), grayscale
+//!   heightmaps (import)
+
+mod export;
 mod gltf;
+mod image;
 mod obj;
+mod palette;
 mod project;
+mod qubicle;
 mod vox;
 
+pub use export::{ColorStorage, ExportOptions, MeshSurface};
 pub use gltf::{
-    export_glb, export_glb_smoothed, export_glb_smoothed_with_transform,
-    export_glb_with_transform, ExportTransform, GlbError, GlbStats, Pivot, SocketNode,
-    UpAxis,
+    export_glb, export_glb_animation, export_glb_parts, export_glb_smoothed,
+    export_glb_smoothed_with_transform, export_glb_with_colors, export_glb_with_options,
+    export_glb_with_transform,
+    ExportTransform, GlbColors, GlbError, GlbFrame, GlbPart, GlbStats, Pivot, SocketNode, UpAxis,
+};
+pub use image::{
+    export_png_slices, heightmap_to_world, import_heightmap, import_png_slices, slice_paths,
+    slices_to_world, world_to_slices, GradientStop, HeightmapOptions, ImageIoError,
+    MAX_IMAGE_SIDE, MAX_SLICES,
+};
+pub use obj::{
+    export_obj, export_obj_smoothed, export_obj_with_options, import_obj_mesh, ObjError, ObjStats,
+};
+pub use palette::{
+    export_palette, import_palette, read_gpl, read_pal, read_png, read_vox, write_gpl,
+    write_pal, PaletteError, PaletteFormat, MAX_IMAGE_PIXELS,
 };
-pub use obj::{export_obj, export_obj_smoothed, ObjError, ObjStats};
 pub use project::{
-    EditorState, Project, ProjectError, ProjectMetadata, SocketData,
-    load_world, load_world_with_state, save_world, save_world_with_state,
+    BackgroundSave, BoneData, CameraBookmark, CameraPathKey, EditorState, FrameData, LayerData,
+    Project, ProjectError, ProjectMetadata, SocketData,
+    CAMERA_BOOKMARK_SLOTS,
+    load_scene_with_state, load_world, load_world_with_state, read_thumbnail,
+    save_project_atomically, save_project_in_background, save_scene_with_state,
+    save_scene_with_thumbnail, save_world, save_world_with_state,
 };
+pub use qubicle::{export_qb, export_qbt, import_qb, import_qbt, QubicleError, QubicleMatrix};
 pub use vox::{
     VoxError, VoxModel, default_palette,
-    export_vox, import_vox,
+    export_vox, export_vox_with_chunks, export_vox_with_stats, import_vox, import_vox_full,
+    read_vox_palette, VoxAxes, VoxChunk, VoxExportStats, VoxImport,
 };
 
 use std::io::{self, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Read exactly `len` bytes from `reader` without trusting `len` enough
 /// to pre-allocate it.
@@ -68,6 +100,22 @@ pub(super) fn skip_bytes<R: Read>(reader: &mut R, n: u64) -> io::Result<()> {
     Ok(())
 }
 
+/// `n` sibling file names based on `path`: `<stem>_000.<ext>`,
+/// `<stem>_001.<ext>` … in `path`'s directory, for exports that write
+/// one file per slice or frame. The index is zero-padded to at least
+/// three digits so lexical order is sequence order; `default_stem`
+/// stands in when `path` has no usable stem.
+pub fn numbered_paths(path: &Path, n: usize, default_stem: &str, ext: &str) -> Vec<PathBuf> {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(default_stem);
+    let digits = n.saturating_sub(1).to_string().len().max(3);
+    (0..n)
+        .map(|i| path.with_file_name(format!("{stem}_{i:0digits$}.{ext}")))
+        .collect()
+}
+
 /// Supported file formats
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FileFormat {
@@ -168,6 +216,12 @@ mod tests {
         assert_eq!(read_exact_vec(&mut c, 3).unwrap(), vec![3, 4, 5]);
     }
 
+    #[test]
+    fn numbered_paths_replace_the_extension() {
+        let paths = numbered_paths(Path::new("out/walk.vox"), 3, "frame", "obj");
+        assert_eq!(paths[2], Path::new("out/walk_002.obj"));
+    }
+
     #[test]
     fn skip_bytes_errors_on_short_stream() {
         let mut c = Cursor::new(vec![0u8; 4]);
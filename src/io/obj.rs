@@ -1,4 +1,5 @@
-//! Wavefront OBJ export.
+//! Wavefront OBJ export, and a minimal reader for showing an OBJ as a
+//! ghost reference ([`import_obj_mesh`]).
 //!
 //! Walks every chunk in the world, re-meshes it with the `GreedyMesher`
 //! (the same path used at render time), and writes the combined
@@ -18,18 +19,25 @@
 //! meshing (TODO) would shrink output a lot more than dedup would.
 
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufRead, BufWriter, Write};
 use std::path::Path;
 
 use thiserror::Error;
 
 use crate::core::World;
-use crate::mesh::{mesh_world_smoothed, ChunkMesh, GreedyMesher, Mesher};
+use crate::mesh::{mesh_world_smoothed, ChunkMesh, GreedyMesher, Mesher, Vertex};
+
+use super::export::{bake_atlas, bake_transform, mesh_parts, ColorStorage, ExportOptions};
+use super::GlbColors;
 
 #[derive(Debug, Error)]
 pub enum ObjError {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("texture encoding failed: {0}")]
+    Image(#[from] ::image::ImageError),
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
 }
 
 /// Summary stats from an OBJ export. Used by the UI to surface a
@@ -39,6 +47,10 @@ pub struct ObjStats {
     pub vertex_count: usize,
     pub triangle_count: usize,
     pub chunk_count: usize,
+    /// Width × height of the baked color atlas written beside the OBJ
+    /// (`<stem>.png` + `<stem>.mtl`), for [`ColorStorage::Atlas`]
+    /// exports.
+    pub atlas_size: Option<[u32; 2]>,
 }
 
 /// Export the current world to a Wavefront OBJ at `path`.
@@ -155,6 +167,7 @@ pub fn export_obj_smoothed(
         vertex_count: mesh.vertex_count(),
         triangle_count: mesh.triangle_count(),
         chunk_count: if mesh.is_empty() { 0 } else { 1 },
+        atlas_size: None,
     };
 
     let file = File::create(path)?;
@@ -173,6 +186,81 @@ pub fn export_obj_smoothed(
     Ok(stats)
 }
 
+/// Export per the Export dialog's [`ExportOptions`]: the chosen surface
+/// and color mode, as one `g Voxelith` group or one `g chunk_x_y_z`
+/// group per chunk. OBJ has no node hierarchy, so the pivot / up-axis /
+/// unit-scale placement is baked into the written positions and
+/// normals. Material groups within a part are written back to back
+/// under the part's `g` line.
+///
+/// With [`ColorStorage::Atlas`] the colors go to a texture instead:
+/// `<stem>.png` (the atlas) and `<stem>.mtl` (one `Voxelith` material
+/// mapping it) are written next to `path`, vertices lose their RGB and
+/// faces become `f v/vt/vn`.
+pub fn export_obj_with_options(
+    world: &World,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<ObjStats, ObjError> {
+    let (mut parts, chunk_count) = mesh_parts(world, options);
+    let atlas = match options.color_storage {
+        ColorStorage::Vertex => None,
+        ColorStorage::Atlas if parts.is_empty() => None,
+        ColorStorage::Atlas => Some(bake_atlas(&mut parts)),
+    };
+    bake_transform(&mut parts, options.transform);
+    let meshes = || parts.iter().flat_map(|p| p.groups.iter().map(|(_, m)| m));
+    let stats = ObjStats {
+        vertex_count: meshes().map(ChunkMesh::vertex_count).sum(),
+        triangle_count: meshes().map(ChunkMesh::triangle_count).sum(),
+        chunk_count,
+        atlas_size: atlas.as_ref().map(|a| [a.width, a.height]),
+    };
+
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Voxelith")
+        .to_string();
+    if let Some(atlas) = &atlas {
+        std::fs::write(path.with_file_name(format!("{stem}.png")), atlas.png_bytes()?)?;
+        let mut mtl = BufWriter::new(File::create(path.with_file_name(format!("{stem}.mtl")))?);
+        writeln!(mtl, "# Voxelith OBJ export color atlas")?;
+        writeln!(mtl, "newmtl Voxelith")?;
+        writeln!(mtl, "Kd 1.000 1.000 1.000")?;
+        writeln!(mtl, "map_Kd {stem}.png")?;
+        mtl.flush()?;
+    }
+
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    writeln!(writer, "# Voxelith OBJ export")?;
+    writeln!(
+        writer,
+        "# vertices: {}, triangles: {}, chunks: {}",
+        stats.vertex_count, stats.triangle_count, stats.chunk_count
+    )?;
+    if atlas.is_some() {
+        writeln!(writer, "mtllib {stem}.mtl")?;
+    }
+    writeln!(writer, "o Voxelith")?;
+    if atlas.is_some() {
+        writeln!(writer, "usemtl Voxelith")?;
+    }
+
+    let mut base: usize = 1;
+    for part in &parts {
+        writeln!(writer, "g {}", part.name)?;
+        for (i, (_, mesh)) in part.groups.iter().enumerate() {
+            let uvs = part.uvs.get(i).map(Vec::as_slice);
+            write_obj_mesh(mesh, base, options.colors, uvs, &mut writer)?;
+            base += mesh.vertex_count();
+        }
+    }
+    writer.flush()?;
+    Ok(stats)
+}
+
 /// Write a single combined `ChunkMesh` to an OBJ writer in the same
 /// format `export_obj` uses per chunk: vertex positions with embedded
 /// colors, then per-vertex normals, then triangle face lines indexed
@@ -181,18 +269,49 @@ fn write_obj_combined_mesh<W: Write>(
     mesh: &ChunkMesh,
     writer: &mut W,
 ) -> Result<(), ObjError> {
-    for v in &mesh.vertices {
-        let c = v.baked_color();
-        writeln!(
-            writer,
-            "v {:.4} {:.4} {:.4} {:.3} {:.3} {:.3}",
-            v.position[0],
-            v.position[1],
-            v.position[2],
-            c[0],
-            c[1],
-            c[2],
-        )?;
+    write_obj_mesh(mesh, 1, GlbColors::Shaded, None, writer)
+}
+
+/// Write `mesh`'s `v` / `vn` / `f` lines, with its first vertex at OBJ
+/// index `base` (1-based, running across the file). `colors` picks
+/// `Vertex::baked_color` or the raw `Vertex::color`. With atlas `uvs`
+/// (one per vertex) the vertices go out without color and each gets a
+/// `vt` line (V flipped to OBJ's bottom-left origin) instead.
+fn write_obj_mesh<W: Write>(
+    mesh: &ChunkMesh,
+    base: usize,
+    colors: GlbColors,
+    uvs: Option<&[[f32; 2]]>,
+    writer: &mut W,
+) -> Result<(), ObjError> {
+    if let Some(uvs) = uvs {
+        for v in &mesh.vertices {
+            writeln!(
+                writer,
+                "v {:.4} {:.4} {:.4}",
+                v.position[0], v.position[1], v.position[2]
+            )?;
+        }
+        for uv in uvs {
+            writeln!(writer, "vt {:.6} {:.6}", uv[0], 1.0 - uv[1])?;
+        }
+    } else {
+        for v in &mesh.vertices {
+            let c = match colors {
+                GlbColors::Shaded => v.baked_color(),
+                GlbColors::Flat => v.color,
+            };
+            writeln!(
+                writer,
+                "v {:.4} {:.4} {:.4} {:.3} {:.3} {:.3}",
+                v.position[0],
+                v.position[1],
+                v.position[2],
+                c[0],
+                c[1],
+                c[2],
+            )?;
+        }
     }
     for v in &mesh.vertices {
         writeln!(
@@ -202,19 +321,86 @@ fn write_obj_combined_mesh<W: Write>(
         )?;
     }
     for tri in mesh.indices.chunks_exact(3) {
-        // OBJ is 1-indexed.
-        let a = tri[0] as usize + 1;
-        let b = tri[1] as usize + 1;
-        let c = tri[2] as usize + 1;
-        writeln!(writer, "f {a}//{a} {b}//{b} {c}//{c}")?;
+        let a = base + tri[0] as usize;
+        let b = base + tri[1] as usize;
+        let c = base + tri[2] as usize;
+        if uvs.is_some() {
+            writeln!(writer, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}")?;
+        } else {
+            writeln!(writer, "f {a}//{a} {b}//{b} {c}//{c}")?;
+        }
     }
     Ok(())
 }
 
+/// Read the triangles of an OBJ for display: every face, fanned into
+/// triangles, flat-shaded. Vertices carrying the `v x y z r g b`
+/// extension keep their color; the rest take `color`. Everything but
+/// vertices and faces (normals, UVs, materials, groups) is ignored.
+pub fn import_obj_mesh<R: BufRead>(reader: R, color: [f32; 4]) -> Result<ChunkMesh, ObjError> {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut colors: Vec<[f32; 4]> = Vec::new();
+    let mut mesh = ChunkMesh::new(crate::core::ChunkPos::ZERO);
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        let parse_err = |message: String| ObjError::Parse { line: n + 1, message };
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let values = tokens
+                    .map(|t| t.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| parse_err(format!("bad vertex: {e}")))?;
+                let [x, y, z, rest @ ..] = values.as_slice() else {
+                    return Err(parse_err("vertex needs x, y and z".into()));
+                };
+                positions.push([*x, *y, *z]);
+                colors.push(match rest {
+                    [r, g, b, ..] => [*r, *g, *b, color[3]],
+                    _ => color,
+                });
+            }
+            Some("f") => {
+                let corners = tokens
+                    .map(|t| {
+                        // `v`, `v/vt`, `v//vn` or `v/vt/vn`; 1-based, or
+                        // negative to count back from the last vertex.
+                        let index = t.split('/').next().unwrap_or_default();
+                        let i: i64 = index
+                            .parse()
+                            .map_err(|_| parse_err(format!("bad face index {t:?}")))?;
+                        let resolved = if i < 0 { positions.len() as i64 + i } else { i - 1 };
+                        usize::try_from(resolved)
+                            .ok()
+                            .filter(|&i| i < positions.len())
+                            .ok_or_else(|| parse_err(format!("face index {i} out of range")))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if corners.len() < 3 {
+                    return Err(parse_err("face needs at least 3 vertices".into()));
+                }
+                for k in 1..corners.len() - 1 {
+                    let tri = [corners[0], corners[k], corners[k + 1]];
+                    let [a, b, c] = tri.map(|i| glam::Vec3::from(positions[i]));
+                    let normal = (b - a).cross(c - a).normalize_or_zero().to_array();
+                    let base = mesh.vertices.len() as u32;
+                    for i in tri {
+                        mesh.vertices.push(Vertex::new(positions[i], normal, colors[i]));
+                    }
+                    mesh.indices.extend([base, base + 1, base + 2]);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(mesh)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::core::Voxel;
+    use crate::io::{ExportTransform, Pivot, UpAxis};
     use std::io::Read;
 
     #[test]
@@ -355,4 +541,136 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_options_bake_pivot_scale_and_up_axis_into_positions() {
+        // One voxel spans [0,1]³. Base-center pivot = (0.5, 0, 0.5),
+        // scale 2 → [-1,1] × [0,2] × [-1,1]; Z-up then maps +Y to +Z.
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.clear_dirty_flags();
+
+        let options = ExportOptions {
+            transform: ExportTransform {
+                pivot: Pivot::BaseCenter,
+                up_axis: UpAxis::Z,
+                unit_scale: 2.0,
+            },
+            ..Default::default()
+        };
+        let path = std::env::temp_dir().join("voxelith_options_xform.obj");
+        let stats = export_obj_with_options(&world, &path, &options).unwrap();
+        assert_eq!(stats.triangle_count, 12);
+        let s = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let mut lo = [f32::INFINITY; 3];
+        let mut hi = [f32::NEG_INFINITY; 3];
+        for line in s.lines().filter(|l| l.starts_with("v ")) {
+            let p: Vec<f32> = line[2..]
+                .split_whitespace()
+                .take(3)
+                .map(|t| t.parse().unwrap())
+                .collect();
+            for axis in 0..3 {
+                lo[axis] = lo[axis].min(p[axis]);
+                hi[axis] = hi[axis].max(p[axis]);
+            }
+        }
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        assert!(close(lo[0], -1.0) && close(hi[0], 1.0), "x {lo:?} {hi:?}");
+        assert!(close(lo[1], -1.0) && close(hi[1], 1.0), "y {lo:?} {hi:?}");
+        assert!(close(lo[2], 0.0) && close(hi[2], 2.0), "z {lo:?} {hi:?}");
+    }
+
+    #[test]
+    fn test_options_merge_chunks_controls_groups() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(40, 0, 0, Voxel::from_rgb(0, 255, 0));
+        world.clear_dirty_flags();
+
+        let path = std::env::temp_dir().join("voxelith_options_groups.obj");
+        let merged = export_obj_with_options(&world, &path, &ExportOptions::default()).unwrap();
+        let s = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(merged.chunk_count, 2);
+        assert_eq!(s.lines().filter(|l| l.starts_with("g ")).count(), 1);
+        assert!(s.contains("g Voxelith"));
+
+        let options = ExportOptions {
+            merge_chunks: false,
+            ..Default::default()
+        };
+        let split = export_obj_with_options(&world, &path, &options).unwrap();
+        let s = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(split.triangle_count, merged.triangle_count);
+        assert!(s.contains("g chunk_0_0_0"));
+        assert!(s.contains("g chunk_1_0_0"));
+    }
+
+    #[test]
+    fn test_options_atlas_writes_png_mtl_and_uv_faces() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.clear_dirty_flags();
+
+        let dir = std::env::temp_dir().join("voxelith_obj_atlas");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("model.obj");
+        let options = ExportOptions {
+            color_storage: ColorStorage::Atlas,
+            ..Default::default()
+        };
+        let stats = export_obj_with_options(&world, &path, &options).unwrap();
+        assert!(stats.atlas_size.is_some());
+
+        let s = std::fs::read_to_string(&path).unwrap();
+        assert!(s.contains("mtllib model.mtl"));
+        assert!(s.contains("usemtl Voxelith"));
+        assert_eq!(s.lines().filter(|l| l.starts_with("vt ")).count(), stats.vertex_count);
+        let face = s.lines().find(|l| l.starts_with("f ")).unwrap();
+        assert!(!face.contains("//"), "faces reference their vt: {face}");
+        let mtl = std::fs::read_to_string(dir.join("model.mtl")).unwrap();
+        assert!(mtl.contains("map_Kd model.png"));
+        let png = std::fs::read(dir.join("model.png")).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_fans_polygons_and_keeps_vertex_colors() {
+        let obj = "# quad\nv 0 0 0 1 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2//1 3//1 -1//1\n";
+        let gray = [0.5, 0.5, 0.5, 1.0];
+        let mesh = import_obj_mesh(obj.as_bytes(), gray).unwrap();
+        assert_eq!(mesh.triangle_count(), 2);
+        assert_eq!(mesh.vertices[0].color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(mesh.vertices[1].color, gray);
+        assert_eq!(mesh.vertices[0].normal, [0.0, 0.0, 1.0]);
+        // Second triangle: corners 1, 3, 4.
+        assert_eq!(mesh.vertices[5].position, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn import_rejects_bad_faces() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 4\n";
+        let err = import_obj_mesh(obj.as_bytes(), [1.0; 4]).unwrap_err();
+        assert!(matches!(err, ObjError::Parse { line: 4, .. }), "{err}");
+        let err = import_obj_mesh("v 0 0\n".as_bytes(), [1.0; 4]).unwrap_err();
+        assert!(matches!(err, ObjError::Parse { line: 1, .. }), "{err}");
+    }
+
+    #[test]
+    fn import_reads_our_own_export() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(1, 0, 0, Voxel::from_rgb(0, 255, 0));
+        let path = std::env::temp_dir().join("voxelith_obj_reimport.obj");
+        let stats = export_obj(&world, &path).unwrap();
+        let file = std::io::BufReader::new(File::open(&path).unwrap());
+        let mesh = import_obj_mesh(file, [1.0; 4]).unwrap();
+        assert_eq!(mesh.triangle_count(), stats.triangle_count);
+        let _ = std::fs::remove_file(&path);
+    }
 }
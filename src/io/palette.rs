@@ -0,0 +1,356 @@
+//! Palette files: GIMP `.gpl` and JASC `.pal` import / export, plus
+//! import-only palettes from MagicaVoxel `.vox` files and `.png`
+//! swatch strips.
+//!
+//! The two text formats are small and line-based, so palettes move freely
+//! between Voxelith, GIMP / Krita / Aseprite (`.gpl`) and Paint Shop Pro
+//! / most pixel-art tools (`.pal`):
+//!
+//! ```text
+//! GIMP Palette          JASC-PAL
+//! Name: Voxelith        0100
+//! Columns: 5            3
+//! #                     255 0 0
+//! 255   0   0 Red       0 255 0
+//! ```
+//!
+//! Neither text format carries alpha, so colors load opaque and export
+//! drops alpha. A `.vox` contributes only its `RGBA` chunk (geometry is
+//! never read), and a `.png` is read pixel by pixel, left to right and
+//! top to bottom — the layout of MagicaVoxel's 256×1 palette export and
+//! the common 16×N swatch strip. Only the RGB channels of a [`Voxel`]
+//! are read or written; material flags and tint zones stay with the
+//! brush, as with palette picks in the editor.
+
+use std::io::{BufRead, Read, Write};
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::core::Voxel;
+
+/// Errors that can occur when reading/writing palette files
+#[derive(Debug, Error)]
+pub enum PaletteError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Not a palette file: expected a `{0}` header")]
+    InvalidHeader(&'static str),
+
+    #[error("Line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("Palette contains no colors")]
+    Empty,
+
+    #[error("Palette image is {width}x{height}; expected a strip of at most {MAX_IMAGE_PIXELS} swatches (e.g. 16xN)")]
+    ImageTooLarge { width: u32, height: u32 },
+
+    #[error("VOX error: {0}")]
+    Vox(#[from] super::VoxError),
+
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("Unsupported palette file type")]
+    UnsupportedFormat,
+}
+
+/// Largest image accepted as a palette strip: 16 × 256 swatches. Anything
+/// bigger is a picture, not a palette, and would flood the editor.
+pub const MAX_IMAGE_PIXELS: u32 = 4096;
+
+/// Palette file flavor, picked from the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// GIMP palette (`.gpl`)
+    Gpl,
+    /// JASC / Paint Shop Pro palette (`.pal`)
+    Pal,
+    /// MagicaVoxel model (`.vox`) — palette import only
+    Vox,
+    /// Swatch image (`.png`) — import only
+    Png,
+}
+
+impl PaletteFormat {
+    /// Detect format from file extension
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "gpl" => Some(Self::Gpl),
+            "pal" => Some(Self::Pal),
+            "vox" => Some(Self::Vox),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+
+    /// Whether [`export_palette`] can write this format.
+    pub fn can_export(&self) -> bool {
+        matches!(self, Self::Gpl | Self::Pal)
+    }
+}
+
+/// Parse one `R G B` triple from whitespace-split fields. Extra fields
+/// (the GIMP color name) are ignored by the caller.
+fn parse_rgb<'a>(
+    mut fields: impl Iterator<Item = &'a str>,
+    line: usize,
+) -> Result<Voxel, PaletteError> {
+    let mut channel = |name: &str| -> Result<u8, PaletteError> {
+        let field = fields.next().ok_or_else(|| PaletteError::Parse {
+            line,
+            message: format!("missing {name} channel"),
+        })?;
+        field.parse::<u8>().map_err(|_| PaletteError::Parse {
+            line,
+            message: format!("{name} channel `{field}` is not 0-255"),
+        })
+    };
+    let r = channel("red")?;
+    let g = channel("green")?;
+    let b = channel("blue")?;
+    Ok(Voxel::from_rgb(r, g, b))
+}
+
+/// Read a GIMP `.gpl` palette. `Name:` / `Columns:` headers and `#`
+/// comments are skipped; everything after the RGB triple on a color
+/// line is its (ignored) name.
+pub fn read_gpl<R: BufRead>(reader: R) -> Result<Vec<Voxel>, PaletteError> {
+    let mut lines = reader.lines();
+    match lines.next().transpose()? {
+        Some(header) if header.trim() == "GIMP Palette" => {}
+        _ => return Err(PaletteError::InvalidHeader("GIMP Palette")),
+    }
+
+    let mut colors = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("Name:")
+            || trimmed.starts_with("Columns:")
+        {
+            continue;
+        }
+        // +2: one for the header, one for 1-based numbering.
+        colors.push(parse_rgb(trimmed.split_whitespace(), i + 2)?);
+    }
+    if colors.is_empty() {
+        return Err(PaletteError::Empty);
+    }
+    Ok(colors)
+}
+
+/// Write `colors` as a GIMP `.gpl` palette named `name`. Colors are
+/// labelled by their hex code since the editor palette has no names.
+pub fn write_gpl<W: Write>(writer: &mut W, name: &str, colors: &[Voxel]) -> Result<(), PaletteError> {
+    writeln!(writer, "GIMP Palette")?;
+    writeln!(writer, "Name: {name}")?;
+    writeln!(writer, "Columns: 5")?;
+    writeln!(writer, "#")?;
+    for c in colors {
+        writeln!(
+            writer,
+            "{:3} {:3} {:3}\t#{:02X}{:02X}{:02X}",
+            c.r, c.g, c.b, c.r, c.g, c.b
+        )?;
+    }
+    Ok(())
+}
+
+/// Read a JASC `.pal` palette. The declared count is only a hint: a
+/// file that lists more or fewer colors loads whatever is there, so
+/// hand-edited palettes don't fail on a stale count.
+pub fn read_pal<R: BufRead>(reader: R) -> Result<Vec<Voxel>, PaletteError> {
+    let mut lines = reader.lines();
+    match lines.next().transpose()? {
+        Some(header) if header.trim() == "JASC-PAL" => {}
+        _ => return Err(PaletteError::InvalidHeader("JASC-PAL")),
+    }
+    // Version ("0100") and color count.
+    for _ in 0..2 {
+        if lines.next().transpose()?.is_none() {
+            return Err(PaletteError::Empty);
+        }
+    }
+
+    let mut colors = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        // +4: three header lines, plus 1-based numbering.
+        colors.push(parse_rgb(trimmed.split_whitespace(), i + 4)?);
+    }
+    if colors.is_empty() {
+        return Err(PaletteError::Empty);
+    }
+    Ok(colors)
+}
+
+/// Write `colors` as a JASC `.pal` palette.
+pub fn write_pal<W: Write>(writer: &mut W, colors: &[Voxel]) -> Result<(), PaletteError> {
+    writeln!(writer, "JASC-PAL")?;
+    writeln!(writer, "0100")?;
+    writeln!(writer, "{}", colors.len())?;
+    for c in colors {
+        writeln!(writer, "{} {} {}", c.r, c.g, c.b)?;
+    }
+    Ok(())
+}
+
+/// Read the palette of a MagicaVoxel file: its 255 colors in slot
+/// order, geometry ignored.
+pub fn read_vox<R: Read>(reader: &mut R) -> Result<Vec<Voxel>, PaletteError> {
+    let colors = super::read_vox_palette(reader)?;
+    Ok(colors
+        .iter()
+        .map(|c| Voxel::from_rgb(c[0], c[1], c[2]))
+        .collect())
+}
+
+/// Read a PNG swatch strip: every pixel is one color, row-major.
+/// Fully transparent pixels are padding and skipped.
+pub fn read_png(bytes: &[u8]) -> Result<Vec<Voxel>, PaletteError> {
+    let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png)?;
+    let (width, height) = (image.width(), image.height());
+    if width.saturating_mul(height) > MAX_IMAGE_PIXELS {
+        return Err(PaletteError::ImageTooLarge { width, height });
+    }
+    let colors: Vec<Voxel> = image
+        .to_rgba8()
+        .pixels()
+        .filter(|p| p[3] != 0)
+        .map(|p| Voxel::from_rgb(p[0], p[1], p[2]))
+        .collect();
+    if colors.is_empty() {
+        return Err(PaletteError::Empty);
+    }
+    Ok(colors)
+}
+
+/// Load a palette file, choosing the parser from its extension.
+pub fn import_palette(path: &Path) -> Result<Vec<Voxel>, PaletteError> {
+    let format = PaletteFormat::from_extension(path).ok_or(PaletteError::UnsupportedFormat)?;
+    match format {
+        PaletteFormat::Png => return read_png(&std::fs::read(path)?),
+        PaletteFormat::Vox | PaletteFormat::Gpl | PaletteFormat::Pal => {}
+    }
+    let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    match format {
+        PaletteFormat::Gpl => read_gpl(reader),
+        PaletteFormat::Pal => read_pal(reader),
+        PaletteFormat::Vox => read_vox(&mut reader),
+        PaletteFormat::Png => unreachable!("handled above"),
+    }
+}
+
+/// Save `colors` to a palette file, choosing the format from its
+/// extension. A `.gpl` is named after the file stem; `.vox` / `.png`
+/// are import-only and rejected.
+pub fn export_palette(path: &Path, colors: &[Voxel]) -> Result<(), PaletteError> {
+    let format = PaletteFormat::from_extension(path)
+        .filter(PaletteFormat::can_export)
+        .ok_or(PaletteError::UnsupportedFormat)?;
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    match format {
+        PaletteFormat::Gpl => {
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Voxelith");
+            write_gpl(&mut writer, name, colors)?;
+        }
+        PaletteFormat::Pal => write_pal(&mut writer, colors)?,
+        PaletteFormat::Vox | PaletteFormat::Png => unreachable!("filtered by can_export"),
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn sample() -> Vec<Voxel> {
+        vec![
+            Voxel::from_rgb(255, 0, 0),
+            Voxel::from_rgb(12, 200, 7),
+            Voxel::from_rgb(0, 0, 0),
+        ]
+    }
+
+    fn rgb(colors: &[Voxel]) -> Vec<(u8, u8, u8)> {
+        colors.iter().map(|c| (c.r, c.g, c.b)).collect()
+    }
+
+    #[test]
+    fn test_gpl_roundtrip() {
+        let mut buf = Vec::new();
+        write_gpl(&mut buf, "Test", &sample()).unwrap();
+        let loaded = read_gpl(Cursor::new(buf)).unwrap();
+        assert_eq!(rgb(&loaded), rgb(&sample()));
+    }
+
+    #[test]
+    fn test_pal_roundtrip() {
+        let mut buf = Vec::new();
+        write_pal(&mut buf, &sample()).unwrap();
+        let loaded = read_pal(Cursor::new(buf)).unwrap();
+        assert_eq!(rgb(&loaded), rgb(&sample()));
+    }
+
+    #[test]
+    fn test_gpl_skips_headers_comments_and_names() {
+        let text = "GIMP Palette\nName: Earth\nColumns: 4\n# a comment\n\n139  90  43\tBrown\n  76 153   0 Grass green\n";
+        let loaded = read_gpl(Cursor::new(text)).unwrap();
+        assert_eq!(rgb(&loaded), vec![(139, 90, 43), (76, 153, 0)]);
+    }
+
+    #[test]
+    fn test_rejects_bad_header_and_bad_channel() {
+        assert!(matches!(
+            read_gpl(Cursor::new("JASC-PAL\n0100\n0\n")),
+            Err(PaletteError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            read_pal(Cursor::new("JASC-PAL\n0100\n1\n300 0 0\n")),
+            Err(PaletteError::Parse { line: 4, .. })
+        ));
+        assert!(matches!(
+            read_gpl(Cursor::new("GIMP Palette\nName: empty\n")),
+            Err(PaletteError::Empty)
+        ));
+    }
+
+    #[test]
+    fn test_png_strip_reads_row_major_and_skips_transparent() {
+        let mut strip = image::RgbaImage::new(2, 2);
+        strip.put_pixel(0, 0, image::Rgba([255, 0, 0, 255]));
+        strip.put_pixel(1, 0, image::Rgba([0, 255, 0, 255]));
+        strip.put_pixel(0, 1, image::Rgba([0, 0, 255, 255]));
+        strip.put_pixel(1, 1, image::Rgba([9, 9, 9, 0]));
+        let mut png = Vec::new();
+        strip
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let loaded = read_png(&png).unwrap();
+        assert_eq!(rgb(&loaded), vec![(255, 0, 0), (0, 255, 0), (0, 0, 255)]);
+
+        let big = image::RgbaImage::new(128, 64);
+        let mut png = Vec::new();
+        big.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        assert!(matches!(
+            read_png(&png),
+            Err(PaletteError::ImageTooLarge { width: 128, height: 64 })
+        ));
+    }
+}
@@ -2,21 +2,66 @@
 //!
 //! Projects are saved as compressed binary files containing:
 //! - Project metadata (name, description, version)
-//! - World data (chunks with voxel data)
+//! - World data (chunks with voxel data), one chunk section per layer
 //! - Editor state (camera position, tool settings, palette)
-
-use crate::core::{Chunk, ChunkPos, Voxel, World, CHUNK_SIZE, CHUNK_VOLUME};
+//!
+//! Version 2 added layers: the first chunk section is still layer 0
+//! (so the layout up to there is unchanged), followed by a layer count
+//! and one chunk section per additional layer. Layer names, flags,
+//! world bounds and voxel counts ride in the JSON header's
+//! `EditorState::layers`, next to the full palette and the named
+//! sockets. Version 1 files load as a single unbounded layer; going
+//! the other way, the gzip body still matches the v1 layout up to the
+//! end of layer 0.
+//!
+//! Version 3 added an optional PNG thumbnail between the version and
+//! the gzip body: a `u32` byte length (0 = none) followed by the PNG
+//! bytes, stored raw since PNG is already compressed. Keeping it ahead
+//! of the body means [`read_thumbnail`] — for file browsers, shell
+//! extensions and the project browser — reads a few KiB off the front
+//! of the file without inflating any voxels.
+//!
+//! Version 4 added animation frames. The layer sections hold the
+//! current frame; after them comes a count of the *other* frames and,
+//! for each in order, a layer count and one chunk section per layer.
+//! Frame names, hold times and the current index ride in
+//! `EditorState::frames`. Older files load as a single frame, and an
+//! older reader of the v4 body still finds every layer of the current
+//! frame where it expects it.
+//!
+//! Encoding a [`Project`] from a scene only run-length encodes chunks;
+//! the gzip pass over them is the slow part of a save. A built project
+//! owns all its data, so [`save_project_in_background`] can compress
+//! and write it on another thread while the scene keeps changing.
+
+use crate::core::{
+    Background, Chunk, ChunkPos, Layer, Light, MaterialTable, ReferenceImage, ReferenceModel,
+    Scene, Voxel, World, WorldBounds, CHUNK_SIZE, CHUNK_VOLUME, DEFAULT_FRAME_MS,
+};
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
 use thiserror::Error;
 
 /// Project file magic bytes
 const PROJECT_MAGIC: [u8; 4] = [b'V', b'X', b'L', b'T'];
 /// Current project format version
-const PROJECT_VERSION: u32 = 1;
+const PROJECT_VERSION: u32 = 4;
+/// First version whose body carries extra layer chunk sections.
+const LAYERS_VERSION: u32 = 2;
+/// First version with the thumbnail block ahead of the gzip body.
+const THUMBNAIL_VERSION: u32 = 3;
+/// First version whose body carries the non-current animation frames.
+const FRAMES_VERSION: u32 = 4;
+/// Largest thumbnail block accepted on load. A 256² RGBA PNG is well
+/// under 256 KiB; the cap only stops a corrupt length from making us
+/// read a whole file's worth of "thumbnail".
+const MAX_THUMBNAIL_BYTES: u32 = 4 << 20;
 /// Cap for the chunk-vector capacity *hint* read from the file header.
 /// `chunk_count` is untrusted; the hint is only a preallocation
 /// optimization, so bounding it stops a corrupt file from requesting a
@@ -40,6 +85,8 @@ pub enum ProjectError {
     InvalidChunkData,
     #[error("Decompression error")]
     DecompressionError,
+    #[error("Thumbnail block too large ({0} bytes)")]
+    ThumbnailTooLarge(u32),
 }
 
 /// Project metadata
@@ -96,6 +143,123 @@ pub struct EditorState {
     /// doesn't need a bump because the addition is purely additive.
     #[serde(default)]
     pub sockets: Vec<SocketData>,
+    /// Name and flags of each layer, bottom first, parallel to the
+    /// file's chunk sections. Empty for pre-layer files, which load as
+    /// one default layer.
+    #[serde(default)]
+    pub layers: Vec<LayerData>,
+    /// Index of the layer that was active when saved.
+    #[serde(default)]
+    pub active_layer: usize,
+    /// Seed of the most recent applied run of each procedural generator,
+    /// keyed by `GeneratorMeta::id` (`"builtin.cave"`, ...). Generators
+    /// draw from `procgen::Pcg32`, so a recorded seed reproduces the
+    /// same voxels on any machine; opening the project loads these
+    /// back into the procgen panel.
+    #[serde(default)]
+    pub procgen_seeds: BTreeMap<String, u64>,
+    /// User camera bookmarks, one per slot (recalled with Ctrl+1..9).
+    /// `None` marks an empty slot, so slot numbers stay stable when
+    /// earlier ones are unused.
+    #[serde(default)]
+    pub camera_bookmarks: [Option<CameraBookmark>; CAMERA_BOOKMARK_SLOTS],
+    /// Material definitions by voxel material id. Files written before
+    /// materials existed load with just the default material, which
+    /// draws exactly like voxels did then.
+    #[serde(default)]
+    pub materials: MaterialTable,
+    /// Name and hold time of each animation frame, in order. Empty for
+    /// files from before frames, which load as one frame.
+    #[serde(default)]
+    pub frames: Vec<FrameData>,
+    /// Index of the frame the layer sections hold.
+    #[serde(default)]
+    pub current_frame: usize,
+    /// Rig bones (`editor::Rig`), in index order. Empty for files from
+    /// before rigging.
+    #[serde(default)]
+    pub bones: Vec<BoneData>,
+    /// Render ▸ Camera Path keys, in playback order.
+    #[serde(default)]
+    pub camera_path: Vec<CameraPathKey>,
+    /// The camera path loops from its last key back to the first.
+    #[serde(default)]
+    pub camera_path_closed: bool,
+    /// Viewport background. Files from before backgrounds load with
+    /// the default dark blue.
+    #[serde(default)]
+    pub background: Background,
+    /// Placed point and spot lights. Older files have none.
+    #[serde(default)]
+    pub lights: Vec<Light>,
+    /// Reference image planes. Older files have none.
+    #[serde(default)]
+    pub references: Vec<ReferenceImage>,
+    /// Model shown as a ghost. Older files have none.
+    #[serde(default)]
+    pub reference_model: Option<ReferenceModel>,
+}
+
+/// One fly-through keyframe (`render::CameraKey` as plain data, like
+/// [`CameraBookmark`]).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraPathKey {
+    pub position: [f32; 3],
+    pub target: [f32; 3],
+    /// Time on to the next key, in milliseconds.
+    pub duration_ms: u32,
+}
+
+/// Serializable form of an `editor::Bone`. Plain data for the same
+/// reason as [`SocketData`]; `parent` indexes this list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoneData {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub pivot: [i32; 3],
+    /// Pose offset in cells and Euler angles in degrees.
+    pub translation: [i32; 3],
+    pub rotation: [i32; 3],
+    pub cells: Vec<[i32; 3]>,
+}
+
+/// Serializable frame header (the voxels live in the chunk sections).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameData {
+    pub name: String,
+    pub duration_ms: u32,
+}
+
+/// Number of camera bookmark slots — one per digit key 1..9.
+pub const CAMERA_BOOKMARK_SLOTS: usize = 9;
+
+/// A saved camera pose. Position + target fix the perspective view
+/// (its zoom is the eye–target distance); `quad_zoom` carries the
+/// quad-view panes' orthographic zoom (`Renderer::quad_zoom`) so a
+/// recalled bookmark restores those too.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    pub position: [f32; 3],
+    pub target: [f32; 3],
+    pub quad_zoom: [f32; 3],
+}
+
+/// Serializable layer header (the voxels live in the chunk sections).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerData {
+    pub name: String,
+    pub visible: bool,
+    pub locked: bool,
+    /// Chunk bounds of a bounded layer world; `None` = unbounded.
+    /// Chunks outside the bounds are dropped on load.
+    #[serde(default)]
+    pub bounds: Option<WorldBounds>,
+    /// Solid voxels in the layer when saved. Lets a file browser show
+    /// stats from the JSON header alone, and flags chunk sections that
+    /// decoded short (a warning, not an error — the rest still loads).
+    /// `None` in files written before the field existed.
+    #[serde(default)]
+    pub voxel_count: Option<u64>,
 }
 
 /// Serializable form of an `editor::Socket` (name + position + outward
@@ -128,6 +292,17 @@ pub struct Project {
     /// Chunk data (serialized separately)
     #[serde(skip)]
     chunks: Vec<ChunkData>,
+    /// Chunk data of layers 1.. (layer 0 is `chunks`)
+    #[serde(skip)]
+    extra_layers: Vec<Vec<ChunkData>>,
+    /// Every frame but the current one, in order, each as one chunk
+    /// section per layer.
+    #[serde(skip)]
+    stored_frames: Vec<Vec<Vec<ChunkData>>>,
+    /// PNG preview image, written ahead of the compressed body. `None`
+    /// for headless saves and files from before version 3.
+    #[serde(skip)]
+    pub thumbnail: Option<Vec<u8>>,
 }
 
 impl Project {
@@ -137,6 +312,9 @@ impl Project {
             metadata: ProjectMetadata::default(),
             editor_state: EditorState::default(),
             chunks: Vec::new(),
+            extra_layers: Vec::new(),
+            stored_frames: Vec::new(),
+            thumbnail: None,
         }
     }
 
@@ -149,35 +327,120 @@ impl Project {
     pub fn from_world_with_state(world: &World, editor_state: EditorState) -> Self {
         let mut project = Self::new();
         project.editor_state = editor_state;
+        project.chunks = encode_world(world);
+        project
+    }
 
-        for (pos, chunk_lock) in world.chunks() {
-            let chunk = chunk_lock.read();
-            if !chunk.is_empty() {
-                let rle_data = rle_encode_chunk(&chunk);
-                project.chunks.push(ChunkData {
-                    pos: *pos,
-                    rle_data,
-                });
-            }
-        }
-
+    /// Create project from every layer and frame of `scene`, recording
+    /// layer names / flags, frame names / hold times and the active
+    /// indices into the editor state.
+    pub fn from_scene_with_state(scene: &Scene, mut editor_state: EditorState) -> Self {
+        editor_state.layers = scene
+            .layers()
+            .iter()
+            .map(|l| LayerData {
+                name: l.name.clone(),
+                visible: l.visible,
+                locked: l.locked,
+                bounds: l.world.bounds().copied(),
+                voxel_count: Some(solid_voxels(&l.world)),
+            })
+            .collect();
+        editor_state.active_layer = scene.active_index();
+        editor_state.frames = scene
+            .frames()
+            .iter()
+            .map(|f| FrameData {
+                name: f.name.clone(),
+                duration_ms: f.duration_ms,
+            })
+            .collect();
+        editor_state.current_frame = scene.current_frame();
+        let mut project = Self::new();
+        project.editor_state = editor_state;
+        let mut sections = scene.layers().iter().map(|l| encode_world(&l.world));
+        project.chunks = sections.next().unwrap_or_default();
+        project.extra_layers = sections.collect();
+        project.stored_frames = (0..scene.frames().len())
+            .filter(|&f| f != scene.current_frame())
+            .map(|f| {
+                (0..scene.len())
+                    .map(|l| scene.frame_layer_world(f, l).map(encode_world).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
         project
     }
 
-    /// Convert project to world
+    /// Convert project to world. Only layer 0 — use [`Self::to_scene`]
+    /// for layered projects.
     pub fn to_world(&self) -> World {
-        let mut world = World::new();
+        let bounds = self.editor_state.layers.first().and_then(|l| l.bounds);
+        decode_world(&self.chunks, bounds)
+    }
 
-        for chunk_data in &self.chunks {
-            if let Some(chunk) = rle_decode_chunk(&chunk_data.rle_data) {
-                // For unbounded worlds, get_or_create_chunk always returns Some
-                if let Some(chunk_lock) = world.get_or_create_chunk(chunk_data.pos) {
-                    *chunk_lock.write() = chunk;
+    /// Convert project to a layered scene. Missing layer headers
+    /// (pre-layer files) fall back to default names and flags.
+    pub fn to_scene(&self) -> Scene {
+        let sections = std::iter::once(&self.chunks).chain(&self.extra_layers);
+        let layers = sections
+            .enumerate()
+            .map(|(i, chunks)| {
+                let data = self.editor_state.layers.get(i);
+                let world = decode_world(chunks, data.and_then(|d| d.bounds));
+                let mut layer = Layer::new(format!("Layer {}", i + 1), world);
+                if let Some(data) = data {
+                    layer.name = data.name.clone();
+                    layer.visible = data.visible;
+                    layer.locked = data.locked;
+                    let found = solid_voxels(&layer.world);
+                    if data.voxel_count.is_some_and(|n| n != found) {
+                        log::warn!(
+                            "Layer \"{}\": header says {:?} voxels, decoded {}",
+                            data.name,
+                            data.voxel_count,
+                            found
+                        );
+                    }
                 }
-            }
-        }
+                layer
+            })
+            .collect();
+        let mut scene = Scene::from_layers(layers);
+        scene.set_active(self.editor_state.active_layer);
+        self.restore_frames(&mut scene);
+        scene
+    }
 
-        world
+    /// Add the stored frames to a freshly built `scene`, whose only
+    /// frame holds the layer sections, and put that one back at its
+    /// saved position.
+    fn restore_frames(&self, scene: &mut Scene) {
+        let headers = &self.editor_state.frames;
+        let current = self.editor_state.current_frame.min(self.stored_frames.len());
+        let header = |i: usize| {
+            headers.get(i).cloned().unwrap_or_else(|| FrameData {
+                name: format!("Frame {}", i + 1),
+                duration_ms: DEFAULT_FRAME_MS,
+            })
+        };
+        let here = header(current);
+        scene.rename_frame(0, here.name);
+        scene.set_frame_duration(0, here.duration_ms);
+        let others = (0..=self.stored_frames.len()).filter(|&i| i != current);
+        for (i, sections) in others.zip(&self.stored_frames) {
+            let worlds = sections
+                .iter()
+                .enumerate()
+                .map(|(l, chunks)| {
+                    let bounds = self.editor_state.layers.get(l).and_then(|d| d.bounds);
+                    decode_world(chunks, bounds)
+                })
+                .collect();
+            let data = header(i);
+            scene.push_frame(data.name, data.duration_ms, worlds);
+        }
+        scene.move_frame(0, current);
     }
 
     /// Save project to writer
@@ -186,6 +449,11 @@ impl Project {
         writer.write_all(&PROJECT_MAGIC)?;
         writer.write_all(&PROJECT_VERSION.to_le_bytes())?;
 
+        // Thumbnail block (length 0 when there is none)
+        let thumbnail = self.thumbnail.as_deref().unwrap_or_default();
+        writer.write_all(&(thumbnail.len() as u32).to_le_bytes())?;
+        writer.write_all(thumbnail)?;
+
         // Create compressed stream
         let mut encoder = GzEncoder::new(writer, Compression::default());
 
@@ -195,19 +463,20 @@ impl Project {
         encoder.write_all(&(header_bytes.len() as u32).to_le_bytes())?;
         encoder.write_all(header_bytes)?;
 
-        // Write chunk count
-        encoder.write_all(&(self.chunks.len() as u32).to_le_bytes())?;
-
-        // Write each chunk
-        for chunk_data in &self.chunks {
-            // Write position
-            encoder.write_all(&chunk_data.pos.x.to_le_bytes())?;
-            encoder.write_all(&chunk_data.pos.y.to_le_bytes())?;
-            encoder.write_all(&chunk_data.pos.z.to_le_bytes())?;
+        // Layer 0, then the extra layers
+        write_chunk_section(&mut encoder, &self.chunks)?;
+        encoder.write_all(&(self.extra_layers.len() as u32).to_le_bytes())?;
+        for chunks in &self.extra_layers {
+            write_chunk_section(&mut encoder, chunks)?;
+        }
 
-            // Write RLE data
-            encoder.write_all(&(chunk_data.rle_data.len() as u32).to_le_bytes())?;
-            encoder.write_all(&chunk_data.rle_data)?;
+        // The other animation frames
+        encoder.write_all(&(self.stored_frames.len() as u32).to_le_bytes())?;
+        for frame in &self.stored_frames {
+            encoder.write_all(&(frame.len() as u32).to_le_bytes())?;
+            for chunks in frame {
+                write_chunk_section(&mut encoder, chunks)?;
+            }
         }
 
         encoder.finish()?;
@@ -231,6 +500,12 @@ impl Project {
             return Err(ProjectError::UnsupportedVersion(version));
         }
 
+        let thumbnail = if version >= THUMBNAIL_VERSION {
+            read_thumbnail_block(reader)?
+        } else {
+            None
+        };
+
         // Decompress
         let mut decoder = GzDecoder::new(reader);
 
@@ -243,43 +518,54 @@ impl Project {
         let (metadata, editor_state): (ProjectMetadata, EditorState) =
             serde_json::from_slice(&header_bytes)?;
 
-        // Read chunk count
-        decoder.read_exact(&mut len_buf)?;
-        let chunk_count = u32::from_le_bytes(len_buf) as usize;
-
-        // Read chunks. Cap the capacity hint so a bogus chunk_count from
-        // a corrupt file can't request a huge eager allocation; the loop
-        // below still reads the full count and fails via read_exact if
-        // the data runs short.
-        let mut chunks = Vec::with_capacity(chunk_count.min(MAX_CHUNK_HINT));
-        for _ in 0..chunk_count {
-            // Read position
-            let mut pos_buf = [0u8; 4];
-            decoder.read_exact(&mut pos_buf)?;
-            let x = i32::from_le_bytes(pos_buf);
-            decoder.read_exact(&mut pos_buf)?;
-            let y = i32::from_le_bytes(pos_buf);
-            decoder.read_exact(&mut pos_buf)?;
-            let z = i32::from_le_bytes(pos_buf);
-
-            // Read RLE data
+        let chunks = read_chunk_section(&mut decoder)?;
+        let mut extra_layers = Vec::new();
+        if version >= LAYERS_VERSION {
             decoder.read_exact(&mut len_buf)?;
-            let rle_len = u32::from_le_bytes(len_buf) as usize;
-            let rle_data = super::read_exact_vec(&mut decoder, rle_len)?;
-
-            chunks.push(ChunkData {
-                pos: ChunkPos::new(x, y, z),
-                rle_data,
-            });
+            let layer_count = u32::from_le_bytes(len_buf) as usize;
+            // Each section is read (and length-checked) before the next,
+            // so a bogus count just runs into EOF; no preallocation.
+            for _ in 0..layer_count {
+                extra_layers.push(read_chunk_section(&mut decoder)?);
+            }
+        }
+        let mut stored_frames = Vec::new();
+        if version >= FRAMES_VERSION {
+            decoder.read_exact(&mut len_buf)?;
+            let frame_count = u32::from_le_bytes(len_buf) as usize;
+            for _ in 0..frame_count {
+                decoder.read_exact(&mut len_buf)?;
+                let section_count = u32::from_le_bytes(len_buf) as usize;
+                let mut sections = Vec::new();
+                for _ in 0..section_count {
+                    sections.push(read_chunk_section(&mut decoder)?);
+                }
+                stored_frames.push(sections);
+            }
         }
 
         Ok(Self {
             metadata,
             editor_state,
             chunks,
+            extra_layers,
+            stored_frames,
+            thumbnail,
         })
     }
 
+    /// The project file's bytes, as [`Self::save`] writes them.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProjectError> {
+        let mut bytes = Vec::new();
+        self.save(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Read a project from a file's bytes, as [`Self::load`] does.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, ProjectError> {
+        Self::load(&mut bytes)
+    }
+
     /// Update metadata modified timestamp
     pub fn touch(&mut self) {
         self.metadata.modified_at = std::time::SystemTime::now()
@@ -295,19 +581,137 @@ impl Default for Project {
     }
 }
 
+/// Read the v3 thumbnail block (length + PNG bytes) at the reader's
+/// position.
+fn read_thumbnail_block<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>, ProjectError> {
+    let mut len_buf = [0u8; 4];
+    reader.read_exact(&mut len_buf)?;
+    let len = u32::from_le_bytes(len_buf);
+    if len > MAX_THUMBNAIL_BYTES {
+        return Err(ProjectError::ThumbnailTooLarge(len));
+    }
+    if len == 0 {
+        return Ok(None);
+    }
+    Ok(Some(super::read_exact_vec(reader, len as usize)?))
+}
+
+/// The embedded PNG thumbnail of a project, reading only the front of
+/// the file (magic, version, thumbnail block) — the voxel body is
+/// never decompressed. `Ok(None)` for projects saved without one,
+/// including every pre-v3 file.
+pub fn read_thumbnail(path: &std::path::Path) -> Result<Option<Vec<u8>>, ProjectError> {
+    let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != PROJECT_MAGIC {
+        return Err(ProjectError::InvalidMagic);
+    }
+    let mut version_buf = [0u8; 4];
+    reader.read_exact(&mut version_buf)?;
+    let version = u32::from_le_bytes(version_buf);
+    if version > PROJECT_VERSION {
+        return Err(ProjectError::UnsupportedVersion(version));
+    }
+    if version < THUMBNAIL_VERSION {
+        return Ok(None);
+    }
+    read_thumbnail_block(&mut reader)
+}
+
+/// RLE-encode every non-empty chunk of `world`.
+fn encode_world(world: &World) -> Vec<ChunkData> {
+    world
+        .chunks()
+        .filter_map(|(pos, chunk_lock)| {
+            let chunk = chunk_lock.read();
+            (!chunk.is_empty()).then(|| ChunkData {
+                pos: *pos,
+                rle_data: rle_encode_chunk(&chunk),
+            })
+        })
+        .collect()
+}
+
+/// Total solid voxels across `world`'s chunks.
+fn solid_voxels(world: &World) -> u64 {
+    world
+        .chunks()
+        .map(|(_, chunk)| chunk.read().solid_count() as u64)
+        .sum()
+}
+
+/// Rebuild a world from encoded chunks, skipping undecodable ones.
+/// With `bounds`, the world is bounded and out-of-bounds chunks are
+/// dropped.
+fn decode_world(chunks: &[ChunkData], bounds: Option<WorldBounds>) -> World {
+    let mut world = bounds.map_or_else(World::new, World::bounded);
+    for chunk_data in chunks {
+        if let Some(chunk) = rle_decode_chunk(&chunk_data.rle_data) {
+            // Ignored for an out-of-bounds chunk of a bounded world
+            world.replace_chunk(chunk_data.pos, chunk);
+        }
+    }
+    world
+}
+
+/// Write a chunk count followed by each chunk's position and RLE data.
+fn write_chunk_section<W: Write>(writer: &mut W, chunks: &[ChunkData]) -> io::Result<()> {
+    writer.write_all(&(chunks.len() as u32).to_le_bytes())?;
+    for chunk_data in chunks {
+        writer.write_all(&chunk_data.pos.x.to_le_bytes())?;
+        writer.write_all(&chunk_data.pos.y.to_le_bytes())?;
+        writer.write_all(&chunk_data.pos.z.to_le_bytes())?;
+        writer.write_all(&(chunk_data.rle_data.len() as u32).to_le_bytes())?;
+        writer.write_all(&chunk_data.rle_data)?;
+    }
+    Ok(())
+}
+
+/// Read one chunk section written by [`write_chunk_section`].
+fn read_chunk_section<R: Read>(reader: &mut R) -> Result<Vec<ChunkData>, ProjectError> {
+    let mut len_buf = [0u8; 4];
+    reader.read_exact(&mut len_buf)?;
+    let chunk_count = u32::from_le_bytes(len_buf) as usize;
+
+    // Cap the capacity hint so a bogus chunk_count from a corrupt file
+    // can't request a huge eager allocation; the loop below still reads
+    // the full count and fails via read_exact if the data runs short.
+    let mut chunks = Vec::with_capacity(chunk_count.min(MAX_CHUNK_HINT));
+    for _ in 0..chunk_count {
+        let mut pos_buf = [0u8; 4];
+        reader.read_exact(&mut pos_buf)?;
+        let x = i32::from_le_bytes(pos_buf);
+        reader.read_exact(&mut pos_buf)?;
+        let y = i32::from_le_bytes(pos_buf);
+        reader.read_exact(&mut pos_buf)?;
+        let z = i32::from_le_bytes(pos_buf);
+
+        reader.read_exact(&mut len_buf)?;
+        let rle_len = u32::from_le_bytes(len_buf) as usize;
+        let rle_data = super::read_exact_vec(reader, rle_len)?;
+
+        chunks.push(ChunkData {
+            pos: ChunkPos::new(x, y, z),
+            rle_data,
+        });
+    }
+    Ok(chunks)
+}
+
 /// Run-length encode chunk voxels
 fn rle_encode_chunk(chunk: &Chunk) -> Vec<u8> {
-    let voxels = chunk.voxels();
+    let mut voxels = chunk.iter_voxels().map(|(_, voxel)| voxel);
     let mut result = Vec::new();
 
-    if voxels.is_empty() {
+    let Some(&first) = voxels.next() else {
         return result;
-    }
+    };
 
-    let mut current = voxels[0];
+    let mut current = first;
     let mut count = 1u16;
 
-    for voxel in voxels.iter().skip(1) {
+    for voxel in voxels {
         if *voxel == current && count < 65535 {
             count += 1;
         } else {
@@ -391,25 +795,116 @@ pub fn save_world_with_state(world: &World, editor_state: EditorState, path: &st
     project.save(&mut writer)
 }
 
-/// Quick load world from file path
+/// Quick load world from file path. Layered projects come back with
+/// their visible layers flattened, i.e. what the editor displays.
 pub fn load_world(path: &std::path::Path) -> Result<World, ProjectError> {
     let file = std::fs::File::open(path)?;
     let mut reader = std::io::BufReader::new(file);
     let project = Project::load(&mut reader)?;
-    Ok(project.to_world())
+    Ok(project.to_scene().flatten())
 }
 
-/// Load world with editor state from file path
+/// Load world with editor state from file path, flattening visible
+/// layers like [`load_world`]. Used by the headless bake, which
+/// exports what the editor shows.
 pub fn load_world_with_state(path: &std::path::Path) -> Result<(World, EditorState), ProjectError> {
     let file = std::fs::File::open(path)?;
     let mut reader = std::io::BufReader::new(file);
     let project = Project::load(&mut reader)?;
-    Ok((project.to_world(), project.editor_state))
+    Ok((project.to_scene().flatten(), project.editor_state))
+}
+
+/// Save every layer of `scene` with editor state to file path
+pub fn save_scene_with_state(scene: &Scene, editor_state: EditorState, path: &std::path::Path) -> Result<(), ProjectError> {
+    save_scene_with_thumbnail(scene, editor_state, None, path)
+}
+
+/// [`save_scene_with_state`] plus an embedded PNG `thumbnail` (see
+/// [`read_thumbnail`]).
+pub fn save_scene_with_thumbnail(
+    scene: &Scene,
+    editor_state: EditorState,
+    thumbnail: Option<Vec<u8>>,
+    path: &std::path::Path,
+) -> Result<(), ProjectError> {
+    let mut project = Project::from_scene_with_state(scene, editor_state);
+    project.thumbnail = thumbnail;
+    let file = std::fs::File::create(path)?;
+    let mut writer = std::io::BufWriter::new(file);
+    project.save(&mut writer)
+}
+
+/// Write `project` to `path` through a temporary file next to it,
+/// renamed over `path` once complete, so an interrupted write never
+/// leaves a truncated project behind.
+pub fn save_project_atomically(project: &Project, path: &Path) -> Result<(), ProjectError> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let written = std::fs::File::create(&tmp)
+        .map_err(ProjectError::from)
+        .and_then(|file| {
+            let mut writer = std::io::BufWriter::new(file);
+            project.save(&mut writer)?;
+            writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+            Ok(())
+        })
+        .and_then(|()| std::fs::rename(&tmp, path).map_err(Into::into));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written
+}
+
+/// A save started by [`save_project_in_background`].
+pub struct BackgroundSave {
+    path: PathBuf,
+    handle: JoinHandle<Result<(), ProjectError>>,
+}
+
+impl BackgroundSave {
+    /// Where the project is being written.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the write is done, successfully or not; [`Self::wait`]
+    /// then returns at once.
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Block until the write is done and return its result.
+    pub fn wait(self) -> Result<(), ProjectError> {
+        self.handle.join().unwrap_or_else(|_| {
+            Err(io::Error::other("the save thread panicked").into())
+        })
+    }
+}
+
+/// Compress and write `project` to `path` on a new thread, the way
+/// [`save_project_atomically`] does, and return at once.
+pub fn save_project_in_background(project: Project, path: PathBuf) -> BackgroundSave {
+    let target = path.clone();
+    let handle = std::thread::Builder::new()
+        .name("project-save".into())
+        .spawn(move || save_project_atomically(&project, &target))
+        .expect("failed to spawn the project save thread");
+    BackgroundSave { path, handle }
+}
+
+/// Load a layered scene with editor state from file path
+pub fn load_scene_with_state(path: &std::path::Path) -> Result<(Scene, EditorState), ProjectError> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    let project = Project::load(&mut reader)?;
+    Ok((project.to_scene(), project.editor_state))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::{BackgroundKind, LightKind, MaterialDef, ReferenceView};
 
     #[test]
     fn test_project_roundtrip() {
@@ -468,6 +963,65 @@ mod tests {
                     normal: [1.0, 0.0, 0.0],
                 },
             ],
+            layers: Vec::new(),
+            active_layer: 0,
+            procgen_seeds: BTreeMap::from([
+                ("builtin.cave".to_string(), 1234),
+                ("builtin.wfc".to_string(), u32::MAX as u64),
+            ]),
+            camera_bookmarks: {
+                let mut slots = [None; CAMERA_BOOKMARK_SLOTS];
+                slots[2] = Some(CameraBookmark {
+                    position: [10.0, 8.0, -6.5],
+                    target: [0.5, 0.0, 0.5],
+                    quad_zoom: [20.0, 12.5, 40.0],
+                });
+                slots
+            },
+            materials: {
+                let mut materials = MaterialTable::new();
+                materials.add(MaterialDef {
+                    roughness: 0.2,
+                    metallic: 1.0,
+                    ..MaterialDef::new("Brass")
+                });
+                materials
+            },
+            frames: vec![FrameData {
+                name: "Walk 1".to_string(),
+                duration_ms: 80,
+            }],
+            current_frame: 0,
+            bones: vec![BoneData {
+                name: "Arm".to_string(),
+                parent: None,
+                pivot: [1, 0, -1],
+                translation: [0, 2, 0],
+                rotation: [0, 0, 45],
+                cells: vec![[1, 0, -1], [2, 0, -1]],
+            }],
+            camera_path: vec![CameraPathKey {
+                position: [12.0, 6.0, 12.0],
+                target: [0.5, 1.0, 0.5],
+                duration_ms: 1500,
+            }],
+            camera_path_closed: true,
+            background: Background {
+                kind: BackgroundKind::Hdri,
+                hdri_path: Some(PathBuf::from("skies/dusk.hdr")),
+                hdri_rotation: 90.0,
+                ..Background::default()
+            },
+            lights: vec![Light::new("Key", LightKind::Spot, [4.0, 8.0, 2.0])],
+            references: vec![ReferenceImage {
+                opacity: 0.3,
+                position: [0.0, 0.0, -4.0],
+                ..ReferenceImage::new(PathBuf::from("art/side.png"), ReferenceView::Side)
+            }],
+            reference_model: Some(ReferenceModel {
+                scale: 0.5,
+                ..ReferenceModel::new(PathBuf::from("old/ship.obj"))
+            }),
         };
 
         let project = Project::from_world_with_state(&world, state.clone());
@@ -483,6 +1037,16 @@ mod tests {
         assert_eq!(es.palette, state.palette);
         assert_eq!(es.selected_tool, state.selected_tool);
         assert_eq!(es.sockets, state.sockets);
+        assert_eq!(es.bones, state.bones);
+        assert_eq!(es.camera_path, state.camera_path);
+        assert!(es.camera_path_closed);
+        assert_eq!(es.background, state.background);
+        assert_eq!(es.lights, state.lights);
+        assert_eq!(es.references, state.references);
+        assert_eq!(es.reference_model, state.reference_model);
+        assert_eq!(es.procgen_seeds, state.procgen_seeds);
+        assert_eq!(es.camera_bookmarks, state.camera_bookmarks);
+        assert_eq!(es.materials, state.materials);
 
         // Every set voxel survives — negatives, far chunks, exact rgba.
         let loaded_world = loaded.to_world();
@@ -498,6 +1062,202 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_scene_roundtrip_preserves_layers() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        scene.add_layer("Armor");
+        scene.active_world_mut().set_voxel(-40, 2, 0, Voxel::from_rgb(0, 0, 255));
+        scene.add_layer("Empty");
+        scene.set_visible(1, false);
+        scene.set_locked(0, true);
+        scene.set_active(1);
+
+        let project = Project::from_scene_with_state(&scene, EditorState::default());
+        let mut buffer = Vec::new();
+        project.save(&mut buffer).unwrap();
+        let loaded = Project::load(&mut buffer.as_slice()).unwrap().to_scene();
+
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.active_index(), 1);
+        let layers = loaded.layers();
+        assert_eq!(layers[1].name, "Armor");
+        assert!(!layers[1].visible);
+        assert!(layers[0].locked);
+        assert_eq!(layers[0].world.get_voxel(0, 0, 0).r, 255);
+        assert!(layers[0].world.get_voxel(-40, 2, 0).is_air());
+        assert_eq!(layers[1].world.get_voxel(-40, 2, 0).b, 255);
+        assert_eq!(layers[2].world.chunk_count(), 0);
+    }
+
+    #[test]
+    fn test_frames_roundtrip() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        scene.add_layer("Top");
+        scene.add_frame();
+        scene.active_world_mut().set_voxel(0, 5, 0, Voxel::from_rgb(0, 255, 0));
+        scene.set_frame_duration(1, 250);
+        scene.duplicate_frame();
+        scene.rename_frame(2, "Hold");
+        scene.set_current_frame(1);
+
+        let mut buffer = Vec::new();
+        Project::from_scene_with_state(&scene, EditorState::default())
+            .save(&mut buffer)
+            .unwrap();
+        let loaded = Project::load(&mut buffer.as_slice()).unwrap().to_scene();
+
+        assert_eq!(loaded.current_frame(), 1);
+        let frames = loaded.frames();
+        let names: Vec<&str> = frames.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["Frame 1", "Frame 2", "Hold"]);
+        assert_eq!(frames[1].duration_ms, 250);
+        assert_eq!(loaded.layers()[1].world.get_voxel(0, 5, 0).g, 255);
+        assert!(loaded.layers()[0].world.get_voxel(0, 0, 0).is_air());
+        assert_eq!(loaded.frame_world(0).get_voxel(0, 0, 0).r, 255);
+        assert_eq!(loaded.frame_world(2).get_voxel(0, 5, 0).g, 255);
+    }
+
+    /// A pre-thumbnail file as older writers produced it: magic,
+    /// version, then gzip(header JSON, one chunk section) — plus an
+    /// empty extra-layer count for version 2.
+    fn legacy_bytes(version: u32, world: &World, state: &EditorState) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&PROJECT_MAGIC);
+        buf.extend_from_slice(&version.to_le_bytes());
+        let mut encoder = GzEncoder::new(&mut buf, Compression::default());
+        let header = serde_json::to_string(&(ProjectMetadata::default(), state)).unwrap();
+        encoder.write_all(&(header.len() as u32).to_le_bytes()).unwrap();
+        encoder.write_all(header.as_bytes()).unwrap();
+        write_chunk_section(&mut encoder, &encode_world(world)).unwrap();
+        if version >= LAYERS_VERSION {
+            encoder.write_all(&0u32.to_le_bytes()).unwrap();
+        }
+        encoder.finish().unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_v1_file_migrates_to_v2() {
+        let mut world = World::new();
+        world.set_voxel(-5, 3, 40, Voxel::from_rgb(9, 8, 7));
+        let state = EditorState {
+            palette: vec![[1, 2, 3, 255]; 40],
+            ..Default::default()
+        };
+        let loaded = Project::load(&mut legacy_bytes(1, &world, &state).as_slice()).unwrap();
+        let scene = loaded.to_scene();
+        assert_eq!(scene.len(), 1);
+        assert_eq!(scene.layers()[0].name, "Layer 1");
+        assert!(scene.layers()[0].world.bounds().is_none());
+        assert_eq!(loaded.editor_state.palette.len(), 40);
+
+        // Re-saving writes the current version and keeps everything.
+        let mut buf = Vec::new();
+        Project::from_scene_with_state(&scene, loaded.editor_state.clone())
+            .save(&mut buf)
+            .unwrap();
+        assert_eq!(buf[4..8], PROJECT_VERSION.to_le_bytes());
+        let again = Project::load(&mut buf.as_slice()).unwrap();
+        assert_eq!(again.editor_state.palette.len(), 40);
+        assert_eq!(again.editor_state.layers[0].voxel_count, Some(1));
+        assert_eq!(again.to_world().get_voxel(-5, 3, 40).r, 9);
+    }
+
+    #[test]
+    fn test_layer0_reads_with_v1_body_layout() {
+        // The other direction: a reader that only knows the v1 body
+        // layout (header + one chunk section) gets layer 0 of a current
+        // file once past the thumbnail block.
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(1, 1, 1, Voxel::from_rgb(200, 0, 0));
+        scene.add_layer("Top");
+        scene.active_world_mut().set_voxel(2, 2, 2, Voxel::from_rgb(0, 200, 0));
+        let mut buf = Vec::new();
+        Project::from_scene_with_state(&scene, EditorState::default())
+            .save(&mut buf)
+            .unwrap();
+
+        assert_eq!(buf[8..12], 0u32.to_le_bytes(), "no thumbnail block");
+        let mut decoder = GzDecoder::new(&buf[12..]);
+        let mut len = [0u8; 4];
+        decoder.read_exact(&mut len).unwrap();
+        let header = crate::io::read_exact_vec(&mut decoder, u32::from_le_bytes(len) as usize)
+            .unwrap();
+        let (_, state): (ProjectMetadata, EditorState) = serde_json::from_slice(&header).unwrap();
+        let layer0 = decode_world(&read_chunk_section(&mut decoder).unwrap(), None);
+        assert_eq!(layer0.get_voxel(1, 1, 1).r, 200);
+        assert!(layer0.get_voxel(2, 2, 2).is_air());
+        assert_eq!(state.layers.len(), 2);
+    }
+
+    #[test]
+    fn test_thumbnail_roundtrip_and_front_read() {
+        let mut scene = Scene::new();
+        scene.active_world_mut().set_voxel(0, 0, 0, Voxel::from_rgb(1, 2, 3));
+        let png = vec![0x89, b'P', b'N', b'G', 1, 2, 3];
+        let path = std::env::temp_dir().join(format!(
+            "voxelith_thumb_test_{}.vxlt",
+            std::process::id()
+        ));
+        save_scene_with_thumbnail(&scene, EditorState::default(), Some(png.clone()), &path)
+            .unwrap();
+        let front = read_thumbnail(&path);
+        let loaded = load_scene_with_state(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(front.unwrap(), Some(png));
+        assert_eq!(loaded.unwrap().0.active_world().get_voxel(0, 0, 0).b, 3);
+    }
+
+    #[test]
+    fn test_v2_file_loads_without_thumbnail() {
+        let mut world = World::new();
+        world.set_voxel(4, 4, 4, Voxel::from_rgb(50, 60, 70));
+        let bytes = legacy_bytes(2, &world, &EditorState::default());
+        let loaded = Project::load(&mut bytes.as_slice()).unwrap();
+        assert!(loaded.thumbnail.is_none());
+        assert_eq!(loaded.to_world().get_voxel(4, 4, 4).g, 60);
+    }
+
+    #[test]
+    fn test_oversized_thumbnail_is_rejected() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&PROJECT_MAGIC);
+        buf.extend_from_slice(&PROJECT_VERSION.to_le_bytes());
+        buf.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            Project::load(&mut buf.as_slice()),
+            Err(ProjectError::ThumbnailTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_layer_bounds_roundtrip() {
+        let bounds = WorldBounds::new(ChunkPos::new(-1, 0, -1), ChunkPos::new(0, 0, 0));
+        let mut bounded = World::bounded(bounds);
+        bounded.set_voxel(-3, 4, -3, Voxel::from_rgb(1, 1, 1));
+        let scene = Scene::from_layers(vec![Layer::new("Box", bounded)]);
+        let mut buf = Vec::new();
+        Project::from_scene_with_state(&scene, EditorState::default())
+            .save(&mut buf)
+            .unwrap();
+        let loaded = Project::load(&mut buf.as_slice()).unwrap().to_scene();
+        let world = &loaded.layers()[0].world;
+        assert_eq!(world.bounds(), Some(&bounds));
+        assert!(world.get_voxel(-3, 4, -3).is_solid());
+    }
+
+    #[test]
+    fn layer_data_without_v2_extras_still_loads() {
+        // Layer headers from before bounds / voxel counts were recorded.
+        let data: LayerData =
+            serde_json::from_str(r#"{ "name": "L", "visible": true, "locked": false }"#).unwrap();
+        assert_eq!(data.bounds, None);
+        assert_eq!(data.voxel_count, None);
+    }
+
     #[test]
     fn editor_state_without_sockets_field_still_loads() {
         // A `.vxlt` written before sockets existed has no `sockets` key
@@ -514,6 +1274,7 @@ mod tests {
         let es: EditorState = serde_json::from_str(json).unwrap();
         assert_eq!(es.selected_tool, 2);
         assert!(es.sockets.is_empty());
+        assert!(es.procgen_seeds.is_empty());
     }
 
     #[test]
@@ -570,4 +1331,37 @@ mod tests {
         assert_eq!(decoded.get(0, 0, 0).r, 128);
         assert_eq!(decoded.get(15, 15, 15).g, 64);
     }
+
+    #[test]
+    fn bytes_and_background_saves_round_trip() {
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (40, 3, 3), Voxel::from_rgb(10, 20, 30));
+        let mut project = Project::from_world(&world);
+        project.thumbnail = Some(vec![1, 2, 3]);
+
+        let bytes = project.to_bytes().unwrap();
+        let loaded = Project::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.thumbnail.as_deref(), Some(&[1, 2, 3][..]));
+        assert_eq!(loaded.to_world().solid_voxel_count(), 41 * 16);
+        assert!(matches!(
+            Project::from_bytes(b"nope"),
+            Err(ProjectError::InvalidMagic)
+        ));
+
+        let dir = std::env::temp_dir().join(format!("voxelith-bg-save-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("world.vxlt");
+        let save = save_project_in_background(project, path.clone());
+        assert_eq!(save.path(), path);
+        save.wait().unwrap();
+        // Written through a temp file that's gone again.
+        assert_eq!(std::fs::read(&path).unwrap(), bytes);
+        assert!(!dir.join("world.vxlt.tmp").exists());
+
+        // A failed write reports its error instead of panicking.
+        let missing = dir.join("missing").join("world.vxlt");
+        let save = save_project_in_background(Project::new(), missing);
+        assert!(matches!(save.wait(), Err(ProjectError::Io(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
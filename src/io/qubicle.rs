@@ -0,0 +1,689 @@
+//! Qubicle `.qb` (Qubicle Binary) and `.qbt` (Qubicle Binary Tree)
+//! import/export.
+//!
+//! Both formats store a model as named **matrices** — axis-aligned
+//! voxel boxes with their own position — which map one-to-one onto
+//! scene layers: import yields one [`QubicleMatrix`] per matrix, and
+//! export writes one matrix per world handed in (the app passes its
+//! visible layers). Colors map to `Voxel` RGB; a zero alpha / mask byte
+//! means empty, anything else solid.
+//!
+//! - **QB**: flat header + matrices, optionally run-length encoded per
+//!   Z slice. Files may be left- or right-handed; left-handed ones are
+//!   mirrored on Z so models import the right way round. We write
+//!   right-handed, RGBA, RLE-compressed files, so export → import is
+//!   the identity.
+//! - **QBT**: `QB 2` magic, optional color map, then a node tree
+//!   (Matrix / Model / Compound) whose matrix data is zlib-compressed.
+//!   Model and compound nodes are flattened into their matrices. We
+//!   write a single Model node of plain-RGB matrices (no color map).
+//!
+//! Format specs: <https://getqubicle.com/qubicle/documentation/docs/file/qb/>
+//! and <https://getqubicle.com/qubicle/documentation/docs/file/qbt/>.
+
+use std::io::{self, Read, Write};
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use thiserror::Error;
+
+use crate::core::{Voxel, World};
+
+/// QB version written and expected: 1.1.0.0.
+const QB_VERSION: [u8; 4] = [1, 1, 0, 0];
+/// RLE marker: the next two u32s are `(count, color)`.
+const QB_CODEFLAG: u32 = 2;
+/// RLE marker: end of the current Z slice.
+const QB_NEXTSLICEFLAG: u32 = 6;
+/// QBT file magic.
+const QBT_MAGIC: [u8; 4] = *b"QB 2";
+/// QBT node type ids.
+const QBT_MATRIX: u32 = 0;
+const QBT_MODEL: u32 = 1;
+const QBT_COMPOUND: u32 = 2;
+/// Most cells, solid or empty, in one matrix we read or write: a 406³
+/// box, 256 MiB as the RGBA cells the formats store. Bounds how many
+/// cells a bogus header — or a handful of QB run codes — can make us
+/// write, while leaving sides free to run well past VOX's 256.
+const MAX_QB_CELLS: u64 = 1 << 26;
+/// Deepest QBT node nesting we follow. Real files nest a few levels;
+/// the cap keeps a hostile file from recursing us off the stack.
+const QBT_MAX_DEPTH: usize = 64;
+
+/// Errors that can occur when reading/writing Qubicle files
+#[derive(Debug, Error)]
+pub enum QubicleError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid magic number, expected 'QB 2'")]
+    InvalidMagic,
+    #[error("Unsupported Qubicle version: {0:?}")]
+    UnsupportedVersion([u8; 4]),
+    #[error("Corrupt matrix data: {0}")]
+    Corrupt(String),
+    #[error("Matrix \"{0}\" spans more than {MAX_QB_CELLS} cells")]
+    MatrixTooLarge(String),
+    #[error("No voxel data found")]
+    NoVoxelData,
+}
+
+/// One Qubicle matrix: a named voxel box, already placed in world
+/// coordinates.
+pub struct QubicleMatrix {
+    pub name: String,
+    pub world: World,
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_i32<R: Read>(reader: &mut R) -> io::Result<i32> {
+    Ok(read_u32(reader)? as i32)
+}
+
+/// A matrix's placement: world-space min corner and size in cells.
+type Extent = ((i32, i32, i32), (u32, u32, u32));
+
+/// Inclusive AABB of `world`'s solid cells as `(min, size)`, or `None`
+/// when it is empty.
+fn matrix_extent(world: &World) -> Option<Extent> {
+    let (min, max) = world.scene_aabb()?;
+    let size = (
+        (max.0 - min.0 + 1) as u32,
+        (max.1 - min.1 + 1) as u32,
+        (max.2 - min.2 + 1) as u32,
+    );
+    Some((min, size))
+}
+
+/// The non-empty `matrices` with their extents, or
+/// [`QubicleError::MatrixTooLarge`] for the first one the importers
+/// would refuse.
+fn placed_matrices<'a>(
+    matrices: &[(&'a str, &'a World)],
+) -> Result<Vec<(&'a str, &'a World, Extent)>, QubicleError> {
+    let placed: Vec<_> = matrices
+        .iter()
+        .filter_map(|(name, world)| matrix_extent(world).map(|e| (*name, *world, e)))
+        .collect();
+    match placed.iter().find(|(_, _, (_, size))| !fits_cell_budget(*size)) {
+        Some((name, _, _)) => Err(QubicleError::MatrixTooLarge(name.to_string())),
+        None => Ok(placed),
+    }
+}
+
+/// Whether a matrix of `size` stays within [`MAX_QB_CELLS`].
+fn fits_cell_budget(size: (u32, u32, u32)) -> bool {
+    (size.0 as u64 * size.1 as u64)
+        .checked_mul(size.2 as u64)
+        .is_some_and(|cells| cells <= MAX_QB_CELLS)
+}
+
+/// Reject a matrix of more than [`MAX_QB_CELLS`] cells, or placed so
+/// some of its cells fall outside `i32` coordinates.
+fn check_matrix(
+    name: &str,
+    pos: (i32, i32, i32),
+    size: (u32, u32, u32),
+) -> Result<(), QubicleError> {
+    if !fits_cell_budget(size) {
+        return Err(QubicleError::Corrupt(format!(
+            "matrix \"{name}\" is {}×{}×{} cells, more than {MAX_QB_CELLS}",
+            size.0, size.1, size.2
+        )));
+    }
+    for (p, s) in [(pos.0, size.0), (pos.1, size.1), (pos.2, size.2)] {
+        if p as i64 + s as i64 - 1 > i32::MAX as i64 {
+            return Err(QubicleError::Corrupt(format!(
+                "matrix \"{name}\" extends past the edge of the world"
+            )));
+        }
+    }
+    Ok(())
+}
+
+// ---- QB ---------------------------------------------------------------
+
+/// Read a `.qb` file into its matrices.
+pub fn import_qb<R: Read>(reader: &mut R) -> Result<Vec<QubicleMatrix>, QubicleError> {
+    let mut version = [0u8; 4];
+    reader.read_exact(&mut version)?;
+    if version[0] != 1 {
+        return Err(QubicleError::UnsupportedVersion(version));
+    }
+    let bgra = read_u32(reader)? == 1;
+    let left_handed = read_u32(reader)? == 0;
+    let compressed = read_u32(reader)? == 1;
+    // Visibility-mask encoding only changes what a non-zero alpha
+    // means (which faces show); zero is empty either way.
+    let _visibility_mask = read_u32(reader)?;
+    let num_matrices = read_u32(reader)?;
+
+    let mut matrices = Vec::new();
+    for _ in 0..num_matrices {
+        let mut len = [0u8; 1];
+        reader.read_exact(&mut len)?;
+        let name = String::from_utf8_lossy(&super::read_exact_vec(reader, len[0] as usize)?)
+            .into_owned();
+        let size = (read_u32(reader)?, read_u32(reader)?, read_u32(reader)?);
+        let pos = (read_i32(reader)?, read_i32(reader)?, read_i32(reader)?);
+        check_matrix(&name, pos, size)?;
+
+        let mut voxels = Vec::new();
+        let mut put = |x: u32, y: u32, z: u32, color: u32| {
+            let [a, b, c, alpha] = color.to_le_bytes();
+            if alpha == 0 {
+                return;
+            }
+            let (r, g, b) = if bgra { (c, b, a) } else { (a, b, c) };
+            let wz = pos.2 + z as i32;
+            // `-1 - wz` rather than `-wz - 1`: the latter overflows
+            // at `i32::MIN`.
+            let wz = if left_handed { -1 - wz } else { wz };
+            voxels.push(((pos.0 + x as i32, pos.1 + y as i32, wz), Voxel::from_rgb(r, g, b)));
+        };
+
+        if compressed {
+            let slice = size.0 as u64 * size.1 as u64;
+            for z in 0..size.2 {
+                let mut index = 0u64;
+                loop {
+                    let data = read_u32(reader)?;
+                    if data == QB_NEXTSLICEFLAG {
+                        break;
+                    }
+                    let (count, color) = if data == QB_CODEFLAG {
+                        (read_u32(reader)? as u64, read_u32(reader)?)
+                    } else {
+                        (1, data)
+                    };
+                    // A run may not spill past its slice, so with the
+                    // matrix size capped a bogus count can't run away.
+                    if index + count > slice {
+                        return Err(QubicleError::Corrupt(format!(
+                            "run overflows slice {z} of matrix \"{name}\""
+                        )));
+                    }
+                    for i in index..index + count {
+                        let x = (i % size.0 as u64) as u32;
+                        let y = (i / size.0 as u64) as u32;
+                        put(x, y, z, color);
+                    }
+                    index += count;
+                }
+            }
+        } else {
+            for z in 0..size.2 {
+                for y in 0..size.1 {
+                    for x in 0..size.0 {
+                        put(x, y, z, read_u32(reader)?);
+                    }
+                }
+            }
+        }
+        let mut world = World::new();
+        world.set_voxels(voxels);
+        matrices.push(QubicleMatrix { name, world });
+    }
+
+    if matrices.is_empty() {
+        return Err(QubicleError::NoVoxelData);
+    }
+    Ok(matrices)
+}
+
+/// Write one RLE-compressed QB slice of `cells` (row-major, x fastest).
+fn write_qb_slice<W: Write>(writer: &mut W, cells: &[u32]) -> io::Result<()> {
+    let mut i = 0;
+    while i < cells.len() {
+        let color = cells[i];
+        let run = cells[i..].iter().take_while(|&&c| c == color).count();
+        // Runs of 1–2 are cheaper written literally — unless the color
+        // itself collides with a marker value.
+        let marker = color == QB_CODEFLAG || color == QB_NEXTSLICEFLAG;
+        if run > 2 || marker {
+            writer.write_all(&QB_CODEFLAG.to_le_bytes())?;
+            writer.write_all(&(run as u32).to_le_bytes())?;
+            writer.write_all(&color.to_le_bytes())?;
+        } else {
+            for _ in 0..run {
+                writer.write_all(&color.to_le_bytes())?;
+            }
+        }
+        i += run;
+    }
+    writer.write_all(&QB_NEXTSLICEFLAG.to_le_bytes())
+}
+
+/// Pack a voxel as a QB / QBT little-endian RGBA cell (alpha 255 =
+/// solid, 0 = empty).
+fn pack_cell(v: Voxel) -> u32 {
+    if v.is_air() {
+        0
+    } else {
+        u32::from_le_bytes([v.r, v.g, v.b, 255])
+    }
+}
+
+/// Write `matrices` (`(name, world)` pairs) as a `.qb` file. Empty
+/// worlds are skipped; returns the number of matrices written. Fails
+/// before writing anything if a world spans more than
+/// [`MAX_QB_CELLS`].
+pub fn export_qb<W: Write>(
+    matrices: &[(&str, &World)],
+    writer: &mut W,
+) -> Result<usize, QubicleError> {
+    let placed = placed_matrices(matrices)?;
+
+    writer.write_all(&QB_VERSION)?;
+    writer.write_all(&0u32.to_le_bytes())?; // RGBA
+    writer.write_all(&1u32.to_le_bytes())?; // right-handed
+    writer.write_all(&1u32.to_le_bytes())?; // RLE compressed
+    writer.write_all(&0u32.to_le_bytes())?; // no visibility mask
+    writer.write_all(&(placed.len() as u32).to_le_bytes())?;
+
+    for (name, world, (min, size)) in &placed {
+        // QB names are length-prefixed by a single byte.
+        let name = truncate_utf8(name, u8::MAX as usize);
+        writer.write_all(&[name.len() as u8])?;
+        writer.write_all(name.as_bytes())?;
+        for d in [size.0, size.1, size.2] {
+            writer.write_all(&d.to_le_bytes())?;
+        }
+        for p in [min.0, min.1, min.2] {
+            writer.write_all(&p.to_le_bytes())?;
+        }
+        let mut cells = Vec::with_capacity(size.0 as usize * size.1 as usize);
+        for z in 0..size.2 as i32 {
+            cells.clear();
+            for y in 0..size.1 as i32 {
+                for x in 0..size.0 as i32 {
+                    cells.push(pack_cell(world.get_voxel(min.0 + x, min.1 + y, min.2 + z)));
+                }
+            }
+            write_qb_slice(writer, &cells)?;
+        }
+    }
+    Ok(placed.len())
+}
+
+/// Longest prefix of `s` that is at most `max` bytes and still valid
+/// UTF-8.
+fn truncate_utf8(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+// ---- QBT --------------------------------------------------------------
+
+/// Read a `.qbt` file into its matrices (model / compound nodes are
+/// flattened).
+pub fn import_qbt<R: Read>(reader: &mut R) -> Result<Vec<QubicleMatrix>, QubicleError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != QBT_MAGIC {
+        return Err(QubicleError::InvalidMagic);
+    }
+    let mut version = [0u8; 2];
+    reader.read_exact(&mut version)?;
+    if version[0] != 1 {
+        return Err(QubicleError::UnsupportedVersion([version[0], version[1], 0, 0]));
+    }
+    super::skip_bytes(reader, 12)?; // global scale (3 × f32)
+
+    let mut section = [0u8; 8];
+    reader.read_exact(&mut section)?;
+    if &section != b"COLORMAP" {
+        return Err(QubicleError::Corrupt("missing COLORMAP section".into()));
+    }
+    let color_count = read_u32(reader)? as usize;
+    let colormap = super::read_exact_vec(reader, color_count.saturating_mul(4))?;
+
+    reader.read_exact(&mut section)?;
+    if &section != b"DATATREE" {
+        return Err(QubicleError::Corrupt("missing DATATREE section".into()));
+    }
+    let mut matrices = Vec::new();
+    read_qbt_node(reader, &colormap, 0, &mut matrices)?;
+    if matrices.is_empty() {
+        return Err(QubicleError::NoVoxelData);
+    }
+    Ok(matrices)
+}
+
+fn read_qbt_node<R: Read>(
+    reader: &mut R,
+    colormap: &[u8],
+    depth: usize,
+    out: &mut Vec<QubicleMatrix>,
+) -> Result<(), QubicleError> {
+    if depth > QBT_MAX_DEPTH {
+        return Err(QubicleError::Corrupt("node tree nested too deeply".into()));
+    }
+    let type_id = read_u32(reader)?;
+    let data_size = read_u32(reader)?;
+    match type_id {
+        QBT_MATRIX => out.push(read_qbt_matrix(reader, colormap)?),
+        QBT_MODEL => {
+            let children = read_u32(reader)?;
+            for _ in 0..children {
+                read_qbt_node(reader, colormap, depth + 1, out)?;
+            }
+        }
+        QBT_COMPOUND => {
+            // A compound is a matrix (its merged voxels) followed by
+            // the child nodes it was built from; the children carry
+            // the same voxels, so keep only the merged matrix.
+            out.push(read_qbt_matrix(reader, colormap)?);
+            let children = read_u32(reader)?;
+            let mut ignored = Vec::new();
+            for _ in 0..children {
+                read_qbt_node(reader, colormap, depth + 1, &mut ignored)?;
+            }
+        }
+        _ => super::skip_bytes(reader, data_size as u64)?,
+    }
+    Ok(())
+}
+
+fn read_qbt_matrix<R: Read>(reader: &mut R, colormap: &[u8]) -> Result<QubicleMatrix, QubicleError> {
+    let name_len = read_u32(reader)? as usize;
+    let name = String::from_utf8_lossy(&super::read_exact_vec(reader, name_len)?).into_owned();
+    let pos = (read_i32(reader)?, read_i32(reader)?, read_i32(reader)?);
+    super::skip_bytes(reader, 12 + 12)?; // local scale (3 × u32) + pivot (3 × f32)
+    let size = (read_u32(reader)?, read_u32(reader)?, read_u32(reader)?);
+    check_matrix(&name, pos, size)?;
+    let compressed_size = read_u32(reader)? as u64;
+
+    let mut voxels = Vec::new();
+    let mut data = ZlibDecoder::new(reader.take(compressed_size));
+    // QBT stores columns: x outermost, then z, then y.
+    for x in 0..size.0 {
+        for z in 0..size.2 {
+            for y in 0..size.1 {
+                let mut cell = [0u8; 4];
+                data.read_exact(&mut cell)?;
+                if cell[3] == 0 {
+                    continue;
+                }
+                let (r, g, b) = if colormap.is_empty() {
+                    (cell[0], cell[1], cell[2])
+                } else {
+                    let i = cell[0] as usize * 4;
+                    match colormap.get(i..i + 3) {
+                        Some(c) => (c[0], c[1], c[2]),
+                        None => {
+                            return Err(QubicleError::Corrupt(format!(
+                                "color index {} outside the color map",
+                                cell[0]
+                            )))
+                        }
+                    }
+                };
+                voxels.push((
+                    (pos.0 + x as i32, pos.1 + y as i32, pos.2 + z as i32),
+                    Voxel::from_rgb(r, g, b),
+                ));
+            }
+        }
+    }
+    // Drain whatever the declared size still covers so the next node
+    // starts in the right place.
+    io::copy(&mut data.into_inner(), &mut io::sink())?;
+    let mut world = World::new();
+    world.set_voxels(voxels);
+    Ok(QubicleMatrix { name, world })
+}
+
+/// Write `matrices` (`(name, world)` pairs) as a `.qbt` file. Empty
+/// worlds are skipped; returns the number of matrices written. Fails
+/// before writing anything if a world spans more than
+/// [`MAX_QB_CELLS`].
+pub fn export_qbt<W: Write>(
+    matrices: &[(&str, &World)],
+    writer: &mut W,
+) -> Result<usize, QubicleError> {
+    let mut nodes = Vec::new();
+    let mut count = 0u32;
+    for (name, world, (min, size)) in placed_matrices(matrices)? {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        for x in 0..size.0 as i32 {
+            for z in 0..size.2 as i32 {
+                for y in 0..size.1 as i32 {
+                    let cell = pack_cell(world.get_voxel(min.0 + x, min.1 + y, min.2 + z));
+                    encoder.write_all(&cell.to_le_bytes())?;
+                }
+            }
+        }
+        let voxels = encoder.finish()?;
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        data.extend_from_slice(name.as_bytes());
+        for p in [min.0, min.1, min.2] {
+            data.extend_from_slice(&p.to_le_bytes());
+        }
+        for _ in 0..3 {
+            data.extend_from_slice(&1u32.to_le_bytes()); // local scale
+        }
+        for _ in 0..3 {
+            data.extend_from_slice(&0f32.to_le_bytes()); // pivot
+        }
+        for d in [size.0, size.1, size.2] {
+            data.extend_from_slice(&d.to_le_bytes());
+        }
+        data.extend_from_slice(&(voxels.len() as u32).to_le_bytes());
+        data.extend_from_slice(&voxels);
+
+        nodes.extend_from_slice(&QBT_MATRIX.to_le_bytes());
+        nodes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        nodes.extend_from_slice(&data);
+        count += 1;
+    }
+
+    writer.write_all(&QBT_MAGIC)?;
+    writer.write_all(&[1, 0])?; // version 1.0
+    for _ in 0..3 {
+        writer.write_all(&1f32.to_le_bytes())?; // global scale
+    }
+    writer.write_all(b"COLORMAP")?;
+    writer.write_all(&0u32.to_le_bytes())?; // plain RGB, no color map
+    writer.write_all(b"DATATREE")?;
+    writer.write_all(&QBT_MODEL.to_le_bytes())?;
+    writer.write_all(&(4 + nodes.len() as u32).to_le_bytes())?;
+    writer.write_all(&count.to_le_bytes())?;
+    writer.write_all(&nodes)?;
+    Ok(count as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> World {
+        let mut world = World::new();
+        world.set_voxel(-2, 0, 3, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(-1, 0, 3, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(0, 0, 3, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(0, 2, 5, Voxel::from_rgb(0, 40, 200));
+        world
+    }
+
+    fn assert_same(a: &World, b: &World) {
+        assert_eq!(a.scene_aabb(), b.scene_aabb());
+        let (min, max) = a.scene_aabb().unwrap();
+        for z in min.2..=max.2 {
+            for y in min.1..=max.1 {
+                for x in min.0..=max.0 {
+                    let (va, vb) = (a.get_voxel(x, y, z), b.get_voxel(x, y, z));
+                    assert_eq!(va.is_air(), vb.is_air(), "at ({x}, {y}, {z})");
+                    assert_eq!((va.r, va.g, va.b), (vb.r, vb.g, vb.b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_qb_roundtrip_keeps_positions_and_names() {
+        let world = sample();
+        let mut other = World::new();
+        other.set_voxel(10, 10, 10, Voxel::from_rgb(1, 2, 3));
+        let mut buf = Vec::new();
+        let written =
+            export_qb(&[("body", &world), ("empty", &World::new()), ("hat", &other)], &mut buf)
+                .unwrap();
+        assert_eq!(written, 2);
+
+        let matrices = import_qb(&mut buf.as_slice()).unwrap();
+        assert_eq!(matrices.len(), 2);
+        assert_eq!(matrices[0].name, "body");
+        assert_eq!(matrices[1].name, "hat");
+        assert_same(&matrices[0].world, &world);
+        assert_same(&matrices[1].world, &other);
+    }
+
+    #[test]
+    fn test_qbt_roundtrip() {
+        let world = sample();
+        let mut buf = Vec::new();
+        assert_eq!(export_qbt(&[("body", &world)], &mut buf).unwrap(), 1);
+        let matrices = import_qbt(&mut buf.as_slice()).unwrap();
+        assert_eq!(matrices.len(), 1);
+        assert_eq!(matrices[0].name, "body");
+        assert_same(&matrices[0].world, &world);
+    }
+
+    #[test]
+    fn test_qb_uncompressed_bgra_left_handed() {
+        // Hand-built 2×1×1 matrix at the origin: one blue cell stored
+        // BGRA, one empty.
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&QB_VERSION);
+        for v in [1u32, 0, 0, 0, 1] {
+            buf.extend_from_slice(&v.to_le_bytes()); // BGRA, left-handed, raw, no mask, 1 matrix
+        }
+        buf.push(1);
+        buf.push(b'm');
+        for v in [2u32, 1, 1, 0, 0, 0] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf.extend_from_slice(&[200, 0, 0, 255]); // B G R A
+        buf.extend_from_slice(&[0, 0, 0, 0]);
+        let matrices = import_qb(&mut buf.as_slice()).unwrap();
+        let v = matrices[0].world.get_voxel(0, 0, -1);
+        assert_eq!((v.r, v.g, v.b), (0, 0, 200));
+        assert!(matrices[0].world.get_voxel(1, 0, -1).is_air());
+    }
+
+    #[test]
+    fn test_qb_rejects_overlong_run() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&QB_VERSION);
+        for v in [0u32, 1, 1, 0, 1] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf.push(0);
+        for v in [2u32, 2, 1, 0, 0, 0, QB_CODEFLAG, u32::MAX, 0xFF00_00FF] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        assert!(matches!(
+            import_qb(&mut buf.as_slice()),
+            Err(QubicleError::Corrupt(_))
+        ));
+    }
+
+    /// A one-matrix QB header (RGBA, uncompressed) up to the cell data.
+    fn qb_header(left_handed: bool, size: [u32; 3], pos: [i32; 3]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&QB_VERSION);
+        for v in [0u32, !left_handed as u32, 0, 0, 1] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf.push(1);
+        buf.push(b'm');
+        for v in size {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in pos {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn test_matrices_wider_than_vox_round_trip() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(299, 3, 0, Voxel::from_rgb(0, 255, 0));
+        world.set_voxel(150, 0, 299, Voxel::from_rgb(0, 0, 255));
+        let mut buf = Vec::new();
+        export_qb(&[("wide", &world)], &mut buf).unwrap();
+        assert_same(&import_qb(&mut buf.as_slice()).unwrap()[0].world, &world);
+        let mut buf = Vec::new();
+        export_qbt(&[("wide", &world)], &mut buf).unwrap();
+        assert_same(&import_qbt(&mut buf.as_slice()).unwrap()[0].world, &world);
+    }
+
+    #[test]
+    fn test_matrices_over_the_cell_budget_are_not_exported() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(1, 1, 1));
+        world.set_voxel(500, 500, 500, Voxel::from_rgb(1, 1, 1));
+        for export in [export_qb::<Vec<u8>>, export_qbt::<Vec<u8>>] {
+            let mut buf = Vec::new();
+            let result = export(&[("huge", &world)], &mut buf);
+            assert!(matches!(result, Err(QubicleError::MatrixTooLarge(name)) if name == "huge"));
+            assert!(buf.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_oversized_matrices_are_rejected() {
+        // Rejected from the header alone, before any cell is read.
+        for size in [[1 << 13, 1 << 13, 2], [u32::MAX; 3]] {
+            let buf = qb_header(false, size, [0, 0, 0]);
+            assert!(matches!(import_qb(&mut buf.as_slice()), Err(QubicleError::Corrupt(_))));
+        }
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&QBT_MAGIC);
+        buf.extend_from_slice(&[1, 0]);
+        buf.extend_from_slice(&[0; 12]);
+        buf.extend_from_slice(b"COLORMAP");
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf.extend_from_slice(b"DATATREE");
+        for v in [QBT_MATRIX, 0, 1] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf.push(b'm');
+        buf.extend_from_slice(&[0; 12 + 12 + 12]);
+        for v in [1u32 << 13, 1 << 13, 2] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        assert!(matches!(import_qbt(&mut buf.as_slice()), Err(QubicleError::Corrupt(_))));
+    }
+
+    #[test]
+    fn test_matrices_past_the_world_edge_are_rejected() {
+        let mut buf = qb_header(false, [2, 1, 1], [i32::MAX, 0, 0]);
+        buf.extend_from_slice(&[0; 8]);
+        assert!(matches!(import_qb(&mut buf.as_slice()), Err(QubicleError::Corrupt(_))));
+
+        // Mirroring a left-handed matrix at the very bottom of Z lands
+        // it at the very top.
+        let mut buf = qb_header(true, [1, 1, 1], [0, 0, i32::MIN]);
+        buf.extend_from_slice(&[9, 9, 9, 255]);
+        let matrices = import_qb(&mut buf.as_slice()).unwrap();
+        assert!(matrices[0].world.get_voxel(0, 0, i32::MAX).is_solid());
+    }
+}
@@ -2,22 +2,36 @@
 //!
 //! VOX is the native format for MagicaVoxel, a popular voxel editor.
 //! Supports reading both **v150** (MagicaVoxel 0.97/0.98) and
-//! **v200** (0.99.7+) files. Writing always produces **v150** —
-//! every MagicaVoxel version reads it, and our `World` data model
-//! has no use for v200's scene graph / materials / layers.
+//! **v200** (0.99.7+) files. Writing produces **v150** whenever the
+//! world fits one 256³ model — every MagicaVoxel version reads it.
+//! Larger worlds are split into a grid of ≤256³ models tied together
+//! by a minimal v200 scene graph (root `nTRN` → `nGRP` → one `nTRN` +
+//! `nSHP` per model) sharing one palette; see [`export_vox_with_stats`].
 //!
 //! v200 reading flattens multi-model scene-graph files into the
 //! `World`'s single voxel grid: each `nSHP` model is placed at the
 //! position determined by the cumulative `nTRN` transform along
-//! its scene-tree path. Material / layer / camera / render-object
-//! chunks are read and discarded.
+//! its scene-tree path. `MATL` materials become a [`MaterialTable`],
+//! and export writes the table's non-diffuse materials back as `MATL`;
+//! chunks we don't interpret (render objects, cameras, notes, newer
+//! extensions) are kept verbatim by [`import_vox_full`] and written
+//! back by [`export_vox_with_chunks`]. Chunks are framed by their
+//! declared sizes alone, so unknown or extended chunks never throw the
+//! reader out of step.
+//!
+//! MagicaVoxel is Z-up and Voxelith Y-up; [`VoxAxes`] picks whether
+//! reading and writing swap the two (the editor's default) or copy
+//! coordinates unchanged. [`import_vox`], [`export_vox`] and
+//! [`export_vox_with_stats`] predate the choice and keep copying them
+//! unchanged; the `_full` / `_with_chunks` variants take the axes.
 //!
 //! Format spec:
 //! - v150 (basic): <https://github.com/ephtracy/voxel-model/blob/master/MagicaVoxel-file-format-vox.txt>
 //! - v200 extension: <https://github.com/ephtracy/voxel-model/blob/master/MagicaVoxel-file-format-vox-extension.txt>
 
-use crate::core::{Voxel, World};
+use crate::core::{MaterialDef, MaterialTable, Voxel, World};
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::io::{self, Read, Write};
 use thiserror::Error;
 
@@ -33,9 +47,13 @@ const MAX_VOXEL_HINT: usize = 1 << 20;
 const MAX_DICT_HINT: usize = 256;
 /// Version we write for export. v150 is the universal reader format.
 const VOX_VERSION_WRITE: i32 = 150;
+/// Version written when the file needs v200 chunks: the scene graph of
+/// a world split into several models, `MATL`, or chunks passed through
+/// from an imported file.
+const VOX_VERSION_SCENE: i32 = 200;
 /// Versions we accept on read. v150 = basic format, v200 = extended
-/// format with scene graph + materials (we read the geometry +
-/// transforms, ignore the materials/layers/etc).
+/// format with scene graph + materials (we read the geometry,
+/// transforms and materials, ignore the layers/etc).
 const VOX_VERSIONS_SUPPORTED: &[i32] = &[150, 200];
 
 /// Maximum dimension size for VOX format (256)
@@ -69,13 +87,13 @@ pub fn default_palette() -> [[u8; 4]; 256] {
     palette[0] = [0, 0, 0, 0];
 
     // Generate a varied color palette
-    for i in 1..256 {
+    for (i, entry) in palette.iter_mut().enumerate().skip(1) {
         let idx = i as u8;
         // Create varied colors based on index
         let r = ((idx.wrapping_mul(37)) ^ (idx >> 2)).wrapping_add(idx);
         let g = ((idx.wrapping_mul(73)) ^ (idx >> 3)).wrapping_add(idx.wrapping_mul(2));
         let b = ((idx.wrapping_mul(149)) ^ (idx >> 1)).wrapping_add(idx.wrapping_mul(3));
-        palette[i] = [r, g, b, 255];
+        *entry = [r, g, b, 255];
     }
 
     // Override with some common colors at the start
@@ -250,6 +268,175 @@ impl ChunkHeader {
     }
 }
 
+/// How VOX axes map onto Voxelith's. MagicaVoxel is Z-up; Voxelith
+/// is Y-up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VoxAxes {
+    /// VOX Z is up: models stand upright after import and export
+    /// standing up in MagicaVoxel. VOX `(x, y, z)` becomes world
+    /// `(x, z, -y)`, a rotation about X rather than a mirror.
+    #[default]
+    ZUp,
+    /// Coordinates copied unchanged; MagicaVoxel models lie on their
+    /// side.
+    AsIs,
+}
+
+impl VoxAxes {
+    pub const ALL: [VoxAxes; 2] = [VoxAxes::ZUp, VoxAxes::AsIs];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            VoxAxes::ZUp => "Z-up (MagicaVoxel)",
+            VoxAxes::AsIs => "Keep axes",
+        }
+    }
+
+    /// `world` laid out in VOX axes, ready to export; `None` when the
+    /// axes already match.
+    ///
+    /// The Y/Z swap reflects depth across the model's own Z bounds
+    /// instead of the origin, so the model keeps its place and
+    /// exporting then importing returns every voxel where it was.
+    fn world_to_vox(self, world: &World) -> Option<World> {
+        match self {
+            VoxAxes::AsIs => None,
+            VoxAxes::ZUp => {
+                let (min, max) = world.scene_aabb()?;
+                let flip = min.2 + max.2;
+                Some(remap_world(world, |(x, y, z)| (x, flip - z, y)))
+            }
+        }
+    }
+
+    /// Inverse of [`Self::world_to_vox`]: `world` read in VOX axes,
+    /// laid out in Voxelith's.
+    fn vox_to_world(self, world: World) -> World {
+        match (self, world.scene_aabb()) {
+            (VoxAxes::ZUp, Some((min, max))) => {
+                let flip = min.1 + max.1;
+                remap_world(&world, |(x, y, z)| (x, z, flip - y))
+            }
+            _ => world,
+        }
+    }
+}
+
+/// Copy of `world`'s solid voxels, each moved to `map(position)`.
+fn remap_world(world: &World, map: impl Fn((i32, i32, i32)) -> (i32, i32, i32)) -> World {
+    let mut out = World::new();
+    for (chunk_pos, chunk_lock) in world.chunks() {
+        let chunk = chunk_lock.read();
+        let (ox, oy, oz) = chunk_pos.world_origin();
+        out.set_voxels(chunk.iter_solid().map(|(local, voxel)| {
+            let pos = (ox + local.x as i32, oy + local.y as i32, oz + local.z as i32);
+            (map(pos), *voxel)
+        }));
+    }
+    out
+}
+
+/// A chunk Voxelith doesn't interpret — render objects (`rOBJ`),
+/// cameras (`rCAM`), palette notes (`NOTE`) and anything a newer
+/// MagicaVoxel adds. [`import_vox_full`] keeps these verbatim so
+/// [`export_vox_with_chunks`] can write them back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoxChunk {
+    pub id: [u8; 4],
+    pub content: Vec<u8>,
+    /// Raw nested chunks; empty for every chunk MagicaVoxel writes
+    /// today.
+    pub children: Vec<u8>,
+}
+
+impl VoxChunk {
+    /// Header + content + children, in bytes.
+    fn len(&self) -> usize {
+        12 + self.content.len() + self.children.len()
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        ChunkHeader {
+            id: self.id,
+            content_size: self.content.len() as i32,
+            children_size: self.children.len() as i32,
+        }
+        .write(writer)?;
+        writer.write_all(&self.content)?;
+        writer.write_all(&self.children)
+    }
+}
+
+/// Chunks we understand but drop on import: `LAYR` (layers of the
+/// flattened scene graph), `IMAP` (palette display order, stale once
+/// the palette is rebuilt on export), `PACK` (model count, obsolete)
+/// and `MATT` (pre-`MATL` materials). Everything else we don't parse
+/// is passed through as a [`VoxChunk`].
+const DROPPED_CHUNKS: &[[u8; 4]] = &[*b"LAYR", *b"IMAP", *b"PACK", *b"MATT"];
+
+/// Streams the chunks under `MAIN`, one at a time.
+///
+/// Each chunk's content is read whole (via `read_exact_vec`, so a
+/// bogus size can't force a huge allocation) before anything parses
+/// it. A parser that reads less than the declared size — or a newer
+/// version that appends fields — therefore can't knock the stream out
+/// of step with the next header; the declared sizes alone decide where
+/// every chunk starts.
+struct ChunkStream<'a, R> {
+    reader: &'a mut R,
+    /// Bytes of `MAIN`'s children not yet consumed.
+    remaining: u64,
+}
+
+impl<'a, R: Read> ChunkStream<'a, R> {
+    /// Read the file header and the `MAIN` chunk header.
+    fn open(reader: &'a mut R) -> Result<(Self, i32), VoxError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != VOX_MAGIC {
+            return Err(VoxError::InvalidMagic);
+        }
+
+        let mut version_buf = [0u8; 4];
+        reader.read_exact(&mut version_buf)?;
+        let version = i32::from_le_bytes(version_buf);
+
+        let main_header = ChunkHeader::read(reader)?;
+        if &main_header.id != b"MAIN" {
+            return Err(VoxError::InvalidChunkId(main_header.id));
+        }
+        // MAIN has no content of its own, but skip any a writer put
+        // there rather than parsing it as a child.
+        super::skip_bytes(reader, main_header.content_size.max(0) as u64)?;
+        let stream = Self {
+            reader,
+            remaining: main_header.children_size.max(0) as u64,
+        };
+        Ok((stream, version))
+    }
+
+    /// The next child chunk, or `None` once `MAIN`'s declared children
+    /// are used up. A chunk that claims more than what's left of `MAIN`
+    /// still reads whole and ends the stream.
+    fn next_chunk(&mut self) -> Result<Option<VoxChunk>, VoxError> {
+        if self.remaining < 12 {
+            return Ok(None);
+        }
+        let header = ChunkHeader::read(self.reader)?;
+        let content_size = header.content_size.max(0) as usize;
+        let children_size = header.children_size.max(0) as usize;
+        let content = super::read_exact_vec(self.reader, content_size)?;
+        let children = super::read_exact_vec(self.reader, children_size)?;
+        let total = 12 + content_size as u64 + children_size as u64;
+        self.remaining = self.remaining.saturating_sub(total);
+        Ok(Some(VoxChunk {
+            id: header.id,
+            content,
+            children,
+        }))
+    }
+}
+
 /// One model's geometry within a v200 scene (size + voxel list,
 /// no palette — palette is shared at the scene level).
 #[derive(Debug, Clone)]
@@ -272,23 +459,20 @@ struct VoxScene {
     models: Vec<VoxModelData>,
     palette: [[u8; 4]; 256],
     nodes: HashMap<i32, SceneNode>,
+    /// `MATL` properties by palette index (the index voxels use in
+    /// `XYZI`, so slot 0 never appears).
+    materials: HashMap<u8, HashMap<String, String>>,
+    /// Chunks passed through untouched, in file order.
+    extra_chunks: Vec<VoxChunk>,
 }
 
 impl VoxScene {
     /// Read a v150 or v200 VOX file. Multi-model + scene graph
-    /// are preserved; ignored chunks (`MATL`, `LAYR`, `IMAP`,
-    /// `rOBJ`, `rCAM`, `NOTE`, `INFO`, `PACK`, `MATT`) are skipped
-    /// by their declared content size.
+    /// are preserved, `MATL` is kept for [`material_from_matl`], the
+    /// [`DROPPED_CHUNKS`] are discarded and every other chunk is kept
+    /// verbatim in `extra_chunks`.
     pub fn read<R: Read>(reader: &mut R) -> Result<Self, VoxError> {
-        let mut magic = [0u8; 4];
-        reader.read_exact(&mut magic)?;
-        if magic != VOX_MAGIC {
-            return Err(VoxError::InvalidMagic);
-        }
-
-        let mut version_buf = [0u8; 4];
-        reader.read_exact(&mut version_buf)?;
-        let version = i32::from_le_bytes(version_buf);
+        let (mut stream, version) = ChunkStream::open(reader)?;
         if !VOX_VERSIONS_SUPPORTED.contains(&version) {
             log::warn!(
                 "VOX version {} (supported {:?}), attempting to read anyway",
@@ -297,46 +481,41 @@ impl VoxScene {
             );
         }
 
-        let main_header = ChunkHeader::read(reader)?;
-        if &main_header.id != b"MAIN" {
-            return Err(VoxError::InvalidChunkId(main_header.id));
-        }
-
         let mut models: Vec<VoxModelData> = Vec::new();
         let mut palette = default_palette();
         let mut nodes: HashMap<i32, SceneNode> = HashMap::new();
+        let mut materials = HashMap::new();
+        let mut extra_chunks = Vec::new();
         // SIZE/XYZI come in pairs; a SIZE chunk publishes a pending
         // size that the next XYZI chunk consumes when writing into
         // a fresh `VoxModelData`.
         let mut pending_size: Option<(u32, u32, u32)> = None;
 
-        let mut bytes_read = 0i32;
-        while bytes_read < main_header.children_size {
-            let chunk_header = ChunkHeader::read(reader)?;
-            bytes_read +=
-                12 + chunk_header.content_size + chunk_header.children_size;
-
-            match &chunk_header.id {
+        while let Some(chunk) = stream.next_chunk()? {
+            // Parse from the chunk's own bytes: a short read is an
+            // error for this chunk only, a long one can't overrun.
+            let content = &mut chunk.content.as_slice();
+            match &chunk.id {
                 b"SIZE" => {
                     let mut buf = [0u8; 4];
-                    reader.read_exact(&mut buf)?;
+                    content.read_exact(&mut buf)?;
                     let x = u32::from_le_bytes(buf);
-                    reader.read_exact(&mut buf)?;
+                    content.read_exact(&mut buf)?;
                     let y = u32::from_le_bytes(buf);
-                    reader.read_exact(&mut buf)?;
+                    content.read_exact(&mut buf)?;
                     let z = u32::from_le_bytes(buf);
                     pending_size = Some((x, y, z));
                 }
                 b"XYZI" => {
                     let mut buf = [0u8; 4];
-                    reader.read_exact(&mut buf)?;
+                    content.read_exact(&mut buf)?;
                     // `.max(0)` first: a negative count would sign-extend
                     // to a colossal usize and make `with_capacity` abort.
                     let num_voxels = i32::from_le_bytes(buf).max(0) as usize;
                     let mut voxels = Vec::with_capacity(num_voxels.min(MAX_VOXEL_HINT));
                     for _ in 0..num_voxels {
                         let mut voxel_data = [0u8; 4];
-                        reader.read_exact(&mut voxel_data)?;
+                        content.read_exact(&mut voxel_data)?;
                         voxels.push((
                             voxel_data[0],
                             voxel_data[1],
@@ -350,7 +529,7 @@ impl VoxScene {
                 b"RGBA" => {
                     for i in 0..256 {
                         let mut color = [0u8; 4];
-                        reader.read_exact(&mut color)?;
+                        content.read_exact(&mut color)?;
                         // VOX file index 0..254 maps to palette
                         // 1..255 (palette[0] is reserved as
                         // empty/transparent). File index 255 is
@@ -360,37 +539,30 @@ impl VoxScene {
                     }
                 }
                 b"nTRN" => {
-                    let node = read_ntrn_chunk(reader, chunk_header.content_size)?;
-                    if let Some((id, n)) = node {
-                        nodes.insert(id, n);
-                    }
+                    let (id, node) = read_ntrn_chunk(content)?;
+                    nodes.insert(id, node);
                 }
                 b"nGRP" => {
-                    let node = read_ngrp_chunk(reader, chunk_header.content_size)?;
-                    if let Some((id, n)) = node {
-                        nodes.insert(id, n);
-                    }
+                    let (id, node) = read_ngrp_chunk(content)?;
+                    nodes.insert(id, node);
                 }
                 b"nSHP" => {
-                    let node = read_nshp_chunk(reader, chunk_header.content_size)?;
-                    if let Some((id, n)) = node {
-                        nodes.insert(id, n);
-                    }
+                    let (id, node) = read_nshp_chunk(content)?;
+                    nodes.insert(id, node);
                 }
-                _ => {
-                    // Skip MATL / LAYR / IMAP / rOBJ / rCAM / NOTE /
-                    // INFO / PACK / MATT / unknowns by streaming past
-                    // exactly `content_size` bytes — no big buffer
-                    // allocation even if a corrupt header inflates it.
-                    super::skip_bytes(reader, chunk_header.content_size.max(0) as u64)?;
+                b"MATL" => {
+                    // Materials only change how voxels look; a garbled
+                    // one shouldn't cost the user the model.
+                    match read_matl_chunk(content) {
+                        Ok((id, props)) if (1..=255).contains(&id) => {
+                            materials.insert(id as u8, props);
+                        }
+                        Ok((id, _)) => log::warn!("VOX MATL for palette index {id} ignored"),
+                        Err(e) => log::warn!("Skipping malformed VOX MATL chunk: {e}"),
+                    }
                 }
-            }
-
-            // Children section. v200 chunks (nTRN/nGRP/nSHP/MATL/…)
-            // all declare children_size = 0; v150 also doesn't use
-            // nested chunks under MAIN's children. Skip defensively.
-            if chunk_header.children_size > 0 {
-                super::skip_bytes(reader, chunk_header.children_size as u64)?;
+                id if DROPPED_CHUNKS.contains(id) => {}
+                _ => extra_chunks.push(chunk),
             }
         }
 
@@ -402,19 +574,68 @@ impl VoxScene {
             models,
             palette,
             nodes,
+            materials,
+            extra_chunks,
         })
     }
 
+    /// The voxel each palette index becomes, plus the material table
+    /// those voxels refer to. Palette entries with a non-diffuse `MATL`
+    /// get a material; entries with identical properties share one.
+    fn palette_voxels(&self) -> ([Voxel; 256], MaterialTable) {
+        let mut table = MaterialTable::new();
+        let mut voxels = [Voxel::AIR; 256];
+        let mut indices: Vec<_> = self.materials.keys().copied().collect();
+        indices.sort_unstable();
+        let mut ids: Vec<(MaterialDef, u16)> = Vec::new();
+        for (i, voxel) in voxels.iter_mut().enumerate().skip(1) {
+            let [r, g, b, a] = self.palette[i];
+            *voxel = Voxel::from_rgba(r, g, b, a);
+        }
+        for index in indices {
+            let Some(def) = material_from_matl(index, &self.materials[&index]) else {
+                continue;
+            };
+            let same = |(d, _): &&(MaterialDef, u16)| {
+                (d.roughness, d.metallic, d.emissive, d.transparency)
+                    == (def.roughness, def.metallic, def.emissive, def.transparency)
+            };
+            let id = match ids.iter().find(same) {
+                Some(&(_, id)) => id,
+                None => match table.add(def.clone()) {
+                    Some(id) => {
+                        ids.push((def, id));
+                        id
+                    }
+                    // Table full: the rest keep the default material.
+                    None => break,
+                },
+            };
+            voxels[index as usize].material = id;
+        }
+        for voxel in voxels.iter_mut().skip(1) {
+            *voxel = table.stamp(*voxel);
+        }
+        (voxels, table)
+    }
+
     /// Flatten the scene graph into a `World`. Walks the tree from
     /// the root `nTRN` (MagicaVoxel convention: id 0), accumulates
     /// translation and rotation, and at each `nSHP` places the
     /// referenced model's voxels rotated around the model's center.
     ///
+    /// The result is converted from VOX to world axes by `axes`.
+    ///
     /// If the scene has no `nTRN` nodes (v150 single-model files
     /// or v200 files we read before the scene graph existed), every
     /// model is placed at the origin — same behavior as the old
     /// single-model reader.
-    pub fn to_world(&self) -> World {
+    pub fn to_world(&self, voxels: &[Voxel; 256], axes: VoxAxes) -> World {
+        axes.vox_to_world(self.to_vox_world(voxels))
+    }
+
+    /// [`Self::to_world`] in VOX axes.
+    fn to_vox_world(&self, voxels: &[Voxel; 256]) -> World {
         let mut world = World::new();
         if self.nodes.is_empty() || !self.nodes.contains_key(&0) {
             // No scene graph: write model voxels directly into
@@ -424,30 +645,24 @@ impl VoxScene {
             // identity. Multi-model v200 files without a scene
             // graph (rare, malformed) get every model overlapped
             // at the origin; users would notice and fix the source.
-            for model in &self.models {
-                for &(x, y, z, color_idx) in &model.voxels {
-                    if color_idx == 0 {
-                        continue;
-                    }
-                    let color = self.palette[color_idx as usize];
-                    let voxel =
-                        Voxel::from_rgba(color[0], color[1], color[2], color[3]);
-                    world.set_voxel(x as i32, y as i32, z as i32, voxel);
-                }
-            }
+            let model_voxels = self.models.iter().flat_map(|model| &model.voxels);
+            world.set_voxels(model_voxels.filter(|v| v.3 != 0).map(|&(x, y, z, color_idx)| {
+                ((x as i32, y as i32, z as i32), voxels[color_idx as usize])
+            }));
             return world;
         }
 
         // DFS from root id 0.
         let mut visited: std::collections::HashSet<i32> =
             std::collections::HashSet::new();
-        self.flatten_node(&mut world, 0, (0, 0, 0), ROT_IDENTITY, &mut visited);
+        self.flatten_node(&mut world, voxels, 0, (0, 0, 0), ROT_IDENTITY, &mut visited);
         world
     }
 
     fn flatten_node(
         &self,
         world: &mut World,
+        voxels: &[Voxel; 256],
         node_id: i32,
         translation: (i32, i32, i32),
         rotation: [[i32; 3]; 3],
@@ -478,11 +693,11 @@ impl VoxScene {
                     translation.2 + rotated_t.2,
                 );
                 let new_r = rotation_compose(rotation, *local_r);
-                self.flatten_node(world, *child_id, new_t, new_r, visited);
+                self.flatten_node(world, voxels, *child_id, new_t, new_r, visited);
             }
             SceneNode::Group { children } => {
                 for &child_id in children {
-                    self.flatten_node(world, child_id, translation, rotation, visited);
+                    self.flatten_node(world, voxels, child_id, translation, rotation, visited);
                 }
             }
             SceneNode::Shape { model_ids } => {
@@ -490,7 +705,7 @@ impl VoxScene {
                     if let Some(model) =
                         self.models.get(model_id.max(0) as usize)
                     {
-                        place_model(world, model, &self.palette, translation, rotation);
+                        place_model(world, model, voxels, translation, rotation);
                     }
                 }
             }
@@ -499,12 +714,13 @@ impl VoxScene {
 }
 
 /// Place one model into the world at `translation`, rotated by
-/// `rotation` around the model's geometric center. Skips palette
-/// index 0 (empty/transparent).
+/// `rotation` around the model's geometric center, each voxel taken
+/// from `voxels` by its palette index. Skips palette index 0
+/// (empty/transparent).
 fn place_model(
     world: &mut World,
     model: &VoxModelData,
-    palette: &[[u8; 4]; 256],
+    voxels: &[Voxel; 256],
     translation: (i32, i32, i32),
     rotation: [[i32; 3]; 3],
 ) {
@@ -514,10 +730,7 @@ fn place_model(
     let cx = (model.size.0 as i32) / 2;
     let cy = (model.size.1 as i32) / 2;
     let cz = (model.size.2 as i32) / 2;
-    for &(x, y, z, color_idx) in &model.voxels {
-        if color_idx == 0 {
-            continue;
-        }
+    world.set_voxels(model.voxels.iter().filter(|v| v.3 != 0).map(|&(x, y, z, color_idx)| {
         let local = (x as i32 - cx, y as i32 - cy, z as i32 - cz);
         let rotated = apply_rotation(rotation, local);
         let world_pos = (
@@ -525,14 +738,11 @@ fn place_model(
             translation.1 + rotated.1,
             translation.2 + rotated.2,
         );
-        let color = palette[color_idx as usize];
-        let voxel = Voxel::from_rgba(color[0], color[1], color[2], color[3]);
-        world.set_voxel(world_pos.0, world_pos.1, world_pos.2, voxel);
-    }
+        (world_pos, voxels[color_idx as usize])
+    }));
 }
 
-/// Read an `nTRN` chunk's body. Returns `Some((id, node))` on
-/// success; `None` on malformed input (caller treats as no-op).
+/// Read an `nTRN` chunk's body into `(id, node)`.
 ///
 /// Layout (per vox-extension spec):
 /// - `i32` node id
@@ -543,10 +753,7 @@ fn place_model(
 /// - `i32` num frames (≥ 1; we use frame 0)
 /// - per frame: DICT with optional `_r` (rotation byte string),
 ///   `_t` (translation "x y z"), `_f` (frame index)
-fn read_ntrn_chunk<R: Read>(
-    reader: &mut R,
-    _content_size: i32,
-) -> Result<Option<(i32, SceneNode)>, VoxError> {
+fn read_ntrn_chunk<R: Read>(reader: &mut R) -> Result<(i32, SceneNode), VoxError> {
     let mut i32buf = [0u8; 4];
     reader.read_exact(&mut i32buf)?;
     let node_id = i32::from_le_bytes(i32buf);
@@ -586,22 +793,19 @@ fn read_ntrn_chunk<R: Read>(
         }
     }
 
-    Ok(Some((
+    Ok((
         node_id,
         SceneNode::Transform {
             child_id,
             translation,
             rotation,
         },
-    )))
+    ))
 }
 
 /// Read an `nGRP` chunk. Layout: `i32` node id + DICT + `i32` num
 /// children + N × `i32` child node ids.
-fn read_ngrp_chunk<R: Read>(
-    reader: &mut R,
-    _content_size: i32,
-) -> Result<Option<(i32, SceneNode)>, VoxError> {
+fn read_ngrp_chunk<R: Read>(reader: &mut R) -> Result<(i32, SceneNode), VoxError> {
     let mut i32buf = [0u8; 4];
     reader.read_exact(&mut i32buf)?;
     let node_id = i32::from_le_bytes(i32buf);
@@ -615,15 +819,12 @@ fn read_ngrp_chunk<R: Read>(
         reader.read_exact(&mut i32buf)?;
         children.push(i32::from_le_bytes(i32buf));
     }
-    Ok(Some((node_id, SceneNode::Group { children })))
+    Ok((node_id, SceneNode::Group { children }))
 }
 
 /// Read an `nSHP` chunk. Layout: `i32` node id + DICT + `i32` num
 /// models + N × {`i32` model id, DICT model-attrs}.
-fn read_nshp_chunk<R: Read>(
-    reader: &mut R,
-    _content_size: i32,
-) -> Result<Option<(i32, SceneNode)>, VoxError> {
+fn read_nshp_chunk<R: Read>(reader: &mut R) -> Result<(i32, SceneNode), VoxError> {
     let mut i32buf = [0u8; 4];
     reader.read_exact(&mut i32buf)?;
     let node_id = i32::from_le_bytes(i32buf);
@@ -637,7 +838,51 @@ fn read_nshp_chunk<R: Read>(
         model_ids.push(i32::from_le_bytes(i32buf));
         let _model_attrs = read_vox_dict(reader)?;
     }
-    Ok(Some((node_id, SceneNode::Shape { model_ids })))
+    Ok((node_id, SceneNode::Shape { model_ids }))
+}
+
+/// Read a `MATL` chunk: `i32` material id (the palette index it
+/// applies to) + DICT of properties.
+fn read_matl_chunk<R: Read>(reader: &mut R) -> io::Result<(i32, HashMap<String, String>)> {
+    let mut i32buf = [0u8; 4];
+    reader.read_exact(&mut i32buf)?;
+    Ok((i32::from_le_bytes(i32buf), read_vox_dict(reader)?))
+}
+
+/// Translate `MATL` properties into a [`MaterialDef`]; `None` for a
+/// plain diffuse material, which is just the default one.
+///
+/// MagicaVoxel's `_type` picks which properties count: `_metal` is the
+/// metalness of `_metal` / `_blend`, `_trans` (older files: `_alpha`)
+/// the transparency of `_glass` / `_blend`, and `_emit` scaled by the
+/// `_flux` power the emission of `_emit` / `_blend`. `_rough` applies
+/// to all of them.
+fn material_from_matl(index: u8, props: &HashMap<String, String>) -> Option<MaterialDef> {
+    let num = |key: &str| props.get(key).and_then(|v| v.parse::<f32>().ok());
+    let kind = props.get("_type").map(String::as_str).unwrap_or("_diffuse");
+    let (metal, glass, emit) = match kind {
+        "_metal" => (true, false, false),
+        "_glass" => (false, true, false),
+        "_emit" => (false, false, true),
+        "_blend" => (true, true, true),
+        _ => return None,
+    };
+    let mut def = MaterialDef::new(format!("VOX {} {}", kind.trim_start_matches('_'), index));
+    if let Some(rough) = num("_rough") {
+        def.roughness = rough.clamp(0.0, 1.0);
+    }
+    if metal {
+        def.metallic = num("_metal").unwrap_or(0.0).clamp(0.0, 1.0);
+    }
+    if glass {
+        def.transparency = num("_trans").or_else(|| num("_alpha")).unwrap_or(0.0).clamp(0.0, 1.0);
+    }
+    if emit {
+        let flux = num("_flux").unwrap_or(0.0).max(0.0);
+        def.emissive = (num("_emit").unwrap_or(0.0) * (1.0 + flux)).clamp(0.0, MaterialDef::MAX_EMISSIVE);
+    }
+    let plain = def.metallic == 0.0 && def.transparency == 0.0 && def.emissive == 0.0;
+    (!plain).then_some(def)
 }
 
 /// Voxel data for VOX format
@@ -666,8 +911,24 @@ impl VoxModel {
         }
     }
 
-    /// Create model from world
-    pub fn from_world(world: &World) -> Result<Self, VoxError> {
+    /// Create model from world, converting world axes to VOX ones by
+    /// `axes`.
+    pub fn from_world(world: &World, axes: VoxAxes) -> Result<Self, VoxError> {
+        let table = MaterialTable::new();
+        let (model, _) = match axes.world_to_vox(world) {
+            Some(converted) => Self::from_vox_world(&converted, &table)?,
+            None => Self::from_vox_world(world, &table)?,
+        };
+        Ok(model)
+    }
+
+    /// [`Self::from_world`] for a world already in VOX axes, plus the
+    /// `MATL` chunks for the palette slots whose voxels use a material
+    /// from `materials` (see [`PaletteBuilder::matl_chunks`]).
+    fn from_vox_world(
+        world: &World,
+        materials: &MaterialTable,
+    ) -> Result<(Self, Vec<VoxChunk>), VoxError> {
         // Find bounding box of all voxels
         let mut min_x = i32::MAX;
         let mut min_y = i32::MAX;
@@ -697,7 +958,7 @@ impl VoxModel {
 
         // Handle empty world
         if min_x > max_x {
-            return Ok(Self::new((1, 1, 1)));
+            return Ok((Self::new((1, 1, 1)), Vec::new()));
         }
 
         // Calculate size
@@ -710,14 +971,7 @@ impl VoxModel {
             return Err(VoxError::ModelTooLarge);
         }
 
-        // Build color palette from unique colors
-        let mut color_to_index: HashMap<[u8; 3], u8> = HashMap::new();
-        let mut palette = default_palette();
-        let mut next_index = 1u8; // 0 is reserved for empty
-        // Distinct colors we had to quantize because the palette filled.
-        let mut overflow_colors: std::collections::HashSet<[u8; 3]> =
-            std::collections::HashSet::new();
-
+        let mut palette = PaletteBuilder::new(materials);
         let mut voxels = Vec::new();
 
         // Second pass: collect voxels and build palette
@@ -730,67 +984,63 @@ impl VoxModel {
                 let y = oy + local_pos.y as i32 - min_y;
                 let z = oz + local_pos.z as i32 - min_z;
 
-                let color = [voxel.r, voxel.g, voxel.b];
-
-                let color_index = if let Some(&idx) = color_to_index.get(&color) {
-                    idx
-                } else if next_index < 255 {
-                    let idx = next_index;
-                    color_to_index.insert(color, idx);
-                    palette[idx as usize] = [color[0], color[1], color[2], 255];
-                    next_index += 1;
-                    idx
-                } else {
-                    // Palette full — quantize to the nearest existing
-                    // entry. Track *distinct* lossy colors so the UI
-                    // can report something meaningful (multiple voxels
-                    // sharing the same lost color count as one).
-                    overflow_colors.insert(color);
-                    find_closest_color(&palette, color)
-                };
-
+                let color_index = palette.index_of(voxel);
                 voxels.push((x as u8, y as u8, z as u8, color_index));
             }
         }
 
-        Ok(Self {
+        let matl = palette.matl_chunks();
+        let model = Self {
             size: (size_x, size_y, size_z),
             voxels,
-            palette,
-            palette_overflow: overflow_colors.len() as u32,
-        })
+            palette_overflow: palette.overflow(),
+            palette: palette.palette,
+        };
+        Ok((model, matl))
     }
 
-    /// Convert to world
-    pub fn to_world(&self) -> World {
+    /// Convert to world, converting VOX axes to world ones by `axes`.
+    pub fn to_world(&self, axes: VoxAxes) -> World {
         let mut world = World::new();
 
-        for &(x, y, z, color_index) in &self.voxels {
-            if color_index > 0 {
-                let color = self.palette[color_index as usize];
-                let voxel = Voxel::from_rgba(color[0], color[1], color[2], color[3]);
-                world.set_voxel(x as i32, y as i32, z as i32, voxel);
-            }
-        }
+        world.set_voxels(self.voxels.iter().filter(|v| v.3 > 0).map(|&(x, y, z, color_index)| {
+            let color = self.palette[color_index as usize];
+            let voxel = Voxel::from_rgba(color[0], color[1], color[2], color[3]);
+            ((x as i32, y as i32, z as i32), voxel)
+        }));
 
-        world
+        axes.vox_to_world(world)
     }
 
     /// Write to VOX file
     pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), VoxError> {
+        self.write_with_chunks(writer, &[])
+    }
+
+    /// Write to VOX file, appending `extra` after the palette. The
+    /// header says v150 unless there are `extra` chunks, which only
+    /// v200 readers know.
+    fn write_with_chunks<W: Write>(&self, writer: &mut W, extra: &[VoxChunk]) -> Result<(), VoxError> {
         // Write header
         writer.write_all(&VOX_MAGIC)?;
-        writer.write_all(&VOX_VERSION_WRITE.to_le_bytes())?;
+        let version = if extra.is_empty() {
+            VOX_VERSION_WRITE
+        } else {
+            VOX_VERSION_SCENE
+        };
+        writer.write_all(&version.to_le_bytes())?;
 
         // Calculate chunk sizes
         let size_content = 12; // 3 x i32
         let xyzi_content = 4 + (self.voxels.len() * 4) as i32; // count + voxels
         let rgba_content = 256 * 4; // 256 colors x 4 bytes
 
+        let extra_size: usize = extra.iter().map(VoxChunk::len).sum();
         let children_size =
             12 + size_content +  // SIZE chunk
             12 + xyzi_content +  // XYZI chunk
-            12 + rgba_content;   // RGBA chunk
+            12 + rgba_content +  // RGBA chunk
+            extra_size as i32;
 
         // Write MAIN chunk header
         ChunkHeader {
@@ -833,20 +1083,322 @@ impl VoxModel {
         }
         writer.write_all(&[0, 0, 0, 0])?; // Unused entry
 
+        for chunk in extra {
+            chunk.write(writer)?;
+        }
+
         Ok(())
     }
 }
 
+/// Assigns VOX palette slots to world colors in first-seen order,
+/// shared by the single-model and split exporters so every model of a
+/// split file indexes one palette.
+///
+/// `MATL` materials belong to a palette slot, so a color used with a
+/// metal, glass or emissive material gets a slot of its own per such
+/// material. Voxels with any plain diffuse material share the color's
+/// slot, which is written without a `MATL`.
+struct PaletteBuilder<'a> {
+    materials: &'a MaterialTable,
+    /// Keyed by color and [`Self::matl_id`].
+    color_to_index: HashMap<([u8; 3], u16), u8>,
+    palette: [[u8; 4]; 256],
+    /// Material written as each slot's `MATL`; 0 = none.
+    slot_materials: [u16; 256],
+    next_index: u8,
+    /// Distinct colors we had to quantize because the palette filled.
+    overflow_colors: std::collections::HashSet<([u8; 3], u16)>,
+}
+
+impl<'a> PaletteBuilder<'a> {
+    fn new(materials: &'a MaterialTable) -> Self {
+        Self {
+            materials,
+            color_to_index: HashMap::new(),
+            palette: default_palette(),
+            slot_materials: [0; 256],
+            next_index: 1, // 0 is reserved for empty
+            overflow_colors: std::collections::HashSet::new(),
+        }
+    }
+
+    /// `voxel`'s material id if it needs a `MATL` chunk — one defined
+    /// in the table that isn't plain diffuse (see
+    /// [`material_from_matl`]) — else 0.
+    fn matl_id(&self, voxel: &Voxel) -> u16 {
+        match self.materials.get(voxel.material) {
+            Some(def) if def.metallic > 0.0 || def.transparency > 0.0 || def.emissive > 0.0 => {
+                voxel.material
+            }
+            _ => 0,
+        }
+    }
+
+    /// Palette slot for `voxel`'s color and material, claiming a new
+    /// one while any are free.
+    fn index_of(&mut self, voxel: &Voxel) -> u8 {
+        let color = [voxel.r, voxel.g, voxel.b];
+        let key = (color, self.matl_id(voxel));
+        if let Some(&idx) = self.color_to_index.get(&key) {
+            idx
+        } else if self.next_index < 255 {
+            let idx = self.next_index;
+            self.color_to_index.insert(key, idx);
+            self.palette[idx as usize] = [color[0], color[1], color[2], 255];
+            self.slot_materials[idx as usize] = key.1;
+            self.next_index += 1;
+            idx
+        } else {
+            // Palette full — quantize to the nearest existing entry.
+            // Track *distinct* lossy colors so the UI can report
+            // something meaningful (multiple voxels sharing the same
+            // lost color count as one).
+            self.overflow_colors.insert(key);
+            find_closest_color(&self.palette, color)
+        }
+    }
+
+    fn overflow(&self) -> u32 {
+        self.overflow_colors.len() as u32
+    }
+
+    /// One `MATL` chunk per slot claimed with a material, in slot
+    /// order.
+    fn matl_chunks(&self) -> Vec<VoxChunk> {
+        let slots = self.slot_materials.iter().enumerate();
+        slots
+            .filter(|&(_, &id)| id != 0)
+            .map(|(index, &id)| matl_chunk(index as u8, self.materials.resolve(id)))
+            .collect()
+    }
+}
+
+/// The `MATL` chunk giving palette slot `index` material `def`, the
+/// inverse of [`material_from_matl`]: `_type` is `_metal`, `_glass` or
+/// `_emit` when only that property is set and `_blend` otherwise.
+/// Emission above 1 goes into `_flux`, since MagicaVoxel's `_emit`
+/// tops out at 1.
+fn matl_chunk(index: u8, def: &MaterialDef) -> VoxChunk {
+    let (metal, glass, emit) = (def.metallic > 0.0, def.transparency > 0.0, def.emissive > 0.0);
+    let kind = match (metal, glass, emit) {
+        (true, false, false) => "_metal",
+        (false, true, false) => "_glass",
+        (false, false, true) => "_emit",
+        _ => "_blend",
+    };
+    let rough = def.roughness.to_string();
+    let metallic = def.metallic.to_string();
+    let trans = def.transparency.to_string();
+    let emission = def.emissive.min(1.0).to_string();
+    let flux = (def.emissive - 1.0).max(0.0).to_string();
+    let mut props = vec![("_type", kind), ("_rough", rough.as_str())];
+    if metal {
+        props.push(("_metal", &metallic));
+    }
+    if glass {
+        props.push(("_trans", &trans));
+    }
+    if emit {
+        props.push(("_emit", &emission));
+        props.push(("_flux", &flux));
+    }
+    let mut content = Vec::new();
+    content.extend_from_slice(&(index as i32).to_le_bytes());
+    push_vox_dict(&mut content, &props);
+    VoxChunk {
+        id: *b"MATL",
+        content,
+        children: Vec::new(),
+    }
+}
+
+/// Summary of a VOX export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoxExportStats {
+    /// Distinct world colors quantized to the nearest palette entry
+    /// because the 255-slot palette filled — 0 means lossless.
+    pub palette_overflow: u32,
+    /// Models written: 1 for a world that fits 256³, more when it was
+    /// split.
+    pub models: usize,
+}
+
+/// Append a chunk (`id`, content, no children) to `out`.
+fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], content: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(content.len() as i32).to_le_bytes());
+    out.extend_from_slice(&0i32.to_le_bytes());
+    out.extend_from_slice(content);
+}
+
+/// Append a VOX STRING (`i32` length + bytes).
+fn push_vox_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as i32).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// Append a VOX DICT of string pairs.
+fn push_vox_dict(out: &mut Vec<u8>, pairs: &[(&str, &str)]) {
+    out.extend_from_slice(&(pairs.len() as i32).to_le_bytes());
+    for (k, v) in pairs {
+        push_vox_string(out, k);
+        push_vox_string(out, v);
+    }
+}
+
+/// Append an `nTRN` chunk with a single frame translated by `t`.
+fn push_ntrn(out: &mut Vec<u8>, node_id: i32, child_id: i32, layer_id: i32, t: Option<(i32, i32, i32)>) {
+    let mut c = Vec::new();
+    c.extend_from_slice(&node_id.to_le_bytes());
+    push_vox_dict(&mut c, &[]);
+    c.extend_from_slice(&child_id.to_le_bytes());
+    c.extend_from_slice(&(-1i32).to_le_bytes()); // reserved
+    c.extend_from_slice(&layer_id.to_le_bytes());
+    c.extend_from_slice(&1i32.to_le_bytes()); // one frame
+    match t {
+        Some((x, y, z)) => push_vox_dict(&mut c, &[("_t", &format!("{x} {y} {z}"))]),
+        None => push_vox_dict(&mut c, &[]),
+    }
+    push_chunk(out, b"nTRN", &c);
+}
+
+/// `(x, y, z, palette_index)` of one voxel inside a model.
+type ModelVoxel = (u8, u8, u8, u8);
+
+/// Write `world` as a v200 file of ≤256³ models on a 256-cell grid
+/// anchored at the world's minimum corner.
+///
+/// Each model's `nTRN` translation is its grid offset plus half its
+/// size, because readers (ours in `place_model`, and MagicaVoxel)
+/// position a model by its center. Model sizes are rounded up to even
+/// so that center is exact and no reader can be off by one. Empty grid
+/// cells produce no model. Positions are relative to the world minimum,
+/// like the single-model export.
+fn write_split_vox<W: Write>(
+    world: &World,
+    min: (i32, i32, i32),
+    materials: &MaterialTable,
+    extra: &[VoxChunk],
+    writer: &mut W,
+) -> Result<VoxExportStats, VoxError> {
+    let tile = MAX_VOX_SIZE as i32;
+    let mut palette = PaletteBuilder::new(materials);
+    let mut tiles: HashMap<(i32, i32, i32), Vec<ModelVoxel>> = HashMap::new();
+    for (chunk_pos, chunk_lock) in world.chunks() {
+        let chunk = chunk_lock.read();
+        let (ox, oy, oz) = chunk_pos.world_origin();
+        for (local_pos, voxel) in chunk.iter_solid() {
+            let rel = (
+                ox + local_pos.x as i32 - min.0,
+                oy + local_pos.y as i32 - min.1,
+                oz + local_pos.z as i32 - min.2,
+            );
+            let key = (rel.0 / tile, rel.1 / tile, rel.2 / tile);
+            let color = palette.index_of(voxel);
+            tiles.entry(key).or_default().push((
+                (rel.0 % tile) as u8,
+                (rel.1 % tile) as u8,
+                (rel.2 % tile) as u8,
+                color,
+            ));
+        }
+    }
+    let mut tiles: Vec<_> = tiles.into_iter().collect();
+    tiles.sort_unstable_by_key(|(key, _)| *key);
+
+    let mut body = Vec::new();
+    let mut sizes = Vec::with_capacity(tiles.len());
+    for (_, voxels) in &tiles {
+        let extent = |axis: fn(&ModelVoxel) -> u8| {
+            let max = voxels.iter().map(axis).max().unwrap_or(0) as u32 + 1;
+            (max + (max & 1)).min(MAX_VOX_SIZE)
+        };
+        let size = (extent(|v| v.0), extent(|v| v.1), extent(|v| v.2));
+        sizes.push(size);
+
+        let mut c = Vec::with_capacity(12);
+        for d in [size.0, size.1, size.2] {
+            c.extend_from_slice(&(d as i32).to_le_bytes());
+        }
+        push_chunk(&mut body, b"SIZE", &c);
+        let mut c = Vec::with_capacity(4 + voxels.len() * 4);
+        c.extend_from_slice(&(voxels.len() as i32).to_le_bytes());
+        for &(x, y, z, i) in voxels {
+            c.extend_from_slice(&[x, y, z, i]);
+        }
+        push_chunk(&mut body, b"XYZI", &c);
+    }
+
+    // Scene graph: 0 = root nTRN → 1 = nGRP → per model k,
+    // 2 + 2k = nTRN → 3 + 2k = nSHP(model k).
+    push_ntrn(&mut body, 0, 1, -1, None);
+    let mut c = Vec::new();
+    c.extend_from_slice(&1i32.to_le_bytes());
+    push_vox_dict(&mut c, &[]);
+    c.extend_from_slice(&(tiles.len() as i32).to_le_bytes());
+    for k in 0..tiles.len() as i32 {
+        c.extend_from_slice(&(2 + 2 * k).to_le_bytes());
+    }
+    push_chunk(&mut body, b"nGRP", &c);
+    for (k, ((key, _), size)) in tiles.iter().zip(&sizes).enumerate() {
+        let k = k as i32;
+        let t = (
+            key.0 * tile + (size.0 / 2) as i32,
+            key.1 * tile + (size.1 / 2) as i32,
+            key.2 * tile + (size.2 / 2) as i32,
+        );
+        push_ntrn(&mut body, 2 + 2 * k, 3 + 2 * k, 0, Some(t));
+        let mut c = Vec::new();
+        c.extend_from_slice(&(3 + 2 * k).to_le_bytes());
+        push_vox_dict(&mut c, &[]);
+        c.extend_from_slice(&1i32.to_le_bytes());
+        c.extend_from_slice(&k.to_le_bytes());
+        push_vox_dict(&mut c, &[]);
+        push_chunk(&mut body, b"nSHP", &c);
+    }
+    // The per-model nTRNs reference layer 0; declare it.
+    let mut c = Vec::new();
+    c.extend_from_slice(&0i32.to_le_bytes());
+    push_vox_dict(&mut c, &[]);
+    c.extend_from_slice(&(-1i32).to_le_bytes());
+    push_chunk(&mut body, b"LAYR", &c);
+
+    let mut c = Vec::with_capacity(256 * 4);
+    for entry in &palette.palette[1..] {
+        c.extend_from_slice(entry);
+    }
+    c.extend_from_slice(&[0, 0, 0, 0]); // Unused entry
+    push_chunk(&mut body, b"RGBA", &c);
+    for chunk in palette.matl_chunks().iter().chain(extra) {
+        chunk.write(&mut body)?;
+    }
+
+    writer.write_all(&VOX_MAGIC)?;
+    writer.write_all(&VOX_VERSION_SCENE.to_le_bytes())?;
+    ChunkHeader {
+        id: *b"MAIN",
+        content_size: 0,
+        children_size: body.len() as i32,
+    }
+    .write(writer)?;
+    writer.write_all(&body)?;
+
+    Ok(VoxExportStats {
+        palette_overflow: palette.overflow(),
+        models: tiles.len(),
+    })
+}
+
 /// Find closest color in palette
 fn find_closest_color(palette: &[[u8; 4]; 256], color: [u8; 3]) -> u8 {
     let mut best_index = 1u8;
     let mut best_dist = u32::MAX;
 
-    for i in 1..256 {
-        let p = palette[i];
-        let dr = (color[0] as i32 - p[0] as i32).abs() as u32;
-        let dg = (color[1] as i32 - p[1] as i32).abs() as u32;
-        let db = (color[2] as i32 - p[2] as i32).abs() as u32;
+    for (i, p) in palette.iter().enumerate().skip(1) {
+        let dr = (color[0] as i32 - p[0] as i32).unsigned_abs();
+        let dg = (color[1] as i32 - p[1] as i32).unsigned_abs();
+        let db = (color[2] as i32 - p[2] as i32).unsigned_abs();
         let dist = dr * dr + dg * dg + db * db;
 
         if dist < best_dist {
@@ -858,28 +1410,120 @@ fn find_closest_color(palette: &[[u8; 4]; 256], color: [u8; 3]) -> u8 {
     best_index
 }
 
-/// Export world to VOX file. Returns the number of distinct world
-/// colors that didn't fit in the 255-slot palette and were quantized
-/// to the nearest existing entry — 0 means a lossless export.
+/// Export world to VOX file, copying coordinates unchanged
+/// ([`VoxAxes::AsIs`]) as it always has; use [`export_vox_with_chunks`]
+/// to pick the axes. Returns the number of distinct world colors that
+/// didn't fit in the 255-slot palette and were quantized to the nearest
+/// existing entry — 0 means a lossless export.
 pub fn export_vox<W: Write>(world: &World, writer: &mut W) -> Result<u32, VoxError> {
-    let model = VoxModel::from_world(world)?;
-    model.write(writer)?;
-    Ok(model.palette_overflow)
+    Ok(export_vox_with_stats(world, writer)?.palette_overflow)
+}
+
+/// Export world to VOX file, splitting it into several models when it
+/// exceeds 256 cells on any axis (MagicaVoxel's per-model limit)
+/// instead of failing with [`VoxError::ModelTooLarge`]. A world that
+/// fits is written exactly as before, as a single-model v150 file.
+/// Coordinates are copied unchanged, as by [`export_vox`].
+pub fn export_vox_with_stats<W: Write>(
+    world: &World,
+    writer: &mut W,
+) -> Result<VoxExportStats, VoxError> {
+    export_vox_with_chunks(world, &MaterialTable::new(), &[], VoxAxes::AsIs, writer)
+}
+
+/// [`export_vox_with_stats`], also writing after the palette a `MATL`
+/// chunk for each metal, glass or emissive material of `materials` the
+/// world's voxels use, then `extra` — typically the
+/// [`VoxImport::chunks`] of the file the world came from — so
+/// materials, render settings, cameras and newer extensions survive an
+/// import / export round trip. `axes` maps world axes to VOX ones. A
+/// file carrying any of these chunks is marked v200.
+pub fn export_vox_with_chunks<W: Write>(
+    world: &World,
+    materials: &MaterialTable,
+    extra: &[VoxChunk],
+    axes: VoxAxes,
+    writer: &mut W,
+) -> Result<VoxExportStats, VoxError> {
+    let converted = axes.world_to_vox(world);
+    let world = converted.as_ref().unwrap_or(world);
+    if let Some((min, max)) = world.scene_aabb() {
+        let fits = |lo: i32, hi: i32| (hi - lo + 1) as u32 <= MAX_VOX_SIZE;
+        if !(fits(min.0, max.0) && fits(min.1, max.1) && fits(min.2, max.2)) {
+            return write_split_vox(world, min, materials, extra, writer);
+        }
+    }
+    let (model, mut chunks) = VoxModel::from_vox_world(world, materials)?;
+    chunks.extend_from_slice(extra);
+    model.write_with_chunks(writer, &chunks)?;
+    Ok(VoxExportStats {
+        palette_overflow: model.palette_overflow,
+        models: 1,
+    })
+}
+
+/// Everything [`import_vox_full`] takes from a VOX file.
+pub struct VoxImport {
+    pub world: World,
+    /// Materials made from the file's `MATL` chunks; the world's voxels
+    /// are already stamped with them.
+    pub materials: MaterialTable,
+    /// Chunks Voxelith doesn't interpret, for
+    /// [`export_vox_with_chunks`].
+    pub chunks: Vec<VoxChunk>,
 }
 
 /// Import world from VOX file. Supports both v150 (single-model)
 /// and v200 (multi-model + scene graph) — v200 files are flattened
 /// into the unified `World` voxel grid, with each `nSHP`'s models
 /// placed at their cumulative `nTRN` transform along the path
-/// from the scene root.
+/// from the scene root. Coordinates are copied unchanged
+/// ([`VoxAxes::AsIs`]); use [`import_vox_full`] to pick the axes.
 pub fn import_vox<R: Read>(reader: &mut R) -> Result<World, VoxError> {
+    Ok(import_vox_full(reader, VoxAxes::AsIs)?.world)
+}
+
+/// [`import_vox`] with the axis mapping chosen by `axes`, also
+/// returning the file's materials and the chunks it carries that
+/// Voxelith doesn't interpret.
+pub fn import_vox_full<R: Read>(reader: &mut R, axes: VoxAxes) -> Result<VoxImport, VoxError> {
     let scene = VoxScene::read(reader)?;
-    Ok(scene.to_world())
+    let (voxels, materials) = scene.palette_voxels();
+    Ok(VoxImport {
+        world: scene.to_world(&voxels, axes),
+        materials,
+        chunks: scene.extra_chunks,
+    })
+}
+
+/// Read just the palette of a VOX file, skipping geometry and the
+/// scene graph — for matching colors to an existing MagicaVoxel
+/// project. Returns the 255 usable colors in file order (palette slots
+/// 1..=255). A file without an `RGBA` chunk yields [`default_palette`],
+/// the same colors [`import_vox`] would give its voxels.
+pub fn read_vox_palette<R: Read>(reader: &mut R) -> Result<Vec<[u8; 4]>, VoxError> {
+    // Version is irrelevant here: the RGBA chunk is the same in v150
+    // and v200.
+    let (mut stream, _version) = ChunkStream::open(reader)?;
+    let mut palette = default_palette();
+    while let Some(chunk) = stream.next_chunk()? {
+        if &chunk.id == b"RGBA" {
+            // Same index shift as `VoxScene::read`: file entry i is
+            // palette slot i + 1; the 256th entry is unused.
+            let content = &mut chunk.content.as_slice();
+            for slot in palette.iter_mut().skip(1) {
+                content.read_exact(slot)?;
+            }
+            break;
+        }
+    }
+    Ok(palette[1..].to_vec())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::DEFAULT_MATERIAL;
 
     #[test]
     fn test_roundtrip() {
@@ -1109,6 +1753,140 @@ mod tests {
         assert_eq!((v.r, v.g, v.b), (0, 255, 0));
     }
 
+    /// MAIN wrapping `chunks`, as a v200 file.
+    fn build_v200_file(chunks: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&VOX_MAGIC);
+        buf.extend_from_slice(&200i32.to_le_bytes());
+        buf.extend_from_slice(b"MAIN");
+        buf.extend_from_slice(&0i32.to_le_bytes());
+        buf.extend_from_slice(&(chunks.len() as i32).to_le_bytes());
+        buf.extend_from_slice(chunks);
+        buf
+    }
+
+    /// One 1×1×1 model with a voxel of palette index 1 at the origin.
+    fn single_voxel_chunks() -> Vec<u8> {
+        let mut chunks = Vec::new();
+        let mut size = Vec::new();
+        for _ in 0..3 {
+            size.extend_from_slice(&1u32.to_le_bytes());
+        }
+        chunks.extend_from_slice(&build_chunk(b"SIZE", &size));
+        let mut xyzi = Vec::new();
+        xyzi.extend_from_slice(&1i32.to_le_bytes());
+        xyzi.extend_from_slice(&[0, 0, 0, 1]);
+        chunks.extend_from_slice(&build_chunk(b"XYZI", &xyzi));
+        chunks
+    }
+
+    #[test]
+    fn chunk_with_trailing_bytes_does_not_desync_stream() {
+        // A newer writer appending fields to nTRN: the reader must
+        // resume at the declared end, not where its parser stopped.
+        let mut chunks = single_voxel_chunks();
+        let mut ntrn = build_ntrn_content(0, 1, (4, 0, 0), None);
+        ntrn.extend_from_slice(&[0x5A; 10]);
+        chunks.extend_from_slice(&build_chunk(b"nTRN", &ntrn));
+        chunks.extend_from_slice(&build_chunk(b"nGRP", &build_ngrp_content(1, &[2])));
+        chunks.extend_from_slice(&build_chunk(b"nSHP", &build_nshp_content(2, &[0])));
+
+        let world = import_vox(&mut build_v200_file(&chunks).as_slice()).unwrap();
+        assert!(world.get_voxel(4, 0, 0).is_solid());
+    }
+
+    #[test]
+    fn matl_becomes_materials_and_unknown_chunks_round_trip() {
+        let mut chunks = single_voxel_chunks();
+        let mut matl = Vec::new();
+        matl.extend_from_slice(&1i32.to_le_bytes());
+        write_vox_dict(&mut matl, &[("_type", "_metal"), ("_metal", "0.9"), ("_rough", "0.2")]);
+        chunks.extend_from_slice(&build_chunk(b"MATL", &matl));
+        // Diffuse materials are just the default one.
+        let mut plain = Vec::new();
+        plain.extend_from_slice(&2i32.to_le_bytes());
+        write_vox_dict(&mut plain, &[("_type", "_diffuse")]);
+        chunks.extend_from_slice(&build_chunk(b"MATL", &plain));
+        chunks.extend_from_slice(&build_chunk(b"rCAM", &[7; 20]));
+        chunks.extend_from_slice(&build_chunk(b"IMAP", &[0; 256]));
+        chunks.extend_from_slice(&build_chunk(b"zNEW", &[1, 2, 3]));
+
+        let import =
+            import_vox_full(&mut build_v200_file(&chunks).as_slice(), VoxAxes::ZUp).unwrap();
+        assert_eq!(import.materials.len(), 2);
+        let voxel = import.world.get_voxel(0, 0, 0);
+        let def = import.materials.resolve(voxel.material);
+        assert_eq!((def.metallic, def.roughness), (0.9, 0.2));
+        assert!(voxel.is_metallic());
+        let ids: Vec<_> = import.chunks.iter().map(|c| &c.id).collect();
+        assert_eq!(ids, [b"rCAM", b"zNEW"], "IMAP dropped, unknowns kept in order");
+
+        let mut buffer = Vec::new();
+        export_vox_with_chunks(
+            &import.world,
+            &import.materials,
+            &import.chunks,
+            VoxAxes::ZUp,
+            &mut buffer,
+        )
+        .unwrap();
+        let again = import_vox_full(&mut buffer.as_slice(), VoxAxes::ZUp).unwrap();
+        assert_eq!(again.chunks, import.chunks);
+        assert!(again.world.get_voxel(0, 0, 0).is_solid());
+    }
+
+    #[test]
+    fn files_carrying_matl_are_written_as_v200() {
+        let mut table = MaterialTable::new();
+        let mut metal = MaterialDef::new("Metal");
+        metal.metallic = 1.0;
+        let metal_id = table.add(metal).unwrap();
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(9, 9, 9));
+        let version = |world: &World| {
+            let mut buffer = Vec::new();
+            export_vox_with_chunks(world, &table, &[], VoxAxes::ZUp, &mut buffer).unwrap();
+            i32::from_le_bytes(buffer[4..8].try_into().unwrap())
+        };
+        // Only the default material: no MATL, so still v150.
+        assert_eq!(version(&world), VOX_VERSION_WRITE);
+        let mut voxel = Voxel::from_rgb(9, 9, 9);
+        voxel.material = metal_id;
+        world.set_voxel(1, 0, 0, table.stamp(voxel));
+        assert_eq!(version(&world), VOX_VERSION_SCENE);
+    }
+
+    #[test]
+    fn materials_round_trip_as_matl() {
+        let mut table = MaterialTable::new();
+        let mut metal = MaterialDef::new("Metal");
+        (metal.metallic, metal.roughness) = (0.8, 0.3);
+        let mut lamp = MaterialDef::new("Lamp");
+        (lamp.transparency, lamp.emissive) = (0.5, 2.5);
+        let metal_id = table.add(metal.clone()).unwrap();
+        let lamp_id = table.add(lamp.clone()).unwrap();
+        let mut world = World::new();
+        let color = |material| {
+            let mut voxel = Voxel::from_rgb(200, 100, 50);
+            voxel.material = material;
+            voxel
+        };
+        world.set_voxel(0, 0, 0, table.stamp(color(metal_id)));
+        world.set_voxel(1, 0, 0, table.stamp(color(lamp_id)));
+        world.set_voxel(2, 0, 0, color(DEFAULT_MATERIAL));
+
+        let mut buffer = Vec::new();
+        export_vox_with_chunks(&world, &table, &[], VoxAxes::ZUp, &mut buffer).unwrap();
+        let import = import_vox_full(&mut buffer.as_slice(), VoxAxes::ZUp).unwrap();
+        assert!(import.chunks.is_empty(), "MATL is read, not passed through");
+        // One color, three slots: each material keeps its own.
+        let def = |x| import.materials.resolve(import.world.get_voxel(x, 0, 0).material);
+        assert_eq!((def(0).metallic, def(0).roughness), (metal.metallic, metal.roughness));
+        assert_eq!((def(1).transparency, def(1).emissive), (lamp.transparency, lamp.emissive));
+        assert_eq!(*def(2), MaterialDef::default());
+        assert!(import.world.get_voxel(1, 0, 0).is_emissive());
+    }
+
     #[test]
     fn v200_multi_model_with_separate_translations() {
         // Two 1×1×1 models (red + blue), each in its own nSHP,
@@ -1211,6 +1989,60 @@ mod tests {
         assert_eq!((blue.r, blue.g, blue.b), (0, 0, 255));
     }
 
+    #[test]
+    fn plain_entry_points_keep_coordinates_unchanged() {
+        // A column standing up Y: exported with the axes kept, the VOX
+        // model is 1×3×1 and stays a Y column on the way back in.
+        let mut world = World::new();
+        for y in 0..3 {
+            world.set_voxel(0, y, 0, Voxel::from_rgb(200, 10 * y as u8, 0));
+        }
+        let mut plain = Vec::new();
+        export_vox(&world, &mut plain).unwrap();
+        let mut as_is = Vec::new();
+        export_vox_with_chunks(&world, &MaterialTable::new(), &[], VoxAxes::AsIs, &mut as_is)
+            .unwrap();
+        assert_eq!(plain, as_is);
+        let mut stats = Vec::new();
+        export_vox_with_stats(&world, &mut stats).unwrap();
+        assert_eq!(plain, stats);
+
+        let scene = VoxScene::read(&mut plain.as_slice()).unwrap();
+        assert_eq!(scene.models[0].size, (1, 3, 1));
+        let imported = import_vox(&mut plain.as_slice()).unwrap();
+        for y in 0..3 {
+            assert_eq!(imported.get_voxel(0, y, 0), world.get_voxel(0, y, 0));
+        }
+        assert_eq!(imported.solid_voxel_count(), 3);
+    }
+
+    #[test]
+    fn z_up_axes_stand_models_upright_and_round_trip() {
+        // A two-voxel column along VOX +Z, plus one voxel along VOX +Y.
+        let mut model = VoxModel::new((1, 2, 2));
+        model.voxels = vec![(0, 0, 0, 1), (0, 0, 1, 2), (0, 1, 0, 3)];
+        let raw = model.to_world(VoxAxes::AsIs);
+        assert!(raw.get_voxel(0, 0, 1).is_solid());
+
+        // Z-up: VOX +Z is world +Y; VOX +Y runs toward world -Z,
+        // reflected inside the model's own depth.
+        let upright = model.to_world(VoxAxes::ZUp);
+        let color = |x, y, z| {
+            let v = upright.get_voxel(x, y, z);
+            [v.r, v.g, v.b, v.a]
+        };
+        assert_eq!(color(0, 0, 1), model.palette[1]);
+        assert_eq!(color(0, 1, 1), model.palette[2]);
+        assert_eq!(color(0, 0, 0), model.palette[3]);
+
+        // Exporting with the same axes gives the VOX layout back.
+        let again = VoxModel::from_world(&upright, VoxAxes::ZUp).unwrap();
+        assert_eq!(again.size, (1, 2, 2));
+        let mut positions: Vec<_> = again.voxels.iter().map(|v| (v.0, v.1, v.2)).collect();
+        positions.sort_unstable();
+        assert_eq!(positions, [(0, 0, 0), (0, 0, 1), (0, 1, 0)]);
+    }
+
     #[test]
     fn test_palette_overflow_reported() {
         // 256 distinct world colors. VOX palette has 254 usable slots
@@ -1236,4 +2068,64 @@ mod tests {
             overflow
         );
     }
+
+    #[test]
+    fn test_read_vox_palette_matches_exported_colors() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(1, 0, 0, Voxel::from_rgb(10, 20, 30));
+        let mut buffer = Vec::new();
+        export_vox(&world, &mut buffer).unwrap();
+
+        let palette = read_vox_palette(&mut buffer.as_slice()).unwrap();
+        assert_eq!(palette.len(), 255);
+        assert!(palette.contains(&[255, 0, 0, 255]));
+        assert!(palette.contains(&[10, 20, 30, 255]));
+        assert!(matches!(
+            read_vox_palette(&mut &b"NOPE\0\0\0\0"[..]),
+            Err(VoxError::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn test_oversized_world_splits_into_models_and_roundtrips() {
+        // 300 wide on X and offset from the origin: needs two models
+        // along X, one of them odd-sized before the even round-up.
+        let mut world = World::new();
+        let red = Voxel::from_rgb(255, 0, 0);
+        let blue = Voxel::from_rgb(0, 0, 255);
+        for x in 0..300 {
+            world.set_voxel(x - 20, 5, -3, if x < 150 { red } else { blue });
+        }
+        world.set_voxel(-20, 9, 0, blue);
+
+        let mut buffer = Vec::new();
+        let stats = export_vox_with_stats(&world, &mut buffer).unwrap();
+        assert_eq!(stats.models, 2);
+        assert_eq!(stats.palette_overflow, 0);
+
+        // Export is relative to the world minimum (-20, 5, -3).
+        let imported = import_vox(&mut buffer.as_slice()).unwrap();
+        let solid: usize = imported
+            .chunks()
+            .map(|(_, c)| c.read().iter_solid().count())
+            .sum();
+        assert_eq!(solid, 301);
+        assert_eq!(imported.get_voxel(0, 0, 0).r, 255);
+        assert_eq!(imported.get_voxel(149, 0, 0).r, 255);
+        assert_eq!(imported.get_voxel(150, 0, 0).b, 255);
+        assert_eq!(imported.get_voxel(299, 0, 0).b, 255);
+        assert_eq!(imported.get_voxel(0, 4, 3).b, 255);
+        assert!(imported.get_voxel(300, 0, 0).is_air());
+    }
+
+    #[test]
+    fn test_small_world_export_stays_single_model_v150() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(1, 2, 3));
+        let mut buffer = Vec::new();
+        let stats = export_vox_with_stats(&world, &mut buffer).unwrap();
+        assert_eq!(stats.models, 1);
+        assert_eq!(i32::from_le_bytes(buffer[4..8].try_into().unwrap()), 150);
+    }
 }
@@ -28,7 +28,9 @@ pub mod mesh;
 pub mod render;
 pub mod ui;
 pub mod editor;
+pub mod headless;
 pub mod io;
+pub mod plugin;
 pub mod prefs;
 pub mod procgen;
 
@@ -1,9 +1,10 @@
 //! Voxelith - Procedural-first voxel asset creation tool
 //!
 //! Entry point. With no subcommand this launches the interactive editor
-//! (the `app` module). The `bake` subcommand runs a headless batch export
-//! — no window, no GPU — driven by a spec file (see `crate::bake` and
-//! `docs/GAME_PIPELINE_ROADMAP.md` §3.5).
+//! (the `app` module), opening the `.vxlt` project or `.vox` model named
+//! as the only argument, if any. The `bake` subcommand runs a headless
+//! batch export — no window, no GPU — driven by a spec file (see
+//! `crate::bake` and `docs/GAME_PIPELINE_ROADMAP.md` §3.5).
 
 mod app;
 
@@ -13,10 +14,17 @@ use clap::{Parser, Subcommand};
 use winit::event_loop::{ControlFlow, EventLoop};
 
 #[derive(Parser)]
-#[command(name = "voxelith", version, about = "Procedural-first voxel asset creation tool")]
+#[command(
+    name = "voxelith",
+    version,
+    about = "Procedural-first voxel asset creation tool",
+    args_conflicts_with_subcommands = true
+)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    /// Project (.vxlt) or MagicaVoxel model (.vox) to open in the editor.
+    file: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -36,7 +44,18 @@ fn main() {
     let cli = Cli::parse();
     match cli.command {
         Some(Commands::Bake { spec, shard }) => run_bake(&spec, shard.as_deref()),
-        None => run_gui(),
+        None => {
+            // Reject a bad path before any window opens, so the error
+            // lands in the terminal that ran the command.
+            let file = cli.file.map(app::StartupFile::from_path).transpose();
+            match file {
+                Ok(file) => run_gui(file),
+                Err(e) => {
+                    eprintln!("voxelith: {e}");
+                    std::process::exit(2);
+                }
+            }
+        }
     }
 }
 
@@ -61,8 +80,9 @@ fn run_bake(spec: &Path, shard: Option<&str>) {
     }
 }
 
-/// Launch the interactive winit + egui editor (the default).
-fn run_gui() {
+/// Launch the interactive winit + egui editor (the default), opening
+/// `file` once the window is up.
+fn run_gui(file: Option<app::StartupFile>) {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
         .format_timestamp(None)
         .init();
@@ -73,5 +93,8 @@ fn run_gui() {
     event_loop.set_control_flow(ControlFlow::Poll);
 
     let mut app = app::App::new();
+    if let Some(file) = file {
+        app.open_on_startup(file);
+    }
     event_loop.run_app(&mut app).unwrap();
 }
@@ -18,9 +18,12 @@
 //!
 //! ### Mask key
 //!
-//! Each mask cell stores `(tint_zone << 40) | (packed_rgba << 8) | packed_ao`
+//! Each mask cell stores
+//! `(material << 48) | (tint_zone << 40) | (packed_rgba << 8) | packed_ao`
 //! as `u64`:
-//! - `tint_zone` (bits 40+): the voxel's faction tint zone (0-3), so
+//! - `material` (bits 48-63): the voxel's material id, so faces shaded
+//!   with different materials (`core::MaterialTable`) never merge
+//! - `tint_zone` (bits 40-47): the voxel's faction tint zone (0-3), so
 //!   voxels of different zones never merge — the zone must survive
 //!   per-vertex to GLB export, where it can't be averaged across a quad
 //! - `packed_rgba` (bits 8-39): the shaded RGBA color via `pack_rgba`
@@ -30,8 +33,8 @@
 //! `0` is reserved as the "no visible face" sentinel — safe because
 //! every editor-placed voxel has α = 255, so a non-air visible face
 //! always packs to a non-zero `packed_rgba`. Two cells merge only
-//! when the entire `u64` matches — color, all 4 corner AO values, and
-//! tint zone — without which the merged quad's bilinear-interpolated AO
+//! when the entire `u64` matches — color, all 4 corner AO values, tint
+//! zone and material — without which the merged quad's bilinear-interpolated AO
 //! would disagree with per-cell AO, or a quad would span two zones.
 
 use super::ao::pack_ao;
@@ -39,17 +42,29 @@ use super::neighbors::{
     lock_neighbors, neighbor_arcs, voxel_at_local, NeighborArcs, NeighborGuards,
 };
 use super::{
-    ao_to_f32, apply_face_shading, compute_face_ao, face_quad_vertices_sized_ao,
-    unpack_ao, ChunkMesh, Face, Mesher,
+    ao_to_f32, apply_face_shading, compute_face_ao, face_exposed,
+    face_quad_vertices_sized_ao, unpack_ao, ChunkMesh, Face, Mesher,
 };
-use crate::core::{Chunk, ChunkPos, World, CHUNK_SIZE};
+use crate::core::{Chunk, ChunkPos, Voxel, World, CHUNK_SIZE};
 
 /// Greedy mesher: merges same-color same-AO same-direction adjacent faces.
-pub struct GreedyMesher;
+pub struct GreedyMesher {
+    ambient_occlusion: bool,
+}
 
 impl GreedyMesher {
     pub fn new() -> Self {
-        Self
+        Self {
+            ambient_occlusion: true,
+        }
+    }
+
+    /// Toggle baked per-vertex AO. With it off every corner is
+    /// unoccluded, so the mask key is color-only and faces next to
+    /// walls merge as freely as open ones.
+    pub fn with_ambient_occlusion(mut self, enabled: bool) -> Self {
+        self.ambient_occlusion = enabled;
+        self
     }
 }
 
@@ -86,8 +101,20 @@ impl Mesher for GreedyMesher {
 
         let world_origin = chunk_pos.world_origin();
         for face in Face::ALL {
-            mesh_face_direction(&chunk, &neighbors, face, world_origin, None, &mut mesh);
+            mesh_face_direction(
+                &chunk,
+                &neighbors,
+                face,
+                world_origin,
+                None,
+                true,
+                self.ambient_occlusion,
+                &mut mesh,
+            );
         }
+        // Translucent faces (glass, water) go last so the renderer can
+        // draw them in its alpha-blended pass.
+        mesh.partition_transparent();
         mesh
     }
 }
@@ -101,7 +128,15 @@ impl Mesher for GreedyMesher {
 /// spans a material boundary. Returns `(group_id, mesh)` for non-empty
 /// groups only; the 26 neighbors are locked once and shared across the
 /// four passes.
-pub fn mesh_chunk_by_material(world: &World, chunk_pos: ChunkPos) -> Vec<(u8, ChunkMesh)> {
+///
+/// `face_shading` bakes the editor's directional face shading into the
+/// vertex colors (as the render path does); `false` keeps the raw voxel
+/// color for exports the target engine will light itself.
+pub fn mesh_chunk_by_material(
+    world: &World,
+    chunk_pos: ChunkPos,
+    face_shading: bool,
+) -> Vec<(u8, ChunkMesh)> {
     let Some(chunk_arc) = world.get_chunk(chunk_pos) else {
         return Vec::new();
     };
@@ -123,6 +158,8 @@ pub fn mesh_chunk_by_material(world: &World, chunk_pos: ChunkPos) -> Vec<(u8, Ch
                 face,
                 world_origin,
                 Some(group),
+                face_shading,
+                true,
                 &mut mesh,
             );
         }
@@ -144,16 +181,26 @@ pub fn mesh_chunk_by_material(world: &World, chunk_pos: ChunkPos) -> Vec<(u8, Ch
 /// visibility and AO still consult all solid voxels regardless, so
 /// culling and shading are unchanged. `None` meshes every voxel (the
 /// render / default path).
+///
+/// `face_shading`: apply `apply_face_shading` to the emitted color.
+/// Always on for rendering; flat-color exports turn it off.
+///
+/// `ambient_occlusion`: sample the 12 AO cells per face. When off every
+/// corner is written as unoccluded (3), skipping the samples entirely.
+#[allow(clippy::too_many_arguments)]
 fn mesh_face_direction(
     chunk: &Chunk,
     neighbors: &NeighborGuards,
     face: Face,
     world_origin: (i32, i32, i32),
     group_filter: Option<u8>,
+    face_shading: bool,
+    ambient_occlusion: bool,
     mesh: &mut ChunkMesh,
 ) {
     const SIZE: usize = CHUNK_SIZE;
-    // 0 = no face; non-zero = (tint_zone << 40) | (packed_rgba << 8) | packed_ao.
+    // 0 = no face; non-zero =
+    // (material << 48) | (tint_zone << 40) | (packed_rgba << 8) | packed_ao.
     let mut mask = [0u64; SIZE * SIZE];
 
     for d in 0..SIZE {
@@ -163,7 +210,7 @@ fn mesh_face_direction(
                 let (cx, cy, cz) = cell_for(face, d, u_idx, v_idx);
                 let voxel = chunk.get(cx, cy, cz);
                 if voxel.is_air()
-                    || !is_face_visible(chunk, neighbors, cx, cy, cz, face)
+                    || !is_face_visible(chunk, neighbors, voxel, (cx, cy, cz), face)
                 {
                     mask[v_idx * SIZE + u_idx] = 0;
                     continue;
@@ -178,30 +225,37 @@ fn mesh_face_direction(
                         continue;
                     }
                 }
-                let shaded = apply_face_shading(voxel.color_f32(), face);
-                let packed_color = pack_rgba(shaded);
+                let color = if face_shading {
+                    apply_face_shading(voxel.color_f32(), face)
+                } else {
+                    voxel.color_f32()
+                };
+                let packed_color = pack_rgba(color);
                 // 4-corner AO via 12 voxel samples through the
                 // 26-neighbor lock array.
                 let world_x = world_origin.0 + cx as i32;
                 let world_y = world_origin.1 + cy as i32;
                 let world_z = world_origin.2 + cz as i32;
-                let ao_int = compute_face_ao(
-                    (world_x, world_y, world_z),
-                    face,
-                    |p| {
+                let ao_int = if ambient_occlusion {
+                    compute_face_ao((world_x, world_y, world_z), face, |p| {
                         let lx = p.0 - world_origin.0;
                         let ly = p.1 - world_origin.1;
                         let lz = p.2 - world_origin.2;
                         voxel_at_local(chunk, neighbors, lx, ly, lz).is_solid()
-                    },
-                );
+                    })
+                } else {
+                    [3; 4]
+                };
                 let packed_ao = pack_ao(ao_int);
                 // Tint zone (0-3) in bits 40+ so voxels of different
                 // zones never merge — the zone must reach export
                 // per-vertex (it can't be averaged across a merged quad).
                 let zone = voxel.tint_zone() as u64;
-                mask[v_idx * SIZE + u_idx] =
-                    (zone << 40) | ((packed_color as u64) << 8) | packed_ao as u64;
+                let material = voxel.material as u64;
+                mask[v_idx * SIZE + u_idx] = (material << 48)
+                    | (zone << 40)
+                    | ((packed_color as u64) << 8)
+                    | packed_ao as u64;
             }
         }
 
@@ -252,6 +306,7 @@ fn mesh_face_direction(
 /// to `mesh`. The mask key contains both color and 4 corner AO —
 /// they apply uniformly across the merged rectangle (cells with
 /// different AO can't merge).
+#[allow(clippy::too_many_arguments)]
 fn emit_merged_quad(
     face: Face,
     d: usize,
@@ -273,6 +328,7 @@ fn emit_merged_quad(
     // Tint zone lives in bits 40+ (above color+ao); `>> 8 as u32` for the
     // color truncates it away, so it must be read from the full key here.
     let tint_zone = ((packed_key >> 40) & 0xFF) as f32;
+    let material = (packed_key >> 48) as u32;
     let color = unpack_rgba(packed_color);
     let ao_int = unpack_ao(packed_ao);
     let ao = [
@@ -293,6 +349,7 @@ fn emit_merged_quad(
     );
     for vert in &mut vertices {
         vert.tint_zone = tint_zone;
+        vert.material = material;
     }
     mesh.add_quad_with_ao_flip(vertices);
 }
@@ -329,31 +386,32 @@ fn unpack_rgba(p: u32) -> [f32; 4] {
     [r, g, b, a]
 }
 
-/// Whether the cell at chunk-local `(x, y, z)` exposes a face in
-/// `face` direction. Routes through `voxel_at_local` (26-neighbor
-/// lock) to handle chunk boundaries uniformly with AO sampling.
+/// Whether `voxel` at chunk-local `(x, y, z)` exposes a face in
+/// `face` direction (see `face_exposed`). Routes through
+/// `voxel_at_local` (26-neighbor lock) to handle chunk boundaries
+/// uniformly with AO sampling.
 fn is_face_visible(
     chunk: &Chunk,
     neighbors: &NeighborGuards,
-    x: usize,
-    y: usize,
-    z: usize,
+    voxel: Voxel,
+    (x, y, z): (usize, usize, usize),
     face: Face,
 ) -> bool {
     let (dx, dy, dz) = face.offset();
-    voxel_at_local(
+    let neighbor = voxel_at_local(
         chunk,
         neighbors,
         x as i32 + dx,
         y as i32 + dy,
         z as i32 + dz,
-    )
-    .is_air()
+    );
+    face_exposed(voxel, neighbor)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mesh::{MesherKind, NaiveMesher};
     use crate::core::{Voxel, CHUNK_SIZE_I32};
 
     #[test]
@@ -419,6 +477,19 @@ mod tests {
         assert!(zones.contains(&1.0) && zones.contains(&2.0));
     }
 
+    #[test]
+    fn test_different_materials_dont_merge() {
+        // Same color, different material ids: shaded differently, so
+        // the faces stay apart and each vertex carries its material.
+        let mut world = World::new();
+        world.set_voxel(1, 1, 1, Voxel::new(1, 100, 100, 100));
+        world.set_voxel(2, 1, 1, Voxel::new(7, 100, 100, 100));
+        let mesh = GreedyMesher::new().generate(&world, ChunkPos::ZERO);
+        assert_eq!(mesh.triangle_count(), 20);
+        let materials: Vec<u32> = mesh.vertices.iter().map(|v| v.material).collect();
+        assert!(materials.contains(&1) && materials.contains(&7));
+    }
+
     #[test]
     fn test_2x2x1_slab_merge() {
         let mut world = World::new();
@@ -474,6 +545,142 @@ mod tests {
         assert_eq!(mesh.triangle_count(), 24);
     }
 
+    /// Total triangles over every chunk of `world` for one mesher.
+    fn world_triangles(world: &World, kind: MesherKind) -> usize {
+        world
+            .chunk_positions()
+            .map(|&pos| kind.generate(world, pos).triangle_count())
+            .sum()
+    }
+
+    #[test]
+    fn test_greedy_vs_naive_triangle_counts() {
+        // Triangle-count comparison on a few representative scenes.
+        // `(name, world, min greedy reduction factor)`. Curved surfaces
+        // gain little: their AO varies cell to cell, which splits the
+        // runs. The chessboard is the worst case — nothing can merge —
+        // so the two agree.
+        let c = Voxel::from_rgb(120, 160, 90);
+        let mut plane = World::new();
+        plane.fill_region((0, 0, 0), (63, 0, 63), c);
+        let mut cube = World::new();
+        cube.fill_region((-8, -8, -8), (7, 7, 7), c);
+        let mut sphere = World::new();
+        for x in -10i32..=10 {
+            for y in -10i32..=10 {
+                for z in -10i32..=10 {
+                    if x * x + y * y + z * z <= 100 {
+                        sphere.set_voxel(x, y, z, c);
+                    }
+                }
+            }
+        }
+        let mut chessboard = World::new();
+        for x in 0..8 {
+            for z in 0..8 {
+                if (x + z) % 2 == 0 {
+                    chessboard.set_voxel(x, 0, z, c);
+                }
+            }
+        }
+
+        let scenes = [
+            ("plane", plane, 8.0),
+            ("cube", cube, 8.0),
+            ("sphere", sphere, 1.1),
+            ("chessboard", chessboard, 1.0),
+        ];
+        for (name, world, factor) in scenes {
+            let naive = world_triangles(&world, MesherKind::Naive);
+            let greedy = world_triangles(&world, MesherKind::Greedy);
+            assert!(
+                greedy as f32 * factor <= naive as f32,
+                "{name}: greedy {greedy} vs naive {naive} tris (want ≥{factor}× fewer)"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mesher_kind_dispatches_to_matching_mesher() {
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (3, 0, 3), Voxel::from_rgb(90, 90, 90));
+        let pos = ChunkPos::ZERO;
+        assert_eq!(
+            MesherKind::Naive.generate(&world, pos).triangle_count(),
+            NaiveMesher::new().generate(&world, pos).triangle_count()
+        );
+        assert_eq!(
+            MesherKind::Greedy.generate(&world, pos).triangle_count(),
+            GreedyMesher::new().generate(&world, pos).triangle_count()
+        );
+        assert_eq!(
+            MesherKind::MarchingCubes.generate(&world, pos).triangle_count(),
+            crate::mesh::MarchingCubesMesher.generate(&world, pos).triangle_count()
+        );
+    }
+
+    #[test]
+    fn test_ambient_occlusion_off_merges_across_ao_gradients() {
+        // A floor with a wall along one edge: the floor row next to the
+        // wall has darkened corners, which splits the greedy cover with
+        // AO on. With AO off the whole floor top is one color.
+        let c = Voxel::from_rgb(150, 150, 150);
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (7, 0, 7), c);
+        world.fill_region((0, 1, 0), (7, 3, 0), c);
+        let pos = ChunkPos::ZERO;
+
+        let with_ao = GreedyMesher::new().generate(&world, pos);
+        let without_ao = GreedyMesher::new()
+            .with_ambient_occlusion(false)
+            .generate(&world, pos);
+        assert!(without_ao.triangle_count() < with_ao.triangle_count());
+        assert!(without_ao.vertices.iter().all(|v| v.ao == 1.0));
+
+        // `MeshSettings` threads the toggle through to the mesher.
+        let settings = crate::mesh::MeshSettings {
+            kind: MesherKind::Greedy,
+            ambient_occlusion: false,
+            smooth_normals: false,
+        };
+        assert_eq!(
+            settings.generate(&world, pos).triangle_count(),
+            without_ao.triangle_count()
+        );
+    }
+
+    #[test]
+    fn test_translucent_voxels_split_from_opaque_and_keep_faces_behind() {
+        // Opaque voxel at x=0, two panes of glass at x=1..=2.
+        let glass = Voxel::from_rgba(180, 220, 255, 100);
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(120, 80, 40));
+        world.set_voxel(1, 0, 0, glass);
+        world.set_voxel(2, 0, 0, glass);
+        let mesh = GreedyMesher::new().generate(&world, ChunkPos::ZERO);
+
+        // The opaque voxel keeps all 6 faces: glass doesn't hide the +X
+        // one. The glass pair is a 2×1×1 box (merged, 6 quads) — the
+        // glass-glass face between them is culled, and so is the glass
+        // face toward the opaque voxel.
+        assert_eq!(mesh.opaque_indices().len() / 6, 6);
+        assert_eq!(mesh.transparent_indices().len() / 6, 5);
+        assert!(mesh
+            .transparent_indices()
+            .iter()
+            .all(|&i| mesh.vertices[i as usize].color[3] < 1.0));
+        assert!(mesh
+            .opaque_indices()
+            .iter()
+            .all(|&i| mesh.vertices[i as usize].color[3] == 1.0));
+
+        // Different translucent colors keep their shared face (from
+        // both sides) and no longer merge: 5 + 6 quads.
+        world.set_voxel(2, 0, 0, Voxel::from_rgba(40, 90, 200, 160));
+        let mesh = GreedyMesher::new().generate(&world, ChunkPos::ZERO);
+        assert_eq!(mesh.transparent_indices().len() / 6, 11);
+    }
+
     #[test]
     fn test_chunk_boundary_culling() {
         let mut world = World::new();
@@ -0,0 +1,300 @@
+//! Level-of-detail chunk meshes.
+//!
+//! A distant chunk covers a handful of pixels, yet meshing it at full
+//! resolution costs as many triangles as the chunk under the cursor —
+//! on a big terrain that is most of the frame. [`LodMesher`] wraps any
+//! chunk mesher and, at level 1 or 2, first downsamples the chunk into
+//! 2× or 4× bigger voxels ([`downsample_chunk`]), meshes that, and
+//! scales the result back into place. The app picks each chunk's level
+//! from its distance to the camera ([`LodSettings::select`]) and
+//! re-meshes a chunk only when its level changes; the old mesh stays on
+//! screen until the new one arrives, so switching never leaves a hole.
+//!
+//! A coarse cell is solid when *any* voxel of its block is (taking the
+//! block's most common voxel), so thin walls and floors survive instead
+//! of dissolving into holes. The ring of cells just outside the chunk,
+//! which only drives face culling and AO, is solid only when its block
+//! is *completely* solid: a face on the chunk boundary is then culled
+//! only where the neighbor really covers it, whatever level the
+//! neighbor is drawn at, so adjacent levels don't open cracks.
+//!
+//! The coarse chunk is only `32 / factor` cells wide, so the ring on its
+//! positive sides lands inside the chunk the inner mesher meshes. Ring
+//! voxels carry a sentinel tint zone so greedy quads never merge across
+//! the ring boundary, and [`strip_ring`] drops the faces they emit.
+
+use crate::core::{ChunkPos, Voxel, World, CHUNK_SIZE_I32};
+
+use super::{ChunkMesh, Mesher};
+
+/// Tint zone marking ring cells in a downsampled world (real zones are
+/// 0–3). Only keeps their faces from merging with the chunk's own.
+const RING_TINT: u8 = u8::MAX;
+
+/// Coarsest level: voxels `1 << MAX_LOD` = 4 times bigger per axis.
+pub const MAX_LOD: u8 = 2;
+
+/// Distance thresholds for picking a chunk's level, persisted with the
+/// viewport settings.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LodSettings {
+    /// Camera distance (in voxels, to the chunk center) beyond which
+    /// chunks drop to level 1; level 2 starts at twice this.
+    pub distance: f32,
+    /// Width of the band around each threshold in which a chunk keeps
+    /// its current level, as a fraction of the threshold. Stops chunks
+    /// right at a threshold from flipping back and forth (popping) as
+    /// the camera drifts.
+    pub hysteresis: f32,
+}
+
+impl Default for LodSettings {
+    fn default() -> Self {
+        Self {
+            distance: 160.0,
+            hysteresis: 0.15,
+        }
+    }
+}
+
+impl LodSettings {
+    /// Distance at which `level` (≥ 1) begins.
+    fn threshold(&self, level: u8) -> f32 {
+        self.distance * (1u32 << (level - 1)) as f32
+    }
+
+    /// Level for a chunk at `distance` that is currently drawn at
+    /// `current`. A chunk moves to a coarser level only once it is
+    /// past that level's threshold by the hysteresis margin, and back
+    /// only once it is inside by the same margin.
+    pub fn select(&self, distance: f32, current: u8) -> u8 {
+        let mut level = current.min(MAX_LOD);
+        while level < MAX_LOD && distance > self.threshold(level + 1) * (1.0 + self.hysteresis) {
+            level += 1;
+        }
+        while level > 0 && distance < self.threshold(level) * (1.0 - self.hysteresis) {
+            level -= 1;
+        }
+        level
+    }
+}
+
+/// Meshes a chunk at LOD `level` with `inner`. Level 0 is `inner`
+/// unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LodMesher<M> {
+    pub inner: M,
+    pub level: u8,
+}
+
+impl<M> LodMesher<M> {
+    pub fn new(inner: M, level: u8) -> Self {
+        Self {
+            inner,
+            level: level.min(MAX_LOD),
+        }
+    }
+}
+
+impl<M: Mesher> Mesher for LodMesher<M> {
+    fn generate(&self, world: &World, chunk_pos: ChunkPos) -> ChunkMesh {
+        if self.level == 0 {
+            return self.inner.generate(world, chunk_pos);
+        }
+        let empty = world
+            .get_chunk(chunk_pos)
+            .is_none_or(|chunk| chunk.read().is_empty());
+        if empty {
+            return ChunkMesh::new(chunk_pos);
+        }
+        let factor = 1 << self.level;
+        let coarse = downsample_chunk(world, chunk_pos, factor);
+        let mut mesh = self.inner.generate(&coarse, ChunkPos::ZERO);
+        strip_ring(&mut mesh, CHUNK_SIZE_I32 / factor);
+        let (ox, oy, oz) = chunk_pos.world_origin();
+        let origin = [ox as f32, oy as f32, oz as f32];
+        for vertex in &mut mesh.vertices {
+            for (p, o) in vertex.position.iter_mut().zip(origin) {
+                *p = o + *p * factor as f32;
+            }
+        }
+        mesh.chunk_pos = chunk_pos;
+        mesh
+    }
+}
+
+/// The chunk at `chunk_pos` downsampled by `factor` (2 or 4) into a
+/// world of its own: coarse cell `(x, y, z)` — at that voxel position,
+/// chunk [`ChunkPos::ZERO`] — covers the `factor³` block at
+/// `origin + (x, y, z) * factor`. Includes the one-cell ring around the
+/// chunk for face culling and AO (see the module docs for the solidity
+/// rules).
+pub fn downsample_chunk(world: &World, chunk_pos: ChunkPos, factor: i32) -> World {
+    let cells = CHUNK_SIZE_I32 / factor;
+    let (ox, oy, oz) = chunk_pos.world_origin();
+    let mut coarse = World::new();
+    let mut counts: Vec<(Voxel, u32)> = Vec::new();
+    for z in -1..=cells {
+        for y in -1..=cells {
+            for x in -1..=cells {
+                let ring = [x, y, z].iter().any(|&c| c < 0 || c >= cells);
+                counts.clear();
+                let mut solid = 0;
+                for dz in 0..factor {
+                    for dy in 0..factor {
+                        for dx in 0..factor {
+                            let voxel = world.get_voxel(
+                                ox + x * factor + dx,
+                                oy + y * factor + dy,
+                                oz + z * factor + dz,
+                            );
+                            if voxel.is_air() {
+                                continue;
+                            }
+                            solid += 1;
+                            match counts.iter_mut().find(|(v, _)| *v == voxel) {
+                                Some((_, n)) => *n += 1,
+                                None => counts.push((voxel, 1)),
+                            }
+                        }
+                    }
+                }
+                if ring && solid < factor * factor * factor {
+                    continue;
+                }
+                if let Some(&(mut voxel, _)) = counts.iter().max_by_key(|(_, n)| *n) {
+                    if ring {
+                        voxel.set_tint_zone(RING_TINT);
+                    }
+                    coarse.set_voxel(x, y, z, voxel);
+                }
+            }
+        }
+    }
+    coarse
+}
+
+/// Drop the triangles of a coarse mesh that belong to ring cells, i.e.
+/// whose face sits on a cell outside `0..cells` (the centroid stepped
+/// half a cell back against the normal), then the vertices no longer
+/// used. Keeps the opaque / translucent split.
+fn strip_ring(mesh: &mut ChunkMesh, cells: i32) {
+    let vertices = &mesh.vertices;
+    let inside = |tri: &[u32]| {
+        (0..3).all(|axis| {
+            let (p, n) = tri.iter().fold((0.0, 0.0), |(p, n), &i| {
+                let v = &vertices[i as usize];
+                (p + v.position[axis] / 3.0, n + v.normal[axis] / 3.0)
+            });
+            let cell = p - n * 0.5;
+            cell >= 0.0 && cell < cells as f32
+        })
+    };
+    let split = mesh.indices.len() - mesh.transparent_index_count;
+    let keep = |indices: &[u32]| -> Vec<u32> {
+        indices
+            .chunks_exact(3)
+            .filter(|tri| inside(tri))
+            .flatten()
+            .copied()
+            .collect()
+    };
+    let opaque = keep(&mesh.indices[..split]);
+    let transparent = keep(&mesh.indices[split..]);
+
+    let mut remap = vec![u32::MAX; mesh.vertices.len()];
+    let mut kept = Vec::new();
+    let indices: Vec<u32> = opaque
+        .iter()
+        .chain(&transparent)
+        .map(|&i| {
+            if remap[i as usize] == u32::MAX {
+                remap[i as usize] = kept.len() as u32;
+                kept.push(mesh.vertices[i as usize]);
+            }
+            remap[i as usize]
+        })
+        .collect();
+    mesh.vertices = kept;
+    mesh.indices = indices;
+    mesh.transparent_index_count = transparent.len();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::MesherKind;
+
+    #[test]
+    fn coarse_mesh_covers_the_same_box_with_fewer_triangles() {
+        // Bumpy ground: every other column one voxel taller, so the
+        // full-resolution greedy mesh can't merge the top.
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (31, 3, 31), Voxel::from_rgb(90, 140, 60));
+        for x in (0..32).step_by(2) {
+            for z in (0..32).step_by(2) {
+                world.set_voxel(x, 4, z, Voxel::from_rgb(90, 140, 60));
+            }
+        }
+        let full = MesherKind::Greedy.generate(&world, ChunkPos::ZERO);
+        let coarse = LodMesher::new(MesherKind::Greedy, 2).generate(&world, ChunkPos::ZERO);
+        assert!(coarse.triangle_count() < full.triangle_count() / 4);
+        assert_eq!(coarse.chunk_pos, ChunkPos::ZERO);
+
+        let bounds = |mesh: &ChunkMesh| {
+            mesh.vertices.iter().fold(([f32::MAX; 3], [f32::MIN; 3]), |(lo, hi), v| {
+                (
+                    std::array::from_fn(|i| lo[i].min(v.position[i])),
+                    std::array::from_fn(|i| hi[i].max(v.position[i])),
+                )
+            })
+        };
+        let (lo, hi) = bounds(&coarse);
+        assert_eq!(lo, [0.0, 0.0, 0.0]);
+        // Any-solid cells round the bumps up to the next 4-voxel cell.
+        assert_eq!(hi, [32.0, 8.0, 32.0]);
+        // Level 0 is the inner mesher untouched.
+        let level0 = LodMesher::new(MesherKind::Greedy, 0).generate(&world, ChunkPos::ZERO);
+        assert_eq!(level0.triangle_count(), full.triangle_count());
+    }
+
+    #[test]
+    fn boundary_faces_stay_unless_the_neighbor_block_is_full() {
+        // A slab spanning two chunks along X. The neighbor chunk is
+        // fully solid against the seam, so the seam face is culled.
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (63, 7, 7), Voxel::from_rgb(200, 200, 200));
+        let mesh = LodMesher::new(MesherKind::Naive, 1).generate(&world, ChunkPos::ZERO);
+        assert!(mesh.vertices.iter().all(|v| v.position[0] < 32.0 || v.normal[0] <= 0.0));
+
+        // With only a thin sliver across the seam the coarse neighbor
+        // block isn't full, so the seam face is kept to cover the step.
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (31, 7, 7), Voxel::from_rgb(200, 200, 200));
+        world.fill_region((32, 0, 0), (32, 7, 7), Voxel::from_rgb(200, 200, 200));
+        let mesh = LodMesher::new(MesherKind::Naive, 1).generate(&world, ChunkPos::ZERO);
+        assert!(mesh
+            .vertices
+            .iter()
+            .any(|v| v.position[0] == 32.0 && v.normal[0] > 0.0));
+    }
+
+    #[test]
+    fn selection_uses_a_hysteresis_band() {
+        let settings = LodSettings {
+            distance: 100.0,
+            hysteresis: 0.1,
+        };
+        assert_eq!(settings.select(50.0, 0), 0);
+        // Just past the threshold: stays put until past the band.
+        assert_eq!(settings.select(105.0, 0), 0);
+        assert_eq!(settings.select(115.0, 0), 1);
+        // And back: level 1 holds until inside the band.
+        assert_eq!(settings.select(95.0, 1), 1);
+        assert_eq!(settings.select(85.0, 1), 0);
+        // Far away jumps straight to the coarsest level.
+        assert_eq!(settings.select(1000.0, 0), MAX_LOD);
+        assert_eq!(settings.select(10.0, MAX_LOD), 0);
+    }
+}
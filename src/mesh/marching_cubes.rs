@@ -1,13 +1,19 @@
-//! Marching Cubes mesher for export-time geometry smoothing.
+//! Marching Cubes meshing for smooth (non-blocky) geometry.
 //!
-//! Used by the "smoothed" variants of the OBJ / GLB exporters. The
-//! editor's render path keeps the chunky greedy mesher; this module
-//! is a separate code path invoked only when the user picks
-//! "Wavefront OBJ - smoothed (.obj)..." (or the GLB equivalent).
-//! It walks the entire world, samples a density field at voxel
-//! centers, applies a 3×3×3 smoothing pass, then runs the classic
-//! Paul Bourke / Lorensen-Cline Marching Cubes algorithm on the
-//! resulting field to produce a continuous interpolated surface.
+//! Two entry points share the same cube-marching core:
+//!
+//! - [`mesh_world_smoothed`] — used by the "smoothed" variants of the
+//!   OBJ / GLB exporters ("Wavefront OBJ - smoothed (.obj)..." or the
+//!   GLB equivalent). It walks the entire world, samples a density
+//!   field at voxel centers, optionally applies a 3×3×3 smoothing
+//!   pass, then runs the classic Paul Bourke / Lorensen-Cline
+//!   Marching Cubes algorithm on the resulting field to produce a
+//!   continuous interpolated surface.
+//! - [`MarchingCubesMesher`] — a per-chunk [`Mesher`] the editor view
+//!   can opt into (Viewport Settings ▸ Mesher) to preview how an
+//!   organic model will look once exported smooth. It marches the raw
+//!   0/1 field only; the blur pass stays export-only because it would
+//!   need cross-chunk density beyond the one-cell neighbor ring.
 //!
 //! Per-vertex output:
 //! - **Position**: linear interpolation along the cube edge between
@@ -26,8 +32,8 @@ mod tables;
 
 use tables::{EDGE_TABLE, TRI_TABLE};
 
-use crate::core::{Voxel, World};
-use crate::mesh::{ChunkMesh, Vertex};
+use crate::core::{ChunkPos, Voxel, World, CHUNK_SIZE};
+use crate::mesh::{ChunkMesh, Mesher, Vertex};
 
 /// Density value above which a sample is considered "inside" the
 /// surface. With voxel-centered density (1.0 for solid, 0.0 for air,
@@ -48,7 +54,6 @@ const ISO_LEVEL: f32 = 0.5;
 /// background. The smoothed mode is what the user gets from the
 /// "smoothed" export menu entries.
 pub fn mesh_world_smoothed(world: &World, smooth: bool) -> ChunkMesh {
-    use crate::core::ChunkPos;
     let Some(bbox) = world_voxel_bbox(world) else {
         return ChunkMesh::new(ChunkPos::ZERO);
     };
@@ -109,6 +114,121 @@ pub fn mesh_world_smoothed(world: &World, smooth: bool) -> ChunkMesh {
     mesh
 }
 
+/// Per-chunk Marching Cubes mesher for the editor view.
+///
+/// Each chunk marches every cube that touches one of its voxels, i.e.
+/// cubes whose min corner lies in `[origin - 1, origin + CHUNK_SIZE - 1]`
+/// on every axis. Cubes on a chunk seam are touched by up to eight
+/// chunks, so to emit each exactly once a cube belongs to the
+/// smallest `ChunkPos` (lexicographic) among the chunks holding one of
+/// its *solid* corner voxels. That chunk is non-empty by construction,
+/// so it always gets meshed — even when the neighbor on the other side
+/// of the seam isn't loaded — and the union of all chunk meshes
+/// matches [`mesh_world_smoothed`] with `smooth = false`.
+///
+/// Unlike the export path, vertices are shifted by half a voxel so
+/// the surface sits on the voxel grid (solid cell `x` spans
+/// `x..x + 1`) and lines up with picking, the grid and the selection
+/// overlay.
+pub struct MarchingCubesMesher;
+
+impl Mesher for MarchingCubesMesher {
+    fn generate(&self, world: &World, chunk_pos: ChunkPos) -> ChunkMesh {
+        let Some(chunk_arc) = world.get_chunk(chunk_pos) else {
+            return ChunkMesh::new(chunk_pos);
+        };
+        // Only peek at emptiness here: the density pass below goes
+        // through `World::get_voxel`, which takes its own read lock.
+        if chunk_arc.read().is_empty() {
+            return ChunkMesh::new(chunk_pos);
+        }
+
+        // Field covers the marched cubes' corners (origin - 1 ..=
+        // origin + CHUNK_SIZE) plus one more layer each side for the
+        // central-difference gradient.
+        let (ox, oy, oz) = chunk_pos.world_origin();
+        let min = (ox - 2, oy - 2, oz - 2);
+        let n = CHUNK_SIZE + 4;
+        let size = (n, n, n);
+        let idx = |dx: usize, dy: usize, dz: usize| -> usize {
+            dx + dy * size.0 + dz * size.0 * size.1
+        };
+        let mut density = vec![0.0_f32; n * n * n];
+        for dz in 0..n {
+            for dy in 0..n {
+                for dx in 0..n {
+                    let v = world.get_voxel(
+                        min.0 + dx as i32,
+                        min.1 + dy as i32,
+                        min.2 + dz as i32,
+                    );
+                    if !v.is_air() {
+                        density[idx(dx, dy, dz)] = 1.0;
+                    }
+                }
+            }
+        }
+
+        let mut mesh = ChunkMesh::new(chunk_pos);
+        for gz in 1..=CHUNK_SIZE + 1 {
+            for gy in 1..=CHUNK_SIZE + 1 {
+                for gx in 1..=CHUNK_SIZE + 1 {
+                    if owns_cube(&density, &idx, min, (gx, gy, gz), chunk_pos) {
+                        march_one_cube(
+                            &density, size, &idx, gx, gy, gz, min, world, &mut mesh,
+                        );
+                    }
+                }
+            }
+        }
+        for v in &mut mesh.vertices {
+            v.position[0] += 0.5;
+            v.position[1] += 0.5;
+            v.position[2] += 0.5;
+        }
+        mesh
+    }
+}
+
+/// Whether `chunk_pos` is the designated emitter for the cube at
+/// field-local `g` — see [`MarchingCubesMesher`] for the rule. Cubes
+/// with no solid corner can't produce a surface and belong to nobody.
+fn owns_cube(
+    density: &[f32],
+    idx: &dyn Fn(usize, usize, usize) -> usize,
+    field_min: (i32, i32, i32),
+    g: (usize, usize, usize),
+    chunk_pos: ChunkPos,
+) -> bool {
+    let mut owner: Option<(i32, i32, i32)> = None;
+    for (cx, cy, cz) in [
+        (0, 0, 0),
+        (1, 0, 0),
+        (0, 1, 0),
+        (1, 1, 0),
+        (0, 0, 1),
+        (1, 0, 1),
+        (0, 1, 1),
+        (1, 1, 1),
+    ] {
+        let (lx, ly, lz) = (g.0 + cx, g.1 + cy, g.2 + cz);
+        if density[idx(lx, ly, lz)] < ISO_LEVEL {
+            continue;
+        }
+        let p = ChunkPos::from_world_pos(
+            field_min.0 + lx as i32,
+            field_min.1 + ly as i32,
+            field_min.2 + lz as i32,
+        );
+        let key = (p.x, p.y, p.z);
+        owner = Some(owner.map_or(key, |o| o.min(key)));
+    }
+    owner == Some((chunk_pos.x, chunk_pos.y, chunk_pos.z))
+}
+
+/// An edge crossing's `(position, normal, color)`.
+type EdgeVertex = ([f32; 3], [f32; 3], [f32; 4]);
+
 /// Process a single MC cube at field-local index `(gx, gy, gz)`.
 /// Samples the 8 corners' densities, looks up the triangulation
 /// from the standard MC tables, and emits triangles into `mesh`.
@@ -159,8 +279,8 @@ fn march_one_cube(
     // (density >= ISO_LEVEL). EDGE_TABLE[index] tells us which of the
     // 12 edges intersect the surface.
     let mut cube_index: usize = 0;
-    for i in 0..8 {
-        if densities[i] >= ISO_LEVEL {
+    for (i, &d) in densities.iter().enumerate() {
+        if d >= ISO_LEVEL {
             cube_index |= 1 << i;
         }
     }
@@ -184,7 +304,7 @@ fn march_one_cube(
     // Compute the 12 potential edge vertex positions (only the ones
     // flagged in `edges` are actually used; we lazily fill them).
     // Edge i connects EDGE_VERTEX_PAIRS[i].0 → EDGE_VERTEX_PAIRS[i].1.
-    let mut edge_vertices: [(([f32; 3], [f32; 3], [f32; 4]), bool); 12] = [
+    let mut edge_vertices: [(EdgeVertex, bool); 12] = [
         (([0.0; 3], [0.0; 3], [0.0; 4]), false); 12
     ];
     for e in 0..12 {
@@ -546,6 +666,58 @@ mod tests {
         assert!(mesh.is_empty());
     }
 
+    #[test]
+    fn test_chunk_mesher_matches_world_mesh_across_seams() {
+        // A sphere centered on the chunk corner at the origin touches
+        // all 8 chunks around it. Seam cubes must be emitted by exactly
+        // one chunk, so the per-chunk total equals the whole-world
+        // raw (unblurred) mesh.
+        let mut world = World::new();
+        for x in -6..6 {
+            for y in -6..6 {
+                for z in -6..6 {
+                    let d = (x as f32 + 0.5).powi(2)
+                        + (y as f32 + 0.5).powi(2)
+                        + (z as f32 + 0.5).powi(2);
+                    if d <= 30.0 {
+                        world.set_voxel(x, y, z, Voxel::from_rgb(90, 160, 90));
+                    }
+                }
+            }
+        }
+        world.clear_dirty_flags();
+        assert_eq!(world.chunk_count(), 8);
+
+        let whole = mesh_world_smoothed(&world, false).triangle_count();
+        let per_chunk: usize = world
+            .chunk_positions()
+            .map(|&pos| MarchingCubesMesher.generate(&world, pos).triangle_count())
+            .sum();
+        assert!(whole > 0);
+        assert_eq!(per_chunk, whole);
+    }
+
+    #[test]
+    fn test_chunk_mesher_surface_sits_on_voxel_grid() {
+        // The viewport variant is shifted half a voxel so a solid cell
+        // at (5, 5, 5) is enclosed by its own unit cube, matching the
+        // blocky meshers and picking.
+        let mut world = World::new();
+        world.set_voxel(5, 5, 5, Voxel::from_rgb(255, 255, 255));
+        world.clear_dirty_flags();
+        let mesh = MarchingCubesMesher.generate(&world, ChunkPos::ZERO);
+        assert!(!mesh.is_empty());
+        for v in &mesh.vertices {
+            for axis in 0..3 {
+                let p = v.position[axis];
+                assert!((5.0..=6.0).contains(&p), "vertex {:?} outside voxel", v.position);
+            }
+        }
+        // Missing chunk → empty mesh, same contract as the other meshers.
+        let far = MarchingCubesMesher.generate(&world, ChunkPos::new(4, 0, 0));
+        assert!(far.is_empty());
+    }
+
     #[test]
     fn test_single_voxel_produces_geometry() {
         // With raw 0/1 density (smooth=false), a single solid voxel
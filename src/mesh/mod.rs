@@ -3,8 +3,16 @@
 //! This module converts voxel chunks into renderable triangle meshes.
 //! Multiple meshing strategies are supported:
 //! - Naive: Simple but generates many triangles
-//! - Greedy: Optimized mesh with merged faces (TODO)
-//! - Marching Cubes: Smooth surfaces (TODO)
+//! - Greedy: Optimized mesh with merged faces (the editor default)
+//! - Marching Cubes: Smooth surfaces (smoothed exports, optional view preview)
+//!
+//! The editor's chunk mesher is picked at runtime through [`MeshSettings`]
+//! (Viewport Settings ▸ Mesher / Ambient Occlusion) and runs off the UI
+//! thread on the [`MeshWorker`] pool. Distant chunks can be meshed at
+//! a coarser level of detail through [`LodMesher`].
+//!
+//! Finished meshes go through [`optimize`] (vertex welding, degenerate
+//! removal, vertex cache ordering) before GPU upload and export.
 //!
 //! `patch_to_mesh` reuses the same face emission helpers to render
 //! a procgen `VoxelPatch` (or any sparse voxel list) directly to a
@@ -13,21 +21,32 @@
 
 mod ao;
 mod greedy;
+mod lod;
 mod marching_cubes;
 mod naive;
 mod neighbors;
+mod normals;
+mod optimize;
 mod patch;
 mod vertex;
+mod worker;
 
 pub use greedy::{mesh_chunk_by_material, GreedyMesher};
-pub use marching_cubes::mesh_world_smoothed;
+pub use lod::{downsample_chunk, LodMesher, LodSettings, MAX_LOD};
+pub use marching_cubes::{mesh_world_smoothed, MarchingCubesMesher};
 pub use naive::NaiveMesher;
+pub use normals::{smooth_normals, smooth_normals_joint};
+pub use optimize::{
+    optimize, optimize_vertex_cache, optimize_vertex_fetch, remove_degenerate_triangles,
+    weld_vertices, OptimizeSettings, OptimizeStats,
+};
 pub use patch::patch_to_mesh;
 pub use vertex::{ChunkMesh, Vertex};
+pub use worker::MeshWorker;
 
 pub(crate) use ao::{ao_to_f32, compute_face_ao, unpack_ao};
 
-use crate::core::{ChunkPos, World};
+use crate::core::{ChunkPos, Voxel, World};
 
 /// Trait for mesh generation strategies.
 ///
@@ -39,6 +58,89 @@ pub trait Mesher {
     fn generate(&self, world: &World, chunk_pos: ChunkPos) -> ChunkMesh;
 }
 
+/// Runtime choice of chunk mesher for the editor view. Greedy and
+/// Naive produce the same surface with the same culling and AO — Naive
+/// just skips the quad merge, which is handy for inspecting per-voxel
+/// AO or comparing triangle counts in the Statistics window. Marching
+/// Cubes previews the smooth surface the "smoothed" exporters write
+/// (raw density, no blur), at a much higher triangle count.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize,
+)]
+pub enum MesherKind {
+    #[default]
+    Greedy,
+    Naive,
+    MarchingCubes,
+}
+
+impl MesherKind {
+    pub const ALL: [MesherKind; 3] =
+        [MesherKind::Greedy, MesherKind::Naive, MesherKind::MarchingCubes];
+
+    /// Display label for the viewport settings combo box.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Greedy => "Greedy (merged quads)",
+            Self::Naive => "Naive (quad per face)",
+            Self::MarchingCubes => "Marching Cubes (smooth)",
+        }
+    }
+}
+
+impl Mesher for MesherKind {
+    fn generate(&self, world: &World, chunk_pos: ChunkPos) -> ChunkMesh {
+        MeshSettings {
+            kind: *self,
+            ambient_occlusion: true,
+            smooth_normals: false,
+        }
+        .generate(world, chunk_pos)
+    }
+}
+
+/// Full mesher configuration for the editor view: which mesher plus
+/// its options. Submitted with every chunk job, so changing any field
+/// means every uploaded mesh is stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshSettings {
+    pub kind: MesherKind,
+    /// Bake per-vertex ambient occlusion into blocky meshes. Marching
+    /// Cubes has no per-face corners to sample and ignores it.
+    pub ambient_occlusion: bool,
+    /// Replace the mesher's normals with area-weighted averages (see
+    /// [`smooth_normals`]).
+    pub smooth_normals: bool,
+}
+
+impl Default for MeshSettings {
+    fn default() -> Self {
+        Self {
+            kind: MesherKind::default(),
+            ambient_occlusion: true,
+            smooth_normals: false,
+        }
+    }
+}
+
+impl Mesher for MeshSettings {
+    fn generate(&self, world: &World, chunk_pos: ChunkPos) -> ChunkMesh {
+        let mut mesh = match self.kind {
+            MesherKind::Greedy => GreedyMesher::new()
+                .with_ambient_occlusion(self.ambient_occlusion)
+                .generate(world, chunk_pos),
+            MesherKind::Naive => NaiveMesher::new()
+                .with_ambient_occlusion(self.ambient_occlusion)
+                .generate(world, chunk_pos),
+            MesherKind::MarchingCubes => MarchingCubesMesher.generate(world, chunk_pos),
+        };
+        if self.smooth_normals {
+            smooth_normals(&mut mesh);
+        }
+        mesh
+    }
+}
+
 /// Face direction for voxel faces
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -82,6 +184,12 @@ impl Face {
         }
     }
 
+    /// Face whose [`Self::offset`] is `offset` — e.g. a raycast hit's
+    /// normal. `None` unless it's a unit axis vector.
+    pub fn from_offset(offset: (i32, i32, i32)) -> Option<Face> {
+        Face::ALL.into_iter().find(|f| f.offset() == offset)
+    }
+
     /// All six faces
     pub const ALL: [Face; 6] = [
         Face::PosX,
@@ -177,6 +285,21 @@ pub(crate) fn face_quad_vertices_sized(
     }
 }
 
+/// Whether `voxel` shows its face toward `neighbor`. Air always
+/// exposes it and an opaque neighbor always hides it. A translucent
+/// neighbor hides only the face of a translucent voxel of the same
+/// color, so the inside of a glass block vanishes but the wall behind a
+/// window, and the boundary between water and glass, stay visible.
+pub(crate) fn face_exposed(voxel: Voxel, neighbor: Voxel) -> bool {
+    if neighbor.is_air() {
+        return true;
+    }
+    if neighbor.is_opaque() {
+        return false;
+    }
+    voxel.is_opaque() || voxel.color() != neighbor.color()
+}
+
 /// Cheap directional shading: top brightest, bottom darkest, sides in
 /// between. Alpha passes through unchanged.
 pub(crate) fn apply_face_shading(color: [f32; 4], face: Face) -> [f32; 4] {
@@ -193,6 +316,7 @@ pub(crate) fn apply_face_shading(color: [f32; 4], face: Face) -> [f32; 4] {
 /// AO values. Wraps `face_quad_vertices_sized` and writes
 /// `ao[i]` into `vertices[i].ao`. Per-vertex AO order matches
 /// `face_vertex_signs` (used by `compute_face_ao`).
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn face_quad_vertices_sized_ao(
     x: f32,
     y: f32,
@@ -9,39 +9,50 @@
 //! sample 3 cells each in the face's outside layer (12 samples per
 //! face) via `mesh::neighbors::voxel_at_local`, which routes through
 //! the 26-neighbor lock array. AO 0–3 maps to a brightness factor in
-//! the fragment shader.
+//! the fragment shader. AO can be switched off per mesher
+//! ([`NaiveMesher::with_ambient_occlusion`]), leaving every corner lit.
 
 use super::neighbors::{
     lock_neighbors, neighbor_arcs, voxel_at_local, NeighborArcs, NeighborGuards,
 };
 use super::{
-    ao_to_f32, apply_face_shading, compute_face_ao, face_quad_vertices_sized_ao,
-    ChunkMesh, Face, Mesher,
+    ao_to_f32, apply_face_shading, compute_face_ao, face_exposed,
+    face_quad_vertices_sized_ao, ChunkMesh, Face, Mesher,
 };
-use crate::core::{Chunk, ChunkPos, World, CHUNK_SIZE};
+use crate::core::{Chunk, ChunkPos, Voxel, World, CHUNK_SIZE};
 
 /// Naive mesher that generates individual quads for each visible face.
-pub struct NaiveMesher;
+pub struct NaiveMesher {
+    ambient_occlusion: bool,
+}
 
 impl NaiveMesher {
     pub fn new() -> Self {
-        Self
+        Self {
+            ambient_occlusion: true,
+        }
     }
 
-    /// Whether the cell at chunk-local `(x, y, z)` exposes a face in
-    /// the given direction. Routes the neighbor lookup through
-    /// `voxel_at_local` so face-edge and corner-edge cells use the
-    /// same 26-neighbor lock array as AO sampling.
+    /// Toggle baked per-vertex AO. Off writes every corner as
+    /// unoccluded and skips the 12 samples per face.
+    pub fn with_ambient_occlusion(mut self, enabled: bool) -> Self {
+        self.ambient_occlusion = enabled;
+        self
+    }
+
+    /// Whether `voxel` at chunk-local `(x, y, z)` exposes a face in
+    /// the given direction (see `face_exposed`). Routes the neighbor
+    /// lookup through `voxel_at_local` so face-edge and corner-edge
+    /// cells use the same 26-neighbor lock array as AO sampling.
     fn is_face_visible(
         chunk: &Chunk,
         neighbors: &NeighborGuards,
-        x: i32,
-        y: i32,
-        z: i32,
+        voxel: Voxel,
+        (x, y, z): (i32, i32, i32),
         face: Face,
     ) -> bool {
         let (dx, dy, dz) = face.offset();
-        voxel_at_local(chunk, neighbors, x + dx, y + dy, z + dz).is_air()
+        face_exposed(voxel, voxel_at_local(chunk, neighbors, x + dx, y + dy, z + dz))
     }
 }
 
@@ -94,9 +105,8 @@ impl Mesher for NaiveMesher {
                         if !Self::is_face_visible(
                             &chunk,
                             &neighbors,
-                            x as i32,
-                            y as i32,
-                            z as i32,
+                            voxel,
+                            (x as i32, y as i32, z as i32),
                             face,
                         ) {
                             continue;
@@ -104,24 +114,28 @@ impl Mesher for NaiveMesher {
                         let shaded = apply_face_shading(color, face);
                         // 4-corner AO via 12 voxel samples through
                         // the 26-neighbor lock array.
-                        let ao_int = compute_face_ao(
-                            (world_x, world_y, world_z),
-                            face,
-                            |p| {
-                                let lx = p.0 - wx;
-                                let ly = p.1 - wy;
-                                let lz = p.2 - wz;
-                                voxel_at_local(&chunk, &neighbors, lx, ly, lz)
-                                    .is_solid()
-                            },
-                        );
+                        let ao_int = if self.ambient_occlusion {
+                            compute_face_ao(
+                                (world_x, world_y, world_z),
+                                face,
+                                |p| {
+                                    let lx = p.0 - wx;
+                                    let ly = p.1 - wy;
+                                    let lz = p.2 - wz;
+                                    voxel_at_local(&chunk, &neighbors, lx, ly, lz)
+                                        .is_solid()
+                                },
+                            )
+                        } else {
+                            [3; 4]
+                        };
                         let ao = [
                             ao_to_f32(ao_int[0]),
                             ao_to_f32(ao_int[1]),
                             ao_to_f32(ao_int[2]),
                             ao_to_f32(ao_int[3]),
                         ];
-                        let vertices = face_quad_vertices_sized_ao(
+                        let mut vertices = face_quad_vertices_sized_ao(
                             world_x as f32,
                             world_y as f32,
                             world_z as f32,
@@ -131,12 +145,16 @@ impl Mesher for NaiveMesher {
                             shaded,
                             ao,
                         );
+                        for vert in &mut vertices {
+                            vert.material = voxel.material as u32;
+                        }
                         mesh.add_quad_with_ao_flip(vertices);
                     }
                 }
             }
         }
 
+        mesh.partition_transparent();
         mesh
     }
 }
@@ -222,6 +240,24 @@ mod tests {
         assert_eq!(ao_count_partial, 2);
     }
 
+    #[test]
+    fn test_ambient_occlusion_off_leaves_every_corner_lit() {
+        // Same L-shaped pair as above, which darkens two corners with
+        // AO on; with AO off every vertex is fully lit.
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(1, 1, 0, Voxel::from_rgb(0, 255, 0));
+
+        let lit = NaiveMesher::new()
+            .with_ambient_occlusion(false)
+            .generate(&world, ChunkPos::ZERO);
+        assert!(!lit.is_empty());
+        assert!(lit.vertices.iter().all(|v| v.ao == 1.0));
+        let shaded = NaiveMesher::new().generate(&world, ChunkPos::ZERO);
+        assert_eq!(lit.triangle_count(), shaded.triangle_count());
+        assert!(shaded.vertices.iter().any(|v| v.ao < 1.0));
+    }
+
     #[test]
     fn test_two_adjacent_voxels() {
         let mut world = World::new();
@@ -266,4 +302,22 @@ mod tests {
         // All 6 faces visible.
         assert_eq!(mesh.triangle_count(), 12);
     }
+
+    #[test]
+    fn test_dense_block_spanning_chunks_emits_only_outer_shell() {
+        // 40³ solid block straddling the 8 chunks around the origin.
+        // Every chunk-boundary face inside it is culled against the
+        // neighbor chunk, so the total is exactly the outer shell:
+        // 6 sides × 40² faces × 2 triangles.
+        let mut world = World::new();
+        world.fill_region((-20, -20, -20), (19, 19, 19), Voxel::from_rgb(90, 90, 90));
+        assert_eq!(world.chunk_count(), 8);
+
+        let mesher = NaiveMesher::new();
+        let total: usize = world
+            .chunk_positions()
+            .map(|&pos| mesher.generate(&world, pos).triangle_count())
+            .sum();
+        assert_eq!(total, 6 * 40 * 40 * 2);
+    }
 }
@@ -0,0 +1,110 @@
+//! Smooth vertex normals.
+//!
+//! Blocky meshes carry one hard normal per face, and Marching Cubes
+//! takes its normals from the density gradient, which shows the voxel
+//! grid through the shading. [`smooth_normals`] replaces them with the
+//! area-weighted average of every triangle meeting at a position, for
+//! organic-looking exports and the viewport's Smooth Normals preview.
+
+use std::collections::HashMap;
+
+use super::ChunkMesh;
+
+/// Give every vertex the area-weighted average normal of all triangles
+/// touching its position, across all of `meshes` — so meshes written
+/// side by side (per-chunk export parts) shade without seams. Positions
+/// with no usable triangle keep their normal.
+pub fn smooth_normals_joint(meshes: &mut [&mut ChunkMesh]) {
+    // The unnormalized cross product is the face normal scaled by twice
+    // the triangle's area — exactly the weighting we want.
+    let mut sums: HashMap<[u32; 3], [f32; 3]> = HashMap::new();
+    for mesh in meshes.iter() {
+        for tri in mesh.indices.chunks_exact(3) {
+            let p = |k: usize| mesh.vertices[tri[k] as usize].position;
+            let (a, b, c) = (p(0), p(1), p(2));
+            let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let w = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let n = [
+                u[1] * w[2] - u[2] * w[1],
+                u[2] * w[0] - u[0] * w[2],
+                u[0] * w[1] - u[1] * w[0],
+            ];
+            for corner in [a, b, c] {
+                let sum = sums.entry(position_key(corner)).or_insert([0.0; 3]);
+                for axis in 0..3 {
+                    sum[axis] += n[axis];
+                }
+            }
+        }
+    }
+    for mesh in meshes.iter_mut() {
+        for v in &mut mesh.vertices {
+            let Some(sum) = sums.get(&position_key(v.position)) else {
+                continue;
+            };
+            let len = (sum[0] * sum[0] + sum[1] * sum[1] + sum[2] * sum[2]).sqrt();
+            if len > f32::EPSILON {
+                v.normal = sum.map(|c| c / len);
+            }
+        }
+    }
+}
+
+/// [`smooth_normals_joint`] for a single mesh. Chunk meshes only see
+/// their own triangles, so the viewport preview can show a faint crease
+/// along chunk borders that a merged export doesn't have.
+pub fn smooth_normals(mesh: &mut ChunkMesh) {
+    smooth_normals_joint(&mut [mesh]);
+}
+
+/// Bitwise position key, with `-0.0` folded into `0.0`.
+fn position_key(p: [f32; 3]) -> [u32; 3] {
+    p.map(|c| (c + 0.0).to_bits())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ChunkPos, Voxel, World};
+    use crate::mesh::{GreedyMesher, Mesher};
+
+    #[test]
+    fn cube_corners_blend_their_three_faces() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(255, 255, 255));
+        let mut mesh = GreedyMesher::new().generate(&world, ChunkPos::ZERO);
+        smooth_normals(&mut mesh);
+        // Every corner blends its three faces, so each normal component
+        // points away from the cube's center; a corner on a face's
+        // triangulation diagonal weighs that face double.
+        for v in &mesh.vertices {
+            let len = v.normal.iter().map(|c| c * c).sum::<f32>().sqrt();
+            assert!((len - 1.0).abs() < 1e-5);
+            for axis in 0..3 {
+                let outward = v.position[axis] - 0.5;
+                assert!(v.normal[axis] * outward > 0.1, "{:?}", v);
+            }
+        }
+    }
+
+    #[test]
+    fn joint_smoothing_spans_meshes() {
+        let mut world = World::new();
+        world.set_voxel(31, 0, 0, Voxel::from_rgb(255, 255, 255));
+        world.set_voxel(32, 0, 0, Voxel::from_rgb(255, 255, 255));
+        let mesher = GreedyMesher::new();
+        let mut a = mesher.generate(&world, ChunkPos::new(0, 0, 0));
+        let mut b = mesher.generate(&world, ChunkPos::new(1, 0, 0));
+        smooth_normals_joint(&mut [&mut a, &mut b]);
+        // On the shared seam (x = 32) the top corners average the two
+        // boxes' top faces and sides: no X component is left.
+        let seam = a
+            .vertices
+            .iter()
+            .chain(&b.vertices)
+            .filter(|v| v.position[0] == 32.0 && v.position[1] == 1.0);
+        for v in seam {
+            assert!(v.normal[0].abs() < 1e-5, "{:?}", v.normal);
+        }
+    }
+}
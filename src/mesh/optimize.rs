@@ -0,0 +1,388 @@
+//! Post-meshing cleanup shared by GPU upload and export.
+//!
+//! Meshers emit every face's corners independently, so a vertex shared
+//! by several triangles exists once per face (and Marching Cubes once
+//! per triangle). [`optimize`] runs up to four passes over a finished
+//! [`ChunkMesh`]:
+//!
+//! 1. **Weld** — merge vertices whose every attribute matches bit for
+//!    bit (position, normal, color, AO, tint zone, material), so the
+//!    surface stays exactly as it was.
+//! 2. **Degenerate removal** — drop triangles that reference one vertex
+//!    twice or have (near) zero area.
+//! 3. **Vertex cache ordering** — reorder the opaque triangles with Tom
+//!    Forsyth's linear-speed algorithm so consecutive triangles reuse
+//!    recently transformed vertices. The translucent tail keeps its
+//!    order, since it's alpha blended.
+//! 4. **Vertex fetch ordering** — renumber vertices in first-use order
+//!    (dropping unused ones) so the vertex buffer is read front to back.
+
+use std::collections::HashMap;
+
+use super::{ChunkMesh, Vertex};
+
+/// Triangles whose doubled area is at or below this are degenerate.
+const DEGENERATE_AREA: f32 = 1e-10;
+
+/// Simulated post-transform cache size for the ordering pass.
+const CACHE_SIZE: usize = 32;
+
+/// Which passes [`optimize`] runs. All on by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptimizeSettings {
+    pub weld: bool,
+    pub remove_degenerate: bool,
+    /// Vertex cache + fetch ordering. Changes triangle order only.
+    pub cache_order: bool,
+}
+
+impl Default for OptimizeSettings {
+    fn default() -> Self {
+        Self {
+            weld: true,
+            remove_degenerate: true,
+            cache_order: true,
+        }
+    }
+}
+
+/// What an [`optimize`] call removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OptimizeStats {
+    pub vertices_removed: usize,
+    pub triangles_removed: usize,
+}
+
+/// Run the passes enabled in `settings` over `mesh` in place. The
+/// opaque / translucent split (`transparent_index_count`) is kept.
+pub fn optimize(mesh: &mut ChunkMesh, settings: OptimizeSettings) -> OptimizeStats {
+    let (vertices, triangles) = (mesh.vertex_count(), mesh.triangle_count());
+    if settings.weld {
+        weld_vertices(mesh);
+    }
+    if settings.remove_degenerate {
+        remove_degenerate_triangles(mesh);
+    }
+    if settings.cache_order {
+        let opaque = mesh.indices.len() - mesh.transparent_index_count;
+        optimize_vertex_cache(&mut mesh.indices[..opaque], mesh.vertices.len());
+        optimize_vertex_fetch(mesh);
+    }
+    OptimizeStats {
+        vertices_removed: vertices - mesh.vertex_count(),
+        triangles_removed: triangles - mesh.triangle_count(),
+    }
+}
+
+/// Merge bit-identical vertices (treating `-0.0` as `0.0`) and remap
+/// the indices onto the survivors, keeping first-seen order.
+pub fn weld_vertices(mesh: &mut ChunkMesh) {
+    let mut index_of: HashMap<[u32; 13], u32> = HashMap::with_capacity(mesh.vertices.len());
+    let mut vertices = Vec::with_capacity(mesh.vertices.len());
+    let remap: Vec<u32> = mesh
+        .vertices
+        .iter()
+        .map(|v| {
+            *index_of.entry(weld_key(v)).or_insert_with(|| {
+                vertices.push(*v);
+                vertices.len() as u32 - 1
+            })
+        })
+        .collect();
+    for i in &mut mesh.indices {
+        *i = remap[*i as usize];
+    }
+    mesh.vertices = vertices;
+}
+
+/// Hash key for welding: the vertex's raw words, with negative zeros
+/// folded into positive ones so `-0.0` and `0.0` weld.
+fn weld_key(v: &Vertex) -> [u32; 13] {
+    let mut key: [u32; 13] = bytemuck::cast(*v);
+    // Every word but the trailing material id is an f32.
+    for word in &mut key[..12] {
+        if *word == (-0.0f32).to_bits() {
+            *word = 0;
+        }
+    }
+    key
+}
+
+/// Drop triangles with a repeated index or (near) zero area, keeping
+/// the order of the rest and the size of the translucent tail in step.
+pub fn remove_degenerate_triangles(mesh: &mut ChunkMesh) {
+    let opaque_len = mesh.indices.len() - mesh.transparent_index_count;
+    let mut indices = Vec::with_capacity(mesh.indices.len());
+    let mut transparent = 0;
+    for (t, tri) in mesh.indices.chunks_exact(3).enumerate() {
+        if is_degenerate(&mesh.vertices, tri) {
+            continue;
+        }
+        indices.extend_from_slice(tri);
+        if t * 3 >= opaque_len {
+            transparent += 3;
+        }
+    }
+    mesh.indices = indices;
+    mesh.transparent_index_count = transparent;
+}
+
+fn is_degenerate(vertices: &[Vertex], tri: &[u32]) -> bool {
+    let (a, b, c) = (tri[0], tri[1], tri[2]);
+    if a == b || b == c || a == c {
+        return true;
+    }
+    let p = |i: u32| vertices[i as usize].position;
+    let (pa, pb, pc) = (p(a), p(b), p(c));
+    let u = [pb[0] - pa[0], pb[1] - pa[1], pb[2] - pa[2]];
+    let w = [pc[0] - pa[0], pc[1] - pa[1], pc[2] - pa[2]];
+    let cross = [
+        u[1] * w[2] - u[2] * w[1],
+        u[2] * w[0] - u[0] * w[2],
+        u[0] * w[1] - u[1] * w[0],
+    ];
+    (cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2]).sqrt() <= DEGENERATE_AREA
+}
+
+/// Forsyth's score for a vertex at `cache_pos` (if cached) with
+/// `remaining` triangles still to emit: recently used vertices score
+/// high (the last triangle's three equally, so strips don't flip back
+/// and forth), and vertices with few triangles left get a boost so
+/// they're finished off instead of lingering.
+fn vertex_score(cache_pos: Option<usize>, remaining: usize) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+    let cache = match cache_pos {
+        Some(pos) if pos < 3 => 0.75,
+        Some(pos) => {
+            let scaled = 1.0 - (pos - 3) as f32 / (CACHE_SIZE - 3) as f32;
+            scaled.powf(1.5)
+        }
+        None => 0.0,
+    };
+    cache + 2.0 / (remaining as f32).sqrt()
+}
+
+/// Reorder the triangles of `indices` for post-transform vertex cache
+/// reuse (Forsyth, "Linear-Speed Vertex Cache Optimisation"). Indices
+/// must be below `vertex_count`.
+pub fn optimize_vertex_cache(indices: &mut [u32], vertex_count: usize) {
+    let tri_count = indices.len() / 3;
+    if tri_count < 2 {
+        return;
+    }
+
+    // Triangles touching each vertex, as one flat adjacency table.
+    let mut offsets = vec![0usize; vertex_count + 1];
+    for &i in indices.iter() {
+        offsets[i as usize + 1] += 1;
+    }
+    for v in 0..vertex_count {
+        offsets[v + 1] += offsets[v];
+    }
+    let mut adjacency = vec![0usize; indices.len()];
+    let mut fill = offsets.clone();
+    for (t, tri) in indices.chunks_exact(3).enumerate() {
+        for &i in tri {
+            adjacency[fill[i as usize]] = t;
+            fill[i as usize] += 1;
+        }
+    }
+    // Live (not yet emitted) triangles per vertex sit at the front of
+    // its adjacency slice; `remaining` counts them.
+    let mut remaining: Vec<usize> = (0..vertex_count).map(|v| offsets[v + 1] - offsets[v]).collect();
+    let mut cache_pos: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut vertex_scores: Vec<f32> = (0..vertex_count)
+        .map(|v| vertex_score(None, remaining[v]))
+        .collect();
+    let tri_scores: Vec<f32> = indices
+        .chunks_exact(3)
+        .map(|tri| tri.iter().map(|&i| vertex_scores[i as usize]).sum())
+        .collect();
+    let mut emitted = vec![false; tri_count];
+
+    let mut output = Vec::with_capacity(indices.len());
+    let mut cache: Vec<u32> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut best = (0..tri_count)
+        .max_by(|&a, &b| tri_scores[a].total_cmp(&tri_scores[b]))
+        .unwrap_or(0);
+    let mut scan = 0usize;
+    while output.len() < indices.len() {
+        let tri = [indices[best * 3], indices[best * 3 + 1], indices[best * 3 + 2]];
+        output.extend_from_slice(&tri);
+        emitted[best] = true;
+
+        // Retire the triangle from its vertices' live lists.
+        for &v in &tri {
+            let v = v as usize;
+            let live = &mut adjacency[offsets[v]..offsets[v] + remaining[v]];
+            if let Some(k) = live.iter().position(|&t| t == best) {
+                live.swap(k, remaining[v] - 1);
+            }
+            remaining[v] -= 1;
+        }
+
+        // Move the triangle's vertices to the front of the cache.
+        let mut next_cache: Vec<u32> = tri.to_vec();
+        next_cache.extend(cache.iter().copied().filter(|v| !tri.contains(v)));
+        for (pos, &v) in next_cache.iter().enumerate() {
+            cache_pos[v as usize] = (pos < CACHE_SIZE).then_some(pos);
+        }
+        cache = next_cache;
+
+        // Rescore the cached vertices and their live triangles, picking
+        // the best of those as the next triangle.
+        let mut best_score = -1.0f32;
+        let mut next = None;
+        for &v in &cache {
+            let v = v as usize;
+            vertex_scores[v] = vertex_score(cache_pos[v], remaining[v]);
+        }
+        for &v in &cache {
+            let v = v as usize;
+            for &t in &adjacency[offsets[v]..offsets[v] + remaining[v]] {
+                let score: f32 = indices[t * 3..t * 3 + 3]
+                    .iter()
+                    .map(|&i| vertex_scores[i as usize])
+                    .sum();
+                if score > best_score {
+                    best_score = score;
+                    next = Some(t);
+                }
+            }
+        }
+        cache.truncate(CACHE_SIZE);
+
+        best = match next {
+            Some(t) => t,
+            // Nothing live touches the cache: start again at the next
+            // unemitted triangle in input order.
+            None => {
+                while scan < tri_count && emitted[scan] {
+                    scan += 1;
+                }
+                if scan == tri_count {
+                    break;
+                }
+                scan
+            }
+        };
+    }
+    indices.copy_from_slice(&output);
+}
+
+/// Renumber vertices in the order the indices first reference them and
+/// drop any that no triangle uses.
+pub fn optimize_vertex_fetch(mesh: &mut ChunkMesh) {
+    let mut remap = vec![u32::MAX; mesh.vertices.len()];
+    let mut vertices = Vec::with_capacity(mesh.vertices.len());
+    for i in &mut mesh.indices {
+        let slot = &mut remap[*i as usize];
+        if *slot == u32::MAX {
+            *slot = vertices.len() as u32;
+            vertices.push(mesh.vertices[*i as usize]);
+        }
+        *i = *slot;
+    }
+    mesh.vertices = vertices;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ChunkPos, Voxel, World};
+    use crate::mesh::{mesh_world_smoothed, GreedyMesher, Mesher};
+
+    fn quad_mesh() -> ChunkMesh {
+        // Two triangles of one quad, each with its own three corners.
+        let v = |x: f32, z: f32| Vertex::new([x, 0.0, z], [0.0, 1.0, 0.0], [1.0; 4]);
+        let mut mesh = ChunkMesh::new(ChunkPos::ZERO);
+        mesh.vertices = vec![v(0.0, 0.0), v(0.0, 1.0), v(1.0, 1.0), v(0.0, 0.0), v(1.0, 1.0), v(1.0, 0.0)];
+        mesh.indices = vec![0, 1, 2, 3, 4, 5];
+        mesh
+    }
+
+    #[test]
+    fn weld_merges_identical_corners() {
+        let mut mesh = quad_mesh();
+        weld_vertices(&mut mesh);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn weld_keeps_vertices_that_differ_in_any_attribute() {
+        let mut mesh = quad_mesh();
+        mesh.vertices[3].ao = 0.5;
+        mesh.vertices[4].normal = [0.0, -1.0, 0.0];
+        weld_vertices(&mut mesh);
+        assert_eq!(mesh.vertex_count(), 6);
+    }
+
+    #[test]
+    fn weld_treats_negative_zero_as_zero() {
+        let mut mesh = quad_mesh();
+        mesh.vertices[3].position = [-0.0, 0.0, -0.0];
+        weld_vertices(&mut mesh);
+        assert_eq!(mesh.vertex_count(), 4);
+    }
+
+    #[test]
+    fn degenerate_triangles_are_dropped_and_tail_tracked() {
+        let mut mesh = quad_mesh();
+        // Collinear opaque sliver, then a translucent repeated-index one.
+        mesh.vertices.push(Vertex::new([2.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0; 4]));
+        mesh.indices.extend_from_slice(&[0, 5, 6, 1, 1, 2, 0, 1, 2]);
+        mesh.transparent_index_count = 6;
+        remove_degenerate_triangles(&mut mesh);
+        assert_eq!(mesh.triangle_count(), 3);
+        assert_eq!(mesh.transparent_index_count, 3);
+        assert_eq!(&mesh.indices[..6], &[0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn cache_order_keeps_every_triangle() {
+        let mut world = World::new();
+        for x in 0..6 {
+            for z in 0..6 {
+                world.set_voxel(x, (x * z) % 3, z, Voxel::from_rgb(40 * x as u8, 90, 30 * z as u8));
+            }
+        }
+        let mut mesh = GreedyMesher::new().generate(&world, ChunkPos::ZERO);
+        let mut before: Vec<[Vertex; 3]> = triangles(&mesh);
+        optimize(&mut mesh, OptimizeSettings::default());
+        let mut after = triangles(&mesh);
+        // Same triangles (up to order and rotation of their corners).
+        let key = |t: &[Vertex; 3]| {
+            let mut k: Vec<[u32; 13]> = t.iter().map(weld_key).collect();
+            k.sort();
+            k
+        };
+        before.sort_by_key(key);
+        after.sort_by_key(key);
+        assert_eq!(before.len(), after.len());
+        for (a, b) in before.iter().zip(&after) {
+            assert_eq!(key(a), key(b));
+        }
+    }
+
+    #[test]
+    fn optimize_shrinks_smoothed_meshes() {
+        let mut world = World::new();
+        world.fill_region((0, 0, 0), (6, 6, 6), Voxel::from_rgb(200, 200, 200));
+        let mut mesh = mesh_world_smoothed(&world, false);
+        let triangles = mesh.triangle_count();
+        let stats = optimize(&mut mesh, OptimizeSettings::default());
+        assert!(stats.vertices_removed > 0);
+        assert_eq!(mesh.triangle_count() + stats.triangles_removed, triangles);
+        assert!(mesh.indices.iter().all(|&i| (i as usize) < mesh.vertex_count()));
+    }
+
+    fn triangles(mesh: &ChunkMesh) -> Vec<[Vertex; 3]> {
+        mesh.indices
+            .chunks_exact(3)
+            .map(|t| [0, 1, 2].map(|k| mesh.vertices[t[k] as usize]))
+            .collect()
+    }
+}
@@ -48,15 +48,15 @@ pub fn patch_to_mesh(
 
             // A face is hidden only if there's a *solid* voxel in the
             // patch right next to it. Air or absent neighbor -> draw.
-            let visible = match map.get(&neighbor) {
-                Some(v) if v.is_solid() => false,
-                _ => true,
-            };
+            let visible = !matches!(map.get(&neighbor), Some(v) if v.is_solid());
 
             if visible {
                 let shaded = apply_face_shading(color, face);
-                let vertices =
+                let mut vertices =
                     face_quad_vertices(x as f32, y as f32, z as f32, face, shaded);
+                for vert in &mut vertices {
+                    vert.material = voxel.material as u32;
+                }
                 mesh.add_quad(vertices);
             }
         }
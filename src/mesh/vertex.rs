@@ -1,7 +1,7 @@
 //! Vertex and mesh data structures for rendering.
 
 use bytemuck::{Pod, Zeroable};
-use crate::core::ChunkPos;
+use crate::core::{ChunkPos, DEFAULT_MATERIAL};
 
 /// Ambient floor used when baking per-vertex AO into exported vertex
 /// colors. **Kept in sync with `ambient_min` in
@@ -19,7 +19,9 @@ pub const AO_AMBIENT_MIN: f32 = 0.5;
 /// - Color: 4 floats (16 bytes)
 /// - AO: 1 float (4 bytes) — 0 = fully occluded, 1 = no occlusion
 /// - Tint zone: 1 float (4 bytes) — faction recolor zone (export only)
-/// Total: 48 bytes per vertex
+/// - Material: 1 u32 (4 bytes) — `Voxel::material` id for shading
+///
+/// Total: 52 bytes per vertex
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 #[repr(C)]
 pub struct Vertex {
@@ -41,6 +43,11 @@ pub struct Vertex {
     /// 3 = reserved. Carried into GLB export as the `_TINTZONE`
     /// attribute; the renderer ignores it. Defaults to 0.0.
     pub tint_zone: f32,
+    /// Material id of the voxel the face belongs to (mirrors
+    /// `Voxel::material`). The shader looks up roughness / metallic /
+    /// emissive for it in the material table (`core::MaterialTable`).
+    /// Defaults to the default material.
+    pub material: u32,
 }
 
 impl Vertex {
@@ -65,6 +72,7 @@ impl Vertex {
             color,
             ao,
             tint_zone: 0.0,
+            material: DEFAULT_MATERIAL as u32,
         }
     }
 
@@ -123,6 +131,12 @@ impl Vertex {
                     shader_location: 4,
                     format: wgpu::VertexFormat::Float32,
                 },
+                // Material id @ offset 48
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 12]>() as wgpu::BufferAddress,
+                    shader_location: 5,
+                    format: wgpu::VertexFormat::Uint32,
+                },
             ],
         }
     }
@@ -137,6 +151,14 @@ pub struct ChunkMesh {
     pub vertices: Vec<Vertex>,
     /// Triangle indices
     pub indices: Vec<u32>,
+    /// Length of the translucent tail of `indices`. After
+    /// [`partition_transparent`](Self::partition_transparent) opaque
+    /// triangles come first and the last `transparent_index_count`
+    /// indices are the alpha-blended ones, so the renderer draws the
+    /// two ranges through different pipelines from one buffer while
+    /// exporters still see every triangle. 0 for meshes that were never
+    /// partitioned (Marching Cubes, previews).
+    pub transparent_index_count: usize,
 }
 
 impl ChunkMesh {
@@ -146,6 +168,7 @@ impl ChunkMesh {
             chunk_pos,
             vertices: Vec::new(),
             indices: Vec::new(),
+            transparent_index_count: 0,
         }
     }
 
@@ -155,6 +178,7 @@ impl ChunkMesh {
             chunk_pos,
             vertices: Vec::with_capacity(vertex_capacity),
             indices: Vec::with_capacity(index_capacity),
+            transparent_index_count: 0,
         }
     }
 
@@ -233,10 +257,58 @@ impl ChunkMesh {
         }
     }
 
+    /// Reorder triangles so the translucent ones (vertex alpha < 1)
+    /// form the tail of `indices`, and record its length. Meshers call
+    /// this once after emitting every face; relative order within each
+    /// group is kept.
+    pub fn partition_transparent(&mut self) {
+        let mut opaque = Vec::with_capacity(self.indices.len());
+        let mut transparent = Vec::new();
+        for tri in self.indices.chunks_exact(3) {
+            // Every vertex of a voxel face carries the same color.
+            if self.vertices[tri[0] as usize].color[3] < 1.0 {
+                transparent.extend_from_slice(tri);
+            } else {
+                opaque.extend_from_slice(tri);
+            }
+        }
+        self.transparent_index_count = transparent.len();
+        opaque.extend_from_slice(&transparent);
+        self.indices = opaque;
+    }
+
+    /// Indices of the opaque triangles.
+    pub fn opaque_indices(&self) -> &[u32] {
+        &self.indices[..self.indices.len() - self.transparent_index_count]
+    }
+
+    /// Indices of the translucent triangles (empty unless partitioned).
+    pub fn transparent_indices(&self) -> &[u32] {
+        &self.indices[self.indices.len() - self.transparent_index_count..]
+    }
+
+    /// Line-list indices (pairs) covering every triangle edge exactly
+    /// once — a quad's shared diagonal isn't doubled. Used for the
+    /// wireframe view, so it shows the real triangulation (greedy
+    /// merges, AO diagonal flips) rather than the voxel grid.
+    pub fn edge_indices(&self) -> Vec<u32> {
+        let mut seen = std::collections::HashSet::with_capacity(self.indices.len());
+        let mut edges = Vec::with_capacity(self.indices.len() * 2);
+        for tri in self.indices.chunks_exact(3) {
+            for (a, b) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                if seen.insert((a.min(b), a.max(b))) {
+                    edges.extend_from_slice(&[a, b]);
+                }
+            }
+        }
+        edges
+    }
+
     /// Clear all mesh data
     pub fn clear(&mut self) {
         self.vertices.clear();
         self.indices.clear();
+        self.transparent_index_count = 0;
     }
 
     /// Get vertex data as bytes for GPU upload
@@ -256,7 +328,7 @@ mod tests {
 
     #[test]
     fn test_vertex_size() {
-        assert_eq!(std::mem::size_of::<Vertex>(), 48);
+        assert_eq!(std::mem::size_of::<Vertex>(), 52);
     }
 
     #[test]
@@ -279,6 +351,49 @@ mod tests {
         assert_eq!(mid.baked_color(), [f, f, f, 0.5]);
     }
 
+    #[test]
+    fn test_partition_transparent_moves_translucent_quads_to_tail() {
+        let quad = |x: f32, alpha: f32| {
+            let c = [1.0, 1.0, 1.0, alpha];
+            [
+                Vertex::new([x, 0.0, 0.0], [0.0; 3], c),
+                Vertex::new([x + 1.0, 0.0, 0.0], [0.0; 3], c),
+                Vertex::new([x + 1.0, 1.0, 0.0], [0.0; 3], c),
+                Vertex::new([x, 1.0, 0.0], [0.0; 3], c),
+            ]
+        };
+        let mut mesh = ChunkMesh::new(ChunkPos::ZERO);
+        mesh.add_quad(quad(0.0, 0.5));
+        mesh.add_quad(quad(1.0, 1.0));
+        mesh.add_quad(quad(2.0, 0.25));
+        assert!(mesh.transparent_indices().is_empty());
+
+        mesh.partition_transparent();
+        assert_eq!(mesh.transparent_index_count, 12);
+        assert_eq!(mesh.opaque_indices(), &[4, 6, 5, 4, 7, 6]);
+        assert!(mesh
+            .transparent_indices()
+            .iter()
+            .all(|&i| mesh.vertices[i as usize].color[3] < 1.0));
+        assert_eq!(mesh.triangle_count(), 6);
+    }
+
+    #[test]
+    fn test_edge_indices_cover_each_triangle_edge_once() {
+        let v = |x: f32, y: f32| Vertex::new([x, y, 0.0], [0.0; 3], [1.0; 4]);
+        let mut mesh = ChunkMesh::new(ChunkPos::ZERO);
+        mesh.add_quad([v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0)]);
+        let edges = mesh.edge_indices();
+        // 4 sides + the shared diagonal.
+        assert_eq!(edges.len(), 10);
+        let mut pairs: Vec<(u32, u32)> = edges
+            .chunks_exact(2)
+            .map(|e| (e[0].min(e[1]), e[0].max(e[1])))
+            .collect();
+        pairs.sort();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (0, 3), (1, 2), (2, 3)]);
+    }
+
     #[test]
     fn test_add_quad_with_ao_flip_picks_correct_diagonal() {
         let mut mesh = ChunkMesh::new(ChunkPos::ZERO);
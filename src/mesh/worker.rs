@@ -0,0 +1,219 @@
+//! Background chunk meshing.
+//!
+//! Re-meshing hundreds of dirty chunks after a flood fill or a VOX
+//! import used to run inline in the frame (parallel, but blocking),
+//! so the UI froze until the last chunk was done. [`MeshWorker`]
+//! moves generation onto a dedicated rayon pool: the app submits
+//! dirty chunks, keeps rendering, and uploads finished meshes from
+//! [`MeshWorker::poll`] as they arrive over an `mpsc` channel.
+//!
+//! Jobs don't borrow the `World`. Each carries a
+//! [`World::neighborhood`] view — the 3×3×3 block of chunk `Arc`s
+//! around the target — which is everything any mesher reads (face
+//! culling, AO and the Marching Cubes gradient all stay within one
+//! chunk of the seam). Edits made while a job is in flight are
+//! picked up because they mark the chunk dirty again and the app
+//! resubmits it; every submission gets a ticket, and only the result
+//! matching a chunk's latest ticket is delivered, so a slow stale
+//! mesh can never overwrite a newer one.
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::core::{ChunkPos, World};
+
+use super::{optimize, ChunkMesh, Mesher, OptimizeSettings};
+
+/// Result message from a worker thread: `(chunk, ticket, mesh)`.
+type Completed = (ChunkPos, u64, ChunkMesh);
+
+/// Dedicated thread pool plus result channel for chunk meshing.
+pub struct MeshWorker {
+    pool: rayon::ThreadPool,
+    tx: mpsc::Sender<Completed>,
+    rx: mpsc::Receiver<Completed>,
+    /// Latest ticket issued per chunk still waiting for its mesh.
+    pending: HashMap<ChunkPos, u64>,
+    next_ticket: u64,
+    /// `(started, meshes delivered)` of the current batch — from the
+    /// first submit after idle until `pending` drains again.
+    batch: Option<(Instant, usize)>,
+    /// Timing of the last batch that drained, handed out once by
+    /// [`Self::take_finished_batch`].
+    finished_batch: Option<(f32, usize)>,
+}
+
+impl MeshWorker {
+    /// Spawn the pool. One core is left to the UI thread (render +
+    /// upload); single-core machines still get one worker.
+    pub fn new() -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get().saturating_sub(1))
+            .unwrap_or(1)
+            .max(1);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("mesh-worker-{i}"))
+            .build()
+            .expect("failed to spawn mesh worker threads");
+        let (tx, rx) = mpsc::channel();
+        Self {
+            pool,
+            tx,
+            rx,
+            pending: HashMap::new(),
+            next_ticket: 0,
+            batch: None,
+            finished_batch: None,
+        }
+    }
+
+    /// Queue `chunk_pos` for meshing with `mesher`. Supersedes any job
+    /// already in flight for the same chunk.
+    pub fn submit<M>(&mut self, world: &World, chunk_pos: ChunkPos, mesher: M)
+    where
+        M: Mesher + Send + 'static,
+    {
+        let ticket = self.next_ticket;
+        self.next_ticket += 1;
+        self.pending.insert(chunk_pos, ticket);
+        self.batch.get_or_insert_with(|| (Instant::now(), 0));
+
+        let view = world.neighborhood(chunk_pos);
+        let tx = self.tx.clone();
+        self.pool.spawn(move || {
+            let mut mesh = mesher.generate(&view, chunk_pos);
+            optimize(&mut mesh, OptimizeSettings::default());
+            // Receiver gone means the app is shutting down.
+            let _ = tx.send((chunk_pos, ticket, mesh));
+        });
+    }
+
+    /// Drain finished jobs without blocking. Returns only meshes that
+    /// are still current; superseded and cancelled results are dropped.
+    pub fn poll(&mut self) -> Vec<ChunkMesh> {
+        let mut meshes = Vec::new();
+        for (pos, ticket, mesh) in self.rx.try_iter() {
+            if self.pending.get(&pos) == Some(&ticket) {
+                self.pending.remove(&pos);
+                meshes.push(mesh);
+            }
+        }
+        if let Some((_, delivered)) = &mut self.batch {
+            *delivered += meshes.len();
+        }
+        if self.pending.is_empty() {
+            if let Some((started, delivered)) = self.batch.take() {
+                self.finished_batch =
+                    Some((started.elapsed().as_secs_f32() * 1000.0, delivered));
+            }
+        }
+        meshes
+    }
+
+    /// Forget every in-flight job — the world was replaced, so their
+    /// results would resurrect meshes for chunks that no longer exist.
+    /// Jobs already running finish, but their results are discarded.
+    pub fn cancel_all(&mut self) {
+        self.pending.clear();
+        self.batch = None;
+    }
+
+    /// Chunks submitted but not yet delivered.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// `(elapsed, delivered, pending)` for the batch still in flight,
+    /// or `None` when the worker is idle. `delivered + pending` is the
+    /// batch size so far — it grows if more chunks are submitted
+    /// before the batch drains.
+    pub fn batch_progress(&self) -> Option<(Duration, usize, usize)> {
+        let (started, delivered) = self.batch?;
+        Some((started.elapsed(), delivered, self.pending.len()))
+    }
+
+    /// `(milliseconds, chunks)` of the most recently drained batch,
+    /// measured from its first submit to its last delivered mesh.
+    /// Returns `Some` once per batch.
+    pub fn take_finished_batch(&mut self) -> Option<(f32, usize)> {
+        self.finished_batch.take()
+    }
+}
+
+impl Default for MeshWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Voxel;
+    use crate::mesh::MesherKind;
+
+    /// Poll until nothing is pending (or give up after a few seconds).
+    fn drain(worker: &mut MeshWorker) -> Vec<ChunkMesh> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut out = Vec::new();
+        while worker.pending() > 0 && Instant::now() < deadline {
+            out.extend(worker.poll());
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        out
+    }
+
+    #[test]
+    fn test_worker_matches_inline_meshing() {
+        let mut world = World::new();
+        world.fill_region((-3, 0, -3), (40, 2, 3), Voxel::from_rgb(120, 90, 60));
+        let mut worker = MeshWorker::new();
+        let positions: Vec<_> = world.chunk_positions().copied().collect();
+        for &pos in &positions {
+            worker.submit(&world, pos, MesherKind::Greedy);
+        }
+        let meshes = drain(&mut worker);
+        assert_eq!(meshes.len(), positions.len());
+        for mesh in &meshes {
+            let inline = MesherKind::Greedy.generate(&world, mesh.chunk_pos);
+            assert_eq!(mesh.triangle_count(), inline.triangle_count());
+        }
+        assert!(worker.batch_progress().is_none());
+        let (_, chunks) = worker.take_finished_batch().expect("batch drained");
+        assert_eq!(chunks, positions.len());
+        assert!(worker.take_finished_batch().is_none());
+    }
+
+    #[test]
+    fn test_resubmit_delivers_only_latest() {
+        let mut world = World::new();
+        world.set_voxel(1, 1, 1, Voxel::from_rgb(200, 0, 0));
+        let mut worker = MeshWorker::new();
+        worker.submit(&world, ChunkPos::ZERO, MesherKind::Greedy);
+        world.set_voxel(2, 1, 1, Voxel::from_rgb(200, 0, 0));
+        worker.submit(&world, ChunkPos::ZERO, MesherKind::Greedy);
+        // Nothing is delivered until polled; the resubmit is still one
+        // chunk of the same batch.
+        let (_, delivered, pending) = worker.batch_progress().expect("batch in flight");
+        assert_eq!((delivered, pending), (0, 1));
+        let meshes = drain(&mut worker);
+        assert_eq!(meshes.len(), 1);
+        // Two merged voxels → one 2×1×1 box.
+        assert_eq!(meshes[0].triangle_count(), 12);
+    }
+
+    #[test]
+    fn test_cancel_all_discards_in_flight_results() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(0, 0, 200));
+        let mut worker = MeshWorker::new();
+        worker.submit(&world, ChunkPos::ZERO, MesherKind::Naive);
+        worker.cancel_all();
+        assert_eq!(worker.pending(), 0);
+        // Give the job time to finish; its result must not surface.
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(worker.poll().is_empty());
+    }
+}
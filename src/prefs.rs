@@ -1,9 +1,11 @@
 //! User preferences persisted across sessions.
 //!
 //! `Prefs` holds everything the user expects to survive a restart:
-//! window geometry, panel visibility toggles, viewport + procgen
-//! settings, last-used brush state, and a recent-files MRU list. The
-//! file lives at the platform-standard config dir
+//! window geometry, panel visibility toggles and dock layout,
+//! viewport + procgen settings, the Preferences window's general
+//! settings (theme, autosave, camera speeds), last-used brush state,
+//! the folders file dialogs last opened in, and a recent-files MRU
+//! list. The file lives at the platform-standard config dir
 //! (`%APPDATA%\voxelith\prefs.ron` on Windows, `~/.config/voxelith/`
 //! on Linux, `~/Library/Application Support/voxelith/` on macOS) and
 //! is encoded as `ron`.
@@ -17,8 +19,11 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::editor::{
+    BrushSettings, ExtrudeDirection, FillSettings, PaintSettings, SculptSettings, WorkPlane,
+};
 use crate::procgen::PipelineGraph;
-use crate::ui::{ProcgenSettings, ViewportSettings};
+use crate::ui::{DockLayout, GeneralSettings, ProcgenSettings, ViewportSettings};
 
 /// Maximum entries kept in the recent-files MRU.
 pub const MAX_RECENT_FILES: usize = 10;
@@ -27,36 +32,26 @@ pub const MAX_RECENT_FILES: usize = 10;
 pub const MAX_RECENT_PROMPTS: usize = 10;
 
 /// Top-level preferences container.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Prefs {
     pub window: WindowPrefs,
     pub panels: PanelVisibility,
+    /// Which panels float or dock, and where.
+    pub layout: DockLayout,
     pub viewport: ViewportSettings,
     pub procgen: ProcgenSettings,
     pub graph: PipelineGraph,
     pub editor: EditorPrefs,
+    /// Edit ▸ Preferences… settings.
+    pub general: GeneralSettings,
+    pub last_dirs: LastDirs,
     pub recent_files: Vec<PathBuf>,
     /// Recent AI-generation prompts, most-recent first. Surfaced as a
     /// History dropdown in the AI panel.
     pub recent_ai_prompts: Vec<String>,
 }
 
-impl Default for Prefs {
-    fn default() -> Self {
-        Self {
-            window: WindowPrefs::default(),
-            panels: PanelVisibility::default(),
-            viewport: ViewportSettings::default(),
-            procgen: ProcgenSettings::default(),
-            graph: PipelineGraph::default(),
-            editor: EditorPrefs::default(),
-            recent_files: Vec::new(),
-            recent_ai_prompts: Vec::new(),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct WindowPrefs {
@@ -85,6 +80,13 @@ pub struct PanelVisibility {
     pub show_viewport_settings: bool,
     pub show_procgen: bool,
     pub show_graph: bool,
+    pub show_layers: bool,
+    pub show_timeline: bool,
+    pub show_rig: bool,
+    pub show_model_info: bool,
+    pub show_history: bool,
+    pub show_materials: bool,
+    pub show_slice_view: bool,
 }
 
 impl Default for PanelVisibility {
@@ -96,10 +98,75 @@ impl Default for PanelVisibility {
             show_viewport_settings: false,
             show_procgen: false,
             show_graph: false,
+            show_layers: true,
+            show_timeline: false,
+            show_rig: false,
+            show_model_info: false,
+            show_history: false,
+            show_materials: false,
+            show_slice_view: false,
         }
     }
 }
 
+/// Which family of files a dialog is for. Each remembers its own last
+/// folder, so exporting to a game's asset folder doesn't move where
+/// projects open from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDirKind {
+    /// Open / Save project.
+    Project,
+    /// Import of models, images and palettes.
+    Import,
+    /// Export of meshes, models, images and palettes.
+    Export,
+    /// Screenshots and turntables.
+    Render,
+}
+
+/// The folder each [`FileDirKind`] of dialog last picked from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LastDirs {
+    pub project: Option<PathBuf>,
+    pub import: Option<PathBuf>,
+    pub export: Option<PathBuf>,
+    pub render: Option<PathBuf>,
+}
+
+impl LastDirs {
+    fn slot(&mut self, kind: FileDirKind) -> &mut Option<PathBuf> {
+        match kind {
+            FileDirKind::Project => &mut self.project,
+            FileDirKind::Import => &mut self.import,
+            FileDirKind::Export => &mut self.export,
+            FileDirKind::Render => &mut self.render,
+        }
+    }
+
+    /// Where a `kind` dialog should open, if one has been used.
+    pub fn get(&self, kind: FileDirKind) -> Option<&Path> {
+        match kind {
+            FileDirKind::Project => self.project.as_deref(),
+            FileDirKind::Import => self.import.as_deref(),
+            FileDirKind::Export => self.export.as_deref(),
+            FileDirKind::Render => self.render.as_deref(),
+        }
+    }
+
+    /// Remember the folder of `file`, a path a `kind` dialog returned.
+    pub fn remember_file(&mut self, kind: FileDirKind, file: &Path) {
+        if let Some(dir) = file.parent().filter(|d| !d.as_os_str().is_empty()) {
+            *self.slot(kind) = Some(dir.to_path_buf());
+        }
+    }
+
+    /// Remember `dir`, a folder a `kind` dialog returned.
+    pub fn remember_folder(&mut self, kind: FileDirKind, dir: &Path) {
+        *self.slot(kind) = Some(dir.to_path_buf());
+    }
+}
+
 /// Editor brush state worth restoring across sessions. `selected_tool`
 /// uses the same numeric encoding as `io::EditorState` for consistency
 /// with project files: 0=Place, 1=Remove, 2=Paint, 3=Eyedropper, 4=Fill.
@@ -114,12 +181,35 @@ pub struct EditorPrefs {
     /// Symmetry axes (`[x, y, z]`). Stored as a plain array rather than
     /// a struct so the on-disk shape stays trivial.
     pub symmetry: [bool; 3],
+    /// Where the symmetry planes cross (`SymmetryAxes::center`).
+    pub symmetry_center: [i32; 3],
     /// Brush material flags (`Voxel::flags`: bit0 emissive / bit1
     /// metallic) so the emissive / metallic toggles survive a restart.
     pub brush_flags: u8,
     /// Brush tint zone (`Voxel::tint_zone`: 0 none / 1 primary /
     /// 2 secondary / 3 reserved) so the zone picker survives a restart.
     pub brush_tint_zone: u8,
+    /// `Box` tool draws a hollow shell instead of a solid block.
+    pub box_hollow: bool,
+    /// Brush paint pattern (gradient / jitter / checker / dither) and
+    /// its settings.
+    pub paint: PaintSettings,
+    /// Brush shape, and the `.vox` the stamp shape was loaded from.
+    pub brush: BrushSettings,
+    /// Sculpt tool mode and strength.
+    pub sculpt: SculptSettings,
+    /// Fill tool region and connectivity.
+    pub fill: FillSettings,
+    /// `Extrude` tool depth in cells and direction (out / inset).
+    pub extrude_depth: u8,
+    pub extrude_direction: ExtrudeDirection,
+    /// Magic wand color tolerance (per RGBA channel).
+    pub wand_tolerance: u8,
+    /// Work-plane slice, whether editing is restricted to it, and
+    /// whether other slices are dimmed.
+    pub work_plane: WorkPlane,
+    pub work_plane_enabled: bool,
+    pub work_plane_dim: bool,
 }
 
 impl Default for EditorPrefs {
@@ -130,8 +220,20 @@ impl Default for EditorPrefs {
             selected_tool: 0,
             palette: Vec::new(),
             symmetry: [false; 3],
+            symmetry_center: [0; 3],
             brush_flags: 0,
             brush_tint_zone: 0,
+            box_hollow: false,
+            paint: PaintSettings::default(),
+            brush: BrushSettings::default(),
+            sculpt: SculptSettings::default(),
+            fill: FillSettings::default(),
+            extrude_depth: 1,
+            extrude_direction: ExtrudeDirection::Out,
+            wand_tolerance: 0,
+            work_plane: WorkPlane::default(),
+            work_plane_enabled: false,
+            work_plane_dim: true,
         }
     }
 }
@@ -185,7 +287,7 @@ impl Prefs {
             std::fs::create_dir_all(parent)?;
         }
         let data = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            .map_err(std::io::Error::other)?;
         std::fs::write(path, data)
     }
 
@@ -227,6 +329,17 @@ mod tests {
         assert_eq!(back.recent_files, p.recent_files);
     }
 
+    #[test]
+    fn test_apply_immediately_roundtrips_and_defaults_off() {
+        // Off by default: Generate stages its result for review.
+        assert!(!Prefs::default().procgen.apply_immediately);
+        let mut p = Prefs::default();
+        p.procgen.apply_immediately = true;
+        let s = ron::ser::to_string_pretty(&p, ron::ser::PrettyConfig::default()).unwrap();
+        let back: Prefs = ron::from_str(&s).unwrap();
+        assert!(back.procgen.apply_immediately);
+    }
+
     #[test]
     fn test_partial_ron_falls_back_to_defaults() {
         // Only provide window — every other section must default.
@@ -283,4 +396,28 @@ mod tests {
         assert_eq!(p.recent_files.len(), MAX_RECENT_FILES);
         assert_eq!(p.recent_files[0], PathBuf::from("/tmp/file10.vxlt"));
     }
+
+    #[test]
+    fn test_last_dirs_remember_per_kind() {
+        let mut dirs = LastDirs::default();
+        assert_eq!(dirs.get(FileDirKind::Export), None);
+        dirs.remember_file(FileDirKind::Export, Path::new("/game/assets/tree.glb"));
+        dirs.remember_folder(FileDirKind::Render, Path::new("/renders/spin"));
+        // A bare file name has no folder to remember.
+        dirs.remember_file(FileDirKind::Project, Path::new("scene.vxlt"));
+        assert_eq!(dirs.get(FileDirKind::Export), Some(Path::new("/game/assets")));
+        assert_eq!(dirs.get(FileDirKind::Render), Some(Path::new("/renders/spin")));
+        assert_eq!(dirs.get(FileDirKind::Project), None);
+        assert_eq!(dirs.get(FileDirKind::Import), None);
+
+        let mut p = Prefs {
+            last_dirs: dirs.clone(),
+            ..Prefs::default()
+        };
+        p.general.autosave_minutes = 0;
+        let s = ron::ser::to_string_pretty(&p, ron::ser::PrettyConfig::default()).unwrap();
+        let back: Prefs = ron::from_str(&s).unwrap();
+        assert_eq!(back.last_dirs, dirs);
+        assert_eq!(back.general.autosave_minutes, 0);
+    }
 }
@@ -0,0 +1,387 @@
+//! Cave / cavern systems via 3D cellular automata.
+//!
+//! Starts from a `width × height × depth` block where each interior
+//! cell is rock with probability `fill_probability`, then runs
+//! `smoothing_iterations` passes of the classic "4-5 rule" lifted to
+//! 3D: a cell becomes rock when at least `rock_threshold` of its 26
+//! neighbors are rock, air otherwise. Out-of-range neighbors count as
+//! rock and the outer shell is pinned solid, so caverns never open
+//! onto the block's faces.
+//!
+//! Noise plus smoothing leaves many small, sealed pockets. With
+//! `keep_largest` on, a 6-connected flood fill labels the air regions
+//! and refills everything but the largest one — a single connected
+//! tunnel system you can actually walk through.
+//!
+//! The output is the remaining rock, shaded darker with depth so the
+//! cave walls read without lighting tweaks.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::core::Voxel;
+
+use super::{
+    GenError, GenProgress, GenResult, GeneratorBackend, GeneratorCategory, GeneratorMeta,
+    Pcg32, VoxelGenerator, VoxelPatch,
+};
+
+/// Largest block the generator accepts (128³). Each smoothing pass
+/// touches 26 neighbors per cell, so this keeps a preview regen in the
+/// low hundreds of milliseconds.
+pub const CAVE_MAX_CELLS: u64 = 128 * 128 * 128;
+
+/// Cellular-automata cave parameters and the entry point implementing
+/// `VoxelGenerator`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CaveGenerator {
+    pub seed: u32,
+    /// X-extent of the rock block in voxels.
+    pub width: u32,
+    /// Y-extent of the rock block in voxels.
+    pub height: u32,
+    /// Z-extent of the rock block in voxels.
+    pub depth: u32,
+    /// World-space min corner of the block.
+    pub origin: (i32, i32, i32),
+    /// Chance each interior cell starts as rock. Lower = more open
+    /// caverns; much above 0.55 mostly seals them up.
+    pub fill_probability: f32,
+    /// Cellular-automata passes. 0 leaves raw noise; 4–6 gives smooth,
+    /// rounded caverns; more changes little.
+    pub smoothing_iterations: u32,
+    /// Rock neighbors (of 26) needed for a cell to become rock. 13 is
+    /// the 3D equivalent of the 2D "4-5 rule"; higher carves wider.
+    pub rock_threshold: u8,
+    /// Keep only the largest connected cavern, refilling the rest.
+    pub keep_largest: bool,
+    /// Rock color at the top of the block; it darkens toward the bottom.
+    pub rock_color: [u8; 3],
+}
+
+impl Default for CaveGenerator {
+    fn default() -> Self {
+        Self {
+            seed: 7,
+            width: 48,
+            height: 24,
+            depth: 48,
+            origin: (-24, 0, -24),
+            fill_probability: 0.45,
+            smoothing_iterations: 5,
+            rock_threshold: 13,
+            keep_largest: true,
+            rock_color: [120, 110, 100],
+        }
+    }
+}
+
+/// Dense boolean grid, `true` = rock, laid out `x + y*w + z*w*h`.
+struct Grid {
+    w: usize,
+    h: usize,
+    d: usize,
+    cells: Vec<bool>,
+}
+
+impl Grid {
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        x + y * self.w + z * self.w * self.h
+    }
+
+    fn is_shell(&self, x: usize, y: usize, z: usize) -> bool {
+        x == 0 || y == 0 || z == 0 || x == self.w - 1 || y == self.h - 1 || z == self.d - 1
+    }
+
+    /// Rock neighbors among the 26 around `(x, y, z)`; out-of-range
+    /// counts as rock.
+    fn rock_neighbors(&self, x: usize, y: usize, z: usize) -> u8 {
+        let mut count = 0;
+        for dz in -1i64..=1 {
+            for dy in -1i64..=1 {
+                for dx in -1i64..=1 {
+                    if dx == 0 && dy == 0 && dz == 0 {
+                        continue;
+                    }
+                    let (nx, ny, nz) = (x as i64 + dx, y as i64 + dy, z as i64 + dz);
+                    let inside = nx >= 0
+                        && ny >= 0
+                        && nz >= 0
+                        && (nx as usize) < self.w
+                        && (ny as usize) < self.h
+                        && (nz as usize) < self.d;
+                    if !inside || self.cells[self.index(nx as usize, ny as usize, nz as usize)] {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn smooth(&mut self, threshold: u8) {
+        let mut next = self.cells.clone();
+        for z in 0..self.d {
+            for y in 0..self.h {
+                for x in 0..self.w {
+                    let i = self.index(x, y, z);
+                    next[i] = self.is_shell(x, y, z) || self.rock_neighbors(x, y, z) >= threshold;
+                }
+            }
+        }
+        self.cells = next;
+    }
+
+    /// Refill every air region except the largest (6-connected).
+    /// Returns how many separate pockets were sealed.
+    fn keep_largest_cavern(&mut self) -> usize {
+        let mut label = vec![u32::MAX; self.cells.len()];
+        let mut sizes = Vec::new();
+        let mut queue = VecDeque::new();
+        for start in 0..self.cells.len() {
+            if self.cells[start] || label[start] != u32::MAX {
+                continue;
+            }
+            let id = sizes.len() as u32;
+            let mut size = 0usize;
+            label[start] = id;
+            queue.push_back(start);
+            while let Some(i) = queue.pop_front() {
+                size += 1;
+                let (x, y, z) = (i % self.w, (i / self.w) % self.h, i / (self.w * self.h));
+                let mut visit = |n: usize| {
+                    if !self.cells[n] && label[n] == u32::MAX {
+                        label[n] = id;
+                        queue.push_back(n);
+                    }
+                };
+                // Air never touches the shell, so interior neighbors
+                // are always in range — but guard anyway.
+                if x > 0 {
+                    visit(i - 1);
+                }
+                if x + 1 < self.w {
+                    visit(i + 1);
+                }
+                if y > 0 {
+                    visit(i - self.w);
+                }
+                if y + 1 < self.h {
+                    visit(i + self.w);
+                }
+                if z > 0 {
+                    visit(i - self.w * self.h);
+                }
+                if z + 1 < self.d {
+                    visit(i + self.w * self.h);
+                }
+            }
+            sizes.push(size);
+        }
+        let Some(largest) = (0..sizes.len()).max_by_key(|&i| sizes[i]) else {
+            return 0;
+        };
+        for (cell, &l) in self.cells.iter_mut().zip(&label) {
+            if l != u32::MAX && l as usize != largest {
+                *cell = true;
+            }
+        }
+        sizes.len() - 1
+    }
+}
+
+impl VoxelGenerator for CaveGenerator {
+    fn metadata(&self) -> GeneratorMeta {
+        GeneratorMeta {
+            id: "builtin.cave",
+            name: "Cellular Caves",
+            description: "Cavern systems carved by 3D cellular automata",
+            category: GeneratorCategory::Terrain,
+            backend: GeneratorBackend::Algorithmic,
+        }
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed as u64)
+    }
+
+    fn generate(&self) -> GenResult<VoxelPatch> {
+        self.generate_with_progress(&GenProgress::none())
+    }
+
+    fn generate_with_progress(&self, progress: &GenProgress) -> GenResult<VoxelPatch> {
+        if self.width < 3 || self.height < 3 || self.depth < 3 {
+            return Err(GenError::InvalidParams(
+                "width, height and depth must be >= 3".into(),
+            ));
+        }
+        let cells = self.width as u64 * self.height as u64 * self.depth as u64;
+        if cells > CAVE_MAX_CELLS {
+            return Err(GenError::InvalidParams(format!(
+                "{} cells exceeds the {} cell limit",
+                cells, CAVE_MAX_CELLS
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.fill_probability) {
+            return Err(GenError::InvalidParams(
+                "fill probability must be within 0..=1".into(),
+            ));
+        }
+
+        let mut rng = Pcg32::from_seed_u64(self.seed as u64);
+        let mut grid = Grid {
+            w: self.width as usize,
+            h: self.height as usize,
+            d: self.depth as usize,
+            cells: vec![true; cells as usize],
+        };
+        for z in 0..grid.d {
+            for y in 0..grid.h {
+                for x in 0..grid.w {
+                    let i = grid.index(x, y, z);
+                    grid.cells[i] =
+                        grid.is_shell(x, y, z) || rng.gen::<f32>() < self.fill_probability;
+                }
+            }
+        }
+        // Each smoothing pass is the bulk of the work; the fill and the
+        // flood fill count as one pass apiece.
+        let passes = self.smoothing_iterations as f32 + 2.0;
+        for i in 0..self.smoothing_iterations {
+            progress.check()?;
+            progress.report((i as f32 + 1.0) / passes);
+            grid.smooth(self.rock_threshold);
+        }
+        progress.check()?;
+        progress.report((passes - 1.0) / passes);
+
+        let mut patch = VoxelPatch::new();
+        if self.keep_largest {
+            let sealed = grid.keep_largest_cavern();
+            if sealed > 0 {
+                patch
+                    .notes
+                    .push(format!("Sealed {} isolated pocket(s) to keep one cavern", sealed));
+            }
+        }
+        if grid.cells.iter().all(|&rock| rock) {
+            patch
+                .notes
+                .push("No caverns survived smoothing — try a lower fill probability".into());
+        }
+
+        // Darken toward the bottom: 100% brightness at the top layer
+        // down to 60% at the floor.
+        let shades: Vec<Voxel> = (0..grid.h)
+            .map(|y| {
+                let t = 0.6 + 0.4 * y as f32 / (grid.h - 1) as f32;
+                let [r, g, b] = self.rock_color.map(|c| (c as f32 * t).round() as u8);
+                Voxel::from_rgb(r, g, b)
+            })
+            .collect();
+        let (ox, oy, oz) = self.origin;
+        for z in 0..grid.d {
+            for (y, &shade) in shades.iter().enumerate() {
+                for x in 0..grid.w {
+                    if grid.cells[grid.index(x, y, z)] {
+                        patch.set(ox + x as i32, oy + y as i32, oz + z as i32, shade);
+                    }
+                }
+            }
+        }
+        Ok(patch)
+    }
+
+    fn estimate_duration(&self) -> Duration {
+        // ~26 neighbor reads per cell per pass; a 48×24×48 block with
+        // 5 passes runs in roughly 20ms.
+        let cells = self.width as u64 * self.height as u64 * self.depth as u64;
+        Duration::from_nanos(cells * (self.smoothing_iterations as u64 + 1) * 150)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn small() -> CaveGenerator {
+        CaveGenerator {
+            width: 20,
+            height: 12,
+            depth: 20,
+            origin: (0, 0, 0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_same_seed_is_deterministic() {
+        let a = small().generate().unwrap();
+        let b = small().generate().unwrap();
+        assert_eq!(a.voxels, b.voxels);
+        let c = CaveGenerator { seed: 99, ..small() }.generate().unwrap();
+        assert_ne!(a.voxels, c.voxels);
+    }
+
+    #[test]
+    fn test_shell_is_solid_and_caverns_are_carved() {
+        let gen = small();
+        let patch = gen.generate().unwrap();
+        let rock: HashSet<_> = patch.voxels.iter().map(|&(p, _)| p).collect();
+        let total = (gen.width * gen.height * gen.depth) as usize;
+        assert!(rock.len() < total, "something must be carved");
+        assert!(rock.len() > total / 4, "most of the block stays rock");
+        for x in 0..gen.width as i32 {
+            for z in 0..gen.depth as i32 {
+                assert!(rock.contains(&(x, 0, z)));
+                assert!(rock.contains(&(x, gen.height as i32 - 1, z)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_keep_largest_leaves_one_connected_cavern() {
+        let gen = CaveGenerator {
+            smoothing_iterations: 1,
+            ..small()
+        };
+        let rock: HashSet<_> = gen.generate().unwrap().voxels.iter().map(|&(p, _)| p).collect();
+        let (w, h, d) = (gen.width as i32, gen.height as i32, gen.depth as i32);
+        let air: Vec<_> = (0..d)
+            .flat_map(|z| (0..h).flat_map(move |y| (0..w).map(move |x| (x, y, z))))
+            .filter(|p| !rock.contains(p))
+            .collect();
+        assert!(!air.is_empty());
+
+        // Flood from the first air cell must reach every air cell.
+        let mut seen = HashSet::from([air[0]]);
+        let mut queue = VecDeque::from([air[0]]);
+        while let Some((x, y, z)) = queue.pop_front() {
+            for n in [
+                (x + 1, y, z),
+                (x - 1, y, z),
+                (x, y + 1, z),
+                (x, y - 1, z),
+                (x, y, z + 1),
+                (x, y, z - 1),
+            ] {
+                if !rock.contains(&n) && air.contains(&n) && seen.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+        assert_eq!(seen.len(), air.len());
+    }
+
+    #[test]
+    fn test_rejects_bad_params() {
+        assert!(CaveGenerator { width: 2, ..small() }.generate().is_err());
+        assert!(CaveGenerator { fill_probability: 1.5, ..small() }.generate().is_err());
+        assert!(CaveGenerator { width: 200, height: 200, depth: 200, ..small() }
+            .generate()
+            .is_err());
+    }
+}
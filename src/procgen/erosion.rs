@@ -0,0 +1,412 @@
+//! Hydraulic + thermal erosion on heightmaps.
+//!
+//! Two passes, both on a float height per `(x, z)` column:
+//!
+//! - **Hydraulic** — droplet simulation (the particle approach popularised
+//!   by Hans Theobald Beyer's "Implementation of a method for hydraulic
+//!   erosion"). Each droplet lands on a column, rolls downhill with some
+//!   inertia, picks up sediment while it's fast and under capacity, and
+//!   drops it where it slows or climbs. That carves valleys along flow
+//!   lines and leaves deposits in basins.
+//! - **Thermal** — slopes steeper than a talus threshold slump toward
+//!   their lower neighbours, softening the one-voxel spikes droplets
+//!   leave behind.
+//!
+//! [`PerlinTerrain`](super::PerlinTerrain) runs this between computing
+//! its height field and voxelizing it; [`ErosionRegion`] runs it on the
+//! surface of an existing world region and emits the difference as a
+//! patch.
+
+use rand::Rng;
+
+use crate::core::{Voxel, World};
+
+use super::{GenError, GenProgress, GenResult, Pcg32, VoxelPatch};
+
+/// Largest column count an [`ErosionRegion`] accepts (512 × 512).
+pub const EROSION_MAX_COLUMNS: usize = 512 * 512;
+
+/// How much of its previous direction a droplet keeps each step.
+const INERTIA: f32 = 0.05;
+/// Fraction of free capacity picked up per step.
+const ERODE_RATE: f32 = 0.3;
+/// Fraction of excess sediment dropped per step.
+const DEPOSIT_RATE: f32 = 0.3;
+/// Fraction of water lost per step.
+const EVAPORATION: f32 = 0.02;
+const GRAVITY: f32 = 4.0;
+/// Steps before a droplet is retired even if it's still moving.
+const MAX_STEPS: usize = 64;
+/// Capacity floor, so droplets on near-flat ground still erode a bit.
+const MIN_CAPACITY: f32 = 0.01;
+
+/// Erosion parameters. Serialized with the terrain generator (prefs /
+/// graph), so every field is `#[serde(default)]`-safe via the
+/// struct-level default.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ErosionSettings {
+    /// Run erosion at all. Off by default so plain FBM terrain is
+    /// unchanged.
+    pub enabled: bool,
+    /// Rain passes. Each pass drops one droplet per column.
+    pub iterations: u32,
+    /// Water each droplet starts with. More water carries more
+    /// sediment, cutting deeper channels.
+    pub rain_amount: f32,
+    /// Sediment a droplet can hold per unit of speed × water × slope.
+    /// Higher = more aggressive carving and larger deposits.
+    pub sediment_capacity: f32,
+    /// Thermal relaxation passes after the rain.
+    pub thermal_iterations: u32,
+    /// Height difference between neighbours (in voxels) above which
+    /// material slumps during the thermal pass.
+    pub talus: f32,
+}
+
+impl Default for ErosionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            iterations: 4,
+            rain_amount: 1.0,
+            sediment_capacity: 4.0,
+            thermal_iterations: 8,
+            talus: 1.5,
+        }
+    }
+}
+
+impl ErosionSettings {
+    pub(super) fn validate(&self) -> GenResult<()> {
+        if !(self.rain_amount > 0.0 && self.sediment_capacity >= 0.0 && self.talus >= 0.0) {
+            return Err(GenError::InvalidParams(
+                "rain amount must be > 0, sediment capacity and talus >= 0".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Erode `heights` (row-major, `width × depth`, `heights[z * width +
+/// x]`) in place: `settings.iterations` rain passes, then the thermal
+/// passes. Cancellation is checked once per pass.
+pub fn erode_heightmap(
+    heights: &mut [f32],
+    width: usize,
+    depth: usize,
+    settings: &ErosionSettings,
+    rng: &mut Pcg32,
+    progress: &GenProgress,
+) -> GenResult<()> {
+    debug_assert_eq!(heights.len(), width * depth);
+    if width < 2 || depth < 2 {
+        return Ok(());
+    }
+    let passes = (settings.iterations + settings.thermal_iterations).max(1) as f32;
+    let mut done = 0.0;
+    for _ in 0..settings.iterations {
+        progress.check()?;
+        for _ in 0..width * depth {
+            let x = rng.gen_range(0.0..(width - 1) as f32);
+            let z = rng.gen_range(0.0..(depth - 1) as f32);
+            droplet(heights, width, depth, x, z, settings);
+        }
+        done += 1.0;
+        progress.report(done / passes);
+    }
+    for _ in 0..settings.thermal_iterations {
+        progress.check()?;
+        thermal_pass(heights, width, depth, settings.talus);
+        done += 1.0;
+        progress.report(done / passes);
+    }
+    Ok(())
+}
+
+/// Height and gradient at a fractional position, bilinear over the
+/// four surrounding columns. `x`, `z` must be in `[0, w-1) × [0, d-1)`.
+fn sample(heights: &[f32], w: usize, x: f32, z: f32) -> (f32, f32, f32) {
+    let (ix, iz) = (x as usize, z as usize);
+    let (u, v) = (x - ix as f32, z - iz as f32);
+    let i = iz * w + ix;
+    let (h00, h10, h01, h11) = (heights[i], heights[i + 1], heights[i + w], heights[i + w + 1]);
+    let gx = (h10 - h00) * (1.0 - v) + (h11 - h01) * v;
+    let gz = (h01 - h00) * (1.0 - u) + (h11 - h10) * u;
+    let h = h00 * (1.0 - u) * (1.0 - v) + h10 * u * (1.0 - v) + h01 * (1.0 - u) * v + h11 * u * v;
+    (h, gx, gz)
+}
+
+/// Add `amount` at a fractional position, split bilinearly over the
+/// four surrounding columns (negative = erode).
+fn splat(heights: &mut [f32], w: usize, x: f32, z: f32, amount: f32) {
+    let (ix, iz) = (x as usize, z as usize);
+    let (u, v) = (x - ix as f32, z - iz as f32);
+    let i = iz * w + ix;
+    heights[i] += amount * (1.0 - u) * (1.0 - v);
+    heights[i + 1] += amount * u * (1.0 - v);
+    heights[i + w] += amount * (1.0 - u) * v;
+    heights[i + w + 1] += amount * u * v;
+}
+
+fn droplet(heights: &mut [f32], w: usize, d: usize, x: f32, z: f32, s: &ErosionSettings) {
+    let (mut x, mut z) = (x, z);
+    let (mut dx, mut dz) = (0.0f32, 0.0f32);
+    let mut speed = 1.0f32;
+    let mut water = s.rain_amount;
+    let mut sediment = 0.0f32;
+
+    for _ in 0..MAX_STEPS {
+        let (h, gx, gz) = sample(heights, w, x, z);
+        dx = dx * INERTIA - gx * (1.0 - INERTIA);
+        dz = dz * INERTIA - gz * (1.0 - INERTIA);
+        let len = (dx * dx + dz * dz).sqrt();
+        if len < 1e-6 {
+            // Flat pit: drop everything and stop.
+            splat(heights, w, x, z, sediment);
+            return;
+        }
+        dx /= len;
+        dz /= len;
+        let (old_x, old_z) = (x, z);
+        x += dx;
+        z += dz;
+        if x < 0.0 || z < 0.0 || x >= (w - 1) as f32 || z >= (d - 1) as f32 {
+            // Ran off the map; its sediment leaves with it.
+            return;
+        }
+
+        let (new_h, _, _) = sample(heights, w, x, z);
+        let dh = new_h - h;
+        let capacity = (-dh * speed * water * s.sediment_capacity).max(MIN_CAPACITY);
+        if dh > 0.0 || sediment > capacity {
+            // Uphill: fill the step behind it (but no more than it
+            // carries); otherwise shed the excess.
+            let amount = if dh > 0.0 {
+                dh.min(sediment)
+            } else {
+                (sediment - capacity) * DEPOSIT_RATE
+            };
+            sediment -= amount;
+            splat(heights, w, old_x, old_z, amount);
+        } else {
+            // Never dig deeper than the drop, or it would carve a pit
+            // behind itself.
+            let amount = ((capacity - sediment) * ERODE_RATE).min(-dh);
+            sediment += amount;
+            splat(heights, w, old_x, old_z, -amount);
+        }
+
+        // Downhill (dh < 0) speeds the droplet up, uphill slows it.
+        speed = (speed * speed - dh * GRAVITY).max(0.0).sqrt();
+        water *= 1.0 - EVAPORATION;
+    }
+    splat(heights, w, x, z, sediment);
+}
+
+/// One thermal relaxation pass: every column moves half its excess
+/// over `talus` toward each lower 4-neighbour. Deltas accumulate in a
+/// buffer so the pass is order-independent.
+fn thermal_pass(heights: &mut [f32], w: usize, d: usize, talus: f32) {
+    let mut delta = vec![0.0f32; heights.len()];
+    for z in 0..d {
+        for x in 0..w {
+            let i = z * w + x;
+            let mut neighbours = [None; 4];
+            if x > 0 {
+                neighbours[0] = Some(i - 1);
+            }
+            if x + 1 < w {
+                neighbours[1] = Some(i + 1);
+            }
+            if z > 0 {
+                neighbours[2] = Some(i - w);
+            }
+            if z + 1 < d {
+                neighbours[3] = Some(i + w);
+            }
+            for j in neighbours.into_iter().flatten() {
+                let diff = heights[i] - heights[j];
+                if diff > talus {
+                    // Quarter of the excess per neighbour keeps the
+                    // total outflow ≤ half, so a column can't overshoot
+                    // below its neighbours in one pass.
+                    let moved = (diff - talus) * 0.25;
+                    delta[i] -= moved;
+                    delta[j] += moved;
+                }
+            }
+        }
+    }
+    for (h, dh) in heights.iter_mut().zip(delta) {
+        *h += dh;
+    }
+}
+
+/// The surface of an existing world region, captured for erosion.
+///
+/// Built on the main thread from a `World` (cheap: one column scan),
+/// then [`erode`](Self::erode)d on a worker. Each column's height is
+/// its topmost solid voxel within `min.1..=max.1`; a column with none
+/// sits at `min.1 - 1`. Eroded columns lose voxels from the top;
+/// raised ones gain `sediment_color` voxels.
+#[derive(Debug, Clone)]
+pub struct ErosionRegion {
+    min: (i32, i32, i32),
+    max: (i32, i32, i32),
+    heights: Vec<i32>,
+}
+
+impl ErosionRegion {
+    /// Capture the region `min..=max` (inclusive). Errors when the
+    /// footprint exceeds [`EROSION_MAX_COLUMNS`].
+    pub fn from_world(world: &World, min: (i32, i32, i32), max: (i32, i32, i32)) -> GenResult<Self> {
+        let w = (max.0 - min.0 + 1).max(0) as usize;
+        let d = (max.2 - min.2 + 1).max(0) as usize;
+        if w * d > EROSION_MAX_COLUMNS {
+            return Err(GenError::InvalidParams(format!(
+                "{} columns exceeds the {} column limit",
+                w * d,
+                EROSION_MAX_COLUMNS
+            )));
+        }
+        let mut heights = Vec::with_capacity(w * d);
+        for z in min.2..=max.2 {
+            for x in min.0..=max.0 {
+                let top = (min.1..=max.1)
+                    .rev()
+                    .find(|&y| world.get_voxel(x, y, z).is_solid())
+                    .unwrap_or(min.1 - 1);
+                heights.push(top);
+            }
+        }
+        Ok(Self { min, max, heights })
+    }
+
+    /// Erode the captured surface and return the voxel edits: air
+    /// above lowered columns, `sediment_color` on raised ones. Heights
+    /// stay inside the region's Y range.
+    pub fn erode(
+        &self,
+        settings: &ErosionSettings,
+        seed: u64,
+        sediment_color: Voxel,
+        progress: &GenProgress,
+    ) -> GenResult<VoxelPatch> {
+        settings.validate()?;
+        let w = (self.max.0 - self.min.0 + 1) as usize;
+        let d = (self.max.2 - self.min.2 + 1) as usize;
+        let mut heights: Vec<f32> = self.heights.iter().map(|&h| h as f32).collect();
+        let mut rng = Pcg32::from_seed_u64(seed);
+        erode_heightmap(&mut heights, w, d, settings, &mut rng, progress)?;
+
+        let mut patch = VoxelPatch::new();
+        let (mut removed, mut added) = (0usize, 0usize);
+        for (i, (&old, &new)) in self.heights.iter().zip(&heights).enumerate() {
+            let new = (new.round() as i32).clamp(self.min.1 - 1, self.max.1);
+            let x = self.min.0 + (i % w) as i32;
+            let z = self.min.2 + (i / w) as i32;
+            for y in new + 1..=old {
+                patch.set(x, y, z, Voxel::AIR);
+                removed += 1;
+            }
+            for y in old + 1..=new {
+                patch.set(x, y, z, sediment_color);
+                added += 1;
+            }
+        }
+        patch.notes.push(format!(
+            "eroded {} voxels, deposited {}",
+            removed, added
+        ));
+        Ok(patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ridge along X: height peaks at the middle row.
+    fn ridge(w: usize, d: usize) -> Vec<f32> {
+        (0..w * d)
+            .map(|i| {
+                let z = (i / w) as f32;
+                12.0 - (z - d as f32 / 2.0).abs()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_erosion_is_deterministic_and_changes_terrain() {
+        let settings = ErosionSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        let run = || {
+            let mut h = ridge(32, 32);
+            let mut rng = Pcg32::from_seed_u64(9);
+            erode_heightmap(&mut h, 32, 32, &settings, &mut rng, &GenProgress::none()).unwrap();
+            h
+        };
+        let a = run();
+        assert_eq!(a, run());
+        assert_ne!(a, ridge(32, 32));
+        // Material only moves around or washes off the edges — erosion
+        // never creates it, and nothing goes non-finite.
+        assert!(a.iter().all(|h| h.is_finite()));
+        let before: f32 = ridge(32, 32).iter().sum();
+        assert!(a.iter().sum::<f32>() <= before * (1.0 + 1e-4));
+    }
+
+    #[test]
+    fn test_thermal_pass_flattens_spike_conserving_mass() {
+        let mut h = vec![0.0f32; 9];
+        h[4] = 10.0;
+        for _ in 0..50 {
+            thermal_pass(&mut h, 3, 3, 1.0);
+        }
+        let total: f32 = h.iter().sum();
+        assert!((total - 10.0).abs() < 1e-3);
+        // Centre within talus of its neighbours afterwards.
+        assert!(h[4] - h[1] <= 1.0 + 1e-3);
+    }
+
+    #[test]
+    fn test_region_erosion_edits_only_surface_inside_region() {
+        let mut world = World::new();
+        let stone = Voxel::from_rgb(100, 100, 100);
+        // 16×16 ridge, heights 2..=10.
+        for z in 0..16 {
+            for x in 0..16 {
+                let top = 10 - (z - 8i32).abs();
+                for y in 0..=top {
+                    world.set_voxel(x, y, z, stone);
+                }
+            }
+        }
+        let region = ErosionRegion::from_world(&world, (0, 0, 0), (15, 15, 15)).unwrap();
+        let settings = ErosionSettings {
+            enabled: true,
+            iterations: 8,
+            ..Default::default()
+        };
+        let sand = Voxel::from_rgb(220, 200, 150);
+        let patch = region
+            .erode(&settings, 3, sand, &GenProgress::none())
+            .unwrap();
+        assert!(!patch.is_empty());
+        for &((x, y, z), v) in &patch.voxels {
+            assert!((0..16).contains(&x) && (0..16).contains(&z));
+            assert!((0..=15).contains(&y));
+            if v.is_air() {
+                // Only carves existing material...
+                assert!(world.get_voxel(x, y, z).is_solid());
+            } else {
+                // ...and only deposits sediment into air.
+                assert_eq!(v, sand);
+                assert!(world.get_voxel(x, y, z).is_air());
+            }
+        }
+    }
+}
@@ -14,6 +14,7 @@
 //! care what nodes do internally.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -21,8 +22,8 @@ use thiserror::Error;
 use crate::core::Voxel;
 
 use super::{
-    GenError, GenResult, LSystemTree, PerlinTerrain, VoxelGenerator, VoxelPatch,
-    WfcGenerator,
+    CaveGenerator, GenError, GenProgress, GenResult, LSystemTree, PerlinTerrain, VoxelGenerator,
+    VoxelPatch, WfcGenerator,
 };
 
 /// Node identifier. Stable within a graph (we never reuse an id after
@@ -59,6 +60,7 @@ pub enum NodeKind {
     Terrain(PerlinTerrain),
     Tree(LSystemTree),
     Wfc(WfcGenerator),
+    Cave(CaveGenerator),
     /// Shift every voxel of `input` by `(dx, dy, dz)` world units.
     Translate {
         input: Option<NodeId>,
@@ -145,11 +147,12 @@ impl Default for FilterPredicate {
 /// the mask's voxel set per `(x, z)` column rather than testing exact
 /// `(x, y, z)` matches — that's what distinguishes Mask from
 /// Combine's set ops.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum MaskMode {
     /// Keep `subject` voxel at `(x, y, z)` iff `mask` has at least one
     /// voxel in the same column with `y_mask < y`. Use for "trees
     /// only above terrain surface".
+    #[default]
     AboveColumn,
     /// Keep `subject` voxel at `(x, y, z)` iff `mask` has at least one
     /// voxel in the same column with `y_mask > y`. Use for "stalactites
@@ -166,12 +169,6 @@ impl MaskMode {
     }
 }
 
-impl Default for MaskMode {
-    fn default() -> Self {
-        Self::AboveColumn
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CombineOp {
     /// Voxels from `a` and `b`. Cells present in both: `b` wins.
@@ -197,7 +194,7 @@ impl NodeKind {
     /// by the UI to render input dropdowns.
     pub fn inputs(&self) -> Vec<NodeId> {
         match self {
-            Self::Terrain(_) | Self::Tree(_) | Self::Wfc(_) => vec![],
+            Self::Terrain(_) | Self::Tree(_) | Self::Wfc(_) | Self::Cave(_) => vec![],
             Self::Translate { input, .. } => input.iter().copied().collect(),
             Self::Filter { input, .. } => input.iter().copied().collect(),
             Self::Mask { subject, mask, .. } => {
@@ -230,6 +227,7 @@ impl NodeKind {
             Self::Terrain(_) => "Source: Terrain",
             Self::Tree(_) => "Source: Tree",
             Self::Wfc(_) => "Source: WFC",
+            Self::Cave(_) => "Source: Caves",
             Self::Translate { .. } => "Translate",
             Self::Filter { .. } => "Filter",
             Self::Mask { .. } => "Mask",
@@ -343,7 +341,7 @@ impl PipelineGraph {
     /// right number of input sockets.
     pub fn input_count(kind: &NodeKind) -> usize {
         match kind {
-            NodeKind::Terrain(_) | NodeKind::Tree(_) | NodeKind::Wfc(_) => 0,
+            NodeKind::Terrain(_) | NodeKind::Tree(_) | NodeKind::Wfc(_) | NodeKind::Cave(_) => 0,
             NodeKind::Translate { .. }
             | NodeKind::Filter { .. }
             | NodeKind::Output { .. } => 1,
@@ -366,7 +364,7 @@ impl PipelineGraph {
     ) -> Result<Option<NodeId>, GraphError> {
         let node = self.get(target).ok_or(GraphError::DanglingReference(target))?;
         Ok(match &node.kind {
-            NodeKind::Terrain(_) | NodeKind::Tree(_) | NodeKind::Wfc(_) => None,
+            NodeKind::Terrain(_) | NodeKind::Tree(_) | NodeKind::Wfc(_) | NodeKind::Cave(_) => None,
             NodeKind::Translate { input, .. }
             | NodeKind::Filter { input, .. }
             | NodeKind::Output { input } => *input,
@@ -442,15 +440,60 @@ impl PipelineGraph {
     /// Run the pipeline and return the patch produced by the Output
     /// node (or an error describing what's wrong with the graph).
     pub fn evaluate(&self) -> GenResult<VoxelPatch> {
+        self.evaluate_with_progress(&GenProgress::none())
+    }
+
+    /// `(GeneratorMeta::id, seed)` of every source node, in node order.
+    /// Recorded with the project when the graph's output is applied.
+    pub fn seeds(&self) -> Vec<(&'static str, u64)> {
+        self.nodes
+            .iter()
+            .filter_map(|n| {
+                let g: &dyn VoxelGenerator = match &n.kind {
+                    NodeKind::Terrain(g) => g,
+                    NodeKind::Tree(g) => g,
+                    NodeKind::Wfc(g) => g,
+                    NodeKind::Cave(g) => g,
+                    _ => return None,
+                };
+                Some((g.metadata().id, g.seed()?))
+            })
+            .collect()
+    }
+
+    /// Sum of the source nodes' `estimate_duration` — the transform
+    /// nodes are linear passes over patches and negligible next to them.
+    pub fn estimate_duration(&self) -> Duration {
+        self.nodes
+            .iter()
+            .map(|n| match &n.kind {
+                NodeKind::Terrain(g) => g.estimate_duration(),
+                NodeKind::Tree(g) => g.estimate_duration(),
+                NodeKind::Wfc(g) => g.estimate_duration(),
+                NodeKind::Cave(g) => g.estimate_duration(),
+                _ => Duration::ZERO,
+            })
+            .sum()
+    }
+
+    /// [`evaluate`](Self::evaluate) for a `GenJob` worker. Every node
+    /// in the evaluation order gets an equal slice of `progress`;
+    /// source nodes report within their slice, and cancellation is
+    /// checked between nodes.
+    pub fn evaluate_with_progress(&self, progress: &GenProgress) -> GenResult<VoxelPatch> {
         let output_id = self.find_output_immut()?;
         let order = self.topo_sort_to(output_id)?;
 
+        let n = order.len().max(1) as f32;
         let mut cache: HashMap<NodeId, VoxelPatch> = HashMap::new();
-        for id in order {
+        for (i, id) in order.into_iter().enumerate() {
+            progress.check()?;
             let node = self
                 .get(id)
                 .ok_or(GraphError::DanglingReference(id))?;
-            let patch = self.eval_node(node, &cache)?;
+            let stage = progress.sub(i as f32 / n, (i + 1) as f32 / n);
+            let patch = self.eval_node(node, &cache, &stage)?;
+            stage.report(1.0);
             cache.insert(id, patch);
         }
         // Output's patch is its sole input's patch (passed through
@@ -515,11 +558,13 @@ impl PipelineGraph {
         &self,
         node: &GraphNode,
         cache: &HashMap<NodeId, VoxelPatch>,
+        progress: &GenProgress,
     ) -> GenResult<VoxelPatch> {
         match &node.kind {
-            NodeKind::Terrain(g) => g.generate(),
-            NodeKind::Tree(g) => g.generate(),
-            NodeKind::Wfc(g) => g.generate(),
+            NodeKind::Terrain(g) => g.generate_with_progress(progress),
+            NodeKind::Tree(g) => g.generate_with_progress(progress),
+            NodeKind::Wfc(g) => g.generate_with_progress(progress),
+            NodeKind::Cave(g) => g.generate_with_progress(progress),
             NodeKind::Translate { input, dx, dy, dz } => {
                 let in_id = input.ok_or(GraphError::MissingInput { node: node.id })?;
                 let in_patch = cache
@@ -593,7 +638,7 @@ fn apply_input_slot(kind: &mut NodeKind, slot: usize, new_input: Option<NodeId>)
             1 => *b = new_input,
             _ => {}
         },
-        NodeKind::Terrain(_) | NodeKind::Tree(_) | NodeKind::Wfc(_) => {}
+        NodeKind::Terrain(_) | NodeKind::Tree(_) | NodeKind::Wfc(_) | NodeKind::Cave(_) => {}
     }
 }
 
@@ -624,7 +669,7 @@ fn clear_input_if(kind: &mut NodeKind, id: NodeId) {
                 *b = None;
             }
         }
-        NodeKind::Terrain(_) | NodeKind::Tree(_) | NodeKind::Wfc(_) => {}
+        NodeKind::Terrain(_) | NodeKind::Tree(_) | NodeKind::Wfc(_) | NodeKind::Cave(_) => {}
     }
 }
 
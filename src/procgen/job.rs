@@ -0,0 +1,276 @@
+//! Off-thread generation jobs.
+//!
+//! Generators are pure CPU, but a large terrain or cave run can take
+//! long enough to stall the editor if it runs inside the frame. A
+//! [`GenJob`] moves the work onto a `std::thread` and streams
+//! [`GenJobEvent`]s back over an `mpsc` channel; the App drains them
+//! once per frame (same shape as the AI job's `tick_ai_job`).
+//!
+//! The worker never touches the `World`. It produces a [`VoxelPatch`]
+//! and the main thread turns that into a single `Command::set_voxels`
+//! when the job finishes, so the whole run is one undo step and an
+//! abandoned (cancelled) run leaves the scene untouched.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{GenError, GenResult, VoxelPatch};
+
+/// Minimum change in reported progress before another event is sent.
+/// Generators call [`GenProgress::report`] from inner loops; this keeps
+/// the channel to a few hundred messages per run at most.
+const REPORT_STEP: f32 = 0.005;
+
+/// Progress sink + cancellation token handed to a running generator.
+///
+/// Fractions passed to [`report`](Self::report) are local to the
+/// callee (`0.0..=1.0`) and mapped into this reporter's slice of the
+/// overall job, so a composite (the pipeline graph) can give each
+/// stage a [`sub`](Self::sub)-range without the stage knowing.
+pub struct GenProgress {
+    cancel: Arc<AtomicBool>,
+    tx: Option<mpsc::Sender<GenJobEvent>>,
+    start: f32,
+    end: f32,
+    last: Cell<f32>,
+}
+
+impl GenProgress {
+    /// A reporter that drops progress and never cancels. Used by the
+    /// synchronous `generate()` path and by preview regeneration.
+    pub fn none() -> Self {
+        Self {
+            cancel: Arc::new(AtomicBool::new(false)),
+            tx: None,
+            start: 0.0,
+            end: 1.0,
+            last: Cell::new(-1.0),
+        }
+    }
+
+    fn new(cancel: Arc<AtomicBool>, tx: mpsc::Sender<GenJobEvent>) -> Self {
+        Self {
+            cancel,
+            tx: Some(tx),
+            start: 0.0,
+            end: 1.0,
+            last: Cell::new(-1.0),
+        }
+    }
+
+    /// Reporter for the `start..end` slice (local fractions) of this one.
+    /// Shares the cancellation token.
+    pub fn sub(&self, start: f32, end: f32) -> Self {
+        let span = self.end - self.start;
+        Self {
+            cancel: self.cancel.clone(),
+            tx: self.tx.clone(),
+            start: self.start + span * start.clamp(0.0, 1.0),
+            end: self.start + span * end.clamp(0.0, 1.0),
+            last: Cell::new(-1.0),
+        }
+    }
+
+    /// Report that `fraction` (0.0..=1.0) of this reporter's work is
+    /// done. Cheap to call per row; small steps are coalesced.
+    pub fn report(&self, fraction: f32) {
+        let Some(tx) = &self.tx else { return };
+        let overall = self.start + (self.end - self.start) * fraction.clamp(0.0, 1.0);
+        if (overall - self.last.get()).abs() < REPORT_STEP && fraction < 1.0 {
+            return;
+        }
+        self.last.set(overall);
+        let _ = tx.send(GenJobEvent::Progress(overall));
+    }
+
+    /// True once the job's owner asked to cancel.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Acquire)
+    }
+
+    /// `Err(GenError::Cancelled)` once cancellation was requested —
+    /// generators `?` this at their checkpoints.
+    pub fn check(&self) -> GenResult<()> {
+        if self.is_cancelled() {
+            Err(GenError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Worker → main-thread messages.
+#[derive(Debug)]
+pub enum GenJobEvent {
+    /// Overall progress, 0.0..=1.0.
+    Progress(f32),
+    /// The run finished (successfully, with an error, or cancelled).
+    /// Always the last event.
+    Done(GenResult<VoxelPatch>),
+}
+
+/// Handle to a generator running on a worker thread.
+///
+/// Dropping the handle doesn't stop the thread; call
+/// [`cancel`](Self::cancel) first if the result is no longer wanted
+/// (the worker notices at its next checkpoint and exits).
+pub struct GenJob {
+    label: String,
+    rx: mpsc::Receiver<GenJobEvent>,
+    cancel: Arc<AtomicBool>,
+    progress: f32,
+    started: Instant,
+    estimate: Duration,
+}
+
+impl GenJob {
+    /// Spawn `work` on a new thread. `label` is the status-bar name of
+    /// the run ("Perlin Terrain", "Graph"); `estimate` is the
+    /// generator's `estimate_duration`, kept for the UI's ETA.
+    pub fn spawn<F>(label: impl Into<String>, estimate: Duration, work: F) -> Self
+    where
+        F: FnOnce(&GenProgress) -> GenResult<VoxelPatch> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let progress = GenProgress::new(cancel.clone(), tx.clone());
+        thread::Builder::new()
+            .name("procgen-job".into())
+            .spawn(move || {
+                let result = progress.check().and_then(|()| work(&progress));
+                // A late cancel wins over a finished result: the user
+                // already saw "cancelling" and expects nothing applied.
+                let result = match result {
+                    Ok(_) if progress.is_cancelled() => Err(GenError::Cancelled),
+                    other => other,
+                };
+                let _ = tx.send(GenJobEvent::Done(result));
+            })
+            .expect("failed to spawn procgen worker thread");
+        Self {
+            label: label.into(),
+            rx,
+            cancel,
+            progress: 0.0,
+            started: Instant::now(),
+            estimate,
+        }
+    }
+
+    /// Ask the worker to stop at its next checkpoint. The job still
+    /// finishes through [`poll`](Self::poll), with `GenError::Cancelled`.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelling(&self) -> bool {
+        self.cancel.load(Ordering::Acquire)
+    }
+
+    /// Drain pending events. Returns the result once the worker is
+    /// done; `None` while it's still running. A worker that died
+    /// without reporting (panic) surfaces as `GenError::Failed`.
+    pub fn poll(&mut self) -> Option<GenResult<VoxelPatch>> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(GenJobEvent::Progress(p)) => self.progress = p,
+                Ok(GenJobEvent::Done(result)) => {
+                    self.progress = 1.0;
+                    return Some(result);
+                }
+                Err(mpsc::TryRecvError::Empty) => return None,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    return Some(Err(GenError::Failed("generator thread panicked".into())));
+                }
+            }
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Last reported progress, 0.0..=1.0.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn estimate(&self) -> Duration {
+        self.estimate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Voxel;
+
+    fn wait(job: &mut GenJob) -> GenResult<VoxelPatch> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            if let Some(result) = job.poll() {
+                return result;
+            }
+            assert!(Instant::now() < deadline, "job never finished");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_job_reports_progress_and_result() {
+        let mut job = GenJob::spawn("test", Duration::ZERO, |progress| {
+            let mut patch = VoxelPatch::new();
+            for i in 0..10 {
+                patch.set(i, 0, 0, Voxel::from_rgb(255, 0, 0));
+                progress.report((i + 1) as f32 / 10.0);
+            }
+            Ok(patch)
+        });
+        let patch = wait(&mut job).unwrap();
+        assert_eq!(patch.len(), 10);
+        assert_eq!(job.progress(), 1.0);
+        assert_eq!(job.label(), "test");
+    }
+
+    #[test]
+    fn test_cancel_stops_worker_at_checkpoint() {
+        let (go_tx, go_rx) = mpsc::channel::<()>();
+        let mut job = GenJob::spawn("test", Duration::ZERO, move |progress| {
+            // Block until the test has requested cancellation, then
+            // hit a checkpoint like a generator's row loop would.
+            go_rx.recv().unwrap();
+            progress.check()?;
+            Ok(VoxelPatch::new())
+        });
+        job.cancel();
+        assert!(job.is_cancelling());
+        go_tx.send(()).unwrap();
+        assert!(matches!(wait(&mut job), Err(GenError::Cancelled)));
+    }
+
+    #[test]
+    fn test_sub_progress_maps_into_parent_range() {
+        let (tx, rx) = mpsc::channel();
+        let root = GenProgress::new(Arc::new(AtomicBool::new(false)), tx);
+        let second_half = root.sub(0.5, 1.0);
+        second_half.report(0.5);
+        let quarter = second_half.sub(0.0, 0.5);
+        quarter.report(1.0);
+        let got: Vec<f32> = rx
+            .try_iter()
+            .map(|e| match e {
+                GenJobEvent::Progress(p) => p,
+                GenJobEvent::Done(_) => panic!("unexpected Done"),
+            })
+            .collect();
+        assert_eq!(got, vec![0.75, 0.75]);
+    }
+}
@@ -1,7 +1,7 @@
 //! Procedural generation algorithms.
 //!
 //! This module hosts the unified entry point for both algorithmic
-//! generators (noise, WFC, L-System, ...) and, eventually, AI
+//! generators (noise + erosion, WFC, L-System, cellular caves, ...) and, eventually, AI
 //! generators. They all implement [`VoxelGenerator`] and emit a
 //! [`VoxelPatch`] — a list of voxel writes — rather than mutating a
 //! `World` directly. Decoupling the output lets callers route the
@@ -10,7 +10,11 @@
 //!
 //! [`CommandHistory`]: crate::editor::CommandHistory
 
+mod cave;
+mod erosion;
 mod graph;
+mod job;
+pub mod rng;
 mod terrain;
 mod tree;
 mod wfc;
@@ -19,6 +23,10 @@ pub use graph::{
     CombineOp, FilterPredicate, GraphError, GraphNode, MaskMode, NodeId,
     NodeKind, PipelineGraph,
 };
+pub use cave::{CaveGenerator, CAVE_MAX_CELLS};
+pub use erosion::{erode_heightmap, ErosionRegion, ErosionSettings, EROSION_MAX_COLUMNS};
+pub use job::{GenJob, GenJobEvent, GenProgress};
+pub use rng::Pcg32;
 pub use terrain::PerlinTerrain;
 pub use tree::LSystemTree;
 pub use wfc::{WfcGenerator, WfcTileset, WFC_TILE_SIZE};
@@ -37,6 +45,9 @@ pub enum GenError {
     InvalidParams(String),
     #[error("Generation timeout")]
     Timeout,
+    /// The run was abandoned via [`GenJob::cancel`]; nothing is applied.
+    #[error("Generation cancelled")]
+    Cancelled,
 }
 
 pub type GenResult<T> = Result<T, GenError>;
@@ -120,9 +131,7 @@ impl VoxelPatch {
     /// `CommandHistory::execute(Command::set_voxels(...))` when the
     /// caller is inside an editor session — that path is reversible.
     pub fn apply(&self, world: &mut World) {
-        for &((x, y, z), voxel) in &self.voxels {
-            world.set_voxel(x, y, z, voxel);
-        }
+        world.set_voxels(self.voxels.iter().copied());
     }
 }
 
@@ -141,12 +150,33 @@ pub trait VoxelGenerator: Send + Sync {
     /// Run the generator and return its output patch.
     fn generate(&self) -> GenResult<VoxelPatch>;
 
+    /// [`generate`](Self::generate) with progress reporting and
+    /// cooperative cancellation, for runs on a [`GenJob`] worker.
+    /// Generators with long loops override this (and forward
+    /// `generate` to it with [`GenProgress::none`]); the default just
+    /// runs `generate` between two checkpoints, which is fine for
+    /// anything that finishes in a few milliseconds.
+    fn generate_with_progress(&self, progress: &GenProgress) -> GenResult<VoxelPatch> {
+        progress.check()?;
+        let patch = self.generate()?;
+        progress.report(1.0);
+        Ok(patch)
+    }
+
     /// Whether the generator can produce output incrementally.
     /// Default: false.
     fn supports_incremental(&self) -> bool {
         false
     }
 
+    /// Seed the next `generate` will use, for generators that have
+    /// one. Recorded in the project when the output is applied (see
+    /// `io::EditorState::procgen_seeds`) so the run can be reproduced.
+    /// Default: `None`.
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+
     /// Hint for UI progress display. Default: zero.
     fn estimate_duration(&self) -> Duration {
         Duration::ZERO
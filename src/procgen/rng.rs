@@ -0,0 +1,180 @@
+//! Seedable, forkable RNG shared by the procedural generators.
+//!
+//! `rand::rngs::StdRng` is explicitly *not* reproducible: its algorithm
+//! may change between `rand` releases and it makes no cross-platform
+//! promises. Generators here need the opposite — a seed typed into the
+//! procgen panel (and recorded in the project, see
+//! `io::EditorState::procgen_seeds`) must rebuild the same voxels on
+//! every machine and every build. [`Pcg32`] is PCG-XSH-RR 64/32
+//! (O'Neill, 2014): pure 64-bit integer arithmetic with wrapping
+//! operations, so the output stream is fully specified by `(seed,
+//! stream)`.
+//!
+//! It implements `rand::RngCore`, so generators keep using `Rng::gen`
+//! / `gen_range` on top of it. Sub-systems that want their own stream
+//! (so adding draws in one doesn't shift every later draw in another)
+//! take a [`fork`](Pcg32::fork) or [`fork_named`](Pcg32::fork_named).
+
+use rand::{RngCore, SeedableRng};
+
+const MULTIPLIER: u64 = 6364136223846793005;
+/// Stream used by `seed_from_u64` / [`Pcg32::from_seed_u64`] — the
+/// reference implementation's default increment.
+const DEFAULT_STREAM: u64 = 0xda3e39cb94b95bdb;
+
+/// PCG-XSH-RR with 64-bit state and 32-bit output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    /// Stream selector; always odd.
+    inc: u64,
+}
+
+impl Pcg32 {
+    /// Generator for `seed` on stream `stream`. Different streams with
+    /// the same seed give unrelated sequences.
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: 0,
+            inc: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    /// Generator for a generator-panel seed on the default stream.
+    pub fn from_seed_u64(seed: u64) -> Self {
+        Self::new(seed, DEFAULT_STREAM >> 1)
+    }
+
+    /// Independent child generator. Advances `self` by four draws, so
+    /// successive forks differ, and the sequence of forks is itself
+    /// deterministic.
+    pub fn fork(&mut self) -> Self {
+        let seed = self.next_u64();
+        let stream = self.next_u64();
+        Self::new(seed, stream)
+    }
+
+    /// Child generator keyed by `name`, without advancing `self`: the
+    /// same parent and name always give the same child, whatever else
+    /// has been drawn from the parent since.
+    pub fn fork_named(&self, name: &str) -> Self {
+        let key = fnv1a(name.as_bytes());
+        Self::new(self.state ^ key, self.inc ^ key.rotate_left(17))
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.inc);
+    }
+}
+
+impl RngCore for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // Low word first, matching `rand_core::impls::next_u64_via_u32`.
+        let lo = self.next_u32() as u64;
+        let hi = self.next_u32() as u64;
+        (hi << 32) | lo
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Pcg32 {
+    /// Little-endian `seed` (bytes 0..8) then `stream` (bytes 8..16).
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut s = [0u8; 8];
+        let mut t = [0u8; 8];
+        s.copy_from_slice(&seed[..8]);
+        t.copy_from_slice(&seed[8..]);
+        Self::new(u64::from_le_bytes(s), u64::from_le_bytes(t))
+    }
+
+    fn seed_from_u64(seed: u64) -> Self {
+        Self::from_seed_u64(seed)
+    }
+}
+
+/// 64-bit FNV-1a — a fixed, platform-independent string hash (unlike
+/// `std::hash`, whose output isn't guaranteed stable).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_matches_pcg32_reference_vector() {
+        // First outputs of the reference `pcg32-demo` (seed 42,
+        // sequence 54) — pins the algorithm bit-for-bit, so a seed
+        // means the same thing on every platform and build.
+        let mut rng = Pcg32::new(42, 54);
+        let got: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+        assert_eq!(
+            got,
+            vec![0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]
+        );
+    }
+
+    #[test]
+    fn test_same_seed_same_stream_and_forks() {
+        let mut a = Pcg32::seed_from_u64(7);
+        let mut b = Pcg32::seed_from_u64(7);
+        let xs: Vec<f32> = (0..16).map(|_| a.gen()).collect();
+        let ys: Vec<f32> = (0..16).map(|_| b.gen()).collect();
+        assert_eq!(xs, ys);
+
+        let (mut fa, mut fb) = (a.fork(), b.fork());
+        assert_eq!(fa.next_u64(), fb.next_u64());
+        // A second fork differs from the first.
+        assert_ne!(a.fork(), fa);
+        assert_ne!(Pcg32::seed_from_u64(7), Pcg32::seed_from_u64(8));
+    }
+
+    #[test]
+    fn test_fork_named_is_stable_and_keyed() {
+        let mut parent = Pcg32::seed_from_u64(1);
+        let branch = parent.fork_named("branches");
+        let leaves = parent.fork_named("leaves");
+        assert_ne!(branch, leaves);
+        // Named forks don't advance the parent, so drawing from one
+        // child doesn't change the other — but drawing from the
+        // parent does move later named forks on.
+        assert_eq!(parent.fork_named("branches"), branch);
+        parent.next_u32();
+        assert_ne!(parent.fork_named("branches"), branch);
+    }
+}
@@ -6,9 +6,11 @@ use noise::{NoiseFn, Perlin};
 
 use crate::core::Voxel;
 
+use super::erosion::{erode_heightmap, ErosionSettings};
+
 use super::{
-    GenError, GenResult, GeneratorBackend, GeneratorCategory, GeneratorMeta,
-    VoxelGenerator, VoxelPatch,
+    GenError, GenProgress, GenResult, GeneratorBackend, GeneratorCategory, GeneratorMeta,
+    Pcg32, VoxelGenerator, VoxelPatch,
 };
 
 /// Heightmap terrain generator using fractal Brownian motion (FBM)
@@ -35,6 +37,11 @@ pub struct PerlinTerrain {
     pub frequency: f64,
     /// Number of FBM octaves (>= 1).
     pub octaves: u32,
+    /// Hydraulic / thermal erosion applied to the height field before
+    /// voxelizing. `#[serde(default)]` (disabled) for prefs and graphs
+    /// saved before erosion existed.
+    #[serde(default)]
+    pub erosion: ErosionSettings,
 }
 
 impl Default for PerlinTerrain {
@@ -53,6 +60,7 @@ impl Default for PerlinTerrain {
             max_height: 12,
             frequency: 0.03,
             octaves: 3,
+            erosion: ErosionSettings::default(),
         }
     }
 }
@@ -68,7 +76,15 @@ impl VoxelGenerator for PerlinTerrain {
         }
     }
 
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed as u64)
+    }
+
     fn generate(&self) -> GenResult<VoxelPatch> {
+        self.generate_with_progress(&GenProgress::none())
+    }
+
+    fn generate_with_progress(&self, progress: &GenProgress) -> GenResult<VoxelPatch> {
         if self.width == 0 || self.depth == 0 {
             return Err(GenError::InvalidParams(
                 "width and depth must be > 0".into(),
@@ -79,6 +95,7 @@ impl VoxelGenerator for PerlinTerrain {
                 "max_height must be >= min_height".into(),
             ));
         }
+        self.erosion.validate()?;
         let octaves = self.octaves.max(1);
 
         let perlin = Perlin::new(self.seed);
@@ -86,19 +103,15 @@ impl VoxelGenerator for PerlinTerrain {
         let half_w = (self.width / 2) as i32;
         let half_d = (self.depth / 2) as i32;
         let height_range = (self.max_height - self.min_height) as f64;
+        let (w, d) = (self.width as usize, self.depth as usize);
 
-        // Rough capacity hint: half the bounding volume tends to be solid.
-        let est = (self.width as usize)
-            * (self.depth as usize)
-            * (height_range as usize).max(1)
-            / 2;
-        let mut patch = VoxelPatch::with_capacity(est);
-
-        // Color stratification (grass / dirt / stone).
-        let grass = Voxel::from_rgb(76, 153, 0);
-        let dirt = Voxel::from_rgb(139, 90, 43);
-        let stone = Voxel::from_rgb(128, 128, 128);
-        let dirt_band: i32 = 4;
+        // Height-field pass and voxelize pass share the progress bar;
+        // erosion (when on) gets the bulk of it.
+        let (field_end, erode_end) = if self.erosion.enabled {
+            (0.2, 0.9)
+        } else {
+            (0.9, 0.9)
+        };
 
         // Span exactly width × depth columns, centered on the origin.
         // Upper bound is `dim - half` (not `half`): for odd sizes the
@@ -107,7 +120,13 @@ impl VoxelGenerator for PerlinTerrain {
         // (dim - half == half).
         let x_end = self.width as i32 - half_w;
         let z_end = self.depth as i32 - half_d;
+
+        // Column heights above `min_height`, row-major (z outer).
+        let field = progress.sub(0.0, field_end);
+        let mut heights: Vec<f64> = Vec::with_capacity(w * d);
         for z in -half_d..z_end {
+            field.check()?;
+            field.report((z + half_d) as f32 / self.depth as f32);
             for x in -half_w..x_end {
                 // FBM: sum octaves with halving amplitude / doubling frequency.
                 // Track total amplitude to normalize the output back to ~[-1, 1].
@@ -125,11 +144,62 @@ impl VoxelGenerator for PerlinTerrain {
                 }
 
                 let h_unit = ((acc / total_amp) + 1.0) * 0.5; // [0, 1]
-                let h = self.min_height + (h_unit * height_range).round() as i32;
+                heights.push(h_unit * height_range);
+            }
+        }
+
+        // Eroded heights, when erosion is on. The raw field is kept so
+        // columns that gained material can be topped with sediment.
+        let eroded: Option<Vec<f32>> = if self.erosion.enabled {
+            let mut e: Vec<f32> = heights.iter().map(|&h| h as f32).collect();
+            let mut rng = Pcg32::from_seed_u64(self.seed as u64);
+            erode_heightmap(
+                &mut e,
+                w,
+                d,
+                &self.erosion,
+                &mut rng,
+                &progress.sub(field_end, erode_end),
+            )?;
+            Some(e)
+        } else {
+            None
+        };
+
+        // Rough capacity hint: half the bounding volume tends to be solid.
+        let est = w * d * (height_range as usize).max(1) / 2;
+        let mut patch = VoxelPatch::with_capacity(est);
+
+        // Color stratification (grass / dirt / stone), plus sand where
+        // erosion deposited material.
+        let grass = Voxel::from_rgb(76, 153, 0);
+        let dirt = Voxel::from_rgb(139, 90, 43);
+        let stone = Voxel::from_rgb(128, 128, 128);
+        let sediment = Voxel::from_rgb(194, 178, 128);
+        let dirt_band: i32 = 4;
+
+        let mut i = 0;
+        for z in -half_d..z_end {
+            progress.check()?;
+            for x in -half_w..x_end {
+                let raw = self.min_height + heights[i].round() as i32;
+                let (h, deposited) = match &eroded {
+                    Some(e) => {
+                        let h = (self.min_height + e[i].round() as i32)
+                            .clamp(self.min_height - 1, self.max_height);
+                        (h, h > raw)
+                    }
+                    None => (raw, false),
+                };
+                i += 1;
 
                 for y in self.min_height..=h {
                     let voxel = if y == h {
-                        grass
+                        if deposited {
+                            sediment
+                        } else {
+                            grass
+                        }
                     } else if y > h - dirt_band {
                         dirt
                     } else {
@@ -139,14 +209,22 @@ impl VoxelGenerator for PerlinTerrain {
                 }
             }
         }
+        progress.report(1.0);
 
         Ok(patch)
     }
 
     fn estimate_duration(&self) -> Duration {
         // Loose linear estimate; on a 64x64x4-octave it runs in < 5ms.
-        let n = (self.width as u64) * (self.depth as u64) * (self.octaves.max(1) as u64);
-        Duration::from_micros(n / 4)
+        // Each erosion pass drops one droplet (up to 64 steps) per
+        // column, ~1µs apiece.
+        let columns = (self.width as u64) * (self.depth as u64);
+        let mut micros = columns * (self.octaves.max(1) as u64) / 4;
+        if self.erosion.enabled {
+            micros += columns * self.erosion.iterations as u64
+                + columns * self.erosion.thermal_iterations as u64 / 50;
+        }
+        Duration::from_micros(micros)
     }
 }
 
@@ -154,6 +232,29 @@ impl VoxelGenerator for PerlinTerrain {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_erosion_reshapes_surface_deterministically() {
+        let plain = PerlinTerrain {
+            max_height: 24,
+            frequency: 0.06,
+            ..Default::default()
+        };
+        let eroded = PerlinTerrain {
+            erosion: ErosionSettings {
+                enabled: true,
+                ..Default::default()
+            },
+            ..plain.clone()
+        };
+        let a = eroded.generate().unwrap();
+        let b = eroded.generate().unwrap();
+        assert_eq!(a.voxels, b.voxels);
+        assert_ne!(a.voxels, plain.generate().unwrap().voxels);
+        // Basins collect sediment, which tops its column in sand.
+        let sand = Voxel::from_rgb(194, 178, 128);
+        assert!(a.voxels.iter().any(|&(_, v)| v == sand));
+    }
+
     #[test]
     fn test_default_generates_nonempty() {
         let gen = PerlinTerrain::default();
@@ -8,14 +8,13 @@
 //! every `]` we drop a small leaf cluster at the branch tip.
 
 use glam::{Mat3, Vec3};
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use rand::Rng;
 
 use crate::core::Voxel;
 
 use super::{
     GenError, GenResult, GeneratorBackend, GeneratorCategory, GeneratorMeta,
-    VoxelGenerator, VoxelPatch,
+    Pcg32, VoxelGenerator, VoxelPatch,
 };
 
 /// Plant L-system grown by a 3D turtle.
@@ -219,6 +218,10 @@ impl VoxelGenerator for LSystemTree {
         }
     }
 
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed as u64)
+    }
+
     fn generate(&self) -> GenResult<VoxelPatch> {
         if self.iterations > MAX_ITERATIONS {
             return Err(GenError::InvalidParams(format!(
@@ -234,7 +237,7 @@ impl VoxelGenerator for LSystemTree {
 
         let s = rewrite(AXIOM, self.iterations);
         let mut patch = VoxelPatch::new();
-        let mut rng = StdRng::seed_from_u64(self.seed as u64);
+        let mut rng = Pcg32::from_seed_u64(self.seed as u64);
 
         let trunk = Voxel::from_rgb(
             self.trunk_color[0],
@@ -15,14 +15,13 @@
 
 use std::time::Duration;
 
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use rand::Rng;
 
 use crate::core::Voxel;
 
 use super::{
-    GenError, GenResult, GeneratorBackend, GeneratorCategory, GeneratorMeta,
-    VoxelGenerator, VoxelPatch,
+    GenError, GenProgress, GenResult, GeneratorBackend, GeneratorCategory, GeneratorMeta,
+    Pcg32, VoxelGenerator, VoxelPatch,
 };
 
 /// Cubic side length of each tile, in voxels.
@@ -60,11 +59,12 @@ pub struct Tileset {
 /// [`Self::build`] + [`Self::label`]; UI dropdowns pick from
 /// [`Self::ALL`].
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, Default,
 )]
 pub enum WfcTileset {
     /// Stone walls, floors, T-junctions, doorways. Single ground
     /// layer with walls rising the full tile height.
+    #[default]
     Dungeon,
     /// Grass plots, asphalt roads with sidewalks, intersections,
     /// and small buildings rising above grass. Connector IDs
@@ -72,12 +72,6 @@ pub enum WfcTileset {
     City,
 }
 
-impl Default for WfcTileset {
-    fn default() -> Self {
-        Self::Dungeon
-    }
-}
-
 impl WfcTileset {
     /// All tilesets, in dropdown order.
     pub const ALL: &'static [Self] = &[Self::Dungeon, Self::City];
@@ -103,9 +97,9 @@ impl WfcTileset {
 /// - `0` = "open" (no wall on that face — anything else with `0` fits)
 /// - `1` = "wall" (wall continues across the face — only other walls fit)
 /// - `2` = "doorway mouth" (this face is the open side of a doorway and
-///         must be matched by a floor-with-door-mouth tile, so the door
-///         is guaranteed to open onto walkable ground rather than into
-///         `empty`)
+///   must be matched by a floor-with-door-mouth tile, so the door
+///   is guaranteed to open onto walkable ground rather than into
+///   `empty`)
 ///
 /// Floor is weighted heaviest so output is mostly open ground;
 /// T-junctions, cross, and door tiles are progressively rarer to keep
@@ -319,7 +313,7 @@ fn city_tileset() -> Tileset {
     // Roads: straight + 4 corners + 4 T + 1 cross. Each is built
     // by `road_y0_pattern` from the four flags marking which faces
     // the asphalt strip exits.
-    let road_specs: &[(&'static str, [u8; 4], (bool, bool, bool, bool), f32)] = &[
+    let road_specs: &[RoadSpec] = &[
         ("road_x",            [1, 1, 0, 0], (true,  true,  false, false), 1.5),
         ("road_z",            [0, 0, 1, 1], (false, false, true,  true ), 1.5),
         ("road_corner_pxpz",  [1, 0, 1, 0], (true,  false, true,  false), 0.4),
@@ -366,6 +360,10 @@ fn city_tileset() -> Tileset {
     }
 }
 
+/// A city road tile: `(name, connectors, faces the asphalt exits as
+/// (+x, -x, +z, -z), weight)`.
+type RoadSpec = (&'static str, [u8; 4], (bool, bool, bool, bool), f32);
+
 /// Build the y=0 layer of a road / corner / T / cross / intersection
 /// tile. Asphalt fills a 2×2 central pad plus a 2-wide strip
 /// extending to each enabled face; the rest of the perimeter (cells
@@ -556,7 +554,15 @@ impl VoxelGenerator for WfcGenerator {
         }
     }
 
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed as u64)
+    }
+
     fn generate(&self) -> GenResult<VoxelPatch> {
+        self.generate_with_progress(&GenProgress::none())
+    }
+
+    fn generate_with_progress(&self, progress: &GenProgress) -> GenResult<VoxelPatch> {
         if self.width == 0 || self.depth == 0 {
             return Err(GenError::InvalidParams(
                 "width and depth must be > 0".into(),
@@ -579,19 +585,22 @@ impl VoxelGenerator for WfcGenerator {
 
         let mut cells: Vec<Cell> =
             vec![Cell { allowed: all_allowed, collapsed: false }; n_cells];
-        let mut rng = StdRng::seed_from_u64(self.seed as u64);
+        let mut rng = Pcg32::from_seed_u64(self.seed as u64);
 
         let weights: Vec<f32> =
             tileset.tiles.iter().map(|t| t.weight).collect();
 
         // Main collapse loop. Pick the lowest-entropy cell, observe
         // it, propagate. Bail when nothing's left to collapse.
-        loop {
-            let Some(target) = lowest_entropy(&cells, &mut rng) else {
-                break;
-            };
+        // Each observation collapses at least one cell, so the
+        // observation count over `n_cells` is a lower bound on progress.
+        let mut observed = 0usize;
+        while let Some(target) = lowest_entropy(&cells, &mut rng) {
+            progress.check()?;
             collapse(&mut cells[target], &weights, &mut rng);
             propagate(&mut cells, w, d, target, &tileset.tiles);
+            observed += 1;
+            progress.report(observed as f32 / n_cells as f32);
         }
 
         let mut patch = VoxelPatch::new();
@@ -654,7 +663,7 @@ impl VoxelGenerator for WfcGenerator {
 
 /// Pick the uncollapsed cell with the smallest non-empty domain. Ties
 /// broken randomly so the output isn't biased toward a corner.
-fn lowest_entropy(cells: &[Cell], rng: &mut StdRng) -> Option<usize> {
+fn lowest_entropy(cells: &[Cell], rng: &mut Pcg32) -> Option<usize> {
     let mut best_count = u32::MAX;
     let mut best: Vec<usize> = Vec::new();
     for (i, cell) in cells.iter().enumerate() {
@@ -684,7 +693,7 @@ fn lowest_entropy(cells: &[Cell], rng: &mut StdRng) -> Option<usize> {
 /// Sample one tile from the cell's domain, weighted by the tileset's
 /// per-tile weights. Reduces the cell to that single tile and marks
 /// it collapsed.
-fn collapse(cell: &mut Cell, weights: &[f32], rng: &mut StdRng) {
+fn collapse(cell: &mut Cell, weights: &[f32], rng: &mut Pcg32) {
     let allowed: Vec<usize> = cell.iter_allowed().collect();
     if allowed.is_empty() {
         cell.collapsed = true;
@@ -741,20 +750,20 @@ fn propagate(
             // Connectors my cell currently exposes on `my_face`. The
             // bitset is over connector IDs (assumed to fit in u32).
             let mut my_conns: u32 = 0;
-            for i in 0..tiles.len() {
+            for (i, tile) in tiles.iter().enumerate() {
                 if allowed & (1u64 << i) != 0 {
-                    my_conns |= 1u32 << tiles[i].connectors[my_face];
+                    my_conns |= 1u32 << tile.connectors[my_face];
                 }
             }
 
             // Filter the neighbor's domain to tiles whose facing
             // connector matches at least one of mine.
             let mut new_allowed: u64 = 0;
-            for j in 0..tiles.len() {
+            for (j, tile) in tiles.iter().enumerate() {
                 if cells[nidx].allowed & (1u64 << j) == 0 {
                     continue;
                 }
-                let c = tiles[j].connectors[neighbor_face];
+                let c = tile.connectors[neighbor_face];
                 if my_conns & (1u32 << c) != 0 {
                     new_allowed |= 1u64 << j;
                 }
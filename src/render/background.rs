@@ -0,0 +1,580 @@
+//! Viewport background: gradient and HDRI drawing, and the image-based
+//! ambient light.
+//!
+//! A solid background is only the main pass's clear color. The other
+//! two draw a fullscreen triangle first thing in the pass, under the
+//! grid and the model ([`BackgroundPass`], `shaders/background.wgsl`):
+//! a gradient in screen space, or an equirectangular image looked up
+//! along each pixel's view ray.
+//!
+//! For lighting, the image is averaged down to a coarse grid
+//! ([`AmbientProbe`]) and integrated into six ambient colors, one per
+//! axis direction; `voxel.wgsl` blends them by the surface normal.
+//! That's the whole of the image-based light — soft and directional,
+//! with no reflections or sharp shadows, which is all the editor's
+//! ambient term ever carried.
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Quat, Vec3};
+use image::DynamicImage;
+use wgpu::util::DeviceExt;
+
+use super::Camera;
+use crate::core::{Background, BackgroundKind};
+
+/// Largest width or height kept on the GPU; bigger panoramas are scaled
+/// down on load.
+pub const ENVIRONMENT_MAX_DIM: u32 = 4096;
+
+/// Grid the image is averaged down to for the ambient light.
+const PROBE_WIDTH: usize = 64;
+const PROBE_HEIGHT: usize = 32;
+
+/// Unit direction at texture coordinates `(u, v)` of an
+/// equirectangular image: u turns with the azimuth, the image center
+/// looking down -Z; v runs from straight up (0) to straight down (1).
+/// The inverse of `equirect_uv` in `background.wgsl`.
+fn equirect_dir(u: f32, v: f32) -> Vec3 {
+    let azimuth = (u - 0.5) * std::f32::consts::TAU;
+    let polar = v * std::f32::consts::PI;
+    Vec3::new(
+        polar.sin() * azimuth.sin(),
+        polar.cos(),
+        -polar.sin() * azimuth.cos(),
+    )
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Bits of the half-precision float nearest below `value`, for the
+/// Rgba16Float texture. Radiance is never negative: negatives and NaN
+/// read as black, anything past half's range as its largest value, and
+/// values too small for a normal half as zero.
+fn f16_bits(value: f32) -> u16 {
+    let v = if value.is_nan() { 0.0 } else { value.clamp(0.0, 65504.0) };
+    let bits = v.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    if exponent <= 0 {
+        return 0;
+    }
+    (((exponent as u32) << 10) | ((bits >> 13) & 0x3ff)) as u16
+}
+
+/// A decoded equirectangular image in linear RGB, ready to upload.
+#[derive(Debug, Clone)]
+pub struct EnvironmentMap {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major, top row first.
+    pub pixels: Vec<[f32; 3]>,
+}
+
+impl EnvironmentMap {
+    /// Take over a decoded image. Radiance (`.hdr`) images are already
+    /// linear; 8- and 16-bit ones are sRGB and get linearized. Scaled
+    /// down to fit [`ENVIRONMENT_MAX_DIM`].
+    pub fn from_image(image: DynamicImage) -> Self {
+        let linear = matches!(
+            image,
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+        );
+        let mut rgb = image.into_rgb32f();
+        if !linear {
+            for p in rgb.pixels_mut() {
+                p.0 = p.0.map(srgb_to_linear);
+            }
+        }
+        let map = Self {
+            width: rgb.width(),
+            height: rgb.height(),
+            pixels: rgb.pixels().map(|p| p.0).collect(),
+        };
+        let (w, h) = (map.width, map.height);
+        if w <= ENVIRONMENT_MAX_DIM && h <= ENVIRONMENT_MAX_DIM {
+            return map;
+        }
+        // Averaged by hand: image's resize filters clamp to 0..1, which
+        // would flatten the bright parts of a Radiance image.
+        let scale = ENVIRONMENT_MAX_DIM as f32 / w.max(h) as f32;
+        let width = ((w as f32 * scale) as u32).max(1);
+        let height = ((h as f32 * scale) as u32).max(1);
+        Self {
+            width,
+            height,
+            pixels: map.box_average(width as usize, height as usize),
+        }
+    }
+
+    /// The image averaged down for [`AmbientProbe::irradiance`].
+    pub fn probe(&self) -> AmbientProbe {
+        AmbientProbe {
+            cells: self.box_average(PROBE_WIDTH, PROBE_HEIGHT),
+        }
+    }
+
+    /// The image shrunk to `width` × `height`, each new pixel the mean
+    /// of the pixels it covers (at least one, when growing instead).
+    fn box_average(&self, width: usize, height: usize) -> Vec<[f32; 3]> {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let mut out = Vec::with_capacity(width * height);
+        for cy in 0..height {
+            let y0 = cy * h / height;
+            let y1 = ((cy + 1) * h / height).max(y0 + 1).min(h);
+            for cx in 0..width {
+                let x0 = cx * w / width;
+                let x1 = ((cx + 1) * w / width).max(x0 + 1).min(w);
+                let mut sum = [0.0f32; 3];
+                for y in y0..y1 {
+                    for p in &self.pixels[y * w + x0..y * w + x1] {
+                        for c in 0..3 {
+                            sum[c] += p[c];
+                        }
+                    }
+                }
+                let n = ((y1 - y0) * (x1 - x0)) as f32;
+                out.push(sum.map(|s| s / n));
+            }
+        }
+        out
+    }
+
+    /// 1×1 black stand-in bound while there's no image.
+    fn placeholder() -> Self {
+        Self {
+            width: 1,
+            height: 1,
+            pixels: vec![[0.0; 3]],
+        }
+    }
+
+    /// Rgba16Float texels for upload.
+    fn texels(&self) -> Vec<u16> {
+        self.pixels
+            .iter()
+            .flat_map(|&[r, g, b]| [f16_bits(r), f16_bits(g), f16_bits(b), f16_bits(1.0)])
+            .collect()
+    }
+}
+
+/// An environment image averaged down to a coarse grid — all the
+/// renderer keeps of it once the full image is on the GPU.
+#[derive(Debug, Clone)]
+pub struct AmbientProbe {
+    /// `PROBE_WIDTH` × `PROBE_HEIGHT`, row-major, top row first.
+    cells: Vec<[f32; 3]>,
+}
+
+impl AmbientProbe {
+    /// Ambient light reaching surfaces that face +X, -X, +Y, -Y, +Z
+    /// and -Z, with the image turned `rotation` degrees around +Y: the
+    /// cosine-weighted irradiance over π, so an evenly white image
+    /// gives 1 everywhere — the plain flat ambient.
+    pub fn irradiance(&self, rotation: f32) -> [[f32; 3]; 6] {
+        const AXES: [Vec3; 6] = [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z];
+        let turn = Quat::from_rotation_y(rotation.to_radians());
+        let cell_angle = (std::f32::consts::TAU / PROBE_WIDTH as f32)
+            * (std::f32::consts::PI / PROBE_HEIGHT as f32);
+        let mut out = [[0.0f32; 3]; 6];
+        for cy in 0..PROBE_HEIGHT {
+            let v = (cy as f32 + 0.5) / PROBE_HEIGHT as f32;
+            // Rows near the poles cover less of the sphere.
+            let solid_angle = cell_angle * (v * std::f32::consts::PI).sin();
+            for cx in 0..PROBE_WIDTH {
+                let u = (cx as f32 + 0.5) / PROBE_WIDTH as f32;
+                let dir = turn * equirect_dir(u, v);
+                let radiance = self.cells[cy * PROBE_WIDTH + cx];
+                for (axis, sum) in AXES.iter().zip(out.iter_mut()) {
+                    let weight = dir.dot(*axis).max(0.0) * solid_angle;
+                    for c in 0..3 {
+                        sum[c] += radiance[c] * weight;
+                    }
+                }
+            }
+        }
+        out.map(|e| e.map(|c| c / std::f32::consts::PI))
+    }
+}
+
+/// Uniform block of `background.wgsl`; field packing is documented
+/// there.
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+struct BackgroundParams {
+    inv_view_proj: [[f32; 4]; 4],
+    top: [f32; 4],
+    bottom: [f32; 4],
+    params: [f32; 4],
+}
+
+impl BackgroundParams {
+    fn new(camera: &Camera, background: &Background) -> Self {
+        let kind = match background.kind {
+            BackgroundKind::Hdri => 2.0,
+            _ => 1.0,
+        };
+        let [tr, tg, tb] = background.top;
+        let [br, bg, bb] = background.bottom;
+        Self {
+            inv_view_proj: camera.view_projection_matrix().inverse().to_cols_array_2d(),
+            top: [tr, tg, tb, 1.0],
+            bottom: [br, bg, bb, 1.0],
+            params: [
+                kind,
+                background.hdri_intensity.max(0.0),
+                background.hdri_rotation.to_radians(),
+                0.0,
+            ],
+        }
+    }
+}
+
+/// The gradient/image background draw: pipeline, parameters and the
+/// uploaded image. Rebuilt with the voxel pipelines when the MSAA level
+/// changes, since it draws in the main pass.
+pub struct BackgroundPass {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    params_buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    texture_view: wgpu::TextureView,
+    surface_format: wgpu::TextureFormat,
+    /// Whether a real image is bound rather than the 1×1 placeholder.
+    pub has_image: bool,
+}
+
+impl BackgroundPass {
+    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Background Params"),
+            contents: bytemuck::cast_slice(&[BackgroundParams::zeroed()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        // Wraps around the horizon, stops at the poles.
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Background Sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let texture_view = Self::create_texture(device, None, &EnvironmentMap::placeholder());
+        let (layout, pipeline) = Self::build(device, surface_format, sample_count);
+        let bind_group =
+            Self::create_bind_group(device, &layout, &params_buffer, &texture_view, &sampler);
+        Self {
+            pipeline,
+            layout,
+            bind_group,
+            params_buffer,
+            sampler,
+            texture_view,
+            surface_format,
+            has_image: false,
+        }
+    }
+
+    /// Recreate the pipeline for a new sample count.
+    pub fn rebuild(&mut self, device: &wgpu::Device, sample_count: u32) {
+        (self.layout, self.pipeline) = Self::build(device, self.surface_format, sample_count);
+        self.bind_group = Self::create_bind_group(
+            device,
+            &self.layout,
+            &self.params_buffer,
+            &self.texture_view,
+            &self.sampler,
+        );
+    }
+
+    /// Upload `map` as the background image, or drop the current one.
+    pub fn set_image(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, map: Option<&EnvironmentMap>) {
+        let placeholder = EnvironmentMap::placeholder();
+        self.has_image = map.is_some();
+        self.texture_view = Self::create_texture(device, Some(queue), map.unwrap_or(&placeholder));
+        self.bind_group = Self::create_bind_group(
+            device,
+            &self.layout,
+            &self.params_buffer,
+            &self.texture_view,
+            &self.sampler,
+        );
+    }
+
+    /// A texture holding `map`, filled right away when `queue` is given
+    /// (the placeholder at startup is left zeroed, i.e. black).
+    fn create_texture(
+        device: &wgpu::Device,
+        queue: Option<&wgpu::Queue>,
+        map: &EnvironmentMap,
+    ) -> wgpu::TextureView {
+        let size = wgpu::Extent3d {
+            width: map.width,
+            height: map.height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Background Image"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        if let Some(queue) = queue {
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                bytemuck::cast_slice(&map.texels()),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(map.width * 8),
+                    rows_per_image: Some(map.height),
+                },
+                size,
+            );
+        }
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        params: &wgpu::Buffer,
+        view: &wgpu::TextureView,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Background Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+
+    fn build(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> (wgpu::BindGroupLayout, wgpu::RenderPipeline) {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Background Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/background.wgsl").into()),
+        });
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Background Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Background Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Background Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            // Part of the main pass, so it must match its depth
+            // attachment, but it neither tests nor writes depth.
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+        (layout, pipeline)
+    }
+
+    /// Set the parameters for the next background draw, seen from
+    /// `camera`. Lands at the next submit, like a camera upload.
+    pub fn write_params(&self, queue: &wgpu::Queue, camera: &Camera, background: &Background) {
+        let params = BackgroundParams::new(camera, background);
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+    }
+
+    /// Draw the background over the whole viewport (or pane).
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The CPU twin of `equirect_uv` in `background.wgsl`.
+    fn equirect_uv(d: Vec3) -> (f32, f32) {
+        let u = 0.5 + d.x.atan2(-d.z) / std::f32::consts::TAU;
+        let v = d.y.clamp(-1.0, 1.0).acos() / std::f32::consts::PI;
+        (u, v)
+    }
+
+    fn map_from(width: u32, height: u32, f: impl Fn(f32, f32) -> [f32; 3]) -> EnvironmentMap {
+        let mut pixels = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let u = (x as f32 + 0.5) / width as f32;
+                let v = (y as f32 + 0.5) / height as f32;
+                pixels.push(f(u, v));
+            }
+        }
+        EnvironmentMap { width, height, pixels }
+    }
+
+    fn brightest(irradiance: &[[f32; 3]; 6]) -> usize {
+        (0..6)
+            .max_by(|&a, &b| irradiance[a][0].total_cmp(&irradiance[b][0]))
+            .unwrap()
+    }
+
+    #[test]
+    fn equirect_mapping_round_trips() {
+        for d in [Vec3::NEG_Z, Vec3::X, Vec3::new(0.3, 0.8, 0.52).normalize()] {
+            let (u, v) = equirect_uv(d);
+            assert!((equirect_dir(u, v) - d).length() < 1e-5, "{d}");
+        }
+        assert_eq!(equirect_uv(Vec3::NEG_Z), (0.5, 0.5));
+    }
+
+    #[test]
+    fn white_image_lights_like_flat_ambient() {
+        let probe = map_from(128, 64, |_, _| [1.0; 3]).probe();
+        for face in probe.irradiance(0.0) {
+            for c in face {
+                assert!((c - 1.0).abs() < 0.02, "{c}");
+            }
+        }
+    }
+
+    #[test]
+    fn bright_sky_lights_tops_not_bottoms() {
+        let probe = map_from(64, 32, |_, v| if v < 0.5 { [2.0; 3] } else { [0.0; 3] }).probe();
+        let e = probe.irradiance(0.0);
+        assert!(e[2][0] > 1.9, "{:?}", e[2]);
+        assert!(e[3][0] < 0.05, "{:?}", e[3]);
+        assert!((e[0][0] - 1.0).abs() < 0.05, "{:?}", e[0]);
+    }
+
+    #[test]
+    fn rotation_turns_the_light_around_y() {
+        // A bright patch at the image center, which faces -Z.
+        let map = map_from(256, 128, |u, v| {
+            if (u - 0.5).abs() < 0.05 && (v - 0.5).abs() < 0.1 {
+                [50.0; 3]
+            } else {
+                [0.0; 3]
+            }
+        });
+        let probe = map.probe();
+        assert_eq!(brightest(&probe.irradiance(0.0)), 5);
+        // A quarter turn carries -Z round to -X.
+        assert_eq!(brightest(&probe.irradiance(90.0)), 1);
+    }
+
+    #[test]
+    fn ldr_images_are_linearized() {
+        let img = image::RgbImage::from_pixel(2, 1, image::Rgb([255, 128, 0]));
+        let map = EnvironmentMap::from_image(DynamicImage::ImageRgb8(img));
+        let [r, g, b] = map.pixels[0];
+        assert!((r - 1.0).abs() < 1e-4);
+        assert!((g - 0.2158).abs() < 1e-3, "{g}");
+        assert_eq!(b, 0.0);
+    }
+
+    #[test]
+    fn oversized_images_are_scaled_down() {
+        let img = image::Rgb32FImage::from_pixel(ENVIRONMENT_MAX_DIM * 2, 4, image::Rgb([3.0, 2.0, 1.0]));
+        let map = EnvironmentMap::from_image(DynamicImage::ImageRgb32F(img));
+        assert_eq!((map.width, map.height), (ENVIRONMENT_MAX_DIM, 2));
+        assert!((map.pixels[0][0] - 3.0).abs() < 1e-4, "hdr values kept as they are");
+    }
+
+    #[test]
+    fn half_floats() {
+        assert_eq!(f16_bits(1.0), 0x3c00);
+        assert_eq!(f16_bits(0.5), 0x3800);
+        assert_eq!(f16_bits(65504.0), 0x7bff);
+        assert_eq!(f16_bits(1e9), 0x7bff);
+        assert_eq!(f16_bits(-2.0), 0);
+        assert_eq!(f16_bits(f32::NAN), 0);
+        assert_eq!(f16_bits(1e-6), 0);
+    }
+}
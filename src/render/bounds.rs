@@ -0,0 +1,37 @@
+//! Wireframe of a bounded world's limits.
+//!
+//! The 12 edges of the box a bounded layer may hold voxels in (see
+//! `WorldBounds`), drawn through the shared `LinePipeline` like the
+//! selection box. Muted blue so it reads as a frame around the scene
+//! rather than something being edited — the selection is yellow, the
+//! hover white.
+
+use bytemuck::cast_slice;
+use wgpu::util::DeviceExt;
+
+use super::selection::aabb_lines;
+use crate::core::WorldBounds;
+
+const BOUNDS_COLOR: [f32; 4] = [0.4, 0.55, 1.0, 0.8];
+
+/// GPU buffer for one world-bounds box.
+pub struct BoundsMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub vertex_count: u32,
+}
+
+impl BoundsMesh {
+    pub fn new(device: &wgpu::Device, bounds: &WorldBounds) -> Self {
+        let (min, max) = bounds.voxel_box();
+        let vertices = aabb_lines(min, max, BOUNDS_COLOR);
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("World Bounds Vertex Buffer"),
+            contents: cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        Self {
+            vertex_buffer,
+            vertex_count: vertices.len() as u32,
+        }
+    }
+}
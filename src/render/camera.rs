@@ -14,18 +14,44 @@ pub struct CameraUniform {
     pub view_proj: [[f32; 4]; 4],
     /// Camera position in world space
     pub camera_pos: [f32; 4],
+    /// Work-plane dimming: x = axis index (negative = off), y = slice
+    /// coordinate, z = how far off-slice voxels fade toward the
+    /// background (0..=1). Not camera state, but every voxel draw
+    /// already binds this buffer; `RenderPipeline::update_camera` fills
+    /// it in from `RenderPipeline::slice`.
+    pub slice: [f32; 4],
+    /// Y-clip: x > 0 hides every cell above y = `y` (viewport
+    /// settings ▸ Clip Above). Filled in like `slice`, from
+    /// `RenderPipeline::clip`.
+    pub clip: [f32; 4],
+    /// Toon shading: x = lighting bands (0 = smooth lighting). Filled
+    /// in like `slice`, from `RenderPipeline::toon`.
+    pub toon: [f32; 4],
 }
 
+/// `CameraUniform::slice` with dimming off.
+pub const NO_SLICE: [f32; 4] = [-1.0, 0.0, 0.0, 0.0];
+
+/// `CameraUniform::clip` with nothing hidden.
+pub const NO_CLIP: [f32; 4] = [0.0; 4];
+
+/// `CameraUniform::toon` with smooth lighting.
+pub const NO_TOON: [f32; 4] = [0.0; 4];
+
 impl Default for CameraUniform {
     fn default() -> Self {
         Self {
             view_proj: Mat4::IDENTITY.to_cols_array_2d(),
             camera_pos: [0.0; 4],
+            slice: NO_SLICE,
+            clip: NO_CLIP,
+            toon: NO_TOON,
         }
     }
 }
 
 /// 3D camera with orbital controls
+#[derive(Debug, Clone)]
 pub struct Camera {
     /// Camera position
     pub position: Vec3,
@@ -41,6 +67,11 @@ pub struct Camera {
     pub near: f32,
     /// Far clipping plane
     pub far: f32,
+    /// Half the visible world-space height when orthographic; `None`
+    /// for the regular perspective projection. Only the quad-view
+    /// panes (see [`ViewPane`]) set this — the main editor camera is
+    /// always perspective.
+    pub ortho_half_height: Option<f32>,
 }
 
 impl Camera {
@@ -54,6 +85,7 @@ impl Camera {
             fov: 45.0_f32.to_radians(),
             near: 0.1,
             far: 1000.0,
+            ortho_half_height: None,
         }
     }
 
@@ -64,7 +96,13 @@ impl Camera {
 
     /// Build the projection matrix
     pub fn projection_matrix(&self) -> Mat4 {
-        Mat4::perspective_rh(self.fov, self.aspect, self.near, self.far)
+        match self.ortho_half_height {
+            Some(h) => {
+                let w = h * self.aspect;
+                Mat4::orthographic_rh(-w, w, -h, h, self.near, self.far)
+            }
+            None => Mat4::perspective_rh(self.fov, self.aspect, self.near, self.far),
+        }
     }
 
     /// Build combined view-projection matrix
@@ -77,6 +115,9 @@ impl Camera {
         CameraUniform {
             view_proj: self.view_projection_matrix().to_cols_array_2d(),
             camera_pos: [self.position.x, self.position.y, self.position.z, 1.0],
+            slice: NO_SLICE,
+            clip: NO_CLIP,
+            toon: NO_TOON,
         }
     }
 
@@ -111,6 +152,363 @@ impl Camera {
     }
 }
 
+/// Distance from the shared target at which the orthographic quad-view
+/// cameras sit. Half the far plane, so geometry up to 500 units either
+/// side of the target stays inside the clip volume — the same reach as
+/// the editor's picking ray.
+const ORTHO_EYE_DISTANCE: f32 = 500.0;
+
+/// Default half-height (world units) of each orthographic pane. Frames
+/// a 40-voxel-tall slab — roughly what the default perspective pose
+/// shows at its 40-unit orbit distance.
+pub const DEFAULT_ORTHO_HALF_HEIGHT: f32 = 20.0;
+
+/// Preset orbit poses for the single-view camera. Each preset only
+/// sets the controller's yaw / pitch — the orbit target and distance
+/// are kept, so snapping to a view never loses what the user was
+/// looking at. Directions follow `CameraController::update_camera_position`:
+/// Front looks down −X, Left down −Z (the quad view's Side pane), and
+/// Right / Back / Bottom are their opposites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CameraView {
+    Top,
+    Bottom,
+    Front,
+    Back,
+    Left,
+    Right,
+    /// Classic isometric: 45° around Y, tilted so the eye sits on the
+    /// (1, 1, 1) diagonal and the three axes foreshorten equally.
+    Isometric,
+}
+
+/// Pitch the Top / Bottom presets use. Just short of ±90°, inside the
+/// ±1.5 clamp orbit motion applies, so `Camera::up` (+Y) never lines
+/// up with the view direction and `look_at_rh` stays well-defined.
+const VERTICAL_VIEW_PITCH: f32 = 1.5;
+
+impl CameraView {
+    /// Every preset, in the order the Viewport Settings buttons list
+    /// them.
+    pub const ALL: [CameraView; 7] = [
+        CameraView::Top,
+        CameraView::Bottom,
+        CameraView::Front,
+        CameraView::Back,
+        CameraView::Left,
+        CameraView::Right,
+        CameraView::Isometric,
+    ];
+
+    /// Button label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Top => "Top",
+            Self::Bottom => "Bottom",
+            Self::Front => "Front",
+            Self::Back => "Back",
+            Self::Left => "Left",
+            Self::Right => "Right",
+            Self::Isometric => "Iso",
+        }
+    }
+
+    /// `(yaw, pitch)` in radians for `CameraController`.
+    pub fn orbit_angles(self) -> (f32, f32) {
+        use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+        match self {
+            Self::Top => (0.0, VERTICAL_VIEW_PITCH),
+            Self::Bottom => (0.0, -VERTICAL_VIEW_PITCH),
+            Self::Front => (0.0, 0.0),
+            Self::Back => (PI, 0.0),
+            Self::Left => (FRAC_PI_2, 0.0),
+            Self::Right => (-FRAC_PI_2, 0.0),
+            // atan(1/√2) ≈ 35.26°: the elevation of the cube diagonal.
+            Self::Isometric => (FRAC_PI_4, std::f32::consts::FRAC_1_SQRT_2.atan()),
+        }
+    }
+}
+
+/// One pane of the quad-view layout: three axis-aligned orthographic
+/// views plus the regular perspective camera, arranged like classic
+/// 3D editors (Top | Front over Side | Perspective).
+///
+/// The orthographic panes have no orbit state of their own — they
+/// look at the perspective camera's `target`, so panning / framing in
+/// any view keeps all four synchronized. Zoom is the one per-pane
+/// property (`Renderer::quad_zoom`), so each ortho pane can be
+/// scrolled independently. View directions match the single-view
+/// [`CameraView`] presets (Front looks down −X, Side is `Left`,
+/// looking down −Z).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViewPane {
+    Top,
+    Front,
+    Side,
+    Perspective,
+}
+
+impl ViewPane {
+    /// All panes in draw order (top-left, top-right, bottom-left,
+    /// bottom-right).
+    pub const ALL: [ViewPane; 4] = [
+        ViewPane::Top,
+        ViewPane::Front,
+        ViewPane::Side,
+        ViewPane::Perspective,
+    ];
+
+    /// Corner label drawn over the pane.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Top => "Top",
+            Self::Front => "Front",
+            Self::Side => "Side",
+            Self::Perspective => "Perspective",
+        }
+    }
+
+    /// Index into `Renderer::quad_zoom`, or `None` for the perspective
+    /// pane (which zooms through the regular `CameraController`).
+    pub fn ortho_index(self) -> Option<usize> {
+        match self {
+            Self::Top => Some(0),
+            Self::Front => Some(1),
+            Self::Side => Some(2),
+            Self::Perspective => None,
+        }
+    }
+
+    /// Pixel rect `[x, y, width, height]` of this pane inside a
+    /// `width × height` surface. The split point is `size / 2` rounded
+    /// down, and the right / bottom panes take the remainder, so the
+    /// four rects tile an odd-sized surface exactly — no 1-px seam of
+    /// stale clear color.
+    pub fn rect(self, width: u32, height: u32) -> [u32; 4] {
+        let half_w = width / 2;
+        let half_h = height / 2;
+        let (col, row) = match self {
+            Self::Top => (0, 0),
+            Self::Front => (1, 0),
+            Self::Side => (0, 1),
+            Self::Perspective => (1, 1),
+        };
+        let (x, w) = if col == 0 { (0, half_w) } else { (half_w, width - half_w) };
+        let (y, h) = if row == 0 { (0, half_h) } else { (half_h, height - half_h) };
+        [x, y, w.max(1), h.max(1)]
+    }
+
+    /// The pane under a surface-space cursor position. Positions
+    /// outside the surface clamp to the nearest pane.
+    pub fn at(pos: (f32, f32), width: u32, height: u32) -> ViewPane {
+        let right = pos.0 >= (width / 2) as f32;
+        let bottom = pos.1 >= (height / 2) as f32;
+        match (right, bottom) {
+            (false, false) => Self::Top,
+            (true, false) => Self::Front,
+            (false, true) => Self::Side,
+            (true, true) => Self::Perspective,
+        }
+    }
+
+    /// Build the camera for this pane. `main` is the perspective editor
+    /// camera (source of the shared target), `aspect` the pane's own
+    /// width / height, and `half_height` the pane's ortho zoom (ignored
+    /// for `Perspective`).
+    pub fn camera(self, main: &Camera, aspect: f32, half_height: f32) -> Camera {
+        let (dir, up) = match self {
+            Self::Perspective => {
+                let mut cam = main.clone();
+                cam.aspect = aspect;
+                return cam;
+            }
+            // Looking straight down −Y, +X to the right, −Z up-screen.
+            Self::Top => (Vec3::Y, Vec3::NEG_Z),
+            Self::Front => (Vec3::X, Vec3::Y),
+            Self::Side => (Vec3::Z, Vec3::Y),
+        };
+        let mut cam = Camera::new(main.target + dir * ORTHO_EYE_DISTANCE, main.target, aspect);
+        cam.up = up;
+        cam.ortho_half_height = Some(half_height);
+        cam
+    }
+}
+
+/// Scroll-zoom an orthographic pane: same 10%-per-notch feel and
+/// clamp range as `CameraController::process_scroll`, applied to the
+/// pane's half-height instead of an orbit distance.
+pub fn zoom_ortho_half_height(half_height: f32, delta: MouseScrollDelta) -> f32 {
+    let scroll = match delta {
+        MouseScrollDelta::LineDelta(_, y) => y,
+        MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.1,
+    };
+    (half_height * (1.0 - scroll * 0.1)).clamp(1.0, 500.0)
+}
+
+/// Default length of a camera move (view preset, Frame, Reset,
+/// bookmark recall), in seconds. Long enough for the eye to follow,
+/// short enough not to get in the way of repeated presses.
+pub const DEFAULT_TWEEN_SECS: f32 = 0.3;
+
+/// Easing curve of a [`CameraTween`] (Viewport Settings ▸ Camera).
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize,
+)]
+pub enum Easing {
+    Linear,
+    /// Smoothstep: starts and lands gently.
+    #[default]
+    EaseInOut,
+    /// Cubic ease-out: leaves at full speed and settles slowly, which
+    /// feels the most responsive for short moves.
+    EaseOut,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 3] = [Easing::Linear, Easing::EaseInOut, Easing::EaseOut];
+
+    /// Combo-box label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::EaseInOut => "Ease in-out",
+            Self::EaseOut => "Ease out",
+        }
+    }
+
+    /// Map linear progress `t` (clamped to 0..=1) to eased progress.
+    /// Every curve fixes 0 → 0 and 1 → 1.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+        }
+    }
+}
+
+/// Orbit-camera pose — the state a [`CameraTween`] interpolates. The
+/// eye position is derived from it ([`eye`](Self::eye)) rather than
+/// stored, so a move between two views swings around the target
+/// instead of cutting a straight line through the model. `distance`
+/// is the perspective zoom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    pub target: Vec3,
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl CameraPose {
+    /// Pose of an eye at `eye` looking at `target`, in the angle
+    /// convention of `CameraController` (pitch clamped to ±1.5 like
+    /// orbit motion).
+    pub fn looking_at(eye: Vec3, target: Vec3) -> Self {
+        let to_camera = eye - target;
+        // Floor avoids `to_camera / 0` when the eye sits exactly on
+        // the target (degenerate — distance becomes 0.01, not NaN).
+        let distance = to_camera.length().max(0.01);
+        let dir = to_camera / distance;
+        Self {
+            target,
+            distance,
+            yaw: dir.z.atan2(dir.x),
+            pitch: dir.y.asin().clamp(-1.5, 1.5),
+        }
+    }
+
+    /// The pose `camera` currently has.
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self::looking_at(camera.position, camera.target)
+    }
+
+    /// World-space eye position.
+    pub fn eye(&self) -> Vec3 {
+        let x = self.distance * self.yaw.cos() * self.pitch.cos();
+        let y = self.distance * self.pitch.sin();
+        let z = self.distance * self.yaw.sin() * self.pitch.cos();
+        self.target + Vec3::new(x, y, z)
+    }
+}
+
+/// A timed move between two [`CameraPose`]s. Target and distance
+/// interpolate linearly and the angles along the shorter arc, all on
+/// the same eased progress so the move reads as one motion.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraTween {
+    from: CameraPose,
+    to: CameraPose,
+    duration: f32,
+    easing: Easing,
+    elapsed: f32,
+}
+
+impl CameraTween {
+    /// Tween from `from` to `to` over `duration` seconds. The goal's
+    /// yaw is unwrapped to within ±π of the start, so e.g. Left → Right
+    /// turns through Front rather than the long way round.
+    pub fn new(from: CameraPose, mut to: CameraPose, duration: f32, easing: Easing) -> Self {
+        use std::f32::consts::{PI, TAU};
+        to.yaw = from.yaw + (to.yaw - from.yaw + PI).rem_euclid(TAU) - PI;
+        Self {
+            from,
+            to,
+            duration,
+            easing,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Pose at linear progress `t` (0 = start, 1 = goal).
+    pub fn sample(&self, t: f32) -> CameraPose {
+        let s = self.easing.apply(t);
+        let mix = |a: f32, b: f32| a + (b - a) * s;
+        CameraPose {
+            target: self.from.target.lerp(self.to.target, s),
+            distance: mix(self.from.distance, self.to.distance),
+            yaw: mix(self.from.yaw, self.to.yaw),
+            pitch: mix(self.from.pitch, self.to.pitch),
+        }
+    }
+
+    /// Advance by `dt` seconds; returns the pose to show and whether
+    /// the tween has reached its goal.
+    pub fn step(&mut self, dt: f32) -> (CameraPose, bool) {
+        self.elapsed += dt;
+        let t = if self.duration > 0.0 {
+            self.elapsed / self.duration
+        } else {
+            1.0
+        };
+        (self.sample(t), t >= 1.0)
+    }
+}
+
+/// User multipliers on the mouse navigation speeds (Preferences ▸
+/// Camera); 1.0 is the built-in feel.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CameraSensitivity {
+    /// Middle-drag orbit.
+    pub orbit: f32,
+    /// Right-drag pan.
+    pub pan: f32,
+    /// Scroll-wheel zoom.
+    pub zoom: f32,
+}
+
+impl Default for CameraSensitivity {
+    fn default() -> Self {
+        Self {
+            orbit: 1.0,
+            pan: 1.0,
+            zoom: 1.0,
+        }
+    }
+}
+
 /// Camera controller for mouse/keyboard input
 pub struct CameraController {
     /// Movement speed
@@ -131,6 +529,16 @@ pub struct CameraController {
     middle_mouse_pressed: bool,
     /// Last mouse position
     last_mouse_pos: Option<(f32, f32)>,
+    /// Length of camera moves started by `animate_to`, in seconds;
+    /// `0` makes them instant. Synced from Viewport Settings.
+    pub tween_duration: f32,
+    /// Easing curve of those moves.
+    pub tween_easing: Easing,
+    /// Orbit / pan / zoom multipliers. Synced from Preferences.
+    pub sensitivity_scale: CameraSensitivity,
+    /// Running camera move, advanced by `update`. Any navigation input
+    /// (orbit, pan, scroll, WASD) or external camera write cancels it.
+    tween: Option<CameraTween>,
 }
 
 impl CameraController {
@@ -145,6 +553,10 @@ impl CameraController {
             right_mouse_pressed: false,
             middle_mouse_pressed: false,
             last_mouse_pos: None,
+            tween_duration: DEFAULT_TWEEN_SECS,
+            tween_easing: Easing::default(),
+            sensitivity_scale: CameraSensitivity::default(),
+            tween: None,
         }
     }
 
@@ -184,7 +596,12 @@ impl CameraController {
     ) {
         let pressed = state == ElementState::Pressed;
         match button {
-            MouseButton::Right => self.right_mouse_pressed = pressed,
+            MouseButton::Right => {
+                self.right_mouse_pressed = pressed;
+                if pressed {
+                    self.tween = None;
+                }
+            }
             MouseButton::Middle => {
                 self.middle_mouse_pressed = pressed;
                 if pressed {
@@ -217,17 +634,32 @@ impl CameraController {
     /// sync: they translate / scale `position` and `target` uniformly,
     /// so the cached `yaw` / `pitch` remain valid (direction unchanged)
     /// and `distance` is updated explicitly inside `process_scroll`.
+    ///
+    /// A sync means the camera was placed from outside, so it also
+    /// cancels any running tween, which would otherwise drag the
+    /// camera back toward its own goal.
     pub fn sync_orbit_state_from_camera(&mut self, camera: &Camera) {
-        let to_camera = camera.position - camera.target;
-        // Floor avoids `to_camera / 0` when camera sits exactly at
-        // target (degenerate — distance becomes 0.01 instead of NaN).
-        let dist = to_camera.length().max(0.01);
-        self.distance = dist;
-        let dir = to_camera / dist;
-        self.yaw = dir.z.atan2(dir.x);
-        // Clamp into the same range orbit motion uses so a first drag
-        // after sync doesn't immediately hit the clamp boundary.
-        self.pitch = dir.y.asin().clamp(-1.5, 1.5);
+        self.tween = None;
+        // `looking_at` clamps pitch into the same range orbit motion
+        // uses, so a first drag after sync doesn't immediately hit the
+        // clamp boundary.
+        self.set_pose(CameraPose::from_camera(camera));
+    }
+
+    /// The controller's orbit state as a pose around `camera.target`.
+    pub fn pose(&self, camera: &Camera) -> CameraPose {
+        CameraPose {
+            target: camera.target,
+            distance: self.distance,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
+
+    fn set_pose(&mut self, pose: CameraPose) {
+        self.distance = pose.distance;
+        self.yaw = pose.yaw;
+        self.pitch = pose.pitch;
     }
 
     /// Handle mouse movement
@@ -242,8 +674,9 @@ impl CameraController {
                 // right swings the camera around to view the right
                 // side of the scene. Inverted from the camera-relative
                 // convention where dragging moves the camera itself.
-                self.yaw += dx * self.sensitivity;
-                self.pitch += dy * self.sensitivity;
+                let sensitivity = self.sensitivity * self.sensitivity_scale.orbit;
+                self.yaw += dx * sensitivity;
+                self.pitch += dy * sensitivity;
 
                 // Clamp pitch to avoid flipping
                 self.pitch = self.pitch.clamp(-1.5, 1.5);
@@ -258,7 +691,7 @@ impl CameraController {
                 // without any discontinuity.
                 let right = camera.right();
                 let up = camera.up;
-                let pan_speed = self.distance * 0.002;
+                let pan_speed = self.distance * 0.002 * self.sensitivity_scale.pan;
 
                 let offset = right * (-dx * pan_speed) + up * (dy * pan_speed);
                 camera.position += offset;
@@ -294,9 +727,11 @@ impl CameraController {
             MouseScrollDelta::LineDelta(_, y) => y,
             MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.1,
         };
+        self.tween = None;
 
         // Intended scale factor: scroll>0 (wheel up) → f<1 (zoom in).
-        let f = 1.0 - scroll * 0.1;
+        // Floored so a high zoom sensitivity can't flip or zero it.
+        let f = (1.0 - scroll * 0.1 * self.sensitivity_scale.zoom).max(0.05);
         let new_distance = (self.distance * f).clamp(1.0, 500.0);
         // After clamp the actual factor may differ from `f`; use the
         // ratio so position / target scale by exactly the amount the
@@ -315,11 +750,61 @@ impl CameraController {
     /// instead of leaving `camera.position` desynced until the next
     /// orbit drag.
     pub fn update_camera_position(&self, camera: &mut Camera) {
-        let x = self.distance * self.yaw.cos() * self.pitch.cos();
-        let y = self.distance * self.pitch.sin();
-        let z = self.distance * self.yaw.sin() * self.pitch.cos();
+        camera.position = self.pose(camera).eye();
+    }
+
+    /// Move the camera to `pose`: tweened over `tween_duration` with
+    /// `tween_easing`, or at once when the duration is zero. `update`
+    /// drives the tween; the orbit state is re-synced from the camera
+    /// first, so the move starts exactly where the view is.
+    pub fn animate_to(&mut self, camera: &mut Camera, pose: CameraPose) {
+        self.sync_orbit_state_from_camera(camera);
+        if self.tween_duration <= 0.0 {
+            camera.target = pose.target;
+            self.set_pose(pose);
+            self.update_camera_position(camera);
+            return;
+        }
+        let from = self.pose(camera);
+        self.tween = Some(CameraTween::new(
+            from,
+            pose,
+            self.tween_duration,
+            self.tween_easing,
+        ));
+    }
+
+    /// Turn to a preset view around the current target, keeping the
+    /// orbit distance.
+    pub fn apply_view(&mut self, view: CameraView, camera: &mut Camera) {
+        let (yaw, pitch) = view.orbit_angles();
+        let pose = CameraPose {
+            yaw,
+            pitch,
+            ..CameraPose::from_camera(camera)
+        };
+        self.animate_to(camera, pose);
+    }
+
+    /// Move the orbit `target` and `distance` to new values, keeping
+    /// the current viewing angle — the Frame commands.
+    pub fn animate_frame(&mut self, camera: &mut Camera, target: Vec3, distance: f32) {
+        let pose = CameraPose {
+            target,
+            distance,
+            ..CameraPose::from_camera(camera)
+        };
+        self.animate_to(camera, pose);
+    }
 
-        camera.position = camera.target + Vec3::new(x, y, z);
+    /// Stop a running tween where it is.
+    pub fn cancel_animation(&mut self) {
+        self.tween = None;
+    }
+
+    /// True while a tween is still moving the camera.
+    pub fn is_animating(&self) -> bool {
+        self.tween.is_some()
     }
 
     #[cfg(test)]
@@ -346,6 +831,16 @@ impl CameraController {
     /// position+target uniformly (pan, WASD itself, scroll-zoom). So
     /// the cached angle is always a valid horizontal forward source.
     pub fn update(&mut self, camera: &mut Camera, dt: f32) {
+        if let Some(tween) = &mut self.tween {
+            let (pose, done) = tween.step(dt);
+            camera.target = pose.target;
+            self.set_pose(pose);
+            self.update_camera_position(camera);
+            if done {
+                self.tween = None;
+            }
+        }
+
         let mut movement = Vec3::ZERO;
         // Horizontal forward from yaw — independent of pitch, so
         // looking straight down doesn't degenerate W/S to Y motion.
@@ -373,6 +868,7 @@ impl CameraController {
         }
 
         if movement != Vec3::ZERO {
+            self.tween = None;
             // Hold Shift to fly 3× faster (FPS convention). Sprint moved
             // off Ctrl: Ctrl is the editor's command modifier (Ctrl+S,
             // Ctrl+A, …) and the window handler now drops Ctrl-chord key
@@ -615,6 +1111,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn zoom_sensitivity_scales_the_step() {
+        let mut camera = Camera::new(Vec3::new(0.0, 20.0, 40.0), Vec3::ZERO, 1.0);
+        let mut controller = CameraController::new_synced_for_test(&camera);
+        controller.sensitivity_scale.zoom = 2.0;
+        let old_dist = controller.distance;
+        controller.process_scroll(line_scroll(1.0), &mut camera, Vec3::ZERO);
+        // f = 1 - 1 * 0.1 * 2 = 0.8.
+        assert!((controller.distance - 0.8 * old_dist).abs() < 1e-3);
+
+        // An extreme multiplier still zooms in, never through the anchor.
+        controller.sensitivity_scale.zoom = 50.0;
+        let old_dist = controller.distance;
+        controller.process_scroll(line_scroll(1.0), &mut camera, Vec3::ZERO);
+        assert!(controller.distance > 0.0 && controller.distance < old_dist);
+    }
+
     // -------- FPS-ground WASD (update) --------
 
     fn pressed(controller: &mut CameraController, key: KeyCode) {
@@ -788,4 +1301,187 @@ mod tests {
         let e = Vec3::splat(10.0);
         assert!(portrait.fit_distance(e, 1.0) > landscape.fit_distance(e, 1.0));
     }
+
+    #[test]
+    fn pane_rects_tile_odd_surfaces_exactly() {
+        let (w, h) = (801, 601);
+        let area: u32 = ViewPane::ALL
+            .iter()
+            .map(|p| {
+                let r = p.rect(w, h);
+                r[2] * r[3]
+            })
+            .sum();
+        assert_eq!(area, w * h);
+        assert_eq!(ViewPane::Top.rect(w, h), [0, 0, 400, 300]);
+        assert_eq!(ViewPane::Perspective.rect(w, h), [400, 300, 401, 301]);
+    }
+
+    #[test]
+    fn pane_at_matches_pane_rects() {
+        let (w, h) = (800, 600);
+        for pane in ViewPane::ALL {
+            let r = pane.rect(w, h);
+            let center = (
+                r[0] as f32 + r[2] as f32 * 0.5,
+                r[1] as f32 + r[3] as f32 * 0.5,
+            );
+            assert_eq!(ViewPane::at(center, w, h), pane);
+        }
+        // Off-surface positions clamp instead of panicking.
+        assert_eq!(ViewPane::at((-5.0, -5.0), w, h), ViewPane::Top);
+        assert_eq!(ViewPane::at((9000.0, 9000.0), w, h), ViewPane::Perspective);
+    }
+
+    #[test]
+    fn ortho_pane_cameras_look_along_their_axis() {
+        let main = Camera::new(Vec3::new(10.0, 20.0, 30.0), Vec3::new(1.0, 2.0, 3.0), 1.5);
+        let top = ViewPane::Top.camera(&main, 1.0, 20.0);
+        assert!((top.forward() - Vec3::NEG_Y).length() < 1e-5);
+        assert_eq!(top.target, main.target);
+        let front = ViewPane::Front.camera(&main, 1.0, 20.0);
+        assert!((front.forward() - Vec3::NEG_X).length() < 1e-5);
+        let side = ViewPane::Side.camera(&main, 1.0, 20.0);
+        assert!((side.forward() - Vec3::NEG_Z).length() < 1e-5);
+        // Perspective pane is the main camera with the pane's aspect.
+        let persp = ViewPane::Perspective.camera(&main, 0.5, 20.0);
+        assert_eq!(persp.position, main.position);
+        assert_eq!(persp.aspect, 0.5);
+        assert!(persp.ortho_half_height.is_none());
+    }
+
+    #[test]
+    fn ortho_projection_maps_half_height_to_ndc_edge() {
+        let main = Camera::new(Vec3::new(0.0, 0.0, 40.0), Vec3::ZERO, 1.0);
+        let front = ViewPane::Front.camera(&main, 2.0, 10.0);
+        let vp = front.view_projection_matrix();
+        // 10 units above the target lands on the top NDC edge; 20
+        // units along the pane's right axis (aspect 2) on the right
+        // edge — independent of the distance to the eye.
+        let top = vp.project_point3(Vec3::new(0.0, 10.0, 0.0));
+        assert!((top.y - 1.0).abs() < 1e-4);
+        let right = vp.project_point3(Vec3::new(0.0, 0.0, -20.0));
+        assert!((right.x - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn ortho_zoom_scales_and_clamps() {
+        let zoomed_in = zoom_ortho_half_height(20.0, MouseScrollDelta::LineDelta(0.0, 1.0));
+        assert!((zoomed_in - 18.0).abs() < 1e-4);
+        let floor = zoom_ortho_half_height(1.0, MouseScrollDelta::LineDelta(0.0, 5.0));
+        assert_eq!(floor, 1.0);
+    }
+
+    #[test]
+    fn view_presets_keep_target_and_distance() {
+        let target = Vec3::new(3.0, 4.0, 5.0);
+        let mut camera = Camera::new(target + Vec3::new(10.0, 10.0, 10.0), target, 1.0);
+        let mut controller = CameraController::new_synced_for_test(&camera);
+        let distance = controller.distance;
+        let forward = |view, camera: &mut Camera, c: &mut CameraController| {
+            c.apply_view(view, camera);
+            c.update(camera, DEFAULT_TWEEN_SECS);
+            assert!(!c.is_animating());
+            assert_eq!(camera.target, target);
+            assert!(((camera.position - target).length() - distance).abs() < 1e-3);
+            camera.forward()
+        };
+        let near = |a: Vec3, b: Vec3| (a - b).length() < 1e-4;
+        assert!(near(forward(CameraView::Front, &mut camera, &mut controller), Vec3::NEG_X));
+        assert!(near(forward(CameraView::Back, &mut camera, &mut controller), Vec3::X));
+        assert!(near(forward(CameraView::Right, &mut camera, &mut controller), Vec3::Z));
+        // Left is the quad view's Side pane direction.
+        let side = ViewPane::Side.camera(&camera, 1.0, 20.0).forward();
+        assert!(near(forward(CameraView::Left, &mut camera, &mut controller), side));
+        assert!(forward(CameraView::Top, &mut camera, &mut controller).y < -0.99);
+        assert!(forward(CameraView::Bottom, &mut camera, &mut controller).y > 0.99);
+        // Isometric looks back down the (1, 1, 1) diagonal.
+        let iso = forward(CameraView::Isometric, &mut camera, &mut controller);
+        assert!(near(iso, Vec3::NEG_ONE.normalize()));
+    }
+
+    #[test]
+    fn frame_animation_glides_to_fit_keeping_direction() {
+        let mut camera = Camera::new(Vec3::new(0.0, 20.0, 40.0), Vec3::ZERO, 1.0);
+        let mut controller = CameraController::new_synced_for_test(&camera);
+        let dir = (camera.position - camera.target).normalize();
+        let goal = Vec3::new(10.0, 0.0, -5.0);
+        controller.animate_frame(&mut camera, goal, 12.0);
+
+        // Halfway through, smoothstep puts the target halfway along.
+        controller.update(&mut camera, DEFAULT_TWEEN_SECS * 0.5);
+        assert!((camera.target - goal * 0.5).length() < 1e-3);
+        assert!(controller.is_animating());
+
+        controller.update(&mut camera, DEFAULT_TWEEN_SECS);
+        assert!(!controller.is_animating());
+        assert!((camera.target - goal).length() < 1e-4);
+        let offset = camera.position - camera.target;
+        assert!((offset.length() - 12.0).abs() < 1e-3);
+        assert!((offset.normalize() - dir).length() < 1e-4, "viewing angle kept");
+    }
+
+    #[test]
+    fn scroll_cancels_frame_animation() {
+        let mut camera = Camera::new(Vec3::new(0.0, 20.0, 40.0), Vec3::ZERO, 1.0);
+        let mut controller = CameraController::new_synced_for_test(&camera);
+        controller.animate_frame(&mut camera, Vec3::new(50.0, 0.0, 0.0), 10.0);
+        controller.update(&mut camera, 0.05);
+        let anchor = camera.target;
+        controller.process_scroll(MouseScrollDelta::LineDelta(0.0, 1.0), &mut camera, anchor);
+        assert!(!controller.is_animating());
+        let target = camera.target;
+        controller.update(&mut camera, 1.0);
+        assert_eq!(camera.target, target, "no further glide after cancel");
+    }
+
+    #[test]
+    fn easing_curves_fix_endpoints_and_increase() {
+        for easing in Easing::ALL {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6);
+            assert_eq!(easing.apply(2.0), easing.apply(1.0), "clamped past the end");
+            let samples: Vec<f32> = (0..=10).map(|i| easing.apply(i as f32 / 10.0)).collect();
+            assert!(samples.windows(2).all(|w| w[1] >= w[0]), "{:?} not monotonic", easing);
+        }
+        // Ease-out covers more ground early than linear.
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+    }
+
+    #[test]
+    fn tween_turns_the_short_way_and_lands_exactly() {
+        use std::f32::consts::PI;
+        let from = CameraPose {
+            target: Vec3::ZERO,
+            distance: 10.0,
+            yaw: PI - 0.1,
+            pitch: 0.0,
+        };
+        let to = CameraPose {
+            yaw: -PI + 0.1,
+            distance: 30.0,
+            ..from
+        };
+        let mut tween = CameraTween::new(from, to, 1.0, Easing::Linear);
+        // Halfway is at ±π (behind), not at yaw 0 (the long way round).
+        let mid = tween.sample(0.5);
+        assert!((mid.yaw.abs() - PI).abs() < 1e-4, "mid yaw {}", mid.yaw);
+        assert!((mid.distance - 20.0).abs() < 1e-4);
+
+        let (_, done) = tween.step(0.6);
+        assert!(!done);
+        let (end, done) = tween.step(0.6);
+        assert!(done);
+        assert!((end.eye() - to.eye()).length() < 1e-3);
+    }
+
+    #[test]
+    fn zero_duration_moves_instantly() {
+        let mut camera = Camera::new(Vec3::new(0.0, 20.0, 40.0), Vec3::ZERO, 1.0);
+        let mut controller = CameraController::new_synced_for_test(&camera);
+        controller.tween_duration = 0.0;
+        controller.apply_view(CameraView::Front, &mut camera);
+        assert!(!controller.is_animating());
+        assert!((camera.forward() - Vec3::NEG_X).length() < 1e-4);
+    }
 }
@@ -0,0 +1,173 @@
+//! Keyframed camera paths for fly-throughs (Render ▸ Camera Path).
+//!
+//! A path is a list of [`CameraKey`]s — an eye position, a look-at
+//! target and the time to travel on to the next key. Both the eye and
+//! the target follow a uniform Catmull-Rom spline through the keys, so
+//! the camera passes exactly through every key without stopping at it.
+//! An open path starts at the first key and ends on the last; a closed
+//! one loops back to the first, for seamless product-shot loops.
+
+use glam::Vec3;
+
+/// Shortest segment time: keeps sampling well-defined when a key's
+/// time is zero.
+const MIN_SEGMENT_MS: u32 = 1;
+
+/// One keyframe of a [`CameraPath`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraKey {
+    pub position: Vec3,
+    pub target: Vec3,
+    /// Time from this key to the next, in milliseconds. Unused on the
+    /// last key of an open path.
+    pub duration_ms: u32,
+}
+
+/// A camera fly-through: keys in playback order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CameraPath {
+    pub keys: Vec<CameraKey>,
+    /// Travel from the last key back to the first.
+    pub closed: bool,
+}
+
+impl CameraPath {
+    /// Number of segments between keys.
+    fn segments(&self) -> usize {
+        match self.keys.len() {
+            0 | 1 => 0,
+            n if self.closed => n,
+            n => n - 1,
+        }
+    }
+
+    /// Length of segment `i` (starting at key `i`), in seconds.
+    fn segment_secs(&self, i: usize) -> f32 {
+        self.keys[i].duration_ms.max(MIN_SEGMENT_MS) as f32 / 1000.0
+    }
+
+    /// Total playback time in seconds; 0 with fewer than two keys.
+    pub fn duration(&self) -> f32 {
+        (0..self.segments()).map(|i| self.segment_secs(i)).sum()
+    }
+
+    /// The camera `(position, target)` `t` seconds in, clamped to the
+    /// path (a closed path wraps instead). `None` for an empty path; a
+    /// single key is a still shot.
+    pub fn sample(&self, t: f32) -> Option<(Vec3, Vec3)> {
+        let first = self.keys.first()?;
+        let segments = self.segments();
+        if segments == 0 {
+            return Some((first.position, first.target));
+        }
+        let duration = self.duration();
+        let mut t = if self.closed {
+            t.rem_euclid(duration)
+        } else {
+            t.clamp(0.0, duration)
+        };
+        let mut segment = 0;
+        while segment + 1 < segments && t >= self.segment_secs(segment) {
+            t -= self.segment_secs(segment);
+            segment += 1;
+        }
+        let u = (t / self.segment_secs(segment)).clamp(0.0, 1.0);
+
+        // Control points around the segment: wrapped on a closed path,
+        // the end keys repeated on an open one.
+        let n = self.keys.len() as isize;
+        let key = |offset: isize| {
+            let i = segment as isize + offset;
+            let i = if self.closed {
+                i.rem_euclid(n)
+            } else {
+                i.clamp(0, n - 1)
+            };
+            self.keys[i as usize]
+        };
+        let (k0, k1, k2, k3) = (key(-1), key(0), key(1), key(2));
+        Some((
+            catmull_rom(k0.position, k1.position, k2.position, k3.position, u),
+            catmull_rom(k0.target, k1.target, k2.target, k3.target, u),
+        ))
+    }
+
+    /// Frames a fly-through at `fps` renders: one every `1 / fps`
+    /// seconds, including the final key of an open path (a closed
+    /// path's end is its start, so it's left out and the loop is
+    /// seamless). At least one.
+    pub fn frame_count(&self, fps: u32) -> u32 {
+        let frames = (self.duration() * fps.max(1) as f32).round() as u32;
+        if self.closed {
+            frames.max(1)
+        } else {
+            frames + 1
+        }
+    }
+}
+
+/// Uniform Catmull-Rom between `p1` (`u = 0`) and `p2` (`u = 1`).
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, u: f32) -> Vec3 {
+    let (u2, u3) = (u * u, u * u * u);
+    0.5 * (2.0 * p1
+        + (p2 - p0) * u
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * u2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * u3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(x: f32, duration_ms: u32) -> CameraKey {
+        CameraKey {
+            position: Vec3::new(x, 5.0, 10.0),
+            target: Vec3::ZERO,
+            duration_ms,
+        }
+    }
+
+    #[test]
+    fn open_path_passes_through_every_key() {
+        let path = CameraPath {
+            keys: vec![key(0.0, 1000), key(4.0, 500), key(-2.0, 0)],
+            closed: false,
+        };
+        assert_eq!(path.duration(), 1.5);
+        for (t, x) in [
+            (-1.0, 0.0),
+            (0.0, 0.0),
+            (1.0, 4.0),
+            (1.5, -2.0),
+            (9.0, -2.0),
+        ] {
+            let (position, target) = path.sample(t).unwrap();
+            assert!((position.x - x).abs() < 1e-4, "t = {t}: {position}");
+            assert_eq!(target, Vec3::ZERO);
+        }
+        // Between keys the spline is smooth, not a straight cut.
+        let (mid, _) = path.sample(0.5).unwrap();
+        assert!(mid.x > 2.0, "{mid}");
+        assert_eq!(path.frame_count(10), 16);
+    }
+
+    #[test]
+    fn closed_path_loops_back_to_the_first_key() {
+        let path = CameraPath {
+            keys: vec![key(0.0, 1000), key(4.0, 1000)],
+            closed: true,
+        };
+        assert_eq!(path.duration(), 2.0);
+        let (start, _) = path.sample(0.0).unwrap();
+        let (wrapped, _) = path.sample(2.0).unwrap();
+        assert!(start.abs_diff_eq(wrapped, 1e-4));
+        assert_eq!(path.frame_count(12), 24);
+
+        let still = CameraPath {
+            keys: vec![key(3.0, 1000)],
+            closed: true,
+        };
+        assert_eq!(still.sample(7.0).unwrap().0.x, 3.0);
+        assert!(CameraPath::default().sample(0.0).is_none());
+    }
+}
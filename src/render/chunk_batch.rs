@@ -0,0 +1,727 @@
+//! Consolidated chunk geometry: every chunk mesh lives in a few large
+//! shared buffers instead of a vertex/index buffer pair of its own.
+//!
+//! Chunks are packed into pages — one vertex buffer plus separate index
+//! buffers for the opaque range, the translucent range and the
+//! wireframe edges — sub-allocated with a first-fit [`RangeAllocator`].
+//! Indices are rebased onto the chunk's slice of the page's vertex
+//! buffer on upload, so chunks whose opaque slots sit back to back form
+//! one run of valid triangles and draw with a single `draw_indexed`
+//! ([`coalesce`]). The opaque pass then costs one buffer bind and a
+//! handful of draws per page, instead of a bind and a draw per chunk —
+//! hundreds of each on a large scene.
+//!
+//! Slots get headroom like `GpuMesh` buffers, so a re-meshed chunk is
+//! written in place with `queue.write_buffer` while it fits. The unused
+//! tail of an opaque slot is padded with degenerate triangles, which
+//! lets a run span it. Pool buffers start small and double (copying
+//! their contents) up to [`PAGE_BYTES`]; a full page opens the next.
+//!
+//! Translucent ranges still draw one chunk at a time because they must
+//! be sorted back to front, and so do edges, since zero-length padding
+//! lines aren't guaranteed to rasterize to nothing. Both skip the
+//! rebind while consecutive chunks share a page.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::core::ChunkPos;
+use crate::mesh::{ChunkMesh, Vertex};
+
+use super::gpu_mesh::{CAPACITY_HEADROOM, SHRINK_FACTOR};
+
+/// Largest buffer a page's pool grows to (clamped to the device's
+/// `max_buffer_size`). Worst-case chunks — a 32³ checkerboard — need
+/// well under this, so any chunk fits an empty page.
+pub const PAGE_BYTES: u64 = 64 << 20;
+
+/// Size of a pool buffer when it is first created; it doubles from
+/// there as chunks arrive.
+const INITIAL_POOL_BYTES: u64 = 256 << 10;
+
+/// Index slots are sized in multiples of this, so every slot starts on
+/// a triangle (and line) boundary and a run of slots stays aligned.
+const INDEX_GRANULE: u32 = 6;
+
+/// First-fit allocator over `0..capacity` elements. The free list is
+/// sorted and never holds two touching ranges.
+#[derive(Debug, Clone, Default)]
+pub struct RangeAllocator {
+    capacity: u32,
+    free: Vec<Range<u32>>,
+}
+
+impl RangeAllocator {
+    pub fn new(capacity: u32) -> Self {
+        let mut allocator = Self::default();
+        allocator.grow(capacity);
+        allocator
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Take `len` elements from the first free range big enough.
+    pub fn alloc(&mut self, len: u32) -> Option<Range<u32>> {
+        let i = self.free.iter().position(|r| r.end - r.start >= len)?;
+        let start = self.free[i].start;
+        self.free[i].start += len;
+        if self.free[i].is_empty() {
+            self.free.remove(i);
+        }
+        Some(start..start + len)
+    }
+
+    /// Give back a range from [`alloc`](Self::alloc), merging it with
+    /// the free ranges it touches.
+    pub fn free(&mut self, range: Range<u32>) {
+        if range.is_empty() {
+            return;
+        }
+        let i = self.free.partition_point(|r| r.start < range.start);
+        let joins_prev = i > 0 && self.free[i - 1].end == range.start;
+        let joins_next = i < self.free.len() && self.free[i].start == range.end;
+        match (joins_prev, joins_next) {
+            (true, true) => {
+                self.free[i - 1].end = self.free[i].end;
+                self.free.remove(i);
+            }
+            (true, false) => self.free[i - 1].end = range.end,
+            (false, true) => self.free[i].start = range.start,
+            (false, false) => self.free.insert(i, range),
+        }
+    }
+
+    /// Extend the space to `capacity` elements; the new tail is free.
+    pub fn grow(&mut self, capacity: u32) {
+        let old = self.capacity;
+        self.capacity = capacity.max(old);
+        self.free(old..self.capacity);
+    }
+
+    /// Free elements at the very end of the space — what an allocation
+    /// can reuse of the current space when the pool has to grow.
+    fn free_tail(&self) -> u32 {
+        self.free
+            .last()
+            .filter(|r| r.end == self.capacity)
+            .map_or(0, |r| r.end - r.start)
+    }
+}
+
+/// Slot size for `len` elements: headroom as for `GpuMesh` buffers,
+/// rounded up to a multiple of `granule`. Zero for zero.
+pub fn slot_capacity(len: u32, granule: u32) -> u32 {
+    let padded = len + (len as f64 * CAPACITY_HEADROOM).ceil() as u32;
+    padded.div_ceil(granule) * granule
+}
+
+/// Merge back-to-back slots into one draw range each. Takes each
+/// slot's allocated range and live length; a run ends at the live end
+/// of its last slot, so trailing padding isn't drawn.
+pub fn coalesce(slots: impl Iterator<Item = (Range<u32>, u32)>) -> Vec<Range<u32>> {
+    let mut slots: Vec<_> = slots.filter(|(range, _)| !range.is_empty()).collect();
+    slots.sort_by_key(|(range, _)| range.start);
+    let mut runs: Vec<Range<u32>> = Vec::new();
+    let mut run_capacity_end = 0;
+    for (range, len) in slots {
+        let live_end = range.start + len;
+        match runs.last_mut() {
+            Some(run) if run_capacity_end == range.start => run.end = live_end,
+            _ => runs.push(range.start..live_end),
+        }
+        run_capacity_end = range.end;
+    }
+    runs
+}
+
+/// One growable buffer of a page, sub-allocated per chunk.
+struct Pool {
+    label: &'static str,
+    usage: wgpu::BufferUsages,
+    elem_size: u64,
+    max_elems: u32,
+    /// `None` until the first allocation.
+    buffer: Option<wgpu::Buffer>,
+    space: RangeAllocator,
+}
+
+impl Pool {
+    fn new(
+        label: &'static str,
+        usage: wgpu::BufferUsages,
+        elem_size: usize,
+        page_bytes: u64,
+    ) -> Self {
+        let elem_size = elem_size as u64;
+        Self {
+            label,
+            usage,
+            elem_size,
+            max_elems: (page_bytes / elem_size).min(u32::MAX as u64) as u32,
+            buffer: None,
+            space: RangeAllocator::default(),
+        }
+    }
+
+    /// Allocate `len` elements, growing the buffer if that makes room
+    /// within the page limit. An empty pool may exceed the limit, so
+    /// an oversized chunk still gets a page of its own.
+    fn alloc(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        len: u32,
+    ) -> Option<Range<u32>> {
+        if len == 0 {
+            return Some(0..0);
+        }
+        if let Some(range) = self.space.alloc(len) {
+            return Some(range);
+        }
+        let capacity = self.space.capacity();
+        let limit = if capacity == 0 {
+            self.max_elems.max(len)
+        } else {
+            self.max_elems
+        };
+        let needed = capacity + len - self.space.free_tail();
+        if needed > limit {
+            return None;
+        }
+        let initial = (INITIAL_POOL_BYTES / self.elem_size) as u32;
+        let grown = needed
+            .max(capacity.saturating_mul(2))
+            .max(initial)
+            .min(limit);
+        self.grow(device, queue, grown);
+        self.space.alloc(len)
+    }
+
+    /// Reallocate the buffer at `capacity` elements, copying the old
+    /// contents over so every slot keeps its offset.
+    fn grow(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, capacity: u32) {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(self.label),
+            size: capacity as u64 * self.elem_size,
+            usage: self.usage | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        if let Some(old) = &self.buffer {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Chunk Pool Grow"),
+            });
+            encoder.copy_buffer_to_buffer(old, 0, &buffer, 0, old.size());
+            queue.submit(std::iter::once(encoder.finish()));
+        }
+        self.buffer = Some(buffer);
+        self.space.grow(capacity);
+    }
+
+    fn write(&self, queue: &wgpu::Queue, start: u32, bytes: &[u8]) {
+        if let (Some(buffer), false) = (&self.buffer, bytes.is_empty()) {
+            queue.write_buffer(buffer, start as u64 * self.elem_size, bytes);
+        }
+    }
+
+    /// Drop the buffer and every allocation.
+    fn reset(&mut self) {
+        self.buffer = None;
+        self.space = RangeAllocator::default();
+    }
+}
+
+/// A set of pools chunks are packed into together: a chunk's indices
+/// point into the vertex pool of its own page.
+struct Page {
+    vertices: Pool,
+    opaque: Pool,
+    transparent: Pool,
+    edges: Pool,
+    /// Opaque draw ranges from [`coalesce`], rebuilt on the next draw
+    /// after any of the page's slots changed (`None`).
+    opaque_runs: RefCell<Option<Vec<Range<u32>>>>,
+}
+
+impl Page {
+    fn new(page_bytes: u64) -> Self {
+        let index = std::mem::size_of::<u32>();
+        Self {
+            vertices: Pool::new(
+                "Chunk Vertex Pool",
+                wgpu::BufferUsages::VERTEX,
+                std::mem::size_of::<Vertex>(),
+                page_bytes,
+            ),
+            opaque: Pool::new(
+                "Chunk Opaque Index Pool",
+                wgpu::BufferUsages::INDEX,
+                index,
+                page_bytes,
+            ),
+            transparent: Pool::new(
+                "Chunk Translucent Index Pool",
+                wgpu::BufferUsages::INDEX,
+                index,
+                page_bytes,
+            ),
+            edges: Pool::new(
+                "Chunk Edge Index Pool",
+                wgpu::BufferUsages::INDEX,
+                index,
+                page_bytes,
+            ),
+            opaque_runs: RefCell::new(None),
+        }
+    }
+
+    /// Allocate every span `need` asks for, or none of them.
+    fn try_place(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        page: usize,
+        need: &Need,
+    ) -> Option<Slot> {
+        let vertices = self
+            .vertices
+            .alloc(device, queue, slot_capacity(need.vertices, 1));
+        let opaque = self
+            .opaque
+            .alloc(device, queue, slot_capacity(need.opaque, INDEX_GRANULE));
+        let transparent = self.transparent.alloc(
+            device,
+            queue,
+            slot_capacity(need.transparent, INDEX_GRANULE),
+        );
+        let edges = match need.edges {
+            Some(len) => self
+                .edges
+                .alloc(device, queue, slot_capacity(len, INDEX_GRANULE))
+                .map(Some),
+            None => Some(None),
+        };
+        if let (Some(vertices), Some(opaque), Some(transparent), Some(edges)) =
+            (&vertices, &opaque, &transparent, &edges)
+        {
+            self.opaque_runs.replace(None);
+            return Some(Slot {
+                page,
+                vertices: Span::new(vertices.clone(), need.vertices),
+                opaque: Span::new(opaque.clone(), need.opaque),
+                transparent: Span::new(transparent.clone(), need.transparent),
+                edges: edges
+                    .clone()
+                    .zip(need.edges)
+                    .map(|(r, len)| Span::new(r, len)),
+            });
+        }
+        self.vertices.space.free(vertices.unwrap_or_default());
+        self.opaque.space.free(opaque.unwrap_or_default());
+        self.transparent.space.free(transparent.unwrap_or_default());
+        self.edges.space.free(edges.flatten().unwrap_or_default());
+        None
+    }
+
+    fn release(&mut self, slot: &Slot) {
+        self.vertices.space.free(slot.vertices.range.clone());
+        self.opaque.space.free(slot.opaque.range.clone());
+        self.transparent.space.free(slot.transparent.range.clone());
+        if let Some(edges) = &slot.edges {
+            self.edges.space.free(edges.range.clone());
+        }
+        self.opaque_runs.replace(None);
+    }
+}
+
+/// Element counts one chunk mesh needs.
+struct Need {
+    vertices: u32,
+    opaque: u32,
+    transparent: u32,
+    /// `None` when edge buffers are off.
+    edges: Option<u32>,
+}
+
+/// An allocated range and how much of it is live.
+#[derive(Debug, Clone)]
+struct Span {
+    range: Range<u32>,
+    len: u32,
+}
+
+impl Span {
+    fn new(range: Range<u32>, len: u32) -> Self {
+        Self { range, len }
+    }
+
+    fn live(&self) -> Range<u32> {
+        self.range.start..self.range.start + self.len
+    }
+
+    /// Whether `len` elements can be written in place: the slot is big
+    /// enough and not so oversized it should be given back.
+    fn fits(&self, len: u32, granule: u32) -> bool {
+        let capacity = self.range.end - self.range.start;
+        len <= capacity && capacity as u64 <= slot_capacity(len, granule) as u64 * SHRINK_FACTOR
+    }
+}
+
+/// Where one chunk's mesh lives.
+#[derive(Debug, Clone)]
+struct Slot {
+    page: usize,
+    vertices: Span,
+    opaque: Span,
+    transparent: Span,
+    edges: Option<Span>,
+}
+
+impl Slot {
+    fn fits(&self, need: &Need) -> bool {
+        let edges_fit = match (&self.edges, need.edges) {
+            (Some(span), Some(len)) => span.fits(len, INDEX_GRANULE),
+            (None, None) => true,
+            _ => false,
+        };
+        edges_fit
+            && self.vertices.fits(need.vertices, 1)
+            && self.opaque.fits(need.opaque, INDEX_GRANULE)
+            && self.transparent.fits(need.transparent, INDEX_GRANULE)
+    }
+}
+
+/// Every uploaded chunk mesh, packed into shared pages (see the module
+/// docs).
+pub struct ChunkBatch {
+    page_bytes: u64,
+    pages: Vec<Page>,
+    slots: HashMap<ChunkPos, Slot>,
+    /// `draw_indexed` calls issued since [`take_draw_calls`](Self::take_draw_calls).
+    draw_calls: Cell<usize>,
+}
+
+impl ChunkBatch {
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            page_bytes: PAGE_BYTES.min(device.limits().max_buffer_size),
+            pages: Vec::new(),
+            slots: HashMap::new(),
+            draw_calls: Cell::new(0),
+        }
+    }
+
+    /// Number of chunks with geometry on the GPU.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Number of pages (sets of shared buffers) in use.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Triangles across every chunk, opaque and translucent.
+    pub fn triangle_count(&self) -> usize {
+        self.slots
+            .values()
+            .map(|s| (s.opaque.len + s.transparent.len) as usize / 3)
+            .sum()
+    }
+
+    /// Chunks that have translucent triangles.
+    pub fn translucent_chunks(&self) -> impl Iterator<Item = ChunkPos> + '_ {
+        self.slots
+            .iter()
+            .filter(|(_, slot)| slot.transparent.len > 0)
+            .map(|(&pos, _)| pos)
+    }
+
+    /// Number of `draw_indexed` calls issued since the last call —
+    /// sampled once per frame for the stats.
+    pub fn take_draw_calls(&self) -> usize {
+        self.draw_calls.take()
+    }
+
+    /// Store `mesh` (non-empty) for its chunk: in place when it fits
+    /// the chunk's current slot, in a new slot otherwise. With
+    /// `with_edges`, the wireframe edge list is stored too.
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mesh: &ChunkMesh,
+        with_edges: bool,
+    ) {
+        let opaque_end = mesh.indices.len() - mesh.transparent_index_count;
+        let edges = with_edges.then(|| mesh.edge_indices());
+        let need = Need {
+            vertices: mesh.vertices.len() as u32,
+            opaque: opaque_end as u32,
+            transparent: mesh.transparent_index_count as u32,
+            edges: edges.as_ref().map(|e| e.len() as u32),
+        };
+        let slot = match self.slots.remove(&mesh.chunk_pos) {
+            Some(slot) if slot.fits(&need) => {
+                let page = &self.pages[slot.page];
+                page.opaque_runs.replace(None);
+                Slot {
+                    vertices: Span::new(slot.vertices.range, need.vertices),
+                    opaque: Span::new(slot.opaque.range, need.opaque),
+                    transparent: Span::new(slot.transparent.range, need.transparent),
+                    edges: slot
+                        .edges
+                        .zip(need.edges)
+                        .map(|(s, len)| Span::new(s.range, len)),
+                    page: slot.page,
+                }
+            }
+            Some(slot) => {
+                self.pages[slot.page].release(&slot);
+                self.place(device, queue, &need)
+            }
+            None => self.place(device, queue, &need),
+        };
+
+        let page = &self.pages[slot.page];
+        let base = slot.vertices.range.start;
+        // Opaque slots are padded to capacity with degenerate triangles
+        // (three copies of one vertex) so coalesced runs can span them.
+        let rebase = |indices: &[u32], padded_len: u32| {
+            let mut out: Vec<u32> = indices.iter().map(|i| i + base).collect();
+            out.resize(padded_len as usize, base);
+            out
+        };
+        page.vertices.write(queue, base, mesh.vertex_bytes());
+        let opaque = rebase(&mesh.indices[..opaque_end], slot.opaque.range.len() as u32);
+        page.opaque.write(
+            queue,
+            slot.opaque.range.start,
+            bytemuck::cast_slice(&opaque),
+        );
+        let transparent = rebase(&mesh.indices[opaque_end..], slot.transparent.len);
+        page.transparent.write(
+            queue,
+            slot.transparent.range.start,
+            bytemuck::cast_slice(&transparent),
+        );
+        if let (Some(span), Some(edges)) = (&slot.edges, &edges) {
+            let edges = rebase(edges, span.len);
+            page.edges
+                .write(queue, span.range.start, bytemuck::cast_slice(&edges));
+        }
+        self.slots.insert(mesh.chunk_pos, slot);
+    }
+
+    /// First page with room for `need`, or a new one.
+    fn place(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, need: &Need) -> Slot {
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            if let Some(slot) = page.try_place(device, queue, i, need) {
+                return slot;
+            }
+        }
+        let mut page = Page::new(self.page_bytes);
+        let slot = page
+            .try_place(device, queue, self.pages.len(), need)
+            .expect("an empty page takes any single chunk");
+        self.pages.push(page);
+        slot
+    }
+
+    /// Drop a chunk's geometry; its slot space is reused by later uploads.
+    pub fn remove(&mut self, pos: ChunkPos) {
+        if let Some(slot) = self.slots.remove(&pos) {
+            self.pages[slot.page].release(&slot);
+        }
+    }
+
+    /// Drop every chunk and free all pages.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.pages.clear();
+    }
+
+    /// Free the edge lists of every chunk (wireframe mode turned off).
+    pub fn drop_edges(&mut self) {
+        for slot in self.slots.values_mut() {
+            slot.edges = None;
+        }
+        for page in &mut self.pages {
+            page.edges.reset();
+        }
+    }
+
+    /// Draw every chunk's opaque range — one run per group of
+    /// back-to-back slots. The caller sets the pipeline.
+    pub fn draw_opaque<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        for (i, page) in self.pages.iter().enumerate() {
+            let (Some(vertices), Some(indices)) = (&page.vertices.buffer, &page.opaque.buffer)
+            else {
+                continue;
+            };
+            let mut cache = page.opaque_runs.borrow_mut();
+            let runs = cache.get_or_insert_with(|| {
+                coalesce(
+                    self.slots
+                        .values()
+                        .filter(|slot| slot.page == i)
+                        .map(|slot| (slot.opaque.range.clone(), slot.opaque.len)),
+                )
+            });
+            if runs.is_empty() {
+                continue;
+            }
+            render_pass.set_vertex_buffer(0, vertices.slice(..));
+            render_pass.set_index_buffer(indices.slice(..), wgpu::IndexFormat::Uint32);
+            for run in runs.iter() {
+                render_pass.draw_indexed(run.clone(), 0, 0..1);
+            }
+            self.draw_calls.set(self.draw_calls.get() + runs.len());
+        }
+    }
+
+    /// Draw the translucent ranges of the chunks in `order` (back to
+    /// front). The caller sets the pipeline.
+    pub fn draw_transparent<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        order: &[ChunkPos],
+    ) {
+        let ranges = order
+            .iter()
+            .filter_map(|pos| self.slots.get(pos))
+            .map(|slot| (slot.page, slot.transparent.live()));
+        self.draw_each(render_pass, |page| &page.transparent, ranges);
+    }
+
+    /// Draw the edge lists of the chunks that have one, as lines. The
+    /// caller sets a line-list pipeline.
+    pub fn draw_edges<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        self.draw_each(
+            render_pass,
+            |page| &page.edges,
+            self.sorted_ranges(|slot| slot.edges.as_ref()),
+        );
+    }
+
+    /// Draw all triangles of the chunks with no edge list yet — the
+    /// stand-in for lines-only wireframe while their re-mesh is in
+    /// flight. The caller sets a triangle pipeline.
+    pub fn draw_without_edges<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        let unedged = |span: fn(&Slot) -> &Span| {
+            self.sorted_ranges(move |slot| slot.edges.is_none().then(|| span(slot)))
+        };
+        self.draw_each(
+            render_pass,
+            |page| &page.opaque,
+            unedged(|slot| &slot.opaque),
+        );
+        self.draw_each(
+            render_pass,
+            |page| &page.transparent,
+            unedged(|slot| &slot.transparent),
+        );
+    }
+
+    /// Live ranges picked by `span`, grouped by page so
+    /// [`draw_each`](Self::draw_each) binds each page once.
+    fn sorted_ranges<'s>(
+        &'s self,
+        span: impl Fn(&'s Slot) -> Option<&'s Span>,
+    ) -> Vec<(usize, Range<u32>)> {
+        let mut ranges: Vec<_> = self
+            .slots
+            .values()
+            .filter_map(|slot| span(slot).map(|s| (slot.page, s.live())))
+            .collect();
+        ranges.sort_by_key(|(page, range)| (*page, range.start));
+        ranges
+    }
+
+    /// One `draw_indexed` per non-empty range, binding the page's
+    /// vertex pool and the index pool picked by `pool` whenever the
+    /// page changes.
+    fn draw_each<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        pool: fn(&Page) -> &Pool,
+        ranges: impl IntoIterator<Item = (usize, Range<u32>)>,
+    ) {
+        let mut bound = None;
+        for (page_index, range) in ranges {
+            if range.is_empty() {
+                continue;
+            }
+            let page = &self.pages[page_index];
+            let (Some(vertices), Some(indices)) = (&page.vertices.buffer, &pool(page).buffer)
+            else {
+                continue;
+            };
+            if bound != Some(page_index) {
+                render_pass.set_vertex_buffer(0, vertices.slice(..));
+                render_pass.set_index_buffer(indices.slice(..), wgpu::IndexFormat::Uint32);
+                bound = Some(page_index);
+            }
+            render_pass.draw_indexed(range, 0, 0..1);
+            self.draw_calls.set(self.draw_calls.get() + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocator_reuses_and_merges_freed_ranges() {
+        let mut space = RangeAllocator::new(100);
+        let a = space.alloc(30).unwrap();
+        let b = space.alloc(30).unwrap();
+        let c = space.alloc(30).unwrap();
+        assert_eq!((a.start, b.start, c.start), (0, 30, 60));
+        assert!(space.alloc(20).is_none(), "only 10 left");
+
+        // Freeing a and b merges them into one 60-element hole.
+        space.free(a);
+        space.free(b);
+        assert_eq!(space.alloc(55), Some(0..55));
+
+        // Growing extends the free tail, merging with what's left.
+        space.free(c);
+        assert_eq!(space.free_tail(), 45);
+        space.grow(200);
+        assert_eq!(space.free_tail(), 145);
+        assert_eq!(space.alloc(145), Some(55..200));
+    }
+
+    #[test]
+    fn back_to_back_slots_draw_as_one_run() {
+        let runs = coalesce(
+            [
+                (12..24, 9), // second, touches the first
+                (0..12, 6),  // first
+                (30..36, 6), // after a hole
+                (36..36, 0), // empty slot, ignored
+            ]
+            .into_iter(),
+        );
+        // The run spans the first slot's padding and stops at the live
+        // end of its last slot.
+        assert_eq!(runs, vec![0..21, 30..36]);
+    }
+
+    #[test]
+    fn slot_capacity_keeps_triangles_aligned() {
+        assert_eq!(slot_capacity(0, INDEX_GRANULE), 0);
+        let capacity = slot_capacity(100, INDEX_GRANULE);
+        assert!(capacity >= 150);
+        assert_eq!(capacity % INDEX_GRANULE, 0);
+        assert_eq!(slot_capacity(3, 1), 5);
+    }
+}
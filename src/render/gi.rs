@@ -0,0 +1,586 @@
+//! Approximate global illumination for the realtime view.
+//!
+//! The scene is downsampled into a coarse grid ([`LightVolume`]), light
+//! is injected from the sky (straight down each column, attenuated by
+//! whatever it passes through) and from emissive voxels, then spread by
+//! a few rounds of neighbor diffusion in which solid cells re-emit what
+//! they receive tinted by their average albedo. That last step is the
+//! "bounce": a red wall next to a shaded floor pushes red into it, and
+//! an emissive block lights up the cave around it.
+//!
+//! The result is uploaded as a small 3D texture ([`GiVolume`]) that
+//! `voxel.wgsl` samples trilinearly to scale its ambient term. It's a
+//! light-propagation-volume in spirit, built on the CPU from the chunk
+//! data: cheap enough to rebuild after an edit, coarse enough that it
+//! only ever shows soft, large-scale light — the per-vertex AO still
+//! carries the small-scale contact shading.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::core::World;
+
+/// Largest grid dimension along any axis. The cell size grows (in
+/// powers of two) until the padded scene fits, so cost stays bounded at
+/// 64³ cells no matter how big the scene is.
+pub const GI_GRID_MAX_DIM: u32 = 64;
+
+/// Irradiance range the 8-bit texture encodes: stored value × this.
+/// Open sky is 1.0; the headroom is for light near emitters.
+pub const GI_RANGE: f32 = 4.0;
+
+/// Radiance an emissive voxel injects into its cell, scaled by how much
+/// of the cell it fills. Same strength the path tracer gives emitters.
+const EMISSIVE_STRENGTH: f32 = 4.0;
+
+/// Fraction of gathered light kept per propagation step. Bounds how far
+/// light reaches into enclosed spaces (0.85¹² ≈ 0.14 after 12 cells).
+const PROPAGATION_DECAY: f32 = 0.85;
+
+/// User-facing GI settings, persisted with the viewport settings.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct GiSettings {
+    /// Blend between flat ambient (`0`) and the GI estimate (`1`).
+    /// Values above 1 exaggerate the effect.
+    pub intensity: f32,
+    /// Diffusion rounds — roughly how many grid cells light travels.
+    pub propagation_steps: u32,
+}
+
+impl Default for GiSettings {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            propagation_steps: 12,
+        }
+    }
+}
+
+/// Coarse irradiance grid over the scene. Cell `(x, y, z)` covers world
+/// voxels `origin + (x, y, z) * cell_size` through `+ cell_size - 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightVolume {
+    /// World voxel at the grid's min corner.
+    pub origin: [i32; 3],
+    /// Edge length of one cell, in voxels.
+    pub cell_size: i32,
+    /// Size in cells along each axis.
+    pub size: [u32; 3],
+    /// Linear RGB irradiance per cell, `x + y * sx + z * sx * sy` order.
+    pub irradiance: Vec<[f32; 3]>,
+}
+
+/// Per-cell material summary gathered from the voxels it covers.
+#[derive(Debug, Clone, Copy, Default)]
+struct CellMaterial {
+    /// Solid fraction of the cell, 0..=1.
+    occupancy: f32,
+    /// Mean color of the solid voxels.
+    albedo: [f32; 3],
+    /// Injected emissive radiance.
+    emission: [f32; 3],
+}
+
+impl LightVolume {
+    /// Build the volume for `world`, running `steps` diffusion rounds.
+    /// `None` for an empty world.
+    pub fn build(world: &World, steps: u32) -> Option<Self> {
+        let (min, max) = world.scene_aabb()?;
+        let extent = [max.0 - min.0 + 1, max.1 - min.1 + 1, max.2 - min.2 + 1];
+
+        // One cell of padding on every side so there's open air around
+        // the scene for sky light to enter through.
+        let mut cell_size = 2;
+        while extent
+            .iter()
+            .any(|&e| (e + cell_size - 1) / cell_size + 2 > GI_GRID_MAX_DIM as i32)
+        {
+            cell_size *= 2;
+        }
+        let size = extent.map(|e| ((e + cell_size - 1) / cell_size + 2) as u32);
+        let origin = [
+            min.0 - cell_size,
+            min.1 - cell_size,
+            min.2 - cell_size,
+        ];
+
+        let materials = Self::gather_materials(world, origin, cell_size, size);
+        let irradiance = Self::propagate(&materials, size, steps);
+        Some(Self {
+            origin,
+            cell_size,
+            size,
+            irradiance,
+        })
+    }
+
+    /// Irradiance of one cell.
+    pub fn cell(&self, x: u32, y: u32, z: u32) -> [f32; 3] {
+        self.irradiance[self.index(x, y, z)]
+    }
+
+    /// Cell containing world voxel `(x, y, z)`, if it's inside the grid.
+    pub fn cell_of(&self, x: i32, y: i32, z: i32) -> Option<(u32, u32, u32)> {
+        let local = [x - self.origin[0], y - self.origin[1], z - self.origin[2]]
+            .map(|v| v.div_euclid(self.cell_size));
+        let inside = local
+            .iter()
+            .zip(self.size)
+            .all(|(&v, s)| v >= 0 && (v as u32) < s);
+        inside.then(|| (local[0] as u32, local[1] as u32, local[2] as u32))
+    }
+
+    fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        let (sx, sy) = (self.size[0] as usize, self.size[1] as usize);
+        x as usize + y as usize * sx + z as usize * sx * sy
+    }
+
+    fn gather_materials(
+        world: &World,
+        origin: [i32; 3],
+        cell_size: i32,
+        size: [u32; 3],
+    ) -> Vec<CellMaterial> {
+        let (sx, sy) = (size[0] as usize, size[1] as usize);
+        let count = sx * sy * size[2] as usize;
+        // Per cell: solid count, color sum, emissive color sum.
+        let mut solid = vec![0u32; count];
+        let mut color = vec![[0.0f32; 3]; count];
+        let mut emitted = vec![[0.0f32; 3]; count];
+
+        for (chunk_pos, chunk) in world.chunks() {
+            let chunk = chunk.read();
+            let o = chunk_pos.world_origin();
+            for (local, voxel) in chunk.iter_solid() {
+                let cx = (o.0 + local.x as i32 - origin[0]) / cell_size;
+                let cy = (o.1 + local.y as i32 - origin[1]) / cell_size;
+                let cz = (o.2 + local.z as i32 - origin[2]) / cell_size;
+                let i = cx as usize + cy as usize * sx + cz as usize * sx * sy;
+                let [r, g, b, _] = voxel.color_f32();
+                solid[i] += 1;
+                color[i] = [color[i][0] + r, color[i][1] + g, color[i][2] + b];
+                if voxel.is_emissive() {
+                    emitted[i] = [emitted[i][0] + r, emitted[i][1] + g, emitted[i][2] + b];
+                }
+            }
+        }
+
+        let cell_volume = (cell_size * cell_size * cell_size) as f32;
+        (0..count)
+            .map(|i| {
+                if solid[i] == 0 {
+                    return CellMaterial::default();
+                }
+                let n = solid[i] as f32;
+                CellMaterial {
+                    occupancy: n / cell_volume,
+                    albedo: color[i].map(|c| c / n),
+                    emission: emitted[i].map(|c| c / cell_volume * EMISSIVE_STRENGTH),
+                }
+            })
+            .collect()
+    }
+
+    /// Sky injection + Jacobi diffusion.
+    ///
+    /// `sky[c]` is the fraction of open sky straight above reaching
+    /// cell `c`, attenuated by the occupancy of every cell above it. A
+    /// cell's light is then its own emission, plus its sky share, plus
+    /// — for the part of it the sky *doesn't* reach — the decayed mean
+    /// of what its six neighbors pass on. A neighbor passes on its
+    /// non-emitted light through its open part and reflects it off its
+    /// solid part tinted by albedo, and passes its own emission whole.
+    /// Outside the grid counts as open sky, except below it.
+    fn propagate(materials: &[CellMaterial], size: [u32; 3], steps: u32) -> Vec<[f32; 3]> {
+        let [sx, sy, sz] = size.map(|s| s as usize);
+        let idx = |x: usize, y: usize, z: usize| x + y * sx + z * sx * sy;
+
+        let mut sky = vec![0.0f32; materials.len()];
+        for z in 0..sz {
+            for x in 0..sx {
+                let mut transmittance = 1.0f32;
+                for y in (0..sy).rev() {
+                    let i = idx(x, y, z);
+                    sky[i] = transmittance;
+                    transmittance *= 1.0 - materials[i].occupancy;
+                }
+            }
+        }
+
+        // Reflection tints by albedo; open air passes light unchanged.
+        let tint = |m: &CellMaterial, c: usize| -> f32 {
+            let open = 1.0 - m.occupancy;
+            open + m.occupancy * m.albedo[c]
+        };
+
+        let base: Vec<[f32; 3]> = materials
+            .iter()
+            .zip(&sky)
+            .map(|(m, &s)| [0, 1, 2].map(|c| m.emission[c] + s))
+            .collect();
+        let mut light = base.clone();
+        let mut next = light.clone();
+
+        for _ in 0..steps {
+            for z in 0..sz {
+                for y in 0..sy {
+                    for x in 0..sx {
+                        let i = idx(x, y, z);
+                        let mut gathered = [0.0f32; 3];
+                        let neighbors = [
+                            (x.checked_sub(1).map(|x| idx(x, y, z)), 1.0),
+                            ((x + 1 < sx).then(|| idx(x + 1, y, z)), 1.0),
+                            (y.checked_sub(1).map(|y| idx(x, y, z)), 0.0),
+                            ((y + 1 < sy).then(|| idx(x, y + 1, z)), 1.0),
+                            (z.checked_sub(1).map(|z| idx(x, y, z)), 1.0),
+                            ((z + 1 < sz).then(|| idx(x, y, z + 1)), 1.0),
+                        ];
+                        for (neighbor, outside) in neighbors {
+                            match neighbor {
+                                Some(n) => {
+                                    let m = &materials[n];
+                                    for (c, g) in gathered.iter_mut().enumerate() {
+                                        let passed = (light[n][c] - m.emission[c]).max(0.0);
+                                        *g += passed * tint(m, c) + m.emission[c];
+                                    }
+                                }
+                                None => {
+                                    for g in gathered.iter_mut() {
+                                        *g += outside;
+                                    }
+                                }
+                            }
+                        }
+                        let shaded = 1.0 - sky[i];
+                        next[i] = [0, 1, 2].map(|c| {
+                            base[i][c] + shaded * PROPAGATION_DECAY * gathered[c] / 6.0
+                        });
+                    }
+                }
+            }
+            std::mem::swap(&mut light, &mut next);
+        }
+        light
+    }
+
+    /// Texel data for an `Rgba8Unorm` 3D texture: irradiance / `GI_RANGE`.
+    fn texels(&self) -> Vec<u8> {
+        self.irradiance
+            .iter()
+            .flat_map(|rgb| {
+                let [r, g, b] = rgb.map(|v| ((v / GI_RANGE).clamp(0.0, 1.0) * 255.0).round() as u8);
+                [r, g, b, 255]
+            })
+            .collect()
+    }
+}
+
+/// Uniform block read by `voxel.wgsl` (bind group 1, binding 0).
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+struct GiUniform {
+    /// xyz = world position of the grid's min corner, w = intensity
+    /// (`0` turns GI off and leaves the flat ambient term).
+    origin: [f32; 4],
+    /// xyz = 1 / grid extent in world units, w = cell size.
+    inv_extent: [f32; 4],
+    /// Ambient color for surfaces facing +X, -X, +Y, -Y, +Z, -Z (rgb;
+    /// a unused), blended by the normal. All ones = the plain flat
+    /// ambient; an HDRI background sets it from its image.
+    sky: [[f32; 4]; 6],
+}
+
+/// GPU side of the GI volume: the 3D texture, its sampler, and the
+/// uniform describing where it sits in the world. Owned by
+/// `RenderPipeline`, since the voxel pipelines' layout includes its
+/// bind group. Starts as a 1³ placeholder with GI off.
+pub struct GiVolume {
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    texture: wgpu::Texture,
+    texture_view: wgpu::TextureView,
+    texture_size: [u32; 3],
+    /// World placement of the uploaded volume (origin, extent, cell
+    /// size); `None` until one is uploaded or after `clear`.
+    placement: Option<([f32; 3], [f32; 3], f32)>,
+    /// `(Renderer::scene_revision, propagation_steps)` the uploaded
+    /// volume was built for. The App rebuilds when this goes stale.
+    pub built_for: Option<(u64, u32)>,
+    /// When the current volume was built — the App throttles rebuilds
+    /// during continuous edits against it.
+    pub built_at: Option<std::time::Instant>,
+    /// Per-axis ambient colors (see [`Self::set_sky_ambient`]).
+    sky: [[f32; 3]; 6],
+}
+
+impl GiVolume {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("GI Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D3,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("GI Uniform"),
+            contents: bytemuck::cast_slice(&[GiUniform::zeroed()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("GI Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let (texture, texture_view) = Self::create_texture(device, [1, 1, 1]);
+        let bind_group = Self::create_bind_group(
+            device,
+            &bind_group_layout,
+            &uniform_buffer,
+            &texture_view,
+            &sampler,
+        );
+
+        Self {
+            bind_group_layout,
+            bind_group,
+            uniform_buffer,
+            sampler,
+            texture,
+            texture_view,
+            texture_size: [1, 1, 1],
+            placement: None,
+            built_for: None,
+            built_at: None,
+            sky: [[1.0; 3]; 6],
+        }
+    }
+
+    fn create_texture(
+        device: &wgpu::Device,
+        size: [u32; 3],
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("GI Volume"),
+            size: wgpu::Extent3d {
+                width: size[0],
+                height: size[1],
+                depth_or_array_layers: size[2],
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        uniform: &wgpu::Buffer,
+        view: &wgpu::TextureView,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("GI Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+
+    /// Upload a freshly built volume, reallocating the texture only
+    /// when its dimensions change.
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, volume: &LightVolume) {
+        if volume.size != self.texture_size {
+            self.texture_size = volume.size;
+            (self.texture, self.texture_view) = Self::create_texture(device, volume.size);
+            self.bind_group = Self::create_bind_group(
+                device,
+                &self.bind_group_layout,
+                &self.uniform_buffer,
+                &self.texture_view,
+                &self.sampler,
+            );
+        }
+        let [w, h, d] = volume.size;
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &volume.texels(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(w * 4),
+                rows_per_image: Some(h),
+            },
+            wgpu::Extent3d {
+                width: w,
+                height: h,
+                depth_or_array_layers: d,
+            },
+        );
+        let cs = volume.cell_size as f32;
+        self.placement = Some((
+            volume.origin.map(|v| v as f32),
+            volume.size.map(|s| s as f32 * cs),
+            cs,
+        ));
+    }
+
+    /// Forget the uploaded volume (empty scene); GI reads as off.
+    pub fn clear(&mut self) {
+        self.placement = None;
+    }
+
+    /// Color the ambient term by direction: one color per face
+    /// direction (+X, -X, +Y, -Y, +Z, -Z), or `None` for the plain
+    /// flat ambient. Works with GI on or off; goes out with the next
+    /// [`Self::write_params`].
+    pub fn set_sky_ambient(&mut self, sky: Option<[[f32; 3]; 6]>) {
+        self.sky = sky.unwrap_or([[1.0; 3]; 6]);
+    }
+
+    /// Write this frame's uniform. `intensity` 0 — or no volume —
+    /// leaves the shader's flat ambient term untouched.
+    pub fn write_params(&self, queue: &wgpu::Queue, intensity: f32) {
+        let mut uniform = match self.placement {
+            Some((origin, extent, cell_size)) => GiUniform {
+                origin: [origin[0], origin[1], origin[2], intensity.max(0.0)],
+                inv_extent: [1.0 / extent[0], 1.0 / extent[1], 1.0 / extent[2], cell_size],
+                sky: [[0.0; 4]; 6],
+            },
+            None => GiUniform::zeroed(),
+        };
+        uniform.sky = self.sky.map(|[r, g, b]| [r, g, b, 1.0]);
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Voxel;
+
+    fn cell_at(vol: &LightVolume, x: i32, y: i32, z: i32) -> [f32; 3] {
+        let (cx, cy, cz) = vol.cell_of(x, y, z).expect("inside volume");
+        vol.cell(cx, cy, cz)
+    }
+
+    /// Hollow stone box spanning `-r..=r` with 3-voxel walls — thick
+    /// enough to fully fill a 2-voxel GI cell whatever the alignment.
+    fn closed_box(world: &mut World, r: i32) {
+        let stone = Voxel::from_rgb(128, 128, 128);
+        for x in -r..=r {
+            for y in -r..=r {
+                for z in -r..=r {
+                    let edge = x.abs() > r - 3 || y.abs() > r - 3 || z.abs() > r - 3;
+                    if edge {
+                        world.set_voxel(x, y, z, stone);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_world_has_no_volume() {
+        assert!(LightVolume::build(&World::new(), 8).is_none());
+    }
+
+    #[test]
+    fn grid_stays_within_max_dim() {
+        let mut world = World::new();
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(1, 1, 1));
+        world.set_voxel(300, 5, -40, Voxel::from_rgb(1, 1, 1));
+        let vol = LightVolume::build(&world, 1).unwrap();
+        assert!(vol.size.iter().all(|&s| s <= GI_GRID_MAX_DIM));
+        assert_eq!(vol.cell_size, 8);
+        // Both voxels land inside the grid.
+        assert!(vol.cell_of(0, 0, 0).is_some());
+        assert!(vol.cell_of(300, 5, -40).is_some());
+    }
+
+    #[test]
+    fn open_sky_is_unit_and_enclosed_space_is_dark() {
+        let mut world = World::new();
+        closed_box(&mut world, 12);
+        let vol = LightVolume::build(&world, 12).unwrap();
+
+        // Top padding row: nothing above it but sky.
+        let above = cell_at(&vol, 0, 14, 0);
+        assert!((above[0] - 1.0).abs() < 1e-4, "open air above: {:?}", above);
+
+        let inside = cell_at(&vol, 0, 0, 0);
+        assert!(inside[0] < 0.2, "sealed interior should be dark: {:?}", inside);
+    }
+
+    #[test]
+    fn emissive_voxel_lights_enclosure_in_its_color() {
+        let mut dark = World::new();
+        closed_box(&mut dark, 12);
+        let mut lit = World::new();
+        closed_box(&mut lit, 12);
+        let mut lamp = Voxel::from_rgb(255, 60, 0);
+        lamp.set_emissive(true);
+        lit.fill_region((-1, -1, -1), (1, 1, 1), lamp);
+
+        let dark_vol = LightVolume::build(&dark, 12).unwrap();
+        let lit_vol = LightVolume::build(&lit, 12).unwrap();
+        let before = cell_at(&dark_vol, 6, 0, 0);
+        let after = cell_at(&lit_vol, 6, 0, 0);
+        assert!(after[0] > before[0] + 0.05, "{:?} vs {:?}", after, before);
+        // Orange light: red gains more than blue.
+        assert!(after[0] - before[0] > after[2] - before[2]);
+    }
+}
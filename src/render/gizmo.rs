@@ -0,0 +1,162 @@
+//! Line mesh for the translate gizmo (`editor::Gizmo`).
+//!
+//! Arrows in the world-axis colors with pyramid heads, and an outlined
+//! square per plane handle, drawn through the overlay `LinePipeline`
+//! so they stay visible — and grabbable — behind voxels. The handle
+//! under the cursor, or being dragged, turns yellow. When the gizmo
+//! moves the symmetry mirror planes, a large square outlines each
+//! enabled plane so the user sees what they're dragging.
+//!
+//! The App scales the gizmo with camera distance, so this rebuilds as
+//! the camera moves; at under a hundred vertices that's nothing.
+
+use bytemuck::cast_slice;
+use glam::Vec3;
+use wgpu::util::DeviceExt;
+
+use super::grid::LineVertex;
+use crate::editor::{axis_index, axis_vec, Gizmo, GizmoHandle, GIZMO_AXES, PLANE_SPAN};
+
+/// Handle colors per axis, matching the world axes.
+const AXIS_COLORS: [[f32; 4]; 3] = [
+    [1.0, 0.25, 0.25, 1.0],
+    [0.3, 1.0, 0.3, 1.0],
+    [0.3, 0.45, 1.0, 1.0],
+];
+/// Hovered or dragged handle.
+const HOT_COLOR: [f32; 4] = [1.0, 0.9, 0.2, 1.0];
+/// Arrowhead length and half-width, as fractions of the gizmo size.
+const HEAD_LEN: f32 = 0.2;
+const HEAD_W: f32 = 0.06;
+/// Half-side of a mirror plane's outline, in gizmo sizes.
+const MIRROR_HALF: f32 = 3.0;
+/// Alpha of the mirror plane outlines.
+const MIRROR_ALPHA: f32 = 0.6;
+
+/// `LineList` mesh of one gizmo.
+pub struct GizmoMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub vertex_count: u32,
+}
+
+impl GizmoMesh {
+    /// Mesh for `gizmo` with `hot` highlighted. `mirror_planes` also
+    /// outlines the plane through the gizmo across each of its axes.
+    pub fn new(
+        device: &wgpu::Device,
+        gizmo: &Gizmo,
+        hot: Option<GizmoHandle>,
+        mirror_planes: bool,
+    ) -> Self {
+        let verts = gizmo_vertices(gizmo, hot, mirror_planes);
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Gizmo Vertex Buffer"),
+            contents: cast_slice(&verts),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        Self {
+            vertex_buffer,
+            vertex_count: verts.len() as u32,
+        }
+    }
+}
+
+fn gizmo_vertices(
+    gizmo: &Gizmo,
+    hot: Option<GizmoHandle>,
+    mirror_planes: bool,
+) -> Vec<LineVertex> {
+    let mut verts = Vec::new();
+    let mut seg = |a: Vec3, b: Vec3, c: [f32; 4]| {
+        verts.push(LineVertex::new(a.to_array(), c));
+        verts.push(LineVertex::new(b.to_array(), c));
+    };
+    let o = gizmo.origin;
+    let size = gizmo.size;
+    let color = |handle: GizmoHandle, axis: usize| {
+        if hot == Some(handle) {
+            HOT_COLOR
+        } else {
+            AXIS_COLORS[axis]
+        }
+    };
+
+    if mirror_planes {
+        for axis in GIZMO_AXES.into_iter().filter(|&a| gizmo.axes[axis_index(a)]) {
+            let i = axis_index(axis);
+            let u = axis_vec(GIZMO_AXES[(i + 1) % 3]) * size * MIRROR_HALF;
+            let v = axis_vec(GIZMO_AXES[(i + 2) % 3]) * size * MIRROR_HALF;
+            let [r, g, b, _] = AXIS_COLORS[i];
+            let c = [r, g, b, MIRROR_ALPHA];
+            let corners = [o - u - v, o + u - v, o + u + v, o - u + v];
+            for k in 0..4 {
+                seg(corners[k], corners[(k + 1) % 4], c);
+            }
+        }
+    }
+
+    for handle in gizmo.handles() {
+        match handle {
+            GizmoHandle::Axis(axis) => {
+                let i = axis_index(axis);
+                let c = color(handle, i);
+                let a = axis_vec(axis);
+                let tip = o + a * size;
+                seg(o, tip, c);
+                let base = tip - a * size * HEAD_LEN;
+                let u = axis_vec(GIZMO_AXES[(i + 1) % 3]) * size * HEAD_W;
+                let v = axis_vec(GIZMO_AXES[(i + 2) % 3]) * size * HEAD_W;
+                let ring = [base + u, base + v, base - u, base - v];
+                for k in 0..4 {
+                    seg(tip, ring[k], c);
+                    seg(ring[k], ring[(k + 1) % 4], c);
+                }
+            }
+            GizmoHandle::Plane(normal) => {
+                let i = axis_index(normal);
+                let c = color(handle, i);
+                let (lo, hi) = PLANE_SPAN;
+                let u = axis_vec(GIZMO_AXES[(i + 1) % 3]) * size;
+                let v = axis_vec(GIZMO_AXES[(i + 2) % 3]) * size;
+                let corners = [
+                    o + u * lo + v * lo,
+                    o + u * hi + v * lo,
+                    o + u * hi + v * hi,
+                    o + u * lo + v * hi,
+                ];
+                for k in 0..4 {
+                    seg(corners[k], corners[(k + 1) % 4], c);
+                }
+            }
+        }
+    }
+    verts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::Axis;
+
+    #[test]
+    fn full_gizmo_has_arrows_and_squares() {
+        let gizmo = Gizmo::new(Vec3::ZERO, 2.0);
+        // Arrow: shaft + 4 head edges + 4 ring edges; square: 4 edges.
+        let verts = gizmo_vertices(&gizmo, None, false);
+        assert_eq!(verts.len(), 2 * (3 * 9 + 3 * 4));
+        // Mirror outlines add a square per axis.
+        let verts = gizmo_vertices(&gizmo, None, true);
+        assert_eq!(verts.len(), 2 * (3 * 9 + 3 * 4 + 3 * 4));
+    }
+
+    #[test]
+    fn hot_handle_is_highlighted() {
+        let gizmo = Gizmo::new(Vec3::ZERO, 2.0);
+        let verts = gizmo_vertices(&gizmo, Some(GizmoHandle::Axis(Axis::Y)), false);
+        // X arrow first, then Y.
+        assert_eq!(verts[0].color, AXIS_COLORS[0]);
+        assert_eq!(verts[18].color, HOT_COLOR);
+        assert_eq!(verts[18].position, [0.0, 0.0, 0.0]);
+        assert_eq!(verts[19].position, [0.0, 2.0, 0.0]);
+    }
+}
@@ -1,9 +1,84 @@
-//! GPU-side mesh storage.
+//! GPU-side mesh storage for standalone meshes — the overlays
+//! (procgen preview, brush hover, move ghost, hover face). Chunk meshes
+//! share pooled buffers instead (`chunk_batch`).
+//!
+//! Buffers are long-lived: when an overlay is rebuilt (the brush
+//! preview is, on every cursor move), [`GpuMesh::update`] writes the
+//! new geometry into the existing buffers with `queue.write_buffer` as
+//! long as it fits, and only reallocates — with headroom, see
+//! [`grown_capacity`] — when it doesn't. Creating and dropping a pair
+//! of buffers per rebuild fragmented VRAM and stalled the driver.
 
 use crate::mesh::ChunkMesh;
-use wgpu::util::DeviceExt;
 
-/// GPU buffer representation of a chunk mesh
+/// Extra room given to a buffer (or chunk slot) when it is
+/// (re)allocated, as a fraction of the space needed: a mesh that grows
+/// by a few faces per stroke then reallocates once every several
+/// strokes, not on each.
+pub(super) const CAPACITY_HEADROOM: f64 = 0.5;
+
+/// Smallest buffer allocated, in bytes — tiny meshes (a single voxel)
+/// would otherwise regrow on nearly every edit.
+const MIN_CAPACITY: u64 = 4096;
+
+/// A buffer (or chunk slot) whose capacity is more than this many
+/// times what its mesh needs is shrunk on the next reallocation check,
+/// so one huge edit (a fill, then undo) doesn't pin VRAM for the rest
+/// of the session.
+pub(super) const SHRINK_FACTOR: u64 = 4;
+
+/// Capacity to allocate for `needed` bytes: `needed` plus headroom,
+/// at least [`MIN_CAPACITY`], rounded up to `wgpu::COPY_BUFFER_ALIGNMENT`
+/// so `write_buffer` can fill any prefix.
+pub fn grown_capacity(needed: u64) -> u64 {
+    let padded = needed + (needed as f64 * CAPACITY_HEADROOM).ceil() as u64;
+    let align = wgpu::COPY_BUFFER_ALIGNMENT;
+    padded.max(MIN_CAPACITY).div_ceil(align) * align
+}
+
+/// Whether a buffer of `capacity` bytes can take `needed` bytes in
+/// place: it must be big enough, and not so oversized that it should be
+/// given back ([`SHRINK_FACTOR`]).
+pub fn fits_in_place(capacity: u64, needed: u64) -> bool {
+    needed <= capacity && capacity <= grown_capacity(needed).saturating_mul(SHRINK_FACTOR)
+}
+
+/// Write `bytes` to the start of `buffer`, reallocating it first (with
+/// [`grown_capacity`]) when it doesn't [`fits_in_place`]. Returns
+/// whether it reallocated.
+fn write_or_grow(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    buffer: &mut wgpu::Buffer,
+    label: &str,
+    usage: wgpu::BufferUsages,
+    bytes: &[u8],
+) -> bool {
+    let regrow = !fits_in_place(buffer.size(), bytes.len() as u64);
+    if regrow {
+        *buffer = create_buffer(device, label, usage, bytes.len() as u64);
+    }
+    queue.write_buffer(buffer, 0, bytes);
+    regrow
+}
+
+fn create_buffer(
+    device: &wgpu::Device,
+    label: &str,
+    usage: wgpu::BufferUsages,
+    needed: u64,
+) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: grown_capacity(needed),
+        usage: usage | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+/// GPU buffer representation of a standalone mesh. The buffers are
+/// usually larger than the mesh (see the module docs); `index_count`
+/// says how much of the index buffer is live.
 pub struct GpuMesh {
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
@@ -12,18 +87,23 @@ pub struct GpuMesh {
 
 impl GpuMesh {
     /// Create GPU mesh from CPU mesh data
-    pub fn new(device: &wgpu::Device, mesh: &ChunkMesh) -> Self {
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Chunk Vertex Buffer"),
-            contents: mesh.vertex_bytes(),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
-
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Chunk Index Buffer"),
-            contents: mesh.index_bytes(),
-            usage: wgpu::BufferUsages::INDEX,
-        });
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, mesh: &ChunkMesh) -> Self {
+        let vertex_bytes = mesh.vertex_bytes();
+        let index_bytes = mesh.index_bytes();
+        let vertex_buffer = create_buffer(
+            device,
+            "Mesh Vertex Buffer",
+            wgpu::BufferUsages::VERTEX,
+            vertex_bytes.len() as u64,
+        );
+        let index_buffer = create_buffer(
+            device,
+            "Mesh Index Buffer",
+            wgpu::BufferUsages::INDEX,
+            index_bytes.len() as u64,
+        );
+        queue.write_buffer(&vertex_buffer, 0, vertex_bytes);
+        queue.write_buffer(&index_buffer, 0, index_bytes);
 
         Self {
             vertex_buffer,
@@ -32,10 +112,85 @@ impl GpuMesh {
         }
     }
 
+    /// Replace the geometry with `mesh`, reusing the existing buffers
+    /// when it fits (see the module docs). Returns whether either
+    /// buffer had to be reallocated.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, mesh: &ChunkMesh) -> bool {
+        let regrew_vertices = write_or_grow(
+            device,
+            queue,
+            &mut self.vertex_buffer,
+            "Mesh Vertex Buffer",
+            wgpu::BufferUsages::VERTEX,
+            mesh.vertex_bytes(),
+        );
+        let regrew_indices = write_or_grow(
+            device,
+            queue,
+            &mut self.index_buffer,
+            "Mesh Index Buffer",
+            wgpu::BufferUsages::INDEX,
+            mesh.index_bytes(),
+        );
+        self.index_count = mesh.indices.len();
+        regrew_vertices || regrew_indices
+    }
+
+    /// Put `mesh` into `slot`: updated in place when there's already a
+    /// mesh there, created otherwise, cleared when `mesh` is empty. For
+    /// overlays that are re-uploaded as the cursor moves.
+    pub fn replace(
+        slot: &mut Option<GpuMesh>,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mesh: &ChunkMesh,
+    ) {
+        if mesh.is_empty() {
+            *slot = None;
+        } else if let Some(existing) = slot {
+            existing.update(device, queue, mesh);
+        } else {
+            *slot = Some(GpuMesh::new(device, queue, mesh));
+        }
+    }
+
     /// Draw this mesh
     pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.index_count == 0 {
+            return;
+        }
         render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
         render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         render_pass.draw_indexed(0..self.index_count as u32, 0, 0..1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity_has_headroom_alignment_and_a_floor() {
+        assert_eq!(grown_capacity(0), MIN_CAPACITY);
+        assert_eq!(grown_capacity(10), MIN_CAPACITY);
+        let cap = grown_capacity(100_001);
+        assert!(cap >= 150_002, "headroom: {}", cap);
+        assert_eq!(cap % wgpu::COPY_BUFFER_ALIGNMENT, 0);
+    }
+
+    #[test]
+    fn small_growth_reuses_and_big_changes_reallocate() {
+        let cap = grown_capacity(100_000);
+        // A chunk gaining a few faces fits the headroom.
+        assert!(fits_in_place(cap, 100_000));
+        assert!(fits_in_place(cap, 140_000));
+        // Outgrowing it reallocates.
+        assert!(!fits_in_place(cap, cap + 4));
+        // Shrinking a little keeps the buffer; shrinking to a sliver
+        // gives the memory back.
+        assert!(fits_in_place(cap, 40_000));
+        assert!(!fits_in_place(cap, 20_000));
+        // The floor never counts as oversized.
+        assert!(fits_in_place(MIN_CAPACITY, 4));
+    }
+}
@@ -115,7 +115,8 @@ impl AxisMesh {
     }
 }
 
-/// Line rendering pipeline
+/// Line rendering pipeline. Drawn in the main pass alongside the voxel
+/// pipelines, so it's rebuilt with them when the MSAA level changes.
 pub struct LinePipeline {
     pub render_pipeline: wgpu::RenderPipeline,
 }
@@ -125,6 +126,40 @@ impl LinePipeline {
         device: &wgpu::Device,
         surface_format: wgpu::TextureFormat,
         camera_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+    ) -> Self {
+        Self::build(
+            device,
+            surface_format,
+            camera_bind_group_layout,
+            sample_count,
+            wgpu::CompareFunction::Less,
+        )
+    }
+
+    /// Same lines drawn over everything, ignoring depth — for handles
+    /// that must stay grabbable behind voxels (the move gizmo).
+    pub fn overlay(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+    ) -> Self {
+        Self::build(
+            device,
+            surface_format,
+            camera_bind_group_layout,
+            sample_count,
+            wgpu::CompareFunction::Always,
+        )
+    }
+
+    fn build(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+        depth_compare: wgpu::CompareFunction,
     ) -> Self {
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Line Shader"),
@@ -168,12 +203,12 @@ impl LinePipeline {
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: wgpu::TextureFormat::Depth32Float,
                 depth_write_enabled: false, // Don't write depth for lines
-                depth_compare: wgpu::CompareFunction::Less,
+                depth_compare,
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
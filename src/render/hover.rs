@@ -0,0 +1,149 @@
+//! Hovered-voxel highlight: an outline around the cell under the
+//! cursor plus a tint on the face the ray hit.
+//!
+//! The brush overlay shows *what* a click would write; this shows
+//! *where the ray landed* — the cell Remove / Paint target and the face
+//! Place stacks onto — which matters most when the brush ghost sits
+//! flush against other geometry and is hard to read.
+//!
+//! Two parts, both depth-tested so nearer voxels occlude them:
+//!
+//! - **Outline**: the cell's 12 edges as a `LineList` through the shared
+//!   `LinePipeline` (same rules as the selection wireframe).
+//! - **Face tint**: one translucent quad on the hit face, drawn through
+//!   `transparent_pipeline` like the brush overlay.
+//!
+//! Both are pushed out from the cell by [`hover_inset`], which grows
+//! with hit distance: a fixed epsilon z-fights with the voxel's own
+//! faces once depth precision thins out far from the camera, while a
+//! large one floats visibly off the surface up close.
+
+use bytemuck::cast_slice;
+use wgpu::util::DeviceExt;
+
+use super::grid::LineVertex;
+use super::GpuMesh;
+use crate::core::ChunkPos;
+use crate::mesh::{face_quad_vertices, ChunkMesh, Face};
+
+/// Near-white outline — distinct from the selection (yellow) and
+/// socket (magenta) wireframes, readable on any voxel color.
+const OUTLINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.9];
+/// Face tint. Low alpha so the voxel's own color still reads through.
+const FACE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.35];
+
+/// Outward offset (world units) for the highlight at hit `distance`.
+/// Proportional to distance so the overlay stays in front of the
+/// surface at any zoom, clamped so it never vanishes up close or
+/// detaches visibly from the voxel far away.
+pub fn hover_inset(distance: f32) -> f32 {
+    (distance * 0.002).clamp(0.004, 0.25)
+}
+
+/// GPU buffers for one hovered cell's outline + hit-face tint.
+pub struct HoverMesh {
+    pub outline_buffer: wgpu::Buffer,
+    pub outline_count: u32,
+    /// `None` if `normal` wasn't a unit axis vector (shouldn't happen
+    /// for raycast hits; the outline still draws).
+    pub face: Option<GpuMesh>,
+}
+
+impl HoverMesh {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        cell: (i32, i32, i32),
+        normal: (i32, i32, i32),
+        inset: f32,
+    ) -> Self {
+        let vertices = build_outline(cell, inset);
+        let outline_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Hover Outline Vertex Buffer"),
+            contents: cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let face = build_face(cell, normal, inset).map(|mesh| GpuMesh::new(device, queue, &mesh));
+        Self {
+            outline_buffer,
+            outline_count: vertices.len() as u32,
+            face,
+        }
+    }
+}
+
+/// 12 edges of `cell`, grown by `inset` on every side.
+fn build_outline(cell: (i32, i32, i32), inset: f32) -> Vec<LineVertex> {
+    let x0 = cell.0 as f32 - inset;
+    let y0 = cell.1 as f32 - inset;
+    let z0 = cell.2 as f32 - inset;
+    let x1 = cell.0 as f32 + 1.0 + inset;
+    let y1 = cell.1 as f32 + 1.0 + inset;
+    let z1 = cell.2 as f32 + 1.0 + inset;
+    let c = OUTLINE_COLOR;
+    let v = LineVertex::new;
+
+    vec![
+        // Bottom face.
+        v([x0, y0, z0], c), v([x1, y0, z0], c),
+        v([x1, y0, z0], c), v([x1, y0, z1], c),
+        v([x1, y0, z1], c), v([x0, y0, z1], c),
+        v([x0, y0, z1], c), v([x0, y0, z0], c),
+        // Top face.
+        v([x0, y1, z0], c), v([x1, y1, z0], c),
+        v([x1, y1, z0], c), v([x1, y1, z1], c),
+        v([x1, y1, z1], c), v([x0, y1, z1], c),
+        v([x0, y1, z1], c), v([x0, y1, z0], c),
+        // Verticals.
+        v([x0, y0, z0], c), v([x0, y1, z0], c),
+        v([x1, y0, z0], c), v([x1, y1, z0], c),
+        v([x1, y0, z1], c), v([x1, y1, z1], c),
+        v([x0, y0, z1], c), v([x0, y1, z1], c),
+    ]
+}
+
+/// Single quad on the `normal` face of `cell`, pushed `inset` outward.
+fn build_face(cell: (i32, i32, i32), normal: (i32, i32, i32), inset: f32) -> Option<ChunkMesh> {
+    let face = Face::from_offset(normal)?;
+    let mut quad = face_quad_vertices(cell.0 as f32, cell.1 as f32, cell.2 as f32, face, FACE_COLOR);
+    for v in &mut quad {
+        v.position[0] += normal.0 as f32 * inset;
+        v.position[1] += normal.1 as f32 * inset;
+        v.position[2] += normal.2 as f32 * inset;
+    }
+    let mut mesh = ChunkMesh::with_capacity(ChunkPos::ZERO, 4, 6);
+    mesh.add_quad(quad);
+    Some(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outline_encloses_cell() {
+        let v = build_outline((2, -1, 5), 0.01);
+        assert_eq!(v.len(), 24);
+        for lv in &v {
+            let [x, y, z] = lv.position;
+            assert!((1.98..=3.02).contains(&x));
+            assert!((-1.02..=0.02).contains(&y));
+            assert!((4.98..=6.02).contains(&z));
+        }
+    }
+
+    #[test]
+    fn face_quad_sits_just_outside_hit_face() {
+        let mesh = build_face((0, 0, 0), (0, 1, 0), 0.05).expect("axis normal");
+        assert_eq!(mesh.vertex_count(), 4);
+        assert!(mesh.vertices.iter().all(|v| (v.position[1] - 1.05).abs() < 1e-6));
+        assert!(build_face((0, 0, 0), (1, 1, 0), 0.05).is_none());
+    }
+
+    #[test]
+    fn inset_scales_with_distance() {
+        assert!(hover_inset(1.0) < hover_inset(100.0));
+        assert_eq!(hover_inset(0.0), 0.004);
+        assert_eq!(hover_inset(1.0e6), 0.25);
+    }
+}
@@ -0,0 +1,240 @@
+//! Placed point and spot lights: the uniform block `voxel.wgsl` lights
+//! with, and the viewport markers showing where they are.
+//!
+//! Lighting is forward: every voxel fragment loops over the enabled
+//! lights (at most [`MAX_LIGHTS`]) and adds each one's diffuse and
+//! highlight on top of the sun and the ambient term. No shadows — a
+//! light shines through walls — which keeps it a few multiply-adds per
+//! light and is enough to warm a corner or spot-light a model.
+//!
+//! Markers draw through the shared `LinePipeline`, like the socket
+//! pins: a six-armed star at each light in its own color, plus the
+//! cone's rim for a spot light. The selected light gets a larger
+//! yellow star so it stands out in a crowd.
+
+use bytemuck::{cast_slice, Pod, Zeroable};
+use glam::{Quat, Vec3};
+use wgpu::util::DeviceExt;
+
+use super::grid::LineVertex;
+use crate::core::{Light, LightKind, MAX_LIGHTS};
+
+/// Half-length of a marker star's arms, in world units.
+const STAR_ARM: f32 = 0.6;
+/// Length of a spot marker's cone.
+const CONE_LEN: f32 = 3.0;
+/// Segments around a spot marker's rim.
+const RIM_SEGMENTS: usize = 16;
+/// Marker color of the selected light.
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.9, 0.2, 1.0];
+/// Marker color of a switched-off light.
+const DISABLED_COLOR: [f32; 4] = [0.45, 0.45, 0.45, 1.0];
+
+/// One light in the uniform block; field packing is documented in
+/// `voxel.wgsl` (`PlacedLight`).
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+struct GpuLight {
+    position: [f32; 4],
+    color: [f32; 4],
+    direction: [f32; 4],
+    cone: [f32; 4],
+}
+
+/// Uniform block read by `voxel.wgsl` (bind group 0, binding 2).
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct LightsUniform {
+    /// x = number of lights in use.
+    count: [u32; 4],
+    lights: [GpuLight; MAX_LIGHTS],
+}
+
+impl LightsUniform {
+    /// The enabled lights among `lights`, up to [`MAX_LIGHTS`]; the
+    /// rest are left out.
+    pub fn new(lights: &[Light]) -> Self {
+        let mut uniform = Self::zeroed();
+        let enabled = lights
+            .iter()
+            .filter(|l| l.enabled && l.intensity > 0.0 && l.range > 0.0);
+        for (slot, light) in uniform.lights.iter_mut().zip(enabled) {
+            let [x, y, z] = light.position;
+            let [r, g, b] = light.color.map(|c| c.max(0.0) * light.intensity);
+            let [dx, dy, dz] = light.unit_direction();
+            let (outer, inner) = light.cone_cosines();
+            let spot = match light.kind {
+                LightKind::Point => 0.0,
+                LightKind::Spot => 1.0,
+            };
+            *slot = GpuLight {
+                position: [x, y, z, light.range],
+                color: [r, g, b, spot],
+                direction: [dx, dy, dz, outer],
+                cone: [inner, 0.0, 0.0, 0.0],
+            };
+            uniform.count[0] += 1;
+        }
+        uniform
+    }
+
+    /// Number of lights that will shine.
+    pub fn count(&self) -> u32 {
+        self.count[0]
+    }
+}
+
+/// Combined `LineList` mesh of every light's marker.
+pub struct LightMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub vertex_count: u32,
+}
+
+impl LightMesh {
+    /// Markers for `lights`, `selected` highlighted, or `None` when
+    /// there are no lights (the caller then clears its slot).
+    pub fn new(device: &wgpu::Device, lights: &[Light], selected: Option<usize>) -> Option<Self> {
+        let verts = marker_vertices(lights, selected);
+        if verts.is_empty() {
+            return None;
+        }
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Marker Vertex Buffer"),
+            contents: cast_slice(&verts),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        Some(Self {
+            vertex_buffer,
+            vertex_count: verts.len() as u32,
+        })
+    }
+}
+
+/// Marker color for a light: its own color brightened so the largest
+/// channel is 1, which keeps dim and very bright lights legible alike.
+fn marker_color(light: &Light) -> [f32; 4] {
+    if !light.enabled {
+        return DISABLED_COLOR;
+    }
+    let [r, g, b] = light.color.map(|c| c.max(0.0));
+    let max = r.max(g).max(b);
+    if max <= 1e-6 {
+        return DISABLED_COLOR;
+    }
+    [r / max, g / max, b / max, 1.0]
+}
+
+fn marker_vertices(lights: &[Light], selected: Option<usize>) -> Vec<LineVertex> {
+    let mut verts = Vec::new();
+    let mut seg = |a: Vec3, b: Vec3, c: [f32; 4]| {
+        verts.push(LineVertex::new(a.to_array(), c));
+        verts.push(LineVertex::new(b.to_array(), c));
+    };
+    for (i, light) in lights.iter().enumerate() {
+        let p = Vec3::from(light.position);
+        let (color, arm) = if selected == Some(i) {
+            (SELECTED_COLOR, STAR_ARM * 1.6)
+        } else {
+            (marker_color(light), STAR_ARM)
+        };
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            seg(p - axis * arm, p + axis * arm, color);
+        }
+        if light.kind == LightKind::Spot {
+            let dir = Vec3::from(light.unit_direction());
+            let q = Quat::from_rotation_arc(Vec3::NEG_Z, dir);
+            let radius = CONE_LEN * light.spot_angle.clamp(1.0, 89.0).to_radians().tan();
+            let rim: Vec<Vec3> = (0..RIM_SEGMENTS)
+                .map(|k| {
+                    let a = k as f32 / RIM_SEGMENTS as f32 * std::f32::consts::TAU;
+                    p + q * Vec3::new(a.cos() * radius, a.sin() * radius, -CONE_LEN)
+                })
+                .collect();
+            for k in 0..RIM_SEGMENTS {
+                seg(rim[k], rim[(k + 1) % RIM_SEGMENTS], color);
+            }
+            for k in (0..RIM_SEGMENTS).step_by(RIM_SEGMENTS / 4) {
+                seg(p, rim[k], color);
+            }
+        }
+    }
+    verts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_skips_disabled_and_caps_count() {
+        let mut lights = vec![Light::default(); MAX_LIGHTS + 3];
+        lights[0].enabled = false;
+        let uniform = LightsUniform::new(&lights);
+        assert_eq!(uniform.count(), MAX_LIGHTS as u32);
+        assert!(LightsUniform::new(&[]).count() == 0);
+    }
+
+    #[test]
+    fn uniform_packs_color_range_and_cone() {
+        let light = Light {
+            kind: LightKind::Spot,
+            position: [1.0, 2.0, 3.0],
+            direction: [0.0, 0.0, -2.0],
+            color: [1.0, 0.5, 0.0],
+            intensity: 2.0,
+            range: 10.0,
+            ..Light::default()
+        };
+        let uniform = LightsUniform::new(std::slice::from_ref(&light));
+        let gpu = uniform.lights[0];
+        assert_eq!(gpu.position, [1.0, 2.0, 3.0, 10.0]);
+        assert_eq!(gpu.color, [2.0, 1.0, 0.0, 1.0]);
+        assert_eq!(&gpu.direction[..3], &[0.0, 0.0, -1.0]);
+        let (outer, inner) = light.cone_cosines();
+        assert_eq!((gpu.direction[3], gpu.cone[0]), (outer, inner));
+    }
+
+    #[test]
+    fn markers_per_kind() {
+        let point = Light::default();
+        let spot = Light {
+            kind: LightKind::Spot,
+            ..Light::default()
+        };
+        // Star: 3 segments; spot adds the rim and 4 spokes.
+        assert_eq!(marker_vertices(std::slice::from_ref(&point), None).len(), 6);
+        assert_eq!(
+            marker_vertices(std::slice::from_ref(&spot), None).len(),
+            2 * (3 + RIM_SEGMENTS + 4)
+        );
+        assert!(marker_vertices(&[], None).is_empty());
+    }
+
+    #[test]
+    fn spot_rim_sits_along_the_direction() {
+        let spot = Light {
+            kind: LightKind::Spot,
+            position: [0.0; 3],
+            direction: [1.0, 0.0, 0.0],
+            ..Light::default()
+        };
+        let verts = marker_vertices(std::slice::from_ref(&spot), None);
+        // First rim vertex follows the star's 6.
+        let rim = Vec3::from(verts[6].position);
+        assert!((rim.x - CONE_LEN).abs() < 1e-4, "{rim}");
+    }
+
+    #[test]
+    fn marker_color_normalizes_brightness() {
+        let light = Light {
+            color: [0.2, 0.1, 0.0],
+            ..Light::default()
+        };
+        assert_eq!(marker_color(&light), [1.0, 0.5, 0.0, 1.0]);
+        let off = Light {
+            enabled: false,
+            ..Light::default()
+        };
+        assert_eq!(marker_color(&off), DISABLED_COLOR);
+    }
+}
@@ -0,0 +1,63 @@
+//! Ruler overlay for the `Measure` tool.
+//!
+//! Outlines the two measured cells and joins their centers with a
+//! straight segment, through the shared `LinePipeline`. Green, so it
+//! reads apart from the selection (yellow), hover (white) and bounds
+//! (blue); the status bar and the dimension label use the same color.
+//! The segment runs through the model between the cells, so the depth
+//! test hides the stretch inside solid voxels — the outlined ends and
+//! the label carry the reading.
+
+use bytemuck::cast_slice;
+use wgpu::util::DeviceExt;
+
+use super::grid::LineVertex;
+use super::selection::aabb_lines;
+
+const MEASURE_COLOR: [f32; 4] = [0.47, 1.0, 0.59, 1.0];
+
+/// GPU buffer for one ruler.
+pub struct MeasureMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub vertex_count: u32,
+}
+
+impl MeasureMesh {
+    pub fn new(device: &wgpu::Device, start: (i32, i32, i32), end: (i32, i32, i32)) -> Self {
+        let vertices = measure_lines(start, end);
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Measure Vertex Buffer"),
+            contents: cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        Self {
+            vertex_buffer,
+            vertex_count: vertices.len() as u32,
+        }
+    }
+}
+
+fn measure_lines(start: (i32, i32, i32), end: (i32, i32, i32)) -> Vec<LineVertex> {
+    let center = |c: (i32, i32, i32)| [c.0 as f32 + 0.5, c.1 as f32 + 0.5, c.2 as f32 + 0.5];
+    let mut vertices = aabb_lines(start, start, MEASURE_COLOR);
+    if end != start {
+        vertices.extend(aabb_lines(end, end, MEASURE_COLOR));
+    }
+    vertices.push(LineVertex::new(center(start), MEASURE_COLOR));
+    vertices.push(LineVertex::new(center(end), MEASURE_COLOR));
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outlines_both_cells_and_joins_their_centers() {
+        let v = measure_lines((0, 0, 0), (3, 0, 0));
+        // Two 12-edge boxes plus the center segment.
+        assert_eq!(v.len(), 2 * 24 + 2);
+        // A single-cell ruler draws its box once.
+        assert_eq!(measure_lines((1, 1, 1), (1, 1, 1)).len(), 24 + 2);
+    }
+}
@@ -5,24 +5,78 @@
 //! - Shader compilation
 //! - Render pipeline management
 //! - Mesh rendering
+//! - Progressive path-traced preview (`path_tracer`)
+//! - Approximate voxel global illumination (`gi`)
+//! - Toon shading and outlines (`toon`)
+//! - Viewport backgrounds and image-based ambient (`background`)
+//! - Placed point and spot lights (`lights`)
+//! - The translate gizmo's handles (`gizmo`)
+//! - Reference image planes and ghost models (`reference`)
+//! - Offscreen project thumbnails (`thumbnail`)
+//! - GPU ID-buffer picking (`picking`)
 
 mod camera;
+mod camera_path;
 mod pipeline;
 mod gpu_mesh;
+mod chunk_batch;
 mod grid;
 mod selection;
+mod bounds;
+mod measure;
 mod socket;
+mod hover;
+mod work_plane;
+mod path_tracer;
+mod gi;
+mod toon;
+mod background;
+mod lights;
+mod gizmo;
+mod reference;
+mod thumbnail;
+mod picking;
+mod sprite;
 
-pub use camera::{Camera, CameraController, CameraUniform};
-pub use pipeline::RenderPipeline;
+pub use camera::{
+    zoom_ortho_half_height, Camera, CameraController, CameraPose, CameraSensitivity, CameraTween,
+    CameraUniform, CameraView, Easing, ViewPane, DEFAULT_ORTHO_HALF_HEIGHT, DEFAULT_TWEEN_SECS,
+};
+pub use camera_path::{CameraKey, CameraPath};
+pub use pipeline::{Msaa, RenderPipeline};
 pub use gpu_mesh::GpuMesh;
+pub use chunk_batch::{coalesce, ChunkBatch, RangeAllocator, PAGE_BYTES};
 pub use grid::{AxisMesh, GridMesh, LinePipeline, LineVertex};
 pub use selection::SelectionMesh;
+pub use bounds::BoundsMesh;
+pub use measure::MeasureMesh;
 pub use socket::SocketMesh;
+pub use hover::{hover_inset, HoverMesh};
+pub use work_plane::WorkPlaneMesh;
+pub use gi::{GiSettings, GiVolume, LightVolume, GI_GRID_MAX_DIM};
+pub use toon::{ToonOutline, ToonSettings};
+pub use background::{AmbientProbe, BackgroundPass, EnvironmentMap};
+pub use lights::{LightMesh, LightsUniform};
+pub use gizmo::GizmoMesh;
+pub use reference::{
+    fit_reference_image, ghost_mesh, ReferencePass, ReferenceVertex, REFERENCE_MAX_DIM,
+};
+pub use picking::{decode_pick_id, pick_id, GpuPicker, PickRequest, PickSample, PICK_WRAP};
+pub use sprite::{
+    outline, sprite_sheet, SpriteDirections, SpriteFrame, SpriteView, SPRITE_PADDING,
+};
+pub use thumbnail::{
+    downsample, thumbnail_camera, turntable_camera, OffscreenStyle, THUMBNAIL_SIZE,
+};
+pub use path_tracer::{
+    PathTraceSettings, PathTracer, VolumeError, VoxelVolume, PATH_TRACE_MAX_CELLS,
+};
 
 use crate::mesh::ChunkMesh;
-use crate::core::ChunkPos;
-use std::collections::HashMap;
+use crate::core::{
+    Background, BackgroundKind, ChunkPos, Light, ReferenceImage, WorldBounds, CHUNK_SIZE,
+};
+use crate::editor::{Gizmo, GizmoHandle};
 use std::sync::Arc;
 
 /// Main renderer state
@@ -33,9 +87,13 @@ pub struct Renderer {
     pub config: wgpu::SurfaceConfiguration,
     pub pipeline: RenderPipeline,
     pub line_pipeline: LinePipeline,
+    /// Lines drawn over everything, for the move gizmo.
+    pub overlay_line_pipeline: LinePipeline,
     pub camera: Camera,
     pub camera_controller: CameraController,
-    pub chunk_meshes: HashMap<ChunkPos, GpuMesh>,
+    /// Every chunk mesh, packed into shared buffers so the opaque pass
+    /// is a few draws per page rather than one per chunk.
+    pub chunks: ChunkBatch,
     pub depth_texture: wgpu::TextureView,
     pub grid_mesh: GridMesh,
     pub axis_mesh: AxisMesh,
@@ -53,19 +111,111 @@ pub struct Renderer {
     /// `LinePipeline` as the grid/axes — bright yellow, 12 edges.
     /// `None` when no selection is active and no drag is in progress.
     pub selection_mesh: Option<SelectionMesh>,
+    /// Wireframe of the active layer's world bounds; `None` for an
+    /// unbounded layer or with the overlay turned off.
+    pub bounds_mesh: Option<BoundsMesh>,
+    /// The `Measure` tool's ruler; `None` when there's none to show.
+    pub measure_mesh: Option<MeasureMesh>,
     /// Translucent voxel-content ghost shown while dragging a box
     /// selection to a new location — the picked-up voxels following
     /// the cursor, alpha-blended through `transparent_pipeline` like
     /// the brush hover overlay. `None` unless a move drag is in
     /// progress. Owned solely by `App::update_selection_visualization`.
     pub move_ghost_mesh: Option<GpuMesh>,
+    /// Translucent, tinted ghosts of the animation frames before and
+    /// after the shown one (onion skinning), through
+    /// `transparent_pipeline`. `None` when onion skin is off. Owned by
+    /// `App::update_onion_skin`.
+    pub onion_skin_mesh: Option<GpuMesh>,
+    /// The ghost reference model, through `transparent_pipeline`.
+    /// `None` without one, or while it's hidden. Owned by the App.
+    pub ghost_model_mesh: Option<GpuMesh>,
+    /// Translucent ghost of the rig's posed bones (pose preview),
+    /// through `transparent_pipeline`. `None` while nothing is posed.
+    /// Owned by `App::update_pose_preview`.
+    pub pose_preview_mesh: Option<GpuMesh>,
     /// Gizmo lines for the named sockets (attachment points), drawn
     /// through the `LinePipeline` like the selection wireframe. `None`
     /// when the scene has no sockets. Rebuilt by
     /// `App::update_socket_visualization` when the socket set changes.
     pub socket_mesh: Option<SocketMesh>,
-    /// Whether wireframe mode is supported
-    pub wireframe_supported: bool,
+    /// Markers for the placed lights, through the `LinePipeline`.
+    /// `None` when there are no lights or markers are hidden. Rebuilt
+    /// by the App when the lights change.
+    pub light_mesh: Option<LightMesh>,
+    /// The move gizmo, through `overlay_line_pipeline`. `None` while
+    /// it has nothing to move. Owned by `App::update_gizmo_visualization`.
+    pub gizmo_mesh: Option<GizmoMesh>,
+    /// Outline + hit-face tint for the voxel under the cursor. `None`
+    /// when nothing real is hovered. Owned by
+    /// `App::update_hover_highlight`.
+    pub hover_mesh: Option<HoverMesh>,
+    /// Sheet + grid + border for the work-plane slice. `None` while
+    /// work-plane mode is off. Owned by
+    /// `App::update_work_plane_visualization`.
+    pub work_plane_mesh: Option<WorkPlaneMesh>,
+    /// Orthographic half-heights for the quad-view Top / Front / Side
+    /// panes, indexed by `ViewPane::ortho_index`. Lives here (not in
+    /// `ViewportSettings`) because it's camera state like `camera`
+    /// itself — reset with the session, not persisted.
+    pub quad_zoom: [f32; 3],
+    /// Path-traced preview state. `None` until the mode is first
+    /// enabled — see `path_tracer_mut` — so sessions that never use it
+    /// pay for neither the pipelines nor the accumulation buffer.
+    pub path_tracer: Option<PathTracer>,
+    /// ID-buffer picker, created on the first pick — see `pick`.
+    pub picker: Option<GpuPicker>,
+    /// Bumped on every chunk-mesh upload / removal / clear — i.e. each
+    /// time the world's visible contents change. Consumers that keep
+    /// their own copy of the scene (the path tracer's voxel volume)
+    /// compare against it to know when to rebuild.
+    pub scene_revision: u64,
+    /// Build `GpuMesh::edge_buffer`s on upload. Set while wireframe
+    /// mode is on (see [`Self::set_edge_buffers`]); off by default so
+    /// the usual session doesn't pay for the extra index buffers.
+    pub edge_buffers: bool,
+    /// Current anti-aliasing level of the main pass. Change it through
+    /// [`Self::set_msaa`], which rebuilds everything sized by it.
+    pub msaa: Msaa,
+    /// Levels the adapter supports for both the surface and depth
+    /// formats (always includes `Msaa::Off`).
+    pub msaa_supported: Vec<Msaa>,
+    /// Multisampled color target the main pass draws into and resolves
+    /// to the surface. `None` with MSAA off.
+    msaa_target: Option<wgpu::TextureView>,
+    /// Toon shading in effect, for the viewport and offscreen renders
+    /// alike; `None` for the regular look. Change it through
+    /// [`Self::set_toon`].
+    pub toon: Option<ToonSettings>,
+    /// Outline post-process for toon shading.
+    pub toon_outline: ToonOutline,
+    /// Background in effect, for the viewport and offscreen renders
+    /// alike. Change it through [`Self::set_background`].
+    pub background: Background,
+    /// Gradient/image background draw.
+    pub background_pass: BackgroundPass,
+    /// What's kept of the background image for lighting; `None` with
+    /// no image loaded.
+    ambient_probe: Option<AmbientProbe>,
+    /// Reference image planes and their uploaded images.
+    pub reference_pass: ReferencePass,
+}
+
+/// `chunks` sorted by decreasing view depth of their centers (distance
+/// along the camera's forward axis, which is what the depth buffer
+/// orders by in both perspective and orthographic panes).
+fn back_to_front(chunks: impl Iterator<Item = ChunkPos>, camera: &Camera) -> Vec<ChunkPos> {
+    let forward = camera.forward();
+    let half = CHUNK_SIZE as f32 / 2.0;
+    let mut keyed: Vec<(f32, ChunkPos)> = chunks
+        .map(|pos| {
+            let (x, y, z) = pos.world_origin();
+            let center = glam::Vec3::new(x as f32 + half, y as f32 + half, z as f32 + half);
+            ((center - camera.position).dot(forward), pos)
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().map(|(_, pos)| pos).collect()
 }
 
 impl Renderer {
@@ -94,22 +244,11 @@ impl Renderer {
 
         log::info!("Using GPU: {}", adapter.get_info().name);
 
-        // Check if wireframe mode is supported
-        let adapter_features = adapter.features();
-        let wireframe_supported = adapter_features.contains(wgpu::Features::POLYGON_MODE_LINE);
-
-        // Request device with optional wireframe support
-        let required_features = if wireframe_supported {
-            wgpu::Features::POLYGON_MODE_LINE
-        } else {
-            wgpu::Features::empty()
-        };
-
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: Some("Voxelith Device"),
-                    required_features,
+                    required_features: wgpu::Features::empty(),
                     required_limits: wgpu::Limits::default(),
                     memory_hints: wgpu::MemoryHints::default(),
                 },
@@ -117,12 +256,6 @@ impl Renderer {
             )
             .await?;
 
-        if wireframe_supported {
-            log::info!("Wireframe mode supported");
-        } else {
-            log::info!("Wireframe mode not supported on this GPU");
-        }
-
         let device = Arc::new(device);
         let queue = Arc::new(queue);
 
@@ -135,6 +268,21 @@ impl Renderer {
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
 
+        // MSAA levels usable for both main-pass attachments.
+        let color_flags = adapter.get_texture_format_features(surface_format).flags;
+        let depth_flags = adapter
+            .get_texture_format_features(wgpu::TextureFormat::Depth32Float)
+            .flags;
+        let msaa_supported: Vec<Msaa> = Msaa::ALL
+            .into_iter()
+            .filter(|m| {
+                let n = m.samples();
+                n == 1
+                    || (color_flags.sample_count_supported(n)
+                        && depth_flags.sample_count_supported(n))
+            })
+            .collect();
+
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
@@ -147,11 +295,17 @@ impl Renderer {
         };
         surface.configure(&device, &config);
 
-        // Create render pipeline with optional wireframe support
-        let pipeline = RenderPipeline::new_with_features(&device, surface_format, required_features);
+        // Start without MSAA; the app applies the saved level through
+        // `set_msaa` on its first frame.
+        let pipeline = RenderPipeline::new(&device, surface_format, 1);
 
         // Create line pipeline (uses same camera bind group layout)
-        let line_pipeline = LinePipeline::new(&device, surface_format, &pipeline.camera_bind_group_layout);
+        let line_pipeline =
+            LinePipeline::new(&device, surface_format, &pipeline.camera_bind_group_layout, 1);
+        let overlay_line_pipeline =
+            LinePipeline::overlay(&device, surface_format, &pipeline.camera_bind_group_layout, 1);
+        let reference_pass =
+            ReferencePass::new(&device, surface_format, &pipeline.camera_bind_group_layout, 1);
 
         // Create camera
         let camera = Camera::new(
@@ -170,11 +324,14 @@ impl Renderer {
         camera_controller.sync_orbit_state_from_camera(&camera);
 
         // Create depth texture
-        let depth_texture = Self::create_depth_texture(&device, &config);
+        let depth_texture = Self::create_depth_texture(&device, &config, 1);
+        let toon_outline = ToonOutline::new(&device, surface_format, 1, &depth_texture);
+        let background_pass = BackgroundPass::new(&device, surface_format, 1);
 
         // Create grid and axis meshes
         let grid_mesh = GridMesh::new(&device, 20, 1.0);
         let axis_mesh = AxisMesh::new(&device, 10.0);
+        let chunks = ChunkBatch::new(&device);
 
         Ok(Self {
             device,
@@ -183,18 +340,41 @@ impl Renderer {
             config,
             pipeline,
             line_pipeline,
+            overlay_line_pipeline,
             camera,
             camera_controller,
-            chunk_meshes: HashMap::new(),
+            chunks,
             depth_texture,
             grid_mesh,
             axis_mesh,
             preview_mesh: None,
             brush_preview_mesh: None,
             selection_mesh: None,
+            bounds_mesh: None,
+            measure_mesh: None,
             move_ghost_mesh: None,
+            onion_skin_mesh: None,
+            ghost_model_mesh: None,
+            pose_preview_mesh: None,
             socket_mesh: None,
-            wireframe_supported,
+            light_mesh: None,
+            gizmo_mesh: None,
+            hover_mesh: None,
+            work_plane_mesh: None,
+            quad_zoom: [DEFAULT_ORTHO_HALF_HEIGHT; 3],
+            path_tracer: None,
+            picker: None,
+            scene_revision: 0,
+            edge_buffers: false,
+            msaa: Msaa::Off,
+            msaa_supported,
+            msaa_target: None,
+            toon: None,
+            toon_outline,
+            background: Background::default(),
+            background_pass,
+            ambient_probe: None,
+            reference_pass,
         })
     }
 
@@ -202,6 +382,7 @@ impl Renderer {
     fn create_depth_texture(
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
     ) -> wgpu::TextureView {
         let size = wgpu::Extent3d {
             width: config.width,
@@ -213,7 +394,7 @@ impl Renderer {
             label: Some("Depth Texture"),
             size,
             mip_level_count: 1,
-            sample_count: 1,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth32Float,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
@@ -223,17 +404,244 @@ impl Renderer {
         texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
 
+    /// Multisampled color target matching the surface, or `None` for
+    /// a single sample.
+    fn create_msaa_target(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Option<wgpu::TextureView> {
+        if sample_count <= 1 {
+            return None;
+        }
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("MSAA Color Target"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
+
+    /// Switch the main pass to `requested` anti-aliasing, or the nearest
+    /// lower level the adapter supports. Rebuilds the voxel and line
+    /// pipelines plus the depth and color targets — only when the level
+    /// actually changes. Returns the level now in effect.
+    pub fn set_msaa(&mut self, requested: Msaa) -> Msaa {
+        let msaa = requested.clamp_to(&self.msaa_supported);
+        if msaa == self.msaa {
+            return msaa;
+        }
+        let samples = msaa.samples();
+        self.pipeline.rebuild(&self.device, samples);
+        self.line_pipeline = LinePipeline::new(
+            &self.device,
+            self.config.format,
+            &self.pipeline.camera_bind_group_layout,
+            samples,
+        );
+        self.overlay_line_pipeline = LinePipeline::overlay(
+            &self.device,
+            self.config.format,
+            &self.pipeline.camera_bind_group_layout,
+            samples,
+        );
+        self.depth_texture = Self::create_depth_texture(&self.device, &self.config, samples);
+        self.msaa_target = Self::create_msaa_target(&self.device, &self.config, samples);
+        self.toon_outline.rebuild(&self.device, samples, &self.depth_texture);
+        self.background_pass.rebuild(&self.device, samples);
+        self.reference_pass
+            .rebuild(&self.device, &self.pipeline.camera_bind_group_layout, samples);
+        self.msaa = msaa;
+        log::info!("Viewport anti-aliasing: {}", msaa.label());
+        msaa
+    }
+
+    /// Switch toon shading on with `toon`'s settings, or off with
+    /// `None`. The lighting bands take effect at the next camera upload.
+    pub fn set_toon(&mut self, toon: Option<&ToonSettings>) {
+        self.pipeline.set_toon_bands(toon.map(|t| t.bands));
+        self.toon = toon.cloned();
+    }
+
+    /// Ink the toon outlines over the pane `rect` (`[x, y, width,
+    /// height]` in pixels) of the frame just drawn into `surface` from
+    /// `camera`. Records its own pass, so call it after the main pass
+    /// in the same encoder; nothing happens unless toon outlines are on.
+    pub fn draw_toon_outline(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        surface: &wgpu::TextureView,
+        camera: &Camera,
+        rect: [u32; 4],
+    ) {
+        let Some(toon) = self.toon.as_ref().filter(|t| t.outlines) else {
+            return;
+        };
+        self.toon_outline.write_params(&self.queue, camera, rect, toon, 1);
+        let (target, resolve_target) = self.color_target(surface);
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Toon Outline Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let [x, y, w, h] = rect;
+        render_pass.set_viewport(x as f32, y as f32, w as f32, h as f32, 0.0, 1.0);
+        render_pass.set_scissor_rect(x, y, w, h);
+        self.toon_outline.draw(&mut render_pass, &self.toon_outline.depth_bind_group);
+    }
+
+    /// Switch to `background`. Its image, if any, is uploaded
+    /// separately through [`Self::set_environment_map`].
+    pub fn set_background(&mut self, background: &Background) {
+        self.background = background.clone();
+        self.update_sky_ambient();
+    }
+
+    /// Upload the background image, or drop it with `None`.
+    pub fn set_environment_map(&mut self, map: Option<&EnvironmentMap>) {
+        self.background_pass.set_image(&self.device, &self.queue, map);
+        self.ambient_probe = map.map(EnvironmentMap::probe);
+        self.update_sky_ambient();
+    }
+
+    /// Light the model from the background image when it's shown and
+    /// asked to; the flat ambient otherwise.
+    fn update_sky_ambient(&mut self) {
+        let bg = &self.background;
+        let sky = self
+            .ambient_probe
+            .as_ref()
+            .filter(|_| bg.kind == BackgroundKind::Hdri && bg.hdri_lighting)
+            .map(|probe| {
+                let intensity = bg.hdri_intensity.max(0.0);
+                probe
+                    .irradiance(bg.hdri_rotation)
+                    .map(|e| e.map(|c| c * intensity))
+            });
+        self.pipeline.gi.set_sky_ambient(sky);
+    }
+
+    /// Color the main pass clears to: the solid color, or what shows
+    /// under a gradient or image (the gradient's bottom, or the solid
+    /// color while no image is loaded).
+    pub fn clear_color(&self) -> wgpu::Color {
+        let bg = &self.background;
+        let [r, g, b] = match bg.kind {
+            BackgroundKind::Gradient => bg.bottom,
+            BackgroundKind::Solid | BackgroundKind::Hdri => bg.color,
+        };
+        wgpu::Color {
+            r: r as f64,
+            g: g as f64,
+            b: b as f64,
+            a: 1.0,
+        }
+    }
+
+    /// Draw the gradient or image background as seen from `camera`,
+    /// first thing in the main pass. Nothing to draw for a solid
+    /// background (the clear color is it) or an image not yet loaded.
+    pub fn draw_background<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, camera: &Camera) {
+        let draws = match self.background.kind {
+            BackgroundKind::Solid => false,
+            BackgroundKind::Gradient => true,
+            BackgroundKind::Hdri => self.background_pass.has_image,
+        };
+        if !draws {
+            return;
+        }
+        self.background_pass.write_params(&self.queue, camera, &self.background);
+        self.background_pass.draw(render_pass);
+    }
+
+    /// `(view, resolve_target)` for a main-pass color attachment that
+    /// ends up on `surface`: the multisampled target resolving into it
+    /// with MSAA on, the surface itself otherwise.
+    pub fn color_target<'a>(
+        &'a self,
+        surface: &'a wgpu::TextureView,
+    ) -> (&'a wgpu::TextureView, Option<&'a wgpu::TextureView>) {
+        match &self.msaa_target {
+            Some(target) => (target, Some(surface)),
+            None => (surface, None),
+        }
+    }
+
     /// Handle window resize
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
             self.config.width = new_size.width;
             self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
-            self.depth_texture = Self::create_depth_texture(&self.device, &self.config);
+            let samples = self.msaa.samples();
+            self.depth_texture = Self::create_depth_texture(&self.device, &self.config, samples);
+            self.msaa_target = Self::create_msaa_target(&self.device, &self.config, samples);
+            self.toon_outline.depth_bind_group =
+                self.toon_outline.bind_depth(&self.device, &self.depth_texture);
             self.camera.aspect = new_size.width as f32 / new_size.height as f32;
+            if let Some(tracer) = &mut self.path_tracer {
+                tracer.resize(&self.device, new_size.width, new_size.height);
+            }
+            if let Some(picker) = &mut self.picker {
+                picker.resize(&self.device, new_size.width, new_size.height);
+            }
         }
     }
 
+    /// The path tracer, created on first use at the current surface
+    /// size and format.
+    pub fn path_tracer_mut(&mut self) -> &mut PathTracer {
+        let (device, config) = (&self.device, &self.config);
+        self.path_tracer.get_or_insert_with(|| {
+            PathTracer::new(device, config.format, config.width, config.height)
+        })
+    }
+
+    /// Start a GPU pick of the chunk meshes at `request.pixel` (see
+    /// `GpuPicker::pick` for when it's skipped). The result arrives
+    /// through `poll_pick`.
+    pub fn pick(&mut self, request: PickRequest) {
+        let (device, queue, config) = (&self.device, &self.queue, &self.config);
+        self.picker
+            .get_or_insert_with(|| GpuPicker::new(device, config.width, config.height))
+            .pick(device, queue, &self.chunks, request);
+    }
+
+    /// The last started pick's result, once it has been read back.
+    pub fn poll_pick(&mut self) -> Option<PickSample> {
+        self.picker.as_mut()?.poll(&self.device)
+    }
+
+    /// Camera for one quad-view pane, with the pane's own aspect ratio
+    /// and (for the orthographic panes) its own zoom. Used both to fill
+    /// the pane's camera uniform and to build its picking ray, so what
+    /// is drawn and what is hit always agree.
+    pub fn pane_camera(&self, pane: ViewPane) -> Camera {
+        let [_, _, w, h] = pane.rect(self.config.width, self.config.height);
+        let half_height = pane
+            .ortho_index()
+            .map_or(DEFAULT_ORTHO_HALF_HEIGHT, |i| self.quad_zoom[i]);
+        pane.camera(&self.camera, w as f32 / h as f32, half_height)
+    }
+
     /// Update grid mesh with new settings
     pub fn update_grid(&mut self, size: i32, spacing: f32) {
         self.grid_mesh = GridMesh::new(&self.device, size, spacing);
@@ -241,27 +649,69 @@ impl Renderer {
 
     /// Upload a chunk mesh to the GPU
     pub fn upload_mesh(&mut self, mesh: &ChunkMesh) {
+        self.scene_revision += 1;
         if mesh.is_empty() {
-            self.chunk_meshes.remove(&mesh.chunk_pos);
+            self.chunks.remove(mesh.chunk_pos);
             return;
         }
+        // Re-meshed chunks are written in place when the new geometry
+        // fits their slot (`ChunkBatch::upload`).
+        self.chunks
+            .upload(&self.device, &self.queue, mesh, self.edge_buffers);
+    }
+
+    /// Switch edge-buffer building for wireframe mode. Turning it off
+    /// frees the existing edge buffers at once; turning it on only
+    /// affects later uploads, so the caller re-meshes every chunk.
+    pub fn set_edge_buffers(&mut self, enabled: bool) {
+        self.edge_buffers = enabled;
+        if !enabled {
+            self.chunks.drop_edges();
+        }
+    }
 
-        let gpu_mesh = GpuMesh::new(&self.device, mesh);
-        self.chunk_meshes.insert(mesh.chunk_pos, gpu_mesh);
+    /// Draw the chunk meshes' triangle edges: lit, vertex-colored lines
+    /// on their own (`shaded == false`), or dark lines over the
+    /// already-drawn shaded meshes. Chunks whose edge buffer isn't
+    /// built yet (re-mesh still in flight) draw their triangles
+    /// instead in lines-only mode, so nothing blinks out.
+    pub fn draw_chunk_edges<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, shaded: bool) {
+        if shaded {
+            render_pass.set_pipeline(&self.pipeline.edge_pipeline);
+            self.pipeline.bind_groups(render_pass);
+            self.chunks.draw_edges(render_pass);
+            return;
+        }
+        self.pipeline.bind_groups(render_pass);
+        render_pass.set_pipeline(&self.pipeline.wireframe_pipeline);
+        self.chunks.draw_edges(render_pass);
+        render_pass.set_pipeline(&self.pipeline.render_pipeline);
+        self.chunks.draw_without_edges(render_pass);
+    }
+
+    /// Draw the opaque range of every chunk mesh through the main
+    /// pipeline.
+    pub fn draw_opaque_chunks<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline.render_pipeline);
+        self.pipeline.bind_groups(render_pass);
+        self.chunks.draw_opaque(render_pass);
     }
 
     /// Remove a chunk mesh
     pub fn remove_mesh(&mut self, chunk_pos: ChunkPos) {
-        self.chunk_meshes.remove(&chunk_pos);
+        self.scene_revision += 1;
+        self.chunks.remove(chunk_pos);
+    }
+
+    /// Drop every chunk mesh (scene replaced / cleared).
+    pub fn clear_meshes(&mut self) {
+        self.scene_revision += 1;
+        self.chunks.clear();
     }
 
     /// Replace the procgen preview overlay. Empty mesh -> clear.
     pub fn set_preview_mesh(&mut self, mesh: &ChunkMesh) {
-        if mesh.is_empty() {
-            self.preview_mesh = None;
-        } else {
-            self.preview_mesh = Some(GpuMesh::new(&self.device, mesh));
-        }
+        GpuMesh::replace(&mut self.preview_mesh, &self.device, &self.queue, mesh);
     }
 
     /// Clear the procgen preview overlay.
@@ -275,18 +725,34 @@ impl Renderer {
     pub fn draw_preview<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         if let Some(preview) = &self.preview_mesh {
             render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
-            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            self.pipeline.bind_groups(render_pass);
             preview.draw(render_pass);
         }
     }
 
+    /// Draw the translucent voxels (glass, water) of every chunk mesh
+    /// through the transparent pipeline, farthest chunk first so the
+    /// blend composites back to front. Call after all opaque geometry;
+    /// depth writes are off, so translucent chunks never hide each
+    /// other. Sorting is per chunk, not per triangle — overlapping
+    /// translucent faces within one chunk can still blend out of order.
+    pub fn draw_transparent_chunks<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        camera: &Camera,
+    ) {
+        let order = back_to_front(self.chunks.translucent_chunks(), camera);
+        if order.is_empty() {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
+        self.pipeline.bind_groups(render_pass);
+        self.chunks.draw_transparent(render_pass, &order);
+    }
+
     /// Replace the brush hover overlay. Empty mesh -> clear.
     pub fn set_brush_preview_mesh(&mut self, mesh: &ChunkMesh) {
-        if mesh.is_empty() {
-            self.brush_preview_mesh = None;
-        } else {
-            self.brush_preview_mesh = Some(GpuMesh::new(&self.device, mesh));
-        }
+        GpuMesh::replace(&mut self.brush_preview_mesh, &self.device, &self.queue, mesh);
     }
 
     /// Clear the brush hover overlay.
@@ -299,18 +765,14 @@ impl Renderer {
     pub fn draw_brush_preview<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         if let Some(preview) = &self.brush_preview_mesh {
             render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
-            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            self.pipeline.bind_groups(render_pass);
             preview.draw(render_pass);
         }
     }
 
     /// Replace the move-drag voxel ghost overlay. Empty mesh -> clear.
     pub fn set_move_ghost_mesh(&mut self, mesh: &ChunkMesh) {
-        if mesh.is_empty() {
-            self.move_ghost_mesh = None;
-        } else {
-            self.move_ghost_mesh = Some(GpuMesh::new(&self.device, mesh));
-        }
+        GpuMesh::replace(&mut self.move_ghost_mesh, &self.device, &self.queue, mesh);
     }
 
     /// Clear the move-drag voxel ghost overlay.
@@ -324,7 +786,67 @@ impl Renderer {
     pub fn draw_move_ghost<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         if let Some(ghost) = &self.move_ghost_mesh {
             render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
-            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            self.pipeline.bind_groups(render_pass);
+            ghost.draw(render_pass);
+        }
+    }
+
+    /// Replace the onion-skin overlay. Empty mesh -> clear.
+    pub fn set_onion_skin_mesh(&mut self, mesh: &ChunkMesh) {
+        GpuMesh::replace(&mut self.onion_skin_mesh, &self.device, &self.queue, mesh);
+    }
+
+    /// Clear the onion-skin overlay.
+    pub fn clear_onion_skin(&mut self) {
+        self.onion_skin_mesh = None;
+    }
+
+    /// Draw the onion-skin ghosts. Same depth/blend rules as
+    /// `draw_preview` — call after opaque geometry.
+    pub fn draw_onion_skin<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(ghost) = &self.onion_skin_mesh {
+            render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
+            self.pipeline.bind_groups(render_pass);
+            ghost.draw(render_pass);
+        }
+    }
+
+    /// Replace the ghost reference model. Empty mesh -> clear.
+    pub fn set_ghost_model_mesh(&mut self, mesh: &ChunkMesh) {
+        GpuMesh::replace(&mut self.ghost_model_mesh, &self.device, &self.queue, mesh);
+    }
+
+    /// Clear the ghost reference model.
+    pub fn clear_ghost_model(&mut self) {
+        self.ghost_model_mesh = None;
+    }
+
+    /// Draw the ghost reference model. Same depth/blend rules as
+    /// `draw_preview` — call after opaque geometry.
+    pub fn draw_ghost_model<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(ghost) = &self.ghost_model_mesh {
+            render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
+            self.pipeline.bind_groups(render_pass);
+            ghost.draw(render_pass);
+        }
+    }
+
+    /// Replace the pose-preview overlay. Empty mesh -> clear.
+    pub fn set_pose_preview_mesh(&mut self, mesh: &ChunkMesh) {
+        GpuMesh::replace(&mut self.pose_preview_mesh, &self.device, &self.queue, mesh);
+    }
+
+    /// Clear the pose-preview overlay.
+    pub fn clear_pose_preview(&mut self) {
+        self.pose_preview_mesh = None;
+    }
+
+    /// Draw the pose-preview ghost. Same depth/blend rules as
+    /// `draw_preview` — call after opaque geometry.
+    pub fn draw_pose_preview<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(ghost) = &self.pose_preview_mesh {
+            render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
+            self.pipeline.bind_groups(render_pass);
             ghost.draw(render_pass);
         }
     }
@@ -357,6 +879,43 @@ impl Renderer {
         }
     }
 
+    /// Show the wireframe of `bounds`, or nothing with `None`.
+    pub fn set_bounds_mesh(&mut self, bounds: Option<&WorldBounds>) {
+        self.bounds_mesh = bounds.map(|b| BoundsMesh::new(&self.device, b));
+    }
+
+    /// Draw the world-bounds wireframe (if any) with the line pipeline,
+    /// depth-tested like the selection box.
+    pub fn draw_bounds<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(bounds) = &self.bounds_mesh {
+            render_pass.set_pipeline(&self.line_pipeline.render_pipeline);
+            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, bounds.vertex_buffer.slice(..));
+            render_pass.draw(0..bounds.vertex_count, 0..1);
+        }
+    }
+
+    /// Show the measure ruler between the `start` and `end` cells.
+    pub fn set_measure_mesh(&mut self, start: (i32, i32, i32), end: (i32, i32, i32)) {
+        self.measure_mesh = Some(MeasureMesh::new(&self.device, start, end));
+    }
+
+    /// Clear the measure ruler.
+    pub fn clear_measure(&mut self) {
+        self.measure_mesh = None;
+    }
+
+    /// Draw the measure ruler (if any) with the line pipeline,
+    /// depth-tested like the selection box.
+    pub fn draw_measure<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(measure) = &self.measure_mesh {
+            render_pass.set_pipeline(&self.line_pipeline.render_pipeline);
+            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, measure.vertex_buffer.slice(..));
+            render_pass.draw(0..measure.vertex_count, 0..1);
+        }
+    }
+
     /// Replace the socket gizmo overlay from a list of `(position,
     /// normal)` pairs. An empty list clears the slot.
     pub fn set_socket_mesh(&mut self, sockets: &[([f32; 3], [f32; 3])]) {
@@ -379,6 +938,139 @@ impl Renderer {
         }
     }
 
+    /// Replace the light markers; `selected` is drawn highlighted. An
+    /// empty list clears the slot.
+    pub fn set_light_mesh(&mut self, lights: &[Light], selected: Option<usize>) {
+        self.light_mesh = LightMesh::new(&self.device, lights, selected);
+    }
+
+    /// Clear the light markers.
+    pub fn clear_lights(&mut self) {
+        self.light_mesh = None;
+    }
+
+    /// Draw the light markers (if any) through the line pipeline, with
+    /// the socket gizmos.
+    pub fn draw_lights<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(lights) = &self.light_mesh {
+            render_pass.set_pipeline(&self.line_pipeline.render_pipeline);
+            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, lights.vertex_buffer.slice(..));
+            render_pass.draw(0..lights.vertex_count, 0..1);
+        }
+    }
+
+    /// Upload the decoded reference image for `path`. Planes showing it
+    /// appear at the next [`Self::set_reference_planes`].
+    pub fn set_reference_image(&mut self, path: &std::path::Path, image: &image::RgbaImage) {
+        self.reference_pass.set_image(&self.device, &self.queue, path, image);
+    }
+
+    /// Replace the reference planes with `references`.
+    pub fn set_reference_planes(&mut self, references: &[ReferenceImage]) {
+        self.reference_pass.set_planes(&self.device, references);
+    }
+
+    /// Draw the reference planes over the opaque voxels, before the
+    /// translucent ones.
+    pub fn draw_references<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        self.reference_pass.draw(render_pass, &self.pipeline.camera_bind_group);
+    }
+
+    /// Replace the move gizmo, `hot` highlighted; `mirror_planes` also
+    /// outlines the planes it moves.
+    pub fn set_gizmo_mesh(
+        &mut self,
+        gizmo: &Gizmo,
+        hot: Option<GizmoHandle>,
+        mirror_planes: bool,
+    ) {
+        self.gizmo_mesh = Some(GizmoMesh::new(&self.device, gizmo, hot, mirror_planes));
+    }
+
+    /// Clear the move gizmo.
+    pub fn clear_gizmo(&mut self) {
+        self.gizmo_mesh = None;
+    }
+
+    /// Draw the move gizmo (if any) over everything drawn so far —
+    /// call last in the main pass.
+    pub fn draw_gizmo<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(gizmo) = &self.gizmo_mesh {
+            render_pass.set_pipeline(&self.overlay_line_pipeline.render_pipeline);
+            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, gizmo.vertex_buffer.slice(..));
+            render_pass.draw(0..gizmo.vertex_count, 0..1);
+        }
+    }
+
+    /// Replace the hovered-voxel highlight: outline `cell` and tint
+    /// its `normal` face, both pushed out by `inset` (see
+    /// [`hover_inset`]).
+    pub fn set_hover_mesh(
+        &mut self,
+        cell: (i32, i32, i32),
+        normal: (i32, i32, i32),
+        inset: f32,
+    ) {
+        self.hover_mesh = Some(HoverMesh::new(&self.device, &self.queue, cell, normal, inset));
+    }
+
+    /// Clear the hovered-voxel highlight.
+    pub fn clear_hover(&mut self) {
+        self.hover_mesh = None;
+    }
+
+    /// Draw the hovered-voxel highlight: the face tint through the
+    /// transparent pipeline, then the outline through the line
+    /// pipeline. Call after opaque geometry so both are depth-gated.
+    pub fn draw_hover<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(hover) = &self.hover_mesh {
+            if let Some(face) = &hover.face {
+                render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
+                self.pipeline.bind_groups(render_pass);
+                face.draw(render_pass);
+            }
+            render_pass.set_pipeline(&self.line_pipeline.render_pipeline);
+            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, hover.outline_buffer.slice(..));
+            render_pass.draw(0..hover.outline_count, 0..1);
+        }
+    }
+
+    /// Replace the work-plane overlay (see [`WorkPlaneMesh::new`] for
+    /// the arguments).
+    pub fn set_work_plane_mesh(&mut self, axis: usize, coord: i32, lo: [i32; 2], hi: [i32; 2]) {
+        self.work_plane_mesh = Some(WorkPlaneMesh::new(
+            &self.device,
+            &self.queue,
+            axis,
+            coord,
+            lo,
+            hi,
+        ));
+    }
+
+    /// Clear the work-plane overlay.
+    pub fn clear_work_plane(&mut self) {
+        self.work_plane_mesh = None;
+    }
+
+    /// Draw the work-plane overlay: the sheet through the transparent
+    /// pipeline, then grid and border through the line pipeline. Call
+    /// after opaque geometry so voxels on the slice cover the sheet.
+    pub fn draw_work_plane<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(plane) = &self.work_plane_mesh {
+            render_pass.set_pipeline(&self.pipeline.transparent_pipeline);
+            self.pipeline.bind_groups(render_pass);
+            plane.sheet.draw(render_pass);
+            render_pass.set_pipeline(&self.line_pipeline.render_pipeline);
+            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, plane.line_buffer.slice(..));
+            render_pass.draw(0..plane.line_count, 0..1);
+        }
+    }
+
     /// Draw grid in render pass
     pub fn draw_grid<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         render_pass.set_pipeline(&self.line_pipeline.render_pipeline);
@@ -412,11 +1104,12 @@ impl Renderer {
             });
 
         {
+            let (target, resolve_target) = self.color_target(&view);
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Main Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    view: target,
+                    resolve_target,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
                             r: 0.1,
@@ -439,13 +1132,8 @@ impl Renderer {
                 occlusion_query_set: None,
             });
 
-            render_pass.set_pipeline(&self.pipeline.render_pipeline);
-            render_pass.set_bind_group(0, &self.pipeline.camera_bind_group, &[]);
-
-            // Render all chunk meshes
-            for mesh in self.chunk_meshes.values() {
-                mesh.draw(&mut render_pass);
-            }
+            self.draw_opaque_chunks(&mut render_pass);
+            self.draw_transparent_chunks(&mut render_pass, &self.camera);
         }
 
         self.queue.submit(std::iter::once(encoder.finish()));
@@ -456,6 +1144,35 @@ impl Renderer {
 
     /// Get total triangle count
     pub fn total_triangles(&self) -> usize {
-        self.chunk_meshes.values().map(|m| m.index_count / 3).sum()
+        self.chunks.triangle_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    #[test]
+    fn test_back_to_front_orders_by_view_depth() {
+        // Looking down -Z from z = 100: the chunk at z = -2 is farthest
+        // and drawn first; sideways offset doesn't outrank depth.
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 100.0), Vec3::ZERO, 1.0);
+        let chunks = [
+            ChunkPos::new(0, 0, 0),
+            ChunkPos::new(0, 0, -2),
+            ChunkPos::new(5, 0, 1),
+            ChunkPos::new(0, 0, -1),
+        ];
+        let order = back_to_front(chunks.into_iter(), &camera);
+        assert_eq!(
+            order,
+            vec![
+                ChunkPos::new(0, 0, -2),
+                ChunkPos::new(0, 0, -1),
+                ChunkPos::new(0, 0, 0),
+                ChunkPos::new(5, 0, 1),
+            ]
+        );
     }
 }
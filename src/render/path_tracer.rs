@@ -0,0 +1,627 @@
+//! Progressive path-traced preview.
+//!
+//! A beauty-render mode alongside the raster view: the world's solid
+//! voxels are packed into a dense grid ([`VoxelVolume`]) and uploaded
+//! as a storage buffer, a compute shader (`shaders/path_trace.wgsl`)
+//! traces one jittered sample per pixel per frame through it — soft sun
+//! shadows, emissive voxels as light sources, diffuse bounces, optional
+//! thin-lens depth of field — and a fullscreen pass
+//! (`shaders/path_present.wgsl`) shows the running average.
+//!
+//! Accumulation restarts whenever the view or the settings change
+//! (see [`PathTracer::trace`]) and stops once `max_samples` is reached,
+//! so a still camera converges and then costs nothing. The dense grid
+//! is bounded by [`PATH_TRACE_MAX_CELLS`]; bigger scenes stay on the
+//! raster view.
+
+use bytemuck::{Pod, Zeroable};
+use thiserror::Error;
+use wgpu::util::DeviceExt;
+
+use super::Camera;
+use crate::core::World;
+
+/// Upper bound on the dense volume's cell count (4 bytes each → 64 MiB,
+/// half of wgpu's default `max_storage_buffer_binding_size`).
+pub const PATH_TRACE_MAX_CELLS: usize = 1 << 24;
+
+const SOLID_BIT: u32 = 1 << 24;
+const EMISSIVE_BIT: u32 = 1 << 25;
+const WORKGROUP_SIZE: u32 = 8;
+/// Accumulation buffer bytes per traced pixel: one `vec4<f32>` sum.
+const ACCUM_PIXEL_BYTES: u64 = 16;
+
+/// User-facing path tracer settings, persisted with the viewport
+/// settings.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PathTraceSettings {
+    /// Stop accumulating after this many samples per pixel.
+    pub max_samples: u32,
+    /// Diffuse bounces after the primary hit.
+    pub max_bounces: u32,
+    /// Thin-lens aperture radius in world units; `0` = pinhole (no DOF).
+    pub aperture: f32,
+    /// Distance from the eye to the in-focus plane.
+    pub focus_distance: f32,
+}
+
+impl Default for PathTraceSettings {
+    fn default() -> Self {
+        Self {
+            max_samples: 256,
+            max_bounces: 3,
+            aperture: 0.0,
+            focus_distance: 40.0,
+        }
+    }
+}
+
+/// Why a world couldn't be packed into a [`VoxelVolume`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum VolumeError {
+    #[error("scene is empty")]
+    Empty,
+    #[error("scene too large to path trace ({cells} cells, max {max})")]
+    TooLarge { cells: usize, max: usize },
+}
+
+/// Dense, packed copy of the world's solid voxels over the scene AABB —
+/// the path tracer's GPU-side scene representation. One `u32` per cell:
+/// RGB in the low 24 bits, then solid / emissive flag bits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoxelVolume {
+    /// World cell of the volume's min corner.
+    pub origin: [i32; 3],
+    /// Size in cells along each axis.
+    pub size: [u32; 3],
+    /// Packed cells, `x + y * sx + z * sx * sy` order (the chunk order).
+    pub cells: Vec<u32>,
+}
+
+impl VoxelVolume {
+    /// Pack every solid voxel inside `world.scene_aabb()`. Fails when
+    /// the world is empty or the AABB exceeds `max_cells`.
+    pub fn from_world(world: &World, max_cells: usize) -> Result<Self, VolumeError> {
+        let (min, max) = world.scene_aabb().ok_or(VolumeError::Empty)?;
+        let size = [
+            (max.0 - min.0 + 1) as u32,
+            (max.1 - min.1 + 1) as u32,
+            (max.2 - min.2 + 1) as u32,
+        ];
+        let count = size[0] as usize * size[1] as usize * size[2] as usize;
+        if count > max_cells {
+            return Err(VolumeError::TooLarge {
+                cells: count,
+                max: max_cells,
+            });
+        }
+
+        let mut cells = vec![0u32; count];
+        let (sx, sy) = (size[0] as usize, size[1] as usize);
+        for (chunk_pos, chunk) in world.chunks() {
+            let chunk = chunk.read();
+            let o = chunk_pos.world_origin();
+            for (local, voxel) in chunk.iter_solid() {
+                let wx = (o.0 + local.x as i32 - min.0) as usize;
+                let wy = (o.1 + local.y as i32 - min.1) as usize;
+                let wz = (o.2 + local.z as i32 - min.2) as usize;
+                cells[wx + wy * sx + wz * sx * sy] = Self::pack(voxel);
+            }
+        }
+
+        Ok(Self {
+            origin: [min.0, min.1, min.2],
+            size,
+            cells,
+        })
+    }
+
+    /// Pack one voxel into the shader's cell format. Air packs to `0`.
+    pub fn pack(voxel: &crate::core::Voxel) -> u32 {
+        if voxel.is_air() {
+            return 0;
+        }
+        let mut cell = voxel.r as u32 | (voxel.g as u32) << 8 | (voxel.b as u32) << 16 | SOLID_BIT;
+        if voxel.is_emissive() {
+            cell |= EMISSIVE_BIT;
+        }
+        cell
+    }
+}
+
+/// Uniform block shared by the trace and present shaders. Field packing
+/// is documented in `path_trace.wgsl`.
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+struct TraceParams {
+    cam_pos: [f32; 4],
+    cam_forward: [f32; 4],
+    cam_right: [f32; 4],
+    cam_up: [f32; 4],
+    grid_origin: [i32; 4],
+    grid_size: [u32; 4],
+    frame: [u32; 4],
+    surface: [u32; 4],
+}
+
+/// Everything that invalidates the accumulated image when it changes:
+/// eye, target, FOV, and the settings that alter the integrand.
+/// `max_samples` is deliberately absent — raising it should keep
+/// refining the current image, not restart it.
+#[derive(Debug, Clone, PartialEq)]
+struct ViewKey {
+    position: [f32; 3],
+    target: [f32; 3],
+    fov: f32,
+    max_bounces: u32,
+    aperture: f32,
+    focus_distance: f32,
+}
+
+impl ViewKey {
+    fn new(camera: &Camera, settings: &PathTraceSettings) -> Self {
+        Self {
+            position: camera.position.to_array(),
+            target: camera.target.to_array(),
+            fov: camera.fov,
+            max_bounces: settings.max_bounces,
+            aperture: settings.aperture,
+            focus_distance: settings.focus_distance,
+        }
+    }
+}
+
+/// GPU state for the path-traced preview. Created lazily by
+/// `Renderer::path_tracer_mut` the first time the mode is enabled.
+pub struct PathTracer {
+    trace_pipeline: wgpu::ComputePipeline,
+    present_pipeline: wgpu::RenderPipeline,
+    trace_layout: wgpu::BindGroupLayout,
+    present_layout: wgpu::BindGroupLayout,
+    params_buffer: wgpu::Buffer,
+    accum_buffer: wgpu::Buffer,
+    voxel_buffer: Option<wgpu::Buffer>,
+    trace_bind_group: Option<wgpu::BindGroup>,
+    present_bind_group: wgpu::BindGroup,
+    volume_origin: [i32; 3],
+    volume_size: [u32; 3],
+    /// Trace resolution — the surface size unless that would overflow
+    /// the accumulation buffer's binding (see [`trace_size`]).
+    width: u32,
+    height: u32,
+    surface_width: u32,
+    surface_height: u32,
+    sample_count: u32,
+    last_view: Option<ViewKey>,
+    /// `Renderer::scene_revision` the uploaded volume was built from;
+    /// `None` until the first upload. The App re-packs the world when
+    /// this falls behind.
+    pub scene_revision: Option<u64>,
+}
+
+impl PathTracer {
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let trace_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Path Trace Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/path_trace.wgsl").into()),
+        });
+        let present_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Path Present Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/path_present.wgsl").into()),
+        });
+
+        let storage_entry = |binding, visibility, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let uniform_entry = |visibility| wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let trace_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Path Trace Bind Group Layout"),
+            entries: &[
+                uniform_entry(wgpu::ShaderStages::COMPUTE),
+                storage_entry(1, wgpu::ShaderStages::COMPUTE, true),
+                storage_entry(2, wgpu::ShaderStages::COMPUTE, false),
+            ],
+        });
+        let present_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Path Present Bind Group Layout"),
+            entries: &[
+                uniform_entry(wgpu::ShaderStages::FRAGMENT),
+                storage_entry(1, wgpu::ShaderStages::FRAGMENT, true),
+            ],
+        });
+
+        let trace_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Path Trace Pipeline Layout"),
+                bind_group_layouts: &[&trace_layout],
+                push_constant_ranges: &[],
+            });
+        let trace_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Path Trace Pipeline"),
+            layout: Some(&trace_pipeline_layout),
+            module: &trace_shader,
+            entry_point: "cs_trace",
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let present_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Path Present Pipeline Layout"),
+                bind_group_layouts: &[&present_layout],
+                push_constant_ranges: &[],
+            });
+        let present_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Path Present Pipeline"),
+            layout: Some(&present_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &present_shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &present_shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Path Trace Params"),
+            contents: bytemuck::cast_slice(&[TraceParams::zeroed()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let (surface_width, surface_height) = (width.max(1), height.max(1));
+        let (width, height) =
+            trace_size(surface_width, surface_height, max_accum_bytes(device));
+        let accum_buffer = Self::create_accum_buffer(device, width, height);
+        let present_bind_group =
+            Self::create_present_bind_group(device, &present_layout, &params_buffer, &accum_buffer);
+
+        Self {
+            trace_pipeline,
+            present_pipeline,
+            trace_layout,
+            present_layout,
+            params_buffer,
+            accum_buffer,
+            voxel_buffer: None,
+            trace_bind_group: None,
+            present_bind_group,
+            volume_origin: [0; 3],
+            volume_size: [0; 3],
+            width,
+            height,
+            surface_width,
+            surface_height,
+            sample_count: 0,
+            last_view: None,
+            scene_revision: None,
+        }
+    }
+
+    fn create_accum_buffer(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Path Trace Accumulation"),
+            size: width.max(1) as u64 * height.max(1) as u64 * ACCUM_PIXEL_BYTES,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn create_present_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        params: &wgpu::Buffer,
+        accum: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Path Present Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: accum.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    fn rebuild_trace_bind_group(&mut self, device: &wgpu::Device) {
+        self.trace_bind_group = self.voxel_buffer.as_ref().map(|voxels| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Path Trace Bind Group"),
+                layout: &self.trace_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: self.params_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: voxels.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.accum_buffer.as_entire_binding(),
+                    },
+                ],
+            })
+        });
+    }
+
+    /// Match the accumulation buffer to a new surface size. Restarts
+    /// accumulation.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if width == self.surface_width && height == self.surface_height {
+            return;
+        }
+        self.surface_width = width;
+        self.surface_height = height;
+        (self.width, self.height) = trace_size(width, height, max_accum_bytes(device));
+        self.accum_buffer = Self::create_accum_buffer(device, width, height);
+        self.present_bind_group = Self::create_present_bind_group(
+            device,
+            &self.present_layout,
+            &self.params_buffer,
+            &self.accum_buffer,
+        );
+        self.rebuild_trace_bind_group(device);
+        self.reset();
+    }
+
+    /// Replace the traced scene. Restarts accumulation.
+    pub fn upload_volume(&mut self, device: &wgpu::Device, volume: &VoxelVolume) {
+        self.voxel_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Path Trace Voxels"),
+            contents: bytemuck::cast_slice(&volume.cells),
+            usage: wgpu::BufferUsages::STORAGE,
+        }));
+        self.volume_origin = volume.origin;
+        self.volume_size = volume.size;
+        self.rebuild_trace_bind_group(device);
+        self.reset();
+    }
+
+    /// Drop the traced scene (empty world / over budget). Presents the
+    /// plain background until a volume is uploaded again.
+    pub fn clear_volume(&mut self) {
+        self.voxel_buffer = None;
+        self.trace_bind_group = None;
+        self.reset();
+    }
+
+    /// Restart accumulation on the next `trace`.
+    pub fn reset(&mut self) {
+        self.sample_count = 0;
+        self.last_view = None;
+    }
+
+    /// Whether a volume is uploaded — `false` for an empty or
+    /// over-budget scene.
+    pub fn has_volume(&self) -> bool {
+        self.trace_bind_group.is_some()
+    }
+
+    /// Samples per pixel accumulated so far.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Record one sample per pixel into `encoder`, unless accumulation
+    /// has converged (`max_samples` reached) or there's no volume. A
+    /// changed view / settings restarts accumulation first.
+    pub fn trace(
+        &mut self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        camera: &Camera,
+        settings: &PathTraceSettings,
+    ) {
+        let view = ViewKey::new(camera, settings);
+        if self.last_view.as_ref() != Some(&view) {
+            self.sample_count = 0;
+            self.last_view = Some(view);
+        }
+
+        let params = self.params(camera, settings);
+        // The present pass reads width from the same uniform, so write
+        // it even when no dispatch happens this frame.
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+
+        let Some(bind_group) = &self.trace_bind_group else {
+            return;
+        };
+        if self.sample_count >= settings.max_samples {
+            return;
+        }
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Path Trace Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.trace_pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.dispatch_workgroups(
+            self.width.div_ceil(WORKGROUP_SIZE),
+            self.height.div_ceil(WORKGROUP_SIZE),
+            1,
+        );
+        drop(pass);
+        self.sample_count += 1;
+    }
+
+    fn params(&self, camera: &Camera, settings: &PathTraceSettings) -> TraceParams {
+        let forward = camera.forward();
+        let right = camera.right();
+        let up = right.cross(forward);
+        TraceParams {
+            cam_pos: camera.position.extend((camera.fov * 0.5).tan()).to_array(),
+            cam_forward: forward.extend(camera.aspect).to_array(),
+            cam_right: right.extend(settings.aperture.max(0.0)).to_array(),
+            cam_up: up.extend(settings.focus_distance.max(0.01)).to_array(),
+            grid_origin: [
+                self.volume_origin[0],
+                self.volume_origin[1],
+                self.volume_origin[2],
+                0,
+            ],
+            grid_size: [
+                self.volume_size[0],
+                self.volume_size[1],
+                self.volume_size[2],
+                settings.max_bounces,
+            ],
+            frame: [
+                self.sample_count,
+                self.width,
+                self.height,
+                self.trace_bind_group.is_some() as u32,
+            ],
+            surface: [self.surface_width, self.surface_height, 0, 0],
+        }
+    }
+
+    /// Draw the accumulated image over the whole render target,
+    /// stretched if it was traced at a reduced resolution.
+    pub fn present<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.present_pipeline);
+        render_pass.set_bind_group(0, &self.present_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Largest accumulation buffer `device` can bind whole.
+fn max_accum_bytes(device: &wgpu::Device) -> u64 {
+    let limits = device.limits();
+    (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size)
+}
+
+/// Trace resolution for a `width` × `height` surface: the surface size,
+/// scaled down with its aspect kept until the accumulation buffer fits
+/// in `max_bytes`. Wgpu's default 128 MiB binding limit is about 2900 ×
+/// 2900 pixels, so a 5K window traces at about three quarters scale.
+fn trace_size(width: u32, height: u32, max_bytes: u64) -> (u32, u32) {
+    let max_pixels = max_bytes / ACCUM_PIXEL_BYTES;
+    let pixels = width as u64 * height as u64;
+    if pixels <= max_pixels {
+        return (width, height);
+    }
+    let scale = (max_pixels as f64 / pixels as f64).sqrt();
+    (
+        ((width as f64 * scale) as u32).max(1),
+        ((height as f64 * scale) as u32).max(1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Voxel;
+
+    #[test]
+    fn pack_sets_color_and_flag_bits() {
+        assert_eq!(VoxelVolume::pack(&Voxel::AIR), 0);
+        let v = Voxel::from_rgb(0x11, 0x22, 0x33);
+        assert_eq!(VoxelVolume::pack(&v), 0x0133_2211);
+        let mut e = v;
+        e.set_emissive(true);
+        assert_eq!(VoxelVolume::pack(&e), 0x0333_2211);
+    }
+
+    #[test]
+    fn volume_spans_scene_aabb_in_chunk_order() {
+        let mut world = World::new();
+        world.set_voxel(-1, 0, 2, Voxel::from_rgb(255, 0, 0));
+        world.set_voxel(1, 3, 2, Voxel::from_rgb(0, 255, 0));
+
+        let vol = VoxelVolume::from_world(&world, PATH_TRACE_MAX_CELLS).unwrap();
+        assert_eq!(vol.origin, [-1, 0, 2]);
+        assert_eq!(vol.size, [3, 4, 1]);
+        assert_eq!(vol.cells.len(), 12);
+        assert_eq!(vol.cells[0] & 0xff, 255);
+        // (1, 3, 2) → local (2, 3, 0) → 2 + 3 * 3.
+        assert_eq!((vol.cells[11] >> 8) & 0xff, 255);
+        assert_eq!(vol.cells.iter().filter(|&&c| c != 0).count(), 2);
+    }
+
+    #[test]
+    fn volume_rejects_empty_and_oversized_scenes() {
+        let mut world = World::new();
+        assert_eq!(
+            VoxelVolume::from_world(&world, PATH_TRACE_MAX_CELLS),
+            Err(VolumeError::Empty)
+        );
+        world.set_voxel(0, 0, 0, Voxel::from_rgb(1, 1, 1));
+        world.set_voxel(9, 0, 0, Voxel::from_rgb(1, 1, 1));
+        assert_eq!(
+            VoxelVolume::from_world(&world, 5),
+            Err(VolumeError::TooLarge { cells: 10, max: 5 })
+        );
+    }
+
+    #[test]
+    fn view_key_ignores_sample_budget() {
+        let camera = Camera::new(glam::Vec3::new(0.0, 5.0, 10.0), glam::Vec3::ZERO, 1.0);
+        let a = PathTraceSettings::default();
+        let b = PathTraceSettings {
+            max_samples: a.max_samples * 4,
+            ..a.clone()
+        };
+        assert_eq!(ViewKey::new(&camera, &a), ViewKey::new(&camera, &b));
+        let c = PathTraceSettings {
+            aperture: 0.5,
+            ..a.clone()
+        };
+        assert_ne!(ViewKey::new(&camera, &a), ViewKey::new(&camera, &c));
+    }
+
+    #[test]
+    fn trace_size_fits_the_binding_limit() {
+        let limit = 128 << 20;
+        assert_eq!(trace_size(1920, 1080, limit), (1920, 1080));
+        let (w, h) = trace_size(5120, 2880, limit);
+        assert!(w as u64 * h as u64 * ACCUM_PIXEL_BYTES <= limit);
+        assert!(w >= 3800 && h >= 2100);
+        assert_eq!(w / 16, h / 9);
+    }
+}
@@ -0,0 +1,407 @@
+//! GPU picking: which voxel is under the cursor, read from an ID buffer
+//! instead of a CPU raycast.
+//!
+//! The CPU DDA in `editor::raycast` walks the world cell by cell, which
+//! gets slow on huge scenes. With Viewport Settings ▸ GPU Picking on,
+//! the chunk meshes are drawn a second time — only the one pixel under
+//! the cursor, via the scissor rect — into an `R32Uint` target whose
+//! fragments carry the ID of their voxel cell (see `shaders/pick.wgsl`).
+//! That texel is copied into a mappable buffer and read back
+//! asynchronously: [`GpuPicker::poll`] hands the result over a frame or
+//! two later, so the hover trails a moving cursor slightly but the
+//! frame never stalls on the GPU.
+//!
+//! Only chunk geometry is drawn, never the editing overlays, so a brush
+//! ghost or procgen preview can't get in the way of what is picked.
+//!
+//! An ID stores each cell coordinate modulo [`PICK_WRAP`];
+//! [`decode_pick_id`] picks the congruent cell nearest the camera, which
+//! is exact within `PICK_WRAP / 2` cells of it.
+
+use std::sync::mpsc;
+
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+
+use super::camera::NO_CLIP;
+use super::ChunkBatch;
+use crate::mesh::Vertex;
+
+/// Period of the coordinates packed into a pick ID (10 bits per axis).
+pub const PICK_WRAP: i32 = 1024;
+
+/// Set on every hit so a hit ID is never 0, the "background" clear.
+const PICK_HIT: u32 = 1 << 30;
+
+const ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct PickUniform {
+    view_proj: [[f32; 4]; 4],
+    clip: [f32; 4],
+}
+
+/// The ID `pick.wgsl` writes for `cell`.
+pub fn pick_id(cell: (i32, i32, i32)) -> u32 {
+    let bits = |c: i32| c.rem_euclid(PICK_WRAP) as u32;
+    PICK_HIT | bits(cell.0) | bits(cell.1) << 10 | bits(cell.2) << 20
+}
+
+/// The cell behind a pick `id`: of all cells with the same coordinates
+/// modulo [`PICK_WRAP`], the one nearest `near` (the camera position).
+/// `None` for the background.
+pub fn decode_pick_id(id: u32, near: Vec3) -> Option<(i32, i32, i32)> {
+    if id & PICK_HIT == 0 {
+        return None;
+    }
+    let axis = |shift: u32, near: f32| {
+        let wrapped = ((id >> shift) & (PICK_WRAP as u32 - 1)) as i32;
+        let near = near.floor() as i32;
+        let offset = (wrapped - near).rem_euclid(PICK_WRAP);
+        let offset = if offset >= PICK_WRAP / 2 { offset - PICK_WRAP } else { offset };
+        near + offset
+    };
+    Some((axis(0, near.x), axis(10, near.y), axis(20, near.z)))
+}
+
+/// What one pick asked for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickRequest {
+    /// Surface pixel under the cursor.
+    pub pixel: [u32; 2],
+    /// `[x, y, width, height]` of the pane the pixel lies in.
+    pub viewport: [u32; 4],
+    pub view_proj: Mat4,
+    /// Hide cells above this height, like the viewport's Y-clip.
+    pub y_clip: Option<i32>,
+    /// `Renderer::scene_revision` the chunk meshes were at.
+    pub scene_revision: u64,
+}
+
+/// A finished pick: the request and the ID read back for it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickSample {
+    pub request: PickRequest,
+    /// 0 when the pixel shows no voxel, else a [`pick_id`].
+    pub id: u32,
+}
+
+/// A pick whose readback hasn't landed yet.
+struct InFlight {
+    request: PickRequest,
+    mapped: mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>,
+}
+
+/// Offscreen ID + depth targets at the surface size, the pick pipeline
+/// and the one-texel readback buffer.
+pub struct GpuPicker {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    id_texture: wgpu::Texture,
+    id_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+    size: [u32; 2],
+    readback: wgpu::Buffer,
+    in_flight: Option<InFlight>,
+    /// The last request submitted, so an unchanged cursor / camera /
+    /// scene isn't picked again every frame.
+    last: Option<PickRequest>,
+}
+
+impl GpuPicker {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Pick Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/pick.wgsl").into()),
+        });
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Pick Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[PickUniform {
+                view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+                clip: NO_CLIP,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Pick Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Pick Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Pick Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Pick Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::layout()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: ID_FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                cull_mode: Some(wgpu::Face::Back),
+                ..Default::default()
+            },
+            // Translucent voxels write depth too: the CPU raycast stops
+            // at glass, so picking does as well.
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Pick Readback"),
+            size: 4,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let (id_texture, id_view, depth_view) = create_targets(device, width, height);
+        Self {
+            pipeline,
+            uniform_buffer,
+            bind_group,
+            id_texture,
+            id_view,
+            depth_view,
+            size: [width, height],
+            readback,
+            in_flight: None,
+            last: None,
+        }
+    }
+
+    /// Match the surface size. Drops the last request so the next
+    /// frame picks again.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if self.size == [width, height] {
+            return;
+        }
+        (self.id_texture, self.id_view, self.depth_view) = create_targets(device, width, height);
+        self.size = [width, height];
+        self.last = None;
+    }
+
+    /// Draw `chunks` into the ID target at `request.pixel` and start
+    /// reading it back. Skipped while an earlier pick is still in
+    /// flight, when nothing changed since the last one, or when the
+    /// pixel is off the surface.
+    pub fn pick(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        chunks: &ChunkBatch,
+        request: PickRequest,
+    ) {
+        let [px, py] = request.pixel;
+        if self.in_flight.is_some()
+            || self.last == Some(request)
+            || px >= self.size[0]
+            || py >= self.size[1]
+        {
+            return;
+        }
+        let clip = match request.y_clip {
+            Some(y) => [1.0, y as f32, 0.0, 0.0],
+            None => NO_CLIP,
+        };
+        let uniform = PickUniform {
+            view_proj: request.view_proj.to_cols_array_2d(),
+            clip,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Pick Encoder"),
+        });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Pick Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.id_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            let [x, y, w, h] = request.viewport;
+            pass.set_viewport(x as f32, y as f32, w as f32, h as f32, 0.0, 1.0);
+            pass.set_scissor_rect(px, py, 1, 1);
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            chunks.draw_opaque(&mut pass);
+            let translucent: Vec<_> = chunks.translucent_chunks().collect();
+            chunks.draw_transparent(&mut pass, &translucent);
+        }
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.id_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x: px, y: py, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let (tx, rx) = mpsc::channel();
+        self.readback
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = tx.send(result);
+            });
+        self.in_flight = Some(InFlight {
+            request,
+            mapped: rx,
+        });
+        self.last = Some(request);
+    }
+
+    /// The in-flight pick's result once its readback has landed, else
+    /// `None`. Never blocks.
+    pub fn poll(&mut self, device: &wgpu::Device) -> Option<PickSample> {
+        let in_flight = self.in_flight.as_ref()?;
+        device.poll(wgpu::Maintain::Poll);
+        let mapped = match in_flight.mapped.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => Err(wgpu::BufferAsyncError),
+        };
+        let request = in_flight.request;
+        self.in_flight = None;
+        if mapped.is_err() {
+            // Pick again next frame.
+            self.last = None;
+            return None;
+        }
+        let id = {
+            let data = self.readback.slice(..).get_mapped_range();
+            u32::from_le_bytes([data[0], data[1], data[2], data[3]])
+        };
+        self.readback.unmap();
+        Some(PickSample { request, id })
+    }
+}
+
+/// `(ID texture, its view, depth view)` at `width` × `height`.
+fn create_targets(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+) -> (wgpu::Texture, wgpu::TextureView, wgpu::TextureView) {
+    let size = wgpu::Extent3d {
+        width: width.max(1),
+        height: height.max(1),
+        depth_or_array_layers: 1,
+    };
+    let id_texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Pick ID Texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: ID_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let depth = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Pick Depth Texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Depth32Float,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let id_view = id_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let depth_view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+    (id_texture, id_view, depth_view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_id_round_trips_near_the_camera() {
+        let camera = Vec3::new(10.5, 40.0, -3.2);
+        for cell in [(0, 0, 0), (-1, -1, -1), (300, -200, 500), (-490, 17, -12)] {
+            let id = pick_id(cell);
+            assert_ne!(id, 0);
+            assert_eq!(decode_pick_id(id, camera), Some(cell));
+        }
+    }
+
+    #[test]
+    fn test_pick_id_resolves_wrap_around_far_from_the_origin() {
+        let camera = Vec3::new(5000.0, 0.0, -7000.0);
+        let cell = (5400, -300, -6600);
+        assert_eq!(decode_pick_id(pick_id(cell), camera), Some(cell));
+    }
+
+    #[test]
+    fn test_background_decodes_to_none() {
+        assert_eq!(decode_pick_id(0, Vec3::ZERO), None);
+    }
+}
@@ -1,36 +1,128 @@
 //! Render pipeline setup and management.
 
+use super::gi::GiVolume;
+use super::camera::{NO_CLIP, NO_SLICE, NO_TOON};
+use super::lights::LightsUniform;
 use super::{Camera, CameraUniform};
+use crate::core::{Light, MaterialTable};
 use crate::mesh::Vertex;
 use wgpu::util::DeviceExt;
 
+/// Multisample anti-aliasing level for the main viewport pass
+/// (Viewport Settings ▸ Anti-aliasing). Levels the adapter can't do for
+/// the surface + depth formats fall back to the nearest lower one — see
+/// `Renderer::set_msaa`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Msaa {
+    Off,
+    X2,
+    /// 4× is guaranteed for the usual color / depth formats on every
+    /// wgpu backend, so it's the default.
+    #[default]
+    X4,
+    X8,
+}
+
+impl Msaa {
+    pub const ALL: [Msaa; 4] = [Msaa::Off, Msaa::X2, Msaa::X4, Msaa::X8];
+
+    /// Samples per pixel (1 = off).
+    pub fn samples(self) -> u32 {
+        match self {
+            Self::Off => 1,
+            Self::X2 => 2,
+            Self::X4 => 4,
+            Self::X8 => 8,
+        }
+    }
+
+    /// The highest level in `supported` that doesn't exceed `self`
+    /// (`Off` if none does).
+    pub fn clamp_to(self, supported: &[Msaa]) -> Msaa {
+        supported
+            .iter()
+            .copied()
+            .filter(|m| *m <= self)
+            .max()
+            .unwrap_or(Msaa::Off)
+    }
+
+    /// Display label for the viewport settings combo box.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::X2 => "2× MSAA",
+            Self::X4 => "4× MSAA",
+            Self::X8 => "8× MSAA",
+        }
+    }
+}
+
 /// Main render pipeline for voxel rendering.
 ///
-/// Three voxel pipelines share the same shader, vertex layout, and
+/// Four voxel pipelines share the same shader, vertex layout, and
 /// camera bind group:
 /// - `render_pipeline`: opaque, depth-write enabled, back-face culled.
-/// - `wireframe_pipeline`: same as opaque but `PolygonMode::Line`,
-///   only present when the GPU exposes `POLYGON_MODE_LINE`.
+/// - `wireframe_pipeline`: the chunk meshes' triangle edges as lit,
+///   vertex-colored lines (Viewport Settings ▸ Wireframe Mode).
+/// - `edge_pipeline`: the same edges as dark lines pulled slightly
+///   toward the camera, drawn over the shaded meshes for the "shaded
+///   with edges" view.
 /// - `transparent_pipeline`: alpha-blended with depth-write disabled,
-///   used for the procgen preview overlay so opaque geometry behind
-///   it remains visible.
+///   used for translucent voxels (the chunk meshes' transparent index
+///   range, drawn back to front) and the preview overlays, so opaque
+///   geometry behind them remains visible.
+///
+/// All four are built for one sample count; [`Self::rebuild`] swaps
+/// them when the MSAA level changes, keeping the camera buffer and GI
+/// volume (and their bind groups) as they are.
+///
+/// Group 0 is the camera, the material table and the placed lights,
+/// group 1 the GI volume
+/// (`gi`); bind both with `bind_groups` before drawing through any of
+/// them.
 pub struct RenderPipeline {
     pub render_pipeline: wgpu::RenderPipeline,
-    pub wireframe_pipeline: Option<wgpu::RenderPipeline>,
+    pub wireframe_pipeline: wgpu::RenderPipeline,
+    pub edge_pipeline: wgpu::RenderPipeline,
     pub transparent_pipeline: wgpu::RenderPipeline,
     pub camera_buffer: wgpu::Buffer,
+    /// Per-material shading properties, bound next to the camera.
+    material_buffer: wgpu::Buffer,
+    /// What `material_buffer` holds, so unchanged tables aren't
+    /// re-uploaded every frame.
+    materials: Vec<[f32; 4]>,
+    /// Placed point / spot lights, bound next to the materials.
+    light_buffer: wgpu::Buffer,
+    /// What `light_buffer` holds, for the same reason.
+    lights: LightsUniform,
+    /// Work-plane dimming written alongside each camera upload (see
+    /// `CameraUniform::slice`).
+    slice: [f32; 4],
+    /// Y-clip written alongside each camera upload (see
+    /// `CameraUniform::clip`).
+    clip: [f32; 4],
+    /// Toon lighting bands written alongside each camera upload (see
+    /// `CameraUniform::toon`).
+    toon: [f32; 4],
     pub camera_bind_group: wgpu::BindGroup,
     pub camera_bind_group_layout: wgpu::BindGroupLayout,
+    pub gi: GiVolume,
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    surface_format: wgpu::TextureFormat,
 }
 
 impl RenderPipeline {
-    /// Create a new render pipeline
-    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
-        Self::new_with_features(device, surface_format, wgpu::Features::empty())
-    }
-
-    /// Create a new render pipeline with optional features
-    pub fn new_with_features(device: &wgpu::Device, surface_format: wgpu::TextureFormat, features: wgpu::Features) -> Self {
+    /// Create a new render pipeline for `sample_count`-sample targets.
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
         // Create shader module
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Voxel Shader"),
@@ -41,16 +133,40 @@ impl RenderPipeline {
         let camera_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("Camera Bind Group Layout"),
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    // Material shading table (`MaterialTable::shading_table`).
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    // Placed lights (`LightsUniform`).
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
                     },
-                    count: None,
-                }],
+                ],
             });
 
         // Create camera uniform buffer
@@ -61,178 +177,305 @@ impl RenderPipeline {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
+        // Material table, starting out as a default-only table until
+        // the app uploads the project's with `update_materials`.
+        let materials = MaterialTable::default().shading_table();
+        let material_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Material Buffer"),
+            contents: bytemuck::cast_slice(&materials),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // No lights until the app uploads the project's with
+        // `update_lights`.
+        let lights = LightsUniform::new(&[]);
+        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Buffer"),
+            contents: bytemuck::cast_slice(&[lights]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
         // Create camera bind group
         let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Camera Bind Group"),
             layout: &camera_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: material_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: light_buffer.as_entire_binding(),
+                },
+            ],
         });
 
+        let gi = GiVolume::new(device);
+
         // Pipeline layout
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&camera_bind_group_layout],
+            bind_group_layouts: &[&camera_bind_group_layout, &gi.bind_group_layout],
             push_constant_ranges: &[],
         });
 
-        // Create render pipeline (fill mode)
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Voxel Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[Vertex::layout()],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-            cache: None,
-        });
-
-        // Create wireframe pipeline if the feature is available
-        let wireframe_pipeline = if features.contains(wgpu::Features::POLYGON_MODE_LINE) {
-            Some(device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("Voxel Wireframe Pipeline"),
-                layout: Some(&pipeline_layout),
-                vertex: wgpu::VertexState {
-                    module: &shader,
-                    entry_point: "vs_main",
-                    buffers: &[Vertex::layout()],
-                    compilation_options: wgpu::PipelineCompilationOptions::default(),
-                },
-                fragment: Some(wgpu::FragmentState {
-                    module: &shader,
-                    entry_point: "fs_main",
-                    targets: &[Some(wgpu::ColorTargetState {
-                        format: surface_format,
-                        blend: Some(wgpu::BlendState::REPLACE),
-                        write_mask: wgpu::ColorWrites::ALL,
-                    })],
-                    compilation_options: wgpu::PipelineCompilationOptions::default(),
-                }),
-                primitive: wgpu::PrimitiveState {
-                    topology: wgpu::PrimitiveTopology::TriangleList,
-                    strip_index_format: None,
-                    front_face: wgpu::FrontFace::Ccw,
-                    cull_mode: None, // No culling in wireframe mode
-                    polygon_mode: wgpu::PolygonMode::Line,
-                    unclipped_depth: false,
-                    conservative: false,
-                },
-                depth_stencil: Some(wgpu::DepthStencilState {
-                    format: wgpu::TextureFormat::Depth32Float,
-                    depth_write_enabled: true,
-                    depth_compare: wgpu::CompareFunction::Less,
-                    stencil: wgpu::StencilState::default(),
-                    bias: wgpu::DepthBiasState::default(),
-                }),
-                multisample: wgpu::MultisampleState {
-                    count: 1,
-                    mask: !0,
-                    alpha_to_coverage_enabled: false,
-                },
-                multiview: None,
-                cache: None,
-            }))
-        } else {
-            None
-        };
-
-        // Transparent pipeline: same shader/layout, alpha blending,
-        // depth-write disabled so the preview doesn't occlude later
-        // transparent geometry. Drawn after opaque chunks so the
-        // already-written opaque depth still gates it correctly.
-        let transparent_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Voxel Transparent Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[Vertex::layout()],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: false,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-            cache: None,
-        });
+        let [render_pipeline, wireframe_pipeline, edge_pipeline, transparent_pipeline] =
+            voxel_pipelines(device, &pipeline_layout, &shader, surface_format, sample_count);
 
         Self {
             render_pipeline,
             wireframe_pipeline,
+            edge_pipeline,
             transparent_pipeline,
             camera_buffer,
+            material_buffer,
+            materials,
+            light_buffer,
+            lights,
+            slice: NO_SLICE,
+            clip: NO_CLIP,
+            toon: NO_TOON,
             camera_bind_group,
             camera_bind_group_layout,
+            gi,
+            shader,
+            pipeline_layout,
+            surface_format,
         }
     }
 
+    /// Recreate the four voxel pipelines for a new sample count.
+    pub fn rebuild(&mut self, device: &wgpu::Device, sample_count: u32) {
+        [
+            self.render_pipeline,
+            self.wireframe_pipeline,
+            self.edge_pipeline,
+            self.transparent_pipeline,
+        ] = voxel_pipelines(
+            device,
+            &self.pipeline_layout,
+            &self.shader,
+            self.surface_format,
+            sample_count,
+        );
+    }
+
+    /// Set the camera and GI bind groups for a voxel-pipeline draw.
+    pub fn bind_groups<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.gi.bind_group, &[]);
+    }
+
     /// Update camera uniform buffer
     pub fn update_camera(&self, queue: &wgpu::Queue, camera: &Camera) {
-        let uniform = camera.uniform();
+        let uniform = CameraUniform {
+            slice: self.slice,
+            clip: self.clip,
+            toon: self.toon,
+            ..camera.uniform()
+        };
         queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
     }
+
+    /// Dim voxels off the `(axis index, coord)` slice by `amount`
+    /// (0..=1), or stop dimming with `None`. Takes effect at the next
+    /// `update_camera`.
+    pub fn set_slice_dim(&mut self, slice: Option<(usize, i32)>, amount: f32) {
+        self.slice = match slice {
+            Some((axis, coord)) => [axis as f32, coord as f32, amount, 0.0],
+            None => NO_SLICE,
+        };
+    }
+
+    /// Hide voxels above height `y_max` (cells with `y > y_max`), or
+    /// show everything with `None`. Takes effect at the next
+    /// `update_camera`.
+    pub fn set_y_clip(&mut self, y_max: Option<i32>) {
+        self.clip = match y_max {
+            Some(y) => [1.0, y as f32, 0.0, 0.0],
+            None => NO_CLIP,
+        };
+    }
+
+    /// Quantize the voxel lighting into `bands` flat steps, or light
+    /// smoothly with `None`. Takes effect at the next `update_camera`.
+    pub fn set_toon_bands(&mut self, bands: Option<u32>) {
+        self.toon = match bands {
+            Some(n) => [n.max(1) as f32, 0.0, 0.0, 0.0],
+            None => NO_TOON,
+        };
+    }
+
+    /// Upload `table`'s shading properties if they changed since the
+    /// last upload.
+    pub fn update_materials(&mut self, queue: &wgpu::Queue, table: &MaterialTable) {
+        let materials = table.shading_table();
+        if materials != self.materials {
+            queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&materials));
+            self.materials = materials;
+        }
+    }
+
+    /// Upload `lights` (the enabled ones, up to `MAX_LIGHTS`) if they
+    /// changed since the last upload.
+    pub fn update_lights(&mut self, queue: &wgpu::Queue, lights: &[Light]) {
+        let uniform = LightsUniform::new(lights);
+        if uniform != self.lights {
+            queue.write_buffer(&self.light_buffer, 0, bytemuck::cast_slice(&[uniform]));
+            self.lights = uniform;
+        }
+    }
+}
+
+/// How one voxel pipeline differs from the others.
+struct VoxelPipelineDesc {
+    label: &'static str,
+    vs_entry: &'static str,
+    fs_entry: &'static str,
+    topology: wgpu::PrimitiveTopology,
+    cull_mode: Option<wgpu::Face>,
+    blend: wgpu::BlendState,
+    depth_write: bool,
+    depth_compare: wgpu::CompareFunction,
+}
+
+/// Build `[render, wireframe, edge, transparent]`.
+fn voxel_pipelines(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    surface_format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> [wgpu::RenderPipeline; 4] {
+    let build = |desc: VoxelPipelineDesc| {
+        voxel_pipeline(device, layout, shader, surface_format, sample_count, desc)
+    };
+    [
+        // Opaque fill.
+        build(VoxelPipelineDesc {
+            label: "Voxel Render Pipeline",
+            vs_entry: "vs_main",
+            fs_entry: "fs_main",
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: Some(wgpu::Face::Back),
+            blend: wgpu::BlendState::REPLACE,
+            depth_write: true,
+            depth_compare: wgpu::CompareFunction::Less,
+        }),
+        // Edge pipelines: the mesh's triangle edges as a line list
+        // (`GpuMesh::edge_buffer`). Plain `LineList` topology rather
+        // than `PolygonMode::Line`, so wireframe works on every
+        // adapter, not just those exposing `POLYGON_MODE_LINE`. Depth
+        // writes stay on so a lines-only wireframe still occludes
+        // itself sensibly; `LessEqual` lets the overlay's pulled-in
+        // edges pass where they sit exactly on an already-drawn face.
+        build(VoxelPipelineDesc {
+            label: "Voxel Wireframe Pipeline",
+            vs_entry: "vs_main",
+            fs_entry: "fs_main",
+            topology: wgpu::PrimitiveTopology::LineList,
+            cull_mode: None,
+            blend: wgpu::BlendState::REPLACE,
+            depth_write: true,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+        }),
+        build(VoxelPipelineDesc {
+            label: "Voxel Edge Overlay Pipeline",
+            vs_entry: "vs_edge",
+            fs_entry: "fs_edge",
+            topology: wgpu::PrimitiveTopology::LineList,
+            cull_mode: None,
+            blend: wgpu::BlendState::REPLACE,
+            depth_write: true,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+        }),
+        // Transparent pipeline: same shader/layout, alpha blending,
+        // depth-write disabled so the preview doesn't occlude later
+        // transparent geometry. Drawn after opaque chunks so the
+        // already-written opaque depth still gates it correctly.
+        build(VoxelPipelineDesc {
+            label: "Voxel Transparent Pipeline",
+            vs_entry: "vs_main",
+            fs_entry: "fs_main",
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: Some(wgpu::Face::Back),
+            blend: wgpu::BlendState::ALPHA_BLENDING,
+            depth_write: false,
+            depth_compare: wgpu::CompareFunction::Less,
+        }),
+    ]
+}
+
+fn voxel_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    surface_format: wgpu::TextureFormat,
+    sample_count: u32,
+    desc: VoxelPipelineDesc,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(desc.label),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: desc.vs_entry,
+            buffers: &[Vertex::layout()],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: desc.fs_entry,
+            targets: &[Some(wgpu::ColorTargetState {
+                format: surface_format,
+                blend: Some(desc.blend),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: desc.topology,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: desc.cull_mode,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: desc.depth_write,
+            depth_compare: desc.depth_compare,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_msaa_clamps_to_nearest_lower_supported_level() {
+        // A typical adapter: 1× and 4× only.
+        let supported = [Msaa::Off, Msaa::X4];
+        assert_eq!(Msaa::X8.clamp_to(&supported), Msaa::X4);
+        assert_eq!(Msaa::X4.clamp_to(&supported), Msaa::X4);
+        assert_eq!(Msaa::X2.clamp_to(&supported), Msaa::Off);
+        assert_eq!(Msaa::X8.clamp_to(&[]), Msaa::Off);
+        assert_eq!(Msaa::default().samples(), 4);
+    }
 }
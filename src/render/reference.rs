@@ -0,0 +1,429 @@
+//! Reference image planes (`core::ReferenceImage`): concept art drawn
+//! as textured, see-through quads in the scene.
+//!
+//! Each loaded file is one texture, kept by path so several planes can
+//! show the same image and a plane edit doesn't upload it again. A
+//! plane is six vertices and its own small opacity uniform. They draw
+//! after the opaque voxels and line overlays, depth-tested but not
+//! writing depth (`shaders/reference.wgsl`), in list order.
+//!
+//! A ghost model (`core::ReferenceModel`) needs no pipeline of its own:
+//! [`ghost_mesh`] places and fades its mesh, and it draws through the
+//! transparent voxel pipeline like the onion skin.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use bytemuck::{Pod, Zeroable};
+use image::{DynamicImage, RgbaImage};
+use wgpu::util::DeviceExt;
+
+use crate::core::{ReferenceImage, ReferenceModel, DEFAULT_MATERIAL};
+use crate::mesh::ChunkMesh;
+
+/// Largest width or height kept on the GPU; bigger images are scaled
+/// down on load.
+pub const REFERENCE_MAX_DIM: u32 = 4096;
+
+/// One corner of a reference plane.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct ReferenceVertex {
+    pub position: [f32; 3],
+    pub uv: [f32; 2],
+}
+
+impl ReferenceVertex {
+    fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: 12,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+            ],
+        }
+    }
+}
+
+/// Decoded image ready to upload, scaled down to fit
+/// [`REFERENCE_MAX_DIM`].
+pub fn fit_reference_image(image: DynamicImage) -> RgbaImage {
+    let (w, h) = (image.width(), image.height());
+    if w <= REFERENCE_MAX_DIM && h <= REFERENCE_MAX_DIM {
+        return image.into_rgba8();
+    }
+    let scale = REFERENCE_MAX_DIM as f32 / w.max(h) as f32;
+    let width = ((w as f32 * scale) as u32).max(1);
+    let height = ((h as f32 * scale) as u32).max(1);
+    image
+        .resize_exact(width, height, image::imageops::FilterType::Triangle)
+        .into_rgba8()
+}
+
+/// `source` — a ghost model as read from its file, at full opacity —
+/// placed and faded for `model`. Shaded with the default material: a
+/// `.vox`'s own material ids mean nothing in this project's table.
+pub fn ghost_mesh(source: &ChunkMesh, model: &ReferenceModel) -> ChunkMesh {
+    let mut mesh = source.clone();
+    let opacity = model.opacity.clamp(0.0, 1.0);
+    for v in &mut mesh.vertices {
+        v.position = model.place(glam::Vec3::from(v.position)).to_array();
+        v.color[3] *= opacity;
+        v.material = DEFAULT_MATERIAL as u32;
+    }
+    mesh
+}
+
+/// Two triangles covering `reference` for an image `aspect` wide, the
+/// image's top row at the plane's top edge.
+fn plane_vertices(reference: &ReferenceImage, aspect: f32) -> [ReferenceVertex; 6] {
+    let [bl, br, tr, tl] = reference.corners(aspect);
+    let v = |p: glam::Vec3, uv: [f32; 2]| ReferenceVertex {
+        position: p.to_array(),
+        uv,
+    };
+    let (bl, br, tr, tl) = (
+        v(bl, [0.0, 1.0]),
+        v(br, [1.0, 1.0]),
+        v(tr, [1.0, 0.0]),
+        v(tl, [0.0, 0.0]),
+    );
+    [bl, br, tr, bl, tr, tl]
+}
+
+/// An uploaded image and its width / height.
+struct ReferenceTexture {
+    view: wgpu::TextureView,
+    aspect: f32,
+}
+
+/// One plane ready to draw.
+struct ReferencePlane {
+    vertex_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// The reference plane draw: pipeline, uploaded images and the planes
+/// built from them. The pipeline is rebuilt with the voxel pipelines
+/// when the MSAA level changes, since it draws in the main pass.
+pub struct ReferencePass {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    surface_format: wgpu::TextureFormat,
+    textures: HashMap<PathBuf, ReferenceTexture>,
+    planes: Vec<ReferencePlane>,
+}
+
+impl ReferencePass {
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+    ) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Reference Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Reference Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let pipeline = Self::build(
+            device,
+            surface_format,
+            camera_bind_group_layout,
+            &layout,
+            sample_count,
+        );
+        Self {
+            pipeline,
+            layout,
+            sampler,
+            surface_format,
+            textures: HashMap::new(),
+            planes: Vec::new(),
+        }
+    }
+
+    /// Recreate the pipeline for a new sample count. Planes and images
+    /// carry over.
+    pub fn rebuild(
+        &mut self,
+        device: &wgpu::Device,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+    ) {
+        self.pipeline = Self::build(
+            device,
+            self.surface_format,
+            camera_bind_group_layout,
+            &self.layout,
+            sample_count,
+        );
+    }
+
+    /// Upload `image` as the picture for `path`, replacing any before.
+    /// Planes pick it up at the next [`Self::set_planes`].
+    pub fn set_image(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: &Path,
+        image: &RgbaImage,
+    ) {
+        let size = wgpu::Extent3d {
+            width: image.width().max(1),
+            height: image.height().max(1),
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Reference Image"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        if !image.is_empty() {
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                image.as_raw(),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(size.width * 4),
+                    rows_per_image: Some(size.height),
+                },
+                size,
+            );
+        }
+        self.textures.insert(
+            path.to_path_buf(),
+            ReferenceTexture {
+                view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                aspect: size.width as f32 / size.height as f32,
+            },
+        );
+    }
+
+    /// Rebuild the planes for `references`. Hidden ones, and ones whose
+    /// image isn't uploaded (yet), are left out; images no reference
+    /// shows any more are dropped.
+    pub fn set_planes(&mut self, device: &wgpu::Device, references: &[ReferenceImage]) {
+        self.textures
+            .retain(|path, _| references.iter().any(|r| &r.path == path));
+        self.planes = references
+            .iter()
+            .filter(|r| r.visible && r.opacity > 0.0)
+            .filter_map(|r| {
+                let texture = self.textures.get(&r.path)?;
+                let vertices = plane_vertices(r, texture.aspect);
+                let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Reference Vertex Buffer"),
+                    contents: bytemuck::cast_slice(&vertices),
+                    usage: wgpu::BufferUsages::VERTEX,
+                });
+                let params = [r.opacity.clamp(0.0, 1.0), 0.0, 0.0, 0.0];
+                let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Reference Params"),
+                    contents: bytemuck::cast_slice(&params),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Reference Bind Group"),
+                    layout: &self.layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: params_buffer.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(&texture.view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: wgpu::BindingResource::Sampler(&self.sampler),
+                        },
+                    ],
+                });
+                Some(ReferencePlane {
+                    vertex_buffer,
+                    bind_group,
+                })
+            })
+            .collect();
+    }
+
+    fn build(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+    ) -> wgpu::RenderPipeline {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Reference Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/reference.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Reference Pipeline Layout"),
+            bind_group_layouts: &[camera_bind_group_layout, layout],
+            push_constant_ranges: &[],
+        });
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Reference Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[ReferenceVertex::layout()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            // Seen from either side.
+            primitive: wgpu::PrimitiveState {
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Draw the planes with `camera_bind_group`'s view.
+    pub fn draw<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        camera_bind_group: &'a wgpu::BindGroup,
+    ) {
+        if self.planes.is_empty() {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        for plane in &self.planes {
+            render_pass.set_bind_group(1, &plane.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, plane.vertex_buffer.slice(..));
+            render_pass.draw(0..6, 0..1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ReferenceView;
+
+    #[test]
+    fn plane_maps_the_image_upright() {
+        let r = ReferenceImage {
+            width: 4.0,
+            ..ReferenceImage::new("a.png".into(), ReferenceView::Side)
+        };
+        let verts = plane_vertices(&r, 2.0);
+        // The top row of the image (v = 0) sits at the top of the plane.
+        for v in &verts {
+            let expected_y = if v.uv[1] == 0.0 { 2.0 } else { 0.0 };
+            assert_eq!(v.position[1], expected_y);
+        }
+        // Seen from +Z, the image's left column is at -X.
+        assert_eq!(verts[0].uv, [0.0, 1.0]);
+        assert_eq!(verts[0].position, [-2.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn ghost_is_placed_and_faded() {
+        let mut source = ChunkMesh::new(crate::core::ChunkPos::ZERO);
+        source.vertices.push(crate::mesh::Vertex::new([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0; 4]));
+        let model = ReferenceModel {
+            opacity: 0.25,
+            scale: 3.0,
+            offset: [0.0, 5.0, 0.0],
+            ..ReferenceModel::new("a.vox".into())
+        };
+        let ghost = ghost_mesh(&source, &model);
+        assert_eq!(ghost.vertices[0].position, [3.0, 5.0, 0.0]);
+        assert_eq!(ghost.vertices[0].color[3], 0.25);
+        assert_eq!(ghost.vertices[0].normal, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn oversized_images_are_scaled_down() {
+        let img = RgbaImage::from_pixel(REFERENCE_MAX_DIM * 2, 8, image::Rgba([10, 20, 30, 255]));
+        let fitted = fit_reference_image(DynamicImage::ImageRgba8(img));
+        assert_eq!(fitted.dimensions(), (REFERENCE_MAX_DIM, 4));
+        let small = RgbaImage::new(3, 5);
+        let kept = fit_reference_image(DynamicImage::ImageRgba8(small));
+        assert_eq!(kept.dimensions(), (3, 5));
+    }
+}
@@ -60,13 +60,22 @@ impl SelectionMesh {
 }
 
 fn build_aabb_lines(min: (i32, i32, i32), max: (i32, i32, i32)) -> Vec<LineVertex> {
+    aabb_lines(min, max, SELECTION_COLOR)
+}
+
+/// The 12 edges (24 vertices) of the closed cell box `[min, max]`,
+/// spanning `min` to `max + 1` in world units, in `c`.
+pub(super) fn aabb_lines(
+    min: (i32, i32, i32),
+    max: (i32, i32, i32),
+    c: [f32; 4],
+) -> Vec<LineVertex> {
     let x0 = min.0 as f32;
     let y0 = min.1 as f32;
     let z0 = min.2 as f32;
     let x1 = (max.0 + 1) as f32;
     let y1 = (max.1 + 1) as f32;
     let z1 = (max.2 + 1) as f32;
-    let c = SELECTION_COLOR;
     let v = LineVertex::new;
 
     vec![
@@ -147,7 +156,7 @@ mod tests {
         let xs: Vec<f32> = v.iter().map(|lv| lv.position[0]).collect();
         assert!(xs.contains(&3.0));
         assert!(xs.contains(&4.0));
-        assert!(!xs.iter().any(|&x| x < 3.0 || x > 4.0));
+        assert!(xs.iter().all(|&x| (3.0..=4.0).contains(&x)));
     }
 
     #[test]
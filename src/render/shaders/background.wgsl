@@ -0,0 +1,69 @@
+// Viewport background (see `background.rs`): a fullscreen triangle
+// drawn first in the main pass, under everything else. Draws either a
+// vertical gradient in screen space or an equirectangular image looked
+// up along each pixel's view ray. A solid background is just the pass's
+// clear color and skips this draw.
+
+struct BackgroundParams {
+    inv_view_proj: mat4x4<f32>,
+    // Gradient colors (linear); a is unused
+    top: vec4<f32>,
+    bottom: vec4<f32>,
+    // x = kind (1 = gradient, 2 = image), y = image intensity,
+    // z = image turn around +Y in radians
+    params: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> bg: BackgroundParams;
+@group(0) @binding(1)
+var env_map: texture_2d<f32>;
+@group(0) @binding(2)
+var env_sampler: sampler;
+
+const PI: f32 = 3.14159265;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+// Single oversized triangle covering the viewport; no vertex buffer.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.ndc = vec2<f32>(x, y);
+    return out;
+}
+
+// Texture coordinates of direction `d` in an equirectangular image:
+// u turns with the azimuth, the image center looking down -Z; v runs
+// from straight up (0) to straight down (1). Must match
+// `equirect_dir` in background.rs (its inverse).
+fn equirect_uv(d: vec3<f32>) -> vec2<f32> {
+    let u = 0.5 + atan2(d.x, -d.z) / (2.0 * PI);
+    let v = acos(clamp(d.y, -1.0, 1.0)) / PI;
+    return vec2<f32>(u, v);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if bg.params.x < 1.5 {
+        let t = clamp(in.ndc.y * 0.5 + 0.5, 0.0, 1.0);
+        return vec4<f32>(mix(bg.bottom.rgb, bg.top.rgb, t), 1.0);
+    }
+    // View ray through this pixel: works for perspective and
+    // orthographic cameras alike.
+    let near = bg.inv_view_proj * vec4<f32>(in.ndc, 0.0, 1.0);
+    let far = bg.inv_view_proj * vec4<f32>(in.ndc, 1.0, 1.0);
+    let ray = normalize(far.xyz / far.w - near.xyz / near.w);
+    // Undo the image's turn: look up the ray in the image's own frame.
+    let c = cos(-bg.params.z);
+    let s = sin(-bg.params.z);
+    let d = vec3<f32>(c * ray.x + s * ray.z, ray.y, -s * ray.x + c * ray.z);
+    let color = textureSampleLevel(env_map, env_sampler, equirect_uv(d), 0.0).rgb;
+    return vec4<f32>(color * bg.params.y, 1.0);
+}
@@ -0,0 +1,50 @@
+// Fullscreen resolve of the path tracer's accumulation buffer: average
+// the summed samples and write them to the surface. The surface format
+// is sRGB, so the linear average is written as-is.
+
+struct TraceParams {
+    cam_pos: vec4<f32>,
+    cam_forward: vec4<f32>,
+    cam_right: vec4<f32>,
+    cam_up: vec4<f32>,
+    grid_origin: vec4<i32>,
+    grid_size: vec4<u32>,
+    frame: vec4<u32>,
+    surface: vec4<u32>,
+};
+
+@group(0) @binding(0)
+var<uniform> params: TraceParams;
+
+@group(0) @binding(1)
+var<storage, read> accum: array<vec4<f32>>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+// Single oversized triangle covering the viewport; no vertex buffer.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // The trace may run below surface resolution; stretch it.
+    let size = params.frame.yz;
+    let scale = vec2<f32>(size) / vec2<f32>(params.surface.xy);
+    let px = min(vec2<u32>(in.clip_position.xy * scale), size - vec2<u32>(1u));
+    let sum = accum[px.y * size.x + px.x];
+    if params.frame.w == 0u || sum.w <= 0.0 {
+        // No volume bound (the buffer may hold a stale image) or
+        // nothing accumulated yet — the viewport background color.
+        return vec4<f32>(0.1, 0.1, 0.15, 1.0);
+    }
+    let color = sum.rgb / sum.w;
+    return vec4<f32>(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)), 1.0);
+}
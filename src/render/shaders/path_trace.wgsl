@@ -0,0 +1,293 @@
+// Progressive path tracer over a dense voxel volume.
+//
+// One invocation per pixel per frame: a jittered (optionally thin-lens)
+// primary ray, DDA traversal of the packed voxel grid, cosine-weighted
+// diffuse bounces, next-event estimation towards a soft sun, emissive
+// voxels as area lights, and a gradient sky. Each frame adds one sample
+// into `accum`; `path_present.wgsl` divides by the sample count.
+
+struct TraceParams {
+    // xyz = eye position, w = tan(fov / 2)
+    cam_pos: vec4<f32>,
+    // xyz = forward, w = aspect
+    cam_forward: vec4<f32>,
+    // xyz = right, w = aperture radius (0 = pinhole)
+    cam_right: vec4<f32>,
+    // xyz = up, w = focus distance
+    cam_up: vec4<f32>,
+    // xyz = world cell of the volume's min corner
+    grid_origin: vec4<i32>,
+    // xyz = volume size in cells, w = max bounces
+    grid_size: vec4<u32>,
+    // x = sample index (0 restarts accumulation), y = width, z = height,
+    // w = 1 when a volume is bound (read by the present pass)
+    frame: vec4<u32>,
+    // xy = surface size; the trace resolution in `frame` may be lower
+    // (read by the present pass)
+    surface: vec4<u32>,
+};
+
+@group(0) @binding(0)
+var<uniform> params: TraceParams;
+
+// Packed cells: bits 0..24 = RGB (r lowest), bit 24 =
+// solid, bit 25 = emissive. See `VoxelVolume::pack`.
+@group(0) @binding(1)
+var<storage, read> voxels: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read_write> accum: array<vec4<f32>>;
+
+const SOLID_BIT: u32 = 0x01000000u;
+const EMISSIVE_BIT: u32 = 0x02000000u;
+
+// Same sun as the raster shader (`voxel.wgsl`), pre-normalized.
+const SUN_DIR: vec3<f32> = vec3<f32>(0.431934, 0.863868, 0.259161);
+// Irradiance chosen so a fully lit face lands near the raster view's
+// 0.3 ambient + 0.7 diffuse brightness.
+const SUN_COLOR: vec3<f32> = vec3<f32>(1.0, 0.95, 0.88);
+// Radius of the jitter sphere around SUN_DIR — larger = softer shadows.
+const SUN_SOFTNESS: f32 = 0.04;
+const EMISSIVE_STRENGTH: f32 = 4.0;
+const RAY_EPSILON: f32 = 1e-3;
+
+var<private> rng_state: u32;
+
+fn pcg(v: u32) -> u32 {
+    let state = v * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn rand() -> f32 {
+    rng_state = pcg(rng_state);
+    return f32(rng_state) / 4294967295.0;
+}
+
+fn random_in_disk() -> vec2<f32> {
+    let r = sqrt(rand());
+    let theta = 6.2831853 * rand();
+    return vec2<f32>(r * cos(theta), r * sin(theta));
+}
+
+fn random_unit_vector() -> vec3<f32> {
+    let z = rand() * 2.0 - 1.0;
+    let a = rand() * 6.2831853;
+    let r = sqrt(max(1.0 - z * z, 0.0));
+    return vec3<f32>(r * cos(a), r * sin(a), z);
+}
+
+// Cosine-weighted hemisphere sample around `n` (normal + unit sphere
+// point is cosine-distributed; the guard avoids a zero vector).
+fn cosine_hemisphere(n: vec3<f32>) -> vec3<f32> {
+    let d = n + random_unit_vector();
+    if dot(d, d) < 1e-8 {
+        return n;
+    }
+    return normalize(d);
+}
+
+// Colors are used as-is (no sRGB decode), matching how the raster
+// pipeline feeds `Voxel::color_f32` straight into lighting — so a
+// palette color reads the same in both modes.
+fn albedo_of(cell: u32) -> vec3<f32> {
+    let r = f32(cell & 0xffu) / 255.0;
+    let g = f32((cell >> 8u) & 0xffu) / 255.0;
+    let b = f32((cell >> 16u) & 0xffu) / 255.0;
+    return vec3<f32>(r, g, b);
+}
+
+fn sky(dir: vec3<f32>) -> vec3<f32> {
+    let up = clamp(dir.y, -1.0, 1.0);
+    if up < 0.0 {
+        // Dim ground bounce below the horizon.
+        return vec3<f32>(0.12, 0.11, 0.10);
+    }
+    let horizon = vec3<f32>(0.62, 0.68, 0.78);
+    let zenith = vec3<f32>(0.28, 0.40, 0.68);
+    return mix(horizon, zenith, up);
+}
+
+fn cell_at(c: vec3<i32>) -> u32 {
+    let local = c - params.grid_origin.xyz;
+    let size = vec3<i32>(params.grid_size.xyz);
+    if any(local < vec3<i32>(0)) || any(local >= size) {
+        return 0u;
+    }
+    let sx = params.grid_size.x;
+    let sy = params.grid_size.y;
+    let idx = u32(local.x) + u32(local.y) * sx + u32(local.z) * sx * sy;
+    return voxels[idx];
+}
+
+struct Hit {
+    found: bool,
+    t: f32,
+    normal: vec3<f32>,
+    cell: u32,
+};
+
+fn safe_inverse(d: vec3<f32>) -> vec3<f32> {
+    let eps = 1e-8;
+    let dx = select(d.x, select(-eps, eps, d.x >= 0.0), abs(d.x) < eps);
+    let dy = select(d.y, select(-eps, eps, d.y >= 0.0), abs(d.y) < eps);
+    let dz = select(d.z, select(-eps, eps, d.z >= 0.0), abs(d.z) < eps);
+    return vec3<f32>(1.0 / dx, 1.0 / dy, 1.0 / dz);
+}
+
+// Amanatides–Woo DDA through the volume, starting where the ray enters
+// its AABB (or at the origin when already inside).
+fn trace(origin: vec3<f32>, dir: vec3<f32>) -> Hit {
+    var miss: Hit;
+    miss.found = false;
+
+    let bmin = vec3<f32>(params.grid_origin.xyz);
+    let bmax = bmin + vec3<f32>(params.grid_size.xyz);
+    let inv = safe_inverse(dir);
+    let t0 = (bmin - origin) * inv;
+    let t1 = (bmax - origin) * inv;
+    let tn = min(t0, t1);
+    let tf = max(t0, t1);
+    let t_enter = max(max(tn.x, tn.y), tn.z);
+    let t_exit = min(min(tf.x, tf.y), tf.z);
+    if t_exit < max(t_enter, 0.0) {
+        return miss;
+    }
+
+    var t = max(t_enter, 0.0);
+    // Zero components step +1 to agree with `safe_inverse`'s +eps, so
+    // an axis-parallel ray's t_max on that axis is huge, not negative.
+    let step = select(vec3<i32>(-1), vec3<i32>(1), dir >= vec3<f32>(0.0));
+    var normal = vec3<f32>(0.0);
+    if t_enter > 0.0 {
+        if t_enter == tn.x {
+            normal = vec3<f32>(-f32(step.x), 0.0, 0.0);
+        } else if t_enter == tn.y {
+            normal = vec3<f32>(0.0, -f32(step.y), 0.0);
+        } else {
+            normal = vec3<f32>(0.0, 0.0, -f32(step.z));
+        }
+    }
+
+    let start = origin + dir * (t + 1e-4);
+    let size = vec3<i32>(params.grid_size.xyz);
+    var cell = clamp(
+        vec3<i32>(floor(start)),
+        params.grid_origin.xyz,
+        params.grid_origin.xyz + size - vec3<i32>(1),
+    );
+    let next = vec3<f32>(cell) + max(vec3<f32>(step), vec3<f32>(0.0));
+    var t_max = (next - origin) * inv;
+    let t_delta = abs(inv);
+
+    let max_steps = u32(size.x + size.y + size.z) + 3u;
+    for (var i = 0u; i < max_steps; i++) {
+        let v = cell_at(cell);
+        if (v & SOLID_BIT) != 0u {
+            var hit: Hit;
+            hit.found = true;
+            hit.t = t;
+            hit.normal = normal;
+            hit.cell = v;
+            return hit;
+        }
+        if t_max.x < t_max.y && t_max.x < t_max.z {
+            cell.x += step.x;
+            t = t_max.x;
+            t_max.x += t_delta.x;
+            normal = vec3<f32>(-f32(step.x), 0.0, 0.0);
+        } else if t_max.y < t_max.z {
+            cell.y += step.y;
+            t = t_max.y;
+            t_max.y += t_delta.y;
+            normal = vec3<f32>(0.0, -f32(step.y), 0.0);
+        } else {
+            cell.z += step.z;
+            t = t_max.z;
+            t_max.z += t_delta.z;
+            normal = vec3<f32>(0.0, 0.0, -f32(step.z));
+        }
+        if t > t_exit {
+            break;
+        }
+    }
+    return miss;
+}
+
+fn radiance(origin_in: vec3<f32>, dir_in: vec3<f32>) -> vec3<f32> {
+    var origin = origin_in;
+    var dir = dir_in;
+    var throughput = vec3<f32>(1.0);
+    var result = vec3<f32>(0.0);
+    let max_bounces = params.grid_size.w;
+
+    for (var bounce = 0u; bounce <= max_bounces; bounce++) {
+        let hit = trace(origin, dir);
+        if !hit.found {
+            result += throughput * sky(dir);
+            break;
+        }
+        let albedo = albedo_of(hit.cell);
+        if (hit.cell & EMISSIVE_BIT) != 0u {
+            result += throughput * albedo * EMISSIVE_STRENGTH;
+            break;
+        }
+
+        let p = origin + dir * hit.t + hit.normal * RAY_EPSILON;
+
+        // Next-event estimation towards a jittered sun direction —
+        // the jitter is what makes the shadows soft.
+        let sun = normalize(SUN_DIR + random_unit_vector() * SUN_SOFTNESS);
+        let ndl = dot(hit.normal, sun);
+        if ndl > 0.0 && !trace(p, sun).found {
+            result += throughput * albedo * SUN_COLOR * ndl;
+        }
+
+        throughput *= albedo;
+        origin = p;
+        dir = cosine_hemisphere(hit.normal);
+    }
+    return result;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn cs_trace(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let width = params.frame.y;
+    let height = params.frame.z;
+    if gid.x >= width || gid.y >= height {
+        return;
+    }
+    let idx = gid.y * width + gid.x;
+    rng_state = pcg(idx ^ pcg(params.frame.x * 9781u + 1u));
+
+    let ndc_x = ((f32(gid.x) + rand()) / f32(width)) * 2.0 - 1.0;
+    let ndc_y = 1.0 - ((f32(gid.y) + rand()) / f32(height)) * 2.0;
+
+    let tan_half = params.cam_pos.w;
+    let aspect = params.cam_forward.w;
+    let forward = params.cam_forward.xyz;
+    let right = params.cam_right.xyz;
+    let up = params.cam_up.xyz;
+
+    var origin = params.cam_pos.xyz;
+    var dir = normalize(forward + right * (ndc_x * tan_half * aspect) + up * (ndc_y * tan_half));
+
+    // Thin-lens depth of field: move the origin across the aperture
+    // disk and re-aim at the point this ray would hit on the focus
+    // plane, so geometry at `focus` stays sharp.
+    let aperture = params.cam_right.w;
+    if aperture > 0.0 {
+        let focus = params.cam_up.w;
+        let focus_point = origin + dir * (focus / dot(dir, forward));
+        let lens = random_in_disk() * aperture;
+        origin += right * lens.x + up * lens.y;
+        dir = normalize(focus_point - origin);
+    }
+
+    let c = radiance(origin, dir);
+    var prev = vec4<f32>(0.0);
+    if params.frame.x > 0u {
+        prev = accum[idx];
+    }
+    accum[idx] = prev + vec4<f32>(c, 1.0);
+}
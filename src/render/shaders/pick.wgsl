@@ -0,0 +1,55 @@
+// GPU picking: writes an ID for the voxel cell under each fragment into
+// an R32Uint target (see `picking.rs`).
+//
+// The ID packs the cell's coordinates modulo 1024 into bits 0..30 and
+// sets bit 30 so a hit is never 0, the cleared "nothing here" value.
+// The CPU side resolves the wrap-around against the camera position.
+
+struct PickUniform {
+    view_proj: mat4x4<f32>,
+    // Y-clip: x > 0 hides cells above y = clip.y (as in voxel.wgsl).
+    clip: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> pick: PickUniform;
+
+// Same layout as voxel.wgsl's input; only position and normal matter
+// here, the rest are declared so the chunk vertex buffers bind as-is.
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) ao: f32,
+    @location(4) tint_zone: f32,
+    @location(5) material: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+};
+
+const PICK_HIT: u32 = 0x40000000u;
+const PICK_MASK: u32 = 1023u;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = pick.view_proj * vec4<f32>(in.position, 1.0);
+    out.world_position = in.position;
+    out.normal = in.normal;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) u32 {
+    // Half a unit behind the face, as `fragment_cell` in voxel.wgsl.
+    let cell = vec3<i32>(floor(in.world_position - in.normal * 0.5));
+    if pick.clip.x > 0.0 && f32(cell.y) > pick.clip.y {
+        discard;
+    }
+    let bits = bitcast<vec3<u32>>(cell) & vec3<u32>(PICK_MASK);
+    return PICK_HIT | bits.x | (bits.y << 10u) | (bits.z << 20u);
+}
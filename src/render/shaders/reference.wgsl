@@ -0,0 +1,52 @@
+// Reference image planes (see `reference.rs`): textured quads drawn
+// see-through over the opaque voxels, depth-tested but not writing
+// depth, so the model hides what's behind it and the image never hides
+// the model's far side.
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct ReferenceParams {
+    // x = opacity
+    params: vec4<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> reference: ReferenceParams;
+@group(1) @binding(1)
+var image: texture_2d<f32>;
+@group(1) @binding(2)
+var image_sampler: sampler;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(in.position, 1.0);
+    out.uv = in.uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(image, image_sampler, in.uv);
+    let alpha = color.a * reference.params.x;
+    if alpha <= 0.0 {
+        discard;
+    }
+    return vec4<f32>(color.rgb, alpha);
+}
@@ -0,0 +1,154 @@
+// Toon outline post-process (see `toon.rs`): a fullscreen pass over
+// one pane that reads the main pass's depth buffer, rebuilds each
+// pixel's world position and surface normal from it, and draws the
+// outline color on
+// - silhouettes: background pixels next to the model,
+// - depth gaps: pixels next to a surface much nearer than the plane
+//   they lie on predicts (one part of the model in front of another),
+// - creases: pixels whose normal turns by more than the crease angle
+//   toward the next pixel right or down.
+// Lines sit outside the nearer surface, so a 1-pixel width traces the
+// silhouette without eating into it.
+//
+// `DEPTH_TEXTURE` is replaced with the depth binding's type when the
+// pipeline is built: a plain or a multisampled depth texture, to match
+// the MSAA level. `textureLoad`'s last argument is the mip level for
+// the one and the sample index for the other; 0 works for both.
+
+struct OutlineParams {
+    inv_view_proj: mat4x4<f32>,
+    // xyz = camera position
+    eye: vec4<f32>,
+    // xyz = camera forward (unit)
+    forward: vec4<f32>,
+    // Pane in framebuffer pixels: x, y, width, height
+    rect: vec4<f32>,
+    // rgb = line color (linear), a = 1
+    color: vec4<f32>,
+    // x = line width in pixels, y = cos of the crease angle,
+    // z = depth gap that counts as an edge, relative to view depth
+    params: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> outline: OutlineParams;
+@group(0) @binding(1)
+var depth_tex: DEPTH_TEXTURE;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+// Single oversized triangle covering the viewport; no vertex buffer.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    return out;
+}
+
+struct Sample {
+    pos: vec3<f32>,
+    // Distance along the camera's forward axis.
+    depth: f32,
+    // false = background (depth still cleared).
+    hit: bool,
+};
+
+// World position behind framebuffer pixel `px`, clamped into the pane.
+fn sample_at(px: vec2<i32>) -> Sample {
+    let lo = vec2<i32>(outline.rect.xy);
+    let hi = lo + vec2<i32>(outline.rect.zw) - vec2<i32>(1);
+    let p = clamp(px, lo, hi);
+    let d = textureLoad(depth_tex, p, 0);
+    let uv = (vec2<f32>(p - lo) + 0.5) / outline.rect.zw;
+    let world = outline.inv_view_proj * vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, d, 1.0);
+    var s: Sample;
+    s.pos = world.xyz / world.w;
+    s.depth = dot(s.pos - outline.eye.xyz, outline.forward.xyz);
+    s.hit = d < 1.0;
+    return s;
+}
+
+// Surface tangent at `c` along one screen axis, from its neighbors
+// `prev` and `next`: the side with the smaller depth step, so a
+// tangent next to an edge doesn't reach across it.
+fn tangent(prev: Sample, c: Sample, next: Sample) -> vec3<f32> {
+    let back = c.pos - prev.pos;
+    let ahead = next.pos - c.pos;
+    if !prev.hit {
+        return ahead;
+    }
+    if !next.hit {
+        return back;
+    }
+    return select(back, ahead, abs(next.depth - c.depth) < abs(c.depth - prev.depth));
+}
+
+// Reconstructed normal at `p` (zero when it can't be told, e.g. on
+// a one-pixel sliver); `c` is the sample at `p`.
+fn normal_at(p: vec2<i32>, c: Sample, w: i32) -> vec3<f32> {
+    let l = sample_at(p - vec2<i32>(w, 0));
+    let r = sample_at(p + vec2<i32>(w, 0));
+    let u = sample_at(p - vec2<i32>(0, w));
+    let d = sample_at(p + vec2<i32>(0, w));
+    let n = cross(tangent(l, c, r), tangent(u, c, d));
+    let len = length(n);
+    if len < 1e-12 {
+        return vec3<f32>(0.0);
+    }
+    return n / len;
+}
+
+// Whether neighbor `n` stands in front of `c` by more than the depth
+// gap, measured against the plane through `c` and the opposite
+// neighbor `o`.
+fn in_front(c: Sample, n: Sample, o: Sample) -> bool {
+    if !n.hit {
+        return false;
+    }
+    if !c.hit {
+        return true;
+    }
+    var predicted = c.depth;
+    if o.hit {
+        predicted = 2.0 * c.depth - o.depth;
+    }
+    return n.depth < predicted - outline.params.z * c.depth;
+}
+
+// Whether the normals `a` and `b` differ by more than the crease angle.
+fn creased(a: vec3<f32>, b: vec3<f32>) -> bool {
+    if all(a == vec3<f32>(0.0)) || all(b == vec3<f32>(0.0)) {
+        return false;
+    }
+    return dot(a, b) < outline.params.y;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let w = max(i32(outline.params.x), 1);
+    let p = vec2<i32>(in.clip_position.xy);
+    let c = sample_at(p);
+    let l = sample_at(p - vec2<i32>(w, 0));
+    let r = sample_at(p + vec2<i32>(w, 0));
+    let u = sample_at(p - vec2<i32>(0, w));
+    let d = sample_at(p + vec2<i32>(0, w));
+
+    var edge = in_front(c, l, r) || in_front(c, r, l) || in_front(c, u, d) || in_front(c, d, u);
+    if !edge && c.hit && outline.params.y < 1.0 {
+        let n = normal_at(p, c, w);
+        if r.hit {
+            edge = creased(n, normal_at(p + vec2<i32>(w, 0), r, w));
+        }
+        if !edge && d.hit {
+            edge = creased(n, normal_at(p + vec2<i32>(0, w), d, w));
+        }
+    }
+    if !edge {
+        discard;
+    }
+    return vec4<f32>(outline.color.rgb, 1.0);
+}
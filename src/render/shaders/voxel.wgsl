@@ -1,14 +1,108 @@
 // Voxel rendering shader
-// Supports basic lighting with ambient and directional components
+// Supports basic lighting with ambient and directional components, the
+// ambient term optionally shaped by the coarse GI volume (`gi.rs`)
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
     camera_pos: vec4<f32>,
+    // Work-plane dimming: x = axis index (< 0 = off), y = slice
+    // coordinate, z = fade toward the background for other slices.
+    slice: vec4<f32>,
+    // Y-clip: x > 0 hides cells above y = clip.y.
+    clip: vec4<f32>,
+    // Toon shading: x = lighting bands (0 = smooth).
+    toon: vec4<f32>,
 };
 
 @group(0) @binding(0)
 var<uniform> camera: CameraUniform;
 
+// Per-material shading, indexed by the voxel's material id (see
+// `MaterialTable::shading_table`): x = roughness, y = metallic,
+// z = emissive strength, w = transparency (already baked into alpha).
+// Must match `MATERIAL_SLOTS` in core/material.rs.
+const MATERIAL_SLOTS: u32 = 256u;
+
+struct MaterialUniform {
+    props: array<vec4<f32>, 256>,
+};
+
+@group(0) @binding(1)
+var<uniform> materials: MaterialUniform;
+
+// Placed point / spot lights (`lights.rs`). Must match `MAX_LIGHTS`
+// in core/light.rs.
+const MAX_LIGHTS: u32 = 16u;
+
+struct PlacedLight {
+    // xyz = world position, w = range (faded out at this distance)
+    position: vec4<f32>,
+    // rgb = color × intensity (linear), w = 1 for a spot light
+    color: vec4<f32>,
+    // Spot lights: xyz = unit direction, w = cos of the cone's rim
+    direction: vec4<f32>,
+    // Spot lights: x = cos of where the soft edge starts
+    cone: vec4<f32>,
+};
+
+struct LightsUniform {
+    // x = lights in use
+    count: vec4<u32>,
+    lights: array<PlacedLight, 16>,
+};
+
+@group(0) @binding(2)
+var<uniform> placed: LightsUniform;
+
+struct GiUniform {
+    // xyz = world position of the volume's min corner,
+    // w = intensity (0 = GI off, flat ambient)
+    origin: vec4<f32>,
+    // xyz = 1 / volume extent in world units, w = cell size
+    inv_extent: vec4<f32>,
+    // Ambient color for normals along +X, -X, +Y, -Y, +Z, -Z (all
+    // ones = flat ambient; an HDRI background sets them)
+    sky: array<vec4<f32>, 6>,
+};
+
+@group(1) @binding(0)
+var<uniform> gi: GiUniform;
+@group(1) @binding(1)
+var gi_volume: texture_3d<f32>;
+@group(1) @binding(2)
+var gi_sampler: sampler;
+
+// Must match `GI_RANGE` in gi.rs (the Rgba8 texture stores value / range).
+const GI_RANGE: f32 = 4.0;
+
+// Ambient multiplier from the GI volume: 1.0 = plain flat ambient.
+// Sampled half a cell off the surface so a face reads the light in
+// front of it rather than its own (mostly solid) cell. Outside the
+// volume is open sky, i.e. 1.0.
+fn gi_ambient(position: vec3<f32>, normal: vec3<f32>) -> vec3<f32> {
+    let intensity = gi.origin.w;
+    if intensity <= 0.0 {
+        return vec3<f32>(1.0);
+    }
+    let p = position + normal * (gi.inv_extent.w * 0.5);
+    let uvw = (p - gi.origin.xyz) * gi.inv_extent.xyz;
+    var irradiance = vec3<f32>(1.0);
+    if all(uvw >= vec3<f32>(0.0)) && all(uvw <= vec3<f32>(1.0)) {
+        irradiance = textureSampleLevel(gi_volume, gi_sampler, uvw, 0.0).rgb * GI_RANGE;
+    }
+    return mix(vec3<f32>(1.0), irradiance, intensity);
+}
+
+// Ambient color from the background image, blended between the three
+// axis colors the normal leans toward (weights sum to 1).
+fn sky_ambient(normal: vec3<f32>) -> vec3<f32> {
+    let n2 = normal * normal;
+    let x = select(gi.sky[1], gi.sky[0], normal.x > 0.0).rgb;
+    let y = select(gi.sky[3], gi.sky[2], normal.y > 0.0).rgb;
+    let z = select(gi.sky[5], gi.sky[4], normal.z > 0.0).rgb;
+    return n2.x * x + n2.y * y + n2.z * z;
+}
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
@@ -21,6 +115,8 @@ struct VertexInput {
     // unused by rendering, declared so the pipeline accepts the vertex
     // buffer's @location(4) attribute.
     @location(4) tint_zone: f32,
+    // Material id (`Voxel::material`), an index into `materials`.
+    @location(5) material: u32,
 };
 
 struct VertexOutput {
@@ -29,30 +125,72 @@ struct VertexOutput {
     @location(1) normal: vec3<f32>,
     @location(2) color: vec4<f32>,
     @location(3) ao: f32,
+    @location(4) @interpolate(flat) material: u32,
 };
 
 @vertex
 fn vs_main(in: VertexInput) -> VertexOutput {
+    return transform_vertex(in);
+}
+
+// Shared by `vs_main` and `vs_edge` (WGSL entry points can't call
+// each other).
+fn transform_vertex(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.clip_position = camera.view_proj * vec4<f32>(in.position, 1.0);
     out.world_position = in.position;
     out.normal = in.normal;
     out.color = in.color;
     out.ao = in.ao;
+    out.material = in.material;
     return out;
 }
 
+// The cell a fragment belongs to: half a unit behind its face, against
+// the normal, which is exact for the axis-aligned voxel faces.
+fn fragment_cell(in: VertexOutput) -> vec3<f32> {
+    return floor(in.world_position - in.normal * 0.5);
+}
+
+// Snap a 0..1 light factor up to the next of `bands` flat steps, for
+// toon shading. Unchanged while toon shading is off.
+fn toon_step(value: f32) -> f32 {
+    let bands = camera.toon.x;
+    if bands <= 0.0 {
+        return value;
+    }
+    return ceil(value * bands - 0.001) / bands;
+}
+
+// Fraction of a placed light left at `distance`, easing from 1 at the
+// light to 0 at `range`. Must match `Light::falloff` in core/light.rs.
+fn light_falloff(distance: f32, range: f32) -> f32 {
+    let x = clamp(distance / range, 0.0, 1.0);
+    let window = 1.0 - x * x;
+    return window * window;
+}
+
+// Whether the viewport's Y-clip hides this fragment's cell.
+fn clipped(in: VertexOutput) -> bool {
+    return camera.clip.x > 0.0 && fragment_cell(in).y > camera.clip.y;
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if clipped(in) {
+        discard;
+    }
     // Light direction (sun-like, from upper right)
     let light_dir = normalize(vec3<f32>(0.5, 1.0, 0.3));
 
-    // Ambient light
+    // Ambient light, shaped by GI when enabled (sky occlusion,
+    // emissive light, colored bounce) and tinted by an HDRI background
     let ambient_strength = 0.3;
-    let ambient = ambient_strength;
+    let ambient = ambient_strength * gi_ambient(in.world_position, in.normal)
+        * sky_ambient(in.normal);
 
     // Diffuse lighting
-    let diff = max(dot(in.normal, light_dir), 0.0);
+    let diff = toon_step(max(dot(in.normal, light_dir), 0.0));
     let diffuse = diff * 0.7;
 
     // Combine lighting
@@ -66,17 +204,65 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // to 0.6) — stacking aggressive AO on top would tip dark
     // corners into near-black.
     let ambient_min = 0.5;
-    let ao_factor = ambient_min + (1.0 - ambient_min) * in.ao;
+    let ao_factor = ambient_min + (1.0 - ambient_min) * toon_step(in.ao);
+
+    // Material: metals trade diffuse for a highlight tinted by their
+    // own color; smoother surfaces get a tighter, stronger highlight
+    // (Blinn-Phong). Roughness 1 (the default material) adds none, so
+    // unmaterialed voxels look exactly as before.
+    let props = materials.props[min(in.material, MATERIAL_SLOTS - 1u)];
+    let roughness = clamp(props.x, 0.0, 1.0);
+    let metallic = clamp(props.y, 0.0, 1.0);
+    let emissive = max(props.z, 0.0);
+
+    let camera_pos = camera.camera_pos.xyz;
+    let view_dir = normalize(camera_pos - in.world_position);
+    let half_dir = normalize(light_dir + view_dir);
+    let shininess = mix(128.0, 4.0, roughness);
+    let spec_color = mix(vec3<f32>(0.04), in.color.rgb, metallic);
+    var highlight = pow(max(dot(in.normal, half_dir), 0.0), shininess);
+    if camera.toon.x > 0.0 {
+        // Toon: a hard-edged highlight instead of a soft falloff.
+        highlight = step(0.5, highlight);
+    }
+    var specular = spec_color * (1.0 - roughness) * select(0.0, 1.0, diff > 0.0) * highlight;
 
-    // Apply lighting + AO to color
-    var result = in.color.rgb * lighting * ao_factor;
+    // Placed lights: the same diffuse and highlight as the sun, faded
+    // with distance and, for spots, toward the cone's rim.
+    var placed_diffuse = vec3<f32>(0.0);
+    for (var i = 0u; i < min(placed.count.x, MAX_LIGHTS); i++) {
+        let light = placed.lights[i];
+        let to_light = light.position.xyz - in.world_position;
+        let distance = length(to_light);
+        let l = to_light / max(distance, 1e-4);
+        var reach = light_falloff(distance, light.position.w);
+        if light.color.w > 0.5 {
+            reach *= smoothstep(light.direction.w, light.cone.x, dot(-l, light.direction.xyz));
+        }
+        let amount = toon_step(max(dot(in.normal, l), 0.0) * reach);
+        if amount <= 0.0 {
+            continue;
+        }
+        placed_diffuse += light.color.rgb * amount;
+        var light_highlight = pow(max(dot(in.normal, normalize(l + view_dir)), 0.0), shininess);
+        if camera.toon.x > 0.0 {
+            light_highlight = step(0.5, light_highlight);
+        }
+        specular += spec_color * (1.0 - roughness) * light.color.rgb * reach * light_highlight;
+    }
+    let diffuse_weight = 1.0 - 0.75 * metallic;
+
+    // Apply lighting + AO to color; emission is unlit (AO doesn't
+    // darken a glowing surface).
+    var result = in.color.rgb * (lighting + placed_diffuse) * ao_factor * diffuse_weight
+        + specular * ao_factor
+        + in.color.rgb * emissive;
 
     // Simple fog based on distance from camera. Tuned for ~256³
     // scenes — fog stays out of the way at typical editing zoom
     // and only kicks in when you really pull back. Editor's mouse-
     // raycast (in app/input.rs) uses the same scale so anything
     // visibly clear is also click-reachable.
-    let camera_pos = camera.camera_pos.xyz;
     let dist = length(in.world_position - camera_pos);
     let fog_start = 200.0;
     let fog_end = 800.0;
@@ -84,5 +270,33 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let fog_factor = clamp((dist - fog_start) / (fog_end - fog_start), 0.0, 1.0);
     result = mix(result, fog_color, fog_factor);
 
+    // Work plane: fade cells off the slice toward the background.
+    if camera.slice.x >= 0.0 {
+        let cell = fragment_cell(in);
+        let axis = u32(camera.slice.x);
+        if cell[axis] != camera.slice.y {
+            result = mix(result, fog_color, camera.slice.z);
+        }
+    }
+
     return vec4<f32>(result, in.color.a);
 }
+
+// Edge overlay ("shaded with edges"): the same vertices drawn as a line
+// list over the shaded mesh. Depth is pulled a hair toward the camera
+// (scaled by w so the offset is constant in NDC) so lines lying exactly
+// on their own faces win the depth test instead of z-fighting them.
+@vertex
+fn vs_edge(in: VertexInput) -> VertexOutput {
+    var out = transform_vertex(in);
+    out.clip_position.z -= 0.0005 * out.clip_position.w;
+    return out;
+}
+
+@fragment
+fn fs_edge(in: VertexOutput) -> @location(0) vec4<f32> {
+    if clipped(in) {
+        discard;
+    }
+    return vec4<f32>(in.color.rgb * 0.15, 1.0);
+}
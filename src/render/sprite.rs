@@ -0,0 +1,303 @@
+//! Pixel-art sprite rendering for Render ▸ Sprite Sheet.
+//!
+//! Sprites are drawn with an orthographic camera whose scale is an
+//! exact whole number of pixels per voxel, so every voxel edge lands on
+//! the same pixel grid in every frame — what 2D game art needs and a
+//! perspective screenshot can't give. The caller turns anti-aliasing
+//! off for the batch; [`outline`] then optionally rings the silhouette
+//! with a 1-pixel line on the read-back image, the classic sprite look.
+//!
+//! A batch renders the model from 1, 4 or 8 directions around +Y. All
+//! frames share one size ([`SpriteFrame::fit`] takes the largest
+//! extent over every direction), so they tile into a sheet and play
+//! back without jitter.
+
+use glam::{Quat, Vec3};
+use image::{Rgba, RgbaImage};
+
+use super::Camera;
+
+/// Empty pixels left around the model on every side, so an outline
+/// has room and the silhouette never touches the frame edge.
+pub const SPRITE_PADDING: u32 = 2;
+
+/// How far back the sprite camera sits from the model center. Any
+/// distance works for an orthographic view; this just keeps the whole
+/// model between the clip planes.
+const SPRITE_EYE_DISTANCE: f32 = 1000.0;
+
+/// Camera elevation of a sprite view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpriteView {
+    /// Classic pixel-art "isometric": 30° down, which makes the floor
+    /// diagonals run 2 pixels across per pixel up.
+    #[default]
+    Dimetric,
+    /// True isometric, ≈35.26° down: the three axes foreshorten
+    /// equally.
+    Isometric,
+    /// Level with the model, for side-on platformer sprites.
+    Side,
+}
+
+impl SpriteView {
+    pub const ALL: [SpriteView; 3] = [Self::Dimetric, Self::Isometric, Self::Side];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Dimetric => "2:1 pixel art",
+            Self::Isometric => "True isometric",
+            Self::Side => "Side",
+        }
+    }
+
+    /// Elevation of the camera above the horizon, in radians.
+    pub fn pitch(self) -> f32 {
+        match self {
+            Self::Dimetric => 30f32.to_radians(),
+            Self::Isometric => (1.0 / 2f32.sqrt()).atan(),
+            Self::Side => 0.0,
+        }
+    }
+
+    /// Whether the view sits diagonally to the model (looking along a
+    /// box diagonal, as isometric art does) rather than square on.
+    fn diagonal(self) -> bool {
+        !matches!(self, Self::Side)
+    }
+}
+
+/// How many directions a batch renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpriteDirections {
+    One,
+    Four,
+    #[default]
+    Eight,
+}
+
+impl SpriteDirections {
+    pub const ALL: [SpriteDirections; 3] = [Self::One, Self::Four, Self::Eight];
+
+    pub fn count(self) -> u32 {
+        match self {
+            Self::One => 1,
+            Self::Four => 4,
+            Self::Eight => 8,
+        }
+    }
+
+    /// Turn of each frame around +Y in whole degrees, starting from the
+    /// front view and going counter-clockwise seen from above.
+    pub fn angles(self) -> Vec<u32> {
+        let step = 360 / self.count();
+        (0..self.count()).map(|i| i * step).collect()
+    }
+}
+
+/// Shared pixel size and world-space extent of every frame in a batch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels_per_voxel: u32,
+    /// Voxel corner the cameras aim at, nearest the model's center.
+    center: Vec3,
+}
+
+impl SpriteFrame {
+    /// Size the frame to hold the AABB `min..=max` (inclusive voxel
+    /// coordinates) from every angle in `angles` at `pixels_per_voxel`,
+    /// plus [`SPRITE_PADDING`]. The cameras aim at the voxel corner
+    /// nearest the box center, which lands on the frame's middle pixel
+    /// corner — so in the side view every voxel edge falls on a pixel
+    /// edge.
+    pub fn fit(
+        min: Vec3,
+        max: Vec3,
+        view: SpriteView,
+        angles: &[u32],
+        pixels_per_voxel: u32,
+    ) -> Self {
+        let (lo, hi) = (min, max + Vec3::ONE);
+        let center = ((lo + hi) * 0.5).round();
+        let corners = [lo.x, hi.x].into_iter().flat_map(|x| {
+            [lo.y, hi.y]
+                .into_iter()
+                .flat_map(move |y| [lo.z, hi.z].into_iter().map(move |z| Vec3::new(x, y, z)))
+        });
+        let ppv = pixels_per_voxel.max(1);
+        let (mut half_w, mut half_h) = (0.0f32, 0.0f32);
+        for &angle in angles {
+            let (right, up, _) = sprite_axes(view, angle);
+            for corner in corners.clone() {
+                let offset = corner - center;
+                half_w = half_w.max(offset.dot(right).abs());
+                half_h = half_h.max(offset.dot(up).abs());
+            }
+        }
+        // Whole pixels per half, so the frame is even and `center` sits
+        // on the middle pixel corner.
+        let half_px = |h: f32| (h * ppv as f32 - 1e-3).ceil().max(1.0) as u32;
+        Self {
+            width: 2 * (half_px(half_w) + SPRITE_PADDING),
+            height: 2 * (half_px(half_h) + SPRITE_PADDING),
+            pixels_per_voxel: ppv,
+            center,
+        }
+    }
+
+    /// Orthographic camera for the frame at `angle` degrees: exactly
+    /// `pixels_per_voxel` pixels per world unit on screen.
+    pub fn camera(&self, view: SpriteView, angle: u32) -> Camera {
+        let (_, up, back) = sprite_axes(view, angle);
+        let mut camera = Camera::new(
+            self.center + back * SPRITE_EYE_DISTANCE,
+            self.center,
+            self.width as f32 / self.height as f32,
+        );
+        camera.up = up;
+        camera.near = 1.0;
+        camera.far = SPRITE_EYE_DISTANCE * 2.0;
+        camera.ortho_half_height = Some(self.height as f32 * 0.5 / self.pixels_per_voxel as f32);
+        camera
+    }
+}
+
+/// Screen right, screen up, and the direction from the model to the
+/// camera for a sprite view turned `angle` degrees around +Y.
+fn sprite_axes(view: SpriteView, angle: u32) -> (Vec3, Vec3, Vec3) {
+    let base_yaw = if view.diagonal() { 45f32 } else { 0.0 };
+    let yaw = Quat::from_rotation_y((base_yaw + angle as f32).to_radians());
+    let pitch = view.pitch();
+    let back = yaw * Vec3::new(0.0, pitch.sin(), pitch.cos());
+    let right = yaw * Vec3::X;
+    let up = back.cross(right).normalize();
+    (right, up, back)
+}
+
+/// Ring the opaque silhouette of `image` with a 1-pixel `color` line:
+/// every transparent pixel with an opaque 4-neighbor takes the color.
+/// Pixels count as opaque from half alpha up, so all but the faintest
+/// translucent voxels get outlined too.
+pub fn outline(image: &RgbaImage, color: [u8; 4]) -> RgbaImage {
+    let (w, h) = image.dimensions();
+    let solid = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && (x as u32) < w
+            && (y as u32) < h
+            && image.get_pixel(x as u32, y as u32)[3] >= 128
+    };
+    let mut out = image.clone();
+    for y in 0..h as i64 {
+        for x in 0..w as i64 {
+            if solid(x, y) {
+                continue;
+            }
+            if solid(x - 1, y) || solid(x + 1, y) || solid(x, y - 1) || solid(x, y + 1) {
+                out.put_pixel(x as u32, y as u32, Rgba(color));
+            }
+        }
+    }
+    out
+}
+
+/// Lay equally sized `frames` out left to right in one image.
+pub fn sprite_sheet(frames: &[RgbaImage]) -> RgbaImage {
+    let Some(first) = frames.first() else {
+        return RgbaImage::new(0, 0);
+    };
+    let (w, h) = first.dimensions();
+    let mut sheet = RgbaImage::new(w * frames.len() as u32, h);
+    for (i, frame) in frames.iter().enumerate() {
+        image::imageops::replace(&mut sheet, frame, (i as u32 * w) as i64, 0);
+    }
+    sheet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directions_split_the_turn_evenly() {
+        assert_eq!(SpriteDirections::One.angles(), vec![0]);
+        assert_eq!(SpriteDirections::Four.angles(), vec![0, 90, 180, 270]);
+        assert_eq!(SpriteDirections::Eight.angles().len(), 8);
+        assert_eq!(SpriteDirections::Eight.angles()[3], 135);
+    }
+
+    #[test]
+    fn side_frame_is_pixels_per_voxel_exact() {
+        // A 4×2×4 box seen square on: 4 voxels wide, 2 tall.
+        let frame = SpriteFrame::fit(
+            Vec3::ZERO,
+            Vec3::new(3.0, 1.0, 3.0),
+            SpriteView::Side,
+            &[0],
+            3,
+        );
+        assert_eq!(frame.width, 4 * 3 + 2 * SPRITE_PADDING);
+        assert_eq!(frame.height, 2 * 3 + 2 * SPRITE_PADDING);
+        let camera = frame.camera(SpriteView::Side, 0);
+        let h = camera.ortho_half_height.unwrap();
+        assert!((2.0 * h * 3.0 - frame.height as f32).abs() < 1e-4);
+        // A box corner lands exactly on a pixel corner inside the padding.
+        let p = camera.view_projection_matrix().project_point3(Vec3::new(0.0, 0.0, 2.0));
+        let px = (p.x * 0.5 + 0.5) * frame.width as f32;
+        assert!((px - SPRITE_PADDING as f32).abs() < 1e-3);
+    }
+
+    #[test]
+    fn odd_sized_model_stays_on_the_pixel_grid() {
+        // 3 voxels wide: the aim point snaps to a voxel corner, so the
+        // far edge needs 2 voxels of half-width and every edge stays on
+        // a pixel edge.
+        let frame = SpriteFrame::fit(Vec3::ZERO, Vec3::new(2.0, 0.0, 0.0), SpriteView::Side, &[0], 3);
+        assert_eq!(frame.width, 2 * (2 * 3 + SPRITE_PADDING));
+        let camera = frame.camera(SpriteView::Side, 0);
+        for x in 0..=3 {
+            let p = camera
+                .view_projection_matrix()
+                .project_point3(Vec3::new(x as f32, 0.0, 1.0));
+            let px = (p.x * 0.5 + 0.5) * frame.width as f32;
+            assert!((px - px.round()).abs() < 1e-3, "edge {x} at {px}");
+        }
+    }
+
+    #[test]
+    fn frame_fits_every_direction() {
+        // A long box turned 90° needs its length in both dimensions.
+        let frame = SpriteFrame::fit(
+            Vec3::ZERO,
+            Vec3::new(9.0, 0.0, 1.0),
+            SpriteView::Side,
+            &SpriteDirections::Four.angles(),
+            1,
+        );
+        assert_eq!(frame.width, 10 + 2 * SPRITE_PADDING);
+    }
+
+    #[test]
+    fn outline_rings_the_silhouette() {
+        let mut image = RgbaImage::new(5, 5);
+        image.put_pixel(2, 2, Rgba([255, 0, 0, 255]));
+        let out = outline(&image, [0, 0, 0, 255]);
+        assert_eq!(out.get_pixel(2, 2), &Rgba([255, 0, 0, 255]));
+        for (x, y) in [(1, 2), (3, 2), (2, 1), (2, 3)] {
+            assert_eq!(out.get_pixel(x, y), &Rgba([0, 0, 0, 255]));
+        }
+        // Diagonals stay empty: a 4-connected ring.
+        assert_eq!(out.get_pixel(1, 1)[3], 0);
+    }
+
+    #[test]
+    fn sheet_places_frames_side_by_side() {
+        let a = RgbaImage::from_pixel(2, 3, Rgba([1, 1, 1, 255]));
+        let b = RgbaImage::from_pixel(2, 3, Rgba([2, 2, 2, 255]));
+        let sheet = sprite_sheet(&[a, b]);
+        assert_eq!(sheet.dimensions(), (4, 3));
+        assert_eq!(sheet.get_pixel(3, 0), &Rgba([2, 2, 2, 255]));
+    }
+}
@@ -0,0 +1,365 @@
+//! Offscreen render-to-texture for project thumbnails, turntables and
+//! screenshots.
+//!
+//! Draws the chunk meshes — plus the grid and axes if asked, never the
+//! editing overlays — into a texture of the surface format, copies it
+//! into a mappable buffer and reads it back as an RGBA image. Used on
+//! project save to embed a preview in the `.vxlt` (see
+//! `io::read_thumbnail`), frame by frame by Render ▸ Turntable, and by
+//! Render ▸ Screenshot, which renders larger than the requested size
+//! and box-filters down ([`downsample`]) for supersampling. With toon
+//! shading on, the outline pass runs over the result as in the
+//! viewport, so every export gets the same stylized look.
+//!
+//! The readback blocks on `Device::poll(Wait)`, which is fine for a
+//! one-off 256² frame on an explicit save (or one turntable frame per
+//! app frame) but is why autosave doesn't take a thumbnail.
+
+use glam::Vec3;
+use image::RgbaImage;
+
+use super::{Camera, Renderer};
+
+/// Edge length of the square thumbnail, in pixels.
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// What an offscreen render draws besides the voxels. The default —
+/// viewport background, no grid or axes — is the thumbnail / turntable
+/// look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OffscreenStyle {
+    /// Clear to fully transparent instead of the viewport background,
+    /// for compositing the model over something else.
+    pub transparent_background: bool,
+    pub show_grid: bool,
+    pub show_axes: bool,
+    /// Factor the image is shrunk by afterwards (supersampling); toon
+    /// outlines are drawn that many times wider so they keep their
+    /// width. 0 and 1 both mean no shrinking.
+    pub downsample: u32,
+}
+
+/// Square perspective camera framing the AABB `min..=max` (voxel
+/// coordinates, inclusive) from `main`'s viewing direction, so the
+/// thumbnail shows the model the way the user was looking at it.
+pub fn thumbnail_camera(main: &Camera, min: Vec3, max: Vec3) -> Camera {
+    turntable_camera(main, min, max, 1.0, 0.0)
+}
+
+/// Camera framing the AABB `min..=max` like [`thumbnail_camera`], with
+/// `aspect` (width / height) and `main`'s viewing direction turned by
+/// `angle` radians around +Y through the box center. The fit uses the
+/// box's bounding sphere, so the model stays framed at every angle of
+/// a turntable spin.
+pub fn turntable_camera(main: &Camera, min: Vec3, max: Vec3, aspect: f32, angle: f32) -> Camera {
+    // Voxel (x, y, z) spans x..x+1, so the box ends one past `max`.
+    let (lo, hi) = (min, max + Vec3::ONE);
+    let center = (lo + hi) * 0.5;
+    let mut camera = Camera::new(main.position, center, aspect);
+    camera.fov = main.fov;
+    let dir = (main.position - main.target)
+        .try_normalize()
+        .unwrap_or(Vec3::new(0.0, 0.5, 1.0).normalize());
+    let dir = glam::Quat::from_rotation_y(angle) * dir;
+    let distance = camera.fit_distance(hi - lo, 1.1);
+    camera.position = center + dir * distance;
+    // Keep the whole box between the clip planes however big it is.
+    camera.far = camera.far.max(distance * 2.0 + (hi - lo).length());
+    camera
+}
+
+impl Renderer {
+    /// Render the loaded chunk meshes from `camera` into a `size`²
+    /// offscreen target and read the pixels back; see
+    /// [`render_offscreen`](Self::render_offscreen).
+    pub fn render_thumbnail(&mut self, camera: &Camera, size: u32) -> Option<RgbaImage> {
+        self.render_offscreen(camera, size, size, OffscreenStyle::default())
+    }
+
+    /// Render the loaded chunk meshes from `camera` into a `width` ×
+    /// `height` offscreen target and read the pixels back. `None` when
+    /// the surface format isn't 8-bit RGBA/BGRA (the voxel pipeline is
+    /// built for the surface format, so the target must share it), the
+    /// size is zero or beyond the device's texture limit, or the
+    /// readback fails.
+    pub fn render_offscreen(
+        &mut self,
+        camera: &Camera,
+        width: u32,
+        height: u32,
+        style: OffscreenStyle,
+    ) -> Option<RgbaImage> {
+        use wgpu::TextureFormat as F;
+        let format = self.config.format;
+        let swap_rb = match format {
+            F::Rgba8Unorm | F::Rgba8UnormSrgb => false,
+            F::Bgra8Unorm | F::Bgra8UnormSrgb => true,
+            _ => return None,
+        };
+        let max_dim = self.device.limits().max_texture_dimension_2d;
+        if width == 0 || height == 0 || width > max_dim || height > max_dim {
+            return None;
+        }
+
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let color = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Thumbnail Color"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        // The voxel pipelines are built for the viewport's MSAA level,
+        // so the thumbnail draws at that sample count too and resolves
+        // into `color` for readback.
+        let samples = self.msaa.samples();
+        let msaa_color = (samples > 1).then(|| {
+            self.device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some("Thumbnail MSAA Color"),
+                    size: extent,
+                    mip_level_count: 1,
+                    sample_count: samples,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        });
+        let depth = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Thumbnail Depth"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: samples,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            // Sampled by the toon outline pass.
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let color_view = color.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Rows in a texture→buffer copy must be 256-byte aligned.
+        let row_bytes = width * 4;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_row = row_bytes.div_ceil(align) * align;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Thumbnail Readback"),
+            size: padded_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        self.pipeline.update_camera(&self.queue, camera);
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Thumbnail Encoder"),
+            });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Thumbnail Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: msaa_color.as_ref().unwrap_or(&color_view),
+                    resolve_target: msaa_color.as_ref().map(|_| &color_view),
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(if style.transparent_background {
+                            wgpu::Color::TRANSPARENT
+                        } else {
+                            self.clear_color()
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            // Same background as the viewport, so the render looks
+            // like the editor.
+            if !style.transparent_background {
+                self.draw_background(&mut render_pass, camera);
+            }
+            if style.show_grid {
+                self.draw_grid(&mut render_pass);
+            }
+            if style.show_axes {
+                self.draw_axes(&mut render_pass);
+            }
+            self.draw_opaque_chunks(&mut render_pass);
+            self.draw_transparent_chunks(&mut render_pass, camera);
+        }
+        if let Some(toon) = self.toon.as_ref().filter(|t| t.outlines) {
+            let outline = &self.toon_outline;
+            let depth = outline.bind_depth(&self.device, &depth_view);
+            outline.write_params(&self.queue, camera, [0, 0, width, height], toon, style.downsample);
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Thumbnail Outline Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: msaa_color.as_ref().unwrap_or(&color_view),
+                    resolve_target: msaa_color.as_ref().map(|_| &color_view),
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            outline.draw(&mut render_pass, &depth);
+        }
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &color,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            extent,
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+        // The camera uniform is shared with the main pass; put the
+        // editor camera back before the next frame's submit.
+        self.pipeline.update_camera(&self.queue, &self.camera);
+
+        let slice = buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv().ok()?.ok()?;
+
+        let mut pixels = Vec::with_capacity(row_bytes as usize * height as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks_exact(padded_row as usize) {
+                pixels.extend_from_slice(&row[..row_bytes as usize]);
+            }
+        }
+        buffer.unmap();
+        if swap_rb {
+            for px in pixels.chunks_exact_mut(4) {
+                px.swap(0, 2);
+            }
+        }
+        RgbaImage::from_raw(width, height, pixels)
+    }
+}
+
+/// Shrink `image` by an integer `factor` with a box filter: each output
+/// pixel averages a `factor`² block — supersampling's resolve step.
+/// Colors are averaged weighted by alpha, so on a transparent
+/// background the (meaningless) color of empty pixels doesn't darken
+/// the model's edges. A `factor` of 1 or less returns the image as is.
+pub fn downsample(image: &RgbaImage, factor: u32) -> RgbaImage {
+    if factor <= 1 {
+        return image.clone();
+    }
+    let (width, height) = (image.width() / factor, image.height() / factor);
+    let samples = (factor * factor) as f32;
+    RgbaImage::from_fn(width, height, |x, y| {
+        let mut rgb = [0.0f32; 3];
+        let mut alpha = 0.0f32;
+        for dy in 0..factor {
+            for dx in 0..factor {
+                let p = image.get_pixel(x * factor + dx, y * factor + dy);
+                let a = p[3] as f32;
+                for c in 0..3 {
+                    rgb[c] += p[c] as f32 * a;
+                }
+                alpha += a;
+            }
+        }
+        let color = |c: f32| if alpha > 0.0 { (c / alpha).round() as u8 } else { 0 };
+        image::Rgba([
+            color(rgb[0]),
+            color(rgb[1]),
+            color(rgb[2]),
+            (alpha / samples).round() as u8,
+        ])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_camera_frames_box_from_main_direction() {
+        let main = Camera::new(Vec3::new(0.0, 0.0, 50.0), Vec3::ZERO, 16.0 / 9.0);
+        let camera =
+            thumbnail_camera(&main, Vec3::new(-10.0, 0.0, -10.0), Vec3::new(9.0, 19.0, 9.0));
+        assert_eq!(camera.aspect, 1.0);
+        assert_eq!(camera.target, Vec3::new(0.0, 10.0, 0.0));
+        // Same viewing direction as the editor camera (looking down -Z).
+        let dir = (camera.position - camera.target).normalize();
+        assert!((dir - Vec3::Z).length() < 1e-5);
+        // Far enough that the box's bounding sphere fits.
+        let radius = Vec3::splat(20.0).length() * 0.5;
+        assert!(camera.position.distance(camera.target) > radius);
+    }
+
+    #[test]
+    fn test_turntable_camera_orbits_center_at_fixed_distance() {
+        let main = Camera::new(Vec3::new(0.0, 0.0, 50.0), Vec3::ZERO, 1.0);
+        let (min, max) = (Vec3::new(-4.0, 0.0, -4.0), Vec3::new(3.0, 7.0, 3.0));
+        let start = turntable_camera(&main, min, max, 16.0 / 9.0, 0.0);
+        let quarter = turntable_camera(&main, min, max, 16.0 / 9.0, std::f32::consts::FRAC_PI_2);
+        assert_eq!(start.aspect, 16.0 / 9.0);
+        assert_eq!(quarter.target, start.target);
+        let (a, b) = (start.position - start.target, quarter.position - quarter.target);
+        assert!((a.length() - b.length()).abs() < 1e-3);
+        // A quarter turn about +Y takes +Z to +X.
+        assert!((b.normalize() - Vec3::X).length() < 1e-5);
+        // Angle 0 is the thumbnail framing with the requested aspect.
+        assert_eq!(start.position, thumbnail_camera(&main, min, max).position);
+    }
+
+    #[test]
+    fn test_downsample_averages_blocks_ignoring_empty_color() {
+        // 4×2 → 2×1 at factor 2. Left block: black and white opaque
+        // pixels average to mid grey. Right block: one opaque red pixel
+        // and three fully transparent (garbage green) ones — color stays
+        // pure red, coverage drops to a quarter.
+        let mut image = RgbaImage::new(4, 2);
+        image.put_pixel(0, 0, image::Rgba([0, 0, 0, 255]));
+        image.put_pixel(1, 0, image::Rgba([255, 255, 255, 255]));
+        image.put_pixel(0, 1, image::Rgba([0, 0, 0, 255]));
+        image.put_pixel(1, 1, image::Rgba([255, 255, 255, 255]));
+        image.put_pixel(2, 0, image::Rgba([255, 0, 0, 255]));
+        for (x, y) in [(3, 0), (2, 1), (3, 1)] {
+            image.put_pixel(x, y, image::Rgba([0, 255, 0, 0]));
+        }
+        let small = downsample(&image, 2);
+        assert_eq!(small.dimensions(), (2, 1));
+        assert_eq!(small.get_pixel(0, 0).0, [128, 128, 128, 255]);
+        assert_eq!(small.get_pixel(1, 0).0, [255, 0, 0, 64]);
+        assert_eq!(downsample(&image, 1), image);
+    }
+}
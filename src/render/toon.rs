@@ -0,0 +1,320 @@
+//! Toon shading: the stylized "illustrated" look for the raster view
+//! and the offscreen renders.
+//!
+//! Two parts. The voxel shader snaps its diffuse light and AO to a few
+//! flat bands and hardens the specular highlight (`CameraUniform::toon`,
+//! set through `RenderPipeline::set_toon_bands`). Then [`ToonOutline`]
+//! runs a fullscreen pass over the finished frame that reads the main
+//! pass's depth buffer, rebuilds positions and normals from it, and
+//! inks silhouettes, depth gaps and creases (`shaders/toon_outline.wgsl`).
+//! Working from depth alone keeps the main pass as it is — no extra
+//! normal target for every pipeline to write.
+//!
+//! The outline pass draws into the same color target as the main pass
+//! (the multisampled one with MSAA on), so in quad view a later pane's
+//! resolve carries the earlier panes' lines along instead of wiping
+//! them.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use super::Camera;
+
+/// Depth step, as a fraction of the view depth, beyond what the
+/// surface's own slope predicts, that counts as an outline.
+const DEPTH_GAP: f32 = 0.02;
+
+/// User-facing toon settings, persisted with the viewport settings.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ToonSettings {
+    /// Flat lighting steps between shadow and full light.
+    pub bands: u32,
+    /// Ink silhouettes and creases.
+    pub outlines: bool,
+    /// Line width in pixels.
+    pub outline_width: u32,
+    /// Line color, linear RGB.
+    pub outline_color: [f32; 3],
+    /// Smallest turn between neighboring surfaces, in degrees, that
+    /// gets a crease line. Anything under 90 catches every voxel
+    /// corner.
+    pub crease_angle: f32,
+}
+
+impl Default for ToonSettings {
+    fn default() -> Self {
+        Self {
+            bands: 3,
+            outlines: true,
+            outline_width: 1,
+            outline_color: [0.01, 0.01, 0.02],
+            crease_angle: 40.0,
+        }
+    }
+}
+
+/// Uniform block of `toon_outline.wgsl`; field packing is documented
+/// there.
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+struct OutlineParams {
+    inv_view_proj: [[f32; 4]; 4],
+    eye: [f32; 4],
+    forward: [f32; 4],
+    rect: [f32; 4],
+    color: [f32; 4],
+    params: [f32; 4],
+}
+
+impl OutlineParams {
+    /// Parameters for outlining the pane `rect` (`[x, y, width,
+    /// height]` in framebuffer pixels) drawn from `camera`, with lines
+    /// `scale` times the set width.
+    fn new(camera: &Camera, rect: [u32; 4], settings: &ToonSettings, scale: u32) -> Self {
+        let inv = camera.view_projection_matrix().inverse();
+        let eye = camera.position;
+        let forward = camera.forward();
+        let [r, g, b] = settings.outline_color;
+        let width = settings.outline_width.max(1) * scale.max(1);
+        Self {
+            inv_view_proj: inv.to_cols_array_2d(),
+            eye: [eye.x, eye.y, eye.z, 1.0],
+            forward: [forward.x, forward.y, forward.z, 0.0],
+            rect: rect.map(|v| v as f32),
+            color: [r, g, b, 1.0],
+            params: [
+                width as f32,
+                settings.crease_angle.clamp(0.0, 180.0).to_radians().cos(),
+                DEPTH_GAP,
+                0.0,
+            ],
+        }
+    }
+}
+
+/// The outline post-process: pipeline, parameters and the depth
+/// binding. Rebuilt with the voxel pipelines when the MSAA level
+/// changes, since the depth texture's type follows it.
+pub struct ToonOutline {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+    params_buffer: wgpu::Buffer,
+    /// Binds the renderer's main depth texture. Re-made with
+    /// [`Self::bind_depth`] whenever that texture is recreated.
+    pub depth_bind_group: wgpu::BindGroup,
+    surface_format: wgpu::TextureFormat,
+}
+
+impl ToonOutline {
+    /// Outline pass for `sample_count`-sample targets, reading `depth`.
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        sample_count: u32,
+        depth: &wgpu::TextureView,
+    ) -> Self {
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Toon Outline Params"),
+            contents: bytemuck::cast_slice(&[OutlineParams::zeroed()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let (layout, pipeline) = Self::build(device, surface_format, sample_count);
+        let depth_bind_group = Self::create_bind_group(device, &layout, &params_buffer, depth);
+        Self {
+            pipeline,
+            layout,
+            params_buffer,
+            depth_bind_group,
+            surface_format,
+        }
+    }
+
+    /// Recreate the pipeline for a new sample count and bind the new
+    /// depth texture that came with it.
+    pub fn rebuild(&mut self, device: &wgpu::Device, sample_count: u32, depth: &wgpu::TextureView) {
+        (self.layout, self.pipeline) = Self::build(device, self.surface_format, sample_count);
+        self.depth_bind_group = self.bind_depth(device, depth);
+    }
+
+    /// A bind group reading `depth`, which must have the sample count
+    /// the pass was built for.
+    pub fn bind_depth(&self, device: &wgpu::Device, depth: &wgpu::TextureView) -> wgpu::BindGroup {
+        Self::create_bind_group(device, &self.layout, &self.params_buffer, depth)
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        params: &wgpu::Buffer,
+        depth: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Toon Outline Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(depth),
+                },
+            ],
+        })
+    }
+
+    fn build(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> (wgpu::BindGroupLayout, wgpu::RenderPipeline) {
+        let multisampled = sample_count > 1;
+        let depth_type = if multisampled {
+            "texture_depth_multisampled_2d"
+        } else {
+            "texture_depth_2d"
+        };
+        let source = include_str!("shaders/toon_outline.wgsl").replace("DEPTH_TEXTURE", depth_type);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Toon Outline Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Toon Outline Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Toon Outline Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Toon Outline Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+        (layout, pipeline)
+    }
+
+    /// Set the parameters for the next outline draw: the pane `rect`
+    /// (`[x, y, width, height]` in pixels) seen from `camera`, lines
+    /// `scale` times as wide as set. Lands at the next submit, like a
+    /// camera upload.
+    pub fn write_params(
+        &self,
+        queue: &wgpu::Queue,
+        camera: &Camera,
+        rect: [u32; 4],
+        settings: &ToonSettings,
+        scale: u32,
+    ) {
+        let params = OutlineParams::new(camera, rect, settings, scale);
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+    }
+
+    /// Draw the outlines over the pane, reading depth through `depth`
+    /// (see [`Self::bind_depth`]). The caller sets the viewport and
+    /// scissor to the pane.
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, depth: &'a wgpu::BindGroup) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, depth, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::{Mat4, Vec3, Vec4};
+
+    /// World position behind pixel center `(px, py)` of a `width` ×
+    /// `height` view at depth-buffer value `depth` — the CPU twin of the
+    /// reconstruction in `toon_outline.wgsl`.
+    fn unproject(inv_view_proj: Mat4, px: f32, py: f32, width: f32, height: f32, depth: f32) -> Vec3 {
+        let ndc = Vec4::new(
+            (px + 0.5) / width * 2.0 - 1.0,
+            1.0 - (py + 0.5) / height * 2.0,
+            depth,
+            1.0,
+        );
+        let world = inv_view_proj * ndc;
+        world.truncate() / world.w
+    }
+
+    #[test]
+    fn params_carry_width_angle_and_pane() {
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO, 2.0);
+        let settings = ToonSettings {
+            outline_width: 2,
+            crease_angle: 90.0,
+            ..ToonSettings::default()
+        };
+        let params = OutlineParams::new(&camera, [10, 20, 400, 200], &settings, 3);
+        assert_eq!(params.rect, [10.0, 20.0, 400.0, 200.0]);
+        assert_eq!(params.params[0], 6.0);
+        assert!(params.params[1].abs() < 1e-6);
+        assert_eq!(params.forward, [0.0, 0.0, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn depth_unprojects_to_the_drawn_point() {
+        // The shader's reconstruction inverts the camera's projection:
+        // a point projected to a pixel center comes back where it was.
+        let camera = Camera::new(Vec3::new(3.0, 4.0, 20.0), Vec3::ZERO, 1.5);
+        let params = OutlineParams::new(&camera, [0, 0, 300, 200], &ToonSettings::default(), 1);
+        let inv = Mat4::from_cols_array_2d(&params.inv_view_proj);
+        let point = Vec3::new(1.0, 2.0, -1.0);
+        let ndc = camera.view_projection_matrix().project_point3(point);
+        let px = (ndc.x + 1.0) * 0.5 * 300.0 - 0.5;
+        let py = (1.0 - ndc.y) * 0.5 * 200.0 - 0.5;
+        let back = unproject(inv, px, py, 300.0, 200.0, ndc.z);
+        assert!((back - point).length() < 1e-3, "{back}");
+    }
+}
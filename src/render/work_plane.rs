@@ -0,0 +1,165 @@
+//! Work-plane overlay: the slice edits are restricted to, drawn as a
+//! faint sheet with a cell grid and a bright border.
+//!
+//! - **Sheet**: two translucent quads through `transparent_pipeline`,
+//!   one facing each way along the axis (that pipeline culls back
+//!   faces), tucked just inside the slab's two faces. Voxels on the
+//!   slice cover it, so the tint marks the slice's *empty* cells — the
+//!   ones a Place would fill. Being voxel-pipeline geometry it goes
+//!   through the shader's slice dimming too, which is why each quad's
+//!   half-cell-behind point (see `voxel.wgsl`) must land on the slice.
+//! - **Grid and border**: `LineList`s through the shared
+//!   `LinePipeline`. The grid runs through the middle of the slab so
+//!   cells on the slice hide it; the border outlines both faces.
+//!
+//! The overlay spans a fixed rectangle in the other two axes (the app
+//! passes the ground grid's extent); the slice itself is unbounded.
+
+use bytemuck::cast_slice;
+use wgpu::util::DeviceExt;
+
+use super::grid::LineVertex;
+use super::GpuMesh;
+use crate::core::ChunkPos;
+use crate::mesh::{ChunkMesh, Vertex};
+
+/// Sheet tint — a pale cyan that reads on dark and light voxels alike.
+const SHEET_COLOR: [f32; 4] = [0.3, 0.8, 1.0, 0.12];
+/// Per-cell grid lines on the sheet.
+const GRID_COLOR: [f32; 4] = [0.3, 0.8, 1.0, 0.3];
+/// Slab border.
+const BORDER_COLOR: [f32; 4] = [0.3, 0.8, 1.0, 0.9];
+
+/// How far the sheet sits inside the slab's faces, so it loses the
+/// depth test to the faces of voxels on the slice instead of z-fighting
+/// them.
+const SHEET_INSET: f32 = 0.01;
+
+/// GPU buffers for the work-plane overlay.
+pub struct WorkPlaneMesh {
+    pub sheet: GpuMesh,
+    pub line_buffer: wgpu::Buffer,
+    pub line_count: u32,
+}
+
+impl WorkPlaneMesh {
+    /// Overlay for the slice `axis == coord` (axis as an index into
+    /// `(x, y, z)`), covering cells `lo..hi` along the other two axes
+    /// in `(axis + 1, axis + 2) % 3` order.
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        axis: usize,
+        coord: i32,
+        lo: [i32; 2],
+        hi: [i32; 2],
+    ) -> Self {
+        let sheet = GpuMesh::new(device, queue, &build_sheet(axis, coord, lo, hi));
+        let lines = build_lines(axis, coord, lo, hi);
+        let line_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Work Plane Line Buffer"),
+            contents: cast_slice(&lines),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        Self {
+            sheet,
+            line_buffer,
+            line_count: lines.len() as u32,
+        }
+    }
+}
+
+/// World position with `along` on `axis` and `(u, v)` on the next two
+/// axes in cyclic order.
+fn point(axis: usize, along: f32, u: f32, v: f32) -> [f32; 3] {
+    let mut p = [0.0; 3];
+    p[axis] = along;
+    p[(axis + 1) % 3] = u;
+    p[(axis + 2) % 3] = v;
+    p
+}
+
+fn build_sheet(axis: usize, coord: i32, lo: [i32; 2], hi: [i32; 2]) -> ChunkMesh {
+    let (u0, v0) = (lo[0] as f32, lo[1] as f32);
+    let (u1, v1) = (hi[0] as f32, hi[1] as f32);
+    let mut mesh = ChunkMesh::with_capacity(ChunkPos::ZERO, 8, 12);
+    for sign in [1.0, -1.0] {
+        let along = if sign > 0.0 {
+            (coord + 1) as f32 - SHEET_INSET
+        } else {
+            coord as f32 + SHEET_INSET
+        };
+        let mut normal = [0.0; 3];
+        normal[axis] = sign;
+        let v = |u, w| Vertex::new(point(axis, along, u, w), normal, SHEET_COLOR);
+        // `add_quad` wants the corners clockwise seen from outside;
+        // the cyclic (u, v) pair is right-handed around +axis.
+        let quad = if sign > 0.0 {
+            [v(u0, v0), v(u0, v1), v(u1, v1), v(u1, v0)]
+        } else {
+            [v(u0, v0), v(u1, v0), v(u1, v1), v(u0, v1)]
+        };
+        mesh.add_quad(quad);
+    }
+    mesh
+}
+
+fn build_lines(axis: usize, coord: i32, lo: [i32; 2], hi: [i32; 2]) -> Vec<LineVertex> {
+    let (u0, v0) = (lo[0] as f32, lo[1] as f32);
+    let (u1, v1) = (hi[0] as f32, hi[1] as f32);
+    let mut lines = Vec::new();
+    let mut segment = |along, a: (f32, f32), b: (f32, f32), color| {
+        lines.push(LineVertex::new(point(axis, along, a.0, a.1), color));
+        lines.push(LineVertex::new(point(axis, along, b.0, b.1), color));
+    };
+
+    let mid = coord as f32 + 0.5;
+    for u in lo[0] + 1..hi[0] {
+        segment(mid, (u as f32, v0), (u as f32, v1), GRID_COLOR);
+    }
+    for v in lo[1] + 1..hi[1] {
+        segment(mid, (u0, v as f32), (u1, v as f32), GRID_COLOR);
+    }
+    for along in [coord as f32, (coord + 1) as f32] {
+        segment(along, (u0, v0), (u1, v0), BORDER_COLOR);
+        segment(along, (u1, v0), (u1, v1), BORDER_COLOR);
+        segment(along, (u1, v1), (u0, v1), BORDER_COLOR);
+        segment(along, (u0, v1), (u0, v0), BORDER_COLOR);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sheet_faces_both_ways_from_inside_the_slice() {
+        let mesh = build_sheet(1, 4, [-2, -3], [2, 3]);
+        assert_eq!(mesh.vertex_count(), 8);
+        for v in &mesh.vertices {
+            // The shader's "cell behind the face" must be on the slice,
+            // or the sheet would dim itself.
+            let behind = v.position[1] - v.normal[1] * 0.5;
+            assert_eq!(behind.floor(), 4.0);
+            assert!((-2.0..=2.0).contains(&v.position[2]));
+            assert!((-3.0..=3.0).contains(&v.position[0]));
+        }
+        // Each quad's triangles wind counter-clockwise around its
+        // normal, so back-face culling keeps the side facing the eye.
+        for (tri, quad) in mesh.indices.chunks(3).zip([0, 0, 1, 1]) {
+            let p = |i: u32| glam::Vec3::from(mesh.vertices[i as usize].position);
+            let n = glam::Vec3::from(mesh.vertices[tri[0] as usize].normal);
+            let cross = (p(tri[1]) - p(tri[0])).cross(p(tri[2]) - p(tri[0]));
+            assert!(cross.dot(n) > 0.0, "quad {quad} winds backwards");
+        }
+    }
+
+    #[test]
+    fn lines_cover_grid_and_both_borders() {
+        let lines = build_lines(0, -1, [0, 0], [3, 2]);
+        // 2 + 1 interior grid lines, plus 4 border edges per face.
+        assert_eq!(lines.len(), 2 * (2 + 1 + 8));
+        assert!(lines.iter().all(|l| (-1.0..=0.0).contains(&l.position[0])));
+    }
+}
@@ -0,0 +1,157 @@
+//! Render ▸ Camera Path: keyframe a camera fly-through and render it.
+//!
+//! Keys are captured from the viewport camera (Add Key / Update), each
+//! with the time on to the next one; the camera glides through them on
+//! a spline. Preview flies the viewport along the path, and Render
+//! writes it out as a PNG sequence at the chosen size and frame rate.
+//! Like the Timeline panel, everything is queued as [`UiAction`]s.
+
+use egui::Context;
+
+use super::{gen_job_ui, Ui, UiAction};
+
+impl Ui {
+    pub(super) fn show_camera_path_panel(&mut self, ctx: &Context) {
+        // Deferred-action pattern: `.open(...)` holds
+        // `self.state.show_camera_path`.
+        let mut actions = Vec::new();
+        let path = &self.camera_path;
+        let count = path.keys.len();
+        let settings = &mut self.flythrough;
+        let job = self.flythrough_job.as_ref();
+        let previewing = self.camera_path_previewing;
+        let selected = &mut self.state.selected_camera_key;
+        if selected.is_some_and(|i| i >= count) {
+            *selected = None;
+        }
+        egui::Window::new("Camera Path")
+            .default_width(280.0)
+            .collapsible(true)
+            .open(&mut self.state.show_camera_path)
+            .show(ctx, |ui| {
+                if count == 0 {
+                    ui.weak("No keys. Frame a shot in the viewport and click Add Key.");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(180.0)
+                    .show(ui, |ui| {
+                        for (i, key) in path.keys.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let label = format!("Key {}", i + 1);
+                                if ui.selectable_label(*selected == Some(i), label).clicked() {
+                                    *selected = Some(i);
+                                }
+                                // The last key of an open path has no
+                                // segment after it.
+                                if i + 1 < count || path.closed {
+                                    let mut secs = key.duration_ms as f32 / 1000.0;
+                                    let response = ui.add(
+                                        egui::DragValue::new(&mut secs)
+                                            .speed(0.05)
+                                            .range(0.01..=600.0)
+                                            .suffix(" s"),
+                                    );
+                                    if response.changed() {
+                                        let ms = (secs * 1000.0).round() as u32;
+                                        actions.push(UiAction::SetCameraKeyDuration(i, ms));
+                                    }
+                                }
+                            });
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Add Key")
+                        .on_hover_text("Key from the current view, after the selected one")
+                        .clicked()
+                    {
+                        actions.push(UiAction::AddCameraKey);
+                    }
+                    let Some(i) = *selected else {
+                        return;
+                    };
+                    if ui
+                        .button("Update")
+                        .on_hover_text("Set this key to the current view")
+                        .clicked()
+                    {
+                        actions.push(UiAction::UpdateCameraKey(i));
+                    }
+                    if ui.button("Go To").clicked() {
+                        actions.push(UiAction::GoToCameraKey(i));
+                    }
+                    if ui.add_enabled(i > 0, egui::Button::new("▲")).clicked() {
+                        actions.push(UiAction::MoveCameraKey { from: i, to: i - 1 });
+                    }
+                    if ui
+                        .add_enabled(i + 1 < count, egui::Button::new("▼"))
+                        .clicked()
+                    {
+                        actions.push(UiAction::MoveCameraKey { from: i, to: i + 1 });
+                    }
+                    if ui.button("Delete").clicked() {
+                        actions.push(UiAction::RemoveCameraKey(i));
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    let mut closed = path.closed;
+                    if ui
+                        .checkbox(&mut closed, "Loop")
+                        .on_hover_text("Fly from the last key back to the first")
+                        .changed()
+                    {
+                        actions.push(UiAction::SetCameraPathClosed(closed));
+                    }
+                    ui.label(format!("{:.2} s", path.duration()));
+                    let preview = if previewing {
+                        "■ Stop"
+                    } else {
+                        "▶ Preview"
+                    };
+                    if ui
+                        .add_enabled(count > 1 || previewing, egui::Button::new(preview))
+                        .clicked()
+                    {
+                        actions.push(UiAction::ToggleCameraPathPreview);
+                    }
+                });
+
+                ui.separator();
+                ui.add_enabled_ui(job.is_none(), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Size");
+                        ui.add(
+                            egui::DragValue::new(&mut settings.width)
+                                .range(16..=4096)
+                                .suffix(" px"),
+                        );
+                        ui.label("×");
+                        ui.add(
+                            egui::DragValue::new(&mut settings.height)
+                                .range(16..=4096)
+                                .suffix(" px"),
+                        );
+                    });
+                    ui.add(egui::Slider::new(&mut settings.fps, 1..=60).text("FPS"));
+                });
+                ui.label(format!(
+                    "{} PNG frames (flythrough_0000.png, …)",
+                    path.frame_count(settings.fps)
+                ));
+                if ui
+                    .add_enabled(job.is_none() && count > 1, egui::Button::new("Render..."))
+                    .clicked()
+                {
+                    actions.push(UiAction::StartFlythrough);
+                }
+                if gen_job_ui(ui, job) {
+                    actions.push(UiAction::CancelFlythrough);
+                }
+            });
+        for action in actions {
+            self.state.request(action);
+        }
+    }
+}
@@ -0,0 +1,687 @@
+//! Command registry and the Ctrl+P command palette.
+//!
+//! [`registry`] lists every editor command once, with its menu label,
+//! category and shortcut text. The menu bar draws its entries from it
+//! (`Ui::command_item`), the keyboard shortcuts in `app::input` queue
+//! the same [`UiAction`]s, and the palette searches it, so a command's
+//! name and shortcut read the same wherever it's reached from.
+//!
+//! Running a command either queues a [`UiAction`] for the App, exactly
+//! like clicking its menu entry, or opens one of the UI's own windows.
+
+use egui::{Context, Key, KeyboardShortcut, Modifiers};
+
+use super::{Ui, UiAction, UiState};
+use crate::editor::{Axis, Downsample, OriginAnchor, Quarter, Scale, Tool};
+use crate::render::CameraView;
+
+/// Opens / closes the command palette.
+pub const COMMAND_PALETTE_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::P);
+
+/// Matches listed by the palette at once; the rest are a keystroke away.
+const MAX_PALETTE_ROWS: usize = 14;
+
+/// A UI window a command can open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiWindow {
+    Statistics,
+    Tools,
+    Palette,
+    ViewportSettings,
+    Layers,
+    Timeline,
+    Rig,
+    ModelInfo,
+    History,
+    SliceEditor,
+    Materials,
+    Lights,
+    References,
+    Procgen,
+    Graph,
+    Ai,
+    Screenshot,
+    Sprites,
+    Export,
+    Turntable,
+    CameraPath,
+    Move,
+    Crop,
+    ProjectSettings,
+    ReplaceColor,
+    AdjustColors,
+    SmoothColors,
+    Preferences,
+    Help,
+    Plugins,
+    About,
+}
+
+impl UiWindow {
+    fn flag(self, state: &mut UiState) -> &mut bool {
+        match self {
+            UiWindow::Statistics => &mut state.show_stats,
+            UiWindow::Tools => &mut state.show_tools,
+            UiWindow::Palette => &mut state.show_palette,
+            UiWindow::ViewportSettings => &mut state.show_viewport_settings,
+            UiWindow::Layers => &mut state.show_layers,
+            UiWindow::Timeline => &mut state.show_timeline,
+            UiWindow::Rig => &mut state.show_rig,
+            UiWindow::ModelInfo => &mut state.show_model_info,
+            UiWindow::History => &mut state.show_history,
+            UiWindow::SliceEditor => &mut state.show_slice_view,
+            UiWindow::Materials => &mut state.show_materials,
+            UiWindow::Lights => &mut state.show_lights,
+            UiWindow::References => &mut state.show_references,
+            UiWindow::Procgen => &mut state.show_procgen,
+            UiWindow::Graph => &mut state.show_graph,
+            UiWindow::Ai => &mut state.show_ai,
+            UiWindow::Screenshot => &mut state.show_screenshot,
+            UiWindow::Sprites => &mut state.show_sprites,
+            UiWindow::Export => &mut state.show_export,
+            UiWindow::Turntable => &mut state.show_turntable,
+            UiWindow::CameraPath => &mut state.show_camera_path,
+            UiWindow::Move => &mut state.show_move,
+            UiWindow::Crop => &mut state.show_crop,
+            UiWindow::ProjectSettings => &mut state.show_project_settings,
+            UiWindow::ReplaceColor => &mut state.show_replace_color,
+            UiWindow::AdjustColors => &mut state.show_adjust_colors,
+            UiWindow::SmoothColors => &mut state.show_smooth_colors,
+            UiWindow::Preferences => &mut state.show_preferences,
+            UiWindow::Help => &mut state.show_help,
+            UiWindow::Plugins => &mut state.show_plugins,
+            UiWindow::About => &mut state.show_about,
+        }
+    }
+}
+
+/// What running a command does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandTarget {
+    /// Queue an action for the App.
+    Action(UiAction),
+    /// Show a window.
+    Open(UiWindow),
+}
+
+/// One registry entry.
+#[derive(Debug, Clone)]
+pub struct Command {
+    /// Menu the command lives under, shown before the label in the
+    /// palette ("Export: glTF Binary (.glb)...").
+    pub category: &'static str,
+    pub label: &'static str,
+    /// Shortcut as shown in menus, e.g. "Ctrl+Shift+S".
+    pub shortcut: Option<&'static str>,
+    pub target: CommandTarget,
+}
+
+impl Command {
+    pub(super) fn new(
+        category: &'static str,
+        label: &'static str,
+        target: CommandTarget,
+    ) -> Self {
+        Self {
+            category,
+            label,
+            shortcut: None,
+            target,
+        }
+    }
+
+    fn key(mut self, shortcut: &'static str) -> Self {
+        self.shortcut = Some(shortcut);
+        self
+    }
+}
+
+/// Every command, in menu order.
+pub fn registry() -> Vec<Command> {
+    use CommandTarget::{Action, Open};
+    let mut commands = vec![
+        Command::new("File", "New", Action(UiAction::NewProject)).key("Ctrl+N"),
+        Command::new("File", "Open...", Action(UiAction::OpenProject)).key("Ctrl+O"),
+        Command::new("File", "Save", Action(UiAction::SaveProject)).key("Ctrl+S"),
+        Command::new("File", "Save As...", Action(UiAction::SaveAs)).key("Ctrl+Shift+S"),
+        Command::new("File", "Clear Recent", Action(UiAction::ClearRecent)),
+        Command::new("File", "Project Settings…", Open(UiWindow::ProjectSettings)),
+        Command::new(
+            "Import",
+            "MagicaVoxel (.vox)...",
+            Action(UiAction::ImportVox),
+        ),
+        Command::new(
+            "Import",
+            "Qubicle (.qb / .qbt)...",
+            Action(UiAction::ImportQubicle),
+        ),
+        Command::new("Import", "PNG Slices...", Action(UiAction::ImportPngSlices)),
+        Command::new(
+            "Import",
+            "Heightmap (.png)...",
+            Action(UiAction::ImportHeightmap),
+        ),
+        Command::new("Import", "Palette...", Action(UiAction::ImportPalette)),
+        Command::new(
+            "Export",
+            "MagicaVoxel (.vox)...",
+            Action(UiAction::ExportVox),
+        ),
+        Command::new("Export", "Qubicle (.qb)...", Action(UiAction::ExportQb)),
+        Command::new(
+            "Export",
+            "Qubicle Binary Tree (.qbt)...",
+            Action(UiAction::ExportQbt),
+        ),
+        Command::new("Export", "PNG Slices...", Action(UiAction::ExportPngSlices)),
+        Command::new(
+            "Export",
+            "Wavefront OBJ (.obj)...",
+            Action(UiAction::ExportObj),
+        ),
+        Command::new(
+            "Export",
+            "glTF Binary (.glb)...",
+            Action(UiAction::ExportGlb),
+        ),
+        Command::new(
+            "Export",
+            "Frames as MagicaVoxel (.vox)...",
+            Action(UiAction::ExportFramesVox),
+        ),
+        Command::new(
+            "Export",
+            "Frames as Wavefront OBJ (.obj)...",
+            Action(UiAction::ExportFramesObj),
+        ),
+        Command::new(
+            "Export",
+            "Frames as animated glTF (.glb)...",
+            Action(UiAction::ExportFramesGlb),
+        ),
+        Command::new(
+            "Export",
+            "Rigged glTF (.glb)...",
+            Action(UiAction::ExportRiggedGlb),
+        ),
+        Command::new("Export", "Palette...", Action(UiAction::ExportPalette)),
+        Command::new("File", "Exit", Action(UiAction::Exit)),
+        Command::new("Edit", "Undo", Action(UiAction::Undo)).key("Ctrl+Z"),
+        Command::new("Edit", "Redo", Action(UiAction::Redo)).key("Ctrl+Y"),
+        Command::new("Edit", "Cut", Action(UiAction::CutSelection)).key("Ctrl+X"),
+        Command::new("Edit", "Copy", Action(UiAction::CopySelection)).key("Ctrl+C"),
+        Command::new("Edit", "Paste", Action(UiAction::PasteClipboard)).key("Ctrl+V"),
+        Command::new(
+            "Edit",
+            "Place Paste…",
+            Action(UiAction::BeginPastePlacement),
+        )
+        .key("Ctrl+Alt+V"),
+        Command::new("Edit", "Delete", Action(UiAction::DeleteSelection)).key("Del"),
+        Command::new("Edit", "Select All", Action(UiAction::SelectAllSolid)).key("Ctrl+A"),
+        Command::new("Edit", "Deselect", Action(UiAction::Deselect)).key("Esc"),
+        Command::new("Edit", "Clear All", Action(UiAction::ClearAll)),
+        Command::new("Edit", "Preferences…", Open(UiWindow::Preferences)),
+        Command::new("Color", "Replace…", Open(UiWindow::ReplaceColor)),
+        Command::new(
+            "Color",
+            "Adjust Hue / Saturation / Brightness…",
+            Open(UiWindow::AdjustColors),
+        ),
+        Command::new("Color", "Smooth…", Open(UiWindow::SmoothColors)),
+    ];
+
+    for (axis, [cw, ccw, half], keys) in [
+        (
+            Axis::X,
+            [
+                "Rotate 90° around X",
+                "Rotate -90° around X",
+                "Rotate 180° around X",
+            ],
+            [Some("Alt+X"), Some("Alt+Shift+X")],
+        ),
+        (
+            Axis::Y,
+            [
+                "Rotate 90° around Y",
+                "Rotate -90° around Y",
+                "Rotate 180° around Y",
+            ],
+            [Some("R"), Some("Shift+R")],
+        ),
+        (
+            Axis::Z,
+            [
+                "Rotate 90° around Z",
+                "Rotate -90° around Z",
+                "Rotate 180° around Z",
+            ],
+            [Some("Alt+Z"), Some("Alt+Shift+Z")],
+        ),
+    ] {
+        for (label, quarter, shortcut) in [
+            (cw, Quarter::Cw, keys[0]),
+            (ccw, Quarter::Ccw, keys[1]),
+            (half, Quarter::Half, None),
+        ] {
+            let mut command = Command::new(
+                "Transform",
+                label,
+                Action(UiAction::Rotate { axis, quarter }),
+            );
+            command.shortcut = shortcut;
+            commands.push(command);
+        }
+    }
+    for (label, scale) in [
+        ("Scale 2×", Scale::Up(2)),
+        ("Scale 3×", Scale::Up(3)),
+        (
+            "Scale 1/2 — majority color",
+            Scale::Down(2, Downsample::Majority),
+        ),
+        (
+            "Scale 1/2 — average color",
+            Scale::Down(2, Downsample::Average),
+        ),
+        (
+            "Scale 1/3 — majority color",
+            Scale::Down(3, Downsample::Majority),
+        ),
+        (
+            "Scale 1/3 — average color",
+            Scale::Down(3, Downsample::Average),
+        ),
+    ] {
+        commands.push(Command::new(
+            "Transform",
+            label,
+            Action(UiAction::Scale { scale }),
+        ));
+    }
+    for (axis, label, shortcut) in [
+        (Axis::X, "Flip X", "M"),
+        (Axis::Y, "Flip Y", "Shift+Y"),
+        (Axis::Z, "Flip Z", "Shift+Z"),
+    ] {
+        commands.push(
+            Command::new("Transform", label, Action(UiAction::Mirror { axis })).key(shortcut),
+        );
+    }
+    commands.extend([
+        Command::new("Transform", "Move…", Open(UiWindow::Move)),
+        Command::new("Transform", "Crop…", Open(UiWindow::Crop)),
+        Command::new(
+            "Transform",
+            "Center on Origin",
+            Action(UiAction::CenterOnOrigin {
+                anchor: OriginAnchor::Center,
+            }),
+        ),
+        Command::new(
+            "Transform",
+            "Base on Origin",
+            Action(UiAction::CenterOnOrigin {
+                anchor: OriginAnchor::Base,
+            }),
+        ),
+    ]);
+
+    for tool in [
+        Tool::Place,
+        Tool::Remove,
+        Tool::Paint,
+        Tool::Eyedropper,
+        Tool::Fill,
+        Tool::Line,
+        Tool::Box,
+        Tool::Sphere,
+        Tool::Cylinder,
+        Tool::Select,
+        Tool::Socket,
+        Tool::Extrude,
+        Tool::Wand,
+        Tool::Measure,
+        Tool::Sculpt,
+    ] {
+        let mut command = Command::new("Tool", tool.name(), Action(UiAction::SelectTool(tool)));
+        command.shortcut = Some(tool.shortcut()).filter(|s| !s.is_empty());
+        commands.push(command);
+    }
+
+    commands.extend([
+        Command::new("View", "Statistics", Open(UiWindow::Statistics)),
+        Command::new("View", "Tools Panel", Open(UiWindow::Tools)),
+        Command::new("View", "Color Palette", Open(UiWindow::Palette)),
+        Command::new("View", "Layers", Open(UiWindow::Layers)),
+        Command::new("View", "Timeline", Open(UiWindow::Timeline)),
+        Command::new("View", "Rig", Open(UiWindow::Rig)),
+        Command::new("View", "Model Info", Open(UiWindow::ModelInfo)),
+        Command::new("View", "History", Open(UiWindow::History)),
+        Command::new("View", "Slice Editor", Open(UiWindow::SliceEditor)),
+        Command::new("View", "Materials", Open(UiWindow::Materials)),
+        Command::new("View", "Lights", Open(UiWindow::Lights)),
+        Command::new("View", "References", Open(UiWindow::References)),
+        Command::new(
+            "View",
+            "Viewport Settings",
+            Open(UiWindow::ViewportSettings),
+        ),
+        Command::new("View", "Procedural Generation", Open(UiWindow::Procgen)),
+        Command::new("View", "Pipeline Graph", Open(UiWindow::Graph)),
+        Command::new("View", "AI Generation", Open(UiWindow::Ai)),
+        Command::new("View", "Frame Model", Action(UiAction::FrameAll)).key("F"),
+        Command::new("View", "Frame Selection", Action(UiAction::FrameSelected)).key("F"),
+        Command::new(
+            "View",
+            "Frame Last Generation",
+            Action(UiAction::FrameGenerated),
+        ),
+        Command::new("Camera", "Reset Camera", Action(UiAction::ResetCamera)),
+    ]);
+    for (view, label, shortcut) in [
+        (CameraView::Front, "Front View", Some("Numpad 1")),
+        (CameraView::Back, "Back View", Some("Ctrl+Numpad 1")),
+        (CameraView::Right, "Right View", Some("Numpad 3")),
+        (CameraView::Left, "Left View", Some("Ctrl+Numpad 3")),
+        (CameraView::Top, "Top View", Some("Numpad 7")),
+        (CameraView::Bottom, "Bottom View", Some("Ctrl+Numpad 7")),
+        (CameraView::Isometric, "Isometric View", Some("Numpad 5")),
+    ] {
+        let mut command = Command::new("Camera", label, Action(UiAction::SetCameraView(view)));
+        command.shortcut = shortcut;
+        commands.push(command);
+    }
+
+    commands.extend([
+        Command::new("Generate", "Test Cube", Action(UiAction::GenerateTestCube)),
+        Command::new("Generate", "Ground Plane", Action(UiAction::GenerateGround)),
+        Command::new("Generate", "Sphere", Action(UiAction::GenerateSphere)),
+        Command::new("Generate", "Pyramid", Action(UiAction::GeneratePyramid)),
+        Command::new("Generate", "Run Pipeline Graph", Action(UiAction::RunGraph)),
+        Command::new("Layer", "Add Layer", Action(UiAction::AddLayer)),
+        Command::new("Animation", "Add Frame", Action(UiAction::AddFrame)),
+        Command::new("Animation", "Duplicate Frame", Action(UiAction::DuplicateFrame)),
+        Command::new("Animation", "Play / Pause", Action(UiAction::TogglePlayback)),
+        Command::new("Animation", "Previous Frame", Action(UiAction::PreviousFrame)),
+        Command::new("Animation", "Next Frame", Action(UiAction::NextFrame)),
+        Command::new("Rig", "Add Bone from Selection", Action(UiAction::AddBone)),
+        Command::new("Rig", "Reset Pose", Action(UiAction::ResetPose)),
+        Command::new("Rig", "Bake Pose", Action(UiAction::BakePose)),
+        Command::new("Render", "Screenshot...", Open(UiWindow::Screenshot)),
+        Command::new("Render", "Sprite Sheet...", Open(UiWindow::Sprites)),
+        Command::new("Render", "Turntable...", Open(UiWindow::Turntable)),
+        Command::new("Render", "Camera Path...", Open(UiWindow::CameraPath)),
+        Command::new(
+            "Render",
+            "Preview Camera Path",
+            Action(UiAction::ToggleCameraPathPreview),
+        ),
+        Command::new("Render", "Add Camera Key", Action(UiAction::AddCameraKey)),
+        Command::new("Help", "Keyboard Shortcuts", Open(UiWindow::Help)),
+        Command::new("Help", "Plugins…", Open(UiWindow::Plugins)),
+        Command::new("Help", "About Voxelith", Open(UiWindow::About)),
+    ]);
+    commands
+}
+
+/// How well `query` fuzzily matches `text`: `None` unless every
+/// non-space query character appears in `text` in order (ignoring
+/// case). Runs of adjacent characters and matches at word starts score
+/// higher, and so does a shorter `text`, so "sa" ranks "Save" above
+/// "Smooth colors…".
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for q in query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+    {
+        let found = next + text[next..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 8;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 6;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score * 4 - text.len() as i32 / 4)
+}
+
+/// Query and highlighted row of the open command palette.
+#[derive(Debug, Default)]
+pub struct CommandPaletteState {
+    pub open: bool,
+    pub query: String,
+    pub selected: usize,
+}
+
+impl Ui {
+    /// Run a registry command as if its menu entry were clicked.
+    pub fn run_command(&mut self, target: &CommandTarget) {
+        match target {
+            CommandTarget::Action(action) => self.state.request(action.clone()),
+            CommandTarget::Open(window) => {
+                *window.flag(&mut self.state) = true;
+                if *window == UiWindow::Crop {
+                    self.state.request(UiAction::FitCropBox);
+                }
+            }
+        }
+    }
+
+    fn command(&self, target: &CommandTarget) -> Option<&Command> {
+        self.commands
+            .iter()
+            .find(|command| command.target == *target)
+    }
+
+    /// Menu entry for the registered command `target`, with its label
+    /// and shortcut. Clicking runs it and closes the menu.
+    pub(super) fn command_item(
+        &mut self,
+        ui: &mut egui::Ui,
+        target: CommandTarget,
+        enabled: bool,
+    ) -> egui::Response {
+        let label = self.command(&target).map_or("?", |command| command.label);
+        self.command_item_as(ui, target, label, enabled)
+    }
+
+    /// [`Ui::command_item`] under a shorter label, for submenus whose
+    /// title already says what the entry is about ("Rotate around X ▸
+    /// 90°").
+    pub(super) fn command_item_as(
+        &mut self,
+        ui: &mut egui::Ui,
+        target: CommandTarget,
+        label: &str,
+        enabled: bool,
+    ) -> egui::Response {
+        debug_assert!(
+            self.command(&target).is_some(),
+            "unregistered command {target:?}"
+        );
+        let shortcut = self.command(&target).and_then(|command| command.shortcut);
+        let mut button = egui::Button::new(label);
+        if let Some(shortcut) = shortcut {
+            button = button.shortcut_text(shortcut);
+        }
+        let response = ui.add_enabled(enabled, button);
+        if response.clicked() {
+            self.run_command(&target);
+            ui.close_menu();
+        }
+        response
+    }
+
+    /// Menu entry queuing `action`.
+    pub(super) fn action_item(&mut self, ui: &mut egui::Ui, action: UiAction) -> egui::Response {
+        self.command_item(ui, CommandTarget::Action(action), true)
+    }
+
+    /// Menu entry opening `window`.
+    pub(super) fn window_item(&mut self, ui: &mut egui::Ui, window: UiWindow) -> egui::Response {
+        self.command_item(ui, CommandTarget::Open(window), true)
+    }
+
+    /// Registry indices matching the palette query, best first; the
+    /// whole registry in menu order for an empty query.
+    fn palette_matches(&self) -> Vec<usize> {
+        let query = &self.command_palette.query;
+        let mut scored: Vec<(i32, usize)> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, command)| {
+                let text = format!("{}: {}", command.category, command.label);
+                // The label alone, so a category prefix doesn't bury
+                // an exact label hit.
+                let score = fuzzy_score(query, command.label)
+                    .map(|score| score + 4)
+                    .max(fuzzy_score(query, &text))?;
+                Some((score, i))
+            })
+            .collect();
+        scored.sort_by_key(|&(score, i)| (-score, i));
+        scored.into_iter().map(|(_, i)| i).collect()
+    }
+
+    /// Ctrl+P toggles the palette; while open it takes the keyboard:
+    /// typing filters, ↑ / ↓ move the highlight, Enter runs the
+    /// highlighted command and Esc closes.
+    pub(super) fn show_command_palette(&mut self, ctx: &Context) {
+        if ctx.input_mut(|i| i.consume_shortcut(&COMMAND_PALETTE_SHORTCUT)) {
+            let palette = &mut self.command_palette;
+            palette.open = !palette.open;
+            palette.query.clear();
+            palette.selected = 0;
+        }
+        if !self.command_palette.open {
+            return;
+        }
+
+        let (down, up, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::Enter),
+                i.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        if escape {
+            self.command_palette.open = false;
+            return;
+        }
+
+        let matches = self.palette_matches();
+        let rows = matches.len().min(MAX_PALETTE_ROWS);
+        let palette = &mut self.command_palette;
+        if down && rows > 0 {
+            palette.selected = (palette.selected + 1) % rows;
+        }
+        if up && rows > 0 {
+            palette.selected = (palette.selected + rows - 1) % rows;
+        }
+        palette.selected = palette.selected.min(rows.saturating_sub(1));
+
+        let mut run = enter.then_some(palette.selected).filter(|_| rows > 0);
+        let mut query_changed = false;
+        egui::Window::new("Command Palette")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 48.0])
+            .fixed_size([440.0, 0.0])
+            .show(ctx, |ui| {
+                let palette = &mut self.command_palette;
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text("Type a command…")
+                        .desired_width(f32::INFINITY),
+                );
+                edit.request_focus();
+                query_changed = edit.changed();
+                ui.separator();
+                if rows == 0 {
+                    ui.weak("No matching command");
+                }
+                for (row, &index) in matches.iter().take(rows).enumerate() {
+                    let command = &self.commands[index];
+                    ui.horizontal(|ui| {
+                        let label = format!("{}: {}", command.category, command.label);
+                        let response = ui.selectable_label(row == palette.selected, label);
+                        if response.clicked() {
+                            run = Some(row);
+                        }
+                        if let Some(shortcut) = command.shortcut {
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.weak(shortcut);
+                                },
+                            );
+                        }
+                    });
+                }
+                if matches.len() > rows {
+                    ui.weak(format!("{} more — keep typing", matches.len() - rows));
+                }
+            });
+        if query_changed {
+            self.command_palette.selected = 0;
+        }
+        if let Some(row) = run {
+            let target = self.commands[matches[row]].target.clone();
+            self.command_palette.open = false;
+            self.run_command(&target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_score_needs_an_in_order_match() {
+        assert!(fuzzy_score("", "Save").is_some());
+        assert!(fuzzy_score("sv", "Save").is_some());
+        assert!(fuzzy_score("S A V E", "save").is_some());
+        assert_eq!(fuzzy_score("vs", "Save"), None);
+        assert_eq!(fuzzy_score("saves", "Save"), None);
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts_and_runs() {
+        let save = fuzzy_score("sa", "Save").unwrap();
+        let smooth = fuzzy_score("sa", "Smooth colors… (all)").unwrap();
+        assert!(save > smooth);
+        let word_starts = fuzzy_score("gb", "glTF Binary").unwrap();
+        let scattered = fuzzy_score("gb", "Heightmap bump").unwrap();
+        assert!(word_starts > scattered);
+    }
+
+    #[test]
+    fn registry_targets_are_unique() {
+        let commands = registry();
+        for (i, command) in commands.iter().enumerate() {
+            assert!(
+                commands[..i]
+                    .iter()
+                    .all(|other| other.target != command.target),
+                "{}: {} registered twice",
+                command.category,
+                command.label
+            );
+        }
+    }
+}
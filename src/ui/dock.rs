@@ -0,0 +1,499 @@
+//! Dockable layout for the always-around panels (Statistics, Tools,
+//! Palette, Viewport Settings). Each one either floats as its own
+//! window or docks into the left or right side panel, where the docked
+//! panels share the column as tabs. Right-clicking a tab moves it
+//! between sides, reorders it or closes it; View ▸ Panel Layout does
+//! the same for floating ones, and View ▸ Reset Layout puts everything
+//! back.
+//!
+//! The arrangement, side widths and floating positions live in
+//! [`DockLayout`], which `prefs::Prefs` persists, so a layout survives
+//! a restart. Which panels are shown at all is still the `show_*`
+//! toggles on `UiState`.
+
+use egui::Context;
+use serde::{Deserialize, Serialize};
+
+use super::{RenderStats, Ui};
+use crate::editor::Editor;
+
+/// Side-panel width range, in points.
+const MIN_DOCK_WIDTH: f32 = 160.0;
+const MAX_DOCK_WIDTH: f32 = 520.0;
+
+/// A panel that can float or dock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DockPanel {
+    Statistics,
+    Tools,
+    Palette,
+    ViewportSettings,
+}
+
+impl DockPanel {
+    pub const ALL: [DockPanel; 4] = [
+        DockPanel::Statistics,
+        DockPanel::Tools,
+        DockPanel::Palette,
+        DockPanel::ViewportSettings,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            DockPanel::Statistics => "Statistics",
+            DockPanel::Tools => "Tools",
+            DockPanel::Palette => "Palette",
+            DockPanel::ViewportSettings => "Viewport Settings",
+        }
+    }
+
+    /// Statistics and Viewport Settings size to their contents when
+    /// floating; the others can be dragged larger.
+    fn resizable(self) -> bool {
+        matches!(self, DockPanel::Tools | DockPanel::Palette)
+    }
+
+    /// Where the floating window first opens without a saved position.
+    fn default_pos(self, ctx: &Context) -> [f32; 2] {
+        match self {
+            DockPanel::Statistics => [60.0, 40.0],
+            DockPanel::Tools => [60.0, 200.0],
+            DockPanel::Palette => [60.0, 450.0],
+            DockPanel::ViewportSettings => [ctx.screen_rect().width() - 220.0, 40.0],
+        }
+    }
+}
+
+/// Where a [`DockPanel`] sits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DockSide {
+    #[default]
+    Floating,
+    Left,
+    Right,
+}
+
+impl DockSide {
+    pub const ALL: [DockSide; 3] = [DockSide::Floating, DockSide::Left, DockSide::Right];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DockSide::Floating => "Float",
+            DockSide::Left => "Left",
+            DockSide::Right => "Right",
+        }
+    }
+}
+
+/// One panel's place in the layout.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DockSlot {
+    pub panel: DockPanel,
+    pub side: DockSide,
+    /// Top-left of the floating window, once it has been shown.
+    pub pos: Option<[f32; 2]>,
+}
+
+/// A layout change picked from a tab's context menu or View ▸ Panel
+/// Layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockMove {
+    /// Float, or dock as the last tab of a side.
+    To(DockSide),
+    /// One tab towards the front of its side.
+    Earlier,
+    /// One tab towards the back of its side.
+    Later,
+}
+
+/// Arrangement of the [`DockPanel`]s.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DockLayout {
+    /// Every panel exactly once, in tab order.
+    pub slots: Vec<DockSlot>,
+    /// Tab shown in each side; the first docked tab when unset.
+    pub left_tab: Option<DockPanel>,
+    pub right_tab: Option<DockPanel>,
+    pub left_width: f32,
+    pub right_width: f32,
+    /// Bumped by [`DockLayout::reset`] so the side panels and windows
+    /// get new egui ids, dropping the sizes and positions egui itself
+    /// remembers for the old ones.
+    #[serde(skip)]
+    generation: u32,
+}
+
+impl Default for DockLayout {
+    /// All four floating where they always opened.
+    fn default() -> Self {
+        Self {
+            slots: DockPanel::ALL
+                .into_iter()
+                .map(|panel| DockSlot {
+                    panel,
+                    side: DockSide::Floating,
+                    pos: None,
+                })
+                .collect(),
+            left_tab: None,
+            right_tab: None,
+            left_width: 240.0,
+            right_width: 240.0,
+            generation: 0,
+        }
+    }
+}
+
+impl DockLayout {
+    /// Repair a hand-edited or older prefs entry: drop repeated panels,
+    /// add missing ones floating, and clamp the widths.
+    pub fn normalize(&mut self) {
+        let mut seen = Vec::new();
+        self.slots.retain(|slot| {
+            let first = !seen.contains(&slot.panel);
+            seen.push(slot.panel);
+            first
+        });
+        for panel in DockPanel::ALL {
+            if !seen.contains(&panel) {
+                self.slots.push(DockSlot {
+                    panel,
+                    side: DockSide::Floating,
+                    pos: None,
+                });
+            }
+        }
+        for width in [&mut self.left_width, &mut self.right_width] {
+            *width = if width.is_finite() {
+                width.clamp(MIN_DOCK_WIDTH, MAX_DOCK_WIDTH)
+            } else {
+                240.0
+            };
+        }
+    }
+
+    /// Back to the default layout.
+    pub fn reset(&mut self) {
+        let generation = self.generation + 1;
+        *self = Self {
+            generation,
+            ..Self::default()
+        };
+    }
+
+    pub fn side(&self, panel: DockPanel) -> DockSide {
+        self.slot(panel).map_or(DockSide::Floating, |slot| slot.side)
+    }
+
+    fn slot(&self, panel: DockPanel) -> Option<&DockSlot> {
+        self.slots.iter().find(|slot| slot.panel == panel)
+    }
+
+    fn slot_mut(&mut self, panel: DockPanel) -> Option<&mut DockSlot> {
+        self.slots.iter_mut().find(|slot| slot.panel == panel)
+    }
+
+    /// Panels on `side`, in tab order.
+    pub fn panels_on(&self, side: DockSide) -> impl Iterator<Item = DockPanel> + '_ {
+        self.slots
+            .iter()
+            .filter(move |slot| slot.side == side)
+            .map(|slot| slot.panel)
+    }
+
+    fn tab_mut(&mut self, side: DockSide) -> Option<&mut Option<DockPanel>> {
+        match side {
+            DockSide::Floating => None,
+            DockSide::Left => Some(&mut self.left_tab),
+            DockSide::Right => Some(&mut self.right_tab),
+        }
+    }
+
+    /// Apply `change` to `panel`. A panel docked onto a side becomes
+    /// its shown tab, so the move is visible.
+    pub fn apply(&mut self, panel: DockPanel, change: DockMove) {
+        let Some(index) = self.slots.iter().position(|slot| slot.panel == panel) else {
+            return;
+        };
+        let side = self.slots[index].side;
+        match change {
+            DockMove::To(to) if to == side => {}
+            DockMove::To(to) => {
+                let mut slot = self.slots.remove(index);
+                slot.side = to;
+                self.slots.push(slot);
+                if let Some(tab) = self.tab_mut(to) {
+                    *tab = Some(panel);
+                }
+            }
+            DockMove::Earlier => {
+                if let Some(other) = self.slots[..index].iter().rposition(|s| s.side == side) {
+                    self.slots.swap(index, other);
+                }
+            }
+            DockMove::Later => {
+                if let Some(offset) = self.slots[index + 1..].iter().position(|s| s.side == side) {
+                    self.slots.swap(index, index + 1 + offset);
+                }
+            }
+        }
+    }
+}
+
+impl Ui {
+    fn panel_shown(&self, panel: DockPanel) -> bool {
+        match panel {
+            DockPanel::Statistics => self.state.show_stats,
+            DockPanel::Tools => self.state.show_tools,
+            DockPanel::Palette => self.state.show_palette,
+            DockPanel::ViewportSettings => self.state.show_viewport_settings,
+        }
+    }
+
+    fn panel_shown_mut(&mut self, panel: DockPanel) -> &mut bool {
+        match panel {
+            DockPanel::Statistics => &mut self.state.show_stats,
+            DockPanel::Tools => &mut self.state.show_tools,
+            DockPanel::Palette => &mut self.state.show_palette,
+            DockPanel::ViewportSettings => &mut self.state.show_viewport_settings,
+        }
+    }
+
+    fn dock_panel_contents(
+        &mut self,
+        panel: DockPanel,
+        ui: &mut egui::Ui,
+        stats: &RenderStats,
+        editor: &mut Editor,
+    ) {
+        match panel {
+            DockPanel::Statistics => self.stats_panel_contents(ui, stats, editor),
+            DockPanel::Tools => self.tools_panel_contents(ui, editor),
+            DockPanel::Palette => self.palette_panel_contents(ui, editor),
+            DockPanel::ViewportSettings => self.viewport_panel_contents(ui, stats),
+        }
+    }
+
+    /// Draw the shown dock panels: the two side columns first, so they
+    /// claim their screen edges before any other window, then the
+    /// floating ones.
+    pub(super) fn show_dock(&mut self, ctx: &Context, stats: &RenderStats, editor: &mut Editor) {
+        self.show_dock_side(ctx, DockSide::Left, stats, editor);
+        self.show_dock_side(ctx, DockSide::Right, stats, editor);
+        let floating: Vec<DockPanel> = self.dock.panels_on(DockSide::Floating).collect();
+        for panel in floating {
+            if self.panel_shown(panel) {
+                self.show_floating_panel(ctx, panel, stats, editor);
+            }
+        }
+    }
+
+    fn show_floating_panel(
+        &mut self,
+        ctx: &Context,
+        panel: DockPanel,
+        stats: &RenderStats,
+        editor: &mut Editor,
+    ) {
+        let pos = self
+            .dock
+            .slot(panel)
+            .and_then(|slot| slot.pos)
+            .unwrap_or_else(|| panel.default_pos(ctx));
+        let response = egui::Window::new(panel.title())
+            .id(egui::Id::new(("dock_window", panel, self.dock.generation)))
+            .default_pos(pos)
+            .resizable(panel.resizable())
+            .collapsible(true)
+            .show(ctx, |ui| self.dock_panel_contents(panel, ui, stats, editor));
+        if let (Some(response), Some(slot)) = (response, self.dock.slot_mut(panel)) {
+            slot.pos = Some(response.response.rect.min.into());
+        }
+    }
+
+    fn show_dock_side(
+        &mut self,
+        ctx: &Context,
+        side: DockSide,
+        stats: &RenderStats,
+        editor: &mut Editor,
+    ) {
+        let tabs: Vec<DockPanel> = self
+            .dock
+            .panels_on(side)
+            .filter(|&panel| self.panel_shown(panel))
+            .collect();
+        let Some(&first) = tabs.first() else {
+            return;
+        };
+        let Some(tab) = self.dock.tab_mut(side) else {
+            return;
+        };
+        let active = match *tab {
+            Some(panel) if tabs.contains(&panel) => panel,
+            _ => first,
+        };
+        *tab = Some(active);
+
+        let id = egui::Id::new(("dock_side", side, self.dock.generation));
+        let (panel, width) = match side {
+            DockSide::Left => (egui::SidePanel::left(id), self.dock.left_width),
+            _ => (egui::SidePanel::right(id), self.dock.right_width),
+        };
+        let mut picked = None;
+        let mut change = None;
+        let mut close = None;
+        let response = panel
+            .resizable(true)
+            .default_width(width)
+            .width_range(MIN_DOCK_WIDTH..=MAX_DOCK_WIDTH)
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for &panel in &tabs {
+                        let response = ui
+                            .selectable_label(panel == active, panel.title())
+                            .on_hover_text("Right-click to move or close");
+                        if response.clicked() {
+                            picked = Some(panel);
+                        }
+                        response.context_menu(|ui| {
+                            for to in DockSide::ALL {
+                                if to != side && ui.button(dock_side_verb(to)).clicked() {
+                                    change = Some((panel, DockMove::To(to)));
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button("Move Tab Earlier").clicked() {
+                                change = Some((panel, DockMove::Earlier));
+                                ui.close_menu();
+                            }
+                            if ui.button("Move Tab Later").clicked() {
+                                change = Some((panel, DockMove::Later));
+                                ui.close_menu();
+                            }
+                            ui.separator();
+                            if ui.button("Close").clicked() {
+                                close = Some(panel);
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_salt(("dock_scroll", side))
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| self.dock_panel_contents(active, ui, stats, editor));
+            });
+
+        let width = response.response.rect.width();
+        match side {
+            DockSide::Left => self.dock.left_width = width,
+            _ => self.dock.right_width = width,
+        }
+        if let (Some(panel), Some(tab)) = (picked, self.dock.tab_mut(side)) {
+            *tab = Some(panel);
+        }
+        if let Some((panel, change)) = change {
+            self.dock.apply(panel, change);
+        }
+        if let Some(panel) = close {
+            *self.panel_shown_mut(panel) = false;
+        }
+    }
+
+    /// View ▸ Panel Layout: a Float / Left / Right choice per panel.
+    pub(super) fn dock_layout_menu(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("dock_layout_grid")
+            .num_columns(4)
+            .spacing([8.0, 4.0])
+            .show(ui, |ui| {
+                for panel in DockPanel::ALL {
+                    ui.label(panel.title());
+                    let side = self.dock.side(panel);
+                    for to in DockSide::ALL {
+                        if ui.radio(side == to, to.label()).clicked() {
+                            self.dock.apply(panel, DockMove::To(to));
+                            *self.panel_shown_mut(panel) = true;
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// View ▸ Reset Layout: default arrangement, and the four panels'
+    /// default visibility.
+    pub(super) fn reset_dock_layout(&mut self) {
+        self.dock.reset();
+        self.state.show_stats = true;
+        self.state.show_tools = true;
+        self.state.show_palette = true;
+        self.state.show_viewport_settings = false;
+    }
+}
+
+fn dock_side_verb(side: DockSide) -> &'static str {
+    match side {
+        DockSide::Floating => "Float",
+        DockSide::Left => "Dock Left",
+        DockSide::Right => "Dock Right",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_repairs_slots_and_widths() {
+        let mut layout = DockLayout {
+            slots: vec![
+                DockSlot {
+                    panel: DockPanel::Palette,
+                    side: DockSide::Left,
+                    pos: None,
+                },
+                DockSlot {
+                    panel: DockPanel::Palette,
+                    side: DockSide::Right,
+                    pos: None,
+                },
+            ],
+            left_width: f32::NAN,
+            right_width: 5000.0,
+            ..DockLayout::default()
+        };
+        layout.normalize();
+        assert_eq!(layout.slots.len(), DockPanel::ALL.len());
+        assert_eq!(layout.side(DockPanel::Palette), DockSide::Left);
+        assert_eq!(layout.side(DockPanel::Tools), DockSide::Floating);
+        assert_eq!(layout.left_width, 240.0);
+        assert_eq!(layout.right_width, MAX_DOCK_WIDTH);
+    }
+
+    #[test]
+    fn docking_and_reordering_tabs() {
+        let mut layout = DockLayout::default();
+        layout.apply(DockPanel::Tools, DockMove::To(DockSide::Right));
+        layout.apply(DockPanel::Palette, DockMove::To(DockSide::Right));
+        assert_eq!(layout.right_tab, Some(DockPanel::Palette));
+        let right = |layout: &DockLayout| layout.panels_on(DockSide::Right).collect::<Vec<_>>();
+        assert_eq!(right(&layout), [DockPanel::Tools, DockPanel::Palette]);
+
+        layout.apply(DockPanel::Palette, DockMove::Earlier);
+        assert_eq!(right(&layout), [DockPanel::Palette, DockPanel::Tools]);
+        // Already first among the right-side tabs.
+        layout.apply(DockPanel::Palette, DockMove::Earlier);
+        assert_eq!(right(&layout), [DockPanel::Palette, DockPanel::Tools]);
+
+        layout.apply(DockPanel::Tools, DockMove::To(DockSide::Floating));
+        assert_eq!(right(&layout), [DockPanel::Palette]);
+
+        layout.reset();
+        assert_eq!(layout.panels_on(DockSide::Right).count(), 0);
+        assert_eq!(layout.generation, 1);
+    }
+}
@@ -0,0 +1,66 @@
+//! View ▸ History: the active layer's undo stack by name.
+//!
+//! Entries are listed oldest first, undone ones (that Redo would bring
+//! back) included. The current state is highlighted; clicking another
+//! row undoes or redoes up to it. Undone rows disappear with the next
+//! edit.
+
+use egui::Context;
+
+use super::{Ui, UiAction};
+use crate::editor::Editor;
+
+impl Ui {
+    pub(super) fn show_history_panel(&mut self, ctx: &Context, editor: &Editor) {
+        let history = &editor.history;
+        let mut action = None;
+        egui::Window::new("History")
+            .default_width(220.0)
+            .open(&mut self.state.show_history)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(history.can_undo(), egui::Button::new("⟲ Undo"))
+                        .clicked()
+                    {
+                        action = Some(UiAction::Undo);
+                    }
+                    if ui
+                        .add_enabled(history.can_redo(), egui::Button::new("⟳ Redo"))
+                        .clicked()
+                    {
+                        action = Some(UiAction::Redo);
+                    }
+                });
+                if history.is_locked() {
+                    ui.weak("Layer is locked");
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .auto_shrink([false, true])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        // Row `i` is the state with `i` entries applied.
+                        let current = history.undo_count();
+                        let mut row = |ui: &mut egui::Ui, i: usize, label: &str| {
+                            let mut text = egui::RichText::new(label);
+                            if i > current {
+                                text = text.weak();
+                            }
+                            if ui.selectable_label(i == current, text).clicked() && i != current {
+                                action = Some(UiAction::JumpToHistory(i));
+                            }
+                        };
+                        row(ui, 0, "Start");
+                        let entries = history.undo_entries().chain(history.redo_entries());
+                        for (i, command) in entries.enumerate() {
+                            row(ui, i + 1, command.label().as_ref());
+                        }
+                    });
+            });
+        if let Some(action) = action {
+            self.state.request(action);
+        }
+    }
+}
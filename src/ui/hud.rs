@@ -23,7 +23,9 @@
 use egui::{Align2, Color32, Context, Id, Order, RichText};
 
 use super::RenderStats;
-use crate::editor::SymmetryAxes;
+use crate::core::Voxel;
+use crate::editor::{SymmetryAxes, WorkPlane};
+use crate::render::ViewPane;
 
 /// Display-ready HUD content, rebuilt by the App every frame.
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -38,6 +40,8 @@ pub struct HudState {
     pub detail: Option<String>,
     /// "Sym: XZ" — only set for tools symmetry affects.
     pub symmetry: Option<String>,
+    /// "Slice: Y = 3" — set while work-plane mode is on.
+    pub work_plane: Option<String>,
     /// "Sel: W×H×D (N cells)" — only set while the Select tool is
     /// active (the status bar keeps the always-on copy for other
     /// tools).
@@ -45,8 +49,15 @@ pub struct HudState {
     /// Key hints for the current modal gesture ("click: commit ·
     /// Esc: cancel"). `None` outside modal gestures.
     pub hints: Option<&'static str>,
+    /// Dimension label for the `Measure` ruler: where its midpoint
+    /// lands on screen (egui points) and the readout to draw there.
+    /// `None` when no ruler is shown or it's off screen.
+    pub measure: Option<([f32; 2], String)>,
 }
 
+/// Color of the measure ruler's readouts, matching its viewport lines.
+pub(super) const MEASURE_COLOR: Color32 = Color32::from_rgb(120, 255, 150);
+
 /// Shared translucent backplate for both HUD corners — research
 /// takeaway: in-viewport text always sits on a contrast aid, never
 /// bare alpha-blended over the scene.
@@ -91,6 +102,9 @@ pub(super) fn show_hud_overlay(ctx: &Context, hud: &HudState) {
                     if let Some(s) = &hud.symmetry {
                         ui.label(RichText::new(s).color(Color32::LIGHT_YELLOW));
                     }
+                    if let Some(s) = &hud.work_plane {
+                        ui.label(RichText::new(s).color(Color32::from_rgb(80, 200, 255)));
+                    }
                     if let Some(s) = &hud.selection {
                         ui.label(
                             RichText::new(s).color(Color32::from_rgb(255, 230, 60)),
@@ -103,6 +117,73 @@ pub(super) fn show_hud_overlay(ctx: &Context, hud: &HudState) {
         });
 }
 
+/// Draw the measure ruler's dimension label centered on its midpoint.
+/// Painted on the background layer like the quad-view labels, so it
+/// never takes a click and every window covers it.
+pub(super) fn show_measure_annotation(ctx: &Context, pos: [f32; 2], text: &str) {
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    let galley = painter.layout_no_wrap(
+        text.to_owned(),
+        egui::FontId::proportional(13.0),
+        MEASURE_COLOR,
+    );
+    let rect = Align2::CENTER_BOTTOM
+        .anchor_size(egui::pos2(pos[0], pos[1] - 6.0), galley.size())
+        .expand2(egui::vec2(6.0, 3.0));
+    painter.rect_filled(rect, 4.0, Color32::from_rgba_unmultiplied(15, 15, 22, 200));
+    painter.galley(rect.shrink2(egui::vec2(6.0, 3.0)).min, galley, MEASURE_COLOR);
+}
+
+/// Tooltip by the cursor describing the hovered voxel: cell, color
+/// swatch with RGB and hex, material and flags. Skipped while the
+/// pointer is over a panel or window, where the hover is stale.
+pub(super) fn show_voxel_tooltip(
+    ctx: &Context,
+    cell: (i32, i32, i32),
+    voxel: Voxel,
+    material: &str,
+) {
+    let Some(pointer) = ctx.pointer_hover_pos() else {
+        return;
+    };
+    if ctx.is_pointer_over_area() {
+        return;
+    }
+    egui::Area::new(Id::new("voxel_tooltip"))
+        .fixed_pos(pointer + egui::vec2(16.0, 16.0))
+        .order(Order::Tooltip)
+        .interactable(false)
+        .show(ctx, |ui| {
+            hud_frame().show(ui, |ui| {
+                ui.spacing_mut().item_spacing.y = 2.0;
+                ui.label(
+                    RichText::new(format!("({}, {}, {})", cell.0, cell.1, cell.2))
+                        .strong()
+                        .color(Color32::from_gray(230)),
+                );
+                ui.horizontal(|ui| {
+                    let (rect, _) =
+                        ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                    ui.painter().rect_filled(
+                        rect,
+                        2.0,
+                        Color32::from_rgb(voxel.r, voxel.g, voxel.b),
+                    );
+                    ui.label(
+                        RichText::new(color_label(voxel)).color(Color32::from_gray(220)),
+                    );
+                });
+                ui.label(
+                    RichText::new(format!("Material {} · {}", voxel.material, material))
+                        .color(Color32::from_gray(200)),
+                );
+                ui.label(
+                    RichText::new(flags_label(voxel)).color(Color32::from_gray(200)),
+                );
+            });
+        });
+}
+
 /// Render the performance readout in the bottom-right corner — the
 /// always-glanceable subset of the Statistics window (which stays
 /// the detailed view). Same click-through contract as the tool HUD.
@@ -128,9 +209,10 @@ pub(super) fn show_perf_overlay(ctx: &Context, stats: &RenderStats) {
                 );
                 ui.label(
                     RichText::new(format!(
-                        "{} tris · {} chunks",
+                        "{} tris · {} chunks · {} draws",
                         compact_count(stats.triangles),
-                        stats.chunks
+                        stats.chunks,
+                        stats.draw_calls
                     ))
                     .color(Color32::from_gray(200)),
                 );
@@ -143,10 +225,56 @@ pub(super) fn show_perf_overlay(ctx: &Context, stats: &RenderStats) {
                         .color(Color32::from_gray(200)),
                     );
                 }
+                if stats.meshing > 0 {
+                    ui.label(
+                        RichText::new(format!("meshing {} chunks…", stats.meshing))
+                            .color(Color32::from_gray(200)),
+                    );
+                }
+                if let Some((samples, max)) = stats.path_trace {
+                    ui.label(
+                        RichText::new(format!("path trace {} / {} spp", samples, max))
+                            .color(Color32::from_gray(200)),
+                    );
+                }
             });
         });
 }
 
+/// Draw the quad-view pane dividers and a label in each pane's top-left
+/// corner. Purely painted (no `Area`), so it can't consume clicks.
+/// Splits `ctx.screen_rect()` at the same midpoints `ViewPane::rect`
+/// uses for the 3D viewports — both cover the whole window, since the
+/// scene renders full-surface underneath the egui panels.
+pub(super) fn show_quad_view_overlay(ctx: &Context) {
+    let screen = ctx.screen_rect();
+    let mid = screen.center();
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    let stroke = egui::Stroke::new(1.0, Color32::from_gray(90));
+    painter.vline(mid.x, screen.y_range(), stroke);
+    painter.hline(screen.x_range(), mid.y, stroke);
+
+    // Left-column labels would sit under the tool strip and the top
+    // row under the menu bar, so inset from the available rect's
+    // corner rather than the raw screen corner.
+    let avail = ctx.available_rect();
+    let panes = [
+        (ViewPane::Top, egui::pos2(avail.left(), avail.top())),
+        (ViewPane::Front, egui::pos2(mid.x, avail.top())),
+        (ViewPane::Side, egui::pos2(avail.left(), mid.y)),
+        (ViewPane::Perspective, egui::pos2(mid.x, mid.y)),
+    ];
+    for (pane, corner) in panes {
+        painter.text(
+            corner + egui::vec2(8.0, 6.0),
+            Align2::LEFT_TOP,
+            pane.label(),
+            egui::FontId::proportional(13.0),
+            Color32::from_gray(200),
+        );
+    }
+}
+
 /// Compact count for at-a-glance HUD use: `950` → `"950"`,
 /// `12_345` → `"12.3k"`, `1_234_567` → `"1.23M"`. Precision is
 /// deliberately coarse — the Statistics window keeps exact numbers.
@@ -212,12 +340,51 @@ pub fn symmetry_label(sym: &SymmetryAxes) -> Option<String> {
     Some(format!("Sym: {}", axes))
 }
 
+/// `"Slice: Y = 3"`.
+pub fn work_plane_label(plane: &WorkPlane) -> String {
+    format!("Slice: {:?} = {}", plane.axis, plane.coord)
+}
+
 /// `"Sel: 12×5×8 (480 cells)"` — same compact format as the status
 /// bar so the two readouts are recognizably the same value.
 pub fn selection_label(w: i32, h: i32, d: i32, cells: usize) -> String {
     format!("Sel: {}×{}×{} ({} cells)", w, h, d, cells)
 }
 
+/// `"RGB 255, 128, 0 · #FF8000"`, with the alpha appended for a
+/// translucent voxel (`"… · #FF800080 · α 128"`).
+pub fn color_label(voxel: Voxel) -> String {
+    let (r, g, b, a) = (voxel.r, voxel.g, voxel.b, voxel.a);
+    if a == 255 {
+        format!("RGB {r}, {g}, {b} · #{r:02X}{g:02X}{b:02X}")
+    } else {
+        format!("RGB {r}, {g}, {b} · #{r:02X}{g:02X}{b:02X}{a:02X} · α {a}")
+    }
+}
+
+/// The voxel's flags as a comma list — `"emissive, metallic, tint:
+/// primary"` — or `"no flags"`.
+pub fn flags_label(voxel: Voxel) -> String {
+    let mut flags = Vec::new();
+    if voxel.is_emissive() {
+        flags.push("emissive");
+    }
+    if voxel.is_metallic() {
+        flags.push("metallic");
+    }
+    match voxel.tint_zone() {
+        0 => {}
+        1 => flags.push("tint: primary"),
+        2 => flags.push("tint: secondary"),
+        _ => flags.push("tint: reserved"),
+    }
+    if flags.is_empty() {
+        "no flags".to_string()
+    } else {
+        flags.join(", ")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,6 +418,7 @@ mod tests {
             x: false,
             y: false,
             z: false,
+            ..Default::default()
         };
         assert_eq!(symmetry_label(&none), None);
 
@@ -258,10 +426,20 @@ mod tests {
             x: true,
             y: false,
             z: true,
+            ..Default::default()
         };
         assert_eq!(symmetry_label(&xz).as_deref(), Some("Sym: XZ"));
     }
 
+    #[test]
+    fn work_plane_label_names_axis_and_coord() {
+        let plane = WorkPlane {
+            axis: crate::editor::Axis::Z,
+            coord: -4,
+        };
+        assert_eq!(work_plane_label(&plane), "Slice: Z = -4");
+    }
+
     #[test]
     fn selection_label_matches_status_bar_format() {
         assert_eq!(selection_label(12, 5, 8, 480), "Sel: 12×5×8 (480 cells)");
@@ -275,4 +453,25 @@ mod tests {
         assert_eq!(compact_count(12_345), "12.3k");
         assert_eq!(compact_count(1_234_567), "1.23M");
     }
+
+    #[test]
+    fn color_label_shows_hex_and_alpha_when_translucent() {
+        assert_eq!(
+            color_label(Voxel::from_rgb(255, 128, 0)),
+            "RGB 255, 128, 0 · #FF8000"
+        );
+        assert_eq!(
+            color_label(Voxel::from_rgba(255, 128, 0, 128)),
+            "RGB 255, 128, 0 · #FF800080 · α 128"
+        );
+    }
+
+    #[test]
+    fn flags_label_lists_set_flags() {
+        let mut voxel = Voxel::from_rgb(1, 2, 3);
+        assert_eq!(flags_label(voxel), "no flags");
+        voxel.set_emissive(true);
+        voxel.set_tint_zone(2);
+        assert_eq!(flags_label(voxel), "emissive, tint: secondary");
+    }
 }
@@ -1,16 +1,50 @@
 //! User interface components using egui.
 
+mod camera_path;
+mod commands;
+mod dock;
+mod history;
 pub mod hud;
 mod panels;
-
+mod plugins;
+mod preferences;
+mod rig;
+mod slice_view;
+mod tasks;
+mod timeline;
+
+pub use commands::{
+    fuzzy_score, registry, Command, CommandPaletteState, CommandTarget, UiWindow,
+    COMMAND_PALETTE_SHORTCUT,
+};
+pub use dock::{DockLayout, DockMove, DockPanel, DockSide, DockSlot};
 pub use hud::HudState;
 pub use panels::{ExportReport, UiAction, UiState};
+pub use preferences::{CustomTheme, GeneralSettings, Theme, MAX_UI_SCALE, MIN_UI_SCALE};
+pub use slice_view::{SliceImage, SliceViewState};
+pub use tasks::{TaskKind, TaskList, TaskStatus};
 
 use crate::ai::AiJobState;
-use crate::editor::{Axis, Editor, Quarter, Tool};
+use crate::core::{
+    Background, BackgroundKind, BoundingBox, Light, LightKind, MaterialDef, ReferenceImage,
+    ReferenceModel, ReferenceView, Voxel, WorldBounds, CHUNK_SIZE_I32, DEFAULT_MATERIAL,
+    MATERIAL_SLOTS, MAX_LIGHTS,
+};
+use crate::editor::{
+    Axis, BrushSettings, BrushShape, ColorAdjust, Downsample, Editor, ExtrudeDirection,
+    FillConnectivity, FillRegion, OriginAnchor, PaintPattern, Measurement, PaintSettings, Quarter,
+    RecolorScope, Scale, SculptMode, Selection, SelectionCounts, Tool, MAX_EXTRUDE_DEPTH,
+    MAX_FILL_VOXELS, MAX_PALETTE_COLORS,
+};
+use crate::io::{ColorStorage, ExportOptions, GlbColors, MeshSurface, Pivot, UpAxis, CAMERA_BOOKMARK_SLOTS};
+use crate::mesh::{LodSettings, MesherKind};
+use crate::render::{
+    CameraPath, CameraView, Easing, GiSettings, Msaa, PathTraceSettings, SpriteDirections,
+    SpriteView, ToonSettings, DEFAULT_TWEEN_SECS,
+};
 use crate::procgen::{
     CombineOp, FilterPredicate, LSystemTree, MaskMode, NodeId, NodeKind,
-    PerlinTerrain, PipelineGraph, WfcGenerator, WfcTileset,
+    PerlinTerrain, PipelineGraph, WfcGenerator, WfcTileset, CaveGenerator, ErosionSettings,
 };
 use egui::Context;
 
@@ -20,7 +54,12 @@ use egui::Context;
 pub struct ViewportSettings {
     pub show_grid: bool,
     pub show_axes: bool,
+    /// Draw chunk meshes as their triangle edges, to inspect mesher
+    /// output (greedy merges, AO diagonal flips).
     pub wireframe_mode: bool,
+    /// With `wireframe_mode`: keep the shaded meshes and draw the edges
+    /// on top, instead of lines only.
+    pub wireframe_shaded: bool,
     pub grid_size: i32,
     pub grid_spacing: f32,
     /// Viewport HUD (bottom-left tool / gesture readout).
@@ -29,6 +68,70 @@ pub struct ViewportSettings {
     /// Default off — stats overlays are opt-in everywhere (Blender /
     /// Unreal / Maya all ship them disabled).
     pub show_perf_hud: bool,
+    /// Split the viewport into Top / Front / Side / Perspective panes.
+    pub quad_view: bool,
+    /// Replace the raster view with the progressive path tracer. Takes
+    /// precedence over `quad_view` — the tracer renders one full-window
+    /// perspective image.
+    pub path_trace: bool,
+    pub path_trace_settings: PathTraceSettings,
+    /// Approximate global illumination in the raster view.
+    pub gi: bool,
+    pub gi_settings: GiSettings,
+    /// Stylized rendering: banded lighting and ink outlines, in the
+    /// raster view and every offscreen render.
+    pub toon: bool,
+    pub toon_settings: ToonSettings,
+    /// Chunk mesher for the editor view. Switching re-meshes every
+    /// chunk; exports always use the greedy mesher.
+    pub mesher: MesherKind,
+    /// Bake per-vertex ambient occlusion into the chunk meshes (Greedy
+    /// and Naive). Off gives flat-lit faces and lets greedy merge
+    /// across AO gradients, for fewer triangles.
+    pub ambient_occlusion: bool,
+    /// Shade chunk meshes with area-weighted averaged normals instead
+    /// of the mesher's own, to preview a smooth-normals export.
+    pub smooth_normals: bool,
+    /// Mesh distant chunks at a coarser level of detail.
+    pub lod: bool,
+    pub lod_settings: LodSettings,
+    /// Multisample anti-aliasing for the raster view. Falls back to
+    /// the nearest supported level; the App writes the applied level
+    /// back here.
+    pub msaa: Msaa,
+    /// Length of camera moves (view presets, Frame, Reset Camera,
+    /// bookmarks) in seconds; 0 snaps instantly.
+    pub camera_tween_secs: f32,
+    /// Easing curve of those moves.
+    pub camera_easing: Easing,
+    /// Hide every voxel above `y_clip_height`, to work on a building's
+    /// interior without deleting its roof. Display only: picking
+    /// reaches through the hidden voxels, and edits and exports still
+    /// see them.
+    pub y_clip: bool,
+    /// Highest layer still drawn while `y_clip` is on.
+    pub y_clip_height: i32,
+    /// The cursor passes through voxels of locked layers to what lies
+    /// behind, so a locked backdrop doesn't catch clicks meant for the
+    /// layer being edited.
+    pub click_through_locked: bool,
+    /// Draw the active layer's world bounds as a wireframe box.
+    pub show_bounds: bool,
+    /// Keep the last `Measure` ruler and its dimension label in the
+    /// viewport after switching to another tool.
+    pub show_measurement: bool,
+    /// Draw a marker at each placed light.
+    pub show_lights: bool,
+    /// Show the move gizmo on the selection, a pinned paste, the
+    /// selected light or the mirror planes.
+    pub move_gizmo: bool,
+    /// Show a tooltip by the cursor with the hovered voxel's position,
+    /// color, material and flags.
+    pub hover_tooltip: bool,
+    /// Find the hovered voxel by reading back an ID buffer rendered on
+    /// the GPU instead of raycasting the world on the CPU. Faster on
+    /// huge scenes; the hover trails the cursor by a frame or two.
+    pub gpu_picking: bool,
 }
 
 impl Default for ViewportSettings {
@@ -37,22 +140,62 @@ impl Default for ViewportSettings {
             show_grid: true,
             show_axes: true,
             wireframe_mode: false,
+            wireframe_shaded: false,
             grid_size: 20,
             grid_spacing: 1.0,
             show_hud: true,
             show_perf_hud: false,
+            quad_view: false,
+            path_trace: false,
+            path_trace_settings: PathTraceSettings::default(),
+            gi: false,
+            gi_settings: GiSettings::default(),
+            toon: false,
+            toon_settings: ToonSettings::default(),
+            mesher: MesherKind::default(),
+            ambient_occlusion: true,
+            smooth_normals: false,
+            lod: false,
+            lod_settings: LodSettings::default(),
+            msaa: Msaa::default(),
+            camera_tween_secs: DEFAULT_TWEEN_SECS,
+            camera_easing: Easing::default(),
+            y_clip: false,
+            y_clip_height: 8,
+            click_through_locked: false,
+            show_bounds: true,
+            show_measurement: false,
+            show_lights: true,
+            move_gizmo: true,
+            hover_tooltip: false,
+            gpu_picking: false,
         }
     }
 }
 
+impl ViewportSettings {
+    /// The highest layer drawn, or `None` when the Y-clip is off.
+    pub fn y_clip_max(&self) -> Option<i32> {
+        self.y_clip.then_some(self.y_clip_height)
+    }
+
+    /// Half the side of the square the ground grid covers around the
+    /// origin — how far out clicking empty space still places on y = 0.
+    pub fn grid_half_extent(&self) -> f32 {
+        self.grid_size as f32 * self.grid_spacing / 2.0
+    }
+}
+
 /// Which generator the procgen panel is currently editing.
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, Default,
 )]
 pub enum GeneratorChoice {
+    #[default]
     Terrain,
     Tree,
     Wfc,
+    Cave,
 }
 
 impl GeneratorChoice {
@@ -62,13 +205,334 @@ impl GeneratorChoice {
             Self::Terrain => "Perlin Terrain",
             Self::Tree => "L-System Tree",
             Self::Wfc => "WFC Tile Layout",
+            Self::Cave => "Cellular Caves",
+        }
+    }
+}
+
+/// Display snapshot of the running procgen job (panel generator or
+/// graph run). Built by the App each frame from its `GenJob`; the
+/// panels only read it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenJobStatus {
+    /// Status-bar name of the run ("Perlin Terrain", "Graph").
+    pub label: String,
+    /// 0.0..=1.0.
+    pub progress: f32,
+    /// Cancel was requested; waiting for the worker's next checkpoint.
+    pub cancelling: bool,
+    /// Rough time left, when there's anything to base it on.
+    pub remaining: Option<std::time::Duration>,
+}
+
+/// Output of Render ▸ Turntable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TurntableFormat {
+    /// One numbered PNG per frame (`turntable_0000.png`, ...) in a
+    /// chosen folder — lossless, for video editors.
+    #[default]
+    PngSequence,
+    /// A single looping animated GIF, ready to post.
+    Gif,
+}
+
+impl TurntableFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::PngSequence => "PNG sequence",
+            Self::Gif => "Animated GIF",
+        }
+    }
+}
+
+/// Render ▸ Turntable settings. Session-only, like the AI panel's
+/// resolution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurntableSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Frames in one full 360° turn.
+    pub frames: u32,
+    /// GIF playback rate. GIF delays are whole centiseconds, so 50 is
+    /// the highest rate every viewer honors.
+    pub fps: u32,
+    pub format: TurntableFormat,
+}
+
+impl Default for TurntableSettings {
+    fn default() -> Self {
+        Self {
+            width: 512,
+            height: 512,
+            frames: 72,
+            fps: 24,
+            format: TurntableFormat::default(),
+        }
+    }
+}
+
+/// Render ▸ Camera Path output settings. Session-only, like the
+/// turntable's; the path itself is saved with the project.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlythroughSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Frames rendered per second of path time.
+    pub fps: u32,
+}
+
+impl Default for FlythroughSettings {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            fps: 30,
+        }
+    }
+}
+
+/// Render ▸ Screenshot settings. Session-only, like the turntable's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenshotSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Render at this multiple of the size and box-filter down
+    /// (1 = off). Stacks with the viewport's MSAA.
+    pub supersample: u32,
+    /// Transparent background, without grid or axes; otherwise the
+    /// viewport background with the grid / axes as currently shown.
+    pub transparent: bool,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            width: 3840,
+            height: 2160,
+            supersample: 2,
+            transparent: false,
+        }
+    }
+}
+
+/// Render ▸ Sprite Sheet settings. Session-only, like the screenshot's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSettings {
+    pub view: SpriteView,
+    pub directions: SpriteDirections,
+    /// Exact on-screen size of one voxel, in pixels.
+    pub pixels_per_voxel: u32,
+    /// Ring the silhouette with a 1-pixel `outline_color` line.
+    pub outline: bool,
+    pub outline_color: [u8; 4],
+    /// One PNG with the frames side by side; otherwise one PNG per
+    /// direction in a folder.
+    pub sheet: bool,
+}
+
+impl Default for SpriteSettings {
+    fn default() -> Self {
+        Self {
+            view: SpriteView::default(),
+            directions: SpriteDirections::default(),
+            pixels_per_voxel: 2,
+            outline: true,
+            outline_color: [0, 0, 0, 255],
+            sheet: true,
+        }
+    }
+}
+
+/// File format written from the Export dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeshExportFormat {
+    Obj,
+    #[default]
+    Glb,
+}
+
+impl MeshExportFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Obj => "Wavefront OBJ (.obj)",
+            Self::Glb => "glTF Binary (.glb)",
+        }
+    }
+}
+
+/// File ▸ Export dialog settings: the target format plus the options
+/// handed to the exporter. Session-only, like the screenshot's.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MeshExportSettings {
+    pub format: MeshExportFormat,
+    pub options: ExportOptions,
+}
+
+/// File ▸ Project Settings… edits: whether the world is bounded and
+/// its size in chunks, applied to every layer. Session-only; refilled
+/// from the scene each time the dialog opens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSettings {
+    pub bounded: bool,
+    /// World size in chunks along X, Y and Z.
+    pub chunks: [u32; 3],
+    /// Filled from the scene since the dialog last opened.
+    filled: bool,
+}
+
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self {
+            bounded: false,
+            chunks: [8, 4, 8],
+            filled: false,
+        }
+    }
+}
+
+impl ProjectSettings {
+    /// The bounds to apply: [`WorldBounds::on_ground`] at the chosen
+    /// size, or `None` for an unbounded world.
+    pub fn bounds(&self) -> Option<WorldBounds> {
+        let [x, y, z] = self.chunks;
+        self.bounded.then(|| WorldBounds::on_ground((x, y, z)))
+    }
+
+    /// Take the settings from the scene's current world bounds (the
+    /// size stays as it was for an unbounded world).
+    fn fill(&mut self, bounds: Option<WorldBounds>) {
+        self.bounded = bounds.is_some();
+        if let Some(bounds) = bounds {
+            let (x, y, z) = bounds.size();
+            self.chunks = [x, y, z];
+        }
+        self.filled = true;
+    }
+}
+
+/// Largest model a `.vox` file holds, in voxels per side; bigger
+/// content is split into several models on export.
+const VOX_MODEL_MAX: u32 = 256;
+
+/// Colors in a `.vox` palette; more are quantized on export.
+const VOX_PALETTE_COLORS: usize = 255;
+
+/// Edit ▸ Transform ▸ Crop… box, inclusive on both corners like a
+/// selection. Session-only; refilled from the selection or the model
+/// bounds each time the dialog opens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CropSettings {
+    pub min: [i32; 3],
+    pub max: [i32; 3],
+    /// Also bound the layer to the box (rounded out to whole chunks).
+    pub bound_layer: bool,
+}
+
+impl Default for CropSettings {
+    fn default() -> Self {
+        Self {
+            min: [0; 3],
+            max: [CHUNK_SIZE_I32 - 1; 3],
+            bound_layer: false,
         }
     }
 }
 
-impl Default for GeneratorChoice {
+impl CropSettings {
+    /// The box as a selection; corners are sorted, so a min dragged
+    /// past its max still makes a valid box.
+    pub fn keep(&self) -> Selection {
+        let [x0, y0, z0] = self.min;
+        let [x1, y1, z1] = self.max;
+        Selection::from_corners((x0, y0, z0), (x1, y1, z1))
+    }
+
+    /// Take the box from `sel`.
+    pub fn fit(&mut self, sel: Selection) {
+        self.min = [sel.min.0, sel.min.1, sel.min.2];
+        self.max = [sel.max.0, sel.max.1, sel.max.2];
+    }
+}
+
+/// Which voxels Edit ▸ Color ▸ Replace… recolors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplaceScope {
+    /// The active layer's whole model.
+    #[default]
+    Layer,
+    /// The voxels inside the selection.
+    Selection,
+    /// The connected run around the voxel the color was picked from.
+    Contiguous,
+}
+
+/// Edit ▸ Color settings, kept between uses. Session-only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecolorSettings {
+    /// Color (RGBA) Replace looks for.
+    pub from: [u8; 4],
+    /// Cell `from` was last picked from in the viewport — the seed for
+    /// [`ReplaceScope::Contiguous`].
+    pub seed: Option<(i32, i32, i32)>,
+    pub scope: ReplaceScope,
+    /// The next viewport click picks `from` (and `seed`) instead of
+    /// using the current tool. Consumed by the App.
+    pub picking: bool,
+    /// Hue / saturation / brightness shift for Adjust Colors….
+    pub adjust: ColorAdjust,
+    /// Blur passes for Smooth….
+    pub smooth_iterations: u32,
+}
+
+impl Default for RecolorSettings {
     fn default() -> Self {
-        Self::Terrain
+        Self {
+            from: [255, 255, 255, 255],
+            seed: None,
+            scope: ReplaceScope::default(),
+            picking: false,
+            adjust: ColorAdjust::default(),
+            smooth_iterations: 1,
+        }
+    }
+}
+
+/// A finished generation waiting for Apply / Discard, as shown by
+/// the procgen and graph panels. Mirrored by the App each frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedStatus {
+    /// Name of the run that produced it ("Perlin Terrain", "Graph").
+    pub label: String,
+    /// Voxels in the staged patch.
+    pub voxels: usize,
+}
+
+/// Summary of the visible model for the Model Info window, computed by
+/// the App from the composited view (so hidden layers don't count —
+/// it's what an export would write).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelInfo {
+    /// Box around the solid voxels; `None` when the model is empty.
+    pub bounds: Option<BoundingBox>,
+    /// Solid voxels.
+    pub voxels: u64,
+    /// Distinct RGBA colors with their voxel counts, most used first.
+    pub colors: Vec<([u8; 4], u64)>,
+    /// Loaded chunks in the view.
+    pub chunks: usize,
+    /// Voxel storage across every layer and the view, in bytes.
+    pub memory_bytes: usize,
+}
+
+/// Colors listed by the Model Info window before "… and N more".
+const MODEL_INFO_TOP_COLORS: usize = 8;
+
+/// "1 color" / "7 colors".
+fn colors_label(n: usize) -> String {
+    if n == 1 {
+        "1 color".to_string()
+    } else {
+        format!("{n} colors")
     }
 }
 
@@ -76,7 +540,7 @@ impl Default for GeneratorChoice {
 ///
 /// Each generator's instance doubles as its parameter state — UI
 /// sliders mutate the fields in place, then `UiAction::GenerateProcedural`
-/// triggers `selected`'s `generate()` in the application layer.
+/// runs `selected` on a worker thread in the application layer.
 ///
 /// `preview_enabled` and `graph_preview_enabled` independently drive
 /// translucent overlays — the first for the selected single generator,
@@ -89,9 +553,18 @@ pub struct ProcgenSettings {
     pub terrain: PerlinTerrain,
     pub tree: LSystemTree,
     pub wfc: WfcGenerator,
+    /// `#[serde(default)]` so prefs saved before the cave generator
+    /// existed still load.
+    #[serde(default)]
+    pub cave: CaveGenerator,
     pub preview_enabled: bool,
     #[serde(default)]
     pub graph_preview_enabled: bool,
+    /// Commit Generate / Run Pipeline output straight into the world
+    /// instead of staging it for review. Off by default: a finished run
+    /// is shown as a translucent overlay until Apply or Discard.
+    #[serde(default)]
+    pub apply_immediately: bool,
 }
 
 /// Main UI manager
@@ -122,6 +595,17 @@ pub struct Ui {
     /// gray out the Paste button without `App::clipboard` leaking
     /// across the UI layer boundary. App syncs it before each frame.
     pub has_clipboard: bool,
+    /// Solid voxels and unique colors in the active selection, kept
+    /// current by the App (`App::refresh_selection_stats`) for the
+    /// status bar and Tools panel. `None` without a selection.
+    pub selection_counts: Option<SelectionCounts>,
+    /// The solid voxel under the cursor and its cell, mirrored from
+    /// the App's hover each frame for the voxel tooltip.
+    pub hovered_voxel: Option<((i32, i32, i32), Voxel)>,
+    /// Which of the nine camera bookmark slots hold a saved view,
+    /// mirrored from `App::camera_bookmarks` each frame so the Camera
+    /// section can label filled slots and disable empty ones.
+    pub camera_bookmarks: [bool; CAMERA_BOOKMARK_SLOTS],
 
     /// User-edited prompt for the AI panel. Owned by the UI (rather
     /// than App) so the input field's state lives next to its widget;
@@ -139,6 +623,82 @@ pub struct Ui {
     /// Mirror of `App::ai_has_key` for the same reason. Refreshed by
     /// the App after every Save / Clear API key action.
     pub ai_has_key: bool,
+    /// Running procgen / graph job, mirrored by the App each frame.
+    /// While `Some`, Generate and Run Pipeline are disabled and both
+    /// panels show its progress bar with a Cancel button.
+    pub gen_job: Option<GenJobStatus>,
+    /// Staged generator output awaiting Apply / Discard, mirrored by
+    /// the App each frame.
+    pub staged: Option<StagedStatus>,
+    /// Render ▸ Turntable settings.
+    pub turntable: TurntableSettings,
+    /// Render ▸ Screenshot settings.
+    pub screenshot: ScreenshotSettings,
+    pub sprites: SpriteSettings,
+    /// File ▸ Export dialog settings.
+    pub mesh_export: MeshExportSettings,
+    /// Edit ▸ Transform ▸ Move… offset, kept between uses so the same
+    /// step can be applied repeatedly.
+    pub move_delta: [i32; 3],
+    /// Edit ▸ Transform ▸ Crop… box and options.
+    pub crop: CropSettings,
+    /// File ▸ Project Settings… world bounds.
+    pub project: ProjectSettings,
+    /// Edit ▸ Color ▸ Replace… / Adjust Colors… / Smooth… settings.
+    pub recolor: RecolorSettings,
+    /// Running turntable render, mirrored by the App each frame. Reuses
+    /// the procgen job snapshot: a label, progress and an ETA.
+    pub turntable_job: Option<GenJobStatus>,
+    /// Render ▸ Camera Path output settings.
+    pub flythrough: FlythroughSettings,
+    /// Running fly-through render, mirrored by the App each frame like
+    /// `turntable_job`.
+    pub flythrough_job: Option<GenJobStatus>,
+    /// Mirror of `App::camera_path` for the Camera Path window.
+    pub camera_path: CameraPath,
+    /// The viewport camera is flying the path (preview), mirrored by
+    /// the App each frame.
+    pub camera_path_previewing: bool,
+    /// Viewport background. Saved with the project rather than in
+    /// prefs; the App applies changes each frame.
+    pub background: Background,
+    /// Point and spot lights, edited in the Lights panel. Saved with
+    /// the project like `background`.
+    pub lights: Vec<Light>,
+    /// Light being edited in the Lights panel (and marked in yellow).
+    pub selected_light: Option<usize>,
+    /// Reference image planes. Saved with the project like
+    /// `background`.
+    pub references: Vec<ReferenceImage>,
+    /// Plane being edited in the References panel.
+    pub selected_reference: Option<usize>,
+    /// Model shown as a ghost, from the References panel. Saved with
+    /// the project like `references`.
+    pub reference_model: Option<ReferenceModel>,
+    /// Model Info window contents. Refreshed by the App while the
+    /// window is open, only after the voxels change (the histogram
+    /// visits every voxel).
+    pub model_info: Option<ModelInfo>,
+    /// Edit ▸ Preferences… settings; the App applies them each frame.
+    pub general: GeneralSettings,
+    /// Where the Statistics / Tools / Palette / Viewport Settings
+    /// panels float or dock. Persisted in prefs.
+    pub dock: DockLayout,
+    /// Every menu / palette command; built once from
+    /// [`commands::registry`].
+    pub commands: Vec<Command>,
+    /// Ctrl+P command palette.
+    pub command_palette: CommandPaletteState,
+    /// Registered generator / exporter plugins; see [`Ui::set_plugins`].
+    pub plugins: Vec<crate::plugin::PluginInfo>,
+    /// Running background work, mirrored by the App each frame for the
+    /// status-bar indicator and the task list popup.
+    pub tasks: TaskList,
+    /// Slice Editor zoom, hovered cell and stroke state.
+    pub slice_view: SliceViewState,
+    /// Slice Editor contents, re-sampled by the App while the window is
+    /// open whenever the voxels or the work plane change.
+    pub slice_image: Option<SliceImage>,
 }
 
 impl Ui {
@@ -153,10 +713,43 @@ impl Ui {
             recent_files: Vec::new(),
             recent_ai_prompts: Vec::new(),
             has_clipboard: false,
+            selection_counts: None,
+            hovered_voxel: None,
+            camera_bookmarks: [false; CAMERA_BOOKMARK_SLOTS],
             ai_prompt: String::new(),
             ai_resolution: 64,
             ai_job: AiJobState::Idle,
             ai_has_key: false,
+            gen_job: None,
+            staged: None,
+            turntable: TurntableSettings::default(),
+            screenshot: ScreenshotSettings::default(),
+            sprites: SpriteSettings::default(),
+            mesh_export: MeshExportSettings::default(),
+            move_delta: [0; 3],
+            crop: CropSettings::default(),
+            project: ProjectSettings::default(),
+            recolor: RecolorSettings::default(),
+            turntable_job: None,
+            flythrough: FlythroughSettings::default(),
+            flythrough_job: None,
+            camera_path: CameraPath::default(),
+            camera_path_previewing: false,
+            background: Background::default(),
+            lights: Vec::new(),
+            selected_light: None,
+            references: Vec::new(),
+            selected_reference: None,
+            reference_model: None,
+            model_info: None,
+            general: GeneralSettings::default(),
+            dock: DockLayout::default(),
+            commands: registry(),
+            command_palette: CommandPaletteState::default(),
+            plugins: Vec::new(),
+            tasks: TaskList::default(),
+            slice_view: SliceViewState::default(),
+            slice_image: None,
         }
     }
 
@@ -175,34 +768,33 @@ impl Ui {
         // Left side panel with tools
         self.show_toolbar(ctx, editor);
 
-        // Stats panel
-        if self.state.show_stats {
-            self.show_stats_panel(ctx, stats, editor);
-        }
+        // Statistics, Tools, Palette and Viewport Settings, docked or
+        // floating
+        self.show_dock(ctx, stats, editor);
 
-        // Tools panel
-        if self.state.show_tools {
-            self.show_tools_panel(ctx, editor);
+        // Procedural generation panel
+        if self.state.show_procgen {
+            self.show_procgen_panel(ctx, editor.selection.is_some());
         }
 
-        // Color palette panel
-        if self.state.show_palette {
-            self.show_palette_panel(ctx, editor);
+        // Pipeline graph panel
+        if self.state.show_graph {
+            self.show_graph_panel(ctx);
         }
 
-        // Viewport settings panel
-        if self.state.show_viewport_settings {
-            self.show_viewport_panel(ctx);
+        // Layers panel
+        if self.state.show_layers {
+            self.show_layers_panel(ctx, stats);
         }
 
-        // Procedural generation panel
-        if self.state.show_procgen {
-            self.show_procgen_panel(ctx);
+        // Animation timeline
+        if self.state.show_timeline {
+            self.show_timeline_panel(ctx, stats);
         }
 
-        // Pipeline graph panel
-        if self.state.show_graph {
-            self.show_graph_panel(ctx);
+        // Rig bones and posing
+        if self.state.show_rig {
+            self.show_rig_panel(ctx, editor);
         }
 
         // AI generation panel
@@ -210,6 +802,102 @@ impl Ui {
             self.show_ai_panel(ctx);
         }
 
+        // Turntable render window
+        if self.state.show_turntable {
+            self.show_turntable_panel(ctx);
+        }
+
+        // Camera path editor / fly-through render window
+        if self.state.show_camera_path {
+            self.show_camera_path_panel(ctx);
+        }
+
+        // Screenshot window
+        if self.state.show_screenshot {
+            self.show_screenshot_panel(ctx);
+        }
+
+        // Sprite sheet window
+        if self.state.show_sprites {
+            self.show_sprites_panel(ctx);
+        }
+
+        // Placed lights
+        if self.state.show_lights {
+            self.show_lights_panel(ctx);
+        }
+
+        // Reference image planes
+        if self.state.show_references {
+            self.show_references_panel(ctx);
+        }
+
+        // Mesh export window
+        if self.state.show_export {
+            self.show_export_panel(ctx);
+        }
+
+        // Model info window
+        if self.state.show_model_info {
+            self.show_model_info_panel(ctx);
+        }
+
+        // Undo history window
+        if self.state.show_history {
+            self.show_history_panel(ctx, editor);
+        }
+
+        // Help ▸ Plugins window
+        if self.state.show_plugins {
+            self.show_plugins_panel(ctx);
+        }
+
+        // Edit ▸ Preferences window
+        if self.state.show_preferences {
+            self.show_preferences_panel(ctx);
+        }
+
+        // Slice Editor window
+        if self.state.show_slice_view {
+            self.show_slice_view_panel(ctx, editor);
+        }
+
+        // Transform ▸ Move dialog
+        if self.state.show_move {
+            self.show_move_panel(ctx, editor.selection.is_some());
+        }
+
+        // File ▸ Project Settings dialog
+        if self.state.show_project_settings {
+            let bounds = stats.layers.first().and_then(|l| l.bounds);
+            let shared = stats.layers.iter().all(|l| l.bounds == bounds);
+            self.show_project_settings_panel(ctx, bounds.filter(|_| shared), shared);
+        } else {
+            self.project.filled = false;
+        }
+
+        // Transform ▸ Crop dialog
+        if self.state.show_crop {
+            let bounds = stats.layers.get(stats.active_layer).and_then(|l| l.bounds);
+            self.show_crop_panel(ctx, editor.selection.is_some(), bounds);
+        }
+
+        // Edit ▸ Color dialogs
+        if self.state.show_replace_color {
+            self.show_replace_color_panel(ctx, editor);
+        }
+        if self.state.show_adjust_colors {
+            self.show_adjust_colors_panel(ctx, editor.selection.is_some());
+        }
+        if self.state.show_smooth_colors {
+            self.show_smooth_colors_panel(ctx, editor.selection.is_some());
+        }
+
+        // Material table editor
+        if self.state.show_materials {
+            self.show_materials_panel(ctx, editor);
+        }
+
         // Help panel
         if self.state.show_help {
             self.show_help_panel(ctx);
@@ -223,6 +911,12 @@ impl Ui {
         // Status bar
         self.show_status_bar(ctx, editor);
 
+        // Quad-view pane dividers + labels. Background layer, so every
+        // panel and the HUD blocks below draw over it.
+        if self.viewport.quad_view && !self.viewport.path_trace {
+            hud::show_quad_view_overlay(ctx);
+        }
+
         // Viewport HUD — after the status bar so every panel has
         // claimed its screen edge and `ctx.available_rect()` is the
         // true viewport rect the HUD anchors inside.
@@ -230,11 +924,30 @@ impl Ui {
             hud::show_hud_overlay(ctx, hud);
         }
 
+        // Measure ruler label — independent of the HUD toggle, it's
+        // part of the ruler itself.
+        if let Some((pos, text)) = &hud.measure {
+            hud::show_measure_annotation(ctx, *pos, text);
+        }
+
+        if self.viewport.hover_tooltip {
+            if let Some((cell, voxel)) = self.hovered_voxel {
+                let material = &editor.materials.resolve(voxel.material).name;
+                hud::show_voxel_tooltip(ctx, cell, voxel, material);
+            }
+        }
+
         // Performance HUD — bottom-right counterpart, same rules.
         if self.viewport.show_perf_hud {
             hud::show_perf_overlay(ctx, stats);
         }
 
+        // Background task list, opened from the status bar
+        self.show_task_list(ctx);
+
+        // Ctrl+P command palette, over every panel
+        self.show_command_palette(ctx);
+
         // Crash-recovery prompt, rendered last so it sits on top. This
         // is an in-app egui dialog, NOT a native `rfd::MessageDialog` —
         // the latter exits the process on this winit + wgpu setup
@@ -243,6 +956,12 @@ impl Ui {
         if self.state.show_recovery_prompt {
             self.show_recovery_prompt(ctx);
         }
+        if self.state.show_large_fill_prompt {
+            self.show_large_fill_prompt(ctx);
+        }
+        if let Some(voxels) = self.state.world_bounds_prompt {
+            self.show_world_bounds_prompt(ctx, voxels);
+        }
 
         // File-operation error dialog (also in-app egui, not native rfd
         // — same crash reason; see `show_recovery_prompt`).
@@ -389,18 +1108,68 @@ impl Ui {
             });
     }
 
+    /// Fill / Cancel prompt for a Fill click over the voxel cap. Both
+    /// dispatch a `UiAction` and clear the flag.
+    fn show_large_fill_prompt(&mut self, ctx: &Context) {
+        egui::Window::new("Large fill")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "This fill would change more than {} voxels.\n\
+                     Fill the whole region anyway?",
+                    MAX_FILL_VOXELS
+                ));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Fill").clicked() {
+                        self.state.request(UiAction::ConfirmLargeFill);
+                        self.state.show_large_fill_prompt = false;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.state.request(UiAction::CancelLargeFill);
+                        self.state.show_large_fill_prompt = false;
+                    }
+                });
+            });
+    }
+
+    /// Delete / Cancel prompt for Project Settings bounds that would
+    /// delete `voxels` voxels. Both dispatch a `UiAction` and clear the
+    /// prompt.
+    fn show_world_bounds_prompt(&mut self, ctx: &Context, voxels: u64) {
+        egui::Window::new("Bound the world")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} voxels lie outside the new bounds, in this or other layers \
+                     and animation frames.\nThey will be deleted and the undo history \
+                     cleared. This can't be undone.",
+                    voxels
+                ));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Delete and apply").clicked() {
+                        self.state.request(UiAction::ConfirmWorldBounds);
+                        self.state.world_bounds_prompt = None;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.state.request(UiAction::CancelWorldBounds);
+                        self.state.world_bounds_prompt = None;
+                    }
+                });
+            });
+    }
+
     fn show_menu_bar(&mut self, ctx: &Context, editor: &Editor) {
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
-                    if ui.button("New").clicked() {
-                        self.state.request(UiAction::NewProject);
-                        ui.close_menu();
-                    }
-                    if ui.button("Open...").clicked() {
-                        self.state.request(UiAction::OpenProject);
-                        ui.close_menu();
-                    }
+                    self.action_item(ui, UiAction::NewProject);
+                    self.action_item(ui, UiAction::OpenProject);
                     ui.menu_button("Open Recent", |ui| {
                         if self.recent_files.is_empty() {
                             ui.add_enabled(false, egui::Button::new("(empty)"));
@@ -419,286 +1188,140 @@ impl Ui {
                                     ui.close_menu();
                                 }
                             }
+                            ui.separator();
+                            self.action_item(ui, UiAction::ClearRecent);
                         }
                     });
-                    if ui.button("Save").clicked() {
-                        self.state.request(UiAction::SaveProject);
-                        ui.close_menu();
-                    }
-                    if ui.button("Save As...").clicked() {
-                        self.state.request(UiAction::SaveAs);
-                        ui.close_menu();
-                    }
+                    self.action_item(ui, UiAction::SaveProject);
+                    self.action_item(ui, UiAction::SaveAs);
+                    ui.separator();
+                    self.window_item(ui, UiWindow::ProjectSettings);
                     ui.separator();
                     ui.menu_button("Import", |ui| {
-                        if ui.button("MagicaVoxel (.vox)...").clicked() {
-                            self.state.request(UiAction::ImportVox);
-                            ui.close_menu();
-                        }
+                        self.action_item(ui, UiAction::ImportVox);
+                        ui.menu_button("VOX Axes", |ui| self.vox_axes_controls(ui));
+                        self.action_item(ui, UiAction::ImportQubicle);
+                        self.action_item(ui, UiAction::ImportPngSlices)
+                            .on_hover_text("Select every slice of a stack; one PNG per Y layer, bottom first by file name");
+                        self.action_item(ui, UiAction::ImportHeightmap)
+                            .on_hover_text("Grayscale PNG as terrain — brightness is height, colored by a sand / grass / rock / snow gradient");
+                        self.action_item(ui, UiAction::ImportPalette)
+                            .on_hover_text(
+                                "Replace the palette from .gpl / .pal, a .vox's colors (no geometry) or a .png swatch strip",
+                            );
                     });
                     ui.menu_button("Export", |ui| {
-                        if ui.button("MagicaVoxel (.vox)...").clicked() {
-                            self.state.request(UiAction::ExportVox);
-                            ui.close_menu();
-                        }
-                        if ui.button("Wavefront OBJ (.obj)...").clicked() {
-                            self.state.request(UiAction::ExportObj);
-                            ui.close_menu();
-                        }
-                        if ui
-                            .button("Wavefront OBJ — smoothed, light (.obj)...")
-                            .on_hover_text(
-                                "Marching Cubes over raw voxel density: \
-                                 voxel surfaces with rounded edges. \
-                                 Preserves thin features (tree branches, \
-                                 sparse detail).",
-                            )
-                            .clicked()
-                        {
-                            self.state.request(UiAction::ExportObjSmoothedLight);
-                            ui.close_menu();
-                        }
-                        if ui
-                            .button("Wavefront OBJ — smoothed, heavy (.obj)...")
-                            .on_hover_text(
-                                "Marching Cubes after a 3×3×3 density \
-                                 blur: clay-like blobs. Best for terrain \
-                                 / large solid masses; thin features may \
-                                 dissolve.",
-                            )
-                            .clicked()
-                        {
-                            self.state.request(UiAction::ExportObjSmoothedHeavy);
-                            ui.close_menu();
-                        }
-                        if ui.button("glTF Binary (.glb)...").clicked() {
-                            self.state.request(UiAction::ExportGlb);
-                            ui.close_menu();
-                        }
-                        if ui
-                            .button("glTF Binary — smoothed, light (.glb)...")
+                        self.action_item(ui, UiAction::ExportVox);
+                        ui.menu_button("VOX Axes", |ui| self.vox_axes_controls(ui));
+                        self.action_item(ui, UiAction::ExportQb);
+                        self.action_item(ui, UiAction::ExportQbt);
+                        self.action_item(ui, UiAction::ExportPngSlices)
+                            .on_hover_text("One PNG per Y layer: <name>_000.png, <name>_001.png, …");
+                        self.action_item(ui, UiAction::ExportObj);
+                        self.action_item(ui, UiAction::ExportGlb);
+                        ui.separator();
+                        self.action_item(ui, UiAction::ExportFramesVox)
+                            .on_hover_text("One file per animation frame: <name>_000.vox, …");
+                        self.action_item(ui, UiAction::ExportFramesObj)
+                            .on_hover_text("One file per animation frame: <name>_000.obj, …");
+                        self.action_item(ui, UiAction::ExportFramesGlb)
                             .on_hover_text(
-                                "Marching Cubes over raw voxel density: \
-                                 voxel surfaces with rounded edges. \
-                                 Preserves thin features.",
-                            )
-                            .clicked()
-                        {
-                            self.state.request(UiAction::ExportGlbSmoothedLight);
-                            ui.close_menu();
-                        }
-                        if ui
-                            .button("glTF Binary — smoothed, heavy (.glb)...")
+                                "All frames in one file, with a looping animation \
+                                 that shows one frame node at a time",
+                            );
+                        self.action_item(ui, UiAction::ExportRiggedGlb)
                             .on_hover_text(
-                                "Marching Cubes after a 3×3×3 density \
-                                 blur: clay-like blobs. Best for terrain.",
-                            )
-                            .clicked()
-                        {
-                            self.state.request(UiAction::ExportGlbSmoothedHeavy);
-                            ui.close_menu();
-                        }
+                                "One node per rig bone, nested by parent, posed \
+                                 by node transforms — re-posable in an engine",
+                            );
+                        self.plugin_exporter_items(ui);
                     });
                     ui.separator();
-                    if ui.button("Exit").clicked() {
-                        self.state.request(UiAction::Exit);
-                    }
+                    self.action_item(ui, UiAction::Exit);
                 });
 
                 ui.menu_button("Edit", |ui| {
-                    let undo_text = if editor.can_undo() { "Undo  Ctrl+Z" } else { "Undo" };
-                    if ui.add_enabled(editor.can_undo(), egui::Button::new(undo_text)).clicked() {
-                        self.state.request(UiAction::Undo);
-                        ui.close_menu();
-                    }
-                    let redo_text = if editor.can_redo() { "Redo  Ctrl+Y" } else { "Redo" };
-                    if ui.add_enabled(editor.can_redo(), egui::Button::new(redo_text)).clicked() {
-                        self.state.request(UiAction::Redo);
-                        ui.close_menu();
-                    }
+                    // "Undo Paste" / "Redo Rotate": name the entry it acts on.
+                    let undo = match editor.history.undo_label() {
+                        Some(label) => format!("Undo {label}"),
+                        None => "Undo".to_string(),
+                    };
+                    let redo = match editor.history.redo_label() {
+                        Some(label) => format!("Redo {label}"),
+                        None => "Redo".to_string(),
+                    };
+                    let target = CommandTarget::Action(UiAction::Undo);
+                    self.command_item_as(ui, target, &undo, editor.can_undo());
+                    let target = CommandTarget::Action(UiAction::Redo);
+                    self.command_item_as(ui, target, &redo, editor.can_redo());
                     ui.separator();
                     let has_sel = editor.selection.is_some();
                     let can_paste = self.has_clipboard;
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Cut  Ctrl+X"))
-                        .clicked()
-                    {
-                        self.state.request(UiAction::CutSelection);
-                        ui.close_menu();
-                    }
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Copy  Ctrl+C"))
-                        .clicked()
-                    {
-                        self.state.request(UiAction::CopySelection);
-                        ui.close_menu();
-                    }
-                    if ui
-                        .add_enabled(can_paste, egui::Button::new("Paste  Ctrl+V"))
-                        .clicked()
-                    {
-                        self.state.request(UiAction::PasteClipboard);
-                        ui.close_menu();
-                    }
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Delete  Del"))
-                        .clicked()
-                    {
-                        self.state.request(UiAction::DeleteSelection);
-                        ui.close_menu();
-                    }
+                    self.command_item(ui, CommandTarget::Action(UiAction::CutSelection), has_sel);
+                    self.command_item(ui, CommandTarget::Action(UiAction::CopySelection), has_sel);
+                    let target = CommandTarget::Action(UiAction::PasteClipboard);
+                    self.command_item(ui, target, can_paste);
+                    let target = CommandTarget::Action(UiAction::BeginPastePlacement);
+                    self.command_item(ui, target, can_paste)
+                        .on_hover_text(
+                            "Paste follows the cursor as a ghost. R / Shift+R \
+                             rotate, M mirrors, click commits, Esc cancels.",
+                        );
+                    let target = CommandTarget::Action(UiAction::DeleteSelection);
+                    self.command_item(ui, target, has_sel);
                     ui.separator();
-                    if ui.button("Select All  Ctrl+A").clicked() {
-                        self.state.request(UiAction::SelectAllSolid);
-                        ui.close_menu();
-                    }
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Deselect  Esc"))
-                        .clicked()
-                    {
-                        self.state.request(UiAction::Deselect);
-                        ui.close_menu();
-                    }
+                    self.action_item(ui, UiAction::SelectAllSolid);
+                    self.command_item(ui, CommandTarget::Action(UiAction::Deselect), has_sel);
                     ui.separator();
-                    if ui.button("Clear All").clicked() {
-                        self.state.request(UiAction::ClearAll);
-                        ui.close_menu();
-                    }
+                    // Same commands as the Selection menu, but enabled
+                    // without a selection: they then turn the active
+                    // layer's whole model.
+                    ui.menu_button("Transform", |ui| {
+                        let target = if editor.selection.is_some() {
+                            "Applies to the selection"
+                        } else {
+                            "Applies to the whole layer"
+                        };
+                        ui.label(egui::RichText::new(target).small().weak());
+                        self.transform_menu_items(ui, true);
+                        self.window_item(ui, UiWindow::Crop);
+                        ui.separator();
+                        let anchor = OriginAnchor::Center;
+                        self.action_item(ui, UiAction::CenterOnOrigin { anchor })
+                            .on_hover_text("Move the layer's model so its bounding-box center is at 0, 0, 0");
+                        let anchor = OriginAnchor::Base;
+                        self.action_item(ui, UiAction::CenterOnOrigin { anchor })
+                            .on_hover_text("Center the layer's model on X / Z and stand it on y = 0");
+                    });
+                    ui.menu_button("Color", |ui| {
+                        self.window_item(ui, UiWindow::ReplaceColor);
+                        self.window_item(ui, UiWindow::AdjustColors);
+                        self.window_item(ui, UiWindow::SmoothColors);
+                    });
+                    ui.separator();
+                    self.action_item(ui, UiAction::ClearAll);
+                    ui.separator();
+                    self.window_item(ui, UiWindow::Preferences);
                 });
 
                 ui.menu_button("Selection", |ui| {
                     let has_sel = editor.selection.is_some();
-                    // Each Rotate submenu hosts CW / CCW / 180°.
-                    // Anchor is selection.min — the rotated AABB
-                    // extends from the same min, so a 4×1×2 region
-                    // becomes 2×1×4 spreading toward +Z.
-                    ui.menu_button("Rotate around X", |ui| {
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("90°"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::X,
-                                quarter: Quarter::Cw,
-                            });
-                            ui.close_menu();
-                        }
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("-90°"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::X,
-                                quarter: Quarter::Ccw,
-                            });
-                            ui.close_menu();
-                        }
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("180°"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::X,
-                                quarter: Quarter::Half,
-                            });
-                            ui.close_menu();
-                        }
-                    });
-                    ui.menu_button("Rotate around Y", |ui| {
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("90° (R)"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::Y,
-                                quarter: Quarter::Cw,
-                            });
-                            ui.close_menu();
-                        }
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("-90° (Shift+R)"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::Y,
-                                quarter: Quarter::Ccw,
-                            });
-                            ui.close_menu();
-                        }
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("180°"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::Y,
-                                quarter: Quarter::Half,
-                            });
-                            ui.close_menu();
-                        }
-                    });
-                    ui.menu_button("Rotate around Z", |ui| {
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("90°"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::Z,
-                                quarter: Quarter::Cw,
-                            });
-                            ui.close_menu();
-                        }
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("-90°"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::Z,
-                                quarter: Quarter::Ccw,
-                            });
-                            ui.close_menu();
-                        }
-                        if ui
-                            .add_enabled(has_sel, egui::Button::new("180°"))
-                            .clicked()
-                        {
-                            self.state.request(UiAction::RotateSelection {
-                                axis: Axis::Z,
-                                quarter: Quarter::Half,
-                            });
-                            ui.close_menu();
-                        }
-                    });
-                    ui.separator();
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Flip X (M)"))
-                        .clicked()
-                    {
-                        self.state
-                            .request(UiAction::MirrorSelection { axis: Axis::X });
-                        ui.close_menu();
-                    }
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Flip Y"))
-                        .clicked()
-                    {
-                        self.state
-                            .request(UiAction::MirrorSelection { axis: Axis::Y });
-                        ui.close_menu();
-                    }
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Flip Z"))
-                        .clicked()
-                    {
-                        self.state
-                            .request(UiAction::MirrorSelection { axis: Axis::Z });
-                        ui.close_menu();
-                    }
+                    self.transform_menu_items(ui, has_sel);
                 });
 
                 ui.menu_button("View", |ui| {
                     ui.checkbox(&mut self.state.show_stats, "Statistics");
                     ui.checkbox(&mut self.state.show_tools, "Tools Panel");
                     ui.checkbox(&mut self.state.show_palette, "Color Palette");
+                    ui.checkbox(&mut self.state.show_layers, "Layers");
+                    ui.checkbox(&mut self.state.show_timeline, "Timeline");
+                    ui.checkbox(&mut self.state.show_rig, "Rig");
+                    ui.checkbox(&mut self.state.show_model_info, "Model Info");
+                    ui.checkbox(&mut self.state.show_history, "History");
+                    ui.checkbox(&mut self.state.show_slice_view, "Slice Editor");
+                    ui.checkbox(&mut self.state.show_materials, "Materials");
+                    ui.checkbox(&mut self.state.show_lights, "Lights");
+                    ui.checkbox(&mut self.state.show_references, "References");
                     ui.checkbox(&mut self.state.show_viewport_settings, "Viewport Settings");
                     ui.checkbox(&mut self.state.show_procgen, "Procedural Generation");
                     ui.checkbox(&mut self.state.show_graph, "Pipeline Graph");
@@ -706,46 +1329,84 @@ impl Ui {
                     ui.separator();
                     ui.checkbox(&mut self.viewport.show_grid, "Show Grid");
                     ui.checkbox(&mut self.viewport.show_axes, "Show Axes");
-                    ui.checkbox(&mut self.viewport.wireframe_mode, "Wireframe Mode");
+                    ui.checkbox(&mut self.viewport.wireframe_mode, "Wireframe Mode")
+                        .on_hover_text("Show the chunk meshes' triangles, e.g. to compare meshers");
+                    ui.add_enabled(
+                        self.viewport.wireframe_mode,
+                        egui::Checkbox::new(&mut self.viewport.wireframe_shaded, "Shaded + Edges"),
+                    )
+                    .on_hover_text("Keep the shaded voxels and draw the edges over them");
+                    ui.checkbox(&mut self.viewport.quad_view, "Quad View")
+                        .on_hover_text(
+                            "Top / Front / Side orthographic panes plus perspective; scroll zooms the pane under the cursor",
+                        );
+                    ui.checkbox(&mut self.viewport.path_trace, "Path Traced Preview")
+                        .on_hover_text(
+                            "Progressive path-traced render; refines while the camera is still",
+                        );
+                    ui.checkbox(&mut self.viewport.gi, "Global Illumination")
+                        .on_hover_text(
+                            "Approximate sky and emissive light bouncing onto nearby surfaces",
+                        );
+                    ui.checkbox(&mut self.viewport.toon, "Toon Shading")
+                        .on_hover_text("Banded lighting and ink outlines for an illustrated look");
                     ui.checkbox(&mut self.viewport.show_hud, "Viewport HUD");
                     ui.checkbox(&mut self.viewport.show_perf_hud, "Performance HUD");
+                    ui.separator();
+                    ui.menu_button("Panel Layout", |ui| self.dock_layout_menu(ui));
+                    if ui
+                        .button("Reset Layout")
+                        .on_hover_text("Float the panels where they first opened")
+                        .clicked()
+                    {
+                        self.reset_dock_layout();
+                        ui.close_menu();
+                    }
+                    ui.menu_button("Appearance", |ui| {
+                        self.appearance_controls(ui);
+                        if ui
+                            .button("Customize…")
+                            .on_hover_text("Custom theme colors, in Preferences")
+                            .clicked()
+                        {
+                            self.general.theme = Theme::Custom;
+                            self.state.show_preferences = true;
+                            ui.close_menu();
+                        }
+                    });
+                    ui.separator();
+                    self.action_item(ui, UiAction::FrameAll)
+                        .on_hover_text("Fit the whole model in view (F with no selection)");
+                    let target = CommandTarget::Action(UiAction::FrameSelected);
+                    self.command_item(ui, target, editor.selection.is_some())
+                        .on_hover_text("Fit the selection in view (F)");
+                    self.action_item(ui, UiAction::FrameGenerated);
                 });
 
                 ui.menu_button("Generate", |ui| {
-                    if ui.button("Test Cube").clicked() {
-                        self.state.request(UiAction::GenerateTestCube);
-                        ui.close_menu();
-                    }
-                    if ui.button("Ground Plane").clicked() {
-                        self.state.request(UiAction::GenerateGround);
-                        ui.close_menu();
-                    }
+                    self.action_item(ui, UiAction::GenerateTestCube);
+                    self.action_item(ui, UiAction::GenerateGround);
                     ui.separator();
-                    if ui.button("Sphere").clicked() {
-                        self.state.request(UiAction::GenerateSphere);
-                        ui.close_menu();
-                    }
-                    if ui.button("Pyramid").clicked() {
-                        self.state.request(UiAction::GeneratePyramid);
-                        ui.close_menu();
-                    }
+                    self.action_item(ui, UiAction::GenerateSphere);
+                    self.action_item(ui, UiAction::GeneratePyramid);
                     ui.separator();
-                    if ui.button("Procedural Terrain...").clicked() {
-                        self.state.show_procgen = true;
-                        ui.close_menu();
-                    }
+                    let target = CommandTarget::Open(UiWindow::Procgen);
+                    self.command_item_as(ui, target, "Procedural Terrain...", true);
+                    self.plugin_generator_items(ui);
+                });
+
+                ui.menu_button("Render", |ui| {
+                    self.window_item(ui, UiWindow::Screenshot);
+                    self.window_item(ui, UiWindow::Sprites);
+                    self.window_item(ui, UiWindow::Turntable);
+                    self.window_item(ui, UiWindow::CameraPath);
                 });
 
                 ui.menu_button("Help", |ui| {
-                    if ui.button("Keyboard Shortcuts").clicked() {
-                        self.state.show_help = true;
-                        ui.close_menu();
-                    }
+                    self.window_item(ui, UiWindow::Help);
+                    self.window_item(ui, UiWindow::Plugins);
                     ui.separator();
-                    if ui.button("About Voxelith").clicked() {
-                        self.state.show_about = true;
-                        ui.close_menu();
-                    }
+                    self.window_item(ui, UiWindow::About);
                 });
             });
         });
@@ -786,6 +1447,15 @@ impl Ui {
                     if tool_button(ui, Tool::Fill, editor.current_tool, "F", "Fill (5)") {
                         editor.current_tool = Tool::Fill;
                     }
+                    if tool_button(
+                        ui,
+                        Tool::Sculpt,
+                        editor.current_tool,
+                        "S",
+                        "Sculpt\nErode, dilate or roughen the surface under the brush.",
+                    ) {
+                        editor.current_tool = Tool::Sculpt;
+                    }
 
                     ui.add_space(8.0);
                     ui.separator();
@@ -804,6 +1474,16 @@ impl Ui {
                     if tool_button(ui, Tool::Cylinder, editor.current_tool, "⌭", "Cylinder (9)") {
                         editor.current_tool = Tool::Cylinder;
                     }
+                    if tool_button(
+                        ui,
+                        Tool::Extrude,
+                        editor.current_tool,
+                        "⇑",
+                        "Extrude\nClick a face to push its connected same-colored \
+                         surface out (or inset it) by the Tools panel depth.",
+                    ) {
+                        editor.current_tool = Tool::Extrude;
+                    }
 
                     ui.add_space(8.0);
                     ui.separator();
@@ -819,6 +1499,16 @@ impl Ui {
                     ) {
                         editor.current_tool = Tool::Select;
                     }
+                    if tool_button(
+                        ui,
+                        Tool::Wand,
+                        editor.current_tool,
+                        "✨",
+                        "Magic Wand\nClick a voxel to select the connected voxels of \
+                         its color. Shift-click adds to the pick.",
+                    ) {
+                        editor.current_tool = Tool::Wand;
+                    }
 
                     ui.add_space(8.0);
                     ui.separator();
@@ -835,6 +1525,16 @@ impl Ui {
                     ) {
                         editor.current_tool = Tool::Socket;
                     }
+                    if tool_button(
+                        ui,
+                        Tool::Measure,
+                        editor.current_tool,
+                        "📏",
+                        "Measure\nClick two voxels to read the offset and distance \
+                         between them in the status bar.",
+                    ) {
+                        editor.current_tool = Tool::Measure;
+                    }
 
                     ui.add_space(16.0);
                     ui.separator();
@@ -858,515 +1558,2271 @@ impl Ui {
             });
     }
 
-    fn show_stats_panel(&self, ctx: &Context, stats: &RenderStats, editor: &Editor) {
-        egui::Window::new("Statistics")
-            .default_pos([60.0, 40.0])
-            .resizable(false)
-            .collapsible(true)
-            .show(ctx, |ui| {
-                egui::Grid::new("stats_grid")
-                    .num_columns(2)
-                    .spacing([20.0, 4.0])
-                    .show(ui, |ui| {
-                        ui.label("FPS:");
-                        ui.label(format!("{:.1}", stats.fps));
-                        ui.end_row();
-
-                        ui.label("Frame time:");
-                        ui.label(format!("{:.2}ms", stats.frame_time_ms));
-                        ui.end_row();
-
-                        ui.label("Triangles:");
-                        ui.label(format!("{}", stats.triangles));
-                        ui.end_row();
-
-                        ui.label("Chunks:");
-                        ui.label(format!("{}", stats.chunks));
-                        ui.end_row();
-
-                        ui.label("History:");
-                        ui.label(format!("{} / {}", editor.history.undo_count(), editor.history.redo_count()));
-                        ui.end_row();
-                    });
+    fn stats_panel_contents(&self, ui: &mut egui::Ui, stats: &RenderStats, editor: &Editor) {
+        egui::Grid::new("stats_grid")
+            .num_columns(2)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("FPS:");
+                ui.label(format!("{:.1}", stats.fps));
+                ui.end_row();
+
+                ui.label("Frame time:");
+                ui.label(format!("{:.2}ms", stats.frame_time_ms));
+                ui.end_row();
+
+                ui.label("Triangles:");
+                ui.label(format!("{}", stats.triangles));
+                ui.end_row();
+
+                ui.label("Chunks:");
+                ui.label(format!("{}", stats.chunks));
+                ui.end_row();
+
+                ui.label("Draw calls:");
+                ui.label(format!("{}", stats.draw_calls));
+                ui.end_row();
+
+                ui.label("History:");
+                ui.label(format!("{} / {}", editor.history.undo_count(), editor.history.redo_count()));
+                ui.end_row();
+            });
 
-                ui.separator();
+        ui.separator();
 
-                ui.label(format!(
-                    "Camera: ({:.1}, {:.1}, {:.1})",
-                    stats.camera_pos.0, stats.camera_pos.1, stats.camera_pos.2
-                ));
-            });
+        ui.label(format!(
+            "Camera: ({:.1}, {:.1}, {:.1})",
+            stats.camera_pos.0, stats.camera_pos.1, stats.camera_pos.2
+        ));
     }
 
-    fn show_tools_panel(&mut self, ctx: &Context, editor: &mut Editor) {
-        egui::Window::new("Tools")
-            .default_pos([60.0, 200.0])
-            .resizable(true)
-            .collapsible(true)
-            .show(ctx, |ui| {
-                // Tool selection — split into Brush (cell-by-cell) and
-                // Shape (click-anchor / drag / release) groups so the
-                // distinct interaction model is visually clear.
-                ui.heading("Brush");
-                egui::Grid::new("brush_tool_grid")
-                    .num_columns(3)
-                    .spacing([4.0, 4.0])
-                    .show(ui, |ui| {
-                        if ui.selectable_label(editor.current_tool == Tool::Place, "Place").clicked() {
-                            editor.current_tool = Tool::Place;
-                        }
-                        if ui.selectable_label(editor.current_tool == Tool::Remove, "Remove").clicked() {
-                            editor.current_tool = Tool::Remove;
-                        }
-                        if ui.selectable_label(editor.current_tool == Tool::Paint, "Paint").clicked() {
-                            editor.current_tool = Tool::Paint;
-                        }
-                        ui.end_row();
-
-                        if ui.selectable_label(editor.current_tool == Tool::Eyedropper, "Pick").clicked() {
-                            editor.current_tool = Tool::Eyedropper;
-                        }
-                        if ui.selectable_label(editor.current_tool == Tool::Fill, "Fill").clicked() {
-                            editor.current_tool = Tool::Fill;
-                        }
-                        ui.end_row();
-                    });
+    fn tools_panel_contents(&mut self, ui: &mut egui::Ui, editor: &mut Editor) {
+        // Tool selection — split into Brush (cell-by-cell) and
+        // Shape (click-anchor / drag / release) groups so the
+        // distinct interaction model is visually clear.
+        ui.heading("Brush");
+        egui::Grid::new("brush_tool_grid")
+            .num_columns(3)
+            .spacing([4.0, 4.0])
+            .show(ui, |ui| {
+                if ui.selectable_label(editor.current_tool == Tool::Place, "Place").clicked() {
+                    editor.current_tool = Tool::Place;
+                }
+                if ui.selectable_label(editor.current_tool == Tool::Remove, "Remove").clicked() {
+                    editor.current_tool = Tool::Remove;
+                }
+                if ui.selectable_label(editor.current_tool == Tool::Paint, "Paint").clicked() {
+                    editor.current_tool = Tool::Paint;
+                }
+                ui.end_row();
 
-                ui.add_space(4.0);
-                ui.heading("Shape");
-                egui::Grid::new("shape_tool_grid")
-                    .num_columns(3)
-                    .spacing([4.0, 4.0])
-                    .show(ui, |ui| {
-                        if ui
-                            .selectable_label(editor.current_tool == Tool::Line, "Line")
-                            .on_hover_text("Drag from anchor to end (3D Bresenham line)")
-                            .clicked()
-                        {
-                            editor.current_tool = Tool::Line;
-                        }
-                        if ui
-                            .selectable_label(editor.current_tool == Tool::Box, "Box")
-                            .on_hover_text("Drag corner to corner (filled AABB)")
-                            .clicked()
-                        {
-                            editor.current_tool = Tool::Box;
-                        }
-                        if ui
-                            .selectable_label(editor.current_tool == Tool::Sphere, "Sphere")
-                            .on_hover_text("Drag bbox; ellipsoid fits in it")
-                            .clicked()
-                        {
-                            editor.current_tool = Tool::Sphere;
-                        }
-                        ui.end_row();
+                if ui.selectable_label(editor.current_tool == Tool::Eyedropper, "Pick").clicked() {
+                    editor.current_tool = Tool::Eyedropper;
+                }
+                if ui.selectable_label(editor.current_tool == Tool::Fill, "Fill").clicked() {
+                    editor.current_tool = Tool::Fill;
+                }
+                if ui.selectable_label(editor.current_tool == Tool::Sculpt, "Sculpt").clicked() {
+                    editor.current_tool = Tool::Sculpt;
+                }
+                ui.end_row();
+            });
 
-                        if ui
-                            .selectable_label(editor.current_tool == Tool::Cylinder, "Cylinder")
-                            .on_hover_text(
-                                "Drag bbox; cylinder axis runs along the longest dimension",
-                            )
-                            .clicked()
-                        {
-                            editor.current_tool = Tool::Cylinder;
-                        }
-                        ui.end_row();
-                    });
+        ui.add_space(4.0);
+        ui.heading("Shape");
+        egui::Grid::new("shape_tool_grid")
+            .num_columns(3)
+            .spacing([4.0, 4.0])
+            .show(ui, |ui| {
+                if ui
+                    .selectable_label(editor.current_tool == Tool::Line, "Line")
+                    .on_hover_text("Drag from anchor to end (3D Bresenham line)")
+                    .clicked()
+                {
+                    editor.current_tool = Tool::Line;
+                }
+                if ui
+                    .selectable_label(editor.current_tool == Tool::Box, "Box")
+                    .on_hover_text("Drag corner to corner (filled or hollow AABB)")
+                    .clicked()
+                {
+                    editor.current_tool = Tool::Box;
+                }
+                if ui
+                    .selectable_label(editor.current_tool == Tool::Sphere, "Sphere")
+                    .on_hover_text("Drag bbox; ellipsoid fits in it")
+                    .clicked()
+                {
+                    editor.current_tool = Tool::Sphere;
+                }
+                ui.end_row();
 
-                ui.add_space(4.0);
-                ui.heading("Selection");
                 if ui
-                    .selectable_label(editor.current_tool == Tool::Select, "Box Select")
+                    .selectable_label(editor.current_tool == Tool::Cylinder, "Cylinder")
                     .on_hover_text(
-                        "Drag corner-to-corner to mark an AABB region for batch \
-                         operations. Esc or Ctrl+D deselects.",
+                        "Drag bbox; cylinder axis runs along the longest dimension",
                     )
                     .clicked()
                 {
-                    editor.current_tool = Tool::Select;
+                    editor.current_tool = Tool::Cylinder;
                 }
-                if let Some(sel) = editor.selection {
-                    let (w, h, d) = sel.size();
-                    ui.label(
-                        egui::RichText::new(format!(
-                            "Active: {}×{}×{} ({} cells)",
-                            w,
-                            h,
-                            d,
-                            sel.cell_count()
-                        ))
-                        .small()
-                        .weak(),
+                ui.end_row();
+            });
+        if editor.current_tool == Tool::Box {
+            ui.horizontal(|ui| {
+                ui.label("Box:");
+                ui.radio_value(&mut editor.box_hollow, false, "Filled")
+                    .on_hover_text("Fill the whole dragged AABB");
+                ui.radio_value(&mut editor.box_hollow, true, "Hollow").on_hover_text(
+                    "Only the six faces — a one-voxel shell with an empty interior",
+                );
+            });
+        }
+        if ui
+            .selectable_label(editor.current_tool == Tool::Extrude, "Extrude Face")
+            .on_hover_text(
+                "Click a face to push the connected, coplanar, same-colored \
+                 surface around it outward — or carve it inward — by the \
+                 depth below. The ghost shows the result before you click.",
+            )
+            .clicked()
+        {
+            editor.current_tool = Tool::Extrude;
+        }
+        if editor.current_tool == Tool::Extrude {
+            ui.horizontal(|ui| {
+                ui.label("Extrude:");
+                ui.radio_value(&mut editor.extrude_direction, ExtrudeDirection::Out, "Out")
+                    .on_hover_text("Stack copies of the face voxels on top of it");
+                ui.radio_value(&mut editor.extrude_direction, ExtrudeDirection::In, "Inset")
+                    .on_hover_text(
+                        "Carve into the model under the face, stopping at any \
+                         hollow behind it",
                     );
-                }
-                let has_sel = editor.selection.is_some();
-                ui.horizontal(|ui| {
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Copy"))
-                        .on_hover_text("Ctrl+C — copy non-air voxels into the clipboard")
-                        .clicked()
-                    {
-                        self.state.request(UiAction::CopySelection);
-                    }
+            });
+            ui.add(
+                egui::Slider::new(&mut editor.extrude_depth, 1..=MAX_EXTRUDE_DEPTH)
+                    .text("Depth"),
+            );
+        }
+
+        ui.add_space(4.0);
+        ui.heading("Selection");
+        if ui
+            .selectable_label(editor.current_tool == Tool::Select, "Box Select")
+            .on_hover_text(
+                "Drag corner-to-corner to mark an AABB region for batch \
+                 operations. Esc or Ctrl+D deselects.",
+            )
+            .clicked()
+        {
+            editor.current_tool = Tool::Select;
+        }
+        if ui
+            .selectable_label(editor.current_tool == Tool::Wand, "Magic Wand")
+            .on_hover_text(
+                "Click a voxel to select every voxel connected to it within \
+                 the color tolerance. Shift-click adds to the pick. Copy, \
+                 cut, delete, move, rotate, flip and the color tools then \
+                 act on just those voxels.",
+            )
+            .clicked()
+        {
+            editor.current_tool = Tool::Wand;
+        }
+        if editor.current_tool == Tool::Wand {
+            ui.add(
+                egui::Slider::new(&mut editor.wand_tolerance, 0..=128)
+                    .text("Tolerance"),
+            )
+            .on_hover_text(
+                "Largest difference on any RGBA channel from the clicked \
+                 voxel's color that still counts as a match",
+            );
+        }
+        if let Some(sel) = editor.selection {
+            let (w, h, d) = sel.size();
+            let cells = match editor.active_mask() {
+                Some(mask) => format!("{} voxels picked", mask.len()),
+                None => format!("{} cells", sel.cell_count()),
+            };
+            ui.label(
+                egui::RichText::new(format!("Active: {}×{}×{} ({})", w, h, d, cells))
+                .small()
+                .weak(),
+            );
+            if let Some(c) = self.selection_counts {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} solid voxels · {}",
+                        c.solid,
+                        colors_label(c.colors)
+                    ))
+                    .small()
+                    .weak(),
+                );
+            }
+        }
+        let has_sel = editor.selection.is_some();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(has_sel, egui::Button::new("Copy"))
+                .on_hover_text("Ctrl+C — copy non-air voxels into the clipboard")
+                .clicked()
+            {
+                self.state.request(UiAction::CopySelection);
+            }
+            if ui
+                .add_enabled(has_sel, egui::Button::new("Cut"))
+                .on_hover_text("Ctrl+X — copy then clear in one undoable Command")
+                .clicked()
+            {
+                self.state.request(UiAction::CutSelection);
+            }
+            let can_paste = self.has_clipboard;
+            if ui
+                .add_enabled(can_paste, egui::Button::new("Paste"))
+                .on_hover_text(
+                    "Ctrl+V — paste at selection origin (or cursor cell if no \
+                     selection). Ctrl+Shift+V always pastes at cursor.",
+                )
+                .clicked()
+            {
+                self.state.request(UiAction::PasteClipboard);
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(has_sel, egui::Button::new("Delete"))
+                .on_hover_text("Del — clear non-air voxels inside the selection")
+                .clicked()
+            {
+                self.state.request(UiAction::DeleteSelection);
+            }
+            if ui
+                .button("Select All")
+                .on_hover_text("Ctrl+A — select the AABB of every non-air voxel")
+                .clicked()
+            {
+                self.state.request(UiAction::SelectAllSolid);
+            }
+            if ui
+                .add_enabled(has_sel, egui::Button::new("Deselect"))
+                .on_hover_text("Esc / Ctrl+D — clear the active selection")
+                .clicked()
+            {
+                editor.set_selection(None);
+            }
+        });
+
+        ui.add_space(4.0);
+        ui.heading("Sockets");
+        if ui
+            .selectable_label(editor.current_tool == Tool::Socket, "Place Socket")
+            .on_hover_text(
+                "Click a voxel face (or the ground) to drop a named \
+                 attachment point. Exports to glTF as an empty node \
+                 (name + position + orientation).",
+            )
+            .clicked()
+        {
+            editor.current_tool = Tool::Socket;
+        }
+        if editor.sockets.is_empty() {
+            ui.label(egui::RichText::new("No sockets yet.").small().weak());
+        } else {
+            // Per-socket row: inline rename + delete + position
+            // readout. Names become glTF node names on export.
+            let mut to_delete: Option<usize> = None;
+            egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+                for (i, s) in editor.sockets.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut s.name)
+                                .desired_width(110.0),
+                        )
+                        .on_hover_text("Name (becomes the glTF node name)");
+                        if ui
+                            .small_button("✕")
+                            .on_hover_text("Delete this socket")
+                            .clicked()
+                        {
+                            to_delete = Some(i);
+                        }
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "({:.1}, {:.1}, {:.1})",
+                                s.position[0], s.position[1], s.position[2]
+                            ))
+                            .small()
+                            .weak(),
+                        );
+                    });
+                }
+            });
+            if let Some(i) = to_delete {
+                editor.sockets.remove(i);
+            }
+            if ui
+                .button("Clear all sockets")
+                .on_hover_text("Remove every socket from the scene")
+                .clicked()
+            {
+                editor.sockets.clear();
+            }
+        }
+
+        ui.add_space(4.0);
+        ui.heading("Measure");
+        if ui
+            .selectable_label(editor.current_tool == Tool::Measure, "Ruler")
+            .on_hover_text(
+                "Click two voxels to read the offset, straight-line distance \
+                 and cell span between them. A third click starts over; Esc \
+                 clears the ruler.",
+            )
+            .clicked()
+        {
+            editor.current_tool = Tool::Measure;
+        }
+        match editor.measurement {
+            Some(Measurement { start, end: Some(end) }) => {
+                ui.label(
+                    egui::RichText::new(Measurement::label(start, end))
+                        .color(hud::MEASURE_COLOR),
+                );
+                if ui.button("Clear").clicked() {
+                    editor.measurement = None;
+                }
+            }
+            Some(m) => {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "From ({}, {}, {}) — click the end voxel",
+                        m.start.0, m.start.1, m.start.2
+                    ))
+                    .small()
+                    .weak(),
+                );
+            }
+            None => {}
+        }
+        ui.checkbox(&mut self.viewport.show_measurement, "Keep on screen")
+            .on_hover_text("Leave the ruler and its label up when you switch tools");
+
+        ui.separator();
+
+        // Brush size
+        ui.heading("Brush Size");
+        let mut size = editor.brush_size as u32;
+        ui.add(egui::Slider::new(&mut size, 1..=10).show_value(true));
+        editor.brush_size = size as u8;
+        if brush_shape_ui(ui, &mut editor.brush) {
+            self.state.request(UiAction::LoadBrushStamp);
+        }
+
+        ui.separator();
+
+        // Per-cell color pattern for Place / Paint
+        ui.heading("Paint Mode");
+        paint_mode_ui(ui, &mut editor.paint);
+
+        ui.separator();
+
+        // Sculpt tool mode and strength
+        ui.heading("Sculpt");
+        ui.horizontal(|ui| {
+            for mode in SculptMode::ALL {
+                ui.selectable_value(&mut editor.sculpt.mode, mode, mode.label());
+            }
+        })
+        .response
+        .on_hover_text(
+            "Erode removes random surface voxels, Dilate grows the surface \
+             outward, Roughen pushes it in and out along a noise pattern",
+        );
+        ui.add(
+            egui::Slider::new(&mut editor.sculpt.strength, 1..=100)
+                .text("Strength")
+                .suffix("%"),
+        );
+
+        ui.separator();
+
+        // Fill tool region and connectivity
+        ui.heading("Fill");
+        ui.horizontal(|ui| {
+            ui.label("Region");
+            for region in FillRegion::ALL {
+                ui.selectable_value(&mut editor.fill.region, region, region.label());
+            }
+        })
+        .response
+        .on_hover_text(
+            "Volume fills everything connected; Slice keeps to the clicked \
+             layer; Surface keeps to the voxels facing the way the clicked \
+             face does",
+        );
+        ui.horizontal(|ui| {
+            ui.label("Across");
+            for connectivity in FillConnectivity::ALL {
+                ui.selectable_value(
+                    &mut editor.fill.connectivity,
+                    connectivity,
+                    connectivity.label(),
+                );
+            }
+        })
+        .response
+        .on_hover_text(
+            "Which neighbors a fill spreads to: shared faces only, or also \
+             edges (lets a surface fill climb steps) or corners",
+        );
+
+        ui.separator();
+
+        // Symmetry
+        ui.heading("Symmetry");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut editor.symmetry.x, "X")
+                .on_hover_text("Mirror brush across the X plane");
+            ui.checkbox(&mut editor.symmetry.y, "Y")
+                .on_hover_text("Mirror brush across the Y plane");
+            ui.checkbox(&mut editor.symmetry.z, "Z")
+                .on_hover_text("Mirror brush across the Z plane");
+        });
+        if editor.symmetry.any() {
+            ui.horizontal(|ui| {
+                ui.label("Center");
+                let center = &mut editor.symmetry.center;
+                ui.add(egui::DragValue::new(&mut center.0).prefix("x "));
+                ui.add(egui::DragValue::new(&mut center.1).prefix("y "));
+                ui.add(egui::DragValue::new(&mut center.2).prefix("z "));
+                if ui
+                    .small_button("Origin")
+                    .on_hover_text("Put the planes back through the world origin")
+                    .clicked()
+                {
+                    *center = (0, 0, 0);
+                }
+            });
+        }
+        ui.label(
+            egui::RichText::new(
+                "Mirrors Place / Remove / Paint / Fill across enabled \
+                 planes through the center; drag them with the move \
+                 gizmo. Eyedropper is exempt.",
+            )
+            .small()
+            .weak(),
+        );
+
+        ui.separator();
+
+        // Work plane (slice editing)
+        ui.heading("Work Plane");
+        ui.checkbox(&mut editor.work_plane_enabled, "Edit one slice only")
+            .on_hover_text(
+                "Restrict every edit to one axis-aligned layer of cells. \
+                 Brush and shape tools aim at the layer itself, so you \
+                 can draw into it through whatever is in front.",
+            );
+        ui.add_enabled_ui(editor.work_plane_enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut editor.work_plane.axis, Axis::X, "X");
+                ui.radio_value(&mut editor.work_plane.axis, Axis::Y, "Y");
+                ui.radio_value(&mut editor.work_plane.axis, Axis::Z, "Z");
+                ui.label("=");
+                ui.add(egui::DragValue::new(&mut editor.work_plane.coord).speed(0.1))
+                    .on_hover_text("PageUp / PageDown step it (Shift: by 10)");
+            });
+            ui.checkbox(&mut editor.work_plane_dim, "Dim other slices")
+                .on_hover_text("Fade voxels off the work plane so the layer stands out");
+        });
+
+        ui.separator();
+
+        // Color
+        ui.heading("Color");
+        let mut color = [
+            editor.brush_color.r as f32 / 255.0,
+            editor.brush_color.g as f32 / 255.0,
+            editor.brush_color.b as f32 / 255.0,
+            editor.brush_color.a as f32 / 255.0,
+        ];
+        if ui.color_edit_button_rgba_unmultiplied(&mut color).changed() {
+            // Only RGBA changes; keep the material flags
+            // (emissive / metallic) so a color pick doesn't reset
+            // what behaves like a brush mode.
+            editor.brush_color.r = (color[0] * 255.0) as u8;
+            editor.brush_color.g = (color[1] * 255.0) as u8;
+            editor.brush_color.b = (color[2] * 255.0) as u8;
+            editor.brush_color.a = (color[3] * 255.0).round() as u8;
+        }
+        // Alpha below 255 paints translucent voxels (glass,
+        // water), drawn in the alpha-blended pass.
+        let mut alpha = editor.brush_color.a;
+        if ui
+            .add(egui::Slider::new(&mut alpha, 0..=255).text("Alpha"))
+            .on_hover_text("Below 255 paints translucent voxels (glass, water)")
+            .changed()
+        {
+            editor.brush_color.a = alpha;
+        }
+
+        // RGBA values
+        ui.horizontal(|ui| {
+            ui.label("RGBA:");
+            ui.label(format!(
+                "{}, {}, {}, {}",
+                editor.brush_color.r,
+                editor.brush_color.g,
+                editor.brush_color.b,
+                editor.brush_color.a
+            ));
+        });
+
+        ui.separator();
+
+        // The brush's material id. Its properties (and the
+        // emissive / metallic flags the app stamps from them,
+        // carried into GLB export) are edited in the Materials
+        // window. A brush mode, like symmetry — picking a color
+        // preserves it.
+        ui.heading("Material");
+        ui.horizontal(|ui| {
+            let mut material = editor.brush_color.material;
+            egui::ComboBox::from_id_salt("brush_material")
+                .selected_text(editor.materials.resolve(material).name.clone())
+                .show_ui(ui, |ui| {
+                    for (id, def) in editor.materials.iter() {
+                        ui.selectable_value(&mut material, id, &def.name);
+                    }
+                });
+            if material != editor.brush_color.material {
+                editor.brush_color.material = material;
+            }
+            if ui
+                .button("Edit…")
+                .on_hover_text("Open the Materials window")
+                .clicked()
+            {
+                self.state.show_materials = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Tint zone");
+            let mut zone = editor.brush_color.tint_zone();
+            let before = zone;
+            let label = match zone {
+                1 => "Primary",
+                2 => "Secondary",
+                3 => "Reserved",
+                _ => "None",
+            };
+            egui::ComboBox::from_id_salt("brush_tint_zone")
+                .selected_text(label)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut zone, 0, "None");
+                    ui.selectable_value(&mut zone, 1, "Primary");
+                    ui.selectable_value(&mut zone, 2, "Secondary");
+                    ui.selectable_value(&mut zone, 3, "Reserved");
+                });
+            if zone != before {
+                editor.brush_color.set_tint_zone(zone);
+            }
+        })
+        .response
+        .on_hover_text(
+            "Faction recolor zone — exported per-vertex as _TINTZONE \
+             for a downstream uber-shader (does not change the editor view)",
+        );
+
+        // Show hovered voxel info
+        if let Some(hit) = &editor.hovered_voxel {
+            ui.separator();
+            ui.heading("Hovered");
+            ui.label(format!("Position: ({}, {}, {})", hit.voxel_pos.0, hit.voxel_pos.1, hit.voxel_pos.2));
+            ui.label(format!("Face: ({}, {}, {})", hit.normal.0, hit.normal.1, hit.normal.2));
+        }
+    }
+
+    /// Palette swatches plus editing: click picks, drag a swatch onto
+    /// another to reorder, right-click removes. Import / Export go
+    /// through `UiAction`s since the file dialogs live in `App`.
+    fn palette_panel_contents(&mut self, ui: &mut egui::Ui, editor: &mut Editor) {
+        let cols = 5;
+        let mut pick = None;
+        let mut remove = None;
+        let mut reorder = None;
+
+        egui::ScrollArea::vertical()
+            .max_height(240.0)
+            .show(ui, |ui| {
+                egui::Grid::new("palette_grid")
+                    .spacing([4.0, 4.0])
+                    .show(ui, |ui| {
+                        for (i, voxel) in editor.palette.iter().enumerate() {
+                            let color = egui::Color32::from_rgb(voxel.r, voxel.g, voxel.b);
+                            let is_selected = editor.brush_color.r == voxel.r
+                                && editor.brush_color.g == voxel.g
+                                && editor.brush_color.b == voxel.b;
+
+                            let size = if is_selected { 24.0 } else { 20.0 };
+                            let (rect, response) = ui.allocate_exact_size(
+                                egui::vec2(size, size),
+                                egui::Sense::click_and_drag(),
+                            );
+                            response.dnd_set_drag_payload(i);
+                            if let Some(from) = response.dnd_release_payload::<usize>() {
+                                reorder = Some((*from, i));
+                            }
+
+                            if response.clicked() {
+                                pick = Some(i);
+                            }
+                            let response = response.on_hover_text(format!(
+                                "#{:02X}{:02X}{:02X} — drag to reorder, right-click to remove",
+                                voxel.r, voxel.g, voxel.b
+                            ));
+                            response.context_menu(|ui| {
+                                if ui.button("Remove").clicked() {
+                                    remove = Some(i);
+                                    ui.close_menu();
+                                }
+                            });
+
+                            ui.painter().rect_filled(rect, 2.0, color);
+                            if is_selected {
+                                ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
+                            } else if response.dnd_hover_payload::<usize>().is_some() {
+                                ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE));
+                            }
+
+                            if (i + 1) % cols == 0 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+            });
+
+        // Applied after the grid so the palette isn't mutated
+        // while it's being iterated.
+        if let Some(i) = pick {
+            // Keep the brush's material flags; only the color
+            // changes (see `set_palette_color`).
+            editor.set_palette_color(i);
+        }
+        if let Some((from, to)) = reorder {
+            editor.move_palette_color(from, to);
+        }
+        if let Some(i) = remove {
+            editor.remove_palette_color(i);
+        }
+
+        ui.separator();
+
+        let selected = editor.palette.iter().position(|v| {
+            v.r == editor.brush_color.r
+                && v.g == editor.brush_color.g
+                && v.b == editor.brush_color.b
+        });
+        ui.horizontal(|ui| {
+            let full = editor.palette.len() >= MAX_PALETTE_COLORS;
+            if ui
+                .add_enabled(selected.is_none() && !full, egui::Button::new("Add"))
+                .on_hover_text("Add the brush color to the palette")
+                .clicked()
+            {
+                editor.add_palette_color(editor.brush_color);
+            }
+            if ui
+                .add_enabled(selected.is_some(), egui::Button::new("Remove"))
+                .on_hover_text("Remove the brush color from the palette")
+                .clicked()
+            {
+                if let Some(i) = selected {
+                    editor.remove_palette_color(i);
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .button("Import…")
+                .on_hover_text("Replace the palette from .gpl / .pal / .vox / .png")
+                .clicked()
+            {
+                self.state.request(UiAction::ImportPalette);
+            }
+            if ui
+                .button("Export…")
+                .on_hover_text("Save the palette as .gpl / .pal")
+                .clicked()
+            {
+                self.state.request(UiAction::ExportPalette);
+            }
+        });
+    }
+
+    /// Layer stack, drawn top layer first like an image editor. Every
+    /// change is queued as a `UiAction`; the panel itself only owns the
+    /// rename buffer.
+    fn show_screenshot_panel(&mut self, ctx: &Context) {
+        // Deferred-action pattern: `.open(...)` holds
+        // `self.state.show_screenshot`.
+        let mut save = false;
+        let settings = &mut self.screenshot;
+        egui::Window::new("Screenshot")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_screenshot)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Size");
+                    ui.add(egui::DragValue::new(&mut settings.width).range(16..=8192).suffix(" px"));
+                    ui.label("×");
+                    ui.add(egui::DragValue::new(&mut settings.height).range(16..=8192).suffix(" px"));
+                });
+                ui.horizontal(|ui| {
+                    for (label, w, h) in [("1080p", 1920, 1080), ("1440p", 2560, 1440), ("4K", 3840, 2160)] {
+                        if ui.button(label).clicked() {
+                            settings.width = w;
+                            settings.height = h;
+                        }
+                    }
+                });
+                egui::ComboBox::from_label("Supersampling")
+                    .selected_text(format!("{}×", settings.supersample))
+                    .show_ui(ui, |ui| {
+                        for factor in 1..=4 {
+                            ui.selectable_value(&mut settings.supersample, factor, format!("{}×", factor));
+                        }
+                    })
+                    .response
+                    .on_hover_text("Render larger and scale down for smoother edges; limited by the GPU's maximum texture size");
+                ui.checkbox(&mut settings.transparent, "Transparent background")
+                    .on_hover_text("Leave out the background, grid and axes, e.g. for compositing");
+                if ui.button("Save PNG...").clicked() {
+                    save = true;
+                }
+            });
+        if save {
+            self.state.request(UiAction::SaveScreenshot);
+        }
+    }
+
+    /// Render ▸ Sprite Sheet: pixel-art sprites at an exact voxel
+    /// scale, from one or several directions.
+    fn show_sprites_panel(&mut self, ctx: &Context) {
+        let mut export = false;
+        let settings = &mut self.sprites;
+        egui::Window::new("Sprite Sheet")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_sprites)
+            .show(ctx, |ui| {
+                egui::ComboBox::from_label("View")
+                    .selected_text(settings.view.label())
+                    .show_ui(ui, |ui| {
+                        for view in SpriteView::ALL {
+                            ui.selectable_value(&mut settings.view, view, view.label());
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.label("Directions");
+                    for directions in SpriteDirections::ALL {
+                        let label = directions.count().to_string();
+                        ui.selectable_value(&mut settings.directions, directions, label);
+                    }
+                })
+                .response
+                .on_hover_text("Frames turned evenly around the vertical axis, starting from the front");
+                ui.horizontal(|ui| {
+                    ui.label("Scale");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.pixels_per_voxel)
+                            .range(1..=16)
+                            .suffix(" px / voxel"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut settings.outline, "Outline");
+                    ui.add_enabled_ui(settings.outline, |ui| {
+                        ui.color_edit_button_srgba_unmultiplied(&mut settings.outline_color);
+                    });
+                });
+                ui.checkbox(&mut settings.sheet, "Single sheet")
+                    .on_hover_text("Save all frames side by side in one PNG instead of one PNG per direction");
+                ui.label(
+                    egui::RichText::new("Orthographic, no anti-aliasing, transparent background")
+                        .weak()
+                        .small(),
+                );
+                let label = if settings.sheet { "Save Sheet..." } else { "Save Frames..." };
+                if ui.button(label).clicked() {
+                    export = true;
+                }
+            });
+        if export {
+            self.state.request(UiAction::ExportSprites);
+        }
+    }
+
+    /// View ▸ Lights: add, pick and edit the project's point and spot
+    /// lights. New lights and "From View" need the camera, so those go
+    /// through the App.
+    fn show_lights_panel(&mut self, ctx: &Context) {
+        let mut actions = Vec::new();
+        let lights = &mut self.lights;
+        let selected = &mut self.selected_light;
+        egui::Window::new("Lights")
+            .default_width(280.0)
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.state.show_lights)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for kind in LightKind::ALL {
+                        if ui
+                            .button(format!("Add {}", kind.label()))
+                            .on_hover_text("Place a new light above the point the camera orbits")
+                            .clicked()
+                        {
+                            actions.push(UiAction::AddLight(kind));
+                        }
+                    }
+                });
+                if lights.len() > MAX_LIGHTS {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Only the first {} switched-on lights shine",
+                            MAX_LIGHTS
+                        ))
+                        .weak()
+                        .small(),
+                    );
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for (i, light) in lights.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut light.enabled, "")
+                                    .on_hover_text("Switch the light on or off");
+                                let label = format!("{}  ({})", light.name, light.kind.label());
+                                if ui.selectable_label(*selected == Some(i), label).clicked() {
+                                    *selected = Some(i);
+                                }
+                            });
+                        }
+                        if lights.is_empty() {
+                            ui.label(egui::RichText::new("No lights").weak());
+                        }
+                    });
+
+                let Some(index) = selected.filter(|&i| i < lights.len()) else {
+                    return;
+                };
+                ui.separator();
+                ui.horizontal(|ui| {
                     if ui
-                        .add_enabled(has_sel, egui::Button::new("Cut"))
-                        .on_hover_text("Ctrl+X — copy then clear in one undoable Command")
+                        .button("From View")
+                        .on_hover_text("Move the light to the camera, pointing where it looks")
                         .clicked()
                     {
-                        self.state.request(UiAction::CutSelection);
+                        actions.push(UiAction::LightFromView);
                     }
-                    let can_paste = self.has_clipboard;
+                    if ui.button("Duplicate").clicked() {
+                        let copy = lights[index].clone();
+                        lights.insert(index + 1, copy);
+                        *selected = Some(index + 1);
+                    }
+                    if ui.button("Delete").clicked() {
+                        lights.remove(index);
+                        *selected = None;
+                    }
+                });
+                let Some(light) = selected.and_then(|i| lights.get_mut(i)) else {
+                    return;
+                };
+                egui::Grid::new("light_props")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Name");
+                        ui.text_edit_singleline(&mut light.name);
+                        ui.end_row();
+                        ui.label("Kind");
+                        ui.horizontal(|ui| {
+                            for kind in LightKind::ALL {
+                                ui.selectable_value(&mut light.kind, kind, kind.label());
+                            }
+                        });
+                        ui.end_row();
+                        ui.label("Position");
+                        ui.horizontal(|ui| {
+                            for v in &mut light.position {
+                                ui.add(egui::DragValue::new(v).speed(0.1));
+                            }
+                        });
+                        ui.end_row();
+                        if light.kind == LightKind::Spot {
+                            ui.label("Direction");
+                            ui.horizontal(|ui| {
+                                for v in &mut light.direction {
+                                    ui.add(egui::DragValue::new(v).speed(0.01).range(-1.0..=1.0));
+                                }
+                            });
+                            ui.end_row();
+                        }
+                        ui.label("Color");
+                        ui.color_edit_button_rgb(&mut light.color);
+                        ui.end_row();
+                        ui.label("Intensity");
+                        ui.add(egui::Slider::new(&mut light.intensity, 0.0..=4.0));
+                        ui.end_row();
+                        ui.label("Range");
+                        ui.add(
+                            egui::Slider::new(&mut light.range, 1.0..=256.0)
+                                .logarithmic(true)
+                                .suffix(" voxels"),
+                        )
+                        .on_hover_text("Distance at which the light has faded out");
+                        ui.end_row();
+                        if light.kind == LightKind::Spot {
+                            ui.label("Cone");
+                            ui.add(egui::Slider::new(&mut light.spot_angle, 1.0..=89.0).suffix("°"))
+                                .on_hover_text("Half-angle from the direction to the cone's rim");
+                            ui.end_row();
+                            ui.label("Softness");
+                            ui.add(egui::Slider::new(&mut light.spot_softness, 0.0..=1.0))
+                                .on_hover_text("How much of the cone fades out toward its rim");
+                            ui.end_row();
+                        }
+                    });
+            });
+        for action in actions {
+            self.state.request(action);
+        }
+    }
+
+    /// View ▸ References: concept art as see-through planes, and a
+    /// model shown as a ghost. Adding either asks for a file, so that
+    /// goes through the App.
+    fn show_references_panel(&mut self, ctx: &Context) {
+        let mut actions = Vec::new();
+        let references = &mut self.references;
+        let selected = &mut self.selected_reference;
+        let ghost = &mut self.reference_model;
+        egui::Window::new("References")
+            .default_width(280.0)
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.state.show_references)
+            .show(ctx, |ui| {
+                ui.heading("Images");
+                if ui
+                    .button("Add Image…")
+                    .on_hover_text("Load an image to model against, standing at the origin")
+                    .clicked()
+                {
+                    actions.push(UiAction::AddReferenceImage);
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for (i, reference) in references.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut reference.visible, "")
+                                    .on_hover_text("Show or hide the plane");
+                                let label =
+                                    format!("{}  ({})", reference.name(), reference.view.label());
+                                if ui.selectable_label(*selected == Some(i), label).clicked() {
+                                    *selected = Some(i);
+                                }
+                            });
+                        }
+                        if references.is_empty() {
+                            ui.label(egui::RichText::new("No reference images").weak());
+                        }
+                    });
+                if let Some(index) = selected.filter(|&i| i < references.len()) {
+                    ui.horizontal(|ui| {
+                        if ui.button("Duplicate").clicked() {
+                            let copy = references[index].clone();
+                            references.insert(index + 1, copy);
+                            *selected = Some(index + 1);
+                        }
+                        if ui.button("Remove").clicked() {
+                            references.remove(index);
+                            *selected = None;
+                        }
+                    });
+                }
+                if let Some(reference) = selected.and_then(|i| references.get_mut(i)) {
+                    egui::Grid::new("reference_props")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("View");
+                            ui.horizontal(|ui| {
+                                for view in ReferenceView::ALL {
+                                    ui.selectable_value(&mut reference.view, view, view.label());
+                                }
+                            })
+                            .response
+                            .on_hover_text("Which camera view the plane faces");
+                            ui.end_row();
+                            ui.label("Opacity");
+                            ui.add(egui::Slider::new(&mut reference.opacity, 0.0..=1.0));
+                            ui.end_row();
+                            ui.label("Width");
+                            ui.add(
+                                egui::Slider::new(&mut reference.width, 1.0..=1024.0)
+                                    .logarithmic(true)
+                                    .suffix(" voxels"),
+                            )
+                            .on_hover_text("The height follows the image's proportions");
+                            ui.end_row();
+                            ui.label("Position");
+                            ui.horizontal(|ui| {
+                                for v in &mut reference.position {
+                                    ui.add(egui::DragValue::new(v).speed(0.1));
+                                }
+                            })
+                            .response
+                            .on_hover_text(match reference.view {
+                                ReferenceView::Top => "Center of the image",
+                                _ => "Middle of the image's bottom edge",
+                            });
+                            ui.end_row();
+                        });
+                }
+
+                ui.separator();
+                ui.heading("Ghost Model");
+                let Some(model) = ghost else {
                     if ui
-                        .add_enabled(can_paste, egui::Button::new("Paste"))
+                        .button("Load Model…")
                         .on_hover_text(
-                            "Ctrl+V — paste at selection origin (or cursor cell if no \
-                             selection). Ctrl+Shift+V always pastes at cursor.",
+                            "Show a .vox or .obj see-through to rebuild against; \
+                             it can't be edited or picked",
+                        )
+                        .clicked()
+                    {
+                        actions.push(UiAction::LoadReferenceModel);
+                    }
+                    return;
+                };
+                let mut remove = false;
+                ui.horizontal(|ui| {
+                    let name = model.name();
+                    ui.checkbox(&mut model.visible, name)
+                        .on_hover_text("Show or hide the ghost");
+                    if ui.button("Replace…").clicked() {
+                        actions.push(UiAction::LoadReferenceModel);
+                    }
+                    remove = ui.button("Remove").clicked();
+                });
+                egui::Grid::new("reference_model_props")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Opacity");
+                        ui.add(egui::Slider::new(&mut model.opacity, 0.0..=1.0));
+                        ui.end_row();
+                        ui.label("Scale");
+                        ui.add(
+                            egui::DragValue::new(&mut model.scale)
+                                .speed(0.01)
+                                .range(0.001..=1000.0),
+                        )
+                        .on_hover_text("Voxels per unit of the file; 1 for a .vox");
+                        ui.end_row();
+                        ui.label("Offset");
+                        ui.horizontal(|ui| {
+                            for v in &mut model.offset {
+                                ui.add(egui::DragValue::new(v).speed(0.1));
+                            }
+                        })
+                        .response
+                        .on_hover_text("Where the file's origin sits in the world");
+                        ui.end_row();
+                    });
+                if remove {
+                    *ghost = None;
+                }
+            });
+        for action in actions {
+            self.state.request(action);
+        }
+    }
+
+    /// File ▸ Export ▸ OBJ / glTF: format and per-export options, then
+    /// Export… asks for the path.
+    fn show_export_panel(&mut self, ctx: &Context) {
+        let mut export = false;
+        let settings = &mut self.mesh_export;
+        egui::Window::new("Export")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_export)
+            .show(ctx, |ui| {
+                let options = &mut settings.options;
+                egui::Grid::new("mesh_export_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Format");
+                        egui::ComboBox::from_id_salt("mesh_export_format")
+                            .selected_text(settings.format.label())
+                            .show_ui(ui, |ui| {
+                                for format in [MeshExportFormat::Obj, MeshExportFormat::Glb] {
+                                    ui.selectable_value(&mut settings.format, format, format.label());
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Surface");
+                        egui::ComboBox::from_id_salt("mesh_export_surface")
+                            .selected_text(options.surface.label())
+                            .show_ui(ui, |ui| {
+                                for surface in MeshSurface::ALL {
+                                    ui.selectable_value(&mut options.surface, surface, surface.label());
+                                }
+                            })
+                            .response
+                            .on_hover_text(
+                                "Smoothed surfaces run Marching Cubes; heavy blurs the \
+                                 density first for clay-like terrain but may dissolve \
+                                 thin features",
+                            );
+                        ui.end_row();
+
+                        ui.label("Colors");
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut options.colors, GlbColors::Shaded, "Shaded")
+                                .on_hover_text("Bake the editor's face shading and ambient occlusion into the colors");
+                            ui.radio_value(&mut options.colors, GlbColors::Flat, "Flat")
+                                .on_hover_text("Raw voxel colors, for engines that light the model themselves");
+                        });
+                        ui.end_row();
+
+                        ui.label("Store colors as");
+                        ui.horizontal(|ui| {
+                            for storage in [ColorStorage::Vertex, ColorStorage::Atlas] {
+                                ui.radio_value(&mut options.color_storage, storage, storage.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "A texture atlas gives every face color one texel plus UVs, \
+                             for engines that ignore vertex colors (ambient occlusion \
+                             isn't kept). OBJ writes the .png and a .mtl beside the file.",
+                        );
+                        ui.end_row();
+
+                        ui.label("Origin");
+                        egui::ComboBox::from_id_salt("mesh_export_pivot")
+                            .selected_text(pivot_label(options.transform.pivot))
+                            .show_ui(ui, |ui| {
+                                for pivot in [Pivot::Origin, Pivot::BaseCenter, Pivot::Center] {
+                                    ui.selectable_value(&mut options.transform.pivot, pivot, pivot_label(pivot));
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Up axis");
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut options.transform.up_axis, UpAxis::Y, "Y");
+                            ui.radio_value(&mut options.transform.up_axis, UpAxis::Z, "Z");
+                        });
+                        ui.end_row();
+
+                        ui.label("Unit scale");
+                        ui.add(
+                            egui::DragValue::new(&mut options.transform.unit_scale)
+                                .range(0.001..=1000.0)
+                                .speed(0.01)
+                                .suffix(" per voxel"),
+                        );
+                        ui.end_row();
+                    });
+                let blocky = options.surface == MeshSurface::Blocky;
+                ui.add_enabled(blocky, egui::Checkbox::new(&mut options.merge_chunks, "Merge chunks"))
+                    .on_hover_text("One mesh for the whole model; off writes one mesh per chunk")
+                    .on_disabled_hover_text("Smoothed surfaces are always one mesh");
+                ui.checkbox(&mut options.smooth_normals, "Smooth normals")
+                    .on_hover_text("Area-weighted averaged normals instead of hard faces, for organic shapes");
+                ui.checkbox(&mut options.optimize, "Optimize mesh")
+                    .on_hover_text("Weld shared vertices, drop degenerate triangles and reorder for the GPU vertex cache");
+                if ui.button("Export...").clicked() {
+                    export = true;
+                }
+            });
+        if export {
+            self.state.request(UiAction::ExportMesh);
+        }
+    }
+
+    fn show_turntable_panel(&mut self, ctx: &Context) {
+        // Deferred-action pattern: `.open(...)` holds
+        // `self.state.show_turntable`.
+        let mut actions = Vec::new();
+        let settings = &mut self.turntable;
+        let job = self.turntable_job.as_ref();
+        egui::Window::new("Turntable")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_turntable)
+            .show(ctx, |ui| {
+                ui.add_enabled_ui(job.is_none(), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Size");
+                        ui.add(egui::DragValue::new(&mut settings.width).range(16..=4096).suffix(" px"));
+                        ui.label("×");
+                        ui.add(egui::DragValue::new(&mut settings.height).range(16..=4096).suffix(" px"));
+                    });
+                    ui.add(egui::Slider::new(&mut settings.frames, 8..=360).text("Frames per turn"));
+                    ui.horizontal(|ui| {
+                        for format in [TurntableFormat::PngSequence, TurntableFormat::Gif] {
+                            ui.radio_value(&mut settings.format, format, format.label());
+                        }
+                    });
+                    ui.add_enabled(
+                        settings.format == TurntableFormat::Gif,
+                        egui::Slider::new(&mut settings.fps, 1..=50).text("FPS"),
+                    );
+                });
+                ui.label("Spins the camera 360° around the model, framed from the current view.");
+                if ui
+                    .add_enabled(job.is_none(), egui::Button::new("Render..."))
+                    .clicked()
+                {
+                    actions.push(UiAction::StartTurntable);
+                }
+                if gen_job_ui(ui, job) {
+                    actions.push(UiAction::CancelTurntable);
+                }
+            });
+        for action in actions {
+            self.state.request(action);
+        }
+    }
+
+    /// Edit ▸ Transform ▸ Move…: a cell offset per axis, applied to the
+    /// selection (or the active layer's whole model) as one undoable
+    /// move. Stays open for repeated steps.
+    fn show_move_panel(&mut self, ctx: &Context, has_selection: bool) {
+        let mut apply = false;
+        let delta = &mut self.move_delta;
+        egui::Window::new("Move")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_move)
+            .show(ctx, |ui| {
+                ui.label(if has_selection {
+                    "Moves the selection"
+                } else {
+                    "Moves the whole layer"
+                });
+                ui.horizontal(|ui| {
+                    for (label, value) in ["X", "Y", "Z"].into_iter().zip(delta.iter_mut()) {
+                        ui.label(label);
+                        ui.add(egui::DragValue::new(value).speed(0.25));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(*delta != [0; 3], egui::Button::new("Apply"))
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if ui.button("Reset").clicked() {
+                        *delta = [0; 3];
+                    }
+                });
+            });
+        if apply {
+            let [x, y, z] = self.move_delta;
+            self.state.request(UiAction::Move { delta: (x, y, z) });
+        }
+    }
+
+    /// World dimensions for the whole project: every layer is bounded
+    /// to the same box, drawn as a blue wireframe in the viewport.
+    /// `bounds` is the scene's shared bounds (`None` when unbounded, or
+    /// with `shared` false when layers were bounded one by one).
+    /// Warns about what a `.vox` export will have to split or quantize.
+    fn show_project_settings_panel(
+        &mut self,
+        ctx: &Context,
+        bounds: Option<WorldBounds>,
+        shared: bool,
+    ) {
+        if !self.project.filled {
+            self.project.fill(bounds);
+        }
+        let mut apply = false;
+        let project = &mut self.project;
+        let info = self.model_info.as_ref();
+        egui::Window::new("Project Settings")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_project_settings)
+            .show(ctx, |ui| {
+                let current = match bounds {
+                    Some(bounds) => {
+                        let (x, y, z) = bounds.size_voxels();
+                        format!("World: {x} × {y} × {z} voxels")
+                    }
+                    None if shared => "World: unbounded".to_string(),
+                    None => "World: layers have their own bounds".to_string(),
+                };
+                ui.label(current);
+                ui.separator();
+                ui.checkbox(&mut project.bounded, "Bounded world")
+                    .on_hover_text(
+                        "Limit every layer to a fixed box; edits outside it are ignored",
+                    );
+                ui.add_enabled_ui(project.bounded, |ui| {
+                    egui::Grid::new("project_bounds").num_columns(4).show(ui, |ui| {
+                        ui.label("Chunks");
+                        for value in project.chunks.iter_mut() {
+                            ui.add(egui::DragValue::new(value).range(1..=64).speed(0.1));
+                        }
+                        ui.end_row();
+                    });
+                    let [x, y, z] = project.chunks.map(|c| c * CHUNK_SIZE_I32 as u32);
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{x} × {y} × {z} voxels, standing on y = 0, centered on X / Z"
+                        ))
+                        .small()
+                        .weak(),
+                    );
+                });
+
+                let target = project.bounds();
+                let mut notes = Vec::new();
+                if let Some(target) = target {
+                    let (x, y, z) = target.size_voxels();
+                    if x.max(y).max(z) > VOX_MODEL_MAX {
+                        notes.push(format!(
+                            "Over {VOX_MODEL_MAX} on an axis: .vox export splits the world into several models"
+                        ));
+                    }
+                }
+                let mut outside = false;
+                if let Some(info) = info {
+                    if let Some(model) = info.bounds {
+                        let (w, h, d) = model.size();
+                        if w.max(h).max(d) > VOX_MODEL_MAX {
+                            notes.push(format!(
+                                "The model is {w} × {h} × {d}: .vox export splits it into several models"
+                            ));
+                        }
+                        outside = target.is_some_and(|t| {
+                            !t.contains_voxel(model.min.0, model.min.1, model.min.2)
+                                || !t.contains_voxel(model.max.0, model.max.1, model.max.2)
+                        });
+                    }
+                    if info.colors.len() > VOX_PALETTE_COLORS {
+                        notes.push(format!(
+                            "{} colors: .vox export quantizes them to a {VOX_PALETTE_COLORS}-color palette",
+                            info.colors.len()
+                        ));
+                    }
+                }
+                for note in notes {
+                    ui.label(egui::RichText::new(note).small().weak());
+                }
+                if outside {
+                    ui.label(
+                        egui::RichText::new(
+                            "Part of the model lies outside these bounds and will be deleted; \
+                             undo history is cleared",
                         )
-                        .clicked()
-                    {
-                        self.state.request(UiAction::PasteClipboard);
+                        .color(ui.visuals().warn_fg_color),
+                    );
+                }
+                ui.separator();
+                if ui.button("Apply").clicked() {
+                    apply = true;
+                }
+            });
+        if apply {
+            self.state.request(UiAction::SetWorldBounds(self.project.bounds()));
+        }
+    }
+
+    /// Crop the active layer to a box: the voxels outside are cleared
+    /// as one undoable edit. Optionally the layer is bounded to the box
+    /// too, so nothing can be drawn outside it — useful before a `.vox`
+    /// export, whose models are at most 256 cells per side.
+    fn show_crop_panel(&mut self, ctx: &Context, has_selection: bool, bounds: Option<WorldBounds>) {
+        let mut apply = false;
+        let mut fit = false;
+        let mut unbound = false;
+        let crop = &mut self.crop;
+        egui::Window::new("Crop")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_crop)
+            .show(ctx, |ui| {
+                ui.label("Clears the active layer outside the box");
+                egui::Grid::new("crop_box").num_columns(4).show(ui, |ui| {
+                    for (label, corner) in [("Min", &mut crop.min), ("Max", &mut crop.max)] {
+                        ui.label(label);
+                        for value in corner.iter_mut() {
+                            ui.add(egui::DragValue::new(value).speed(0.25));
+                        }
+                        ui.end_row();
                     }
                 });
+                let (w, h, d) = crop.keep().size();
+                ui.label(format!("Size: {w} × {h} × {d}"));
+                if w.max(h).max(d) > 256 {
+                    ui.label(
+                        egui::RichText::new(
+                            "Over 256 on an axis: .vox export splits it into several models",
+                        )
+                        .small()
+                        .weak(),
+                    );
+                }
+                let fit_label = if has_selection { "Fit to Selection" } else { "Fit to Model" };
+                if ui.button(fit_label).clicked() {
+                    fit = true;
+                }
+                ui.separator();
+                ui.checkbox(&mut crop.bound_layer, "Bound the layer to the box")
+                    .on_hover_text(
+                        "Rounded out to whole chunks. Edits outside are ignored, \
+                         and the layer's undo history is cleared",
+                    );
                 ui.horizontal(|ui| {
-                    if ui
-                        .add_enabled(has_sel, egui::Button::new("Delete"))
-                        .on_hover_text("Del — clear non-air voxels inside the selection")
-                        .clicked()
-                    {
-                        self.state.request(UiAction::DeleteSelection);
+                    if ui.button("Crop").clicked() {
+                        apply = true;
                     }
                     if ui
-                        .button("Select All")
-                        .on_hover_text("Ctrl+A — select the AABB of every non-air voxel")
+                        .add_enabled(bounds.is_some(), egui::Button::new("Remove Bounds"))
                         .clicked()
                     {
-                        self.state.request(UiAction::SelectAllSolid);
+                        unbound = true;
+                    }
+                });
+                if let Some(bounds) = bounds {
+                    let (bx, by, bz) = bounds.size_voxels();
+                    ui.label(
+                        egui::RichText::new(format!("Layer bounded to {bx} × {by} × {bz}"))
+                            .small()
+                            .weak(),
+                    );
+                }
+            });
+        if fit {
+            self.state.request(UiAction::FitCropBox);
+        }
+        if apply {
+            self.state.request(UiAction::Crop {
+                keep: self.crop.keep(),
+                bound_layer: self.crop.bound_layer,
+            });
+        }
+        if unbound {
+            self.state.request(UiAction::UnboundLayer);
+        }
+    }
+
+    /// Replace one color with the brush's, across the active layer,
+    /// the selection, or the connected run around a picked voxel. The
+    /// color to find is typed in or picked by clicking a voxel.
+    fn show_replace_color_panel(&mut self, ctx: &Context, editor: &Editor) {
+        let mut apply = false;
+        let settings = &mut self.recolor;
+        let has_selection = editor.selection.is_some();
+        if settings.scope == ReplaceScope::Selection && !has_selection
+            || settings.scope == ReplaceScope::Contiguous && settings.seed.is_none()
+        {
+            settings.scope = ReplaceScope::Layer;
+        }
+        egui::Window::new("Replace Color")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_replace_color)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Find");
+                    let mut color = settings.from.map(|c| c as f32 / 255.0);
+                    if ui.color_edit_button_rgba_unmultiplied(&mut color).changed() {
+                        settings.from = color.map(|c| (c * 255.0).round() as u8);
+                        // A typed-in color has no voxel to grow from.
+                        settings.seed = None;
                     }
+                    ui.toggle_value(&mut settings.picking, "Pick")
+                        .on_hover_text("Click a voxel in the viewport to take its color");
+                });
+                if settings.picking {
+                    ui.label(egui::RichText::new("Click a voxel…").small().weak());
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Replace with");
+                    let brush = editor.brush_color;
+                    let swatch = egui::Color32::from_rgba_unmultiplied(
+                        brush.r, brush.g, brush.b, brush.a,
+                    );
+                    egui::color_picker::show_color(ui, swatch, egui::vec2(40.0, 16.0));
+                    ui.label(egui::RichText::new("(brush)").small().weak());
+                });
+                ui.separator();
+                ui.radio_value(&mut settings.scope, ReplaceScope::Layer, "Whole layer");
+                ui.add_enabled_ui(has_selection, |ui| {
+                    ui.radio_value(&mut settings.scope, ReplaceScope::Selection, "Selection");
+                });
+                ui.add_enabled_ui(settings.seed.is_some(), |ui| {
+                    ui.radio_value(&mut settings.scope, ReplaceScope::Contiguous, "Contiguous")
+                        .on_hover_text("The connected run around the picked voxel");
+                });
+                if ui.button("Replace").clicked() {
+                    apply = true;
+                }
+            });
+        if apply {
+            let scope = match (self.recolor.scope, editor.selection, self.recolor.seed) {
+                (ReplaceScope::Selection, Some(sel), _) => RecolorScope::Region(sel),
+                (ReplaceScope::Contiguous, _, Some(seed)) => RecolorScope::Contiguous(seed),
+                _ => RecolorScope::All,
+            };
+            self.state.request(UiAction::ReplaceColor {
+                from: self.recolor.from,
+                scope,
+            });
+        }
+    }
+
+    /// Shift hue / saturation / brightness of the selection's voxels,
+    /// or of the active layer's whole model when nothing is selected.
+    fn show_adjust_colors_panel(&mut self, ctx: &Context, has_selection: bool) {
+        let mut apply = false;
+        let adjust = &mut self.recolor.adjust;
+        egui::Window::new("Adjust Colors")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_adjust_colors)
+            .show(ctx, |ui| {
+                ui.label(if has_selection {
+                    "Adjusts the selection"
+                } else {
+                    "Adjusts the whole layer"
+                });
+                ui.add(egui::Slider::new(&mut adjust.hue, -180..=180).text("Hue").suffix("°"));
+                ui.add(
+                    egui::Slider::new(&mut adjust.saturation, -100..=100)
+                        .text("Saturation")
+                        .suffix("%"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut adjust.brightness, -100..=100)
+                        .text("Brightness")
+                        .suffix("%"),
+                );
+                ui.horizontal(|ui| {
                     if ui
-                        .add_enabled(has_sel, egui::Button::new("Deselect"))
-                        .on_hover_text("Esc / Ctrl+D — clear the active selection")
+                        .add_enabled(!adjust.is_identity(), egui::Button::new("Apply"))
                         .clicked()
                     {
-                        editor.selection = None;
+                        apply = true;
+                    }
+                    if ui.button("Reset").clicked() {
+                        *adjust = ColorAdjust::default();
                     }
                 });
+            });
+        if apply {
+            self.state.request(UiAction::AdjustColors(self.recolor.adjust));
+        }
+    }
 
-                ui.add_space(4.0);
-                ui.heading("Sockets");
-                if ui
-                    .selectable_label(editor.current_tool == Tool::Socket, "Place Socket")
+    /// Blur colors across neighboring voxels — of the selection, or of
+    /// the active layer's whole model when nothing is selected. Handy
+    /// after noisy procgen output.
+    fn show_smooth_colors_panel(&mut self, ctx: &Context, has_selection: bool) {
+        let mut apply = false;
+        let iterations = &mut self.recolor.smooth_iterations;
+        egui::Window::new("Smooth Colors")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_smooth_colors)
+            .show(ctx, |ui| {
+                ui.label(if has_selection {
+                    "Smooths the selection"
+                } else {
+                    "Smooths the whole layer"
+                });
+                ui.add(egui::Slider::new(iterations, 1..=10).text("Iterations"))
                     .on_hover_text(
-                        "Click a voxel face (or the ground) to drop a named \
-                         attachment point. Exports to glTF as an empty node \
-                         (name + position + orientation).",
-                    )
-                    .clicked()
-                {
-                    editor.current_tool = Tool::Socket;
+                        "Each pass averages every voxel with its solid face neighbors",
+                    );
+                if ui.button("Apply").clicked() {
+                    apply = true;
                 }
-                if editor.sockets.is_empty() {
-                    ui.label(egui::RichText::new("No sockets yet.").small().weak());
-                } else {
-                    // Per-socket row: inline rename + delete + position
-                    // readout. Names become glTF node names on export.
-                    let mut to_delete: Option<usize> = None;
-                    egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
-                        for (i, s) in editor.sockets.iter_mut().enumerate() {
-                            ui.horizontal(|ui| {
-                                ui.add(
-                                    egui::TextEdit::singleline(&mut s.name)
-                                        .desired_width(110.0),
-                                )
-                                .on_hover_text("Name (becomes the glTF node name)");
-                                if ui
-                                    .small_button("✕")
-                                    .on_hover_text("Delete this socket")
-                                    .clicked()
-                                {
-                                    to_delete = Some(i);
-                                }
-                                ui.label(
-                                    egui::RichText::new(format!(
-                                        "({:.1}, {:.1}, {:.1})",
-                                        s.position[0], s.position[1], s.position[2]
-                                    ))
-                                    .small()
-                                    .weak(),
-                                );
-                            });
+            });
+        if apply {
+            self.state.request(UiAction::SmoothColors {
+                iterations: self.recolor.smooth_iterations,
+            });
+        }
+    }
+
+    /// Rotate submenus (90° / -90° / 180° per axis), Scale, Move… and
+    /// the three flips, shared by the Selection and Edit ▸ Transform
+    /// menus. Each acts on the selection, or on the active layer's whole
+    /// model when nothing is selected. The rotation / scale anchor is the
+    /// box's `min` corner, so a 4×1×2 region becomes 2×1×4 spreading
+    /// toward +Z.
+    fn transform_menu_items(&mut self, ui: &mut egui::Ui, enabled: bool) {
+        let rotations = [
+            (Axis::X, "Rotate around X"),
+            (Axis::Y, "Rotate around Y"),
+            (Axis::Z, "Rotate around Z"),
+        ];
+        for (axis, title) in rotations {
+            ui.menu_button(title, |ui| {
+                for (label, quarter) in [("90°", Quarter::Cw), ("-90°", Quarter::Ccw), ("180°", Quarter::Half)] {
+                    let target = CommandTarget::Action(UiAction::Rotate { axis, quarter });
+                    self.command_item_as(ui, target, label, enabled);
+                }
+            });
+        }
+        ui.menu_button("Scale", |ui| {
+            let scales = [
+                ("2×", Scale::Up(2)),
+                ("3×", Scale::Up(3)),
+                ("1/2 — majority color", Scale::Down(2, Downsample::Majority)),
+                ("1/2 — average color", Scale::Down(2, Downsample::Average)),
+                ("1/3 — majority color", Scale::Down(3, Downsample::Majority)),
+                ("1/3 — average color", Scale::Down(3, Downsample::Average)),
+            ];
+            for (i, (label, scale)) in scales.into_iter().enumerate() {
+                if i == 2 {
+                    ui.separator();
+                }
+                let target = CommandTarget::Action(UiAction::Scale { scale });
+                let button = self.command_item_as(ui, target, label, enabled);
+                match scale {
+                    Scale::Up(_) => button.on_hover_text("Each voxel becomes a cube of voxels"),
+                    Scale::Down(..) => button.on_hover_text(
+                        "Each block becomes one voxel, kept when at least half the block is solid",
+                    ),
+                };
+            }
+        });
+        self.command_item(ui, CommandTarget::Open(UiWindow::Move), enabled);
+        ui.separator();
+        for axis in [Axis::X, Axis::Y, Axis::Z] {
+            self.command_item(ui, CommandTarget::Action(UiAction::Mirror { axis }), enabled);
+        }
+    }
+
+    /// The material table: pick the brush material from the list, edit
+    /// the picked one's name and properties, add and delete materials.
+    /// Edits apply live — the app re-stamps voxels using a changed
+    /// material (`App::sync_materials`); they aren't undoable.
+    fn show_materials_panel(&mut self, ctx: &Context, editor: &mut Editor) {
+        egui::Window::new("Materials")
+            .default_width(260.0)
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.state.show_materials)
+            .show(ctx, |ui| {
+                let selected = editor.brush_color.material;
+                egui::ScrollArea::vertical()
+                    .max_height(180.0)
+                    .show(ui, |ui| {
+                        for (id, def) in editor.materials.iter() {
+                            let label = format!("{:>3}  {}", id, def.name);
+                            if ui
+                                .selectable_label(id == selected, label)
+                                .on_hover_text("Paint with this material")
+                                .clicked()
+                            {
+                                editor.brush_color.material = id;
+                            }
                         }
                     });
-                    if let Some(i) = to_delete {
-                        editor.sockets.remove(i);
+
+                ui.horizontal(|ui| {
+                    let full = editor.materials.len() >= MATERIAL_SLOTS - 1;
+                    if ui
+                        .add_enabled(!full, egui::Button::new("New"))
+                        .on_hover_text("Add a material and paint with it")
+                        .clicked()
+                    {
+                        let name = format!("Material {}", editor.materials.len() + 1);
+                        if let Some(id) = editor.materials.add(MaterialDef::new(name)) {
+                            editor.brush_color.material = id;
+                        }
                     }
                     if ui
-                        .button("Clear all sockets")
-                        .on_hover_text("Remove every socket from the scene")
+                        .add_enabled(selected != DEFAULT_MATERIAL, egui::Button::new("Delete"))
+                        .on_hover_text("Remove this material; its voxels revert to Default")
                         .clicked()
                     {
-                        editor.sockets.clear();
+                        editor.materials.remove(selected);
+                        editor.brush_color.material = DEFAULT_MATERIAL;
                     }
-                }
-
-                ui.separator();
-
-                // Brush size
-                ui.heading("Brush Size");
-                let mut size = editor.brush_size as u32;
-                ui.add(egui::Slider::new(&mut size, 1..=10).show_value(true));
-                editor.brush_size = size as u8;
+                });
 
                 ui.separator();
-
-                // Symmetry
-                ui.heading("Symmetry");
-                ui.horizontal(|ui| {
-                    ui.checkbox(&mut editor.symmetry.x, "X")
-                        .on_hover_text("Mirror brush across the x = 0 plane");
-                    ui.checkbox(&mut editor.symmetry.y, "Y")
-                        .on_hover_text("Mirror brush across the y = 0 plane");
-                    ui.checkbox(&mut editor.symmetry.z, "Z")
-                        .on_hover_text("Mirror brush across the z = 0 plane");
-                });
+                let Some(def) = editor.materials.get_mut(editor.brush_color.material) else {
+                    return;
+                };
+                egui::Grid::new("material_props")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Name");
+                        ui.text_edit_singleline(&mut def.name);
+                        ui.end_row();
+                        ui.label("Roughness");
+                        ui.add(egui::Slider::new(&mut def.roughness, 0.0..=1.0))
+                            .on_hover_text("0 = sharp, glossy highlight; 1 = matte");
+                        ui.end_row();
+                        ui.label("Metallic");
+                        ui.add(egui::Slider::new(&mut def.metallic, 0.0..=1.0))
+                            .on_hover_text(
+                                "Tints highlights with the voxel color and darkens \
+                                 diffuse; from 0.5 up, exported as a glTF metal",
+                            );
+                        ui.end_row();
+                        ui.label("Emissive");
+                        ui.add(egui::Slider::new(&mut def.emissive, 0.0..=MaterialDef::MAX_EMISSIVE))
+                            .on_hover_text(
+                                "Self-illumination strength; above 0, lights GI and \
+                                 exports as a glTF emissive material",
+                            );
+                        ui.end_row();
+                        ui.label("Transparency");
+                        ui.add(egui::Slider::new(&mut def.transparency, 0.0..=1.0))
+                            .on_hover_text(
+                                "Above 0, sets the alpha of every voxel with this \
+                                 material (drawn in the translucent pass)",
+                            );
+                        ui.end_row();
+                    });
                 ui.label(
                     egui::RichText::new(
-                        "Mirrors Place / Remove / Paint / Fill across enabled \
-                         planes through the world origin. Eyedropper is exempt.",
+                        "Changes apply to every voxel with this material and \
+                         aren't undoable.",
                     )
                     .small()
                     .weak(),
                 );
+            });
+    }
 
+    fn show_model_info_panel(&mut self, ctx: &Context) {
+        let info = self.model_info.as_ref();
+        egui::Window::new("Model Info")
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_model_info)
+            .show(ctx, |ui| {
+                let Some(info) = info else {
+                    ui.label("Measuring…");
+                    return;
+                };
+                let Some(bounds) = info.bounds else {
+                    ui.label("The model is empty.");
+                    return;
+                };
+                let (w, h, d) = bounds.size();
+                egui::Grid::new("model_info_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label("Dimensions:");
+                        ui.label(format!("{} × {} × {} voxels", w, h, d));
+                        ui.end_row();
+                        ui.label("Bounds:");
+                        ui.label(format!("{:?} → {:?}", bounds.min, bounds.max));
+                        ui.end_row();
+                        ui.label("Voxels:");
+                        let fill = 100.0 * info.voxels as f64 / bounds.volume() as f64;
+                        ui.label(format!("{} ({:.1}% of the box)", panels::group_thousands(info.voxels as usize), fill))
+                            .on_hover_text("Solid voxels, and how much of the bounding box they fill");
+                        ui.end_row();
+                        ui.label("Unique colors:");
+                        ui.label(panels::group_thousands(info.colors.len()))
+                            .on_hover_text("Distinct RGBA colors. A .vox palette holds 255; past that, export quantizes.");
+                        ui.end_row();
+                        ui.label("Chunks:");
+                        ui.label(panels::group_thousands(info.chunks));
+                        ui.end_row();
+                        ui.label("Memory:");
+                        ui.label(panels::format_bytes(info.memory_bytes as u64))
+                            .on_hover_text("Voxel storage for every layer plus the composited view");
+                        ui.end_row();
+                    });
                 ui.separator();
+                ui.label("Most used colors");
+                for &([r, g, b, a], count) in info.colors.iter().take(MODEL_INFO_TOP_COLORS) {
+                    ui.horizontal(|ui| {
+                        let (rect, _) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgb(r, g, b));
+                        let share = 100.0 * count as f64 / info.voxels as f64;
+                        let alpha = if a < 255 { format!(" α{}", a) } else { String::new() };
+                        ui.label(format!(
+                            "#{:02X}{:02X}{:02X}{}  {} ({:.1}%)",
+                            r,
+                            g,
+                            b,
+                            alpha,
+                            panels::group_thousands(count as usize),
+                            share
+                        ));
+                    });
+                }
+                if info.colors.len() > MODEL_INFO_TOP_COLORS {
+                    ui.weak(format!("… and {} more", info.colors.len() - MODEL_INFO_TOP_COLORS));
+                }
+            });
+    }
 
-                // Color
-                ui.heading("Color");
-                let mut color = [
-                    editor.brush_color.r as f32 / 255.0,
-                    editor.brush_color.g as f32 / 255.0,
-                    editor.brush_color.b as f32 / 255.0,
-                ];
-                if ui.color_edit_button_rgb(&mut color).changed() {
-                    // Only RGB changes; keep alpha + material flags
-                    // (emissive / metallic) so a color pick doesn't reset
-                    // what behaves like a brush mode.
-                    editor.brush_color.r = (color[0] * 255.0) as u8;
-                    editor.brush_color.g = (color[1] * 255.0) as u8;
-                    editor.brush_color.b = (color[2] * 255.0) as u8;
-                }
-
-                // RGB values
-                ui.horizontal(|ui| {
-                    ui.label("RGB:");
-                    ui.label(format!("{}, {}, {}", editor.brush_color.r, editor.brush_color.g, editor.brush_color.b));
-                });
+    fn show_layers_panel(&mut self, ctx: &Context, stats: &RenderStats) {
+        // Deferred-action pattern (same as `show_ai_panel`): `.open(...)`
+        // holds `self.state.show_layers`, so intents are collected here
+        // and requested once the window closure is done.
+        let mut actions = Vec::new();
+        let rename = &mut self.state.layer_rename;
+        let count = stats.layers.len();
+        egui::Window::new("Layers")
+            .default_pos([ctx.screen_rect().width() - 220.0, 320.0])
+            .resizable(false)
+            .collapsible(true)
+            .open(&mut self.state.show_layers)
+            .show(ctx, |ui| {
+                for (i, layer) in stats.layers.iter().enumerate().rev() {
+                    ui.horizontal(|ui| {
+                        let eye = if layer.visible { "👁" } else { "—" };
+                        if ui
+                            .selectable_label(layer.visible, eye)
+                            .on_hover_text("Show / hide")
+                            .clicked()
+                        {
+                            actions.push(UiAction::SetLayerVisible(i, !layer.visible));
+                        }
+                        let lock = if layer.locked { "🔒" } else { "🔓" };
+                        if ui
+                            .selectable_label(layer.locked, lock)
+                            .on_hover_text("Lock against edits")
+                            .clicked()
+                        {
+                            actions.push(UiAction::SetLayerLocked(i, !layer.locked));
+                        }
+
+                        match rename {
+                            Some((idx, buffer)) if *idx == i => {
+                                let response = ui.text_edit_singleline(buffer);
+                                response.request_focus();
+                                if response.lost_focus() {
+                                    let name = buffer.trim().to_string();
+                                    if !name.is_empty() {
+                                        actions.push(UiAction::RenameLayer(i, name));
+                                    }
+                                    *rename = None;
+                                }
+                            }
+                            _ => {
+                                let response = ui
+                                    .selectable_label(i == stats.active_layer, &layer.name)
+                                    .on_hover_text("Click to edit this layer, double-click to rename");
+                                if response.double_clicked() {
+                                    *rename = Some((i, layer.name.clone()));
+                                } else if response.clicked() {
+                                    actions.push(UiAction::SelectLayer(i));
+                                }
+                            }
+                        }
+                    });
+                }
 
                 ui.separator();
 
-                // Material flags baked into the brush's voxel template and
-                // carried into GLB export as glTF materials. A brush mode,
-                // like symmetry — picking a color preserves these.
-                ui.heading("Material");
+                let active = stats.active_layer;
                 ui.horizontal(|ui| {
-                    let mut emissive = editor.brush_color.is_emissive();
+                    if ui.button("Add").clicked() {
+                        actions.push(UiAction::AddLayer);
+                    }
                     if ui
-                        .checkbox(&mut emissive, "Emissive")
-                        .on_hover_text(
-                            "Mark placed voxels as self-illuminating \
-                             (exported as a glTF emissive material)",
-                        )
-                        .changed()
+                        .add_enabled(count > 1, egui::Button::new("Delete"))
+                        .clicked()
                     {
-                        editor.brush_color.set_emissive(emissive);
+                        actions.push(UiAction::RemoveLayer(active));
                     }
-                    let mut metallic = editor.brush_color.is_metallic();
                     if ui
-                        .checkbox(&mut metallic, "Metallic")
-                        .on_hover_text(
-                            "Mark placed voxels as metal (exported as a \
-                             glTF metallic material)",
-                        )
-                        .changed()
+                        .add_enabled(active > 0, egui::Button::new("Merge Down"))
+                        .clicked()
                     {
-                        editor.brush_color.set_metallic(metallic);
+                        actions.push(UiAction::MergeLayerDown(active));
                     }
                 });
                 ui.horizontal(|ui| {
-                    ui.label("Tint zone");
-                    let mut zone = editor.brush_color.tint_zone();
-                    let before = zone;
-                    let label = match zone {
-                        1 => "Primary",
-                        2 => "Secondary",
-                        3 => "Reserved",
-                        _ => "None",
-                    };
-                    egui::ComboBox::from_id_salt("brush_tint_zone")
-                        .selected_text(label)
-                        .show_ui(ui, |ui| {
-                            ui.selectable_value(&mut zone, 0, "None");
-                            ui.selectable_value(&mut zone, 1, "Primary");
-                            ui.selectable_value(&mut zone, 2, "Secondary");
-                            ui.selectable_value(&mut zone, 3, "Reserved");
+                    if ui
+                        .add_enabled(active + 1 < count, egui::Button::new("▲ Up"))
+                        .clicked()
+                    {
+                        actions.push(UiAction::MoveLayer {
+                            from: active,
+                            to: active + 1,
                         });
-                    if zone != before {
-                        editor.brush_color.set_tint_zone(zone);
                     }
-                })
-                .response
-                .on_hover_text(
-                    "Faction recolor zone — exported per-vertex as _TINTZONE \
-                     for a downstream uber-shader (does not change the editor view)",
-                );
-
-                // Show hovered voxel info
-                if let Some(hit) = &editor.hovered_voxel {
-                    ui.separator();
-                    ui.heading("Hovered");
-                    ui.label(format!("Position: ({}, {}, {})", hit.voxel_pos.0, hit.voxel_pos.1, hit.voxel_pos.2));
-                    ui.label(format!("Face: ({}, {}, {})", hit.normal.0, hit.normal.1, hit.normal.2));
-                }
+                    if ui
+                        .add_enabled(active > 0, egui::Button::new("▼ Down"))
+                        .clicked()
+                    {
+                        actions.push(UiAction::MoveLayer {
+                            from: active,
+                            to: active - 1,
+                        });
+                    }
+                });
             });
-    }
-
-    fn show_palette_panel(&mut self, ctx: &Context, editor: &mut Editor) {
-        egui::Window::new("Palette")
-            .default_pos([60.0, 450.0])
-            .resizable(true)
-            .collapsible(true)
-            .show(ctx, |ui| {
-                let palette = &editor.palette;
-                let cols = 5;
-
-                egui::Grid::new("palette_grid")
-                    .spacing([4.0, 4.0])
-                    .show(ui, |ui| {
-                        for (i, voxel) in palette.iter().enumerate() {
-                            let color = egui::Color32::from_rgb(voxel.r, voxel.g, voxel.b);
-                            let is_selected = editor.brush_color.r == voxel.r
-                                && editor.brush_color.g == voxel.g
-                                && editor.brush_color.b == voxel.b;
-
-                            let size = if is_selected { 24.0 } else { 20.0 };
-                            let (rect, response) = ui.allocate_exact_size(
-                                egui::vec2(size, size),
-                                egui::Sense::click(),
-                            );
 
-                            if response.clicked() {
-                                // Keep the brush's material flags; only the
-                                // color changes (see `set_palette_color`).
-                                editor.brush_color.r = voxel.r;
-                                editor.brush_color.g = voxel.g;
-                                editor.brush_color.b = voxel.b;
-                                editor.brush_color.a = voxel.a;
-                            }
+        for action in actions {
+            self.state.request(action);
+        }
+    }
 
-                            ui.painter().rect_filled(rect, 2.0, color);
-                            if is_selected {
-                                ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
-                            }
+    fn viewport_panel_contents(&mut self, ui: &mut egui::Ui, stats: &RenderStats) {
+        ui.heading("Display");
+        ui.checkbox(&mut self.viewport.show_grid, "Show Grid");
+        ui.checkbox(&mut self.viewport.show_axes, "Show Axes");
+        ui.checkbox(&mut self.viewport.wireframe_mode, "Wireframe Mode")
+            .on_hover_text("Show the chunk meshes' triangles, e.g. to compare meshers");
+        ui.add_enabled(
+            self.viewport.wireframe_mode,
+            egui::Checkbox::new(&mut self.viewport.wireframe_shaded, "Shaded + Edges"),
+        )
+        .on_hover_text("Keep the shaded voxels and draw the edges over them");
+        ui.checkbox(&mut self.viewport.show_hud, "Viewport HUD")
+            .on_hover_text(
+                "Tool & gesture readout in the bottom-left corner of the viewport",
+            );
+        ui.checkbox(&mut self.viewport.show_perf_hud, "Performance HUD")
+            .on_hover_text(
+                "FPS, triangles, and re-mesh time in the bottom-right corner",
+            );
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.viewport.y_clip, "Clip Above Y")
+                .on_hover_text(
+                    "Hide every voxel above this height, e.g. a roof while you \
+                     work inside; clicks reach through to what's shown",
+                );
+            ui.add_enabled(
+                self.viewport.y_clip,
+                egui::DragValue::new(&mut self.viewport.y_clip_height).speed(0.1),
+            );
+        });
+        ui.checkbox(&mut self.viewport.show_bounds, "World Bounds")
+            .on_hover_text(
+                "Outline the box a bounded layer can hold voxels in; set it in File ▸ Project Settings",
+            );
+        ui.checkbox(&mut self.viewport.hover_tooltip, "Voxel Tooltip")
+            .on_hover_text(
+                "Show the hovered voxel's position, color, material and flags next to the cursor",
+            );
+        ui.checkbox(&mut self.viewport.show_measurement, "Dimension Annotation")
+            .on_hover_text(
+                "Keep the Measure tool's ruler and its label in the viewport with other tools active",
+            );
+        ui.checkbox(&mut self.viewport.move_gizmo, "Move Gizmo").on_hover_text(
+            "Arrows and squares to drag the selection, a pinned paste, the selected \
+             light or the mirror planes along the grid",
+        );
+        ui.checkbox(&mut self.viewport.show_lights, "Light Markers")
+            .on_hover_text("Mark each light placed in the Lights panel; the selected one in yellow");
+        ui.checkbox(&mut self.viewport.click_through_locked, "Click Through Locked Layers")
+            .on_hover_text(
+                "The cursor ignores voxels on locked layers and reaches what's behind them",
+            );
+        egui::ComboBox::from_label("Mesher")
+            .selected_text(self.viewport.mesher.label())
+            .show_ui(ui, |ui| {
+                for kind in MesherKind::ALL {
+                    ui.selectable_value(&mut self.viewport.mesher, kind, kind.label());
+                }
+            })
+            .response
+            .on_hover_text(
+                "How chunks are turned into triangles for the viewport; compare counts in the Performance HUD",
+            );
+        ui.add_enabled(
+            self.viewport.mesher != MesherKind::MarchingCubes,
+            egui::Checkbox::new(&mut self.viewport.ambient_occlusion, "Ambient Occlusion"),
+        )
+        .on_hover_text(
+            "Darken face corners next to neighboring voxels; baked into the mesh, no runtime cost",
+        );
+        ui.checkbox(&mut self.viewport.smooth_normals, "Smooth Normals")
+            .on_hover_text(
+                "Average the normals where faces meet, for rounded shading; \
+                 previews the Export dialog's Smooth normals option",
+            );
+        ui.checkbox(&mut self.viewport.gpu_picking, "GPU Picking")
+            .on_hover_text(
+                "Find the voxel under the cursor on the GPU instead of raycasting \
+                 the world; faster on huge scenes, the hover lags a frame behind",
+            );
+        ui.checkbox(&mut self.viewport.lod, "Level of Detail")
+            .on_hover_text(
+                "Mesh distant chunks with 2× / 4× bigger voxels, for far fewer triangles on big scenes",
+            );
+        ui.add_enabled(
+            self.viewport.lod,
+            egui::Slider::new(&mut self.viewport.lod_settings.distance, 32.0..=1024.0)
+                .logarithmic(true)
+                .text("LOD distance"),
+        )
+        .on_hover_text("Chunks farther than this (in voxels) use half resolution, twice as far a quarter");
+        egui::ComboBox::from_label("Anti-aliasing")
+            .selected_text(self.viewport.msaa.label())
+            .show_ui(ui, |ui| {
+                for msaa in Msaa::ALL {
+                    ui.selectable_value(&mut self.viewport.msaa, msaa, msaa.label());
+                }
+            })
+            .response
+            .on_hover_text("Smooths shimmering voxel edges; higher levels cost GPU fill rate");
 
-                            if (i + 1) % cols == 0 {
-                                ui.end_row();
-                            }
-                        }
-                    });
+        ui.separator();
 
-                ui.separator();
+        ui.heading("Grid");
+        ui.add(egui::Slider::new(&mut self.viewport.grid_size, 5..=50).text("Size"));
+        ui.add(egui::Slider::new(&mut self.viewport.grid_spacing, 0.5..=5.0).text("Spacing"));
 
-                // Quick color buttons
+        ui.separator();
+
+        ui.heading("Background");
+        let bg = &mut self.background;
+        egui::ComboBox::from_label("Kind")
+            .selected_text(bg.kind.label())
+            .show_ui(ui, |ui| {
+                for kind in BackgroundKind::ALL {
+                    ui.selectable_value(&mut bg.kind, kind, kind.label());
+                }
+            })
+            .response
+            .on_hover_text("Saved with the project");
+        match bg.kind {
+            BackgroundKind::Solid => {
                 ui.horizontal(|ui| {
-                    if ui.button("Add").clicked() {
-                        // Check if color already exists in palette
-                        let color = editor.brush_color;
-                        let exists = editor.palette.iter().any(|v| {
-                            v.r == color.r && v.g == color.g && v.b == color.b
-                        });
-                        if !exists && editor.palette.len() < 32 {
-                            editor.palette.push(color);
-                        }
-                    }
+                    ui.color_edit_button_rgb(&mut bg.color);
+                    ui.label("Color");
                 });
-            });
-    }
-
-    fn show_viewport_panel(&mut self, ctx: &Context) {
-        egui::Window::new("Viewport Settings")
-            .default_pos([ctx.screen_rect().width() - 220.0, 40.0])
-            .resizable(false)
-            .collapsible(true)
-            .show(ctx, |ui| {
-                ui.heading("Display");
-                ui.checkbox(&mut self.viewport.show_grid, "Show Grid");
-                ui.checkbox(&mut self.viewport.show_axes, "Show Axes");
-                ui.checkbox(&mut self.viewport.wireframe_mode, "Wireframe Mode");
-                ui.checkbox(&mut self.viewport.show_hud, "Viewport HUD")
-                    .on_hover_text(
-                        "Tool & gesture readout in the bottom-left corner of the viewport",
+            }
+            BackgroundKind::Gradient => {
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_rgb(&mut bg.top);
+                    ui.label("Top");
+                    ui.color_edit_button_rgb(&mut bg.bottom);
+                    ui.label("Bottom");
+                });
+            }
+            BackgroundKind::Hdri => {
+                ui.horizontal(|ui| {
+                    let name = bg
+                        .hdri_path
+                        .as_ref()
+                        .and_then(|p| p.file_name())
+                        .map_or("No image".into(), |n| n.to_string_lossy());
+                    ui.label(name).on_hover_text(
+                        bg.hdri_path
+                            .as_ref()
+                            .map_or(String::new(), |p| p.display().to_string()),
                     );
-                ui.checkbox(&mut self.viewport.show_perf_hud, "Performance HUD")
+                    if ui.button("Browse...").clicked() {
+                        self.state.request(UiAction::PickBackgroundImage);
+                    }
+                });
+                ui.add(egui::Slider::new(&mut bg.hdri_intensity, 0.0..=4.0).text("Intensity"));
+                ui.add(
+                    egui::Slider::new(&mut bg.hdri_rotation, -180.0..=180.0)
+                        .text("Rotation")
+                        .suffix("°"),
+                );
+                ui.checkbox(&mut bg.hdri_lighting, "Light the Model")
                     .on_hover_text(
-                        "FPS, triangles, and re-mesh time in the bottom-right corner",
+                        "Tint the ambient light by the image: bright sky above lights the tops, \
+                         a dark floor leaves the undersides dim",
                     );
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_rgb(&mut bg.color);
+                    ui.label("Fallback Color")
+                        .on_hover_text("Shown until the image loads, or if it can't be read");
+                });
+            }
+        }
 
-                ui.separator();
+        ui.separator();
+
+        ui.heading("Lighting");
+        ui.checkbox(&mut self.viewport.gi, "Global Illumination")
+            .on_hover_text(
+                "Coarse sky occlusion, emissive light and colored bounce in the realtime view",
+            );
+        let gi = &mut self.viewport.gi_settings;
+        ui.add_enabled(
+            self.viewport.gi,
+            egui::Slider::new(&mut gi.intensity, 0.0..=2.0).text("Intensity"),
+        );
+        ui.add_enabled(
+            self.viewport.gi,
+            egui::Slider::new(&mut gi.propagation_steps, 1..=32).text("Reach"),
+        )
+        .on_hover_text("Propagation steps — how far light spreads, in GI cells");
 
-                ui.heading("Grid");
-                ui.add(egui::Slider::new(&mut self.viewport.grid_size, 5..=50).text("Size"));
-                ui.add(egui::Slider::new(&mut self.viewport.grid_spacing, 0.5..=5.0).text("Spacing"));
+        ui.separator();
 
-                ui.separator();
+        ui.heading("Toon Shading");
+        ui.checkbox(&mut self.viewport.toon, "Toon Shading").on_hover_text(
+            "Banded lighting and ink outlines; screenshots, turntables and sprites render with it too",
+        );
+        ui.add_enabled_ui(self.viewport.toon, |ui| {
+            let toon = &mut self.viewport.toon_settings;
+            ui.add(egui::Slider::new(&mut toon.bands, 2..=6).text("Bands"))
+                .on_hover_text("Flat lighting steps between shadow and full light");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut toon.outlines, "Outlines");
+                ui.color_edit_button_rgb(&mut toon.outline_color);
+            });
+            ui.add_enabled(
+                toon.outlines,
+                egui::Slider::new(&mut toon.outline_width, 1..=4).text("Width").suffix(" px"),
+            );
+            ui.add_enabled(
+                toon.outlines,
+                egui::Slider::new(&mut toon.crease_angle, 10.0..=85.0)
+                    .text("Crease Angle")
+                    .suffix("°"),
+            )
+            .on_hover_text("Smallest bend between surfaces that gets a line; silhouettes always do");
+        });
+
+        ui.separator();
+
+        ui.heading("Path Tracing");
+        ui.checkbox(&mut self.viewport.path_trace, "Path Traced Preview")
+            .on_hover_text(
+                "Soft shadows, emissive light and depth of field; accumulates while the camera is still",
+            );
+        let pt = &mut self.viewport.path_trace_settings;
+        ui.add(
+            egui::Slider::new(&mut pt.max_samples, 1..=4096)
+                .logarithmic(true)
+                .text("Samples"),
+        );
+        ui.add(egui::Slider::new(&mut pt.max_bounces, 0..=8).text("Bounces"));
+        ui.add(
+            egui::Slider::new(&mut pt.aperture, 0.0..=2.0)
+                .text("Aperture"),
+        )
+        .on_hover_text("Lens radius for depth of field; 0 = everything in focus");
+        ui.add_enabled(
+            pt.aperture > 0.0,
+            egui::Slider::new(&mut pt.focus_distance, 1.0..=500.0)
+                .logarithmic(true)
+                .text("Focus"),
+        );
+        if let Some((samples, max)) = stats.path_trace {
+            ui.label(format!("{} / {} samples", samples, max));
+        }
+
+        ui.separator();
 
-                ui.heading("Camera");
-                if ui.button("Reset Camera").clicked() {
-                    self.state.request(UiAction::ResetCamera);
+        ui.heading("Camera");
+        if ui.button("Reset Camera").clicked() {
+            self.state.request(UiAction::ResetCamera);
+        }
+        ui.add(
+            egui::Slider::new(&mut self.viewport.camera_tween_secs, 0.0..=1.0)
+                .text("Animation")
+                .suffix(" s"),
+        )
+        .on_hover_text("How long view changes take to glide; 0 snaps instantly");
+        egui::ComboBox::from_label("Easing")
+            .selected_text(self.viewport.camera_easing.label())
+            .show_ui(ui, |ui| {
+                for easing in Easing::ALL {
+                    ui.selectable_value(&mut self.viewport.camera_easing, easing, easing.label());
                 }
+            });
 
-                ui.horizontal(|ui| {
-                    if ui.button("Top").clicked() {
-                        self.state.request(UiAction::SetCameraView(CameraView::Top));
-                    }
-                    if ui.button("Front").clicked() {
-                        self.state.request(UiAction::SetCameraView(CameraView::Front));
-                    }
-                    if ui.button("Side").clicked() {
-                        self.state.request(UiAction::SetCameraView(CameraView::Side));
-                    }
+        ui.horizontal_wrapped(|ui| {
+            for view in CameraView::ALL {
+                if ui.button(view.label()).clicked() {
+                    self.state.request(UiAction::SetCameraView(view));
+                }
+            }
+        });
+        ui.label("Numpad: 1 Front, 3 Right, 7 Top (Ctrl = opposite), 5 Iso")
+            .on_hover_text("Blender-style numeric view shortcuts");
+
+        // Bookmark slots: click to recall, right-click to save
+        // the current view. Ctrl+1..9 / Ctrl+Shift+1..9 do the
+        // same from the keyboard.
+        ui.horizontal(|ui| {
+            ui.label("Bookmarks");
+            for (slot, &saved) in self.camera_bookmarks.iter().enumerate() {
+                let text = egui::RichText::new(format!("{}", slot + 1));
+                let text = if saved { text.strong() } else { text.weak() };
+                let response = ui.button(text).on_hover_text(if saved {
+                    "Click: recall (Ctrl+N) · Right-click: overwrite with the current view (Ctrl+Shift+N)"
+                } else {
+                    "Empty · Right-click: save the current view (Ctrl+Shift+N)"
                 });
+                if response.clicked() && saved {
+                    self.state.request(UiAction::RecallCameraBookmark(slot));
+                }
+                if response.secondary_clicked() {
+                    self.state.request(UiAction::SaveCameraBookmark(slot));
+                }
+            }
+        });
 
-                ui.horizontal(|ui| {
-                    if ui
-                        .button("Frame All")
-                        .on_hover_text("Fit the whole scene in view (F with no selection)")
-                        .clicked()
-                    {
-                        self.state.request(UiAction::FrameAll);
-                    }
-                    if ui
-                        .button("Frame Sel.")
-                        .on_hover_text("Fit the selection in view (F with a selection)")
-                        .clicked()
-                    {
-                        self.state.request(UiAction::FrameSelected);
-                    }
-                    if ui
-                        .button("Frame Gen.")
-                        .on_hover_text("Fit the most recent generation in view")
-                        .clicked()
-                    {
-                        self.state.request(UiAction::FrameGenerated);
-                    }
-                });
-            });
+        ui.horizontal(|ui| {
+            if ui
+                .button("Frame All")
+                .on_hover_text("Fit the whole scene in view (F with no selection)")
+                .clicked()
+            {
+                self.state.request(UiAction::FrameAll);
+            }
+            if ui
+                .button("Frame Sel.")
+                .on_hover_text("Fit the selection in view (F with a selection)")
+                .clicked()
+            {
+                self.state.request(UiAction::FrameSelected);
+            }
+            if ui
+                .button("Frame Gen.")
+                .on_hover_text("Fit the most recent generation in view")
+                .clicked()
+            {
+                self.state.request(UiAction::FrameGenerated);
+            }
+        });
     }
 
-    fn show_procgen_panel(&mut self, ctx: &Context) {
+    fn show_procgen_panel(&mut self, ctx: &Context, has_selection: bool) {
         // Deferred-action pattern: `.open(...)` borrows self.state.show_procgen
         // and the closure borrows self.procgen, so we can't dispatch a UiAction
         // (which mutates self.state) until both are released.
         let mut generate = false;
+        let mut cancel = false;
+        let mut erode_selection = false;
+        let mut staged_action = None;
         let procgen = &mut self.procgen;
+        let gen_job = &self.gen_job;
+        let staged = &self.staged;
 
         egui::Window::new("Procedural Generation")
             .default_pos([ctx.screen_rect().width() - 240.0, 200.0])
@@ -1395,6 +3851,11 @@ impl Ui {
                                 GeneratorChoice::Wfc,
                                 GeneratorChoice::Wfc.label(),
                             );
+                            ui.selectable_value(
+                                &mut procgen.selected,
+                                GeneratorChoice::Cave,
+                                GeneratorChoice::Cave.label(),
+                            );
                         });
                 });
 
@@ -1402,7 +3863,22 @@ impl Ui {
 
                 match procgen.selected {
                     GeneratorChoice::Terrain => {
-                        terrain_params_ui(ui, &mut procgen.terrain)
+                        terrain_params_ui(ui, &mut procgen.terrain);
+                        // Region erosion reuses the terrain's erosion
+                        // settings (and seed) on existing voxels.
+                        if ui
+                            .add_enabled(
+                                has_selection && gen_job.is_none(),
+                                egui::Button::new("Erode Selection"),
+                            )
+                            .on_hover_text(
+                                "Run the erosion settings above on the terrain \
+                                 surface inside the selection",
+                            )
+                            .clicked()
+                        {
+                            erode_selection = true;
+                        }
                     }
                     GeneratorChoice::Tree => {
                         tree_params_ui(ui, &mut procgen.tree)
@@ -1410,6 +3886,9 @@ impl Ui {
                     GeneratorChoice::Wfc => {
                         wfc_params_ui(ui, &mut procgen.wfc)
                     }
+                    GeneratorChoice::Cave => {
+                        cave_params_ui(ui, &mut procgen.cave)
+                    }
                 }
 
                 ui.separator();
@@ -1420,19 +3899,40 @@ impl Ui {
                             "Show a translucent overlay of the generator's \
                              current output (debounced ~150ms)",
                         );
+                    let (text, hover) = match (procgen.apply_immediately, staged.is_some()) {
+                        (true, _) => ("Generate", "Apply generated voxels (undo-able)"),
+                        (false, false) => ("Generate", "Generate and stage the result for review"),
+                        (false, true) => ("Regenerate", "Replace the staged result"),
+                    };
                     if ui
-                        .button("Generate")
-                        .on_hover_text("Apply generated voxels (undo-able)")
+                        .add_enabled(gen_job.is_none(), egui::Button::new(text))
+                        .on_hover_text(hover)
                         .clicked()
                     {
                         generate = true;
                     }
                 });
+                ui.checkbox(&mut procgen.apply_immediately, "Apply immediately")
+                    .on_hover_text(
+                        "Write generated voxels straight into the world \
+                         instead of staging them for Apply / Discard",
+                    );
+                cancel = gen_job_ui(ui, gen_job.as_ref());
+                staged_action = staged_ui(ui, staged.as_ref());
             });
 
         if generate {
             self.state.request(UiAction::GenerateProcedural);
         }
+        if erode_selection {
+            self.state.request(UiAction::ErodeSelection);
+        }
+        if cancel {
+            self.state.request(UiAction::CancelGeneration);
+        }
+        if let Some(action) = staged_action {
+            self.state.request(action);
+        }
     }
 
     fn show_graph_panel(&mut self, ctx: &Context) {
@@ -1450,6 +3950,10 @@ impl Ui {
         let selected = &mut self.selected_node;
         let drag_wire = &mut self.dragging_wire;
         let preview_enabled = &mut self.procgen.graph_preview_enabled;
+        let gen_job = &self.gen_job;
+        let staged = &self.staged;
+        let mut cancel = false;
+        let mut staged_action = None;
 
         egui::Window::new("Pipeline Graph")
             .default_pos([240.0, 80.0])
@@ -1462,7 +3966,7 @@ impl Ui {
                 // ===== Top toolbar =====
                 ui.horizontal(|ui| {
                     if ui
-                        .button("▶ Run Pipeline")
+                        .add_enabled(gen_job.is_none(), egui::Button::new("▶ Run Pipeline"))
                         .on_hover_text("Evaluate the graph and apply (undo-able)")
                         .clicked()
                     {
@@ -1491,6 +3995,8 @@ impl Ui {
                     ui.separator();
                     ui.label(format!("Nodes: {}", graph.nodes.len()));
                 });
+                cancel = gen_job_ui(ui, gen_job.as_ref());
+                staged_action = staged_ui(ui, staged.as_ref());
                 ui.separator();
 
                 // ===== Split: canvas (left) + sidebar (right) =====
@@ -1553,6 +4059,12 @@ impl Ui {
         if run {
             self.state.request(UiAction::RunGraph);
         }
+        if cancel {
+            self.state.request(UiAction::CancelGeneration);
+        }
+        if let Some(action) = staged_action {
+            self.state.request(action);
+        }
     }
 
     fn show_ai_panel(&mut self, ctx: &Context) {
@@ -1820,6 +4332,10 @@ impl Ui {
                         ui.label("Paint stays on the first hit's face plane");
                         ui.end_row();
 
+                        ui.label("PageUp / PageDown");
+                        ui.label("Step the work plane one slice (Shift: 10)");
+                        ui.end_row();
+
                         ui.end_row();
                         ui.heading("Edit");
                         ui.end_row();
@@ -1860,6 +4376,10 @@ impl Ui {
                         ui.label("Paste at cursor cell");
                         ui.end_row();
 
+                        ui.label("Ctrl+Alt+V");
+                        ui.label("Place paste: ghost follows cursor, R / M orient, click commits");
+                        ui.end_row();
+
                         ui.label("Del");
                         ui.label("Delete non-air voxels in selection");
                         ui.end_row();
@@ -1869,7 +4389,7 @@ impl Ui {
                         ui.end_row();
 
                         ui.label("Esc / Ctrl+D");
-                        ui.label("Deselect");
+                        ui.label("Deselect (Esc also clears the measure ruler)");
                         ui.end_row();
 
                         ui.label("Arrows");
@@ -1884,8 +4404,16 @@ impl Ui {
                         ui.label("Rotate 90° around Y (CW / CCW)");
                         ui.end_row();
 
-                        ui.label("M");
-                        ui.label("Mirror across X (full axis set: Selection menu)");
+                        ui.label("Alt+X / Alt+Z");
+                        ui.label("Rotate 90° around X / Z (Shift: CCW)");
+                        ui.end_row();
+
+                        ui.label("M / Shift+X, Y, Z");
+                        ui.label("Flip along X / along an axis (180°: Edit ▸ Transform)");
+                        ui.end_row();
+
+                        ui.label("");
+                        ui.label("Rotate / flip act on the whole layer when nothing is selected");
                         ui.end_row();
 
                         ui.end_row();
@@ -1955,6 +4483,10 @@ impl Ui {
                         ui.label("Left Click");
                         ui.label("Apply tool");
                         ui.end_row();
+
+                        ui.label("Ctrl+P");
+                        ui.label("Command palette — search every command");
+                        ui.end_row();
                     });
             });
     }
@@ -2036,21 +4568,38 @@ impl Ui {
                 }
                 if let Some(sel) = editor.selection {
                     let (w, h, d) = sel.size();
+                    let cells = match editor.active_mask() {
+                        Some(mask) => format!("{} picked", mask.len()),
+                        None => format!("{} cells", sel.cell_count()),
+                    };
+                    let contents = self
+                        .selection_counts
+                        .map(|c| format!(" · {} solid · {}", c.solid, colors_label(c.colors)))
+                        .unwrap_or_default();
                     ui.separator();
                     ui.label(
                         egui::RichText::new(format!(
-                            "Sel: {}×{}×{} ({} cells)",
-                            w,
-                            h,
-                            d,
-                            sel.cell_count()
+                            "Sel: {}×{}×{} ({}){}",
+                            w, h, d, cells, contents
                         ))
                         .color(egui::Color32::from_rgb(255, 230, 60)),
                     );
                 }
 
+                if let Some(Measurement { start, end: Some(end) }) = self.measure_span(editor) {
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Measure: {}",
+                            Measurement::label(start, end)
+                        ))
+                        .color(hud::MEASURE_COLOR),
+                    );
+                }
+
                 // Right-aligned viewport / preview info.
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    self.task_status_item(ui);
                     if self.viewport.wireframe_mode {
                         ui.label("[Wireframe]");
                     }
@@ -2073,6 +4622,15 @@ impl Ui {
         });
     }
 
+    /// The completed ruler to report, if any: while the Measure tool
+    /// is active, or with any tool when the dimension annotation is on.
+    fn measure_span(&self, editor: &Editor) -> Option<Measurement> {
+        if editor.current_tool != Tool::Measure && !self.viewport.show_measurement {
+            return None;
+        }
+        editor.measurement.filter(Measurement::is_complete)
+    }
+
     /// Set a status message to display
     pub fn set_status(&mut self, message: impl Into<String>) {
         self.state.status_message = Some((message.into(), std::time::Instant::now()));
@@ -2097,18 +4655,243 @@ pub struct RenderStats {
     pub frame_time_ms: f32,
     pub triangles: usize,
     pub chunks: usize,
+    /// Chunk `draw_indexed` calls issued since the previous sample —
+    /// one frame, every quad-view pane included. Far below `chunks`
+    /// because back-to-back chunks share a draw (`ChunkBatch`).
+    pub draw_calls: usize,
     pub camera_pos: (f32, f32, f32),
     /// `(milliseconds, chunk count)` of the most recent dirty-chunk
-    /// re-mesh (generation + upload). `None` until the first rebuild.
+    /// re-mesh batch (submit to last upload). `None` until the first
+    /// rebuild.
     pub last_rebuild: Option<(f32, usize)>,
+    /// Chunks queued on the background mesh worker right now.
+    pub meshing: usize,
+    /// `(accumulated, max)` samples per pixel of the path-traced
+    /// preview. `None` when the mode is off or there's nothing to trace.
+    pub path_trace: Option<(u32, u32)>,
+    /// Scene layers, bottom first, for the Layers panel.
+    pub layers: Vec<LayerSummary>,
+    /// Index into `layers` of the layer edits go to.
+    pub active_layer: usize,
+    /// Animation frames in timeline order, for the Timeline panel.
+    pub frames: Vec<FrameSummary>,
+    /// Index into `frames` of the frame in the viewport.
+    pub current_frame: usize,
+    /// Whether the frames are being played back.
+    pub playing: bool,
+}
+
+/// What the Layers panel shows per layer. A copy rather than a borrow
+/// of `core::Layer` so the UI never holds the scene's voxel data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerSummary {
+    pub name: String,
+    pub visible: bool,
+    pub locked: bool,
+    /// The layer's chunk bounds, `None` when it's unbounded.
+    pub bounds: Option<WorldBounds>,
+}
+
+/// What the Timeline panel shows per animation frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameSummary {
+    pub name: String,
+    pub duration_ms: u32,
 }
 
-/// Preset camera views
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum CameraView {
-    Top,
-    Front,
-    Side,
+/// Export dialog name for a [`Pivot`].
+fn pivot_label(pivot: Pivot) -> &'static str {
+    match pivot {
+        Pivot::Origin => "Model origin",
+        Pivot::BaseCenter => "Base center",
+        Pivot::Center => "Center",
+    }
+}
+
+/// Tools panel shape picker for Place / Remove / Paint and its
+/// surface-only options. With the stamp shape, also the loaded stamp
+/// and a button to load one; returns whether that was clicked.
+fn brush_shape_ui(ui: &mut egui::Ui, brush: &mut BrushSettings) -> bool {
+    ui.horizontal(|ui| {
+        for shape in BrushShape::ALL {
+            ui.selectable_value(&mut brush.shape, shape, shape.label());
+        }
+    })
+    .response
+    .on_hover_text(
+        "Disc lies flat on the face under the cursor; Stamp writes a \
+         small .vox model in its own colors",
+    );
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut brush.surface_snap, "Surface snap").on_hover_text(
+            "Place only fills empty cells resting on the model and Paint \
+             only touches voxels facing you, so drags stay on the surface",
+        );
+        ui.checkbox(&mut brush.top_layer_only, "Top layer only")
+            .on_hover_text("Paint and Remove only touch the model's outer layer of voxels");
+    });
+    if brush.shape != BrushShape::Stamp {
+        return false;
+    }
+    let mut load = false;
+    ui.horizontal(|ui| {
+        match &brush.stamp {
+            Some(stamp) => {
+                let (x, y, z) = stamp.size();
+                ui.label(format!("{} ({}×{}×{})", stamp.name, x, y, z));
+            }
+            None => {
+                ui.label(egui::RichText::new("No stamp loaded").weak());
+            }
+        }
+        load = ui.button("Load Stamp…").clicked();
+    });
+    load
+}
+
+/// Tools panel controls for the brush's [`PaintSettings`]: the pattern
+/// picker plus the active pattern's options. Applies to Place and
+/// Paint; the other tools ignore it.
+fn paint_mode_ui(ui: &mut egui::Ui, paint: &mut PaintSettings) {
+    egui::ComboBox::from_id_salt("paint_pattern")
+        .selected_text(paint.pattern.label())
+        .show_ui(ui, |ui| {
+            for pattern in PaintPattern::ALL {
+                ui.selectable_value(&mut paint.pattern, pattern, pattern.label());
+            }
+        })
+        .response
+        .on_hover_text("How Place and Paint color each cell they write");
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut paint.soft, "Soft")
+            .on_hover_text(
+                "Paint blends into the existing colors, strongest at the \
+                 brush center and fading toward its edge",
+            );
+        if paint.soft {
+            ui.add(
+                egui::Slider::new(&mut paint.soft_strength, 1..=100)
+                    .text("Strength")
+                    .suffix("%"),
+            );
+        }
+    });
+    let second_color = |ui: &mut egui::Ui, second: &mut [u8; 4], label: &str| {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            let mut color = second.map(|c| c as f32 / 255.0);
+            if ui.color_edit_button_rgba_unmultiplied(&mut color).changed() {
+                *second = color.map(|c| (c * 255.0).round() as u8);
+            }
+        });
+    };
+    match paint.pattern {
+        PaintPattern::Solid => {}
+        PaintPattern::Gradient => {
+            second_color(ui, &mut paint.second, "To color");
+            ui.horizontal(|ui| {
+                ui.label("Along");
+                for (axis, label) in [(Axis::X, "X"), (Axis::Y, "Y"), (Axis::Z, "Z")] {
+                    ui.radio_value(&mut paint.axis, axis, label);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("From");
+                ui.add(egui::DragValue::new(&mut paint.gradient_start).speed(0.25));
+                ui.label("to");
+                ui.add(egui::DragValue::new(&mut paint.gradient_end).speed(0.25));
+            })
+            .response
+            .on_hover_text("World coordinates where the blend starts and ends");
+        }
+        PaintPattern::Jitter => {
+            ui.add(
+                egui::Slider::new(&mut paint.jitter_brightness, 0..=100)
+                    .text("Brightness ±")
+                    .suffix("%"),
+            );
+            ui.add(egui::Slider::new(&mut paint.jitter_hue, 0..=180).text("Hue ±").suffix("°"));
+            if ui
+                .button("Reseed")
+                .on_hover_text("Roll a different random pattern")
+                .clicked()
+            {
+                paint.seed = paint.seed.wrapping_add(1);
+            }
+        }
+        PaintPattern::Checker => {
+            second_color(ui, &mut paint.second, "Other color");
+            ui.add(egui::Slider::new(&mut paint.checker_size, 1..=8).text("Square size"));
+        }
+        PaintPattern::Dither => {
+            second_color(ui, &mut paint.second, "Other color");
+            ui.add(
+                egui::Slider::new(&mut paint.dither_mix, 0..=100)
+                    .text("Mix")
+                    .suffix("%"),
+            );
+        }
+    }
+}
+
+/// Progress row for a running procgen job: label, bar with a rough
+/// ETA, and Cancel. Draws nothing when no job is running. Shared by
+/// the procgen and graph panels — either can cancel whichever job is
+/// active — and by the Turntable window for its render. Returns true
+/// when Cancel was clicked.
+fn gen_job_ui(ui: &mut egui::Ui, job: Option<&GenJobStatus>) -> bool {
+    let Some(job) = job else {
+        return false;
+    };
+    let mut cancel = false;
+    ui.separator();
+    let text = match (job.cancelling, job.remaining) {
+        (true, _) => format!("{}: cancelling…", job.label),
+        (false, Some(left)) if left.as_secs_f32() >= 1.0 => {
+            format!("{}: ~{:.0}s left", job.label, left.as_secs_f32().ceil())
+        }
+        (false, _) => format!("{}: generating…", job.label),
+    };
+    ui.label(text);
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::ProgressBar::new(job.progress)
+                .show_percentage()
+                .desired_width(160.0),
+        );
+        if ui
+            .add_enabled(!job.cancelling, egui::Button::new("Cancel"))
+            .clicked()
+        {
+            cancel = true;
+        }
+    });
+    cancel
+}
+
+/// Apply / Discard row for staged generator output. Draws nothing
+/// when nothing is staged. Returns the action for the clicked button.
+fn staged_ui(ui: &mut egui::Ui, staged: Option<&StagedStatus>) -> Option<UiAction> {
+    let staged = staged?;
+    let mut action = None;
+    ui.separator();
+    ui.label(format!(
+        "Staged: {} ({} voxels)",
+        staged.label, staged.voxels
+    ));
+    ui.horizontal(|ui| {
+        if ui
+            .button("Apply")
+            .on_hover_text("Write the staged voxels into the active layer (undo-able)")
+            .clicked()
+        {
+            action = Some(UiAction::ApplyStagedGeneration);
+        }
+        if ui.button("Discard").clicked() {
+            action = Some(UiAction::DiscardStagedGeneration);
+        }
+    });
+    action
 }
 
 // ---- Procgen panel parameter editors ---------------------------------
@@ -2172,6 +4955,41 @@ fn terrain_params_ui(ui: &mut egui::Ui, t: &mut PerlinTerrain) {
         t.depth,
         (t.max_height - t.min_height).max(0)
     ));
+
+    ui.collapsing("Erosion", |ui| erosion_params_ui(ui, &mut t.erosion));
+}
+
+fn erosion_params_ui(ui: &mut egui::Ui, e: &mut ErosionSettings) {
+    ui.checkbox(&mut e.enabled, "Erode generated terrain")
+        .on_hover_text("Hydraulic (rain droplets) then thermal (slope slumping) erosion");
+    egui::Grid::new("erosion_params")
+        .num_columns(2)
+        .spacing([10.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("Iterations");
+            ui.add(egui::Slider::new(&mut e.iterations, 0..=32))
+                .on_hover_text("Rain passes — one droplet per column each");
+            ui.end_row();
+
+            ui.label("Rain");
+            ui.add(egui::Slider::new(&mut e.rain_amount, 0.1..=4.0))
+                .on_hover_text("Water per droplet — more water cuts deeper channels");
+            ui.end_row();
+
+            ui.label("Sediment cap.");
+            ui.add(egui::Slider::new(&mut e.sediment_capacity, 0.5..=16.0))
+                .on_hover_text("How much soil a droplet can carry");
+            ui.end_row();
+
+            ui.label("Thermal passes");
+            ui.add(egui::Slider::new(&mut e.thermal_iterations, 0..=64));
+            ui.end_row();
+
+            ui.label("Talus");
+            ui.add(egui::Slider::new(&mut e.talus, 0.5..=4.0))
+                .on_hover_text("Steepest slope (voxels per column) that doesn't slump");
+            ui.end_row();
+        });
 }
 
 fn tree_params_ui(ui: &mut egui::Ui, t: &mut LSystemTree) {
@@ -2286,6 +5104,80 @@ fn wfc_params_ui(ui: &mut egui::Ui, t: &mut WfcGenerator) {
     ));
 }
 
+fn cave_params_ui(ui: &mut egui::Ui, t: &mut CaveGenerator) {
+    ui.heading(GeneratorChoice::Cave.label());
+    ui.add_space(4.0);
+
+    egui::Grid::new("cave_params")
+        .num_columns(2)
+        .spacing([10.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("Seed");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut t.seed).speed(1.0));
+                if ui
+                    .button("Rand")
+                    .on_hover_text("Randomize seed")
+                    .clicked()
+                {
+                    t.seed = rand::random();
+                }
+            });
+            ui.end_row();
+
+            ui.label("Width");
+            ui.add(egui::Slider::new(&mut t.width, 8..=128));
+            ui.end_row();
+
+            ui.label("Height");
+            ui.add(egui::Slider::new(&mut t.height, 8..=128));
+            ui.end_row();
+
+            ui.label("Depth");
+            ui.add(egui::Slider::new(&mut t.depth, 8..=128));
+            ui.end_row();
+
+            ui.label("Origin");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut t.origin.0).prefix("x:"));
+                ui.add(egui::DragValue::new(&mut t.origin.1).prefix("y:"));
+                ui.add(egui::DragValue::new(&mut t.origin.2).prefix("z:"));
+            });
+            ui.end_row();
+
+            ui.label("Fill")
+                .on_hover_text("Chance each cell starts as rock — lower opens bigger caverns");
+            ui.add(egui::Slider::new(&mut t.fill_probability, 0.3..=0.7));
+            ui.end_row();
+
+            ui.label("Smoothing");
+            ui.add(egui::Slider::new(&mut t.smoothing_iterations, 0..=10));
+            ui.end_row();
+
+            ui.label("Threshold")
+                .on_hover_text("Rock neighbors (of 26) a cell needs to stay rock — higher carves wider");
+            ui.add(egui::Slider::new(&mut t.rock_threshold, 9..=18));
+            ui.end_row();
+
+            ui.label("Connectivity");
+            ui.checkbox(&mut t.keep_largest, "Keep largest cavern")
+                .on_hover_text("Refill isolated pockets so one connected tunnel system remains");
+            ui.end_row();
+
+            ui.label("Rock");
+            color_button_u8(ui, &mut t.rock_color);
+            ui.end_row();
+        });
+
+    let cells = t.width as u64 * t.height as u64 * t.depth as u64;
+    if cells > crate::procgen::CAVE_MAX_CELLS {
+        ui.colored_label(
+            egui::Color32::from_rgb(230, 120, 80),
+            format!("{} cells — over the 128³ limit", cells),
+        );
+    }
+}
+
 // =============================================================
 // Visual graph editor: layout constants + helpers
 // =============================================================
@@ -2296,13 +5188,16 @@ const NODE_HEADER_H: f32 = 22.0;
 const SOCKET_R: f32 = 6.0;
 const SOCKET_HIT_R: f32 = SOCKET_R + 4.0;
 
+/// One "+ Add Node" menu entry: (label, factory, separator_after).
+type NodeMenuOption = (&'static str, fn() -> NodeKind, bool);
+
 /// Available node kinds in the "+ Add Node" menu.
-/// Tuple is (label, factory, separator_after).
-fn node_menu_options() -> Vec<(&'static str, fn() -> NodeKind, bool)> {
+fn node_menu_options() -> Vec<NodeMenuOption> {
     vec![
         ("Source: Terrain", || NodeKind::Terrain(PerlinTerrain::default()), false),
         ("Source: Tree", || NodeKind::Tree(LSystemTree::default()), false),
-        ("Source: WFC", || NodeKind::Wfc(WfcGenerator::default()), true),
+        ("Source: WFC", || NodeKind::Wfc(WfcGenerator::default()), false),
+        ("Source: Caves", || NodeKind::Cave(CaveGenerator::default()), true),
         (
             "Translate",
             || NodeKind::Translate { input: None, dx: 0, dy: 0, dz: 0 },
@@ -2345,6 +5240,7 @@ fn node_header_color(kind: &NodeKind) -> egui::Color32 {
         NodeKind::Terrain(_) => egui::Color32::from_rgb(70, 110, 60),
         NodeKind::Tree(_) => egui::Color32::from_rgb(60, 100, 60),
         NodeKind::Wfc(_) => egui::Color32::from_rgb(100, 90, 50),
+        NodeKind::Cave(_) => egui::Color32::from_rgb(90, 80, 70),
         NodeKind::Translate { .. } => egui::Color32::from_rgb(70, 80, 110),
         NodeKind::Filter { .. } => egui::Color32::from_rgb(80, 100, 110),
         NodeKind::Mask { .. } => egui::Color32::from_rgb(90, 110, 130),
@@ -2365,6 +5261,9 @@ fn node_summary(kind: &NodeKind) -> String {
         NodeKind::Wfc(t) => {
             format!("seed {} • {}×{}", t.seed, t.width, t.depth)
         }
+        NodeKind::Cave(t) => {
+            format!("seed {} • {}×{}×{}", t.seed, t.width, t.height, t.depth)
+        }
         NodeKind::Translate { dx, dy, dz, .. } => {
             format!("offset ({}, {}, {})", dx, dy, dz)
         }
@@ -2761,6 +5660,7 @@ fn graph_sidebar(
             NodeKind::Terrain(t) => terrain_params_ui(ui, t),
             NodeKind::Tree(t) => tree_params_ui(ui, t),
             NodeKind::Wfc(t) => wfc_params_ui(ui, t),
+            NodeKind::Cave(t) => cave_params_ui(ui, t),
             NodeKind::Translate { input, dx, dy, dz } => {
                 input_slot(ui, "Input", input, &candidates, id);
                 ui.horizontal(|ui| {
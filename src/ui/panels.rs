@@ -2,9 +2,12 @@
 
 use std::path::PathBuf;
 
-use crate::editor::{Axis, Quarter};
+use crate::core::{LightKind, WorldBounds};
+use crate::editor::{
+    Axis, BonePose, ColorAdjust, OriginAnchor, Quarter, RecolorScope, Scale, Selection, Tool,
+};
 
-use super::CameraView;
+use crate::render::CameraView;
 
 /// One-shot UI actions that need to be processed by the application.
 ///
@@ -17,25 +20,62 @@ pub enum UiAction {
     OpenProject,
     /// Open a specific path from the recent-files MRU.
     OpenRecent(PathBuf),
+    /// Empty the recent-files list.
+    ClearRecent,
+    /// Write the prefs file now instead of waiting for exit.
+    SavePrefs,
+    /// Drop the remembered file-dialog folders.
+    ForgetLastDirs,
     SaveProject,
     SaveAs,
     ImportVox,
     ExportVox,
+    /// Import a Qubicle `.qb` / `.qbt`, one layer per matrix.
+    ImportQubicle,
+    /// Export visible layers as Qubicle `.qb`.
+    ExportQb,
+    /// Export visible layers as Qubicle `.qbt`.
+    ExportQbt,
+    /// Import a stack of per-Y-layer PNG slices.
+    ImportPngSlices,
+    /// Export the visible scene as per-Y-layer PNG slices.
+    ExportPngSlices,
+    /// Import a grayscale heightmap PNG as terrain.
+    ImportHeightmap,
+    /// Open the Export dialog with Wavefront OBJ selected.
     ExportObj,
-    /// MC smoothed OBJ, no blur — preserves thin features
-    ExportObjSmoothedLight,
-    /// MC smoothed OBJ, 3×3×3 blur — clay-like, may dissolve thin features
-    ExportObjSmoothedHeavy,
+    /// Open the Export dialog with glTF Binary selected.
     ExportGlb,
-    /// MC smoothed GLB, no blur
-    ExportGlbSmoothedLight,
-    /// MC smoothed GLB, 3×3×3 blur
-    ExportGlbSmoothedHeavy,
+    /// The Export dialog's Export… button: ask for a path and write the
+    /// visible scene per `Ui::mesh_export`.
+    ExportMesh,
+    /// Replace the editor palette from a `.gpl` / `.pal` file, a
+    /// `.vox`'s palette or a `.png` swatch strip.
+    ImportPalette,
+    /// Save the editor palette as `.gpl` / `.pal`.
+    ExportPalette,
+    /// Ask for a path and write the scene with the registered exporter
+    /// plugin with this id ([`crate::plugin::ExporterPlugin`]).
+    ExportPlugin(&'static str),
+    /// Write every animation frame to numbered `.vox` files
+    /// (`<stem>_000.vox`, `<stem>_001.vox`, ...).
+    ExportFramesVox,
+    /// Same as `ExportFramesVox`, as greedy-meshed `.obj` files.
+    ExportFramesObj,
+    /// Every animation frame into one `.glb` with a playable flipbook
+    /// animation (see `io::export_glb_animation`).
+    ExportFramesGlb,
+    /// The model as one `.glb` node per rig bone, nested by parent,
+    /// with the pose in the node transforms (see `io::export_glb_parts`).
+    ExportRiggedGlb,
     Exit,
 
     // Edit operations
     Undo,
     Redo,
+    /// Undo / redo until this many history entries are applied
+    /// (History panel click).
+    JumpToHistory(usize),
     ClearAll,
 
     // Selection / clipboard operations
@@ -45,6 +85,8 @@ pub enum UiAction {
     /// selection exists). Ctrl+Shift+V's "always paste at cursor"
     /// is keyboard-only — UI buttons go through this default path.
     PasteClipboard,
+    /// Enter paste placement (ghost follows the cursor, click commits)
+    BeginPastePlacement,
     DeleteSelection,
     /// Set the selection to the AABB of every non-air voxel in
     /// the world.
@@ -53,14 +95,112 @@ pub enum UiAction {
     /// Deselect). Mirror of `editor.selection = None` for menu-
     /// bar contexts that don't get `&mut Editor`.
     Deselect,
-    /// Rotate the selection's voxel contents around `axis` by
-    /// `quarter` (90° / -90° / 180°). Anchor is `selection.min`;
-    /// the AABB may swap dimensions but its `min` corner stays put.
-    /// One Ctrl+Z reverses the entire rotation.
-    RotateSelection { axis: Axis, quarter: Quarter },
-    /// Mirror the selection's voxel contents across the midplane
-    /// perpendicular to `axis`. AABB unchanged.
-    MirrorSelection { axis: Axis },
+    /// Make `Tool` the current tool (digit keys, command palette).
+    SelectTool(Tool),
+    /// Rotate the selection's voxel contents — or, with nothing
+    /// selected, the active layer's whole model — around `axis` by
+    /// `quarter` (90° / -90° / 180°). Anchor is the box's `min`; the
+    /// AABB may swap dimensions but its `min` corner stays put. One
+    /// Ctrl+Z reverses the entire rotation.
+    Rotate { axis: Axis, quarter: Quarter },
+    /// Mirror the selection's voxel contents (or the active layer's
+    /// whole model) across the midplane perpendicular to `axis`. AABB
+    /// unchanged.
+    Mirror { axis: Axis },
+    /// Scale the selection's voxel contents (or the active layer's
+    /// whole model) up or down by an integer factor, `min` anchored.
+    Scale { scale: Scale },
+    /// Translate the selection's voxel contents (or the active layer's
+    /// whole model) by `delta` cells — Edit ▸ Transform ▸ Move….
+    Move { delta: (i32, i32, i32) },
+    /// Shift the active layer's whole model so its bounding-box center
+    /// (or base) sits on the world origin.
+    CenterOnOrigin { anchor: OriginAnchor },
+    /// Fill the Crop dialog's box from the selection, or the active
+    /// layer's model bounds when nothing is selected.
+    FitCropBox,
+    /// Clear the active layer's voxels outside `keep` (undoable). With
+    /// `bound_layer`, also bound the layer to `keep` rounded out to
+    /// whole chunks — which drops the layer's undo history.
+    Crop { keep: Selection, bound_layer: bool },
+    /// Lift the active layer's bounds so it can grow without limit.
+    UnboundLayer,
+    /// Bound every layer to the same box, or unbound them all with
+    /// `None` — File ▸ Project Settings…. Voxels outside are deleted,
+    /// which drops every undo history, so that asks first.
+    SetWorldBounds(Option<WorldBounds>),
+    /// Turn every voxel colored `from` (RGBA) in `scope` into the brush
+    /// voxel — Edit ▸ Color ▸ Replace…. One undoable edit.
+    ReplaceColor { from: [u8; 4], scope: RecolorScope },
+    /// Shift hue / saturation / brightness of the selection's voxels,
+    /// or the active layer's whole model. One undoable edit.
+    AdjustColors(ColorAdjust),
+    /// Average each voxel's color with its solid neighbors, over the
+    /// selection or the active layer's whole model, `iterations`
+    /// times. One undoable edit.
+    SmoothColors { iterations: u32 },
+    /// Slice Editor brush dab over these work-plane cells. `begin`
+    /// opens a new stroke; later dabs merge into its undo entry until
+    /// `SliceStrokeEnd`, like a viewport drag.
+    SlicePaint { cells: Vec<(i32, i32, i32)>, begin: bool },
+    /// The Slice Editor brush stroke was released.
+    SliceStrokeEnd,
+    /// Slice Editor flood fill from this cell, kept to the slice.
+    SliceFill((i32, i32, i32)),
+    /// Slice Editor eyedropper: take this cell's color as the brush.
+    SlicePick((i32, i32, i32)),
+
+    // Layer operations (indices are bottom-first, as in `core::Scene`)
+    /// Insert a new empty layer above the active one and select it.
+    AddLayer,
+    /// Make the layer at this index the edit target.
+    SelectLayer(usize),
+    RenameLayer(usize, String),
+    SetLayerVisible(usize, bool),
+    SetLayerLocked(usize, bool),
+    /// Restack: the layer at `from` ends up at index `to`.
+    MoveLayer { from: usize, to: usize },
+    /// Fold the layer into the one below it (upper voxels win).
+    MergeLayerDown(usize),
+    RemoveLayer(usize),
+
+    // Animation frames (indices are timeline order, as in `core::Scene`)
+    /// Insert an empty frame after the current one and show it.
+    AddFrame,
+    /// Insert a copy of the current frame after it and show it.
+    DuplicateFrame,
+    /// Show the frame at this index in the viewport (the edit target).
+    SelectFrame(usize),
+    RenameFrame(usize, String),
+    /// How long the frame at this index is held during playback, in ms.
+    SetFrameDuration(usize, u32),
+    /// Reorder: the frame at `from` ends up at index `to`.
+    MoveFrame { from: usize, to: usize },
+    RemoveFrame(usize),
+    /// Start / stop looping playback of the frames.
+    TogglePlayback,
+    PreviousFrame,
+    NextFrame,
+
+    // Rig operations (indices are bone order, as in `editor::Rig`)
+    /// New bone owning the active layer's selected voxels, pivoting
+    /// about the selection's center.
+    AddBone,
+    /// Hand the selected voxels to the bone at this index.
+    AssignSelectionToBone(usize),
+    /// Select the bone's voxels (as a selection mask).
+    SelectBoneVoxels(usize),
+    RenameBone(usize, String),
+    /// Re-parent a bone; `None` hangs it off the body.
+    SetBoneParent(usize, Option<usize>),
+    SetBonePivot(usize, [i32; 3]),
+    SetBonePose(usize, BonePose),
+    RemoveBone(usize),
+    /// Put every bone back in its rest pose.
+    ResetPose,
+    /// Write the current pose into the voxels (one undo step) and make
+    /// it the rest pose.
+    BakePose,
 
     // Generate operations
     GenerateTestCube,
@@ -68,14 +208,85 @@ pub enum UiAction {
     GenerateSphere,
     GeneratePyramid,
     /// Run the procgen panel's currently-selected generator (terrain
-    /// / tree / ...) and apply the result via CommandHistory (undo-able).
+    /// / tree / ...) on a worker thread; the result is staged for
+    /// review, or applied via CommandHistory (undo-able) when
+    /// `ProcgenSettings::apply_immediately` is set.
     GenerateProcedural,
-    /// Run the pipeline graph and apply its output via CommandHistory.
+    /// Run the pipeline graph on a worker thread; its output is staged
+    /// or applied the same way as `GenerateProcedural`.
     RunGraph,
+    /// Run the registered generator plugin with this id
+    /// ([`crate::plugin::GeneratorPlugin`]) with a fresh seed; stages or
+    /// applies like `GenerateProcedural`.
+    RunPluginGenerator(&'static str),
+    /// Erode the terrain surface inside the selection with the terrain
+    /// generator's erosion settings; runs and stages like a generation.
+    ErodeSelection,
+    /// Cancel the running procedural generation / graph run.
+    CancelGeneration,
+    /// Commit the staged generator / graph output as one undo step.
+    ApplyStagedGeneration,
+    /// Drop the staged generator / graph output without writing it.
+    DiscardStagedGeneration,
+
+    /// Render ▸ Screenshot: ask for a PNG path and render the current
+    /// view there at the Screenshot window's size.
+    SaveScreenshot,
+    /// Render ▸ Sprite Sheet: ask for a PNG path (or a folder for
+    /// separate frames) and render the sprite batch there.
+    ExportSprites,
+    /// Lights panel: add a light of this kind above the orbit center
+    /// and select it.
+    AddLight(LightKind),
+    /// Lights panel: move the selected light to the camera, aimed
+    /// along the view.
+    LightFromView,
+    /// Viewport Settings ▸ Background: ask for an equirectangular image
+    /// to show behind the scene.
+    PickBackgroundImage,
+    /// References panel: ask for an image to add as a new plane.
+    AddReferenceImage,
+    /// References panel: ask for a .vox or .obj to show as the ghost
+    /// model, replacing any before.
+    LoadReferenceModel,
+    /// Tools panel: ask for a .vox to use as the stamp brush.
+    LoadBrushStamp,
+    /// Render ▸ Turntable: ask for an output location, then render
+    /// one frame per app frame until the turn is done.
+    StartTurntable,
+    /// Stop the running turntable render.
+    CancelTurntable,
+    /// Render ▸ Camera Path: ask for an output folder, then render the
+    /// fly-through as a PNG sequence, one frame per app frame.
+    StartFlythrough,
+    /// Stop the running fly-through render.
+    CancelFlythrough,
+
+    // Camera path (indices are key order, as in `render::CameraPath`)
+    /// Insert a key from the current view after the selected key (or
+    /// at the end) and select it.
+    AddCameraKey,
+    /// Replace the key's position and target with the current view.
+    UpdateCameraKey(usize),
+    /// Move the viewport camera to the key.
+    GoToCameraKey(usize),
+    /// Time from the key on to the next, in milliseconds.
+    SetCameraKeyDuration(usize, u32),
+    /// Reorder: the key at `from` ends up at index `to`.
+    MoveCameraKey { from: usize, to: usize },
+    RemoveCameraKey(usize),
+    SetCameraPathClosed(bool),
+    /// Start / stop flying the viewport camera along the path.
+    ToggleCameraPathPreview,
 
     // Camera operations
     ResetCamera,
     SetCameraView(CameraView),
+    /// Store the current camera (position, target, ortho zooms) in
+    /// bookmark slot `0..CAMERA_BOOKMARK_SLOTS`; saved with the project.
+    SaveCameraBookmark(usize),
+    /// Move the camera to the view stored in a bookmark slot.
+    RecallCameraBookmark(usize),
     /// Fit the camera to an AABB — center the target and pull back to
     /// the fit distance, keeping the current viewing angle. Three
     /// targets: the whole scene, the active selection, or the most
@@ -89,6 +300,15 @@ pub enum UiAction {
     RecoverAutosave,
     /// Discard the on-disk autosave and keep the fresh default scene.
     DiscardAutosave,
+    /// Large-fill prompt: run the held fill past the voxel cap.
+    ConfirmLargeFill,
+    /// Large-fill prompt: drop the held fill.
+    CancelLargeFill,
+    /// World-bounds prompt: apply the held bounds, deleting the voxels
+    /// outside them.
+    ConfirmWorldBounds,
+    /// World-bounds prompt: drop the held bounds.
+    CancelWorldBounds,
 
     // AI operations
     /// Submit a new AI generation job using the current `ai_prompt` /
@@ -165,7 +385,7 @@ pub fn group_thousands(n: usize) -> String {
     let len = s.len();
     let mut out = String::with_capacity(len + len / 3);
     for (i, ch) in s.chars().enumerate() {
-        if i > 0 && (len - i) % 3 == 0 {
+        if i > 0 && (len - i).is_multiple_of(3) {
             out.push(',');
         }
         out.push(ch);
@@ -186,12 +406,42 @@ pub struct UiState {
     pub show_help: bool,
     pub show_about: bool,
     pub show_ai: bool,
+    pub show_layers: bool,
+    pub show_turntable: bool,
+    pub show_camera_path: bool,
+    pub show_screenshot: bool,
+    pub show_sprites: bool,
+    pub show_export: bool,
+    pub show_model_info: bool,
+    pub show_history: bool,
+    pub show_plugins: bool,
+    pub show_timeline: bool,
+    pub show_rig: bool,
+    pub show_slice_view: bool,
+    pub show_preferences: bool,
+    pub show_materials: bool,
+    pub show_lights: bool,
+    pub show_references: bool,
+    pub show_move: bool,
+    pub show_crop: bool,
+    pub show_project_settings: bool,
+    pub show_replace_color: bool,
+    pub show_adjust_colors: bool,
+    pub show_smooth_colors: bool,
 
     /// Crash-recovery prompt: an in-app egui dialog (NOT a native rfd
     /// modal — `rfd::MessageDialog` exits the process on this winit+wgpu
     /// setup). Set true at startup when an autosave is on disk; cleared
     /// when the user picks Recover or Discard.
     pub show_recovery_prompt: bool,
+    /// Large-fill prompt (in-app egui, like `show_recovery_prompt`):
+    /// set when a Fill click would write more than `MAX_FILL_VOXELS`,
+    /// cleared by Fill / Cancel.
+    pub show_large_fill_prompt: bool,
+    /// World-bounds prompt (in-app egui, like `show_large_fill_prompt`):
+    /// `Some(voxels that would be deleted)` while Project Settings'
+    /// Apply waits on a confirmation, cleared by Delete / Cancel.
+    pub world_bounds_prompt: Option<u64>,
 
     /// Active file-operation error, shown as an in-app egui dialog
     /// (`(title, detail)`). Same reason as `show_recovery_prompt`: a
@@ -216,6 +466,33 @@ pub struct UiState {
     /// moved out into a `UiAction::AiSaveKey(_)` and the buffer is
     /// cleared.
     pub ai_key_input: String,
+
+    /// Inline rename in the Layers panel: `(layer index, edit buffer)`
+    /// while a name is being edited. Committed as
+    /// `UiAction::RenameLayer` on Enter / focus loss.
+    pub layer_rename: Option<(usize, String)>,
+
+    /// Inline rename in the Timeline panel: `(frame index, edit buffer)`,
+    /// committed as `UiAction::RenameFrame` like `layer_rename`.
+    pub frame_rename: Option<(usize, String)>,
+
+    /// Draw the previous and next animation frames as translucent
+    /// ghosts around the current one. Read by the App each frame.
+    pub onion_skin: bool,
+
+    /// Inline rename in the Rig panel: `(bone index, edit buffer)`,
+    /// committed as `UiAction::RenameBone` like `layer_rename`.
+    pub bone_rename: Option<(usize, String)>,
+
+    /// Bone whose pivot and pose the Rig panel is editing.
+    pub selected_bone: Option<usize>,
+
+    /// Draw posed bones as a ghost over the model. Read by the App
+    /// each frame.
+    pub pose_preview: bool,
+
+    /// Key the Camera Path window is editing.
+    pub selected_camera_key: Option<usize>,
 }
 
 impl UiState {
@@ -230,12 +507,43 @@ impl UiState {
             show_help: false,
             show_about: false,
             show_ai: false,
+            show_layers: true,
+            show_turntable: false,
+            show_camera_path: false,
+            show_screenshot: false,
+            show_sprites: false,
+            show_export: false,
+            show_model_info: false,
+            show_history: false,
+            show_plugins: false,
+            show_timeline: false,
+            show_rig: false,
+            show_slice_view: false,
+            show_preferences: false,
+            show_materials: false,
+            show_lights: false,
+            show_references: false,
+            show_move: false,
+            show_crop: false,
+            show_project_settings: false,
+            show_replace_color: false,
+            show_adjust_colors: false,
+            show_smooth_colors: false,
             show_recovery_prompt: false,
+            show_large_fill_prompt: false,
+            world_bounds_prompt: None,
             error_dialog: None,
             export_report: None,
             pending_actions: Vec::new(),
             status_message: None,
             ai_key_input: String::new(),
+            layer_rename: None,
+            frame_rename: None,
+            onion_skin: false,
+            bone_rename: None,
+            selected_bone: None,
+            pose_preview: true,
+            selected_camera_key: None,
         }
     }
 
@@ -0,0 +1,114 @@
+//! Registered plugins in the UI: the Help ▸ Plugins window and the
+//! Generate ▸ Plugins / File ▸ Export menu entries.
+//!
+//! The App hands over [`PluginInfo`] rows once at startup
+//! ([`Ui::set_plugins`]); running one queues a
+//! [`UiAction::RunPluginGenerator`] / [`UiAction::ExportPlugin`] with the
+//! plugin's id, and the App looks it up in the registry.
+
+use egui::Context;
+
+use super::commands::{Command, CommandTarget};
+use super::{Ui, UiAction};
+use crate::plugin::{PluginInfo, PluginKind};
+
+impl Ui {
+    /// List `plugins` in the menus, the command palette and Help ▸
+    /// Plugins.
+    pub fn set_plugins(&mut self, plugins: Vec<PluginInfo>) {
+        for info in &plugins {
+            let (category, action) = match info.kind {
+                PluginKind::Generator => ("Generate", UiAction::RunPluginGenerator(info.id)),
+                PluginKind::Exporter => ("Export", UiAction::ExportPlugin(info.id)),
+            };
+            self.commands.push(Command::new(
+                category,
+                info.name,
+                CommandTarget::Action(action),
+            ));
+        }
+        self.plugins = plugins;
+    }
+
+    /// Generate ▸ Plugins submenu. Disabled while a generation runs,
+    /// like the procgen panel's Generate button.
+    pub(super) fn plugin_generator_items(&mut self, ui: &mut egui::Ui) {
+        let generators: Vec<PluginInfo> = self
+            .plugins
+            .iter()
+            .filter(|p| p.kind == PluginKind::Generator)
+            .cloned()
+            .collect();
+        if generators.is_empty() {
+            return;
+        }
+        let idle = self.gen_job.is_none();
+        ui.menu_button("Plugins", |ui| {
+            for info in generators {
+                let target = CommandTarget::Action(UiAction::RunPluginGenerator(info.id));
+                self.command_item(ui, target, idle)
+                    .on_hover_text(info.description);
+            }
+        });
+    }
+
+    /// File ▸ Export entries for exporter plugins, after a separator.
+    pub(super) fn plugin_exporter_items(&mut self, ui: &mut egui::Ui) {
+        let exporters: Vec<PluginInfo> = self
+            .plugins
+            .iter()
+            .filter(|p| p.kind == PluginKind::Exporter)
+            .cloned()
+            .collect();
+        if exporters.is_empty() {
+            return;
+        }
+        ui.separator();
+        for info in exporters {
+            let target = CommandTarget::Action(UiAction::ExportPlugin(info.id));
+            self.command_item_as(ui, target, &format!("{}...", info.name), true)
+                .on_hover_text(info.description);
+        }
+    }
+
+    pub(super) fn show_plugins_panel(&mut self, ctx: &Context) {
+        let plugins = &self.plugins;
+        egui::Window::new("Plugins")
+            .default_width(360.0)
+            .collapsible(false)
+            .open(&mut self.state.show_plugins)
+            .show(ctx, |ui| {
+                if plugins.is_empty() {
+                    ui.label("No plugins registered.");
+                    return;
+                }
+                egui::Grid::new("plugins_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.strong("Name");
+                        ui.strong("Kind");
+                        ui.strong("Description");
+                        ui.end_row();
+                        for info in plugins {
+                            ui.label(info.name).on_hover_text(format!("id: {}", info.id));
+                            ui.label(match info.kind {
+                                PluginKind::Generator => "Generator",
+                                PluginKind::Exporter => "Exporter",
+                            });
+                            if info.extensions.is_empty() {
+                                ui.label(info.description);
+                            } else {
+                                ui.label(format!(
+                                    "{} (.{})",
+                                    info.description,
+                                    info.extensions.join(", .")
+                                ));
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
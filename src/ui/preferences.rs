@@ -0,0 +1,360 @@
+//! Edit ▸ Preferences…: app-wide settings that aren't about the model
+//! — theme and UI scale (also under View ▸ Appearance), autosave
+//! cadence, mouse navigation speeds, streaming for huge worlds, the
+//! VOX axis mapping (also in File ▸ Import / Export) — plus a way back
+//! to the default viewport settings.
+//!
+//! Everything here lives in [`GeneralSettings`], which `prefs::Prefs`
+//! persists next to the viewport settings, so an edit takes effect at
+//! once and survives a restart without an OK / Cancel step.
+
+use std::time::Duration;
+
+use egui::{Color32, Context};
+use serde::{Deserialize, Serialize};
+
+use super::{Ui, UiAction, ViewportSettings};
+use crate::core::StreamingSettings;
+use crate::io::VoxAxes;
+use crate::prefs::Prefs;
+use crate::render::CameraSensitivity;
+
+/// Longest autosave interval the dialog offers, in minutes.
+const MAX_AUTOSAVE_MINUTES: u32 = 30;
+
+/// Largest streaming keep radius the dialog offers, in chunks.
+const MAX_KEEP_RADIUS: u32 = 64;
+
+/// Largest streaming memory budget the dialog offers, in MB.
+const MAX_BUDGET_MB: u32 = 16 * 1024;
+
+/// UI scale range offered by View ▸ Appearance, as a multiple of the
+/// display's own scale factor.
+pub const MIN_UI_SCALE: f32 = 0.75;
+pub const MAX_UI_SCALE: f32 = 2.0;
+
+/// egui color scheme.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    /// [`CustomTheme`]'s colors over a dark or light base.
+    Custom,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::Custom];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+            Theme::Custom => "Custom",
+        }
+    }
+}
+
+/// The user's own colors for [`Theme::Custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomTheme {
+    /// Start from egui's dark visuals (else light).
+    pub dark: bool,
+    /// Selection, hyperlink and active-widget highlight.
+    pub accent: [u8; 3],
+    /// Panel and window background.
+    pub background: [u8; 3],
+}
+
+impl Default for CustomTheme {
+    /// egui's dark selection and panel colors, so switching to Custom
+    /// barely changes the look until a color is edited.
+    fn default() -> Self {
+        Self {
+            dark: true,
+            accent: [0, 92, 128],
+            background: [27, 27, 27],
+        }
+    }
+}
+
+impl CustomTheme {
+    pub fn visuals(&self) -> egui::Visuals {
+        let mut visuals = if self.dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+        let [r, g, b] = self.accent;
+        let accent = Color32::from_rgb(r, g, b);
+        visuals.selection.bg_fill = accent;
+        visuals.hyperlink_color = accent;
+        visuals.widgets.active.bg_fill = accent;
+        let [r, g, b] = self.background;
+        let background = Color32::from_rgb(r, g, b);
+        visuals.panel_fill = background;
+        visuals.window_fill = background;
+        // Text edits and scroll troughs sit a step further from the
+        // text color than the panels do.
+        visuals.extreme_bg_color = if self.dark {
+            background.gamma_multiply(0.6)
+        } else {
+            background.lerp_to_gamma(Color32::WHITE, 0.5)
+        };
+        visuals
+    }
+}
+
+/// App-wide preferences edited in the Preferences window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneralSettings {
+    pub theme: Theme,
+    pub custom_theme: CustomTheme,
+    /// Size of all UI text and widgets, on top of the display's scale
+    /// factor; `MIN_UI_SCALE..=MAX_UI_SCALE`.
+    pub ui_scale: f32,
+    /// Minutes between crash-recovery autosaves while there are
+    /// unsaved changes; 0 turns autosave off. The default minute is
+    /// long enough that saving a big world doesn't hitch editing, short
+    /// enough that a crash loses little work.
+    pub autosave_minutes: u32,
+    /// Mouse orbit / pan / zoom speed multipliers.
+    pub camera: CameraSensitivity,
+    /// Cooling distant chunks of huge worlds.
+    pub streaming: StreamingSettings,
+    /// How .vox import and export map MagicaVoxel's axes.
+    pub vox_axes: VoxAxes,
+}
+
+impl Default for GeneralSettings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            custom_theme: CustomTheme::default(),
+            ui_scale: 1.0,
+            autosave_minutes: 1,
+            camera: CameraSensitivity::default(),
+            streaming: StreamingSettings::default(),
+            vox_axes: VoxAxes::default(),
+        }
+    }
+}
+
+impl GeneralSettings {
+    /// Time between autosaves, or `None` when autosave is off.
+    pub fn autosave_interval(&self) -> Option<Duration> {
+        (self.autosave_minutes > 0).then(|| Duration::from_secs(60 * self.autosave_minutes as u64))
+    }
+
+    /// egui visuals for the chosen theme.
+    pub fn visuals(&self) -> egui::Visuals {
+        match self.theme {
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light => egui::Visuals::light(),
+            Theme::Custom => self.custom_theme.visuals(),
+        }
+    }
+
+    /// `ui_scale` kept in range, for a hand-edited prefs file.
+    pub fn ui_scale(&self) -> f32 {
+        if self.ui_scale.is_finite() {
+            self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+        } else {
+            1.0
+        }
+    }
+}
+
+impl Ui {
+    /// Theme picker and UI scale slider, shared by View ▸ Appearance
+    /// and the Preferences window.
+    pub(super) fn appearance_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for theme in Theme::ALL {
+                ui.radio_value(&mut self.general.theme, theme, theme.label());
+            }
+        });
+        ui.add(
+            egui::Slider::new(&mut self.general.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE)
+                .text("UI scale")
+                .suffix("×")
+                .step_by(0.05),
+        )
+        .on_hover_text("Size of all text and controls, on top of the display's own scaling");
+    }
+
+    /// VOX axis mapping radios, shared by File ▸ Import / Export and
+    /// the Preferences window.
+    pub(super) fn vox_axes_controls(&mut self, ui: &mut egui::Ui) {
+        for axes in VoxAxes::ALL {
+            ui.radio_value(&mut self.general.vox_axes, axes, axes.label())
+                .on_hover_text(match axes {
+                    VoxAxes::ZUp => "MagicaVoxel's Z axis becomes Voxelith's up (Y), so models stand upright",
+                    VoxAxes::AsIs => "Copy .vox coordinates unchanged; MagicaVoxel models lie on their side",
+                });
+        }
+    }
+
+    pub(super) fn show_preferences_panel(&mut self, ctx: &Context) {
+        let mut open = self.state.show_preferences;
+        egui::Window::new("Preferences")
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.heading("Appearance");
+                self.appearance_controls(ui);
+                if self.general.theme == Theme::Custom {
+                    let custom = &mut self.general.custom_theme;
+                    ui.horizontal(|ui| {
+                        ui.label("Base:");
+                        ui.radio_value(&mut custom.dark, true, "Dark");
+                        ui.radio_value(&mut custom.dark, false, "Light");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.color_edit_button_srgb(&mut custom.accent);
+                        ui.label("Accent");
+                        ui.color_edit_button_srgb(&mut custom.background);
+                        ui.label("Background");
+                    });
+                }
+
+                ui.separator();
+                ui.heading("Autosave");
+                ui.add(
+                    egui::Slider::new(&mut self.general.autosave_minutes, 0..=MAX_AUTOSAVE_MINUTES)
+                        .text("Interval")
+                        .suffix(" min"),
+                )
+                .on_hover_text(
+                    "How often unsaved work is written to the crash-recovery file; 0 turns \
+                     autosave off",
+                );
+
+                ui.separator();
+                ui.heading("Camera");
+                let camera = &mut self.general.camera;
+                for (value, label, hint) in [
+                    (&mut camera.orbit, "Orbit speed", "Middle-drag rotation"),
+                    (&mut camera.pan, "Pan speed", "Right-drag panning"),
+                    (&mut camera.zoom, "Zoom speed", "Scroll-wheel zoom step"),
+                ] {
+                    ui.add(
+                        egui::Slider::new(value, 0.1..=4.0)
+                            .logarithmic(true)
+                            .text(label)
+                            .suffix("×"),
+                    )
+                    .on_hover_text(hint);
+                }
+                if ui.button("Reset Camera Speeds").clicked() {
+                    self.general.camera = CameraSensitivity::default();
+                }
+
+                ui.separator();
+                ui.heading("Large Worlds");
+                let streaming = &mut self.general.streaming;
+                ui.checkbox(&mut streaming.enabled, "Stream out distant chunks")
+                    .on_hover_text(
+                        "Compress chunks far from the camera so huge terrains fit in memory; \
+                         they're decompressed again when read",
+                    );
+                ui.add_enabled_ui(streaming.enabled, |ui| {
+                    ui.add(
+                        egui::Slider::new(&mut streaming.keep_radius, 1..=MAX_KEEP_RADIUS)
+                            .text("Keep radius")
+                            .suffix(" chunks"),
+                    )
+                    .on_hover_text("Chunks this close to the camera stay uncompressed");
+                    ui.add(
+                        egui::Slider::new(&mut streaming.budget_mb, 0..=MAX_BUDGET_MB)
+                            .logarithmic(true)
+                            .text("Memory budget")
+                            .suffix(" MB"),
+                    )
+                    .on_hover_text(
+                        "Over this much voxel memory, nearer chunks are compressed too, \
+                         farthest first; 0 means no budget",
+                    );
+                    ui.checkbox(&mut streaming.spill_to_disk, "Spill to a temporary file")
+                        .on_hover_text("Keep compressed chunks on disk instead of in memory");
+                });
+
+                ui.separator();
+                ui.heading("Viewport");
+                if ui
+                    .button("Reset Viewport Settings")
+                    .on_hover_text("Grid, mesher, lighting, clipping and camera animation")
+                    .clicked()
+                {
+                    self.viewport = ViewportSettings::default();
+                }
+
+                ui.separator();
+                ui.heading("Files");
+                ui.label("MagicaVoxel (.vox) axes:");
+                self.vox_axes_controls(ui);
+                if ui
+                    .button("Forget Last-Used Folders")
+                    .on_hover_text("File dialogs open in their default folder again")
+                    .clicked()
+                {
+                    self.state.request(UiAction::ForgetLastDirs);
+                }
+                if let Some(path) = Prefs::config_path() {
+                    ui.label(
+                        egui::RichText::new(format!("Saved to {}", path.display()))
+                            .small()
+                            .weak(),
+                    );
+                }
+                if ui
+                    .button("Save Now")
+                    .on_hover_text("Preferences are also saved on exit")
+                    .clicked()
+                {
+                    self.state.request(UiAction::SavePrefs);
+                }
+            });
+        self.state.show_preferences = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autosave_interval_is_off_at_zero() {
+        let mut settings = GeneralSettings::default();
+        assert_eq!(settings.autosave_interval(), Some(Duration::from_secs(60)));
+        settings.autosave_minutes = 5;
+        assert_eq!(settings.autosave_interval(), Some(Duration::from_secs(300)));
+        settings.autosave_minutes = 0;
+        assert_eq!(settings.autosave_interval(), None);
+    }
+
+    #[test]
+    fn custom_theme_and_ui_scale() {
+        let mut settings = GeneralSettings {
+            theme: Theme::Custom,
+            ..GeneralSettings::default()
+        };
+        // The default custom colors are egui's dark ones.
+        let dark = egui::Visuals::dark();
+        assert_eq!(settings.visuals().panel_fill, dark.panel_fill);
+        assert_eq!(settings.visuals().selection.bg_fill, dark.selection.bg_fill);
+        settings.custom_theme.accent = [200, 40, 40];
+        settings.custom_theme.dark = false;
+        let visuals = settings.visuals();
+        assert!(!visuals.dark_mode);
+        assert_eq!(visuals.selection.bg_fill, Color32::from_rgb(200, 40, 40));
+
+        settings.ui_scale = 5.0;
+        assert_eq!(settings.ui_scale(), MAX_UI_SCALE);
+        settings.ui_scale = f32::NAN;
+        assert_eq!(settings.ui_scale(), 1.0);
+    }
+}
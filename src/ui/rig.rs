@@ -0,0 +1,196 @@
+//! View ▸ Rig: bones that pose parts of the model.
+//!
+//! A bone is made from the selected voxels of the active layer, then
+//! picked in the list (indented under its parent) to edit its parent,
+//! pivot and pose. Poses are previewed as a ghost until baked into the
+//! voxels. Like the Layers panel, everything is queued as
+//! [`UiAction`]s for the App.
+
+use egui::Context;
+
+use super::{Ui, UiAction};
+use crate::editor::{BonePose, Editor};
+
+impl Ui {
+    pub(super) fn show_rig_panel(&mut self, ctx: &Context, editor: &Editor) {
+        // Same deferral as the Layers panel: the window borrows
+        // `show_rig`, so actions are requested after it closes.
+        let mut actions = Vec::new();
+        let rig = &editor.rig;
+        let bones = rig.bones();
+        let has_selection = editor.selection.is_some();
+        let rename = &mut self.state.bone_rename;
+        let preview = &mut self.state.pose_preview;
+        let selected = &mut self.state.selected_bone;
+        if selected.is_some_and(|i| i >= bones.len()) {
+            *selected = None;
+        }
+        egui::Window::new("Rig")
+            .default_width(260.0)
+            .open(&mut self.state.show_rig)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(has_selection, egui::Button::new("Add Bone"))
+                        .on_hover_text("New bone from the selected voxels")
+                        .clicked()
+                    {
+                        actions.push(UiAction::AddBone);
+                    }
+                    if ui
+                        .add_enabled(rig.is_posed(), egui::Button::new("Reset Pose"))
+                        .clicked()
+                    {
+                        actions.push(UiAction::ResetPose);
+                    }
+                    if ui
+                        .add_enabled(rig.is_posed(), egui::Button::new("Bake"))
+                        .on_hover_text("Write the pose into the voxels (undoable)")
+                        .clicked()
+                    {
+                        actions.push(UiAction::BakePose);
+                    }
+                    ui.checkbox(preview, "Preview");
+                });
+                ui.separator();
+
+                if bones.is_empty() {
+                    ui.weak("No bones. Select voxels and click Add Bone.");
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for (i, bone) in bones.iter().enumerate() {
+                            // Depth for the indent; bounded in case of a
+                            // malformed chain.
+                            let depth = std::iter::successors(bone.parent, |&p| bones[p].parent)
+                                .take(bones.len())
+                                .count();
+                            ui.horizontal(|ui| {
+                                ui.add_space(depth as f32 * 12.0);
+                                match rename {
+                                    Some((idx, buffer)) if *idx == i => {
+                                        let response = ui.text_edit_singleline(buffer);
+                                        response.request_focus();
+                                        if response.lost_focus() {
+                                            let name = buffer.trim().to_string();
+                                            if !name.is_empty() {
+                                                actions.push(UiAction::RenameBone(i, name));
+                                            }
+                                            *rename = None;
+                                        }
+                                    }
+                                    _ => {
+                                        let posed = if bone.pose.is_rest() { "" } else { " •" };
+                                        let label = format!("{}{posed}", bone.name);
+                                        let response = ui
+                                            .selectable_label(*selected == Some(i), label)
+                                            .on_hover_text(format!(
+                                                "{} voxels — double-click to rename",
+                                                bone.cells().len()
+                                            ));
+                                        if response.double_clicked() {
+                                            *rename = Some((i, bone.name.clone()));
+                                        } else if response.clicked() {
+                                            *selected = Some(i);
+                                        }
+                                    }
+                                }
+                            });
+                        }
+                    });
+
+                let Some(index) = *selected else {
+                    return;
+                };
+                let bone = &bones[index];
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Parent");
+                    let parent_name = |p: Option<usize>| match p {
+                        Some(p) => bones[p].name.as_str(),
+                        None => "(body)",
+                    };
+                    egui::ComboBox::from_id_salt("bone_parent")
+                        .selected_text(parent_name(bone.parent))
+                        .show_ui(ui, |ui| {
+                            let options = std::iter::once(None)
+                                .chain((0..bones.len()).filter(|&p| p != index).map(Some));
+                            for option in options {
+                                let chosen = option == bone.parent;
+                                if ui.selectable_label(chosen, parent_name(option)).clicked()
+                                    && !chosen
+                                {
+                                    actions.push(UiAction::SetBoneParent(index, option));
+                                }
+                            }
+                        });
+                });
+
+                let mut pivot = bone.pivot;
+                let mut pose = bone.pose;
+                egui::Grid::new("bone_pose").num_columns(4).show(ui, |ui| {
+                    ui.label("Pivot");
+                    let mut changed = false;
+                    for v in &mut pivot {
+                        changed |= ui.add(egui::DragValue::new(v)).changed();
+                    }
+                    if changed {
+                        actions.push(UiAction::SetBonePivot(index, pivot));
+                    }
+                    ui.end_row();
+
+                    ui.label("Move");
+                    let mut changed = false;
+                    for v in &mut pose.translation {
+                        changed |= ui.add(egui::DragValue::new(v)).changed();
+                    }
+                    ui.end_row();
+
+                    ui.label("Rotate");
+                    for v in &mut pose.rotation {
+                        changed |= ui
+                            .add(egui::DragValue::new(v).range(-180..=180).suffix("°"))
+                            .changed();
+                    }
+                    ui.end_row();
+                    if changed {
+                        actions.push(UiAction::SetBonePose(index, pose));
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!bone.pose.is_rest(), egui::Button::new("Rest"))
+                        .on_hover_text("Reset this bone's pose")
+                        .clicked()
+                    {
+                        actions.push(UiAction::SetBonePose(index, BonePose::default()));
+                    }
+                    if ui
+                        .add_enabled(has_selection, egui::Button::new("Assign"))
+                        .on_hover_text("Give the selected voxels to this bone")
+                        .clicked()
+                    {
+                        actions.push(UiAction::AssignSelectionToBone(index));
+                    }
+                    if ui
+                        .button("Select")
+                        .on_hover_text("Select this bone's voxels")
+                        .clicked()
+                    {
+                        actions.push(UiAction::SelectBoneVoxels(index));
+                    }
+                    if ui.button("Delete").clicked() {
+                        actions.push(UiAction::RemoveBone(index));
+                    }
+                });
+            });
+
+        for action in actions {
+            self.state.request(action);
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Slice Editor window: the work-plane slice as a flat grid of colored
+//! squares, painted on directly in 2D.
+//!
+//! The slice is the editor's `work_plane`, so the window and the 3D
+//! viewport always show the same layer — step it here and the viewport
+//! overlay moves; step it with PageUp / PageDown and the window
+//! follows. The App samples the layer into a [`SliceImage`] (the UI has
+//! no world access) and re-samples it whenever voxels change, so 3D
+//! edits show up here too.
+//!
+//! Clicks don't write anything themselves: they queue `SlicePaint` /
+//! `SliceFill` / `SlicePick` actions, which the App runs through the
+//! same brush, flood fill, and undo history as the viewport — brush
+//! size, paint patterns and symmetry included, with the writes clipped
+//! to the slice. The cell under the cursor is mirrored into the 3D
+//! hover highlight.
+
+use egui::{Color32, Context, Pos2, Rect, Sense, Stroke, Vec2};
+
+use super::{Ui, UiAction};
+use crate::editor::{line_voxels, Axis, Editor, Tool, WorkPlane};
+
+/// Cell size range, in screen points.
+const MIN_ZOOM: f32 = 4.0;
+const MAX_ZOOM: f32 = 40.0;
+
+/// Below this cell size the per-cell grid lines would swamp the colors.
+const GRID_MIN_ZOOM: f32 = 8.0;
+
+/// Empty cells: a two-tone checker, so they read as "nothing here"
+/// rather than as black voxels.
+const EMPTY_DARK: Color32 = Color32::from_gray(34);
+const EMPTY_LIGHT: Color32 = Color32::from_gray(44);
+
+/// One sampled slice, row-major from the top-left of the window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceImage {
+    /// The slice this was sampled from.
+    pub plane: WorkPlane,
+    /// Cells span `-half..half` on both in-plane axes (the ground
+    /// grid's extent, matching the viewport overlay).
+    pub half: i32,
+    /// RGBA per cell, `None` for air.
+    pub colors: Vec<Option<[u8; 4]>>,
+}
+
+impl SliceImage {
+    /// Sample `plane` over `-half..half` with `sample`, which returns a
+    /// cell's color or `None` for air.
+    pub fn new(
+        plane: WorkPlane,
+        half: i32,
+        sample: impl Fn((i32, i32, i32)) -> Option<[u8; 4]>,
+    ) -> Self {
+        let side = (2 * half.max(0)) as usize;
+        let mut image = Self {
+            plane,
+            half: half.max(0),
+            colors: Vec::with_capacity(side * side),
+        };
+        for row in 0..side {
+            for col in 0..side {
+                image.colors.push(sample(image.cell(col, row)));
+            }
+        }
+        image
+    }
+
+    /// Cells per side.
+    pub fn side(&self) -> usize {
+        (2 * self.half) as usize
+    }
+
+    /// World cell at grid column `col`, row `row`.
+    pub fn cell(&self, col: usize, row: usize) -> (i32, i32, i32) {
+        let (across, down, up) = screen_axes(self.plane.axis);
+        let col = col as i32 - self.half;
+        let row = row as i32;
+        let mut pos = [0; 3];
+        pos[self.plane.axis_index()] = self.plane.coord;
+        pos[across] = col;
+        pos[down] = if up {
+            self.half - 1 - row
+        } else {
+            row - self.half
+        };
+        (pos[0], pos[1], pos[2])
+    }
+
+    pub fn color(&self, col: usize, row: usize) -> Option<[u8; 4]> {
+        self.colors.get(row * self.side() + col).copied().flatten()
+    }
+}
+
+/// How a slice lies on screen: the world axis running left → right,
+/// the one running down the window, and whether down the window is
+/// toward *negative* values of it. Side slices (X / Z) keep +Y up, as
+/// in the Front / Side views; the Y slice is a top view with +Z down,
+/// toward the default camera.
+pub fn screen_axes(axis: Axis) -> (usize, usize, bool) {
+    match axis {
+        Axis::X => (2, 1, true),
+        Axis::Y => (0, 2, false),
+        Axis::Z => (0, 1, true),
+    }
+}
+
+/// Per-session window state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceViewState {
+    /// Cell size in screen points.
+    pub zoom: f32,
+    /// Cell the cursor is over, if any. The App highlights it in the
+    /// viewport.
+    pub hover: Option<(i32, i32, i32)>,
+    /// Last cell the held brush stroke wrote; `None` between strokes.
+    last: Option<(i32, i32, i32)>,
+}
+
+impl Default for SliceViewState {
+    fn default() -> Self {
+        Self {
+            zoom: 14.0,
+            hover: None,
+            last: None,
+        }
+    }
+}
+
+impl Ui {
+    pub(super) fn show_slice_view_panel(&mut self, ctx: &Context, editor: &mut Editor) {
+        let mut open = self.state.show_slice_view;
+        self.slice_view.hover = None;
+        egui::Window::new("Slice Editor")
+            .open(&mut open)
+            .resizable(true)
+            .default_size([380.0, 440.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Slice:");
+                    ui.radio_value(&mut editor.work_plane.axis, Axis::X, "X");
+                    ui.radio_value(&mut editor.work_plane.axis, Axis::Y, "Y");
+                    ui.radio_value(&mut editor.work_plane.axis, Axis::Z, "Z");
+                    ui.label("=");
+                    if ui
+                        .small_button("◀")
+                        .on_hover_text("Previous slice")
+                        .clicked()
+                    {
+                        editor.work_plane = editor.work_plane.stepped(-1);
+                    }
+                    ui.add(egui::DragValue::new(&mut editor.work_plane.coord).speed(0.1));
+                    if ui.small_button("▶").on_hover_text("Next slice").clicked() {
+                        editor.work_plane = editor.work_plane.stepped(1);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut editor.work_plane_enabled, "Lock viewport to slice")
+                        .on_hover_text(
+                            "Work-plane mode: restrict 3D edits to this slice and draw \
+                             it in the viewport",
+                        );
+                    ui.add(
+                        egui::Slider::new(&mut self.slice_view.zoom, MIN_ZOOM..=MAX_ZOOM)
+                            .text("Zoom")
+                            .show_value(false),
+                    );
+                });
+                let hint = match editor.current_tool {
+                    Tool::Place | Tool::Remove | Tool::Paint => {
+                        "Drag to draw with the brush. Alt-click picks a color."
+                    }
+                    Tool::Fill => "Click to flood-fill within the slice.",
+                    Tool::Eyedropper => "Click to pick a color.",
+                    _ => "Pick Place, Remove, Paint, Fill or Eyedropper to edit here.",
+                };
+                ui.label(egui::RichText::new(hint).small().weak());
+                ui.separator();
+
+                let Some(image) = &self.slice_image else {
+                    ui.label("Sampling…");
+                    return;
+                };
+                let zoom = self.slice_view.zoom;
+                let view = &mut self.slice_view;
+                let state = &mut self.state;
+                egui::ScrollArea::both()
+                    .drag_to_scroll(false)
+                    .show(ui, |ui| {
+                        let side = image.side();
+                        let size = Vec2::splat(side as f32 * zoom);
+                        let (rect, response) =
+                            ui.allocate_exact_size(size, Sense::click_and_drag());
+                        paint_slice(ui, rect, image, zoom);
+
+                        let (down, pressed, alt) = ui.input(|i| {
+                            (
+                                i.pointer.primary_down(),
+                                i.pointer.primary_pressed(),
+                                i.modifiers.alt,
+                            )
+                        });
+                        // A press that started on the grid keeps the drag
+                        // even when the pointer wanders off it.
+                        let held = down && response.is_pointer_button_down_on();
+                        let pressed = pressed && held;
+                        if !held && view.last.take().is_some() {
+                            state.request(UiAction::SliceStrokeEnd);
+                        }
+
+                        let pointer = response.interact_pointer_pos().or(response.hover_pos());
+                        let hovered = pointer.and_then(|p| grid_cell(rect, zoom, side, p));
+                        let Some((col, row)) = hovered else {
+                            return;
+                        };
+                        let cell = image.cell(col, row);
+                        view.hover = Some(cell);
+                        let cell_rect = cell_rect(rect, zoom, col, row);
+                        ui.painter()
+                            .rect_stroke(cell_rect, 0.0, Stroke::new(2.0, Color32::WHITE));
+                        response
+                            .clone()
+                            .on_hover_text(format!("({}, {}, {})", cell.0, cell.1, cell.2));
+                        if !held {
+                            return;
+                        }
+
+                        // Alt turns any tool into the eyedropper.
+                        let tool = if alt {
+                            Tool::Eyedropper
+                        } else {
+                            editor.current_tool
+                        };
+                        match tool {
+                            Tool::Eyedropper if pressed => state.request(UiAction::SlicePick(cell)),
+                            Tool::Fill if pressed => state.request(UiAction::SliceFill(cell)),
+                            Tool::Place | Tool::Remove | Tool::Paint if view.last != Some(cell) => {
+                                // Fill in the cells a fast drag skipped
+                                // between frames.
+                                let (cells, begin) = match view.last {
+                                    Some(last) => (line_voxels(last, cell), false),
+                                    None => (vec![cell], true),
+                                };
+                                state.request(UiAction::SlicePaint { cells, begin });
+                                view.last = Some(cell);
+                            }
+                            _ => {}
+                        }
+                    });
+            });
+        self.state.show_slice_view = open;
+    }
+}
+
+/// Screen rect of grid cell `(col, row)`.
+fn cell_rect(grid: Rect, zoom: f32, col: usize, row: usize) -> Rect {
+    let min = grid.min + Vec2::new(col as f32 * zoom, row as f32 * zoom);
+    Rect::from_min_size(min, Vec2::splat(zoom))
+}
+
+/// Grid cell under screen point `p`, if it's on the grid.
+fn grid_cell(grid: Rect, zoom: f32, side: usize, p: Pos2) -> Option<(usize, usize)> {
+    if !grid.contains(p) {
+        return None;
+    }
+    let col = ((p.x - grid.min.x) / zoom) as usize;
+    let row = ((p.y - grid.min.y) / zoom) as usize;
+    (col < side && row < side).then_some((col, row))
+}
+
+/// Draw the cells in view, then grid lines and the two world axes
+/// through the origin.
+fn paint_slice(ui: &egui::Ui, grid: Rect, image: &SliceImage, zoom: f32) {
+    let painter = ui.painter();
+    let side = image.side();
+    // Only the visible part — a large grid is tens of thousands of
+    // cells, most of them scrolled away.
+    let clip = ui.clip_rect().intersect(grid);
+    if !clip.is_positive() {
+        return;
+    }
+    let range = |lo: f32, hi: f32, origin: f32| {
+        let first = ((lo - origin) / zoom).floor().max(0.0) as usize;
+        let last = (((hi - origin) / zoom).ceil() as usize).min(side);
+        first..last
+    };
+    let cols = range(clip.min.x, clip.max.x, grid.min.x);
+    let rows = range(clip.min.y, clip.max.y, grid.min.y);
+    for row in rows.clone() {
+        for col in cols.clone() {
+            let rect = cell_rect(grid, zoom, col, row);
+            let empty = if (col + row) % 2 == 0 {
+                EMPTY_DARK
+            } else {
+                EMPTY_LIGHT
+            };
+            painter.rect_filled(rect, 0.0, empty);
+            if let Some([r, g, b, a]) = image.color(col, row) {
+                painter.rect_filled(rect, 0.0, Color32::from_rgba_unmultiplied(r, g, b, a));
+            }
+        }
+    }
+
+    if zoom >= GRID_MIN_ZOOM {
+        let line = Stroke::new(1.0, Color32::from_black_alpha(90));
+        for col in cols.start..=cols.end {
+            let x = grid.min.x + col as f32 * zoom;
+            painter.line_segment([Pos2::new(x, clip.min.y), Pos2::new(x, clip.max.y)], line);
+        }
+        for row in rows.start..=rows.end {
+            let y = grid.min.y + row as f32 * zoom;
+            painter.line_segment([Pos2::new(clip.min.x, y), Pos2::new(clip.max.x, y)], line);
+        }
+    }
+
+    // World axes: the line between cells -1 and 0 on each in-plane axis.
+    let axis = Stroke::new(1.0, Color32::from_white_alpha(70));
+    let mid = grid.min + Vec2::splat(image.half as f32 * zoom);
+    painter.line_segment(
+        [Pos2::new(mid.x, clip.min.y), Pos2::new(mid.x, clip.max.y)],
+        axis,
+    );
+    painter.line_segment(
+        [Pos2::new(clip.min.x, mid.y), Pos2::new(clip.max.x, mid.y)],
+        axis,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_rows_follow_the_screen_layout() {
+        let plane = WorkPlane {
+            axis: Axis::Z,
+            coord: 5,
+        };
+        // Color encodes the cell, so each sample can be traced back.
+        let image = SliceImage::new(plane, 2, |(x, y, _)| {
+            (x >= 0 && y >= 0).then_some([x as u8, y as u8, 0, 255])
+        });
+        assert_eq!(image.side(), 4);
+        // Top-left is (-half, +half - 1): Y runs up the window.
+        assert_eq!(image.cell(0, 0), (-2, 1, 5));
+        assert_eq!(image.cell(3, 3), (1, -2, 5));
+        assert_eq!(image.color(3, 0), Some([1, 1, 0, 255]));
+        assert_eq!(image.color(0, 0), None);
+
+        // A top view keeps +Z down the window.
+        let top = SliceImage::new(WorkPlane::default(), 2, |_| None);
+        assert_eq!(top.cell(0, 0), (-2, 0, -2));
+        assert_eq!(top.cell(1, 3), (-1, 0, 1));
+    }
+
+    #[test]
+    fn grid_cell_maps_points_inside_only() {
+        let grid = Rect::from_min_size(Pos2::new(10.0, 20.0), Vec2::splat(40.0));
+        assert_eq!(
+            grid_cell(grid, 10.0, 4, Pos2::new(10.0, 20.0)),
+            Some((0, 0))
+        );
+        assert_eq!(
+            grid_cell(grid, 10.0, 4, Pos2::new(45.0, 31.0)),
+            Some((3, 1))
+        );
+        assert_eq!(grid_cell(grid, 10.0, 4, Pos2::new(9.0, 25.0)), None);
+        assert_eq!(grid_cell(grid, 10.0, 4, Pos2::new(25.0, 61.0)), None);
+    }
+}
@@ -0,0 +1,205 @@
+//! Background tasks: the status-bar progress indicator and the task
+//! list popup.
+//!
+//! The App owns the work — procgen jobs, the mesh worker, turntable
+//! renders, AI jobs, file imports / exports — and condenses whatever is
+//! running into [`TaskStatus`] rows each frame (`App::collect_tasks`).
+//! The status bar shows the first one with a small progress bar; the
+//! "Tasks" button next to it opens a list of all of them, each with a
+//! Cancel button when the work behind it can be stopped.
+
+use egui::Context;
+
+use super::{Ui, UiAction};
+
+/// Width of the status-bar progress bar.
+const STATUS_BAR_PROGRESS_WIDTH: f32 = 90.0;
+
+/// What a task is, for its icon in the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    /// Procgen panel / pipeline graph run.
+    Generation,
+    /// Chunk meshes rebuilding on the mesh worker.
+    Meshing,
+    /// Render ▸ Turntable.
+    Turntable,
+    /// AI generation request.
+    Ai,
+    /// Import / export / project open.
+    File,
+}
+
+impl TaskKind {
+    fn icon(self) -> &'static str {
+        match self {
+            TaskKind::Generation => "⛰",
+            TaskKind::Meshing => "▦",
+            TaskKind::Turntable => "🎞",
+            TaskKind::Ai => "✨",
+            TaskKind::File => "🗁",
+        }
+    }
+}
+
+/// Display snapshot of one running task, built by the App each frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskStatus {
+    pub kind: TaskKind,
+    /// "Perlin Terrain", "Exporting castle.glb", ...
+    pub label: String,
+    /// 0.0..=1.0, or `None` while the task can't tell (a spinner is
+    /// drawn instead).
+    pub progress: Option<f32>,
+    /// Secondary text: time left, chunk counts, "cancelling…".
+    pub detail: Option<String>,
+    /// Requested by the task's Cancel button; `None` hides the button.
+    pub cancel: Option<UiAction>,
+}
+
+/// Running tasks, mirrored by the App each frame, and whether the
+/// list popup is open.
+#[derive(Debug, Default)]
+pub struct TaskList {
+    pub tasks: Vec<TaskStatus>,
+    pub show_list: bool,
+}
+
+impl TaskList {
+    /// Status-bar text: the first task's label, plus how many more are
+    /// running.
+    pub fn headline(&self) -> Option<String> {
+        let first = self.tasks.first()?;
+        Some(match self.tasks.len() {
+            1 => first.label.clone(),
+            n => format!("{} (+{} more)", first.label, n - 1),
+        })
+    }
+
+    /// Mean progress of the tasks that report one, or `None` when none
+    /// of them do.
+    pub fn overall_progress(&self) -> Option<f32> {
+        let known: Vec<f32> = self.tasks.iter().filter_map(|t| t.progress).collect();
+        if known.is_empty() {
+            return None;
+        }
+        Some(known.iter().map(|p| p.clamp(0.0, 1.0)).sum::<f32>() / known.len() as f32)
+    }
+}
+
+impl Ui {
+    /// Status-bar indicator, drawn inside the bar's right-to-left
+    /// layout: the Tasks toggle, the overall progress and the headline.
+    /// Draws nothing while idle.
+    pub(super) fn task_status_item(&mut self, ui: &mut egui::Ui) {
+        let Some(headline) = self.tasks.headline() else {
+            return;
+        };
+        let count = self.tasks.tasks.len();
+        ui.toggle_value(&mut self.tasks.show_list, format!("Tasks ({count})"))
+            .on_hover_text("Show running background tasks");
+        match self.tasks.overall_progress() {
+            Some(progress) => {
+                ui.add(
+                    egui::ProgressBar::new(progress)
+                        .desired_width(STATUS_BAR_PROGRESS_WIDTH)
+                        .show_percentage(),
+                );
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        ui.label(headline);
+        ui.separator();
+    }
+
+    /// The task list popup, anchored above the status bar's right end.
+    /// Closes itself once the last task finishes.
+    pub(super) fn show_task_list(&mut self, ctx: &Context) {
+        if self.tasks.tasks.is_empty() {
+            self.tasks.show_list = false;
+        }
+        if !self.tasks.show_list {
+            return;
+        }
+        let mut cancel = Vec::new();
+        egui::Window::new("Background Tasks")
+            .open(&mut self.tasks.show_list)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -32.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_min_width(280.0);
+                for (i, task) in self.tasks.tasks.iter().enumerate() {
+                    if i > 0 {
+                        ui.separator();
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label(task.kind.icon());
+                        ui.strong(&task.label);
+                    });
+                    ui.horizontal(|ui| {
+                        match task.progress {
+                            Some(progress) => {
+                                ui.add(
+                                    egui::ProgressBar::new(progress)
+                                        .desired_width(180.0)
+                                        .show_percentage(),
+                                );
+                            }
+                            None => {
+                                ui.spinner();
+                            }
+                        }
+                        if let Some(action) = &task.cancel {
+                            if ui.button("Cancel").clicked() {
+                                cancel.push(action.clone());
+                            }
+                        }
+                    });
+                    if let Some(detail) = &task.detail {
+                        ui.weak(detail);
+                    }
+                }
+            });
+        for action in cancel {
+            self.state.request(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(label: &str, progress: Option<f32>) -> TaskStatus {
+        TaskStatus {
+            kind: TaskKind::Generation,
+            label: label.to_string(),
+            progress,
+            detail: None,
+            cancel: None,
+        }
+    }
+
+    #[test]
+    fn headline_and_progress_summarize_tasks() {
+        let mut list = TaskList::default();
+        assert_eq!(list.headline(), None);
+        assert_eq!(list.overall_progress(), None);
+
+        list.tasks.push(task("Exporting a.glb", None));
+        assert_eq!(list.headline().as_deref(), Some("Exporting a.glb"));
+        assert_eq!(list.overall_progress(), None);
+
+        // Indeterminate tasks don't drag the mean down.
+        list.tasks.push(task("Meshing", Some(0.25)));
+        list.tasks.push(task("Graph", Some(0.75)));
+        assert_eq!(
+            list.headline().as_deref(),
+            Some("Exporting a.glb (+2 more)")
+        );
+        assert_eq!(list.overall_progress(), Some(0.5));
+    }
+}
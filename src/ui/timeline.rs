@@ -0,0 +1,146 @@
+//! View ▸ Timeline: the scene's animation frames.
+//!
+//! One button per frame in playback order; clicking shows that frame in
+//! the viewport (where edits go), double-clicking renames it. Below the
+//! strip sit the frame list edits, the current frame's hold time, the
+//! play / step controls and the onion-skin toggle. Like the Layers
+//! panel, everything is queued as [`UiAction`]s for the App.
+
+use egui::Context;
+
+use super::{RenderStats, Ui, UiAction};
+
+impl Ui {
+    pub(super) fn show_timeline_panel(&mut self, ctx: &Context, stats: &RenderStats) {
+        // Same deferral as the Layers panel: the window borrows
+        // `show_timeline`, so actions are requested after it closes.
+        let mut actions = Vec::new();
+        let rename = &mut self.state.frame_rename;
+        let onion_skin = &mut self.state.onion_skin;
+        let count = stats.frames.len();
+        let current = stats.current_frame;
+        egui::Window::new("Timeline")
+            .default_pos([240.0, ctx.screen_rect().height() - 200.0])
+            .default_width(420.0)
+            .collapsible(true)
+            .open(&mut self.state.show_timeline)
+            .show(ctx, |ui| {
+                egui::ScrollArea::horizontal()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            for (i, frame) in stats.frames.iter().enumerate() {
+                                match rename {
+                                    Some((idx, buffer)) if *idx == i => {
+                                        let response = ui.add(
+                                            egui::TextEdit::singleline(buffer).desired_width(80.0),
+                                        );
+                                        response.request_focus();
+                                        if response.lost_focus() {
+                                            let name = buffer.trim().to_string();
+                                            if !name.is_empty() {
+                                                actions.push(UiAction::RenameFrame(i, name));
+                                            }
+                                            *rename = None;
+                                        }
+                                    }
+                                    _ => {
+                                        let response = ui
+                                            .selectable_label(i == current, &frame.name)
+                                            .on_hover_text(format!(
+                                                "{} ms — click to show, double-click to rename",
+                                                frame.duration_ms
+                                            ));
+                                        if response.double_clicked() {
+                                            *rename = Some((i, frame.name.clone()));
+                                        } else if response.clicked() && i != current {
+                                            actions.push(UiAction::SelectFrame(i));
+                                        }
+                                    }
+                                }
+                            }
+                        });
+                    });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Add")
+                        .on_hover_text("Empty frame after this one")
+                        .clicked()
+                    {
+                        actions.push(UiAction::AddFrame);
+                    }
+                    if ui
+                        .button("Duplicate")
+                        .on_hover_text("Copy of this frame after it")
+                        .clicked()
+                    {
+                        actions.push(UiAction::DuplicateFrame);
+                    }
+                    if ui
+                        .add_enabled(count > 1, egui::Button::new("Delete"))
+                        .clicked()
+                    {
+                        actions.push(UiAction::RemoveFrame(current));
+                    }
+                    if ui
+                        .add_enabled(current > 0, egui::Button::new("◀ Move"))
+                        .clicked()
+                    {
+                        actions.push(UiAction::MoveFrame {
+                            from: current,
+                            to: current - 1,
+                        });
+                    }
+                    if ui
+                        .add_enabled(current + 1 < count, egui::Button::new("Move ▶"))
+                        .clicked()
+                    {
+                        actions.push(UiAction::MoveFrame {
+                            from: current,
+                            to: current + 1,
+                        });
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("⏮").on_hover_text("Previous frame").clicked() {
+                        actions.push(UiAction::PreviousFrame);
+                    }
+                    let play = if stats.playing {
+                        "⏸ Pause"
+                    } else {
+                        "▶ Play"
+                    };
+                    if ui.add_enabled(count > 1, egui::Button::new(play)).clicked() {
+                        actions.push(UiAction::TogglePlayback);
+                    }
+                    if ui.button("⏭").on_hover_text("Next frame").clicked() {
+                        actions.push(UiAction::NextFrame);
+                    }
+                    ui.separator();
+                    if let Some(frame) = stats.frames.get(current) {
+                        let mut duration = frame.duration_ms;
+                        ui.label("Hold");
+                        let response = ui.add(
+                            egui::DragValue::new(&mut duration)
+                                .range(1..=10_000)
+                                .suffix(" ms"),
+                        );
+                        if response.changed() {
+                            actions.push(UiAction::SetFrameDuration(current, duration));
+                        }
+                    }
+                    ui.separator();
+                    ui.checkbox(onion_skin, "Onion Skin")
+                        .on_hover_text("Show the previous and next frames as ghosts");
+                });
+            });
+
+        for action in actions {
+            self.state.request(action);
+        }
+    }
+}